@@ -1,8 +1,13 @@
 use clap::ValueEnum;
+use eda::units::UnitSystem;
 use eda::EdaTool;
-use planning::actions::{AddOrRemoveAction, SetOrClearAction};
+use planning::actions::{AddOrRemoveAction, DeleteStrategy, SetOrClearAction};
+use planning::part::MoistureEvent;
 use planning::placement::{PlacementOperation, PlacementSortingMode};
 use planning::process::TaskAction;
+use planning::project::Artifact;
+use planning::report::handover::HandoverReportFormat;
+use planning::settings::{OriginConvention, RotationDirection};
 use pnp::pcb::PcbSide;
 use util::sorting::SortOrder;
 
@@ -89,6 +94,23 @@ fn from(value: AddOrRemoveOperationArg) -> Self {
     }
 }
 
+#[derive(ValueEnum, Debug, Clone)]
+pub enum DeleteStrategyArg {
+    Block,
+    Cascade,
+    Orphan,
+}
+
+impl From<DeleteStrategyArg> for DeleteStrategy {
+    fn from(value: DeleteStrategyArg) -> Self {
+        match value {
+            DeleteStrategyArg::Block => Self::Block,
+            DeleteStrategyArg::Cascade => Self::Cascade,
+            DeleteStrategyArg::Orphan => Self::Orphan,
+        }
+    }
+}
+
 #[derive(ValueEnum, Debug, Clone)]
 pub enum SetOrClearOperationArg {
     Set,
@@ -125,6 +147,59 @@ pub fn build(&self) -> EdaTool {
     }
 }
 
+#[derive(Clone, Debug)]
+#[derive(ValueEnum)]
+pub enum UnitSystemArg {
+    #[value(name("mm"))]
+    Millimeters,
+    #[value(name("mil"))]
+    Mils,
+    #[value(name("inch"))]
+    Inches,
+}
+
+impl From<UnitSystemArg> for UnitSystem {
+    fn from(value: UnitSystemArg) -> Self {
+        match value {
+            UnitSystemArg::Millimeters => Self::Millimeters,
+            UnitSystemArg::Mils => Self::Mils,
+            UnitSystemArg::Inches => Self::Inches,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[derive(ValueEnum)]
+pub enum OriginConventionArg {
+    BottomLeft,
+    TopLeft,
+}
+
+impl From<OriginConventionArg> for OriginConvention {
+    fn from(value: OriginConventionArg) -> Self {
+        match value {
+            OriginConventionArg::BottomLeft => Self::BottomLeft,
+            OriginConventionArg::TopLeft => Self::TopLeft,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[derive(ValueEnum)]
+pub enum RotationDirectionArg {
+    AntiClockwise,
+    Clockwise,
+}
+
+impl From<RotationDirectionArg> for RotationDirection {
+    fn from(value: RotationDirectionArg) -> Self {
+        match value {
+            RotationDirectionArg::AntiClockwise => Self::AntiClockwise,
+            RotationDirectionArg::Clockwise => Self::Clockwise,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 #[derive(ValueEnum)]
 pub enum PlacementOperationArg {
@@ -146,6 +221,39 @@ fn from(value: PlacementOperationArg) -> Self {
     }
 }
 
+#[derive(Clone, Debug)]
+#[derive(ValueEnum)]
+pub enum InspectionResultArg {
+    #[value(name("pass"))]
+    Pass,
+    #[value(name("fail"))]
+    Fail,
+}
+
+impl InspectionResultArg {
+    pub fn is_pass(&self) -> bool {
+        matches!(self, Self::Pass)
+    }
+}
+
+#[derive(Clone, Debug)]
+#[derive(ValueEnum)]
+pub enum MoistureEventArg {
+    #[value(name("reel-opened"))]
+    ReelOpened,
+    #[value(name("baked"))]
+    Baked,
+}
+
+impl From<MoistureEventArg> for MoistureEvent {
+    fn from(value: MoistureEventArg) -> Self {
+        match value {
+            MoistureEventArg::ReelOpened => MoistureEvent::ReelOpened,
+            MoistureEventArg::Baked => MoistureEvent::Baked,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 #[derive(ValueEnum)]
 pub enum TaskActionArg {
@@ -166,3 +274,48 @@ fn from(value: TaskActionArg) -> Self {
         }
     }
 }
+
+#[derive(Clone, Debug)]
+#[derive(ValueEnum)]
+pub enum ArtifactArg {
+    #[value(name("inspection-checklist"))]
+    InspectionChecklist,
+    #[value(name("stencil-setup-sheet"))]
+    StencilSetupSheet,
+    #[value(name("machine-job"))]
+    MachineJob,
+    #[value(name("labels"))]
+    Labels,
+    #[value(name("analytics"))]
+    Analytics,
+}
+
+impl From<ArtifactArg> for Artifact {
+    fn from(value: ArtifactArg) -> Self {
+        match value {
+            ArtifactArg::InspectionChecklist => Artifact::InspectionChecklist,
+            ArtifactArg::StencilSetupSheet => Artifact::StencilSetupSheet,
+            ArtifactArg::MachineJob => Artifact::MachineJob,
+            ArtifactArg::Labels => Artifact::Labels,
+            ArtifactArg::Analytics => Artifact::Analytics,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[derive(ValueEnum)]
+pub enum HandoverReportFormatArg {
+    #[value(name("markdown"))]
+    Markdown,
+    #[value(name("html"))]
+    Html,
+}
+
+impl From<HandoverReportFormatArg> for HandoverReportFormat {
+    fn from(value: HandoverReportFormatArg) -> Self {
+        match value {
+            HandoverReportFormatArg::Markdown => HandoverReportFormat::Markdown,
+            HandoverReportFormatArg::Html => HandoverReportFormat::Html,
+        }
+    }
+}
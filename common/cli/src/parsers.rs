@@ -9,6 +9,8 @@
 use planning::placement::PlacementSortingItem;
 use pnp::panel::Dimensions;
 use rust_decimal::Decimal;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
 use crate::args::{PlacementSortingModeArg, SortOrderArg};
 
@@ -245,3 +247,8 @@ fn test_vector2_parser_errors() {
         assert_eq!(result, Err("Invalid key: 'foo'; Invalid key: 'bar'; Expected exactly 1 equal sign in 'meh=blah=blah', found 2; Failed to parse decimal value for key 'x': Invalid decimal: unknown character; Missing/invalid keys: x, expected keys: x, y".to_string()))
     }
 }
+
+/// Parses an RFC 3339 date and time, e.g. '2025-03-10T08:00:00Z'
+pub fn rfc3339_parser(s: &str) -> Result<OffsetDateTime, String> {
+    OffsetDateTime::parse(s, &Rfc3339).map_err(|error| format!("Invalid RFC 3339 date and time: {}", error))
+}
@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
@@ -7,28 +8,90 @@ pub struct I18nConfig {
     pub default: String,
     pub fallback: String,
     pub languages: Vec<String>,
+    /// Languages to use, in order, for messages missing from a language, before `fallback` is used.
+    ///
+    /// e.g. `es-MX` -> `[es-ES]`, results in `es-MX` -> `es-ES` -> `en-US` when the `fallback` is `en-US`.
+    pub fallback_chains: HashMap<String, Vec<String>>,
+    pub source: TranslationSource,
 }
 
-pub fn init(config: I18nConfig) {
-    for identifier in config.languages {
-        let mut path = PathBuf::from("assets/translations");
-        path.push(identifier.clone());
-        path.push("translations.ftl");
-        debug!(
-            "Loading translations. identifier: {}, absolute_path: {:?}",
-            identifier,
-            std::path::absolute(path.clone()).unwrap()
-        );
-        match fs::read_to_string(path.clone()) {
-            Ok(content) => match egui_i18n::load_translations_from_text(identifier, content) {
-                Err(e) => error!("Error parsing translation file: {}, cause: {}", path.display(), e),
-                Ok(_) => info!("Loaded translations. file: {}", path.display()),
-            },
-            Err(e) => {
-                error!("Error reading translation file: {}, cause: {}", path.display(), e);
+/// Where to load the translations from.
+pub enum TranslationSource {
+    /// A directory with a `<identifier>/translations.ftl` file for each language.
+    Directory(PathBuf),
+    /// Translations embedded at compile time, keyed by language identifier, see [`include_str!`].
+    Embedded(&'static [(&'static str, &'static str)]),
+}
+
+impl Default for TranslationSource {
+    fn default() -> Self {
+        Self::Directory(PathBuf::from("assets/translations"))
+    }
+}
+
+impl TranslationSource {
+    fn load(&self, identifier: &str) -> Option<String> {
+        match self {
+            TranslationSource::Directory(directory) => {
+                let mut path = directory.clone();
+                path.push(identifier);
+                path.push("translations.ftl");
+                debug!(
+                    "Loading translations. identifier: {}, absolute_path: {:?}",
+                    identifier,
+                    std::path::absolute(path.clone()).unwrap()
+                );
+                match fs::read_to_string(path.clone()) {
+                    Ok(content) => Some(content),
+                    Err(e) => {
+                        error!("Error reading translation file: {}, cause: {}", path.display(), e);
+                        None
+                    }
+                }
+            }
+            TranslationSource::Embedded(translations) => {
+                let content = translations
+                    .iter()
+                    .find(|(candidate, _content)| *candidate == identifier)
+                    .map(|(_identifier, content)| content.to_string());
+                if content.is_none() {
+                    error!("No embedded translations. identifier: {}", identifier);
+                }
+                content
             }
         }
     }
+}
+
+pub fn init(config: I18nConfig) {
+    let mut contents: HashMap<String, Option<String>> = HashMap::new();
+    let mut load = |identifier: &String| -> Option<String> {
+        contents
+            .entry(identifier.clone())
+            .or_insert_with(|| config.source.load(identifier))
+            .clone()
+    };
+
+    for identifier in config.languages.iter() {
+        let Some(content) = load(identifier) else {
+            continue;
+        };
+
+        let fallback_contents: Vec<String> = config
+            .fallback_chains
+            .get(identifier)
+            .into_iter()
+            .flatten()
+            .filter_map(|fallback_identifier| load(fallback_identifier))
+            .collect();
+
+        let content = merge_translations(&content, &fallback_contents);
+
+        match egui_i18n::load_translations_from_text(identifier.clone(), content) {
+            Err(e) => error!("Error parsing translations. identifier: {}, cause: {}", identifier, e),
+            Ok(_) => info!("Loaded translations. identifier: {}", identifier),
+        }
+    }
 
     egui_i18n::set_language(&config.default);
     egui_i18n::set_fallback(&config.fallback);
@@ -36,6 +99,63 @@ pub fn init(config: I18nConfig) {
     debug!("languages: {:?}", egui_i18n::languages());
 }
 
+/// Appends the entries (messages and terms) from each fallback that are missing from the content, earlier fallbacks
+/// take precedence.
+fn merge_translations(content: &str, fallback_contents: &[String]) -> String {
+    let mut identifiers: HashSet<&str> = split_entries(content)
+        .into_iter()
+        .map(|(identifier, _entry)| identifier)
+        .collect();
+
+    let mut merged = content.to_string();
+
+    for fallback_content in fallback_contents.iter() {
+        for (identifier, entry) in split_entries(fallback_content) {
+            if identifiers.insert(identifier) {
+                if !merged.ends_with('\n') {
+                    merged.push('\n');
+                }
+                merged.push_str(&entry);
+            }
+        }
+    }
+
+    merged
+}
+
+/// Splits fluent content into `(identifier, entry)` pairs, an entry is the line with the identifier and any following
+/// indented (continuation) lines.  Comments and blank lines are not included.
+fn split_entries(content: &str) -> Vec<(&str, String)> {
+    let mut entries: Vec<(&str, String)> = vec![];
+    let mut in_entry = false;
+
+    for line in content.lines() {
+        let is_continuation = line.starts_with([' ', '\t']) && !line.trim().is_empty();
+        if is_continuation {
+            if let (true, Some((_identifier, entry))) = (in_entry, entries.last_mut()) {
+                entry.push_str(line);
+                entry.push('\n');
+            }
+            continue;
+        }
+
+        in_entry = false;
+        if line.starts_with('#') {
+            continue;
+        }
+
+        if let Some((identifier, _value)) = line.split_once('=') {
+            let identifier = identifier.trim();
+            if !identifier.is_empty() {
+                entries.push((identifier, format!("{}\n", line)));
+                in_entry = true;
+            }
+        }
+    }
+
+    entries
+}
+
 pub mod fluent_argument_helpers {
 
     #[cfg(feature = "json")]
@@ -124,3 +244,40 @@ pub fn build_fluent_args(args: &HashMap<String, Arg>) -> FluentArgs<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_translations_with_fallbacks() {
+        // given
+        let content = "# comment\nhello = Hola\n";
+        let fallback_contents = vec![
+            "hello = Hola!\ngoodbye = Adiós\n".to_string(),
+            "goodbye = Goodbye\nmultiline =\n    Line 1\n    Line 2\n-term = Term\n".to_string(),
+        ];
+
+        // when
+        let result = merge_translations(content, &fallback_contents);
+
+        // then
+        assert_eq!(
+            result,
+            "# comment\nhello = Hola\ngoodbye = Adiós\nmultiline =\n    Line 1\n    Line 2\n-term = Term\n"
+        );
+    }
+
+    #[test]
+    fn merge_translations_without_trailing_newline() {
+        // given
+        let content = "hello = Hola";
+        let fallback_contents = vec!["goodbye = Goodbye".to_string()];
+
+        // when
+        let result = merge_translations(content, &fallback_contents);
+
+        // then
+        assert_eq!(result, "hello = Hola\ngoodbye = Goodbye\n");
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use thiserror::Error;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A token that long-running operations check periodically, so that they can be aborted.
+///
+/// Clones share the same cancellation state, a shell keeps a clone and can cancel it from any thread while an
+/// operation is in progress.
+///
+/// Only the id is serialized, a deserialized token has its own, un-cancelled, state.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct CancellationToken {
+    id: u64,
+    #[serde(skip)]
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            cancelled: Default::default(),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled
+            .store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns an error if the token has been cancelled, for use with `?` in loops.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        match self.is_cancelled() {
+            true => Err(Cancelled),
+            false => Ok(()),
+        }
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for CancellationToken {}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("Operation cancelled")]
+pub struct Cancelled;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_share_cancellation_state() {
+        // given
+        let token = CancellationToken::new();
+        let other_token = CancellationToken::new();
+        let clone = token.clone();
+
+        // when
+        clone.cancel();
+
+        // then
+        assert!(token.is_cancelled());
+        assert_eq!(token.check(), Err(Cancelled));
+        assert_eq!(other_token.check(), Ok(()));
+        assert_ne!(token, other_token);
+    }
+
+    #[test]
+    fn deserialized_token_is_not_cancelled() {
+        // given
+        let token = CancellationToken::new();
+        token.cancel();
+
+        // when
+        let json = serde_json::to_string(&token).unwrap();
+        let deserialized: CancellationToken = serde_json::from_str(&json).unwrap();
+
+        // then
+        assert_eq!(deserialized, token);
+        assert!(!deserialized.is_cancelled());
+    }
+}
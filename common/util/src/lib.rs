@@ -1,4 +1,5 @@
 pub mod assert;
+pub mod cancellation;
 pub mod dynamic;
 pub mod path;
 pub mod range_utils;
@@ -25,11 +25,19 @@ impl FromStr for Source {
     type Err = SourceError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if Self::is_url(s) {
+            return Ok(Source::Url(s.to_string()));
+        }
         Ok(Source::File(PathBuf::from(s)))
     }
 }
 
 impl Source {
+    /// Returns true if the value is an HTTP(S) URL, anything else is treated as a path.
+    pub fn is_url(value: &str) -> bool {
+        value.starts_with("http://") || value.starts_with("https://")
+    }
+
     pub fn try_from_relative_path(path: PathBuf) -> Result<Source, SourceError> {
         if !path.is_relative() {
             panic!()
@@ -81,9 +89,7 @@ impl Display for Source {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Source::File(path) => f.write_str(path.display().to_string().as_str()),
-            Source::Url(_) => {
-                unimplemented!()
-            }
+            Source::Url(url) => f.write_str(url),
         }
     }
 }
@@ -97,3 +103,24 @@ pub enum SourceError {
     #[error("Source is not a path.")]
     NotAPath,
 }
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("load_out.csv", Source::File(PathBuf::from("load_out.csv")))]
+    #[case("/project/load_out.csv", Source::File(PathBuf::from("/project/load_out.csv")))]
+    #[case("https://example.com/load_outs/1", Source::Url("https://example.com/load_outs/1".to_string()))]
+    #[case("http://localhost:8080/parts", Source::Url("http://localhost:8080/parts".to_string()))]
+    fn from_str(#[case] value: &str, #[case] expected_source: Source) {
+        // when
+        let source = Source::from_str(value).unwrap();
+
+        // then
+        assert_eq!(source, expected_source);
+        assert_eq!(source.to_string(), value);
+    }
+}
@@ -6,6 +6,7 @@
 use thiserror::Error;
 
 use crate::placement::{EdaPlacement, EdaPlacementField};
+use crate::units::{to_millimeters, UnitSystem};
 
 // TODO add tests for aliases
 
@@ -16,9 +17,22 @@ pub struct DiptracePlacementRecord {
     name: String,
     value: String,
     side: DipTracePcbSide,
-    #[serde(alias = "Center X (mm)")]
+    /// See [`crate::units::detect_unit_system_from_headers`]
+    #[serde(
+        alias = "Center X (mm)",
+        alias = "Center X (mils)",
+        alias = "Center X (mil)",
+        alias = "Center X (inch)",
+        alias = "Center X (in)"
+    )]
     x: Decimal,
-    #[serde(alias = "Center Y (mm)")]
+    #[serde(
+        alias = "Center Y (mm)",
+        alias = "Center Y (mils)",
+        alias = "Center Y (mil)",
+        alias = "Center Y (inch)",
+        alias = "Center Y (in)"
+    )]
     y: Decimal,
     /// Positive values indicate anti-clockwise rotation
     /// Range is 0 - < 360
@@ -49,7 +63,7 @@ pub enum DiptracePlacementRecordError {
 }
 
 impl DiptracePlacementRecord {
-    pub fn build_eda_placement(&self) -> Result<EdaPlacement, DiptracePlacementRecordError> {
+    pub fn build_eda_placement(&self, unit_system: UnitSystem) -> Result<EdaPlacement, DiptracePlacementRecordError> {
         Ok(EdaPlacement {
             ref_des: self.ref_des.to_string(),
             place: true,
@@ -64,8 +78,8 @@ pub fn build_eda_placement(&self) -> Result<EdaPlacement, DiptracePlacementRecor
                 },
             ],
             pcb_side: PcbSide::from(&self.side),
-            x: self.x,
-            y: self.y,
+            x: to_millimeters(self.x, unit_system),
+            y: to_millimeters(self.y, unit_system),
             rotation: DipTraceRotationConverter::convert(self.rotation),
         })
 
@@ -0,0 +1,238 @@
+//! Loads placements directly from an EasyEDA (Standard edition) board file, as saved by 'File > EasyEDA Source',
+//! avoiding the need to export a pick-and-place file.
+//!
+//! Board coordinates are in units of 10 mil with positive Y down, they are converted to millimeters relative to the
+//! canvas origin with positive Y up, which matches the pick-and-place export when using 'mm'.
+//!
+//! Footprints are stored as `LIB` shapes, the fields of which are separated by `~`, with sub-shapes separated by `#@$`.
+//! The designator and value are taken from the `TEXT~P` and `TEXT~N` sub-shapes, a designator on the bottom silkscreen
+//! layer indicates the footprint is on the bottom of the board.
+
+use std::str::FromStr;
+
+use pnp::pcb::PcbSide;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use thiserror::Error;
+
+use crate::easyeda::csv::EasyEdaRotationConverter;
+use crate::placement::{EdaPlacement, EdaPlacementField};
+
+/// 10 mil
+const UNIT_IN_MM: Decimal = dec!(0.254);
+
+const LAYER_BOTTOM_SILKSCREEN: &str = "4";
+
+const SHAPE_SEPARATOR: &str = "#@$";
+const FIELD_SEPARATOR: char = '~';
+
+#[derive(Error, Debug)]
+pub enum EasyEdaBoardError {
+    #[error("JSON error. cause: {0}")]
+    JsonError(serde_json::Error),
+    #[error("Not a PCB document. doc_type: {0}")]
+    NotABoard(String),
+    #[error("Footprint is missing a designator. shape: {0}")]
+    MissingDesignator(String),
+    #[error("Invalid number. value: {0}")]
+    InvalidNumber(String),
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct EasyEdaDocument {
+    head: EasyEdaHead,
+    #[serde(default)]
+    shape: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct EasyEdaHead {
+    #[serde(rename = "docType")]
+    doc_type: String,
+    #[serde(deserialize_with = "deserialize_number")]
+    x: Decimal,
+    #[serde(deserialize_with = "deserialize_number")]
+    y: Decimal,
+}
+
+/// EasyEDA is inconsistent, numbers are sometimes stored as strings.
+fn deserialize_number<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        String(String),
+        Number(f64),
+    }
+
+    match serde::Deserialize::deserialize(deserializer)? {
+        StringOrNumber::String(value) => parse_decimal(&value).map_err(serde::de::Error::custom),
+        StringOrNumber::Number(value) => Decimal::try_from(value).map_err(serde::de::Error::custom),
+    }
+}
+
+pub fn load_board_placements(content: &str) -> Result<Vec<EdaPlacement>, EasyEdaBoardError> {
+    let document: EasyEdaDocument = serde_json::from_str(content).map_err(EasyEdaBoardError::JsonError)?;
+
+    // "3" is the document type for a PCB
+    if document.head.doc_type != "3" {
+        return Err(EasyEdaBoardError::NotABoard(document.head.doc_type));
+    }
+
+    let origin = (document.head.x, document.head.y);
+
+    document
+        .shape
+        .iter()
+        .filter(|shape| shape.starts_with("LIB~"))
+        .map(|shape| build_eda_placement(shape, origin))
+        .collect()
+}
+
+fn build_eda_placement(shape: &str, origin: (Decimal, Decimal)) -> Result<EdaPlacement, EasyEdaBoardError> {
+    let mut sub_shapes = shape.split(SHAPE_SEPARATOR);
+
+    // LIB~x~y~attributes~rotation~...
+    let lib: Vec<&str> = sub_shapes
+        .next()
+        .unwrap_or_default()
+        .split(FIELD_SEPARATOR)
+        .collect();
+
+    let field = |index: usize| {
+        lib.get(index)
+            .copied()
+            .unwrap_or_default()
+    };
+
+    // attributes are '`' separated key-value pairs, e.g. "package`R0603`Value`10k"
+    let attributes: Vec<&str> = field(3).split('`').collect();
+    let attribute = |name: &str| {
+        attributes
+            .chunks(2)
+            .find_map(|pair| match pair {
+                [key, value] if *key == name && !value.is_empty() => Some(*value),
+                _ => None,
+            })
+    };
+
+    // TEXT~type~x~y~stroke_width~rotation~mirror~layer~net~font_size~text~...
+    let texts: Vec<Vec<&str>> = sub_shapes
+        .filter(|sub_shape| sub_shape.starts_with("TEXT~"))
+        .map(|sub_shape| {
+            sub_shape
+                .split(FIELD_SEPARATOR)
+                .collect()
+        })
+        .collect();
+    let text = |kind: &str| {
+        texts
+            .iter()
+            .find(|text| text.get(1) == Some(&kind))
+    };
+
+    let designator = text("P").ok_or_else(|| EasyEdaBoardError::MissingDesignator(field(0).to_string()))?;
+    let ref_des = designator
+        .get(10)
+        .copied()
+        .unwrap_or_default();
+
+    let pcb_side = match designator.get(7) {
+        Some(&LAYER_BOTTOM_SILKSCREEN) => PcbSide::Bottom,
+        _ => PcbSide::Top,
+    };
+
+    let value = attribute("Value")
+        .or_else(|| text("N").and_then(|text| text.get(10).copied()))
+        .unwrap_or_default();
+    let device = attribute("package").unwrap_or_default();
+
+    let x = (parse_decimal(field(1))? - origin.0) * UNIT_IN_MM;
+    let y = -(parse_decimal(field(2))? - origin.1) * UNIT_IN_MM;
+    let rotation = match field(4) {
+        "" => Decimal::ZERO,
+        rotation => parse_decimal(rotation)?,
+    };
+
+    Ok(EdaPlacement {
+        ref_des: ref_des.to_string(),
+        place: true,
+        fields: vec![
+            EdaPlacementField::new("device".to_string(), device.to_string()),
+            EdaPlacementField::new("value".to_string(), value.to_string()),
+        ],
+        pcb_side,
+        x: x.normalize(),
+        y: y.normalize(),
+        rotation: EasyEdaRotationConverter::convert(rotation).normalize(),
+    })
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal, EasyEdaBoardError> {
+    Decimal::from_str(value).map_err(|_| EasyEdaBoardError::InvalidNumber(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn load_placements() {
+        // given
+        let content = r#"{
+            "head": { "docType": "3", "x": "4000", "y": "3000" },
+            "shape": [
+                "TRACK~1~1~S$1~4000 3000 4100 3000~gge1~0",
+                "LIB~4010~2990~package`R0402`nameAlias`Resistor`Value`10k`~90~~gge2~1~~0~~#@$TEXT~P~4008~2985~0.6~90~0~3~~4.5~R1~M 0 0~~gge3~~#@$TEXT~N~4012~2985~0.6~90~0~3~~4.5~10k~M 0 0~none~gge4~~",
+                "LIB~4020~3010~package`C0603`~270~~gge5~1~~0~~#@$TEXT~P~4018~3015~0.6~270~1~4~~4.5~C1~M 0 0~~gge6~~#@$TEXT~N~4022~3015~0.6~270~1~4~~4.5~100nF~M 0 0~none~gge7~~"
+            ]
+        }"#;
+
+        let expected_placements = vec![
+            EdaPlacement {
+                ref_des: "R1".to_string(),
+                place: true,
+                fields: vec![
+                    EdaPlacementField::new("device".to_string(), "R0402".to_string()),
+                    EdaPlacementField::new("value".to_string(), "10k".to_string()),
+                ],
+                pcb_side: PcbSide::Top,
+                x: dec!(2.54),
+                y: dec!(2.54),
+                rotation: dec!(90),
+            },
+            EdaPlacement {
+                ref_des: "C1".to_string(),
+                place: true,
+                fields: vec![
+                    EdaPlacementField::new("device".to_string(), "C0603".to_string()),
+                    EdaPlacementField::new("value".to_string(), "100nF".to_string()),
+                ],
+                pcb_side: PcbSide::Bottom,
+                x: dec!(5.08),
+                y: dec!(-2.54),
+                rotation: dec!(-90),
+            },
+        ];
+
+        // when
+        let placements = load_board_placements(content).unwrap();
+
+        // then
+        assert_eq!(placements, expected_placements);
+    }
+
+    #[test]
+    pub fn load_placements_from_schematic() {
+        // given
+        let content = r#"{ "head": { "docType": "1", "x": 0, "y": 0 }, "shape": [] }"#;
+
+        // when
+        let result = load_board_placements(content);
+
+        // then
+        assert!(matches!(result, Err(EasyEdaBoardError::NotABoard(doc_type)) if doc_type == "1"));
+    }
+}
@@ -7,6 +7,7 @@
 use thiserror::Error;
 
 use crate::placement::{EdaPlacement, EdaPlacementField};
+use crate::units::{parse_unit_system, to_millimeters, UnitSystem};
 
 #[derive(Error, Debug)]
 pub enum EasyEdaPlacementRecordError {
@@ -54,7 +55,12 @@ fn from(value: &EasyEdaPcbSide) -> Self {
 }
 
 impl EasyEdaPlacementRecord {
-    pub fn build_eda_placement(&self) -> Result<EdaPlacement, EasyEdaPlacementRecordError> {
+    /// The unit used for the position, EasyEDA includes the unit in the values, e.g. '10.5mm'.
+    pub fn unit_system(&self) -> Option<UnitSystem> {
+        EasyEdaUnitParser::parse_unit(&self.x)
+    }
+
+    pub fn build_eda_placement(&self, unit_system: UnitSystem) -> Result<EdaPlacement, EasyEdaPlacementRecordError> {
         let x =
             EasyEdaUnitParser::parse(&self.x).map_err(|cause| EasyEdaPlacementRecordError::UnitParseError(cause))?;
         let y =
@@ -74,8 +80,8 @@ pub fn build_eda_placement(&self) -> Result<EdaPlacement, EasyEdaPlacementRecord
                 },
             ],
             pcb_side: PcbSide::from(&self.side),
-            x,
-            y,
+            x: to_millimeters(x, unit_system),
+            y: to_millimeters(y, unit_system),
             rotation: EasyEdaRotationConverter::convert(self.rotation),
         })
 
@@ -83,7 +89,7 @@ pub fn build_eda_placement(&self) -> Result<EdaPlacement, EasyEdaPlacementRecord
     }
 }
 
-struct EasyEdaRotationConverter {}
+pub(crate) struct EasyEdaRotationConverter {}
 impl EasyEdaRotationConverter {
     pub fn convert(mut input: Decimal) -> Decimal {
         while input >= dec!(360) {
@@ -129,7 +135,7 @@ impl EasyEdaUnitParser {
     ///
     /// The format is '<decimal-value><unit>', e.g. '359.999mm'
     ///
-    /// Currently unit is ignored, users should export pick-and-place files using 'mm' in the EasyEDA UI.
+    /// The unit is ignored, see [`EasyEdaUnitParser::parse_unit`].
     pub fn parse(input: &String) -> Result<Decimal, EasyEdaUnitParserError> {
         let pattern = Regex::new(r#"^(?<value>[-]?(\d+)+(\.(\d+))?){1}.*"#).unwrap();
 
@@ -142,6 +148,15 @@ pub fn parse(input: &String) -> Result<Decimal, EasyEdaUnitParserError> {
             Some(value) => Ok(Decimal::try_from(value).unwrap()),
         }
     }
+
+    /// Extract the unit from the input, e.g. 'mil' from '359.999mil'.
+    ///
+    /// Returns `None` if there is no unit, or if it's unknown.
+    pub fn parse_unit(input: &str) -> Option<UnitSystem> {
+        let unit = input.trim_start_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '.');
+
+        parse_unit_system(unit)
+    }
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -157,6 +172,7 @@ mod unit_parser_tests {
     use rust_decimal_macros::dec;
 
     use crate::easyeda::csv::{EasyEdaUnitParser, EasyEdaUnitParserError};
+    use crate::units::UnitSystem;
 
     #[rstest]
     #[case("3", Ok(dec!(3)))]
@@ -169,4 +185,14 @@ mod unit_parser_tests {
     fn parse(#[case] value: &str, #[case] expected_value: Result<Decimal, EasyEdaUnitParserError>) {
         assert_eq!(EasyEdaUnitParser::parse(&value.to_string()), expected_value);
     }
+
+    #[rstest]
+    #[case("3", None)]
+    #[case("3mm", Some(UnitSystem::Millimeters))]
+    #[case("-0.3mil", Some(UnitSystem::Mils))]
+    #[case("3in", Some(UnitSystem::Inches))]
+    #[case("3bananas", None)]
+    fn parse_unit(#[case] value: &str, #[case] expected_unit_system: Option<UnitSystem>) {
+        assert_eq!(EasyEdaUnitParser::parse_unit(value), expected_unit_system);
+    }
 }
@@ -0,0 +1,396 @@
+//! Loads placements directly from a KiCad board file (`.kicad_pcb`), avoiding the need to export a position file.
+//!
+//! Supports the s-expression format used by KiCad 5 (`module`, `fp_text`) and KiCad 6+ (`footprint`, `property`).
+//!
+//! The resulting placements match those of the KiCad position file exporter when 'Use drill/place file origin' is
+//! enabled, that is, coordinates are relative to the auxiliary axis origin, if set, and positive Y is up.
+
+use std::str::FromStr;
+
+use pnp::pcb::PcbSide;
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::placement::{EdaPlacement, EdaPlacementField};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum KiCadBoardError {
+    #[error("Syntax error. reason: {reason}, offset: {offset}")]
+    SyntaxError { reason: String, offset: usize },
+    #[error("Not a KiCad board file. root: {0}")]
+    NotABoard(String),
+    #[error("Footprint is missing a node. footprint: {footprint}, node: {node}")]
+    MissingNode { footprint: String, node: String },
+    #[error("Invalid number. value: {0}")]
+    InvalidNumber(String),
+}
+
+pub fn load_board_placements(content: &str) -> Result<Vec<EdaPlacement>, KiCadBoardError> {
+    let root = SExpr::parse(content)?;
+
+    if root.name() != Some("kicad_pcb") {
+        return Err(KiCadBoardError::NotABoard(
+            root.name()
+                .unwrap_or_default()
+                .to_string(),
+        ));
+    }
+
+    let origin = match root
+        .child("setup")
+        .and_then(|setup| setup.child("aux_axis_origin"))
+    {
+        Some(node) => (node.decimal_at(1)?, node.decimal_at(2)?),
+        None => (Decimal::ZERO, Decimal::ZERO),
+    };
+
+    root.children()
+        .filter(|node| matches!(node.name(), Some("footprint") | Some("module")))
+        .map(|footprint| build_eda_placement(footprint, origin))
+        .collect()
+}
+
+fn build_eda_placement(footprint: &SExpr, origin: (Decimal, Decimal)) -> Result<EdaPlacement, KiCadBoardError> {
+    let footprint_name = footprint.atom_at(1).unwrap_or_default();
+
+    let missing_node = |node: &str| KiCadBoardError::MissingNode {
+        footprint: footprint_name.to_string(),
+        node: node.to_string(),
+    };
+
+    let ref_des = footprint
+        .text("Reference", "reference")
+        .ok_or_else(|| missing_node("reference"))?;
+    let value = footprint
+        .text("Value", "value")
+        .unwrap_or_default();
+
+    let layer = footprint
+        .child("layer")
+        .and_then(|layer| layer.atom_at(1))
+        .ok_or_else(|| missing_node("layer"))?;
+    let pcb_side = match layer {
+        "B.Cu" => PcbSide::Bottom,
+        _ => PcbSide::Top,
+    };
+
+    let at = footprint
+        .child("at")
+        .ok_or_else(|| missing_node("at"))?;
+    let x = at.decimal_at(1)? - origin.0;
+    // board files use positive Y down
+    let y = -(at.decimal_at(2)? - origin.1);
+    let rotation = match at.atom_at(3) {
+        Some(_) => at.decimal_at(3)?,
+        None => Decimal::ZERO,
+    };
+
+    let excluded = footprint
+        .child("attr")
+        .map(|attr| {
+            attr.children()
+                .filter_map(SExpr::as_atom)
+                .any(|flag| matches!(flag, "exclude_from_pos_files" | "dnp" | "virtual"))
+        })
+        .unwrap_or(false)
+        || footprint.child("dnp").is_some();
+
+    // the position file only includes the footprint name, not the library
+    let package = footprint_name
+        .split_once(':')
+        .map_or(footprint_name, |(_library, name)| name);
+
+    Ok(EdaPlacement {
+        ref_des: ref_des.to_string(),
+        place: !excluded,
+        fields: vec![
+            EdaPlacementField::new("package".to_string(), package.to_string()),
+            EdaPlacementField::new("val".to_string(), value.to_string()),
+        ],
+        pcb_side,
+        x: x.normalize(),
+        y: y.normalize(),
+        rotation: normalize_rotation(rotation),
+    })
+}
+
+/// Normalize to the range >-180 to +180.
+fn normalize_rotation(mut rotation: Decimal) -> Decimal {
+    let full = Decimal::from(360);
+    let half = Decimal::from(180);
+
+    rotation %= full;
+    if rotation > half {
+        rotation -= full;
+    } else if rotation <= -half {
+        rotation += full;
+    }
+    rotation.normalize()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SExpr {
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+impl SExpr {
+    fn parse(content: &str) -> Result<SExpr, KiCadBoardError> {
+        let mut parser = SExprParser {
+            content: content.as_bytes(),
+            offset: 0,
+        };
+
+        parser.skip_whitespace();
+        let expr = parser.parse_list()?;
+        parser.skip_whitespace();
+        if parser.offset != parser.content.len() {
+            return Err(parser.error("trailing content"));
+        }
+
+        Ok(expr)
+    }
+
+    fn as_atom(&self) -> Option<&str> {
+        match self {
+            SExpr::Atom(atom) => Some(atom),
+            SExpr::List(_) => None,
+        }
+    }
+
+    fn items(&self) -> &[SExpr] {
+        match self {
+            SExpr::Atom(_) => &[],
+            SExpr::List(items) => items,
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.atom_at(0)
+    }
+
+    fn atom_at(&self, index: usize) -> Option<&str> {
+        self.items()
+            .get(index)
+            .and_then(SExpr::as_atom)
+    }
+
+    fn decimal_at(&self, index: usize) -> Result<Decimal, KiCadBoardError> {
+        let value = self.atom_at(index).unwrap_or_default();
+
+        Decimal::from_str(value)
+            .or_else(|_| Decimal::from_scientific(value))
+            .map_err(|_| KiCadBoardError::InvalidNumber(value.to_string()))
+    }
+
+    fn children(&self) -> impl Iterator<Item = &SExpr> {
+        self.items().iter().skip(1)
+    }
+
+    fn child(&self, name: &str) -> Option<&SExpr> {
+        self.children()
+            .find(|child| child.name() == Some(name))
+    }
+
+    /// KiCad 6+ uses `(property "Reference" "R1")`, KiCad 5 uses `(fp_text reference R1)`.
+    fn text(&self, property_name: &str, fp_text_kind: &str) -> Option<&str> {
+        self.children()
+            .find_map(|child| match child.name() {
+                Some("property") if child.atom_at(1) == Some(property_name) => child.atom_at(2),
+                Some("fp_text") if child.atom_at(1) == Some(fp_text_kind) => child.atom_at(2),
+                _ => None,
+            })
+    }
+}
+
+struct SExprParser<'a> {
+    content: &'a [u8],
+    offset: usize,
+}
+
+impl SExprParser<'_> {
+    fn error(&self, reason: &str) -> KiCadBoardError {
+        KiCadBoardError::SyntaxError {
+            reason: reason.to_string(),
+            offset: self.offset,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.content.get(self.offset).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(byte) if byte.is_ascii_whitespace()) {
+            self.offset += 1;
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<SExpr, KiCadBoardError> {
+        if self.peek() != Some(b'(') {
+            return Err(self.error("expected '('"));
+        }
+        self.offset += 1;
+
+        let mut items = vec![];
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => return Err(self.error("unexpected end of content")),
+                Some(b')') => {
+                    self.offset += 1;
+                    return Ok(SExpr::List(items));
+                }
+                Some(b'(') => items.push(self.parse_list()?),
+                Some(b'"') => items.push(self.parse_string()?),
+                Some(_) => items.push(self.parse_atom()),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<SExpr, KiCadBoardError> {
+        // skip the opening quote
+        self.offset += 1;
+
+        let mut bytes = vec![];
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(b'"') => {
+                    self.offset += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    self.offset += 1;
+                    let escaped = self
+                        .peek()
+                        .ok_or_else(|| self.error("unterminated string"))?;
+                    bytes.push(match escaped {
+                        b'n' => b'\n',
+                        b't' => b'\t',
+                        other => other,
+                    });
+                    self.offset += 1;
+                }
+                Some(byte) => {
+                    bytes.push(byte);
+                    self.offset += 1;
+                }
+            }
+        }
+
+        String::from_utf8(bytes)
+            .map(SExpr::Atom)
+            .map_err(|_| self.error("invalid utf-8"))
+    }
+
+    fn parse_atom(&mut self) -> SExpr {
+        let start = self.offset;
+        while matches!(self.peek(), Some(byte) if !byte.is_ascii_whitespace() && byte != b'(' && byte != b')') {
+            self.offset += 1;
+        }
+
+        SExpr::Atom(String::from_utf8_lossy(&self.content[start..self.offset]).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    const BOARD: &str = r#"(kicad_pcb (version 20221018) (generator pcbnew)
+  (setup
+    (aux_axis_origin 100 150)
+  )
+  (footprint "Resistor_SMD:R_0402_1005Metric" (layer "F.Cu")
+    (at 110.5 140.25 90)
+    (property "Reference" "R1" (at 0 -1.17 90) (layer "F.SilkS"))
+    (property "Value" "10k" (at 0 1.17 90) (layer "F.Fab"))
+    (attr smd)
+  )
+  (footprint "Capacitor_SMD:C_0603_1608Metric" (layer "B.Cu")
+    (at 120 160 270)
+    (property "Reference" "C1" (at 0 -1.43 90) (layer "B.SilkS"))
+    (property "Value" "100nF \"X7R\"" (at 0 1.43 90) (layer "B.Fab"))
+    (attr smd exclude_from_pos_files)
+  )
+  (module Connector:TestPoint (layer F.Cu)
+    (at 100 150)
+    (fp_text reference TP1 (at 0 -1.5) (layer F.SilkS))
+    (fp_text value TestPoint (at 0 1.5) (layer F.Fab))
+  )
+)"#;
+
+    #[test]
+    pub fn load_placements() {
+        // given
+        let expected_placements = vec![
+            EdaPlacement {
+                ref_des: "R1".to_string(),
+                place: true,
+                fields: vec![
+                    EdaPlacementField::new("package".to_string(), "R_0402_1005Metric".to_string()),
+                    EdaPlacementField::new("val".to_string(), "10k".to_string()),
+                ],
+                pcb_side: PcbSide::Top,
+                x: dec!(10.5),
+                y: dec!(9.75),
+                rotation: dec!(90),
+            },
+            EdaPlacement {
+                ref_des: "C1".to_string(),
+                place: false,
+                fields: vec![
+                    EdaPlacementField::new("package".to_string(), "C_0603_1608Metric".to_string()),
+                    EdaPlacementField::new("val".to_string(), "100nF \"X7R\"".to_string()),
+                ],
+                pcb_side: PcbSide::Bottom,
+                x: dec!(20),
+                y: dec!(-10),
+                rotation: dec!(-90),
+            },
+            EdaPlacement {
+                ref_des: "TP1".to_string(),
+                place: true,
+                fields: vec![
+                    EdaPlacementField::new("package".to_string(), "TestPoint".to_string()),
+                    EdaPlacementField::new("val".to_string(), "TestPoint".to_string()),
+                ],
+                pcb_side: PcbSide::Top,
+                x: dec!(0),
+                y: dec!(0),
+                rotation: dec!(0),
+            },
+        ];
+
+        // when
+        let placements = load_board_placements(BOARD).unwrap();
+
+        // then
+        assert_eq!(placements, expected_placements);
+    }
+
+    #[rstest]
+    #[case("(kicad_sch (version 20230121))", KiCadBoardError::NotABoard("kicad_sch".to_string()))]
+    #[case("(kicad_pcb (version 20221018)", KiCadBoardError::SyntaxError { reason: "unexpected end of content".to_string(), offset: 29 })]
+    #[case("(kicad_pcb (footprint \"R\" (layer \"F.Cu\") (at 1 2)))", KiCadBoardError::MissingNode { footprint: "R".to_string(), node: "reference".to_string() })]
+    pub fn load_placements_errors(#[case] content: &str, #[case] expected_error: KiCadBoardError) {
+        // expect
+        assert_eq!(load_board_placements(content), Err(expected_error));
+    }
+
+    #[rstest]
+    #[case(dec!(0), dec!(0))]
+    #[case(dec!(180), dec!(180))]
+    #[case(dec!(-180), dec!(180))]
+    #[case(dec!(270), dec!(-90))]
+    #[case(dec!(-270), dec!(90))]
+    #[case(dec!(450.5), dec!(90.5))]
+    pub fn rotation_normalization(#[case] rotation: Decimal, #[case] expected_rotation: Decimal) {
+        // expect
+        assert_eq!(normalize_rotation(rotation), expected_rotation);
+    }
+}
@@ -3,6 +3,7 @@
 use thiserror::Error;
 
 use crate::placement::{EdaPlacement, EdaPlacementField};
+use crate::units::{to_millimeters, UnitSystem};
 
 #[derive(Error, Debug)]
 pub enum KiCadPlacementRecordError {
@@ -47,7 +48,8 @@ fn from(value: &KiCadPcbSide) -> Self {
 }
 
 impl KiCadPlacementRecord {
-    pub fn build_eda_placement(&self) -> Result<EdaPlacement, KiCadPlacementRecordError> {
+    /// KiCad CSV exports do not include the unit, it must be specified, the default export unit is millimeters.
+    pub fn build_eda_placement(&self, unit_system: UnitSystem) -> Result<EdaPlacement, KiCadPlacementRecordError> {
         Ok(EdaPlacement {
             ref_des: self.ref_des.to_string(),
             place: true,
@@ -62,8 +64,8 @@ pub fn build_eda_placement(&self) -> Result<EdaPlacement, KiCadPlacementRecordEr
                 },
             ],
             pcb_side: PcbSide::from(&self.side),
-            x: self.x,
-            y: self.y,
+            x: to_millimeters(self.x, unit_system),
+            y: to_millimeters(self.y, unit_system),
             // TODO normalize rotation in case kicad uses values outside it's expected range.
             rotation: self.rotation,
         })
@@ -5,8 +5,9 @@
 pub mod criteria;
 pub mod placement;
 pub mod substitution;
+pub mod units;
 
-#[derive(serde::Serialize, serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EdaTool {
     DipTrace,
     KiCad,
@@ -1,6 +1,11 @@
+use eda_units::eda_units::angle::AngleUnit;
+use eda_units::eda_units::dimension_unit::{DimensionUnitPoint2, DimensionUnitPoint2Ext};
 use pnp::pcb::PcbSide;
+use pnp::placement::PlacementPositionUnit;
 use rust_decimal::Decimal;
 
+use crate::units::UnitSystem;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EdaPlacementField {
     pub name: String,
@@ -26,15 +31,25 @@ pub struct EdaPlacement {
     pub fields: Vec<EdaPlacementField>,
     pub pcb_side: PcbSide,
 
-    /// Positive = Right
+    /// Millimeters, Positive = Right
     pub x: Decimal,
-    /// Positive = Up
+    /// Millimeters, Positive = Up
     pub y: Decimal,
     /// Positive values indicate anti-clockwise rotation
     /// Range is >-180 to +180.
     pub rotation: Decimal,
 }
 
+impl EdaPlacement {
+    /// The position in the given unit system, usually the unit system the placements were exported with.
+    pub fn position_unit(&self, unit_system: UnitSystem) -> PlacementPositionUnit {
+        PlacementPositionUnit::new(
+            DimensionUnitPoint2::new_dim_decimal(self.x, self.y, UnitSystem::Millimeters).in_unit_system(unit_system),
+            AngleUnit::new_degrees_decimal(self.rotation),
+        )
+    }
+}
+
 impl Default for EdaPlacement {
     fn default() -> Self {
         Self {
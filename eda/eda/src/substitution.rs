@@ -1,7 +1,11 @@
 use criteria::FieldCriterion;
+use thiserror::Error;
 
 use crate::placement::EdaPlacement;
 
+/// The maximum amount of rules applied to a single placement, to stop runaway chains.
+pub const MAX_CHAIN_LENGTH: usize = 100;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EdaSubstitutionRuleTransformItem {
     pub field_name: String,
@@ -91,11 +95,38 @@ pub struct EdaSubstitutionChainEntry<'rule> {
     pub rule: &'rule EdaSubstitutionRule,
 }
 
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EdaSubstitutionChainError {
+    /// A rule changed the placement back to a previous state, e.g. A -> B -> A.
+    #[error("Cyclic substitution chain. length: {length}")]
+    Cycle { length: usize },
+    #[error("Substitution chain too long. length: {length}")]
+    TooLong { length: usize },
+}
+
 #[derive(Debug, PartialEq)]
 pub struct EdaSubstitutionResult<'placement, 'rule> {
     pub original_placement: &'placement EdaPlacement,
+    /// When there is a chain error, the placement after the last rule in the chain.
     pub resulting_placement: EdaPlacement,
     pub chain: Vec<EdaSubstitutionChainEntry<'rule>>,
+    pub chain_error: Option<EdaSubstitutionChainError>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct EdaSubstitutionDiagnostics<'rule> {
+    /// Rules that did not match any placement, in the order of the rules.
+    ///
+    /// A rule that matched, but did not change a placement, is not included.
+    pub unmatched_rules: Vec<&'rule EdaSubstitutionRule>,
+    /// The ref-des of each placement with a chain error, in the order of the placements.
+    pub chain_errors: Vec<(String, EdaSubstitutionChainError)>,
+}
+
+impl EdaSubstitutionDiagnostics<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.unmatched_rules.is_empty() && self.chain_errors.is_empty()
+    }
 }
 
 pub struct EdaSubstitutor {}
@@ -105,17 +136,43 @@ pub fn substitute<'placement, 'rule>(
         original_eda_placements: &'placement [EdaPlacement],
         eda_substitution_rules: &'rule [EdaSubstitutionRule],
     ) -> Vec<EdaSubstitutionResult<'placement, 'rule>> {
+        let (results, _diagnostics) =
+            Self::substitute_with_diagnostics(original_eda_placements, eda_substitution_rules);
+
+        results
+    }
+
+    /// Rules are applied repeatedly, in order, until no rule changes the placement.
+    ///
+    /// A chain is stopped when a rule changes the placement back to a previous state, or when the chain is longer than
+    /// [`MAX_CHAIN_LENGTH`], see [`EdaSubstitutionResult::chain_error`].
+    pub fn substitute_with_diagnostics<'placement, 'rule>(
+        original_eda_placements: &'placement [EdaPlacement],
+        eda_substitution_rules: &'rule [EdaSubstitutionRule],
+    ) -> (
+        Vec<EdaSubstitutionResult<'placement, 'rule>>,
+        EdaSubstitutionDiagnostics<'rule>,
+    ) {
         let mut results = vec![];
+        let mut matched_rules = vec![false; eda_substitution_rules.len()];
 
         for original_eda_placement in original_eda_placements.iter() {
             let mut eda_placement = original_eda_placement.clone();
             let mut chain = vec![];
+            let mut chain_error = None;
+            // the states of the placement in the chain, for detecting cycles
+            let mut previous_eda_placements = vec![original_eda_placement.clone()];
 
-            loop {
+            'passes: loop {
                 let mut applied_rule_count_this_pass = 0;
 
-                for rule in eda_substitution_rules.iter() {
+                for (rule_index, rule) in eda_substitution_rules
+                    .iter()
+                    .enumerate()
+                {
                     if rule.matches(&eda_placement) {
+                        matched_rules[rule_index] = true;
+
                         let original_eda_placement = eda_placement.clone();
                         eda_placement = rule.apply(&original_eda_placement);
 
@@ -128,6 +185,20 @@ pub fn substitute<'placement, 'rule>(
                             chain.push(EdaSubstitutionChainEntry {
                                 rule,
                             });
+
+                            if previous_eda_placements.contains(&eda_placement) {
+                                chain_error = Some(EdaSubstitutionChainError::Cycle {
+                                    length: chain.len(),
+                                });
+                                break 'passes;
+                            }
+                            if chain.len() >= MAX_CHAIN_LENGTH {
+                                chain_error = Some(EdaSubstitutionChainError::TooLong {
+                                    length: chain.len(),
+                                });
+                                break 'passes;
+                            }
+                            previous_eda_placements.push(eda_placement.clone());
                         }
                     }
                 }
@@ -141,10 +212,36 @@ pub fn substitute<'placement, 'rule>(
                 original_placement: original_eda_placement,
                 resulting_placement: eda_placement,
                 chain,
+                chain_error,
             })
         }
 
-        results
+        let diagnostics = EdaSubstitutionDiagnostics {
+            unmatched_rules: eda_substitution_rules
+                .iter()
+                .zip(matched_rules)
+                .filter_map(|(rule, matched)| (!matched).then_some(rule))
+                .collect(),
+            chain_errors: results
+                .iter()
+                .filter_map(|result| {
+                    result
+                        .chain_error
+                        .clone()
+                        .map(|chain_error| {
+                            (
+                                result
+                                    .original_placement
+                                    .ref_des
+                                    .clone(),
+                                chain_error,
+                            )
+                        })
+                })
+                .collect(),
+        };
+
+        (results, diagnostics)
     }
 }
 
@@ -155,8 +252,8 @@ pub mod eda_substitutor_tests {
 
     use crate::placement::{EdaPlacement, EdaPlacementField};
     use crate::substitution::{
-        EdaSubstitutionChainEntry, EdaSubstitutionResult, EdaSubstitutionRule, EdaSubstitutionRuleTransformItem,
-        EdaSubstitutor,
+        EdaSubstitutionChainEntry, EdaSubstitutionChainError, EdaSubstitutionDiagnostics, EdaSubstitutionResult,
+        EdaSubstitutionRule, EdaSubstitutionRuleTransformItem, EdaSubstitutor,
     };
 
     #[test]
@@ -241,6 +338,7 @@ pub fn substitute_one_diptrace_placement_using_a_chain() {
                     rule: &eda_substitution_rules[0],
                 },
             ],
+            chain_error: None,
         }];
 
         // when
@@ -330,6 +428,7 @@ pub fn substitute_one_kicad_placement_using_a_chain() {
                     rule: &eda_substitutions[0],
                 },
             ],
+            chain_error: None,
         }];
 
         // when
@@ -393,6 +492,7 @@ pub fn substitute_using_rule_that_always_matches() {
             chain: vec![EdaSubstitutionChainEntry {
                 rule: &eda_substitution_rules[0],
             }],
+            chain_error: None,
         }];
 
         // when
@@ -401,4 +501,50 @@ pub fn substitute_using_rule_that_always_matches() {
         // then
         assert_eq!(results, expected_results);
     }
+
+    #[test]
+    pub fn cyclic_chains_are_stopped() {
+        // given
+        let eda_placements = vec![EdaPlacement {
+            ref_des: "R1".to_string(),
+            fields: vec![EdaPlacementField::new("name".to_string(), "NAME1".to_string())],
+            ..EdaPlacement::default()
+        }];
+
+        // and rules that change the name back and forth
+        let build_rule = |from: &str, to: &str| EdaSubstitutionRule {
+            criteria: vec![Box::new(ExactMatchCriterion {
+                field_name: "name".to_string(),
+                field_pattern: from.to_string(),
+            })],
+            transforms: vec![EdaSubstitutionRuleTransformItem {
+                field_name: "name".to_string(),
+                field_value: to.to_string(),
+            }],
+        };
+        let eda_substitution_rules = vec![
+            build_rule("NAME1", "NAME2"),
+            build_rule("NAME2", "NAME1"),
+            build_rule("NAME3", "NAME4"),
+        ];
+
+        // when
+        let (results, diagnostics) =
+            EdaSubstitutor::substitute_with_diagnostics(eda_placements.as_slice(), eda_substitution_rules.as_slice());
+
+        // then
+        assert_eq!(results[0].chain.len(), 2);
+        assert_eq!(
+            results[0].chain_error,
+            Some(EdaSubstitutionChainError::Cycle {
+                length: 2
+            })
+        );
+        assert_eq!(diagnostics, EdaSubstitutionDiagnostics {
+            unmatched_rules: vec![&eda_substitution_rules[2]],
+            chain_errors: vec![("R1".to_string(), EdaSubstitutionChainError::Cycle {
+                length: 2
+            })],
+        });
+    }
 }
@@ -0,0 +1,69 @@
+//! EDA tools can export placements using different units, placements are always converted to millimeters when loaded.
+
+pub use eda_units::eda_units::unit_system::UnitSystem;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Parse a unit, as used in headers and values of placement exports, e.g. 'mm', 'mil', 'inch'.
+pub fn parse_unit_system(unit: &str) -> Option<UnitSystem> {
+    match unit
+        .trim()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "mm" | "millimeter" | "millimeters" => Some(UnitSystem::Millimeters),
+        "mil" | "mils" | "thou" => Some(UnitSystem::Mils),
+        "in" | "inch" | "inches" | "\"" => Some(UnitSystem::Inches),
+        _ => None,
+    }
+}
+
+/// Detect the unit system from CSV headers that contain a unit in brackets, e.g. 'Center X (mm)'.
+///
+/// Returns `None` if no header specifies a unit.
+pub fn detect_unit_system_from_headers<'a>(headers: impl IntoIterator<Item = &'a str>) -> Option<UnitSystem> {
+    headers.into_iter().find_map(|header| {
+        let (_, remainder) = header.rsplit_once('(')?;
+        let (unit, _) = remainder.split_once(')')?;
+
+        parse_unit_system(unit)
+    })
+}
+
+pub fn to_millimeters(value: Decimal, unit_system: UnitSystem) -> Decimal {
+    let factor = match unit_system {
+        UnitSystem::Millimeters => return value,
+        UnitSystem::Inches => dec!(25.4),
+        UnitSystem::Mils => dec!(0.0254),
+        UnitSystem::Si => dec!(0.01),
+    };
+
+    (value * factor).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(vec!["RefDes", "Name", "Value", "Side", "Center X (mm)", "Center Y (mm)", "Rotation"], Some(UnitSystem::Millimeters))]
+    #[case(vec!["RefDes", "Name", "Value", "Side", "Center X (mils)", "Center Y (mils)", "Rotation"], Some(UnitSystem::Mils))]
+    #[case(vec!["RefDes", "Name", "Value", "Side", "Center X (inch)", "Center Y (inch)", "Rotation"], Some(UnitSystem::Inches))]
+    #[case(vec!["Ref", "Val", "Package", "PosX", "PosY", "Rot", "Side"], None)]
+    #[case(vec!["Rotation (deg)"], None)]
+    fn detect_from_headers(#[case] headers: Vec<&str>, #[case] expected_unit_system: Option<UnitSystem>) {
+        // expect
+        assert_eq!(detect_unit_system_from_headers(headers), expected_unit_system);
+    }
+
+    #[rstest]
+    #[case(dec!(1.5), UnitSystem::Millimeters, dec!(1.5))]
+    #[case(dec!(1000), UnitSystem::Mils, dec!(25.4))]
+    #[case(dec!(-0.5), UnitSystem::Inches, dec!(-12.7))]
+    fn conversion(#[case] value: Decimal, #[case] unit_system: UnitSystem, #[case] expected_value: Decimal) {
+        // expect
+        assert_eq!(to_millimeters(value, unit_system), expected_value);
+    }
+}
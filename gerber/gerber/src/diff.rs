@@ -0,0 +1,307 @@
+//! Geometric comparison of gerber layers.
+//!
+//! Both layers are rendered onto a bitmap with a configurable resolution, the bitmaps are XOR'd and the differing
+//! cells are grouped into regions.  This allows users to confirm that gerbers which were re-exported from an EDA tool
+//! have not changed, even if the gerber commands themselves are different (e.g. ordering, aperture numbering).
+//!
+//! See [`crate::geometry`] for the limitations.
+
+use gerber_parser::GerberDoc;
+use gerber_types::Command;
+use thiserror::Error;
+use tracing::debug;
+
+use crate::geometry::{Bounds, GeometryError, Point, Shape, build_shapes};
+
+/// The maximum amount of cells in the bitmap, to prevent excessive memory usage when a too-fine resolution is used.
+const MAX_CELLS: usize = 64_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffOptions {
+    /// The size of a bitmap cell, in millimeters.
+    pub resolution: f64,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            resolution: 0.01,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DiffRegionKind {
+    /// Present in the 'new' layer, but not the 'old' layer.
+    Added,
+    /// Present in the 'old' layer, but not the 'new' layer.
+    Removed,
+}
+
+/// A connected region of cells that differ, coordinates are in millimeters.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DiffRegion {
+    pub kind: DiffRegionKind,
+    pub min: (f64, f64),
+    pub max: (f64, f64),
+    /// In square millimeters.
+    pub area: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DiffReport {
+    pub regions: Vec<DiffRegion>,
+}
+
+impl DiffReport {
+    pub fn is_identical(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    pub fn added(&self) -> impl Iterator<Item = &DiffRegion> {
+        self.regions
+            .iter()
+            .filter(|region| region.kind == DiffRegionKind::Added)
+    }
+
+    pub fn removed(&self) -> impl Iterator<Item = &DiffRegion> {
+        self.regions
+            .iter()
+            .filter(|region| region.kind == DiffRegionKind::Removed)
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum DiffError {
+    #[error("Invalid resolution. resolution: {0}")]
+    InvalidResolution(f64),
+    #[error("Resolution too fine for layer size. cells: {cells}, max: {max}")]
+    TooManyCells { cells: usize, max: usize },
+    #[error(transparent)]
+    GeometryError(#[from] GeometryError),
+}
+
+/// Compare two parsed gerber documents.
+///
+/// Commands that failed to parse are ignored.
+pub fn diff_documents(old: &GerberDoc, new: &GerberDoc, options: DiffOptions) -> Result<DiffReport, DiffError> {
+    fn commands(doc: &GerberDoc) -> Vec<Command> {
+        doc.commands
+            .iter()
+            .filter_map(|command| command.as_ref().ok().cloned())
+            .collect()
+    }
+
+    diff_commands(&commands(old), &commands(new), options)
+}
+
+/// Compare two layers, given as a list of gerber commands.
+pub fn diff_commands(old: &[Command], new: &[Command], options: DiffOptions) -> Result<DiffReport, DiffError> {
+    if !(options.resolution > 0.0) {
+        return Err(DiffError::InvalidResolution(options.resolution));
+    }
+
+    let old_shapes = build_shapes(old)?;
+    let new_shapes = build_shapes(new)?;
+
+    let Some(bounds) = Bounds::of_layers(&old_shapes, &new_shapes) else {
+        // both layers are empty
+        return Ok(DiffReport::default());
+    };
+
+    let grid = Grid::new(bounds, options.resolution)?;
+    debug!("diff grid. width: {}, height: {}", grid.width, grid.height);
+
+    let old_bitmap = grid.render(&old_shapes);
+    let new_bitmap = grid.render(&new_shapes);
+
+    let regions = grid.regions(&old_bitmap, &new_bitmap);
+
+    Ok(DiffReport {
+        regions,
+    })
+}
+
+struct Grid {
+    origin: Point,
+    resolution: f64,
+    width: usize,
+    height: usize,
+}
+
+impl Grid {
+    fn new(bounds: Bounds, resolution: f64) -> Result<Self, DiffError> {
+        let width = ((bounds.max.x - bounds.min.x) / resolution).ceil() as usize + 1;
+        let height = ((bounds.max.y - bounds.min.y) / resolution).ceil() as usize + 1;
+
+        let cells = width.saturating_mul(height);
+        if cells > MAX_CELLS {
+            return Err(DiffError::TooManyCells {
+                cells,
+                max: MAX_CELLS,
+            });
+        }
+
+        Ok(Self {
+            origin: bounds.min,
+            resolution,
+            width,
+            height,
+        })
+    }
+
+    fn cell_center(&self, x: usize, y: usize) -> Point {
+        Point {
+            x: self.origin.x + (x as f64 + 0.5) * self.resolution,
+            y: self.origin.y + (y as f64 + 0.5) * self.resolution,
+        }
+    }
+
+    fn cell_index(&self, value: f64, origin: f64, limit: usize) -> usize {
+        (((value - origin) / self.resolution)
+            .floor()
+            .max(0.0) as usize)
+            .min(limit - 1)
+    }
+
+    /// Shapes are rendered in order, so that 'clear' shapes remove previously rendered 'dark' shapes.
+    fn render(&self, shapes: &[Shape]) -> Vec<bool> {
+        let mut bitmap = vec![false; self.width * self.height];
+
+        for shape in shapes {
+            let Some(bounds) = shape.bounds() else {
+                continue;
+            };
+            let (x_min, x_max) = (
+                self.cell_index(bounds.min.x, self.origin.x, self.width),
+                self.cell_index(bounds.max.x, self.origin.x, self.width),
+            );
+            let (y_min, y_max) = (
+                self.cell_index(bounds.min.y, self.origin.y, self.height),
+                self.cell_index(bounds.max.y, self.origin.y, self.height),
+            );
+
+            for y in y_min..=y_max {
+                for x in x_min..=x_max {
+                    if shape.contains(self.cell_center(x, y)) {
+                        bitmap[y * self.width + x] = shape.dark;
+                    }
+                }
+            }
+        }
+
+        bitmap
+    }
+
+    /// Groups the differing cells into 4-connected regions.
+    fn regions(&self, old: &[bool], new: &[bool]) -> Vec<DiffRegion> {
+        let mut visited = vec![false; self.width * self.height];
+        let mut regions = vec![];
+        let mut stack = vec![];
+
+        for start in 0..visited.len() {
+            if visited[start] || old[start] == new[start] {
+                continue;
+            }
+
+            let kind = match new[start] {
+                true => DiffRegionKind::Added,
+                false => DiffRegionKind::Removed,
+            };
+
+            let (mut x_min, mut y_min, mut x_max, mut y_max) = (usize::MAX, usize::MAX, 0, 0);
+            let mut count = 0_usize;
+
+            visited[start] = true;
+            stack.push(start);
+            while let Some(index) = stack.pop() {
+                let (x, y) = (index % self.width, index / self.width);
+                count += 1;
+                x_min = x_min.min(x);
+                y_min = y_min.min(y);
+                x_max = x_max.max(x);
+                y_max = y_max.max(y);
+
+                let neighbours = [
+                    (x > 0).then(|| index - 1),
+                    (x + 1 < self.width).then(|| index + 1),
+                    (y > 0).then(|| index - self.width),
+                    (y + 1 < self.height).then(|| index + self.width),
+                ];
+
+                for neighbour in neighbours.into_iter().flatten() {
+                    if !visited[neighbour] && old[neighbour] != new[neighbour] && new[neighbour] == new[start] {
+                        visited[neighbour] = true;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+
+            regions.push(DiffRegion {
+                kind,
+                min: (
+                    self.origin.x + x_min as f64 * self.resolution,
+                    self.origin.y + y_min as f64 * self.resolution,
+                ),
+                max: (
+                    self.origin.x + (x_max + 1) as f64 * self.resolution,
+                    self.origin.y + (y_max + 1) as f64 * self.resolution,
+                ),
+                area: count as f64 * self.resolution * self.resolution,
+            });
+        }
+
+        regions
+    }
+}
+
+#[cfg(test)]
+mod diff_tests {
+    use super::*;
+    use crate::testing::flash_commands;
+
+    #[test]
+    fn identical_layers() {
+        // given
+        let commands = flash_commands(&[(0.0, 0.0), (5.0, 5.0)]);
+
+        // when
+        let report = diff_commands(&commands, &commands, DiffOptions::default()).unwrap();
+
+        // then
+        assert!(report.is_identical());
+    }
+
+    #[test]
+    fn added_and_removed_pads() {
+        // given
+        let old = flash_commands(&[(0.0, 0.0), (5.0, 5.0)]);
+        let new = flash_commands(&[(0.0, 0.0), (10.0, 0.0)]);
+
+        // when
+        let report = diff_commands(&old, &new, DiffOptions {
+            resolution: 0.05,
+        })
+        .unwrap();
+
+        // then
+        assert_eq!(report.added().count(), 1);
+        assert_eq!(report.removed().count(), 1);
+
+        let added = report.added().next().unwrap();
+        assert!(added.min.0 < 10.0 && added.max.0 > 10.0);
+        // area of a 1mm circle is ~0.785mm²
+        assert!((added.area - 0.785).abs() < 0.05);
+    }
+
+    #[test]
+    fn invalid_resolution() {
+        // when
+        let result = diff_commands(&[], &[], DiffOptions {
+            resolution: 0.0,
+        });
+
+        // then
+        assert_eq!(result, Err(DiffError::InvalidResolution(0.0)));
+    }
+}
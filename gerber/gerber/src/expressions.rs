@@ -0,0 +1,294 @@
+//! Evaluation of aperture macro (AM) arithmetic expressions and variables.
+//!
+//! Expressions use the operators `+`, `-`, `x` (multiplication) and `/`, parentheses, decimal constants and variables,
+//! e.g. `($1+$2)x0.5-$3`.  Multiplication and division take precedence over addition and subtraction, and unary `+` and
+//! `-` are allowed.
+//!
+//! Variables `$1`..`$n` are set from the parameters of the aperture definition, and can be (re)defined within the macro,
+//! e.g. `$4=$1/2`.  Variables that are not defined, e.g. when fewer parameters are given than the macro uses, are zero.
+
+use std::collections::BTreeMap;
+
+use gerber_types::{MacroBoolean, MacroDecimal, MacroInteger};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ExpressionError {
+    #[error("Unexpected character. expression: '{expression}', position: {position}")]
+    UnexpectedCharacter { expression: String, position: usize },
+    #[error("Unexpected end of expression. expression: '{0}'")]
+    UnexpectedEnd(String),
+    #[error("Invalid number. expression: '{expression}', position: {position}")]
+    InvalidNumber { expression: String, position: usize },
+    #[error("Division by zero. expression: '{0}'")]
+    DivisionByZero(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MacroVariables(BTreeMap<u32, f64>);
+
+impl MacroVariables {
+    /// The first argument is `$1`.
+    pub fn from_arguments(arguments: &[f64]) -> Self {
+        Self(
+            arguments
+                .iter()
+                .enumerate()
+                .map(|(index, value)| (index as u32 + 1, *value))
+                .collect(),
+        )
+    }
+
+    /// Undefined variables are zero.
+    pub fn get(&self, number: u32) -> f64 {
+        self.0
+            .get(&number)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    pub fn set(&mut self, number: u32, value: f64) {
+        self.0.insert(number, value);
+    }
+}
+
+pub fn evaluate(expression: &str, variables: &MacroVariables) -> Result<f64, ExpressionError> {
+    let mut parser = Parser {
+        expression,
+        bytes: expression.as_bytes(),
+        position: 0,
+        variables,
+    };
+
+    let value = parser.parse_sum()?;
+    parser.skip_whitespace();
+    if parser.position < parser.bytes.len() {
+        return Err(parser.unexpected_character());
+    }
+
+    Ok(value)
+}
+
+pub fn evaluate_decimal(value: &MacroDecimal, variables: &MacroVariables) -> Result<f64, ExpressionError> {
+    match value {
+        MacroDecimal::Value(value) => Ok(*value),
+        MacroDecimal::Variable(number) => Ok(variables.get(*number)),
+        MacroDecimal::Expression(expression) => evaluate(expression, variables),
+    }
+}
+
+/// Non-zero values are `true`, e.g. for the exposure of a primitive.
+pub fn evaluate_boolean(value: &MacroBoolean, variables: &MacroVariables) -> Result<bool, ExpressionError> {
+    match value {
+        MacroBoolean::Value(value) => Ok(*value),
+        MacroBoolean::Variable(number) => Ok(variables.get(*number) != 0.0),
+        MacroBoolean::Expression(expression) => evaluate(expression, variables).map(|value| value != 0.0),
+    }
+}
+
+/// Values are rounded to the nearest integer, negative values are zero.
+pub fn evaluate_integer(value: &MacroInteger, variables: &MacroVariables) -> Result<u32, ExpressionError> {
+    let value = match value {
+        MacroInteger::Value(value) => return Ok(*value),
+        MacroInteger::Variable(number) => variables.get(*number),
+        MacroInteger::Expression(expression) => evaluate(expression, variables)?,
+    };
+
+    Ok(value.round().max(0.0) as u32)
+}
+
+/// Recursive descent parser, the value is computed while parsing.
+struct Parser<'a> {
+    expression: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+    variables: &'a MacroVariables,
+}
+
+impl<'a> Parser<'a> {
+    fn parse_sum(&mut self) -> Result<f64, ExpressionError> {
+        let mut value = self.parse_product()?;
+        loop {
+            match self.peek() {
+                Some(b'+') => {
+                    self.position += 1;
+                    value += self.parse_product()?;
+                }
+                Some(b'-') => {
+                    self.position += 1;
+                    value -= self.parse_product()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<f64, ExpressionError> {
+        let mut value = self.parse_factor()?;
+        loop {
+            match self.peek() {
+                Some(b'x' | b'X') => {
+                    self.position += 1;
+                    value *= self.parse_factor()?;
+                }
+                Some(b'/') => {
+                    self.position += 1;
+                    let divisor = self.parse_factor()?;
+                    if divisor == 0.0 {
+                        return Err(ExpressionError::DivisionByZero(self.expression.to_string()));
+                    }
+                    value /= divisor;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn parse_factor(&mut self) -> Result<f64, ExpressionError> {
+        match self.peek() {
+            None => Err(ExpressionError::UnexpectedEnd(self.expression.to_string())),
+            Some(b'+') => {
+                self.position += 1;
+                self.parse_factor()
+            }
+            Some(b'-') => {
+                self.position += 1;
+                self.parse_factor().map(|value| -value)
+            }
+            Some(b'(') => {
+                self.position += 1;
+                let value = self.parse_sum()?;
+                match self.peek() {
+                    Some(b')') => {
+                        self.position += 1;
+                        Ok(value)
+                    }
+                    Some(_) => Err(self.unexpected_character()),
+                    None => Err(ExpressionError::UnexpectedEnd(self.expression.to_string())),
+                }
+            }
+            Some(b'$') => {
+                self.position += 1;
+                let start = self.position;
+                let digits = self.take_while(|byte| byte.is_ascii_digit());
+                let number = digits
+                    .parse::<u32>()
+                    .map_err(|_| ExpressionError::InvalidNumber {
+                        expression: self.expression.to_string(),
+                        position: start,
+                    })?;
+                Ok(self.variables.get(number))
+            }
+            Some(byte) if byte.is_ascii_digit() || byte == b'.' => {
+                let start = self.position;
+                let digits = self.take_while(|byte| byte.is_ascii_digit() || byte == b'.');
+                digits
+                    .parse::<f64>()
+                    .map_err(|_| ExpressionError::InvalidNumber {
+                        expression: self.expression.to_string(),
+                        position: start,
+                    })
+            }
+            Some(_) => Err(self.unexpected_character()),
+        }
+    }
+
+    /// Skips whitespace, then returns the next byte, without consuming it.
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.bytes.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(|byte| byte.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(|byte| predicate(*byte))
+        {
+            self.position += 1;
+        }
+        let expression = self.expression;
+        // Safety: only ASCII bytes are taken, so the slice is on character boundaries
+        &expression[start..self.position]
+    }
+
+    fn unexpected_character(&self) -> ExpressionError {
+        ExpressionError::UnexpectedCharacter {
+            expression: self.expression.to_string(),
+            position: self.position,
+        }
+    }
+}
+
+#[cfg(test)]
+mod expressions_tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("1.5", 1.5)]
+    #[case("1+2x3", 7.0)]
+    #[case("(1+2)x3", 9.0)]
+    #[case("10/4-1", 1.5)]
+    #[case("-$1+2", 0.0)]
+    #[case("$1x$2", 6.0)]
+    #[case("($1+$2)/2", 2.5)]
+    #[case("2X-$1", -4.0)]
+    #[case(" $1 + .5 ", 2.5)]
+    #[case("$9", 0.0)]
+    pub fn evaluate_expression(#[case] expression: &str, #[case] expected_value: f64) {
+        // given
+        let variables = MacroVariables::from_arguments(&[2.0, 3.0]);
+
+        // when
+        let value = evaluate(expression, &variables).unwrap();
+
+        // then
+        assert_eq!(value, expected_value);
+    }
+
+    #[rstest]
+    #[case("1+", ExpressionError::UnexpectedEnd("1+".to_string()))]
+    #[case("(1+2", ExpressionError::UnexpectedEnd("(1+2".to_string()))]
+    #[case("1*2", ExpressionError::UnexpectedCharacter { expression: "1*2".to_string(), position: 1 })]
+    #[case("1.2.3", ExpressionError::InvalidNumber { expression: "1.2.3".to_string(), position: 0 })]
+    #[case("$", ExpressionError::InvalidNumber { expression: "$".to_string(), position: 1 })]
+    #[case("1/(2-2)", ExpressionError::DivisionByZero("1/(2-2)".to_string()))]
+    pub fn invalid_expression(#[case] expression: &str, #[case] expected_error: ExpressionError) {
+        // when
+        let result = evaluate(expression, &MacroVariables::default());
+
+        // then
+        assert_eq!(result, Err(expected_error));
+    }
+
+    #[test]
+    pub fn evaluate_macro_values() {
+        // given
+        let variables = MacroVariables::from_arguments(&[0.0, 5.6]);
+
+        // expect
+        assert_eq!(evaluate_boolean(&MacroBoolean::Variable(1), &variables), Ok(false));
+        assert_eq!(
+            evaluate_boolean(&MacroBoolean::Expression("1-$1".to_string()), &variables),
+            Ok(true)
+        );
+        assert_eq!(evaluate_integer(&MacroInteger::Variable(2), &variables), Ok(6));
+        assert_eq!(
+            evaluate_decimal(&MacroDecimal::Expression("$2/2".to_string()), &variables),
+            Ok(2.8)
+        );
+    }
+}
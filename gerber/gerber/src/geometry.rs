@@ -0,0 +1,891 @@
+//! Interpretation of gerber commands into shapes, used for comparing and rendering layers.
+//!
+//! Limitations:
+//! * Arcs (G02/G03) are treated as straight lines to the end point.
+//! * Step-and-repeat is not supported, and is reported via [`GeometryError`].
+//! * Aperture macros can only be flashed, the circular edges of outline-like primitives (moiré rings, thermals) are
+//!   approximated by polygons, and primitives with exposure 'off' clear everything beneath them, not just the
+//!   primitives of the macro.
+
+use std::collections::HashMap;
+
+use gerber_types::{
+    Aperture, ApertureDefinition, ApertureMacro, Command, Coordinates, DCode, ExtendedCode, FunctionCode, GCode,
+    MacroContent, MacroDecimal, Operation, Polarity, Unit,
+};
+use thiserror::Error;
+use tracing::trace;
+
+use crate::expressions::{
+    ExpressionError, MacroVariables, evaluate, evaluate_boolean, evaluate_decimal, evaluate_integer,
+};
+
+const MM_PER_INCH: f64 = 25.4;
+
+/// The amount of line segments used to approximate a circle, when a circle cannot be represented by [`Geometry::Circle`].
+pub(crate) const CIRCLE_SEGMENTS: usize = 64;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum GeometryError {
+    #[error("Undefined aperture. code: {0}")]
+    UndefinedAperture(i32),
+    #[error("Unsupported aperture. code: {0}")]
+    UnsupportedAperture(i32),
+    #[error("Unsupported command. command: {0}")]
+    UnsupportedCommand(String),
+    #[error("Undefined aperture macro. name: {0}")]
+    UndefinedMacro(String),
+    #[error("Aperture macro error. name: {name}, cause: {cause}")]
+    MacroError { name: String, cause: ExpressionError },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Geometry {
+    Circle {
+        center: Point,
+        radius: f64,
+    },
+    Rectangle {
+        center: Point,
+        half_width: f64,
+        half_height: f64,
+    },
+    /// A line segment with a width, and round ends.
+    Stroke {
+        start: Point,
+        end: Point,
+        radius: f64,
+    },
+    Polygon {
+        vertices: Vec<Point>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Shape {
+    pub geometry: Geometry,
+    /// `false` for shapes drawn with a 'clear' polarity.
+    pub dark: bool,
+}
+
+impl Shape {
+    /// `None` for polygons without vertices, which cover nothing.
+    pub fn bounds(&self) -> Option<Bounds> {
+        match &self.geometry {
+            Geometry::Circle {
+                center,
+                radius,
+            } => Some(Bounds::around(*center, *radius, *radius)),
+            Geometry::Rectangle {
+                center,
+                half_width,
+                half_height,
+            } => Some(Bounds::around(*center, *half_width, *half_height)),
+            Geometry::Stroke {
+                start,
+                end,
+                radius,
+            } => Some(Bounds::around(*start, *radius, *radius).union(&Bounds::around(*end, *radius, *radius))),
+            Geometry::Polygon {
+                vertices,
+            } => vertices.first().map(|first| {
+                vertices
+                    .iter()
+                    .fold(Bounds::around(*first, 0.0, 0.0), |bounds, vertex| {
+                        bounds.union(&Bounds::around(*vertex, 0.0, 0.0))
+                    })
+            }),
+        }
+    }
+
+    /// In square millimeters.
+    pub fn area(&self) -> f64 {
+        match &self.geometry {
+            Geometry::Circle {
+                radius, ..
+            } => std::f64::consts::PI * radius * radius,
+            Geometry::Rectangle {
+                half_width,
+                half_height,
+                ..
+            } => 4.0 * half_width * half_height,
+            Geometry::Stroke {
+                start,
+                end,
+                radius,
+            } => std::f64::consts::PI * radius * radius + 2.0 * radius * distance_squared(*start, *end).sqrt(),
+            Geometry::Polygon {
+                vertices,
+            } => {
+                // shoelace formula
+                let twice_area: f64 = vertices
+                    .iter()
+                    .zip(vertices.iter().cycle().skip(1))
+                    .map(|(a, b)| a.x * b.y - b.x * a.y)
+                    .sum();
+                twice_area.abs() / 2.0
+            }
+        }
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        match &self.geometry {
+            Geometry::Circle {
+                center,
+                radius,
+            } => distance_squared(*center, point) <= radius * radius,
+            Geometry::Rectangle {
+                center,
+                half_width,
+                half_height,
+            } => (point.x - center.x).abs() <= *half_width && (point.y - center.y).abs() <= *half_height,
+            Geometry::Stroke {
+                start,
+                end,
+                radius,
+            } => segment_distance_squared(*start, *end, point) <= radius * radius,
+            Geometry::Polygon {
+                vertices,
+            } => polygon_contains(vertices, point),
+        }
+    }
+}
+
+fn distance_squared(a: Point, b: Point) -> f64 {
+    (a.x - b.x).powi(2) + (a.y - b.y).powi(2)
+}
+
+fn segment_distance_squared(start: Point, end: Point, point: Point) -> f64 {
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return distance_squared(start, point);
+    }
+
+    let t = (((point.x - start.x) * dx + (point.y - start.y) * dy) / length_squared).clamp(0.0, 1.0);
+    distance_squared(
+        Point {
+            x: start.x + t * dx,
+            y: start.y + t * dy,
+        },
+        point,
+    )
+}
+
+/// Even-odd rule.
+fn polygon_contains(vertices: &[Point], point: Point) -> bool {
+    let Some(mut j) = vertices.len().checked_sub(1) else {
+        return false;
+    };
+    let mut inside = false;
+    for i in 0..vertices.len() {
+        let (a, b) = (vertices[i], vertices[j]);
+        if (a.y > point.y) != (b.y > point.y) && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn around(center: Point, half_width: f64, half_height: f64) -> Self {
+        Self {
+            min: Point {
+                x: center.x - half_width,
+                y: center.y - half_height,
+            },
+            max: Point {
+                x: center.x + half_width,
+                y: center.y + half_height,
+            },
+        }
+    }
+
+    pub fn union(&self, other: &Bounds) -> Self {
+        Self {
+            min: Point {
+                x: self.min.x.min(other.min.x),
+                y: self.min.y.min(other.min.y),
+            },
+            max: Point {
+                x: self.max.x.max(other.max.x),
+                y: self.max.y.max(other.max.y),
+            },
+        }
+    }
+
+    pub fn of_shapes<'a>(shapes: impl Iterator<Item = &'a Shape>) -> Option<Self> {
+        shapes
+            .map(Shape::bounds)
+            .reduce(|a, b| a.union(&b))
+    }
+
+    pub fn of_layers(old: &[Shape], new: &[Shape]) -> Option<Self> {
+        Self::of_shapes(old.iter().chain(new.iter()))
+    }
+}
+
+/// The amount of dark shapes of a layer, e.g. the apertures of a paste layer, and their total area.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ApertureSummary {
+    pub count: usize,
+    /// In square millimeters, overlapping shapes are not merged.
+    pub area: f64,
+}
+
+/// Summarizes the dark shapes of the layer, shapes drawn with a 'clear' polarity are ignored.
+pub fn summarize_apertures(commands: &[Command]) -> Result<ApertureSummary, GeometryError> {
+    let shapes = build_shapes(commands)?;
+
+    Ok(shapes
+        .iter()
+        .filter(|shape| shape.dark)
+        .fold(ApertureSummary::default(), |summary, shape| ApertureSummary {
+            count: summary.count + 1,
+            area: summary.area + shape.area(),
+        }))
+}
+
+/// Interprets the commands, building a list of shapes in millimeters.
+pub(crate) fn build_shapes(commands: &[Command]) -> Result<Vec<Shape>, GeometryError> {
+    let mut apertures: HashMap<i32, Aperture> = HashMap::new();
+    let mut macros: HashMap<String, ApertureMacro> = HashMap::new();
+    let mut scale = 1.0;
+    let mut current_aperture: Option<i32> = None;
+    let mut position = Point {
+        x: 0.0,
+        y: 0.0,
+    };
+    let mut dark = true;
+    let mut region: Option<Vec<Point>> = None;
+    let mut shapes = vec![];
+
+    let resolve = |coordinates: &Option<Coordinates>, position: Point, scale: f64| -> Point {
+        let Some(coordinates) = coordinates else {
+            return position;
+        };
+        Point {
+            x: coordinates
+                .x
+                .map_or(position.x, |x| f64::from(x) * scale),
+            y: coordinates
+                .y
+                .map_or(position.y, |y| f64::from(y) * scale),
+        }
+    };
+
+    for command in commands {
+        trace!("geometry command: {:?}", command);
+        match command {
+            Command::ExtendedCode(ExtendedCode::Unit(unit)) => {
+                scale = match unit {
+                    Unit::Millimeters => 1.0,
+                    Unit::Inches => MM_PER_INCH,
+                };
+            }
+            Command::ExtendedCode(ExtendedCode::ApertureDefinition(ApertureDefinition {
+                code,
+                aperture,
+            })) => {
+                apertures.insert(*code, aperture.clone());
+            }
+            Command::ExtendedCode(ExtendedCode::ApertureMacro(aperture_macro)) => {
+                macros.insert(aperture_macro.name.clone(), aperture_macro.clone());
+            }
+            Command::ExtendedCode(ExtendedCode::LoadPolarity(polarity)) => {
+                dark = matches!(polarity, Polarity::Dark);
+            }
+            Command::ExtendedCode(ExtendedCode::StepAndRepeat(_)) => {
+                return Err(GeometryError::UnsupportedCommand(format!("{:?}", command)));
+            }
+            Command::FunctionCode(FunctionCode::GCode(GCode::RegionMode(enabled))) => {
+                if *enabled {
+                    region = Some(vec![]);
+                } else if let Some(vertices) = region.take() {
+                    push_region(&mut shapes, vertices, dark);
+                }
+            }
+            Command::FunctionCode(FunctionCode::DCode(DCode::SelectAperture(code))) => {
+                current_aperture = Some(*code);
+            }
+            Command::FunctionCode(FunctionCode::DCode(DCode::Operation(operation))) => match operation {
+                Operation::Move(coordinates) => {
+                    position = resolve(coordinates, position, scale);
+                    if let Some(vertices) = region.as_mut() {
+                        // a move within a region starts a new contour
+                        let contour = std::mem::take(vertices);
+                        push_region(&mut shapes, contour, dark);
+                        vertices.push(position);
+                    }
+                }
+                Operation::Interpolate(coordinates, _offset) => {
+                    let end = resolve(coordinates, position, scale);
+                    if let Some(vertices) = region.as_mut() {
+                        if vertices.is_empty() {
+                            vertices.push(position);
+                        }
+                        vertices.push(end);
+                    } else {
+                        let aperture = lookup_aperture(&apertures, current_aperture)?;
+                        let radius = stroke_radius(aperture, current_aperture.unwrap())? * scale;
+                        shapes.push(Shape {
+                            geometry: Geometry::Stroke {
+                                start: position,
+                                end,
+                                radius,
+                            },
+                            dark,
+                        });
+                    }
+                    position = end;
+                }
+                Operation::Flash(coordinates) => {
+                    position = resolve(coordinates, position, scale);
+                    let aperture = lookup_aperture(&apertures, current_aperture)?;
+                    if let Aperture::Macro(name, arguments) = aperture {
+                        let aperture_macro = macros
+                            .get(name)
+                            .ok_or(GeometryError::UndefinedMacro(name.clone()))?;
+                        let arguments = arguments.as_deref().unwrap_or_default();
+                        shapes.extend(macro_shapes(aperture_macro, arguments, position, scale, dark).map_err(
+                            |cause| GeometryError::MacroError {
+                                name: name.clone(),
+                                cause,
+                            },
+                        )?);
+                    } else {
+                        let geometry = flash_geometry(aperture, current_aperture.unwrap(), position, scale)?;
+                        shapes.push(Shape {
+                            geometry,
+                            dark,
+                        });
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    Ok(shapes)
+}
+
+fn push_region(shapes: &mut Vec<Shape>, vertices: Vec<Point>, dark: bool) {
+    if vertices.len() < 3 {
+        return;
+    }
+    shapes.push(Shape {
+        geometry: Geometry::Polygon {
+            vertices,
+        },
+        dark,
+    });
+}
+
+fn lookup_aperture(apertures: &HashMap<i32, Aperture>, code: Option<i32>) -> Result<&Aperture, GeometryError> {
+    let code = code.ok_or(GeometryError::UndefinedAperture(0))?;
+    apertures
+        .get(&code)
+        .ok_or(GeometryError::UndefinedAperture(code))
+}
+
+fn stroke_radius(aperture: &Aperture, code: i32) -> Result<f64, GeometryError> {
+    match aperture {
+        Aperture::Circle(circle) => Ok(circle.diameter / 2.0),
+        // FUTURE rectangular apertures can be used for strokes, but the spec discourages it, approximate with a circle.
+        Aperture::Rectangle(rectangular) | Aperture::Obround(rectangular) => Ok(rectangular.x.min(rectangular.y) / 2.0),
+        _ => Err(GeometryError::UnsupportedAperture(code)),
+    }
+}
+
+fn flash_geometry(aperture: &Aperture, code: i32, center: Point, scale: f64) -> Result<Geometry, GeometryError> {
+    match aperture {
+        Aperture::Circle(circle) => Ok(Geometry::Circle {
+            center,
+            radius: circle.diameter / 2.0 * scale,
+        }),
+        Aperture::Rectangle(rectangular) => Ok(Geometry::Rectangle {
+            center,
+            half_width: rectangular.x / 2.0 * scale,
+            half_height: rectangular.y / 2.0 * scale,
+        }),
+        Aperture::Obround(rectangular) => {
+            let (width, height) = (rectangular.x * scale, rectangular.y * scale);
+            let radius = width.min(height) / 2.0;
+            let (dx, dy) = if width > height {
+                (width / 2.0 - radius, 0.0)
+            } else {
+                (0.0, height / 2.0 - radius)
+            };
+            Ok(Geometry::Stroke {
+                start: Point {
+                    x: center.x - dx,
+                    y: center.y - dy,
+                },
+                end: Point {
+                    x: center.x + dx,
+                    y: center.y + dy,
+                },
+                radius,
+            })
+        }
+        Aperture::Polygon(polygon) => {
+            let radius = polygon.diameter / 2.0 * scale;
+            let rotation = polygon
+                .rotation
+                .unwrap_or(0.0)
+                .to_radians();
+            let vertices = (0..polygon.vertices)
+                .map(|index| {
+                    let angle = rotation + std::f64::consts::TAU * index as f64 / polygon.vertices as f64;
+                    Point {
+                        x: center.x + radius * angle.cos(),
+                        y: center.y + radius * angle.sin(),
+                    }
+                })
+                .collect();
+            Ok(Geometry::Polygon {
+                vertices,
+            })
+        }
+        _ => Err(GeometryError::UnsupportedAperture(code)),
+    }
+}
+
+/// Maps macro coordinates, in the unit of the file, to millimeters, rotated about the origin of the macro, which is
+/// placed at the flash position.
+struct MacroTransform {
+    position: Point,
+    scale: f64,
+    /// In degrees, counter-clockwise.
+    rotation: f64,
+}
+
+impl MacroTransform {
+    fn apply(&self, point: Point) -> Point {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let (x, y) = (point.x * self.scale, point.y * self.scale);
+        Point {
+            x: self.position.x + x * cos - y * sin,
+            y: self.position.y + x * sin + y * cos,
+        }
+    }
+
+    fn polygon(&self, vertices: Vec<Point>) -> Geometry {
+        Geometry::Polygon {
+            vertices: vertices
+                .into_iter()
+                .map(|vertex| self.apply(vertex))
+                .collect(),
+        }
+    }
+}
+
+/// Builds the shapes of the primitives of a flashed aperture macro, in the order they are defined.
+fn macro_shapes(
+    aperture_macro: &ApertureMacro,
+    arguments: &[MacroDecimal],
+    position: Point,
+    scale: f64,
+    dark: bool,
+) -> Result<Vec<Shape>, ExpressionError> {
+    let arguments = arguments
+        .iter()
+        .map(|argument| evaluate_decimal(argument, &MacroVariables::default()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut variables = MacroVariables::from_arguments(&arguments);
+
+    let transform = |rotation: f64| MacroTransform {
+        position,
+        scale,
+        rotation,
+    };
+
+    let mut shapes = vec![];
+    for content in aperture_macro.content.iter() {
+        trace!("macro content: {:?}", content);
+        match content {
+            MacroContent::VariableDefinition(definition) => {
+                let value = evaluate(&definition.expression, &variables)?;
+                variables.set(definition.number, value);
+            }
+            MacroContent::Circle(circle) => {
+                let rotation = circle
+                    .angle
+                    .as_ref()
+                    .map(|angle| evaluate_decimal(angle, &variables))
+                    .transpose()?
+                    .unwrap_or(0.0);
+                let center = evaluate_point(&circle.center, &variables)?;
+                shapes.push(Shape {
+                    geometry: Geometry::Circle {
+                        center: transform(rotation).apply(center),
+                        radius: evaluate_decimal(&circle.diameter, &variables)? / 2.0 * scale,
+                    },
+                    dark: dark && evaluate_boolean(&circle.exposure, &variables)?,
+                });
+            }
+            MacroContent::VectorLine(line) => {
+                let start = evaluate_point(&line.start, &variables)?;
+                let end = evaluate_point(&line.end, &variables)?;
+                let half_width = evaluate_decimal(&line.width, &variables)? / 2.0;
+                let length = distance_squared(start, end).sqrt();
+                if length == 0.0 {
+                    continue;
+                }
+                // perpendicular to the line, half the width long
+                let (dx, dy) = (
+                    -(end.y - start.y) / length * half_width,
+                    (end.x - start.x) / length * half_width,
+                );
+                let vertices = vec![
+                    Point {
+                        x: start.x + dx,
+                        y: start.y + dy,
+                    },
+                    Point {
+                        x: start.x - dx,
+                        y: start.y - dy,
+                    },
+                    Point {
+                        x: end.x - dx,
+                        y: end.y - dy,
+                    },
+                    Point {
+                        x: end.x + dx,
+                        y: end.y + dy,
+                    },
+                ];
+                shapes.push(Shape {
+                    geometry: transform(evaluate_decimal(&line.angle, &variables)?).polygon(vertices),
+                    dark: dark && evaluate_boolean(&line.exposure, &variables)?,
+                });
+            }
+            MacroContent::CenterLine(line) => {
+                let center = evaluate_point(&line.center, &variables)?;
+                let width = evaluate_decimal(&line.dimensions.0, &variables)?;
+                let height = evaluate_decimal(&line.dimensions.1, &variables)?;
+                shapes.push(Shape {
+                    geometry: transform(evaluate_decimal(&line.angle, &variables)?).polygon(rectangle_vertices(
+                        center,
+                        width / 2.0,
+                        height / 2.0,
+                    )),
+                    dark: dark && evaluate_boolean(&line.exposure, &variables)?,
+                });
+            }
+            MacroContent::Outline(outline) => {
+                let vertices = outline
+                    .points
+                    .iter()
+                    .map(|point| evaluate_point(point, &variables))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if vertices.len() < 3 {
+                    continue;
+                }
+                shapes.push(Shape {
+                    geometry: transform(evaluate_decimal(&outline.angle, &variables)?).polygon(vertices),
+                    dark: dark && evaluate_boolean(&outline.exposure, &variables)?,
+                });
+            }
+            MacroContent::Polygon(polygon) => {
+                let center = evaluate_point(&polygon.center, &variables)?;
+                let radius = evaluate_decimal(&polygon.diameter, &variables)? / 2.0;
+                let vertex_count = evaluate_integer(&polygon.vertices, &variables)? as usize;
+                if vertex_count < 3 {
+                    continue;
+                }
+                // the first vertex is on the positive X axis, before rotation
+                let vertices = (0..vertex_count)
+                    .map(|index| circle_point(center, radius, index, vertex_count))
+                    .collect();
+                shapes.push(Shape {
+                    geometry: transform(evaluate_decimal(&polygon.angle, &variables)?).polygon(vertices),
+                    dark: dark && evaluate_boolean(&polygon.exposure, &variables)?,
+                });
+            }
+            MacroContent::Moire(moire) => {
+                let transform = transform(evaluate_decimal(&moire.angle, &variables)?);
+                let center = evaluate_point(&moire.center, &variables)?;
+                let ring_thickness = evaluate_decimal(&moire.ring_thickness, &variables)?;
+                let gap = evaluate_decimal(&moire.gap, &variables)?;
+                let max_rings = evaluate_integer(&moire.max_rings, &variables)?;
+                let cross_hair_thickness = evaluate_decimal(&moire.cross_hair_thickness, &variables)?;
+                let cross_hair_length = evaluate_decimal(&moire.cross_hair_length, &variables)?;
+
+                let mut outer_radius = evaluate_decimal(&moire.diameter, &variables)? / 2.0;
+                for _ in 0..max_rings {
+                    if outer_radius <= 0.0 {
+                        break;
+                    }
+                    let inner_radius = outer_radius - ring_thickness;
+                    shapes.push(Shape {
+                        geometry: transform.polygon(ring_vertices(center, outer_radius, inner_radius)),
+                        dark,
+                    });
+                    outer_radius -= ring_thickness + gap;
+                }
+
+                for (half_width, half_height) in [
+                    (cross_hair_length / 2.0, cross_hair_thickness / 2.0),
+                    (cross_hair_thickness / 2.0, cross_hair_length / 2.0),
+                ] {
+                    shapes.push(Shape {
+                        geometry: transform.polygon(rectangle_vertices(center, half_width, half_height)),
+                        dark,
+                    });
+                }
+            }
+            MacroContent::Thermal(thermal) => {
+                let transform = transform(evaluate_decimal(&thermal.angle, &variables)?);
+                let center = evaluate_point(&thermal.center, &variables)?;
+                let outer_radius = evaluate_decimal(&thermal.outer_diameter, &variables)? / 2.0;
+                let inner_radius = evaluate_decimal(&thermal.inner_diameter, &variables)? / 2.0;
+                let half_gap = evaluate_decimal(&thermal.gap, &variables)? / 2.0;
+
+                for vertices in thermal_quadrant_vertices(center, outer_radius, inner_radius, half_gap) {
+                    shapes.push(Shape {
+                        geometry: transform.polygon(vertices),
+                        dark,
+                    });
+                }
+            }
+            MacroContent::Comment(_) => {}
+        }
+    }
+
+    Ok(shapes)
+}
+
+fn evaluate_point(point: &(MacroDecimal, MacroDecimal), variables: &MacroVariables) -> Result<Point, ExpressionError> {
+    Ok(Point {
+        x: evaluate_decimal(&point.0, variables)?,
+        y: evaluate_decimal(&point.1, variables)?,
+    })
+}
+
+fn rectangle_vertices(center: Point, half_width: f64, half_height: f64) -> Vec<Point> {
+    [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+        .into_iter()
+        .map(|(x, y)| Point {
+            x: center.x + x * half_width,
+            y: center.y + y * half_height,
+        })
+        .collect()
+}
+
+/// The point at `index` of `count` points, evenly spaced counter-clockwise around the circle, starting on the positive X
+/// axis.
+fn circle_point(center: Point, radius: f64, index: usize, count: usize) -> Point {
+    let angle = std::f64::consts::TAU * (index % count) as f64 / count as f64;
+    Point {
+        x: center.x + radius * angle.cos(),
+        y: center.y + radius * angle.sin(),
+    }
+}
+
+/// A ring as a single polygon, the inner contour is reversed and joined to the outer contour, so that it forms a hole
+/// using the even-odd rule.
+fn ring_vertices(center: Point, outer_radius: f64, inner_radius: f64) -> Vec<Point> {
+    let outer = (0..=CIRCLE_SEGMENTS).map(|index| circle_point(center, outer_radius, index, CIRCLE_SEGMENTS));
+    if inner_radius <= 0.0 {
+        return outer.take(CIRCLE_SEGMENTS).collect();
+    }
+    let inner = (0..=CIRCLE_SEGMENTS)
+        .rev()
+        .map(|index| circle_point(center, inner_radius, index, CIRCLE_SEGMENTS));
+
+    outer.chain(inner).collect()
+}
+
+/// The four parts of a ring that is split by a cross shaped gap, one for each quadrant.
+fn thermal_quadrant_vertices(center: Point, outer_radius: f64, inner_radius: f64, half_gap: f64) -> Vec<Vec<Point>> {
+    // the gap is wider than the ring
+    if half_gap * std::f64::consts::SQRT_2 >= outer_radius {
+        return vec![];
+    }
+
+    let arc = |radius: f64, start_angle: f64, end_angle: f64| {
+        let segments = CIRCLE_SEGMENTS / 4;
+        (0..=segments).map(move |index| {
+            let angle = start_angle + (end_angle - start_angle) * index as f64 / segments as f64;
+            Point {
+                x: radius * angle.cos(),
+                y: radius * angle.sin(),
+            }
+        })
+    };
+
+    // first quadrant, from the gap along the X axis to the gap along the Y axis
+    let outer_angle = (half_gap / outer_radius).asin();
+    let mut vertices = arc(outer_radius, outer_angle, std::f64::consts::FRAC_PI_2 - outer_angle).collect::<Vec<_>>();
+    if half_gap < inner_radius {
+        let inner_angle = (half_gap / inner_radius).asin();
+        vertices.extend(arc(
+            inner_radius,
+            std::f64::consts::FRAC_PI_2 - inner_angle,
+            inner_angle,
+        ));
+    } else {
+        // the gap is wider than the hole
+        vertices.push(Point {
+            x: half_gap,
+            y: half_gap,
+        });
+    }
+
+    [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)]
+        .into_iter()
+        .map(|(x_sign, y_sign)| {
+            vertices
+                .iter()
+                .map(|vertex| Point {
+                    x: center.x + x_sign * vertex.x,
+                    y: center.y + y_sign * vertex.y,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod geometry_tests {
+    use super::*;
+    use crate::testing::flash_commands;
+
+    #[test]
+    fn summarize_apertures_of_flashes() {
+        // given
+        let commands = flash_commands(&[(0.0, 0.0), (5.0, 0.0), (10.0, 0.0)]);
+
+        // when
+        let summary = summarize_apertures(&commands).unwrap();
+
+        // then
+        assert_eq!(summary.count, 3);
+        // 1mm diameter circles
+        let expected_area = 3.0 * std::f64::consts::PI * 0.25;
+        assert!((summary.area - expected_area).abs() < 1e-9);
+    }
+
+    #[test]
+    fn area_of_polygon() {
+        // given
+        let shape = Shape {
+            geometry: Geometry::Polygon {
+                vertices: vec![
+                    Point {
+                        x: 0.0,
+                        y: 0.0,
+                    },
+                    Point {
+                        x: 4.0,
+                        y: 0.0,
+                    },
+                    Point {
+                        x: 4.0,
+                        y: 2.0,
+                    },
+                    Point {
+                        x: 0.0,
+                        y: 2.0,
+                    },
+                ],
+            },
+            dark: true,
+        };
+
+        // when
+        let area = shape.area();
+
+        // then
+        assert_eq!(area, 8.0);
+    }
+
+    #[test]
+    fn empty_polygon_has_no_bounds() {
+        // given
+        let shape = Shape {
+            geometry: Geometry::Polygon {
+                vertices: vec![],
+            },
+            dark: true,
+        };
+
+        // when
+        let bounds = shape.bounds();
+
+        // then
+        assert_eq!(bounds, None);
+        assert!(!shape.contains(Point {
+            x: 0.0,
+            y: 0.0,
+        }));
+    }
+
+    #[test]
+    fn undefined_aperture_macro() {
+        // given
+        let mut commands = flash_commands(&[(0.0, 0.0)]);
+        commands[1] = Command::ExtendedCode(ExtendedCode::ApertureDefinition(ApertureDefinition {
+            code: 10,
+            aperture: Aperture::Macro("MISSING".to_string(), None),
+        }));
+
+        // when
+        let result = summarize_apertures(&commands);
+
+        // then
+        assert_eq!(result, Err(GeometryError::UndefinedMacro("MISSING".to_string())));
+    }
+
+    #[test]
+    fn thermal_quadrants() {
+        // when
+        let quadrants = thermal_quadrant_vertices(
+            Point {
+                x: 0.0,
+                y: 0.0,
+            },
+            2.0,
+            1.0,
+            0.25,
+        );
+
+        // then
+        assert_eq!(quadrants.len(), 4);
+        for vertices in quadrants.iter() {
+            let shape = Shape {
+                geometry: Geometry::Polygon {
+                    vertices: vertices.clone(),
+                },
+                dark: true,
+            };
+            // a quarter of the ring, less the gap, the arcs are approximated
+            let expected_area = (std::f64::consts::PI * (4.0 - 1.0)) / 4.0 - 0.25 * (2.0 - 1.0) * 2.0;
+            assert!((shape.area() - expected_area).abs() < 0.05);
+        }
+
+        // and the gap is clear
+        assert!(
+            !quadrants
+                .iter()
+                .any(|vertices| polygon_contains(vertices, Point {
+                    x: 1.5,
+                    y: 0.0
+                }))
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Grouping, ordering and presets for sets of layers, based on the function of each layer.
+//!
+//! The standard stack-up, from the top of the PCB to the bottom, is: assembly, component, legend (silk), paste,
+//! solder (mask), copper, then the profile, followed by the bottom layers in the reverse order.  Layers with the
+//! 'other' function are outermost.
+
+use std::collections::BTreeMap;
+
+use pnp::pcb::PcbSide;
+use strum_macros::VariantArray;
+
+use crate::render::Color;
+use crate::{GerberFileFunction, GerberFileFunctionDiscriminants};
+
+/// The position of the profile, which is between the top and bottom layers, see [`stackup_position`].
+const STACKUP_MIDDLE: usize = 7;
+
+/// Lower is nearer the top of the PCB.
+pub fn stackup_position(function: &GerberFileFunction) -> usize {
+    // how far from the outside of the PCB the layer is
+    let depth = match GerberFileFunctionDiscriminants::from(function) {
+        GerberFileFunctionDiscriminants::Other => 0,
+        GerberFileFunctionDiscriminants::Assembly => 1,
+        GerberFileFunctionDiscriminants::Component => 2,
+        GerberFileFunctionDiscriminants::Legend => 3,
+        GerberFileFunctionDiscriminants::Paste => 4,
+        GerberFileFunctionDiscriminants::Solder => 5,
+        GerberFileFunctionDiscriminants::Copper => 6,
+        GerberFileFunctionDiscriminants::Profile => STACKUP_MIDDLE,
+    };
+
+    match layer_side(function) {
+        Some(PcbSide::Top) => depth,
+        Some(PcbSide::Bottom) => 2 * STACKUP_MIDDLE - depth,
+        None => STACKUP_MIDDLE,
+    }
+}
+
+/// Unlike [`GerberFileFunction::pcb_side`], the side of 'other' layers is included.
+fn layer_side(function: &GerberFileFunction) -> Option<PcbSide> {
+    match function {
+        GerberFileFunction::Other(pcb_side) => *pcb_side,
+        _ => function.pcb_side(),
+    }
+}
+
+/// The position of the layer in the drawing order when viewing the PCB from a side, i.e. layers furthest from the
+/// viewer are first.  Layers without a function are drawn before all other layers.
+pub fn viewing_order(function: Option<&GerberFileFunction>, viewed_from: PcbSide) -> Option<usize> {
+    function.map(|function| {
+        let position = stackup_position(function);
+        match viewed_from {
+            PcbSide::Top => 2 * STACKUP_MIDDLE - position,
+            PcbSide::Bottom => position,
+        }
+    })
+}
+
+/// Sorts the layers into drawing order, see [`viewing_order`], the sort is stable.
+pub fn sort_for_viewing<T>(
+    layers: &mut [T],
+    viewed_from: PcbSide,
+    function_of: impl Fn(&T) -> Option<GerberFileFunction>,
+) {
+    layers.sort_by_key(|layer| viewing_order(function_of(layer).as_ref(), viewed_from));
+}
+
+/// Groups the layers by function, e.g. all copper layers, each group is in stack-up order, top first.
+///
+/// Layers without a function are in the `None` group.
+pub fn group_by_function<T>(
+    layers: impl IntoIterator<Item = T>,
+    function_of: impl Fn(&T) -> Option<GerberFileFunction>,
+) -> BTreeMap<Option<GerberFileFunctionDiscriminants>, Vec<T>> {
+    let mut groups = layers
+        .into_iter()
+        .fold(BTreeMap::<_, Vec<T>>::new(), |mut groups, layer| {
+            let group = function_of(&layer).map(GerberFileFunctionDiscriminants::from);
+            groups
+                .entry(group)
+                .or_default()
+                .push(layer);
+            groups
+        });
+
+    for layers in groups.values_mut() {
+        sort_for_viewing(layers, PcbSide::Bottom, &function_of);
+    }
+
+    groups
+}
+
+/// The default color used to display a layer of each function, copper and mask layers are translucent.
+pub fn default_color(function: &GerberFileFunction) -> Color {
+    match function {
+        GerberFileFunction::Copper(PcbSide::Top) => Color::rgba(200, 130, 60, 200),
+        GerberFileFunction::Copper(PcbSide::Bottom) => Color::rgba(70, 120, 200, 200),
+        GerberFileFunction::Solder(_) => Color::rgba(0, 110, 40, 140),
+        GerberFileFunction::Paste(_) => Color::rgb(170, 170, 180),
+        GerberFileFunction::Legend(_) => Color::rgb(240, 240, 240),
+        GerberFileFunction::Profile => Color::rgb(230, 200, 0),
+        GerberFileFunction::Assembly(_) => Color::rgb(200, 90, 200),
+        GerberFileFunction::Component(_) => Color::rgb(90, 200, 200),
+        GerberFileFunction::Other(_) => Color::rgb(128, 128, 128),
+    }
+}
+
+/// A set of visible layers, viewed from a side of the PCB.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    VariantArray,
+    serde::Serialize,
+    serde::Deserialize
+)]
+pub enum LayerPreset {
+    /// Profile, assembly, component, legend and paste layers.
+    TopAssembly,
+    BottomAssembly,
+    /// Profile and paste layers.
+    TopPaste,
+    BottomPaste,
+    /// Profile, solder mask and copper layers.
+    TopCopper,
+    BottomCopper,
+    /// All the layers, including layers without a function, viewed from the top.
+    All,
+}
+
+impl LayerPreset {
+    pub fn viewed_from(&self) -> PcbSide {
+        match self {
+            LayerPreset::BottomAssembly | LayerPreset::BottomPaste | LayerPreset::BottomCopper => PcbSide::Bottom,
+            _ => PcbSide::Top,
+        }
+    }
+
+    fn groups(&self) -> &'static [GerberFileFunctionDiscriminants] {
+        match self {
+            LayerPreset::TopAssembly | LayerPreset::BottomAssembly => &[
+                GerberFileFunctionDiscriminants::Assembly,
+                GerberFileFunctionDiscriminants::Component,
+                GerberFileFunctionDiscriminants::Legend,
+                GerberFileFunctionDiscriminants::Paste,
+            ],
+            LayerPreset::TopPaste | LayerPreset::BottomPaste => &[GerberFileFunctionDiscriminants::Paste],
+            LayerPreset::TopCopper | LayerPreset::BottomCopper => &[
+                GerberFileFunctionDiscriminants::Solder,
+                GerberFileFunctionDiscriminants::Copper,
+            ],
+            LayerPreset::All => &[],
+        }
+    }
+
+    /// The profile is included in every preset.
+    pub fn includes(&self, function: Option<&GerberFileFunction>) -> bool {
+        match (self, function) {
+            (LayerPreset::All, _) => true,
+            (_, None) => false,
+            (_, Some(GerberFileFunction::Profile)) => true,
+            (_, Some(function)) => {
+                layer_side(function) == Some(self.viewed_from())
+                    && self
+                        .groups()
+                        .contains(&GerberFileFunctionDiscriminants::from(function))
+            }
+        }
+    }
+
+    /// Sorts the layers into drawing order for the side the preset is viewed from, and sets the visibility of each
+    /// layer.
+    pub fn apply<T>(
+        &self,
+        layers: &mut [T],
+        function_of: impl Fn(&T) -> Option<GerberFileFunction>,
+        mut set_visible: impl FnMut(&mut T, bool),
+    ) {
+        sort_for_viewing(layers, self.viewed_from(), &function_of);
+
+        for layer in layers.iter_mut() {
+            let visible = self.includes(function_of(layer).as_ref());
+            set_visible(layer, visible);
+        }
+    }
+}
+
+#[cfg(test)]
+mod layers_tests {
+    use super::*;
+
+    fn layers() -> Vec<(GerberFileFunction, bool)> {
+        vec![
+            (GerberFileFunction::Paste(PcbSide::Top), false),
+            (GerberFileFunction::Copper(PcbSide::Bottom), false),
+            (GerberFileFunction::Profile, false),
+            (GerberFileFunction::Legend(PcbSide::Top), false),
+            (GerberFileFunction::Copper(PcbSide::Top), false),
+            (GerberFileFunction::Paste(PcbSide::Bottom), false),
+        ]
+    }
+
+    #[test]
+    pub fn sort_for_viewing_from_the_top() {
+        // given
+        let mut layers = layers();
+
+        // when
+        sort_for_viewing(&mut layers, PcbSide::Top, |(function, _)| Some(*function));
+
+        // then
+        assert_eq!(
+            layers
+                .iter()
+                .map(|(function, _)| *function)
+                .collect::<Vec<_>>(),
+            vec![
+                GerberFileFunction::Paste(PcbSide::Bottom),
+                GerberFileFunction::Copper(PcbSide::Bottom),
+                GerberFileFunction::Profile,
+                GerberFileFunction::Copper(PcbSide::Top),
+                GerberFileFunction::Paste(PcbSide::Top),
+                GerberFileFunction::Legend(PcbSide::Top),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn group() {
+        // when
+        let groups = group_by_function(layers(), |(function, _)| Some(*function));
+
+        // then
+        assert_eq!(groups.len(), 4);
+        assert_eq!(groups[&Some(GerberFileFunctionDiscriminants::Copper)], vec![
+            (GerberFileFunction::Copper(PcbSide::Top), false),
+            (GerberFileFunction::Copper(PcbSide::Bottom), false),
+        ]);
+    }
+
+    #[test]
+    pub fn apply_bottom_paste_preset() {
+        // given
+        let mut layers = layers();
+
+        // when
+        LayerPreset::BottomPaste.apply(
+            &mut layers,
+            |(function, _)| Some(*function),
+            |(_, visible), preset_visible| *visible = preset_visible,
+        );
+
+        // then
+        assert_eq!(layers, vec![
+            (GerberFileFunction::Legend(PcbSide::Top), false),
+            (GerberFileFunction::Paste(PcbSide::Top), false),
+            (GerberFileFunction::Copper(PcbSide::Top), false),
+            (GerberFileFunction::Profile, true),
+            (GerberFileFunction::Copper(PcbSide::Bottom), false),
+            (GerberFileFunction::Paste(PcbSide::Bottom), true),
+        ]);
+    }
+
+    #[test]
+    pub fn layers_without_a_function_are_only_included_in_all() {
+        // expect
+        assert!(LayerPreset::All.includes(None));
+        assert!(!LayerPreset::TopAssembly.includes(None));
+        assert!(!LayerPreset::TopAssembly.includes(Some(&GerberFileFunction::Other(None))));
+    }
+}
@@ -1,5 +1,5 @@
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use gerber_types::{
     Command, CommentContent, ExtendedCode, ExtendedPosition, FileAttribute, FileFunction, FunctionCode, GCode,
@@ -10,6 +10,13 @@
 use thiserror::Error;
 use tracing::{error, info, trace};
 
+pub mod diff;
+pub mod expressions;
+pub mod geometry;
+pub mod layers;
+pub mod outline;
+pub mod render;
+
 #[allow(dead_code)]
 #[cfg(test)]
 mod testing;
@@ -27,7 +34,7 @@ pub struct GerberFile {
 #[derive(Debug, serde::Serialize, serde::Deserialize, Copy, Clone, PartialEq, Eq, Hash)]
 #[derive(EnumDiscriminants)]
 #[strum_discriminants(vis(pub))]
-#[strum_discriminants(derive(VariantArray))]
+#[strum_discriminants(derive(VariantArray, PartialOrd, Ord))]
 pub enum GerberFileFunction {
     Assembly(PcbSide),
     Component(PcbSide),
@@ -163,6 +170,39 @@ fn test_into_gerber_purpose_file_function(
     }
 }
 
+#[derive(Error, Debug)]
+pub enum LoadError {
+    #[error("IO error. cause: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Parse error. cause: {0}")]
+    ParseError(String),
+}
+
+/// Loads the commands of a gerber file, commands that failed to parse are ignored.
+pub fn load_commands(path: &Path) -> Result<Vec<Command>, LoadError> {
+    let file = std::fs::File::open(path)?;
+    let doc = gerber_parser::parse(BufReader::new(file))
+        .map_err(|(_partial_doc, error)| LoadError::ParseError(error.to_string()))?;
+
+    Ok(doc
+        .commands
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect())
+}
+
+/// Like [`load_commands`], but for content that has already been read, e.g. by a shell.
+pub fn parse_commands(content: &[u8]) -> Result<Vec<Command>, LoadError> {
+    let doc = gerber_parser::parse(BufReader::new(content))
+        .map_err(|(_partial_doc, error)| LoadError::ParseError(error.to_string()))?;
+
+    Ok(doc
+        .commands
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect())
+}
+
 #[derive(Error, Debug)]
 pub enum DetectionError {
     #[error("Parse error")]
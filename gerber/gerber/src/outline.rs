@@ -0,0 +1,1380 @@
+//! Import of board outlines from DXF and SVG files, for when a profile gerber is not available.
+//!
+//! An imported [`Outline`] is converted into a profile gerber, see [`Outline::write_profile_gerber`], which is then
+//! used for bounding boxes, panel sizing and rendering in the same way as a profile gerber exported by an EDA tool.
+//!
+//! Limitations:
+//! * DXF: only ASCII DXF files are supported, and only the LINE, LWPOLYLINE, POLYLINE, ARC and CIRCLE entities, other
+//!   entities, e.g. SPLINE and INSERT, are ignored.  The `$INSUNITS` header variable is used for the units, millimeters
+//!   are assumed when it is absent.
+//! * SVG: only the path, rect, circle, line, polyline and polygon elements are supported.  Elliptical arcs in paths are
+//!   treated as straight lines to the end point, and transforms are ignored.  The units are determined from the `width`
+//!   and `viewBox` of the root element, otherwise user units are pixels (96 DPI).
+//!
+//! Lines and arcs that share end points are joined into contours.
+
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use gerber_types::{
+    Aperture, ApertureDefinition, Circle, Command, CoordinateFormat, CoordinateNumber, Coordinates, DCode,
+    ExtendedCode, FileAttribute, FileFunction, FunctionCode, GCode, GerberCode, InterpolationMode, MCode, Operation,
+    Unit,
+};
+use thiserror::Error;
+use tracing::{trace, warn};
+
+use crate::LoadError;
+use crate::geometry::{CIRCLE_SEGMENTS, Geometry, GeometryError, build_shapes};
+
+const MM_PER_INCH: f64 = 25.4;
+const MM_PER_PIXEL: f64 = MM_PER_INCH / 96.0;
+
+/// The amount of line segments used to approximate each bezier curve of an SVG path.
+const BEZIER_SEGMENTS: usize = 16;
+
+/// End points closer than this, in millimeters, are joined.
+const JOIN_TOLERANCE: f64 = 0.001;
+
+/// The diameter of the aperture used to draw the profile, in millimeters.
+const PROFILE_APERTURE_DIAMETER: f64 = 0.1;
+
+#[derive(Error, Debug)]
+pub enum OutlineError {
+    #[error("IO error. cause: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Unsupported outline file, expected a DXF or SVG file. file: {0:?}")]
+    UnsupportedFile(PathBuf),
+    #[error("Invalid DXF. line: {line}, reason: {reason}")]
+    InvalidDxf { line: usize, reason: String },
+    #[error("Invalid SVG. reason: {0}")]
+    InvalidSvg(String),
+    #[error("No outline found")]
+    Empty,
+    #[error("Coordinate out of range. value: {0}")]
+    CoordinateOutOfRange(f64),
+    #[error("Serialization error. cause: {0}")]
+    SerializationError(String),
+    #[error("Load error. cause: {0}")]
+    LoadError(#[from] LoadError),
+    #[error("Geometry error. cause: {0}")]
+    GeometryError(#[from] GeometryError),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Contour {
+    /// In millimeters.
+    pub vertices: Vec<(f64, f64)>,
+    /// When `true` the last vertex is joined to the first, the first vertex is not repeated.
+    pub closed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutlineBounds {
+    pub min: (f64, f64),
+    pub max: (f64, f64),
+}
+
+impl OutlineBounds {
+    pub fn size(&self) -> (f64, f64) {
+        (self.max.0 - self.min.0, self.max.1 - self.min.1)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Outline {
+    pub contours: Vec<Contour>,
+}
+
+impl Outline {
+    fn from_contours(contours: Vec<Contour>) -> Result<Self, OutlineError> {
+        let contours = join_contours(contours);
+        if contours.is_empty() {
+            return Err(OutlineError::Empty);
+        }
+
+        Ok(Self {
+            contours,
+        })
+    }
+
+    /// The outline drawn by the commands of a profile gerber, the width of the lines is ignored.
+    pub fn from_profile_commands(commands: &[Command]) -> Result<Self, OutlineError> {
+        let contours = build_shapes(commands)?
+            .into_iter()
+            .filter(|shape| shape.dark)
+            .map(|shape| match shape.geometry {
+                Geometry::Stroke {
+                    start,
+                    end,
+                    ..
+                } => Contour {
+                    vertices: vec![(start.x, start.y), (end.x, end.y)],
+                    closed: false,
+                },
+                Geometry::Polygon {
+                    vertices,
+                } => Contour {
+                    vertices: vertices
+                        .iter()
+                        .map(|vertex| (vertex.x, vertex.y))
+                        .collect(),
+                    closed: true,
+                },
+                Geometry::Circle {
+                    center,
+                    radius,
+                } => circle_contour((center.x, center.y), radius),
+                Geometry::Rectangle {
+                    center,
+                    half_width,
+                    half_height,
+                } => Contour {
+                    vertices: vec![
+                        (center.x - half_width, center.y - half_height),
+                        (center.x + half_width, center.y - half_height),
+                        (center.x + half_width, center.y + half_height),
+                        (center.x - half_width, center.y + half_height),
+                    ],
+                    closed: true,
+                },
+            })
+            .collect();
+
+        Self::from_contours(contours)
+    }
+
+    /// Moves every vertex by the offset, in millimeters.
+    pub fn translate(&mut self, offset: (f64, f64)) {
+        for vertex in self
+            .contours
+            .iter_mut()
+            .flat_map(|contour| contour.vertices.iter_mut())
+        {
+            *vertex = (vertex.0 + offset.0, vertex.1 + offset.1);
+        }
+    }
+
+    pub fn bounds(&self) -> Option<OutlineBounds> {
+        self.contours
+            .iter()
+            .flat_map(|contour| contour.vertices.iter())
+            .fold(None, |bounds, &(x, y)| {
+                Some(match bounds {
+                    None => OutlineBounds {
+                        min: (x, y),
+                        max: (x, y),
+                    },
+                    Some(OutlineBounds {
+                        min,
+                        max,
+                    }) => OutlineBounds {
+                        min: (min.0.min(x), min.1.min(y)),
+                        max: (max.0.max(x), max.1.max(y)),
+                    },
+                })
+            })
+    }
+
+    /// The commands of a profile gerber, in millimeters, including the file function attribute.
+    pub fn to_commands(&self) -> Result<Vec<Command>, OutlineError> {
+        let format = CoordinateFormat::new(4, 6);
+        let coordinates = |(x, y): (f64, f64)| -> Result<Coordinates, OutlineError> {
+            Ok(Coordinates::new(
+                CoordinateNumber::try_from(x).map_err(|_| OutlineError::CoordinateOutOfRange(x))?,
+                CoordinateNumber::try_from(y).map_err(|_| OutlineError::CoordinateOutOfRange(y))?,
+                format,
+            ))
+        };
+
+        let mut commands = vec![
+            Command::ExtendedCode(ExtendedCode::FileAttribute(FileAttribute::FileFunction(
+                FileFunction::Profile(None),
+            ))),
+            Command::ExtendedCode(ExtendedCode::CoordinateFormat(format)),
+            Command::ExtendedCode(ExtendedCode::Unit(Unit::Millimeters)),
+            Command::ExtendedCode(ExtendedCode::ApertureDefinition(ApertureDefinition {
+                code: 10,
+                aperture: Aperture::Circle(Circle {
+                    diameter: PROFILE_APERTURE_DIAMETER,
+                    hole_diameter: None,
+                }),
+            })),
+            Command::FunctionCode(FunctionCode::DCode(DCode::SelectAperture(10))),
+            Command::FunctionCode(FunctionCode::GCode(GCode::InterpolationMode(InterpolationMode::Linear))),
+        ];
+
+        for contour in &self.contours {
+            let Some(first) = contour.vertices.first() else {
+                continue;
+            };
+            commands.push(Command::FunctionCode(FunctionCode::DCode(DCode::Operation(
+                Operation::Move(Some(coordinates(*first)?)),
+            ))));
+
+            let closing_vertex = contour.closed.then_some(first);
+            for vertex in contour
+                .vertices
+                .iter()
+                .skip(1)
+                .chain(closing_vertex)
+            {
+                commands.push(Command::FunctionCode(FunctionCode::DCode(DCode::Operation(
+                    Operation::Interpolate(Some(coordinates(*vertex)?), None),
+                ))));
+            }
+        }
+
+        commands.push(Command::FunctionCode(FunctionCode::MCode(MCode::EndOfFile)));
+
+        Ok(commands)
+    }
+
+    pub fn write_profile_gerber(&self, path: &Path) -> Result<(), OutlineError> {
+        let content = self.to_profile_gerber()?;
+
+        let mut file = File::create(path)?;
+        file.write_all(&content)?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// The content of a profile gerber, see [`Self::to_commands`].
+    pub fn to_profile_gerber(&self) -> Result<Vec<u8>, OutlineError> {
+        let commands = self.to_commands()?;
+
+        let mut content = vec![];
+        commands
+            .serialize(&mut content)
+            .map_err(|error| OutlineError::SerializationError(error.to_string()))?;
+
+        Ok(content)
+    }
+}
+
+enum OutlineFormat {
+    Dxf,
+    Svg,
+}
+
+fn outline_format(path: &Path) -> Option<OutlineFormat> {
+    let extension = path.extension().map(|extension| {
+        extension
+            .to_string_lossy()
+            .to_ascii_lowercase()
+    });
+
+    match extension.as_deref() {
+        Some("dxf") => Some(OutlineFormat::Dxf),
+        Some("svg") => Some(OutlineFormat::Svg),
+        _ => None,
+    }
+}
+
+/// Based on the file extension.
+pub fn is_outline_file(path: &Path) -> bool {
+    outline_format(path).is_some()
+}
+
+/// The path of the profile gerber for an outline file, in the same directory.
+///
+/// e.g. `outline.dxf` -> `outline-profile.gbr`
+pub fn profile_gerber_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    path.with_file_name(format!("{}-profile.gbr", stem))
+}
+
+pub fn load_outline(path: &Path) -> Result<Outline, OutlineError> {
+    // the format is checked before reading the file
+    outline_format(path).ok_or_else(|| OutlineError::UnsupportedFile(path.to_path_buf()))?;
+    let content = std::fs::read_to_string(path)?;
+
+    parse_outline(path, &content)
+}
+
+/// Parses the content of an outline file, the format is based on the file extension of the `path`.
+pub fn parse_outline(path: &Path, content: &str) -> Result<Outline, OutlineError> {
+    let format = outline_format(path).ok_or_else(|| OutlineError::UnsupportedFile(path.to_path_buf()))?;
+
+    match format {
+        OutlineFormat::Dxf => parse_dxf(content),
+        OutlineFormat::Svg => parse_svg(content),
+    }
+}
+
+/// Loads the outline from a profile gerber, see [`Outline::from_profile_commands`].
+pub fn load_profile(path: &Path) -> Result<Outline, OutlineError> {
+    let commands = crate::load_commands(path)?;
+
+    Outline::from_profile_commands(&commands)
+}
+
+/// Like [`load_profile`], but for content that has already been read, e.g. by a shell.
+pub fn parse_profile(content: &[u8]) -> Result<Outline, OutlineError> {
+    let commands = crate::parse_commands(content)?;
+
+    Outline::from_profile_commands(&commands)
+}
+
+/// Joins open contours that share end points, contours whose ends meet are closed.
+fn join_contours(contours: Vec<Contour>) -> Vec<Contour> {
+    let (mut joined, mut open): (Vec<Contour>, Vec<Contour>) = contours
+        .into_iter()
+        .filter(|contour| contour.vertices.len() >= 2)
+        .partition(|contour| contour.closed);
+
+    while !open.is_empty() {
+        let mut contour = open.remove(0);
+
+        // extend the end, then the start
+        extend_contour(&mut contour, &mut open);
+        contour.vertices.reverse();
+        extend_contour(&mut contour, &mut open);
+        contour.vertices.reverse();
+
+        let (first, last) = (contour.vertices[0], contour.vertices[contour.vertices.len() - 1]);
+        if contour.vertices.len() > 2 && is_same_point(first, last) {
+            contour.vertices.pop();
+            contour.closed = true;
+        }
+        joined.push(contour);
+    }
+
+    joined
+}
+
+fn extend_contour(contour: &mut Contour, open: &mut Vec<Contour>) {
+    loop {
+        let end = contour.vertices[contour.vertices.len() - 1];
+        let candidate = open
+            .iter()
+            .enumerate()
+            .find_map(|(index, candidate)| {
+                if is_same_point(candidate.vertices[0], end) {
+                    Some((index, false))
+                } else if is_same_point(candidate.vertices[candidate.vertices.len() - 1], end) {
+                    Some((index, true))
+                } else {
+                    None
+                }
+            });
+        let Some((index, reversed)) = candidate else {
+            return;
+        };
+
+        let mut next = open.remove(index);
+        if reversed {
+            next.vertices.reverse();
+        }
+        contour
+            .vertices
+            .extend(next.vertices.into_iter().skip(1));
+    }
+}
+
+fn is_same_point(a: (f64, f64), b: (f64, f64)) -> bool {
+    (a.0 - b.0).abs() <= JOIN_TOLERANCE && (a.1 - b.1).abs() <= JOIN_TOLERANCE
+}
+
+/// Points on an arc, including both ends, angles are in radians, a negative sweep is clockwise.
+fn arc_points(center: (f64, f64), radius: f64, start_angle: f64, sweep: f64) -> Vec<(f64, f64)> {
+    let segments = ((sweep.abs() / TAU) * CIRCLE_SEGMENTS as f64)
+        .ceil()
+        .max(1.0) as usize;
+
+    (0..=segments)
+        .map(|index| {
+            let angle = start_angle + sweep * index as f64 / segments as f64;
+            (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+        })
+        .collect()
+}
+
+fn circle_contour(center: (f64, f64), radius: f64) -> Contour {
+    let mut vertices = arc_points(center, radius, 0.0, TAU);
+    vertices.pop();
+
+    Contour {
+        vertices,
+        closed: true,
+    }
+}
+
+//
+// DXF
+//
+
+struct DxfPair<'a> {
+    /// The line number of the group code, for error messages.
+    line: usize,
+    code: i32,
+    value: &'a str,
+}
+
+struct DxfEntity<'a> {
+    kind: &'a str,
+    pairs: &'a [DxfPair<'a>],
+}
+
+impl<'a> DxfEntity<'a> {
+    fn line(&self) -> usize {
+        self.pairs
+            .first()
+            .map_or(0, |pair| pair.line)
+    }
+
+    fn value(&self, code: i32) -> Option<&'a str> {
+        self.pairs
+            .iter()
+            .find(|pair| pair.code == code)
+            .map(|pair| pair.value)
+    }
+
+    fn optional_number(&self, code: i32) -> Result<Option<f64>, OutlineError> {
+        self.pairs
+            .iter()
+            .find(|pair| pair.code == code)
+            .map(parse_dxf_number)
+            .transpose()
+    }
+
+    fn number(&self, code: i32) -> Result<f64, OutlineError> {
+        self.optional_number(code)?
+            .ok_or_else(|| OutlineError::InvalidDxf {
+                line: self.line(),
+                reason: format!("Missing group code {} for {}", code, self.kind),
+            })
+    }
+
+    fn flags(&self) -> Result<i32, OutlineError> {
+        Ok(self.optional_number(70)?.unwrap_or(0.0) as i32)
+    }
+}
+
+fn parse_dxf_number(pair: &DxfPair) -> Result<f64, OutlineError> {
+    pair.value
+        .parse::<f64>()
+        .map_err(|_| OutlineError::InvalidDxf {
+            line: pair.line + 1,
+            reason: format!("Invalid number '{}'", pair.value),
+        })
+}
+
+fn dxf_pairs(content: &str) -> Result<Vec<DxfPair<'_>>, OutlineError> {
+    let lines = content.lines().collect::<Vec<_>>();
+
+    lines
+        .chunks(2)
+        .enumerate()
+        .filter(|(_, chunk)| !chunk[0].trim().is_empty())
+        .map(|(index, chunk)| {
+            let line = index * 2 + 1;
+            let code = chunk[0]
+                .trim()
+                .parse::<i32>()
+                .map_err(|_| OutlineError::InvalidDxf {
+                    line,
+                    reason: format!("Invalid group code '{}'", chunk[0].trim()),
+                })?;
+            let value = chunk
+                .get(1)
+                .ok_or_else(|| OutlineError::InvalidDxf {
+                    line,
+                    reason: "Missing value".to_string(),
+                })?
+                .trim();
+
+            Ok(DxfPair {
+                line,
+                code,
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Millimeters per drawing unit, from the `$INSUNITS` header variable.
+fn dxf_scale(pairs: &[DxfPair]) -> f64 {
+    let units = pairs
+        .windows(2)
+        .find(|window| window[0].code == 9 && window[0].value == "$INSUNITS" && window[1].code == 70)
+        .map(|window| window[1].value);
+
+    match units {
+        // unitless
+        None | Some("0") => 1.0,
+        Some("1") => MM_PER_INCH,
+        Some("2") => MM_PER_INCH * 12.0,
+        Some("4") => 1.0,
+        Some("5") => 10.0,
+        Some("6") => 1000.0,
+        Some("8") => MM_PER_INCH / 1_000_000.0,
+        Some("9") => MM_PER_INCH / 1000.0,
+        Some("13") => 0.001,
+        Some(units) => {
+            warn!("Unsupported DXF units, using millimeters. units: {}", units);
+            1.0
+        }
+    }
+}
+
+/// Vertices of a polyline, with the bulge of the segment that starts at each vertex.
+fn bulge_contour(vertices: &[(f64, f64, f64)], closed: bool) -> Contour {
+    let mut points = vec![];
+    for (index, &(x, y, bulge)) in vertices.iter().enumerate() {
+        points.push((x, y));
+
+        let next = match vertices.get(index + 1) {
+            Some(next) => Some(next),
+            None if closed => vertices.first(),
+            None => None,
+        };
+        if let Some(&(next_x, next_y, _)) = next.filter(|_| bulge != 0.0) {
+            points.extend(bulge_points((x, y), (next_x, next_y), bulge));
+        }
+    }
+
+    Contour {
+        vertices: points,
+        closed,
+    }
+}
+
+/// The points between the ends of a polyline arc segment, the bulge is the tangent of a quarter of the included angle,
+/// and is negative for clockwise arcs.
+fn bulge_points(start: (f64, f64), end: (f64, f64), bulge: f64) -> Vec<(f64, f64)> {
+    let sweep = 4.0 * bulge.atan();
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let chord = (dx * dx + dy * dy).sqrt();
+    if chord == 0.0 {
+        return vec![];
+    }
+
+    // the center is to the left of the chord for counter-clockwise arcs
+    let normal = (-dy / chord, dx / chord);
+    let offset = chord / 2.0 / (sweep / 2.0).tan();
+    let center = (
+        (start.0 + end.0) / 2.0 + normal.0 * offset,
+        (start.1 + end.1) / 2.0 + normal.1 * offset,
+    );
+    let radius = ((start.0 - center.0).powi(2) + (start.1 - center.1).powi(2)).sqrt();
+    let start_angle = (start.1 - center.1).atan2(start.0 - center.0);
+
+    let points = arc_points(center, radius, start_angle, sweep);
+    points[1..points.len() - 1].to_vec()
+}
+
+pub fn parse_dxf(content: &str) -> Result<Outline, OutlineError> {
+    let pairs = dxf_pairs(content)?;
+    let scale = dxf_scale(&pairs);
+
+    let entity_starts = pairs
+        .iter()
+        .enumerate()
+        .filter(|(_, pair)| pair.code == 0)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+
+    let mut contours: Vec<Contour> = vec![];
+    let mut section: Option<&str> = None;
+    // POLYLINE entities are followed by VERTEX entities, and end with a SEQEND entity
+    let mut polyline: Option<(Vec<(f64, f64, f64)>, bool)> = None;
+
+    for (index, start) in entity_starts.iter().enumerate() {
+        let end = entity_starts
+            .get(index + 1)
+            .copied()
+            .unwrap_or(pairs.len());
+        let entity = DxfEntity {
+            kind: pairs[*start].value,
+            pairs: &pairs[start + 1..end],
+        };
+
+        match entity.kind {
+            "SECTION" => section = entity.value(2),
+            "ENDSEC" => section = None,
+            _ if section != Some("ENTITIES") => {}
+            "LINE" => contours.push(Contour {
+                vertices: vec![
+                    (entity.number(10)? * scale, entity.number(20)? * scale),
+                    (entity.number(11)? * scale, entity.number(21)? * scale),
+                ],
+                closed: false,
+            }),
+            "LWPOLYLINE" => {
+                let mut vertices: Vec<(f64, f64, f64)> = vec![];
+                for pair in entity.pairs {
+                    match (pair.code, vertices.last_mut()) {
+                        (10, _) => vertices.push((parse_dxf_number(pair)? * scale, 0.0, 0.0)),
+                        (20, Some(vertex)) => vertex.1 = parse_dxf_number(pair)? * scale,
+                        (42, Some(vertex)) => vertex.2 = parse_dxf_number(pair)?,
+                        _ => {}
+                    }
+                }
+                let closed = entity.flags()? & 1 != 0;
+                contours.push(bulge_contour(&vertices, closed));
+            }
+            "POLYLINE" => polyline = Some((vec![], entity.flags()? & 1 != 0)),
+            "VERTEX" => {
+                if let Some((vertices, _)) = polyline.as_mut() {
+                    vertices.push((
+                        entity.number(10)? * scale,
+                        entity.number(20)? * scale,
+                        entity
+                            .optional_number(42)?
+                            .unwrap_or(0.0),
+                    ));
+                }
+            }
+            "SEQEND" => {
+                if let Some((vertices, closed)) = polyline.take() {
+                    contours.push(bulge_contour(&vertices, closed));
+                }
+            }
+            "CIRCLE" => contours.push(circle_contour(
+                (entity.number(10)? * scale, entity.number(20)? * scale),
+                entity.number(40)? * scale,
+            )),
+            "ARC" => {
+                let start_angle = entity.number(50)?;
+                let mut sweep = (entity.number(51)? - start_angle).rem_euclid(360.0);
+                if sweep == 0.0 {
+                    sweep = 360.0;
+                }
+                contours.push(Contour {
+                    vertices: arc_points(
+                        (entity.number(10)? * scale, entity.number(20)? * scale),
+                        entity.number(40)? * scale,
+                        start_angle.to_radians(),
+                        sweep.to_radians(),
+                    ),
+                    closed: false,
+                });
+            }
+            kind => trace!("Ignoring DXF entity. kind: {}, line: {}", kind, entity.line()),
+        }
+    }
+
+    Outline::from_contours(contours)
+}
+
+//
+// SVG
+//
+
+struct SvgElement<'a> {
+    name: &'a str,
+    attributes: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> SvgElement<'a> {
+    fn number(&self, name: &str) -> Result<Option<f64>, OutlineError> {
+        let Some(value) = self.attributes.get(name) else {
+            return Ok(None);
+        };
+
+        NumberScanner::new(value)
+            .number()
+            .map(Some)
+            .ok_or_else(|| {
+                OutlineError::InvalidSvg(format!(
+                    "Invalid number. element: {}, attribute: {}, value: '{}'",
+                    self.name, name, value
+                ))
+            })
+    }
+
+    fn required_number(&self, name: &str) -> Result<f64, OutlineError> {
+        self.number(name)?.ok_or_else(|| {
+            OutlineError::InvalidSvg(format!(
+                "Missing attribute. element: {}, attribute: {}",
+                self.name, name
+            ))
+        })
+    }
+}
+
+/// Elements within `defs` are skipped, nesting is otherwise ignored.
+fn svg_elements(content: &str) -> Result<Vec<SvgElement<'_>>, OutlineError> {
+    let mut elements = vec![];
+    let mut remaining = content;
+    let mut defs_depth = 0_usize;
+
+    while let Some(start) = remaining.find('<') {
+        remaining = &remaining[start..];
+
+        if let Some(comment) = remaining.strip_prefix("<!--") {
+            let end = comment
+                .find("-->")
+                .ok_or_else(|| OutlineError::InvalidSvg("Unterminated comment".to_string()))?;
+            remaining = &comment[end + 3..];
+            continue;
+        }
+
+        let end = remaining
+            .find('>')
+            .ok_or_else(|| OutlineError::InvalidSvg("Unterminated tag".to_string()))?;
+        let tag = &remaining[1..end];
+        remaining = &remaining[end + 1..];
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            if element_name(name.trim()) == "defs" {
+                defs_depth = defs_depth.saturating_sub(1);
+            }
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let name_end = tag
+            .find(char::is_whitespace)
+            .unwrap_or(tag.len());
+        let name = element_name(&tag[..name_end]);
+
+        if name == "defs" {
+            if !self_closing {
+                defs_depth += 1;
+            }
+            continue;
+        }
+        if defs_depth > 0 {
+            continue;
+        }
+
+        elements.push(SvgElement {
+            name,
+            attributes: svg_attributes(&tag[name_end..])?,
+        });
+    }
+
+    Ok(elements)
+}
+
+/// Without the namespace prefix, if any, e.g. `svg:path` -> `path`.
+fn element_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn svg_attributes(content: &str) -> Result<HashMap<&str, &str>, OutlineError> {
+    let invalid = || OutlineError::InvalidSvg(format!("Invalid attributes '{}'", content.trim()));
+
+    let mut attributes = HashMap::new();
+    let mut remaining = content.trim_start();
+    while !remaining.is_empty() {
+        let (name, rest) = remaining
+            .split_once('=')
+            .ok_or_else(invalid)?;
+        let rest = rest.trim_start();
+        let quote = rest
+            .chars()
+            .next()
+            .ok_or_else(invalid)?;
+        if quote != '"' && quote != '\'' {
+            return Err(invalid());
+        }
+        let (value, rest) = rest[1..]
+            .split_once(quote)
+            .ok_or_else(invalid)?;
+
+        attributes.insert(name.trim(), value);
+        remaining = rest.trim_start();
+    }
+
+    Ok(attributes)
+}
+
+/// In millimeters, `None` for relative lengths, e.g. percentages.
+fn parse_length(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let unit_start = value
+        .find(|c: char| c.is_ascii_alphabetic() || c == '%')
+        .unwrap_or(value.len());
+    let number = value[..unit_start]
+        .trim()
+        .parse::<f64>()
+        .ok()?;
+
+    let scale = match &value[unit_start..] {
+        "" | "px" => MM_PER_PIXEL,
+        "mm" => 1.0,
+        "cm" => 10.0,
+        "in" => MM_PER_INCH,
+        "pt" => MM_PER_INCH / 72.0,
+        "pc" => MM_PER_INCH / 6.0,
+        _ => return None,
+    };
+
+    Some(number * scale)
+}
+
+/// Millimeters per user unit.
+fn svg_scale(svg: &SvgElement) -> f64 {
+    let width = svg
+        .attributes
+        .get("width")
+        .and_then(|width| parse_length(width));
+    let view_box_width = svg
+        .attributes
+        .get("viewBox")
+        .map(|view_box| NumberScanner::new(view_box).numbers())
+        .filter(|numbers| numbers.len() == 4)
+        .map(|numbers| numbers[2]);
+
+    match (width, view_box_width) {
+        (Some(width), Some(view_box_width)) if view_box_width > 0.0 => width / view_box_width,
+        _ => MM_PER_PIXEL,
+    }
+}
+
+/// Scans numbers separated by whitespace and/or commas, or by a sign or second decimal point, e.g. `10-5` and `.5.5`,
+/// as used in SVG path data and point lists.
+struct NumberScanner<'a> {
+    content: &'a str,
+    position: usize,
+}
+
+impl<'a> NumberScanner<'a> {
+    fn new(content: &'a str) -> Self {
+        Self {
+            content,
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.content
+            .as_bytes()
+            .get(self.position)
+            .copied()
+    }
+
+    fn skip_separators(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|byte| byte.is_ascii_whitespace() || byte == b',')
+        {
+            self.position += 1;
+        }
+    }
+
+    fn is_at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.peek().is_none()
+    }
+
+    fn skip_digits(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|byte| byte.is_ascii_digit())
+        {
+            self.position += 1;
+        }
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        self.skip_separators();
+        let start = self.position;
+
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.position += 1;
+        }
+        self.skip_digits();
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            self.skip_digits();
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let mantissa_end = self.position;
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            if self
+                .peek()
+                .is_some_and(|byte| byte.is_ascii_digit())
+            {
+                self.skip_digits();
+            } else {
+                self.position = mantissa_end;
+            }
+        }
+
+        let number = self.content[start..self.position]
+            .parse::<f64>()
+            .ok();
+        if number.is_none() {
+            self.position = start;
+        }
+        number
+    }
+
+    fn numbers(&mut self) -> Vec<f64> {
+        std::iter::from_fn(|| self.number()).collect()
+    }
+
+    fn point(&mut self, origin: (f64, f64)) -> Option<(f64, f64)> {
+        Some((origin.0 + self.number()?, origin.1 + self.number()?))
+    }
+
+    /// A path command letter, if the next non-separator character is a letter.
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let command = self
+            .peek()
+            .filter(|byte| byte.is_ascii_alphabetic())?;
+        self.position += 1;
+        Some(command)
+    }
+}
+
+#[derive(Default)]
+struct PathBuilder {
+    contours: Vec<Contour>,
+    current: Vec<(f64, f64)>,
+    position: (f64, f64),
+    start: (f64, f64),
+}
+
+impl PathBuilder {
+    fn finish_contour(&mut self, closed: bool) {
+        let mut vertices = std::mem::take(&mut self.current);
+        if closed && vertices.len() > 2 && is_same_point(vertices[0], vertices[vertices.len() - 1]) {
+            vertices.pop();
+        }
+        if vertices.len() >= 2 {
+            self.contours.push(Contour {
+                vertices,
+                closed,
+            });
+        }
+    }
+
+    fn move_to(&mut self, point: (f64, f64)) {
+        self.finish_contour(false);
+        self.current.push(point);
+        self.position = point;
+        self.start = point;
+    }
+
+    fn line_to(&mut self, point: (f64, f64)) {
+        if self.current.is_empty() {
+            self.current.push(self.position);
+        }
+        self.current.push(point);
+        self.position = point;
+    }
+
+    fn close(&mut self) {
+        self.finish_contour(true);
+        self.position = self.start;
+    }
+
+    fn cubic_to(&mut self, control_1: (f64, f64), control_2: (f64, f64), end: (f64, f64)) {
+        let start = self.position;
+        for index in 1..=BEZIER_SEGMENTS {
+            let t = index as f64 / BEZIER_SEGMENTS as f64;
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            self.line_to((
+                a * start.0 + b * control_1.0 + c * control_2.0 + d * end.0,
+                a * start.1 + b * control_1.1 + c * control_2.1 + d * end.1,
+            ));
+        }
+    }
+
+    fn quadratic_to(&mut self, control: (f64, f64), end: (f64, f64)) {
+        let start = self.position;
+        for index in 1..=BEZIER_SEGMENTS {
+            let t = index as f64 / BEZIER_SEGMENTS as f64;
+            let u = 1.0 - t;
+            let (a, b, c) = (u * u, 2.0 * u * t, t * t);
+            self.line_to((
+                a * start.0 + b * control.0 + c * end.0,
+                a * start.1 + b * control.1 + c * end.1,
+            ));
+        }
+    }
+}
+
+fn path_contours(data: &str) -> Result<Vec<Contour>, OutlineError> {
+    let invalid = || OutlineError::InvalidSvg(format!("Invalid path data '{}'", data));
+
+    let mut scanner = NumberScanner::new(data);
+    let mut builder = PathBuilder::default();
+    let mut command: Option<u8> = None;
+
+    loop {
+        if let Some(next_command) = scanner.command() {
+            command = Some(next_command);
+        } else if scanner.is_at_end() {
+            break;
+        }
+        // without a new command letter, the previous command is repeated
+        let current_command = command.ok_or_else(invalid)?;
+
+        let relative = current_command.is_ascii_lowercase();
+        let origin = if relative { builder.position } else { (0.0, 0.0) };
+
+        match current_command.to_ascii_uppercase() {
+            b'M' => {
+                builder.move_to(
+                    scanner
+                        .point(origin)
+                        .ok_or_else(invalid)?,
+                );
+                // subsequent pairs of coordinates are lines
+                command = Some(if relative { b'l' } else { b'L' });
+            }
+            b'L' => builder.line_to(
+                scanner
+                    .point(origin)
+                    .ok_or_else(invalid)?,
+            ),
+            b'H' => {
+                let x = origin.0 + scanner.number().ok_or_else(invalid)?;
+                builder.line_to((x, builder.position.1));
+            }
+            b'V' => {
+                let y = origin.1 + scanner.number().ok_or_else(invalid)?;
+                builder.line_to((builder.position.0, y));
+            }
+            b'C' => {
+                let control_1 = scanner
+                    .point(origin)
+                    .ok_or_else(invalid)?;
+                let control_2 = scanner
+                    .point(origin)
+                    .ok_or_else(invalid)?;
+                let end = scanner
+                    .point(origin)
+                    .ok_or_else(invalid)?;
+                builder.cubic_to(control_1, control_2, end);
+            }
+            b'Q' => {
+                let control = scanner
+                    .point(origin)
+                    .ok_or_else(invalid)?;
+                let end = scanner
+                    .point(origin)
+                    .ok_or_else(invalid)?;
+                builder.quadratic_to(control, end);
+            }
+            b'A' => {
+                // radii, rotation and flags
+                for _ in 0..5 {
+                    scanner.number().ok_or_else(invalid)?;
+                }
+                builder.line_to(
+                    scanner
+                        .point(origin)
+                        .ok_or_else(invalid)?,
+                );
+            }
+            b'Z' => {
+                builder.close();
+                command = None;
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    builder.finish_contour(false);
+
+    Ok(builder.contours)
+}
+
+fn points_contour(element: &SvgElement, closed: bool) -> Result<Contour, OutlineError> {
+    let numbers = element
+        .attributes
+        .get("points")
+        .map(|points| NumberScanner::new(points).numbers())
+        .unwrap_or_default();
+
+    Ok(Contour {
+        vertices: numbers
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect(),
+        closed,
+    })
+}
+
+fn element_contours(element: &SvgElement) -> Result<Vec<Contour>, OutlineError> {
+    let contours = match element.name {
+        "path" => path_contours(
+            element
+                .attributes
+                .get("d")
+                .copied()
+                .unwrap_or_default(),
+        )?,
+        "rect" => {
+            let (x, y) = (element.number("x")?.unwrap_or(0.0), element.number("y")?.unwrap_or(0.0));
+            let (width, height) = (element.required_number("width")?, element.required_number("height")?);
+            vec![Contour {
+                vertices: vec![(x, y), (x + width, y), (x + width, y + height), (x, y + height)],
+                closed: true,
+            }]
+        }
+        "circle" => vec![circle_contour(
+            (
+                element.number("cx")?.unwrap_or(0.0),
+                element.number("cy")?.unwrap_or(0.0),
+            ),
+            element.required_number("r")?,
+        )],
+        "line" => vec![Contour {
+            vertices: vec![
+                (
+                    element.number("x1")?.unwrap_or(0.0),
+                    element.number("y1")?.unwrap_or(0.0),
+                ),
+                (
+                    element.number("x2")?.unwrap_or(0.0),
+                    element.number("y2")?.unwrap_or(0.0),
+                ),
+            ],
+            closed: false,
+        }],
+        "polyline" => vec![points_contour(element, false)?],
+        "polygon" => vec![points_contour(element, true)?],
+        _ => return Ok(vec![]),
+    };
+
+    if element
+        .attributes
+        .contains_key("transform")
+    {
+        warn!(
+            "SVG transforms are not supported, ignoring transform. element: {}",
+            element.name
+        );
+    }
+
+    Ok(contours)
+}
+
+pub fn parse_svg(content: &str) -> Result<Outline, OutlineError> {
+    let elements = svg_elements(content)?;
+
+    let scale = elements
+        .iter()
+        .find(|element| element.name == "svg")
+        .map_or(MM_PER_PIXEL, svg_scale);
+
+    let mut contours = vec![];
+    for element in &elements {
+        contours.extend(element_contours(element)?);
+    }
+
+    // SVG Y coordinates increase downwards
+    for contour in contours.iter_mut() {
+        for vertex in contour.vertices.iter_mut() {
+            *vertex = (vertex.0 * scale, -vertex.1 * scale);
+        }
+    }
+
+    Outline::from_contours(contours)
+}
+
+#[cfg(test)]
+mod outline_tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::geometry::{Bounds, build_shapes};
+
+    fn assert_bounds(bounds: Option<OutlineBounds>, min: (f64, f64), max: (f64, f64)) {
+        let bounds = bounds.expect("bounds");
+        for (actual, expected) in [
+            (bounds.min.0, min.0),
+            (bounds.min.1, min.1),
+            (bounds.max.0, max.0),
+            (bounds.max.1, max.1),
+        ] {
+            assert!(
+                (actual - expected).abs() < 1e-6,
+                "bounds: {:?}, expected min: {:?}, max: {:?}",
+                bounds,
+                min,
+                max
+            );
+        }
+    }
+
+    fn dxf(header: &str, entities: &[&str]) -> String {
+        let mut lines = vec!["0", "SECTION", "2", "HEADER"];
+        lines.extend(header.split_whitespace());
+        lines.extend(["0", "ENDSEC", "0", "SECTION", "2", "ENTITIES"]);
+        for entity in entities {
+            lines.extend(entity.split_whitespace());
+        }
+        lines.extend(["0", "ENDSEC", "0", "EOF"]);
+        lines.join("\n")
+    }
+
+    #[test]
+    pub fn dxf_lines_are_joined_into_a_closed_contour() {
+        // given
+        let content = dxf("9 $INSUNITS 70 1", &[
+            "0 LINE 8 0 10 0 20 0 11 1 21 0",
+            // reversed
+            "0 LINE 8 0 10 1 20 0.5 11 1 21 0",
+            "0 LINE 8 0 10 0 20 0.5 11 0 21 0",
+            "0 LINE 8 0 10 1 20 0.5 11 0 21 0.5",
+        ]);
+
+        // when
+        let outline = parse_dxf(&content).unwrap();
+
+        // then
+        assert_eq!(outline.contours.len(), 1);
+        assert!(outline.contours[0].closed);
+        assert_eq!(outline.contours[0].vertices.len(), 4);
+        assert_bounds(outline.bounds(), (0.0, 0.0), (25.4, 12.7));
+    }
+
+    #[test]
+    pub fn dxf_polyline_bulges_and_circles() {
+        // given
+        let content = dxf("", &[
+            // the segment from (10,0) to (10,10) is a counter-clockwise semi-circle
+            "0 LWPOLYLINE 8 0 90 4 70 1 10 0 20 0 10 10 20 0 42 1 10 10 20 10 10 0 20 10",
+            "0 CIRCLE 8 0 10 -5 20 5 40 2",
+        ]);
+
+        // when
+        let outline = parse_dxf(&content).unwrap();
+
+        // then
+        assert_eq!(outline.contours.len(), 2);
+        assert!(
+            outline
+                .contours
+                .iter()
+                .all(|contour| contour.closed)
+        );
+        assert_bounds(outline.bounds(), (-7.0, 0.0), (15.0, 10.0));
+    }
+
+    #[test]
+    pub fn dxf_without_entities() {
+        // when
+        let result = parse_dxf(&dxf("", &["0 TEXT 8 0 10 0 20 0 1 HELLO"]));
+
+        // then
+        assert!(matches!(result, Err(OutlineError::Empty)));
+    }
+
+    #[rstest]
+    #[case::path_in_millimeters(
+        r#"<svg width="100mm" height="50mm" viewBox="0 0 200 100"><path d="M0,0 H200 V100 H0 Z"/></svg>"#,
+        (0.0, -50.0),
+        (100.0, 0.0)
+    )]
+    #[case::relative_path(
+        r#"<svg width="1in" viewBox="0 0 10 10"><path d="m1,1 l8,0 0 8-8,0z"/></svg>"#,
+        (2.54, -22.86),
+        (22.86, -2.54)
+    )]
+    #[case::rect_and_circle(
+        r#"<svg viewBox="0 0 96 96"><rect x="0" y="0" width="96" height="48"/><circle cx="48" cy="48" r="48"/></svg>"#,
+        (0.0, -25.4),
+        (25.4, 0.0)
+    )]
+    #[case::defs_are_ignored(
+        r#"<svg width="10mm" viewBox="0 0 10 10">
+            <defs><rect width="100" height="100"/></defs>
+            <!-- <rect/> -->
+            <polygon points="0,0 10,0 10,10"/>
+        </svg>"#,
+        (0.0, -10.0),
+        (10.0, 0.0)
+    )]
+    pub fn svg_outline(#[case] content: &str, #[case] min: (f64, f64), #[case] max: (f64, f64)) {
+        // when
+        let outline = parse_svg(content).unwrap();
+
+        // then
+        assert_bounds(outline.bounds(), min, max);
+        assert!(outline.contours[0].closed);
+    }
+
+    #[test]
+    pub fn svg_invalid_path() {
+        // when
+        let result = parse_svg(r#"<svg><path d="M0,0 L10"/></svg>"#);
+
+        // then
+        assert!(matches!(result, Err(OutlineError::InvalidSvg(_))));
+    }
+
+    #[test]
+    pub fn unsupported_file() {
+        // when
+        let result = load_outline(Path::new("outline.pdf"));
+
+        // then
+        assert!(matches!(result, Err(OutlineError::UnsupportedFile(_))));
+    }
+
+    #[test]
+    pub fn profile_commands() {
+        // given
+        let outline =
+            parse_svg(r#"<svg width="40mm" viewBox="0 0 40 20"><rect width="40" height="20"/></svg>"#).unwrap();
+
+        // when
+        let commands = outline.to_commands().unwrap();
+
+        // then
+        let shapes = build_shapes(&commands).unwrap();
+        assert_eq!(shapes.len(), 4);
+
+        let bounds = Bounds::of_shapes(shapes.iter()).unwrap();
+        let radius = PROFILE_APERTURE_DIAMETER / 2.0;
+        assert_bounds(
+            Some(OutlineBounds {
+                min: (bounds.min.x, bounds.min.y),
+                max: (bounds.max.x, bounds.max.y),
+            }),
+            (-radius, -20.0 - radius),
+            (40.0 + radius, radius),
+        );
+    }
+
+    #[test]
+    pub fn outline_from_profile_commands() {
+        // given
+        let outline = parse_dxf(&dxf("", &[
+            "0 LWPOLYLINE 8 0 90 3 70 1 10 0 20 0 10 10 20 0 10 10 20 5",
+        ]))
+        .unwrap();
+        let commands = outline.to_commands().unwrap();
+
+        // when
+        let profile = Outline::from_profile_commands(&commands).unwrap();
+
+        // then
+        assert_eq!(profile, outline);
+    }
+
+    #[test]
+    pub fn profile_gerber_path_is_next_to_the_outline() {
+        // expect
+        assert_eq!(
+            profile_gerber_path(Path::new("/pcbs/board.dxf")),
+            PathBuf::from("/pcbs/board-profile.gbr")
+        );
+    }
+}
@@ -0,0 +1,732 @@
+//! Headless rendering of gerber layers, without a GUI.
+//!
+//! Layers are rendered in order, each with its own color, either to an SVG document or to an RGBA bitmap which can be
+//! saved as a PNG (requires the `png` feature).  This allows board images to be embedded in reports and thumbnails to
+//! be generated.
+//!
+//! See [`crate::geometry`] for the limitations.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use gerber_types::Command;
+use thiserror::Error;
+use tracing::debug;
+
+use crate::LoadError;
+use crate::geometry::{Bounds, Geometry, GeometryError, Point, Shape, build_shapes};
+
+/// The maximum amount of pixels in a bitmap, to prevent excessive memory usage.
+const MAX_PIXELS: usize = 16_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self {
+            r,
+            g,
+            b,
+            a: 255,
+        }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self {
+            r,
+            g,
+            b,
+            a,
+        }
+    }
+
+    fn svg_fill(&self) -> String {
+        format!(
+            "fill=\"#{:02x}{:02x}{:02x}\" fill-opacity=\"{:.3}\"",
+            self.r,
+            self.g,
+            self.b,
+            self.a as f64 / 255.0
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderLayer {
+    pub commands: Vec<Command>,
+    pub color: Color,
+}
+
+impl RenderLayer {
+    /// Load a layer from a gerber file, commands that failed to parse are ignored.
+    pub fn from_file(path: &Path, color: Color) -> Result<Self, RenderError> {
+        let commands = crate::load_commands(path).map_err(|error| match error {
+            LoadError::IoError(cause) => RenderError::IoError(cause),
+            LoadError::ParseError(cause) => RenderError::ParseError(cause),
+        })?;
+
+        Ok(Self {
+            commands,
+            color,
+        })
+    }
+}
+
+/// Applied to the gerber coordinates, in the order: mirroring, rotation, offset.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderTransform {
+    /// In degrees, counter-clockwise.
+    pub rotation: f64,
+    /// Mirror along the Y axis, e.g. for viewing the bottom of a PCB.
+    pub mirrored: bool,
+    /// In millimeters.
+    pub offset: (f64, f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderSize {
+    PixelsPerMm(f64),
+    /// Scaled to fit, the aspect ratio is preserved.
+    FitWithin {
+        width: u32,
+        height: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderOptions {
+    pub size: RenderSize,
+    /// `None` for a transparent background.
+    pub background: Option<Color>,
+    pub transform: RenderTransform,
+    /// Space around the layers, in millimeters.
+    pub margin: f64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            size: RenderSize::FitWithin {
+                width: 256,
+                height: 256,
+            },
+            background: None,
+            transform: RenderTransform::default(),
+            margin: 1.0,
+        }
+    }
+}
+
+/// An RGBA bitmap, 4 bytes per pixel, the first row is the top of the image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bitmap {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Bitmap {
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        let index = ((y * self.width + x) * 4) as usize;
+        Color::rgba(
+            self.pixels[index],
+            self.pixels[index + 1],
+            self.pixels[index + 2],
+            self.pixels[index + 3],
+        )
+    }
+
+    #[cfg(feature = "png")]
+    pub fn save_png(&self, path: &Path) -> Result<(), RenderError> {
+        let image = image::RgbaImage::from_raw(self.width, self.height, self.pixels.clone())
+            .expect("pixel buffer matches the dimensions");
+
+        image
+            .save_with_format(path, image::ImageFormat::Png)
+            .map_err(|error| RenderError::ImageError(error.to_string()))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum RenderError {
+    #[error("IO error. cause: {0}")]
+    IoError(std::io::Error),
+    #[error("Parse error. cause: {0}")]
+    ParseError(String),
+    #[error(transparent)]
+    GeometryError(#[from] GeometryError),
+    #[error("Nothing to render")]
+    Empty,
+    #[error("Invalid size. size: {0:?}")]
+    InvalidSize(RenderSize),
+    #[error("Image too large. pixels: {pixels}, max: {max}")]
+    TooManyPixels { pixels: usize, max: usize },
+    #[error("Image error. cause: {0}")]
+    ImageError(String),
+}
+
+/// Maps gerber coordinates to render coordinates, `x' = a * x + c * y + e`, `y' = b * x + d * y + f`.
+///
+/// The same layout as an SVG `matrix(a,b,c,d,e,f)`.
+#[derive(Debug, Clone, Copy)]
+struct Affine {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+    e: f64,
+    f: f64,
+}
+
+impl Affine {
+    fn from_transform(transform: &RenderTransform) -> Self {
+        let (sin, cos) = transform
+            .rotation
+            .to_radians()
+            .sin_cos();
+        let mirror = if transform.mirrored { -1.0 } else { 1.0 };
+
+        Self {
+            a: mirror * cos,
+            b: mirror * sin,
+            c: -sin,
+            d: cos,
+            e: transform.offset.0,
+            f: transform.offset.1,
+        }
+    }
+
+    fn apply(&self, point: Point) -> Point {
+        Point {
+            x: self.a * point.x + self.c * point.y + self.e,
+            y: self.b * point.x + self.d * point.y + self.f,
+        }
+    }
+
+    fn invert(&self, point: Point) -> Point {
+        let determinant = self.a * self.d - self.b * self.c;
+        let (x, y) = (point.x - self.e, point.y - self.f);
+        Point {
+            x: (self.d * x - self.c * y) / determinant,
+            y: (self.a * y - self.b * x) / determinant,
+        }
+    }
+
+    fn apply_bounds(&self, bounds: &Bounds) -> Bounds {
+        [
+            Point {
+                x: bounds.min.x,
+                y: bounds.min.y,
+            },
+            Point {
+                x: bounds.max.x,
+                y: bounds.min.y,
+            },
+            Point {
+                x: bounds.min.x,
+                y: bounds.max.y,
+            },
+            Point {
+                x: bounds.max.x,
+                y: bounds.max.y,
+            },
+        ]
+        .into_iter()
+        .map(|corner| Bounds::around(self.apply(corner), 0.0, 0.0))
+        .reduce(|a, b| a.union(&b))
+        .unwrap()
+    }
+}
+
+/// The shapes of each layer, and the area to render, in transformed coordinates.
+struct Scene {
+    layers: Vec<(Vec<Shape>, Color)>,
+    affine: Affine,
+    bounds: Bounds,
+    /// Pixels per millimeter
+    scale: f64,
+    width: u32,
+    height: u32,
+}
+
+impl Scene {
+    fn build(layers: &[RenderLayer], options: &RenderOptions) -> Result<Self, RenderError> {
+        let affine = Affine::from_transform(&options.transform);
+
+        let layers = layers
+            .iter()
+            .map(|layer| build_shapes(&layer.commands).map(|shapes| (shapes, layer.color)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let bounds = layers
+            .iter()
+            .flat_map(|(shapes, _color)| shapes.iter())
+            .filter_map(|shape| shape.bounds())
+            .map(|bounds| affine.apply_bounds(&bounds))
+            .reduce(|a, b| a.union(&b))
+            .ok_or(RenderError::Empty)?;
+        let bounds = Bounds {
+            min: Point {
+                x: bounds.min.x - options.margin,
+                y: bounds.min.y - options.margin,
+            },
+            max: Point {
+                x: bounds.max.x + options.margin,
+                y: bounds.max.y + options.margin,
+            },
+        };
+
+        let (width_mm, height_mm) = (bounds.max.x - bounds.min.x, bounds.max.y - bounds.min.y);
+        if !(width_mm > 0.0 && height_mm > 0.0) {
+            return Err(RenderError::Empty);
+        }
+
+        let scale = match options.size {
+            RenderSize::PixelsPerMm(scale) if scale > 0.0 => scale,
+            RenderSize::FitWithin {
+                width,
+                height,
+            } if width > 0 && height > 0 => (width as f64 / width_mm).min(height as f64 / height_mm),
+            size => return Err(RenderError::InvalidSize(size)),
+        };
+
+        let width = ((width_mm * scale).round() as u32).max(1);
+        let height = ((height_mm * scale).round() as u32).max(1);
+
+        let pixels = (width as usize).saturating_mul(height as usize);
+        if pixels > MAX_PIXELS {
+            return Err(RenderError::TooManyPixels {
+                pixels,
+                max: MAX_PIXELS,
+            });
+        }
+
+        debug!("render scene. width: {}, height: {}, scale: {}", width, height, scale);
+
+        Ok(Self {
+            layers,
+            affine,
+            bounds,
+            scale,
+            width,
+            height,
+        })
+    }
+
+    /// The center of the pixel, in transformed coordinates.
+    fn pixel_center(&self, x: u32, y: u32) -> Point {
+        Point {
+            x: self.bounds.min.x + (x as f64 + 0.5) / self.scale,
+            y: self.bounds.max.y - (y as f64 + 0.5) / self.scale,
+        }
+    }
+
+    fn pixel_range(&self, min: f64, max: f64, limit: u32) -> (u32, u32) {
+        let clamp = |value: f64| (value.floor().max(0.0) as u32).min(limit - 1);
+        (clamp(min * self.scale), clamp(max * self.scale))
+    }
+
+    /// Shapes are rendered in order, so that 'clear' shapes remove previously rendered 'dark' shapes.
+    fn render_mask(&self, shapes: &[Shape]) -> Vec<bool> {
+        let mut mask = vec![false; (self.width * self.height) as usize];
+
+        for shape in shapes {
+            let Some(bounds) = shape.bounds() else {
+                continue;
+            };
+            let bounds = self.affine.apply_bounds(&bounds);
+            let (x_min, x_max) = self.pixel_range(
+                bounds.min.x - self.bounds.min.x,
+                bounds.max.x - self.bounds.min.x,
+                self.width,
+            );
+            let (y_min, y_max) = self.pixel_range(
+                self.bounds.max.y - bounds.max.y,
+                self.bounds.max.y - bounds.min.y,
+                self.height,
+            );
+
+            for y in y_min..=y_max {
+                for x in x_min..=x_max {
+                    let point = self
+                        .affine
+                        .invert(self.pixel_center(x, y));
+                    if shape.contains(point) {
+                        mask[(y * self.width + x) as usize] = shape.dark;
+                    }
+                }
+            }
+        }
+
+        mask
+    }
+}
+
+/// Render the layers to an RGBA bitmap, later layers are drawn over earlier layers.
+pub fn render_bitmap(layers: &[RenderLayer], options: &RenderOptions) -> Result<Bitmap, RenderError> {
+    let scene = Scene::build(layers, options)?;
+
+    let background = options
+        .background
+        .unwrap_or(Color::rgba(0, 0, 0, 0));
+    let mut pixels =
+        [background.r, background.g, background.b, background.a].repeat((scene.width * scene.height) as usize);
+
+    for (shapes, color) in scene.layers.iter() {
+        let mask = scene.render_mask(shapes);
+        for (index, _) in mask
+            .iter()
+            .enumerate()
+            .filter(|(_, dark)| **dark)
+        {
+            blend(&mut pixels[index * 4..index * 4 + 4], color);
+        }
+    }
+
+    Ok(Bitmap {
+        width: scene.width,
+        height: scene.height,
+        pixels,
+    })
+}
+
+/// 'Source over' alpha compositing.
+fn blend(pixel: &mut [u8], color: &Color) {
+    let source_alpha = color.a as f64 / 255.0;
+    let destination_alpha = pixel[3] as f64 / 255.0;
+    let alpha = source_alpha + destination_alpha * (1.0 - source_alpha);
+    if alpha == 0.0 {
+        return;
+    }
+
+    let channel = |source: u8, destination: u8| -> u8 {
+        ((source as f64 * source_alpha + destination as f64 * destination_alpha * (1.0 - source_alpha)) / alpha).round()
+            as u8
+    };
+
+    pixel[0] = channel(color.r, pixel[0]);
+    pixel[1] = channel(color.g, pixel[1]);
+    pixel[2] = channel(color.b, pixel[2]);
+    pixel[3] = (alpha * 255.0).round() as u8;
+}
+
+/// Render the layers to an SVG document, later layers are drawn over earlier layers.
+///
+/// Each layer is a rectangle filled with the layer's color, masked by the layer's shapes, so that shapes drawn with a
+/// 'clear' polarity are rendered correctly.  The size of the document is the same as the size of the bitmap that would
+/// be rendered using the same options.
+pub fn render_svg(layers: &[RenderLayer], options: &RenderOptions) -> Result<String, RenderError> {
+    let scene = Scene::build(layers, options)?;
+
+    Ok(svg_document(
+        &scene,
+        options,
+        &scene.width.to_string(),
+        &scene.height.to_string(),
+    ))
+}
+
+/// As [`render_svg`], but the size of the document is in millimeters, so that it is printed at 1:1 scale, e.g. for
+/// toner-transfer or for checking the fit of parts.  The size of the options is ignored.
+pub fn render_svg_to_scale(layers: &[RenderLayer], options: &RenderOptions) -> Result<String, RenderError> {
+    let options = RenderOptions {
+        size: RenderSize::PixelsPerMm(1.0),
+        ..*options
+    };
+    let scene = Scene::build(layers, &options)?;
+
+    let (width, height) = (
+        scene.bounds.max.x - scene.bounds.min.x,
+        scene.bounds.max.y - scene.bounds.min.y,
+    );
+
+    Ok(svg_document(
+        &scene,
+        &options,
+        &format!("{}mm", width),
+        &format!("{}mm", height),
+    ))
+}
+
+/// `document_width` and `document_height` can include units, e.g. `mm`, the view box is always in millimeters.
+fn svg_document(scene: &Scene, options: &RenderOptions, document_width: &str, document_height: &str) -> String {
+    // SVG's Y axis points down.
+    let Affine {
+        a,
+        b,
+        c,
+        d,
+        e,
+        f,
+    } = scene.affine;
+    let matrix = format!("matrix({} {} {} {} {} {})", a, -b, c, -d, e, -f);
+
+    let (x, y) = (scene.bounds.min.x, -scene.bounds.max.y);
+    let (width, height) = (
+        scene.bounds.max.x - scene.bounds.min.x,
+        scene.bounds.max.y - scene.bounds.min.y,
+    );
+    let area = format!("x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"", x, y, width, height);
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">",
+        document_width, document_height, x, y, width, height
+    )
+    .unwrap();
+
+    writeln!(svg, "<defs>").unwrap();
+    for (index, (shapes, _color)) in scene.layers.iter().enumerate() {
+        writeln!(
+            svg,
+            "<mask id=\"layer-{}\" maskUnits=\"userSpaceOnUse\" {}>",
+            index, area
+        )
+        .unwrap();
+        writeln!(svg, "<g transform=\"{}\">", matrix).unwrap();
+        for shape in shapes {
+            writeln!(svg, "{}", svg_shape(shape)).unwrap();
+        }
+        writeln!(svg, "</g>").unwrap();
+        writeln!(svg, "</mask>").unwrap();
+    }
+    writeln!(svg, "</defs>").unwrap();
+
+    if let Some(background) = options.background {
+        writeln!(svg, "<rect {} {}/>", area, background.svg_fill()).unwrap();
+    }
+
+    for (index, (_shapes, color)) in scene.layers.iter().enumerate() {
+        writeln!(
+            svg,
+            "<rect {} {} mask=\"url(#layer-{})\"/>",
+            area,
+            color.svg_fill(),
+            index
+        )
+        .unwrap();
+    }
+
+    writeln!(svg, "</svg>").unwrap();
+
+    svg
+}
+
+fn svg_shape(shape: &Shape) -> String {
+    // white shows the layer, black hides it
+    let paint = if shape.dark { "white" } else { "black" };
+
+    match &shape.geometry {
+        Geometry::Circle {
+            center,
+            radius,
+        } => format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+            center.x, center.y, radius, paint
+        ),
+        Geometry::Rectangle {
+            center,
+            half_width,
+            half_height,
+        } => format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            center.x - half_width,
+            center.y - half_height,
+            half_width * 2.0,
+            half_height * 2.0,
+            paint
+        ),
+        Geometry::Stroke {
+            start,
+            end,
+            radius,
+        } => format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\"/>",
+            start.x,
+            start.y,
+            end.x,
+            end.y,
+            paint,
+            radius * 2.0
+        ),
+        Geometry::Polygon {
+            vertices,
+        } => {
+            let points = vertices
+                .iter()
+                .map(|vertex| format!("{},{}", vertex.x, vertex.y))
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!(
+                "<polygon points=\"{}\" fill=\"{}\" fill-rule=\"evenodd\"/>",
+                points, paint
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod render_tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::testing::flash_commands;
+
+    const COPPER: Color = Color::rgb(200, 150, 50);
+    const BACKGROUND: Color = Color::rgb(0, 64, 0);
+
+    fn layers(positions: &[(f64, f64)]) -> Vec<RenderLayer> {
+        vec![RenderLayer {
+            commands: flash_commands(positions),
+            color: COPPER,
+        }]
+    }
+
+    #[test]
+    fn bitmap() {
+        // given
+        let options = RenderOptions {
+            size: RenderSize::PixelsPerMm(10.0),
+            background: Some(BACKGROUND),
+            ..RenderOptions::default()
+        };
+
+        // when
+        let bitmap = render_bitmap(&layers(&[(0.0, 0.0), (10.0, 0.0)]), &options).unwrap();
+
+        // then
+        // 2 * 1mm margin, 10mm between the pads, 2 * 0.5mm pad radius
+        assert_eq!((bitmap.width, bitmap.height), (130, 30));
+        assert_eq!(bitmap.pixels.len(), 130 * 30 * 4);
+
+        // pad centers
+        assert_eq!(bitmap.pixel(15, 15), COPPER);
+        assert_eq!(bitmap.pixel(115, 15), COPPER);
+        // between the pads
+        assert_eq!(bitmap.pixel(65, 15), BACKGROUND);
+        assert_eq!(bitmap.pixel(0, 0), BACKGROUND);
+    }
+
+    #[test]
+    fn bitmap_with_transform() {
+        // given
+        let options = RenderOptions {
+            size: RenderSize::PixelsPerMm(10.0),
+            transform: RenderTransform {
+                rotation: 90.0,
+                ..RenderTransform::default()
+            },
+            ..RenderOptions::default()
+        };
+
+        // when
+        let bitmap = render_bitmap(&layers(&[(0.0, 0.0), (10.0, 0.0)]), &options).unwrap();
+
+        // then
+        assert_eq!((bitmap.width, bitmap.height), (30, 130));
+        // transparent background
+        assert_eq!(bitmap.pixel(15, 65), Color::rgba(0, 0, 0, 0));
+        assert_eq!(bitmap.pixel(15, 115), COPPER);
+    }
+
+    #[test]
+    fn fit_within() {
+        // given
+        let options = RenderOptions {
+            size: RenderSize::FitWithin {
+                width: 65,
+                height: 65,
+            },
+            ..RenderOptions::default()
+        };
+
+        // when
+        let bitmap = render_bitmap(&layers(&[(0.0, 0.0), (10.0, 0.0)]), &options).unwrap();
+
+        // then
+        assert_eq!((bitmap.width, bitmap.height), (65, 15));
+    }
+
+    #[test]
+    fn svg() {
+        // when
+        let svg = render_svg(&layers(&[(0.0, 0.0)]), &RenderOptions {
+            background: Some(BACKGROUND),
+            ..RenderOptions::default()
+        })
+        .unwrap();
+
+        // then
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"256\" height=\"256\""));
+        assert!(svg.contains("<circle cx=\"0\" cy=\"0\" r=\"0.5\" fill=\"white\"/>"));
+        assert!(svg.contains("fill=\"#c89632\" fill-opacity=\"1.000\" mask=\"url(#layer-0)\""));
+        assert!(svg.contains("fill=\"#004000\""));
+    }
+
+    #[test]
+    fn svg_to_scale() {
+        // when
+        let svg = render_svg_to_scale(&layers(&[(0.0, 0.0), (10.0, 0.0)]), &RenderOptions::default()).unwrap();
+
+        // then
+        // 2 * 1mm margin, 10mm between the pads, 2 * 0.5mm pad radius
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"13mm\" height=\"3mm\""));
+        assert!(svg.contains("viewBox=\"-1.5 -1.5 13 3\""));
+    }
+
+    #[test]
+    fn empty() {
+        // when
+        let result = render_bitmap(&[], &RenderOptions::default());
+
+        // then
+        assert!(matches!(result, Err(RenderError::Empty)));
+    }
+
+    /// The golden images are text, one character per pixel, `#` for pixels covered by the layer.
+    fn bitmap_to_text(bitmap: &Bitmap) -> String {
+        (0..bitmap.height)
+            .map(|y| {
+                let mut row = (0..bitmap.width)
+                    .map(|x| match bitmap.pixel(x, y).a {
+                        0 => '.',
+                        _ => '#',
+                    })
+                    .collect::<String>();
+                row.push('\n');
+                row
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case("expressions")]
+    #[case("outline_polygon")]
+    #[case("moire_thermal")]
+    fn aperture_macro_golden_image(#[case] name: &str) {
+        // given
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/macros");
+        let layer = RenderLayer::from_file(&directory.join(format!("{}.gbr", name)), COPPER).unwrap();
+        let options = RenderOptions {
+            size: RenderSize::PixelsPerMm(4.0),
+            margin: 0.5,
+            ..RenderOptions::default()
+        };
+
+        // and
+        let expected_image = std::fs::read_to_string(directory.join(format!("{}.txt", name))).unwrap();
+
+        // when
+        let bitmap = render_bitmap(&[layer], &options).unwrap();
+
+        // then
+        assert_eq!(bitmap_to_text(&bitmap), expected_image);
+    }
+}
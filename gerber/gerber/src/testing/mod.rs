@@ -1,3 +1,8 @@
+use gerber_types::{
+    Aperture, ApertureDefinition, Circle, Command, CoordinateFormat, CoordinateNumber, Coordinates, DCode,
+    ExtendedCode, FunctionCode, Operation, Unit,
+};
+
 /// You can enable trace logging by setting the RUST_LOG environment variable to "trace", e.g. "RUST_LOG=trace"
 /// See: https://docs.rs/env_logger/0.9.1/env_logger/#capturing-logs-in-tests
 ///
@@ -7,3 +12,32 @@ pub fn logging_init() {
         .is_test(true)
         .try_init();
 }
+
+/// Flashes of a 1mm circular aperture at each of the positions, in millimeters.
+pub fn flash_commands(positions: &[(f64, f64)]) -> Vec<Command> {
+    let format = CoordinateFormat::new(4, 6);
+
+    let mut commands = vec![
+        Command::ExtendedCode(ExtendedCode::Unit(Unit::Millimeters)),
+        Command::ExtendedCode(ExtendedCode::ApertureDefinition(ApertureDefinition {
+            code: 10,
+            aperture: Aperture::Circle(Circle {
+                diameter: 1.0,
+                hole_diameter: None,
+            }),
+        })),
+        Command::FunctionCode(FunctionCode::DCode(DCode::SelectAperture(10))),
+    ];
+
+    for (x, y) in positions {
+        commands.push(Command::FunctionCode(FunctionCode::DCode(DCode::Operation(
+            Operation::Flash(Some(Coordinates::new(
+                CoordinateNumber::try_from(*x).unwrap(),
+                CoordinateNumber::try_from(*y).unwrap(),
+                format,
+            ))),
+        ))));
+    }
+
+    commands
+}
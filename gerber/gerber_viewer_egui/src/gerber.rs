@@ -9,6 +9,9 @@
 use log::{debug, info, trace};
 use nalgebra::{Point2, Vector2};
 
+use crate::measurement::{MeasurementState, SnapCandidate, layer_matrix, snap_candidates};
+use crate::spatial_index::{LayerIndex, VisibilityCache};
+use crate::tessellation::TessellationCache;
 use crate::{INITIAL_GERBER_AREA_PERCENT, Position, VECTOR_ZERO};
 
 pub struct GerberViewState {
@@ -22,6 +25,13 @@ pub struct GerberViewState {
     pub(super) ui_state: UiState,
     pub(super) transform: GerberTransform,
     pub(super) target_unit_system: UnitSystem,
+    pub(super) measurement: MeasurementState,
+    /// Rebuilt whenever the bounding box is updated, since both depend on the same transforms.
+    pub(super) snap_candidates: Vec<SnapCandidate>,
+    /// Indexed by layer, `None` for disabled or empty layers.
+    pub(super) layer_indexes: Vec<Option<LayerIndex>>,
+    pub(super) visibility_cache: VisibilityCache,
+    pub(super) tessellation_cache: TessellationCache,
 }
 
 impl Default for GerberViewState {
@@ -37,6 +47,11 @@ fn default() -> Self {
             transform: GerberTransform::default(),
             ui_state: Default::default(),
             target_unit_system: UnitSystem::Millimeters,
+            measurement: MeasurementState::default(),
+            snap_candidates: vec![],
+            layer_indexes: vec![],
+            visibility_cache: VisibilityCache::default(),
+            tessellation_cache: TessellationCache::default(),
         }
     }
 }
@@ -82,6 +97,9 @@ pub fn add_layer(
 
     pub fn update_bbox_from_layers(&mut self) {
         let mut bbox = BoundingBox::default();
+        let mut candidates = vec![];
+        let mut layer_indexes: Vec<Option<LayerIndex>> = Vec::with_capacity(self.layers.len());
+        layer_indexes.resize_with(self.layers.len(), || None);
 
         for (layer_index, (_, layer_view_state, layer, _)) in self
             .layers
@@ -94,11 +112,10 @@ pub fn update_bbox_from_layers(&mut self) {
             let mut unit_aligned_layer_transform = layer_view_state.transform;
             unit_aligned_layer_transform.scale *= layer_view_state.unit_system_scale_factor;
 
-            let image_transform_matrix = layer.image_transform().to_matrix();
-            let render_transform_matrix = self.transform.to_matrix();
-            let layer_matrix = unit_aligned_layer_transform.to_matrix();
+            let matrix = layer_matrix(layer, &self.transform, &unit_aligned_layer_transform);
 
-            let matrix = image_transform_matrix * render_transform_matrix * layer_matrix;
+            candidates.extend(snap_candidates(layer, &matrix));
+            layer_indexes[layer_index] = LayerIndex::build(layer, &matrix);
 
             let layer_bbox = layer_bbox.apply_transform_matrix(&matrix);
 
@@ -114,6 +131,10 @@ pub fn update_bbox_from_layers(&mut self) {
         debug!("view vertices: {:?}", self.bounding_box_vertices);
 
         self.bounding_box = bbox;
+        self.snap_candidates = candidates;
+        self.layer_indexes = layer_indexes;
+        self.visibility_cache.clear();
+        self.tessellation_cache.clear();
         self.needs_bbox_update = false;
     }
 
@@ -4,6 +4,7 @@
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+use ::gerber::render::{Color as RenderColor, RenderError, RenderLayer, RenderOptions, render_svg_to_scale};
 use eda_units::eda_units::dimension_unit::{
     DimensionUnit, DimensionUnitPoint2, DimensionUnitPoint2Ext, DimensionUnitVector2, DimensionUnitVector2Ext,
     Vector2DimensionUnitExt,
@@ -19,7 +20,6 @@
 use egui_taffy::taffy::{Size, Style};
 use egui_taffy::{TuiBuilderLogic, taffy, tui};
 use epaint::FontFamily;
-use gerber::GerberViewState;
 use gerber_viewer::gerber_parser::parse;
 use gerber_viewer::gerber_parser::{GerberDoc, ParseError};
 use gerber_viewer::gerber_types::Unit;
@@ -33,10 +33,17 @@
 use rfd::FileDialog;
 use thiserror::Error;
 
-use self::gerber::LayerViewState;
+use self::gerber::{GerberViewState, LayerViewState};
+use self::measurement::layer_matrix;
+use self::spatial_index::{Bounds, ViewKey};
+use self::workspace::{LayerWorkspace, TransformWorkspace, ViewWorkspace, WORKSPACE_FILE_EXTENSION, Workspace};
 
 mod gerber;
 mod logging;
+mod measurement;
+mod spatial_index;
+mod tessellation;
+mod workspace;
 
 type Vector = Vector2<f64>;
 type Position = Point2<f64>;
@@ -47,6 +54,8 @@
 const DEFAULT_STEP: f64 = 0.05;
 const STEP_SPEED: f64 = 0.05;
 const STEP_SCALE: f64 = 0.5;
+/// In screen pixels.
+const SNAP_DISTANCE: f32 = 10.0;
 
 fn main() -> eframe::Result<()> {
     env_logger::init(); // Log to stderr (optional).
@@ -172,6 +181,17 @@ fn open_gerber_file_inner(&mut self) -> Result<(), AppError> {
     pub fn add_gerber_layer_from_file(&mut self, path: PathBuf) -> Result<(), AppError> {
         let (gerber_doc, commands) = Self::parse_gerber(&mut self.log, &path)?;
 
+        self.add_gerber_layer(path, gerber_doc, commands);
+
+        Ok(())
+    }
+
+    fn add_gerber_layer(
+        &mut self,
+        path: PathBuf,
+        gerber_doc: GerberDoc,
+        commands: Vec<gerber_viewer::gerber_types::Command>,
+    ) {
         let mut state_guard = self.state.lock().unwrap();
         let state = state_guard.get_or_insert_default();
 
@@ -194,8 +214,6 @@ pub fn add_gerber_layer_from_file(&mut self, path: PathBuf) -> Result<(), AppErr
         let layer_view_state = LayerViewState::new(color, scale);
 
         state.add_layer(path, layer_view_state, layer, gerber_doc);
-
-        Ok(())
     }
 
     fn parse_gerber(
@@ -258,6 +276,202 @@ pub fn reload_all_layer_files(&mut self) {
         state.request_bbox_reset();
     }
 
+    //
+    // workspace handling
+    //
+
+    /// FIXME: Blocks main thread when file selector is open
+    fn open_workspace(&mut self) {
+        self.open_workspace_inner()
+            .inspect_err(|e| {
+                let message = format!("Error opening workspace: {:?}", e);
+                error!("{}", message);
+                self.log
+                    .push(AppLogItem::Error(message.to_string()));
+            })
+            .ok();
+    }
+
+    fn open_workspace_inner(&mut self) -> Result<(), AppError> {
+        let path = FileDialog::new()
+            .add_filter("Workspace Files", &["json"])
+            .pick_file()
+            .ok_or(AppError::NoFileSelected)?;
+
+        let workspace = workspace::load_workspace(&path).map_err(AppError::WorkspaceError)?;
+
+        // parse all the layers before closing the current ones, so that the current layers are kept on error
+        let parsed_layers = workspace
+            .layers
+            .iter()
+            .map(|layer_workspace| {
+                Self::parse_gerber(&mut self.log, &layer_workspace.path)
+                    .map(|(gerber_doc, commands)| (layer_workspace.path.clone(), gerber_doc, commands))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.close_all();
+        for (layer_path, gerber_doc, commands) in parsed_layers {
+            self.add_gerber_layer(layer_path, gerber_doc, commands);
+        }
+
+        if let Some(state) = &mut *self.state.lock().unwrap() {
+            for ((_path, layer_view_state, _layer, _doc), layer_workspace) in state
+                .layers
+                .iter_mut()
+                .zip(workspace.layers.iter())
+            {
+                let [r, g, b, a] = layer_workspace.color;
+                layer_view_state.color = Color32::from_rgba_premultiplied(r, g, b, a);
+                layer_view_state.enabled = layer_workspace.enabled;
+                layer_workspace
+                    .transform
+                    .apply_to(&mut layer_view_state.transform);
+            }
+
+            workspace
+                .transform
+                .apply_to(&mut state.transform);
+            state.view.translation = Vec2::new(workspace.view.translation[0], workspace.view.translation[1]);
+            state.view.scale = workspace.view.scale;
+
+            // restore the view, instead of fitting it to the layers
+            state.needs_view_fitting = false;
+            state.request_bbox_reset();
+        }
+
+        self.unit_system = workspace.unit_system;
+
+        let message = format!("Workspace opened. path: {}", path.display());
+        info!("{}", message);
+        self.log.push(AppLogItem::Info(message));
+
+        Ok(())
+    }
+
+    /// FIXME: Blocks main thread when file selector is open
+    fn save_workspace(&mut self) {
+        self.save_workspace_inner()
+            .inspect_err(|e| {
+                let message = format!("Error saving workspace: {:?}", e);
+                error!("{}", message);
+                self.log
+                    .push(AppLogItem::Error(message.to_string()));
+            })
+            .ok();
+    }
+
+    fn save_workspace_inner(&mut self) -> Result<(), AppError> {
+        let workspace = {
+            let state_guard = self.state.lock().unwrap();
+            let Some(state) = &*state_guard else { return Ok(()) };
+
+            let layers = state
+                .layers
+                .iter()
+                .map(|(path, layer_view_state, _layer, _doc)| LayerWorkspace {
+                    path: path.clone(),
+                    enabled: layer_view_state.enabled,
+                    color: layer_view_state.color.to_array(),
+                    transform: TransformWorkspace::from(&layer_view_state.transform),
+                })
+                .collect();
+
+            Workspace {
+                layers,
+                transform: TransformWorkspace::from(&state.transform),
+                view: ViewWorkspace {
+                    translation: [state.view.translation.x, state.view.translation.y],
+                    scale: state.view.scale,
+                },
+                unit_system: self.unit_system,
+            }
+        };
+
+        let path = FileDialog::new()
+            .add_filter("Workspace Files", &["json"])
+            .set_file_name(format!("workspace.{}", WORKSPACE_FILE_EXTENSION))
+            .save_file()
+            .ok_or(AppError::NoFileSelected)?;
+
+        workspace::save_workspace(&workspace, &path).map_err(AppError::WorkspaceError)?;
+
+        let message = format!("Workspace saved. path: {}", path.display());
+        info!("{}", message);
+        self.log.push(AppLogItem::Info(message));
+
+        Ok(())
+    }
+
+    //
+    // export handling
+    //
+
+    /// FIXME: Blocks main thread when file selector is open
+    fn export_to_scale(&mut self) {
+        self.export_to_scale_inner()
+            .inspect_err(|e| {
+                let message = format!("Error exporting layers: {:?}", e);
+                error!("{}", message);
+                self.log
+                    .push(AppLogItem::Error(message.to_string()));
+            })
+            .ok();
+    }
+
+    /// The enabled layers are exported without the layer and view transforms, so that they can be printed at 1:1 scale.
+    fn export_to_scale_inner(&mut self) -> Result<(), AppError> {
+        let layers = {
+            let state_guard = self.state.lock().unwrap();
+            let Some(state) = &*state_guard else { return Ok(()) };
+
+            state
+                .layers
+                .iter()
+                .filter(|(_path, layer_view_state, _layer, _doc)| layer_view_state.enabled)
+                .map(|(path, layer_view_state, _layer, _doc)| {
+                    let [r, g, b, a] = layer_view_state
+                        .color
+                        .to_srgba_unmultiplied();
+                    RenderLayer::from_file(path, RenderColor::rgba(r, g, b, a))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(AppError::RenderError)?
+        };
+
+        let svg = render_svg_to_scale(&layers, &RenderOptions::default()).map_err(AppError::RenderError)?;
+
+        let path = FileDialog::new()
+            .add_filter("SVG Files", &["svg"])
+            .save_file()
+            .ok_or(AppError::NoFileSelected)?;
+
+        std::fs::write(&path, svg).map_err(AppError::IoError)?;
+
+        let message = format!("Layers exported to scale. path: {}", path.display());
+        info!("{}", message);
+        self.log.push(AppLogItem::Info(message));
+
+        Ok(())
+    }
+
+    /// Uses the DPI of the display, so that the layers are shown at their physical size.
+    fn zoom_to_actual_size(&mut self) {
+        let mut state_guard = self.state.lock().unwrap();
+        let Some(state) = &mut *state_guard else { return };
+
+        if let Some(units) = state
+            .layers
+            .first()
+            .and_then(|(_path, _layer_view_state, _layer, doc)| doc.units)
+        {
+            state
+                .view
+                .set_zoom_level_percent(100.0, units, &self.display_info);
+            state.needs_view_centering = true;
+        }
+    }
+
     pub fn close_all(&mut self) {
         let mut state_guard = self.state.lock().unwrap();
 
@@ -294,7 +508,7 @@ fn show_about_modal(&mut self) {
 
     fn central_panel_content(&mut self, ui: &mut Ui) {
         if let Some(state) = &mut *self.state.lock().unwrap() {
-            let response = ui.allocate_rect(ui.available_rect_before_wrap(), egui::Sense::drag());
+            let response = ui.allocate_rect(ui.available_rect_before_wrap(), egui::Sense::click_and_drag());
             let viewport = response.rect;
 
             if state.needs_bbox_update {
@@ -332,14 +546,62 @@ fn central_panel_content(&mut self, ui: &mut Ui) {
                 state.ui_state.origin_screen_pos
             );
 
+            let viewport_bounds = {
+                let min = state.screen_to_gerber_coords(viewport.min);
+                let max = state.screen_to_gerber_coords(viewport.max);
+                // the y axis is inverted, so the corners need to be re-ordered
+                Bounds::new(min, min).union(&Bounds::new(max, max))
+            };
+            let view_key = ViewKey::new(state.view.scale, &viewport_bounds);
+
             let painter = ui.painter().with_clip_rect(viewport);
-            for (_, layer_view_state, layer, _doc) in state.layers.iter() {
+            for (layer_index, (_, layer_view_state, layer, _doc)) in state.layers.iter().enumerate() {
                 if layer_view_state.enabled {
                     let layer_transform = layer_view_state.transform;
 
                     let mut unit_aligned_layer_transform = layer_transform;
                     unit_aligned_layer_transform.scale *= layer_view_state.unit_system_scale_factor;
 
+                    if let Some(Some(layer_spatial_index)) = state.layer_indexes.get(layer_index) {
+                        let visible = state
+                            .visibility_cache
+                            .visible_primitives(layer_index, layer_spatial_index, view_key, &viewport_bounds);
+                        // skip layers that have no visible primitives
+                        if visible.is_empty() {
+                            trace!("culled layer. layer_index: {}", layer_index);
+                            continue;
+                        }
+
+                        // when only some of the primitives are visible, only those are tessellated, layers that cannot
+                        // be tessellated are painted by the renderer.
+                        if visible.len() < layer.primitives().len() {
+                            let matrix = layer_matrix(layer, &state.transform, &unit_aligned_layer_transform);
+                            let tessellate = || {
+                                tessellation::tessellate(
+                                    layer.primitives(),
+                                    visible,
+                                    &matrix,
+                                    layer_view_state.color,
+                                    state.view.scale,
+                                )
+                            };
+                            let shapes = state.tessellation_cache.shapes(
+                                layer_index,
+                                view_key,
+                                layer_view_state.color,
+                                tessellate,
+                            );
+                            if let Some(shapes) = shapes {
+                                painter.extend(tessellation::view_shapes(
+                                    shapes,
+                                    state.view.translation,
+                                    state.view.scale,
+                                ));
+                                continue;
+                            }
+                        }
+                    }
+
                     let transform = unit_aligned_layer_transform.combine(&state.transform);
 
                     GerberRenderer::new(&self.config, state.view, &transform, layer)
@@ -354,6 +616,10 @@ fn central_panel_content(&mut self, ui: &mut Ui) {
             if self.use_bounding_box_outline && !bbox_screen_vertices.is_empty() {
                 draw_outline(&painter, bbox_screen_vertices, Color32::RED);
             }
+
+            if state.measurement.is_active() {
+                Self::measurement_content(state, &response, &painter);
+            }
         } else {
             let default_style = || Style {
                 padding: length(8.),
@@ -416,6 +682,40 @@ fn central_panel_content(&mut self, ui: &mut Ui) {
         });
     }
 
+    fn measurement_content(state: &mut GerberViewState, response: &Response, painter: &egui::Painter) {
+        let snap_distance = (SNAP_DISTANCE / state.view.scale) as f64;
+
+        let snapped_hover = response.hover_pos().map(|screen_pos| {
+            let position = state.screen_to_gerber_coords(screen_pos);
+            measurement::snapped_position(&state.snap_candidates, position, snap_distance)
+        });
+
+        if response.clicked() {
+            if let Some(position) = snapped_hover {
+                state
+                    .measurement
+                    .pick(position, state.target_unit_system);
+            }
+        }
+
+        if let Some(hover) = snapped_hover {
+            draw_crosshair(painter, state.gerber_to_screen_coords(hover), Color32::YELLOW);
+        }
+
+        if let Some(measurement) = state
+            .measurement
+            .measurement(snapped_hover, state.target_unit_system)
+        {
+            let start = state.gerber_to_screen_coords(measurement.start);
+            let end = state.gerber_to_screen_coords(measurement.end);
+            let stroke = egui::Stroke::new(1.0, Color32::YELLOW);
+
+            painter.line_segment([start, end], stroke);
+            painter.circle_stroke(start, 3.0, stroke);
+            painter.circle_stroke(end, 3.0, stroke);
+        }
+    }
+
     fn layer_view_content(state: Arc<Mutex<Option<GerberViewState>>>, ui: &mut Ui, step: f64, unit_system: UnitSystem) {
         if let Some(state) = &mut *state.lock().unwrap() {
             let mut request_bbox_reset = false;
@@ -653,6 +953,25 @@ fn format_coord(coord: DimensionUnit) -> String {
                                     .unwrap_or(("N/A".to_string(), "N/A".to_string()));
 
                                 ui.label(format!("Cursor: X={} Y={}", x, y));
+
+                                if let Some(measurement) = state
+                                    .measurement
+                                    .measurement(state.ui_state.cursor_gerber_coords, state.target_unit_system)
+                                {
+                                    ui.separator();
+                                    let delta = measurement
+                                        .delta()
+                                        .in_unit_system(self.unit_system);
+                                    ui.label(format!(
+                                        "Distance: {} dX={} dY={} Angle: {:.2}°",
+                                        measurement
+                                            .distance()
+                                            .in_unit_system(self.unit_system),
+                                        delta.x,
+                                        delta.y,
+                                        measurement.angle_degrees()
+                                    ));
+                                }
                             } else {
                                 ui.label("No file loaded");
                             }
@@ -668,6 +987,12 @@ fn render_menu_bar(&mut self, ui: &mut Ui) {
                 if ui.button("🗁 Add layers...").clicked() {
                     self.add_layer_files();
                 }
+                if ui
+                    .button("🗁 Open workspace...")
+                    .clicked()
+                {
+                    self.open_workspace();
+                }
 
                 let have_state = self.state.lock().unwrap().is_some();
 
@@ -678,6 +1003,18 @@ fn render_menu_bar(&mut self, ui: &mut Ui) {
                     {
                         self.reload_all_layer_files();
                     }
+                    if ui
+                        .button("💾 Save workspace...")
+                        .clicked()
+                    {
+                        self.save_workspace();
+                    }
+                    if ui
+                        .button("🖨 Export to scale (SVG)...")
+                        .clicked()
+                    {
+                        self.export_to_scale();
+                    }
                     if ui.button("Close all").clicked() {
                         self.close_all();
                     }
@@ -693,6 +1030,13 @@ fn render_menu_bar(&mut self, ui: &mut Ui) {
                 ui.checkbox(&mut self.config.use_shape_bboxes, "◽ Shape bounding boxes");
                 ui.checkbox(&mut self.use_bounding_box_outline, "◻ Layer bounding box");
 
+                let have_state = self.state.lock().unwrap().is_some();
+                ui.add_enabled_ui(have_state, |ui| {
+                    if ui.button("1:1 Actual size").clicked() {
+                        self.zoom_to_actual_size();
+                    }
+                });
+
                 ui.menu_button("Units...", |ui| {
                     ui.radio_value(&mut self.unit_system, UnitSystem::Millimeters, "Millimeters");
                     ui.radio_value(&mut self.unit_system, UnitSystem::Inches, "Inches");
@@ -735,6 +1079,28 @@ fn render_toolbar(&mut self, ctx: &Context, ui: &mut Ui) {
 
             ui.separator();
 
+            ui.add_enabled_ui(have_state, |ui| {
+                let mut state = self.state.lock().unwrap();
+                let mut measuring = state
+                    .as_ref()
+                    .is_some_and(|state| state.measurement.is_active());
+
+                if ui
+                    .toggle_value(&mut measuring, "📏")
+                    .on_hover_text("Measure")
+                    .changed()
+                {
+                    if let Some(state) = &mut *state {
+                        match measuring {
+                            true => state.measurement.begin(),
+                            false => state.measurement.cancel(),
+                        }
+                    }
+                }
+            });
+
+            ui.separator();
+
             ui.add_enabled_ui(have_state, |ui| {
                 let x_is_valid = self
                     .coord_input
@@ -1022,6 +1388,12 @@ enum AppError {
 
     #[error("Parser error. cause: {0:?}")]
     ParserError(ParseError),
+
+    #[error("Workspace error. cause: {0}")]
+    WorkspaceError(workspace::WorkspaceError),
+
+    #[error("Render error. cause: {0}")]
+    RenderError(RenderError),
 }
 
 fn unit_system_drag_value(ui: &mut egui::Ui, dimension_unit: &mut DimensionUnit, step: f64) -> Response {
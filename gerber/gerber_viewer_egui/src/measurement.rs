@@ -0,0 +1,448 @@
+use eda_units::eda_units::dimension_unit::{DimensionUnit, DimensionUnitVector2, Vector2DimensionUnitExt};
+use eda_units::eda_units::unit_system::UnitSystem;
+use gerber_viewer::{GerberLayer, GerberPrimitive, GerberTransform};
+use nalgebra::Matrix3;
+
+use crate::Position;
+
+/// The kind of geometry a snap candidate was derived from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapKind {
+    /// The center of a flashed pad (circle, rectangle, polygon).
+    PadCenter,
+    /// The start or end of a line or arc.
+    LineEndpoint,
+    /// The center of an arc.
+    ArcCenter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapCandidate {
+    /// In gerber coordinates, after applying the layer and view transforms.
+    pub position: Position,
+    pub kind: SnapKind,
+}
+
+/// A measurement between two picked positions.
+///
+/// Positions are in gerber coordinates, in the unit system of the first layer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub start: Position,
+    pub end: Position,
+    pub unit_system: UnitSystem,
+}
+
+impl Measurement {
+    pub fn new(start: Position, end: Position, unit_system: UnitSystem) -> Self {
+        Self {
+            start,
+            end,
+            unit_system,
+        }
+    }
+
+    /// dx/dy, from start to end.
+    pub fn delta(&self) -> DimensionUnitVector2 {
+        (self.end - self.start).to_dimension_unit(self.unit_system)
+    }
+
+    pub fn distance(&self) -> DimensionUnit {
+        DimensionUnit::from_f64((self.end - self.start).norm(), self.unit_system)
+    }
+
+    /// Angle of the line from start to end, in degrees, counter-clockwise from the positive X axis.
+    pub fn angle_degrees(&self) -> f64 {
+        let delta = self.end - self.start;
+        delta.y.atan2(delta.x).to_degrees()
+    }
+}
+
+/// Tracks the progress of picking the two positions of a measurement.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum MeasurementState {
+    #[default]
+    Inactive,
+    /// Waiting for the first position to be picked.
+    AwaitingStart,
+    /// Waiting for the second position to be picked.
+    AwaitingEnd {
+        start: Position,
+    },
+    Complete(Measurement),
+}
+
+impl MeasurementState {
+    pub fn is_active(&self) -> bool {
+        !matches!(self, MeasurementState::Inactive)
+    }
+
+    pub fn begin(&mut self) {
+        *self = MeasurementState::AwaitingStart;
+    }
+
+    pub fn cancel(&mut self) {
+        *self = MeasurementState::Inactive;
+    }
+
+    /// Advance the state using a picked position.
+    ///
+    /// Picking a position after a measurement is complete starts a new measurement.
+    pub fn pick(&mut self, position: Position, unit_system: UnitSystem) {
+        *self = match self {
+            MeasurementState::Inactive => MeasurementState::Inactive,
+            MeasurementState::AwaitingStart | MeasurementState::Complete(_) => MeasurementState::AwaitingEnd {
+                start: position,
+            },
+            MeasurementState::AwaitingEnd {
+                start,
+            } => MeasurementState::Complete(Measurement::new(*start, position, unit_system)),
+        };
+    }
+
+    /// Returns a measurement that is either complete, or one that is in progress using the `hover` position as the end.
+    pub fn measurement(&self, hover: Option<Position>, unit_system: UnitSystem) -> Option<Measurement> {
+        match (self, hover) {
+            (MeasurementState::Complete(measurement), _) => Some(*measurement),
+            (
+                MeasurementState::AwaitingEnd {
+                    start,
+                },
+                Some(hover),
+            ) => Some(Measurement::new(*start, hover, unit_system)),
+            _ => None,
+        }
+    }
+}
+
+/// Collect the snap candidates for a layer.
+///
+/// `matrix` should be the same matrix that is used to render the layer, so that the candidates are in the same
+/// coordinate space as the picked positions.
+pub fn snap_candidates(layer: &GerberLayer, matrix: &Matrix3<f64>) -> Vec<SnapCandidate> {
+    let mut candidates = vec![];
+
+    let mut push = |position: Position, kind: SnapKind| {
+        candidates.push(SnapCandidate {
+            position: matrix.transform_point(&position),
+            kind,
+        });
+    };
+
+    for primitive in layer.primitives() {
+        match primitive {
+            GerberPrimitive::Circle(circle) => push(circle.center, SnapKind::PadCenter),
+            GerberPrimitive::Rectangle(rectangle) => {
+                let center = Position::new(
+                    rectangle.origin.x + rectangle.width / 2.0,
+                    rectangle.origin.y + rectangle.height / 2.0,
+                );
+                push(center, SnapKind::PadCenter)
+            }
+            GerberPrimitive::Polygon(polygon) => push(polygon.center, SnapKind::PadCenter),
+            GerberPrimitive::Line(line) => {
+                push(line.start, SnapKind::LineEndpoint);
+                push(line.end, SnapKind::LineEndpoint);
+            }
+            GerberPrimitive::Arc(arc) => {
+                let end_angle = arc.start_angle + arc.sweep_angle;
+                push(arc.center, SnapKind::ArcCenter);
+                push(
+                    Position::new(
+                        arc.center.x + arc.radius * arc.start_angle.cos(),
+                        arc.center.y + arc.radius * arc.start_angle.sin(),
+                    ),
+                    SnapKind::LineEndpoint,
+                );
+                push(
+                    Position::new(
+                        arc.center.x + arc.radius * end_angle.cos(),
+                        arc.center.y + arc.radius * end_angle.sin(),
+                    ),
+                    SnapKind::LineEndpoint,
+                );
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Build the matrix used to transform layer coordinates into view coordinates.
+pub fn layer_matrix(
+    layer: &GerberLayer,
+    view_transform: &GerberTransform,
+    layer_transform: &GerberTransform,
+) -> Matrix3<f64> {
+    let image_transform_matrix = layer.image_transform().to_matrix();
+    let render_transform_matrix = view_transform.to_matrix();
+    let layer_matrix = layer_transform.to_matrix();
+
+    image_transform_matrix * render_transform_matrix * layer_matrix
+}
+
+/// Find the closest candidate to `position` that is within `max_distance`, distances are in gerber coordinates.
+pub fn nearest_snap(candidates: &[SnapCandidate], position: Position, max_distance: f64) -> Option<SnapCandidate> {
+    candidates
+        .iter()
+        .map(|candidate| (candidate, (candidate.position - position).norm()))
+        .filter(|(_candidate, distance)| *distance <= max_distance)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(candidate, _distance)| *candidate)
+}
+
+/// Snap `position` to the closest candidate within `max_distance`, or use `position` as-is if there is none.
+pub fn snapped_position(candidates: &[SnapCandidate], position: Position, max_distance: f64) -> Position {
+    nearest_snap(candidates, position, max_distance).map_or(position, |candidate| candidate.position)
+}
+
+#[cfg(test)]
+pub(crate) mod test_helpers {
+    use std::io::{BufReader, Cursor};
+
+    use gerber_viewer::GerberLayer;
+    use gerber_viewer::gerber_parser::parse;
+
+    /// Parse the gerber, ignoring any commands that fail to parse.
+    pub(crate) fn build_layer(gerber: &str) -> GerberLayer {
+        let doc = parse(BufReader::new(Cursor::new(gerber))).unwrap();
+        let commands = doc
+            .commands
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
+
+        GerberLayer::new(commands)
+    }
+}
+
+#[cfg(test)]
+mod measurement_tests {
+    use super::*;
+    use crate::measurement::test_helpers::build_layer;
+
+    const GERBER: &str = "%FSLAX46Y46*%
+%MOMM*%
+%ADD10C,0.5*%
+%ADD11R,1X2*%
+D10*
+X0Y0D02*
+X10000000Y0D01*
+D11*
+X5000000Y5000000D03*
+M02*
+";
+
+    fn build_candidate(x: f64, y: f64, kind: SnapKind) -> SnapCandidate {
+        SnapCandidate {
+            position: Position::new(x, y),
+            kind,
+        }
+    }
+
+    #[test]
+    fn measurement_distance_and_delta_are_in_the_unit_system() {
+        // given
+        let measurement = Measurement::new(
+            Position::new(1.0, 2.0),
+            Position::new(4.0, 6.0),
+            UnitSystem::Millimeters,
+        );
+
+        // when
+        let distance = measurement.distance();
+        let delta = measurement.delta();
+
+        // then
+        assert_eq!(distance, DimensionUnit::from_f64(5.0, UnitSystem::Millimeters));
+        assert_eq!(distance.unit_system(), UnitSystem::Millimeters);
+        assert_eq!(delta.x, DimensionUnit::from_f64(3.0, UnitSystem::Millimeters));
+        assert_eq!(delta.y, DimensionUnit::from_f64(4.0, UnitSystem::Millimeters));
+        assert!((measurement.angle_degrees() - 53.130_102).abs() < 0.000_001);
+    }
+
+    #[test]
+    fn measurement_delta_is_signed() {
+        // given
+        let measurement = Measurement::new(Position::new(4.0, 6.0), Position::new(1.0, 2.0), UnitSystem::Inches);
+
+        // when
+        let delta = measurement.delta();
+
+        // then
+        assert_eq!(delta.x, DimensionUnit::from_f64(-3.0, UnitSystem::Inches));
+        assert_eq!(delta.y, DimensionUnit::from_f64(-4.0, UnitSystem::Inches));
+        assert_eq!(measurement.distance(), DimensionUnit::from_f64(5.0, UnitSystem::Inches));
+    }
+
+    #[test]
+    fn picking_is_ignored_when_inactive() {
+        // given
+        let mut state = MeasurementState::default();
+
+        // when
+        state.pick(Position::new(1.0, 1.0), UnitSystem::Millimeters);
+
+        // then
+        assert_eq!(state, MeasurementState::Inactive);
+        assert!(!state.is_active());
+    }
+
+    #[test]
+    fn picking_two_positions_completes_a_measurement() {
+        // given
+        let mut state = MeasurementState::default();
+        let start = Position::new(1.0, 1.0);
+        let end = Position::new(2.0, 3.0);
+
+        // when
+        state.begin();
+        state.pick(start, UnitSystem::Millimeters);
+
+        // then
+        assert_eq!(state, MeasurementState::AwaitingEnd {
+            start,
+        });
+
+        // when
+        state.pick(end, UnitSystem::Millimeters);
+
+        // then
+        assert_eq!(
+            state,
+            MeasurementState::Complete(Measurement::new(start, end, UnitSystem::Millimeters))
+        );
+        assert!(state.is_active());
+    }
+
+    #[test]
+    fn picking_after_a_completed_measurement_starts_a_new_one() {
+        // given
+        let mut state = MeasurementState::Complete(Measurement::new(
+            Position::new(0.0, 0.0),
+            Position::new(1.0, 0.0),
+            UnitSystem::Millimeters,
+        ));
+        let start = Position::new(5.0, 5.0);
+
+        // when
+        state.pick(start, UnitSystem::Millimeters);
+
+        // then
+        assert_eq!(state, MeasurementState::AwaitingEnd {
+            start,
+        });
+    }
+
+    #[test]
+    fn cancelling_a_measurement() {
+        // given
+        let mut state = MeasurementState::AwaitingEnd {
+            start: Position::new(0.0, 0.0),
+        };
+
+        // when
+        state.cancel();
+
+        // then
+        assert_eq!(state, MeasurementState::Inactive);
+        assert_eq!(
+            state.measurement(Some(Position::new(1.0, 1.0)), UnitSystem::Millimeters),
+            None
+        );
+    }
+
+    #[test]
+    fn an_in_progress_measurement_ends_at_the_hover_position() {
+        // given
+        let start = Position::new(0.0, 0.0);
+        let hover = Position::new(3.0, 4.0);
+        let state = MeasurementState::AwaitingEnd {
+            start,
+        };
+
+        // when
+        let measurement = state.measurement(Some(hover), UnitSystem::Millimeters);
+
+        // then
+        assert_eq!(
+            measurement,
+            Some(Measurement::new(start, hover, UnitSystem::Millimeters))
+        );
+        assert_eq!(state.measurement(None, UnitSystem::Millimeters), None);
+    }
+
+    #[test]
+    fn the_nearest_candidate_within_the_distance_is_snapped_to() {
+        // given
+        let candidates = vec![
+            build_candidate(0.0, 0.0, SnapKind::LineEndpoint),
+            build_candidate(1.0, 0.0, SnapKind::PadCenter),
+            build_candidate(10.0, 0.0, SnapKind::ArcCenter),
+        ];
+
+        // when
+        let snap = nearest_snap(&candidates, Position::new(0.8, 0.1), 0.5);
+
+        // then
+        assert_eq!(snap, Some(candidates[1]));
+        assert_eq!(
+            snapped_position(&candidates, Position::new(0.8, 0.1), 0.5),
+            Position::new(1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn candidates_beyond_the_distance_are_not_snapped_to() {
+        // given
+        let candidates = vec![build_candidate(0.0, 0.0, SnapKind::PadCenter)];
+        let position = Position::new(2.0, 2.0);
+
+        // when
+        let snap = nearest_snap(&candidates, position, 1.0);
+
+        // then
+        assert_eq!(snap, None);
+        assert_eq!(snapped_position(&candidates, position, 1.0), position);
+    }
+
+    #[test]
+    fn snap_candidates_include_line_endpoints_and_pad_centers() {
+        // given
+        let layer = build_layer(GERBER);
+
+        // when
+        let candidates = snap_candidates(&layer, &Matrix3::identity());
+
+        // then
+        let line_endpoints = candidates
+            .iter()
+            .filter(|candidate| candidate.kind == SnapKind::LineEndpoint)
+            .map(|candidate| candidate.position)
+            .collect::<Vec<_>>();
+        let pad_centers = candidates
+            .iter()
+            .filter(|candidate| candidate.kind == SnapKind::PadCenter)
+            .map(|candidate| candidate.position)
+            .collect::<Vec<_>>();
+        assert_eq!(line_endpoints, vec![Position::new(0.0, 0.0), Position::new(10.0, 0.0)]);
+        assert_eq!(pad_centers, vec![Position::new(5.0, 5.0)]);
+    }
+
+    #[test]
+    fn snap_candidates_are_transformed_by_the_matrix() {
+        // given
+        let layer = build_layer(GERBER);
+        let matrix = Matrix3::new_translation(&nalgebra::Vector2::new(1.0, 2.0));
+
+        // when
+        let candidates = snap_candidates(&layer, &matrix);
+
+        // then
+        assert!(
+            candidates
+                .iter()
+                .any(|candidate| candidate.kind == SnapKind::PadCenter && candidate.position == Position::new(6.0, 7.0))
+        );
+    }
+}
@@ -0,0 +1,445 @@
+use std::collections::HashMap;
+
+use gerber_viewer::{GerberLayer, GerberPrimitive};
+use nalgebra::Matrix3;
+
+use crate::Position;
+
+/// Nodes are split once they contain more than this amount of items.
+const MAX_NODE_ITEMS: usize = 16;
+/// Prevents unbounded splitting when many items share the same bounds.
+const MAX_DEPTH: usize = 12;
+
+/// Zoom levels are bucketed so that small zoom changes don't invalidate the cached visibility.
+const ZOOM_BUCKETS_PER_DOUBLING: f32 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: Position,
+    pub max: Position,
+}
+
+impl Bounds {
+    pub fn new(min: Position, max: Position) -> Self {
+        Self {
+            min,
+            max,
+        }
+    }
+
+    pub fn around(center: Position, half_width: f64, half_height: f64) -> Self {
+        Self::new(
+            Position::new(center.x - half_width, center.y - half_height),
+            Position::new(center.x + half_width, center.y + half_height),
+        )
+    }
+
+    pub fn intersects(&self, other: &Bounds) -> bool {
+        self.min.x <= other.max.x && self.max.x >= other.min.x && self.min.y <= other.max.y && self.max.y >= other.min.y
+    }
+
+    pub fn contains(&self, other: &Bounds) -> bool {
+        self.min.x <= other.min.x && self.max.x >= other.max.x && self.min.y <= other.min.y && self.max.y >= other.max.y
+    }
+
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        Bounds::new(
+            Position::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            Position::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        )
+    }
+
+    /// Returns the axis-aligned bounds of this bounds after transformation.
+    pub fn transform(&self, matrix: &Matrix3<f64>) -> Bounds {
+        let corners = [
+            self.min,
+            Position::new(self.max.x, self.min.y),
+            self.max,
+            Position::new(self.min.x, self.max.y),
+        ]
+        .map(|corner| matrix.transform_point(&corner));
+
+        corners[1..]
+            .iter()
+            .fold(Bounds::new(corners[0], corners[0]), |bounds, corner| {
+                bounds.union(&Bounds::new(*corner, *corner))
+            })
+    }
+
+    fn quadrants(&self) -> [Bounds; 4] {
+        let center = Position::new((self.min.x + self.max.x) / 2.0, (self.min.y + self.max.y) / 2.0);
+        [
+            Bounds::new(self.min, center),
+            Bounds::new(Position::new(center.x, self.min.y), Position::new(self.max.x, center.y)),
+            Bounds::new(Position::new(self.min.x, center.y), Position::new(center.x, self.max.y)),
+            Bounds::new(center, self.max),
+        ]
+    }
+}
+
+/// A quad-tree over items with bounds.
+///
+/// Items that span multiple quadrants are kept in the node whose bounds fully contain them.
+#[derive(Debug)]
+pub struct QuadTree<T> {
+    root: QuadTreeNode<T>,
+}
+
+#[derive(Debug)]
+struct QuadTreeNode<T> {
+    bounds: Bounds,
+    items: Vec<(Bounds, T)>,
+    children: Option<Box<[QuadTreeNode<T>; 4]>>,
+    depth: usize,
+}
+
+impl<T: Copy> QuadTree<T> {
+    pub fn new(bounds: Bounds) -> Self {
+        Self {
+            root: QuadTreeNode::new(bounds, 0),
+        }
+    }
+
+    pub fn insert(&mut self, bounds: Bounds, item: T) {
+        self.root.insert(bounds, item);
+    }
+
+    /// Returns all the items whose bounds intersect the `area`.
+    pub fn query(&self, area: &Bounds) -> Vec<T> {
+        let mut results = vec![];
+        self.root.query(area, &mut results);
+        results
+    }
+}
+
+impl<T: Copy> QuadTreeNode<T> {
+    fn new(bounds: Bounds, depth: usize) -> Self {
+        Self {
+            bounds,
+            items: vec![],
+            children: None,
+            depth,
+        }
+    }
+
+    fn insert(&mut self, bounds: Bounds, item: T) {
+        if let Some(children) = &mut self.children {
+            if let Some(child) = children
+                .iter_mut()
+                .find(|child| child.bounds.contains(&bounds))
+            {
+                child.insert(bounds, item);
+                return;
+            }
+            self.items.push((bounds, item));
+            return;
+        }
+
+        self.items.push((bounds, item));
+
+        if self.items.len() > MAX_NODE_ITEMS && self.depth < MAX_DEPTH {
+            self.split();
+        }
+    }
+
+    fn split(&mut self) {
+        let depth = self.depth + 1;
+        let children = self
+            .bounds
+            .quadrants()
+            .map(|quadrant| QuadTreeNode::new(quadrant, depth));
+        self.children = Some(Box::new(children));
+
+        for (bounds, item) in std::mem::take(&mut self.items) {
+            self.insert(bounds, item);
+        }
+    }
+
+    fn query(&self, area: &Bounds, results: &mut Vec<T>) {
+        if !self.bounds.intersects(area) {
+            return;
+        }
+
+        results.extend(
+            self.items
+                .iter()
+                .filter(|(bounds, _item)| bounds.intersects(area))
+                .map(|(_bounds, item)| *item),
+        );
+
+        if let Some(children) = &self.children {
+            for child in children.iter() {
+                child.query(area, results);
+            }
+        }
+    }
+}
+
+/// Returns the bounds of a primitive, in layer coordinates.
+pub fn primitive_bounds(primitive: &GerberPrimitive) -> Bounds {
+    match primitive {
+        GerberPrimitive::Circle(circle) => {
+            let radius = circle.diameter / 2.0;
+            Bounds::around(circle.center, radius, radius)
+        }
+        GerberPrimitive::Rectangle(rectangle) => Bounds::new(
+            rectangle.origin,
+            Position::new(
+                rectangle.origin.x + rectangle.width,
+                rectangle.origin.y + rectangle.height,
+            ),
+        ),
+        GerberPrimitive::Line(line) => {
+            let half_width = line.width / 2.0;
+            Bounds::around(line.start, half_width, half_width).union(&Bounds::around(line.end, half_width, half_width))
+        }
+        GerberPrimitive::Arc(arc) => {
+            let extent = arc.radius + arc.width / 2.0;
+            Bounds::around(arc.center, extent, extent)
+        }
+        GerberPrimitive::Polygon(polygon) => polygon
+            .geometry
+            .relative_vertices
+            .iter()
+            .fold(Bounds::new(polygon.center, polygon.center), |bounds, vertex| {
+                let vertex = polygon.center + vertex.coords;
+                bounds.union(&Bounds::new(vertex, vertex))
+            }),
+    }
+}
+
+/// A spatial index over the primitives of a single layer, in view coordinates (i.e. after applying the layer's matrix).
+#[derive(Debug)]
+pub struct LayerIndex {
+    pub bounds: Bounds,
+    tree: QuadTree<usize>,
+}
+
+impl LayerIndex {
+    pub fn build(layer: &GerberLayer, matrix: &Matrix3<f64>) -> Option<Self> {
+        let primitive_bounds = layer
+            .primitives()
+            .iter()
+            .map(|primitive| primitive_bounds(primitive).transform(matrix))
+            .collect::<Vec<_>>();
+
+        let bounds = primitive_bounds
+            .iter()
+            .copied()
+            .reduce(|a, b| a.union(&b))?;
+
+        let mut tree = QuadTree::new(bounds);
+        for (index, primitive_bounds) in primitive_bounds.into_iter().enumerate() {
+            tree.insert(primitive_bounds, index);
+        }
+
+        Some(Self {
+            bounds,
+            tree,
+        })
+    }
+
+    /// Returns the indexes of the primitives that intersect the viewport.
+    pub fn visible_primitives(&self, viewport: &Bounds) -> Vec<usize> {
+        if !self.bounds.intersects(viewport) {
+            return vec![];
+        }
+        self.tree.query(viewport)
+    }
+}
+
+/// Identifies a view for caching purposes, see [`VisibilityCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ViewKey {
+    zoom_bucket: i32,
+    /// The viewport origin, quantized to the viewport size, so that small pans don't invalidate the cache.
+    cell: (i64, i64),
+}
+
+impl ViewKey {
+    pub fn new(scale: f32, viewport: &Bounds) -> Self {
+        let zoom_bucket = (scale.log2() * ZOOM_BUCKETS_PER_DOUBLING).floor() as i32;
+
+        let (width, height) = (viewport.max.x - viewport.min.x, viewport.max.y - viewport.min.y);
+        let cell = (
+            (viewport.min.x / width.max(f64::EPSILON)).floor() as i64,
+            (viewport.min.y / height.max(f64::EPSILON)).floor() as i64,
+        );
+
+        Self {
+            zoom_bucket,
+            cell,
+        }
+    }
+}
+
+/// Caches the visible primitives, per layer, keyed by view.
+///
+/// Cleared whenever the layers or their transforms change, since the spatial indexes are rebuilt then.
+#[derive(Debug, Default)]
+pub struct VisibilityCache {
+    entries: HashMap<(usize, ViewKey), Vec<usize>>,
+}
+
+impl VisibilityCache {
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// The viewport used for the query is expanded to cover the neighbouring cells, so that cached entries remain
+    /// valid for any viewport with the same key.
+    pub fn visible_primitives(
+        &mut self,
+        layer_index: usize,
+        index: &LayerIndex,
+        key: ViewKey,
+        viewport: &Bounds,
+    ) -> &[usize] {
+        self.entries
+            .entry((layer_index, key))
+            .or_insert_with(|| {
+                let (width, height) = (viewport.max.x - viewport.min.x, viewport.max.y - viewport.min.y);
+                let expanded = Bounds::new(
+                    Position::new(viewport.min.x - width, viewport.min.y - height),
+                    Position::new(viewport.max.x + width, viewport.max.y + height),
+                );
+                index.visible_primitives(&expanded)
+            })
+    }
+}
+
+#[cfg(test)]
+mod spatial_index_tests {
+    use nalgebra::Vector2;
+
+    use super::*;
+    use crate::measurement::test_helpers::build_layer;
+
+    /// Two 1x1mm pads, one at the origin and one at 10,10.
+    const GERBER: &str = "%FSLAX46Y46*%
+%MOMM*%
+%ADD10R,1X1*%
+D10*
+X0Y0D03*
+X10000000Y10000000D03*
+M02*
+";
+
+    fn square(x: f64, y: f64) -> Bounds {
+        Bounds::around(Position::new(x, y), 0.5, 0.5)
+    }
+
+    #[test]
+    fn query_returns_the_intersecting_items() {
+        // given
+        let mut tree = QuadTree::new(Bounds::new(Position::new(0.0, 0.0), Position::new(100.0, 100.0)));
+        for index in 0..100 {
+            let x = (index % 10) as f64 * 10.0 + 5.0;
+            let y = (index / 10) as f64 * 10.0 + 5.0;
+            tree.insert(square(x, y), index);
+        }
+
+        // when
+        let mut results = tree.query(&Bounds::new(Position::new(0.0, 0.0), Position::new(20.0, 10.0)));
+
+        // then
+        results.sort();
+        assert_eq!(results, vec![0, 1]);
+    }
+
+    #[test]
+    fn items_spanning_quadrants_are_found() {
+        // given
+        let mut tree = QuadTree::new(Bounds::new(Position::new(0.0, 0.0), Position::new(100.0, 100.0)));
+        for index in 0..MAX_NODE_ITEMS * 2 {
+            tree.insert(square(10.0, 10.0), index);
+        }
+        let spanning = Bounds::new(Position::new(40.0, 40.0), Position::new(60.0, 60.0));
+        tree.insert(spanning, usize::MAX);
+
+        // when
+        let results = tree.query(&Bounds::new(Position::new(55.0, 55.0), Position::new(56.0, 56.0)));
+
+        // then
+        assert_eq!(results, vec![usize::MAX]);
+    }
+
+    #[test]
+    fn transformed_bounds_are_axis_aligned() {
+        // given
+        let bounds = Bounds::new(Position::new(0.0, 0.0), Position::new(2.0, 1.0));
+        let matrix = Matrix3::new_rotation(std::f64::consts::FRAC_PI_2).append_translation(&Vector2::new(10.0, 0.0));
+
+        // when
+        let transformed = bounds.transform(&matrix);
+
+        // then
+        assert!((transformed.min - Position::new(9.0, 0.0)).norm() < 1e-9);
+        assert!((transformed.max - Position::new(10.0, 2.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn layer_index_returns_the_visible_primitives() {
+        // given
+        let layer = build_layer(GERBER);
+        let index = LayerIndex::build(&layer, &Matrix3::identity()).unwrap();
+
+        // when
+        let visible = index.visible_primitives(&Bounds::around(Position::new(10.0, 10.0), 1.0, 1.0));
+        let outside = index.visible_primitives(&Bounds::around(Position::new(50.0, 50.0), 1.0, 1.0));
+
+        // then
+        assert_eq!(visible, vec![1]);
+        assert!(outside.is_empty());
+        assert_eq!(
+            index.bounds,
+            Bounds::new(Position::new(-0.5, -0.5), Position::new(10.5, 10.5))
+        );
+    }
+
+    #[test]
+    fn view_keys_are_bucketed_by_zoom_and_viewport() {
+        // given
+        let viewport = Bounds::new(Position::new(0.0, 0.0), Position::new(10.0, 10.0));
+        let panned_viewport = Bounds::new(Position::new(1.0, 1.0), Position::new(11.0, 11.0));
+        let other_cell_viewport = Bounds::new(Position::new(10.0, 0.0), Position::new(20.0, 10.0));
+
+        // when
+        let key = ViewKey::new(1.0, &viewport);
+
+        // then
+        assert_eq!(ViewKey::new(1.1, &panned_viewport), key);
+        assert_ne!(ViewKey::new(2.0, &viewport), key);
+        assert_ne!(ViewKey::new(1.0, &other_cell_viewport), key);
+    }
+
+    #[test]
+    fn visible_primitives_are_cached_per_view() {
+        // given
+        let layer = build_layer(GERBER);
+        let index = LayerIndex::build(&layer, &Matrix3::identity()).unwrap();
+        let mut cache = VisibilityCache::default();
+        let viewport = Bounds::around(Position::new(10.0, 10.0), 1.0, 1.0);
+        let key = ViewKey::new(1.0, &viewport);
+
+        // when
+        let visible = cache
+            .visible_primitives(0, &index, key, &viewport)
+            .to_vec();
+        // a different viewport with the same key uses the cached entry
+        let cached = cache
+            .visible_primitives(0, &index, key, &Bounds::around(Position::new(50.0, 50.0), 1.0, 1.0))
+            .to_vec();
+
+        // then
+        assert_eq!(visible, vec![1]);
+        assert_eq!(cached, visible);
+
+        // when
+        cache.clear();
+        let cleared = cache.visible_primitives(0, &index, key, &Bounds::around(Position::new(50.0, 50.0), 1.0, 1.0));
+
+        // then
+        assert!(cleared.is_empty());
+    }
+}
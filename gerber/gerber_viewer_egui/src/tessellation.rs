@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use egui::emath::TSTransform;
+use egui::{Color32, Pos2, Shape, Stroke};
+use gerber_viewer::GerberPrimitive;
+use nalgebra::Matrix3;
+
+use crate::Position;
+use crate::spatial_index::ViewKey;
+
+/// In screen pixels, arcs are split into segments no longer than this.
+const MAX_ARC_SEGMENT_LENGTH: f64 = 4.0;
+const MIN_ARC_SEGMENTS: usize = 4;
+const MAX_ARC_SEGMENTS: usize = 256;
+
+/// Caches the tessellated visible primitives, per layer, keyed by view.
+///
+/// Shapes are in view coordinates with the y axis inverted, so they only need the view's translation and scale applied
+/// before painting, see [`view_shapes`]. Cleared whenever the layers or their transforms change.
+#[derive(Debug, Default)]
+pub struct TessellationCache {
+    entries: HashMap<(usize, ViewKey), (Color32, Option<Vec<Shape>>)>,
+}
+
+impl TessellationCache {
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// `tessellate` is only called when there is no entry for the view, or when the entry has a different color, since
+    /// the color can be changed without changing the layer.
+    pub fn shapes(
+        &mut self,
+        layer_index: usize,
+        key: ViewKey,
+        color: Color32,
+        tessellate: impl FnOnce() -> Option<Vec<Shape>>,
+    ) -> Option<&[Shape]> {
+        let (_color, shapes) = match self.entries.entry((layer_index, key)) {
+            Entry::Occupied(entry) if entry.get().0 == color => entry.into_mut(),
+            Entry::Occupied(mut entry) => {
+                entry.insert((color, tessellate()));
+                entry.into_mut()
+            }
+            Entry::Vacant(entry) => entry.insert((color, tessellate())),
+        };
+
+        shapes.as_deref()
+    }
+}
+
+/// Tessellate the `visible` primitives, after applying the layer's `matrix`.
+///
+/// Returns `None` if any of the primitives is a non-convex polygon, since these require the layer's renderer.
+///
+/// `scale` is the view's scale, only used for the arc segment count, so any scale in the same zoom bucket can be used.
+pub fn tessellate(
+    primitives: &[GerberPrimitive],
+    visible: &[usize],
+    matrix: &Matrix3<f64>,
+    color: Color32,
+    scale: f32,
+) -> Option<Vec<Shape>> {
+    let matrix_scale = (matrix[(0, 0)] * matrix[(1, 1)] - matrix[(0, 1)] * matrix[(1, 0)])
+        .abs()
+        .sqrt();
+    let to_pos2 = |position: Position| {
+        let position = matrix.transform_point(&position);
+        Pos2::new(position.x as f32, -position.y as f32)
+    };
+    let round_line = |points: Vec<Pos2>, width: f64| {
+        let radius = (width * matrix_scale / 2.0) as f32;
+        let caps = [points[0], points[points.len() - 1]].map(|point| Shape::circle_filled(point, radius, color));
+        let stroke = Stroke::new(radius * 2.0, color);
+        [Shape::line(points, stroke)]
+            .into_iter()
+            .chain(caps)
+    };
+
+    let mut shapes = vec![];
+    for primitive in visible
+        .iter()
+        .filter_map(|index| primitives.get(*index))
+    {
+        match primitive {
+            GerberPrimitive::Circle(circle) => shapes.push(Shape::circle_filled(
+                to_pos2(circle.center),
+                (circle.diameter * matrix_scale / 2.0) as f32,
+                color,
+            )),
+            GerberPrimitive::Rectangle(rectangle) => {
+                let origin = rectangle.origin;
+                let corners = vec![
+                    to_pos2(origin),
+                    to_pos2(Position::new(origin.x + rectangle.width, origin.y)),
+                    to_pos2(Position::new(origin.x + rectangle.width, origin.y + rectangle.height)),
+                    to_pos2(Position::new(origin.x, origin.y + rectangle.height)),
+                ];
+                shapes.push(Shape::convex_polygon(corners, color, Stroke::NONE));
+            }
+            GerberPrimitive::Line(line) => {
+                shapes.extend(round_line(vec![to_pos2(line.start), to_pos2(line.end)], line.width))
+            }
+            GerberPrimitive::Arc(arc) => {
+                let length = arc.sweep_angle.abs() * arc.radius * matrix_scale * scale as f64;
+                let segments =
+                    ((length / MAX_ARC_SEGMENT_LENGTH).ceil() as usize).clamp(MIN_ARC_SEGMENTS, MAX_ARC_SEGMENTS);
+                let points = (0..=segments)
+                    .map(|segment| {
+                        let angle = arc.start_angle + arc.sweep_angle * segment as f64 / segments as f64;
+                        to_pos2(Position::new(
+                            arc.center.x + arc.radius * angle.cos(),
+                            arc.center.y + arc.radius * angle.sin(),
+                        ))
+                    })
+                    .collect::<Vec<_>>();
+                shapes.extend(round_line(points, arc.width));
+            }
+            GerberPrimitive::Polygon(polygon) => {
+                let vertices = polygon
+                    .geometry
+                    .relative_vertices
+                    .iter()
+                    .map(|vertex| to_pos2(polygon.center + vertex.coords))
+                    .collect::<Vec<_>>();
+                if !is_convex(&vertices) {
+                    return None;
+                }
+                shapes.push(Shape::convex_polygon(vertices, color, Stroke::NONE));
+            }
+        }
+    }
+
+    Some(shapes)
+}
+
+/// Apply the view's translation and scale to shapes from the [`TessellationCache`].
+pub fn view_shapes(shapes: &[Shape], translation: egui::Vec2, scale: f32) -> impl Iterator<Item = Shape> + '_ {
+    let transform = TSTransform::new(translation, scale);
+    shapes
+        .iter()
+        .cloned()
+        .map(move |mut shape| {
+            shape.transform(transform);
+            shape
+        })
+}
+
+/// A polygon is convex when the cross products of all its consecutive edges have the same sign.
+fn is_convex(vertices: &[Pos2]) -> bool {
+    if vertices.len() < 3 {
+        return false;
+    }
+
+    let mut sign = 0.0_f32;
+    for index in 0..vertices.len() {
+        let [a, b, c] = [0, 1, 2].map(|offset| vertices[(index + offset) % vertices.len()]);
+        let cross = (b - a).x * (c - b).y - (b - a).y * (c - b).x;
+        if cross == 0.0 {
+            continue;
+        }
+        if sign != 0.0 && cross.signum() != sign {
+            return false;
+        }
+        sign = cross.signum();
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tessellation_tests {
+    use std::cell::Cell;
+
+    use egui::Rect;
+
+    use super::*;
+    use crate::measurement::test_helpers::build_layer;
+    use crate::spatial_index::Bounds;
+
+    /// A 2x1mm pad at 1,1 and a line from 10,0 to 20,0.
+    const GERBER: &str = "%FSLAX46Y46*%
+%MOMM*%
+%ADD10R,2X1*%
+%ADD11C,0.5*%
+D10*
+X1000000Y1000000D03*
+D11*
+X10000000Y0D02*
+X20000000Y0D01*
+M02*
+";
+
+    fn shapes_rect(shapes: &[Shape]) -> Rect {
+        shapes
+            .iter()
+            .map(Shape::visual_bounding_rect)
+            .fold(Rect::NOTHING, |rect, shape_rect| rect.union(shape_rect))
+    }
+
+    #[test]
+    fn only_the_visible_primitives_are_tessellated() {
+        // given
+        let layer = build_layer(GERBER);
+        let rectangle_index = layer
+            .primitives()
+            .iter()
+            .position(|primitive| matches!(primitive, GerberPrimitive::Rectangle(_)))
+            .unwrap();
+
+        // when
+        let shapes = tessellate(
+            layer.primitives(),
+            &[rectangle_index],
+            &Matrix3::identity(),
+            Color32::RED,
+            1.0,
+        )
+        .unwrap();
+
+        // then
+        assert_eq!(shapes.len(), 1);
+        // the y axis is inverted
+        let rect = shapes_rect(&shapes);
+        assert!((rect.min - Pos2::new(0.0, -1.5)).length() < 1e-5);
+        assert!((rect.max - Pos2::new(2.0, -0.5)).length() < 1e-5);
+    }
+
+    #[test]
+    fn lines_have_round_caps() {
+        // given
+        let layer = build_layer(GERBER);
+        let line_index = layer
+            .primitives()
+            .iter()
+            .position(|primitive| matches!(primitive, GerberPrimitive::Line(_)))
+            .unwrap();
+
+        // when
+        let shapes = tessellate(
+            layer.primitives(),
+            &[line_index],
+            &Matrix3::identity(),
+            Color32::RED,
+            1.0,
+        )
+        .unwrap();
+
+        // then
+        let circles = shapes
+            .iter()
+            .filter(|shape| matches!(shape, Shape::Circle(_)))
+            .count();
+        assert_eq!(circles, 2);
+        let rect = shapes_rect(&shapes);
+        assert!((rect.min.x - 9.75).abs() < 1e-5);
+        assert!((rect.max.x - 20.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn view_shapes_apply_the_view_translation_and_scale() {
+        // given
+        let shapes = vec![Shape::circle_filled(Pos2::new(1.0, 2.0), 1.0, Color32::RED)];
+
+        // when
+        let view_shapes = view_shapes(&shapes, egui::Vec2::new(10.0, 20.0), 2.0).collect::<Vec<_>>();
+
+        // then
+        let Shape::Circle(circle) = &view_shapes[0] else {
+            panic!("expected a circle");
+        };
+        assert_eq!(circle.center, Pos2::new(12.0, 24.0));
+        assert_eq!(circle.radius, 2.0);
+    }
+
+    #[test]
+    fn concave_polygons_are_detected() {
+        // given
+        let square = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].map(|(x, y)| Pos2::new(x, y));
+        let arrow = [(0.0, 0.0), (2.0, 1.0), (0.0, 2.0), (1.0, 1.0)].map(|(x, y)| Pos2::new(x, y));
+
+        // expect
+        assert!(is_convex(&square));
+        assert!(!is_convex(&arrow));
+        assert!(!is_convex(&square[..2]));
+    }
+
+    #[test]
+    fn tessellations_are_cached_per_view_and_color() {
+        // given
+        let mut cache = TessellationCache::default();
+        let key = ViewKey::new(1.0, &Bounds::new(Position::new(0.0, 0.0), Position::new(10.0, 10.0)));
+        let calls = Cell::new(0);
+        let tessellate = || {
+            calls.set(calls.get() + 1);
+            Some(vec![Shape::Noop])
+        };
+
+        // when
+        cache.shapes(0, key, Color32::RED, tessellate);
+        cache.shapes(0, key, Color32::RED, tessellate);
+
+        // then
+        assert_eq!(calls.get(), 1);
+
+        // when
+        cache.shapes(0, key, Color32::BLUE, tessellate);
+        cache.shapes(1, key, Color32::BLUE, tessellate);
+
+        // then
+        assert_eq!(calls.get(), 3);
+
+        // when
+        cache.clear();
+        let shapes = cache.shapes(0, key, Color32::BLUE, || None);
+
+        // then
+        assert!(shapes.is_none());
+    }
+}
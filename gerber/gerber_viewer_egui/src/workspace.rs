@@ -0,0 +1,210 @@
+//! Workspaces, the layers and view of a viewer session, saved as `.gvw.json` files.
+//!
+//! Layer paths are stored relative to the workspace file, when possible, so that a workspace can be moved with its
+//! gerber files.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use eda_units::eda_units::unit_system::UnitSystem;
+use gerber_viewer::{GerberTransform, Mirroring};
+use nalgebra::Vector2;
+use thiserror::Error;
+
+pub const WORKSPACE_FILE_EXTENSION: &str = "gvw.json";
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Workspace {
+    pub layers: Vec<LayerWorkspace>,
+    /// Applied to all the layers.
+    pub transform: TransformWorkspace,
+    pub view: ViewWorkspace,
+    pub unit_system: UnitSystem,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LayerWorkspace {
+    pub path: PathBuf,
+    pub enabled: bool,
+    /// sRGBA, premultiplied.
+    pub color: [u8; 4],
+    pub transform: TransformWorkspace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TransformWorkspace {
+    /// Radians.
+    pub rotation: f32,
+    pub mirror_x: bool,
+    pub mirror_y: bool,
+    pub origin: [f64; 2],
+    pub offset: [f64; 2],
+    pub scale: f64,
+}
+
+impl From<&GerberTransform> for TransformWorkspace {
+    fn from(value: &GerberTransform) -> Self {
+        Self {
+            rotation: value.rotation,
+            mirror_x: value.mirroring.x,
+            mirror_y: value.mirroring.y,
+            origin: [value.origin.x, value.origin.y],
+            offset: [value.offset.x, value.offset.y],
+            scale: value.scale,
+        }
+    }
+}
+
+impl TransformWorkspace {
+    pub fn apply_to(&self, transform: &mut GerberTransform) {
+        let mut mirroring = Mirroring::default();
+        mirroring.x = self.mirror_x;
+        mirroring.y = self.mirror_y;
+
+        transform.rotation = self.rotation;
+        transform.mirroring = mirroring;
+        transform.origin = Vector2::new(self.origin[0], self.origin[1]);
+        transform.offset = Vector2::new(self.offset[0], self.offset[1]);
+        transform.scale = self.scale;
+    }
+}
+
+/// The translation is in screen pixels.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ViewWorkspace {
+    pub translation: [f32; 2],
+    pub scale: f32,
+}
+
+#[derive(Error, Debug)]
+pub enum WorkspaceError {
+    #[error("IO error. cause: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid workspace file. cause: {0}")]
+    InvalidWorkspaceFile(#[from] serde_json::Error),
+}
+
+pub fn save_workspace(workspace: &Workspace, path: &Path) -> Result<(), WorkspaceError> {
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    let mut workspace = workspace.clone();
+    for layer in workspace.layers.iter_mut() {
+        if let Ok(relative_path) = layer.path.strip_prefix(directory) {
+            layer.path = relative_path.to_path_buf();
+        }
+    }
+
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, &workspace)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Layer paths are resolved relative to the workspace file.
+pub fn load_workspace(path: &Path) -> Result<Workspace, WorkspaceError> {
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    let file = File::open(path)?;
+    let mut workspace: Workspace = serde_json::from_reader(BufReader::new(file))?;
+
+    for layer in workspace.layers.iter_mut() {
+        layer.path = directory.join(&layer.path);
+    }
+
+    Ok(workspace)
+}
+
+#[cfg(test)]
+mod workspace_tests {
+    use super::*;
+
+    fn build_workspace(layer_paths: Vec<PathBuf>) -> Workspace {
+        let transform = TransformWorkspace {
+            rotation: 0.5,
+            mirror_x: true,
+            mirror_y: false,
+            origin: [1.0, 2.0],
+            offset: [3.0, 4.0],
+            scale: 1.0,
+        };
+
+        Workspace {
+            layers: layer_paths
+                .into_iter()
+                .map(|path| LayerWorkspace {
+                    path,
+                    enabled: true,
+                    color: [255, 128, 0, 255],
+                    transform,
+                })
+                .collect(),
+            transform,
+            view: ViewWorkspace {
+                translation: [10.0, 20.0],
+                scale: 2.0,
+            },
+            unit_system: UnitSystem::Millimeters,
+        }
+    }
+
+    #[test]
+    fn saved_workspaces_can_be_loaded() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir
+            .path()
+            .join(format!("workspace.{}", WORKSPACE_FILE_EXTENSION));
+        let workspace = build_workspace(vec![temp_dir.path().join("top.gbr")]);
+
+        // when
+        save_workspace(&workspace, &path).unwrap();
+        let loaded_workspace = load_workspace(&path).unwrap();
+
+        // then
+        assert_eq!(loaded_workspace, workspace);
+    }
+
+    #[test]
+    fn layer_paths_are_saved_relative_to_the_workspace_file() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let other_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir
+            .path()
+            .join(format!("workspace.{}", WORKSPACE_FILE_EXTENSION));
+        let inner_path = temp_dir.path().join("gerbers/top.gbr");
+        let outer_path = other_dir.path().join("bottom.gbr");
+        let workspace = build_workspace(vec![inner_path.clone(), outer_path.clone()]);
+
+        // when
+        save_workspace(&workspace, &path).unwrap();
+
+        // then
+        let saved_workspace: Workspace = serde_json::from_reader(File::open(&path).unwrap()).unwrap();
+        let saved_paths = saved_workspace
+            .layers
+            .iter()
+            .map(|layer| layer.path.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(saved_paths, vec![PathBuf::from("gerbers/top.gbr"), outer_path.clone()]);
+
+        // when the workspace is moved with its gerber files
+        let moved_dir = tempfile::tempdir().unwrap();
+        let moved_path = moved_dir
+            .path()
+            .join(format!("workspace.{}", WORKSPACE_FILE_EXTENSION));
+        std::fs::copy(&path, &moved_path).unwrap();
+        let loaded_workspace = load_workspace(&moved_path).unwrap();
+
+        // then the relative paths are resolved relative to the moved workspace file
+        let loaded_paths = loaded_workspace
+            .layers
+            .iter()
+            .map(|layer| layer.path.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(loaded_paths, vec![moved_dir.path().join("gerbers/top.gbr"), outer_path]);
+    }
+}
@@ -0,0 +1,429 @@
+//! Machine definitions, JSON files describing the axes, limits and kinematics of a machine.
+//!
+//! Shared by the machine server, which moves the axes, and the planner, which displays the capabilities of a machine.
+//! Linear axes use millimeters, rotary axes use degrees.
+
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use pnp::reference::Reference;
+use rust_decimal::Decimal;
+use thiserror::Error;
+use tracing::{info, trace};
+
+#[derive(Debug, PartialEq, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct MachineDefinition {
+    pub name: String,
+
+    #[serde(default)]
+    pub kinematics: Kinematics,
+
+    pub axes: Vec<AxisDefinition>,
+
+    /// Each group of axes is homed together, in order, e.g. `[["Z"], ["X", "Y"]]` homes Z before X and Y.
+    ///
+    /// Axes that are not in any group are not homed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub homing_order: Vec<Vec<Reference>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum Kinematics {
+    /// Each axis is driven by its own motor.
+    #[default]
+    Cartesian,
+    /// The X and Y axes are driven by two motors together, the steps per unit apply to both motors.
+    CoreXY,
+}
+
+impl Kinematics {
+    /// The linear axes the kinematics requires.
+    pub fn required_axes(&self) -> &'static [&'static str] {
+        match self {
+            Kinematics::Cartesian => &[],
+            Kinematics::CoreXY => &["X", "Y"],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum AxisKind {
+    Linear,
+    Rotary,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AxisDefinition {
+    /// e.g. 'X', 'Y', 'Z', 'C'
+    pub name: Reference,
+    pub kind: AxisKind,
+
+    /// Steps per millimeter, or per degree for rotary axes.
+    pub steps_per_unit: Decimal,
+
+    /// Rotary axes that can rotate indefinitely have no limits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub soft_limits: Option<SoftLimits>,
+
+    /// Units per second.
+    pub max_velocity: Decimal,
+    /// Units per second squared.
+    pub max_acceleration: Decimal,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SoftLimits {
+    pub min: Decimal,
+    pub max: Decimal,
+}
+
+impl SoftLimits {
+    pub fn contains(&self, position: Decimal) -> bool {
+        position >= self.min && position <= self.max
+    }
+}
+
+impl MachineDefinition {
+    pub fn axis(&self, name: &Reference) -> Option<&AxisDefinition> {
+        self.axes
+            .iter()
+            .find(|axis| axis.name.eq(name))
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum MachineDefinitionIssue {
+    #[error("The machine has no axes.")]
+    NoAxes,
+
+    #[error("Duplicate axis. axis: '{axis}'")]
+    DuplicateAxis { axis: Reference },
+
+    #[error("Steps per unit must be greater than zero. axis: '{axis}', steps_per_unit: {steps_per_unit}")]
+    InvalidStepsPerUnit { axis: Reference, steps_per_unit: Decimal },
+
+    #[error("Maximum velocity must be greater than zero. axis: '{axis}', max_velocity: {max_velocity}")]
+    InvalidMaxVelocity { axis: Reference, max_velocity: Decimal },
+
+    #[error("Maximum acceleration must be greater than zero. axis: '{axis}', max_acceleration: {max_acceleration}")]
+    InvalidMaxAcceleration { axis: Reference, max_acceleration: Decimal },
+
+    #[error("Soft limits minimum must be less than the maximum. axis: '{axis}', min: {min}, max: {max}")]
+    InvalidSoftLimits {
+        axis: Reference,
+        min: Decimal,
+        max: Decimal,
+    },
+
+    #[error("Linear axes must have soft limits. axis: '{axis}'")]
+    MissingSoftLimits { axis: Reference },
+
+    #[error("Unknown axis in homing order. axis: '{axis}'")]
+    UnknownHomingAxis { axis: Reference },
+
+    #[error("Axis is homed more than once. axis: '{axis}'")]
+    AxisHomedMoreThanOnce { axis: Reference },
+
+    #[error("Kinematics requires a linear axis. kinematics: {kinematics:?}, axis: '{axis}'")]
+    MissingKinematicsAxis { kinematics: Kinematics, axis: Reference },
+}
+
+/// Checks the axes, their limits, the homing order and the kinematics of the definition.
+///
+/// Returns all the issues, so they can all be corrected at once.
+pub fn validate_machine_definition(definition: &MachineDefinition) -> Vec<MachineDefinitionIssue> {
+    let mut issues = vec![];
+
+    if definition.axes.is_empty() {
+        issues.push(MachineDefinitionIssue::NoAxes);
+    }
+
+    let mut names = BTreeSet::new();
+    for axis in definition.axes.iter() {
+        if !names.insert(&axis.name) {
+            issues.push(MachineDefinitionIssue::DuplicateAxis {
+                axis: axis.name.clone(),
+            });
+        }
+
+        if axis.steps_per_unit <= Decimal::ZERO {
+            issues.push(MachineDefinitionIssue::InvalidStepsPerUnit {
+                axis: axis.name.clone(),
+                steps_per_unit: axis.steps_per_unit,
+            });
+        }
+        if axis.max_velocity <= Decimal::ZERO {
+            issues.push(MachineDefinitionIssue::InvalidMaxVelocity {
+                axis: axis.name.clone(),
+                max_velocity: axis.max_velocity,
+            });
+        }
+        if axis.max_acceleration <= Decimal::ZERO {
+            issues.push(MachineDefinitionIssue::InvalidMaxAcceleration {
+                axis: axis.name.clone(),
+                max_acceleration: axis.max_acceleration,
+            });
+        }
+
+        match (&axis.soft_limits, axis.kind) {
+            (Some(limits), _) if limits.min >= limits.max => {
+                issues.push(MachineDefinitionIssue::InvalidSoftLimits {
+                    axis: axis.name.clone(),
+                    min: limits.min,
+                    max: limits.max,
+                });
+            }
+            (None, AxisKind::Linear) => {
+                issues.push(MachineDefinitionIssue::MissingSoftLimits {
+                    axis: axis.name.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let mut homed = BTreeSet::new();
+    for axis in definition.homing_order.iter().flatten() {
+        if definition.axis(axis).is_none() {
+            issues.push(MachineDefinitionIssue::UnknownHomingAxis {
+                axis: axis.clone(),
+            });
+        } else if !homed.insert(axis) {
+            issues.push(MachineDefinitionIssue::AxisHomedMoreThanOnce {
+                axis: axis.clone(),
+            });
+        }
+    }
+
+    for required_axis in definition.kinematics.required_axes() {
+        let required_axis = Reference::from_raw_str(required_axis);
+        let is_linear = definition
+            .axis(&required_axis)
+            .is_some_and(|axis| axis.kind == AxisKind::Linear);
+
+        if !is_linear {
+            issues.push(MachineDefinitionIssue::MissingKinematicsAxis {
+                kinematics: definition.kinematics,
+                axis: required_axis,
+            });
+        }
+    }
+
+    issues
+}
+
+#[derive(Error, Debug)]
+pub enum MachineDefinitionError {
+    #[error("IO error. cause: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid machine definition file. cause: {0}")]
+    InvalidFile(#[from] serde_json::Error),
+
+    #[error("Invalid machine definition. issues:\n{}", format_issues(.issues))]
+    InvalidDefinition { issues: Vec<MachineDefinitionIssue> },
+}
+
+fn format_issues(issues: &[MachineDefinitionIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| format!("* {}", issue))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns an error, with all the issues, if the definition is invalid, see [`validate_machine_definition`].
+pub fn load_machine_definition(path: &Path) -> Result<MachineDefinition, MachineDefinitionError> {
+    info!("Loading machine definition. path: {:?}", path);
+
+    let file = File::open(path)?;
+    let definition: MachineDefinition = serde_json::from_reader(BufReader::new(file))?;
+
+    trace!("{:?}", definition);
+
+    let issues = validate_machine_definition(&definition);
+    if !issues.is_empty() {
+        return Err(MachineDefinitionError::InvalidDefinition {
+            issues,
+        });
+    }
+
+    Ok(definition)
+}
+
+#[cfg(test)]
+mod machine_definition_tests {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn build_axis(name: &str, kind: AxisKind, soft_limits: Option<SoftLimits>) -> AxisDefinition {
+        AxisDefinition {
+            name: Reference::from_raw_str(name),
+            kind,
+            steps_per_unit: dec!(80),
+            soft_limits,
+            max_velocity: dec!(500),
+            max_acceleration: dec!(3000),
+        }
+    }
+
+    fn build_definition() -> MachineDefinition {
+        let limits = Some(SoftLimits {
+            min: dec!(0),
+            max: dec!(400),
+        });
+
+        MachineDefinition {
+            name: "MACHINE1".to_string(),
+            kinematics: Kinematics::CoreXY,
+            axes: vec![
+                build_axis("X", AxisKind::Linear, limits),
+                build_axis("Y", AxisKind::Linear, limits),
+                build_axis("Z", AxisKind::Linear, limits),
+                build_axis("C", AxisKind::Rotary, None),
+            ],
+            homing_order: vec![vec![Reference::from_raw_str("Z")], vec![
+                Reference::from_raw_str("X"),
+                Reference::from_raw_str("Y"),
+            ]],
+        }
+    }
+
+    #[test]
+    pub fn valid_definition() {
+        // given
+        let definition = build_definition();
+
+        // when
+        let issues = validate_machine_definition(&definition);
+
+        // then
+        assert_eq!(issues, vec![]);
+    }
+
+    #[test]
+    pub fn invalid_definition() {
+        // given
+        let mut definition = build_definition();
+        definition.axes[0].kind = AxisKind::Rotary;
+        definition.axes[1].soft_limits = Some(SoftLimits {
+            min: dec!(10),
+            max: dec!(10),
+        });
+        definition.axes[2].soft_limits = None;
+        definition.axes[3].max_velocity = dec!(0);
+        definition
+            .axes
+            .push(build_axis("C", AxisKind::Rotary, None));
+        definition.homing_order = vec![vec![Reference::from_raw_str("Z"), Reference::from_raw_str("A")], vec![
+            Reference::from_raw_str("Z"),
+        ]];
+
+        // when
+        let issues = validate_machine_definition(&definition);
+
+        // then
+        assert_eq!(issues, vec![
+            MachineDefinitionIssue::InvalidSoftLimits {
+                axis: Reference::from_raw_str("Y"),
+                min: dec!(10),
+                max: dec!(10),
+            },
+            MachineDefinitionIssue::MissingSoftLimits {
+                axis: Reference::from_raw_str("Z"),
+            },
+            MachineDefinitionIssue::InvalidMaxVelocity {
+                axis: Reference::from_raw_str("C"),
+                max_velocity: dec!(0),
+            },
+            MachineDefinitionIssue::DuplicateAxis {
+                axis: Reference::from_raw_str("C"),
+            },
+            MachineDefinitionIssue::UnknownHomingAxis {
+                axis: Reference::from_raw_str("A"),
+            },
+            MachineDefinitionIssue::AxisHomedMoreThanOnce {
+                axis: Reference::from_raw_str("Z"),
+            },
+            MachineDefinitionIssue::MissingKinematicsAxis {
+                kinematics: Kinematics::CoreXY,
+                axis: Reference::from_raw_str("X"),
+            },
+        ]);
+    }
+
+    #[test]
+    pub fn load_definition() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let json_file = temp_dir.child("machine.json");
+        json_file.write_str(indoc! {r#"
+            {
+              "name": "MACHINE1",
+              "axes": [
+                {
+                  "name": "Z", "kind": "Linear", "steps_per_unit": 400, "soft_limits": { "min": -30, "max": 0 },
+                  "max_velocity": 50, "max_acceleration": 500
+                },
+                {
+                  "name": "C", "kind": "Rotary", "steps_per_unit": 8.888,
+                  "max_velocity": 720, "max_acceleration": 5000
+                }
+              ],
+              "homing_order": [["Z"]]
+            }
+        "#})?;
+
+        // when
+        let definition = load_machine_definition(json_file.path())?;
+
+        // then
+        assert_eq!(definition.kinematics, Kinematics::Cartesian);
+        let axis = definition
+            .axis(&Reference::from_raw_str("C"))
+            .unwrap();
+        assert_eq!(axis.kind, AxisKind::Rotary);
+        assert_eq!(axis.steps_per_unit, dec!(8.888));
+        assert_eq!(axis.soft_limits, None);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn invalid_definitions_are_rejected() -> Result<(), Box<dyn std::error::Error>> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let json_file = temp_dir.child("machine.json");
+        json_file.write_str(indoc! {r#"
+            {
+              "name": "MACHINE1",
+              "kinematics": "CoreXY",
+              "axes": []
+            }
+        "#})?;
+
+        // when
+        let result = load_machine_definition(json_file.path());
+
+        // then
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("* The machine has no axes."));
+        assert!(message.contains("* Kinematics requires a linear axis. kinematics: CoreXY, axis: 'X'"));
+
+        Ok(())
+    }
+}
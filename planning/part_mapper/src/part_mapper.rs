@@ -3,7 +3,7 @@
 use assembly::rules::AssemblyRule;
 use eda::placement::EdaPlacement;
 use pnp::load_out::LoadOutItem;
-use pnp::part::Part;
+use pnp::part::{Part, PartAliases};
 use pnp::reference::Reference;
 
 use crate::part_mapping::PartMapping;
@@ -17,6 +17,7 @@ pub fn process<'placement, 'mapping>(
         part_mappings: &'mapping [PartMapping<'mapping>],
         load_out_items: &[LoadOutItem],
         assembly_rules: &[AssemblyRule],
+        part_aliases: &PartAliases,
     ) -> Result<Vec<PlacementPartMappingResult<'placement, 'mapping>>, PartMapperError<'placement, 'mapping>> {
         let mut error_count: usize = 0;
         let mut mappings = vec![];
@@ -40,6 +41,7 @@ pub fn process<'placement, 'mapping>(
                 &mut part_mapping_results,
                 load_out_items,
                 assembly_rules,
+                part_aliases,
             );
 
             let applied_rule_count = part_mapping_results
@@ -81,18 +83,22 @@ pub fn process<'placement, 'mapping>(
     }
 }
 
+/// Assembly rules and load-out items also apply to the aliases of the mapped parts, however, when multiple mappings
+/// match, a load-out item for a mapped part is preferred over a load-out item for an alias.
 fn apply_rules<'mapping>(
     ref_des: &String,
     mapping_results: &mut [PartMappingResult<'mapping>],
     load_out_items: &[LoadOutItem],
     assembly_rules: &[AssemblyRule],
+    part_aliases: &PartAliases,
 ) {
     for mapping_result in mapping_results.iter_mut() {
         let maybe_assembly_rule = assembly_rules.iter().find(|rule| {
             let mapped_part = mapping_result.part_mapping;
             *ref_des == rule.ref_des
-                && mapped_part.part.manufacturer == rule.manufacturer
-                && mapped_part.part.mpn == rule.mpn
+                && part_aliases
+                    .aliases(mapped_part.part)
+                    .any(|part| part.manufacturer == rule.manufacturer && part.mpn == rule.mpn)
         });
 
         if let Some(_rule) = maybe_assembly_rule {
@@ -106,22 +112,34 @@ fn apply_rules<'mapping>(
             mapping_results[0].applied_rule = Some(AppliedMappingRule::AutoSelected);
         }
         2.. => {
-            for mapping_result in mapping_results.iter_mut() {
-                let maybe_load_out_item = load_out_items.iter().find(|item| {
-                    let mapped_part = mapping_result.part_mapping;
-                    (item.mpn == mapped_part.part.mpn) && (item.manufacturer == mapped_part.part.manufacturer)
-                });
-
-                if let Some(load_out_item) = maybe_load_out_item {
-                    mapping_result.applied_rule =
-                        Some(AppliedMappingRule::FoundInLoadOut(load_out_item.reference.clone()));
-                }
+            if !apply_load_out_rules(mapping_results, load_out_items, &PartAliases::default()) {
+                apply_load_out_rules(mapping_results, load_out_items, part_aliases);
             }
         }
         _ => (),
     }
 }
 
+/// Returns `true` if any of the mapped parts were found in the load-out.
+fn apply_load_out_rules(
+    mapping_results: &mut [PartMappingResult<'_>],
+    load_out_items: &[LoadOutItem],
+    part_aliases: &PartAliases,
+) -> bool {
+    let mut found = false;
+    for mapping_result in mapping_results.iter_mut() {
+        let mapped_part = mapping_result.part_mapping;
+        let maybe_load_out_item =
+            pnp::load_out::find_load_out_item_by_part_or_alias(load_out_items, mapped_part.part, part_aliases);
+
+        if let Some(load_out_item) = maybe_load_out_item {
+            mapping_result.applied_rule = Some(AppliedMappingRule::FoundInLoadOut(load_out_item.reference.clone()));
+            found = true;
+        }
+    }
+    found
+}
+
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Debug)]
 pub enum PartMapperError<'placement, 'mapping> {
@@ -181,7 +199,7 @@ mod tests {
     use criteria::{ExactMatchCriterion, GenericCriteria};
     use eda::placement::{EdaPlacement, EdaPlacementField};
     use pnp::load_out::LoadOutItem;
-    use pnp::part::Part;
+    use pnp::part::{Part, PartAliases};
     use pnp::reference::Reference;
 
     use crate::part_mapping::PartMapping;
@@ -281,7 +299,7 @@ fn map_placements() {
         ]);
 
         // when
-        let matched_mappings = PartMapper::process(&eda_placements, &part_mappings, &[], &[]);
+        let matched_mappings = PartMapper::process(&eda_placements, &part_mappings, &[], &[], &PartAliases::default());
 
         // then
         assert_eq!(matched_mappings, expected_results);
@@ -342,7 +360,7 @@ fn map_placements_with_multiple_matching_mappings() {
         }]));
 
         // when
-        let matched_mappings = PartMapper::process(&eda_placements, &part_mappings, &[], &[]);
+        let matched_mappings = PartMapper::process(&eda_placements, &part_mappings, &[], &[], &PartAliases::default());
 
         // then
         assert_eq!(matched_mappings, expected_results);
@@ -372,7 +390,7 @@ fn map_placements_with_no_part_mappings() {
         }]));
 
         // when
-        let matched_mappings = PartMapper::process(&eda_placements, &part_mappings, &[], &[]);
+        let matched_mappings = PartMapper::process(&eda_placements, &part_mappings, &[], &[], &PartAliases::default());
 
         // then
         assert_eq!(matched_mappings, expected_results);
@@ -450,7 +468,13 @@ fn map_placements_with_multiple_matching_mappings_with_one_in_the_load_out() {
         }]);
 
         // when
-        let matched_mappings = PartMapper::process(&eda_placements, &part_mappings, &load_out_items, &[]);
+        let matched_mappings = PartMapper::process(
+            &eda_placements,
+            &part_mappings,
+            &load_out_items,
+            &[],
+            &PartAliases::default(),
+        );
 
         // then
         assert_eq!(matched_mappings, expected_results);
@@ -525,7 +549,13 @@ fn map_placements_with_multiple_matching_mappings_with_an_assembly_rule() {
         }]);
 
         // when
-        let matched_mappings = PartMapper::process(&eda_placements, &part_mappings, &[], assembly_rules);
+        let matched_mappings = PartMapper::process(
+            &eda_placements,
+            &part_mappings,
+            &[],
+            assembly_rules,
+            &PartAliases::default(),
+        );
 
         // then
         assert_eq!(matched_mappings, expected_results);
@@ -601,7 +631,87 @@ fn map_placements_with_multiple_matching_mappings_with_an_assembly_rule_and_load
         }]);
 
         // when
-        let matched_mappings = PartMapper::process(&eda_placements, &part_mappings, &load_out_items, assembly_rules);
+        let matched_mappings = PartMapper::process(
+            &eda_placements,
+            &part_mappings,
+            &load_out_items,
+            assembly_rules,
+            &PartAliases::default(),
+        );
+
+        // then
+        assert_eq!(matched_mappings, expected_results);
+    }
+
+    #[test]
+    fn map_placements_with_multiple_matching_mappings_with_an_alias_in_the_load_out() {
+        // given
+        let eda_placement1 = EdaPlacement {
+            ref_des: "R1".to_string(),
+            fields: vec![EdaPlacementField::new("name".to_string(), "NAME1".to_string())],
+            ..EdaPlacement::default()
+        };
+
+        let eda_placements = vec![eda_placement1];
+
+        // and
+        let part1 = Part::new("MFR1".to_string(), "PART1".to_string());
+        let part2 = Part::new("MFR2".to_string(), "PART2".to_string());
+        let part3 = Part::new("MFR3".to_string(), "PART3".to_string());
+
+        let parts = [part1, part2, part3];
+
+        // and
+        let criteria1 = GenericCriteria {
+            criteria: vec![Box::new(ExactMatchCriterion::new(
+                "name".to_string(),
+                "NAME1".to_string(),
+            ))],
+        };
+        let part_mapping1 = PartMapping::new(&parts[1 - 1], vec![Box::new(criteria1)]);
+        let criteria2 = GenericCriteria {
+            criteria: vec![Box::new(ExactMatchCriterion::new(
+                "name".to_string(),
+                "NAME1".to_string(),
+            ))],
+        };
+        let part_mapping2 = PartMapping::new(&parts[2 - 1], vec![Box::new(criteria2)]);
+
+        let part_mappings = vec![part_mapping1, part_mapping2];
+
+        // and part 3, which is not mapped, is an alias of part 2
+        let mut part_aliases = PartAliases::default();
+        part_aliases.add("GROUP1".to_string(), parts[2 - 1].clone());
+        part_aliases.add("GROUP1".to_string(), parts[3 - 1].clone());
+
+        // and
+        let load_out_items = vec![LoadOutItem::new(
+            Some(Reference::from_raw_str("REFERENCE_1")),
+            "MFR3".to_string(),
+            "PART3".to_string(),
+        )];
+
+        // and
+        let expected_results = Ok(vec![PlacementPartMappingResult {
+            part: Some(&parts[2 - 1]),
+            eda_placement: &eda_placements[0],
+            mapping_result: Ok(vec![
+                PartMappingResult {
+                    part_mapping: &part_mappings[0],
+                    applied_rule: None,
+                },
+                PartMappingResult {
+                    part_mapping: &part_mappings[1],
+                    applied_rule: Some(AppliedMappingRule::FoundInLoadOut(Some(Reference::from_raw_str(
+                        "REFERENCE_1",
+                    )))),
+                },
+            ]),
+        }]);
+
+        // when
+        let matched_mappings =
+            PartMapper::process(&eda_placements, &part_mappings, &load_out_items, &[], &part_aliases);
 
         // then
         assert_eq!(matched_mappings, expected_results);
@@ -0,0 +1,435 @@
+use std::future::Future;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Write;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crux_core::capability::Operation;
+use crux_core::command::RequestBuilder;
+use crux_core::{Command, Request};
+
+/// File IO operations, serviced by the shell, so that the core does not require a filesystem.
+///
+/// Native shells can use [`perform`] to service the operations.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum FileIoOperation {
+    Read {
+        path: PathBuf,
+    },
+    /// Creates the file and any missing parent directories, or replaces its content if it already exists.
+    Write {
+        path: PathBuf,
+        content: Vec<u8>,
+    },
+    /// Appends the content to the file, creating the file if it does not exist.
+    Append {
+        path: PathBuf,
+        content: Vec<u8>,
+    },
+    /// Lists the entries of a directory, non-recursive.
+    List {
+        directory: PathBuf,
+    },
+    /// Removes the file, removing a file that does not exist is not an error.
+    Remove {
+        path: PathBuf,
+    },
+    /// Reads the modification time of the file.
+    Modified {
+        path: PathBuf,
+    },
+    /// Copies the file, creating any missing parent directories, an existing destination is not replaced.
+    Copy {
+        from: PathBuf,
+        to: PathBuf,
+    },
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum FileIoResult {
+    Read {
+        content: Vec<u8>,
+    },
+    Written,
+    Removed,
+    /// Sorted, so that the result does not depend on the order returned by the filesystem.
+    Listed {
+        entries: Vec<PathBuf>,
+    },
+    /// `None` when the filesystem does not provide the modification time.
+    Modified {
+        modified: Option<SystemTime>,
+    },
+    /// `copied` is `false` when the destination already existed.
+    Copied {
+        copied: bool,
+    },
+    Failed {
+        reason: String,
+    },
+}
+
+impl FileIoResult {
+    /// Returns the content of a [`FileIoResult::Read`] result, or an error for any other result.
+    pub fn into_content(self) -> Result<Vec<u8>, std::io::Error> {
+        match self {
+            FileIoResult::Read {
+                content,
+            } => Ok(content),
+            other => Err(other.into_error()),
+        }
+    }
+
+    /// Returns an error for any result other than [`FileIoResult::Written`].
+    pub fn into_written(self) -> Result<(), std::io::Error> {
+        match self {
+            FileIoResult::Written => Ok(()),
+            other => Err(other.into_error()),
+        }
+    }
+
+    /// Returns the entries of a [`FileIoResult::Listed`] result, or an error for any other result.
+    pub fn into_entries(self) -> Result<Vec<PathBuf>, std::io::Error> {
+        match self {
+            FileIoResult::Listed {
+                entries,
+            } => Ok(entries),
+            other => Err(other.into_error()),
+        }
+    }
+
+    /// Returns an error for any result other than [`FileIoResult::Removed`].
+    pub fn into_removed(self) -> Result<(), std::io::Error> {
+        match self {
+            FileIoResult::Removed => Ok(()),
+            other => Err(other.into_error()),
+        }
+    }
+
+    /// Returns the modification time of a [`FileIoResult::Modified`] result, or an error for any other result.
+    pub fn into_modified(self) -> Result<Option<SystemTime>, std::io::Error> {
+        match self {
+            FileIoResult::Modified {
+                modified,
+            } => Ok(modified),
+            other => Err(other.into_error()),
+        }
+    }
+
+    /// Returns whether the file was copied for a [`FileIoResult::Copied`] result, or an error for any other result.
+    pub fn into_copied(self) -> Result<bool, std::io::Error> {
+        match self {
+            FileIoResult::Copied {
+                copied,
+            } => Ok(copied),
+            other => Err(other.into_error()),
+        }
+    }
+
+    fn into_error(self) -> std::io::Error {
+        match self {
+            FileIoResult::Failed {
+                reason,
+            } => std::io::Error::other(reason),
+            other => std::io::Error::other(format!("Unexpected file IO result. result: {:?}", other)),
+        }
+    }
+}
+
+impl Operation for FileIoOperation {
+    type Output = FileIoResult;
+}
+
+pub fn request_builder<Effect, Event>(
+    operation: FileIoOperation,
+) -> RequestBuilder<Effect, Event, impl Future<Output = FileIoResult>>
+where
+    Effect: From<Request<FileIoOperation>> + Send + 'static,
+    Event: Send + 'static,
+{
+    Command::request_from_shell(operation)
+}
+
+/// Request a file IO operation, the result is sent back to the core using the event built by `make_event`.
+pub fn request<Effect, Event, F>(operation: FileIoOperation, make_event: F) -> Command<Effect, Event>
+where
+    Effect: From<Request<FileIoOperation>> + Send + 'static,
+    Event: Send + 'static,
+    F: FnOnce(FileIoResult) -> Event + Send + 'static,
+{
+    request_builder(operation).then_send(make_event)
+}
+
+/// Services the operation using the local filesystem.
+///
+/// Not available on wasm32, where there is no filesystem, browser-based shells must service the operations themselves.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn perform(operation: &FileIoOperation) -> FileIoResult {
+    let result = match operation {
+        FileIoOperation::Read {
+            path,
+        } => std::fs::read(path).map(|content| FileIoResult::Read {
+            content,
+        }),
+        FileIoOperation::Write {
+            path,
+            content,
+        } => create_parent_directories(path)
+            .and_then(|_| std::fs::write(path, content))
+            .map(|_| FileIoResult::Written),
+        FileIoOperation::Append {
+            path,
+            content,
+        } => std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(content))
+            .map(|_| FileIoResult::Written),
+        FileIoOperation::List {
+            directory,
+        } => std::fs::read_dir(directory).and_then(|entries| {
+            let mut entries = entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            entries.sort();
+
+            Ok(FileIoResult::Listed {
+                entries,
+            })
+        }),
+        FileIoOperation::Remove {
+            path,
+        } => match std::fs::remove_file(path) {
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(FileIoResult::Removed),
+            result => result.map(|_| FileIoResult::Removed),
+        },
+        FileIoOperation::Modified {
+            path,
+        } => std::fs::metadata(path).map(|metadata| FileIoResult::Modified {
+            modified: metadata.modified().ok(),
+        }),
+        FileIoOperation::Copy {
+            from,
+            to,
+        } => match to.exists() {
+            true => Ok(FileIoResult::Copied {
+                copied: false,
+            }),
+            false => create_parent_directories(to)
+                .and_then(|_| std::fs::copy(from, to))
+                .map(|_| FileIoResult::Copied {
+                    copied: true,
+                }),
+        },
+    };
+
+    result.unwrap_or_else(|error| FileIoResult::Failed {
+        reason: format!("{:?}, operation: {:?}", error, OperationSummary(operation)),
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn create_parent_directories(path: &Path) -> std::io::Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => std::fs::create_dir_all(parent),
+        _ => Ok(()),
+    }
+}
+
+/// Omits the content of write operations.
+#[cfg(not(target_arch = "wasm32"))]
+struct OperationSummary<'a>(&'a FileIoOperation);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl std::fmt::Debug for OperationSummary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            FileIoOperation::Read {
+                path,
+            } => write!(f, "Read {{ path: {:?} }}", path),
+            FileIoOperation::Write {
+                path,
+                content,
+            } => write!(f, "Write {{ path: {:?}, length: {} }}", path, content.len()),
+            FileIoOperation::Append {
+                path,
+                content,
+            } => write!(f, "Append {{ path: {:?}, length: {} }}", path, content.len()),
+            FileIoOperation::List {
+                directory,
+            } => write!(f, "List {{ directory: {:?} }}", directory),
+            FileIoOperation::Remove {
+                path,
+            } => write!(f, "Remove {{ path: {:?} }}", path),
+            FileIoOperation::Modified {
+                path,
+            } => write!(f, "Modified {{ path: {:?} }}", path),
+            FileIoOperation::Copy {
+                from,
+                to,
+            } => write!(f, "Copy {{ from: {:?}, to: {:?} }}", from, to),
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod file_io_tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn write_read_and_list() {
+        // given
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("file.json");
+
+        // when
+        let write_result = perform(&FileIoOperation::Write {
+            path: path.clone(),
+            content: b"{}".to_vec(),
+        });
+        let read_result = perform(&FileIoOperation::Read {
+            path: path.clone(),
+        });
+        let list_result = perform(&FileIoOperation::List {
+            directory: temp_dir.path().to_path_buf(),
+        });
+
+        // then
+        assert_eq!(write_result, FileIoResult::Written);
+        assert_eq!(read_result.into_content().unwrap(), b"{}".to_vec());
+        assert_eq!(list_result, FileIoResult::Listed {
+            entries: vec![path],
+        });
+    }
+
+    #[test]
+    fn append() {
+        // given
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("file.jsonl");
+
+        // when
+        let first_result = perform(&FileIoOperation::Append {
+            path: path.clone(),
+            content: b"1\n".to_vec(),
+        });
+        let second_result = perform(&FileIoOperation::Append {
+            path: path.clone(),
+            content: b"2\n".to_vec(),
+        });
+
+        // then the file is created by the first append
+        assert_eq!(first_result, FileIoResult::Written);
+        assert_eq!(second_result, FileIoResult::Written);
+        assert_eq!(std::fs::read(&path).unwrap(), b"1\n2\n".to_vec());
+    }
+
+    #[test]
+    fn remove() {
+        // given
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("file.json");
+        std::fs::write(&path, b"{}").unwrap();
+
+        // when
+        let remove_result = perform(&FileIoOperation::Remove {
+            path: path.clone(),
+        });
+        let remove_again_result = perform(&FileIoOperation::Remove {
+            path: path.clone(),
+        });
+
+        // then
+        assert_eq!(remove_result, FileIoResult::Removed);
+        assert!(!path.exists());
+
+        // and removing a file that does not exist is not an error
+        assert_eq!(remove_again_result, FileIoResult::Removed);
+    }
+
+    #[test]
+    fn modified() {
+        // given
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("file.json");
+        std::fs::write(&path, b"{}").unwrap();
+
+        // when
+        let result = perform(&FileIoOperation::Modified {
+            path: path.clone(),
+        });
+
+        // then
+        let expected_modified = std::fs::metadata(&path)
+            .unwrap()
+            .modified()
+            .ok();
+        assert_eq!(result.into_modified().unwrap(), expected_modified);
+    }
+
+    #[test]
+    fn write_creates_the_parent_directories() {
+        // given
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("pcbs/1/file.json");
+
+        // when
+        let result = perform(&FileIoOperation::Write {
+            path: path.clone(),
+            content: b"{}".to_vec(),
+        });
+
+        // then
+        assert_eq!(result, FileIoResult::Written);
+        assert_eq!(std::fs::read(&path).unwrap(), b"{}".to_vec());
+    }
+
+    #[test]
+    fn copy() {
+        // given
+        let temp_dir = tempdir().unwrap();
+        let from = temp_dir.path().join("template.csv");
+        std::fs::write(&from, b"template").unwrap();
+        let to = temp_dir
+            .path()
+            .join("load_outs/load_out.csv");
+
+        // when
+        let copy_result = perform(&FileIoOperation::Copy {
+            from: from.clone(),
+            to: to.clone(),
+        });
+        std::fs::write(&to, b"changed").unwrap();
+        let copy_again_result = perform(&FileIoOperation::Copy {
+            from: from.clone(),
+            to: to.clone(),
+        });
+
+        // then
+        assert!(copy_result.into_copied().unwrap());
+
+        // and an existing destination is not replaced
+        assert!(!copy_again_result.into_copied().unwrap());
+        assert_eq!(std::fs::read(&to).unwrap(), b"changed".to_vec());
+    }
+
+    #[test]
+    fn missing_file() {
+        // given
+        let temp_dir = tempdir().unwrap();
+
+        // when
+        let result = perform(&FileIoOperation::Read {
+            path: temp_dir.path().join("missing.json"),
+        });
+
+        // then
+        assert!(matches!(result, FileIoResult::Failed { .. }));
+        assert!(result.into_content().is_err());
+    }
+}
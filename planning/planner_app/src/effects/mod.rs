@@ -1,2 +1,5 @@
+pub mod file_io;
 pub mod pcb_view_renderer;
 pub mod project_view_renderer;
+pub mod view_invalidation;
+pub mod vision;
@@ -0,0 +1,39 @@
+use std::future::Future;
+
+use crux_core::capability::Operation;
+use crux_core::command::NotificationBuilder;
+use crux_core::{Command, Request};
+
+use crate::ProjectViewRequest;
+
+/// Notifies the shell that a project view it may be showing is out-of-date, see [`crate::view_dependencies`].
+///
+/// Shells should request the view again if it is shown, other views can be ignored.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum ViewInvalidationOperation {
+    ViewInvalidated { view: ProjectViewRequest },
+}
+
+impl Operation for ViewInvalidationOperation {
+    type Output = ();
+}
+
+pub fn invalidated_builder<Effect, Event>(
+    view: ProjectViewRequest,
+) -> NotificationBuilder<Effect, Event, impl Future<Output = ()>>
+where
+    Effect: From<Request<ViewInvalidationOperation>> + Send + 'static,
+    Event: Send + 'static,
+{
+    Command::notify_shell(ViewInvalidationOperation::ViewInvalidated {
+        view,
+    })
+}
+
+pub fn invalidated<Effect, Event>(view: ProjectViewRequest) -> Command<Effect, Event>
+where
+    Effect: From<Request<ViewInvalidationOperation>> + Send + 'static,
+    Event: Send + 'static,
+{
+    invalidated_builder(view).into()
+}
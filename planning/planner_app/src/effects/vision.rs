@@ -0,0 +1,77 @@
+use std::future::Future;
+
+use crux_core::capability::Operation;
+use crux_core::command::RequestBuilder;
+use crux_core::{Command, Request};
+use nalgebra::Vector2;
+use planning::phase::PhaseReference;
+use pnp::object_path::ObjectPath;
+
+/// Vision operations, serviced by shells that own a camera.
+///
+/// Shells without a camera should respond with [`VisionResult::Unavailable`].
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum VisionOperation {
+    CaptureFrame,
+    /// Locate a fiducial near its expected position, in machine coordinates, millimeters.
+    ///
+    /// The location is recorded in the phase's operation history.
+    LocateFiducial {
+        phase: PhaseReference,
+        expected_position: Vector2<f64>,
+    },
+    /// Inspect a placement after it has been placed, the result is recorded in the phase's operation history.
+    InspectPlacement {
+        phase: PhaseReference,
+        object_path: ObjectPath,
+    },
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum VisionResult {
+    /// The shell owns the frame data, only a reference to it is returned to the core.
+    Frame {
+        reference: String,
+        width: u32,
+        height: u32,
+    },
+    Located(VisionMeasurement),
+    Failed {
+        reason: String,
+    },
+    Unavailable,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct VisionMeasurement {
+    /// From the expected position, millimeters.
+    pub offset: Vector2<f64>,
+    /// From the expected rotation, degrees, positive is anti-clockwise.
+    pub rotation: f64,
+    /// 0.0 to 1.0
+    pub confidence: f64,
+}
+
+impl Operation for VisionOperation {
+    type Output = VisionResult;
+}
+
+pub fn request_builder<Effect, Event>(
+    operation: VisionOperation,
+) -> RequestBuilder<Effect, Event, impl Future<Output = VisionResult>>
+where
+    Effect: From<Request<VisionOperation>> + Send + 'static,
+    Event: Send + 'static,
+{
+    Command::request_from_shell(operation)
+}
+
+/// Request a vision operation, the result is sent back to the core using the event built by `make_event`.
+pub fn request<Effect, Event, F>(operation: VisionOperation, make_event: F) -> Command<Effect, Event>
+where
+    Effect: From<Request<VisionOperation>> + Send + 'static,
+    Event: Send + 'static,
+    F: FnOnce(VisionResult) -> Event + Send + 'static,
+{
+    request_builder(operation).then_send(make_event)
+}
@@ -0,0 +1,457 @@
+//! A serializable view of the errors returned by the core, see [`PlannerOperationViewModel::error`].
+//!
+//! Each variant mirrors an [`AppError`] variant, and carries the references and paths that the error is about, so
+//! that shells can localize the message, see [`AppErrorView::i18n_key`] and [`AppErrorView::args`], and offer actions
+//! for the affected objects, e.g. navigating to a phase.
+//!
+//! The causes of wrapped errors are not localized, they are included as an argument.
+//!
+//! [`PlannerOperationViewModel::error`]: crate::PlannerOperationViewModel::error
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use args::Arg;
+use planning::archive::ArchiveError;
+use planning::phase::{PhaseError, PhaseReference};
+use planning::process::{ProcessError, ProcessReference};
+use planning::project::{InspectionError, PartStateError};
+use planning::template::TemplateError;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use thiserror::Error;
+
+use crate::selection::SelectionId;
+use crate::AppError;
+
+#[derive(Error, serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub enum AppErrorView {
+    #[error("Operation requires a project")]
+    OperationRequiresProject,
+    #[error("Operation error, cause: {cause}")]
+    OperationError { cause: String },
+    #[error("Phase error. cause: {cause}")]
+    PhaseError {
+        cause: String,
+        phase: Option<PhaseReference>,
+        process: Option<ProcessReference>,
+    },
+    #[error("Project error, cause: {cause}")]
+    ProjectError { cause: String },
+    #[error("Template error. cause: {cause}")]
+    TemplateError { cause: String, path: Option<PathBuf> },
+    #[error("Archive error. cause: {cause}")]
+    ArchiveError { cause: String, path: Option<PathBuf> },
+    #[error("Process error. cause: {cause}")]
+    ProcessError {
+        cause: String,
+        process: Option<ProcessReference>,
+    },
+    #[error("Part error. cause: {cause}")]
+    PartError { cause: String, part: Part },
+    #[error("Source error. cause: {cause}")]
+    SourceError { cause: String },
+    #[error("Loadout error. cause: {cause}")]
+    LoadoutError { cause: String },
+    #[error("PCB error. cause: {cause}")]
+    PcbOperationError { cause: String },
+    #[error("IO error. cause: {cause}")]
+    IoError { cause: String },
+    #[error("Inspection error. cause: {cause}")]
+    InspectionError {
+        cause: String,
+        object_path: Option<ObjectPath>,
+        phase: Option<PhaseReference>,
+    },
+    #[error("Cancelled. cause: {cause}")]
+    Cancelled { cause: String },
+    #[error("Vision error. cause: {cause}")]
+    VisionError { cause: String },
+    #[error("Serial number error. cause: {cause}")]
+    SerialNumberError { cause: String },
+
+    #[error("Operation requires a placement session")]
+    PlacementSessionRequired,
+    #[error("Placement session has no more pending placements")]
+    PlacementSessionComplete,
+
+    #[error("Unknown phase reference. reference: {phase}")]
+    UnknownPhaseReference { phase: PhaseReference },
+    #[error("Unknown process reference. reference: {process}")]
+    UnknownProcessReference { process: ProcessReference },
+    #[error("Unknown placement selection. selection: {selection}")]
+    UnknownPlacementSelection { selection: SelectionId },
+    #[error("Unknown placement. object_path: {object_path}")]
+    UnknownPlacement { object_path: ObjectPath },
+}
+
+impl AppErrorView {
+    /// The key of the localized message, the arguments of the message are [`Self::args`].
+    pub fn i18n_key(&self) -> &'static str {
+        match self {
+            AppErrorView::OperationRequiresProject => "app-error-operation-requires-project",
+            AppErrorView::OperationError {
+                ..
+            } => "app-error-operation",
+            AppErrorView::PhaseError {
+                ..
+            } => "app-error-phase",
+            AppErrorView::ProjectError {
+                ..
+            } => "app-error-project",
+            AppErrorView::TemplateError {
+                ..
+            } => "app-error-template",
+            AppErrorView::ArchiveError {
+                ..
+            } => "app-error-archive",
+            AppErrorView::ProcessError {
+                ..
+            } => "app-error-process",
+            AppErrorView::PartError {
+                ..
+            } => "app-error-part",
+            AppErrorView::SourceError {
+                ..
+            } => "app-error-source",
+            AppErrorView::LoadoutError {
+                ..
+            } => "app-error-loadout",
+            AppErrorView::PcbOperationError {
+                ..
+            } => "app-error-pcb-operation",
+            AppErrorView::IoError {
+                ..
+            } => "app-error-io",
+            AppErrorView::InspectionError {
+                ..
+            } => "app-error-inspection",
+            AppErrorView::Cancelled {
+                ..
+            } => "app-error-cancelled",
+            AppErrorView::VisionError {
+                ..
+            } => "app-error-vision",
+            AppErrorView::SerialNumberError {
+                ..
+            } => "app-error-serial-number",
+            AppErrorView::PlacementSessionRequired => "app-error-placement-session-required",
+            AppErrorView::PlacementSessionComplete => "app-error-placement-session-complete",
+            AppErrorView::UnknownPhaseReference {
+                ..
+            } => "app-error-unknown-phase-reference",
+            AppErrorView::UnknownProcessReference {
+                ..
+            } => "app-error-unknown-process-reference",
+            AppErrorView::UnknownPlacementSelection {
+                ..
+            } => "app-error-unknown-placement-selection",
+            AppErrorView::UnknownPlacement {
+                ..
+            } => "app-error-unknown-placement",
+        }
+    }
+
+    /// Arguments that are `None` are omitted, e.g. a phase error that is not about a specific phase has no `phase`
+    /// argument.
+    pub fn args(&self) -> HashMap<String, Arg> {
+        let mut args = HashMap::new();
+        let mut insert = |name: &str, value: Option<String>| {
+            if let Some(value) = value {
+                args.insert(name.to_string(), Arg::String(value));
+            }
+        };
+
+        match self {
+            AppErrorView::OperationRequiresProject
+            | AppErrorView::PlacementSessionRequired
+            | AppErrorView::PlacementSessionComplete => {}
+            AppErrorView::OperationError {
+                cause,
+            }
+            | AppErrorView::ProjectError {
+                cause,
+            }
+            | AppErrorView::SourceError {
+                cause,
+            }
+            | AppErrorView::LoadoutError {
+                cause,
+            }
+            | AppErrorView::PcbOperationError {
+                cause,
+            }
+            | AppErrorView::IoError {
+                cause,
+            }
+            | AppErrorView::Cancelled {
+                cause,
+            }
+            | AppErrorView::VisionError {
+                cause,
+            }
+            | AppErrorView::SerialNumberError {
+                cause,
+            } => {
+                insert("cause", Some(cause.clone()));
+            }
+            AppErrorView::PhaseError {
+                cause,
+                phase,
+                process,
+            } => {
+                insert("cause", Some(cause.clone()));
+                insert("phase", phase.as_ref().map(ToString::to_string));
+                insert(
+                    "process",
+                    process
+                        .as_ref()
+                        .map(ToString::to_string),
+                );
+            }
+            AppErrorView::TemplateError {
+                cause,
+                path,
+            }
+            | AppErrorView::ArchiveError {
+                cause,
+                path,
+            } => {
+                insert("cause", Some(cause.clone()));
+                insert(
+                    "path",
+                    path.as_ref()
+                        .map(|path| path.display().to_string()),
+                );
+            }
+            AppErrorView::ProcessError {
+                cause,
+                process,
+            } => {
+                insert("cause", Some(cause.clone()));
+                insert(
+                    "process",
+                    process
+                        .as_ref()
+                        .map(ToString::to_string),
+                );
+            }
+            AppErrorView::PartError {
+                cause,
+                part,
+            } => {
+                insert("cause", Some(cause.clone()));
+                insert("manufacturer", Some(part.manufacturer.clone()));
+                insert("mpn", Some(part.mpn.clone()));
+            }
+            AppErrorView::InspectionError {
+                cause,
+                object_path,
+                phase,
+            } => {
+                insert("cause", Some(cause.clone()));
+                insert(
+                    "object_path",
+                    object_path
+                        .as_ref()
+                        .map(ToString::to_string),
+                );
+                insert("phase", phase.as_ref().map(ToString::to_string));
+            }
+            AppErrorView::UnknownPhaseReference {
+                phase,
+            } => {
+                insert("phase", Some(phase.to_string()));
+            }
+            AppErrorView::UnknownProcessReference {
+                process,
+            } => {
+                insert("process", Some(process.to_string()));
+            }
+            AppErrorView::UnknownPlacementSelection {
+                selection,
+            } => {
+                insert("selection", Some(selection.to_string()));
+            }
+            AppErrorView::UnknownPlacement {
+                object_path,
+            } => {
+                insert("object_path", Some(object_path.to_string()));
+            }
+        }
+
+        args
+    }
+}
+
+impl From<&AppError> for AppErrorView {
+    fn from(error: &AppError) -> Self {
+        match error {
+            AppError::OperationRequiresProject => AppErrorView::OperationRequiresProject,
+            AppError::OperationError(cause) => AppErrorView::OperationError {
+                // alternate format, to include the context of the cause
+                cause: format!("{:#}", cause),
+            },
+            AppError::PhaseError(cause) => {
+                let (phase, process) = match cause {
+                    PhaseError::UnknownPhase(phase)
+                    | PhaseError::InvalidOperationForPhase(phase, ..)
+                    | PhaseError::InvalidTaskForOperation(phase, ..)
+                    | PhaseError::PhaseInUse(phase)
+                    | PhaseError::DuplicatePhase(phase) => (Some(phase.clone()), None),
+                    PhaseError::UnknownProcess(process) => (None, Some(process.clone())),
+                };
+                AppErrorView::PhaseError {
+                    cause: cause.to_string(),
+                    phase,
+                    process,
+                }
+            }
+            AppError::ProjectError(cause) => AppErrorView::ProjectError {
+                cause: cause.to_string(),
+            },
+            AppError::TemplateError(cause) => AppErrorView::TemplateError {
+                cause: cause.to_string(),
+                path: match cause {
+                    TemplateError::UnableToLoad {
+                        path, ..
+                    } => Some(path.clone()),
+                    TemplateError::PhaseError(_) => None,
+                },
+            },
+            AppError::ArchiveError(cause) => AppErrorView::ArchiveError {
+                cause: cause.to_string(),
+                path: match cause {
+                    ArchiveError::InvalidPath(path) => Some(path.clone()),
+                    ArchiveError::IoError(_) | ArchiveError::ZipError(_) | ArchiveError::PcbNotLoaded(_) => None,
+                },
+            },
+            AppError::ProcessError(cause) => AppErrorView::ProcessError {
+                cause: cause.to_string(),
+                process: match cause {
+                    ProcessError::ProcessInUse {
+                        process_reference,
+                    }
+                    | ProcessError::ProcessInProgress {
+                        process_reference,
+                    }
+                    | ProcessError::DuplicateProcessReference {
+                        process_reference,
+                    }
+                    | ProcessError::UndefinedTask {
+                        process_reference, ..
+                    }
+                    | ProcessError::CoreTaskRedefined {
+                        process_reference, ..
+                    } => Some(process_reference.clone()),
+                    ProcessError::UndefinedProcessError {
+                        ..
+                    }
+                    | ProcessError::UnknownPreset {
+                        ..
+                    } => None,
+                },
+            },
+            AppError::PartError(cause) => {
+                let PartStateError::NoPartStateFound {
+                    part,
+                } = cause;
+                AppErrorView::PartError {
+                    cause: cause.to_string(),
+                    part: part.clone(),
+                }
+            }
+            AppError::SourceError(cause) => AppErrorView::SourceError {
+                cause: cause.to_string(),
+            },
+            AppError::LoadoutError(cause) => AppErrorView::LoadoutError {
+                cause: cause.to_string(),
+            },
+            AppError::PcbOperationError(cause) => AppErrorView::PcbOperationError {
+                cause: cause.to_string(),
+            },
+            AppError::IoError(cause) => AppErrorView::IoError {
+                cause: cause.to_string(),
+            },
+            AppError::InspectionError(cause) => {
+                let (object_path, phase) = match cause {
+                    InspectionError::UnknownPlacement(object_path) => (Some(object_path.clone()), None),
+                    InspectionError::PlacementNotInPhase {
+                        object_path,
+                        phase,
+                    } => (Some(object_path.clone()), Some(phase.clone())),
+                    InspectionError::NoPlacementTask(phase) => (None, Some(phase.clone())),
+                    InspectionError::HistoryError(_) => (None, None),
+                };
+                AppErrorView::InspectionError {
+                    cause: cause.to_string(),
+                    object_path,
+                    phase,
+                }
+            }
+            AppError::Cancelled(cause) => AppErrorView::Cancelled {
+                cause: cause.to_string(),
+            },
+            AppError::VisionError(cause) => AppErrorView::VisionError {
+                cause: cause.clone(),
+            },
+            AppError::SerialNumberError(cause) => AppErrorView::SerialNumberError {
+                cause: cause.to_string(),
+            },
+            AppError::PlacementSessionRequired => AppErrorView::PlacementSessionRequired,
+            AppError::PlacementSessionComplete => AppErrorView::PlacementSessionComplete,
+            AppError::UnknownPhaseReference(phase) => AppErrorView::UnknownPhaseReference {
+                phase: phase.clone(),
+            },
+            AppError::UnknownProcessReference(process) => AppErrorView::UnknownProcessReference {
+                process: process.clone(),
+            },
+            AppError::UnknownPlacementSelection(selection) => AppErrorView::UnknownPlacementSelection {
+                selection: *selection,
+            },
+            AppError::UnknownPlacement(object_path) => AppErrorView::UnknownPlacement {
+                object_path: object_path.clone(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod error_view_tests {
+    use std::str::FromStr;
+
+    use pnp::reference::Reference;
+
+    use super::*;
+
+    #[test]
+    pub fn affected_references_are_arguments() {
+        // given
+        let error = AppError::InspectionError(InspectionError::PlacementNotInPhase {
+            object_path: ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap(),
+            phase: Reference::from_raw_str("top_1"),
+        });
+
+        // when
+        let error_view = AppErrorView::from(&error);
+
+        // then
+        assert_eq!(error_view.i18n_key(), "app-error-inspection");
+        assert_eq!(
+            error_view.args(),
+            HashMap::from([
+                (
+                    "cause".to_string(),
+                    Arg::String(
+                        "Placement not in phase. object_path: pcb=1::unit=1::ref_des=R1, phase: top_1".to_string()
+                    )
+                ),
+                (
+                    "object_path".to_string(),
+                    Arg::String("pcb=1::unit=1::ref_des=R1".to_string())
+                ),
+                ("phase".to_string(), Arg::String("top_1".to_string())),
+            ])
+        );
+    }
+}
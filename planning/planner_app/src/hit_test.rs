@@ -0,0 +1,139 @@
+//! Hit testing, finding the placements near a panel position, for cross-probing from a PCB view to the placements, see
+//! [`ProjectViewRequest::HitTest`].
+//!
+//! Positions are in millimeters, relative to the panel origin, as viewed from the top, like the placement positions.
+//!
+//! [`ProjectViewRequest::HitTest`]: crate::ProjectViewRequest::HitTest
+
+use nalgebra::Vector2;
+use planning::phase::PhaseReference;
+use planning::placement::ProjectPlacementStatus;
+use planning::project::Project;
+use pnp::object_path::ObjectPath;
+use pnp::pcb::PcbSide;
+use pnp::placement::RefDes;
+use rust_decimal::prelude::ToPrimitive;
+
+/// The maximum distance, in millimeters, between the position and a placement.
+pub const HIT_TEST_TOLERANCE: f64 = 2.0;
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct HitTestQuery {
+    /// The index of the pcb in the project.
+    pub pcb: u16,
+    pub position: Vector2<f64>,
+    pub pcb_side: PcbSide,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct HitTestResults {
+    /// The index of the pcb in the project.
+    pub pcb: u16,
+    pub position: Vector2<f64>,
+    pub pcb_side: PcbSide,
+    /// Ordered by distance, nearest first.
+    pub hits: Vec<HitTestItem>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct HitTestItem {
+    pub object_path: ObjectPath,
+    pub ref_des: RefDes,
+    pub phase: Option<PhaseReference>,
+    /// Millimeters.
+    pub distance: f64,
+}
+
+/// Only used placements on the given side of the pcb are hit.
+pub fn hit_test(project: &Project, pcb_index: u16, position: Vector2<f64>, pcb_side: PcbSide) -> HitTestResults {
+    let mut hits = project
+        .placements
+        .iter()
+        .filter(|(object_path, state)| {
+            // reminder: the pcb instance is 1-based in the object path
+            state.project_status == ProjectPlacementStatus::Used
+                && state.placement.pcb_side == pcb_side
+                && object_path
+                    .pcb_instance()
+                    .is_ok_and(|pcb_instance| pcb_instance == pcb_index + 1)
+        })
+        .filter_map(|(object_path, state)| {
+            let placement_position = Vector2::new(state.unit_position.x.to_f64()?, state.unit_position.y.to_f64()?);
+            let distance = (placement_position - position).norm();
+
+            (distance <= HIT_TEST_TOLERANCE).then(|| HitTestItem {
+                object_path: object_path.clone(),
+                ref_des: state.placement.ref_des.clone(),
+                phase: state.phase.clone(),
+                distance,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+    HitTestResults {
+        pcb: pcb_index,
+        position,
+        pcb_side,
+        hits,
+    }
+}
+
+#[cfg(test)]
+mod hit_test_tests {
+    use std::str::FromStr;
+
+    use planning::pcb::UnitPlacementPosition;
+    use planning::placement::PlacementState;
+    use pnp::placement::Placement;
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    fn add_placement(project: &mut Project, pcb: u16, ref_des: &str, pcb_side: PcbSide, x: i64, y: i64) {
+        let object_path = ObjectPath::from_str(&format!("pcb={}::unit=1::ref_des={}", pcb, ref_des)).unwrap();
+        project
+            .placements
+            .insert(object_path.clone(), PlacementState {
+                placement: Placement {
+                    ref_des: RefDes::from(ref_des),
+                    pcb_side,
+                    ..Placement::default()
+                },
+                unit_position: UnitPlacementPosition {
+                    x: Decimal::new(x, 0),
+                    y: Decimal::new(y, 0),
+                    rotation: Decimal::ZERO,
+                },
+                project_status: ProjectPlacementStatus::Used,
+                ..PlacementState::default()
+            });
+    }
+
+    #[test]
+    pub fn nearest_placements_are_hit_first() {
+        // given
+        let mut project = Project::default();
+        add_placement(&mut project, 1, "R1", PcbSide::Top, 10, 10);
+        add_placement(&mut project, 1, "R2", PcbSide::Top, 11, 10);
+        // and a placement that is too far away
+        add_placement(&mut project, 1, "R3", PcbSide::Top, 20, 10);
+        // and a placement on the other side
+        add_placement(&mut project, 1, "R4", PcbSide::Bottom, 11, 10);
+        // and a placement on another pcb
+        add_placement(&mut project, 2, "R5", PcbSide::Top, 11, 10);
+
+        // when
+        let results = hit_test(&project, 0, Vector2::new(11.5, 10.0), PcbSide::Top);
+
+        // then
+        let ref_des_list = results
+            .hits
+            .iter()
+            .map(|hit| hit.ref_des.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ref_des_list, vec!["R2", "R1"]);
+        assert_eq!(results.hits[0].distance, 0.5);
+    }
+}
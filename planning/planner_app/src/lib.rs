@@ -1,6 +1,8 @@
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::BitOrAssign;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::SystemTime;
 
@@ -10,18 +12,35 @@
 use crux_core::render::RenderOperation;
 pub use crux_core::Core;
 use crux_core::{render, App, Command};
+use eda_units::eda_units::angle::AngleUnit;
+use eda_units::eda_units::dimension_unit::{DimensionUnitPoint2, DimensionUnitPoint2Ext};
+pub use eda_units::eda_units::unit_system::UnitSystem;
+pub use gerber::layers::{self as gerber_layers, LayerPreset};
+use gerber::outline as gerber_outline;
+pub use gerber::outline::{Contour, Outline};
 use gerber::GerberFile;
 pub use gerber::{GerberFileFunction, GerberFileFunctionDiscriminants, PcbSideRequirement};
 use indexmap::IndexSet;
+pub use machine_definition::{self, AxisDefinition, AxisKind, Kinematics, MachineDefinition, SoftLimits};
 use nalgebra::Vector2;
 use package_mapper::package_mapping::PackageMapping;
+use petgraph::graph::NodeIndex;
 use petgraph::Graph;
-pub use planning::actions::{AddOrRemoveAction, SetOrClearAction};
+pub use planning::actions::{AddOrRemoveAction, DeleteStrategy, SetOrClearAction};
+use planning::archive::{self, ArchiveError, ImportedArchive};
+use planning::build_run;
+pub use planning::build_run::{BuildRunInstanceNumber, BuildRunSummary};
+use planning::capacity;
 pub use planning::design::{DesignIndex, DesignName, DesignNumber, DesignVariant};
-pub use planning::file::{FileReference, FileReferenceError};
+pub use planning::file::{FileFormat, FileReference, FileReferenceError};
 pub use planning::library::LibraryConfig;
-use planning::pcb::{Pcb, PcbError};
+use planning::nozzle::NozzleAssignments;
+pub use planning::nozzle::{NozzleConfiguration, NozzleDefinition, NozzlePackageRule, NozzleReference};
+use planning::operation_history::{FiducialLocationHistoryKind, PlacementInspectionHistoryKind};
+pub use planning::part::MoistureEvent;
+use planning::pcb::{Pcb, PcbError, UnitPlacementPosition};
 pub use planning::pcb::{PcbAssemblyFlip, PcbAssemblyOrientation};
+pub use planning::phase::AssemblyWindow;
 pub use planning::phase::PhaseReference;
 pub use planning::phase::PhaseStatus;
 use planning::phase::{Phase, PhaseError, PhaseState};
@@ -35,40 +54,97 @@
 pub use planning::process::TaskReference;
 pub use planning::process::TaskStatus;
 pub use planning::process::{
-    OperationDefinition, OperationReference, OperationStatus, ProcessDefinition, ProcessRuleReference, TaskAction,
+    CustomTaskDefinition, OperationDefinition, OperationReference, OperationStatus, ProcessDefinition,
+    ProcessRuleReference, TaskAction, TaskParameterDefinition, TaskParameterKind, TaskParameterValue,
 };
+pub use planning::project::{Artifact, DeleteReport};
 use planning::project::{
-    PartStateError, PcbOperationError, ProcessPresetFactory, ProcessPresetFactoryError, Project, ProjectError,
-    ProjectPcb,
+    ArtifactGenerationError, InspectionError, PartStateError, PcbOperationError, PhaseAssignmentResult,
+    ProcessPresetFactory, ProcessPresetFactoryError, Project, ProjectError, ProjectPcb, RefreshResult,
+    TaskActionOptions,
 };
+pub use planning::recent::{self, RecentProject, RecentProjects};
+pub use planning::recovery::AUTOSAVE_INTERVAL;
+use planning::recovery::{self, RecoveryPcb, RecoverySnapshot};
+use planning::relink;
+use planning::report::analytics;
+pub use planning::report::analytics::{PartAnalytics, PhaseAnalytics, ProjectAnalytics, SkippedRefDesAnalytics};
+pub use planning::report::handover::HandoverReportFormat;
+pub use planning::report::issues::ProjectIssue;
+use planning::report::issues::{self, IssueCheck};
 pub use planning::report::{IssueKind, IssueSeverity, ProjectReport};
+pub use planning::rotation::{RotationOffsetSource, RotationOffsetTarget};
+pub use planning::serial_number::{SerialNumber, SerialNumberError, SerialNumberScheme};
+pub use planning::settings::{OriginConvention, ProjectSettings, RotationDirection};
+pub use planning::store::StoreBackend;
+use planning::template::{self, TemplateError};
 pub use planning::variant::VariantName;
-use planning::{file, pcb, project, report};
-pub use pnp::load_out::LoadOutItem;
-pub use pnp::object_path::ObjectPath;
-use pnp::package::Package;
+use planning::{file, pcb, project, report, rotation};
+pub use pnp::load_out::{FeederBank, FeederLayoutIssue, FeederSlot, LoadOutItem};
+pub use pnp::object_path::{ObjectPath, ObjectPathPattern, ObjectPathQuery};
+pub use pnp::package::{Package, PackageDimensions};
 pub use pnp::panel::{DesignSizing, Dimensions, FiducialParameters, PanelSizing, PcbUnitPositioning, Unit};
-pub use pnp::part::Part;
+pub use pnp::part::{MoistureSensitivityLevel, Part, PartAliasGroupName, PartAliases, PartMoistureSensitivity};
 pub use pnp::pcb::PcbSide;
 pub use pnp::pcb::{PcbInstanceIndex, PcbInstanceNumber, PcbUnitIndex, PcbUnitNumber};
 pub use pnp::placement::RefDes;
 pub use pnp::placement::{Placement, PlacementPosition, PlacementPositionUnit};
 pub use pnp::reference::Reference;
+use pnp::transform::{GerberTransformer, UnitToPanel};
 use regex::Regex;
+use rust_decimal::prelude::ToPrimitive;
+pub use rust_decimal::Decimal;
 use serde_with::serde_as;
+use stores::backend::{CsvImport, LoadOutStore, Store};
 use stores::load_out::LoadOutOperationError;
 pub use stores::load_out::LoadOutSource;
 pub use stores::package_mappings::PackageMappingsSource;
 pub use stores::packages::PackagesSource;
+pub use stores::parts::PartsSource;
+use stores::placements::{AllPlacements, PlacementsCache, PlacementsSource};
+pub use stores::unit_positionings::UnitPositioningsSource;
 use thiserror::Error;
+use time::OffsetDateTime;
 use tracing::{debug, error, info, trace, warn};
+pub use util::cancellation::CancellationToken;
+use util::cancellation::Cancelled;
 use util::source::SourceError;
 
+use crate::effects::file_io::{FileIoOperation, FileIoResult};
 use crate::effects::pcb_view_renderer::PcbViewRendererOperation;
 use crate::effects::project_view_renderer::ProjectViewRendererOperation;
-use crate::effects::{pcb_view_renderer, project_view_renderer};
+use crate::effects::view_invalidation::ViewInvalidationOperation;
+use crate::effects::vision::{VisionOperation, VisionResult};
+use crate::effects::{file_io, pcb_view_renderer, project_view_renderer, view_invalidation, vision};
+pub use crate::error_view::AppErrorView;
+pub use crate::hit_test::{HitTestItem, HitTestQuery, HitTestResults};
+use crate::paging::PlacementsPage;
+pub use crate::paging::{PlacementsPageRequest, PlacementsSort, PlacementsSortKey};
+use crate::replay::{EventRecorder, ModifiedFlags, ReplayEntry};
+pub use crate::search::{SearchItem, SearchResult, SearchResults};
+use crate::selection::{PlacementSelections, SelectionIds};
+pub use crate::selection::{PlacementsSelector, SelectionId};
+pub use crate::tree_path::{TreePath, TreePathSegment};
+use crate::warnings::push_warning;
+pub use crate::warnings::{AppWarning, Warnings};
 
 pub mod effects;
+pub mod error_view;
+pub mod hit_test;
+pub mod paging;
+pub mod replay;
+pub mod search;
+pub mod selection;
+pub mod tree_path;
+pub mod view_dependencies;
+pub mod warnings;
+
+// The core builds for wasm32-unknown-unknown, for browser-based shells.  Projects, PCBs and archives are loaded and
+// saved via the file IO effect, the native implementations, i.e. `file_io::perform` and `replay::read_replay`, are not
+// available.  Other operations that use the filesystem directly (templates, stores, artifacts) return an
+// 'unsupported' IO error at runtime.
+#[cfg(all(target_arch = "wasm32", any(feature = "sqlite", feature = "remote")))]
+compile_error!("The 'sqlite' and 'remote' features are not supported on wasm32.");
 
 extern crate serde_regex;
 
@@ -80,7 +156,24 @@ pub struct ModelProject {
     path: PathBuf,
     project_directory: PathBuf,
     project: Project,
-    modified: bool,
+    modified: Modified,
+    placement_session: Option<ModelPlacementSession>,
+    /// See [`Event::SelectPlacements`].
+    placement_selections: PlacementSelections,
+    /// A snapshot with unsaved changes, found when the project was loaded, see [`Event::RestoreRecovery`].
+    recovery: Option<RecoverySnapshot>,
+    /// See [`Planner::refresh_project`].
+    refresh_cache: RefreshCache,
+}
+
+/// So that refreshing only loads the placements files that have changed, and only refreshes the placements of the
+/// design variants that have changed.
+#[derive(Default)]
+struct RefreshCache {
+    placements: PlacementsCache,
+    /// A hash of the unit assignments and PCBs of the last successful refresh, `None` if there was no successful
+    /// refresh, or when the project was replaced.
+    inputs_hash: Option<u64>,
 }
 
 impl ModelProject {
@@ -97,13 +190,108 @@ fn pcbs<'a, 'b>(&'a self, model_pcbs: &'b ModelPcbs) -> impl Iterator<Item = Opt
     }
 }
 
+/// Whether there are unsaved changes, and a revision that changes with every change, so that a write only clears the
+/// modified state when there have been no changes since the write was requested, see [`Event::ProjectFileWritten`]
+/// and [`Event::PcbFileWritten`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Modified {
+    unsaved: bool,
+    revision: u64,
+}
+
+impl Modified {
+    /// For projects and PCBs that have not been saved yet.
+    fn unsaved() -> Self {
+        Self {
+            unsaved: true,
+            revision: 0,
+        }
+    }
+
+    fn is_modified(&self) -> bool {
+        self.unsaved
+    }
+
+    fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Clears the modified state, unless there have been changes since `revision` was written, returns `true` if
+    /// cleared.
+    fn saved(&mut self, revision: u64) -> bool {
+        if self.revision != revision {
+            return false;
+        }
+
+        self.unsaved = false;
+        true
+    }
+}
+
+impl BitOrAssign<bool> for Modified {
+    fn bitor_assign(&mut self, changed: bool) {
+        if changed {
+            self.unsaved = true;
+            self.revision = self.revision.wrapping_add(1);
+        }
+    }
+}
+
+/// A guided, step-by-step, placement session for a phase.
+struct ModelPlacementSession {
+    phase: PhaseReference,
+    /// The placements that were pending when the session was started, in placement order.
+    object_paths: Vec<ObjectPath>,
+    /// Index into `object_paths`, `None` when there are no more pending placements.
+    current: Option<usize>,
+    placed: usize,
+    skipped: usize,
+}
+
+impl ModelPlacementSession {
+    /// Advance to the next pending placement, wrapping around to any placements that were passed over.
+    fn advance(&mut self, placements: &BTreeMap<ObjectPath, PlacementState>) {
+        let count = self.object_paths.len();
+        let start = self
+            .current
+            .map_or(0, |index| index + 1);
+
+        self.current = (0..count)
+            .map(|offset| (start + offset) % count)
+            .find(|index| {
+                placements
+                    .get(&self.object_paths[*index])
+                    .is_some_and(|state| state.operation_status == PlacementStatus::Pending)
+            });
+    }
+}
+
 pub struct ModelPcb {
     pcb: Pcb,
-    modified: bool,
+    modified: Modified,
+    /// The modification time of the PCB file when it was loaded or saved, `None` for PCBs that have not been saved or
+    /// when the filesystem does not provide it.
+    file_modified: Option<SystemTime>,
+}
+
+impl ModelPcb {
+    /// `true` when the PCB file has been changed since it was loaded or saved, e.g. by another instance or by
+    /// hand, PCBs with unsaved changes are never stale, so that the changes are not discarded.
+    ///
+    /// `file_modified` is the current modification time of the PCB file, as read by the shell.
+    fn is_stale(&self, file_modified: Option<SystemTime>) -> bool {
+        !self.modified.is_modified()
+            && self
+                .file_modified
+                .is_some_and(|loaded_file_modified| file_modified != Some(loaded_file_modified))
+    }
 }
 
 type ModelPcbs = BTreeMap<PathBuf, ModelPcb>;
 
+/// See [`Model::cancelled_operations`].
+const MAX_CANCELLED_OPERATIONS: usize = 32;
+
 #[derive(Default)]
 pub struct Model {
     model_project: Option<ModelProject>,
@@ -112,10 +300,50 @@ pub struct Model {
     /// Important: Can contain instances of [`ModelPcb`] that have been created or loaded, but not assigned to a project yet.
     model_pcbs: ModelPcbs,
 
-    error: Option<(chrono::DateTime<chrono::Utc>, String)>,
+    error: Option<(chrono::DateTime<chrono::Utc>, AppErrorView)>,
+    /// The warnings of the last processed event.
+    warnings: Warnings,
+    /// What was affected by the last processed event, if it deleted an item, see [`Event::RemovePcb`] and
+    /// [`Event::DeletePhase`].
+    delete_report: Option<DeleteReport>,
+    /// The selection made by the last processed event, see [`Event::SelectPlacements`].
+    placement_selection: Option<SelectionId>,
+    selection_ids: SelectionIds,
+    /// The design variants refreshed by the last processed event, see [`Event::RefreshFromDesignVariants`].
+    refreshed_design_variants: Option<BTreeSet<DesignVariant>>,
+
+    /// The ids of tokens that were cancelled via [`Event::CancelOperation`], operations that are requested with one
+    /// of these tokens are cancelled before they start.  An id is removed when an operation uses it, only the most
+    /// recent [`MAX_CANCELLED_OPERATIONS`] are kept, for tokens that are never used.
+    cancelled_operations: BTreeSet<u64>,
+
+    /// See [`Event::SetEventRecording`].
+    event_recorder: Option<EventRecorder>,
 }
 
 impl Model {
+    /// Returns the token to check during an operation, operations that were requested without one cannot be cancelled.
+    fn operation_token(&mut self, token: Option<CancellationToken>) -> CancellationToken {
+        let token = token.unwrap_or_default();
+        if self
+            .cancelled_operations
+            .remove(&token.id())
+        {
+            token.cancel();
+        }
+        token
+    }
+
+    fn cancel_operation(&mut self, token: &CancellationToken) {
+        self.cancelled_operations
+            .insert(token.id());
+
+        // token ids are allocated in ascending order, so the first ids are the oldest
+        while self.cancelled_operations.len() > MAX_CANCELLED_OPERATIONS {
+            self.cancelled_operations.pop_first();
+        }
+    }
+
     /// an iterator over the pcbs for the project
     ///
     /// will return Some(None) for any PCB that hasn't been loaded.
@@ -134,47 +362,30 @@ fn project_model_pcbs_maybe<'a, 'b>(
             .flat_map(|model_project| model_project.pcbs(model_pcbs))
     }
 
-    // FUTURE consider adding a 'refresh_project_pcbs' that re-loads them.
-
-    /// Load PCBs for the project that are not already loaded.
+    /// Returns an error if any of the PCBs of the project has not been loaded yet.
     ///
-    /// * Requires a project to be loaded.
-    /// * Stops and returns on the first error.
-    fn load_unloaded_project_pcbs(&mut self, root: &PathBuf) -> Result<(), AppError> {
-        let Some(model_project) = &mut self.model_project else {
-            return Err(AppError::OperationRequiresProject);
-        };
+    /// The PCBs are read by the shell after the project file, so that large projects open quickly, see
+    /// [`Event::PcbFileModifiedTimeRead`].
+    fn ensure_project_pcbs_loaded(project: &Project, model_pcbs: &ModelPcbs, root: &PathBuf) -> Result<(), AppError> {
+        for project_pcb in project.pcbs.iter() {
+            let pcb_path = project_pcb.pcb_file.build_path(root);
+            if !model_pcbs.contains_key(&pcb_path) {
+                return Err(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded));
+            }
+        }
 
-        Self::load_project_pcbs_inner(&mut model_project.project, &mut self.model_pcbs, root)
+        Ok(())
     }
 
-    fn load_project_pcbs_inner(
-        project: &mut Project,
-        model_pcbs: &mut ModelPcbs,
-        root: &PathBuf,
-    ) -> Result<(), AppError> {
-        let pcbs_to_load = project
-            .pcbs
-            .iter_mut()
-            .filter(|pcb| {
-                let pcb_path = pcb.pcb_file.build_path(root);
-                !model_pcbs.contains_key(&pcb_path)
-            })
-            .collect::<Vec<_>>();
-
-        // Then, process one-by-one: load and insert
-        for pcb in pcbs_to_load {
-            let (_pcb_file, pcb_data, pcb_path) = pcb
-                .load_pcb(root)
-                .map_err(AppError::IoError)?;
-
-            model_pcbs.insert(pcb_path.clone(), ModelPcb {
-                pcb: pcb_data,
-                modified: false,
-            });
-        }
+    fn loaded_pcb<'a>(model_pcbs: &'a mut ModelPcbs, path: &PathBuf) -> Result<&'a mut ModelPcb, AppError> {
+        model_pcbs
+            .get_mut(path)
+            .ok_or(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded))
+    }
 
-        Ok(())
+    /// Returns the PCB, an error if it has not been loaded yet.
+    fn model_pcb(&mut self, path: &PathBuf) -> Result<&mut ModelPcb, AppError> {
+        Self::loaded_pcb(&mut self.model_pcbs, path)
     }
 
     #[allow(dead_code)]
@@ -195,41 +406,17 @@ fn project_pcbs_inner<'a>(iter: impl Iterator<Item = Option<&'a ModelPcb>>) -> V
         })
         .collect::<Vec<_>>()
     }
-
-    /// Load a PCB, no project required.
-    fn load_pcb(&mut self, path: &PathBuf) -> Result<(), AppError> {
-        let pcb = pcb::load_pcb(path).map_err(AppError::IoError)?;
-
-        self.model_pcbs
-            .insert(path.clone(), ModelPcb {
-                pcb,
-                modified: false,
-            });
-
-        Ok(())
-    }
-
-    /// Save a PCB, no project required.
-    fn save_pcb(&mut self, path: &PathBuf) -> Result<(), AppError> {
-        info!("Saving PCB. path: {:?}", path);
-        let model_pcb = self
-            .model_pcbs
-            .get_mut(path)
-            .ok_or(AppError::OperationError(anyhow!("PCB not loaded. path: {:?}", path)))?;
-
-        file::save::<Pcb>(&model_pcb.pcb, path).map_err(AppError::IoError)?;
-
-        model_pcb.modified = false;
-
-        Ok(())
-    }
 }
 
-#[effect]
+#[cfg_attr(feature = "typegen", effect(typegen))]
+#[cfg_attr(not(feature = "typegen"), effect)]
 pub enum Effect {
     Render(RenderOperation),
     ProjectView(ProjectViewRendererOperation),
     PcbView(PcbViewRendererOperation),
+    Vision(VisionOperation),
+    FileIo(FileIoOperation),
+    ViewInvalidated(ViewInvalidationOperation),
 }
 
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
@@ -245,6 +432,29 @@ pub struct ProjectPcbOverview {
     pub pcb_path: PathBuf,
 }
 
+/// The board outline and the component bodies of the placed placements on a PCB, for previewing the assembled board.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct BoardPreview {
+    pub index: u16,
+    /// From the PCB's profile gerber, or the panel size when the PCB has no profile gerber.
+    pub outline: Outline,
+    pub components: Vec<BoardPreviewComponent>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct BoardPreviewComponent {
+    pub path: ObjectPath,
+    pub ref_des: RefDes,
+    pub pcb_side: PcbSide,
+    /// Position on the panel, see [`PlacementState::unit_position`].
+    pub x: Decimal,
+    pub y: Decimal,
+    pub rotation: Decimal,
+    /// `None` when the part has no package, or the package has no dimensions.
+    pub dimensions: Option<PackageDimensions>,
+    pub placed: bool,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
 pub struct PcbOverview {
     pub path: PathBuf,
@@ -272,6 +482,75 @@ pub struct PcbOverview {
     pub orientation: PcbAssemblyOrientation,
 }
 
+/// The gerbers of a design, positioned on a unit of the panel.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PanelPreviewUnit {
+    pub unit: PcbUnitIndex,
+    pub design: DesignIndex,
+    pub gerbers: Vec<PcbGerberItem>,
+
+    /// Anti-clockwise positive degrees, the gerbers are rotated about the `pivot`, then translated by the `offset`.
+    ///
+    /// The design's gerber offset is included, see [`pnp::transform::GerberTransformer`].
+    pub rotation: Decimal,
+    pub pivot: Vector2<f64>,
+    pub offset: Vector2<f64>,
+}
+
+/// The panel as designed, composed from the PCB's gerbers and the gerbers of the design assigned to each unit.
+///
+/// The assembly orientation is not applied.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PanelPreview {
+    pub path: PathBuf,
+
+    pub size: Vector2<f64>,
+    pub edge_rails: Dimensions<f64>,
+    pub fiducials: Vec<FiducialParameters>,
+
+    /// The PCB's own gerbers, e.g. the panel outline and rails, positioned using the `gerber_offset`.
+    pub pcb_gerbers: Vec<PcbGerberItem>,
+    pub gerber_offset: Vector2<f64>,
+
+    /// In unit order, units without a design are not included.
+    pub units: Vec<PanelPreviewUnit>,
+}
+
+/// Where a placement is on its panel, so that shells can locate it on a rendered panel.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PlacementLocation {
+    /// index, 0-based
+    pub pcb: u16,
+    pub object_path: ObjectPath,
+    pub pcb_side: PcbSide,
+    /// Position on the panel, as viewed from the top, in millimeters, see [`PlacementState::unit_position`].
+    pub position: PlacementPositionUnit,
+    /// Position on the panel, as viewed from the `pcb_side`, bottom side positions are mirrored in the Y axis, i.e. X
+    /// is negated, the rotation is not changed.
+    pub viewed_position: PlacementPositionUnit,
+}
+
+/// The position and label of each unit of a PCB, so that unit numbers can be overlaid on a rendered panel.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PcbUnitOverlay {
+    pub index: u16,
+    /// In unit order, units without a design are not included.
+    pub units: Vec<PcbUnitOverlayItem>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PcbUnitOverlayItem {
+    pub unit: PcbUnitIndex,
+    pub unit_number: PcbUnitNumber,
+    /// The center of the unit in panel coordinates, i.e. before the panel is oriented for assembly.
+    pub centroid: Vector2<f64>,
+    /// Anti-clockwise positive degrees, see [`PcbUnitPositioning::rotation`].
+    pub rotation: Decimal,
+    pub design_name: DesignName,
+    /// `None` when the unit has no variant assigned.
+    pub variant_name: Option<VariantName>,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
 pub struct PcbUnitAssignments {
     /// the design name for the pcb unit index can be obtained via the PCB overview
@@ -287,12 +566,46 @@ pub struct LoadOut {
     pub items: Vec<LoadOutItem>,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct FeederLayout {
+    pub phase_reference: PhaseReference,
+    pub banks: Vec<FeederLayoutBank>,
+    /// Load-out items without a feeder reference, or whose feeder reference does not match a slot.
+    pub unassigned_items: Vec<LoadOutItem>,
+    pub issues: Vec<FeederLayoutIssue>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct FeederLayoutBank {
+    pub reference: Reference,
+    pub slots: Vec<FeederLayoutSlot>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct FeederLayoutSlot {
+    pub reference: Reference,
+    /// Millimeters, the pick position in machine coordinates.
+    pub x: Decimal,
+    /// Millimeters, the pick position in machine coordinates.
+    pub y: Decimal,
+    /// Millimeters
+    pub width: Decimal,
+    /// The load-out items assigned to the slot, more than one is a conflict.
+    pub items: Vec<LoadOutItem>,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
 pub struct Phases {
     /// in the order defined by the project's phase orderings
     pub phases: Vec<PhaseOverview>,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct ProjectIssues {
+    /// sorted by severity, kind and message
+    pub issues: Vec<ProjectIssue>,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
 pub struct PhaseOverview {
     pub phase_reference: PhaseReference,
@@ -302,6 +615,11 @@ pub struct PhaseOverview {
     pub phase_placement_orderings: Vec<PlacementSortingItem>,
     pub can_start: bool,
     pub state: PhaseState,
+    /// The rules of the process, see [`Event::RecordPhaseOperation`].
+    pub process_rules: Vec<ProcessRuleReference>,
+    /// The user-defined tasks of each operation of the process, the values of their parameters are required when
+    /// completing them, see [`Event::RecordPhaseOperation`].
+    pub custom_tasks: BTreeMap<OperationReference, Vec<CustomTaskDefinition>>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
@@ -309,18 +627,78 @@ pub struct PlacementsItem {
     pub path: ObjectPath,
     pub state: PlacementState,
     pub ordering: usize,
+    /// The nozzle assigned to the placement, only for phase placements.
+    pub nozzle: Option<NozzleReference>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PlacementRotationItem {
+    pub path: ObjectPath,
+    pub part: Part,
+    /// `None` if the part could not be mapped to a package.
+    pub package: Option<String>,
+    /// The rotation of the placement on the panel, before the offset is applied.
+    ///
+    /// The rotations use the rotation direction of the project settings, see [`ProjectSettings::rotation`].
+    pub rotation: Decimal,
+    pub offset: Decimal,
+    pub offset_source: RotationOffsetSource,
+    /// The rotation used for artifact generation.
+    pub effective_rotation: Decimal,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PlacementRotations {
+    pub placements: Vec<PlacementRotationItem>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
 pub struct PhasePlacements {
     pub phase_reference: PhaseReference,
 
+    /// The requested page of the placements, or all of them, see [`Event::RequestPhasePlacementsView`].
     pub placements: Vec<PlacementsItem>,
+    /// The offset of the first placement of the page.
+    pub offset: usize,
+    /// The amount of placements in the phase.
+    pub total: usize,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PlacementSessionItem {
+    pub path: ObjectPath,
+    /// Contains the part, and the design position and rotation.
+    pub state: PlacementState,
+    /// Position and rotation on the panel, in millimeters and degrees, see [`PlacementState::unit_position`].
+    pub position: PlacementPositionUnit,
+    /// `None` if the part is not in the phase's load-out or if no feeder has been assigned.
+    pub feeder_reference: Option<Reference>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PlacementSessionProgress {
+    /// The amount of placements that were pending when the session was started.
+    pub total: usize,
+    /// Placed during the session.
+    pub placed: usize,
+    /// Skipped during the session.
+    pub skipped: usize,
+    /// Placements that are still pending.
+    pub remaining: usize,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PlacementSession {
+    pub phase_reference: PhaseReference,
+    /// `None` when there are no more pending placements.
+    pub current: Option<PlacementSessionItem>,
+    pub progress: PlacementSessionProgress,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
 pub struct PartWithState {
     pub part: Part,
+    pub alias_group: Option<PartAliasGroupName>,
     pub processes: Vec<ProcessReference>,
     pub ref_des_set: BTreeSet<RefDes>,
     pub quantity: usize,
@@ -331,9 +709,27 @@ pub struct PartStates {
     pub parts: Vec<PartWithState>,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PartPackage {
+    pub part: Part,
+    /// `None` if the part could not be mapped to a package.
+    pub package: Option<Package>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct PartPackages {
+    /// in the same order as the project's parts
+    pub parts: Vec<PartPackage>,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
 pub struct PlacementsList {
+    /// The requested page of the placements, or all of them, see [`Event::RequestPlacementsView`].
     pub placements: Vec<PlacementsItem>,
+    /// The offset of the first placement of the page.
+    pub offset: usize,
+    /// The amount of placements in the project.
+    pub total: usize,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone, Eq)]
@@ -341,9 +737,7 @@ pub struct ProjectTreeItem {
     pub key: String,
     pub args: HashMap<String, args::Arg>,
 
-    /// "/" = root, paths are "/" separated.
-    // FIXME path elements that contain a `/` need to be escaped and un-escaped.  e.g. a phase reference of `top/1`
-    pub path: String,
+    pub path: TreePath,
 }
 
 impl Default for ProjectTreeItem {
@@ -351,7 +745,7 @@ fn default() -> Self {
         Self {
             key: "unknown".to_string(),
             args: HashMap::new(),
-            path: "/".to_string(),
+            path: TreePath::root(),
         }
     }
 }
@@ -363,6 +757,10 @@ pub struct ProjectOverview {
     pub library_config: LibraryConfig,
 
     pub pcbs: Vec<ProjectPcb>,
+
+    /// The current build run, if any.
+    #[serde(default)]
+    pub build_run: Option<BuildRunSummary>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone)]
@@ -413,51 +811,93 @@ fn graph_eq<N, E, Ty, Ix>(a: &petgraph::Graph<N, E, Ty, Ix>, b: &petgraph::Graph
 pub enum ProjectView {
     Overview(ProjectOverview),
     Parts(PartStates),
+    PartPackages(PartPackages),
+    PlacementRotations(PlacementRotations),
     PcbOverview(ProjectPcbOverview),
+    BoardPreview(BoardPreview),
     PcbUnitAssignments(PcbUnitAssignments),
+    PcbUnitOverlay(PcbUnitOverlay),
+    PlacementLocation(PlacementLocation),
+    HitTest(HitTestResults),
     Phases(Phases),
     PhaseLoadOut(LoadOut),
+    FeederLayout(FeederLayout),
     PhaseOverview(PhaseOverview),
     PhasePlacements(PhasePlacements),
     Placements(PlacementsList),
+    PlacementSession(PlacementSession),
     ProcessDefinition(ProcessDefinition),
     ProjectTree(ProjectTreeView),
     ProjectReport(ProjectReport),
+    Issues(ProjectIssues),
+    Analytics(ProjectAnalytics),
+    Search(SearchResults),
+    Settings(ProjectSettings),
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub enum ProjectViewRequest {
     Overview,
     Parts,
+    PartPackages,
+    PlacementRotations,
     PcbOverview { pcb: u16 },
+    BoardPreview { pcb: u16 },
     PcbUnitAssignments { pcb: u16 },
+    PcbUnitOverlay { pcb: u16 },
+    PlacementLocation { object_path: ObjectPath },
+    HitTest { query: HitTestQuery },
     Phases,
     PhaseLoadOut { phase: PhaseReference },
+    FeederLayout { phase: PhaseReference },
     PhaseOverview { phase: PhaseReference },
     PhasePlacements { phase: PhaseReference },
     Placements,
+    PlacementSession,
     ProcessDefinition { process: ProcessReference },
     ProjectTree,
     ProjectReport,
+    Issues,
+    Analytics,
+    Search { query: String },
+    Settings,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
 pub enum PcbView {
     PcbOverview(PcbOverview),
     PanelSizing(PanelSizing),
+    PanelPreview(PanelPreview),
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub enum PcbViewRequest {
     Overview { path: PathBuf },
     Panel { path: PathBuf },
+    PanelPreview { path: PathBuf },
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Default, PartialEq, Debug)]
 pub struct PlannerOperationViewModel {
     pub project_modified: bool,
     pub pcbs_modified: bool,
-    pub error: Option<(chrono::DateTime<chrono::Utc>, String)>,
+    /// A recovery snapshot, with unsaved changes, was found when the project was loaded.
+    pub recovery_available: bool,
+    pub error: Option<(chrono::DateTime<chrono::Utc>, AppErrorView)>,
+    /// Non-fatal problems that occurred while processing the last event, see [`warnings`].
+    pub warnings: Warnings,
+    /// What was affected by the last processed event, if it deleted an item.
+    pub delete_report: Option<DeleteReport>,
+    /// The selection made by the last processed event, see [`Event::SelectPlacements`].
+    pub placement_selection: Option<SelectionId>,
+    /// The design variants whose placements were refreshed by the last processed event, design variants whose
+    /// placements files are unchanged are not refreshed, see [`Event::RefreshFromDesignVariants`].
+    pub refreshed_design_variants: Option<BTreeSet<DesignVariant>>,
+}
+
+/// See [`Event::DeletePhase`].
+fn legacy_delete_phase_strategy() -> DeleteStrategy {
+    DeleteStrategy::Orphan
 }
 
 #[serde_as]
@@ -471,26 +911,112 @@ pub enum Event {
         packages: Option<PackagesSource>,
         package_mappings: Option<PackageMappingsSource>,
     },
-    // TODO consider if the 'shell' should be loading and saving the project, not the core?
-    //      currently the core does all loading/saving and uses stores too, this might not be how
-    //      crux is intended to be used.
+    CreateProjectFromTemplate {
+        /// The template directory
+        template: PathBuf,
+        name: String,
+        /// The name of the project file
+        path: PathBuf,
+    },
+    /// A template load-out has been copied to the project directory by the shell, see
+    /// [`Event::CreateProjectFromTemplate`].
+    TemplateLoadOutCopied {
+        path: PathBuf,
+        result: FileIoResult,
+    },
+    // The project file is read and written by the shell, see [`effects::file_io`].
+    // FUTURE port the remaining IO (stores, artifacts) to the file IO effect.
     Save,
     Load {
         /// The name of the project file
         path: PathBuf,
     },
+    /// The project file has been read by the shell, see [`Event::Load`].
+    ProjectFileRead {
+        path: PathBuf,
+        result: FileIoResult,
+    },
+    /// The project file has been written by the shell, see [`Event::Save`].
+    ///
+    /// The project is only marked as saved when it has not been changed since the write was requested.
+    ProjectFileWritten {
+        path: PathBuf,
+        result: FileIoResult,
+        /// The modification revision of the project when the write was requested.
+        #[serde(default)]
+        revision: u64,
+    },
+    /// Writes a recovery snapshot of the project and modified PCBs, if there are unsaved changes.
+    ///
+    /// Shells should send this every [`AUTOSAVE_INTERVAL`], nothing is written while a recovery snapshot is pending,
+    /// see [`Event::RestoreRecovery`] and [`Event::DiscardRecovery`].
+    Autosave,
+    /// The recovery snapshot has been written by the shell, see [`Event::Autosave`].
+    AutosaveWritten {
+        path: PathBuf,
+        result: FileIoResult,
+    },
+    /// The recovery file has been read by the shell, after loading the project, a missing file is not an error.
+    RecoveryFileRead {
+        path: PathBuf,
+        result: FileIoResult,
+    },
+    /// Replaces the project and PCBs with the recovery snapshot, they are marked as modified, but are not saved.
+    RestoreRecovery,
+    /// Removes the recovery file, without restoring it.
+    DiscardRecovery,
+    /// The recovery file has been removed by the shell, see [`Event::DiscardRecovery`] and [`Event::Save`].
+    RecoveryFileRemoved {
+        path: PathBuf,
+        result: FileIoResult,
+    },
+    ExportProjectArchive {
+        /// The path of the zip file
+        path: PathBuf,
+    },
+    ImportProjectArchive {
+        /// The path of the zip file
+        archive: PathBuf,
+        /// The directory to extract the archive into
+        directory: PathBuf,
+    },
+    /// The archive has been read by the shell, see [`Event::ImportProjectArchive`].
+    ProjectArchiveRead {
+        directory: PathBuf,
+        result: FileIoResult,
+    },
+    /// A file extracted from the archive has been written by the shell, see [`Event::ProjectArchiveRead`].
+    ArchiveFileWritten {
+        path: PathBuf,
+        result: FileIoResult,
+    },
+    /// Finds missing PCB, gerber and load-out files in the search roots.
+    RelinkFiles {
+        search_roots: Vec<PathBuf>,
+        /// Relink files that have exactly one candidate, otherwise the candidates are just logged.
+        apply: bool,
+    },
     AddPcb {
         pcb_file: FileReference,
     },
+    /// See [`project::remove_pcb`], the affected unit assignments and placements are in
+    /// [`PlannerOperationViewModel::delete_report`].
     RemovePcb {
         index: PcbUnitIndex,
+        #[serde(default)]
+        strategy: DeleteStrategy,
     },
     CreateProjectPcb {
         name: String,
         units: u16,
         unit_map: BTreeMap<PcbUnitNumber, DesignName>,
     },
-    RefreshPcbs,
+    /// Reloads the loaded PCBs whose files have changed since they were loaded, and the PCBs with unsaved changes,
+    /// the PCB files are read by the shell, see [`Event::PcbFileModifiedTimeRead`].
+    RefreshPcbs {
+        #[serde(default)]
+        token: Option<CancellationToken>,
+    },
     SaveAllPcbs,
     CreateProcessFromPreset {
         preset: ProcessReference,
@@ -502,16 +1028,38 @@ pub enum Event {
     DeleteProcess {
         process_reference: ProcessReference,
     },
+    /// Copies a process definition, phases using the process are not changed.
+    CloneProcess {
+        process_reference: ProcessReference,
+        new_reference: ProcessReference,
+    },
     ApplyPackageSources {
         packages_source: Option<PackagesSource>,
         package_mappings_source: Option<PackageMappingsSource>,
     },
+    /// Replaces the part alias groups of the project with the groups from the 'AliasGroup' column of the parts file.
+    ImportPartAliases {
+        parts_source: PartsSource,
+    },
+    /// Sets the moisture sensitivity of the project's parts from the 'Msl' and 'FloorLifeHours' columns of the parts
+    /// file, parts that are not used by the project are reported as a warning.
+    ImportMoistureSensitivities {
+        parts_source: PartsSource,
+    },
+    /// Imports the placements CSV files of the design variants, the load-out CSV files of the phases and the parts
+    /// file, if any, into the project's store backend, e.g. after changing the project to the SQLite store backend.
+    ImportCsvIntoStore {
+        parts_source: Option<PartsSource>,
+    },
     AssignVariantToUnit {
         unit: ObjectPath,
         /// some to make assignment, none to un-assign.
         variant: Option<VariantName>,
     },
-    RefreshFromDesignVariants,
+    RefreshFromDesignVariants {
+        #[serde(default)]
+        token: Option<CancellationToken>,
+    },
     AssignProcessToParts {
         process: ProcessReference,
         operation: AddOrRemoveAction,
@@ -520,14 +1068,48 @@ pub enum Event {
         #[serde(with = "serde_regex")]
         mpn: Regex,
     },
+    /// Set, or clear when `moisture_sensitivity` is `None`, the moisture sensitivity of the matching parts.
+    SetPartMoistureSensitivity {
+        #[serde(with = "serde_regex")]
+        manufacturer: Regex,
+        #[serde(with = "serde_regex")]
+        mpn: Regex,
+        moisture_sensitivity: Option<PartMoistureSensitivity>,
+    },
+    /// Records a reel open or bake event for the matching parts, at the current time.
+    RecordPartMoistureEvent {
+        #[serde(with = "serde_regex")]
+        manufacturer: Regex,
+        #[serde(with = "serde_regex")]
+        mpn: Regex,
+        event: MoistureEvent,
+    },
     CreatePhase {
         process: ProcessReference,
         reference: PhaseReference,
         load_out: LoadOutSource,
         pcb_side: PcbSide,
     },
+    /// See [`Project::delete_phase`], the affected placements are in [`PlannerOperationViewModel::delete_report`].
     DeletePhase {
         reference: PhaseReference,
+        /// Events serialized before the strategy was added, e.g. replays and scripts, unassigned the placements of the
+        /// phase, so a missing strategy is [`DeleteStrategy::Orphan`], not the default [`DeleteStrategy::Block`].
+        #[serde(default = "legacy_delete_phase_strategy")]
+        strategy: DeleteStrategy,
+    },
+    /// Copies the process, PCB side, placement orderings, assembly window and load-out items of a phase, the load-out
+    /// of the copy is named after the new phase, e.g. `load_out_top_1.csv` -> `load_out_top_2.csv`.
+    ///
+    /// No placements are assigned to the copy.
+    ClonePhase {
+        phase_reference: PhaseReference,
+        new_reference: PhaseReference,
+    },
+    /// Replaces the phase with a sub-phase for each pass when the phase requires more feeders than the machine has
+    /// feeder slots, e.g. `top_smt` is replaced with `top_smt_1` and `top_smt_2`.
+    SplitPhaseByFeederCapacity {
+        phase: PhaseReference,
     },
     SetPhaseOrdering {
         phases: Vec<PhaseReference>,
@@ -537,8 +1119,11 @@ pub enum Event {
         operation: SetOrClearAction,
 
         /// to apply to object path (not refdes)
-        #[serde(with = "serde_regex")]
-        placements: Regex,
+        placements: PlacementsSelector,
+
+        /// assign placements that are not on the PCB side of the phase, e.g. through-hole parts.
+        #[serde(default)]
+        force: bool,
     },
     AddPartsToLoadout {
         phase: PhaseReference,
@@ -555,21 +1140,110 @@ pub enum Event {
         #[serde(with = "serde_regex")]
         mpn: Regex,
     },
+    /// Upgrade the load-out files of all phases to the latest format.
+    MigrateLoadOuts {},
+    /// Set the format of the project file, the project is always marked as modified so that the file is rewritten.
+    SetProjectFileFormat {
+        format: FileFormat,
+    },
     SetPlacementOrdering {
         phase: PhaseReference,
         placement_orderings: Vec<PlacementSortingItem>,
     },
-    GenerateArtifacts,
+    /// Set, or clear when `assembly_window` is `None`, the scheduled assembly window of a phase.
+    SetPhaseAssemblyWindow {
+        phase: PhaseReference,
+        assembly_window: Option<AssemblyWindow>,
+    },
+    /// Changes the units, coordinate and rotation conventions of the project, see [`planning::settings`].
+    ///
+    /// `None` leaves the setting unchanged.
+    ApplyProjectSettings {
+        unit_system: Option<UnitSystem>,
+        origin: Option<OriginConvention>,
+        rotation_direction: Option<RotationDirection>,
+        default_placement_orderings: Option<Vec<PlacementSortingItem>>,
+    },
+    /// Set, or clear when `offset` is `None`, a rotation offset, in degrees, in the rotation direction of the project
+    /// settings, see [`ProjectSettings::rotation_direction`].
+    SetRotationOffset {
+        target: RotationOffsetTarget,
+        offset: Option<Decimal>,
+    },
+    /// Set, or clear when `notes` is `None`, the operator notes of a placement.
+    SetPlacementNotes {
+        object_path: ObjectPath,
+        notes: Option<String>,
+    },
+    /// Replaces the attachments of a placement, e.g. photos, an empty list clears them.
+    SetPlacementAttachments {
+        object_path: ObjectPath,
+        attachments: Vec<PathBuf>,
+    },
+    /// Generates the phase placements, the project report, and the selected artifacts, in the project directory.
+    GenerateArtifacts {
+        #[serde(default)]
+        token: Option<CancellationToken>,
+        #[serde(default = "Artifact::all")]
+        artifacts: BTreeSet<Artifact>,
+    },
+    /// Generates a snapshot of the work in progress, for a shift handover, in the project directory.
+    GenerateHandoverReport {
+        format: HandoverReportFormat,
+    },
+    /// Starts a run of `quantity` builds of the project's PCBs, each build has its own placement and operation
+    /// statuses, the first build is activated.
+    StartBuildRun {
+        quantity: u32,
+    },
+    /// Activates a build of the current build run, the placement and operation statuses of the build become the
+    /// project's statuses.
+    ActivateBuildRunInstance {
+        instance: BuildRunInstanceNumber,
+    },
     RecordPhaseOperation {
         phase: PhaseReference,
         operation: OperationReference,
         task: TaskReference,
         action: TaskAction,
+        /// Rules of the process that are not enforced for this action, see [`planning::process::ProcessRule`], the
+        /// violations of the overridden rules are recorded in the operation history.
+        #[serde(default)]
+        overridden_rules: Vec<ProcessRuleReference>,
+        /// Recorded when completing a custom task, see [`CustomTaskDefinition`].
+        #[serde(default)]
+        parameter_values: BTreeMap<String, TaskParameterValue>,
     },
     /// Record placements operation
     RecordPlacementsOperation {
-        #[serde(with = "serde_regex")]
-        object_path_patterns: Vec<Regex>,
+        /// Events serialized before selections were added use `object_path_patterns`.
+        #[serde(alias = "object_path_patterns")]
+        placements: Vec<PlacementsSelector>,
+        operation: PlacementOperation,
+    },
+    /// Assigns serial numbers to the units of the PCB that do not already have one.
+    AssignSerialNumbers {
+        pcb: PcbInstanceIndex,
+        scheme: SerialNumberScheme,
+    },
+    /// Record a phase operation for a single serialized unit.
+    RecordUnitPhaseOperation {
+        serial_number: SerialNumber,
+        phase: PhaseReference,
+        operation: OperationReference,
+        task: TaskReference,
+        action: TaskAction,
+        /// See [`Event::RecordPhaseOperation`].
+        #[serde(default)]
+        overridden_rules: Vec<ProcessRuleReference>,
+        /// See [`Event::RecordPhaseOperation`].
+        #[serde(default)]
+        parameter_values: BTreeMap<String, TaskParameterValue>,
+    },
+    /// Record placements operation, only the placements of the serialized unit are updated.
+    RecordUnitPlacementsOperation {
+        serial_number: SerialNumber,
+        object_path_patterns: Vec<ObjectPathPattern>,
         operation: PlacementOperation,
     },
     RemoveUsedPlacements {
@@ -578,35 +1252,150 @@ pub enum Event {
     /// Reset operations
     ResetOperations {},
 
+    //
+    // Placement selections
+    //
+    /// Select the placements that match the filter, subsequent events can refer to the selected placements using
+    /// [`PlacementsSelector::Selection`].
+    ///
+    /// The model allocates the id of the selection, see [`PlannerOperationViewModel::placement_selection`].
+    SelectPlacements {
+        filter: ObjectPathPattern,
+    },
+    ReleasePlacementSelection {
+        selection: SelectionId,
+    },
+
+    //
+    // Placement session
+    //
+    /// Start a guided placement session for the pending placements of a phase, replacing any existing session.
+    StartPlacementSession {
+        phase: PhaseReference,
+    },
+    /// Move to the next pending placement without recording an operation for the current placement.
+    NextPlacement {},
+    /// Record the current placement as placed and move to the next pending placement.
+    ConfirmPlacement {},
+    /// Record the current placement as skipped and move to the next pending placement.
+    SkipPlacement {},
+    EndPlacementSession {},
+
+    //
+    // Cancellation
+    //
+    /// Cancel the operations that were requested with the token.
+    ///
+    /// Since events are processed one at a time, shells that share the token with the core should also call
+    /// [`CancellationToken::cancel`] directly to abort an operation that is in progress.  Operations are aborted
+    /// before any changes are applied to the model.
+    CancelOperation {
+        token: CancellationToken,
+    },
+
+    //
+    // Diagnostics
+    //
+    /// Records the events processed after this one to a replay file, replacing the file if it exists, see [`replay`].
+    ///
+    /// Recording stops when `path` is `None`.
+    SetEventRecording {
+        path: Option<PathBuf>,
+    },
+    /// The shell has written to the replay file, see [`Event::SetEventRecording`].
+    ///
+    /// Not recorded, and does not replace the error, or the warnings, of the last recorded event.
+    EventRecordingWritten {
+        path: PathBuf,
+        result: FileIoResult,
+    },
+
+    //
+    // Vision
+    //
+    RequestVisionOperation {
+        operation: VisionOperation,
+    },
+    /// Sent when a shell has serviced a vision operation
+    VisionOperationCompleted {
+        operation: VisionOperation,
+        result: VisionResult,
+    },
+
     //
     // Project Views
     //
     RequestOverviewView {},
-    RequestPlacementsView {},
+    /// Without a `page` all the placements are in the view.
+    RequestPlacementsView {
+        #[serde(default)]
+        page: Option<PlacementsPageRequest>,
+    },
     RequestProjectTreeView {},
     RequestPhasesView {},
     RequestPhaseOverviewView {
         phase_reference: PhaseReference,
     },
+    /// Without a `page` all the placements of the phase are in the view.
     RequestPhasePlacementsView {
         phase_reference: PhaseReference,
+        #[serde(default)]
+        page: Option<PlacementsPageRequest>,
     },
     RequestPartStatesView,
+    RequestPartPackagesView {},
+    RequestPlacementRotationsView {},
     RequestPhaseLoadOutView {
         phase_reference: PhaseReference,
     },
+    RequestFeederLayoutView {
+        phase_reference: PhaseReference,
+    },
     RequestProjectPcbOverviewView {
         /// index, 0-based
         pcb: u16,
     },
+    RequestBoardPreviewView {
+        /// index, 0-based
+        pcb: u16,
+    },
+    /// The profile gerber of the PCB has been read by the shell, see [`Event::RequestBoardPreviewView`].
+    BoardPreviewProfileRead {
+        /// index, 0-based
+        pcb: u16,
+        file: PathBuf,
+        result: FileIoResult,
+    },
     RequestPcbUnitAssignmentsView {
         /// index, 0-based
         pcb: u16,
     },
+    RequestPcbUnitOverlayView {
+        /// index, 0-based
+        pcb: u16,
+    },
+    RequestPlacementLocationView {
+        object_path: ObjectPath,
+    },
+    /// Finds the placements near a panel position, see [`hit_test`].
+    RequestHitTestView {
+        pcb: u16,
+        position: Vector2<f64>,
+        side: PcbSide,
+    },
     RequestProcessDefinitionView {
         process_reference: ProcessReference,
     },
     RequestProjectReportView {},
+    RequestIssuesView {},
+    /// Aggregated from the operation history of each phase, across all builds.
+    RequestAnalyticsView {},
+    RequestPlacementSessionView {},
+    /// Fuzzy search across refdes, object paths, MPNs, manufacturers, phases and processes, see [`search`].
+    RequestSearchView {
+        query: String,
+    },
+    RequestSettingsView {},
 
     //
     // PCB operations
@@ -617,12 +1406,52 @@ pub enum Event {
         path: PathBuf,
         unit_map: Option<BTreeMap<PcbUnitNumber, DesignName>>,
     },
+    /// Saves a copy of a PCB, with a new name, alongside the PCB, i.e. `<new_name>.pcb.json`.
+    ///
+    /// The copy is not added to the project.
+    ClonePcb {
+        source_path: PathBuf,
+        new_name: String,
+    },
+    /// The directory of the PCB has been listed by the shell, see [`Event::ClonePcb`], an existing file is not
+    /// overwritten.
+    PcbCloneDirectoryListed {
+        source_path: PathBuf,
+        pcb_path: PathBuf,
+        new_name: String,
+        result: FileIoResult,
+    },
     LoadPcb {
         path: PathBuf,
     },
     SavePcb {
         path: PathBuf,
     },
+    /// A PCB file has been written by the shell, see [`Event::SavePcb`] and [`Event::SaveAllPcbs`].
+    ///
+    /// The PCB is only marked as saved when it has not been changed since the write was requested.
+    PcbFileWritten {
+        path: PathBuf,
+        result: FileIoResult,
+        /// The modification revision of the PCB when the write was requested.
+        #[serde(default)]
+        revision: u64,
+    },
+    /// The shell has read the modification time of a PCB file, the PCB file is read when the PCB is not loaded, when
+    /// the file has changed since the PCB was loaded, or when `reload` is set.
+    ///
+    /// Otherwise, the modification time is kept, for PCBs without unsaved changes.
+    PcbFileModifiedTimeRead {
+        path: PathBuf,
+        reload: bool,
+        result: FileIoResult,
+    },
+    /// A PCB file has been read by the shell, replaces the loaded PCB, if any, see [`Event::PcbFileModifiedTimeRead`].
+    PcbFileRead {
+        path: PathBuf,
+        file_modified: Option<SystemTime>,
+        result: FileIoResult,
+    },
     ApplyPcbUnitConfiguration {
         path: PathBuf,
         units: u16,
@@ -644,6 +1473,14 @@ pub enum Event {
         design_sizings: Option<HashMap<DesignName, DesignSizing>>,
         pcb_unit_positionings: Option<HashMap<PcbUnitNumber, PcbUnitPositioning>>,
     },
+    /// Replaces the unit positionings of a PCB with the positionings in a CSV file, e.g. the unit origin table of a
+    /// panel drawing, see [`stores::unit_positionings`].
+    ///
+    /// There must be a positioning for each unit, when the panel size has been set the units must be on the panel.
+    ImportPcbUnitPositionings {
+        path: PathBuf,
+        source: UnitPositioningsSource,
+    },
     ApplyAssemblyOrientation {
         path: PathBuf,
         assembly_orientation: PcbAssemblyOrientation,
@@ -653,6 +1490,8 @@ pub enum Event {
         design: Option<DesignName>,
         // TODO use FileReferences, not paths?
         files: Vec<(PathBuf, Option<GerberFileFunction>)>,
+        #[serde(default)]
+        token: Option<CancellationToken>,
     },
     RemoveGerberFiles {
         path: PathBuf,
@@ -662,11 +1501,47 @@ pub enum Event {
     RefreshGerberFiles {
         path: PathBuf,
         design: Option<DesignName>,
+        #[serde(default)]
+        token: Option<CancellationToken>,
     },
     ApplyGerberFileFunctions {
         path: PathBuf,
         file_functions: Vec<(PathBuf, Option<GerberFileFunction>)>,
     },
+    /// Converts a DXF or SVG outline into a profile gerber, next to the outline file, and adds it to the PCB or design.
+    ///
+    /// An existing profile gerber is not overwritten, see [`gerber_outline::profile_gerber_path`].
+    ImportPcbOutline {
+        path: PathBuf,
+        design: Option<DesignName>,
+        file: PathBuf,
+        #[serde(default)]
+        token: Option<CancellationToken>,
+    },
+    /// The directory of the outline file has been listed by the shell, see [`Event::ImportPcbOutline`].
+    PcbOutlineDirectoryListed {
+        path: PathBuf,
+        design: Option<DesignName>,
+        file: PathBuf,
+        token: Option<CancellationToken>,
+        result: FileIoResult,
+    },
+    /// The outline file has been read by the shell, see [`Event::ImportPcbOutline`].
+    PcbOutlineRead {
+        path: PathBuf,
+        design: Option<DesignName>,
+        file: PathBuf,
+        token: Option<CancellationToken>,
+        result: FileIoResult,
+    },
+    /// The profile gerber has been written by the shell, see [`Event::ImportPcbOutline`].
+    PcbOutlineProfileWritten {
+        path: PathBuf,
+        design: Option<DesignName>,
+        profile_path: PathBuf,
+        token: Option<CancellationToken>,
+        result: FileIoResult,
+    },
 
     //
     // PCB views
@@ -677,6 +1552,9 @@ pub enum Event {
     RequestPcbPanelSizingView {
         path: PathBuf,
     },
+    RequestPcbPanelPreviewView {
+        path: PathBuf,
+    },
 }
 
 impl Planner {
@@ -698,7 +1576,7 @@ fn update_inner(
             } => Box::new(|model: &mut Model| {
                 info!("Creating project. path: {:?}", &path);
 
-                let project_directory = path.parent().unwrap().to_path_buf();
+                let project_directory = parent_directory(&path).to_path_buf();
 
                 let project = Project::new(name, packages, package_mappings);
                 model
@@ -707,20 +1585,136 @@ fn update_inner(
                         path,
                         project_directory,
                         project,
-                        modified: true,
+                        modified: Modified::unsaved(),
+                        placement_session: None,
+                        placement_selections: Default::default(),
+                        recovery: None,
+                        refresh_cache: Default::default(),
                     });
 
                 info!("Created project successfully.");
                 Ok(render::render())
             }),
-            Event::Load {
+            Event::CreateProjectFromTemplate {
+                template,
+                name,
+                path,
+            } => Box::new(move |model: &mut Model| {
+                info!(
+                    "Creating project from template. template: {:?}, path: {:?}",
+                    &template, &path
+                );
+
+                let project_directory = parent_directory(&path).to_path_buf();
+
+                let project_template = template::load_template(&template).map_err(AppError::TemplateError)?;
+                let project = project_template
+                    .build_project(name, &project_directory)
+                    .map_err(AppError::TemplateError)?;
+
+                let load_out_files = project_template.load_out_files(&template, &project_directory);
+
+                let store = Self::open_store(&project, &project_directory)?;
+                for phase in project.phases.values() {
+                    // copied load-outs are ensured after the shell has copied them, see `Event::TemplateLoadOutCopied`
+                    let load_out_path = PathBuf::from(&phase.load_out_source);
+                    if load_out_files
+                        .iter()
+                        .any(|(_template_file, project_file)| project_file.eq(&load_out_path))
+                    {
+                        continue;
+                    }
+                    let load_out_source =
+                        LoadOutSource::from_str(&phase.load_out_source).map_err(AppError::SourceError)?;
+                    store
+                        .ensure_load_out(&load_out_source)
+                        .map_err(AppError::OperationError)?;
+                }
+
+                model
+                    .model_project
+                    .replace(ModelProject {
+                        path,
+                        project_directory,
+                        project,
+                        modified: Modified::unsaved(),
+                        placement_session: None,
+                        placement_selections: Default::default(),
+                        recovery: None,
+                        refresh_cache: Default::default(),
+                    });
+
+                let copy_requests = load_out_files
+                    .into_iter()
+                    .map(|(template_file, project_file)| {
+                        file_io::request(
+                            FileIoOperation::Copy {
+                                from: template_file,
+                                to: project_file.clone(),
+                            },
+                            move |result| Event::TemplateLoadOutCopied {
+                                path: project_file,
+                                result,
+                            },
+                        )
+                    });
+
+                info!("Created project successfully.");
+                Ok(Command::all([render::render(), Command::all(copy_requests)]))
+            }),
+            Event::TemplateLoadOutCopied {
                 path,
+                result,
             } => Box::new(move |model: &mut Model| {
+                let copied = result
+                    .into_copied()
+                    .map_err(AppError::IoError)?;
+                if copied {
+                    info!("Copied load-out. path: {:?}", path);
+                }
+
+                let ModelProject {
+                    project,
+                    project_directory,
+                    ..
+                } = model
+                    .model_project
+                    .as_ref()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let load_out_source =
+                    LoadOutSource::from_str(&path.display().to_string()).map_err(AppError::SourceError)?;
+                Self::open_store(project, project_directory)?
+                    .ensure_load_out(&load_out_source)
+                    .map_err(AppError::OperationError)?;
+
+                Ok(Command::done())
+            }),
+            Event::Load {
+                path,
+            } => Box::new(move |_model: &mut Model| {
                 info!("Load project. path: {:?}", &path);
 
-                let project: Project = file::load(&path).map_err(AppError::IoError)?;
+                Ok(file_io::request(
+                    FileIoOperation::Read {
+                        path: path.clone(),
+                    },
+                    move |result| Event::ProjectFileRead {
+                        path,
+                        result,
+                    },
+                ))
+            }),
+            Event::ProjectFileRead {
+                path,
+                result,
+            } => Box::new(move |model: &mut Model| {
+                let content = result
+                    .into_content()
+                    .map_err(AppError::IoError)?;
+                let project: Project = file::from_bytes(&content).map_err(AppError::IoError)?;
 
-                let project_directory = path.parent().unwrap().to_path_buf();
+                let project_directory = parent_directory(&path).to_path_buf();
 
                 model
                     .model_project
@@ -728,10 +1722,171 @@ fn update_inner(
                         path: path.clone(),
                         project_directory: project_directory.clone(),
                         project,
-                        modified: false,
+                        modified: Modified::default(),
+                        placement_session: None,
+                        placement_selections: Default::default(),
+                        recovery: None,
+                        refresh_cache: Default::default(),
                     });
 
-                model.load_unloaded_project_pcbs(&project_directory)?;
+                let recovery_path = recovery::build_recovery_file_path(&path);
+
+                let pcb_requests = Self::project_pcb_requests(model.model_project.as_ref().unwrap());
+
+                Ok(Command::all([
+                    render::render(),
+                    pcb_requests,
+                    file_io::request(
+                        FileIoOperation::Read {
+                            path: recovery_path.clone(),
+                        },
+                        move |result| Event::RecoveryFileRead {
+                            path: recovery_path,
+                            result,
+                        },
+                    ),
+                ]))
+            }),
+            Event::ExportProjectArchive {
+                path: archive_path,
+            } => Box::new(move |model: &mut Model| {
+                let (
+                    ModelProject {
+                        project,
+                        path,
+                        ..
+                    },
+                    pcbs,
+                    ..,
+                ) = Self::model_project_and_pcbs(model)?;
+
+                archive::export_project_archive(project, path, &pcbs, &archive_path).map_err(AppError::ArchiveError)?;
+
+                Ok(render::render())
+            }),
+            Event::ImportProjectArchive {
+                archive,
+                directory,
+            } => Box::new(move |_model: &mut Model| {
+                info!(
+                    "Import project archive. archive: {:?}, directory: {:?}",
+                    &archive, &directory
+                );
+
+                Ok(file_io::request(
+                    FileIoOperation::Read {
+                        path: archive,
+                    },
+                    move |result| Event::ProjectArchiveRead {
+                        directory,
+                        result,
+                    },
+                ))
+            }),
+            Event::ProjectArchiveRead {
+                directory,
+                result,
+            } => Box::new(move |_model: &mut Model| {
+                let content = result
+                    .into_content()
+                    .map_err(AppError::IoError)?;
+                let ImportedArchive {
+                    project_path,
+                    files,
+                } = archive::import_project_archive(&content, &directory).map_err(AppError::ArchiveError)?;
+
+                let write_requests = files
+                    .into_iter()
+                    .map(|(path, content)| {
+                        file_io::request(
+                            FileIoOperation::Write {
+                                path: path.clone(),
+                                content,
+                            },
+                            move |result| Event::ArchiveFileWritten {
+                                path,
+                                result,
+                            },
+                        )
+                    });
+
+                // the project is loaded once all the files have been written
+                Ok(Command::all(write_requests).then(file_io::request(
+                    FileIoOperation::Read {
+                        path: project_path.clone(),
+                    },
+                    move |result| Event::ProjectFileRead {
+                        path: project_path,
+                        result,
+                    },
+                )))
+            }),
+            Event::ArchiveFileWritten {
+                path,
+                result,
+            } => Box::new(move |_model: &mut Model| {
+                result
+                    .into_written()
+                    .map_err(AppError::IoError)?;
+
+                trace!("Extracted archive file. path: {:?}", path);
+
+                Ok(Command::done())
+            }),
+            Event::RelinkFiles {
+                search_roots,
+                apply,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project,
+                    project_directory,
+                    modified,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let mut dangling_files = relink::find_dangling_project_files(project, project_directory);
+                relink::search(&mut dangling_files, &search_roots).map_err(AppError::IoError)?;
+
+                for dangling_file in dangling_files.iter() {
+                    match (dangling_file.resolution(), apply) {
+                        (Some(file), true) => {
+                            *modified |=
+                                relink::relink_project_file(project, project_directory, &dangling_file.kind, file);
+                        }
+                        _ => info!(
+                            "Dangling file. kind: {:?}, path: {:?}, candidates: {:?}",
+                            dangling_file.kind, dangling_file.path, dangling_file.candidates
+                        ),
+                    }
+                }
+
+                // gerbers can only be found for PCBs that have been loaded, PCBs whose files are missing are not
+                for (pcb_index, project_pcb) in project.pcbs.iter().enumerate() {
+                    let pcb_path = project_pcb
+                        .pcb_file
+                        .build_path(project_directory);
+                    let Some(model_pcb) = model.model_pcbs.get_mut(&pcb_path) else {
+                        continue;
+                    };
+
+                    let mut dangling_gerbers = relink::find_dangling_gerbers(pcb_index, &model_pcb.pcb);
+                    relink::search(&mut dangling_gerbers, &search_roots).map_err(AppError::IoError)?;
+
+                    for dangling_gerber in dangling_gerbers.iter() {
+                        match (dangling_gerber.resolution(), apply) {
+                            (Some(file), true) => {
+                                model_pcb.modified |= relink::relink_gerber(&mut model_pcb.pcb, dangling_gerber, file);
+                            }
+                            _ => info!(
+                                "Dangling file. kind: {:?}, path: {:?}, candidates: {:?}",
+                                dangling_gerber.kind, dangling_gerber.path, dangling_gerber.candidates
+                            ),
+                        }
+                    }
+                }
 
                 Ok(render::render())
             }),
@@ -748,13 +1903,206 @@ fn update_inner(
 
                 info!("Save project. path: {:?}", &path);
 
-                file::save(project, path).map_err(AppError::IoError)?;
+                let content = file::to_bytes_with_format(project, project.file_format).map_err(AppError::IoError)?;
+                let path = path.clone();
+                let revision = modified.revision();
+
+                Ok(file_io::request(
+                    FileIoOperation::Write {
+                        path: path.clone(),
+                        content,
+                    },
+                    move |result| Event::ProjectFileWritten {
+                        path,
+                        result,
+                        revision,
+                    },
+                ))
+            }),
+            Event::ProjectFileWritten {
+                path,
+                result,
+                revision,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    modified,
+                    recovery: model_project_recovery,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                result
+                    .into_written()
+                    .map_err(AppError::IoError)?;
 
                 info!("Saved project. path: {:?}", path);
-                *modified = false;
+                if !modified.saved(revision) {
+                    info!("Project changed while saving, keeping the unsaved changes and any recovery snapshot.");
+                    return Ok(render::render());
+                }
+
+                // the saved project supersedes any recovery snapshot
+                let recovery_path = recovery::build_recovery_file_path(&path);
+                model_project_recovery.take();
+
+                Ok(Command::all([
+                    render::render(),
+                    Self::remove_recovery_file(recovery_path),
+                ]))
+            }),
+            Event::Autosave => Box::new(|model: &mut Model| {
+                let Some(model_project) = model.model_project.as_ref() else {
+                    return Ok(Command::done());
+                };
+
+                if model_project.recovery.is_some() {
+                    trace!("Not autosaving, the pending recovery snapshot would be overwritten.");
+                    return Ok(Command::done());
+                }
+
+                let pcbs = model
+                    .model_pcbs
+                    .iter()
+                    .filter(|(_path, model_pcb)| model_pcb.modified.is_modified())
+                    .map(|(path, model_pcb)| RecoveryPcb {
+                        path: path.clone(),
+                        pcb: model_pcb.pcb.clone(),
+                    })
+                    .collect::<Vec<_>>();
+
+                if !model_project.modified.is_modified() && pcbs.is_empty() {
+                    trace!("Nothing to autosave.");
+                    return Ok(Command::done());
+                }
+
+                let snapshot = RecoverySnapshot {
+                    taken: OffsetDateTime::now_utc(),
+                    project: model_project.project.clone(),
+                    pcbs,
+                };
+
+                let path = recovery::build_recovery_file_path(&model_project.path);
+                info!("Autosave. path: {:?}", path);
+
+                let content = file::to_bytes(&snapshot).map_err(AppError::IoError)?;
+
+                Ok(file_io::request(
+                    FileIoOperation::Write {
+                        path: path.clone(),
+                        content,
+                    },
+                    move |result| Event::AutosaveWritten {
+                        path,
+                        result,
+                    },
+                ))
+            }),
+            Event::AutosaveWritten {
+                path,
+                result,
+            } => Box::new(move |_model: &mut Model| {
+                result
+                    .into_written()
+                    .map_err(AppError::IoError)?;
+
+                info!("Autosaved. path: {:?}", path);
+
+                Ok(Command::done())
+            }),
+            Event::RecoveryFileRead {
+                path,
+                result,
+            } => Box::new(move |model: &mut Model| {
+                let Ok(content) = result.into_content() else {
+                    trace!("No recovery file. path: {:?}", path);
+                    return Ok(Command::done());
+                };
+
+                let snapshot = match file::from_bytes::<RecoverySnapshot>(&content) {
+                    Ok(snapshot) => snapshot,
+                    Err(cause) => {
+                        push_warning(&mut model.warnings, AppWarning::UnreadableRecoveryFile {
+                            path,
+                            cause: cause.to_string(),
+                        });
+                        return Ok(Command::done());
+                    }
+                };
+
+                let model_project = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                info!("Found recovery file. path: {:?}, taken: {}", path, snapshot.taken);
+                model_project.recovery.replace(snapshot);
+
+                Ok(render::render())
+            }),
+            Event::RestoreRecovery => Box::new(|model: &mut Model| {
+                let model_project = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let RecoverySnapshot {
+                    taken,
+                    project,
+                    pcbs,
+                } = model_project
+                    .recovery
+                    .take()
+                    .ok_or(AppError::OperationError(anyhow!("No recovery snapshot")))?;
+
+                info!("Restoring recovery snapshot. taken: {}", taken);
+
+                model_project.project = project;
+                model_project.refresh_cache = Default::default();
+                model_project.modified |= true;
+
+                for RecoveryPcb {
+                    path,
+                    pcb,
+                } in pcbs
+                {
+                    model.model_pcbs.insert(path, ModelPcb {
+                        pcb,
+                        modified: Modified::unsaved(),
+                        file_modified: None,
+                    });
+                }
 
                 Ok(render::render())
             }),
+            Event::DiscardRecovery => Box::new(|model: &mut Model| {
+                let model_project = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                model_project.recovery.take();
+
+                let recovery_path = recovery::build_recovery_file_path(&model_project.path);
+
+                Ok(Command::all([
+                    render::render(),
+                    Self::remove_recovery_file(recovery_path),
+                ]))
+            }),
+            Event::RecoveryFileRemoved {
+                path,
+                result,
+            } => Box::new(move |_model: &mut Model| {
+                result
+                    .into_removed()
+                    .map_err(AppError::IoError)?;
+
+                trace!("Removed recovery file. path: {:?}", path);
+
+                Ok(Command::done())
+            }),
             Event::CreateProjectPcb {
                 name,
                 units,
@@ -772,9 +2120,9 @@ fn update_inner(
                 let mut pcb_path = project_directory.to_path_buf();
                 pcb_path.push(pcb_file_name.clone());
 
-                Self::create_and_add_pcb(name, units, unit_map, model, &pcb_path)?;
+                let command = Self::create_and_add_pcb(name, units, unit_map, model, &pcb_path)?;
 
-                Ok(render::render())
+                Ok(command.then(render::render()))
             }),
             Event::CreatePcb {
                 path: pcb_path,
@@ -782,25 +2130,100 @@ fn update_inner(
                 units,
                 unit_map,
             } => Box::new(move |model: &mut Model| {
-                Self::create_and_add_pcb(name, units, unit_map.unwrap_or_default(), model, &pcb_path)?;
+                let command = Self::create_and_add_pcb(name, units, unit_map.unwrap_or_default(), model, &pcb_path)?;
 
-                Ok(render::render())
+                Ok(command.then(render::render()))
             }),
-            Event::ApplyPcbUnitConfiguration {
-                path: pcb_path,
-                units,
-                gerber_offset: placement_offset,
-                designs,
-                unit_map,
+            Event::ClonePcb {
+                source_path,
+                new_name,
             } => Box::new(move |model: &mut Model| {
-                let ModelPcb {
-                    modified,
-                    pcb,
-                    ..
-                } = model
+                model.model_pcb(&source_path)?;
+
+                let directory = source_path
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default();
+                let pcb_path = directory.join(format!("{}.pcb.json", new_name));
+
+                if model.model_pcbs.contains_key(&pcb_path) {
+                    return Err(AppError::PcbOperationError(PcbOperationError::PcbFileExists(pcb_path)));
+                }
+
+                // a bare file name is relative to the current directory
+                let directory = match directory.as_os_str().is_empty() {
+                    true => PathBuf::from("."),
+                    false => directory,
+                };
+
+                Ok(file_io::request(
+                    FileIoOperation::List {
+                        directory,
+                    },
+                    move |result| Event::PcbCloneDirectoryListed {
+                        source_path,
+                        pcb_path,
+                        new_name,
+                        result,
+                    },
+                ))
+            }),
+            Event::PcbCloneDirectoryListed {
+                source_path,
+                pcb_path,
+                new_name,
+                result,
+            } => Box::new(move |model: &mut Model| {
+                let entries = result
+                    .into_entries()
+                    .map_err(AppError::IoError)?;
+
+                // the entries are prefixed with the listed directory, e.g. `./`, so only the file names are compared
+                if entries
+                    .iter()
+                    .any(|entry| entry.file_name() == pcb_path.file_name())
+                    || model.model_pcbs.contains_key(&pcb_path)
+                {
+                    return Err(AppError::PcbOperationError(PcbOperationError::PcbFileExists(pcb_path)));
+                }
+
+                let mut pcb = model
+                    .model_pcb(&source_path)?
+                    .pcb
+                    .clone();
+
+                info!(
+                    "Cloning PCB. source_path: {:?}, pcb_path: {:?}, name: {}",
+                    source_path, pcb_path, new_name
+                );
+                pcb.name = new_name;
+
+                let modified = Modified::unsaved();
+                let command = Self::write_pcb_file(pcb_path.clone(), &pcb, modified.revision())?;
+
+                model
                     .model_pcbs
-                    .get_mut(&pcb_path)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded))?;
+                    .insert(pcb_path, ModelPcb {
+                        pcb,
+                        // not saved, yet
+                        modified,
+                        file_modified: None,
+                    });
+
+                Ok(command.then(render::render()))
+            }),
+            Event::ApplyPcbUnitConfiguration {
+                path: pcb_path,
+                units,
+                gerber_offset: placement_offset,
+                designs,
+                unit_map,
+            } => Box::new(move |model: &mut Model| {
+                let ModelPcb {
+                    modified,
+                    pcb,
+                    ..
+                } = model.model_pcb(&pcb_path)?;
 
                 info!(
                     "Applying PCB unit configuration. pcb_path: {:?}, units: {:?}, designs: {:?}, unit_map: {:?}",
@@ -872,7 +2295,7 @@ fn update_inner(
                 pcb.panel_sizing
                     .ensure_unit_positionings(units);
 
-                *modified = true;
+                *modified |= true;
 
                 // Once a PCB has been modified, any project using it needs to re-load it and handle inconsistencies.
                 Ok(render::render())
@@ -885,10 +2308,7 @@ fn update_inner(
                     modified,
                     pcb,
                     ..
-                } = model
-                    .model_pcbs
-                    .get_mut(&pcb_path)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded))?;
+                } = model.model_pcb(&pcb_path)?;
 
                 info!(
                     "Applying panel sizing. pcb_path: {:?}, panel_sizing: {:?}",
@@ -919,7 +2339,7 @@ fn update_inner(
 
                 pcb.panel_sizing = panel_sizing;
 
-                *modified = true;
+                *modified |= true;
 
                 // Once a PCB has been modified, any project using it needs to re-load it and handle inconsistencies.
                 Ok(render::render())
@@ -936,10 +2356,7 @@ fn update_inner(
                     modified,
                     pcb,
                     ..
-                } = model
-                    .model_pcbs
-                    .get_mut(&pcb_path)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded))?;
+                } = model.model_pcb(&pcb_path)?;
 
                 info!(
                     "Applying partial panel sizing. pcb_path: {:?}, edge_rails: {:?}, size: {:?}, fiducials: {:?}, design_sizings: {:?}, pcb_unit_positionings: {:?}",
@@ -971,50 +2388,95 @@ fn update_inner(
                         .collect::<Result<Vec<_>, _>>()?;
 
                     pcb.panel_sizing.design_sizings = design_sizings;
-                    *modified = true;
+                    *modified |= true;
                 }
 
-                if let Some(mut pcb_unit_positionings) = pcb_unit_positionings {
-                    let pcb_unit_count = pcb_unit_positionings.len() as u16;
-                    let expected_pcb_unit_count = pcb.units;
-                    if pcb_unit_count != expected_pcb_unit_count {
-                        return Err(AppError::PcbOperationError(
-                            PcbOperationError::UnitSizingCountMismatch {
-                                expected: expected_pcb_unit_count,
-                                actual: pcb_unit_count,
-                            },
-                        ));
-                    }
-
-                    let pcb_unit_positionings = (1..=pcb_unit_count)
-                        .map(|pcb_unit_index| {
-                            pcb_unit_positionings
-                                .remove(&pcb_unit_index)
-                                .ok_or(AppError::PcbOperationError(
-                                    PcbOperationError::MissingPcbUnitPositioning(pcb_unit_index),
-                                ))
-                        })
-                        .collect::<Result<Vec<_>, _>>()?;
-
-                    pcb.panel_sizing.pcb_unit_positionings = pcb_unit_positionings;
-                    *modified = true;
+                if let Some(pcb_unit_positionings) = pcb_unit_positionings {
+                    pcb.panel_sizing.pcb_unit_positionings =
+                        Self::ordered_pcb_unit_positionings(pcb, pcb_unit_positionings)?;
+                    *modified |= true;
                 }
 
                 if let Some(edge_rails) = edge_rails {
                     pcb.panel_sizing.edge_rails = edge_rails;
-                    *modified = true;
+                    *modified |= true;
                 }
 
                 if let Some(size) = size {
                     pcb.panel_sizing.size = size;
-                    *modified = true;
+                    *modified |= true;
                 }
 
                 if let Some(fiducials) = fiducials {
                     pcb.panel_sizing.fiducials = fiducials;
-                    *modified = true;
+                    *modified |= true;
+                }
+
+                // Once a PCB has been modified, any project using it needs to re-load it and handle inconsistencies.
+                Ok(render::render())
+            }),
+            Event::ImportPcbUnitPositionings {
+                path: pcb_path,
+                source,
+            } => Box::new(move |model: &mut Model| {
+                let ModelPcb {
+                    modified,
+                    pcb,
+                    ..
+                } = model.model_pcb(&pcb_path)?;
+
+                info!(
+                    "Importing unit positionings. pcb_path: {:?}, source: {:?}",
+                    pcb_path, source
+                );
+
+                let records = stores::unit_positionings::load(&source).map_err(|cause| {
+                    AppError::PcbOperationError(PcbOperationError::UnableToLoadUnitPositionings(cause))
+                })?;
+
+                let pcb_unit_positionings = records
+                    .into_values()
+                    .map(|record| {
+                        let (Some(x), Some(y)) = (record.x.to_f64(), record.y.to_f64()) else {
+                            return Err(AppError::PcbOperationError(
+                                PcbOperationError::UnableToLoadUnitPositionings(anyhow!(
+                                    "Unit offset is not representable. record: {:?}",
+                                    record
+                                )),
+                            ));
+                        };
+
+                        Ok((record.unit, PcbUnitPositioning {
+                            offset: Vector2::new(x, y),
+                            rotation: record.rotation,
+                        }))
+                    })
+                    .collect::<Result<HashMap<_, _>, _>>()?;
+
+                let pcb_unit_positionings = Self::ordered_pcb_unit_positionings(pcb, pcb_unit_positionings)?;
+
+                // a panel size of zero has not been set yet
+                let size = pcb.panel_sizing.size;
+                if size.x > 0.0 && size.y > 0.0 {
+                    if let Some((index, positioning)) = pcb_unit_positionings
+                        .iter()
+                        .enumerate()
+                        .find(|(_index, positioning)| {
+                            let offset = positioning.offset;
+                            offset.x < 0.0 || offset.y < 0.0 || offset.x > size.x || offset.y > size.y
+                        })
+                    {
+                        return Err(AppError::PcbOperationError(PcbOperationError::PcbUnitOutsidePanel {
+                            unit: index as PcbUnitNumber + 1,
+                            x: positioning.offset.x,
+                            y: positioning.offset.y,
+                        }));
+                    }
                 }
 
+                pcb.panel_sizing.pcb_unit_positionings = pcb_unit_positionings;
+                *modified |= true;
+
                 // Once a PCB has been modified, any project using it needs to re-load it and handle inconsistencies.
                 Ok(render::render())
             }),
@@ -1026,10 +2488,7 @@ fn update_inner(
                     modified,
                     pcb,
                     ..
-                } = model
-                    .model_pcbs
-                    .get_mut(&pcb_path)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded))?;
+                } = model.model_pcb(&pcb_path)?;
 
                 info!(
                     "Applying assembly orientation. pcb_path: {:?}, assembly_orientation: {:?}",
@@ -1037,7 +2496,7 @@ fn update_inner(
                 );
 
                 pcb.orientation = assembly_orientation;
-                *modified = true;
+                *modified |= true;
 
                 Ok(render::render())
             }),
@@ -1047,9 +2506,7 @@ fn update_inner(
                 // Note: doesn't require a project.
                 info!("Load PCB. path: {:?}", &path);
 
-                model.load_pcb(&path)?;
-
-                Ok(render::render())
+                Ok(Self::pcb_file_modified_time_request(path, true))
             }),
             Event::SavePcb {
                 path,
@@ -1057,43 +2514,125 @@ fn update_inner(
                 // Note: doesn't require a project.
                 info!("Save PCB. path: {:?}", &path);
 
-                model.save_pcb(&path)?;
+                let model_pcb = model
+                    .model_pcbs
+                    .get(&path)
+                    .ok_or(AppError::OperationError(anyhow!("PCB not loaded. path: {:?}", path)))?;
+
+                let command = Self::write_pcb_file(path, &model_pcb.pcb, model_pcb.modified.revision())?;
+
+                Ok(command.then(render::render()))
+            }),
+            Event::PcbFileWritten {
+                path,
+                result,
+                revision,
+            } => Box::new(move |model: &mut Model| {
+                result
+                    .into_written()
+                    .map_err(AppError::IoError)?;
 
                 info!("Saved PCB. path: {:?}", path);
 
-                Ok(render::render())
+                // the modification time of the written file is kept when it has been read by the shell
+                if let Some(model_pcb) = model.model_pcbs.get_mut(&path) {
+                    if !model_pcb.modified.saved(revision) {
+                        info!(
+                            "PCB changed while saving, keeping the unsaved changes. path: {:?}",
+                            path
+                        );
+                    }
+                    model_pcb.file_modified = None;
+                }
+
+                Ok(Self::pcb_file_modified_time_request(path, false))
             }),
-            Event::RefreshPcbs => Box::new(move |model: &mut Model| {
-                info!("Refreshing PCBs");
+            Event::PcbFileModifiedTimeRead {
+                path,
+                reload,
+                result,
+            } => Box::new(move |model: &mut Model| {
+                let file_modified = result
+                    .into_modified()
+                    .map_err(AppError::IoError)?;
+
+                match model.model_pcbs.get_mut(&path) {
+                    Some(model_pcb) if !reload && !model_pcb.is_stale(file_modified) => {
+                        if !model_pcb.modified.is_modified() {
+                            model_pcb.file_modified = file_modified;
+                        }
 
-                let paths = model
-                    .model_pcbs
-                    .keys()
-                    .cloned()
-                    .collect::<Vec<_>>();
+                        Ok(Command::done())
+                    }
+                    _ => {
+                        info!("Loading PCB. path: {:?}", path);
 
-                for path in paths.into_iter() {
-                    model.load_pcb(&path)?;
+                        Ok(file_io::request(
+                            FileIoOperation::Read {
+                                path: path.clone(),
+                            },
+                            move |result| Event::PcbFileRead {
+                                path,
+                                file_modified,
+                                result,
+                            },
+                        ))
+                    }
                 }
+            }),
+            Event::PcbFileRead {
+                path,
+                file_modified,
+                result,
+            } => Box::new(move |model: &mut Model| {
+                let content = result
+                    .into_content()
+                    .map_err(AppError::IoError)?;
+                let pcb = pcb::parse_pcb(&content).map_err(AppError::IoError)?;
+
+                info!("Loaded PCB. path: {:?}", path);
+
+                model.model_pcbs.insert(path, ModelPcb {
+                    pcb,
+                    modified: Modified::default(),
+                    file_modified,
+                });
 
                 Ok(render::render())
             }),
-            Event::SaveAllPcbs => Box::new(|model: &mut Model| {
-                for (path, model_pcb) in model.model_pcbs.iter_mut() {
-                    let ModelPcb {
-                        pcb,
-                        modified,
-                    } = model_pcb;
+            Event::RefreshPcbs {
+                token,
+            } => Box::new(move |model: &mut Model| {
+                info!("Refreshing PCBs");
+                model
+                    .operation_token(token)
+                    .check()
+                    .map_err(AppError::Cancelled)?;
 
-                    info!("Save PCB. path: {:?}", path);
+                // unmodified PCBs whose file hasn't changed are already up-to-date
+                let requests = model
+                    .model_pcbs
+                    .iter()
+                    .map(|(path, model_pcb)| {
+                        Self::pcb_file_modified_time_request(path.clone(), model_pcb.modified.is_modified())
+                    })
+                    .collect::<Vec<_>>();
 
-                    file::save(pcb, &path).map_err(AppError::IoError)?;
+                Ok(Command::all(requests).then(render::render()))
+            }),
+            Event::SaveAllPcbs => Box::new(|model: &mut Model| {
+                let commands = model
+                    .model_pcbs
+                    .iter()
+                    .map(|(path, model_pcb)| {
+                        info!("Save PCB. path: {:?}", path);
 
-                    info!("Saved PCB. path: {:?}", path);
-                    *modified = false;
-                }
+                        Self::write_pcb_file(path.clone(), &model_pcb.pcb, model_pcb.modified.revision())
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
 
-                Ok(render::render())
+                // render once all the PCBs have been written, so the shell sees the final modified state
+                Ok(Command::all(commands).then(render::render()))
             }),
             Event::AddPcb {
                 pcb_file,
@@ -1108,26 +2647,22 @@ fn update_inner(
                     .as_mut()
                     .ok_or(AppError::OperationRequiresProject)?;
 
-                let project_directory = path.parent().unwrap();
+                let project_directory = parent_directory(&path);
                 let pcb_path = pcb_file.build_path(&project_directory.to_path_buf());
 
-                let pcb = pcb::load_pcb(&pcb_path).map_err(AppError::IoError)?;
-
                 project::add_pcb(project, &pcb_file).map_err(AppError::PcbOperationError)?;
 
-                model
-                    .model_pcbs
-                    .insert(pcb_path, ModelPcb {
-                        pcb,
-                        modified: false,
-                    });
-
                 *modified |= true;
 
-                Ok(render::render())
+                // the PCB file is read by the shell, unless the PCB is already loaded
+                Ok(Command::all([
+                    render::render(),
+                    Self::pcb_file_modified_time_request(pcb_path, false),
+                ]))
             }),
             Event::RemovePcb {
                 index,
+                strategy,
             } => Box::new(move |model: &mut Model| {
                 let ModelProject {
                     project,
@@ -1139,11 +2674,13 @@ fn update_inner(
                     .as_mut()
                     .ok_or(AppError::OperationRequiresProject)?;
 
-                let pcb_path = project::remove_pcb(project, index, path).map_err(AppError::PcbOperationError)?;
+                let (pcb_path, report) =
+                    project::remove_pcb(project, index, path, strategy).map_err(AppError::PcbOperationError)?;
 
                 model.model_pcbs.remove(&pcb_path);
 
                 *modified |= true;
+                model.delete_report = Some(report);
 
                 Ok(render::render())
             }),
@@ -1224,6 +2761,10 @@ enum ApplyMode {
                     }
                 }
 
+                process_definition
+                    .ensure_tasks_defined()
+                    .map_err(AppError::ProcessError)?;
+
                 project
                     .ensure_process_not_in_progress(&process_reference)
                     .map_err(AppError::ProcessError)?;
@@ -1273,7 +2814,7 @@ enum ApplyMode {
                     }
                 }
 
-                *modified = true;
+                *modified |= true;
 
                 Ok(render::render())
             }),
@@ -1299,7 +2840,28 @@ enum ApplyMode {
                     .delete_process(&process_reference)
                     .map_err(AppError::ProcessError)?;
 
-                *modified = true;
+                *modified |= true;
+
+                Ok(render::render())
+            }),
+            Event::CloneProcess {
+                process_reference,
+                new_reference,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project,
+                    modified,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                project
+                    .clone_process(&process_reference, new_reference)
+                    .map_err(AppError::ProcessError)?;
+
+                *modified |= true;
 
                 Ok(render::render())
             }),
@@ -1343,11 +2905,115 @@ enum ApplyMode {
                     project
                         .library_config
                         .package_mappings_source = package_mappings;
-                    *modified = true;
+                    *modified |= true;
+                }
+
+                Ok(render::render())
+            }),
+            Event::ImportPartAliases {
+                parts_source,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project,
+                    modified,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let part_aliases = stores::parts::load_part_aliases(&parts_source)
+                    .map_err(|error| AppError::OperationError(anyhow!("parts source error. cause: {:?}", error)))?;
+
+                if project.part_aliases != part_aliases {
+                    info!("Imported part aliases. groups: {}", part_aliases.groups().count());
+                    project.part_aliases = part_aliases;
+                    *modified |= true;
+                }
+
+                Ok(render::render())
+            }),
+            Event::ImportMoistureSensitivities {
+                parts_source,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project,
+                    modified,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let sensitivities = stores::parts::load_part_moisture_sensitivities(&parts_source)
+                    .map_err(|error| AppError::OperationError(anyhow!("parts source error. cause: {:?}", error)))?;
+
+                let mut unknown_parts = vec![];
+                for (part, moisture_sensitivity) in sensitivities {
+                    if !project.part_states.contains_key(&part) {
+                        unknown_parts.push(part);
+                        continue;
+                    }
+                    *modified |= project::update_moisture_sensitivity(project, vec![part], Some(moisture_sensitivity));
+                }
+
+                if !unknown_parts.is_empty() {
+                    push_warning(&mut model.warnings, AppWarning::UnknownParts {
+                        parts: unknown_parts,
+                    });
                 }
 
                 Ok(render::render())
             }),
+            Event::ImportCsvIntoStore {
+                parts_source,
+            } => Box::new(move |model: &mut Model| {
+                let (
+                    ModelProject {
+                        project, ..
+                    },
+                    pcbs,
+                    directory,
+                ) = Self::model_project_and_pcbs(model)?;
+
+                let placements = project
+                    .unique_design_variants(&pcbs)
+                    .iter()
+                    .map(|design_variant| {
+                        PlacementsSource::File(stores::placements::build_placements_path(&directory, design_variant))
+                    })
+                    .collect();
+
+                // remote load-outs are not stored in the backend
+                let load_outs = project
+                    .phases
+                    .values()
+                    .filter(|phase| !LoadOutSource::is_url(&phase.load_out_source))
+                    .map(|phase| {
+                        let file_source = try_build_phase_load_out_source(&directory, phase, &StoreBackend::Csv)?;
+                        let source = try_build_phase_load_out_source(&directory, phase, &project.store_backend)?;
+                        Ok((file_source, source))
+                    })
+                    .collect::<Result<Vec<_>, SourceError>>()
+                    .map_err(AppError::SourceError)?;
+
+                let import = CsvImport {
+                    parts: parts_source.into_iter().collect(),
+                    load_outs,
+                    placements,
+                };
+                info!(
+                    "Importing CSV files into the store. parts: {}, load-outs: {}, placements: {}",
+                    import.parts.len(),
+                    import.load_outs.len(),
+                    import.placements.len()
+                );
+
+                stores::backend::import_csv(&project.store_backend, &directory, &import)
+                    .map_err(AppError::OperationError)?;
+
+                Ok(render::render())
+            }),
             Event::AssignVariantToUnit {
                 variant: variant_name,
                 unit,
@@ -1357,6 +3023,7 @@ enum ApplyMode {
                         project,
                         path,
                         modified,
+                        refresh_cache,
                         ..
                     },
                     pcbs,
@@ -1368,24 +3035,33 @@ enum ApplyMode {
                     .map_err(AppError::OperationError)?;
                 *modified |= true;
 
-                let refresh_result = Self::refresh_project(project, &pcbs, path).map_err(AppError::ProjectError)?;
-                *modified |= refresh_result;
+                let refresh_result =
+                    Self::refresh_project(project, &pcbs, path, refresh_cache, &CancellationToken::default())?;
+                *modified |= refresh_result.modified;
+
+                Self::report_refresh(model, refresh_result);
 
                 Ok(render::render())
             }),
-            Event::RefreshFromDesignVariants => Box::new(|model: &mut Model| {
+            Event::RefreshFromDesignVariants {
+                token,
+            } => Box::new(move |model: &mut Model| {
+                let cancellation = model.operation_token(token);
                 let (
                     ModelProject {
                         project,
                         path,
                         modified,
+                        refresh_cache,
                         ..
                     },
                     pcbs,
                     ..,
                 ) = { Self::model_project_and_pcbs(model) }?;
-                let refresh_result = Self::refresh_project(project, &pcbs, path).map_err(AppError::ProjectError)?;
-                *modified |= refresh_result;
+                let refresh_result = Self::refresh_project(project, &pcbs, path, refresh_cache, &cancellation)?;
+                *modified |= refresh_result.modified;
+
+                Self::report_refresh(model, refresh_result);
 
                 Ok(render::render())
             }),
@@ -1420,11 +3096,10 @@ enum ApplyMode {
 
                 Ok(render::render())
             }),
-            Event::CreatePhase {
-                process: process_reference,
-                reference,
-                load_out,
-                pcb_side,
+            Event::SetPartMoistureSensitivity {
+                manufacturer: manufacturer_pattern,
+                mpn: mpn_pattern,
+                moisture_sensitivity,
             } => Box::new(move |model: &mut Model| {
                 let ModelProject {
                     project,
@@ -1435,23 +3110,21 @@ enum ApplyMode {
                     .as_mut()
                     .ok_or(AppError::OperationRequiresProject)?;
 
-                let process = project
-                    .find_process(&process_reference)
-                    .map_err(AppError::ProcessError)?
-                    .clone();
-
-                *modified |= true;
+                let unique_parts = Self::unique_parts(project)
+                    .into_iter()
+                    .collect::<Vec<_>>();
 
-                stores::load_out::ensure_load_out(&load_out).map_err(AppError::OperationError)?;
+                let parts_to_modify =
+                    project::find_parts_to_modify(project, unique_parts.as_slice(), manufacturer_pattern, mpn_pattern);
 
-                project
-                    .update_phase(reference, process.reference.clone(), load_out.to_string(), pcb_side)
-                    .map_err(AppError::PhaseError)?;
+                *modified |= project::update_moisture_sensitivity(project, parts_to_modify, moisture_sensitivity);
 
                 Ok(render::render())
             }),
-            Event::DeletePhase {
-                reference,
+            Event::RecordPartMoistureEvent {
+                manufacturer: manufacturer_pattern,
+                mpn: mpn_pattern,
+                event,
             } => Box::new(move |model: &mut Model| {
                 let ModelProject {
                     project,
@@ -1462,11 +3135,181 @@ enum ApplyMode {
                     .as_mut()
                     .ok_or(AppError::OperationRequiresProject)?;
 
-                project
-                    .delete_phase(reference)
-                    .map_err(AppError::PhaseError)?;
+                let unique_parts = Self::unique_parts(project)
+                    .into_iter()
+                    .collect::<Vec<_>>();
 
-                *modified |= true;
+                let parts_to_modify =
+                    project::find_parts_to_modify(project, unique_parts.as_slice(), manufacturer_pattern, mpn_pattern);
+
+                *modified |= project::record_moisture_event(project, parts_to_modify, event, OffsetDateTime::now_utc());
+
+                Ok(render::render())
+            }),
+            Event::CreatePhase {
+                process: process_reference,
+                reference,
+                load_out,
+                pcb_side,
+            } => Box::new(move |model: &mut Model| {
+                let (
+                    ModelProject {
+                        project,
+                        modified,
+                        ..
+                    },
+                    directory,
+                ) = Self::model_project_and_directory(model)?;
+
+                let process = project
+                    .find_process(&process_reference)
+                    .map_err(AppError::ProcessError)?
+                    .clone();
+
+                *modified |= true;
+
+                Self::open_store(project, &directory)?
+                    .ensure_load_out(&load_out)
+                    .map_err(AppError::OperationError)?;
+
+                project
+                    .update_phase(reference, process.reference.clone(), load_out.to_string(), pcb_side)
+                    .map_err(AppError::PhaseError)?;
+
+                Ok(render::render())
+            }),
+            Event::DeletePhase {
+                reference,
+                strategy,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project,
+                    modified,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let report = project
+                    .delete_phase(reference, strategy)
+                    .map_err(AppError::PhaseError)?;
+
+                *modified |= true;
+                model.delete_report = Some(report);
+
+                Ok(render::render())
+            }),
+            Event::ClonePhase {
+                phase_reference,
+                new_reference,
+            } => Box::new(move |model: &mut Model| {
+                let (
+                    ModelProject {
+                        project,
+                        modified,
+                        ..
+                    },
+                    directory,
+                ) = Self::model_project_and_directory(model)?;
+
+                // the load-out is copied first, so the project is unchanged if the copy fails
+                let cloned_load_out_source = project
+                    .cloned_phase_load_out_source(&phase_reference, &new_reference)
+                    .map_err(AppError::PhaseError)?;
+
+                // Safety: the phase exists, see above
+                let phase = &project.phases[&phase_reference];
+                let load_out_source = try_build_phase_load_out_source(&directory, phase, &project.store_backend)
+                    .map_err(AppError::SourceError)?;
+                let cloned_phase = Phase {
+                    load_out_source: cloned_load_out_source,
+                    ..phase.clone()
+                };
+                let cloned_load_out_source =
+                    try_build_phase_load_out_source(&directory, &cloned_phase, &project.store_backend)
+                        .map_err(AppError::SourceError)?;
+
+                let store = Self::open_store(project, &directory)?;
+                // another phase, or another project, may use the load-out
+                if store
+                    .load_out_exists(&cloned_load_out_source)
+                    .map_err(AppError::OperationError)?
+                {
+                    return Err(AppError::OperationError(anyhow!(
+                        "Load-out already exists. source: '{}'",
+                        cloned_load_out_source
+                    )));
+                }
+
+                let load_out_items = store
+                    .load_items(&load_out_source)
+                    .map_err(AppError::OperationError)?;
+
+                store
+                    .ensure_load_out(&cloned_load_out_source)
+                    .map_err(AppError::OperationError)?;
+                store
+                    .store_items(&cloned_load_out_source, &load_out_items)
+                    .map_err(AppError::OperationError)?;
+
+                project
+                    .clone_phase(&phase_reference, new_reference.clone())
+                    .map_err(AppError::PhaseError)?;
+
+                *modified |= true;
+
+                Ok(render::render())
+            }),
+            Event::SplitPhaseByFeederCapacity {
+                phase: phase_reference,
+            } => Box::new(move |model: &mut Model| {
+                let (
+                    ModelProject {
+                        project,
+                        modified,
+                        ..
+                    },
+                    directory,
+                ) = Self::model_project_and_directory(model)?;
+
+                let phase = project
+                    .phases
+                    .get(&phase_reference)
+                    .ok_or(AppError::UnknownPhaseReference(phase_reference.clone()))?;
+
+                let load_out_source = try_build_phase_load_out_source(&directory, phase, &project.store_backend)
+                    .map_err(AppError::SourceError)?;
+
+                let store = Self::open_store(project, &directory)?;
+                let load_out_items = store
+                    .load_items(&load_out_source)
+                    .map_err(AppError::OperationError)?;
+
+                let phase_capacity = capacity::analyze_phase_capacity(project, &phase_reference, &load_out_items)
+                    .map_err(|cause| AppError::OperationError(cause.into()))?;
+
+                let sub_phases = capacity::split_phase(project, &phase_capacity, &load_out_items)
+                    .map_err(|cause| AppError::OperationError(cause.into()))?;
+
+                *modified |= true;
+
+                for sub_phase in sub_phases {
+                    // Safety: the sub-phase was just created
+                    let phase = project
+                        .phases
+                        .get(&sub_phase.reference)
+                        .unwrap();
+                    let load_out_source = try_build_phase_load_out_source(&directory, phase, &project.store_backend)
+                        .map_err(AppError::SourceError)?;
+
+                    store
+                        .ensure_load_out(&load_out_source)
+                        .map_err(AppError::OperationError)?;
+                    store
+                        .store_items(&load_out_source, &sub_phase.load_out_items)
+                        .map_err(AppError::OperationError)?;
+                }
 
                 Ok(render::render())
             }),
@@ -1490,7 +3333,7 @@ fn are_sets_equal_in_order<T: PartialEq>(a: &IndexSet<T>, b: &IndexSet<T>) -> bo
 
                 if !are_sets_equal_in_order(&project.phase_orderings, &new_phase_orderings) {
                     project.phase_orderings = new_phase_orderings;
-                    *modified = true;
+                    *modified |= true;
                 }
 
                 Ok(render::render())
@@ -1498,13 +3341,16 @@ fn are_sets_equal_in_order<T: PartialEq>(a: &IndexSet<T>, b: &IndexSet<T>) -> bo
             Event::AssignPlacementsToPhase {
                 phase: phase_reference,
                 operation,
-                placements: placements_pattern,
+                placements,
+                force,
             } => Box::new(move |model: &mut Model| {
                 let (
                     ModelProject {
                         project,
                         path,
                         modified,
+                        refresh_cache,
+                        placement_selections,
                         ..
                     },
                     pcbs,
@@ -1512,8 +3358,9 @@ fn are_sets_equal_in_order<T: PartialEq>(a: &IndexSet<T>, b: &IndexSet<T>) -> bo
                     ..,
                 ) = { Self::model_project_and_pcbs(model) }?;
 
-                let refresh_result = Self::refresh_project(project, &pcbs, path).map_err(AppError::ProjectError)?;
-                *modified |= refresh_result;
+                let refresh_result =
+                    Self::refresh_project(project, &pcbs, path, refresh_cache, &CancellationToken::default())?;
+                *modified |= refresh_result.modified;
 
                 let phase = project
                     .phases
@@ -1521,15 +3368,22 @@ fn are_sets_equal_in_order<T: PartialEq>(a: &IndexSet<T>, b: &IndexSet<T>) -> bo
                     .ok_or(AppError::UnknownPhaseReference(phase_reference.clone()))?
                     .clone();
 
-                let parts = project::assign_placements_to_phase(project, &phase, operation.clone(), placements_pattern)
+                let placements_pattern = placement_selections
+                    .resolve(placements)
+                    .map_err(AppError::UnknownPlacementSelection)?;
+
+                let PhaseAssignmentResult {
+                    required_load_out_parts: parts,
+                    ignored_placements,
+                } = project::assign_placements_to_phase(project, &phase, operation.clone(), placements_pattern, force)
                     .map_err(|cause| AppError::ProjectError(ProjectError::UnableToAssignPhaseToPlacements(cause)))?;
 
                 trace!("Required load_out parts: {:?}", parts);
 
                 *modified |= project::refresh_phase_operation_states(project);
 
-                let load_out_source =
-                    try_build_phase_load_out_source(&directory, &phase).map_err(AppError::SourceError)?;
+                let load_out_source = try_build_phase_load_out_source(&directory, &phase, &project.store_backend)
+                    .map_err(AppError::SourceError)?;
 
                 match operation {
                     SetOrClearAction::Set => {
@@ -1544,13 +3398,30 @@ fn are_sets_equal_in_order<T: PartialEq>(a: &IndexSet<T>, b: &IndexSet<T>) -> bo
 
                             *modified |= project::add_process_to_part(part_state, part, phase.process.clone());
                         }
-                        stores::load_out::add_parts_to_load_out(&load_out_source, parts)
-                            .map_err(AppError::LoadoutError)?;
+                        let store = Self::open_store(project, &directory)?;
+                        stores::load_out::add_parts_to_load_out(
+                            store.as_ref(),
+                            &load_out_source,
+                            parts,
+                            &project.part_aliases,
+                        )
+                        .map_err(AppError::LoadoutError)?;
                     }
                     SetOrClearAction::Clear => {
                         // FUTURE not currently sure if cleanup should happen automatically or if it should be explicit.
                     }
                 }
+
+                if !ignored_placements.is_empty() {
+                    push_warning(&mut model.warnings, AppWarning::PlacementsOnOtherPcbSide {
+                        phase: phase.reference,
+                        phase_side: phase.pcb_side,
+                        object_paths: ignored_placements,
+                    });
+                }
+
+                Self::report_refresh(model, refresh_result);
+
                 Ok(render::render())
             }),
             Event::AddPartsToLoadout {
@@ -1570,12 +3441,60 @@ fn are_sets_equal_in_order<T: PartialEq>(a: &IndexSet<T>, b: &IndexSet<T>) -> bo
                     .get_mut(&phase_reference)
                     .ok_or(AppError::UnknownPhaseReference(phase_reference.clone()))?;
 
-                let load_out_source =
-                    try_build_phase_load_out_source(&directory, phase).map_err(AppError::SourceError)?;
+                let load_out_source = try_build_phase_load_out_source(&directory, phase, &project.store_backend)
+                    .map_err(AppError::SourceError)?;
 
                 let parts = project::find_phase_parts(project, &phase_reference, manufacturer_pattern, mpn_pattern);
 
-                stores::load_out::add_parts_to_load_out(&load_out_source, parts).map_err(AppError::LoadoutError)?;
+                let store = Self::open_store(project, &directory)?;
+                stores::load_out::add_parts_to_load_out(store.as_ref(), &load_out_source, parts, &project.part_aliases)
+                    .map_err(AppError::LoadoutError)?;
+
+                Ok(render::render())
+            }),
+            Event::MigrateLoadOuts {} => Box::new(move |model: &mut Model| {
+                let (
+                    ModelProject {
+                        project, ..
+                    },
+                    directory,
+                ) = Self::model_project_and_directory(model)?;
+
+                if !project.store_backend.is_csv() {
+                    info!("Load-outs are not stored in CSV files, nothing to migrate.");
+                    return Ok(render::render());
+                }
+
+                // phases can share a load-out
+                let load_out_sources = project
+                    .phases
+                    .values()
+                    .map(|phase| {
+                        try_build_phase_load_out_source(&directory, phase, &project.store_backend)
+                            .map_err(AppError::SourceError)
+                    })
+                    .collect::<Result<BTreeSet<_>, _>>()?;
+
+                for load_out_source in load_out_sources {
+                    stores::load_out::migrate_load_out(&load_out_source).map_err(AppError::OperationError)?;
+                }
+
+                Ok(render::render())
+            }),
+            Event::SetProjectFileFormat {
+                format,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project,
+                    modified,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                project.file_format = format;
+                *modified |= true;
 
                 Ok(render::render())
             }),
@@ -1618,15 +3537,18 @@ fn are_sets_equal_in_order<T: PartialEq>(a: &IndexSet<T>, b: &IndexSet<T>) -> bo
                     .map_err(AppError::ProcessError)?
                     .clone();
 
-                let load_out_source =
-                    try_build_phase_load_out_source(&directory, phase).map_err(AppError::SourceError)?;
+                let load_out_source = try_build_phase_load_out_source(&directory, phase, &project.store_backend)
+                    .map_err(AppError::SourceError)?;
 
+                let store = Self::open_store(project, &directory)?;
                 stores::load_out::assign_feeder_to_load_out_item(
+                    store.as_ref(),
                     &load_out_source,
                     &process,
                     feeder_reference,
                     manufacturer,
                     mpn,
+                    &project.part_aliases,
                 )
                 .map_err(AppError::OperationError)?;
                 Ok(render::render())
@@ -1640,59 +3562,30 @@ fn are_sets_equal_in_order<T: PartialEq>(a: &IndexSet<T>, b: &IndexSet<T>) -> bo
                         project,
                         path,
                         modified,
+                        refresh_cache,
                         ..
                     },
                     pcbs,
                     ..,
                 ) = { Self::model_project_and_pcbs(model) }?;
 
-                let refresh_result = Self::refresh_project(project, &pcbs, path).map_err(AppError::ProjectError)?;
-                *modified |= refresh_result;
+                let refresh_result =
+                    Self::refresh_project(project, &pcbs, path, refresh_cache, &CancellationToken::default())?;
+                *modified |= refresh_result.modified;
 
                 *modified |= project::update_placement_orderings(project, &reference, &placement_orderings)
                     .map_err(AppError::OperationError)?;
 
-                Ok(render::render())
-            }),
-            Event::GenerateArtifacts => Box::new(|model: &mut Model| {
-                let (
-                    ModelProject {
-                        project,
-                        modified,
-                        ..
-                    },
-                    pcbs,
-                    project_directory,
-                ) = { Self::model_project_and_pcbs(model) }?;
-
-                *modified |= project::refresh_phase_operation_states(project);
-
-                let phase_load_out_item_map = Self::build_phase_load_out_item_map(project, &project_directory)
-                    .map_err(AppError::OperationError)?;
-
-                let mut packages = Vec::new();
-                let mut package_mappings = Vec::new();
-                let part_packages_map = Self::load_part_packages_map(project, &mut packages, &mut package_mappings)?;
+                Self::report_refresh(model, refresh_result);
 
-                project::generate_artifacts(
-                    project,
-                    &pcbs,
-                    &project_directory,
-                    phase_load_out_item_map,
-                    &part_packages_map,
-                )
-                .map_err(|cause| AppError::OperationError(cause.into()))?;
                 Ok(render::render())
             }),
-            Event::RecordPhaseOperation {
+            Event::SetPhaseAssemblyWindow {
                 phase: reference,
-                operation,
-                task,
-                action,
+                assembly_window,
             } => Box::new(move |model: &mut Model| {
                 let ModelProject {
                     project,
-                    path,
                     modified,
                     ..
                 } = model
@@ -1700,31 +3593,38 @@ fn are_sets_equal_in_order<T: PartialEq>(a: &IndexSet<T>, b: &IndexSet<T>) -> bo
                     .as_mut()
                     .ok_or(AppError::OperationRequiresProject)?;
 
-                let directory = path.parent().unwrap();
-                *modified |=
-                    project::apply_phase_operation_task_action(project, directory, &reference, operation, task, action)
-                        .map_err(AppError::OperationError)?;
+                *modified |= project::update_assembly_window(project, &reference, assembly_window)
+                    .map_err(AppError::OperationError)?;
+
                 Ok(render::render())
             }),
-            Event::RecordPlacementsOperation {
-                object_path_patterns,
-                operation,
+            Event::SetRotationOffset {
+                target,
+                offset,
             } => Box::new(move |model: &mut Model| {
                 let ModelProject {
                     project,
-                    path,
                     modified,
                     ..
                 } = model
                     .model_project
                     .as_mut()
                     .ok_or(AppError::OperationRequiresProject)?;
-                let directory = path.parent().unwrap();
-                *modified |= project::update_placements_operation(project, directory, object_path_patterns, operation)
-                    .map_err(AppError::OperationError)?;
+
+                let offset = offset.map(|offset| project.settings.rotation_from(offset));
+
+                *modified |= project
+                    .rotation_offsets
+                    .update(target, offset);
+
                 Ok(render::render())
             }),
-            Event::ResetOperations {} => Box::new(|model: &mut Model| {
+            Event::ApplyProjectSettings {
+                unit_system,
+                origin,
+                rotation_direction,
+                default_placement_orderings,
+            } => Box::new(move |model: &mut Model| {
                 let ModelProject {
                     project,
                     modified,
@@ -1733,1091 +3633,5313 @@ fn are_sets_equal_in_order<T: PartialEq>(a: &IndexSet<T>, b: &IndexSet<T>) -> bo
                     .model_project
                     .as_mut()
                     .ok_or(AppError::OperationRequiresProject)?;
-                project::reset_operations(project).map_err(AppError::OperationError)?;
 
-                *modified |= true;
+                let mut settings = project.settings.clone();
+                if let Some(unit_system) = unit_system {
+                    settings.unit_system = unit_system;
+                }
+                if let Some(origin) = origin {
+                    settings.origin = origin;
+                }
+                if let Some(rotation_direction) = rotation_direction {
+                    settings.rotation_direction = rotation_direction;
+                }
+                if let Some(default_placement_orderings) = default_placement_orderings {
+                    settings.default_placement_orderings = default_placement_orderings;
+                }
+
+                if !project.settings.eq(&settings) {
+                    info!(
+                        "Applying project settings. old: {:?}, new: {:?}",
+                        project.settings, settings
+                    );
+                    project.settings = settings;
+                    *modified |= true;
+                }
+
                 Ok(render::render())
             }),
-
-            //
-            // Gerber file management
-            //
-            Event::AddGerberFiles {
-                path: pcb_path,
-                design,
-                files,
+            Event::SetPlacementNotes {
+                object_path,
+                notes,
             } => Box::new(move |model: &mut Model| {
-                let ModelPcb {
+                let ModelProject {
+                    project,
                     modified,
-                    pcb,
                     ..
                 } = model
-                    .model_pcbs
-                    .get_mut(&pcb_path)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded))?;
-
-                debug!(
-                    "Adding gerbers to pcb. pcb_file: {:?}, design: {:?} files: {:?}",
-                    pcb_path, design, files
-                );
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
 
-                *modified |= pcb
-                    .update_gerbers(design, files)
-                    .map_err(|e| AppError::PcbOperationError(PcbOperationError::PcbError(e)))?;
+                *modified |=
+                    project::update_placement_notes(project, &object_path, notes).map_err(AppError::OperationError)?;
 
                 Ok(render::render())
             }),
-            Event::RemoveGerberFiles {
-                path: pcb_path,
-                design,
-                files,
+            Event::SetPlacementAttachments {
+                object_path,
+                attachments,
             } => Box::new(move |model: &mut Model| {
-                let ModelPcb {
+                let ModelProject {
+                    project,
                     modified,
-                    pcb,
                     ..
                 } = model
-                    .model_pcbs
-                    .get_mut(&pcb_path)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded))?;
-
-                debug!(
-                    "Removing gerbers from pcb. pcb_file: {:?}, design: {:?} files: {:?}",
-                    pcb_path, design, files
-                );
-                let (was_modified, unremoved_files) = pcb
-                    .remove_gerbers(design, files)
-                    .map_err(|e| AppError::PcbOperationError(PcbOperationError::PcbError(e)))?;
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
 
-                if !unremoved_files.is_empty() {
-                    warn!("Unable to remove the following gerbers. files: {:?}", unremoved_files);
-                }
-                *modified |= was_modified;
+                *modified |= project::update_placement_attachments(project, &object_path, attachments)
+                    .map_err(AppError::OperationError)?;
 
                 Ok(render::render())
             }),
-            Event::RefreshGerberFiles {
-                path: pcb_path,
-                design,
+            Event::GenerateArtifacts {
+                token,
+                artifacts,
             } => Box::new(move |model: &mut Model| {
-                let ModelPcb {
-                    modified,
-                    pcb,
-                    ..
-                } = model
-                    .model_pcbs
-                    .get_mut(&pcb_path)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded))?;
+                let cancellation = model.operation_token(token);
+                let (
+                    ModelProject {
+                        project,
+                        modified,
+                        ..
+                    },
+                    pcbs,
+                    project_directory,
+                ) = { Self::model_project_and_pcbs(model) }?;
 
-                debug!(
-                    "Refreshing gerbers from pcb. pcb_file: {:?}, design: {:?}",
-                    pcb_path, design
-                );
-                let was_modified = pcb
-                    .update_gerbers(design, vec![])
-                    .map_err(|e| AppError::PcbOperationError(PcbOperationError::PcbError(e)))?;
+                *modified |= project::refresh_phase_operation_states(project);
 
-                *modified |= was_modified;
+                let phase_load_out_item_map = Self::build_phase_load_out_item_map(project, &project_directory)
+                    .map_err(AppError::OperationError)?;
+
+                let mut packages = Vec::new();
+                let mut package_mappings = Vec::new();
+                let part_packages_map = Self::load_part_packages_map(project, &mut packages, &mut package_mappings)?;
 
+                project::generate_artifacts(
+                    project,
+                    &pcbs,
+                    &project_directory,
+                    phase_load_out_item_map,
+                    &part_packages_map,
+                    &artifacts,
+                    &cancellation,
+                )
+                .map_err(|cause| match cause {
+                    ArtifactGenerationError::Cancelled(cancelled) => AppError::Cancelled(cancelled),
+                    cause => AppError::OperationError(cause.into()),
+                })?;
                 Ok(render::render())
             }),
-            Event::ApplyGerberFileFunctions {
-                path: pcb_path,
-                file_functions,
+            Event::GenerateHandoverReport {
+                format,
             } => Box::new(move |model: &mut Model| {
-                let ModelPcb {
-                    modified,
-                    pcb,
-                    ..
-                } = model
-                    .model_pcbs
-                    .get_mut(&pcb_path)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded))?;
+                let (
+                    ModelProject {
+                        project,
+                        modified,
+                        ..
+                    },
+                    pcbs,
+                    project_directory,
+                ) = { Self::model_project_and_pcbs(model) }?;
 
-                let was_modified = pcb.apply_file_functions(file_functions);
+                *modified |= project::refresh_phase_operation_states(project);
 
-                *modified |= was_modified;
+                let phase_load_out_item_map = Self::build_phase_load_out_item_map(project, &project_directory)
+                    .map_err(AppError::OperationError)?;
+
+                project::generate_handover_report(project, &pcbs, &project_directory, &phase_load_out_item_map, format)
+                    .map_err(|cause| AppError::OperationError(cause.into()))?;
 
                 Ok(render::render())
             }),
-
-            //
-            // Views
-            //
-            Event::RequestOverviewView {} => Box::new(|model: &mut Model| {
+            Event::StartBuildRun {
+                quantity,
+            } => Box::new(move |model: &mut Model| {
                 let ModelProject {
-                    project, ..
+                    project,
+                    modified,
+                    ..
                 } = model
                     .model_project
                     .as_mut()
                     .ok_or(AppError::OperationRequiresProject)?;
 
-                let overview = ProjectOverview {
-                    name: project.name.clone(),
-                    processes: project
-                        .processes
-                        .iter()
-                        .map(|process| process.reference.clone())
-                        .collect(),
-                    library_config: project.library_config.clone(),
-                    pcbs: project.pcbs.to_vec(),
-                };
-                Ok(project_view_renderer::view(ProjectView::Overview(overview)))
+                build_run::start_build_run(project, quantity, OffsetDateTime::now_utc())
+                    .map_err(|cause| AppError::OperationError(cause.into()))?;
+                *modified |= true;
+
+                Ok(render::render())
             }),
-            Event::RequestProjectPcbOverviewView {
-                pcb: pcb_index,
+            Event::ActivateBuildRunInstance {
+                instance,
             } => Box::new(move |model: &mut Model| {
                 let ModelProject {
                     project,
-                    project_directory,
+                    modified,
                     ..
                 } = model
                     .model_project
                     .as_mut()
                     .ok_or(AppError::OperationRequiresProject)?;
 
-                // we need to make sure the index is valid before attempting to get the corresponding PCB from the model.
-                let project_pcb = project
-                    .pcbs
-                    .get(pcb_index as usize)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::Unknown))?;
-
-                let pcb_path = project_pcb
-                    .pcb_file
-                    .build_path(project_directory);
-                let pcb_overview = ProjectPcbOverview {
-                    index: pcb_index,
-                    pcb_file: project_pcb.pcb_file.clone(),
-                    pcb_path,
-                };
-
-                Ok(project_view_renderer::view(ProjectView::PcbOverview(pcb_overview)))
-            }),
-            Event::RequestPcbPanelSizingView {
-                path: pcb_path,
-            } => Box::new(move |model: &mut Model| {
-                let ModelPcb {
-                    pcb, ..
-                } = &model
-                    .model_pcbs
-                    .get(&pcb_path)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded))?;
-
-                let panel_sizing = pcb.panel_sizing.clone();
-
-                let view = pcb_view_renderer::view(PcbView::PanelSizing(panel_sizing));
-                Ok(view)
-            }),
-            Event::RequestPcbOverviewView {
-                path: pcb_path,
-            } => Box::new(move |model: &mut Model| {
-                let ModelPcb {
-                    pcb, ..
-                } = &model
-                    .model_pcbs
-                    .get(&pcb_path)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::PcbNotLoaded))?;
-
-                let designs = pcb
-                    .unique_designs_iter()
-                    .cloned()
-                    .collect::<Vec<_>>();
-
-                let design_gerbers = designs
-                    .iter()
-                    .enumerate()
-                    .map(|(index, _design_name)| {
-                        let design_index = DesignIndex::from(index);
-
-                        let design_gerbers = pcb
-                            .design_gerbers
-                            .get(&design_index)
-                            .map_or(Vec::new(), |v| {
-                                v.iter()
-                                    .map(Self::gerber_file_to_pcb_gerber_item)
-                                    .collect::<Vec<_>>()
-                            });
-
-                        design_gerbers
-                    })
-                    .collect::<Vec<_>>();
-
-                let pcb_gerbers = pcb
-                    .pcb_gerbers
-                    .iter()
-                    .map(Self::gerber_file_to_pcb_gerber_item)
-                    .collect::<Vec<_>>();
-
-                let unit_map = pcb
-                    .unit_map
-                    .iter()
-                    .map(|(a, b)| (*a, *b))
-                    .collect::<HashMap<PcbUnitIndex, DesignIndex>>();
-
-                let pcb_overview = PcbOverview {
-                    path: pcb_path.clone(),
-                    name: pcb.name.clone(),
-                    units: pcb.units,
-                    designs,
-                    unit_map,
-                    pcb_gerbers,
-                    gerber_offset: pcb.gerber_offset,
-                    design_gerbers,
-                    orientation: pcb.orientation.clone(),
-                };
+                *modified |= build_run::activate_build_run_instance(project, instance)
+                    .map_err(|cause| AppError::OperationError(cause.into()))?;
 
-                Ok(pcb_view_renderer::view(PcbView::PcbOverview(pcb_overview)))
+                Ok(render::render())
             }),
-            Event::RequestPcbUnitAssignmentsView {
-                pcb: pcb_index,
+            Event::RecordPhaseOperation {
+                phase: reference,
+                operation,
+                task,
+                action,
+                overridden_rules,
+                parameter_values,
             } => Box::new(move |model: &mut Model| {
                 let ModelProject {
-                    project, ..
+                    project,
+                    path,
+                    modified,
+                    ..
                 } = model
                     .model_project
                     .as_mut()
                     .ok_or(AppError::OperationRequiresProject)?;
 
-                let project_pcb = project
-                    .pcbs
-                    .get(pcb_index as usize)
-                    .ok_or(AppError::PcbOperationError(PcbOperationError::Unknown))?;
-
-                let unit_assignments = project_pcb
-                    .unit_assignments
-                    .iter()
-                    .map(|(pcb_unit_index, design_variant)| (*pcb_unit_index, design_variant.clone()))
-                    .collect::<HashMap<_, _>>();
-
-                let pcb_unit_assignments = PcbUnitAssignments {
-                    index: pcb_index,
-                    unit_assignments,
-                };
-                Ok(project_view_renderer::view(ProjectView::PcbUnitAssignments(
-                    pcb_unit_assignments,
-                )))
+                let directory = parent_directory(&path);
+                let load_out_items =
+                    Self::load_phase_load_out_items_for_rules(project, directory, &reference, &mut model.warnings)?;
+                *modified |= project::apply_phase_operation_task_action(
+                    project,
+                    directory,
+                    &reference,
+                    operation,
+                    task,
+                    action,
+                    TaskActionOptions {
+                        scope: None,
+                        load_out_items: &load_out_items,
+                        overridden_rules: &overridden_rules,
+                        parameter_values,
+                    },
+                )
+                .map_err(AppError::OperationError)?;
+                Ok(render::render())
             }),
-            Event::RequestPlacementsView {} => Box::new(|model: &mut Model| {
+            Event::RecordPlacementsOperation {
+                placements,
+                operation,
+            } => Box::new(move |model: &mut Model| {
                 let ModelProject {
-                    project, ..
+                    project,
+                    path,
+                    modified,
+                    placement_selections,
+                    ..
                 } = model
                     .model_project
                     .as_mut()
                     .ok_or(AppError::OperationRequiresProject)?;
-
-                let placements = project
-                    .placements
-                    .iter()
-                    .enumerate()
-                    .map(|(ordering, (path, state))| PlacementsItem {
-                        path: path.clone(),
-                        state: state.clone(),
-                        ordering,
-                    })
-                    .collect();
-
-                let placements = PlacementsList {
-                    placements,
-                };
-
-                Ok(project_view_renderer::view(ProjectView::Placements(placements)))
+                let directory = parent_directory(&path);
+                let object_path_patterns = placements
+                    .into_iter()
+                    .map(|selector| placement_selections.resolve(selector))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(AppError::UnknownPlacementSelection)?;
+                *modified |=
+                    project::update_placements_operation(project, directory, object_path_patterns, operation, None)
+                        .map_err(AppError::OperationError)?;
+                Ok(render::render())
             }),
-            Event::RequestProjectTreeView {} => Box::new(|model: &mut Model| {
+            Event::AssignSerialNumbers {
+                pcb: pcb_index,
+                scheme,
+            } => Box::new(move |model: &mut Model| {
                 let (
                     ModelProject {
-                        project, ..
+                        project,
+                        modified,
+                        ..
                     },
                     pcbs,
                     ..,
                 ) = { Self::model_project_and_pcbs(model) }?;
 
-                let add_test_nodes = false;
-
-                let mut project_tree = ProjectTreeView::new();
+                let date = OffsetDateTime::now_utc().date();
+                let assigned = project
+                    .assign_serial_numbers(&pcbs, pcb_index, &scheme, date)
+                    .map_err(AppError::SerialNumberError)?;
+                *modified |= !assigned.is_empty();
 
-                let root_node = project_tree
-                    .tree
-                    .add_node(ProjectTreeItem {
-                        key: "root".to_string(),
-                        path: "/".to_string(),
-                        ..ProjectTreeItem::default()
-                    });
+                Ok(render::render())
+            }),
+            Event::RecordUnitPhaseOperation {
+                serial_number,
+                phase: reference,
+                operation,
+                task,
+                action,
+                overridden_rules,
+                parameter_values,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project,
+                    path,
+                    modified,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let scope = project
+                    .find_unit_by_serial_number(&serial_number)
+                    .map_err(AppError::SerialNumberError)?;
+
+                let directory = parent_directory(&path);
+                let load_out_items =
+                    Self::load_phase_load_out_items_for_rules(project, directory, &reference, &mut model.warnings)?;
+                *modified |= project::apply_phase_operation_task_action(
+                    project,
+                    directory,
+                    &reference,
+                    operation,
+                    task,
+                    action,
+                    TaskActionOptions {
+                        scope: Some(&scope),
+                        load_out_items: &load_out_items,
+                        overridden_rules: &overridden_rules,
+                        parameter_values,
+                    },
+                )
+                .map_err(AppError::OperationError)?;
+                Ok(render::render())
+            }),
+            Event::RecordUnitPlacementsOperation {
+                serial_number,
+                object_path_patterns,
+                operation,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project,
+                    path,
+                    modified,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let scope = project
+                    .find_unit_by_serial_number(&serial_number)
+                    .map_err(AppError::SerialNumberError)?;
+
+                let directory = parent_directory(&path);
+                *modified |= project::update_placements_operation(
+                    project,
+                    directory,
+                    object_path_patterns,
+                    operation,
+                    Some(&scope),
+                )
+                .map_err(AppError::OperationError)?;
+                Ok(render::render())
+            }),
+            Event::ResetOperations {} => Box::new(|model: &mut Model| {
+                let ModelProject {
+                    project,
+                    modified,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+                project::reset_operations(project).map_err(AppError::OperationError)?;
+
+                *modified |= true;
+                Ok(render::render())
+            }),
+
+            //
+            // Placement selections
+            //
+            Event::SelectPlacements {
+                filter,
+            } => Box::new(move |model: &mut Model| {
+                let selection = model.selection_ids.next();
+
+                let ModelProject {
+                    project,
+                    placement_selections,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let count = placement_selections.select(project, selection, &filter);
+                info!(
+                    "Selected placements. selection: {}, filter: {}, placements: {}",
+                    selection, filter, count
+                );
+                model
+                    .placement_selection
+                    .replace(selection);
+
+                Ok(render::render())
+            }),
+            Event::ReleasePlacementSelection {
+                selection,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    placement_selections, ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                if !placement_selections.release(&selection) {
+                    return Err(AppError::UnknownPlacementSelection(selection));
+                }
+
+                Ok(render::render())
+            }),
+
+            //
+            // Placement session
+            //
+            Event::StartPlacementSession {
+                phase,
+            } => Box::new(move |model: &mut Model| {
+                let (model_project, pcbs, project_directory) = Self::model_project_and_pcbs(model)?;
+
+                let (placements, _load_out_items, _nozzle_assignments) =
+                    Self::sorted_phase_placements(&model_project.project, &pcbs, &project_directory, &phase)?;
+
+                let object_paths = placements
+                    .into_iter()
+                    .filter(|(_path, state)| {
+                        state.placement.place && state.operation_status == PlacementStatus::Pending
+                    })
+                    .map(|(path, _state)| path)
+                    .collect::<Vec<_>>();
+
+                info!(
+                    "Starting placement session. phase: {}, placements: {}",
+                    phase,
+                    object_paths.len()
+                );
+
+                let mut placement_session = ModelPlacementSession {
+                    phase,
+                    object_paths,
+                    current: None,
+                    placed: 0,
+                    skipped: 0,
+                };
+                placement_session.advance(&model_project.project.placements);
+
+                model_project
+                    .placement_session
+                    .replace(placement_session);
+
+                Self::placement_session_view(model)
+            }),
+            Event::NextPlacement {} => Box::new(|model: &mut Model| Self::update_placement_session(model, None)),
+            Event::ConfirmPlacement {} => {
+                Box::new(|model: &mut Model| Self::update_placement_session(model, Some(PlacementOperation::Place)))
+            }
+            Event::SkipPlacement {} => {
+                Box::new(|model: &mut Model| Self::update_placement_session(model, Some(PlacementOperation::Skip)))
+            }
+            Event::EndPlacementSession {} => Box::new(|model: &mut Model| {
+                let ModelProject {
+                    placement_session, ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let placement_session = placement_session
+                    .take()
+                    .ok_or(AppError::PlacementSessionRequired)?;
+                info!(
+                    "Ended placement session. phase: {}, placed: {}, skipped: {}",
+                    placement_session.phase, placement_session.placed, placement_session.skipped
+                );
+
+                Ok(render::render())
+            }),
+
+            //
+            // Cancellation
+            //
+            Event::CancelOperation {
+                token,
+            } => Box::new(move |model: &mut Model| {
+                info!("Cancelling operation. token: {}", token.id());
+
+                token.cancel();
+                model.cancel_operation(&token);
+
+                Ok(render::render())
+            }),
+
+            //
+            // Diagnostics
+            //
+            Event::SetEventRecording {
+                path,
+            } => Box::new(move |model: &mut Model| {
+                let command = match path {
+                    Some(path) => {
+                        let (event_recorder, operation) = EventRecorder::create(path.clone());
+                        model.event_recorder = Some(event_recorder);
+
+                        Command::all([render::render(), Self::event_recording_request(path, operation)])
+                    }
+                    None => {
+                        info!("Stopped recording events.");
+                        model.event_recorder = None;
+
+                        render::render()
+                    }
+                };
+
+                Ok(command)
+            }),
+            Event::EventRecordingWritten {
+                path,
+                result,
+            } => Box::new(move |_model: &mut Model| {
+                if let Err(cause) = result.into_written() {
+                    warn!("Unable to record event. path: {:?}, cause: {:?}", path, cause);
+                }
+
+                Ok(Command::done())
+            }),
+
+            //
+            // Vision
+            //
+            Event::RequestVisionOperation {
+                operation,
+            } => Box::new(move |model: &mut Model| {
+                let _ = model
+                    .model_project
+                    .as_ref()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let completed_operation = operation.clone();
+                Ok(vision::request(operation, move |result| {
+                    Event::VisionOperationCompleted {
+                        operation: completed_operation,
+                        result,
+                    }
+                }))
+            }),
+            Event::VisionOperationCompleted {
+                operation,
+                result,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project,
+                    path,
+                    ..
+                } = model
+                    .model_project
+                    .as_ref()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                match (operation, result) {
+                    (
+                        VisionOperation::InspectPlacement {
+                            phase,
+                            object_path,
+                        },
+                        VisionResult::Located(measurement),
+                    ) => {
+                        let directory = parent_directory(&path);
+                        let inspection = PlacementInspectionHistoryKind {
+                            object_path,
+                            offset_x: measurement.offset.x,
+                            offset_y: measurement.offset.y,
+                            rotation: measurement.rotation,
+                            confidence: measurement.confidence,
+                        };
+                        project::record_placement_inspection(project, directory, &phase, inspection)
+                            .map_err(AppError::InspectionError)?;
+                    }
+                    // FUTURE use located fiducials to correct the positions of the placements.
+                    (
+                        VisionOperation::LocateFiducial {
+                            phase,
+                            expected_position,
+                        },
+                        VisionResult::Located(measurement),
+                    ) => {
+                        let directory = parent_directory(&path);
+                        let location = FiducialLocationHistoryKind {
+                            expected_x: expected_position.x,
+                            expected_y: expected_position.y,
+                            offset_x: measurement.offset.x,
+                            offset_y: measurement.offset.y,
+                            confidence: measurement.confidence,
+                        };
+                        project::record_fiducial_location(project, directory, &phase, location)
+                            .map_err(AppError::InspectionError)?;
+                    }
+                    (
+                        operation,
+                        VisionResult::Failed {
+                            reason,
+                        },
+                    ) => {
+                        return Err(AppError::VisionError(format!(
+                            "operation: {:?}, reason: {}",
+                            operation, reason
+                        )));
+                    }
+                    (operation, VisionResult::Unavailable) => {
+                        return Err(AppError::VisionError(format!(
+                            "unavailable. operation: {:?}",
+                            operation
+                        )));
+                    }
+                    (operation, result) => {
+                        info!(
+                            "Vision operation completed. operation: {:?}, result: {:?}",
+                            operation, result
+                        );
+                    }
+                }
+
+                Ok(render::render())
+            }),
+
+            //
+            // Gerber file management
+            //
+            Event::AddGerberFiles {
+                path: pcb_path,
+                design,
+                files,
+                token,
+            } => Box::new(move |model: &mut Model| {
+                let cancellation = model.operation_token(token);
+                let ModelPcb {
+                    modified,
+                    pcb,
+                    ..
+                } = model.model_pcb(&pcb_path)?;
+
+                debug!(
+                    "Adding gerbers to pcb. pcb_file: {:?}, design: {:?} files: {:?}",
+                    pcb_path, design, files
+                );
+
+                *modified |= pcb
+                    .update_gerbers(design, files, &cancellation)
+                    .map_err(AppError::from_pcb_error)?;
+
+                Ok(render::render())
+            }),
+            Event::RemoveGerberFiles {
+                path: pcb_path,
+                design,
+                files,
+            } => Box::new(move |model: &mut Model| {
+                let ModelPcb {
+                    modified,
+                    pcb,
+                    ..
+                } = model.model_pcb(&pcb_path)?;
+
+                debug!(
+                    "Removing gerbers from pcb. pcb_file: {:?}, design: {:?} files: {:?}",
+                    pcb_path, design, files
+                );
+                let (was_modified, unremoved_files) = pcb
+                    .remove_gerbers(design, files)
+                    .map_err(|e| AppError::PcbOperationError(PcbOperationError::PcbError(e)))?;
+
+                *modified |= was_modified;
+
+                if !unremoved_files.is_empty() {
+                    push_warning(&mut model.warnings, AppWarning::UnremovedGerbers {
+                        pcb_file: pcb_path,
+                        files: unremoved_files,
+                    });
+                }
+
+                Ok(render::render())
+            }),
+            Event::RefreshGerberFiles {
+                path: pcb_path,
+                design,
+                token,
+            } => Box::new(move |model: &mut Model| {
+                let cancellation = model.operation_token(token);
+                let ModelPcb {
+                    modified,
+                    pcb,
+                    ..
+                } = model.model_pcb(&pcb_path)?;
+
+                debug!(
+                    "Refreshing gerbers from pcb. pcb_file: {:?}, design: {:?}",
+                    pcb_path, design
+                );
+                let was_modified = pcb
+                    .update_gerbers(design, vec![], &cancellation)
+                    .map_err(AppError::from_pcb_error)?;
+
+                *modified |= was_modified;
+
+                Ok(render::render())
+            }),
+            Event::ApplyGerberFileFunctions {
+                path: pcb_path,
+                file_functions,
+            } => Box::new(move |model: &mut Model| {
+                let ModelPcb {
+                    modified,
+                    pcb,
+                    ..
+                } = model.model_pcb(&pcb_path)?;
+
+                let was_modified = pcb.apply_file_functions(file_functions);
+
+                *modified |= was_modified;
+
+                Ok(render::render())
+            }),
+            Event::ImportPcbOutline {
+                path: pcb_path,
+                design,
+                file,
+                token,
+            } => Box::new(move |model: &mut Model| {
+                model.model_pcb(&pcb_path)?;
+
+                if !gerber_outline::is_outline_file(&file) {
+                    return Err(AppError::OperationError(
+                        gerber_outline::OutlineError::UnsupportedFile(file).into(),
+                    ));
+                }
+
+                // a bare file name is relative to the current directory
+                let directory = file
+                    .parent()
+                    .filter(|directory| !directory.as_os_str().is_empty())
+                    .map(Path::to_path_buf)
+                    .unwrap_or_else(|| PathBuf::from("."));
+
+                Ok(file_io::request(
+                    FileIoOperation::List {
+                        directory,
+                    },
+                    move |result| Event::PcbOutlineDirectoryListed {
+                        path: pcb_path,
+                        design,
+                        file,
+                        token,
+                        result,
+                    },
+                ))
+            }),
+            Event::PcbOutlineDirectoryListed {
+                path: pcb_path,
+                design,
+                file,
+                token,
+                result,
+            } => Box::new(move |_model: &mut Model| {
+                let entries = result
+                    .into_entries()
+                    .map_err(AppError::IoError)?;
+
+                // the entries are prefixed with the listed directory, e.g. `./`, so only the file names are compared
+                let profile_path = gerber_outline::profile_gerber_path(&file);
+                if entries
+                    .iter()
+                    .any(|entry| entry.file_name() == profile_path.file_name())
+                {
+                    return Err(AppError::OperationError(anyhow!(
+                        "Profile gerber already exists, remove it, or rename the outline file. path: {:?}",
+                        profile_path
+                    )));
+                }
+
+                Ok(file_io::request(
+                    FileIoOperation::Read {
+                        path: file.clone(),
+                    },
+                    move |result| Event::PcbOutlineRead {
+                        path: pcb_path,
+                        design,
+                        file,
+                        token,
+                        result,
+                    },
+                ))
+            }),
+            Event::PcbOutlineRead {
+                path: pcb_path,
+                design,
+                file,
+                token,
+                result,
+            } => Box::new(move |_model: &mut Model| {
+                let content = result
+                    .into_content()
+                    .map_err(AppError::IoError)?;
+                let content = String::from_utf8(content).map_err(|cause| AppError::OperationError(cause.into()))?;
+
+                let outline = gerber_outline::parse_outline(&file, &content)
+                    .map_err(|cause| AppError::OperationError(cause.into()))?;
+                let profile_path = gerber_outline::profile_gerber_path(&file);
+                let profile = outline
+                    .to_profile_gerber()
+                    .map_err(|cause| AppError::OperationError(cause.into()))?;
+
+                info!(
+                    "Imported outline. outline: {:?}, profile: {:?}, bounds: {:?}",
+                    file,
+                    profile_path,
+                    outline.bounds()
+                );
+
+                Ok(file_io::request(
+                    FileIoOperation::Write {
+                        path: profile_path.clone(),
+                        content: profile,
+                    },
+                    move |result| Event::PcbOutlineProfileWritten {
+                        path: pcb_path,
+                        design,
+                        profile_path,
+                        token,
+                        result,
+                    },
+                ))
+            }),
+            Event::PcbOutlineProfileWritten {
+                path: pcb_path,
+                design,
+                profile_path,
+                token,
+                result,
+            } => Box::new(move |model: &mut Model| {
+                result
+                    .into_written()
+                    .map_err(AppError::IoError)?;
+
+                let cancellation = model.operation_token(token);
+                let ModelPcb {
+                    modified,
+                    pcb,
+                    ..
+                } = model.model_pcb(&pcb_path)?;
+
+                *modified |= pcb
+                    .update_gerbers(
+                        design,
+                        vec![(profile_path, Some(GerberFileFunction::Profile))],
+                        &cancellation,
+                    )
+                    .map_err(AppError::from_pcb_error)?;
+
+                Ok(render::render())
+            }),
+
+            //
+            // Views
+            //
+            Event::RequestOverviewView {} => Box::new(|model: &mut Model| {
+                let ModelProject {
+                    project, ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let overview = ProjectOverview {
+                    name: project.name.clone(),
+                    processes: project
+                        .processes
+                        .iter()
+                        .map(|process| process.reference.clone())
+                        .collect(),
+                    library_config: project.library_config.clone(),
+                    pcbs: project.pcbs.to_vec(),
+                    build_run: build_run::build_run_summary(project),
+                };
+                Ok(project_view_renderer::view(ProjectView::Overview(overview)))
+            }),
+            Event::RequestProjectPcbOverviewView {
+                pcb: pcb_index,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project,
+                    project_directory,
+                    ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                // we need to make sure the index is valid before attempting to get the corresponding PCB from the model.
+                let project_pcb = project
+                    .pcbs
+                    .get(pcb_index as usize)
+                    .ok_or(AppError::PcbOperationError(PcbOperationError::Unknown))?;
+
+                let pcb_path = project_pcb
+                    .pcb_file
+                    .build_path(project_directory);
+                let pcb_overview = ProjectPcbOverview {
+                    index: pcb_index,
+                    pcb_file: project_pcb.pcb_file.clone(),
+                    pcb_path,
+                };
+
+                Ok(project_view_renderer::view(ProjectView::PcbOverview(pcb_overview)))
+            }),
+            Event::RequestBoardPreviewView {
+                pcb: pcb_index,
+            } => Box::new(move |model: &mut Model| {
+                let (_model_project, pcb) = Self::model_project_and_pcb(model, pcb_index)?;
+
+                let profile_file = pcb
+                    .pcb_gerbers
+                    .iter()
+                    .find(|gerber_file| gerber_file.function == Some(GerberFileFunction::Profile))
+                    .map(|gerber_file| gerber_file.file.clone());
+
+                match profile_file {
+                    Some(file) => Ok(file_io::request(
+                        FileIoOperation::Read {
+                            path: file.clone(),
+                        },
+                        move |result| Event::BoardPreviewProfileRead {
+                            pcb: pcb_index,
+                            file,
+                            result,
+                        },
+                    )),
+                    None => Self::board_preview_view(model, pcb_index, None),
+                }
+            }),
+            Event::BoardPreviewProfileRead {
+                pcb: pcb_index,
+                file,
+                result,
+            } => Box::new(move |model: &mut Model| {
+                let profile_outline = result
+                    .into_content()
+                    .map_err(|error| error.to_string())
+                    .and_then(|content| gerber_outline::parse_profile(&content).map_err(|error| error.to_string()));
+
+                let profile_outline = match profile_outline {
+                    Ok(outline) => Some(outline),
+                    Err(cause) => {
+                        push_warning(&mut model.warnings, AppWarning::UnreadableProfileGerber {
+                            file,
+                            cause,
+                        });
+                        None
+                    }
+                };
+
+                Self::board_preview_view(model, pcb_index, profile_outline)
+            }),
+            Event::RequestPcbPanelSizingView {
+                path: pcb_path,
+            } => Box::new(move |model: &mut Model| {
+                let ModelPcb {
+                    pcb, ..
+                } = model.model_pcb(&pcb_path)?;
+
+                let panel_sizing = pcb.panel_sizing.clone();
+
+                let view = pcb_view_renderer::view(PcbView::PanelSizing(panel_sizing));
+                Ok(view)
+            }),
+            Event::RequestPcbPanelPreviewView {
+                path: pcb_path,
+            } => Box::new(move |model: &mut Model| {
+                let ModelPcb {
+                    pcb, ..
+                } = model.model_pcb(&pcb_path)?;
+
+                let panel_sizing = &pcb.panel_sizing;
+
+                let units = pcb
+                    .unit_map
+                    .iter()
+                    .map(|(unit_index, design_index)| {
+                        let design_sizing = panel_sizing
+                            .design_sizings
+                            .get(*design_index)
+                            .ok_or(PcbError::DesignIndexOutOfRange {
+                                index: *design_index,
+                                min: 0,
+                                max: pcb.design_names.len(),
+                            })?;
+                        let unit_positioning = panel_sizing
+                            .pcb_unit_positionings
+                            .get(*unit_index as usize)
+                            .ok_or(PcbError::MissingUnitPositioning {
+                                unit: *unit_index,
+                            })?;
+
+                        let transformer =
+                            GerberTransformer::new(design_sizing, unit_positioning.offset, unit_positioning.rotation);
+
+                        let gerbers = pcb
+                            .design_gerbers
+                            .get(design_index)
+                            .map_or(Vec::new(), |gerbers| {
+                                gerbers
+                                    .iter()
+                                    .map(Self::gerber_file_to_pcb_gerber_item)
+                                    .collect::<Vec<_>>()
+                            });
+
+                        Ok(PanelPreviewUnit {
+                            unit: *unit_index,
+                            design: *design_index,
+                            gerbers,
+                            rotation: unit_positioning.rotation,
+                            pivot: transformer.pivot(),
+                            offset: transformer.offset(),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, PcbError>>()
+                    .map_err(AppError::from_pcb_error)?;
+
+                let pcb_gerbers = pcb
+                    .pcb_gerbers
+                    .iter()
+                    .map(Self::gerber_file_to_pcb_gerber_item)
+                    .collect::<Vec<_>>();
+
+                let panel_preview = PanelPreview {
+                    path: pcb_path.clone(),
+                    size: panel_sizing.size,
+                    edge_rails: panel_sizing.edge_rails.clone(),
+                    fiducials: panel_sizing.fiducials.clone(),
+                    pcb_gerbers,
+                    gerber_offset: pcb.gerber_offset,
+                    units,
+                };
+
+                Ok(pcb_view_renderer::view(PcbView::PanelPreview(panel_preview)))
+            }),
+            Event::RequestPcbOverviewView {
+                path: pcb_path,
+            } => Box::new(move |model: &mut Model| {
+                let ModelPcb {
+                    pcb, ..
+                } = model.model_pcb(&pcb_path)?;
+
+                let designs = pcb
+                    .unique_designs_iter()
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                let design_gerbers = designs
+                    .iter()
+                    .enumerate()
+                    .map(|(index, _design_name)| {
+                        let design_index = DesignIndex::from(index);
+
+                        let design_gerbers = pcb
+                            .design_gerbers
+                            .get(&design_index)
+                            .map_or(Vec::new(), |v| {
+                                v.iter()
+                                    .map(Self::gerber_file_to_pcb_gerber_item)
+                                    .collect::<Vec<_>>()
+                            });
+
+                        design_gerbers
+                    })
+                    .collect::<Vec<_>>();
+
+                let pcb_gerbers = pcb
+                    .pcb_gerbers
+                    .iter()
+                    .map(Self::gerber_file_to_pcb_gerber_item)
+                    .collect::<Vec<_>>();
+
+                let unit_map = pcb
+                    .unit_map
+                    .iter()
+                    .map(|(a, b)| (*a, *b))
+                    .collect::<HashMap<PcbUnitIndex, DesignIndex>>();
+
+                let pcb_overview = PcbOverview {
+                    path: pcb_path.clone(),
+                    name: pcb.name.clone(),
+                    units: pcb.units,
+                    designs,
+                    unit_map,
+                    pcb_gerbers,
+                    gerber_offset: pcb.gerber_offset,
+                    design_gerbers,
+                    orientation: pcb.orientation.clone(),
+                };
+
+                Ok(pcb_view_renderer::view(PcbView::PcbOverview(pcb_overview)))
+            }),
+            Event::RequestPcbUnitAssignmentsView {
+                pcb: pcb_index,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project, ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let project_pcb = project
+                    .pcbs
+                    .get(pcb_index as usize)
+                    .ok_or(AppError::PcbOperationError(PcbOperationError::Unknown))?;
+
+                let unit_assignments = project_pcb
+                    .unit_assignments
+                    .iter()
+                    .map(|(pcb_unit_index, design_variant)| (*pcb_unit_index, design_variant.clone()))
+                    .collect::<HashMap<_, _>>();
+
+                let pcb_unit_assignments = PcbUnitAssignments {
+                    index: pcb_index,
+                    unit_assignments,
+                };
+                Ok(project_view_renderer::view(ProjectView::PcbUnitAssignments(
+                    pcb_unit_assignments,
+                )))
+            }),
+            Event::RequestPcbUnitOverlayView {
+                pcb: pcb_index,
+            } => Box::new(move |model: &mut Model| {
+                let (
+                    ModelProject {
+                        project, ..
+                    },
+                    pcb,
+                ) = Self::model_project_and_pcb(model, pcb_index)?;
+
+                let project_pcb = &project.pcbs[pcb_index as usize];
+
+                let panel_sizing = &pcb.panel_sizing;
+
+                let units = pcb
+                    .unit_map
+                    .iter()
+                    .map(|(unit_index, design_index)| {
+                        let design_sizing = panel_sizing
+                            .design_sizings
+                            .get(*design_index)
+                            .ok_or(PcbError::DesignIndexOutOfRange {
+                                index: *design_index,
+                                min: 0,
+                                max: pcb.design_names.len(),
+                            })?;
+                        let unit_positioning = panel_sizing
+                            .pcb_unit_positionings
+                            .get(*unit_index as usize)
+                            .ok_or(PcbError::MissingUnitPositioning {
+                                unit: *unit_index,
+                            })?;
+                        let design_name = pcb
+                            .design_names
+                            .get_index(*design_index)
+                            .ok_or(PcbError::DesignIndexOutOfRange {
+                                index: *design_index,
+                                min: 0,
+                                max: pcb.design_names.len(),
+                            })?;
+
+                        let unit_to_panel = UnitToPanel {
+                            origin: design_sizing.origin,
+                            unit_rotation: unit_positioning.rotation,
+                            unit_offset: unit_positioning.offset,
+                        };
+                        let centroid = unit_to_panel.unit_center(design_sizing.size);
+
+                        let variant_name = project_pcb
+                            .unit_assignments
+                            .get(unit_index)
+                            .filter(|design_variant| {
+                                design_variant
+                                    .design_name
+                                    .eq(design_name)
+                            })
+                            .map(|design_variant| design_variant.variant_name.clone());
+
+                        Ok(PcbUnitOverlayItem {
+                            unit: *unit_index,
+                            unit_number: *unit_index + 1,
+                            centroid: Vector2::new(centroid.x, centroid.y),
+                            rotation: unit_positioning.rotation,
+                            design_name: design_name.clone(),
+                            variant_name,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, PcbError>>()
+                    .map_err(AppError::from_pcb_error)?;
+
+                let pcb_unit_overlay = PcbUnitOverlay {
+                    index: pcb_index,
+                    units,
+                };
+
+                Ok(project_view_renderer::view(ProjectView::PcbUnitOverlay(
+                    pcb_unit_overlay,
+                )))
+            }),
+            Event::RequestPlacementLocationView {
+                object_path,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project, ..
+                } = model
+                    .model_project
+                    .as_ref()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let state = project
+                    .placements
+                    .get(&object_path)
+                    .ok_or_else(|| AppError::UnknownPlacement(object_path.clone()))?;
+
+                // reminder: the pcb instance is 1-based in the object path
+                let pcb_index = object_path
+                    .pcb_instance()
+                    .map_err(|_| AppError::UnknownPlacement(object_path.clone()))?
+                    .checked_sub(1)
+                    .ok_or(AppError::PcbOperationError(PcbOperationError::InvalidInstanceIndex))?;
+
+                let placement_location = Self::placement_location(
+                    pcb_index,
+                    object_path.clone(),
+                    state.placement.pcb_side,
+                    &state.unit_position,
+                );
+
+                Ok(project_view_renderer::view(ProjectView::PlacementLocation(
+                    placement_location,
+                )))
+            }),
+            Event::RequestHitTestView {
+                pcb,
+                position,
+                side,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project, ..
+                } = model
+                    .model_project
+                    .as_ref()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                if pcb as usize >= project.pcbs.len() {
+                    return Err(AppError::PcbOperationError(PcbOperationError::InvalidInstanceIndex));
+                }
+
+                let hit_test_results = hit_test::hit_test(project, pcb, position, side);
+
+                Ok(project_view_renderer::view(ProjectView::HitTest(hit_test_results)))
+            }),
+            Event::RequestPlacementsView {
+                page,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project, ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let placements = project
+                    .placements
+                    .iter()
+                    .enumerate()
+                    .map(|(ordering, (path, state))| PlacementsItem {
+                        path: path.clone(),
+                        state: state.clone(),
+                        ordering,
+                        nozzle: None,
+                    })
+                    .collect();
+
+                let PlacementsPage {
+                    placements,
+                    offset,
+                    total,
+                } = paging::page_placements(placements, page.as_ref());
+
+                let placements = PlacementsList {
+                    placements,
+                    offset,
+                    total,
+                };
+
+                Ok(project_view_renderer::view(ProjectView::Placements(placements)))
+            }),
+            Event::RequestPlacementRotationsView {} => Box::new(|model: &mut Model| {
+                let ModelProject {
+                    project, ..
+                } = model
+                    .model_project
+                    .as_ref()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let mut packages = Vec::new();
+                let mut package_mappings = Vec::new();
+                let part_packages_map = Self::load_part_packages_map(project, &mut packages, &mut package_mappings)?;
+
+                let placements = project
+                    .placements
+                    .iter()
+                    .map(|(path, state)| {
+                        let part = &state.placement.part;
+                        let package = part_packages_map.get(part).copied();
+                        let (offset, offset_source) = project
+                            .rotation_offsets
+                            .offset_for(part, package);
+                        let rotation = state.unit_position.rotation;
+                        let effective_rotation = rotation::apply_rotation_offset(rotation, offset);
+
+                        let settings = &project.settings;
+                        PlacementRotationItem {
+                            path: path.clone(),
+                            part: part.clone(),
+                            package: package.map(|package| package.name.clone()),
+                            rotation: settings.rotation(rotation),
+                            offset: settings.rotation(offset),
+                            offset_source,
+                            effective_rotation: settings.rotation(effective_rotation),
+                        }
+                    })
+                    .collect();
+
+                Ok(project_view_renderer::view(ProjectView::PlacementRotations(
+                    PlacementRotations {
+                        placements,
+                    },
+                )))
+            }),
+            Event::RequestProjectTreeView {} => Box::new(|model: &mut Model| {
+                // PCBs are not loaded for the tree, so that it can be shown without loading every PCB of a large
+                // project, PCBs that have not been loaded yet are shown using their file name and assigned units.
+                let model_project = model
+                    .model_project
+                    .as_ref()
+                    .ok_or(AppError::OperationRequiresProject)?;
+                let project = &model_project.project;
+
+                let add_test_nodes = false;
+
+                let mut project_tree = ProjectTreeView::new();
+
+                let root_node = project_tree
+                    .tree
+                    .add_node(ProjectTreeItem {
+                        key: "root".to_string(),
+                        path: TreePath::root(),
+                        ..ProjectTreeItem::default()
+                    });
+
+                let issues_node = project_tree
+                    .tree
+                    .add_node(ProjectTreeItem {
+                        key: "issues".to_string(),
+                        path: TreePath::root().join("issues"),
+                        ..ProjectTreeItem::default()
+                    });
+                project_tree
+                    .tree
+                    .add_edge(root_node, issues_node, ());
+
+                let parts_node = project_tree
+                    .tree
+                    .add_node(ProjectTreeItem {
+                        key: "parts".to_string(),
+                        path: TreePath::root().join("parts"),
+                        ..ProjectTreeItem::default()
+                    });
+                project_tree
+                    .tree
+                    .add_edge(root_node, parts_node, ());
+                add_parts_tree_nodes(&mut project_tree.tree, parts_node, project);
+
+                let placements_node = project_tree
+                    .tree
+                    .add_node(ProjectTreeItem {
+                        key: "placements".to_string(),
+                        path: TreePath::root().join("placements"),
+                        ..ProjectTreeItem::default()
+                    });
+                project_tree
+                    .tree
+                    .add_edge(root_node, placements_node, ());
+                add_placements_tree_nodes(&mut project_tree.tree, placements_node, project);
+
+                let pcbs_node = project_tree
+                    .tree
+                    .add_node(ProjectTreeItem {
+                        key: "pcbs".to_string(),
+                        path: TreePath::root().join("pcbs"),
+                        ..ProjectTreeItem::default()
+                    });
+                project_tree
+                    .tree
+                    .add_edge(root_node, pcbs_node, ());
+
+                for (pcb_index, (project_pcb, model_pcb)) in project
+                    .pcbs
+                    .iter()
+                    .zip(model_project.pcbs(&model.model_pcbs))
+                    .enumerate()
+                {
+                    let pcb_name = match model_pcb {
+                        Some(model_pcb) => model_pcb.pcb.name.clone(),
+                        None => project_pcb
+                            .pcb_file
+                            .build_path(&model_project.project_directory)
+                            .file_stem()
+                            .map(|file_stem| file_stem.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                    };
+
+                    let pcb_path = TreePath::root()
+                        .join("pcbs")
+                        .join(pcb_index.to_string());
+                    let pcb_node = project_tree
+                        .tree
+                        .add_node(ProjectTreeItem {
+                            key: "pcb".to_string(),
+                            args: HashMap::from([("name".to_string(), Arg::String(pcb_name))]),
+                            path: pcb_path.clone(),
+                        });
+                    project_tree
+                        .tree
+                        .add_edge(pcbs_node, pcb_node, ());
+
+                    let units_path = pcb_path.join("units");
+                    let unit_assignments_node = project_tree
+                        .tree
+                        .add_node(ProjectTreeItem {
+                            key: "unit-assignments".to_string(),
+                            path: units_path.clone(),
+                            ..ProjectTreeItem::default()
+                        });
+                    project_tree
+                        .tree
+                        .add_edge(pcb_node, unit_assignments_node, ());
+
+                    let unit_designs = match model_pcb {
+                        Some(ModelPcb {
+                            pcb, ..
+                        }) => pcb
+                            .unit_map
+                            .iter()
+                            .map(|(pcb_unit_index, design_index)| {
+                                let design_name = pcb
+                                    .design_names
+                                    .iter()
+                                    .nth(*design_index as usize)
+                                    .unwrap();
+                                (pcb_unit_index, design_name)
+                            })
+                            .collect::<Vec<_>>(),
+                        None => project_pcb
+                            .unit_assignments
+                            .iter()
+                            .map(|(pcb_unit_index, design_variant)| (pcb_unit_index, &design_variant.design_name))
+                            .collect::<Vec<_>>(),
+                    };
+
+                    for (map_index, (pcb_unit_index, design_name)) in unit_designs.into_iter().enumerate() {
+                        let mut object_path = ObjectPath::default();
+                        object_path.set_pcb_instance((pcb_index + 1) as u16);
+                        object_path.set_pcb_unit(pcb_unit_index + 1);
+
+                        let mut args = HashMap::from([("name".to_string(), Arg::String(object_path.to_string()))]);
+                        args.insert("design_name".to_string(), Arg::String(design_name.to_string()));
+
+                        if let Some(assignment_design_variant) = project_pcb
+                            .unit_assignments
+                            .get(pcb_unit_index)
+                        {
+                            let design_changed = assignment_design_variant
+                                .design_name
+                                .ne(design_name);
+                            if design_changed {
+                                // It's invalid for these to be mismatched; if this occurs, then the pcb variant map is
+                                // out of sync with the pcb unit assignments and needs to be re-synced; since that
+                                // should happen before this code, ignore this `unit_map` entry.
+                                error!("PCB unit map is out of sync with pcb unit assignments. map_index: {}, unit: {}, assignment_design_variant: {}, design_name: {}",
+                                    map_index, pcb_unit_index, assignment_design_variant, design_name
+                                );
+                                continue;
+                            } else {
+                                args.insert(
+                                    "variant_name".to_string(),
+                                    Arg::String(
+                                        assignment_design_variant
+                                            .variant_name
+                                            .to_string(),
+                                    ),
+                                );
+                            }
+                        }
+
+                        let unit_assignment_node = project_tree
+                            .tree
+                            .add_node(ProjectTreeItem {
+                                key: "unit-assignment".to_string(),
+                                args,
+                                path: units_path.join(pcb_unit_index.to_string()),
+                            });
+
+                        project_tree
+                            .tree
+                            .add_edge(unit_assignments_node, unit_assignment_node, ());
+                    }
+                }
+
+                let processes_node = project_tree
+                    .tree
+                    .add_node(ProjectTreeItem {
+                        key: "processes".to_string(),
+                        path: TreePath::root().join("processes"),
+                        ..ProjectTreeItem::default()
+                    });
+                project_tree
+                    .tree
+                    .add_edge(root_node, processes_node, ());
+
+                for process in project.processes.iter() {
+                    let process_node = project_tree
+                        .tree
+                        .add_node(ProjectTreeItem {
+                            key: "process".to_string(),
+                            args: HashMap::from([("name".to_string(), Arg::String(process.reference.to_string()))]),
+                            path: TreePath::root()
+                                .join("processes")
+                                .join(process.reference.to_string()),
+                        });
+
+                    project_tree
+                        .tree
+                        .add_edge(processes_node, process_node, ());
+                }
+
+                let phases_node = project_tree
+                    .tree
+                    .add_node(ProjectTreeItem {
+                        key: "phases".to_string(),
+                        path: TreePath::root().join("phases"),
+                        ..ProjectTreeItem::default()
+                    });
+                project_tree
+                    .tree
+                    .add_edge(root_node, phases_node, ());
+
+                for reference in &project.phase_orderings {
+                    let phase = project.phases.get(reference).unwrap();
+                    //
+                    // add phase node
+                    //
+                    let phase_path = TreePath::root()
+                        .join("phases")
+                        .join(reference.to_string());
+                    let phase_node = project_tree
+                        .tree
+                        .add_node(ProjectTreeItem {
+                            key: "phase".to_string(),
+                            args: HashMap::from([
+                                ("reference".to_string(), Arg::String(reference.to_string())),
+                                ("process".to_string(), Arg::String(phase.process.to_string())),
+                                ("pcb_side".to_string(), Arg::String(phase.pcb_side.to_string())),
+                            ]),
+                            path: phase_path.clone(),
+                        });
+                    project_tree
+                        .tree
+                        .add_edge(phases_node, phase_node, ());
+
+                    //
+                    // add loadout node to the phase
+                    //
+                    let loadout_node = project_tree
+                        .tree
+                        .add_node(ProjectTreeItem {
+                            key: "phase-loadout".to_string(),
+                            args: HashMap::from([(
+                                "source".to_string(),
+                                Arg::String(phase.load_out_source.to_string()),
+                            )]),
+                            path: phase_path.join("loadout"),
+                        });
+                    project_tree
+                        .tree
+                        .add_edge(phase_node, loadout_node, ());
+
+                    if add_test_nodes {
+                        let test_node = project_tree
+                            .tree
+                            .add_node(ProjectTreeItem {
+                                key: "test".to_string(),
+                                path: phase_path.join("test"),
+                                ..ProjectTreeItem::default()
+                            });
+                        project_tree
+                            .tree
+                            .add_edge(phase_node, test_node, ());
+                    }
+                }
+
+                if add_test_nodes {
+                    let test_node = project_tree
+                        .tree
+                        .add_node(ProjectTreeItem {
+                            key: "test".to_string(),
+                            path: TreePath::root().join("test"),
+                            ..ProjectTreeItem::default()
+                        });
+                    project_tree
+                        .tree
+                        .add_edge(root_node, test_node, ());
+                }
+
+                Ok(project_view_renderer::view(ProjectView::ProjectTree(project_tree)))
+            }),
+            Event::RequestPhasesView {} => Box::new(|model: &mut Model| {
+                let (
+                    ModelProject {
+                        project, ..
+                    },
+                    directory,
+                ) = Self::model_project_and_directory(model)?;
+
+                let phases = project
+                    .phase_orderings
+                    .iter()
+                    .map(|phase_reference| {
+                        let phase = project
+                            .phases
+                            .get(phase_reference)
+                            .unwrap();
+                        let phase_state = project
+                            .phase_states
+                            .get(phase_reference)
+                            .unwrap();
+                        let can_start = project.can_start_phase(&phase_reference);
+
+                        // FUTURE try and avoid the [`unwrap`] here, ideally by ensuring load-out sources are always correct
+                        //        for every situation instead of using [`try_build_phase_load_out_source`]
+                        try_build_phase_overview(
+                            &directory,
+                            phase_reference.clone(),
+                            phase,
+                            can_start,
+                            phase_state,
+                            project
+                                .find_process(&phase.process)
+                                .ok(),
+                            &project.store_backend,
+                        )
+                        .unwrap()
+                    })
+                    .collect::<Vec<PhaseOverview>>();
+
+                let phases = Phases {
+                    phases,
+                };
+
+                Ok(project_view_renderer::view(ProjectView::Phases(phases)))
+            }),
+
+            Event::RequestPhaseOverviewView {
+                phase_reference,
+            } => Box::new(|model: &mut Model| {
+                let (
+                    ModelProject {
+                        project, ..
+                    },
+                    directory,
+                ) = Self::model_project_and_directory(model)?;
+
+                let phase = project
+                    .phases
+                    .get(&phase_reference)
+                    .ok_or(AppError::UnknownPhaseReference(phase_reference.clone()))?;
+                let phase_state = project
+                    .phase_states
+                    .get(&phase_reference)
+                    .unwrap();
+                let can_start = project.can_start_phase(&phase_reference);
+
+                let phase_overview = try_build_phase_overview(
+                    &directory,
+                    phase_reference,
+                    phase,
+                    can_start,
+                    phase_state,
+                    project
+                        .find_process(&phase.process)
+                        .ok(),
+                    &project.store_backend,
+                )
+                .map_err(AppError::SourceError)?;
+
+                Ok(project_view_renderer::view(ProjectView::PhaseOverview(phase_overview)))
+            }),
+            Event::RequestProcessDefinitionView {
+                process_reference,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project, ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let process = project
+                    .processes
+                    .iter()
+                    .find(|it| it.reference.eq(&process_reference))
+                    .ok_or(AppError::UnknownProcessReference(process_reference.clone()))?;
+
+                Ok(project_view_renderer::view(ProjectView::ProcessDefinition(
+                    process.clone(),
+                )))
+            }),
+            Event::RequestPhasePlacementsView {
+                phase_reference,
+                page,
+            } => Box::new(move |model: &mut Model| {
+                let (model_project, pcbs, project_directory) = Self::model_project_and_pcbs(model)?;
+
+                let (placements, _load_out_items, mut nozzle_assignments) =
+                    Self::sorted_phase_placements(&model_project.project, &pcbs, &project_directory, &phase_reference)?;
+
+                let placements = placements
+                    .into_iter()
+                    .enumerate()
+                    .map(|(ordering, (path, state))| PlacementsItem {
+                        nozzle: nozzle_assignments.remove(&path),
+                        path,
+                        state,
+                        ordering,
+                    })
+                    .collect();
+
+                let PlacementsPage {
+                    placements,
+                    offset,
+                    total,
+                } = paging::page_placements(placements, page.as_ref());
+
+                let phase_placements = PhasePlacements {
+                    phase_reference,
+                    placements,
+                    offset,
+                    total,
+                };
+                Ok(project_view_renderer::view(ProjectView::PhasePlacements(
+                    phase_placements,
+                )))
+            }),
+            Event::RequestPartStatesView {} => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project, ..
+                } = model
+                    .model_project
+                    .as_mut()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let mut parts = project
+                    .part_states
+                    .iter()
+                    .map(|(part, state)| {
+                        let processes = state
+                            .applicable_processes
+                            .iter()
+                            .cloned()
+                            .collect();
+                        PartWithState {
+                            part: part.clone(),
+                            alias_group: project
+                                .part_aliases
+                                .find_group(part)
+                                .cloned(),
+                            processes,
+                            ref_des_set: Default::default(),
+                            quantity: 0,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                //
+                // add the set of ref_des and count the quantity for each part.
+                //
+                for (_object_path, placement_state) in project.placements.iter_mut() {
+                    if let Some(part) = parts
+                        .iter_mut()
+                        .find(|part_with_state| {
+                            part_with_state
+                                .part
+                                .eq(&placement_state.placement.part)
+                        })
+                    {
+                        part.quantity += 1;
+                        let _inserted = part.ref_des_set.insert(
+                            placement_state
+                                .placement
+                                .ref_des
+                                .clone(),
+                        );
+                    }
+                }
+
+                let part_states_view = PartStates {
+                    parts,
+                };
+
+                Ok(project_view_renderer::view(ProjectView::Parts(part_states_view)))
+            }),
+            Event::RequestPartPackagesView {} => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project, ..
+                } = model
+                    .model_project
+                    .as_ref()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let mut packages = Vec::new();
+                let mut package_mappings = Vec::new();
+                let part_packages_map = Self::load_part_packages_map(project, &mut packages, &mut package_mappings)?;
+
+                let parts = project
+                    .part_states
+                    .keys()
+                    .map(|part| PartPackage {
+                        part: part.clone(),
+                        package: part_packages_map
+                            .get(part)
+                            .map(|&package| package.clone()),
+                    })
+                    .collect::<Vec<_>>();
+
+                let part_packages_view = PartPackages {
+                    parts,
+                };
+
+                Ok(project_view_renderer::view(ProjectView::PartPackages(
+                    part_packages_view,
+                )))
+            }),
+            Event::RequestPhaseLoadOutView {
+                phase_reference,
+            } => Box::new(move |model: &mut Model| {
+                let (
+                    ModelProject {
+                        project, ..
+                    },
+                    directory,
+                ) = Self::model_project_and_directory(model)?;
+
+                let phase = project
+                    .phases
+                    .get(&phase_reference)
+                    .ok_or(AppError::UnknownPhaseReference(phase_reference.clone()))?;
+
+                let load_out_source = try_build_phase_load_out_source(&directory, &phase, &project.store_backend)
+                    .map_err(AppError::SourceError)?;
+
+                let items = Self::open_store(project, &directory)?
+                    .load_items(&load_out_source)
+                    .map_err(AppError::OperationError)?;
+
+                let load_out_view = LoadOut {
+                    phase_reference,
+                    source: load_out_source,
+                    items,
+                };
+
+                Ok(project_view_renderer::view(ProjectView::PhaseLoadOut(load_out_view)))
+            }),
+            Event::RequestFeederLayoutView {
+                phase_reference,
+            } => Box::new(move |model: &mut Model| {
+                let (
+                    ModelProject {
+                        project, ..
+                    },
+                    directory,
+                ) = Self::model_project_and_directory(model)?;
+
+                let phase = project
+                    .phases
+                    .get(&phase_reference)
+                    .ok_or(AppError::UnknownPhaseReference(phase_reference.clone()))?;
+
+                let process = project
+                    .find_process(&phase.process)
+                    .map_err(AppError::ProcessError)?;
+
+                let load_out_source = try_build_phase_load_out_source(&directory, &phase, &project.store_backend)
+                    .map_err(AppError::SourceError)?;
+
+                let items = Self::open_store(project, &directory)?
+                    .load_items(&load_out_source)
+                    .map_err(AppError::OperationError)?;
+
+                let issues = pnp::load_out::validate_feeder_layout(&process.feeder_banks, &items);
+
+                let banks = process
+                    .feeder_banks
+                    .iter()
+                    .map(|bank| FeederLayoutBank {
+                        reference: bank.reference.clone(),
+                        slots: bank
+                            .slots
+                            .iter()
+                            .map(|slot| {
+                                let (x, y) = bank.slot_position(slot);
+                                let items = items
+                                    .iter()
+                                    .filter(|item| item.reference.as_ref() == Some(&slot.reference))
+                                    .cloned()
+                                    .collect();
+
+                                FeederLayoutSlot {
+                                    reference: slot.reference.clone(),
+                                    x,
+                                    y,
+                                    width: slot.width,
+                                    items,
+                                }
+                            })
+                            .collect(),
+                    })
+                    .collect();
+
+                let unassigned_items = items
+                    .into_iter()
+                    .filter(|item| match &item.reference {
+                        Some(reference) => pnp::load_out::find_feeder_slot(&process.feeder_banks, reference).is_none(),
+                        None => true,
+                    })
+                    .collect();
+
+                let feeder_layout = FeederLayout {
+                    phase_reference,
+                    banks,
+                    unassigned_items,
+                    issues,
+                };
+
+                Ok(project_view_renderer::view(ProjectView::FeederLayout(feeder_layout)))
+            }),
+            Event::RequestProjectReportView {} => Box::new(|model: &mut Model| {
+                let (
+                    ModelProject {
+                        project, ..
+                    },
+                    pcbs,
+                    project_directory,
+                ) = { Self::model_project_and_pcbs(model) }?;
+
+                let phase_load_out_item_map = Self::build_phase_load_out_item_map(project, &project_directory)
+                    .map_err(AppError::OperationError)?;
+
+                let report = report::project_generate_report(project, &pcbs, &phase_load_out_item_map);
+
+                Ok(project_view_renderer::view(ProjectView::ProjectReport(report)))
+            }),
+            Event::RequestIssuesView {} => Box::new(|model: &mut Model| {
+                let (
+                    ModelProject {
+                        project, ..
+                    },
+                    pcbs,
+                    project_directory,
+                ) = { Self::model_project_and_pcbs(model) }?;
+
+                let phase_load_out_item_map = Self::build_phase_load_out_item_map(project, &project_directory)
+                    .map_err(AppError::OperationError)?;
+
+                let issues = issues::analyze(project, &pcbs, &phase_load_out_item_map, IssueCheck::ALL)
+                    .into_iter()
+                    .map(ProjectIssue::from)
+                    .collect();
+
+                Ok(project_view_renderer::view(ProjectView::Issues(ProjectIssues {
+                    issues,
+                })))
+            }),
+            Event::RequestAnalyticsView {} => Box::new(|model: &mut Model| {
+                let ModelProject {
+                    project,
+                    project_directory,
+                    ..
+                } = model
+                    .model_project
+                    .as_ref()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let operation_histories = analytics::read_operation_histories(project, project_directory)
+                    .map_err(|cause| AppError::OperationError(cause.into()))?;
+
+                let project_analytics = analytics::build_analytics(project, &operation_histories);
+
+                Ok(project_view_renderer::view(ProjectView::Analytics(project_analytics)))
+            }),
+            Event::RequestPlacementSessionView {} => Box::new(|model: &mut Model| Self::placement_session_view(model)),
+            Event::RequestSearchView {
+                query,
+            } => Box::new(move |model: &mut Model| {
+                let ModelProject {
+                    project, ..
+                } = model
+                    .model_project
+                    .as_ref()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                let search_results = search::search(project, &query);
+
+                Ok(project_view_renderer::view(ProjectView::Search(search_results)))
+            }),
+            Event::RequestSettingsView {} => Box::new(|model: &mut Model| {
+                let ModelProject {
+                    project, ..
+                } = model
+                    .model_project
+                    .as_ref()
+                    .ok_or(AppError::OperationRequiresProject)?;
+
+                Ok(project_view_renderer::view(ProjectView::Settings(
+                    project.settings.clone(),
+                )))
+            }),
+        }
+    }
+
+    fn build_phase_load_out_item_map(
+        project: &mut Project,
+        project_directory: &PathBuf,
+    ) -> Result<BTreeMap<Reference, Vec<LoadOutItem>>, Error> {
+        let store = stores::backend::open_store(&project.store_backend, project_directory)?;
+
+        project.phases.iter().try_fold(
+            BTreeMap::<Reference, Vec<LoadOutItem>>::new(),
+            |mut map, (reference, phase)| {
+                let load_out_source = match &project.store_backend {
+                    StoreBackend::Csv if !LoadOutSource::is_url(&phase.load_out_source) => {
+                        LoadOutSource::try_from_path(&project_directory, PathBuf::from_str(&phase.load_out_source)?)?
+                    }
+                    _ => try_build_phase_load_out_source(project_directory, phase, &project.store_backend)?,
+                };
+                let load_out_items = store.load_items(&load_out_source)?;
+                map.insert(reference.clone(), load_out_items);
+                Ok::<BTreeMap<Reference, Vec<LoadOutItem>>, anyhow::Error>(map)
+            },
+        )
+    }
+
+    fn load_packages(packages_source: &PackagesSource) -> Result<Vec<Package>, AppError> {
+        let packages: Vec<Package> = stores::packages::load_packages(packages_source)
+            .map_err(|error| AppError::OperationError(anyhow!("package source error. cause: {:?}", error)))?;
+
+        Ok(packages)
+    }
+
+    fn load_package_mappings<'a>(
+        package_mappings_source: &PackageMappingsSource,
+        packages: &'a Vec<Package>,
+    ) -> Result<Vec<PackageMapping<'a>>, AppError> {
+        let package_mappings = stores::package_mappings::load_package_mappings(packages, package_mappings_source)
+            .map_err(|error| AppError::OperationError(anyhow!("package source error. cause: {:?}", error)))?;
+
+        Ok(package_mappings)
+    }
+
+    fn project_unique_parts(project: &Project) -> BTreeSet<&Part> {
+        let unique_parts = BTreeSet::from_iter(
+            project
+                .placements
+                .iter()
+                .map(|(_path, placement_state)| &placement_state.placement.part),
+        );
+
+        unique_parts
+    }
+
+    fn build_project_part_package_map<'a, 'b, 'c, 'd>(
+        unique_parts: &'a BTreeSet<&'b Part>,
+        package_mappings: &'c Vec<PackageMapping<'d>>,
+    ) -> Result<BTreeMap<&'b Part, &'c Package>, AppError> {
+        let part_packages_map = package_mapper::PackageMapper::process(unique_parts, package_mappings)
+            .map_err(|mapper_error| AppError::OperationError(anyhow!("part mapper error. cause: {:?}", mapper_error)))?
+            .into_iter()
+            .filter_map(|result| {
+                result
+                    .package
+                    .map(|package| (result.part, package))
+            })
+            .collect::<BTreeMap<_, _>>();
+        Ok(part_packages_map)
+    }
+
+    // Due to the lifetimes of the mappings, the storage for the packages and package mappings needs to be provided
+    // up-front.
+    fn load_part_packages_map<'a, 'b>(
+        project: &'a Project,
+        // Provide mutable references to variables that will be populated
+        packages_storage: &'b mut Vec<Package>,
+        package_mappings_storage: &'b mut Vec<PackageMapping<'b>>,
+    ) -> Result<BTreeMap<&'a Part, &'b Package>, AppError> {
+        // Check if both sources are available
+        let (Some(packages_source), Some(package_mappings_source)) = (
+            project
+                .library_config
+                .package_source
+                .as_ref(),
+            project
+                .library_config
+                .package_mappings_source
+                .as_ref(),
+        ) else {
+            return Ok(BTreeMap::new());
+        };
+
+        // Load the packages and store them in the provided output vector
+        *packages_storage = Self::load_packages(packages_source)?;
+
+        // Load the mappings and store them in the provided output vector
+        *package_mappings_storage = Self::load_package_mappings(package_mappings_source, packages_storage)?;
+
+        // Build the mapping
+        let unique_parts = Self::project_unique_parts(project);
+        let part_packages_map = Self::build_project_part_package_map(&unique_parts, package_mappings_storage)?;
+
+        Ok(part_packages_map)
+    }
+
+    fn create_and_add_pcb(
+        name: String,
+        units: u16,
+        unit_map: BTreeMap<PcbUnitNumber, DesignName>,
+        model: &mut Model,
+        pcb_path: &PathBuf,
+    ) -> Result<Command<Effect, Event>, AppError> {
+        let pcb = planning::pcb::create_pcb(name, units, unit_map).map_err(AppError::PcbOperationError)?;
+
+        let modified = Modified::unsaved();
+        let command = Self::write_pcb_file(pcb_path.clone(), &pcb, modified.revision())?;
+
+        model
+            .model_pcbs
+            .insert(pcb_path.clone(), ModelPcb {
+                pcb,
+                // not saved, yet
+                modified,
+                file_modified: None,
+            });
+
+        Ok(command)
+    }
+
+    /// Requests the shell to remove the recovery file, the result is handled by [`Event::RecoveryFileRemoved`].
+    fn remove_recovery_file(path: PathBuf) -> Command<Effect, Event> {
+        file_io::request(
+            FileIoOperation::Remove {
+                path: path.clone(),
+            },
+            move |result| Event::RecoveryFileRemoved {
+                path,
+                result,
+            },
+        )
+    }
+
+    /// Requests the shell to write the PCB file, the result is handled by [`Event::PcbFileWritten`].
+    ///
+    /// `revision` is the modification revision of the PCB, see [`Modified`].
+    fn write_pcb_file(path: PathBuf, pcb: &Pcb, revision: u64) -> Result<Command<Effect, Event>, AppError> {
+        let content = file::to_bytes(pcb).map_err(AppError::IoError)?;
+
+        Ok(file_io::request(
+            FileIoOperation::Write {
+                path: path.clone(),
+                content,
+            },
+            move |result| Event::PcbFileWritten {
+                path,
+                result,
+                revision,
+            },
+        ))
+    }
+
+    /// Requests the shell to read the modification time of the PCB file, the result is handled by
+    /// [`Event::PcbFileModifiedTimeRead`].
+    fn pcb_file_modified_time_request(path: PathBuf, reload: bool) -> Command<Effect, Event> {
+        file_io::request(
+            FileIoOperation::Modified {
+                path: path.clone(),
+            },
+            move |result| Event::PcbFileModifiedTimeRead {
+                path,
+                reload,
+                result,
+            },
+        )
+    }
+
+    /// Loads the PCBs of the project that are not loaded, or that have changed since they were loaded.
+    fn project_pcb_requests(model_project: &ModelProject) -> Command<Effect, Event> {
+        Command::all(
+            model_project
+                .project
+                .pcbs
+                .iter()
+                .map(|project_pcb| {
+                    let pcb_path = project_pcb
+                        .pcb_file
+                        .build_path(&model_project.project_directory);
+                    Self::pcb_file_modified_time_request(pcb_path, false)
+                }),
+        )
+    }
+
+    /// Requests the shell to write to the replay file, the result is handled by [`Event::EventRecordingWritten`].
+    fn event_recording_request(path: PathBuf, operation: FileIoOperation) -> Command<Effect, Event> {
+        file_io::request(operation, move |result| Event::EventRecordingWritten {
+            path,
+            result,
+        })
+    }
+
+    fn gerber_file_to_pcb_gerber_item(gerber_file: &GerberFile) -> PcbGerberItem {
+        // convert from project type to view type
+        PcbGerberItem {
+            path: gerber_file.file.clone(),
+            function: gerber_file.function,
+        }
+    }
+
+    fn model_project_and_pcbs(model: &mut Model) -> Result<(&mut ModelProject, Vec<&Pcb>, PathBuf), AppError> {
+        let Some(model_project) = model.model_project.as_mut() else {
+            return Err(AppError::OperationRequiresProject);
+        };
+
+        let project_directory = model_project
+            .path
+            .parent()
+            .unwrap()
+            .to_path_buf();
+
+        Model::ensure_project_pcbs_loaded(&model_project.project, &model.model_pcbs, &project_directory)?;
+
+        let iter = model_project.pcbs(&model.model_pcbs);
+        let pcbs = Model::project_pcbs_inner(iter);
+
+        Ok((model_project, pcbs, project_directory))
+    }
+
+    fn placement_location(
+        pcb_index: u16,
+        object_path: ObjectPath,
+        pcb_side: PcbSide,
+        unit_position: &UnitPlacementPosition,
+    ) -> PlacementLocation {
+        let build_position = |x: Decimal| {
+            PlacementPositionUnit::new(
+                DimensionUnitPoint2::new_dim_decimal(x, unit_position.y, UnitSystem::Millimeters),
+                AngleUnit::new_degrees_decimal(unit_position.rotation),
+            )
+        };
+
+        let viewed_x = match pcb_side {
+            PcbSide::Top => unit_position.x,
+            PcbSide::Bottom => -unit_position.x,
+        };
+
+        PlacementLocation {
+            pcb: pcb_index,
+            object_path,
+            pcb_side,
+            position: build_position(unit_position.x),
+            viewed_position: build_position(viewed_x),
+        }
+    }
+
+    /// Like [`Self::model_project_and_pcbs`], but only the PCB at the index is loaded, for views of a single PCB.
+    fn model_project_and_pcb(model: &mut Model, pcb_index: u16) -> Result<(&mut ModelProject, &Pcb), AppError> {
+        let Some(model_project) = model.model_project.as_mut() else {
+            return Err(AppError::OperationRequiresProject);
+        };
+
+        let pcb_path = model_project
+            .project
+            .pcbs
+            .get(pcb_index as usize)
+            .ok_or(AppError::PcbOperationError(PcbOperationError::Unknown))?
+            .pcb_file
+            .build_path(&model_project.project_directory);
+
+        let model_pcb = Model::loaded_pcb(&mut model.model_pcbs, &pcb_path)?;
+
+        Ok((model_project, &model_pcb.pcb))
+    }
+
+    /// The outline is the profile outline, if any, otherwise the panel size.
+    fn board_preview_view(
+        model: &mut Model,
+        pcb_index: u16,
+        profile_outline: Option<Outline>,
+    ) -> Result<Command<Effect, Event>, AppError> {
+        let (
+            ModelProject {
+                project, ..
+            },
+            pcb,
+        ) = Self::model_project_and_pcb(model, pcb_index)?;
+
+        let outline = match profile_outline {
+            Some(mut outline) => {
+                outline.translate((pcb.gerber_offset.x, pcb.gerber_offset.y));
+                outline
+            }
+            None => {
+                let size = pcb.panel_sizing.size;
+                Outline {
+                    contours: vec![Contour {
+                        vertices: vec![(0.0, 0.0), (size.x, 0.0), (size.x, size.y), (0.0, size.y)],
+                        closed: true,
+                    }],
+                }
+            }
+        };
+
+        let mut packages = Vec::new();
+        let mut package_mappings = Vec::new();
+        let part_packages_map = Self::load_part_packages_map(project, &mut packages, &mut package_mappings)?;
+
+        // reminder: the pcb instance is 1-based in the object path
+        let components = project
+            .placements
+            .iter()
+            .filter(|(path, state)| {
+                state.placement.place
+                    && state.project_status == ProjectPlacementStatus::Used
+                    && path
+                        .pcb_instance()
+                        .is_ok_and(|pcb_instance| pcb_instance == pcb_index + 1)
+            })
+            .map(|(path, state)| BoardPreviewComponent {
+                path: path.clone(),
+                ref_des: state.placement.ref_des.clone(),
+                pcb_side: state.placement.pcb_side,
+                x: state.unit_position.x,
+                y: state.unit_position.y,
+                rotation: state.unit_position.rotation,
+                dimensions: part_packages_map
+                    .get(&state.placement.part)
+                    .and_then(|package| package.dimensions_mm.clone()),
+                placed: state.operation_status == PlacementStatus::Placed,
+            })
+            .collect();
+
+        let board_preview = BoardPreview {
+            index: pcb_index,
+            outline,
+            components,
+        };
+
+        Ok(project_view_renderer::view(ProjectView::BoardPreview(board_preview)))
+    }
+
+    /// Returns the placements assigned to the phase, in placement order, the phase's load-out items and the nozzle
+    /// assignments of the placements.
+    fn sorted_phase_placements(
+        project: &Project,
+        pcbs: &[&Pcb],
+        project_directory: &PathBuf,
+        phase_reference: &PhaseReference,
+    ) -> Result<(Vec<(ObjectPath, PlacementState)>, Vec<LoadOutItem>, NozzleAssignments), AppError> {
+        let phase = project
+            .phases
+            .get(phase_reference)
+            .ok_or(AppError::UnknownPhaseReference(phase_reference.clone()))?;
+
+        let process = project
+            .find_process(&phase.process)
+            .map_err(AppError::ProcessError)?;
+
+        let load_out_source = try_build_phase_load_out_source(project_directory, phase, &project.store_backend)
+            .map_err(AppError::SourceError)?;
+
+        let loadout_items = Self::open_store(project, project_directory)?
+            .load_items(&load_out_source)
+            .map_err(AppError::OperationError)?;
+
+        let mut placements: Vec<(&ObjectPath, &PlacementState)> = project
+            .placements
+            .iter()
+            .filter(|(_path, state)| match &state.phase {
+                Some(candidate_phase) if phase_reference == candidate_phase => true,
+                _ => false,
+            })
+            .collect();
+
+        let pcb_unit_positioning_map = project::build_pcbs_unit_positioning_map(pcbs);
+
+        let mut packages = Vec::new();
+        let mut package_mappings = Vec::new();
+        let part_packages_map = Self::load_part_packages_map(project, &mut packages, &mut package_mappings)?;
+
+        let nozzle_assignments = process
+            .nozzles
+            .assign_nozzles(&placements, &part_packages_map);
+
+        project::sort_placements(
+            &mut placements,
+            &phase.placement_orderings,
+            &loadout_items,
+            &project.part_aliases,
+            &part_packages_map,
+            &pcb_unit_positioning_map,
+            &nozzle_assignments,
+        );
+
+        let placements = placements
+            .into_iter()
+            .map(|(path, state)| (path.clone(), state.clone()))
+            .collect();
+
+        Ok((placements, loadout_items, nozzle_assignments))
+    }
+
+    /// Record the operation, if any, for the current placement of the placement session, then advance the session.
+    fn update_placement_session(
+        model: &mut Model,
+        operation: Option<PlacementOperation>,
+    ) -> Result<Command<Effect, Event>, AppError> {
+        let ModelProject {
+            project,
+            path,
+            modified,
+            placement_session,
+            ..
+        } = model
+            .model_project
+            .as_mut()
+            .ok_or(AppError::OperationRequiresProject)?;
+
+        let placement_session = placement_session
+            .as_mut()
+            .ok_or(AppError::PlacementSessionRequired)?;
+        let index = placement_session
+            .current
+            .ok_or(AppError::PlacementSessionComplete)?;
+
+        if let Some(operation) = operation {
+            let object_path = &placement_session.object_paths[index];
+            let pattern = ObjectPathPattern::exact(object_path);
+
+            let directory = parent_directory(&path);
+            *modified |=
+                project::update_placements_operation(project, directory, vec![pattern], operation.clone(), None)
+                    .map_err(AppError::OperationError)?;
+
+            match operation {
+                PlacementOperation::Place => placement_session.placed += 1,
+                PlacementOperation::Skip => placement_session.skipped += 1,
+                PlacementOperation::Reset
+                | PlacementOperation::Inspected {
+                    ..
+                } => {}
+            }
+        }
+
+        placement_session.advance(&project.placements);
+
+        Ok(Command::all([render::render(), Self::placement_session_view(model)?]))
+    }
+
+    fn placement_session_view(model: &mut Model) -> Result<Command<Effect, Event>, AppError> {
+        let (
+            ModelProject {
+                project,
+                placement_session,
+                ..
+            },
+            project_directory,
+        ) = Self::model_project_and_directory(model)?;
+
+        let placement_session = placement_session
+            .as_ref()
+            .ok_or(AppError::PlacementSessionRequired)?;
+
+        let phase = project
+            .phases
+            .get(&placement_session.phase)
+            .ok_or(AppError::UnknownPhaseReference(placement_session.phase.clone()))?;
+
+        let load_out_source = try_build_phase_load_out_source(&project_directory, phase, &project.store_backend)
+            .map_err(AppError::SourceError)?;
+        let load_out_items = Self::open_store(project, &project_directory)?
+            .load_items(&load_out_source)
+            .map_err(AppError::OperationError)?;
+
+        let current = placement_session
+            .current
+            .map(|index| &placement_session.object_paths[index])
+            .and_then(|path| {
+                project
+                    .placements
+                    .get(path)
+                    .map(|state| (path, state))
+            })
+            .map(|(path, state)| {
+                let feeder_reference = pnp::load_out::find_load_out_item_by_part_or_alias(
+                    &load_out_items,
+                    &state.placement.part,
+                    &project.part_aliases,
+                )
+                .and_then(|load_out_item| load_out_item.reference.clone());
+
+                let position = PlacementPositionUnit::new(
+                    DimensionUnitPoint2::new_dim_decimal(
+                        state.unit_position.x,
+                        state.unit_position.y,
+                        UnitSystem::Millimeters,
+                    ),
+                    AngleUnit::new_degrees_decimal(state.unit_position.rotation),
+                );
+
+                PlacementSessionItem {
+                    path: path.clone(),
+                    state: state.clone(),
+                    position,
+                    feeder_reference,
+                }
+            });
+
+        let remaining = placement_session
+            .object_paths
+            .iter()
+            .filter(|path| {
+                project
+                    .placements
+                    .get(*path)
+                    .is_some_and(|state| state.operation_status == PlacementStatus::Pending)
+            })
+            .count();
+
+        let progress = PlacementSessionProgress {
+            total: placement_session.object_paths.len(),
+            placed: placement_session.placed,
+            skipped: placement_session.skipped,
+            remaining,
+        };
+
+        Ok(project_view_renderer::view(ProjectView::PlacementSession(
+            PlacementSession {
+                phase_reference: placement_session.phase.clone(),
+                current,
+                progress,
+            },
+        )))
+    }
+
+    fn model_project_and_directory(model: &mut Model) -> Result<(&mut ModelProject, PathBuf), AppError> {
+        let Some(model_project) = model.model_project.as_mut() else {
+            return Err(AppError::OperationRequiresProject);
+        };
+
+        let project_directory = model_project
+            .path
+            .parent()
+            .unwrap()
+            .to_path_buf();
+        Ok((model_project, project_directory))
+    }
+
+    fn open_store(project: &Project, project_directory: &Path) -> Result<Box<dyn Store>, AppError> {
+        stores::backend::open_store(&project.store_backend, project_directory).map_err(AppError::OperationError)
+    }
+
+    /// The load-out of the phase, for checking the process rules.
+    ///
+    /// A load-out that cannot be loaded is treated as empty, so that actions which do not depend on the load-out are
+    /// not prevented, the load-out rules report the missing items instead.
+    fn load_phase_load_out_items_for_rules(
+        project: &Project,
+        project_directory: &Path,
+        phase_reference: &PhaseReference,
+        warnings: &mut Warnings,
+    ) -> Result<Vec<LoadOutItem>, AppError> {
+        let phase = project
+            .phases
+            .get(phase_reference)
+            .ok_or(AppError::UnknownPhaseReference(phase_reference.clone()))?;
+
+        let load_out_source =
+            try_build_phase_load_out_source(&project_directory.to_path_buf(), phase, &project.store_backend)
+                .map_err(AppError::SourceError)?;
+
+        let load_out_items = Self::open_store(project, project_directory)?
+            .load_items(&load_out_source)
+            .unwrap_or_else(|error| {
+                push_warning(warnings, AppWarning::LoadOutUnavailableForRules {
+                    phase: phase_reference.clone(),
+                    cause: format!("{:#}", error),
+                });
+                Vec::new()
+            });
+
+        Ok(load_out_items)
+    }
+}
+
+impl App for Planner {
+    type Event = Event;
+    type Model = Model;
+    type ViewModel = PlannerOperationViewModel;
+    type Capabilities = ();
+    type Effect = Effect;
+
+    fn update(
+        &self,
+        event: Self::Event,
+        model: &mut Self::Model,
+        _caps: &Self::Capabilities,
+    ) -> Command<Self::Effect, Self::Event> {
+        if matches!(event, Event::EventRecordingWritten { .. }) {
+            return self.update_inner(event)(model).unwrap_or_else(|_| Command::done());
+        }
+
+        // serialized before processing, since the event is consumed.
+        let recorded_event = model
+            .event_recorder
+            .as_ref()
+            .map(|_| serde_json::to_value(&event));
+
+        let changes = view_dependencies::event_changes(&event);
+
+        let try_fn = self.update_inner(event);
+
+        model.warnings.clear();
+        model.delete_report.take();
+        model.placement_selection.take();
+        model.refreshed_design_variants.take();
+
+        let command = match try_fn(model) {
+            Err(e) => {
+                model
+                    .error
+                    .replace((chrono::Utc::now(), AppErrorView::from(&e)));
+                render::render()
+            }
+            Ok(command) => {
+                model.error.take();
+
+                // events that do not render, e.g. view requests, can have warnings too.
+                let command = if model.warnings.is_empty() {
+                    command
+                } else {
+                    Command::all([render::render(), command])
+                };
+
+                match &model.model_project {
+                    Some(model_project) if !changes.is_empty() => {
+                        let invalidations = view_dependencies::invalidated_views(changes, &model_project.project)
+                            .into_iter()
+                            .map(view_invalidation::invalidated);
+                        Command::all([command, Command::all(invalidations)])
+                    }
+                    _ => command,
+                }
+            }
+        };
+
+        // the events that start and stop recording are not recorded.
+        if let (Some(event_recorder), Some(recorded_event)) = (&model.event_recorder, recorded_event) {
+            let view_model = self.view(model);
+            let result = recorded_event.and_then(|event| {
+                let entry = ReplayEntry {
+                    date_time: chrono::Utc::now(),
+                    event,
+                    modified: ModifiedFlags {
+                        project_modified: view_model.project_modified,
+                        pcbs_modified: view_model.pcbs_modified,
+                    },
+                };
+                event_recorder.record(&entry)
+            });
+
+            match result {
+                Ok(operation) => {
+                    let path = event_recorder.path().to_path_buf();
+                    return Command::all([command, Self::event_recording_request(path, operation)]);
+                }
+                Err(cause) => {
+                    warn!(
+                        "Unable to record event. path: {:?}, cause: {:?}",
+                        event_recorder.path(),
+                        cause
+                    );
+                }
+            }
+        }
+
+        command
+    }
+
+    fn view(&self, model: &Self::Model) -> Self::ViewModel {
+        let project_modified = model
+            .model_project
+            .as_ref()
+            .map_or(false, |project| project.modified.is_modified());
+
+        let pcbs_modified = model
+            .model_pcbs
+            .iter()
+            .any(|(_file_reference, pcb)| pcb.modified.is_modified());
+
+        let recovery_available = model
+            .model_project
+            .as_ref()
+            .map_or(false, |project| project.recovery.is_some());
+
+        let view_model = PlannerOperationViewModel {
+            project_modified,
+            pcbs_modified,
+            recovery_available,
+            error: model.error.clone(),
+            warnings: model.warnings.clone(),
+            delete_report: model.delete_report.clone(),
+            placement_selection: model.placement_selection,
+            refreshed_design_variants: model.refreshed_design_variants.clone(),
+        };
+
+        trace!("view model: {:?}", view_model);
+
+        view_model
+    }
+}
+
+#[derive(Error, Debug)]
+enum AppError {
+    #[error("Operation requires a project")]
+    OperationRequiresProject,
+    #[error("Operation error, cause: {0}")]
+    OperationError(anyhow::Error),
+    #[error("Phase error. cause: {0}")]
+    PhaseError(PhaseError),
+    #[error("Project error, cause: {0}")]
+    ProjectError(ProjectError),
+    #[error("Template error. cause: {0}")]
+    TemplateError(TemplateError),
+    #[error("Archive error. cause: {0}")]
+    ArchiveError(ArchiveError),
+    #[error("Process error. cause: {0}")]
+    ProcessError(ProcessError),
+    #[error("Part error. cause: {0}")]
+    PartError(PartStateError),
+    #[error("Source error. cause: {0}")]
+    SourceError(SourceError),
+    #[error("Loadout error. cause: {0}")]
+    LoadoutError(LoadOutOperationError),
+    #[error("PCB error. cause: {0}")]
+    PcbOperationError(PcbOperationError),
+    #[error("IO error. cause: {0}")]
+    IoError(std::io::Error),
+    #[error("Inspection error. cause: {0}")]
+    InspectionError(InspectionError),
+    #[error("Cancelled. cause: {0}")]
+    Cancelled(Cancelled),
+    #[error("Vision error. cause: {0}")]
+    VisionError(String),
+    #[error("Serial number error. cause: {0}")]
+    SerialNumberError(SerialNumberError),
+
+    #[error("Operation requires a placement session")]
+    PlacementSessionRequired,
+    #[error("Placement session has no more pending placements")]
+    PlacementSessionComplete,
+
+    #[error("Unknown phase reference. reference: {0}")]
+    UnknownPhaseReference(Reference),
+    #[error("Unknown process reference. reference: {0}")]
+    UnknownProcessReference(ProcessReference),
+    #[error("Unknown placement selection. selection: {0}")]
+    UnknownPlacementSelection(SelectionId),
+    #[error("Unknown placement. object_path: {0}")]
+    UnknownPlacement(ObjectPath),
+}
+
+impl AppError {
+    fn from_pcb_error(error: PcbError) -> Self {
+        match error {
+            PcbError::Cancelled(cancelled) => AppError::Cancelled(cancelled),
+            error => AppError::PcbOperationError(PcbOperationError::PcbError(error)),
+        }
+    }
+}
+
+impl Planner {
+    /// The project is only modified after all the placements have been loaded, so cancellation leaves it unchanged.
+    ///
+    /// Only the design variants with changed placements files are refreshed, unless the unit assignments or PCBs
+    /// have changed since the last successful refresh, in which case all the design variants are refreshed.
+    fn refresh_project(
+        project: &mut Project,
+        pcbs: &[&Pcb],
+        path: &PathBuf,
+        refresh_cache: &mut RefreshCache,
+        cancellation: &CancellationToken,
+    ) -> Result<RefreshResult, AppError> {
+        let directory = parent_directory(&path);
+
+        let unique_design_variants = project.unique_design_variants(pcbs);
+
+        // cleared until the refresh succeeds, so that a failed or cancelled refresh is not skipped next time
+        let previous_inputs_hash = refresh_cache.inputs_hash.take();
+        let inputs_hash = Self::refresh_inputs_hash(project, pcbs);
+
+        let store = stores::backend::open_store(&project.store_backend, directory)
+            .map_err(|error| AppError::ProjectError(ProjectError::UnableToLoadPlacements(error)))?;
+
+        let AllPlacements {
+            placements: design_variant_placement_map,
+            changed,
+        } = stores::placements::load_all_placements(
+            store.as_ref(),
+            unique_design_variants,
+            directory,
+            &mut refresh_cache.placements,
+            cancellation,
+        )
+        .map_err(|error| match error.downcast::<Cancelled>() {
+            Ok(cancelled) => AppError::Cancelled(cancelled),
+            Err(error) => AppError::ProjectError(ProjectError::UnableToLoadPlacements(error)),
+        })?;
+
+        let changed = (previous_inputs_hash == Some(inputs_hash)).then_some(&changed);
+        let refresh_result =
+            project::refresh_from_design_variants(project, pcbs, design_variant_placement_map, changed)
+                .map_err(AppError::ProjectError);
+
+        if let Ok(RefreshResult {
+            modified,
+            warnings,
+            refreshed,
+        }) = &refresh_result
+        {
+            refresh_cache.inputs_hash = Some(inputs_hash);
+
+            trace!(
+                "Refreshed from design variants. modified: {}, design variants with warnings: {}, refreshed: {:?}",
+                modified,
+                warnings.len(),
+                refreshed
+            );
+        }
+
+        refresh_result
+    }
+
+    /// One warning for each design variant with placement warnings, see [`RefreshResult::warnings`], and the design
+    /// variants that were refreshed, see [`PlannerOperationViewModel::refreshed_design_variants`].
+    fn report_refresh(model: &mut Model, refresh_result: RefreshResult) {
+        for (design_variant, placement_warnings) in refresh_result.warnings.iter() {
+            push_warning(&mut model.warnings, AppWarning::PlacementWarnings {
+                design_variant: design_variant.clone(),
+                warnings: placement_warnings
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+            });
+        }
+        model.refreshed_design_variants = Some(refresh_result.refreshed);
+    }
+
+    /// Hashes the inputs of a refresh, other than the placements.
+    fn refresh_inputs_hash(project: &Project, pcbs: &[&Pcb]) -> u64 {
+        let content = serde_json::to_vec(&(&project.pcbs, pcbs, &project.store_backend)).unwrap_or_default();
+
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the positionings in unit order, there must be a positioning for each unit of the PCB.
+    fn ordered_pcb_unit_positionings(
+        pcb: &Pcb,
+        mut pcb_unit_positionings: HashMap<PcbUnitNumber, PcbUnitPositioning>,
+    ) -> Result<Vec<PcbUnitPositioning>, AppError> {
+        let pcb_unit_count = pcb_unit_positionings.len() as u16;
+        let expected_pcb_unit_count = pcb.units;
+        if pcb_unit_count != expected_pcb_unit_count {
+            return Err(AppError::PcbOperationError(
+                PcbOperationError::UnitSizingCountMismatch {
+                    expected: expected_pcb_unit_count,
+                    actual: pcb_unit_count,
+                },
+            ));
+        }
+
+        (1..=pcb_unit_count)
+            .map(|pcb_unit_index| {
+                pcb_unit_positionings
+                    .remove(&pcb_unit_index)
+                    .ok_or(AppError::PcbOperationError(
+                        PcbOperationError::MissingPcbUnitPositioning(pcb_unit_index),
+                    ))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    fn unique_parts<'a>(project: &'a Project) -> impl IntoIterator<Item = &'a Part> + 'a {
+        let unique_parts = project
+            .placements
+            .iter()
+            .map(|(_path, state)| &state.placement.part)
+            .collect::<IndexSet<_>>();
+        unique_parts
+    }
+}
+
+#[cfg(test)]
+mod app_tests {
+    use crux_core::assert_effect;
+    use crux_core::testing::{AppTester, Update};
+
+    use super::*;
+
+    #[test]
+    fn minimal() {
+        let hello = AppTester::<Planner>::default();
+        let mut model = Model::default();
+
+        // Call 'update' and request effects
+        let update = hello.update(Event::None, &mut model);
+
+        // Check update asked us to `Render`
+        assert_effect!(update, Effect::Render(_));
+
+        // Make sure the view matches our expectations
+        let actual_view = &hello.view(&model);
+        let expected_view = PlannerOperationViewModel::default();
+        assert_eq!(actual_view, &expected_view);
+    }
+
+    #[test]
+    fn load_reads_the_project_file_via_the_shell() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let path = PathBuf::from("projects/job1/project-job1.mpnp.json");
+
+        // when
+        let update = app.update(
+            Event::Load {
+                path: path.clone(),
+            },
+            &mut model,
+        );
+
+        // then
+        let operation = update
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::FileIo(request) => Some(request.operation.clone()),
+                _ => None,
+            });
+        assert_eq!(
+            operation,
+            Some(FileIoOperation::Read {
+                path: path.clone()
+            })
+        );
+
+        // when the shell responds with the file content
+        let content = file::to_bytes(&Project::new("job1".to_string(), None, None)).unwrap();
+        let update = app.update(
+            Event::ProjectFileRead {
+                path,
+                result: FileIoResult::Read {
+                    content,
+                },
+            },
+            &mut model,
+        );
+
+        // then
+        assert_effect!(update, Effect::Render(_));
+        assert_eq!(
+            model
+                .model_project
+                .as_ref()
+                .map(|model_project| model_project.project.name.as_str()),
+            Some("job1")
+        );
+        assert_eq!(app.view(&model).error, None);
+    }
+
+    #[test]
+    fn save_writes_the_project_file_via_the_shell() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let path = PathBuf::from("projects/job1/project-job1.mpnp.json");
+        model
+            .model_project
+            .replace(ModelProject {
+                path: path.clone(),
+                project_directory: PathBuf::from("projects/job1"),
+                project: Project::new("job1".to_string(), None, None),
+                modified: Modified::unsaved(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: None,
+                refresh_cache: Default::default(),
+            });
 
-                let issues_node = project_tree
-                    .tree
-                    .add_node(ProjectTreeItem {
-                        key: "issues".to_string(),
-                        path: "/issues".to_string(),
-                        ..ProjectTreeItem::default()
-                    });
-                project_tree
-                    .tree
-                    .add_edge(root_node, issues_node, ());
+        // when
+        let update = app.update(Event::Save, &mut model);
 
-                let parts_node = project_tree
-                    .tree
-                    .add_node(ProjectTreeItem {
-                        key: "parts".to_string(),
-                        path: "/parts".to_string(),
-                        ..ProjectTreeItem::default()
-                    });
-                project_tree
-                    .tree
-                    .add_edge(root_node, parts_node, ());
+        // then the project is still modified until the shell has written the file
+        assert_effect!(update, Effect::FileIo(_));
+        assert!(app.view(&model).project_modified);
 
-                let placements_node = project_tree
-                    .tree
-                    .add_node(ProjectTreeItem {
-                        key: "placements".to_string(),
-                        path: "/placements".to_string(),
-                        ..ProjectTreeItem::default()
-                    });
-                project_tree
-                    .tree
-                    .add_edge(root_node, placements_node, ());
+        // when
+        let update = app.update(
+            Event::ProjectFileWritten {
+                path,
+                result: FileIoResult::Written,
+                revision: 0,
+            },
+            &mut model,
+        );
 
-                let pcbs_node = project_tree
-                    .tree
-                    .add_node(ProjectTreeItem {
-                        key: "pcbs".to_string(),
-                        path: "/pcbs".to_string(),
-                        ..ProjectTreeItem::default()
-                    });
-                project_tree
-                    .tree
-                    .add_edge(root_node, pcbs_node, ());
+        // then
+        assert_effect!(update, Effect::Render(_));
+        assert!(!app.view(&model).project_modified);
+    }
 
-                for (pcb_index, (project_pcb, pcb)) in project
-                    .pcbs
-                    .iter()
-                    .zip(pcbs)
-                    .enumerate()
-                {
-                    let pcb_node = project_tree
-                        .tree
-                        .add_node(ProjectTreeItem {
-                            key: "pcb".to_string(),
-                            args: HashMap::from([("name".to_string(), Arg::String(pcb.name.clone()))]),
-                            path: format!("/pcbs/{}", pcb_index).to_string(),
-                        });
-                    project_tree
-                        .tree
-                        .add_edge(pcbs_node, pcb_node, ());
+    #[test]
+    fn changes_made_while_saving_are_kept() {
+        // given a modified project with a recovery snapshot
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let path = PathBuf::from("projects/job1/project-job1.mpnp.json");
+        let project = Project::new("job1".to_string(), None, None);
+        model
+            .model_project
+            .replace(ModelProject {
+                path: path.clone(),
+                project_directory: PathBuf::from("projects/job1"),
+                project: project.clone(),
+                modified: Modified::unsaved(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: Some(RecoverySnapshot {
+                    taken: OffsetDateTime::now_utc(),
+                    project,
+                    pcbs: vec![],
+                }),
+                refresh_cache: Default::default(),
+            });
 
-                    let unit_assignments_node = project_tree
-                        .tree
-                        .add_node(ProjectTreeItem {
-                            key: "unit-assignments".to_string(),
-                            path: format!("/pcbs/{}/units", pcb_index).to_string(),
-                            ..ProjectTreeItem::default()
-                        });
-                    project_tree
-                        .tree
-                        .add_edge(pcb_node, unit_assignments_node, ());
+        // and a save has been requested
+        let revision = model
+            .model_project
+            .as_ref()
+            .unwrap()
+            .modified
+            .revision();
+        let _update = app.update(Event::Save, &mut model);
+
+        // and the project is changed before the shell has written the file
+        let _update = app.update(Event::ResetOperations {}, &mut model);
+        assert_eq!(app.view(&model).error, None);
+
+        // when
+        let _update = app.update(
+            Event::ProjectFileWritten {
+                path: path.clone(),
+                result: FileIoResult::Written,
+                revision,
+            },
+            &mut model,
+        );
 
-                    for (map_index, (pcb_unit_index, design_index)) in pcb.unit_map.iter().enumerate() {
-                        let mut object_path = ObjectPath::default();
-                        object_path.set_pcb_instance((pcb_index + 1) as u16);
-                        object_path.set_pcb_unit(pcb_unit_index + 1);
+        // then
+        assert!(app.view(&model).project_modified);
+        assert!(model
+            .model_project
+            .as_ref()
+            .unwrap()
+            .recovery
+            .is_some());
 
-                        let mut args = HashMap::from([("name".to_string(), Arg::String(object_path.to_string()))]);
+        // when the changed project is saved
+        let revision = model
+            .model_project
+            .as_ref()
+            .unwrap()
+            .modified
+            .revision();
+        let _update = app.update(Event::Save, &mut model);
+        let _update = app.update(
+            Event::ProjectFileWritten {
+                path,
+                result: FileIoResult::Written,
+                revision,
+            },
+            &mut model,
+        );
 
-                        let design_name = pcb
-                            .design_names
-                            .iter()
-                            .nth(*design_index as usize)
-                            .unwrap();
-                        args.insert("design_name".to_string(), Arg::String(design_name.to_string()));
+        // then
+        assert!(!app.view(&model).project_modified);
+        assert!(model
+            .model_project
+            .as_ref()
+            .unwrap()
+            .recovery
+            .is_none());
+    }
 
-                        if let Some(assignment_design_variant) = project_pcb
-                            .unit_assignments
-                            .get(pcb_unit_index)
-                        {
-                            let design_changed = assignment_design_variant
-                                .design_name
-                                .ne(design_name);
-                            if design_changed {
-                                // It's invalid for these to be mismatched; if this occurs, then the pcb variant map is
-                                // out of sync with the pcb unit assignments and needs to be re-synced; since that
-                                // should happen before this code, ignore this `unit_map` entry.
-                                error!("PCB unit map is out of sync with pcb unit assignments. map_index: {}, unit: {}, assignment_design_variant: {}, design_index: {}",
-                                    map_index, pcb_unit_index, assignment_design_variant, design_index
-                                );
-                                continue;
-                            } else {
-                                args.insert(
-                                    "variant_name".to_string(),
-                                    Arg::String(
-                                        assignment_design_variant
-                                            .variant_name
-                                            .to_string(),
-                                    ),
-                                );
-                            }
-                        }
+    #[test]
+    fn create_project_from_template_copies_the_load_outs_via_the_shell() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let template_directory = tempfile::tempdir().unwrap();
+        let template_path = template_directory.path().to_path_buf();
+        let project_directory = tempfile::tempdir().unwrap();
+        let project_path = project_directory.path().to_path_buf();
+
+        // and a template with a load-out in the template directory, and one that is not
+        let project_template = template::ProjectTemplate {
+            phases: vec![
+                template::PhaseTemplate {
+                    reference: Reference::from_raw_str("top_1"),
+                    process: Reference::from_raw_str("pnp"),
+                    load_out_source: "load_outs/smt.csv".to_string(),
+                    pcb_side: PcbSide::Top,
+                },
+                template::PhaseTemplate {
+                    reference: Reference::from_raw_str("bottom_1"),
+                    process: Reference::from_raw_str("manual"),
+                    load_out_source: "th.csv".to_string(),
+                    pcb_side: PcbSide::Bottom,
+                },
+            ],
+            ..Default::default()
+        };
+        file::save(&project_template, &template_path.join(template::TEMPLATE_FILE_NAME)).unwrap();
+        std::fs::create_dir_all(template_path.join("load_outs")).unwrap();
+        std::fs::write(template_path.join("load_outs/smt.csv"), "template\n").unwrap();
+
+        // when
+        let update = app.update(
+            Event::CreateProjectFromTemplate {
+                template: template_path.clone(),
+                name: "job1".to_string(),
+                path: project_path.join("project-job1.mpnp.json"),
+            },
+            &mut model,
+        );
+
+        // then the template load-out is copied by the shell
+        let copy_operation = FileIoOperation::Copy {
+            from: template_path.join("load_outs/smt.csv"),
+            to: project_path.join("load_outs/smt.csv"),
+        };
+        assert!(update.effects().any(|effect| matches!(
+            effect,
+            Effect::FileIo(request) if request.operation == copy_operation
+        )));
+        assert!(!project_path
+            .join("load_outs/smt.csv")
+            .exists());
+
+        // and the other load-out is created
+        assert!(project_path.join("th.csv").exists());
+
+        // when the shell has copied the load-out
+        let _update = app.update(
+            Event::TemplateLoadOutCopied {
+                path: project_path.join("load_outs/smt.csv"),
+                result: file_io::perform(&copy_operation),
+            },
+            &mut model,
+        );
+
+        // then
+        assert_eq!(app.view(&model).error, None);
+        assert_eq!(
+            std::fs::read_to_string(project_path.join("load_outs/smt.csv")).unwrap(),
+            "template\n"
+        );
+    }
+
+    #[test]
+    fn import_project_archive_writes_the_files_via_the_shell() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let source_directory = tempfile::tempdir().unwrap();
+        let archive_path = source_directory.path().join("job1.zip");
+        archive::export_project_archive(
+            &Project::new("job1".to_string(), None, None),
+            &source_directory
+                .path()
+                .join("project-job1.mpnp.json"),
+            &[],
+            &archive_path,
+        )
+        .unwrap();
+        let directory = PathBuf::from("projects/job1");
+
+        // when
+        let update = app.update(
+            Event::ImportProjectArchive {
+                archive: archive_path.clone(),
+                directory: directory.clone(),
+            },
+            &mut model,
+        );
+
+        // then the archive is read by the shell
+        assert!(update.effects().any(|effect| matches!(
+            effect,
+            Effect::FileIo(request) if request.operation == FileIoOperation::Read {
+                path: archive_path.clone(),
+            }
+        )));
+
+        // when
+        let update = app.update(
+            Event::ProjectArchiveRead {
+                directory: directory.clone(),
+                result: FileIoResult::Read {
+                    content: std::fs::read(&archive_path).unwrap(),
+                },
+            },
+            &mut model,
+        );
+
+        // then the extracted files are written by the shell
+        let written_paths = update
+            .effects()
+            .filter_map(|effect| match effect {
+                Effect::FileIo(request) => match &request.operation {
+                    FileIoOperation::Write {
+                        path, ..
+                    } => Some(path.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(written_paths, vec![
+            directory.join("project-job1.mpnp.json"),
+            directory.join(archive::MANIFEST_FILE_NAME),
+        ]);
+
+        // and the project is not read until the files have been written
+        assert!(!update.effects().any(|effect| matches!(
+            effect,
+            Effect::FileIo(request) if matches!(request.operation, FileIoOperation::Read { .. })
+        )));
+        assert!(model.model_project.is_none());
+    }
+
+    #[test]
+    fn project_pcbs_are_not_loaded_for_the_project_tree() {
+        // given a project with a PCB file that has not been loaded, or does not exist
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let mut project = Project::new("job1".to_string(), None, None);
+        project::add_pcb(
+            &mut project,
+            &FileReference::Relative(PathBuf::from("panel_a.mpnp.json")),
+        )
+        .unwrap();
+
+        // when
+        let _update = app.update(
+            Event::ProjectFileRead {
+                path: PathBuf::from("projects/job1/project-job1.mpnp.json"),
+                result: FileIoResult::Read {
+                    content: file::to_bytes(&project).unwrap(),
+                },
+            },
+            &mut model,
+        );
+        let update = app.update(Event::RequestProjectTreeView {}, &mut model);
+
+        // then
+        assert_effect!(update, Effect::ProjectView(_));
+        assert_eq!(app.view(&model).error, None);
+        assert!(model.model_pcbs.is_empty());
+    }
+
+    #[test]
+    fn autosave_and_restore_recovery_snapshot() {
+        // given a modified project
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let path = PathBuf::from("projects/job1/project-job1.mpnp.json");
+        let recovery_path = PathBuf::from("projects/job1/project-job1.mpnp.json.recovery");
+        let mut project = Project::new("job1".to_string(), None, None);
+        model
+            .model_project
+            .replace(ModelProject {
+                path: path.clone(),
+                project_directory: PathBuf::from("projects/job1"),
+                project: project.clone(),
+                modified: Modified::unsaved(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: None,
+                refresh_cache: Default::default(),
+            });
+
+        // when
+        let update = app.update(Event::Autosave, &mut model);
+
+        // then the snapshot is written to the recovery file
+        let content = update
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::FileIo(request) => match &request.operation {
+                    FileIoOperation::Write {
+                        path,
+                        content,
+                    } if *path == recovery_path => Some(content.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .unwrap();
+
+        // when the project is loaded again, e.g. after a crash, the project file does not contain the unsaved changes
+        project.name = "saved".to_string();
+        let _update = app.update(
+            Event::ProjectFileRead {
+                path: path.clone(),
+                result: FileIoResult::Read {
+                    content: file::to_bytes(&project).unwrap(),
+                },
+            },
+            &mut model,
+        );
+        let update = app.update(
+            Event::RecoveryFileRead {
+                path: recovery_path,
+                result: FileIoResult::Read {
+                    content,
+                },
+            },
+            &mut model,
+        );
+
+        // then
+        assert_effect!(update, Effect::Render(_));
+        assert!(app.view(&model).recovery_available);
+        assert!(!app.view(&model).project_modified);
+
+        // when
+        let _update = app.update(Event::RestoreRecovery, &mut model);
+
+        // then the project is restored, but not saved
+        let view = app.view(&model);
+        assert!(!view.recovery_available);
+        assert!(view.project_modified);
+        assert_eq!(
+            model
+                .model_project
+                .as_ref()
+                .map(|model_project| model_project.project.name.as_str()),
+            Some("job1")
+        );
+    }
+
+    #[test]
+    fn rotation_offsets_use_the_rotation_direction_of_the_settings() {
+        // given a project that uses clockwise rotations
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let mut project = Project::new("job1".to_string(), None, None);
+        project.settings.rotation_direction = RotationDirection::Clockwise;
+        let _update = app.update(
+            Event::ProjectFileRead {
+                path: PathBuf::from("projects/job1/project-job1.mpnp.json"),
+                result: FileIoResult::Read {
+                    content: file::to_bytes(&project).unwrap(),
+                },
+            },
+            &mut model,
+        );
+
+        // when only the origin is changed
+        let _update = app.update(
+            Event::ApplyProjectSettings {
+                unit_system: None,
+                origin: Some(OriginConvention::TopLeft),
+                rotation_direction: None,
+                default_placement_orderings: None,
+            },
+            &mut model,
+        );
+
+        // then the other settings are unchanged
+        let settings = &model
+            .model_project
+            .as_ref()
+            .unwrap()
+            .project
+            .settings;
+        assert_eq!(settings.origin, OriginConvention::TopLeft);
+        assert_eq!(settings.rotation_direction, RotationDirection::Clockwise);
+        assert_eq!(settings.unit_system, UnitSystem::Millimeters);
+
+        // when
+        let _update = app.update(
+            Event::SetRotationOffset {
+                target: RotationOffsetTarget::Package("SOT-23".to_string()),
+                offset: Some(Decimal::from(90)),
+            },
+            &mut model,
+        );
+
+        // then the offset is stored as a positive anti-clockwise rotation
+        assert_eq!(
+            model
+                .model_project
+                .as_ref()
+                .unwrap()
+                .project
+                .rotation_offsets
+                .packages["SOT-23"],
+            Decimal::from(-90)
+        );
+    }
+
+    #[test]
+    fn clone_pcb_does_not_overwrite_an_existing_file() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let source_path = PathBuf::from("projects/job1/panel_a.pcb.json");
+        let pcb_path = PathBuf::from("projects/job1/panel_b.pcb.json");
+        model
+            .model_pcbs
+            .insert(source_path.clone(), ModelPcb {
+                pcb: Pcb::new("panel_a".to_string(), 1, IndexSet::new(), BTreeMap::new()),
+                modified: Modified::default(),
+                file_modified: None,
+            });
+
+        // when
+        let update = app.update(
+            Event::ClonePcb {
+                source_path: source_path.clone(),
+                new_name: "panel_b".to_string(),
+            },
+            &mut model,
+        );
+
+        // then the directory of the PCB is listed by the shell
+        assert!(update.effects().any(|effect| matches!(
+            effect,
+            Effect::FileIo(request) if request.operation == FileIoOperation::List {
+                directory: PathBuf::from("projects/job1"),
+            }
+        )));
+
+        // when the directory contains the new PCB
+        let _update = app.update(
+            Event::PcbCloneDirectoryListed {
+                source_path,
+                pcb_path: pcb_path.clone(),
+                new_name: "panel_b".to_string(),
+                result: FileIoResult::Listed {
+                    entries: vec![pcb_path.clone()],
+                },
+            },
+            &mut model,
+        );
+
+        // then
+        assert!(app.view(&model).error.is_some());
+        assert!(!model.model_pcbs.contains_key(&pcb_path));
+    }
+
+    #[test]
+    fn import_pcb_outline() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let pcb_path = PathBuf::from("projects/job1/panel_a.pcb.json");
+        let file = PathBuf::from("projects/job1/outline.svg");
+        let profile_path = PathBuf::from("projects/job1/outline-profile.gbr");
+        model
+            .model_pcbs
+            .insert(pcb_path.clone(), ModelPcb {
+                pcb: Pcb::new("panel_a".to_string(), 1, IndexSet::new(), BTreeMap::new()),
+                modified: Modified::default(),
+                file_modified: None,
+            });
+
+        // when the directory of the outline does not contain a profile gerber
+        let update = app.update(
+            Event::PcbOutlineDirectoryListed {
+                path: pcb_path.clone(),
+                design: None,
+                file: file.clone(),
+                token: None,
+                result: FileIoResult::Listed {
+                    entries: vec![file.clone()],
+                },
+            },
+            &mut model,
+        );
+
+        // then the outline is read by the shell
+        assert!(update.effects().any(|effect| matches!(
+            effect,
+            Effect::FileIo(request) if request.operation == FileIoOperation::Read {
+                path: file.clone(),
+            }
+        )));
+
+        // when
+        let update = app.update(
+            Event::PcbOutlineRead {
+                path: pcb_path.clone(),
+                design: None,
+                file: file.clone(),
+                token: None,
+                result: FileIoResult::Read {
+                    content: br#"<svg width="40mm" viewBox="0 0 40 20"><rect width="40" height="20"/></svg>"#.to_vec(),
+                },
+            },
+            &mut model,
+        );
+
+        // then the profile gerber is written by the shell
+        assert!(update.effects().any(|effect| matches!(
+            effect,
+            Effect::FileIo(request) if matches!(
+                &request.operation,
+                FileIoOperation::Write { path, .. } if *path == profile_path
+            )
+        )));
+
+        // when
+        let _update = app.update(
+            Event::PcbOutlineProfileWritten {
+                path: pcb_path.clone(),
+                design: None,
+                profile_path: profile_path.clone(),
+                token: None,
+                result: FileIoResult::Written,
+            },
+            &mut model,
+        );
+
+        // then
+        let model_pcb = &model.model_pcbs[&pcb_path];
+        assert!(model_pcb.modified.is_modified());
+        assert_eq!(model_pcb.pcb.pcb_gerbers[0].file, profile_path);
+    }
+
+    #[test]
+    fn import_pcb_outline_does_not_overwrite_a_profile_gerber() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+
+        // when the directory of the outline already contains a profile gerber
+        let update = app.update(
+            Event::PcbOutlineDirectoryListed {
+                path: PathBuf::from("projects/job1/panel_a.pcb.json"),
+                design: None,
+                file: PathBuf::from("outline.dxf"),
+                token: None,
+                result: FileIoResult::Listed {
+                    entries: vec![PathBuf::from("./outline.dxf"), PathBuf::from("./outline-profile.gbr")],
+                },
+            },
+            &mut model,
+        );
+
+        // then
+        assert!(app.view(&model).error.is_some());
+        assert!(!update
+            .effects()
+            .any(|effect| matches!(effect, Effect::FileIo(_))));
+    }
+
+    #[test]
+    fn board_preview_reads_the_profile_gerber_via_the_shell() {
+        // given a project with a PCB that has a profile gerber
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let mut project = Project::new("job1".to_string(), None, None);
+        project
+            .pcbs
+            .push(ProjectPcb::new(FileReference::Relative("panel_a.pcb.json".into())));
+        model
+            .model_project
+            .replace(ModelProject {
+                path: PathBuf::from("projects/job1/project-job1.mpnp.json"),
+                project_directory: PathBuf::from("projects/job1"),
+                project,
+                modified: Modified::default(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: None,
+                refresh_cache: Default::default(),
+            });
+        let profile_path = PathBuf::from("projects/job1/outline-profile.gbr");
+        let mut pcb = Pcb::new("panel_a".to_string(), 1, IndexSet::new(), BTreeMap::new());
+        pcb.pcb_gerbers.push(GerberFile {
+            file: profile_path.clone(),
+            function: Some(GerberFileFunction::Profile),
+        });
+        model
+            .model_pcbs
+            .insert(PathBuf::from("projects/job1/panel_a.pcb.json"), ModelPcb {
+                pcb,
+                modified: Modified::default(),
+                file_modified: None,
+            });
+
+        // when
+        let update = app.update(
+            Event::RequestBoardPreviewView {
+                pcb: 0,
+            },
+            &mut model,
+        );
+
+        // then the profile gerber is read by the shell
+        assert!(update.effects().any(|effect| matches!(
+            effect,
+            Effect::FileIo(request) if request.operation == FileIoOperation::Read {
+                path: profile_path.clone(),
+            }
+        )));
+
+        // when the profile gerber cannot be read
+        let update = app.update(
+            Event::BoardPreviewProfileRead {
+                pcb: 0,
+                file: profile_path.clone(),
+                result: FileIoResult::Failed {
+                    reason: "not found".to_string(),
+                },
+            },
+            &mut model,
+        );
+
+        // then the panel size is used
+        assert_effect!(update, Effect::ProjectView(_));
+        assert!(matches!(
+            app.view(&model).warnings.as_slice(),
+            [(_, AppWarning::UnreadableProfileGerber { file, .. })] if *file == profile_path
+        ));
+    }
+
+    #[test]
+    fn event_recording_results_do_not_replace_the_error() {
+        // given an error from the last recorded event
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let _update = app.update(
+            Event::RequestBoardPreviewView {
+                pcb: 0,
+            },
+            &mut model,
+        );
+        assert!(app.view(&model).error.is_some());
 
-                        let unit_assignment_node = project_tree
-                            .tree
-                            .add_node(ProjectTreeItem {
-                                key: "unit-assignment".to_string(),
-                                args,
-                                path: format!("/pcbs/{}/units/{}", pcb_index, pcb_unit_index).to_string(),
-                            });
+        // when
+        let update = app.update(
+            Event::EventRecordingWritten {
+                path: PathBuf::from("replay.jsonl"),
+                result: FileIoResult::Written,
+            },
+            &mut model,
+        );
 
-                        project_tree
-                            .tree
-                            .add_edge(unit_assignments_node, unit_assignment_node, ());
-                    }
-                }
+        // then
+        assert_eq!(update.effects().count(), 0);
+        assert!(app.view(&model).error.is_some());
+    }
 
-                let processes_node = project_tree
-                    .tree
-                    .add_node(ProjectTreeItem {
-                        key: "processes".to_string(),
-                        path: "/processes".to_string(),
-                        ..ProjectTreeItem::default()
-                    });
-                project_tree
-                    .tree
-                    .add_edge(root_node, processes_node, ());
+    #[test]
+    fn hit_testing_an_unknown_pcb_is_an_invalid_instance_index() {
+        // given a project without pcbs
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        model
+            .model_project
+            .replace(ModelProject {
+                path: PathBuf::from("projects/job1/project-job1.mpnp.json"),
+                project_directory: PathBuf::from("projects/job1"),
+                project: Project::new("job1".to_string(), None, None),
+                modified: Modified::default(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: None,
+                refresh_cache: Default::default(),
+            });
 
-                for process in project.processes.iter() {
-                    let process_node = project_tree
-                        .tree
-                        .add_node(ProjectTreeItem {
-                            key: "process".to_string(),
-                            args: HashMap::from([("name".to_string(), Arg::String(process.reference.to_string()))]),
-                            path: format!("/processes/{}", process.reference).to_string(),
-                        });
+        // when
+        let _update = app.update(
+            Event::RequestHitTestView {
+                pcb: 0,
+                position: Vector2::new(10.0, 10.0),
+                side: PcbSide::Top,
+            },
+            &mut model,
+        );
 
-                    project_tree
-                        .tree
-                        .add_edge(processes_node, process_node, ());
-                }
+        // then
+        assert!(matches!(
+            app.view(&model).error,
+            Some((_, AppErrorView::PcbOperationError { cause }))
+                if cause == PcbOperationError::InvalidInstanceIndex.to_string()
+        ));
+    }
 
-                let phases_node = project_tree
-                    .tree
-                    .add_node(ProjectTreeItem {
-                        key: "phases".to_string(),
-                        path: "/phases".to_string(),
-                        ..ProjectTreeItem::default()
-                    });
-                project_tree
-                    .tree
-                    .add_edge(root_node, phases_node, ());
+    #[test]
+    fn project_pcbs_are_loaded_via_the_shell() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let path = PathBuf::from("projects/job1/project-job1.mpnp.json");
+        let pcb_path = PathBuf::from("projects/job1/panel_a.pcb.json");
+        let mut project = Project::new("job1".to_string(), None, None);
+        project
+            .pcbs
+            .push(ProjectPcb::new(FileReference::Relative("panel_a.pcb.json".into())));
 
-                for reference in &project.phase_orderings {
-                    let phase = project.phases.get(reference).unwrap();
-                    //
-                    // add phase node
-                    //
-                    let phase_path = format!("/phases/{}", reference).to_string();
-                    let phase_node = project_tree
-                        .tree
-                        .add_node(ProjectTreeItem {
-                            key: "phase".to_string(),
-                            args: HashMap::from([
-                                ("reference".to_string(), Arg::String(reference.to_string())),
-                                ("process".to_string(), Arg::String(phase.process.to_string())),
-                                ("pcb_side".to_string(), Arg::String(phase.pcb_side.to_string())),
-                            ]),
-                            path: phase_path.clone(),
-                        });
-                    project_tree
-                        .tree
-                        .add_edge(phases_node, phase_node, ());
+        // when
+        let update = app.update(
+            Event::ProjectFileRead {
+                path,
+                result: FileIoResult::Read {
+                    content: file::to_bytes(&project).unwrap(),
+                },
+            },
+            &mut model,
+        );
 
-                    //
-                    // add loadout node to the phase
-                    //
-                    let loadout_node = project_tree
-                        .tree
-                        .add_node(ProjectTreeItem {
-                            key: "phase-loadout".to_string(),
-                            args: HashMap::from([(
-                                "source".to_string(),
-                                Arg::String(phase.load_out_source.to_string()),
-                            )]),
-                            path: format!("{}/loadout", phase_path),
-                        });
-                    project_tree
-                        .tree
-                        .add_edge(phase_node, loadout_node, ());
+        // then the modification time of the PCB file is read by the shell
+        assert!(update.effects().any(|effect| matches!(
+            effect,
+            Effect::FileIo(request) if request.operation == FileIoOperation::Modified {
+                path: pcb_path.clone(),
+            }
+        )));
+
+        // when
+        let file_modified = Some(SystemTime::UNIX_EPOCH);
+        let update = app.update(
+            Event::PcbFileModifiedTimeRead {
+                path: pcb_path.clone(),
+                reload: false,
+                result: FileIoResult::Modified {
+                    modified: file_modified,
+                },
+            },
+            &mut model,
+        );
 
-                    if add_test_nodes {
-                        let test_node = project_tree
-                            .tree
-                            .add_node(ProjectTreeItem {
-                                key: "test".to_string(),
-                                path: format!("/phases/{}/test", reference).to_string(),
-                                ..ProjectTreeItem::default()
-                            });
-                        project_tree
-                            .tree
-                            .add_edge(phase_node, test_node, ());
-                    }
-                }
+        // then the PCB file is read by the shell, since the PCB is not loaded
+        assert!(update.effects().any(|effect| matches!(
+            effect,
+            Effect::FileIo(request) if request.operation == FileIoOperation::Read {
+                path: pcb_path.clone(),
+            }
+        )));
+
+        // when
+        let pcb = Pcb::new("panel_a".to_string(), 1, IndexSet::new(), BTreeMap::new());
+        let _update = app.update(
+            Event::PcbFileRead {
+                path: pcb_path.clone(),
+                file_modified,
+                result: FileIoResult::Read {
+                    content: file::to_bytes(&pcb).unwrap(),
+                },
+            },
+            &mut model,
+        );
 
-                if add_test_nodes {
-                    let test_node = project_tree
-                        .tree
-                        .add_node(ProjectTreeItem {
-                            key: "test".to_string(),
-                            path: "/test".to_string(),
-                            ..ProjectTreeItem::default()
-                        });
-                    project_tree
-                        .tree
-                        .add_edge(root_node, test_node, ());
-                }
+        // then
+        let model_pcb = &model.model_pcbs[&pcb_path];
+        assert_eq!(model_pcb.pcb.name, "panel_a");
+        assert_eq!(model_pcb.file_modified, file_modified);
+    }
 
-                Ok(project_view_renderer::view(ProjectView::ProjectTree(project_tree)))
-            }),
-            Event::RequestPhasesView {} => Box::new(|model: &mut Model| {
-                let (
-                    ModelProject {
-                        project, ..
-                    },
-                    directory,
-                ) = Self::model_project_and_directory(model)?;
+    #[test]
+    fn stale_pcbs_are_reloaded() {
+        // given a PCB, loaded when the file had an earlier modification time
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let pcb_path = PathBuf::from("projects/job1/panel_a.pcb.json");
+        let loaded_file_modified = SystemTime::UNIX_EPOCH;
+        model
+            .model_pcbs
+            .insert(pcb_path.clone(), ModelPcb {
+                pcb: Pcb::new("panel_a".to_string(), 1, IndexSet::new(), BTreeMap::new()),
+                modified: Modified::default(),
+                file_modified: Some(loaded_file_modified),
+            });
 
-                let phases = project
-                    .phase_orderings
-                    .iter()
-                    .map(|phase_reference| {
-                        let phase = project
-                            .phases
-                            .get(phase_reference)
-                            .unwrap();
-                        let phase_state = project
-                            .phase_states
-                            .get(phase_reference)
-                            .unwrap();
-                        let can_start = project.can_start_phase(&phase_reference);
+        // when the file has not changed
+        let update = app.update(
+            Event::PcbFileModifiedTimeRead {
+                path: pcb_path.clone(),
+                reload: false,
+                result: FileIoResult::Modified {
+                    modified: Some(loaded_file_modified),
+                },
+            },
+            &mut model,
+        );
 
-                        // FUTURE try and avoid the [`unwrap`] here, ideally by ensuring load-out sources are always correct
-                        //        for every situation instead of using [`try_build_phase_load_out_source`]
-                        try_build_phase_overview(&directory, phase_reference.clone(), phase, can_start, phase_state)
-                            .unwrap()
-                    })
-                    .collect::<Vec<PhaseOverview>>();
+        // then
+        assert_eq!(update.effects().count(), 0);
+
+        // when the file has changed
+        let update = app.update(
+            Event::PcbFileModifiedTimeRead {
+                path: pcb_path.clone(),
+                reload: false,
+                result: FileIoResult::Modified {
+                    modified: Some(loaded_file_modified + std::time::Duration::from_secs(1)),
+                },
+            },
+            &mut model,
+        );
 
-                let phases = Phases {
-                    phases,
-                };
+        // then the PCB file is read again
+        assert!(update.effects().any(|effect| matches!(
+            effect,
+            Effect::FileIo(request) if request.operation == FileIoOperation::Read {
+                path: pcb_path.clone(),
+            }
+        )));
+    }
 
-                Ok(project_view_renderer::view(ProjectView::Phases(phases)))
-            }),
+    #[test]
+    fn autosave_does_not_overwrite_a_pending_recovery_snapshot() {
+        // given a modified project, with a recovery snapshot that has been neither restored nor discarded
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let project = Project::new("job1".to_string(), None, None);
+        model
+            .model_project
+            .replace(ModelProject {
+                path: PathBuf::from("projects/job1/project-job1.mpnp.json"),
+                project_directory: PathBuf::from("projects/job1"),
+                project: project.clone(),
+                modified: Modified::unsaved(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: Some(RecoverySnapshot {
+                    taken: OffsetDateTime::now_utc(),
+                    project,
+                    pcbs: vec![],
+                }),
+                refresh_cache: Default::default(),
+            });
 
-            Event::RequestPhaseOverviewView {
-                phase_reference,
-            } => Box::new(|model: &mut Model| {
-                let (
-                    ModelProject {
-                        project, ..
-                    },
-                    directory,
-                ) = Self::model_project_and_directory(model)?;
+        // when
+        let update = app.update(Event::Autosave, &mut model);
 
-                let phase = project
-                    .phases
-                    .get(&phase_reference)
-                    .ok_or(AppError::UnknownPhaseReference(phase_reference.clone()))?;
-                let phase_state = project
-                    .phase_states
-                    .get(&phase_reference)
-                    .unwrap();
-                let can_start = project.can_start_phase(&phase_reference);
+        // then
+        assert_eq!(update.effects().count(), 0);
+        assert!(app.view(&model).recovery_available);
+    }
 
-                let phase_overview =
-                    try_build_phase_overview(&directory, phase_reference, phase, can_start, phase_state)
-                        .map_err(AppError::SourceError)?;
+    #[test]
+    fn placements_tree_nodes_have_counts() {
+        // given
+        let mut project = Project::new("job1".to_string(), None, None);
+        let phase_reference = Reference::from_raw_str("top_1");
+        project
+            .phase_orderings
+            .insert(phase_reference.clone());
+        for (ref_des, phase, operation_status) in [
+            ("R1", Some(&phase_reference), PlacementStatus::Placed),
+            ("R2", Some(&phase_reference), PlacementStatus::Pending),
+            ("R3", Some(&phase_reference), PlacementStatus::Pending),
+            ("R4", None, PlacementStatus::Pending),
+        ] {
+            project.placements.insert(
+                ObjectPath::from_str(&format!("pcb=1::unit=1::ref_des={}", ref_des)).unwrap(),
+                PlacementState {
+                    phase: phase.cloned(),
+                    operation_status,
+                    ..PlacementState::default()
+                },
+            );
+        }
 
-                Ok(project_view_renderer::view(ProjectView::PhaseOverview(phase_overview)))
-            }),
-            Event::RequestProcessDefinitionView {
-                process_reference,
-            } => Box::new(move |model: &mut Model| {
-                let ModelProject {
-                    project, ..
-                } = model
-                    .model_project
-                    .as_mut()
-                    .ok_or(AppError::OperationRequiresProject)?;
+        // and
+        let mut tree = Graph::new();
+        let placements_node = tree.add_node(ProjectTreeItem {
+            key: "placements".to_string(),
+            path: TreePath::root().join("placements"),
+            ..ProjectTreeItem::default()
+        });
+
+        // when
+        add_placements_tree_nodes(&mut tree, placements_node, &project);
+
+        // then
+        let counts = |count: i64, complete: i64, percentage: i64| {
+            HashMap::from([
+                ("count".to_string(), Arg::Integer(count)),
+                ("complete".to_string(), Arg::Integer(complete)),
+                ("percentage".to_string(), Arg::Integer(percentage)),
+            ])
+        };
+        assert_eq!(tree[placements_node].args, counts(4, 1, 25));
+
+        // and
+        let phase_item = tree
+            .node_weights()
+            .find(|item| item.key == "placements-phase")
+            .unwrap();
+        let mut expected_phase_args = counts(3, 1, 33);
+        expected_phase_args.insert("phase".to_string(), Arg::String("top_1".to_string()));
+        assert_eq!(phase_item.args, expected_phase_args);
+        assert_eq!(phase_item.path.to_string(), "/placements/phases/top_1");
+
+        // and
+        let status_args = tree
+            .node_weights()
+            .filter(|item| item.key == "placements-status")
+            .map(|item| (item.args["status"].clone(), item.args["count"].clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(status_args, vec![
+            (Arg::String("Pending".to_string()), Arg::Integer(3)),
+            (Arg::String("Placed".to_string()), Arg::Integer(1)),
+        ]);
+    }
 
-                let process = project
-                    .processes
-                    .iter()
-                    .find(|it| it.reference.eq(&process_reference))
-                    .ok_or(AppError::UnknownProcessReference(process_reference.clone()))?;
+    #[test]
+    fn unreadable_recovery_file_is_a_warning() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let path = PathBuf::from("projects/job1/project-job1.mpnp.json");
+        let recovery_path = PathBuf::from("projects/job1/project-job1.mpnp.json.recovery");
+        let _update = app.update(
+            Event::ProjectFileRead {
+                path,
+                result: FileIoResult::Read {
+                    content: file::to_bytes(&Project::new("job1".to_string(), None, None)).unwrap(),
+                },
+            },
+            &mut model,
+        );
 
-                Ok(project_view_renderer::view(ProjectView::ProcessDefinition(
-                    process.clone(),
-                )))
-            }),
-            Event::RequestPhasePlacementsView {
-                phase_reference,
-            } => Box::new(move |model: &mut Model| {
-                let (
-                    ModelProject {
-                        project, ..
-                    },
-                    pcbs,
-                    project_directory,
-                ) = Self::model_project_and_pcbs(model)?;
-                let phase = project
-                    .phases
-                    .get(&phase_reference)
-                    .ok_or(AppError::UnknownPhaseReference(phase_reference.clone()))?;
+        // when
+        let update = app.update(
+            Event::RecoveryFileRead {
+                path: recovery_path.clone(),
+                result: FileIoResult::Read {
+                    content: b"not a snapshot".to_vec(),
+                },
+            },
+            &mut model,
+        );
+
+        // then the shell is told about the warning
+        assert_effect!(update, Effect::Render(_));
+        let view = app.view(&model);
+        assert_eq!(view.error, None);
+        assert!(matches!(
+            view.warnings.as_slice(),
+            [(_, AppWarning::UnreadableRecoveryFile { path, .. })] if *path == recovery_path
+        ));
+
+        // when
+        let _update = app.update(Event::RequestOverviewView {}, &mut model);
+
+        // then the warnings are only for the last event
+        assert!(app.view(&model).warnings.is_empty());
+    }
+
+    #[test]
+    fn delete_phase_report_is_in_the_view_model() {
+        // given a project with a placement assigned to a phase
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let mut project = Project::new("job1".to_string(), None, None);
+        project
+            .update_phase(
+                PhaseReference::from_raw_str("top_1"),
+                ProcessReference::from_raw_str("pnp"),
+                "load_out_top_1.csv".to_string(),
+                PcbSide::Top,
+            )
+            .unwrap();
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap();
+        project
+            .placements
+            .insert(object_path.clone(), PlacementState {
+                unit_path: object_path.pcb_unit_path().unwrap(),
+                phase: Some(PhaseReference::from_raw_str("top_1")),
+                ..PlacementState::default()
+            });
+        let _update = app.update(
+            Event::ProjectFileRead {
+                path: PathBuf::from("projects/job1/project-job1.mpnp.json"),
+                result: FileIoResult::Read {
+                    content: file::to_bytes(&project).unwrap(),
+                },
+            },
+            &mut model,
+        );
+
+        // when
+        let _update = app.update(
+            Event::DeletePhase {
+                reference: PhaseReference::from_raw_str("top_1"),
+                strategy: DeleteStrategy::Block,
+            },
+            &mut model,
+        );
+
+        // then the phase is in use
+        let view = app.view(&model);
+        assert!(view.error.is_some());
+        assert_eq!(view.delete_report, None);
+
+        // when
+        let _update = app.update(
+            Event::DeletePhase {
+                reference: PhaseReference::from_raw_str("top_1"),
+                strategy: DeleteStrategy::Orphan,
+            },
+            &mut model,
+        );
 
-                let load_out_source =
-                    try_build_phase_load_out_source(&project_directory, &phase).map_err(AppError::SourceError)?;
+        // then
+        let view = app.view(&model);
+        assert_eq!(view.error, None);
+        assert_eq!(
+            view.delete_report
+                .unwrap()
+                .unassigned_placements,
+            vec![object_path]
+        );
 
-                let loadout_items = stores::load_out::load_items(&load_out_source).map_err(AppError::OperationError)?;
+        // when
+        let _update = app.update(Event::RequestOverviewView {}, &mut model);
 
-                let mut placements: Vec<(&ObjectPath, &PlacementState)> = project
-                    .placements
-                    .iter()
-                    .filter(|(_path, state)| match &state.phase {
-                        Some(candidate_phase) if phase_reference == *candidate_phase => true,
-                        _ => false,
-                    })
-                    .collect();
+        // then the report is only for the last event
+        assert_eq!(app.view(&model).delete_report, None);
+    }
 
-                let pcb_unit_positioning_map = project::build_pcbs_unit_positioning_map(&pcbs);
+    #[test]
+    fn delete_phase_events_without_a_strategy_unassign_the_placements() {
+        // when
+        let event: Event = serde_json::from_str(r#"{"DeletePhase": {"reference": "top_1"}}"#).unwrap();
+
+        // then
+        assert!(matches!(event, Event::DeletePhase {
+            strategy: DeleteStrategy::Orphan,
+            ..
+        }));
+    }
 
-                let mut packages = Vec::new();
-                let mut package_mappings = Vec::new();
-                let part_packages_map = Self::load_part_packages_map(project, &mut packages, &mut package_mappings)?;
+    #[test]
+    fn placement_selections_are_allocated_by_the_model() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let mut project = Project::new("job1".to_string(), None, None);
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap();
+        project
+            .placements
+            .insert(object_path.clone(), PlacementState {
+                unit_path: object_path.pcb_unit_path().unwrap(),
+                ..PlacementState::default()
+            });
+        let _update = app.update(
+            Event::ProjectFileRead {
+                path: PathBuf::from("projects/job1/project-job1.mpnp.json"),
+                result: FileIoResult::Read {
+                    content: file::to_bytes(&project).unwrap(),
+                },
+            },
+            &mut model,
+        );
 
-                project::sort_placements(
-                    &mut placements,
-                    &phase.placement_orderings,
-                    &loadout_items,
-                    &part_packages_map,
-                    &pcb_unit_positioning_map,
-                );
+        // when
+        let _update = app.update(
+            Event::SelectPlacements {
+                filter: ObjectPathPattern::exact(&object_path),
+            },
+            &mut model,
+        );
+        let first_selection = app
+            .view(&model)
+            .placement_selection
+            .unwrap();
+        let _update = app.update(
+            Event::SelectPlacements {
+                filter: ObjectPathPattern::exact(&object_path),
+            },
+            &mut model,
+        );
+        let second_selection = app
+            .view(&model)
+            .placement_selection
+            .unwrap();
+
+        // then
+        assert_ne!(first_selection, second_selection);
+
+        // when
+        let _update = app.update(
+            Event::ReleasePlacementSelection {
+                selection: first_selection,
+            },
+            &mut model,
+        );
 
-                let placements = placements
-                    .into_iter()
-                    .enumerate()
-                    .map(|(ordering, (path, state))| PlacementsItem {
-                        path: path.clone(),
-                        state: state.clone(),
-                        ordering,
-                    })
-                    .collect();
+        // then the selection is only in the view model for the event that made it
+        let view = app.view(&model);
+        assert_eq!(view.error, None);
+        assert_eq!(view.placement_selection, None);
 
-                let phase_placements = PhasePlacements {
-                    phase_reference,
-                    placements,
-                };
-                Ok(project_view_renderer::view(ProjectView::PhasePlacements(
-                    phase_placements,
-                )))
-            }),
-            Event::RequestPartStatesView {} => Box::new(move |model: &mut Model| {
-                let ModelProject {
-                    project, ..
-                } = model
-                    .model_project
-                    .as_mut()
-                    .ok_or(AppError::OperationRequiresProject)?;
+        // when
+        let _update = app.update(
+            Event::ReleasePlacementSelection {
+                selection: first_selection,
+            },
+            &mut model,
+        );
 
-                let mut parts = project
-                    .part_states
-                    .iter()
-                    .map(|(part, state)| {
-                        let processes = state
-                            .applicable_processes
-                            .iter()
-                            .cloned()
-                            .collect();
-                        PartWithState {
-                            part: part.clone(),
-                            processes,
-                            ref_des_set: Default::default(),
-                            quantity: 0,
-                        }
-                    })
-                    .collect::<Vec<_>>();
+        // then
+        assert!(app.view(&model).error.is_some());
+    }
 
-                //
-                // add the set of ref_des and count the quantity for each part.
-                //
-                for (_object_path, placement_state) in project.placements.iter_mut() {
-                    if let Some(part) = parts
-                        .iter_mut()
-                        .find(|part_with_state| {
-                            part_with_state
-                                .part
-                                .eq(&placement_state.placement.part)
-                        })
-                    {
-                        part.quantity += 1;
-                        let _inserted = part.ref_des_set.insert(
-                            placement_state
-                                .placement
-                                .ref_des
-                                .clone(),
-                        );
-                    }
-                }
+    #[test]
+    fn placement_events_serialized_before_selections_are_deserialized() {
+        // when
+        let event: Event = serde_json::from_str(
+            r#"{"RecordPlacementsOperation": {"object_path_patterns": ["ref_des=R1$"], "operation": "Place"}}"#,
+        )
+        .unwrap();
+
+        // then
+        assert!(matches!(
+            event,
+            Event::RecordPlacementsOperation { placements, .. }
+                if matches!(placements.as_slice(), [PlacementsSelector::Pattern(_)])
+        ));
+
+        // when
+        let event: Event = serde_json::from_str(
+            r#"{"AssignPlacementsToPhase": {"phase": "top_1", "operation": "set", "placements": "ref_des=R1$"}}"#,
+        )
+        .unwrap();
 
-                let part_states_view = PartStates {
-                    parts,
-                };
+        // then
+        assert!(matches!(event, Event::AssignPlacementsToPhase {
+            placements: PlacementsSelector::Pattern(_),
+            ..
+        }));
+    }
 
-                Ok(project_view_renderer::view(ProjectView::Parts(part_states_view)))
-            }),
-            Event::RequestPhaseLoadOutView {
-                phase_reference,
-            } => Box::new(move |model: &mut Model| {
-                let (
-                    ModelProject {
-                        project, ..
-                    },
-                    directory,
-                ) = Self::model_project_and_directory(model)?;
+    #[test]
+    fn bottom_placement_locations_are_mirrored_when_viewed_from_the_bottom() {
+        // given
+        let object_path = ObjectPath::from_str("pcb=2::unit=1::ref_des=R1").unwrap();
+        let unit_position = UnitPlacementPosition {
+            x: Decimal::new(105, 1),
+            y: Decimal::new(20, 0),
+            rotation: Decimal::new(90, 0),
+        };
 
-                let phase = project
-                    .phases
-                    .get(&phase_reference)
-                    .ok_or(AppError::UnknownPhaseReference(phase_reference.clone()))?;
+        // when
+        let location = Planner::placement_location(1, object_path, PcbSide::Bottom, &unit_position);
+
+        // then
+        let expected_rotation = AngleUnit::new_degrees_decimal(Decimal::new(90, 0));
+        assert_eq!(location.pcb, 1);
+        assert_eq!(
+            location.position,
+            PlacementPositionUnit::new(
+                DimensionUnitPoint2::new_dim_decimal(
+                    Decimal::new(105, 1),
+                    Decimal::new(20, 0),
+                    UnitSystem::Millimeters
+                ),
+                expected_rotation,
+            )
+        );
+        assert_eq!(
+            location.viewed_position,
+            PlacementPositionUnit::new(
+                DimensionUnitPoint2::new_dim_decimal(
+                    Decimal::new(-105, 1),
+                    Decimal::new(20, 0),
+                    UnitSystem::Millimeters
+                ),
+                expected_rotation,
+            )
+        );
+    }
 
-                let load_out_source =
-                    try_build_phase_load_out_source(&directory, &phase).map_err(AppError::SourceError)?;
+    #[test]
+    fn operations_requested_with_a_cancelled_token_are_cancelled_before_they_start() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let token = CancellationToken::new();
 
-                let items = stores::load_out::load_items(&load_out_source).map_err(AppError::OperationError)?;
+        // and tokens with the same id, but their own state, like tokens sent by a shell
+        let shell_token =
+            || -> CancellationToken { serde_json::from_str(&serde_json::to_string(&token).unwrap()).unwrap() };
 
-                let load_out_view = LoadOut {
-                    phase_reference,
-                    source: load_out_source,
-                    items,
-                };
+        // when
+        let _update = app.update(
+            Event::CancelOperation {
+                token: token.clone(),
+            },
+            &mut model,
+        );
+        let _update = app.update(
+            Event::RefreshPcbs {
+                token: Some(shell_token()),
+            },
+            &mut model,
+        );
 
-                Ok(project_view_renderer::view(ProjectView::PhaseLoadOut(load_out_view)))
-            }),
-            Event::RequestProjectReportView {} => Box::new(|model: &mut Model| {
-                let (
-                    ModelProject {
-                        project, ..
-                    },
-                    pcbs,
-                    project_directory,
-                ) = { Self::model_project_and_pcbs(model) }?;
+        // then
+        assert!(matches!(
+            app.view(&model).error,
+            Some((_, AppErrorView::Cancelled { .. }))
+        ));
+        assert!(model.cancelled_operations.is_empty());
+
+        // when the token is used again
+        let _update = app.update(
+            Event::RefreshPcbs {
+                token: Some(shell_token()),
+            },
+            &mut model,
+        );
 
-                let phase_load_out_item_map = Self::build_phase_load_out_item_map(project, &project_directory)
-                    .map_err(AppError::OperationError)?;
+        // then
+        assert_eq!(app.view(&model).error, None);
+    }
 
-                let report = report::project_generate_report(project, &pcbs, &phase_load_out_item_map);
+    #[test]
+    fn only_the_most_recent_cancelled_operations_are_kept() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let tokens = (0..MAX_CANCELLED_OPERATIONS + 10)
+            .map(|_| CancellationToken::new())
+            .collect::<Vec<_>>();
 
-                Ok(project_view_renderer::view(ProjectView::ProjectReport(report)))
-            }),
+        // when
+        for token in tokens.iter() {
+            let _update = app.update(
+                Event::CancelOperation {
+                    token: token.clone(),
+                },
+                &mut model,
+            );
         }
+
+        // then
+        assert_eq!(model.cancelled_operations.len(), MAX_CANCELLED_OPERATIONS);
+        assert!(!model
+            .cancelled_operations
+            .contains(&tokens[0].id()));
+        assert!(model
+            .cancelled_operations
+            .contains(&tokens.last().unwrap().id()));
     }
 
-    fn build_phase_load_out_item_map(
-        project: &mut Project,
-        project_directory: &PathBuf,
-    ) -> Result<BTreeMap<Reference, Vec<LoadOutItem>>, Error> {
-        project.phases.iter().try_fold(
-            BTreeMap::<Reference, Vec<LoadOutItem>>::new(),
-            |mut map, (reference, phase)| {
-                let load_out_items = stores::load_out::load_items(&LoadOutSource::try_from_path(
-                    &project_directory,
-                    PathBuf::from_str(&phase.load_out_source)?,
-                )?)?;
-                map.insert(reference.clone(), load_out_items);
-                Ok::<BTreeMap<Reference, Vec<LoadOutItem>>, anyhow::Error>(map)
-            },
+    #[test]
+    fn import_moisture_sensitivities_reports_unknown_parts() {
+        // given a project that uses one of the parts
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let ic1 = Part::new("IC_MFR1".to_string(), "IC1".to_string());
+        let mut project = Project::new("job1".to_string(), None, None);
+        project
+            .part_states
+            .insert(ic1.clone(), Default::default());
+        model
+            .model_project
+            .replace(ModelProject {
+                path: PathBuf::from("projects/job1/project-job1.mpnp.json"),
+                project_directory: PathBuf::from("projects/job1"),
+                project,
+                modified: Modified::default(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: None,
+                refresh_cache: Default::default(),
+            });
+
+        // and
+        let temp_dir = tempfile::tempdir().unwrap();
+        let parts_path = temp_dir.path().join("parts.csv");
+        std::fs::write(
+            &parts_path,
+            "\"Manufacturer\",\"Mpn\",\"Msl\",\"FloorLifeHours\"\n\
+             \"IC_MFR1\",\"IC1\",\"3\",\"\"\n\
+             \"IC_MFR2\",\"IC2\",\"6\",\"4\"\n",
         )
-    }
+        .unwrap();
 
-    fn load_packages(packages_source: &PackagesSource) -> Result<Vec<Package>, AppError> {
-        let packages: Vec<Package> = stores::packages::load_packages(packages_source)
-            .map_err(|error| AppError::OperationError(anyhow!("package source error. cause: {:?}", error)))?;
+        // when
+        let update = app.update(
+            Event::ImportMoistureSensitivities {
+                parts_source: PartsSource::from_absolute_path(parts_path).unwrap(),
+            },
+            &mut model,
+        );
 
-        Ok(packages)
+        // then
+        assert_effect!(update, Effect::Render(_));
+        let model_project = model.model_project.as_ref().unwrap();
+        assert!(model_project.modified.is_modified());
+        assert_eq!(
+            model_project.project.part_states[&ic1].moisture_sensitivity,
+            Some(PartMoistureSensitivity {
+                level: MoistureSensitivityLevel::Msl3,
+                floor_life_hours: None,
+            })
+        );
+
+        // and the part that is not used by the project is reported, and not added
+        assert_eq!(model_project.project.part_states.len(), 1);
+        let warnings = app
+            .view(&model)
+            .warnings
+            .into_iter()
+            .map(|(_timestamp, warning)| warning)
+            .collect::<Vec<_>>();
+        assert_eq!(warnings, vec![AppWarning::UnknownParts {
+            parts: vec![Part::new("IC_MFR2".to_string(), "IC2".to_string())],
+        }]);
     }
 
-    fn load_package_mappings<'a>(
-        package_mappings_source: &PackageMappingsSource,
-        packages: &'a Vec<Package>,
-    ) -> Result<Vec<PackageMapping<'a>>, AppError> {
-        let package_mappings = stores::package_mappings::load_package_mappings(packages, package_mappings_source)
-            .map_err(|error| AppError::OperationError(anyhow!("package source error. cause: {:?}", error)))?;
+    #[test]
+    fn only_design_variants_with_changed_placements_files_are_refreshed() {
+        // given a project with a PCB that has a unit for each of two design variants
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let design_variant = |variant_name: &str| DesignVariant {
+            design_name: DesignName::from_str("design_a").unwrap(),
+            variant_name: VariantName::from_str(variant_name).unwrap(),
+        };
+        let mut project_pcb = ProjectPcb::new(FileReference::Relative("panel_a.pcb.json".into()));
+        project_pcb
+            .unit_assignments
+            .insert(0, design_variant("variant_a"));
+        project_pcb
+            .unit_assignments
+            .insert(1, design_variant("variant_b"));
+        let mut project = Project::new("job1".to_string(), None, None);
+        project.pcbs.push(project_pcb);
+        model
+            .model_project
+            .replace(ModelProject {
+                path: temp_dir
+                    .path()
+                    .join("project-job1.mpnp.json"),
+                project_directory: temp_dir.path().to_path_buf(),
+                project,
+                modified: Modified::default(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: None,
+                refresh_cache: Default::default(),
+            });
+        model
+            .model_pcbs
+            .insert(temp_dir.path().join("panel_a.pcb.json"), ModelPcb {
+                pcb: Pcb::new(
+                    "panel_a".to_string(),
+                    2,
+                    IndexSet::from([DesignName::from_str("design_a").unwrap()]),
+                    BTreeMap::from([(0, 0), (1, 0)]),
+                ),
+                modified: Modified::default(),
+                file_modified: None,
+            });
 
-        Ok(package_mappings)
-    }
+        // and
+        let placements_content = "\"RefDes\",\"Manufacturer\",\"Mpn\",\"Place\",\"PcbSide\",\"X\",\"Y\",\"Rotation\"\n\
+                                  \"R1\",\"RES_MFR1\",\"RES1\",\"true\",\"Top\",\"10\",\"110\",\"0\"\n";
+        for variant_name in ["variant_a", "variant_b"] {
+            std::fs::write(
+                temp_dir
+                    .path()
+                    .join(format!("design_a_{}_placements.csv", variant_name)),
+                placements_content,
+            )
+            .unwrap();
+        }
+        let refresh = |model: &mut Model| {
+            let _update = app.update(
+                Event::RefreshFromDesignVariants {
+                    token: None,
+                },
+                model,
+            );
+            app.view(model)
+        };
 
-    fn project_unique_parts(project: &Project) -> BTreeSet<&Part> {
-        let unique_parts = BTreeSet::from_iter(
-            project
+        // and the first refresh loads all the design variants
+        let view = refresh(&mut model);
+        assert_eq!(view.error, None);
+        assert_eq!(
+            view.refreshed_design_variants,
+            Some(BTreeSet::from([
+                design_variant("variant_a"),
+                design_variant("variant_b")
+            ]))
+        );
+
+        // when only one of the placements files is changed
+        std::fs::write(
+            temp_dir
+                .path()
+                .join("design_a_variant_b_placements.csv"),
+            format!(
+                "{}\"R2\",\"RES_MFR1\",\"RES1\",\"true\",\"Top\",\"20\",\"110\",\"0\"\n",
+                placements_content
+            ),
+        )
+        .unwrap();
+        let view = refresh(&mut model);
+
+        // then only that design variant is refreshed
+        assert_eq!(view.error, None);
+        assert_eq!(
+            view.refreshed_design_variants,
+            Some(BTreeSet::from([design_variant("variant_b")]))
+        );
+        assert_eq!(
+            model
+                .model_project
+                .as_ref()
+                .unwrap()
+                .project
                 .placements
-                .iter()
-                .map(|(_path, placement_state)| &placement_state.placement.part),
+                .len(),
+            3
         );
 
-        unique_parts
+        // and nothing is refreshed when nothing changed
+        let view = refresh(&mut model);
+        assert_eq!(view.refreshed_design_variants, Some(BTreeSet::new()));
     }
 
-    fn build_project_part_package_map<'a, 'b, 'c, 'd>(
-        unique_parts: &'a BTreeSet<&'b Part>,
-        package_mappings: &'c Vec<PackageMapping<'d>>,
-    ) -> Result<BTreeMap<&'b Part, &'c Package>, AppError> {
-        let part_packages_map = package_mapper::PackageMapper::process(unique_parts, package_mappings)
-            .map_err(|mapper_error| AppError::OperationError(anyhow!("part mapper error. cause: {:?}", mapper_error)))?
-            .into_iter()
-            .filter_map(|result| {
-                result
-                    .package
-                    .map(|package| (result.part, package))
-            })
-            .collect::<BTreeMap<_, _>>();
-        Ok(part_packages_map)
-    }
+    #[test]
+    fn only_the_selected_artifacts_are_generated() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let temp_dir = tempfile::tempdir().unwrap();
+        model
+            .model_project
+            .replace(ModelProject {
+                path: temp_dir
+                    .path()
+                    .join("project-job1.mpnp.json"),
+                project_directory: temp_dir.path().to_path_buf(),
+                project: Project::new("job1".to_string(), None, None),
+                modified: Modified::default(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: None,
+                refresh_cache: Default::default(),
+            });
+        let analytics_path = analytics::build_analytics_file_path("job1", temp_dir.path(), "phases");
+        let generate = |model: &mut Model, artifacts: BTreeSet<Artifact>| {
+            let _update = app.update(
+                Event::GenerateArtifacts {
+                    token: None,
+                    artifacts,
+                },
+                model,
+            );
+            app.view(model)
+        };
 
-    // Due to the lifetimes of the mappings, the storage for the packages and package mappings needs to be provided
-    // up-front.
-    fn load_part_packages_map<'a, 'b>(
-        project: &'a Project,
-        // Provide mutable references to variables that will be populated
-        packages_storage: &'b mut Vec<Package>,
-        package_mappings_storage: &'b mut Vec<PackageMapping<'b>>,
-    ) -> Result<BTreeMap<&'a Part, &'b Package>, AppError> {
-        // Check if both sources are available
-        let (Some(packages_source), Some(package_mappings_source)) = (
-            project
-                .library_config
-                .package_source
-                .as_ref(),
-            project
-                .library_config
-                .package_mappings_source
-                .as_ref(),
-        ) else {
-            return Ok(BTreeMap::new());
+        // when
+        let view = generate(&mut model, BTreeSet::new());
+
+        // then the report is always generated
+        assert_eq!(view.error, None);
+        assert!(temp_dir
+            .path()
+            .join("job1_report.json")
+            .exists());
+        assert!(!analytics_path.exists());
+
+        // when
+        let view = generate(&mut model, BTreeSet::from([Artifact::Analytics]));
+
+        // then
+        assert_eq!(view.error, None);
+        assert!(analytics_path.exists());
+    }
+
+    #[test]
+    fn vision_inspections_are_recorded_in_the_phase_history() {
+        use indexmap::IndexMap;
+        use planning::process::{OperationState, PlacementTaskState, SerializableTaskState, TaskState};
+
+        use crate::effects::vision::VisionMeasurement;
+
+        // given a project with a placement in a phase that has a placement task in progress
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let phase = PhaseReference::from_raw_str("top_1");
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=U1").unwrap();
+        let mut project = Project::new("job1".to_string(), None, None);
+        project
+            .placements
+            .insert(object_path.clone(), PlacementState {
+                phase: Some(phase.clone()),
+                ..PlacementState::default()
+            });
+        let mut placement_task_state = Box::new(PlacementTaskState::default()) as Box<dyn SerializableTaskState>;
+        placement_task_state.set_started();
+        project
+            .phase_states
+            .insert(phase.clone(), PhaseState {
+                operation_states: vec![OperationState {
+                    reference: OperationReference::from_raw_str("operation_1"),
+                    task_states: IndexMap::from([(TaskReference::from_raw_str("task_1"), placement_task_state)]),
+                }],
+            });
+        model
+            .model_project
+            .replace(ModelProject {
+                path: temp_dir
+                    .path()
+                    .join("project-job1.mpnp.json"),
+                project_directory: temp_dir.path().to_path_buf(),
+                project,
+                modified: Modified::default(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: None,
+                refresh_cache: Default::default(),
+            });
+        let operation = VisionOperation::InspectPlacement {
+            phase,
+            object_path,
         };
 
-        // Load the packages and store them in the provided output vector
-        *packages_storage = Self::load_packages(packages_source)?;
+        // when
+        let update = app.update(
+            Event::RequestVisionOperation {
+                operation: operation.clone(),
+            },
+            &mut model,
+        );
 
-        // Load the mappings and store them in the provided output vector
-        *package_mappings_storage = Self::load_package_mappings(package_mappings_source, packages_storage)?;
+        // then the shell is asked to inspect the placement
+        assert!(update
+            .effects()
+            .any(|effect| matches!(effect, Effect::Vision(request) if request.operation == operation)));
 
-        // Build the mapping
-        let unique_parts = Self::project_unique_parts(project);
-        let part_packages_map = Self::build_project_part_package_map(&unique_parts, package_mappings_storage)?;
+        // when the shell responds with the measurement
+        let _update = app.update(
+            Event::VisionOperationCompleted {
+                operation,
+                result: VisionResult::Located(VisionMeasurement {
+                    offset: nalgebra::Vector2::new(0.05, -0.02),
+                    rotation: 0.5,
+                    confidence: 0.9,
+                }),
+            },
+            &mut model,
+        );
 
-        Ok(part_packages_map)
+        // then
+        assert!(app.view(&model).error.is_none());
+        let phase_log = std::fs::read_to_string(temp_dir.path().join("top_1_log.json")).unwrap();
+        assert!(phase_log.contains("placement_inspection"));
+
+        // when the shell has no camera
+        let _update = app.update(
+            Event::VisionOperationCompleted {
+                operation: VisionOperation::CaptureFrame,
+                result: VisionResult::Unavailable,
+            },
+            &mut model,
+        );
+
+        // then
+        assert!(app.view(&model).error.is_some());
     }
 
-    fn create_and_add_pcb(
-        name: String,
-        units: u16,
-        unit_map: BTreeMap<PcbUnitNumber, DesignName>,
-        model: &mut Model,
-        pcb_path: &PathBuf,
-    ) -> Result<(), AppError> {
-        let pcb = planning::pcb::create_pcb(name, units, unit_map).map_err(AppError::PcbOperationError)?;
+    #[test]
+    fn part_packages_view_links_parts_to_packages() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let packages_path = temp_dir.path().join("packages.csv");
+        std::fs::write(
+            &packages_path,
+            "\"Name\",\"LeadCount\",\"LeadPitchMm\",\"SizeX\",\"SizeY\",\"SizeZ\",\"Pin1OrientationDegrees\"\n\
+             \"SOIC-8\",\"8\",\"1.27\",\"4.9\",\"6.0\",\"1.75\",\"135\"\n",
+        )
+        .unwrap();
+        let package_mappings_path = temp_dir
+            .path()
+            .join("package-mappings.csv");
+        std::fs::write(
+            &package_mappings_path,
+            "\"Manufacturer\",\"Mpn\",\"Name\"\n\
+             \"MFR1\",\"IC1\",\"SOIC-8\"\n",
+        )
+        .unwrap();
 
+        // and a project with a mapped and an unmapped part
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let ic1 = Part::new("MFR1".to_string(), "IC1".to_string());
+        let ic2 = Part::new("MFR1".to_string(), "IC2".to_string());
+        let mut project = Project::new(
+            "job1".to_string(),
+            Some(PackagesSource::from_absolute_path(packages_path).unwrap()),
+            Some(PackageMappingsSource::from_absolute_path(package_mappings_path).unwrap()),
+        );
+        for (ref_des, part) in [("U1", &ic1), ("U2", &ic2)] {
+            project
+                .part_states
+                .insert(part.clone(), Default::default());
+            project.placements.insert(
+                ObjectPath::from_str(&format!("pcb=1::unit=1::ref_des={}", ref_des)).unwrap(),
+                PlacementState {
+                    placement: Placement {
+                        part: part.clone(),
+                        ..Placement::default()
+                    },
+                    ..PlacementState::default()
+                },
+            );
+        }
         model
-            .model_pcbs
-            .insert(pcb_path.clone(), ModelPcb {
-                pcb: pcb.clone(),
-                // not saved, yet
-                modified: true,
+            .model_project
+            .replace(ModelProject {
+                path: temp_dir
+                    .path()
+                    .join("project-job1.mpnp.json"),
+                project_directory: temp_dir.path().to_path_buf(),
+                project,
+                modified: Modified::default(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: None,
+                refresh_cache: Default::default(),
             });
 
-        model.save_pcb(pcb_path)?;
-        Ok(())
+        // when
+        let update = app.update(Event::RequestPartPackagesView {}, &mut model);
+
+        // then
+        let part_packages = update
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::ProjectView(request) => match &request.operation {
+                    ProjectViewRendererOperation::View {
+                        view: ProjectView::PartPackages(part_packages),
+                    } => Some(part_packages.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .unwrap();
+        let expected_package = Package::new("SOIC-8".to_string())
+            .with_lead_count(8)
+            .with_pitch(Decimal::new(127, 2))
+            .with_dimensions(PackageDimensions::new(
+                Decimal::new(49, 1),
+                Decimal::new(60, 1),
+                Decimal::new(175, 2),
+            ))
+            .with_pin1_orientation(Decimal::from(135));
+        assert_eq!(part_packages, PartPackages {
+            parts: vec![
+                PartPackage {
+                    part: ic1,
+                    package: Some(expected_package),
+                },
+                PartPackage {
+                    part: ic2,
+                    package: None,
+                },
+            ],
+        });
     }
 
-    fn gerber_file_to_pcb_gerber_item(gerber_file: &GerberFile) -> PcbGerberItem {
-        // convert from project type to view type
-        PcbGerberItem {
-            path: gerber_file.file.clone(),
-            function: gerber_file.function,
-        }
+    #[test]
+    fn issues_view_has_severities_and_codes() {
+        // given a project with a placement that is not in a phase
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut project = Project::new("job1".to_string(), None, None);
+        project.placements.insert(
+            ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap(),
+            PlacementState::default(),
+        );
+        model
+            .model_project
+            .replace(ModelProject {
+                path: temp_dir
+                    .path()
+                    .join("project-job1.mpnp.json"),
+                project_directory: temp_dir.path().to_path_buf(),
+                project,
+                modified: Modified::default(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: None,
+                refresh_cache: Default::default(),
+            });
+
+        // when
+        let update = app.update(Event::RequestIssuesView {}, &mut model);
+
+        // then
+        let issues = update
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::ProjectView(request) => match &request.operation {
+                    ProjectViewRendererOperation::View {
+                        view: ProjectView::Issues(project_issues),
+                    } => Some(project_issues.issues.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .unwrap();
+        let mut codes = issues
+            .iter()
+            .map(|issue| (issue.code.as_str(), issue.severity.clone()))
+            .collect::<Vec<_>>();
+        codes.sort();
+        assert_eq!(codes, vec![
+            ("no-pcbs-assigned", IssueSeverity::Severe),
+            ("no-phases-created", IssueSeverity::Severe),
+            ("unassigned-placement", IssueSeverity::Warning),
+        ]);
     }
 
-    fn model_project_and_pcbs(model: &mut Model) -> Result<(&mut ModelProject, Vec<&Pcb>, PathBuf), AppError> {
-        let Some(model_project) = model.model_project.as_mut() else {
-            return Err(AppError::OperationRequiresProject);
-        };
+    /// A project with a `top_1` phase, with a placement task in progress, and a load-out with a feeder for `PART1`.
+    ///
+    /// R1 (PART1) and R2 (PART2) are pending, R3 (PART1) has already been placed.
+    fn build_placement_session_model(temp_dir: &tempfile::TempDir) -> (Model, PhaseReference) {
+        use indexmap::IndexMap;
+        use planning::process::{OperationState, PlacementTaskState, SerializableTaskState, TaskState};
+
+        let phase = PhaseReference::from_raw_str("top_1");
+        let load_out_path = temp_dir.path().join("top_1.csv");
+        std::fs::write(
+            &load_out_path,
+            "\"Reference\",\"Manufacturer\",\"Mpn\"\n\
+             \"FEEDER_1\",\"MFR1\",\"PART1\"\n",
+        )
+        .unwrap();
+
+        let mut project = Project::new("job1".to_string(), None, None);
+        project
+            .phases
+            .insert(phase.clone(), Phase {
+                reference: phase.clone(),
+                process: ProcessReference::from_raw_str("pnp"),
+                load_out_source: load_out_path
+                    .to_str()
+                    .unwrap()
+                    .to_string(),
+                pcb_side: PcbSide::Top,
+                placement_orderings: vec![],
+                assembly_window: None,
+            });
 
-        let project_directory = model_project
-            .path
-            .parent()
-            .unwrap()
-            .to_path_buf();
+        for (ref_des, mpn, x, operation_status) in [
+            ("R1", "PART1", 10, PlacementStatus::Pending),
+            ("R2", "PART2", 20, PlacementStatus::Pending),
+            ("R3", "PART1", 30, PlacementStatus::Placed),
+        ] {
+            let object_path = ObjectPath::from_str(&format!("pcb=1::unit=1::ref_des={}", ref_des)).unwrap();
+            project
+                .placements
+                .insert(object_path, PlacementState {
+                    placement: Placement {
+                        ref_des: RefDes::from(ref_des),
+                        part: Part::new("MFR1".to_string(), mpn.to_string()),
+                        ..Placement::default()
+                    },
+                    unit_position: UnitPlacementPosition {
+                        x: Decimal::new(x, 0),
+                        y: Decimal::new(5, 0),
+                        rotation: Decimal::new(90, 0),
+                    },
+                    operation_status,
+                    phase: Some(phase.clone()),
+                    ..PlacementState::default()
+                });
+        }
 
-        let model_pcbs = &mut model.model_pcbs;
-        Model::load_project_pcbs_inner(&mut model_project.project, model_pcbs, &project_directory)?;
+        let mut placement_task_state = Box::new(PlacementTaskState::default()) as Box<dyn SerializableTaskState>;
+        placement_task_state.set_started();
+        project
+            .phase_states
+            .insert(phase.clone(), PhaseState {
+                operation_states: vec![OperationState {
+                    reference: OperationReference::from_raw_str("operation_1"),
+                    task_states: IndexMap::from([(TaskReference::from_raw_str("task_1"), placement_task_state)]),
+                }],
+            });
 
-        let iter = model_project.pcbs(&model.model_pcbs);
-        let pcbs = Model::project_pcbs_inner(iter);
+        let mut model = Model::default();
+        model
+            .model_project
+            .replace(ModelProject {
+                path: temp_dir
+                    .path()
+                    .join("project-job1.mpnp.json"),
+                project_directory: temp_dir.path().to_path_buf(),
+                project,
+                modified: Modified::default(),
+                placement_session: None,
+                placement_selections: Default::default(),
+                recovery: None,
+                refresh_cache: Default::default(),
+            });
 
-        Ok((model_project, pcbs, project_directory))
+        (model, phase)
     }
 
-    fn model_project_and_directory(model: &mut Model) -> Result<(&mut ModelProject, PathBuf), AppError> {
-        let Some(model_project) = model.model_project.as_mut() else {
-            return Err(AppError::OperationRequiresProject);
-        };
-
-        let project_directory = model_project
-            .path
-            .parent()
-            .unwrap()
-            .to_path_buf();
-        Ok((model_project, project_directory))
+    fn find_placement_session_view(update: &Update<Effect, Event>) -> Option<PlacementSession> {
+        update
+            .effects()
+            .find_map(|effect| match effect {
+                Effect::ProjectView(request) => match &request.operation {
+                    ProjectViewRendererOperation::View {
+                        view: ProjectView::PlacementSession(placement_session),
+                    } => Some(placement_session.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
     }
-}
 
-impl App for Planner {
-    type Event = Event;
-    type Model = Model;
-    type ViewModel = PlannerOperationViewModel;
-    type Capabilities = ();
-    type Effect = Effect;
+    fn current_ref_des(placement_session: &PlacementSession) -> Option<String> {
+        placement_session
+            .current
+            .as_ref()
+            .map(|item| item.state.placement.ref_des.to_string())
+    }
 
-    fn update(
-        &self,
-        event: Self::Event,
-        model: &mut Self::Model,
-        _caps: &Self::Capabilities,
-    ) -> Command<Self::Effect, Self::Event> {
-        let try_fn = self.update_inner(event);
+    #[test]
+    fn placement_session_guides_the_operator_through_the_pending_placements() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (mut model, phase) = build_placement_session_model(&temp_dir);
+
+        // when
+        let update = app.update(
+            Event::StartPlacementSession {
+                phase: phase.clone(),
+            },
+            &mut model,
+        );
 
-        match try_fn(model) {
-            Err(e) => {
-                model
-                    .error
-                    .replace((chrono::DateTime::from(SystemTime::now()), format!("{:?}", e)));
-                render::render()
-            }
-            Ok(command) => {
-                model.error.take();
-                command
-            }
-        }
+        // then the first pending placement is current, with its feeder, position and rotation
+        let placement_session = find_placement_session_view(&update).unwrap();
+        assert_eq!(placement_session.phase_reference, phase);
+        let current = placement_session
+            .current
+            .clone()
+            .unwrap();
+        assert_eq!(current.path, ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap());
+        assert_eq!(current.feeder_reference, Some(Reference::from_raw_str("FEEDER_1")));
+        assert_eq!(
+            current.position,
+            PlacementPositionUnit::new(
+                DimensionUnitPoint2::new_dim_decimal(Decimal::new(10, 0), Decimal::new(5, 0), UnitSystem::Millimeters),
+                AngleUnit::new_degrees_decimal(Decimal::new(90, 0)),
+            )
+        );
+        // and the already placed placement is not part of the session
+        assert_eq!(placement_session.progress, PlacementSessionProgress {
+            total: 2,
+            placed: 0,
+            skipped: 0,
+            remaining: 2,
+        });
+
+        // when
+        let update = app.update(Event::ConfirmPlacement {}, &mut model);
+
+        // then the placement is recorded as placed, and the next placement, which has no feeder, is current
+        let placement_session = find_placement_session_view(&update).unwrap();
+        let current = placement_session
+            .current
+            .clone()
+            .unwrap();
+        assert_eq!(current_ref_des(&placement_session), Some("R2".to_string()));
+        assert_eq!(current.feeder_reference, None);
+        assert_eq!(placement_session.progress, PlacementSessionProgress {
+            total: 2,
+            placed: 1,
+            skipped: 0,
+            remaining: 1,
+        });
+        let model_project = model.model_project.as_ref().unwrap();
+        assert_eq!(
+            model_project.project.placements[&ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap()]
+                .operation_status,
+            PlacementStatus::Placed
+        );
+        assert!(model_project.modified.is_modified());
+
+        // when
+        let update = app.update(Event::SkipPlacement {}, &mut model);
+
+        // then the session is complete
+        let placement_session = find_placement_session_view(&update).unwrap();
+        assert_eq!(placement_session.current, None);
+        assert_eq!(placement_session.progress, PlacementSessionProgress {
+            total: 2,
+            placed: 1,
+            skipped: 1,
+            remaining: 0,
+        });
+
+        // when
+        let _update = app.update(Event::ConfirmPlacement {}, &mut model);
+
+        // then
+        assert!(matches!(
+            app.view(&model).error,
+            Some((_, AppErrorView::PlacementSessionComplete))
+        ));
     }
 
-    fn view(&self, model: &Self::Model) -> Self::ViewModel {
-        let project_modified = model
+    #[test]
+    fn next_placement_wraps_around_to_the_placements_that_were_passed_over() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (mut model, phase) = build_placement_session_model(&temp_dir);
+        let _update = app.update(
+            Event::StartPlacementSession {
+                phase,
+            },
+            &mut model,
+        );
+
+        // when
+        let update = app.update(Event::NextPlacement {}, &mut model);
+
+        // then
+        let placement_session = find_placement_session_view(&update).unwrap();
+        assert_eq!(current_ref_des(&placement_session), Some("R2".to_string()));
+
+        // when
+        let update = app.update(Event::NextPlacement {}, &mut model);
+
+        // then no operation was recorded
+        let placement_session = find_placement_session_view(&update).unwrap();
+        assert_eq!(current_ref_des(&placement_session), Some("R1".to_string()));
+        assert_eq!(placement_session.progress, PlacementSessionProgress {
+            total: 2,
+            placed: 0,
+            skipped: 0,
+            remaining: 2,
+        });
+        assert!(!model
             .model_project
             .as_ref()
-            .map_or(false, |project| project.modified);
+            .unwrap()
+            .modified
+            .is_modified());
+    }
 
-        let pcbs_modified = model
+    #[test]
+    fn placement_session_events_require_a_placement_session() {
+        // given
+        let app = AppTester::<Planner>::default();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (mut model, phase) = build_placement_session_model(&temp_dir);
+
+        // when
+        let _update = app.update(Event::ConfirmPlacement {}, &mut model);
+
+        // then
+        assert!(matches!(
+            app.view(&model).error,
+            Some((_, AppErrorView::PlacementSessionRequired))
+        ));
+
+        // when the session is ended
+        let _update = app.update(
+            Event::StartPlacementSession {
+                phase,
+            },
+            &mut model,
+        );
+        let _update = app.update(Event::EndPlacementSession {}, &mut model);
+        let _update = app.update(Event::SkipPlacement {}, &mut model);
+
+        // then
+        assert!(matches!(
+            app.view(&model).error,
+            Some((_, AppErrorView::PlacementSessionRequired))
+        ));
+    }
+
+    #[test]
+    fn import_pcb_unit_positionings_orders_and_validates_the_units() {
+        // given a 2 unit PCB with a panel size
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let pcb_path = temp_dir.path().join("panel_a.pcb.json");
+        let mut pcb = Pcb::new(
+            "panel_a".to_string(),
+            2,
+            IndexSet::from([DesignName::from_str("design_a").unwrap()]),
+            BTreeMap::from([(0, 0), (1, 0)]),
+        );
+        pcb.panel_sizing.size = Vector2::new(100.0, 50.0);
+        model
             .model_pcbs
-            .iter()
-            .any(|(_file_reference, pcb)| pcb.modified);
+            .insert(pcb_path.clone(), ModelPcb {
+                pcb,
+                modified: Modified::default(),
+                file_modified: None,
+            });
 
-        let view_model = PlannerOperationViewModel {
-            project_modified,
-            pcbs_modified,
-            error: model.error.clone(),
+        // and unit positionings, not in unit order
+        let import = |model: &mut Model, content: &str| {
+            let source_path = temp_dir
+                .path()
+                .join("unit-positionings.csv");
+            std::fs::write(&source_path, content).unwrap();
+            let _update = app.update(
+                Event::ImportPcbUnitPositionings {
+                    path: pcb_path.clone(),
+                    source: UnitPositioningsSource::from_absolute_path(source_path).unwrap(),
+                },
+                model,
+            );
+            app.view(model).error
         };
 
-        trace!("view model: {:?}", view_model);
+        // when
+        let error = import(
+            &mut model,
+            "\"Unit\",\"X\",\"Y\",\"Rotation\"\n\
+             \"2\",\"60.5\",\"5\",\"180\"\n\
+             \"1\",\"10\",\"5\",\"0\"\n",
+        );
 
-        view_model
-    }
-}
+        // then
+        assert_eq!(error, None);
+        let model_pcb = &model.model_pcbs[&pcb_path];
+        assert!(model_pcb.modified.is_modified());
+        assert_eq!(
+            model_pcb
+                .pcb
+                .panel_sizing
+                .pcb_unit_positionings,
+            vec![
+                PcbUnitPositioning {
+                    offset: Vector2::new(10.0, 5.0),
+                    rotation: Decimal::new(0, 0),
+                },
+                PcbUnitPositioning {
+                    offset: Vector2::new(60.5, 5.0),
+                    rotation: Decimal::new(180, 0),
+                },
+            ]
+        );
 
-#[derive(Error, Debug)]
-enum AppError {
-    #[error("Operation requires a project")]
-    OperationRequiresProject,
-    #[error("Operation error, cause: {0}")]
-    OperationError(anyhow::Error),
-    #[error("Phase error. cause: {0}")]
-    PhaseError(PhaseError),
-    #[error("Project error, cause: {0}")]
-    ProjectError(ProjectError),
-    #[error("Process error. cause: {0}")]
-    ProcessError(ProcessError),
-    #[error("Part error. cause: {0}")]
-    PartError(PartStateError),
-    #[error("Source error. cause: {0}")]
-    SourceError(SourceError),
-    #[error("Loadout error. cause: {0}")]
-    LoadoutError(LoadOutOperationError),
-    #[error("PCB error. cause: {0}")]
-    PcbOperationError(PcbOperationError),
-    #[error("IO error. cause: {0}")]
-    IoError(std::io::Error),
+        // when a unit is outside the panel
+        let error = import(
+            &mut model,
+            "\"Unit\",\"X\",\"Y\",\"Rotation\"\n\
+             \"1\",\"10\",\"5\",\"0\"\n\
+             \"2\",\"160.5\",\"5\",\"180\"\n",
+        );
 
-    #[error("Unknown phase reference. reference: {0}")]
-    UnknownPhaseReference(Reference),
-    #[error("Unknown process reference. reference: {0}")]
-    UnknownProcessReference(ProcessReference),
+        // then the unit positionings are not changed
+        assert!(matches!(error, Some((_, AppErrorView::PcbOperationError { .. }))));
+        assert_eq!(
+            model.model_pcbs[&pcb_path]
+                .pcb
+                .panel_sizing
+                .pcb_unit_positionings[1]
+                .offset,
+            Vector2::new(60.5, 5.0)
+        );
+
+        // when there are fewer unit positionings than units
+        let error = import(
+            &mut model,
+            "\"Unit\",\"X\",\"Y\",\"Rotation\"\n\"1\",\"10\",\"5\",\"0\"\n",
+        );
+
+        // then
+        assert!(matches!(error, Some((_, AppErrorView::PcbOperationError { .. }))));
+    }
 }
 
-impl Planner {
-    fn refresh_project(project: &mut Project, pcbs: &[&Pcb], path: &PathBuf) -> Result<bool, ProjectError> {
-        let directory = path.parent().unwrap();
+/// Adds a `count` argument to the parts node, and a child node for each process with the number of parts that the
+/// process applies to, and one for the parts without a process, if any.
+fn add_parts_tree_nodes(tree: &mut Graph<ProjectTreeItem, ()>, parts_node: NodeIndex, project: &Project) {
+    let parts_path = tree[parts_node].path.clone();
+    tree[parts_node]
+        .args
+        .insert("count".to_string(), Arg::Integer(project.part_states.len() as i64));
+
+    let mut add_node = |key: &str, args: HashMap<String, Arg>, path: TreePath| {
+        let node = tree.add_node(ProjectTreeItem {
+            key: key.to_string(),
+            args,
+            path,
+        });
+        tree.add_edge(parts_node, node, ());
+    };
+
+    for process in project.processes.iter() {
+        let count = project
+            .part_states
+            .values()
+            .filter(|part_state| {
+                part_state
+                    .applicable_processes
+                    .contains(&process.reference)
+            })
+            .count();
+
+        add_node(
+            "parts-process",
+            HashMap::from([
+                ("process".to_string(), Arg::String(process.reference.to_string())),
+                ("count".to_string(), Arg::Integer(count as i64)),
+            ]),
+            parts_path
+                .join("processes")
+                .join(process.reference.to_string()),
+        );
+    }
 
-        let unique_design_variants = project.unique_design_variants(pcbs);
+    let unassigned_count = project
+        .part_states
+        .values()
+        .filter(|part_state| {
+            part_state
+                .applicable_processes
+                .is_empty()
+        })
+        .count();
+    if unassigned_count > 0 {
+        add_node(
+            "parts-unassigned",
+            HashMap::from([("count".to_string(), Arg::Integer(unassigned_count as i64))]),
+            parts_path.join("unassigned"),
+        );
+    }
+}
 
-        let design_variant_placement_map = stores::placements::load_all_placements(unique_design_variants, directory)
-            .map_err(ProjectError::UnableToLoadPlacements)?;
-        let refresh_result = project::refresh_from_design_variants(project, pcbs, design_variant_placement_map);
+/// The placements that are used and to be placed, and how many of them are complete, i.e. placed or skipped.
+#[derive(Default)]
+struct PlacementCounts {
+    count: usize,
+    complete: usize,
+}
 
-        if let Ok(modified) = &refresh_result {
-            trace!("Refreshed from design variants. modified: {}", modified);
+impl PlacementCounts {
+    fn add(&mut self, placement_state: &PlacementState) {
+        self.count += 1;
+        if placement_state.operation_status != PlacementStatus::Pending {
+            self.complete += 1;
         }
-
-        refresh_result
     }
 
-    fn unique_parts<'a>(project: &'a Project) -> impl IntoIterator<Item = &'a Part> + 'a {
-        let unique_parts = project
-            .placements
-            .iter()
-            .map(|(_path, state)| &state.placement.part)
-            .collect::<IndexSet<_>>();
-        unique_parts
+    /// `count`, `complete` and `percentage`, the completion percentage is rounded down.
+    fn args(&self) -> HashMap<String, Arg> {
+        let percentage = match self.count {
+            0 => 0,
+            count => self.complete * 100 / count,
+        };
+
+        HashMap::from([
+            ("count".to_string(), Arg::Integer(self.count as i64)),
+            ("complete".to_string(), Arg::Integer(self.complete as i64)),
+            ("percentage".to_string(), Arg::Integer(percentage as i64)),
+        ])
     }
 }
 
-#[cfg(test)]
-mod app_tests {
-    use crux_core::{assert_effect, testing::AppTester};
-
-    use super::*;
-
-    #[test]
-    fn minimal() {
-        let hello = AppTester::<Planner>::default();
-        let mut model = Model::default();
+/// Adds the completion of the used placements to the placements node, and child nodes for the completion of each
+/// phase, the placements without a phase, if any, and the number of placements with each status.
+fn add_placements_tree_nodes(tree: &mut Graph<ProjectTreeItem, ()>, placements_node: NodeIndex, project: &Project) {
+    let placements_path = tree[placements_node].path.clone();
+
+    let mut total_counts = PlacementCounts::default();
+    let mut phase_counts: BTreeMap<&PhaseReference, PlacementCounts> = BTreeMap::new();
+    let mut unassigned_counts = PlacementCounts::default();
+    let mut status_counts: BTreeMap<&PlacementStatus, usize> = BTreeMap::new();
+
+    for placement_state in project
+        .placements
+        .values()
+        .filter(|state| state.placement.place && state.project_status == ProjectPlacementStatus::Used)
+    {
+        total_counts.add(placement_state);
+        match &placement_state.phase {
+            Some(phase) => phase_counts
+                .entry(phase)
+                .or_default()
+                .add(placement_state),
+            None => unassigned_counts.add(placement_state),
+        }
+        *status_counts
+            .entry(&placement_state.operation_status)
+            .or_default() += 1;
+    }
 
-        // Call 'update' and request effects
-        let update = hello.update(Event::None, &mut model);
+    tree[placements_node]
+        .args
+        .extend(total_counts.args());
+
+    let mut add_node = |key: &str, args: HashMap<String, Arg>, path: TreePath| {
+        let node = tree.add_node(ProjectTreeItem {
+            key: key.to_string(),
+            args,
+            path,
+        });
+        tree.add_edge(placements_node, node, ());
+    };
+
+    for reference in project.phase_orderings.iter() {
+        let mut args = phase_counts
+            .remove(reference)
+            .unwrap_or_default()
+            .args();
+        args.insert("phase".to_string(), Arg::String(reference.to_string()));
+
+        add_node(
+            "placements-phase",
+            args,
+            placements_path
+                .join("phases")
+                .join(reference.to_string()),
+        );
+    }
 
-        // Check update asked us to `Render`
-        assert_effect!(update, Effect::Render(_));
+    if unassigned_counts.count > 0 {
+        add_node(
+            "placements-unassigned",
+            unassigned_counts.args(),
+            placements_path.join("unassigned"),
+        );
+    }
 
-        // Make sure the view matches our expectations
-        let actual_view = &hello.view(&model);
-        let expected_view = PlannerOperationViewModel::default();
-        assert_eq!(actual_view, &expected_view);
+    for (status, count) in status_counts {
+        add_node(
+            "placements-status",
+            HashMap::from([
+                ("status".to_string(), Arg::String(status.to_string())),
+                ("count".to_string(), Arg::Integer(count as i64)),
+            ]),
+            placements_path
+                .join("statuses")
+                .join(status.to_string()),
+        );
     }
 }
 
+/// Returns the directory containing the file, or an empty path (the current directory) if the path has no parent, e.g.
+/// for paths in a virtual filesystem provided by a shell.
+fn parent_directory(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new(""))
+}
+
 /// Build a load-out source, where the load-out source *may* be a relative or absolute path.
-fn try_build_phase_load_out_source(project_path: &PathBuf, phase: &Phase) -> Result<LoadOutSource, SourceError> {
+fn try_build_phase_load_out_source(
+    project_path: &PathBuf,
+    phase: &Phase,
+    store_backend: &StoreBackend,
+) -> Result<LoadOutSource, SourceError> {
+    // there is no filesystem to check on wasm32, the shell does all the file IO.
+    #[cfg(not(target_arch = "wasm32"))]
     assert!(project_path.is_dir());
 
-    let directory = project_path
-        .parent()
-        .unwrap()
-        .to_path_buf();
+    // remote load-outs are shared, regardless of the store backend
+    if LoadOutSource::is_url(&phase.load_out_source) {
+        return Ok(LoadOutSource::Url(phase.load_out_source.clone()));
+    }
+
+    let directory = parent_directory(project_path).to_path_buf();
 
-    LoadOutSource::try_from_path(&directory, PathBuf::from(&phase.load_out_source))
+    match store_backend {
+        StoreBackend::Csv => LoadOutSource::try_from_path(&directory, PathBuf::from(&phase.load_out_source)),
+        // the load-out is identified by its path, but there is no file
+        StoreBackend::Sqlite {
+            ..
+        } => Ok(LoadOutSource::File(project_path.join(&phase.load_out_source))),
+    }
 }
 
 fn try_build_phase_overview(
@@ -2826,8 +8948,25 @@ fn try_build_phase_overview(
     phase: &Phase,
     can_start: bool,
     state: &PhaseState,
+    process_definition: Option<&ProcessDefinition>,
+    store_backend: &StoreBackend,
 ) -> Result<PhaseOverview, SourceError> {
-    let load_out_source = try_build_phase_load_out_source(directory, phase)?;
+    let load_out_source = try_build_phase_load_out_source(directory, phase, store_backend)?;
+
+    let process_rules = process_definition
+        .map(|process_definition| process_definition.rules.clone())
+        .unwrap_or_default();
+
+    let custom_tasks = process_definition
+        .map(|process_definition| {
+            process_definition
+                .operations
+                .iter()
+                .filter(|operation| !operation.custom_tasks.is_empty())
+                .map(|operation| (operation.reference.clone(), operation.custom_tasks.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
 
     Ok(PhaseOverview {
         phase_reference,
@@ -2837,5 +8976,7 @@ fn try_build_phase_overview(
         phase_placement_orderings: phase.placement_orderings.clone(),
         can_start,
         state: state.clone(),
+        process_rules,
+        custom_tasks,
     })
 }
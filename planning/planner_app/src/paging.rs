@@ -0,0 +1,172 @@
+//! Paging of the placement views, so that shells can fetch only the rows that are visible, e.g. for projects with
+//! tens of thousands of placements, see [`Event::RequestPlacementsView`] and [`Event::RequestPhasePlacementsView`].
+//!
+//! [`Event::RequestPlacementsView`]: crate::Event::RequestPlacementsView
+//! [`Event::RequestPhasePlacementsView`]: crate::Event::RequestPhasePlacementsView
+
+use std::cmp::Ordering;
+
+use util::sorting::SortOrder;
+
+use crate::PlacementsItem;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct PlacementsPageRequest {
+    pub offset: usize,
+    /// The maximum amount of placements in the page.
+    pub limit: usize,
+    /// `None` to keep the order of the view, see [`PlacementsItem::ordering`].
+    #[serde(default)]
+    pub sort: Option<PlacementsSort>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct PlacementsSort {
+    pub key: PlacementsSortKey,
+    pub order: SortOrder,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementsSortKey {
+    Ordering,
+    ObjectPath,
+    RefDes,
+    Manufacturer,
+    Mpn,
+    Phase,
+    OperationStatus,
+}
+
+impl PlacementsSortKey {
+    fn compare(&self, a: &PlacementsItem, b: &PlacementsItem) -> Ordering {
+        match self {
+            PlacementsSortKey::Ordering => a.ordering.cmp(&b.ordering),
+            PlacementsSortKey::ObjectPath => a.path.cmp(&b.path),
+            PlacementsSortKey::RefDes => a
+                .state
+                .placement
+                .ref_des
+                .cmp(&b.state.placement.ref_des),
+            PlacementsSortKey::Manufacturer => a
+                .state
+                .placement
+                .part
+                .manufacturer
+                .cmp(&b.state.placement.part.manufacturer),
+            PlacementsSortKey::Mpn => a
+                .state
+                .placement
+                .part
+                .mpn
+                .cmp(&b.state.placement.part.mpn),
+            PlacementsSortKey::Phase => a.state.phase.cmp(&b.state.phase),
+            PlacementsSortKey::OperationStatus => a
+                .state
+                .operation_status
+                .cmp(&b.state.operation_status),
+        }
+    }
+}
+
+/// A page of placements, and the total amount of placements in the view, so that shells can size their tables.
+pub struct PlacementsPage {
+    pub placements: Vec<PlacementsItem>,
+    pub offset: usize,
+    pub total: usize,
+}
+
+/// Without a request all the placements are returned.
+///
+/// Placements that compare equal keep the order of the view, so that pages do not overlap.
+pub fn page_placements(mut placements: Vec<PlacementsItem>, request: Option<&PlacementsPageRequest>) -> PlacementsPage {
+    let total = placements.len();
+
+    let Some(request) = request else {
+        return PlacementsPage {
+            placements,
+            offset: 0,
+            total,
+        };
+    };
+
+    if let Some(sort) = &request.sort {
+        placements.sort_by(|a, b| {
+            let ordering = sort
+                .key
+                .compare(a, b)
+                .then_with(|| a.ordering.cmp(&b.ordering));
+            match sort.order {
+                SortOrder::Asc => ordering,
+                SortOrder::Desc => ordering.reverse(),
+            }
+        });
+    }
+
+    let placements = placements
+        .into_iter()
+        .skip(request.offset)
+        .take(request.limit)
+        .collect();
+
+    PlacementsPage {
+        placements,
+        offset: request.offset,
+        total,
+    }
+}
+
+#[cfg(test)]
+mod paging_tests {
+    use planning::placement::PlacementState;
+    use pnp::object_path::ObjectPath;
+    use pnp::placement::{Placement, RefDes};
+
+    use super::*;
+
+    fn placements_item(ordering: usize, ref_des: &str) -> PlacementsItem {
+        PlacementsItem {
+            path: ObjectPath::from_raw_str(&format!("pcb=1::unit=1::ref_des={}", ref_des)),
+            state: PlacementState {
+                placement: Placement {
+                    ref_des: RefDes::from(ref_des),
+                    ..Placement::default()
+                },
+                ..PlacementState::default()
+            },
+            ordering,
+            nozzle: None,
+        }
+    }
+
+    #[test]
+    pub fn sorted_page() {
+        // given
+        let placements = vec![
+            placements_item(0, "R2"),
+            placements_item(1, "C1"),
+            placements_item(2, "R1"),
+            placements_item(3, "C2"),
+        ];
+        let request = PlacementsPageRequest {
+            offset: 1,
+            limit: 2,
+            sort: Some(PlacementsSort {
+                key: PlacementsSortKey::RefDes,
+                order: SortOrder::Desc,
+            }),
+        };
+
+        // when
+        let page = page_placements(placements, Some(&request));
+
+        // then
+        let ref_des_list = page
+            .placements
+            .iter()
+            .map(|item| item.state.placement.ref_des.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ref_des_list, vec!["R1", "C2"]);
+        assert_eq!(page.offset, 1);
+        assert_eq!(page.total, 4);
+    }
+}
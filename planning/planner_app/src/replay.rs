@@ -0,0 +1,261 @@
+//! Event recording and replay, for debugging.
+//!
+//! When recording, see [`Event::SetEventRecording`], every event processed by the core is appended to a replay file,
+//! one JSON object per line, along with the modified flags of the view model after the event was processed.  The
+//! replay file is written by the shell, see [`FileIoOperation::Append`].
+//!
+//! Events that carry the results of effects, e.g. [`Event::ProjectFileRead`], are recorded too, so a replay does not
+//! require the files that the shell read when recording.  Files that the core still reads itself, e.g. load-outs and
+//! package libraries, must be present when replaying.
+//!
+//! A replay can be attached to a bug report instead of the steps to reproduce, see [`replay`].
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+use tracing::info;
+
+use crate::effects::file_io::FileIoOperation;
+use crate::Event;
+#[cfg(any(test, feature = "testing"))]
+use crate::{Model, Planner, PlannerOperationViewModel};
+
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error("IO error. path: {path:?}, cause: {cause}")]
+    IoError { path: PathBuf, cause: std::io::Error },
+    #[error("Invalid entry. path: {path:?}, line: {line}, cause: {cause}")]
+    InvalidEntry {
+        path: PathBuf,
+        line: usize,
+        cause: serde_json::Error,
+    },
+    #[error(
+        "Replay diverged from the recording. index: {index}, expected: {expected:?}, actual: {actual:?}, event: {event}"
+    )]
+    Diverged {
+        index: usize,
+        expected: ModifiedFlags,
+        actual: ModifiedFlags,
+        event: String,
+    },
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModifiedFlags {
+    pub project_modified: bool,
+    pub pcbs_modified: bool,
+}
+
+/// `E` is [`Event`] when reading, events are serialized before they are processed, since they are consumed by the core.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ReplayEntry<E = Event> {
+    pub date_time: chrono::DateTime<chrono::Utc>,
+    pub event: E,
+    /// After the event was processed.
+    #[serde(flatten)]
+    pub modified: ModifiedFlags,
+}
+
+#[derive(Debug)]
+pub struct EventRecorder {
+    path: PathBuf,
+}
+
+impl EventRecorder {
+    /// Returns the recorder, and the operation that creates the replay file, or truncates it if it already exists.
+    pub fn create(path: PathBuf) -> (Self, FileIoOperation) {
+        info!("Recording events. path: {:?}", path);
+
+        let operation = FileIoOperation::Write {
+            path: path.clone(),
+            content: vec![],
+        };
+
+        (
+            Self {
+                path,
+            },
+            operation,
+        )
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the operation that appends the entry to the replay file, each entry is appended separately, so that
+    /// the entries recorded before a crash are kept.
+    pub fn record(&self, entry: &ReplayEntry<serde_json::Value>) -> Result<FileIoOperation, serde_json::Error> {
+        let mut content = serde_json::to_vec(entry)?;
+        content.push(b'\n');
+
+        Ok(FileIoOperation::Append {
+            path: self.path.clone(),
+            content,
+        })
+    }
+}
+
+/// Not available on wasm32, where there is no filesystem.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_replay(path: &Path) -> Result<Vec<ReplayEntry>, ReplayError> {
+    let file = File::open(path).map_err(|cause| ReplayError::IoError {
+        path: path.to_path_buf(),
+        cause,
+    })?;
+
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_index, line)| {
+            !line
+                .as_ref()
+                .is_ok_and(|line| line.trim().is_empty())
+        })
+        .map(|(index, line)| {
+            let line = line.map_err(|cause| ReplayError::IoError {
+                path: path.to_path_buf(),
+                cause,
+            })?;
+
+            serde_json::from_str(&line).map_err(|cause| ReplayError::InvalidEntry {
+                path: path.to_path_buf(),
+                line: index + 1,
+                cause,
+            })
+        })
+        .collect()
+}
+
+/// Reconstructs a model by processing the recorded events, effects are not performed.
+///
+/// Returns an error if the modified flags differ from the recording, e.g. when the replay does not reproduce the
+/// recorded behavior.
+#[cfg(any(test, feature = "testing"))]
+pub fn replay(entries: Vec<ReplayEntry>) -> Result<Model, ReplayError> {
+    use crux_core::App;
+
+    let app = Planner;
+    let mut model = Model::default();
+
+    for (index, entry) in entries.into_iter().enumerate() {
+        let event = format!("{:?}", entry.event);
+
+        let _command = app.update(entry.event, &mut model, &());
+
+        let PlannerOperationViewModel {
+            project_modified,
+            pcbs_modified,
+            ..
+        } = app.view(&model);
+        let actual = ModifiedFlags {
+            project_modified,
+            pcbs_modified,
+        };
+
+        if actual != entry.modified {
+            return Err(ReplayError::Diverged {
+                index,
+                expected: entry.modified,
+                actual,
+                event,
+            });
+        }
+    }
+
+    Ok(model)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod replay_tests {
+    use crux_core::testing::AppTester;
+    use planning::file;
+    use planning::project::Project;
+
+    use super::*;
+    use crate::effects::file_io::{perform, FileIoResult};
+    use crate::Effect;
+
+    #[test]
+    pub fn record_and_replay() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let replay_path = temp_dir.path().join("replay.jsonl");
+        let project_path = PathBuf::from("projects/job1/project-job1.mpnp.json");
+
+        let app = AppTester::<Planner>::default();
+        let mut model = Model::default();
+
+        // the writes to the replay file are serviced, like a shell would
+        let perform_file_io = |update: crux_core::testing::Update<Effect, Event>| {
+            for effect in update.effects() {
+                if let Effect::FileIo(request) = effect {
+                    if let FileIoOperation::Write {
+                        path, ..
+                    }
+                    | FileIoOperation::Append {
+                        path, ..
+                    } = &request.operation
+                    {
+                        assert_eq!(path, &replay_path);
+                        perform(&request.operation);
+                    }
+                }
+            }
+        };
+
+        // when
+        let update = app.update(
+            Event::SetEventRecording {
+                path: Some(replay_path.clone()),
+            },
+            &mut model,
+        );
+        perform_file_io(update);
+        let update = app.update(
+            Event::Load {
+                path: project_path.clone(),
+            },
+            &mut model,
+        );
+        perform_file_io(update);
+        let update = app.update(
+            Event::ProjectFileRead {
+                path: project_path,
+                result: FileIoResult::Read {
+                    content: file::to_bytes(&Project::new("job1".to_string(), None, None)).unwrap(),
+                },
+            },
+            &mut model,
+        );
+        perform_file_io(update);
+        let update = app.update(
+            Event::SetEventRecording {
+                path: None,
+            },
+            &mut model,
+        );
+        perform_file_io(update);
+
+        // then
+        let entries = read_replay(&replay_path).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        // when
+        let replayed_model = replay(entries).unwrap();
+
+        // then
+        assert_eq!(
+            replayed_model
+                .model_project
+                .as_ref()
+                .map(|model_project| model_project.project.name.as_str()),
+            Some("job1")
+        );
+    }
+}
@@ -0,0 +1,297 @@
+//! Fuzzy search across a project, for 'quick-open' style navigation in shells, see [`ProjectViewRequest::Search`].
+//!
+//! Each result has the path of the project tree item that shows it, see [`crate::ProjectTreeItem`], so that shells can
+//! navigate to a result the same way as when the tree item is selected.
+//!
+//! [`ProjectViewRequest::Search`]: crate::ProjectViewRequest::Search
+
+use planning::phase::PhaseReference;
+use planning::placement::ProjectPlacementStatus;
+use planning::process::ProcessReference;
+use planning::project::Project;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use pnp::placement::RefDes;
+
+use crate::TreePath;
+
+/// The maximum amount of results, the best matches are kept.
+pub const MAX_RESULTS: usize = 50;
+
+const EXACT_SCORE: u32 = 1000;
+const PREFIX_SCORE: u32 = 750;
+const SUBSTRING_SCORE: u32 = 500;
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct SearchResults {
+    pub query: String,
+    /// Ordered by score, best match first.
+    pub results: Vec<SearchResult>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub struct SearchResult {
+    pub item: SearchItem,
+    /// Higher is better.
+    pub score: u32,
+    pub path: TreePath,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug, Clone)]
+pub enum SearchItem {
+    /// Matched by refdes or object path.
+    Placement {
+        object_path: ObjectPath,
+        ref_des: RefDes,
+        part: Part,
+    },
+    /// Matched by manufacturer or MPN.
+    Part {
+        part: Part,
+    },
+    Phase {
+        phase: PhaseReference,
+    },
+    Process {
+        process: ProcessReference,
+    },
+}
+
+/// An empty query has no results.
+pub fn search(project: &Project, query: &str) -> SearchResults {
+    let query = query.trim();
+
+    let mut results = vec![];
+    if !query.is_empty() {
+        let mut add_result = |candidates: &[&str], item: SearchItem, path: TreePath| {
+            let score = candidates
+                .iter()
+                .filter_map(|candidate| fuzzy_score(query, candidate))
+                .max();
+
+            if let Some(score) = score {
+                results.push(SearchResult {
+                    item,
+                    score,
+                    path,
+                });
+            }
+        };
+
+        for (object_path, state) in project
+            .placements
+            .iter()
+            .filter(|(_, state)| state.project_status == ProjectPlacementStatus::Used)
+        {
+            // the tree only has an item for the unassigned placements that are to be placed
+            let path = match (&state.phase, state.placement.place) {
+                (Some(phase), _) => TreePath::root()
+                    .join("placements")
+                    .join("phases")
+                    .join(phase.to_string()),
+                (None, true) => TreePath::root()
+                    .join("placements")
+                    .join("unassigned"),
+                (None, false) => TreePath::root().join("placements"),
+            };
+
+            add_result(
+                &[&state.placement.ref_des.to_string(), &object_path.to_string()],
+                SearchItem::Placement {
+                    object_path: object_path.clone(),
+                    ref_des: state.placement.ref_des.clone(),
+                    part: state.placement.part.clone(),
+                },
+                path,
+            );
+        }
+
+        for (part, part_state) in project.part_states.iter() {
+            // the first process, like the order of the tree
+            let process = project
+                .processes
+                .iter()
+                .find(|process| {
+                    part_state
+                        .applicable_processes
+                        .contains(&process.reference)
+                });
+            let path = match process {
+                Some(process) => TreePath::root()
+                    .join("parts")
+                    .join("processes")
+                    .join(process.reference.to_string()),
+                None => TreePath::root()
+                    .join("parts")
+                    .join("unassigned"),
+            };
+
+            add_result(
+                &[&part.manufacturer, &part.mpn],
+                SearchItem::Part {
+                    part: part.clone(),
+                },
+                path,
+            );
+        }
+
+        for phase in project.phase_orderings.iter() {
+            add_result(
+                &[&phase.to_string()],
+                SearchItem::Phase {
+                    phase: phase.clone(),
+                },
+                TreePath::root()
+                    .join("phases")
+                    .join(phase.to_string()),
+            );
+        }
+
+        for process in project.processes.iter() {
+            add_result(
+                &[&process.reference.to_string()],
+                SearchItem::Process {
+                    process: process.reference.clone(),
+                },
+                TreePath::root()
+                    .join("processes")
+                    .join(process.reference.to_string()),
+            );
+        }
+    }
+
+    // stable, so equal scores keep the order above
+    results.sort_by(|a, b| b.score.cmp(&a.score));
+    results.truncate(MAX_RESULTS);
+
+    SearchResults {
+        query: query.to_string(),
+        results,
+    }
+}
+
+/// Case-insensitive, returns `None` if the candidate does not contain all the characters of the query, in order.
+///
+/// Exact, prefix and substring matches score higher than matches where the characters are spread out.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let query = query.to_lowercase();
+    let candidate = candidate.to_lowercase();
+
+    if candidate.eq(&query) {
+        return Some(EXACT_SCORE);
+    }
+    if candidate.starts_with(&query) {
+        return Some(PREFIX_SCORE);
+    }
+    if candidate.contains(&query) {
+        return Some(SUBSTRING_SCORE);
+    }
+
+    let mut score = 0;
+    let mut previous_matched = false;
+    let mut candidate_chars = candidate.chars();
+    for query_char in query.chars() {
+        loop {
+            match candidate_chars.next() {
+                Some(candidate_char) if candidate_char == query_char => {
+                    // consecutive characters score higher
+                    score += if previous_matched { 2 } else { 1 };
+                    previous_matched = true;
+                    break;
+                }
+                Some(_) => previous_matched = false,
+                None => return None,
+            }
+        }
+    }
+
+    Some(score.min(SUBSTRING_SCORE - 1))
+}
+
+#[cfg(test)]
+mod search_tests {
+    use std::str::FromStr;
+
+    use planning::placement::PlacementState;
+    use pnp::placement::Placement;
+
+    use super::*;
+
+    #[test]
+    pub fn fuzzy_scores() {
+        // expect
+        assert_eq!(fuzzy_score("r1", "R1"), Some(EXACT_SCORE));
+        assert_eq!(fuzzy_score("r1", "R10"), Some(PREFIX_SCORE));
+        assert_eq!(fuzzy_score("1000", "RES_10000"), Some(SUBSTRING_SCORE));
+        assert_eq!(fuzzy_score("rs10", "RES_10000"), Some(5));
+        assert_eq!(fuzzy_score("r2", "R10"), None);
+    }
+
+    #[test]
+    pub fn placements_are_found_by_refdes() {
+        // given
+        let mut project = Project::default();
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap();
+        project
+            .placements
+            .insert(object_path.clone(), PlacementState {
+                placement: Placement {
+                    ref_des: RefDes::from("R1"),
+                    ..Placement::default()
+                },
+                phase: Some(PhaseReference::from_raw_str("top_1")),
+                ..PlacementState::default()
+            });
+
+        // when
+        let results = search(&project, " r1 ");
+
+        // then
+        assert_eq!(results.query, "r1");
+        assert_eq!(results.results.len(), 1);
+        let result = &results.results[0];
+        assert_eq!(result.score, EXACT_SCORE);
+        assert_eq!(result.path.to_string(), "/placements/phases/top_1");
+        assert!(matches!(
+            &result.item,
+            SearchItem::Placement { object_path: result_object_path, .. } if result_object_path.eq(&object_path)
+        ));
+    }
+
+    #[test]
+    pub fn unassigned_placements_that_are_not_placed_are_shown_by_the_placements_item() {
+        // given
+        let mut project = Project::default();
+        for (ref_des, place) in [("R1", true), ("R2", false)] {
+            project.placements.insert(
+                ObjectPath::from_str(&format!("pcb=1::unit=1::ref_des={}", ref_des)).unwrap(),
+                PlacementState {
+                    placement: Placement {
+                        ref_des: RefDes::from(ref_des),
+                        place,
+                        ..Placement::default()
+                    },
+                    ..PlacementState::default()
+                },
+            );
+        }
+
+        // when
+        let placed_results = search(&project, "R1");
+        let unplaced_results = search(&project, "R2");
+
+        // then
+        assert_eq!(
+            placed_results.results[0]
+                .path
+                .to_string(),
+            "/placements/unassigned"
+        );
+        assert_eq!(
+            unplaced_results.results[0]
+                .path
+                .to_string(),
+            "/placements"
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Placement selections, so that bulk operations on many placements do not have to re-send, and re-match, the same
+//! patterns for each event, see [`Event::SelectPlacements`].
+//!
+//! The model allocates the id of a selection, the id is in the view model after the selection is made, see
+//! [`PlannerOperationViewModel::placement_selection`].  The selected placements are resolved when the selection is
+//! made and are not updated when the placements change.  Selections are discarded when the project is replaced.
+//!
+//! [`Event::SelectPlacements`]: crate::Event::SelectPlacements
+//! [`PlannerOperationViewModel::placement_selection`]: crate::PlannerOperationViewModel::placement_selection
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use planning::project::Project;
+use pnp::object_path::{ObjectPath, ObjectPathPattern};
+use regex::Regex;
+
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash
+)]
+pub struct SelectionId(u64);
+
+/// Allocates the ids of the selections of a model.
+///
+/// Ids are not reused, not even when the project is replaced, so that a stale id is never resolved to another
+/// selection.
+#[derive(Debug, Default)]
+pub(crate) struct SelectionIds(u64);
+
+impl SelectionIds {
+    pub fn next(&mut self) -> SelectionId {
+        self.0 += 1;
+        SelectionId(self.0)
+    }
+}
+
+impl Display for SelectionId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The placements an event applies to.
+///
+/// Events that were serialized before selections were added have a pattern instead, and events that were serialized
+/// before patterns were added have a regular expression, both are deserialized as [`PlacementsSelector::Pattern`].
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub enum PlacementsSelector {
+    Pattern(ObjectPathPattern),
+    /// A selection made with [`crate::Event::SelectPlacements`].
+    Selection(SelectionId),
+}
+
+impl<'de> serde::Deserialize<'de> for PlacementsSelector {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        enum Selector {
+            Pattern(ObjectPathPattern),
+            Selection(SelectionId),
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Form {
+            Selector(Selector),
+            Pattern(ObjectPathPattern),
+            Regex(#[serde(with = "serde_regex")] Regex),
+        }
+
+        Ok(match Form::deserialize(deserializer)? {
+            Form::Selector(Selector::Pattern(pattern)) | Form::Pattern(pattern) => Self::Pattern(pattern),
+            Form::Selector(Selector::Selection(selection)) => Self::Selection(selection),
+            Form::Regex(regex) => Self::Pattern(ObjectPathPattern::Regex(regex)),
+        })
+    }
+}
+
+impl From<ObjectPathPattern> for PlacementsSelector {
+    fn from(value: ObjectPathPattern) -> Self {
+        Self::Pattern(value)
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct PlacementSelections(BTreeMap<SelectionId, BTreeSet<ObjectPath>>);
+
+impl PlacementSelections {
+    /// Replaces any existing selection with the same id, returns the amount of selected placements.
+    pub fn select(&mut self, project: &Project, selection: SelectionId, filter: &ObjectPathPattern) -> usize {
+        let object_paths = project
+            .placements
+            .keys()
+            .filter(|object_path| filter.is_match(object_path))
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        let count = object_paths.len();
+        self.0.insert(selection, object_paths);
+        count
+    }
+
+    /// Returns `true` if the selection existed.
+    pub fn release(&mut self, selection: &SelectionId) -> bool {
+        self.0.remove(selection).is_some()
+    }
+
+    /// A selection is resolved to a pattern that only matches the selected placements, returns the id of the
+    /// selection if it is unknown.
+    pub fn resolve(&self, selector: PlacementsSelector) -> Result<ObjectPathPattern, SelectionId> {
+        match selector {
+            PlacementsSelector::Pattern(pattern) => Ok(pattern),
+            PlacementsSelector::Selection(selection) => self
+                .0
+                .get(&selection)
+                .map(|object_paths| ObjectPathPattern::Paths(object_paths.clone()))
+                .ok_or(selection),
+        }
+    }
+}
+
+#[cfg(test)]
+mod selection_tests {
+    use planning::placement::PlacementState;
+    use regex::Regex;
+
+    use super::*;
+
+    #[test]
+    pub fn selections_are_resolved_to_the_selected_placements() {
+        // given
+        let mut project = Project::default();
+        for object_path in [
+            "pcb=1::unit=1::ref_des=R1",
+            "pcb=1::unit=1::ref_des=C1",
+            "pcb=1::unit=2::ref_des=R1",
+        ] {
+            project
+                .placements
+                .insert(ObjectPath::from_raw_str(object_path), PlacementState::default());
+        }
+        let mut selections = PlacementSelections::default();
+        let selection = SelectionIds::default().next();
+
+        // when
+        let count = selections.select(
+            &project,
+            selection,
+            &Regex::new("ref_des=R1$")
+                .unwrap()
+                .into(),
+        );
+
+        // then
+        assert_eq!(count, 2);
+        let pattern = selections
+            .resolve(PlacementsSelector::Selection(selection))
+            .unwrap();
+        assert!(pattern.is_match(&ObjectPath::from_raw_str("pcb=1::unit=2::ref_des=R1")));
+        assert!(!pattern.is_match(&ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=C1")));
+
+        // and
+        assert!(selections.release(&selection));
+        assert_eq!(
+            selections.resolve(PlacementsSelector::Selection(selection)),
+            Err(selection)
+        );
+    }
+
+    #[test]
+    pub fn selectors_serialized_before_selections_are_deserialized_as_patterns() {
+        // given
+        let object_path = ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R1");
+
+        for json in [
+            r#"{"Pattern":{"Regex":"ref_des=R1$"}}"#,
+            r#"{"Regex":"ref_des=R1$"}"#,
+            r#""ref_des=R1$""#,
+        ] {
+            // when
+            let selector: PlacementsSelector = serde_json::from_str(json).unwrap();
+
+            // then
+            let PlacementsSelector::Pattern(pattern) = selector else {
+                panic!("expected a pattern. json: {}", json);
+            };
+            assert!(pattern.is_match(&object_path));
+        }
+
+        // and
+        let selector: PlacementsSelector = serde_json::from_str(r#"{"Selection":3}"#).unwrap();
+        assert!(matches!(selector, PlacementsSelector::Selection(SelectionId(3))));
+    }
+}
@@ -0,0 +1,168 @@
+//! Paths of the items in the project tree, see [`crate::ProjectTreeItem`].
+//!
+//! Segments can contain any character, e.g. a phase reference of `top/1`.  The canonical string form, used for
+//! serialization and by shells for matching paths, is `/` separated and escapes `/` and `%` in the segments, e.g.
+//! `/phases/top%2F1/loadout`, so that a segment never contains a `/`.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+
+const SEPARATOR: char = '/';
+
+/// An un-escaped path segment.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TreePathSegment(String);
+
+impl TreePathSegment {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn escape(value: &str) -> String {
+        value
+            .replace('%', "%25")
+            .replace(SEPARATOR, "%2F")
+    }
+
+    /// Unknown escape sequences are kept as-is.
+    pub fn unescape(value: &str) -> String {
+        let mut unescaped = String::with_capacity(value.len());
+        let mut remaining = value;
+        while let Some(index) = remaining.find('%') {
+            unescaped.push_str(&remaining[..index]);
+            let escape_sequence = remaining
+                .get(index..index + 3)
+                .map(str::to_ascii_uppercase);
+            let (character, length) = match escape_sequence.as_deref() {
+                Some("%25") => ('%', 3),
+                Some("%2F") => (SEPARATOR, 3),
+                _ => ('%', 1),
+            };
+            unescaped.push(character);
+            remaining = &remaining[index + length..];
+        }
+        unescaped.push_str(remaining);
+        unescaped
+    }
+}
+
+impl From<&str> for TreePathSegment {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<String> for TreePathSegment {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl Display for TreePathSegment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Self::escape(&self.0))
+    }
+}
+
+/// The root path has no segments.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr)]
+pub struct TreePath(Vec<TreePathSegment>);
+
+impl TreePath {
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    pub fn join(&self, segment: impl Into<TreePathSegment>) -> Self {
+        let mut segments = self.0.clone();
+        segments.push(segment.into());
+        Self(segments)
+    }
+
+    pub fn segments(&self) -> &[TreePathSegment] {
+        &self.0
+    }
+}
+
+impl Display for TreePath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "{}", SEPARATOR);
+        }
+        for segment in &self.0 {
+            write!(f, "{}{}", SEPARATOR, segment)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TreePathError {
+    #[error("Tree paths must start with a '/'. path: '{0}'")]
+    NotAbsolute(String),
+}
+
+impl FromStr for TreePath {
+    type Err = TreePathError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let remainder = value
+            .strip_prefix(SEPARATOR)
+            .ok_or_else(|| TreePathError::NotAbsolute(value.to_string()))?;
+
+        if remainder.is_empty() {
+            return Ok(Self::root());
+        }
+
+        let segments = remainder
+            .split(SEPARATOR)
+            .map(|segment| TreePathSegment(TreePathSegment::unescape(segment)))
+            .collect();
+
+        Ok(Self(segments))
+    }
+}
+
+#[cfg(test)]
+mod tree_path_tests {
+    use super::*;
+
+    #[test]
+    pub fn segments_with_separators_are_escaped() {
+        // given
+        let path = TreePath::root()
+            .join("phases")
+            .join("top/1%")
+            .join("loadout");
+
+        // when
+        let canonical = path.to_string();
+
+        // then
+        assert_eq!(canonical, "/phases/top%2F1%25/loadout");
+
+        // and
+        assert_eq!(TreePath::from_str(&canonical), Ok(path));
+    }
+
+    #[test]
+    pub fn root() {
+        // expect
+        assert_eq!(TreePath::root().to_string(), "/");
+        assert_eq!(TreePath::from_str("/"), Ok(TreePath::root()));
+        assert_eq!(
+            TreePath::from_str("phases"),
+            Err(TreePathError::NotAbsolute("phases".to_string()))
+        );
+    }
+
+    #[test]
+    pub fn unknown_escape_sequences_are_kept() {
+        // expect
+        assert_eq!(TreePathSegment::unescape("100%"), "100%");
+        assert_eq!(TreePathSegment::unescape("a%2fb%41"), "a/b%41");
+    }
+}
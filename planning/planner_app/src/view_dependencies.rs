@@ -0,0 +1,639 @@
+//! Tracks which project views depend on which parts of the model, so that shells can refresh only the views that are
+//! affected by an event, see [`effects::view_invalidation`](crate::effects::view_invalidation).
+//!
+//! The changes are determined from the kind of event, not by comparing the model, so a view may be invalidated even
+//! when the event did not actually change anything it depends on.
+
+use planning::project::Project;
+
+use crate::{Event, ProjectViewRequest};
+
+/// A part of the model that an event can change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelChange {
+    /// The project was created, loaded or replaced, all views are affected.
+    Project,
+    Pcbs,
+    Processes,
+    Parts,
+    Phases,
+    /// Placements, their phase assignment, ordering and status.
+    Placements,
+    LoadOuts,
+    /// The state of the phase operations and tasks.
+    Operations,
+    RotationOffsets,
+    PlacementSession,
+    /// Units, coordinate and rotation conventions.
+    Settings,
+}
+
+/// Events that do not change the model, e.g. view requests, saving, and the results of writes, return no changes.
+///
+/// The match is exhaustive, so that new events have to be added here.
+pub fn event_changes(event: &Event) -> &'static [ModelChange] {
+    use ModelChange::*;
+
+    match event {
+        Event::CreateProject {
+            ..
+        }
+        | Event::CreateProjectFromTemplate {
+            ..
+        }
+        | Event::ProjectFileRead {
+            ..
+        }
+        | Event::RestoreRecovery
+        | Event::RelinkFiles {
+            ..
+        } => &[Project],
+
+        Event::AddPcb {
+            ..
+        }
+        | Event::RemovePcb {
+            ..
+        }
+        | Event::CreateProjectPcb {
+            ..
+        }
+        | Event::PcbFileRead {
+            ..
+        }
+        | Event::AssignVariantToUnit {
+            ..
+        }
+        | Event::RefreshFromDesignVariants {
+            ..
+        } => &[Pcbs, Placements, Parts],
+
+        Event::CreatePcb {
+            ..
+        }
+        | Event::PcbCloneDirectoryListed {
+            ..
+        }
+        | Event::ApplyPcbUnitConfiguration {
+            ..
+        }
+        | Event::ApplyPanelSizing {
+            ..
+        }
+        | Event::ApplyPartialPanelSizing {
+            ..
+        }
+        | Event::ApplyAssemblyOrientation {
+            ..
+        }
+        | Event::AddGerberFiles {
+            ..
+        }
+        | Event::RemoveGerberFiles {
+            ..
+        }
+        | Event::RefreshGerberFiles {
+            ..
+        }
+        | Event::ApplyGerberFileFunctions {
+            ..
+        }
+        | Event::PcbOutlineProfileWritten {
+            ..
+        }
+        | Event::ImportPcbUnitPositionings {
+            ..
+        } => &[Pcbs],
+
+        Event::CreateProcessFromPreset {
+            ..
+        }
+        | Event::ApplyProcessDefinition {
+            ..
+        }
+        | Event::DeleteProcess {
+            ..
+        }
+        | Event::CloneProcess {
+            ..
+        } => &[Processes],
+
+        // the placements can be sorted by their packages
+        Event::ApplyPackageSources {
+            ..
+        } => &[Parts, Placements],
+        Event::ImportPartAliases {
+            ..
+        }
+        | Event::ImportMoistureSensitivities {
+            ..
+        }
+        | Event::AssignProcessToParts {
+            ..
+        }
+        | Event::SetPartMoistureSensitivity {
+            ..
+        }
+        | Event::RecordPartMoistureEvent {
+            ..
+        } => &[Parts],
+
+        Event::CreatePhase {
+            ..
+        }
+        | Event::SetPhaseOrdering {
+            ..
+        }
+        | Event::SetPhaseAssemblyWindow {
+            ..
+        } => &[Phases],
+        Event::ClonePhase {
+            ..
+        } => &[Phases, LoadOuts],
+        Event::DeletePhase {
+            ..
+        }
+        | Event::SplitPhaseByFeederCapacity {
+            ..
+        } => &[Phases, Placements, LoadOuts],
+
+        // the parts are added to the load out of the phase, and the phase operations may become complete
+        Event::AssignPlacementsToPhase {
+            ..
+        } => &[Placements, Parts, LoadOuts, Operations],
+        Event::SetPlacementOrdering {
+            ..
+        }
+        | Event::RemoveUsedPlacements {
+            ..
+        } => &[Placements],
+
+        Event::AddPartsToLoadout {
+            ..
+        }
+        | Event::AssignFeederToLoadOutItem {
+            ..
+        }
+        | Event::TemplateLoadOutCopied {
+            ..
+        }
+        | Event::MigrateLoadOuts {
+            ..
+        }
+        | Event::ImportCsvIntoStore {
+            ..
+        } => &[LoadOuts],
+
+        Event::SetRotationOffset {
+            ..
+        } => &[RotationOffsets],
+        Event::ApplyProjectSettings {
+            ..
+        } => &[Settings],
+        Event::SetPlacementNotes {
+            ..
+        }
+        | Event::SetPlacementAttachments {
+            ..
+        } => &[Placements],
+
+        Event::RecordPhaseOperation {
+            ..
+        }
+        | Event::GenerateArtifacts {
+            ..
+        }
+        | Event::RecordUnitPhaseOperation {
+            ..
+        }
+        | Event::AssignSerialNumbers {
+            ..
+        } => &[Operations],
+        Event::RecordPlacementsOperation {
+            ..
+        }
+        | Event::RecordUnitPlacementsOperation {
+            ..
+        }
+        | Event::ResetOperations {
+            ..
+        }
+        | Event::StartBuildRun {
+            ..
+        }
+        | Event::ActivateBuildRunInstance {
+            ..
+        }
+        | Event::VisionOperationCompleted {
+            ..
+        } => &[Placements, Operations],
+
+        Event::StartPlacementSession {
+            ..
+        }
+        | Event::NextPlacement {}
+        | Event::EndPlacementSession {} => &[PlacementSession],
+        Event::ConfirmPlacement {} | Event::SkipPlacement {} => &[PlacementSession, Placements, Operations],
+
+        // nothing the views depend on, e.g. view and file IO requests, saving, selections, and the results of reads and
+        // writes that are processed by other events, e.g. `PcbFileRead`.
+        Event::None
+        | Event::Save
+        | Event::Load {
+            ..
+        }
+        | Event::ProjectFileWritten {
+            ..
+        }
+        | Event::Autosave
+        | Event::AutosaveWritten {
+            ..
+        }
+        | Event::RecoveryFileRead {
+            ..
+        }
+        | Event::DiscardRecovery
+        | Event::RecoveryFileRemoved {
+            ..
+        }
+        | Event::ExportProjectArchive {
+            ..
+        }
+        | Event::ImportProjectArchive {
+            ..
+        }
+        | Event::ProjectArchiveRead {
+            ..
+        }
+        | Event::ArchiveFileWritten {
+            ..
+        }
+        | Event::RefreshPcbs {
+            ..
+        }
+        | Event::SaveAllPcbs
+        | Event::SetProjectFileFormat {
+            ..
+        }
+        | Event::GenerateHandoverReport {
+            ..
+        }
+        | Event::SelectPlacements {
+            ..
+        }
+        | Event::ReleasePlacementSelection {
+            ..
+        }
+        | Event::CancelOperation {
+            ..
+        }
+        | Event::SetEventRecording {
+            ..
+        }
+        | Event::EventRecordingWritten {
+            ..
+        }
+        | Event::RequestVisionOperation {
+            ..
+        }
+        | Event::RequestPlacementsView {
+            ..
+        }
+        | Event::RequestProjectTreeView {
+            ..
+        }
+        | Event::RequestPhasesView {
+            ..
+        }
+        | Event::RequestPhaseOverviewView {
+            ..
+        }
+        | Event::RequestPhasePlacementsView {
+            ..
+        }
+        | Event::RequestPartStatesView
+        | Event::RequestPartPackagesView {
+            ..
+        }
+        | Event::RequestPlacementRotationsView {
+            ..
+        }
+        | Event::RequestPhaseLoadOutView {
+            ..
+        }
+        | Event::RequestFeederLayoutView {
+            ..
+        }
+        | Event::RequestProjectPcbOverviewView {
+            ..
+        }
+        | Event::RequestBoardPreviewView {
+            ..
+        }
+        | Event::BoardPreviewProfileRead {
+            ..
+        }
+        | Event::RequestPcbUnitAssignmentsView {
+            ..
+        }
+        | Event::RequestPcbUnitOverlayView {
+            ..
+        }
+        | Event::RequestPlacementLocationView {
+            ..
+        }
+        | Event::RequestHitTestView {
+            ..
+        }
+        | Event::RequestProcessDefinitionView {
+            ..
+        }
+        | Event::RequestProjectReportView {
+            ..
+        }
+        | Event::RequestIssuesView {
+            ..
+        }
+        | Event::RequestAnalyticsView {
+            ..
+        }
+        | Event::RequestPlacementSessionView {
+            ..
+        }
+        | Event::RequestSearchView {
+            ..
+        }
+        | Event::RequestSettingsView {
+            ..
+        }
+        | Event::ClonePcb {
+            ..
+        }
+        | Event::LoadPcb {
+            ..
+        }
+        | Event::SavePcb {
+            ..
+        }
+        | Event::PcbFileWritten {
+            ..
+        }
+        | Event::PcbFileModifiedTimeRead {
+            ..
+        }
+        | Event::ImportPcbOutline {
+            ..
+        }
+        | Event::PcbOutlineDirectoryListed {
+            ..
+        }
+        | Event::PcbOutlineRead {
+            ..
+        }
+        | Event::RequestPcbOverviewView {
+            ..
+        }
+        | Event::RequestPcbPanelSizingView {
+            ..
+        }
+        | Event::RequestPcbPanelPreviewView {
+            ..
+        } => &[],
+    }
+}
+
+/// The views that are affected by the changes, parameterized views are expanded using the project, e.g. one
+/// [`ProjectViewRequest::PhaseOverview`] per phase.
+pub fn invalidated_views(changes: &[ModelChange], project: &Project) -> Vec<ProjectViewRequest> {
+    let phase_views = |make_view: fn(planning::phase::PhaseReference) -> ProjectViewRequest| {
+        project
+            .phase_orderings
+            .iter()
+            .cloned()
+            .map(make_view)
+            .collect::<Vec<_>>()
+    };
+    let pcb_views = |make_view: fn(u16) -> ProjectViewRequest| {
+        (0..project.pcbs.len() as u16)
+            .map(make_view)
+            .collect::<Vec<_>>()
+    };
+
+    let mut views: Vec<ProjectViewRequest> = vec![];
+    let mut extend = |additional_views: Vec<ProjectViewRequest>| {
+        for view in additional_views {
+            if !views.contains(&view) {
+                views.push(view);
+            }
+        }
+    };
+
+    for change in changes {
+        match change {
+            ModelChange::Project => {
+                let all_changes = [
+                    ModelChange::Pcbs,
+                    ModelChange::Processes,
+                    ModelChange::Parts,
+                    ModelChange::Phases,
+                    ModelChange::Placements,
+                    ModelChange::LoadOuts,
+                    ModelChange::Operations,
+                    ModelChange::RotationOffsets,
+                    ModelChange::PlacementSession,
+                    ModelChange::Settings,
+                ];
+                extend(invalidated_views(&all_changes, project));
+            }
+            ModelChange::Pcbs => {
+                extend(vec![ProjectViewRequest::Overview, ProjectViewRequest::ProjectTree]);
+                extend(pcb_views(|pcb| ProjectViewRequest::PcbOverview {
+                    pcb,
+                }));
+                extend(pcb_views(|pcb| ProjectViewRequest::BoardPreview {
+                    pcb,
+                }));
+                extend(pcb_views(|pcb| ProjectViewRequest::PcbUnitAssignments {
+                    pcb,
+                }));
+                extend(pcb_views(|pcb| ProjectViewRequest::PcbUnitOverlay {
+                    pcb,
+                }));
+            }
+            ModelChange::Processes => {
+                extend(vec![ProjectViewRequest::Overview, ProjectViewRequest::ProjectTree]);
+                extend(
+                    project
+                        .processes
+                        .iter()
+                        .map(|process| ProjectViewRequest::ProcessDefinition {
+                            process: process.reference.clone(),
+                        })
+                        .collect(),
+                );
+            }
+            ModelChange::Parts => {
+                extend(vec![
+                    ProjectViewRequest::Parts,
+                    ProjectViewRequest::PartPackages,
+                    ProjectViewRequest::PlacementRotations,
+                ]);
+            }
+            ModelChange::Phases => {
+                extend(vec![ProjectViewRequest::ProjectTree, ProjectViewRequest::Phases]);
+                extend(phase_views(|phase| ProjectViewRequest::PhaseOverview {
+                    phase,
+                }));
+            }
+            ModelChange::Placements => {
+                extend(vec![ProjectViewRequest::Placements, ProjectViewRequest::Analytics]);
+                extend(phase_views(|phase| ProjectViewRequest::PhasePlacements {
+                    phase,
+                }));
+                extend(pcb_views(|pcb| ProjectViewRequest::PcbUnitOverlay {
+                    pcb,
+                }));
+            }
+            ModelChange::LoadOuts => {
+                extend(phase_views(|phase| ProjectViewRequest::PhaseLoadOut {
+                    phase,
+                }));
+                extend(phase_views(|phase| ProjectViewRequest::FeederLayout {
+                    phase,
+                }));
+            }
+            ModelChange::Operations => {
+                extend(vec![
+                    ProjectViewRequest::Overview,
+                    ProjectViewRequest::Phases,
+                    ProjectViewRequest::Analytics,
+                ]);
+                extend(phase_views(|phase| ProjectViewRequest::PhaseOverview {
+                    phase,
+                }));
+            }
+            ModelChange::RotationOffsets => {
+                extend(vec![ProjectViewRequest::PlacementRotations]);
+                extend(phase_views(|phase| ProjectViewRequest::PhasePlacements {
+                    phase,
+                }));
+            }
+            ModelChange::PlacementSession => {
+                extend(vec![ProjectViewRequest::PlacementSession]);
+            }
+            ModelChange::Settings => {
+                // the rotations in the view use the rotation direction of the settings
+                extend(vec![
+                    ProjectViewRequest::Settings,
+                    ProjectViewRequest::PlacementRotations,
+                ]);
+            }
+        }
+    }
+
+    // issues are derived from most of the model
+    if !changes.is_empty() && !changes.eq(&[ModelChange::PlacementSession]) {
+        extend(vec![ProjectViewRequest::ProjectReport, ProjectViewRequest::Issues]);
+    }
+
+    views
+}
+
+#[cfg(test)]
+mod view_dependencies_tests {
+    use std::str::FromStr;
+
+    use planning::actions::SetOrClearAction;
+    use pnp::object_path::{ObjectPath, ObjectPathPattern};
+    use pnp::reference::Reference;
+
+    use super::*;
+
+    #[test]
+    pub fn phase_views_are_expanded_for_each_phase() {
+        // given
+        let mut project = Project::default();
+        project
+            .phase_orderings
+            .insert(Reference::from_raw_str("top_1"));
+        project
+            .phase_orderings
+            .insert(Reference::from_raw_str("bottom_1"));
+
+        // when
+        let views = invalidated_views(&[ModelChange::LoadOuts], &project);
+
+        // then
+        assert_eq!(views, vec![
+            ProjectViewRequest::PhaseLoadOut {
+                phase: Reference::from_raw_str("top_1"),
+            },
+            ProjectViewRequest::PhaseLoadOut {
+                phase: Reference::from_raw_str("bottom_1"),
+            },
+            ProjectViewRequest::FeederLayout {
+                phase: Reference::from_raw_str("top_1"),
+            },
+            ProjectViewRequest::FeederLayout {
+                phase: Reference::from_raw_str("bottom_1"),
+            },
+            ProjectViewRequest::ProjectReport,
+            ProjectViewRequest::Issues,
+        ]);
+    }
+
+    #[test]
+    pub fn placement_session_changes_only_invalidate_the_session_view() {
+        // given
+        let project = Project::default();
+
+        // when
+        let views = invalidated_views(event_changes(&Event::NextPlacement {}), &project);
+
+        // then
+        assert_eq!(views, vec![ProjectViewRequest::PlacementSession]);
+    }
+
+    #[test]
+    pub fn assigning_placements_invalidates_the_phase_views() {
+        // given
+        let mut project = Project::default();
+        project
+            .phase_orderings
+            .insert(Reference::from_raw_str("top_1"));
+        let event = Event::AssignPlacementsToPhase {
+            phase: Reference::from_raw_str("top_1"),
+            operation: SetOrClearAction::Set,
+            placements: ObjectPathPattern::exact(&ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap()).into(),
+            force: false,
+        };
+
+        // when
+        let views = invalidated_views(event_changes(&event), &project);
+
+        // then
+        // the shells rely on these, instead of refreshing the phase views themselves.
+        for view in [
+            ProjectViewRequest::Phases,
+            ProjectViewRequest::Placements,
+            ProjectViewRequest::PhaseOverview {
+                phase: Reference::from_raw_str("top_1"),
+            },
+            ProjectViewRequest::PhasePlacements {
+                phase: Reference::from_raw_str("top_1"),
+            },
+            ProjectViewRequest::PhaseLoadOut {
+                phase: Reference::from_raw_str("top_1"),
+            },
+            ProjectViewRequest::Issues,
+        ] {
+            assert!(views.contains(&view), "view: {:?}", view);
+        }
+    }
+
+    #[test]
+    pub fn view_requests_do_not_invalidate_views() {
+        // expect
+        assert!(event_changes(&Event::RequestOverviewView {}).is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+//! Non-fatal problems that occurred while processing an event, see [`PlannerOperationViewModel::warnings`].
+//!
+//! Unlike an error, the operation completes, e.g. the gerbers that could be removed are removed.  The warnings in the
+//! view model are the warnings of the last processed event, like the error.
+//!
+//! [`PlannerOperationViewModel::warnings`]: crate::PlannerOperationViewModel::warnings
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use args::Arg;
+use planning::design::DesignVariant;
+use planning::phase::PhaseReference;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use pnp::pcb::PcbSide;
+use thiserror::Error;
+use tracing::warn;
+
+pub type Warnings = Vec<(chrono::DateTime<chrono::Utc>, AppWarning)>;
+
+#[derive(Error, serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub enum AppWarning {
+    #[error("Unable to remove gerbers. pcb_file: {pcb_file:?}, files: {files:?}")]
+    UnremovedGerbers { pcb_file: PathBuf, files: Vec<PathBuf> },
+    #[error("Ignoring unreadable recovery file. path: {path:?}, cause: {cause}")]
+    UnreadableRecoveryFile { path: PathBuf, cause: String },
+    #[error("Unable to load profile gerber, using panel size. file: {file:?}, cause: {cause}")]
+    UnreadableProfileGerber { file: PathBuf, cause: String },
+    #[error("Unable to load the load-out, process rules use an empty load-out. phase: {phase}, cause: {cause}")]
+    LoadOutUnavailableForRules { phase: PhaseReference, cause: String },
+    #[error(
+        "Ignored placements that are not on the PCB side of the phase. phase: {phase}, phase_side: {phase_side}, placements: {object_paths:?}"
+    )]
+    PlacementsOnOtherPcbSide {
+        phase: PhaseReference,
+        phase_side: PcbSide,
+        object_paths: Vec<ObjectPath>,
+    },
+    #[error("Placements were imported with warnings. design_variant: {design_variant}, warnings: {warnings:?}")]
+    PlacementWarnings {
+        design_variant: DesignVariant,
+        warnings: Vec<String>,
+    },
+    #[error("Ignored parts that are not used by the project. parts: {parts:?}")]
+    UnknownParts { parts: Vec<Part> },
+}
+
+impl AppWarning {
+    /// The key of the localized message, the arguments of the message are [`Self::args`].
+    pub fn i18n_key(&self) -> &'static str {
+        match self {
+            AppWarning::UnremovedGerbers {
+                ..
+            } => "app-warning-unremoved-gerbers",
+            AppWarning::UnreadableRecoveryFile {
+                ..
+            } => "app-warning-unreadable-recovery-file",
+            AppWarning::UnreadableProfileGerber {
+                ..
+            } => "app-warning-unreadable-profile-gerber",
+            AppWarning::LoadOutUnavailableForRules {
+                ..
+            } => "app-warning-load-out-unavailable-for-rules",
+            AppWarning::PlacementsOnOtherPcbSide {
+                ..
+            } => "app-warning-placements-on-other-pcb-side",
+            AppWarning::PlacementWarnings {
+                ..
+            } => "app-warning-placement-warnings",
+            AppWarning::UnknownParts {
+                ..
+            } => "app-warning-unknown-parts",
+        }
+    }
+
+    pub fn args(&self) -> HashMap<String, Arg> {
+        let path_arg = |path: &PathBuf| Arg::String(path.display().to_string());
+
+        match self {
+            AppWarning::UnremovedGerbers {
+                pcb_file,
+                files,
+            } => HashMap::from([
+                ("pcb_file".to_string(), path_arg(pcb_file)),
+                ("count".to_string(), Arg::Integer(files.len() as i64)),
+            ]),
+            AppWarning::UnreadableRecoveryFile {
+                path: file,
+                cause,
+            }
+            | AppWarning::UnreadableProfileGerber {
+                file,
+                cause,
+            } => HashMap::from([
+                ("file".to_string(), path_arg(file)),
+                ("cause".to_string(), Arg::String(cause.clone())),
+            ]),
+            AppWarning::LoadOutUnavailableForRules {
+                phase,
+                cause,
+            } => HashMap::from([
+                ("phase".to_string(), Arg::String(phase.to_string())),
+                ("cause".to_string(), Arg::String(cause.clone())),
+            ]),
+            AppWarning::PlacementsOnOtherPcbSide {
+                phase,
+                phase_side,
+                object_paths,
+            } => HashMap::from([
+                ("phase".to_string(), Arg::String(phase.to_string())),
+                ("pcb_side".to_string(), Arg::String(phase_side.to_string())),
+                ("count".to_string(), Arg::Integer(object_paths.len() as i64)),
+                (
+                    "placements".to_string(),
+                    Arg::String(
+                        object_paths
+                            .iter()
+                            .map(ObjectPath::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ),
+                ),
+            ]),
+            AppWarning::PlacementWarnings {
+                design_variant,
+                warnings,
+            } => HashMap::from([
+                ("design_variant".to_string(), Arg::String(design_variant.to_string())),
+                ("count".to_string(), Arg::Integer(warnings.len() as i64)),
+                ("warnings".to_string(), Arg::String(warnings.join("; "))),
+            ]),
+            AppWarning::UnknownParts {
+                parts,
+            } => HashMap::from([
+                ("count".to_string(), Arg::Integer(parts.len() as i64)),
+                (
+                    "parts".to_string(),
+                    Arg::String(
+                        parts
+                            .iter()
+                            .map(|part| format!("{} {}", part.manufacturer, part.mpn))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ),
+                ),
+            ]),
+        }
+    }
+}
+
+/// The warning is logged too, for shells that do not show warnings, e.g. the CLI.
+pub(crate) fn push_warning(warnings: &mut Warnings, warning: AppWarning) {
+    warn!("{}", warning);
+    warnings.push((chrono::Utc::now(), warning));
+}
@@ -1,16 +1,21 @@
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Instant;
 
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 use clap::Parser;
 use crossbeam_channel::unbounded;
-use planner_app::{Effect, Event};
-use tracing::trace;
+use planner_app::effects::file_io;
+use planner_app::{DeleteReport, Effect, Event, AUTOSAVE_INTERVAL};
+use planning::recent;
+use tracing::{info, trace, warn};
 
 use crate::core::Core;
 use crate::opts::{build_project_file_path, ModeCommand, Opts, PcbCommand, ProjectCommand};
 
 mod core;
 mod opts;
+mod script;
 
 fn main() -> anyhow::Result<()> {
     let args = argfile::expand_args(argfile::parse_fromfile, argfile::PREFIX).unwrap();
@@ -21,66 +26,246 @@ fn main() -> anyhow::Result<()> {
 
     let core = core::new();
 
-    let event = match &opts.command {
+    if let Some(path) = &opts.record_events {
+        run_loop(
+            &core,
+            Event::SetEventRecording {
+                path: Some(path.clone()),
+            },
+            SaveMode::Implicit,
+        )?;
+    }
+
+    if opts.recent {
+        return list_recent_projects();
+    }
+
+    let Some(command) = &opts.command else {
+        bail!("Missing command");
+    };
+
+    let event = match command {
         ModeCommand::Project(project_args) => {
-            if !matches!(project_args.command, ProjectCommand::Create { .. }) {
+            if !matches!(
+                project_args.command,
+                ProjectCommand::Create { .. }
+                    | ProjectCommand::CreateFromTemplate { .. }
+                    | ProjectCommand::ImportArchive { .. }
+            ) {
                 let project_name = &project_args.project;
                 let directory = project_args.path.clone();
 
                 let path = build_project_file_path(project_name, &directory);
-                run_loop(&core, Event::Load {
-                    path,
-                })?;
+                if let Err(cause) = run_loop(
+                    &core,
+                    Event::Load {
+                        path,
+                    },
+                    SaveMode::Implicit,
+                ) {
+                    // the project is loaded even if some of the files it references are missing
+                    if !matches!(project_args.command, ProjectCommand::RelinkFiles { .. }) {
+                        return Err(cause);
+                    }
+                    warn!(
+                        "Unable to load all the files referenced by the project. cause: {:?}",
+                        cause
+                    );
+                }
+
+                // the implicit save removes the recovery file, which would discard the unsaved changes
+                if core.view().recovery_available
+                    && !matches!(
+                        project_args.command,
+                        ProjectCommand::RestoreRecovery {} | ProjectCommand::DiscardRecovery {}
+                    )
+                {
+                    bail!("Unsaved changes were found, use 'restore-recovery' or 'discard-recovery' first");
+                }
+            }
+
+            if let ProjectCommand::Run {
+                script,
+                var,
+            } = &project_args.command
+            {
+                return run_script(&core, script, var);
             }
+
             Event::try_from(opts)?
         }
         ModeCommand::Pcb(pcb_args) => {
             if !matches!(pcb_args.command, PcbCommand::Create { .. }) {
                 let path = pcb_args.pcb_file.clone();
-                run_loop(&core, Event::LoadPcb {
-                    path,
-                })?;
+                run_loop(
+                    &core,
+                    Event::LoadPcb {
+                        path,
+                    },
+                    SaveMode::Implicit,
+                )?;
             }
             Event::try_from(opts)?
         }
     };
 
-    run_loop(&core, event)?;
+    run_loop(&core, event, SaveMode::Implicit)?;
 
     Ok(())
 }
 
-fn run_loop(core: &Core, event: Event) -> Result<(), anyhow::Error> {
+/// Lists the projects recently opened by the GUI, see [`recent::default_recent_projects_file_path`].
+fn list_recent_projects() -> anyhow::Result<()> {
+    let path =
+        recent::default_recent_projects_file_path().ok_or(anyhow!("Unable to locate the recent projects file"))?;
+    let recent_projects = recent::load_recent_projects(&path)?;
+
+    for recent_project in recent_projects.sorted() {
+        let pinned = if recent_project.pinned { "*" } else { " " };
+        println!(
+            "{} {} {} {}",
+            pinned,
+            recent_project.last_opened.date(),
+            recent_project.name,
+            recent_project.path.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Lists what was affected by deleting an item, e.g. the placements that were unassigned from a deleted phase.
+fn print_delete_report(report: &DeleteReport) {
+    for object_path in report.unit_assignments.iter() {
+        println!("unit assignment removed: {}", object_path);
+    }
+    for object_path in report.deleted_placements.iter() {
+        println!("placement deleted: {}", object_path);
+    }
+    for object_path in report.unassigned_placements.iter() {
+        println!("placement unassigned: {}", object_path);
+    }
+    for object_path in report.reset_placements.iter() {
+        println!("placement reset: {}", object_path);
+    }
+    for (old_object_path, new_object_path) in report.renumbered_placements.iter() {
+        println!("placement renumbered: {} -> {}", old_object_path, new_object_path);
+    }
+    if report.operation_history {
+        println!("phase had recorded operations");
+    }
+}
+
+/// Prints the warnings of the last processed event, this is called once for each processed event, instead of for each
+/// render, since the core can render more than once for an event.
+fn print_warnings(core: &Core) {
+    for (_date_time, warning) in core.view().warnings {
+        println!("warning: {}", warning);
+    }
+}
+
+/// Saving after any operation is implicit for the CLI, except for scripts, which are saved after all the events have
+/// been applied, so that a failing script does not leave the project partially modified.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SaveMode {
+    Implicit,
+    Deferred,
+}
+
+fn run_script(core: &Core, script: &Path, variables: &[(String, String)]) -> Result<(), anyhow::Error> {
+    // all the events are validated before any are applied
+    let events = script::load_script(script, variables)?;
+
+    info!("Running script. script: {:?}, events: {}", script, events.len());
+
+    let mut last_autosave = Instant::now();
+    for event in events {
+        run_loop(core, event, SaveMode::Deferred)?;
+
+        // the changes of a long-running script can be recovered if it does not complete, see `restore-recovery`.
+        if last_autosave.elapsed() >= AUTOSAVE_INTERVAL {
+            run_loop(core, Event::Autosave, SaveMode::Deferred)?;
+            last_autosave = Instant::now();
+        }
+    }
+
+    save_modified(core)
+}
+
+fn run_loop(core: &Core, event: Event, save_mode: SaveMode) -> Result<(), anyhow::Error> {
     let (tx, rx) = unbounded::<Effect>();
 
     core::update(&core, event, &Arc::new(tx))?;
+    print_warnings(core);
 
     while let Ok(effect) = rx.recv() {
-        trace!("run_loop. effect: {:?}", effect);
-        match effect {
-            _render @ Effect::Render(_) => {
-                let view = core.view();
+        process_effect(core, effect, save_mode)?;
+    }
+    Ok(())
+}
 
-                if let Some((_date_time, error)) = view.error {
-                    bail!(error)
-                }
+fn save_modified(core: &Core) -> Result<(), anyhow::Error> {
+    let view = core.view();
 
-                // Saving after any operation is implicit for the CLI.
-                // FUTURE: Maybe it would be useful to have a 'dry-run' flag that doesn't trigger a save.
-                if view.project_modified {
-                    run_loop(core, Event::Save)?
-                }
-                if view.pcbs_modified {
-                    run_loop(core, Event::SaveAllPcbs)?
+    if view.project_modified {
+        run_loop(core, Event::Save, SaveMode::Implicit)?
+    }
+    if view.pcbs_modified {
+        run_loop(core, Event::SaveAllPcbs, SaveMode::Implicit)?
+    }
+    Ok(())
+}
+
+fn process_effect(core: &Core, effect: Effect, save_mode: SaveMode) -> Result<(), anyhow::Error> {
+    trace!("run_loop. effect: {:?}", effect);
+    match effect {
+        _render @ Effect::Render(_) => {
+            let view = core.view();
+
+            if let Some((_date_time, error)) = view.error {
+                bail!(error)
+            }
+
+            if let Some(report) = view.delete_report {
+                print_delete_report(&report);
+            }
+
+            if let Some(design_variants) = view.refreshed_design_variants {
+                for design_variant in design_variants.iter() {
+                    println!("design variant refreshed: {}", design_variant);
                 }
             }
-            Effect::ProjectView(_) => {
-                // Currently, the CLI app should not cause these effects.
-                unreachable!()
+
+            // FUTURE: Maybe it would be useful to have a 'dry-run' flag that doesn't trigger a save.
+            if save_mode == SaveMode::Implicit {
+                save_modified(core)?
             }
-            Effect::PcbView(_) => {
-                // Currently, the CLI app should not cause these effects.
-                unreachable!()
+        }
+        Effect::ProjectView(_) => {
+            // Currently, the CLI app should not cause these effects.
+            unreachable!()
+        }
+        Effect::PcbView(_) => {
+            // Currently, the CLI app should not cause these effects.
+            unreachable!()
+        }
+        Effect::Vision(_) => {
+            // Currently, the CLI app should not cause these effects.
+            unreachable!()
+        }
+        Effect::ViewInvalidated(_) => {
+            // The CLI does not show any views.
+        }
+        Effect::FileIo(mut request) => {
+            let result = file_io::perform(&request.operation);
+
+            let effects = core
+                .resolve(&mut request, result)
+                .map_err(|error| anyhow!("{:?}", error))?;
+            print_warnings(core);
+
+            for effect in effects {
+                process_effect(core, effect, save_mode)?;
             }
         }
     }
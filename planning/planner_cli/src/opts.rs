@@ -5,17 +5,25 @@
 
 use clap::{Parser, Subcommand};
 use clap_verbosity_flag::{InfoLevel, Verbosity};
-use cli::args::{AddOrRemoveOperationArg, PcbSideArg, PlacementOperationArg, SetOrClearOperationArg, TaskActionArg};
-use cli::parsers::{dimensions_decimal_parser, vector2_decimal_parser};
+use cli::args::{
+    AddOrRemoveOperationArg, ArtifactArg, DeleteStrategyArg, HandoverReportFormatArg, InspectionResultArg,
+    MoistureEventArg, OriginConventionArg, PcbSideArg, PlacementOperationArg, RotationDirectionArg,
+    SetOrClearOperationArg, TaskActionArg, UnitSystemArg,
+};
+use cli::parsers::{dimensions_decimal_parser, rfc3339_parser, vector2_decimal_parser};
 use nalgebra::Vector2;
-use planner_app::Event;
+use planner_app::{Artifact, Event, FileFormat, PlacementsSelector, TaskParameterValue};
 use planning::design::DesignName;
 use planning::file::FileReference;
-use planning::placement::PlacementSortingItem;
+use planning::phase::AssemblyWindow;
+use planning::placement::{PlacementOperation, PlacementSortingItem};
 use planning::process::ProcessReference;
+use planning::rotation::RotationOffsetTarget;
+use planning::serial_number::{SerialNumber, SerialNumberScheme};
 use planning::variant::VariantName;
-use pnp::object_path::ObjectPath;
+use pnp::object_path::{ObjectPath, ObjectPathPattern, ObjectPathQuery};
 use pnp::panel::{DesignSizing, Dimensions, PcbUnitPositioning};
+use pnp::part::{MoistureSensitivityLevel, Part, PartMoistureSensitivity};
 use pnp::pcb::PcbUnitNumber;
 use pnp::reference::Reference;
 use regex::Regex;
@@ -24,20 +32,32 @@
 use stores::load_out::LoadOutSource;
 use stores::package_mappings::PackageMappingsSource;
 use stores::packages::PackagesSource;
+use stores::parts::PartsSource;
+use stores::unit_positionings::UnitPositioningsSource;
 use thiserror::Error;
+use time::OffsetDateTime;
 
 #[derive(Parser, Debug)]
 #[command(name = "planner_cli")]
 #[command(bin_name = "planner_cli")]
 #[command(version, about, long_about = None)]
+#[command(arg_required_else_help = true)]
 pub(crate) struct Opts {
     #[command(subcommand)]
-    pub(crate) command: ModeCommand,
+    pub(crate) command: Option<ModeCommand>,
+
+    /// List the recently opened projects, pinned projects first
+    #[arg(long)]
+    pub(crate) recent: bool,
 
     /// Trace log file
     #[arg(long, num_args = 0..=1, default_missing_value = "trace.log")]
     pub(crate) trace: Option<PathBuf>,
 
+    /// Record the processed events to a replay file, for bug reports
+    #[arg(long, value_name = "REPLAY_FILE")]
+    pub(crate) record_events: Option<PathBuf>,
+
     #[command(flatten)]
     pub(crate) verbose: Verbosity<InfoLevel>,
 }
@@ -110,6 +130,18 @@ pub(crate) enum PcbCommand {
         #[arg(long, value_name = "PCB_UNIT_POSITIONING", action = clap::ArgAction::Append)]
         pcb_unit_position: Vec<PcbUnitPositioningArgs>,
     },
+    /// Import the unit positionings of a PCB from a CSV file, e.g. a panel drawing's unit origin table
+    ImportUnitPositionings {
+        /// The source for the unit positionings, with 'Unit', 'X', 'Y' and 'Rotation' columns
+        #[arg(long)]
+        unit_positionings: UnitPositioningsSource,
+    },
+    /// Save a copy of a PCB file, alongside the PCB file
+    Clone {
+        /// Name of the copy, e.g. 'panel_2'
+        #[arg(long)]
+        name: String,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -337,6 +369,18 @@ fn parse_design_kv(s: &str) -> Result<(u16, DesignName), String> {
     Ok((key_parsed, value.into()))
 }
 
+fn parse_parameter_kv(s: &str) -> Result<(String, String), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("Missing value in '{}'", s))?;
+
+    if name.is_empty() {
+        return Err(format!("Missing name in '{}'", s));
+    }
+
+    Ok((name.to_string(), value.to_string()))
+}
+
 #[derive(Subcommand, Debug)]
 //#[command(arg_required_else_help(true))]
 pub(crate) enum ProjectCommand {
@@ -350,6 +394,38 @@ pub(crate) enum ProjectCommand {
         #[arg(long)]
         package_mappings: Option<PackageMappingsSource>,
     },
+    /// Create a new job from a template
+    CreateFromTemplate {
+        /// The template directory, containing a 'template.json' file
+        #[arg(long)]
+        template: PathBuf,
+    },
+    /// Export the project and all the files it references to a zip archive
+    ExportArchive {
+        /// The path of the zip archive
+        #[arg(long)]
+        archive: PathBuf,
+    },
+    /// Import a project from a zip archive, into the project path
+    ImportArchive {
+        /// The path of the zip archive
+        #[arg(long)]
+        archive: PathBuf,
+    },
+    /// Find missing PCB, gerber and load-out files, and relink them
+    RelinkFiles {
+        /// Directories to search, recursively, e.g. 'gerbers,pcbs'
+        #[arg(long, required = true, num_args = 1.., value_delimiter = ',', value_name = "DIRECTORY")]
+        search_roots: Vec<PathBuf>,
+
+        /// Relink files that have exactly one candidate, otherwise the candidates are just logged
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Restore the unsaved changes of the recovery snapshot, e.g. after the GUI exited unexpectedly
+    RestoreRecovery {},
+    /// Discard the unsaved changes of the recovery snapshot
+    DiscardRecovery {},
     /// Add a PCB file to the project
     AddPcb {
         /// The path of the PCB, e.g. 'relative:<some_relative_path>' or '<some_absolute_path>'
@@ -362,6 +438,10 @@ pub(crate) enum ProjectCommand {
         /// The zero-based index of the PCB
         #[arg(long)]
         index: u16,
+
+        /// How unit assignments and placements of the PCB are handled, 'cascade' removes them too
+        #[arg(long, default_value = "block")]
+        strategy: DeleteStrategyArg,
     },
     /// Assign a design variant to a PCB unit
     AssignVariantToUnit {
@@ -373,6 +453,16 @@ pub(crate) enum ProjectCommand {
         #[arg(long, value_parser = clap::value_parser!(VariantName), value_name = "VARIANT_NAME")]
         variant: Option<VariantName>,
     },
+    /// Assign serial numbers to the units of a PCB that do not already have one
+    AssignSerialNumbers {
+        /// The zero-based index of the PCB
+        #[arg(long)]
+        pcb: u16,
+
+        /// Serial number scheme (e.g. '{project}-{date}-{seq:04}'), placeholders: project, date, seq, pcb, unit
+        #[arg(long, value_parser = clap::value_parser!(SerialNumberScheme), value_name = "SCHEME")]
+        scheme: SerialNumberScheme,
+    },
     /// Refresh from design variants
     RefreshFromDesignVariants,
     /// Create a process from presets
@@ -387,6 +477,16 @@ pub(crate) enum ProjectCommand {
         #[arg(long)]
         process: ProcessReference,
     },
+    /// Copy a process
+    CloneProcess {
+        /// Process name
+        #[arg(long)]
+        process: ProcessReference,
+
+        /// Name of the copy
+        #[arg(long)]
+        new_process: ProcessReference,
+    },
     /// Assign a process to parts
     AssignProcessToParts {
         /// Process name
@@ -405,6 +505,56 @@ pub(crate) enum ProjectCommand {
         #[arg(long)]
         mpn: Regex,
     },
+    /// Set, or clear, the moisture sensitivity level (MSL) of parts
+    SetPartMoistureSensitivity {
+        /// Manufacturer pattern (regexp)
+        #[arg(long)]
+        manufacturer: Regex,
+
+        /// Manufacturer part number (regexp)
+        #[arg(long)]
+        mpn: Regex,
+
+        /// Moisture sensitivity level (e.g. '3', '2a'), omit to clear
+        #[arg(long)]
+        msl: Option<MoistureSensitivityLevel>,
+
+        /// Floor life in hours, overrides the default for the level (e.g. from an MSL 6 label)
+        #[arg(long, requires = "msl")]
+        floor_life_hours: Option<u32>,
+    },
+    /// Record a moisture event (reel opened, baked) for parts, at the current time
+    RecordPartMoistureEvent {
+        /// Manufacturer pattern (regexp)
+        #[arg(long)]
+        manufacturer: Regex,
+
+        /// Manufacturer part number (regexp)
+        #[arg(long)]
+        mpn: Regex,
+
+        /// The moisture event
+        #[arg(long)]
+        event: MoistureEventArg,
+    },
+    /// Import part alias groups from the 'AliasGroup' column of a parts file
+    ImportPartAliases {
+        /// The source for parts
+        #[arg(long)]
+        parts: PartsSource,
+    },
+    /// Import the moisture sensitivity of parts from the 'Msl' and 'FloorLifeHours' columns of a parts file
+    ImportMoistureSensitivities {
+        /// The source for parts
+        #[arg(long)]
+        parts: PartsSource,
+    },
+    /// Import the placements and load-out CSV files, and optionally a parts file, into the project's store backend
+    ImportCsvIntoStore {
+        /// The source for parts
+        #[arg(long)]
+        parts: Option<PartsSource>,
+    },
     /// Create a phase
     CreatePhase {
         /// Process name
@@ -434,8 +584,46 @@ pub(crate) enum ProjectCommand {
         operation: SetOrClearOperationArg,
 
         /// Placements object path pattern (regexp)
+        #[arg(
+            long,
+            conflicts_with = "placements_query",
+            required_unless_present = "placements_query"
+        )]
+        placements: Option<Regex>,
+
+        /// Placements object path query (e.g. 'pcb=1, unit=3..8, ref_des=R*')
+        #[arg(long, value_name = "QUERY")]
+        placements_query: Option<ObjectPathQuery>,
+
+        /// Also assign placements on the other PCB side (e.g. through-hole parts)
         #[arg(long)]
-        placements: Regex,
+        force: bool,
+    },
+    /// Split a phase into a phase for each pass when it requires more feeders than the machine has feeder slots
+    SplitPhaseByFeederCapacity {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+    },
+    /// Delete a phase
+    DeletePhase {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// How assigned placements are handled, 'orphan' unassigns them, 'cascade' also resets their status
+        #[arg(long, default_value = "block")]
+        strategy: DeleteStrategyArg,
+    },
+    /// Copy a phase, including its load-out, without placements
+    ClonePhase {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// Reference of the copy (e.g. 'top_2')
+        #[arg(long)]
+        new_phase: Reference,
     },
     /// Assign feeder to load-out item
     AssignFeederToLoadOutItem {
@@ -455,6 +643,10 @@ pub(crate) enum ProjectCommand {
         #[arg(long)]
         mpn: Regex,
     },
+    /// Upgrade the load-out files of all phases to the latest format
+    MigrateLoadOuts {},
+    /// Rewrite the project file using the canonical, diff-friendly, format, which is used for all subsequent saves
+    Canonicalize {},
     /// Set placement ordering for a phase
     SetPlacementOrdering {
         /// Phase reference (e.g. 'top_1')
@@ -466,9 +658,106 @@ pub(crate) enum ProjectCommand {
         placement_orderings: Vec<PlacementSortingItem>,
     },
 
+    /// Set, or clear, the scheduled assembly window of a phase
+    SetPhaseAssemblyWindow {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// Start of the window, RFC 3339 (e.g. '2025-03-10T08:00:00Z'), omit with `end` to clear the window
+        #[arg(long, value_parser = rfc3339_parser, requires = "end")]
+        start: Option<OffsetDateTime>,
+
+        /// End of the window, RFC 3339 (e.g. '2025-03-12T17:00:00Z')
+        #[arg(long, value_parser = rfc3339_parser, requires = "start")]
+        end: Option<OffsetDateTime>,
+    },
+
+    /// Apply the units, coordinate and rotation conventions used by the generated artifacts
+    ApplySettings {
+        /// Unit of lengths, unchanged if not specified
+        #[arg(long)]
+        unit_system: Option<UnitSystemArg>,
+
+        /// Origin of positions, unchanged if not specified
+        #[arg(long)]
+        origin: Option<OriginConventionArg>,
+
+        /// Direction of positive rotations, unchanged if not specified
+        #[arg(long)]
+        rotation_direction: Option<RotationDirectionArg>,
+
+        /// Placement orderings of new phases (e.g. 'PCB_UNIT:ASC,REF_DES:ASC'), unchanged if not specified, cleared if empty
+        #[arg(long, num_args = 0.., value_delimiter = ',', value_parser = cli::parsers::PlacementSortingItemParser::default())]
+        default_placement_orderings: Option<Vec<PlacementSortingItem>>,
+    },
+
+    /// Set, or clear, the rotation offset for a package or a part
+    SetRotationOffset {
+        /// Package name (e.g. 'SOT-23')
+        #[arg(long, conflicts_with_all = ["manufacturer", "mpn"], required_unless_present = "manufacturer")]
+        package: Option<String>,
+
+        /// Manufacturer (e.g. 'RASPBERRY PI')
+        #[arg(long, requires = "mpn")]
+        manufacturer: Option<String>,
+
+        /// Manufacturer part number (e.g. 'RP2040')
+        #[arg(long, requires = "manufacturer")]
+        mpn: Option<String>,
+
+        /// Offset in degrees, in the rotation direction of the project settings (e.g. '-90'), omit to clear the offset
+        #[arg(long, allow_negative_numbers = true)]
+        offset: Option<Decimal>,
+    },
+
+    /// Set, or clear, the operator notes of a placement
+    SetPlacementNotes {
+        /// Placement path (e.g. 'pcb=1::unit=1::ref_des=R1')
+        #[arg(long, value_parser = clap::value_parser!(ObjectPath), value_name = "OBJECT_PATH")]
+        placement: ObjectPath,
+
+        /// Notes (e.g. 'lift pin 3'), omit to clear the notes
+        #[arg(long)]
+        notes: Option<String>,
+    },
+
+    /// Set, or clear, the attachments of a placement, e.g. photos
+    SetPlacementAttachments {
+        /// Placement path (e.g. 'pcb=1::unit=1::ref_des=R1')
+        #[arg(long, value_parser = clap::value_parser!(ObjectPath), value_name = "OBJECT_PATH")]
+        placement: ObjectPath,
+
+        /// Attachment paths, relative to the project directory or absolute, omit to clear the attachments
+        #[arg(long, num_args = 0.., value_delimiter = ',')]
+        attachments: Vec<PathBuf>,
+    },
+
     // FUTURE consider adding a command to allow the phase ordering to be changed, currently phase ordering is determined by the order of phase creation.
     /// Generate artifacts
-    GenerateArtifacts {},
+    GenerateArtifacts {
+        /// The artifacts to generate in addition to the phase placements and the report, omit to generate all of them
+        #[arg(long, value_delimiter = ',')]
+        artifacts: Vec<ArtifactArg>,
+    },
+    /// Generate a shift-handover report, a snapshot of the work in progress
+    GenerateHandoverReport {
+        /// The format of the report
+        #[arg(long, default_value = "markdown")]
+        format: HandoverReportFormatArg,
+    },
+    /// Start a build run, each build of the run has its own placement and operation statuses
+    StartBuildRun {
+        /// The number of builds in the run
+        #[arg(long)]
+        quantity: u32,
+    },
+    /// Activate a build of the current build run
+    ActivateBuildRunInstance {
+        /// The build to activate (1-based)
+        #[arg(long)]
+        instance: u32,
+    },
     /// Record phase operation
     RecordPhaseOperation {
         /// Phase reference (e.g. 'top_1')
@@ -486,19 +775,68 @@ pub(crate) enum ProjectCommand {
         /// The task action to apply
         #[arg(long)]
         action: TaskActionArg,
+
+        /// Serial number of the unit to record the operation for
+        #[arg(long)]
+        serial_number: Option<SerialNumber>,
+
+        /// Process rule to override (e.g. 'core::placed_before_soldering'), repeat for each rule
+        #[arg(long = "override-rule")]
+        override_rules: Vec<Reference>,
+
+        /// Parameter value of a custom task, recorded on completion (e.g. 'oven_profile_id=lead_free_1'), repeat for
+        /// each parameter
+        #[arg(long = "parameter", value_parser = parse_parameter_kv)]
+        parameters: Vec<(String, String)>,
     },
     /// Record placements operation
     RecordPlacementsOperation {
         /// List of reference designators to apply the operation to
-        #[arg(long, required = true, num_args = 1.., value_delimiter = ',')]
+        #[arg(long, num_args = 1.., value_delimiter = ',', required_unless_present = "object_path_queries")]
         object_path_patterns: Vec<Regex>,
 
+        /// Object path queries to apply the operation to (e.g. 'pcb=1, unit=3..8, ref_des=R*'), repeat for each query
+        #[arg(long, value_name = "QUERY")]
+        object_path_queries: Vec<ObjectPathQuery>,
+
         /// The completed operation to apply
         #[arg(long)]
         operation: PlacementOperationArg,
+
+        /// Serial number of the unit, only the placements of the unit are updated
+        #[arg(long)]
+        serial_number: Option<SerialNumber>,
+    },
+    /// Record the inspection result of placed placements
+    RecordPlacementsInspection {
+        /// List of reference designators to apply the inspection result to
+        #[arg(long, num_args = 1.., value_delimiter = ',', required_unless_present = "object_path_queries")]
+        object_path_patterns: Vec<Regex>,
+
+        /// Object path queries to apply the inspection result to (e.g. 'pcb=1, unit=3..8, ref_des=R*'), repeat for each query
+        #[arg(long, value_name = "QUERY")]
+        object_path_queries: Vec<ObjectPathQuery>,
+
+        /// The inspection result, failed placements are returned to pending
+        #[arg(long)]
+        result: InspectionResultArg,
+
+        /// Inspection notes (e.g. 'tombstoned', 'wrong polarity')
+        #[arg(long)]
+        notes: Option<String>,
     },
     /// Reset operations
     ResetOperations {},
+    /// Run a script of events, the project is only saved if all the events succeed
+    Run {
+        /// The path of the script, YAML or JSON
+        #[arg(long)]
+        script: PathBuf,
+
+        /// Script variables, overriding the variables in the script, e.g. 'side=top'
+        #[arg(long, value_parser = crate::script::parse_variable_kv, action = clap::ArgAction::Append, value_name = "NAME=VALUE")]
+        var: Vec<(String, String)>,
+    },
 }
 
 // FUTURE consider merging the AssignProcessToParts and AssignLoadOutToParts commands
@@ -510,13 +848,23 @@ pub enum EventError {
     MissingProjectName,
     #[error("Missing command")]
     MissingCommand,
+    #[error("Missing rotation offset target")]
+    MissingRotationOffsetTarget,
+    #[error("Missing placements pattern")]
+    MissingPlacementsPattern,
+    #[error("Scripts are not a single event")]
+    ScriptCommand,
 }
 
 impl TryFrom<Opts> for Event {
     type Error = EventError;
 
     fn try_from(opts: Opts) -> Result<Self, Self::Error> {
-        match opts.command {
+        let Some(command) = opts.command else {
+            return Err(EventError::MissingCommand);
+        };
+
+        match command {
             ModeCommand::Pcb(pcb_args) => match pcb_args.command {
                 PcbCommand::Create {
                     name,
@@ -534,6 +882,18 @@ fn try_from(opts: Opts) -> Result<Self, Self::Error> {
                         path: pcb_args.pcb_file.to_path_buf(),
                     })
                 }
+                PcbCommand::ImportUnitPositionings {
+                    unit_positionings,
+                } => Ok(Event::ImportPcbUnitPositionings {
+                    path: pcb_args.pcb_file.to_path_buf(),
+                    source: unit_positionings,
+                }),
+                PcbCommand::Clone {
+                    name,
+                } => Ok(Event::ClonePcb {
+                    source_path: pcb_args.pcb_file.to_path_buf(),
+                    new_name: name,
+                }),
                 PcbCommand::ConfigurePanelSizing {
                     edge_rails,
                     size,
@@ -610,6 +970,40 @@ fn try_from(opts: Opts) -> Result<Self, Self::Error> {
                         package_mappings,
                     })
                 }
+                ProjectCommand::CreateFromTemplate {
+                    template,
+                } => {
+                    let name = project_args.project;
+                    let directory = project_args.path.clone();
+
+                    let path = build_project_file_path(&name, &directory);
+
+                    Ok(Event::CreateProjectFromTemplate {
+                        template,
+                        name,
+                        path,
+                    })
+                }
+                ProjectCommand::ExportArchive {
+                    archive,
+                } => Ok(Event::ExportProjectArchive {
+                    path: archive,
+                }),
+                ProjectCommand::ImportArchive {
+                    archive,
+                } => Ok(Event::ImportProjectArchive {
+                    archive,
+                    directory: project_args.path.clone(),
+                }),
+                ProjectCommand::RelinkFiles {
+                    search_roots,
+                    apply,
+                } => Ok(Event::RelinkFiles {
+                    search_roots,
+                    apply,
+                }),
+                ProjectCommand::RestoreRecovery {} => Ok(Event::RestoreRecovery),
+                ProjectCommand::DiscardRecovery {} => Ok(Event::DiscardRecovery),
                 ProjectCommand::AddPcb {
                     file,
                 } => Ok(Event::AddPcb {
@@ -617,8 +1011,10 @@ fn try_from(opts: Opts) -> Result<Self, Self::Error> {
                 }),
                 ProjectCommand::RemovePcb {
                     index,
+                    strategy,
                 } => Ok(Event::RemovePcb {
                     index,
+                    strategy: strategy.into(),
                 }),
                 ProjectCommand::AssignVariantToUnit {
                     unit,
@@ -627,7 +1023,16 @@ fn try_from(opts: Opts) -> Result<Self, Self::Error> {
                     unit,
                     variant,
                 }),
-                ProjectCommand::RefreshFromDesignVariants => Ok(Event::RefreshFromDesignVariants),
+                ProjectCommand::AssignSerialNumbers {
+                    pcb,
+                    scheme,
+                } => Ok(Event::AssignSerialNumbers {
+                    pcb,
+                    scheme,
+                }),
+                ProjectCommand::RefreshFromDesignVariants => Ok(Event::RefreshFromDesignVariants {
+                    token: None,
+                }),
                 ProjectCommand::CreateProcessFromPreset {
                     preset,
                 } => Ok(Event::CreateProcessFromPreset {
@@ -638,6 +1043,13 @@ fn try_from(opts: Opts) -> Result<Self, Self::Error> {
                 } => Ok(Event::DeleteProcess {
                     process_reference: process,
                 }),
+                ProjectCommand::CloneProcess {
+                    process,
+                    new_process,
+                } => Ok(Event::CloneProcess {
+                    process_reference: process,
+                    new_reference: new_process,
+                }),
                 ProjectCommand::AssignProcessToParts {
                     process,
                     operation,
@@ -649,6 +1061,43 @@ fn try_from(opts: Opts) -> Result<Self, Self::Error> {
                     manufacturer,
                     mpn,
                 }),
+                ProjectCommand::SetPartMoistureSensitivity {
+                    manufacturer,
+                    mpn,
+                    msl,
+                    floor_life_hours,
+                } => Ok(Event::SetPartMoistureSensitivity {
+                    manufacturer,
+                    mpn,
+                    moisture_sensitivity: msl.map(|level| PartMoistureSensitivity {
+                        level,
+                        floor_life_hours,
+                    }),
+                }),
+                ProjectCommand::RecordPartMoistureEvent {
+                    manufacturer,
+                    mpn,
+                    event,
+                } => Ok(Event::RecordPartMoistureEvent {
+                    manufacturer,
+                    mpn,
+                    event: event.into(),
+                }),
+                ProjectCommand::ImportPartAliases {
+                    parts,
+                } => Ok(Event::ImportPartAliases {
+                    parts_source: parts,
+                }),
+                ProjectCommand::ImportMoistureSensitivities {
+                    parts,
+                } => Ok(Event::ImportMoistureSensitivities {
+                    parts_source: parts,
+                }),
+                ProjectCommand::ImportCsvIntoStore {
+                    parts,
+                } => Ok(Event::ImportCsvIntoStore {
+                    parts_source: parts,
+                }),
                 ProjectCommand::CreatePhase {
                     process,
                     reference,
@@ -664,10 +1113,40 @@ fn try_from(opts: Opts) -> Result<Self, Self::Error> {
                     phase,
                     operation,
                     placements,
-                } => Ok(Event::AssignPlacementsToPhase {
+                    placements_query,
+                    force,
+                } => {
+                    let placements = match (placements, placements_query) {
+                        (Some(placements), _) => ObjectPathPattern::Regex(placements),
+                        (None, Some(placements_query)) => ObjectPathPattern::Query(placements_query),
+                        (None, None) => return Err(EventError::MissingPlacementsPattern),
+                    };
+
+                    Ok(Event::AssignPlacementsToPhase {
+                        phase,
+                        operation: operation.into(),
+                        placements: placements.into(),
+                        force,
+                    })
+                }
+                ProjectCommand::SplitPhaseByFeederCapacity {
                     phase,
-                    operation: operation.into(),
-                    placements,
+                } => Ok(Event::SplitPhaseByFeederCapacity {
+                    phase,
+                }),
+                ProjectCommand::DeletePhase {
+                    phase,
+                    strategy,
+                } => Ok(Event::DeletePhase {
+                    reference: phase,
+                    strategy: strategy.into(),
+                }),
+                ProjectCommand::ClonePhase {
+                    phase,
+                    new_phase,
+                } => Ok(Event::ClonePhase {
+                    phase_reference: phase,
+                    new_reference: new_phase,
                 }),
                 ProjectCommand::SetPlacementOrdering {
                     phase,
@@ -676,7 +1155,91 @@ fn try_from(opts: Opts) -> Result<Self, Self::Error> {
                     phase,
                     placement_orderings,
                 }),
-                ProjectCommand::GenerateArtifacts {} => Ok(Event::GenerateArtifacts),
+                ProjectCommand::SetPhaseAssemblyWindow {
+                    phase,
+                    start,
+                    end,
+                } => Ok(Event::SetPhaseAssemblyWindow {
+                    phase,
+                    assembly_window: start
+                        .zip(end)
+                        .map(|(start, end)| AssemblyWindow {
+                            start,
+                            end,
+                        }),
+                }),
+                ProjectCommand::ApplySettings {
+                    unit_system,
+                    origin,
+                    rotation_direction,
+                    default_placement_orderings,
+                } => Ok(Event::ApplyProjectSettings {
+                    unit_system: unit_system.map(Into::into),
+                    origin: origin.map(Into::into),
+                    rotation_direction: rotation_direction.map(Into::into),
+                    default_placement_orderings,
+                }),
+                ProjectCommand::SetRotationOffset {
+                    package,
+                    manufacturer,
+                    mpn,
+                    offset,
+                } => {
+                    let target = match (package, manufacturer, mpn) {
+                        (Some(package), _, _) => RotationOffsetTarget::Package(package),
+                        (None, Some(manufacturer), Some(mpn)) => {
+                            RotationOffsetTarget::Part(Part::new(manufacturer, mpn))
+                        }
+                        _ => return Err(EventError::MissingRotationOffsetTarget),
+                    };
+
+                    Ok(Event::SetRotationOffset {
+                        target,
+                        offset,
+                    })
+                }
+                ProjectCommand::SetPlacementNotes {
+                    placement,
+                    notes,
+                } => Ok(Event::SetPlacementNotes {
+                    object_path: placement,
+                    notes,
+                }),
+                ProjectCommand::SetPlacementAttachments {
+                    placement,
+                    attachments,
+                } => Ok(Event::SetPlacementAttachments {
+                    object_path: placement,
+                    attachments,
+                }),
+                ProjectCommand::GenerateArtifacts {
+                    artifacts,
+                } => Ok(Event::GenerateArtifacts {
+                    token: None,
+                    artifacts: if artifacts.is_empty() {
+                        Artifact::all()
+                    } else {
+                        artifacts
+                            .into_iter()
+                            .map(Into::into)
+                            .collect()
+                    },
+                }),
+                ProjectCommand::GenerateHandoverReport {
+                    format,
+                } => Ok(Event::GenerateHandoverReport {
+                    format: format.into(),
+                }),
+                ProjectCommand::StartBuildRun {
+                    quantity,
+                } => Ok(Event::StartBuildRun {
+                    quantity,
+                }),
+                ProjectCommand::ActivateBuildRunInstance {
+                    instance,
+                } => Ok(Event::ActivateBuildRunInstance {
+                    instance,
+                }),
                 ProjectCommand::AssignFeederToLoadOutItem {
                     phase,
                     feeder_reference,
@@ -693,20 +1256,96 @@ fn try_from(opts: Opts) -> Result<Self, Self::Error> {
                     operation,
                     task,
                     action,
-                } => Ok(Event::RecordPhaseOperation {
-                    phase,
-                    operation: operation.into(),
-                    task: task.into(),
-                    action: action.into(),
-                }),
+                    serial_number,
+                    override_rules,
+                    parameters,
+                } => {
+                    // the values are converted to the kind of each parameter when the task is completed
+                    let parameter_values = parameters
+                        .into_iter()
+                        .map(|(name, value)| (name, TaskParameterValue::Text(value)))
+                        .collect::<BTreeMap<_, _>>();
+
+                    match serial_number {
+                        Some(serial_number) => Ok(Event::RecordUnitPhaseOperation {
+                            serial_number,
+                            phase,
+                            operation: operation.into(),
+                            task: task.into(),
+                            action: action.into(),
+                            overridden_rules: override_rules,
+                            parameter_values,
+                        }),
+                        None => Ok(Event::RecordPhaseOperation {
+                            phase,
+                            operation: operation.into(),
+                            task: task.into(),
+                            action: action.into(),
+                            overridden_rules: override_rules,
+                            parameter_values,
+                        }),
+                    }
+                }
                 ProjectCommand::RecordPlacementsOperation {
                     object_path_patterns,
+                    object_path_queries,
                     operation,
-                } => Ok(Event::RecordPlacementsOperation {
+                    serial_number,
+                } => {
+                    let object_path_patterns = object_path_patterns
+                        .into_iter()
+                        .map(ObjectPathPattern::Regex)
+                        .chain(
+                            object_path_queries
+                                .into_iter()
+                                .map(ObjectPathPattern::Query),
+                        )
+                        .collect::<Vec<_>>();
+
+                    match serial_number {
+                        Some(serial_number) => Ok(Event::RecordUnitPlacementsOperation {
+                            serial_number,
+                            object_path_patterns,
+                            operation: operation.into(),
+                        }),
+                        None => Ok(Event::RecordPlacementsOperation {
+                            placements: object_path_patterns
+                                .into_iter()
+                                .map(PlacementsSelector::Pattern)
+                                .collect(),
+                            operation: operation.into(),
+                        }),
+                    }
+                }
+                ProjectCommand::RecordPlacementsInspection {
                     object_path_patterns,
-                    operation: operation.into(),
+                    object_path_queries,
+                    result,
+                    notes,
+                } => Ok(Event::RecordPlacementsOperation {
+                    placements: object_path_patterns
+                        .into_iter()
+                        .map(ObjectPathPattern::Regex)
+                        .chain(
+                            object_path_queries
+                                .into_iter()
+                                .map(ObjectPathPattern::Query),
+                        )
+                        .map(PlacementsSelector::Pattern)
+                        .collect(),
+                    operation: PlacementOperation::Inspected {
+                        pass: result.is_pass(),
+                        notes,
+                    },
                 }),
                 ProjectCommand::ResetOperations {} => Ok(Event::ResetOperations {}),
+                ProjectCommand::MigrateLoadOuts {} => Ok(Event::MigrateLoadOuts {}),
+                ProjectCommand::Canonicalize {} => Ok(Event::SetProjectFileFormat {
+                    format: FileFormat::Canonical,
+                }),
+                ProjectCommand::Run {
+                    ..
+                } => Err(EventError::ScriptCommand),
             },
         }
     }
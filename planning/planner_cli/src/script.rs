@@ -0,0 +1,222 @@
+//! Scripts, for repeatable project setups.
+//!
+//! A script is a YAML, or JSON, file containing a list of events, using the serialized form of [`Event`], and
+//! optional variables, e.g.
+//!
+//! ```yaml
+//! variables:
+//!   side: top
+//! events:
+//!   - CreatePhase:
+//!       process: pnp
+//!       reference: "${side}_1"
+//!       load_out:
+//!         File: "load_out_${side}_1.csv"
+//!       pcb_side: Top
+//!   - SetPhaseOrdering:
+//!       phases: ["${side}_1"]
+//! ```
+//!
+//! `${<name>}` placeholders in any string value are replaced with the value of the variable, variables given on the
+//! command line override the variables in the script.
+//!
+//! The whole script is parsed and validated before any of the events are applied.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use planner_app::Event;
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ScriptError {
+    #[error("Unable to read script. cause: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Unable to parse script. cause: {0}")]
+    YamlError(#[from] serde_yaml::Error),
+    #[error("Unable to parse script. cause: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Unterminated placeholder. value: '{0}'")]
+    UnterminatedPlaceholder(String),
+    #[error("Unknown variable. name: '{0}'")]
+    UnknownVariable(String),
+    #[error("Invalid event. index: {index}, cause: {cause}")]
+    InvalidEvent { index: usize, cause: serde_json::Error },
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Script {
+    #[serde(default)]
+    variables: BTreeMap<String, String>,
+    events: Vec<Value>,
+}
+
+/// Loads the script, YAML is assumed unless the file has a `.json` extension.
+pub fn load_script(path: &Path, variables: &[(String, String)]) -> Result<Vec<Event>, ScriptError> {
+    let content = std::fs::read_to_string(path)?;
+
+    let script: Script = match path
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("json") => serde_json::from_str(&content)?,
+        _ => serde_yaml::from_str(&content)?,
+    };
+
+    build_events(script, variables)
+}
+
+fn build_events(script: Script, variables: &[(String, String)]) -> Result<Vec<Event>, ScriptError> {
+    let mut script_variables = script.variables;
+    script_variables.extend(variables.iter().cloned());
+
+    script
+        .events
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut value)| {
+            substitute_variables(&mut value, &script_variables)?;
+            serde_json::from_value::<Event>(value).map_err(|cause| ScriptError::InvalidEvent {
+                index,
+                cause,
+            })
+        })
+        .collect()
+}
+
+fn substitute_variables(value: &mut Value, variables: &BTreeMap<String, String>) -> Result<(), ScriptError> {
+    match value {
+        Value::String(string) => {
+            *string = substitute_string(string, variables)?;
+        }
+        Value::Array(values) => {
+            for value in values {
+                substitute_variables(value, variables)?;
+            }
+        }
+        Value::Object(map) => {
+            for value in map.values_mut() {
+                substitute_variables(value, variables)?;
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+    Ok(())
+}
+
+fn substitute_string(value: &str, variables: &BTreeMap<String, String>) -> Result<String, ScriptError> {
+    let mut result = String::new();
+    let mut remainder = value;
+
+    while let Some(start) = remainder.find("${") {
+        result.push_str(&remainder[..start]);
+
+        let end = remainder[start..]
+            .find('}')
+            .ok_or(ScriptError::UnterminatedPlaceholder(value.to_string()))?
+            + start;
+
+        let name = &remainder[start + 2..end];
+        let variable = variables
+            .get(name)
+            .ok_or(ScriptError::UnknownVariable(name.to_string()))?;
+        result.push_str(variable);
+
+        remainder = &remainder[end + 1..];
+    }
+    result.push_str(remainder);
+
+    Ok(result)
+}
+
+/// e.g. 'side=top'
+pub fn parse_variable_kv(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("Missing value in '{}', expected '<name>=<value>'", s))
+}
+
+#[cfg(test)]
+mod script_tests {
+    use std::path::PathBuf;
+
+    use indoc::indoc;
+    use planner_app::{PhaseReference, ProcessReference};
+    use stores::load_out::LoadOutSource;
+
+    use super::*;
+
+    #[test]
+    pub fn build_events_with_variables() {
+        // given
+        let script: Script = serde_yaml::from_str(indoc! {r#"
+            variables:
+              side: top
+              process: manual
+            events:
+              - CreatePhase:
+                  process: "${process}"
+                  reference: "${side}_1"
+                  load_out:
+                    File: "load_out_${side}_1.csv"
+                  pcb_side: Top
+              - SetPhaseOrdering:
+                  phases: ["${side}_1"]
+        "#})
+        .unwrap();
+
+        // and the command line overrides the script
+        let variables = vec![("process".to_string(), "pnp".to_string())];
+
+        // when
+        let events = build_events(script, &variables).unwrap();
+
+        // then
+        assert_eq!(events.len(), 2);
+        assert!(
+            matches!(&events[0], Event::CreatePhase { process, reference, load_out, .. }
+                if *process == ProcessReference::from_raw_str("pnp")
+                && *reference == PhaseReference::from_raw_str("top_1")
+                && *load_out == LoadOutSource::File(PathBuf::from("load_out_top_1.csv"))
+            )
+        );
+        assert!(matches!(&events[1], Event::SetPhaseOrdering { phases }
+            if *phases == vec![PhaseReference::from_raw_str("top_1")]
+        ));
+    }
+
+    #[test]
+    pub fn unknown_variable() {
+        // given
+        let script: Script =
+            serde_json::from_str(r#"{"events": [{"DeletePhase": {"reference": "${side}_1"}}]}"#).unwrap();
+
+        // when
+        let result = build_events(script, &[]);
+
+        // then
+        assert!(matches!(result, Err(ScriptError::UnknownVariable(name)) if name == "side"));
+    }
+
+    #[test]
+    pub fn invalid_event_is_reported_before_any_events_are_applied() {
+        // given
+        let script: Script =
+            serde_json::from_str(r#"{"events": [{"DeletePhase": {"reference": "top_1"}}, {"UnknownEvent": {}}]}"#)
+                .unwrap();
+
+        // when
+        let result = build_events(script, &[]);
+
+        // then
+        assert!(matches!(
+            result,
+            Err(ScriptError::InvalidEvent {
+                index: 1,
+                ..
+            })
+        ));
+    }
+}
@@ -3504,7 +3504,7 @@ fn no_args() {
 
         // and
         let expected_output = indoc! {"
-            Usage: planner_cli [OPTIONS] <COMMAND>
+            Usage: planner_cli [OPTIONS] [COMMAND]
 
             Commands:
               project  Project mode
@@ -3512,11 +3512,13 @@ fn no_args() {
               help     Print this message or the help of the given subcommand(s)
 
             Options:
-                  --trace [<TRACE>]  Trace log file
-              -v, --verbose...       Increase logging verbosity
-              -q, --quiet...         Decrease logging verbosity
-              -h, --help             Print help
-              -V, --version          Print version
+                  --recent                       List the recently opened projects, pinned projects first
+                  --trace [<TRACE>]              Trace log file
+                  --record-events <REPLAY_FILE>  Record the processed events to a replay file, for bug reports
+              -v, --verbose...                   Increase logging verbosity
+              -q, --quiet...                     Decrease logging verbosity
+              -h, --help                         Print help
+              -V, --version                      Print version
         "};
         // when
         cmd
@@ -3545,9 +3547,11 @@ fn no_args() {
                 Usage: planner_cli pcb [OPTIONS] --pcb-file <PCB_FILE> <COMMAND>
 
                 Commands:
-                  create                  Create a PCB file
-                  configure-panel-sizing  Configure a PCB
-                  help                    Print this message or the help of the given subcommand(s)
+                  create                    Create a PCB file
+                  configure-panel-sizing    Configure a PCB
+                  import-unit-positionings  Import the unit positionings of a PCB from a CSV file, e.g. a panel drawing's unit origin table
+                  clone                     Save a copy of a PCB file, alongside the PCB file
+                  help                      Print this message or the help of the given subcommand(s)
 
                 Options:
                       --pcb-file <PCB_FILE>  Specify a PCB context
@@ -3651,21 +3655,49 @@ fn no_args() {
                 
                 Commands:
                   create                          Create a new job
+                  create-from-template            Create a new job from a template
+                  export-archive                  Export the project and all the files it references to a zip archive
+                  import-archive                  Import a project from a zip archive, into the project path
+                  relink-files                    Find missing PCB, gerber and load-out files, and relink them
+                  restore-recovery                Restore the unsaved changes of the recovery snapshot, e.g. after the GUI exited unexpectedly
+                  discard-recovery                Discard the unsaved changes of the recovery snapshot
                   add-pcb                         Add a PCB file to the project
                   remove-pcb                      Remove a PCB from the project
                   assign-variant-to-unit          Assign a design variant to a PCB unit
+                  assign-serial-numbers           Assign serial numbers to the units of a PCB that do not already have one
                   refresh-from-design-variants    Refresh from design variants
                   create-process-from-preset      Create a process from presets
                   delete-process                  Delete a process from the project
+                  clone-process                   Copy a process
                   assign-process-to-parts         Assign a process to parts
+                  set-part-moisture-sensitivity   Set, or clear, the moisture sensitivity level (MSL) of parts
+                  record-part-moisture-event      Record a moisture event (reel opened, baked) for parts, at the current time
+                  import-part-aliases             Import part alias groups from the 'AliasGroup' column of a parts file
+                  import-moisture-sensitivities   Import the moisture sensitivity of parts from the 'Msl' and 'FloorLifeHours' columns of a parts file
+                  import-csv-into-store           Import the placements and load-out CSV files, and optionally a parts file, into the project's store backend
                   create-phase                    Create a phase
                   assign-placements-to-phase      Assign placements to a phase
+                  split-phase-by-feeder-capacity  Split a phase into a phase for each pass when it requires more feeders than the machine has feeder slots
+                  delete-phase                    Delete a phase
+                  clone-phase                     Copy a phase, including its load-out, without placements
                   assign-feeder-to-load-out-item  Assign feeder to load-out item
+                  migrate-load-outs               Upgrade the load-out files of all phases to the latest format
+                  canonicalize                    Rewrite the project file using the canonical, diff-friendly, format, which is used for all subsequent saves
                   set-placement-ordering          Set placement ordering for a phase
+                  set-phase-assembly-window       Set, or clear, the scheduled assembly window of a phase
+                  apply-settings                  Apply the units, coordinate and rotation conventions used by the generated artifacts
+                  set-rotation-offset             Set, or clear, the rotation offset for a package or a part
+                  set-placement-notes             Set, or clear, the operator notes of a placement
+                  set-placement-attachments       Set, or clear, the attachments of a placement, e.g. photos
                   generate-artifacts              Generate artifacts
+                  generate-handover-report        Generate a shift-handover report, a snapshot of the work in progress
+                  start-build-run                 Start a build run, each build of the run has its own placement and operation statuses
+                  activate-build-run-instance     Activate a build of the current build run
                   record-phase-operation          Record phase operation
                   record-placements-operation     Record placements operation
+                  record-placements-inspection    Record the inspection result of placed placements
                   reset-operations                Reset operations
+                  run                             Run a script of events, the project is only saved if all the events succeed
                   help                            Print this message or the help of the given subcommand(s)
                 
                 Options:
@@ -3713,6 +3745,115 @@ fn help_for_create() {
                 .stdout(print("stdout").and(predicate::str::diff(expected_output)));
         }
 
+        #[test]
+        fn help_for_create_from_template() {
+            // given
+            let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner_cli"));
+
+            // and
+            let expected_output = indoc! {"
+                Create a new job from a template
+
+                Usage: planner_cli project --project <PROJECT_NAME> create-from-template [OPTIONS] --template <TEMPLATE>
+
+                Options:
+                      --template <TEMPLATE>  The template directory, containing a 'template.json' file
+                  -v, --verbose...           Increase logging verbosity
+                  -q, --quiet...             Decrease logging verbosity
+                  -h, --help                 Print help
+            "};
+
+            // when
+            cmd.args(["project", "create-from-template", "--help"])
+                // then
+                .assert()
+                .success()
+                .stderr(print("stderr"))
+                .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+        }
+
+        #[test]
+        fn help_for_export_archive() {
+            // given
+            let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner_cli"));
+
+            // and
+            let expected_output = indoc! {"
+                Export the project and all the files it references to a zip archive
+
+                Usage: planner_cli project --project <PROJECT_NAME> export-archive [OPTIONS] --archive <ARCHIVE>
+
+                Options:
+                      --archive <ARCHIVE>  The path of the zip archive
+                  -v, --verbose...         Increase logging verbosity
+                  -q, --quiet...           Decrease logging verbosity
+                  -h, --help               Print help
+            "};
+
+            // when
+            cmd.args(["project", "export-archive", "--help"])
+                // then
+                .assert()
+                .success()
+                .stderr(print("stderr"))
+                .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+        }
+
+        #[test]
+        fn help_for_import_archive() {
+            // given
+            let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner_cli"));
+
+            // and
+            let expected_output = indoc! {"
+                Import a project from a zip archive, into the project path
+
+                Usage: planner_cli project --project <PROJECT_NAME> import-archive [OPTIONS] --archive <ARCHIVE>
+
+                Options:
+                      --archive <ARCHIVE>  The path of the zip archive
+                  -v, --verbose...         Increase logging verbosity
+                  -q, --quiet...           Decrease logging verbosity
+                  -h, --help               Print help
+            "};
+
+            // when
+            cmd.args(["project", "import-archive", "--help"])
+                // then
+                .assert()
+                .success()
+                .stderr(print("stderr"))
+                .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+        }
+
+        #[test]
+        fn help_for_relink_files() {
+            // given
+            let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner_cli"));
+
+            // and
+            let expected_output = indoc! {"
+                Find missing PCB, gerber and load-out files, and relink them
+
+                Usage: planner_cli project --project <PROJECT_NAME> relink-files [OPTIONS] --search-roots <DIRECTORY>...
+
+                Options:
+                      --search-roots <DIRECTORY>...  Directories to search, recursively, e.g. 'gerbers,pcbs'
+                      --apply                        Relink files that have exactly one candidate, otherwise the candidates are just logged
+                  -v, --verbose...                   Increase logging verbosity
+                  -q, --quiet...                     Decrease logging verbosity
+                  -h, --help                         Print help
+            "};
+
+            // when
+            cmd.args(["project", "relink-files", "--help"])
+                // then
+                .assert()
+                .success()
+                .stderr(print("stderr"))
+                .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+        }
+
         #[test]
         fn help_for_add_pcb() {
             // given
@@ -3752,10 +3893,11 @@ fn help_for_remove_pcb() {
                 Usage: planner_cli project --project <PROJECT_NAME> remove-pcb [OPTIONS] --index <INDEX>
 
                 Options:
-                      --index <INDEX>  The zero-based index of the PCB
-                  -v, --verbose...     Increase logging verbosity
-                  -q, --quiet...       Decrease logging verbosity
-                  -h, --help           Print help
+                      --index <INDEX>        The zero-based index of the PCB
+                      --strategy <STRATEGY>  How unit assignments and placements of the PCB are handled, 'cascade' removes them too [default: block] [possible values: block, cascade, orphan]
+                  -v, --verbose...           Increase logging verbosity
+                  -q, --quiet...             Decrease logging verbosity
+                  -h, --help                 Print help
             "};
 
             // when
@@ -3767,6 +3909,34 @@ fn help_for_remove_pcb() {
                 .stdout(print("stdout").and(predicate::str::diff(expected_output)));
         }
 
+        #[test]
+        fn help_for_delete_phase() {
+            // given
+            let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner_cli"));
+
+            // and
+            let expected_output = indoc! {"
+                Delete a phase
+
+                Usage: planner_cli project --project <PROJECT_NAME> delete-phase [OPTIONS] --phase <PHASE>
+
+                Options:
+                      --phase <PHASE>        Phase reference (e.g. 'top_1')
+                      --strategy <STRATEGY>  How assigned placements are handled, 'orphan' unassigns them, 'cascade' also resets their status [default: block] [possible values: block, cascade, orphan]
+                  -v, --verbose...           Increase logging verbosity
+                  -q, --quiet...             Decrease logging verbosity
+                  -h, --help                 Print help
+            "};
+
+            // when
+            cmd.args(["project", "delete-phase", "--help"])
+                // then
+                .assert()
+                .success()
+                .stderr(print("stderr"))
+                .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+        }
+
         #[test]
         fn help_for_assign_variant_to_unit() {
             // given
@@ -3944,15 +4114,17 @@ fn help_for_assign_placements_to_phase() {
             let expected_output = indoc! {"
                 Assign placements to a phase
 
-                Usage: planner_cli project --project <PROJECT_NAME> assign-placements-to-phase [OPTIONS] --phase <PHASE> --operation <OPERATION> --placements <PLACEMENTS>
+                Usage: planner_cli project --project <PROJECT_NAME> assign-placements-to-phase [OPTIONS] --phase <PHASE> --operation <OPERATION>
 
                 Options:
-                      --phase <PHASE>            Phase reference (e.g. 'top_1')
-                      --operation <OPERATION>    Operation [possible values: set, clear]
-                      --placements <PLACEMENTS>  Placements object path pattern (regexp)
-                  -v, --verbose...               Increase logging verbosity
-                  -q, --quiet...                 Decrease logging verbosity
-                  -h, --help                     Print help
+                      --phase <PHASE>             Phase reference (e.g. 'top_1')
+                      --operation <OPERATION>     Operation [possible values: set, clear]
+                      --placements <PLACEMENTS>   Placements object path pattern (regexp)
+                      --placements-query <QUERY>  Placements object path query (e.g. 'pcb=1, unit=3..8, ref_des=R*')
+                      --force                     Also assign placements on the other PCB side (e.g. through-hole parts)
+                  -v, --verbose...                Increase logging verbosity
+                  -q, --quiet...                  Decrease logging verbosity
+                  -h, --help                      Print help
             "};
 
             // when
@@ -4039,9 +4211,10 @@ fn help_for_generate_artifacts() {
                 Usage: planner_cli project --project <PROJECT_NAME> generate-artifacts [OPTIONS]
 
                 Options:
-                  -v, --verbose...  Increase logging verbosity
-                  -q, --quiet...    Decrease logging verbosity
-                  -h, --help        Print help
+                      --artifacts <ARTIFACTS>  The artifacts to generate in addition to the phase placements and the report, omit to generate all of them [possible values: inspection-checklist, stencil-setup-sheet, machine-job, labels, analytics]
+                  -v, --verbose...             Increase logging verbosity
+                  -q, --quiet...               Decrease logging verbosity
+                  -h, --help                   Print help
             "};
 
             // when
@@ -4092,11 +4265,13 @@ fn help_for_record_placements_operation() {
             let expected_output = indoc! {"
                 Record placements operation
 
-                Usage: planner_cli project --project <PROJECT_NAME> record-placements-operation [OPTIONS] --object-path-patterns <OBJECT_PATH_PATTERNS>... --operation <OPERATION>
+                Usage: planner_cli project --project <PROJECT_NAME> record-placements-operation [OPTIONS] --operation <OPERATION>
 
                 Options:
                       --object-path-patterns <OBJECT_PATH_PATTERNS>...
                           List of reference designators to apply the operation to
+                      --object-path-queries <QUERY>
+                          Object path queries to apply the operation to (e.g. 'pcb=1, unit=3..8, ref_des=R*'), repeat for each query
                       --operation <OPERATION>
                           The completed operation to apply [possible values: placed, skipped, reset]
                   -v, --verbose...
@@ -4144,6 +4319,57 @@ fn help_for_reset_operations() {
     }
 }
 
+mod recent {
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::print;
+
+    #[test]
+    fn list_recent_projects() {
+        // given
+        let temp_dir = tempdir().unwrap();
+        let recent_projects_path = temp_dir
+            .path()
+            .join("recent_projects.json");
+        std::fs::write(&recent_projects_path, indoc! {r#"
+            [
+              {
+                "path": "projects/project-job1.mpnp.json",
+                "name": "job1",
+                "last_opened": "2025-06-01T10:00:00Z",
+                "pinned": false
+              },
+              {
+                "path": "projects/project-job2.mpnp.json",
+                "name": "job2",
+                "last_opened": "2025-05-01T10:00:00Z",
+                "pinned": true
+              }
+            ]
+        "#})
+        .unwrap();
+
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner_cli"));
+
+        // and
+        let expected_output = indoc! {"
+            * 2025-05-01 job2 projects/project-job2.mpnp.json
+              2025-06-01 job1 projects/project-job1.mpnp.json
+        "};
+
+        // when
+        cmd.env("MAKERPNP_RECENT_PROJECTS", &recent_projects_path)
+            .args(["--recent"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+}
+
 /// calculate the offset for the bottom left position of a unit.
 ///
 /// assumes the routing gap fully surrounds each unit
@@ -8,13 +8,42 @@ language-en-US = English (United States)
 
 menu-top-level-file = File
 menu-item-quit = Quit
+menu-item-open-recent = Open Recent
 
 modal-errors-title = Errors - { $file }
+modal-warnings-title = Warnings - { $file }
+modal-warnings-column-index = Index
+modal-warnings-column-date-time = Date/time
+modal-warnings-column-warnings = Warnings
+modal-delete-report-title = Deleted - { $file }
+modal-delete-report-column-change = Change
+modal-delete-report-column-item = Item
+modal-delete-report-unit-assignment-removed = Unit assignment removed
+modal-delete-report-placement-deleted = Placement deleted
+modal-delete-report-placement-unassigned = Placement unassigned
+modal-delete-report-placement-reset = Placement reset
+modal-delete-report-placement-renumbered = Placement renumbered
+modal-delete-report-operation-history = The phase had recorded operations.
+modal-recovery-title = Unsaved changes - { $file }
+modal-recovery-message = The project has unsaved changes from a previous session, restore them, or discard them?
+modal-recovery-button-restore = Restore
+modal-recovery-button-discard = Discard
+modal-confirmation-dont-ask-again = Don't ask again
+modal-confirmation-close-project-title = Close project - { $file }
+modal-confirmation-close-project-message = The project has unsaved changes, close it anyway?
+modal-confirmation-close-pcb-title = Close PCB - { $file }
+modal-confirmation-close-pcb-message = The PCB has unsaved changes, close it anyway?
+modal-confirmation-delete-phase-title = Delete phase - { $phase }
+modal-confirmation-delete-phase-message = Delete the phase?
 modal-add-phase-title = Add phase - { $file }
 modal-package-sources-title = Package sources - { $file }
 modal-create-unit-assignment-title = Create unit assignment - { $file }
 modal-phase-placement-orderings-title = Phase placement orderings - { $phase }
+modal-task-parameters-title = Complete task - { $task }
 modal-manager-gerbers-title = Manage gerbers - { $design }
+modal-manager-gerbers-button-import-outline = Import outline
+modal-command-palette-filter-hint = Type a command name
+modal-command-palette-no-matches = No matching commands
 
 toolbar-button-home = Home
 toolbar-button-new-project = New project
@@ -26,13 +55,21 @@ toolbar-button-close-all = Close all
 
 project-toolbar-button-show-explorer = Show explorer
 project-toolbar-button-generate-artifacts = Generate artifacts
+project-toolbar-button-artifacts = Artifacts
 project-toolbar-button-refresh = Refresh
+project-toolbar-button-cancel-refresh = Cancel refresh
 project-toolbar-button-remove-unused-placements = Remove unused placements
 project-toolbar-button-add-pcb = Add PCB
 project-toolbar-button-add-phase = Add phase
 project-toolbar-button-package-sources = Package sources
 project-toolbar-button-reset-operations = Reset operations
 
+artifact-inspection-checklist = Inspection checklists
+artifact-stencil-setup-sheet = Stencil setup sheets
+artifact-machine-job = Machine jobs
+artifact-labels = Labels
+artifact-analytics = Analytics
+
 project-pcb-toolbar-button-create-unit-assignment = Create unit assignment
 project-pcb-toolbar-button-show-pcb = Show PCB
 
@@ -42,6 +79,54 @@ tab-label-new-pcb = New PCB
 
 home-banner = MakerPnP - Planner
 home-checkbox-label-show-on-startup = Show on startup
+home-recent-projects-header = Recent projects
+home-recent-projects-none = No recent projects
+home-recent-projects-open-hover-text = Open the project
+home-recent-projects-pin-hover-text = Pin, pinned projects are listed first and are never removed automatically
+home-recent-projects-unpin-hover-text = Unpin
+home-recent-projects-remove-hover-text = Remove from the recent projects
+home-key-bindings-header = Keyboard shortcuts
+home-key-bindings-unbound = None
+home-key-bindings-recording = Press a key with Ctrl or Alt, or a function key...
+home-key-bindings-record-hover-text = Click, then press the new shortcut, escape cancels
+home-key-bindings-clear-hover-text = Remove the shortcut
+home-key-bindings-reset = Reset
+home-key-bindings-reset-hover-text = Restore the default shortcut
+home-profile-header = Profile
+home-profile-new-hint = New profile name
+home-profile-add = Add
+home-profile-add-hover-text = Add a profile with the default settings and switch to it
+home-confirmations-header = Confirmations
+home-confirmations-close-modified-project = Ask before closing a modified project
+home-confirmations-close-modified-pcb = Ask before closing a modified PCB
+home-confirmations-remove-phase = Ask before deleting a phase
+
+home-machine-header = Machine
+home-machine-none = No machine definition
+home-machine-choose = Choose...
+home-machine-reload = Reload
+home-machine-clear-hover-text = Forget the machine definition
+home-machine-name = Name
+home-machine-kinematics = Kinematics
+home-machine-kinematics-cartesian = Cartesian
+home-machine-kinematics-corexy = CoreXY
+home-machine-homing-order = Homing order
+home-machine-axis = Axis
+home-machine-axis-kind = Kind
+home-machine-axis-kind-linear = Linear (mm)
+home-machine-axis-kind-rotary = Rotary (°)
+home-machine-axis-steps-per-unit = Steps per unit
+home-machine-axis-soft-limits = Soft limits
+home-machine-axis-unlimited = Unlimited
+home-machine-axis-max-velocity = Maximum velocity (units/s)
+home-machine-axis-max-acceleration = Maximum acceleration (units/s²)
+
+command-save = Save
+command-open-project = Open project
+command-generate-artifacts = Generate artifacts
+command-next-placement = Next placement
+command-toggle-home-tab = Toggle home tab
+command-command-palette = Command palette
 
 new-project-banner = New project
 form-new-project-input-name = Project name
@@ -57,8 +142,19 @@ project-overview-detail-name = Name: { $name }
 project-overview-phases-header = Phases
 project-overview-pcbs-header = PCBs
 project-overview-pcbs-input-remove-disabled-hover-text-in-use-1 = In use - remove PCB unit assignments.
+project-overview-build-run-header = Build run
+project-overview-build-run-none = No build run has been started.
+project-overview-build-run-detail = Run { $run }, build { $instance } of { $quantity } active, { $completed } completed.
+project-overview-build-run-instance = Build
+project-overview-build-run-button-activate = Activate
+project-overview-build-run-quantity = Quantity
+project-overview-build-run-button-start = Start run
 
 project-pcb-designs-header = Designs
+project-pcb-gerbers-header = Gerbers
+pcb-thumbnail-no-gerbers = No gerbers to preview
+pcb-thumbnail-error = Unable to render the preview
+project-pcb-preview-header = Preview
 
 project-placements-tab-label = Placements
 project-placements-tab-phase-hover-text-no-phases = No phases defined.
@@ -75,6 +171,9 @@ project-unit-assignments-tab-label = Unit assignments ({ $pcb })
 
 phase-toolbar-add-parts-to-loadout = Add parts to load-out.
 phase-toolbar-placement-orderings = Placement orderings.
+phase-toolbar-override-rules = Override rules.
+phase-toolbar-override-rules-count = Override rules ({ $count }).
+phase-toolbar-override-rules-help = The selected rules are not enforced for the next task action, the overrides are recorded.
 
 phase-reference = Reference
 phase-load-out-source = Load-out source
@@ -93,8 +192,13 @@ project-explorer-tab-label = Project explorer
 
 project-explorer-node-root = Overview
 project-explorer-node-issues = Issues
-project-explorer-node-parts = Parts
-project-explorer-node-placements = Placements
+project-explorer-node-parts = Parts ({ $count })
+project-explorer-node-parts-process = { $process } ({ $count })
+project-explorer-node-parts-unassigned = No process ({ $count })
+project-explorer-node-placements = Placements ({ $complete }/{ $count }, { $percentage }%)
+project-explorer-node-placements-phase = { $phase } ({ $complete }/{ $count }, { $percentage }%)
+project-explorer-node-placements-unassigned = No phase ({ $complete }/{ $count }, { $percentage }%)
+project-explorer-node-placements-status = { $status } ({ $count })
 project-explorer-node-phases = Phases
 project-explorer-node-phase = { $reference } ({ $process } - { $pcb_side })
 project-explorer-node-phase-loadout = { $source }
@@ -145,6 +249,15 @@ pcb-gerber-viewer-tab-label-panel = Panel
 pcb-gerber-viewer-tab-label-design = Design ({ $index })
 pcb-gerber-viewer-layers-window-title = Layers
 pcb-gerber-viewer-input-go-to = Go to
+pcb-gerber-viewer-layer-preset = Preset
+
+layer-preset-top-assembly = Top assembly
+layer-preset-bottom-assembly = Bottom assembly
+layer-preset-top-paste = Top paste
+layer-preset-bottom-paste = Bottom paste
+layer-preset-top-copper = Top copper
+layer-preset-bottom-copper = Bottom copper
+layer-preset-all = All layers
 
 form-configure-pcb-input-units = Units
 form-configure-pcb-input-gerber-offset = Gerber offset
@@ -258,6 +371,7 @@ placement-project-status-unused = Unused
 sort-mode-area = Area
 sort-mode-feeder-reference = Feeder reference
 sort-mode-height = Height
+sort-mode-nozzle = Nozzle
 sort-mode-part = Part
 sort-mode-pcb = PCB instance
 sort-mode-pcb-unit = PCB unit
@@ -303,6 +417,10 @@ table-parts-column-mpn = MPN
 table-parts-column-processes = Processes
 table-parts-column-ref-des-set = Ref. Des. Set
 table-parts-column-quantity = Quantity
+table-parts-column-package = Package
+table-parts-package-size = Size (mm)
+table-parts-package-pitch = Pitch (mm)
+table-parts-package-pin1-orientation = Pin 1 orientation
 
 table-designs-column-index = #
 table-designs-column-name = Name
@@ -368,6 +486,7 @@ table-pcbs-column-actions = Actions
 
 table-issues-column-index = #
 table-issues-column-severity = Severity
+table-issues-column-code = Code
 table-issues-column-message = Message
 table-issues-column-details = Details
 table-issues-column-actions = Actions
@@ -390,6 +509,53 @@ expanding-header-details = Details
 
 process-error-name-already-in-use = Attempted to rename a process to a name already in use
 
+app-error-operation-requires-project = Operation requires a project
+app-error-operation = Operation error. { $cause }
+app-error-phase = Phase error. { $cause }
+app-error-project = Project error. { $cause }
+app-error-template = Template error. { $cause }
+app-error-archive = Archive error. { $cause }
+app-error-process = Process error. { $cause }
+app-error-part = Part error. manufacturer: { $manufacturer }, mpn: { $mpn }
+app-error-source = Source error. { $cause }
+app-error-loadout = Load-out error. { $cause }
+app-error-pcb-operation = PCB error. { $cause }
+app-error-io = IO error. { $cause }
+app-error-inspection = Inspection error. { $cause }
+app-error-cancelled = Operation cancelled
+app-error-vision = Vision error. { $cause }
+app-error-serial-number = Serial number error. { $cause }
+app-error-placement-session-required = Operation requires a placement session
+app-error-placement-session-complete = Placement session has no more pending placements
+app-error-unknown-phase-reference = Unknown phase. phase: { $phase }
+app-error-unknown-process-reference = Unknown process. process: { $process }
+app-error-unknown-placement-selection = Unknown placement selection. selection: { $selection }
+app-error-unknown-placement = Unknown placement. object path: { $object_path }
+
+#
+# warnings
+#
+
+app-warning-unremoved-gerbers = { $count ->
+        [one] 1 gerber could not be removed
+       *[other] { $count } gerbers could not be removed
+    }. PCB: { $pcb_file }
+app-warning-unreadable-recovery-file = Ignoring unreadable recovery file. file: { $file }, cause: { $cause }
+app-warning-unreadable-profile-gerber = Unable to load the profile gerber, using the panel size. file: { $file }, cause: { $cause }
+app-warning-load-out-unavailable-for-rules = Unable to load the load-out, process rules use an empty load-out. phase: { $phase }, cause: { $cause }
+app-warning-placements-on-other-pcb-side = { $count ->
+        [one] 1 placement was not assigned
+       *[other] { $count } placements were not assigned
+    }, they are not on the PCB side of the phase. phase: { $phase }, side: { $pcb_side }, placements: { $placements }
+app-warning-placement-warnings = { $count ->
+        [one] 1 placement problem
+       *[other] { $count } placement problems
+    }, the placements were imported regardless. design variant: { $design_variant }, problems: { $warnings }
+app-warning-unknown-parts = { $count ->
+        [one] 1 part was ignored
+       *[other] { $count } parts were ignored
+    }, they are not used by the project. parts: { $parts }
+
 #
 # egui-data-tables
 #
@@ -8,13 +8,42 @@ language-en-US = English (United States)
 
 menu-top-level-file = Archivo
 menu-item-quit = Salir
+menu-item-open-recent = Abrir reciente
 
 modal-errors-title = Errores - { $file }
+modal-warnings-title = Advertencias - { $file }
+modal-warnings-column-index = Índice
+modal-warnings-column-date-time = Fecha/hora
+modal-warnings-column-warnings = Advertencias
+modal-delete-report-title = Eliminado - { $file }
+modal-delete-report-column-change = Cambio
+modal-delete-report-column-item = Elemento
+modal-delete-report-unit-assignment-removed = Asignación de unidad eliminada
+modal-delete-report-placement-deleted = Colocación eliminada
+modal-delete-report-placement-unassigned = Colocación desasignada
+modal-delete-report-placement-reset = Colocación restablecida
+modal-delete-report-placement-renumbered = Colocación renumerada
+modal-delete-report-operation-history = La fase tenía operaciones registradas.
+modal-recovery-title = Cambios sin guardar - { $file }
+modal-recovery-message = El proyecto tiene cambios sin guardar de una sesión anterior, ¿restaurarlos o descartarlos?
+modal-recovery-button-restore = Restaurar
+modal-recovery-button-discard = Descartar
+modal-confirmation-dont-ask-again = No volver a preguntar
+modal-confirmation-close-project-title = Cerrar proyecto - { $file }
+modal-confirmation-close-project-message = El proyecto tiene cambios sin guardar, ¿cerrarlo de todos modos?
+modal-confirmation-close-pcb-title = Cerrar PCB - { $file }
+modal-confirmation-close-pcb-message = La PCB tiene cambios sin guardar, ¿cerrarla de todos modos?
+modal-confirmation-delete-phase-title = Eliminar fase - { $phase }
+modal-confirmation-delete-phase-message = ¿Eliminar la fase?
 modal-add-phase-title = Añadir fase - { $file }
 modal-package-sources-title = Fuentes de envases - { $file }
 modal-create-unit-assignment-title = Crear asignación de unidad - { $file }
 modal-phase-placement-orderings-title = Ordenación de la colocación de fases - { $phase }
+modal-task-parameters-title = Completar tarea - { $task }
 modal-manager-gerbers-title = Gestionar gerbers  - { $design }
+modal-manager-gerbers-button-import-outline = Importar contorno
+modal-command-palette-filter-hint = Escriba el nombre de un comando
+modal-command-palette-no-matches = No hay comandos coincidentes
 
 toolbar-button-home = Inicio
 toolbar-button-new-project = Nuevo proyecto
@@ -26,13 +55,21 @@ toolbar-button-close-all = Cerrar todo
 
 project-toolbar-button-show-explorer = Mostrar explorador
 project-toolbar-button-generate-artifacts = Generar artefactos
+project-toolbar-button-artifacts = Artefactos
 project-toolbar-button-refresh = Actualizar
+project-toolbar-button-cancel-refresh = Cancelar actualización
 project-toolbar-button-remove-unused-placements = Eliminar ubicaciones no utilizadas
 project-toolbar-button-add-pcb = Añadir placa
 project-toolbar-button-add-phase = Añadir fase
 project-toolbar-button-package-sources = Fuentes de envases
 project-toolbar-button-reset-operations = Restablecer operaciones
 
+artifact-inspection-checklist = Listas de inspección
+artifact-stencil-setup-sheet = Hojas de configuración de plantilla
+artifact-machine-job = Trabajos de máquina
+artifact-labels = Etiquetas
+artifact-analytics = Analíticas
+
 project-pcb-toolbar-button-create-unit-assignment = Crear asignacion de unidad
 project-pcb-toolbar-button-show-pcb = Mostrar Placa
 
@@ -42,6 +79,54 @@ tab-label-new-pcb = New placa
 
 home-banner = MakerPnP - Planner
 home-checkbox-label-show-on-startup = Mostrar al inicio
+home-recent-projects-header = Proyectos recientes
+home-recent-projects-none = No hay proyectos recientes
+home-recent-projects-open-hover-text = Abrir el proyecto
+home-recent-projects-pin-hover-text = Fijar, los proyectos fijados se muestran primero y nunca se eliminan automáticamente
+home-recent-projects-unpin-hover-text = Desfijar
+home-recent-projects-remove-hover-text = Quitar de los proyectos recientes
+home-key-bindings-header = Atajos de teclado
+home-key-bindings-unbound = Ninguno
+home-key-bindings-recording = Pulse una tecla con Ctrl o Alt, o una tecla de función...
+home-key-bindings-record-hover-text = Haga clic y pulse el nuevo atajo, escape cancela
+home-key-bindings-clear-hover-text = Quitar el atajo
+home-key-bindings-reset = Restablecer
+home-key-bindings-reset-hover-text = Restaurar el atajo predeterminado
+home-profile-header = Perfil
+home-profile-new-hint = Nombre del nuevo perfil
+home-profile-add = Añadir
+home-profile-add-hover-text = Añadir un perfil con la configuración predeterminada y cambiar a él
+home-confirmations-header = Confirmaciones
+home-confirmations-close-modified-project = Preguntar antes de cerrar un proyecto modificado
+home-confirmations-close-modified-pcb = Preguntar antes de cerrar una PCB modificada
+home-confirmations-remove-phase = Preguntar antes de eliminar una fase
+
+home-machine-header = Máquina
+home-machine-none = Sin definición de máquina
+home-machine-choose = Elegir...
+home-machine-reload = Recargar
+home-machine-clear-hover-text = Olvidar la definición de máquina
+home-machine-name = Nombre
+home-machine-kinematics = Cinemática
+home-machine-kinematics-cartesian = Cartesiana
+home-machine-kinematics-corexy = CoreXY
+home-machine-homing-order = Orden de referenciado
+home-machine-axis = Eje
+home-machine-axis-kind = Tipo
+home-machine-axis-kind-linear = Lineal (mm)
+home-machine-axis-kind-rotary = Rotativo (°)
+home-machine-axis-steps-per-unit = Pasos por unidad
+home-machine-axis-soft-limits = Límites de software
+home-machine-axis-unlimited = Sin límites
+home-machine-axis-max-velocity = Velocidad máxima (unidades/s)
+home-machine-axis-max-acceleration = Aceleración máxima (unidades/s²)
+
+command-save = Guardar
+command-open-project = Abrir proyecto
+command-generate-artifacts = Generar artefactos
+command-next-placement = Siguiente colocación
+command-toggle-home-tab = Alternar pestaña de inicio
+command-command-palette = Paleta de comandos
 
 new-project-banner = Nuevo proyecto
 form-new-project-input-name = Nombre del proyecto
@@ -57,8 +142,19 @@ project-overview-detail-name = Nombre: { $name }
 project-overview-phases-header = Fases
 project-overview-pcbs-header = Placas
 project-overview-pcbs-input-remove-disabled-hover-text-in-use-1 = En uso: eliminar asignaciones de unidades PCB.
+project-overview-build-run-header = Tanda de fabricación
+project-overview-build-run-none = No se ha iniciado ninguna tanda de fabricación.
+project-overview-build-run-detail = Tanda { $run }, unidad { $instance } de { $quantity } activa, { $completed } completadas.
+project-overview-build-run-instance = Unidad
+project-overview-build-run-button-activate = Activar
+project-overview-build-run-quantity = Cantidad
+project-overview-build-run-button-start = Iniciar tanda
 
 project-pcb-designs-header = Diseños
+project-pcb-gerbers-header = Gerbers
+pcb-thumbnail-no-gerbers = No hay gerbers para previsualizar
+pcb-thumbnail-error = No se puede generar la vista previa
+project-pcb-preview-header = Vista previa
 
 project-placements-tab-label = Ubicaciones
 project-placements-tab-phase-hover-text-no-phases = No hay fases definidas.
@@ -75,6 +171,9 @@ project-unit-assignments-tab-label = Asignaciones de unidad ({ $pcb })
 
 phase-toolbar-add-parts-to-loadout = Añadir piezas a la carga.
 phase-toolbar-placement-orderings = Ordenaciones de colocación.
+phase-toolbar-override-rules = Anular reglas.
+phase-toolbar-override-rules-count = Anular reglas ({ $count }).
+phase-toolbar-override-rules-help = Las reglas seleccionadas no se aplican a la siguiente acción de tarea, las anulaciones se registran.
 
 phase-reference = Referencia
 phase-load-out-source = Fuente de carga
@@ -93,8 +192,13 @@ project-explorer-tab-label = Explorador de proyecto
 
 project-explorer-node-root = Resumen
 project-explorer-node-issues = Problemas
-project-explorer-node-parts = Piezas
-project-explorer-node-placements = Ubicaciones
+project-explorer-node-parts = Piezas ({ $count })
+project-explorer-node-parts-process = { $process } ({ $count })
+project-explorer-node-parts-unassigned = Sin proceso ({ $count })
+project-explorer-node-placements = Ubicaciones ({ $complete }/{ $count }, { $percentage }%)
+project-explorer-node-placements-phase = { $phase } ({ $complete }/{ $count }, { $percentage }%)
+project-explorer-node-placements-unassigned = Sin fase ({ $complete }/{ $count }, { $percentage }%)
+project-explorer-node-placements-status = { $status } ({ $count })
 project-explorer-node-phases = Fases
 project-explorer-node-phase = { $reference } ({ $process } - { $pcb_side })
 project-explorer-node-phase-loadout = { $source }
@@ -145,6 +249,15 @@ pcb-gerber-viewer-tab-label-panel = Panel
 pcb-gerber-viewer-tab-label-design = Diseño ({ $index })
 pcb-gerber-viewer-layers-window-title = Capas
 pcb-gerber-viewer-input-go-to = Ir a
+pcb-gerber-viewer-layer-preset = Preajuste
+
+layer-preset-top-assembly = Ensamblaje superior
+layer-preset-bottom-assembly = Ensamblaje inferior
+layer-preset-top-paste = Pasta superior
+layer-preset-bottom-paste = Pasta inferior
+layer-preset-top-copper = Cobre superior
+layer-preset-bottom-copper = Cobre inferior
+layer-preset-all = Todas las capas
 
 form-configure-pcb-input-units = Units
 form-configure-pcb-input-gerber-offset = Despl. de colocación
@@ -258,6 +371,7 @@ placement-project-status-unused = No utilizado
 sort-mode-area = Área
 sort-mode-feeder-reference = Referencia del alimentador
 sort-mode-height = Altura
+sort-mode-nozzle = Boquilla
 sort-mode-part = Parte
 sort-mode-pcb = Instancia placa
 sort-mode-pcb-unit = Unidad placa
@@ -303,6 +417,10 @@ table-parts-column-mpn = MPN
 table-parts-column-processes = Procesos
 table-parts-column-ref-des-set = Des. de Ref. Conjunto
 table-parts-column-quantity = Cantidad
+table-parts-column-package = Encapsulado
+table-parts-package-size = Tamaño (mm)
+table-parts-package-pitch = Paso (mm)
+table-parts-package-pin1-orientation = Orientación del pin 1
 
 table-designs-column-index = #
 table-designs-column-actions = Acciones
@@ -368,6 +486,7 @@ table-pcbs-column-actions = Acciones
 
 table-issues-column-index = #
 table-issues-column-severity = Severidad
+table-issues-column-code = Código
 table-issues-column-message = Mensaje
 table-issues-column-details = Detalles
 table-issues-column-actions = Acciones
@@ -390,6 +509,53 @@ expanding-header-details = Detalles
 
 process-error-name-already-in-use = Attempted to rename a process to a name already in use
 
+app-error-operation-requires-project = La operación requiere un proyecto
+app-error-operation = Error de operación. { $cause }
+app-error-phase = Error de fase. { $cause }
+app-error-project = Error de proyecto. { $cause }
+app-error-template = Error de plantilla. { $cause }
+app-error-archive = Error de archivo. { $cause }
+app-error-process = Error de proceso. { $cause }
+app-error-part = Error de pieza. fabricante: { $manufacturer }, mpn: { $mpn }
+app-error-source = Error de origen. { $cause }
+app-error-loadout = Error de carga. { $cause }
+app-error-pcb-operation = Error de PCB. { $cause }
+app-error-io = Error de E/S. { $cause }
+app-error-inspection = Error de inspección. { $cause }
+app-error-cancelled = Operación cancelada
+app-error-vision = Error de visión. { $cause }
+app-error-serial-number = Error de número de serie. { $cause }
+app-error-placement-session-required = La operación requiere una sesión de colocación
+app-error-placement-session-complete = La sesión de colocación no tiene más colocaciones pendientes
+app-error-unknown-phase-reference = Fase desconocida. fase: { $phase }
+app-error-unknown-process-reference = Proceso desconocido. proceso: { $process }
+app-error-unknown-placement-selection = Selección de colocaciones desconocida. selección: { $selection }
+app-error-unknown-placement = Colocación desconocida. ruta del objeto: { $object_path }
+
+#
+# warnings
+#
+
+app-warning-unremoved-gerbers = { $count ->
+        [one] No se pudo eliminar 1 gerber
+       *[other] No se pudieron eliminar { $count } gerbers
+    }. PCB: { $pcb_file }
+app-warning-unreadable-recovery-file = Se ignora el archivo de recuperación ilegible. archivo: { $file }, causa: { $cause }
+app-warning-unreadable-profile-gerber = No se pudo cargar el gerber de perfil, se usa el tamaño del panel. archivo: { $file }, causa: { $cause }
+app-warning-load-out-unavailable-for-rules = No se pudo cargar la carga, las reglas del proceso usan una carga vacía. fase: { $phase }, causa: { $cause }
+app-warning-placements-on-other-pcb-side = { $count ->
+        [one] No se asignó 1 colocación
+       *[other] No se asignaron { $count } colocaciones
+    }, no están en el lado del PCB de la fase. fase: { $phase }, lado: { $pcb_side }, colocaciones: { $placements }
+app-warning-placement-warnings = { $count ->
+        [one] 1 problema en las colocaciones
+       *[other] { $count } problemas en las colocaciones
+    }, las colocaciones se importaron igualmente. variante de diseño: { $design_variant }, problemas: { $warnings }
+app-warning-unknown-parts = { $count ->
+        [one] Se ignoró 1 pieza
+       *[other] Se ignoraron { $count } piezas
+    }, el proyecto no las usa. piezas: { $parts }
+
 #
 # egui-data-tables
 #
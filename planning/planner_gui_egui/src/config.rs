@@ -1,15 +1,441 @@
+//! Application configuration, persisted with the app state, see [`Config`].
+//!
+//! Settings are grouped into named profiles, e.g. one for each user of a shared workstation, only the settings of the
+//! active profile are used.  Settings are changed using a [`SettingsChange`], so that open tabs can be notified of the
+//! change, see [`crate::ui_commands::UiCommand::SettingsChanged`].
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use egui::{KeyboardShortcut, ThemePreference};
+use planner_app::{RecentProjects, recent};
+use time::OffsetDateTime;
+use tracing::{info, warn};
+
+use crate::ui_commands::{CommandId, CommandRegistry};
+
+/// Incremented when the configuration changes in a way that requires a migration, see [`Config::migrate`].
+///
+/// * `1` - settings are grouped into profiles.
+/// * `2` - the recent projects have names, version 1 persisted a list of paths, see [`deserialize_recent_projects`].
+pub const CONFIG_VERSION: u32 = 2;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
 pub struct Config {
+    /// `0` for configurations persisted before the configuration was versioned.
+    #[serde(default)]
+    pub version: u32,
+    pub active_profile: String,
+    pub profiles: BTreeMap<String, Settings>,
+    /// Shared by all profiles, see [`Config::add_recent_project`].
+    #[serde(deserialize_with = "deserialize_recent_projects")]
+    pub recent_projects: RecentProjects,
+    /// The file the recent projects are exported to, so that the CLI can list them, `None` to not export them.
+    #[serde(skip)]
+    pub recent_projects_file: Option<PathBuf>,
+
+    // version 0 fields, moved to the active profile by `migrate`.
+    #[serde(skip_serializing)]
+    show_home_tab_on_startup: Option<bool>,
+    #[serde(skip_serializing)]
+    language_identifier: Option<String>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
     pub show_home_tab_on_startup: bool,
     pub language_identifier: String,
+    pub theme: ThemePreference,
+    pub default_directories: DefaultDirectories,
+    pub table_layouts: BTreeMap<TableId, TableLayout>,
+    pub confirmations: Confirmations,
+    /// Overrides the default keyboard shortcuts of the commands, a `None` binding removes the shortcut, see
+    /// [`CommandRegistry`].
+    pub key_bindings: BTreeMap<CommandId, Option<KeyboardShortcut>>,
+    /// The machine definition file, the capabilities of the machine are shown on the home tab.
+    pub machine_definition: Option<PathBuf>,
 }
 
-impl Default for Config {
+impl Default for Settings {
     fn default() -> Self {
         Self {
             show_home_tab_on_startup: true,
             language_identifier: egui_i18n::get_language(),
+            theme: ThemePreference::System,
+            default_directories: Default::default(),
+            table_layouts: Default::default(),
+            confirmations: Default::default(),
+            key_bindings: Default::default(),
+            machine_definition: None,
+        }
+    }
+}
+
+/// The directories file pickers start in, when `None` the platform's default is used.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct DefaultDirectories {
+    pub projects: Option<PathBuf>,
+    pub pcbs: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub enum DirectoryKind {
+    Projects,
+    Pcbs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub enum TableId {
+    Placements,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TableLayout {
+    /// Column indexes, in display order, `None` for the default order.
+    pub column_ordering: Option<Vec<usize>>,
+}
+
+/// Whether to ask for confirmation before an action, each confirmation dialog can be turned off by the user.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct Confirmations {
+    pub close_modified_project: bool,
+    pub close_modified_pcb: bool,
+    pub remove_phase: bool,
+}
+
+impl Default for Confirmations {
+    fn default() -> Self {
+        Self {
+            close_modified_project: true,
+            close_modified_pcb: true,
+            remove_phase: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingsChange {
+    ShowHomeTabOnStartup(bool),
+    Language(String),
+    Theme(ThemePreference),
+    DefaultDirectory(DirectoryKind, Option<PathBuf>),
+    TableLayout(TableId, TableLayout),
+    Confirmations(Confirmations),
+    /// Binds the shortcut to the command, `None` removes the command's shortcut.  The shortcut is removed from any
+    /// other command that uses it.
+    KeyBinding(CommandId, Option<KeyboardShortcut>),
+    /// Restores the default shortcut of the command.
+    ResetKeyBinding(CommandId),
+    /// Switches to another profile, the profile is created, with the default settings, if required.
+    ActiveProfile(String),
+    MachineDefinition(Option<PathBuf>),
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            active_profile: DEFAULT_PROFILE.to_string(),
+            profiles: BTreeMap::from([(DEFAULT_PROFILE.to_string(), Settings::default())]),
+            recent_projects: Default::default(),
+            recent_projects_file: None,
+            show_home_tab_on_startup: None,
+            language_identifier: None,
+        }
+    }
+}
+
+impl Config {
+    /// Must be called after deserializing, upgrades configurations persisted by older versions.
+    pub fn migrate(&mut self) {
+        if self.version == CONFIG_VERSION {
+            return;
+        }
+
+        info!(
+            "Migrating configuration. from: {}, to: {}",
+            self.version, CONFIG_VERSION
+        );
+
+        let show_home_tab_on_startup = self.show_home_tab_on_startup.take();
+        let language_identifier = self.language_identifier.take();
+
+        let settings = self.settings_mut();
+        if let Some(show_home_tab_on_startup) = show_home_tab_on_startup {
+            settings.show_home_tab_on_startup = show_home_tab_on_startup;
+        }
+        if let Some(language_identifier) = language_identifier {
+            settings.language_identifier = language_identifier;
+        }
+
+        self.version = CONFIG_VERSION;
+    }
+
+    /// The settings of the active profile.
+    pub fn settings(&self) -> Settings {
+        self.profiles
+            .get(&self.active_profile)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The settings of the active profile, the profile is created if required.
+    pub fn settings_mut(&mut self) -> &mut Settings {
+        self.profiles
+            .entry(self.active_profile.clone())
+            .or_default()
+    }
+
+    pub fn profile_names(&self) -> Vec<&String> {
+        self.profiles.keys().collect()
+    }
+
+    /// Returns the settings of the active profile, after the change.
+    pub fn apply(&mut self, change: SettingsChange) -> Settings {
+        match change {
+            SettingsChange::ShowHomeTabOnStartup(value) => {
+                self.settings_mut()
+                    .show_home_tab_on_startup = value
+            }
+            SettingsChange::Language(language_identifier) => {
+                self.settings_mut().language_identifier = language_identifier
+            }
+            SettingsChange::Theme(theme) => self.settings_mut().theme = theme,
+            SettingsChange::DefaultDirectory(kind, directory) => {
+                let default_directories = &mut self.settings_mut().default_directories;
+                match kind {
+                    DirectoryKind::Projects => default_directories.projects = directory,
+                    DirectoryKind::Pcbs => default_directories.pcbs = directory,
+                }
+            }
+            SettingsChange::TableLayout(table, layout) => {
+                self.settings_mut()
+                    .table_layouts
+                    .insert(table, layout);
+            }
+            SettingsChange::Confirmations(confirmations) => self.settings_mut().confirmations = confirmations,
+            SettingsChange::KeyBinding(command_id, shortcut) => {
+                let key_bindings = &mut self.settings_mut().key_bindings;
+                unbind_other_commands(key_bindings, command_id, shortcut);
+                key_bindings.insert(command_id, shortcut);
+            }
+            SettingsChange::ResetKeyBinding(command_id) => {
+                let key_bindings = &mut self.settings_mut().key_bindings;
+                unbind_other_commands(key_bindings, command_id, command_id.default_shortcut());
+                key_bindings.remove(&command_id);
+            }
+            SettingsChange::ActiveProfile(profile) => {
+                self.active_profile = profile;
+                self.settings_mut();
+            }
+            SettingsChange::MachineDefinition(path) => self.settings_mut().machine_definition = path,
+        }
+
+        self.settings()
+    }
+
+    /// Moves the project to the start of the recent projects, only call this once the project has been opened.
+    pub fn add_recent_project(&mut self, path: &Path, name: String) {
+        self.recent_projects
+            .add(path, name, OffsetDateTime::now_utc());
+        self.export_recent_projects();
+    }
+
+    pub fn set_recent_project_pinned(&mut self, path: &Path, pinned: bool) {
+        self.recent_projects
+            .set_pinned(path, pinned);
+        self.export_recent_projects();
+    }
+
+    pub fn remove_recent_project(&mut self, path: &Path) {
+        self.recent_projects.remove(path);
+        self.export_recent_projects();
+    }
+
+    /// Failures are only logged, since the GUI does not read the exported file.
+    fn export_recent_projects(&self) {
+        let Some(path) = &self.recent_projects_file else {
+            return;
+        };
+
+        if let Err(cause) = recent::save_recent_projects(&self.recent_projects, path) {
+            warn!("Unable to export recent projects. path: {:?}, cause: {}", path, cause);
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum PersistedRecentProjects {
+    Current(RecentProjects),
+    /// Version 1, most recently opened first.
+    Paths(Vec<PathBuf>),
+}
+
+/// Accepts the recent projects of version 1 configurations, a list of paths, the names are derived from the paths.
+fn deserialize_recent_projects<'de, D>(deserializer: D) -> Result<RecentProjects, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let recent_projects = match <PersistedRecentProjects as serde::Deserialize>::deserialize(deserializer)? {
+        PersistedRecentProjects::Current(recent_projects) => recent_projects,
+        PersistedRecentProjects::Paths(paths) => RecentProjects::from_paths(paths, OffsetDateTime::now_utc()),
+    };
+
+    Ok(recent_projects)
+}
+
+/// Removes the shortcut from any command, other than `command_id`, that uses it.
+fn unbind_other_commands(
+    key_bindings: &mut BTreeMap<CommandId, Option<KeyboardShortcut>>,
+    command_id: CommandId,
+    shortcut: Option<KeyboardShortcut>,
+) {
+    let Some(shortcut) = shortcut else {
+        return;
+    };
+
+    let registry = CommandRegistry::new(key_bindings);
+    if let Some(other_command_id) = registry
+        .find_command(&shortcut)
+        .filter(|other_command_id| *other_command_id != command_id)
+    {
+        key_bindings.insert(other_command_id, None);
+    }
+}
+
+#[cfg(test)]
+mod config_tests {
+    use super::*;
+
+    #[test]
+    pub fn legacy_settings_are_moved_to_the_active_profile() {
+        // given
+        let mut config = Config {
+            version: 0,
+            show_home_tab_on_startup: Some(false),
+            language_identifier: Some("es-ES".to_string()),
+            ..Config::default()
+        };
+
+        // when
+        config.migrate();
+
+        // then
+        assert_eq!(config.version, CONFIG_VERSION);
+        let settings = config.settings();
+        assert!(!settings.show_home_tab_on_startup);
+        assert_eq!(settings.language_identifier, "es-ES");
+    }
+
+    #[test]
+    pub fn profiles_have_independent_settings() {
+        // given
+        let mut config = Config::default();
+        config.apply(SettingsChange::Theme(ThemePreference::Dark));
+
+        // when
+        let settings = config.apply(SettingsChange::ActiveProfile("bench".to_string()));
+
+        // then
+        assert_eq!(settings.theme, ThemePreference::System);
+        assert_eq!(config.profile_names(), vec!["bench", DEFAULT_PROFILE]);
+
+        // and
+        let settings = config.apply(SettingsChange::ActiveProfile(DEFAULT_PROFILE.to_string()));
+        assert_eq!(settings.theme, ThemePreference::Dark);
+    }
+
+    #[test]
+    pub fn version_1_recent_projects_are_migrated() {
+        // given
+        let json = r#"{
+            "version": 1,
+            "active_profile": "default",
+            "recent_projects": ["projects/project-job2.mpnp.json", "projects/project-job1.mpnp.json"]
+        }"#;
+
+        // when
+        let mut config: Config = serde_json::from_str(json).unwrap();
+        config.migrate();
+
+        // then
+        assert_eq!(config.version, CONFIG_VERSION);
+        let recent_projects = config
+            .recent_projects
+            .iter()
+            .map(|project| (project.path.clone(), project.name.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(recent_projects, vec![
+            (PathBuf::from("projects/project-job2.mpnp.json"), "job2".to_string()),
+            (PathBuf::from("projects/project-job1.mpnp.json"), "job1".to_string()),
+        ]);
+
+        // and the current format is accepted
+        let json = serde_json::to_string(&config).unwrap();
+        let config: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(config.recent_projects.len(), 2);
+    }
+
+    #[test]
+    pub fn recent_projects_are_most_recent_first() {
+        // given
+        let mut config = Config::default();
+        for index in 0..=recent::MAX_RECENT_PROJECTS {
+            config.add_recent_project(
+                &PathBuf::from(format!("project_{}.mpnp.json", index)),
+                format!("project {}", index),
+            );
         }
+
+        // when
+        config.add_recent_project(&PathBuf::from("project_5.mpnp.json"), "project 5".to_string());
+
+        // then
+        let paths = config
+            .recent_projects
+            .iter()
+            .map(|project| project.path.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(paths.len(), recent::MAX_RECENT_PROJECTS);
+        assert_eq!(paths[0], PathBuf::from("project_5.mpnp.json"));
+        assert_eq!(
+            paths[1],
+            PathBuf::from(format!("project_{}.mpnp.json", recent::MAX_RECENT_PROJECTS))
+        );
+        // and the least recent was removed
+        assert!(!paths.contains(&PathBuf::from("project_0.mpnp.json")));
+    }
+
+    #[test]
+    pub fn rebinding_a_shortcut_removes_it_from_the_other_command() {
+        // given
+        let mut config = Config::default();
+        let save_shortcut = CommandId::Save
+            .default_shortcut()
+            .unwrap();
+
+        // when
+        let settings = config.apply(SettingsChange::KeyBinding(CommandId::OpenProject, Some(save_shortcut)));
+
+        // then
+        let registry = CommandRegistry::new(&settings.key_bindings);
+        assert_eq!(registry.shortcut(CommandId::OpenProject), Some(save_shortcut));
+        assert_eq!(registry.shortcut(CommandId::Save), None);
+
+        // and
+        let settings = config.apply(SettingsChange::ResetKeyBinding(CommandId::Save));
+        let registry = CommandRegistry::new(&settings.key_bindings);
+        assert_eq!(registry.shortcut(CommandId::Save), Some(save_shortcut));
+        assert_eq!(registry.shortcut(CommandId::OpenProject), None);
     }
 }
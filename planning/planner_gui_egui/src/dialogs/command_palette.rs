@@ -0,0 +1,130 @@
+use egui::{KeyboardShortcut, Modal};
+use egui_i18n::tr;
+use egui_mobius::Value;
+use tracing::debug;
+
+use crate::ui_commands::{CommandId, CommandRegistry};
+use crate::ui_component::{ComponentState, UiComponent};
+
+/// Lists the commands, and their shortcuts, the commands can be filtered by name, see [`CommandRegistry`].
+#[derive(Debug)]
+pub struct CommandPaletteModal {
+    commands: Vec<(CommandId, Option<KeyboardShortcut>)>,
+    filter: Value<String>,
+
+    pub component: ComponentState<CommandPaletteModalUiCommand>,
+}
+
+impl CommandPaletteModal {
+    pub fn new(registry: &CommandRegistry) -> Self {
+        Self {
+            commands: registry.commands(),
+            filter: Default::default(),
+            component: Default::default(),
+        }
+    }
+
+    /// The commands whose translated name contains the filter, ignoring case.
+    fn matching_commands(&self, filter: &str) -> Vec<(CommandId, Option<KeyboardShortcut>)> {
+        let filter = filter.to_lowercase();
+
+        self.commands
+            .iter()
+            .filter(|(command_id, _shortcut)| {
+                tr!(command_id.i18n_key())
+                    .to_lowercase()
+                    .contains(&filter)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CommandPaletteModalUiCommand {
+    Execute(CommandId),
+    Cancel,
+}
+
+#[derive(Debug, Clone)]
+pub enum CommandPaletteModalAction {
+    Execute(CommandId),
+    CloseDialog,
+}
+
+impl UiComponent for CommandPaletteModal {
+    type UiContext<'context> = ();
+    type UiCommand = CommandPaletteModalUiCommand;
+    type UiAction = CommandPaletteModalAction;
+
+    #[profiling::function]
+    fn ui<'context>(&self, ui: &mut egui::Ui, _context: &mut Self::UiContext<'context>) {
+        let modal_id = ui.id().with("command_palette_modal");
+
+        let response = Modal::new(modal_id).show(ui.ctx(), |ui| {
+            ui.set_min_width(400.0);
+
+            let mut filter = self.filter.lock().unwrap();
+            let filter_response = ui.add(
+                egui::TextEdit::singleline(&mut *filter)
+                    .hint_text(tr!("modal-command-palette-filter-hint"))
+                    .desired_width(f32::INFINITY),
+            );
+            filter_response.request_focus();
+
+            let matching_commands = self.matching_commands(&filter);
+
+            // enter executes the first matching command
+            if ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                if let Some((command_id, _shortcut)) = matching_commands.first() {
+                    self.component
+                        .send(CommandPaletteModalUiCommand::Execute(*command_id));
+                }
+            }
+
+            if matching_commands.is_empty() {
+                ui.label(tr!("modal-command-palette-no-matches"));
+                return;
+            }
+
+            egui::Grid::new(ui.id().with("commands"))
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (command_id, shortcut) in matching_commands {
+                        if ui
+                            .add(egui::Button::new(tr!(command_id.i18n_key())).frame(false))
+                            .clicked()
+                        {
+                            self.component
+                                .send(CommandPaletteModalUiCommand::Execute(command_id));
+                        }
+
+                        let shortcut_text = shortcut
+                            .map(|shortcut| ui.ctx().format_shortcut(&shortcut))
+                            .unwrap_or_default();
+                        ui.weak(shortcut_text);
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if response.should_close() {
+            self.component
+                .send(CommandPaletteModalUiCommand::Cancel);
+        }
+    }
+
+    #[profiling::function]
+    fn update<'context>(
+        &mut self,
+        command: Self::UiCommand,
+        _context: &mut Self::UiContext<'context>,
+    ) -> Option<Self::UiAction> {
+        debug!("command palette command: {:?}", command);
+        match command {
+            CommandPaletteModalUiCommand::Execute(command_id) => Some(CommandPaletteModalAction::Execute(command_id)),
+            CommandPaletteModalUiCommand::Cancel => Some(CommandPaletteModalAction::CloseDialog),
+        }
+    }
+}
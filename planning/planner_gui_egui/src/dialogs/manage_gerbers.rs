@@ -21,6 +21,7 @@ pub struct ManageGerbersModal {
     gerber_file_functions: Vec<(Option<GerberFileFunctionDiscriminants>, Option<PcbSide>)>,
 
     file_picker: Value<Picker>,
+    outline_picker: Value<Picker>,
 
     pub component: ComponentState<ManagerGerbersModalUiCommand>,
 }
@@ -47,6 +48,7 @@ pub fn new(title: String, gerbers: Vec<PcbGerberItem>) -> Self {
             gerber_file_functions,
             component: Default::default(),
             file_picker: Default::default(),
+            outline_picker: Default::default(),
         }
     }
 
@@ -65,6 +67,10 @@ pub enum ManagerGerbersModalUiCommand {
     GerberFilesPicked {
         picked_files: Vec<PathBuf>,
     },
+    ImportOutline,
+    OutlineFilePicked {
+        picked_file: PathBuf,
+    },
     Refresh,
     FunctionChanged {
         index: usize,
@@ -86,6 +92,9 @@ pub enum ManagerGerberModalAction {
     AddGerberFiles {
         files: Vec<PathBuf>,
     },
+    ImportOutline {
+        file: PathBuf,
+    },
     RefreshGerberFiles,
     ApplyGerberFileFunctions {
         file_functions: Vec<(PathBuf, Option<GerberFileFunction>)>,
@@ -111,6 +120,18 @@ fn ui<'context>(&self, ui: &mut egui::Ui, _context: &mut Self::UiContext<'contex
                 });
         }
 
+        if let Ok(picked_file) = self
+            .outline_picker
+            .lock()
+            .unwrap()
+            .picked()
+        {
+            self.component
+                .send(ManagerGerbersModalUiCommand::OutlineFilePicked {
+                    picked_file,
+                });
+        }
+
         ui.ctx().style_mut(|style| {
             // if this is not done, text in labels/checkboxes/etc wraps when using taffy
             style.wrap_mode = Some(egui::TextWrapMode::Extend);
@@ -315,6 +336,13 @@ fn ui<'context>(&self, ui: &mut egui::Ui, _context: &mut Self::UiContext<'contex
                         self.component
                             .send(ManagerGerbersModalUiCommand::Add);
                     }
+                    if ui
+                        .button(tr!("modal-manager-gerbers-button-import-outline"))
+                        .clicked()
+                    {
+                        self.component
+                            .send(ManagerGerbersModalUiCommand::ImportOutline);
+                    }
                     if ui
                         .button(tr!("form-common-button-refresh"))
                         .clicked()
@@ -366,6 +394,18 @@ fn update<'context>(
                     .pick_files();
                 None
             }
+            ManagerGerbersModalUiCommand::ImportOutline => {
+                self.outline_picker
+                    .lock()
+                    .unwrap()
+                    .pick_file();
+                None
+            }
+            ManagerGerbersModalUiCommand::OutlineFilePicked {
+                picked_file,
+            } => Some(ManagerGerberModalAction::ImportOutline {
+                file: picked_file,
+            }),
             ManagerGerbersModalUiCommand::Refresh => Some(ManagerGerberModalAction::RefreshGerberFiles),
             ManagerGerbersModalUiCommand::GerberFilesPicked {
                 picked_files,
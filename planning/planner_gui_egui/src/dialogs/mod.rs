@@ -1 +1,2 @@
+pub mod command_palette;
 pub mod manage_gerbers;
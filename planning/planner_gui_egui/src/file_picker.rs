@@ -31,7 +31,10 @@ pub fn is_picking(&self) -> bool {
         matches!(self.state, PickerState::Picking(_))
     }
 
-    fn prepare(&mut self, some_fn: fn() -> Option<Vec<PathBuf>>) {
+    fn prepare<F>(&mut self, some_fn: F)
+    where
+        F: FnOnce() -> Option<Vec<PathBuf>> + Send + 'static,
+    {
         // initialise the boolean flag in the mutex to false, so that when the main thread continues it can see a
         // file has not been picked yet.  note that the mutex may not be locked until the picker thread starts to run
         // and lock it.
@@ -54,6 +57,19 @@ pub fn pick_file(&mut self) {
         });
     }
 
+    /// `directory` - the directory to start in, when `None` the platform's default is used.
+    pub fn pick_file_in(&mut self, directory: Option<PathBuf>) {
+        self.prepare(move || {
+            let mut dialog = rfd::FileDialog::new();
+            if let Some(directory) = directory {
+                dialog = dialog.set_directory(directory);
+            }
+            dialog
+                .pick_file()
+                .map(|file| vec![file])
+        });
+    }
+
     pub fn pick_files(&mut self) {
         self.prepare(|| rfd::FileDialog::new().pick_files());
     }
@@ -117,6 +117,38 @@ pub fn matches(&self, value: &str) -> bool {
     pub fn is_regex_mode(&self) -> bool {
         matches!(self.mode, FilterMode::RegexMatch { .. })
     }
+
+    /// The state that can be persisted, e.g. in a project session, see [`Filter::restore`].
+    pub fn state(&self) -> FilterState {
+        FilterState {
+            expression: self.expression.clone(),
+            regex: self.is_regex_mode(),
+            case_sensitive: self.is_case_sensitive(),
+        }
+    }
+
+    pub fn restore(&mut self, state: &FilterState) {
+        self.expression = state.expression.clone();
+        self.mode = match state.regex {
+            true => FilterMode::RegexMatch {
+                flags: HashSet::new(),
+                regex: None,
+            },
+            false => FilterMode::PartialMatch {
+                flags: HashSet::new(),
+            },
+        };
+        self.set_case_sensitivity(state.case_sensitive);
+        self.on_expression_changed();
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct FilterState {
+    pub expression: String,
+    pub regex: bool,
+    pub case_sensitive: bool,
 }
 
 impl Default for Filter {
@@ -1,6 +1,6 @@
 use planner_app::{
-    GerberFileFunction, GerberFileFunctionDiscriminants, OperationStatus, PcbAssemblyFlip, PcbSide, PhaseStatus,
-    PlacementSortingMode, PlacementStatus, ProjectPlacementStatus, TaskStatus,
+    GerberFileFunction, GerberFileFunctionDiscriminants, LayerPreset, OperationStatus, PcbAssemblyFlip, PcbSide,
+    PhaseStatus, PlacementSortingMode, PlacementStatus, ProjectPlacementStatus, TaskStatus,
 };
 use util::sorting::SortOrder;
 
@@ -43,6 +43,7 @@ pub fn placement_sorting_mode_to_i18n_key(mode: &PlacementSortingMode) -> &'stat
         PlacementSortingMode::RefDes => "sort-mode-ref-des",
         PlacementSortingMode::Area => "sort-mode-area",
         PlacementSortingMode::Height => "sort-mode-height",
+        PlacementSortingMode::Nozzle => "sort-mode-nozzle",
         PlacementSortingMode::Part => "sort-mode-part",
     }
 }
@@ -111,6 +112,18 @@ pub fn gerber_file_function_discriminant_to_i18n_key(
     }
 }
 
+pub fn layer_preset_to_i18n_key(preset: &LayerPreset) -> &'static str {
+    match preset {
+        LayerPreset::TopAssembly => "layer-preset-top-assembly",
+        LayerPreset::BottomAssembly => "layer-preset-bottom-assembly",
+        LayerPreset::TopPaste => "layer-preset-top-paste",
+        LayerPreset::BottomPaste => "layer-preset-bottom-paste",
+        LayerPreset::TopCopper => "layer-preset-top-copper",
+        LayerPreset::BottomCopper => "layer-preset-bottom-copper",
+        LayerPreset::All => "layer-preset-all",
+    }
+}
+
 pub fn pcb_orientation_pitch_flip_to_i18n_key(flip: PcbAssemblyFlip) -> &'static str {
     match flip {
         PcbAssemblyFlip::None => "pcb-assembly-orientation-flip-none",
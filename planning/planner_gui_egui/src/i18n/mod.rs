@@ -1 +1,14 @@
 pub mod conversions;
+
+/// The translations, embedded at compile time, so that the `assets/translations` directory is not required at runtime.
+#[cfg(feature = "embedded-translations")]
+pub const EMBEDDED_TRANSLATIONS: &[(&str, &str)] = &[
+    (
+        "en-US",
+        include_str!("../../assets/translations/en-US/translations.ftl"),
+    ),
+    (
+        "es-ES",
+        include_str!("../../assets/translations/es-ES/translations.ftl"),
+    ),
+];
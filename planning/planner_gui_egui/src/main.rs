@@ -1,7 +1,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 // hide console window on Windows in release
-use i18n::I18nConfig;
+use i18n::{I18nConfig, TranslationSource};
 use planner_gui_egui::ui_app::UiApp;
 /// Run as follows:
 /// `run --package planner_gui_egui --bin planner_gui_egui`
@@ -27,6 +27,11 @@ fn main() {
         languages: vec![String::from("es-ES"), String::from("en-US")],
         default: "en-US".to_string(),
         fallback: "en-US".to_string(),
+        fallback_chains: Default::default(),
+        #[cfg(feature = "embedded-translations")]
+        source: TranslationSource::Embedded(planner_gui_egui::i18n::EMBEDDED_TRANSLATIONS),
+        #[cfg(not(feature = "embedded-translations"))]
+        source: TranslationSource::default(),
     });
 
     let default_options = eframe::NativeOptions {
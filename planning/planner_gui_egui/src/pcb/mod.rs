@@ -8,10 +8,12 @@
 use eda_units::eda_units::unit_system::UnitSystem;
 use egui::Ui;
 use egui_dock::Split;
+use egui_i18n::tr;
 use egui_mobius::Value;
 use egui_mobius::types::Enqueue;
 use planner_app::{
     DesignIndex, Event, ObjectPath, PanelSizing, PcbOverview, PcbSide, PcbView, PcbViewRequest, PlacementPositionUnit,
+    Warnings,
 };
 use regex::Regex;
 use slotmap::new_key_type;
@@ -24,11 +26,14 @@
 };
 use tracing::{debug, error, info, trace};
 
+use crate::config::{Confirmations, Settings, SettingsChange};
 use crate::pcb::core_helper::PcbCoreHelper;
 use crate::pcb::tabs::explorer_tab::ExplorerTabUiAction;
 use crate::pcb::tabs::panel_tab::{PanelTab, PanelTabUi, PanelTabUiAction, PanelTabUiCommand, PanelTabUiContext};
 use crate::pcb::tabs::{PcbTabAction, PcbTabContext, PcbTabUiCommand, PcbTabs};
 use crate::planner_app_core::{PlannerCoreService, PlannerError};
+use crate::project::dialogs::confirmation::{ConfirmationResponse, show_confirmation_modal};
+use crate::project::dialogs::warnings::{append_warnings, show_warnings_modal};
 use crate::task::Task;
 use crate::ui_component::{ComponentState, UiComponent};
 use crate::ui_components::gerber_viewer_ui::{GerberViewerMode, GerberViewerUiInstanceArgs};
@@ -47,6 +52,9 @@ pub enum PcbAction {
     SetModifiedState(bool),
     UiCommand(PcbUiCommand),
     RequestRepaint,
+    SettingsChanged(SettingsChange),
+    /// The close has been confirmed, see [`PcbUiCommand::ConfirmClose`].
+    Close,
 }
 
 #[derive(Derivative)]
@@ -63,6 +71,14 @@ pub struct Pcb {
     pcb_overview: Option<PcbOverview>,
     panel_sizing: Option<PanelSizing>,
 
+    /// list of warnings to show
+    warnings: Vec<(chrono::DateTime<chrono::Utc>, String)>,
+
+    /// from the settings of the active profile
+    confirmations: Confirmations,
+    /// the close has been requested, shown until confirmed or cancelled
+    confirming_close: bool,
+
     pcb_tabs: Value<PcbTabs>,
 
     pub component: ComponentState<(PcbKey, PcbUiCommand)>,
@@ -110,6 +126,9 @@ fn new_inner(
             modified: false,
             pcb_overview: None,
             panel_sizing: None,
+            warnings: Default::default(),
+            confirmations: Default::default(),
+            confirming_close: false,
             component,
             pcb_tabs,
         };
@@ -487,6 +506,7 @@ pub enum PcbUiCommand {
     SetModifiedState {
         project_modified: bool,
         pcbs_modified: bool,
+        warnings: Warnings,
     },
 
     Create {
@@ -506,6 +526,19 @@ pub enum PcbUiCommand {
     // errors
     //
     Error(PlannerError),
+    ClearWarnings,
+
+    /// The settings of the active profile, sent when opened and when the settings change.
+    SettingsChanged(Settings),
+    ChangeSettings(SettingsChange),
+
+    //
+    // confirmations
+    //
+    /// Asks for confirmation before closing, sent when a modified PCB is closed.
+    ConfirmClose,
+    Close,
+    ConfirmationResponse(ConfirmationResponse),
 
     //
     // views
@@ -551,7 +584,7 @@ impl UiComponent for Pcb {
     type UiAction = PcbAction;
 
     #[profiling::function]
-    fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
+    fn ui<'context>(&self, ui: &mut Ui, context: &mut Self::UiContext<'context>) {
         ui.ctx().style_mut(|style| {
             // if this is not done, text in labels/checkboxes/etc wraps when using taffy
             style.wrap_mode = Some(egui::TextWrapMode::Extend);
@@ -567,6 +600,31 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
         let mut pcb_tabs = self.pcb_tabs.lock().unwrap();
         pcb_tabs.cleanup_tabs(&mut tab_context);
         pcb_tabs.ui(ui, &mut tab_context);
+
+        if !self.warnings.is_empty() {
+            let key = context.key;
+            show_warnings_modal(ui, &self.path, &self.warnings, || {
+                self.component
+                    .send((key, PcbUiCommand::ClearWarnings))
+            });
+        } else if self.confirming_close {
+            let key = context.key;
+            let file_name = self
+                .path
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap();
+            show_confirmation_modal(
+                ui,
+                tr!("modal-confirmation-close-pcb-title", { file: file_name }),
+                tr!("modal-confirmation-close-pcb-message"),
+                |response| {
+                    self.component
+                        .send((key, PcbUiCommand::ConfirmationResponse(response)))
+                },
+            );
+        }
     }
 
     #[profiling::function]
@@ -645,6 +703,41 @@ fn update<'context>(
                 // TODO show a dialog for PCB errors
                 None
             }
+            PcbUiCommand::ClearWarnings => {
+                self.warnings.clear();
+                None
+            }
+            PcbUiCommand::SettingsChanged(settings) => {
+                self.confirmations = settings.confirmations;
+                None
+            }
+            PcbUiCommand::ConfirmClose => {
+                self.confirming_close = true;
+                None
+            }
+            PcbUiCommand::ChangeSettings(change) => Some(PcbAction::SettingsChanged(change)),
+            PcbUiCommand::Close => Some(PcbAction::Close),
+            PcbUiCommand::ConfirmationResponse(response) => {
+                self.confirming_close = false;
+                let ConfirmationResponse::Confirmed {
+                    dont_ask_again,
+                } = response
+                else {
+                    return None;
+                };
+
+                let mut tasks = vec![];
+                if dont_ask_again {
+                    let mut confirmations = self.confirmations;
+                    confirmations.close_modified_pcb = false;
+                    tasks.push(Task::done(PcbAction::UiCommand(PcbUiCommand::ChangeSettings(
+                        SettingsChange::Confirmations(confirmations),
+                    ))));
+                }
+                tasks.push(Task::done(PcbAction::UiCommand(PcbUiCommand::Close)));
+
+                Some(PcbAction::Task(key, Task::batch(tasks)))
+            }
             PcbUiCommand::RefreshPcb => {
                 let task1 = Task::done(PcbAction::UiCommand(PcbUiCommand::RequestPcbView(
                     PcbViewRequest::Overview {
@@ -704,14 +797,20 @@ fn update<'context>(
 
                         self.panel_sizing = Some(panel_sizing);
                     }
+                    PcbView::PanelPreview(_panel_preview) => {
+                        // nothing requests this view
+                    }
                 }
                 None
             }
             PcbUiCommand::SetModifiedState {
-                pcbs_modified, ..
+                pcbs_modified,
+                warnings,
+                ..
             } => {
                 // FIXME we want to know if *THIS* pcb is modified, not any pcb.
                 self.modified = pcbs_modified;
+                append_warnings(&mut self.warnings, warnings);
                 Some(PcbAction::SetModifiedState(pcbs_modified))
             }
             PcbUiCommand::RequestPcbView(view_request) => {
@@ -726,6 +825,11 @@ fn update<'context>(
                     } => Some(Event::RequestPcbPanelSizingView {
                         path,
                     }),
+                    PcbViewRequest::PanelPreview {
+                        path,
+                    } => Some(Event::RequestPcbPanelPreviewView {
+                        path,
+                    }),
                 };
 
                 // TODO remove the 'if let'
@@ -869,6 +973,7 @@ fn update<'context>(
                                 path: path.clone(),
                                 design,
                                 files,
+                                token: None,
                             })
                             .into_actions()
                         {
@@ -922,6 +1027,39 @@ fn update<'context>(
                             Err(error_action) => Some(error_action),
                         }
                     }
+                    Some(ConfigurationTabUiAction::ImportOutline {
+                        path,
+                        design,
+                        file,
+                    }) => {
+                        match self
+                            .planner_core_service
+                            .update(Event::ImportPcbOutline {
+                                path: path.clone(),
+                                design,
+                                file,
+                                token: None,
+                            })
+                            .into_actions()
+                        {
+                            Ok(actions) => {
+                                let mut tasks = actions
+                                    .into_iter()
+                                    .map(Task::done)
+                                    .collect::<Vec<_>>();
+
+                                let additional_tasks = vec![Task::done(PcbAction::UiCommand(
+                                    PcbUiCommand::RequestPcbView(PcbViewRequest::Overview {
+                                        path,
+                                    }),
+                                ))];
+                                tasks.extend(additional_tasks);
+
+                                Some(PcbAction::Task(key, Task::batch(tasks)))
+                            }
+                            Err(error_action) => Some(error_action),
+                        }
+                    }
                     Some(ConfigurationTabUiAction::RefreshGerberFiles {
                         path,
                         design,
@@ -931,6 +1069,7 @@ fn update<'context>(
                             .update(Event::RefreshGerberFiles {
                                 path: path.clone(),
                                 design,
+                                token: None,
                             })
                             .into_actions()
                         {
@@ -1150,15 +1289,22 @@ fn into_project_action(action: PlannerAction) -> PcbAction {
             PlannerAction::SetModifiedState {
                 project_modified,
                 pcbs_modified,
+                warnings,
+                ..
             } => PcbAction::UiCommand(PcbUiCommand::SetModifiedState {
                 project_modified,
                 pcbs_modified,
+                warnings,
             }),
             PlannerAction::ProjectView(_project_view) => {
                 warn!("pcb received project view action. ignoring.");
                 PcbAction::UiCommand(PcbUiCommand::None)
             }
             PlannerAction::PcbView(pcb_view) => PcbAction::UiCommand(PcbUiCommand::PcbView(pcb_view)),
+            PlannerAction::ViewInvalidated(_view_request) => {
+                // pcb tabs only show pcb views, which are not invalidated.
+                PcbAction::UiCommand(PcbUiCommand::None)
+            }
         }
     }
 
@@ -993,6 +993,11 @@ pub enum ConfigurationTabUiAction {
         path: PathBuf,
         design: Option<DesignName>,
     },
+    ImportOutline {
+        path: PathBuf,
+        design: Option<DesignName>,
+        file: PathBuf,
+    },
     Reset,
     Apply(PcbUnitConfigurationArgs),
     ApplyGerberFileFunctions {
@@ -1323,6 +1328,16 @@ fn update<'context>(
                                 files,
                             })
                         }
+                        Some(ManagerGerberModalAction::ImportOutline {
+                            file,
+                        }) => {
+                            debug!("outline file picked. mode: {:?}, picked: {:?}", mode, file);
+                            Some(ConfigurationTabUiAction::ImportOutline {
+                                path: pcb_overview.path.clone(),
+                                design,
+                                file,
+                            })
+                        }
                         Some(ManagerGerberModalAction::RefreshGerberFiles) => {
                             debug!("refreshing gerber files. mode: {:?}", mode);
                             Some(ConfigurationTabUiAction::RefreshGerberFiles {
@@ -8,10 +8,11 @@
 use egui_dock::tab_viewer::OnCloseResponse;
 use egui_extras::{Column, TableBuilder};
 use egui_i18n::tr;
-use planner_app::{ObjectPath, PanelSizing, PcbOverview, PcbSide, PlacementPositionUnit};
+use planner_app::{LayerPreset, ObjectPath, PanelSizing, PcbOverview, PcbSide, PlacementPositionUnit};
+use strum::VariantArray;
 use tracing::trace;
 
-use crate::i18n::conversions::{gerber_file_function_to_i18n_key, pcb_side_to_i18n_key};
+use crate::i18n::conversions::{gerber_file_function_to_i18n_key, layer_preset_to_i18n_key, pcb_side_to_i18n_key};
 use crate::pcb::tabs::PcbTabContext;
 use crate::tabs::{Tab, TabKey};
 use crate::ui_component::{ComponentState, UiComponent};
@@ -185,6 +186,7 @@ pub enum GerberViewerTabUiCommand {
     GerberViewerUiCommand(GerberViewerUiCommand),
     GoToClicked(f64, f64),
     CoordinatesChanged(String, String),
+    LayerPresetClicked(LayerPreset),
     LocateComponent {
         object_path: ObjectPath,
         pcb_side: PcbSide,
@@ -288,7 +290,26 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
                         .default_size([200.0, 200.0])
                         .show(tr!("pcb-gerber-viewer-layers-window-title"), {
                             let layers_binding = self.gerber_viewer_ui.layers();
+                            let sender = self.component.sender.clone();
                             move |ui| {
+                                egui::ComboBox::from_id_salt(ui.id().with("layer_preset"))
+                                    .selected_text(tr!("pcb-gerber-viewer-layer-preset"))
+                                    .show_ui(ui, |ui| {
+                                        for preset in LayerPreset::VARIANTS {
+                                            if ui
+                                                .add(egui::Button::selectable(
+                                                    false,
+                                                    tr!(layer_preset_to_i18n_key(preset)),
+                                                ))
+                                                .clicked()
+                                            {
+                                                sender
+                                                    .send(GerberViewerTabUiCommand::LayerPresetClicked(*preset))
+                                                    .expect("sent");
+                                            }
+                                        }
+                                    });
+
                                 let layers_map = layers_binding.lock().unwrap();
 
                                 Self::show_layers_table(ui, &layers_map);
@@ -314,6 +335,9 @@ fn update<'context>(
                 match action {
                     None => None,
                     Some(GerberViewerUiAction::None) => Some(GerberViewerTabUiAction::None),
+                    Some(GerberViewerUiAction::PointClicked {
+                        ..
+                    }) => None,
                 }
             }
             GerberViewerTabUiCommand::GoToClicked(x, y) => {
@@ -327,6 +351,11 @@ fn update<'context>(
                 self.coord_input = (x.to_string(), y.to_string());
                 None
             }
+            GerberViewerTabUiCommand::LayerPresetClicked(preset) => {
+                self.gerber_viewer_ui
+                    .apply_layer_preset(preset);
+                None
+            }
             GerberViewerTabUiCommand::LocateComponent {
                 object_path,
                 pcb_side,
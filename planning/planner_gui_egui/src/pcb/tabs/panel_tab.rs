@@ -1225,6 +1225,9 @@ fn update<'context>(
                 match action {
                     None => None,
                     Some(GerberViewerUiAction::None) => None,
+                    Some(GerberViewerUiAction::PointClicked {
+                        ..
+                    }) => None,
                 }
             }
             PanelTabUiCommand::Reset => {
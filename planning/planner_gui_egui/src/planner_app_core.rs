@@ -1,9 +1,14 @@
 use std::sync::Arc;
 
+use planner_app::effects::file_io;
 use planner_app::effects::pcb_view_renderer::PcbViewRendererOperation;
 use planner_app::effects::project_view_renderer::ProjectViewRendererOperation;
-use planner_app::{Effect, Event, PcbView, Planner, ProjectView};
-use tracing::{error, trace};
+use planner_app::effects::view_invalidation::ViewInvalidationOperation;
+use planner_app::effects::vision::VisionResult;
+use planner_app::{
+    AppErrorView, DeleteReport, Effect, Event, PcbView, Planner, ProjectView, ProjectViewRequest, SelectionId, Warnings,
+};
+use tracing::{error, info, trace};
 
 type Core = Arc<planner_app::Core<Planner>>;
 
@@ -16,14 +21,18 @@ pub enum PlannerAction {
     SetModifiedState {
         project_modified: bool,
         pcbs_modified: bool,
+        warnings: Warnings,
+        delete_report: Option<DeleteReport>,
+        recovery_available: bool,
     },
     ProjectView(ProjectView),
     PcbView(PcbView),
+    ViewInvalidated(ProjectViewRequest),
 }
 
 #[derive(Debug, Clone)]
 pub enum PlannerError {
-    CoreError((chrono::DateTime<chrono::Utc>, String)),
+    CoreError((chrono::DateTime<chrono::Utc>, AppErrorView)),
     Other((chrono::DateTime<chrono::Utc>, String)),
 }
 
@@ -41,50 +50,100 @@ pub fn update(&mut self, event: Event) -> Result<Vec<PlannerAction>, PlannerErro
         let mut actions: Vec<PlannerAction> = Vec::new();
 
         for effect in self.core.process_event(event) {
-            let action = Self::process_effect(&self.core, effect);
-            match action {
-                Ok(action) => {
-                    actions.push(action);
-                }
-                Err(error) => return Err(error),
-            }
+            actions.extend(Self::process_effect(&self.core, effect)?);
         }
 
         Ok(actions)
     }
 
-    pub fn process_effect(core: &Core, effect: Effect) -> Result<PlannerAction, PlannerError> {
+    /// The selection made by the last event, see [`Event::SelectPlacements`].
+    pub fn placement_selection(&self) -> Option<SelectionId> {
+        self.core.view().placement_selection
+    }
+
+    pub fn process_effect(core: &Core, effect: Effect) -> Result<Vec<PlannerAction>, PlannerError> {
         trace!("effect: {:?}", effect);
 
         match effect {
-            Effect::Render(_) => {
-                let mut view = core.view();
-                let task = match view.error.take() {
-                    Some(error) => {
-                        error!("core error: {:?}", error);
-                        Err(PlannerError::CoreError(error))
-                    }
-                    None => Ok(PlannerAction::SetModifiedState {
-                        project_modified: view.project_modified,
-                        pcbs_modified: view.pcbs_modified,
-                    }),
-                };
-
-                task
-            }
+            Effect::Render(_) => Ok(vec![Self::render(core)?]),
             Effect::ProjectView(request) => {
                 let ProjectViewRendererOperation::View {
                     view,
                 } = request.operation;
 
-                Ok(PlannerAction::ProjectView(view))
+                Ok(vec![PlannerAction::ProjectView(view)])
             }
             Effect::PcbView(request) => {
                 let PcbViewRendererOperation::View {
                     view,
                 } = request.operation;
 
-                Ok(PlannerAction::PcbView(view))
+                Ok(vec![PlannerAction::PcbView(view)])
+            }
+            Effect::ViewInvalidated(request) => {
+                let ViewInvalidationOperation::ViewInvalidated {
+                    view,
+                } = request.operation;
+
+                Ok(vec![PlannerAction::ViewInvalidated(view)])
+            }
+            Effect::Vision(mut request) => {
+                // The GUI does not own a camera, so the operation is always unavailable, the core reports this as
+                // an error.
+                let effects = core
+                    .resolve(&mut request, VisionResult::Unavailable)
+                    .map_err(|error| PlannerError::Other((chrono::Utc::now(), format!("{:?}", error))))?;
+
+                Self::process_resolved_effects(core, effects)
+            }
+            Effect::FileIo(mut request) => {
+                let result = file_io::perform(&request.operation);
+
+                let effects = core
+                    .resolve(&mut request, result)
+                    .map_err(|error| PlannerError::Other((chrono::Utc::now(), format!("{:?}", error))))?;
+
+                Self::process_resolved_effects(core, effects)
+            }
+        }
+    }
+
+    /// The actions for all the effects of a resolved request, a render when there are none, e.g. when other writes
+    /// are still pending.
+    fn process_resolved_effects(
+        core: &Core,
+        effects: impl IntoIterator<Item = Effect>,
+    ) -> Result<Vec<PlannerAction>, PlannerError> {
+        let mut actions = Vec::new();
+        for effect in effects {
+            actions.extend(Self::process_effect(core, effect)?);
+        }
+
+        if actions.is_empty() {
+            actions.push(Self::render(core)?);
+        }
+
+        Ok(actions)
+    }
+
+    fn render(core: &Core) -> Result<PlannerAction, PlannerError> {
+        let mut view = core.view();
+        match view.error.take() {
+            Some(error) => {
+                error!("core error: {:?}", error);
+                Err(PlannerError::CoreError(error))
+            }
+            None => {
+                if let Some(design_variants) = &view.refreshed_design_variants {
+                    info!("Design variants refreshed: {:?}", design_variants);
+                }
+                Ok(PlannerAction::SetModifiedState {
+                    project_modified: view.project_modified,
+                    pcbs_modified: view.pcbs_modified,
+                    warnings: view.warnings,
+                    delete_report: view.delete_report,
+                    recovery_available: view.recovery_available,
+                })
             }
         }
     }
@@ -0,0 +1,73 @@
+use egui::{Modal, RichText, Ui};
+use egui_i18n::tr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfirmationResponse {
+    Cancelled,
+    Confirmed { dont_ask_again: bool },
+}
+
+/// Asks for confirmation before an action, see [`crate::config::Confirmations`].
+///
+/// `on_response` is called when a button is clicked, or the modal is dismissed, which cancels.
+pub fn show_confirmation_modal(
+    ui: &mut Ui,
+    title: String,
+    message: String,
+    on_response: impl FnOnce(ConfirmationResponse),
+) {
+    let modal_id = ui.id().with("confirmation");
+    let dont_ask_again_id = modal_id.with("dont_ask_again");
+
+    let mut dont_ask_again = ui
+        .ctx()
+        .data(|data| data.get_temp::<bool>(dont_ask_again_id))
+        .unwrap_or(false);
+
+    let mut response = None;
+
+    let modal = Modal::new(modal_id).show(ui.ctx(), |ui| {
+        ui.add(egui::Label::new(RichText::from(title).heading()).selectable(false));
+
+        ui.label(message);
+
+        ui.checkbox(&mut dont_ask_again, tr!("modal-confirmation-dont-ask-again"));
+
+        egui::Sides::new().show(
+            ui,
+            |_ui| {},
+            |ui| {
+                if ui
+                    .button(tr!("form-common-button-cancel"))
+                    .clicked()
+                {
+                    response = Some(ConfirmationResponse::Cancelled);
+                }
+                if ui
+                    .button(tr!("form-common-button-ok"))
+                    .clicked()
+                {
+                    response = Some(ConfirmationResponse::Confirmed {
+                        dont_ask_again,
+                    });
+                }
+            },
+        );
+    });
+
+    if response.is_none() && modal.should_close() {
+        response = Some(ConfirmationResponse::Cancelled);
+    }
+
+    match response {
+        Some(response) => {
+            ui.ctx()
+                .data_mut(|data| data.remove::<bool>(dont_ask_again_id));
+            on_response(response);
+        }
+        None => {
+            ui.ctx()
+                .data_mut(|data| data.insert_temp(dont_ask_again_id, dont_ask_again));
+        }
+    }
+}
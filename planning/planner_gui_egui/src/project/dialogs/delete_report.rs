@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use egui::{Modal, RichText, Ui};
+use egui_extras::{Column, TableBuilder};
+use egui_i18n::tr;
+use planner_app::DeleteReport;
+
+use crate::project::{ProjectKey, ProjectUiCommand};
+use crate::ui_component::ComponentState;
+
+/// Lists what was affected by deleting a phase, or removing a PCB, one row per affected item.
+pub fn show_delete_report_modal(
+    ui: &mut Ui,
+    key: ProjectKey,
+    path: &PathBuf,
+    report: &DeleteReport,
+    component: &ComponentState<(ProjectKey, ProjectUiCommand)>,
+) {
+    let modal_id = ui.id().with("delete_report");
+
+    let width = ui.ctx().screen_rect().width() * 0.8;
+
+    let rows = report
+        .unit_assignments
+        .iter()
+        .map(|object_path| {
+            (
+                tr!("modal-delete-report-unit-assignment-removed"),
+                object_path.to_string(),
+            )
+        })
+        .chain(
+            report
+                .deleted_placements
+                .iter()
+                .map(|object_path| (tr!("modal-delete-report-placement-deleted"), object_path.to_string())),
+        )
+        .chain(
+            report
+                .unassigned_placements
+                .iter()
+                .map(|object_path| (tr!("modal-delete-report-placement-unassigned"), object_path.to_string())),
+        )
+        .chain(
+            report
+                .reset_placements
+                .iter()
+                .map(|object_path| (tr!("modal-delete-report-placement-reset"), object_path.to_string())),
+        )
+        .chain(
+            report
+                .renumbered_placements
+                .iter()
+                .map(|(old_object_path, new_object_path)| {
+                    (
+                        tr!("modal-delete-report-placement-renumbered"),
+                        format!("{} -> {}", old_object_path, new_object_path),
+                    )
+                }),
+        )
+        .collect::<Vec<_>>();
+
+    Modal::new(modal_id).show(ui.ctx(), |ui| {
+        ui.set_width(width);
+        let file_name = path
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap();
+
+        ui.add(
+            egui::Label::new(RichText::from(tr!("modal-delete-report-title", {file: file_name})).heading())
+                .selectable(false),
+        );
+
+        if report.operation_history {
+            ui.label(tr!("modal-delete-report-operation-history"));
+        }
+
+        if !rows.is_empty() {
+            let table = TableBuilder::new(ui)
+                .striped(true)
+                .auto_shrink(true)
+                .resizable(false)
+                .column(Column::auto())
+                .column(Column::remainder());
+            table
+                .header(20.0, |mut header| {
+                    header.col(|ui| {
+                        ui.strong(tr!("modal-delete-report-column-change"));
+                    });
+                    header.col(|ui| {
+                        ui.strong(tr!("modal-delete-report-column-item"));
+                    });
+                })
+                .body(|mut body| {
+                    for (change, item) in rows.iter() {
+                        body.row(18.0, |mut row| {
+                            row.col(|ui| {
+                                ui.label(change);
+                            });
+                            row.col(|ui| {
+                                ui.label(item);
+                            });
+                        })
+                    }
+                });
+        }
+
+        egui::Sides::new().show(
+            ui,
+            |_ui| {},
+            |ui| {
+                if ui
+                    .button(tr!("form-common-button-ok"))
+                    .clicked()
+                {
+                    component.send((key, ProjectUiCommand::ClearDeleteReport))
+                }
+            },
+        );
+    });
+}
@@ -1,6 +1,11 @@
 pub mod add_phase;
+pub mod confirmation;
+pub mod delete_report;
 pub mod errors;
 pub mod placement_orderings;
+pub mod recovery;
+pub mod task_parameters;
+pub mod warnings;
 
 pub mod package_sources;
 
@@ -87,6 +87,10 @@ fn show_form(&self, ui: &mut Ui, form: &Form<PlacementOrderingFields, PlacementO
                                         PlacementSortingMode::Height,
                                         tr!(placement_sorting_mode_to_i18n_key(&PlacementSortingMode::Height)),
                                     ),
+                                    (
+                                        PlacementSortingMode::Nozzle,
+                                        tr!(placement_sorting_mode_to_i18n_key(&PlacementSortingMode::Nozzle)),
+                                    ),
                                     (
                                         PlacementSortingMode::Part,
                                         tr!(placement_sorting_mode_to_i18n_key(&PlacementSortingMode::Part)),
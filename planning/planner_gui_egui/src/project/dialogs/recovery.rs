@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use egui::{Modal, RichText, Ui};
+use egui_i18n::tr;
+
+use crate::project::{ProjectKey, ProjectUiCommand};
+use crate::ui_component::ComponentState;
+
+/// Shown when the project has a recovery snapshot, e.g. after the application exited with unsaved changes.
+pub fn show_recovery_modal(
+    ui: &mut Ui,
+    key: ProjectKey,
+    path: &PathBuf,
+    component: &ComponentState<(ProjectKey, ProjectUiCommand)>,
+) {
+    let modal_id = ui.id().with("recovery");
+
+    Modal::new(modal_id).show(ui.ctx(), |ui| {
+        let file_name = path
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap();
+
+        ui.add(
+            egui::Label::new(RichText::from(tr!("modal-recovery-title", {file: file_name})).heading())
+                .selectable(false),
+        );
+
+        ui.label(tr!("modal-recovery-message"));
+
+        egui::Sides::new().show(
+            ui,
+            |_ui| {},
+            |ui| {
+                if ui
+                    .button(tr!("modal-recovery-button-discard"))
+                    .clicked()
+                {
+                    component.send((key, ProjectUiCommand::DiscardRecovery))
+                }
+                if ui
+                    .button(tr!("modal-recovery-button-restore"))
+                    .clicked()
+                {
+                    component.send((key, ProjectUiCommand::RestoreRecovery))
+                }
+            },
+        );
+    });
+}
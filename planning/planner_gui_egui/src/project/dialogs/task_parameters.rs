@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+
+use egui::{Modal, Ui};
+use egui_i18n::tr;
+use egui_mobius::Value;
+use planner_app::{OperationReference, TaskParameterDefinition, TaskParameterKind, TaskParameterValue, TaskReference};
+use tracing::debug;
+
+use crate::ui_component::{ComponentState, UiComponent};
+
+/// Prompts for the values of the parameters of a custom task, the values are recorded when the task is completed.
+///
+/// Numeric values are entered as text, they are validated, and converted, by the planner core.
+#[derive(Debug)]
+pub struct TaskParametersModal {
+    operation: OperationReference,
+    task: TaskReference,
+    parameters: Vec<TaskParameterDefinition>,
+
+    values: Value<BTreeMap<String, TaskParameterValue>>,
+
+    pub component: ComponentState<TaskParametersModalUiCommand>,
+}
+
+impl TaskParametersModal {
+    pub fn new(operation: OperationReference, task: TaskReference, parameters: Vec<TaskParameterDefinition>) -> Self {
+        let values = parameters
+            .iter()
+            .map(|parameter| {
+                let value = match parameter.kind {
+                    TaskParameterKind::Checkbox => TaskParameterValue::Checkbox(false),
+                    TaskParameterKind::Numeric {
+                        ..
+                    }
+                    | TaskParameterKind::Text => TaskParameterValue::Text(String::new()),
+                };
+                (parameter.name.clone(), value)
+            })
+            .collect();
+
+        Self {
+            operation,
+            task,
+            parameters,
+            values: Value::new(values),
+            component: Default::default(),
+        }
+    }
+
+    /// Every parameter requires a value, text is never empty.
+    fn is_valid(values: &BTreeMap<String, TaskParameterValue>) -> bool {
+        values
+            .values()
+            .all(|value| !matches!(value, TaskParameterValue::Text(text) if text.trim().is_empty()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum TaskParametersModalUiCommand {
+    Submit,
+    Cancel,
+}
+
+#[derive(Debug, Clone)]
+pub enum TaskParametersModalAction {
+    Submit {
+        operation: OperationReference,
+        task: TaskReference,
+        parameter_values: BTreeMap<String, TaskParameterValue>,
+    },
+    CloseDialog,
+}
+
+impl UiComponent for TaskParametersModal {
+    type UiContext<'context> = ();
+    type UiCommand = TaskParametersModalUiCommand;
+    type UiAction = TaskParametersModalAction;
+
+    #[profiling::function]
+    fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
+        let modal_id = ui.id().with("task_parameters_modal");
+
+        Modal::new(modal_id).show(ui.ctx(), |ui| {
+            ui.set_min_width(400.0);
+
+            ui.heading(tr!("modal-task-parameters-title", { task: self.task.to_string() }));
+
+            let mut values = self.values.lock().unwrap();
+
+            egui::Grid::new(ui.id().with("parameters"))
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for parameter in self.parameters.iter() {
+                        ui.label(&parameter.name);
+                        match values.get_mut(&parameter.name) {
+                            Some(TaskParameterValue::Checkbox(checked)) => {
+                                ui.checkbox(checked, "");
+                            }
+                            Some(TaskParameterValue::Text(text)) => {
+                                let hint = match &parameter.kind {
+                                    TaskParameterKind::Numeric {
+                                        min,
+                                        max,
+                                    } => format!(
+                                        "{} .. {}",
+                                        min.map(|min| min.to_string())
+                                            .unwrap_or_default(),
+                                        max.map(|max| max.to_string())
+                                            .unwrap_or_default()
+                                    ),
+                                    _ => String::new(),
+                                };
+                                ui.add(egui::TextEdit::singleline(text).hint_text(hint));
+                            }
+                            _ => {
+                                ui.label("");
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+
+            let is_valid = Self::is_valid(&values);
+
+            egui::Sides::new().show(
+                ui,
+                |_ui| {},
+                |ui| {
+                    if ui
+                        .button(tr!("form-common-button-cancel"))
+                        .clicked()
+                    {
+                        self.component
+                            .send(TaskParametersModalUiCommand::Cancel);
+                    }
+
+                    if ui
+                        .add_enabled(is_valid, egui::Button::new(tr!("form-common-button-ok")))
+                        .clicked()
+                    {
+                        self.component
+                            .send(TaskParametersModalUiCommand::Submit);
+                    }
+                },
+            );
+        });
+    }
+
+    #[profiling::function]
+    fn update<'context>(
+        &mut self,
+        command: Self::UiCommand,
+        _context: &mut Self::UiContext<'context>,
+    ) -> Option<Self::UiAction> {
+        debug!("task parameters command: {:?}", command);
+        match command {
+            TaskParametersModalUiCommand::Submit => Some(TaskParametersModalAction::Submit {
+                operation: self.operation.clone(),
+                task: self.task.clone(),
+                parameter_values: self.values.lock().unwrap().clone(),
+            }),
+            TaskParametersModalUiCommand::Cancel => Some(TaskParametersModalAction::CloseDialog),
+        }
+    }
+}
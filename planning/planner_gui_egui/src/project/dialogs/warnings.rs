@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use egui::{Modal, RichText, Ui};
+use egui_extras::{Column, TableBuilder};
+use egui_i18n::{tr, translate_fluent};
+use i18n::fluent_argument_helpers::args::build_fluent_args;
+use planner_app::Warnings;
+
+/// Translates the warnings and appends them.
+///
+/// Warnings that have already been appended are skipped, the planner core can render more than once for an event and
+/// every render has the warnings of the event.
+pub fn append_warnings(warnings: &mut Vec<(chrono::DateTime<chrono::Utc>, String)>, additional_warnings: Warnings) {
+    for (date_time, warning) in additional_warnings {
+        let args = warning.args();
+        let message = translate_fluent(warning.i18n_key(), &build_fluent_args(&args));
+
+        let warning = (date_time, message);
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+}
+
+/// `on_ok` is called when the warnings have been acknowledged, it should clear the warnings.
+pub fn show_warnings_modal(
+    ui: &mut Ui,
+    path: &PathBuf,
+    warnings: &Vec<(chrono::DateTime<chrono::Utc>, String)>,
+    on_ok: impl FnOnce(),
+) {
+    let modal_id = ui.id().with("warnings");
+
+    let width = ui.ctx().screen_rect().width() * 0.8;
+
+    Modal::new(modal_id).show(ui.ctx(), |ui| {
+        ui.set_width(width);
+        let file_name = path
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap();
+
+        ui.add(
+            egui::Label::new(RichText::from(tr!("modal-warnings-title", {file: file_name})).heading())
+                .selectable(false),
+        );
+
+        let table = TableBuilder::new(ui)
+            .striped(true)
+            .auto_shrink(true)
+            .resizable(false)
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::remainder());
+        table
+            .header(20.0, |mut header| {
+                header.col(|ui| {
+                    ui.strong(tr!("modal-warnings-column-index"));
+                });
+                header.col(|ui| {
+                    ui.strong(tr!("modal-warnings-column-date-time"));
+                });
+                header.col(|ui| {
+                    ui.strong(tr!("modal-warnings-column-warnings"));
+                });
+            })
+            .body(|mut body| {
+                for (index, (date_time, warning)) in warnings.iter().enumerate() {
+                    body.row(18.0, |mut row| {
+                        row.col(|ui| {
+                            ui.label(format!("{}", index));
+                        });
+                        row.col(|ui| {
+                            ui.label(format!("{}", date_time));
+                        });
+                        row.col(|ui| {
+                            ui.label(warning);
+                        });
+                    })
+                }
+            });
+
+        egui::Sides::new().show(
+            ui,
+            |_ui| {},
+            |ui| {
+                if ui
+                    .button(tr!("form-common-button-ok"))
+                    .clicked()
+                {
+                    on_ok();
+                }
+            },
+        );
+    });
+}
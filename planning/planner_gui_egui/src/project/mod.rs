@@ -1,16 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::PathBuf;
 use std::time::SystemTime;
 
 use derivative::Derivative;
 use egui::Ui;
 use egui_dock::Split;
-use egui_i18n::tr;
+use egui_i18n::{tr, translate_fluent};
 use egui_mobius::types::{Enqueue, Value, ValueGuard};
+use i18n::fluent_argument_helpers::args::build_fluent_args;
 use planner_app::{
-    AddOrRemoveAction, Event, FileReference, LibraryConfig, LoadOutSource, ObjectPath, PcbSide, PcbUnitIndex, PcbView,
+    AUTOSAVE_INTERVAL, AddOrRemoveAction, CancellationToken, DeleteReport, DeleteStrategy, Event, FileReference,
+    HitTestQuery, LibraryConfig, LoadOutSource, ObjectPath, ObjectPathPattern, PcbSide, PcbUnitIndex, PcbView,
     PcbViewRequest, PhaseOverview, PhaseReference, PlacementOperation, PlacementPositionUnit, PlacementState,
-    PlacementStatus, ProcessReference, ProjectOverview, ProjectView, ProjectViewRequest, Reference, SetOrClearAction,
+    PlacementStatus, PlacementsSelector, ProcessReference, ProjectIssue, ProjectOverview, ProjectView,
+    ProjectViewRequest, Reference, SetOrClearAction, TreePath, Warnings,
 };
 use regex::Regex;
 use slotmap::new_key_type;
@@ -27,15 +30,19 @@
     UnitAssignmentsTab, UnitAssignmentsTabUi, UnitAssignmentsTabUiAction, UnitAssignmentsTabUiCommand,
     UnitAssignmentsTabUiContext, UpdateUnitAssignmentsArgs,
 };
-use tracing::{debug, error, info, trace};
+use tracing::{debug, error, info, trace, warn};
 
+use crate::config::{Confirmations, Settings, SettingsChange, TableId};
 use crate::file_picker::Picker;
 use crate::planner_app_core::{PlannerCoreService, PlannerError};
 use crate::project::core_helper::ProjectCoreHelper;
 use crate::project::dialogs::add_phase::{AddPhaseModal, AddPhaseModalAction, AddPhaseModalUiCommand};
+use crate::project::dialogs::confirmation::{ConfirmationResponse, show_confirmation_modal};
 use crate::project::dialogs::package_sources::{
     PackageSourcesModal, PackageSourcesModalAction, PackageSourcesModalUiCommand,
 };
+use crate::project::session::ProjectSession;
+use crate::project::tables::placements::PlacementsTableUiCommand;
 use crate::project::tabs::issues_tab::{
     IssuesTab, IssuesTabUi, IssuesTabUiAction, IssuesTabUiCommand, IssuesTabUiContext,
 };
@@ -45,7 +52,7 @@
     ProcessTab, ProcessTabUi, ProcessTabUiAction, ProcessTabUiCommand, ProcessTabUiContext,
 };
 use crate::project::tabs::{ProjectTabAction, ProjectTabContext, ProjectTabUiCommand, ProjectTabs};
-use crate::project::toolbar::{ProjectToolbar, ProjectToolbarAction, ProjectToolbarUiCommand};
+use crate::project::toolbar::{ProjectToolbar, ProjectToolbarAction, ProjectToolbarContext, ProjectToolbarUiCommand};
 use crate::task::Task;
 use crate::ui_component::{ComponentState, UiComponent};
 use crate::ui_util::NavigationPath;
@@ -53,8 +60,9 @@
 //
 // other modules
 //
-mod dialogs;
+pub(crate) mod dialogs;
 mod process;
+pub mod session;
 mod tables;
 pub mod tabs;
 mod toolbar;
@@ -71,6 +79,14 @@ pub enum ProjectAction {
     UiCommand(ProjectUiCommand),
     ShowPcb(PathBuf),
     RequestRepaint,
+    SettingsChanged(SettingsChange),
+    /// The close has been confirmed, see [`ProjectUiCommand::ConfirmClose`].
+    Close,
+    /// The project was loaded or created and its name is known, e.g. to add it to the recent projects.
+    Opened {
+        path: PathBuf,
+        name: String,
+    },
     LocateComponent {
         pcb_file: PathBuf,
         object_path: ObjectPath,
@@ -93,6 +109,22 @@ pub struct Project {
 
     /// list of errors to show
     errors: Vec<(chrono::DateTime<chrono::Utc>, String)>,
+    /// list of warnings to show
+    warnings: Vec<(chrono::DateTime<chrono::Utc>, String)>,
+    /// what was affected by the last delete, shown until dismissed
+    delete_report: Option<DeleteReport>,
+    /// the project has a recovery snapshot, shown until it is restored or discarded
+    recovery_available: bool,
+    /// from the settings of the active profile
+    confirmations: Confirmations,
+    /// the action to confirm, shown until confirmed or cancelled
+    confirmation: Option<ProjectConfirmation>,
+    /// the `egui` input time of the last autosave
+    last_autosave: Value<f64>,
+    /// set when the project has been loaded or created, cleared when the name is known, see [`ProjectAction::Opened`]
+    opened: bool,
+    /// the token of the refresh in progress, the refresh can be cancelled until it completes
+    refresh_token: Option<CancellationToken>,
 
     /// initially empty until the OverviewView has been received and processed.
     processes: Vec<ProcessReference>,
@@ -119,10 +151,17 @@ pub struct Project {
 }
 
 // FUTURE consider moving this into the planner app core itself, so it can be re-used by all apps using the same core.
+/// The segments of the paths are escaped, see [`planner_app::TreePath`], so they never contain a `/`, use [`segment`]
+/// to get the un-escaped value of a captured segment.
 pub mod tree_item {
     use std::sync::LazyLock;
 
-    use regex::Regex;
+    use planner_app::TreePathSegment;
+    use regex::{Captures, Regex};
+
+    pub const PARTS: &str = r"^/project/parts(?:/.*)?$";
+    pub const PLACEMENTS: &str = r"^/project/placements(?:/.*)?$";
+    pub const PLACEMENTS_PHASE: &str = r"^/project/placements/phases/(?<phase>[^/]*){1}$";
 
     pub const PHASES: &str = r"^/project/phases$";
     pub const PHASE: &str = r"^/project/phases/(?<phase>[^/]*){1}$";
@@ -133,6 +172,9 @@ pub mod tree_item {
     pub const PROCESS: &str = r"^/project/processes/(?<process>[^/]*){1}$";
 
     pub struct RegularExpressions {
+        pub parts: Regex,
+        pub placements: Regex,
+        pub placements_phase: Regex,
         pub phases: Regex,
         pub phase: Regex,
         pub phase_loadout: Regex,
@@ -144,6 +186,9 @@ pub struct RegularExpressions {
     impl Default for RegularExpressions {
         fn default() -> Self {
             Self {
+                parts: Regex::new(PARTS).unwrap(),
+                placements: Regex::new(PLACEMENTS).unwrap(),
+                placements_phase: Regex::new(PLACEMENTS_PHASE).unwrap(),
                 phases: Regex::new(PHASES).unwrap(),
                 phase: Regex::new(PHASE).unwrap(),
                 phase_loadout: Regex::new(PHASE_LOADOUT).unwrap(),
@@ -155,6 +200,11 @@ fn default() -> Self {
     }
 
     pub static REGULAR_EXPRESSIONS: LazyLock<RegularExpressions> = LazyLock::new(|| RegularExpressions::default());
+
+    /// Panics if the regular expression has no group with the name.
+    pub fn segment(captures: &Captures, name: &str) -> String {
+        TreePathSegment::unescape(captures.name(name).unwrap().as_str())
+    }
 }
 
 impl Project {
@@ -212,6 +262,14 @@ fn new_inner(
             pcbs_modified: false,
             pcbs: Default::default(),
             errors: Default::default(),
+            warnings: Default::default(),
+            delete_report: None,
+            recovery_available: false,
+            confirmations: Default::default(),
+            confirmation: None,
+            last_autosave: Value::new(0.0),
+            opened: false,
+            refresh_token: None,
             processes: Default::default(),
             library_config: None,
             phases: Default::default(),
@@ -233,6 +291,47 @@ pub fn tabs(&self) -> Value<ProjectTabs> {
         self.project_tabs.clone()
     }
 
+    pub fn session(&self) -> ProjectSession {
+        let tabs = {
+            let project_tabs = self.project_tabs.lock().unwrap();
+            serde_json::to_value(&*project_tabs)
+                .inspect_err(|cause| warn!("Unable to save the session tabs. cause: {}", cause))
+                .ok()
+        };
+
+        let state = self.project_ui_state.lock().unwrap();
+
+        ProjectSession {
+            version: session::SESSION_VERSION,
+            tabs,
+            placements_table: state.placements_ui.table_session(),
+            explorer: state.explorer_tab_ui.session(),
+        }
+    }
+
+    /// Failures are only logged, a session is a convenience, not required to use the project.
+    pub fn save_session(&self) {
+        debug!("Saving project session. path: {}", self.path.display());
+        if let Err(cause) = session::save_session(&self.session(), &self.path) {
+            warn!(
+                "Unable to save the project session. path: {}, cause: {}",
+                self.path.display(),
+                cause
+            );
+        }
+    }
+
+    /// Restores the view state, the tabs are restored when the project is created, see [`ProjectSession::tabs`].
+    pub fn restore_session(&mut self, session: &ProjectSession) {
+        let mut state = self.project_ui_state.lock().unwrap();
+        state
+            .placements_ui
+            .restore_table_session(&session.placements_table);
+        state
+            .explorer_tab_ui
+            .restore_session(session.explorer.clone());
+    }
+
     #[must_use]
     pub fn configure_tabs(&mut self, key: ProjectKey) -> Vec<ProjectUiCommand> {
         let component_sender = self.component.sender.clone();
@@ -271,6 +370,68 @@ pub fn configure_tabs(&mut self, key: ProjectKey) -> Vec<ProjectUiCommand> {
         })
     }
 
+    /// Returns `true` if a tab that uses the view is open.
+    fn is_view_shown(&self, view_request: &ProjectViewRequest) -> bool {
+        let project_tabs = self.project_tabs.lock().unwrap();
+        let is_shown = |f: &dyn Fn(&ProjectTabKind) -> bool| project_tabs.find_tab(f).is_some();
+
+        match view_request {
+            ProjectViewRequest::Overview => is_shown(&|tab| matches!(tab, ProjectTabKind::Overview(_))),
+            ProjectViewRequest::ProjectTree => is_shown(&|tab| matches!(tab, ProjectTabKind::Explorer(_))),
+            ProjectViewRequest::Parts | ProjectViewRequest::PartPackages => {
+                is_shown(&|tab| matches!(tab, ProjectTabKind::Parts(_)))
+            }
+            ProjectViewRequest::Placements => is_shown(&|tab| matches!(tab, ProjectTabKind::Placements(_))),
+            ProjectViewRequest::ProjectReport | ProjectViewRequest::Issues => {
+                is_shown(&|tab| matches!(tab, ProjectTabKind::Issues(_)))
+            }
+            ProjectViewRequest::PhaseOverview {
+                phase,
+            }
+            | ProjectViewRequest::PhasePlacements {
+                phase,
+            } => is_shown(&|tab| matches!(tab, ProjectTabKind::Phase(tab) if tab.phase.eq(phase))),
+            ProjectViewRequest::PhaseLoadOut {
+                phase,
+            } => is_shown(&|tab| matches!(tab, ProjectTabKind::LoadOut(tab) if tab.phase.eq(phase))),
+            ProjectViewRequest::ProcessDefinition {
+                process,
+            } => is_shown(&|tab| matches!(tab, ProjectTabKind::Process(tab) if tab.process.eq(process))),
+            ProjectViewRequest::PcbOverview {
+                pcb,
+            } => is_shown(&|tab| matches!(tab, ProjectTabKind::Pcb(tab) if tab.pcb_index == *pcb)),
+            ProjectViewRequest::PcbUnitAssignments {
+                pcb,
+            } => is_shown(&|tab| matches!(tab, ProjectTabKind::UnitAssignments(tab) if tab.pcb_index == *pcb)),
+            // the phases are used by the placements and overview tabs.
+            ProjectViewRequest::Phases => true,
+            // the settings are used by the placements tables.
+            ProjectViewRequest::Settings => true,
+            // FUTURE these views are not shown in tabs yet.
+            ProjectViewRequest::PlacementRotations
+            | ProjectViewRequest::BoardPreview {
+                ..
+            }
+            | ProjectViewRequest::PcbUnitOverlay {
+                ..
+            }
+            | ProjectViewRequest::PlacementLocation {
+                ..
+            }
+            | ProjectViewRequest::HitTest {
+                ..
+            }
+            | ProjectViewRequest::FeederLayout {
+                ..
+            }
+            | ProjectViewRequest::PlacementSession
+            | ProjectViewRequest::Analytics
+            | ProjectViewRequest::Search {
+                ..
+            } => false,
+        }
+    }
+
     pub fn show_explorer(&mut self) -> Task<ProjectAction> {
         let mut project_tabs = self.project_tabs.lock().unwrap();
         let result = project_tabs.show_tab(|candidate_tab| matches!(candidate_tab, ProjectTabKind::Explorer(_)));
@@ -291,7 +452,7 @@ pub fn show_issues(&mut self) -> Vec<Task<ProjectAction>> {
         }
 
         let tasks = vec![Task::done(ProjectAction::UiCommand(
-            ProjectUiCommand::RequestProjectView(ProjectViewRequest::ProjectReport),
+            ProjectUiCommand::RequestProjectView(ProjectViewRequest::Issues),
         ))];
 
         tasks
@@ -311,6 +472,9 @@ pub fn show_overview(&mut self) -> Vec<Task<ProjectAction>> {
             Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
                 ProjectViewRequest::Phases,
             ))),
+            Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
+                ProjectViewRequest::Settings,
+            ))),
         ];
 
         tasks
@@ -323,9 +487,14 @@ pub fn show_parts(&mut self) -> Task<ProjectAction> {
             project_tabs.add_tab_to_leaf_or_split(ProjectTabKind::Parts(PartsTab::default()), 0.25, Split::Right);
         }
 
-        Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
-            ProjectViewRequest::Parts,
-        )))
+        Task::batch(vec![
+            Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
+                ProjectViewRequest::Parts,
+            ))),
+            Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
+                ProjectViewRequest::PartPackages,
+            ))),
+        ])
     }
 
     pub fn show_placements(&mut self) -> Vec<Task<ProjectAction>> {
@@ -510,6 +679,7 @@ pub fn show_unit_assignments(&mut self, key: ProjectKey, pcb_index: u16) -> Opti
     fn ensure_phase(&self, key: ProjectKey, phase: &Reference) -> bool {
         let phase = phase.clone();
         let mut state = self.project_ui_state.lock().unwrap();
+        let settings = state.settings.clone();
         let mut created = false;
         let _phase_state = state
             .phases_tab_uis
@@ -518,6 +688,7 @@ fn ensure_phase(&self, key: ProjectKey, phase: &Reference) -> bool {
                 created = true;
                 debug!("ensuring phase ui. phase: {:?}", phase);
                 let mut phase_ui = PhaseTabUi::new();
+                phase_ui.apply_settings(settings);
                 phase_ui
                     .component
                     .configure_mapper(self.component.sender.clone(), {
@@ -690,7 +861,10 @@ fn handle_issues(key: &ProjectKey, path: &NavigationPath) -> Option<ProjectActio
 
         #[must_use]
         fn handle_placements(key: &ProjectKey, path: &NavigationPath) -> Option<ProjectAction> {
-            if path.eq(&"/project/placements".into()) {
+            if tree_item::REGULAR_EXPRESSIONS
+                .placements
+                .is_match(path)
+            {
                 let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::ShowPlacements));
                 Some(ProjectAction::Task(*key, task))
             } else {
@@ -700,7 +874,10 @@ fn handle_placements(key: &ProjectKey, path: &NavigationPath) -> Option<ProjectA
 
         #[must_use]
         fn handle_parts(key: &ProjectKey, path: &NavigationPath) -> Option<ProjectAction> {
-            if path.eq(&"/project/parts".into()) {
+            if tree_item::REGULAR_EXPRESSIONS
+                .parts
+                .is_match(path)
+            {
                 let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::ShowParts));
                 Some(ProjectAction::Task(*key, task))
             } else {
@@ -724,11 +901,24 @@ fn handle_phase(key: &ProjectKey, path: &NavigationPath) -> Option<ProjectAction
                 .phase
                 .captures(&path)
             {
-                let phase_reference: String = captures
-                    .name("phase")
-                    .unwrap()
-                    .as_str()
-                    .to_string();
+                let phase_reference = tree_item::segment(&captures, "phase");
+                debug!("phase_reference: {}", phase_reference);
+
+                let reference = Reference::from_raw(phase_reference);
+                let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::ShowPhase(reference)));
+                Some(ProjectAction::Task(*key, task))
+            } else {
+                None
+            }
+        }
+
+        #[must_use]
+        fn handle_placements_phase(key: &ProjectKey, path: &NavigationPath) -> Option<ProjectAction> {
+            if let Some(captures) = tree_item::REGULAR_EXPRESSIONS
+                .placements_phase
+                .captures(&path)
+            {
+                let phase_reference = tree_item::segment(&captures, "phase");
                 debug!("phase_reference: {}", phase_reference);
 
                 let reference = Reference::from_raw(phase_reference);
@@ -745,11 +935,7 @@ fn handle_process(key: &ProjectKey, path: &NavigationPath) -> Option<ProjectActi
                 .process
                 .captures(&path)
             {
-                let process_reference: String = captures
-                    .name("process")
-                    .unwrap()
-                    .as_str()
-                    .to_string();
+                let process_reference = tree_item::segment(&captures, "process");
                 debug!("process_reference: {}", process_reference);
 
                 let reference = Reference::from_raw(process_reference);
@@ -766,11 +952,7 @@ fn handle_phase_loadout(key: &ProjectKey, path: &NavigationPath) -> Option<Proje
                 .phase_loadout
                 .captures(&path)
             {
-                let phase_reference: String = captures
-                    .name("phase")
-                    .unwrap()
-                    .as_str()
-                    .to_string();
+                let phase_reference = tree_item::segment(&captures, "phase");
                 debug!("phase_reference: {}", phase_reference);
 
                 let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::ShowPhaseLoadout {
@@ -837,6 +1019,7 @@ fn handle_unit_assignments(key: &ProjectKey, path: &NavigationPath) -> Option<Pr
             handle_phase,
             handle_phase_loadout,
             handle_phases,
+            handle_placements_phase,
             handle_placements,
             handle_process,
             handle_unit_assignments,
@@ -870,7 +1053,7 @@ fn update_placement(
         object_path: ObjectPath,
         new_placement: PlacementState,
         old_placement: PlacementState,
-    ) -> (Vec<Task<ProjectAction>>, Vec<UpdatePlacementAction>) {
+    ) -> Vec<Task<ProjectAction>> {
         let mut tasks = vec![];
 
         fn handle_phase(
@@ -879,60 +1062,29 @@ fn handle_phase(
             object_path: &ObjectPath,
             new_placement: &PlacementState,
             old_placement: &PlacementState,
-        ) -> Option<(Vec<UpdatePlacementAction>, Result<Vec<ProjectAction>, ProjectAction>)> {
+        ) -> Option<Result<Vec<ProjectAction>, ProjectAction>> {
             if !new_placement
                 .phase
                 .eq(&old_placement.phase)
             {
-                // it's possible that assigning/clearing a placement could make the phase complete
-
-                let (phase, operation, mut update_placement_actions) =
-                    match (&new_placement.phase, &old_placement.phase) {
-                        (Some(new_phase), None) => (new_phase, SetOrClearAction::Set, vec![
-                            UpdatePlacementAction::RefreshPhasePlacements {
-                                phase: new_phase.clone(),
-                            },
-                            UpdatePlacementAction::RefreshPhaseOverview {
-                                phase: new_phase.clone(),
-                            },
-                        ]),
-                        (Some(new_phase), Some(old_phase)) => (new_phase, SetOrClearAction::Set, vec![
-                            UpdatePlacementAction::RefreshPhasePlacements {
-                                phase: new_phase.clone(),
-                            },
-                            UpdatePlacementAction::RefreshPhasePlacements {
-                                phase: old_phase.clone(),
-                            },
-                            UpdatePlacementAction::RefreshPhaseOverview {
-                                phase: new_phase.clone(),
-                            },
-                            UpdatePlacementAction::RefreshPhaseOverview {
-                                phase: old_phase.clone(),
-                            },
-                        ]),
-                        (None, Some(old_phase)) => (old_phase, SetOrClearAction::Clear, vec![
-                            UpdatePlacementAction::RefreshPhasePlacements {
-                                phase: old_phase.clone(),
-                            },
-                            UpdatePlacementAction::RefreshPhaseOverview {
-                                phase: old_phase.clone(),
-                            },
-                        ]),
-                        _ => unreachable!(),
-                    };
-
-                update_placement_actions.push(UpdatePlacementAction::RefreshPhases);
+                // the core invalidates the phase views, it's possible that assigning/clearing a placement could make
+                // the phase complete
+                let (phase, operation) = match (&new_placement.phase, &old_placement.phase) {
+                    (Some(new_phase), _) => (new_phase, SetOrClearAction::Set),
+                    (None, Some(old_phase)) => (old_phase, SetOrClearAction::Clear),
+                    _ => unreachable!(),
+                };
 
-                Some((
-                    update_placement_actions,
+                Some(
                     planner_core_service
                         .update(Event::AssignPlacementsToPhase {
                             phase: phase.clone(),
                             operation,
-                            placements: exact_match(&object_path.to_string()),
+                            placements: ObjectPathPattern::exact(object_path).into(),
+                            force: false,
                         })
                         .into_actions(),
-                ))
+                )
             } else {
                 None
             }
@@ -944,7 +1096,7 @@ fn handle_placed(
             object_path: &ObjectPath,
             new_placement: &PlacementState,
             old_placement: &PlacementState,
-        ) -> Option<(Vec<UpdatePlacementAction>, Result<Vec<ProjectAction>, ProjectAction>)> {
+        ) -> Option<Result<Vec<ProjectAction>, ProjectAction>> {
             if new_placement.phase.is_none() {
                 error!(
                     "Attempt to place a placement that has not been assigned to a phase. placement: {:?}",
@@ -960,25 +1112,14 @@ fn handle_placed(
                     PlacementStatus::Pending => PlacementOperation::Reset,
                 };
 
-                let phase = new_placement.phase.as_ref().unwrap();
-
-                Some((
-                    vec![
-                        UpdatePlacementAction::RefreshPhaseOverview {
-                            phase: phase.clone(),
-                        },
-                        UpdatePlacementAction::RefreshPhasePlacements {
-                            phase: phase.clone(),
-                        },
-                        UpdatePlacementAction::RefreshPhases,
-                    ],
+                Some(
                     planner_core_service
                         .update(Event::RecordPlacementsOperation {
-                            object_path_patterns: vec![exact_match(&object_path.to_string())],
+                            placements: vec![ObjectPathPattern::exact(object_path).into()],
                             operation,
                         })
                         .into_actions(),
-                ))
+                )
             } else {
                 None
             }
@@ -996,15 +1137,12 @@ enum Operation {
 
         let action_handlers = [handle_phase, handle_placed];
 
-        let mut update_placement_actions = vec![];
-
         for (operation, handler) in operations
             .into_iter()
             .zip(action_handlers.into_iter())
         {
             trace!("update placement, trying handler for operation: {:?}", operation);
-            if let Some((additional_update_placement_actions, core_result)) =
-                handler(planner_core_service, &key, &object_path, &new_placement, &old_placement)
+            if let Some(core_result) = handler(planner_core_service, &key, &object_path, &new_placement, &old_placement)
             {
                 debug!("update placement, applicable handler found. operation: {:?}", operation);
                 match core_result {
@@ -1015,7 +1153,6 @@ enum Operation {
                             .map(Task::done)
                             .collect();
                         tasks.extend(effect_tasks);
-                        update_placement_actions.extend(additional_update_placement_actions);
                     }
                     Err(service_error) => {
                         tasks.push(Task::done(service_error));
@@ -1037,51 +1174,72 @@ enum Operation {
         )));
         tasks.push(final_task);
 
-        update_placement_actions.dedup();
-
-        (tasks, update_placement_actions)
+        tasks
     }
 
-    fn handle_update_placement_actions(
-        tasks: &mut Vec<Task<ProjectAction>>,
-        actions: Vec<UpdatePlacementAction>,
-        request_issues_refresh: &mut bool,
-    ) {
-        // Updating placements can create/clear issues.
-        *request_issues_refresh = true;
-
-        for action in actions {
-            if let Some(task) = match action {
-                UpdatePlacementAction::RefreshPhases => Some(Task::done(ProjectAction::UiCommand(
-                    ProjectUiCommand::RequestProjectView(ProjectViewRequest::Phases),
-                ))),
-                UpdatePlacementAction::RefreshPhaseOverview {
-                    phase,
-                } => Some(Task::done(ProjectAction::UiCommand(
-                    ProjectUiCommand::RequestProjectView(ProjectViewRequest::PhaseOverview {
-                        phase,
-                    }),
-                ))),
-                UpdatePlacementAction::RefreshPhasePlacements {
-                    phase,
-                } => Some(Task::done(ProjectAction::UiCommand(
-                    ProjectUiCommand::RequestProjectView(ProjectViewRequest::PhasePlacements {
-                        phase,
-                    }),
-                ))),
-            } {
-                tasks.push(task);
+    /// Applies an event to a selection of the placements, so that the placements are only matched once, the selection
+    /// is released afterwards, see [`Event::SelectPlacements`].
+    fn update_selected_placements(
+        planner_core_service: &mut PlannerCoreService,
+        filter: ObjectPathPattern,
+        make_event: impl FnOnce(PlacementsSelector) -> Event,
+    ) -> Vec<Task<ProjectAction>> {
+        let mut tasks = vec![];
+
+        let selection = match planner_core_service
+            .update(Event::SelectPlacements {
+                filter,
+            })
+            .into_actions()
+        {
+            Ok(actions) => {
+                tasks.extend(actions.into_iter().map(Task::done));
+                planner_core_service.placement_selection()
+            }
+            Err(service_error) => {
+                tasks.push(Task::done(service_error));
+                None
+            }
+        };
+
+        if let Some(selection) = selection {
+            let events = [
+                make_event(PlacementsSelector::Selection(selection)),
+                Event::ReleasePlacementSelection {
+                    selection,
+                },
+            ];
+            for event in events {
+                match planner_core_service
+                    .update(event)
+                    .into_actions()
+                {
+                    Ok(actions) => tasks.extend(actions.into_iter().map(Task::done)),
+                    Err(service_error) => tasks.push(Task::done(service_error)),
+                }
             }
         }
+
+        tasks
     }
 
+    /// Locates the component in the gerber viewers of the PCB window, and in the PCB tab of the project, using the
+    /// location from the core, see [`ProjectViewRequest::PlacementLocation`].
     fn locate_component(
         &self,
+        key: ProjectKey,
         object_path: ObjectPath,
         pcb_side: PcbSide,
         design_position: PlacementPositionUnit,
         unit_position: PlacementPositionUnit,
     ) -> Option<ProjectAction> {
+        self.component.send((
+            key,
+            ProjectUiCommand::RequestProjectView(ProjectViewRequest::PlacementLocation {
+                object_path: object_path.clone(),
+            }),
+        ));
+
         let (pcb_number, unit_number) = object_path
             .pcb_instance_and_unit()
             .unwrap();
@@ -1137,13 +1295,6 @@ fn pick_pcb_file(&mut self) {
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum UpdatePlacementAction {
-    RefreshPhases,
-    RefreshPhaseOverview { phase: PhaseReference },
-    RefreshPhasePlacements { phase: PhaseReference },
-}
-
 pub struct ProjectContext {
     pub key: ProjectKey,
 }
@@ -1169,7 +1320,10 @@ fn ui<'context>(&self, ui: &mut Ui, context: &mut Self::UiContext<'context>) {
                 ui.spinner();
             }
 
-            self.toolbar.ui(ui, &mut ());
+            self.toolbar
+                .ui(ui, &mut ProjectToolbarContext {
+                    refreshing: self.refresh_token.is_some(),
+                });
         });
 
         //
@@ -1186,6 +1340,55 @@ fn ui<'context>(&self, ui: &mut Ui, context: &mut Self::UiContext<'context>) {
 
         if !self.errors.is_empty() {
             dialogs::errors::show_errors_modal(ui, *key, &self.path, &self.errors, &self.component);
+        } else if !self.warnings.is_empty() {
+            dialogs::warnings::show_warnings_modal(ui, &self.path, &self.warnings, || {
+                self.component
+                    .send((*key, ProjectUiCommand::ClearWarnings))
+            });
+        } else if let Some(delete_report) = &self.delete_report {
+            dialogs::delete_report::show_delete_report_modal(ui, *key, &self.path, delete_report, &self.component);
+        } else if self.recovery_available {
+            dialogs::recovery::show_recovery_modal(ui, *key, &self.path, &self.component);
+        } else if let Some(confirmation) = &self.confirmation {
+            let (title, message) = match confirmation {
+                ProjectConfirmation::Close => {
+                    let file_name = self
+                        .path
+                        .file_name()
+                        .unwrap()
+                        .to_str()
+                        .unwrap();
+                    (
+                        tr!("modal-confirmation-close-project-title", { file: file_name }),
+                        tr!("modal-confirmation-close-project-message"),
+                    )
+                }
+                ProjectConfirmation::DeletePhase(reference) => (
+                    tr!("modal-confirmation-delete-phase-title", { phase: reference.to_string() }),
+                    tr!("modal-confirmation-delete-phase-message"),
+                ),
+            };
+            show_confirmation_modal(ui, title, message, |response| {
+                self.component
+                    .send((*key, ProjectUiCommand::ConfirmationResponse(response)))
+            });
+        }
+
+        //
+        // Autosave
+        //
+        {
+            let now = ui.input(|input| input.time);
+            let interval = AUTOSAVE_INTERVAL.as_secs_f64();
+            let mut last_autosave = self.last_autosave.lock().unwrap();
+            if now - *last_autosave >= interval {
+                *last_autosave = now;
+                self.component
+                    .send((*key, ProjectUiCommand::Autosave));
+            }
+            // the ui is otherwise only repainted on input
+            ui.ctx()
+                .request_repaint_after_secs((*last_autosave + interval - now) as f32);
         }
 
         //
@@ -1224,8 +1427,6 @@ fn update<'context>(
     ) -> Option<Self::UiAction> {
         let (key, command) = command;
 
-        let mut request_issues_refresh = false;
-
         let action = match command {
             ProjectUiCommand::None => None,
             ProjectUiCommand::Create => {
@@ -1240,6 +1441,7 @@ fn update<'context>(
                     .when_ok(key, |_| Some(ProjectUiCommand::Created))
             }
             ProjectUiCommand::Created => {
+                self.opened = true;
                 let show_explorer_task = self.show_explorer();
                 let show_overview_tasks = self.show_overview();
                 let show_issues_tasks = self.show_issues();
@@ -1259,6 +1461,7 @@ fn update<'context>(
                     .when_ok(key, |_tasks| Some(ProjectUiCommand::Loaded))
             }
             ProjectUiCommand::Loaded => {
+                self.opened = true;
                 let show_explorer_task = self.show_explorer();
                 let show_overview_tasks = self.show_overview();
                 let show_issues_tasks = self.show_issues();
@@ -1270,6 +1473,7 @@ fn update<'context>(
             }
             ProjectUiCommand::Save => {
                 debug!("Saving project. path: {}", self.path.display());
+                self.save_session();
                 self.planner_core_service
                     .update(Event::Save)
                     .when_ok(key, |_| Some(ProjectUiCommand::Saved))
@@ -1278,53 +1482,35 @@ fn update<'context>(
                 debug!("Saved project.");
                 None
             }
-            ProjectUiCommand::ProjectRefreshed => {
-                debug!("Project refreshed.");
-
-                // FUTURE The current approach is to know exactly what needs to happen, however, the child elements/tabs
-                //        themselves should be responsible for taking appropriate actions, this requires ui components
-                //        to subscribe to refresh events or something and there is no mechanism for that yet.
-
-                let mut requests = vec![
-                    // Update anything that uses data from views
-                    ProjectViewRequest::Overview,
-                    // Update the tree, since phases may have been deleted, etc.
-                    ProjectViewRequest::ProjectTree,
-                    // refresh phases
-                    ProjectViewRequest::Phases,
-                    // refresh placements
-                    ProjectViewRequest::Placements,
-                ];
-
-                let phase_requests = self
-                    .phases
-                    .iter()
-                    .flat_map(|phase| {
-                        vec![
-                            ProjectViewRequest::PhaseOverview {
-                                phase: phase.phase_reference.clone(),
-                            },
-                            ProjectViewRequest::PhasePlacements {
-                                phase: phase.phase_reference.clone(),
-                            },
-                        ]
-                    })
-                    .collect::<Vec<_>>();
-                requests.extend(phase_requests);
-
-                let tasks = requests
-                    .into_iter()
-                    .map(|request| Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(request))))
-                    .collect::<Vec<_>>();
-
-                Some(ProjectAction::Task(key, Task::batch(tasks)))
+            ProjectUiCommand::Autosave => self
+                .planner_core_service
+                .update(Event::Autosave)
+                .when_ok(key, |_| None),
+            ProjectUiCommand::RestoreRecovery => {
+                // the views that depend on the project are invalidated by the planner core
+                self.planner_core_service
+                    .update(Event::RestoreRecovery)
+                    .when_ok(key, |_| None)
             }
+            ProjectUiCommand::DiscardRecovery => self
+                .planner_core_service
+                .update(Event::DiscardRecovery)
+                .when_ok(key, |_| None),
             ProjectUiCommand::SetModifiedState {
                 project_modified,
                 pcbs_modified,
+                warnings,
+                delete_report,
+                recovery_available,
             } => {
                 self.modified = project_modified;
                 self.pcbs_modified = pcbs_modified;
+                self.recovery_available = recovery_available;
+                dialogs::warnings::append_warnings(&mut self.warnings, warnings);
+                if let Some(delete_report) = delete_report.filter(|report| !report.is_empty()) {
+                    self.delete_report
+                        .replace(delete_report);
+                }
                 // TODO remove the logical or here when AddPcbs has been reworked.
                 Some(ProjectAction::SetModifiedState(project_modified || pcbs_modified))
             }
@@ -1334,8 +1520,10 @@ fn update<'context>(
             //
             ProjectUiCommand::Error(error) => {
                 match error {
-                    PlannerError::CoreError(message) => {
-                        self.errors.push(message);
+                    PlannerError::CoreError((date_time, error)) => {
+                        let args = error.args();
+                        let message = translate_fluent(error.i18n_key(), &build_fluent_args(&args));
+                        self.errors.push((date_time, message));
                     }
                     PlannerError::Other(message) => {
                         self.errors.push(message);
@@ -1347,6 +1535,62 @@ fn update<'context>(
                 self.errors.clear();
                 None
             }
+            ProjectUiCommand::ClearWarnings => {
+                self.warnings.clear();
+                None
+            }
+            ProjectUiCommand::ClearDeleteReport => {
+                self.delete_report.take();
+                None
+            }
+            ProjectUiCommand::ChangeSettings(change) => Some(ProjectAction::SettingsChanged(change)),
+            ProjectUiCommand::ConfirmClose => {
+                self.confirmation = Some(ProjectConfirmation::Close);
+                None
+            }
+            ProjectUiCommand::Close => Some(ProjectAction::Close),
+            ProjectUiCommand::ConfirmationResponse(response) => {
+                let confirmation = self.confirmation.take()?;
+                let ConfirmationResponse::Confirmed {
+                    dont_ask_again,
+                } = response
+                else {
+                    return None;
+                };
+
+                let mut confirmations = self.confirmations;
+                let command = match confirmation {
+                    ProjectConfirmation::Close => {
+                        confirmations.close_modified_project = false;
+                        ProjectUiCommand::Close
+                    }
+                    ProjectConfirmation::DeletePhase(reference) => {
+                        confirmations.remove_phase = false;
+                        ProjectUiCommand::DeletePhase(reference)
+                    }
+                };
+
+                let mut tasks = vec![];
+                if dont_ask_again {
+                    tasks.push(Task::done(ProjectAction::UiCommand(ProjectUiCommand::ChangeSettings(
+                        SettingsChange::Confirmations(confirmations),
+                    ))));
+                }
+                tasks.push(Task::done(ProjectAction::UiCommand(command)));
+
+                Some(ProjectAction::Task(key, Task::batch(tasks)))
+            }
+            ProjectUiCommand::SettingsChanged(settings) => {
+                self.confirmations = settings.confirmations;
+
+                let mut state = self.project_ui_state.lock().unwrap();
+                state.placements_ui.apply_table_layout(
+                    settings
+                        .table_layouts
+                        .get(&TableId::Placements),
+                );
+                None
+            }
 
             //
             // project views
@@ -1356,7 +1600,13 @@ fn update<'context>(
                 let event = match view_request {
                     ProjectViewRequest::Overview => Event::RequestOverviewView {},
                     ProjectViewRequest::Parts => Event::RequestPartStatesView {},
-                    ProjectViewRequest::Placements => Event::RequestPlacementsView {},
+                    ProjectViewRequest::PartPackages => Event::RequestPartPackagesView {},
+                    ProjectViewRequest::PlacementRotations => Event::RequestPlacementRotationsView {},
+                    // FUTURE request pages of the placements when the tables virtualize data fetching
+                    ProjectViewRequest::Placements => Event::RequestPlacementsView {
+                        page: None,
+                    },
+                    ProjectViewRequest::PlacementSession => Event::RequestPlacementSessionView {},
                     ProjectViewRequest::Phases => Event::RequestPhasesView {},
                     ProjectViewRequest::ProjectTree => Event::RequestProjectTreeView {},
                     ProjectViewRequest::PhaseOverview {
@@ -1369,37 +1619,93 @@ fn update<'context>(
                     } => Event::RequestPhaseLoadOutView {
                         phase_reference: phase,
                     },
+                    ProjectViewRequest::FeederLayout {
+                        phase,
+                    } => Event::RequestFeederLayoutView {
+                        phase_reference: phase,
+                    },
                     ProjectViewRequest::PhasePlacements {
                         phase,
                     } => Event::RequestPhasePlacementsView {
                         phase_reference: phase,
+                        page: None,
                     },
                     ProjectViewRequest::PcbOverview {
                         pcb,
                     } => Event::RequestProjectPcbOverviewView {
                         pcb,
                     },
+                    ProjectViewRequest::BoardPreview {
+                        pcb,
+                    } => Event::RequestBoardPreviewView {
+                        pcb,
+                    },
                     ProjectViewRequest::PcbUnitAssignments {
                         pcb,
                     } => Event::RequestPcbUnitAssignmentsView {
                         pcb,
                     },
+                    ProjectViewRequest::PcbUnitOverlay {
+                        pcb,
+                    } => Event::RequestPcbUnitOverlayView {
+                        pcb,
+                    },
+                    ProjectViewRequest::PlacementLocation {
+                        object_path,
+                    } => Event::RequestPlacementLocationView {
+                        object_path,
+                    },
+                    ProjectViewRequest::HitTest {
+                        query,
+                    } => Event::RequestHitTestView {
+                        pcb: query.pcb,
+                        position: query.position,
+                        side: query.pcb_side,
+                    },
                     ProjectViewRequest::ProcessDefinition {
                         process,
                     } => Event::RequestProcessDefinitionView {
                         process_reference: process,
                     },
                     ProjectViewRequest::ProjectReport => Event::RequestProjectReportView {},
+                    ProjectViewRequest::Issues => Event::RequestIssuesView {},
+                    ProjectViewRequest::Analytics => Event::RequestAnalyticsView {},
+                    ProjectViewRequest::Search {
+                        query,
+                    } => Event::RequestSearchView {
+                        query,
+                    },
+                    ProjectViewRequest::Settings => Event::RequestSettingsView {},
                 };
 
                 self.planner_core_service
                     .update(event)
                     .when_ok(key, |_| None)
             }
+            ProjectUiCommand::ViewInvalidated(view_request) => {
+                if self.is_view_shown(&view_request) {
+                    let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
+                        view_request,
+                    )));
+                    Some(ProjectAction::Task(key, task))
+                } else {
+                    trace!("Ignoring invalidated view. view_request: {:?}", view_request);
+                    None
+                }
+            }
             ProjectUiCommand::ProjectView(view) => {
+                let mut action = None;
                 match view {
                     ProjectView::Overview(project_overview) => {
                         trace!("project overview: {:?}", project_overview);
+                        if self.opened {
+                            self.opened = false;
+                            action = Some(ProjectAction::Opened {
+                                path: self.path.clone(),
+                                name: project_overview.name.clone(),
+                            });
+                        }
+
                         self.update_processes(&project_overview);
                         self.update_pcbs(&project_overview);
                         self.update_library_config(&project_overview.library_config);
@@ -1442,6 +1748,65 @@ fn update<'context>(
                             unit_assignments_ui.update_project_pcb_overview(project_pcb_overview.clone());
                         }
                     }
+                    ProjectView::BoardPreview(board_preview) => {
+                        trace!("board_preview: {:?}", board_preview);
+
+                        let mut state = self.project_ui_state.lock().unwrap();
+
+                        if let Some(pcb_ui) = state
+                            .pcb_tab_uis
+                            .get_mut(&(board_preview.index as usize))
+                        {
+                            pcb_ui.update_board_preview(board_preview);
+                        }
+                    }
+                    ProjectView::PcbUnitOverlay(pcb_unit_overlay) => {
+                        trace!("pcb_unit_overlay: {:?}", pcb_unit_overlay);
+
+                        let mut state = self.project_ui_state.lock().unwrap();
+
+                        if let Some(pcb_ui) = state
+                            .pcb_tab_uis
+                            .get_mut(&(pcb_unit_overlay.index as usize))
+                        {
+                            pcb_ui.update_unit_overlay(pcb_unit_overlay);
+                        }
+                    }
+                    ProjectView::PlacementLocation(placement_location) => {
+                        trace!("placement_location: {:?}", placement_location);
+
+                        let mut state = self.project_ui_state.lock().unwrap();
+
+                        if let Some(pcb_ui) = state
+                            .pcb_tab_uis
+                            .get_mut(&(placement_location.pcb as usize))
+                        {
+                            pcb_ui.locate_placement(placement_location);
+                        }
+                    }
+                    ProjectView::HitTest(hit_test_results) => {
+                        trace!("hit_test_results: {:?}", hit_test_results);
+
+                        // nearest first
+                        let object_paths = hit_test_results
+                            .hits
+                            .into_iter()
+                            .map(|hit| hit.object_path)
+                            .collect::<Vec<_>>();
+
+                        let has_hits = !object_paths.is_empty();
+
+                        self.project_ui_state
+                            .lock()
+                            .unwrap()
+                            .placements_ui
+                            .probe_placements(object_paths);
+
+                        if has_hits {
+                            let tasks = self.show_placements();
+                            action = Some(ProjectAction::Task(key, Task::batch(tasks)));
+                        }
+                    }
                     ProjectView::PcbUnitAssignments(pcb_unit_assignments) => {
                         trace!("pcb_unit_assignments: {:?}", pcb_unit_assignments);
 
@@ -1523,6 +1888,26 @@ fn update<'context>(
                             .parts_tab_ui
                             .update_part_states(part_states, self.processes.clone())
                     }
+                    ProjectView::PlacementRotations(placement_rotations) => {
+                        // FUTURE add a tab to review the effective rotations, and to set the rotation offsets.
+                        trace!("placement_rotations: {:?}", placement_rotations);
+                    }
+                    ProjectView::PlacementSession(placement_session) => {
+                        // FUTURE add an operator-mode tab that guides the user through the placement session.
+                        trace!("placement_session: {:?}", placement_session);
+                    }
+                    ProjectView::FeederLayout(feeder_layout) => {
+                        // FUTURE add a tab that renders the feeder bank diagram for operator setup.
+                        trace!("feeder_layout: {:?}", feeder_layout);
+                    }
+                    ProjectView::PartPackages(part_packages) => {
+                        trace!("part_packages: {:?}", part_packages);
+                        let mut state = self.project_ui_state.lock().unwrap();
+
+                        state
+                            .parts_tab_ui
+                            .update_part_packages(part_packages)
+                    }
                     ProjectView::PhaseLoadOut(load_out) => {
                         trace!("load_out: {:?}", load_out);
                         let load_out_source = load_out.source.clone();
@@ -1540,14 +1925,47 @@ fn update<'context>(
                     ProjectView::ProjectReport(report) => {
                         info!("report:\n{:?}", report);
 
-                        request_issues_refresh = false;
+                        let mut state = self.project_ui_state.lock().unwrap();
+
+                        state.issues_ui.update_issues(
+                            report
+                                .issues
+                                .into_iter()
+                                .map(ProjectIssue::from)
+                                .collect(),
+                        )
+                    }
+                    ProjectView::Issues(project_issues) => {
+                        info!("issues:\n{:?}", project_issues);
 
                         let mut state = self.project_ui_state.lock().unwrap();
 
-                        state.issues_ui.update_report(report)
+                        state
+                            .issues_ui
+                            .update_issues(project_issues.issues)
+                    }
+                    ProjectView::Analytics(analytics) => {
+                        // FUTURE add a tab with charts of the phase durations and part skip/defect rates.
+                        trace!("analytics: {:?}", analytics);
+                    }
+                    ProjectView::Search(search_results) => {
+                        // FUTURE add a quick-open dialog, results use the same paths as the project tree items.
+                        trace!("search results: {:?}", search_results);
+                    }
+                    ProjectView::Settings(settings) => {
+                        // FUTURE add a settings tab, the settings can only be changed using the CLI.
+                        trace!("settings: {:?}", settings);
+                        let mut state = self.project_ui_state.lock().unwrap();
+                        state
+                            .placements_ui
+                            .apply_settings(settings.clone());
+                        for phase_ui in state.phases_tab_uis.values_mut() {
+                            phase_ui.apply_settings(settings.clone());
+                        }
+                        state.settings = settings;
                     }
                 }
-                None
+                action
             }
 
             //
@@ -1566,6 +1984,11 @@ fn update<'context>(
                         // TODO add/use a suitable core event
                         None
                     }
+                    PcbViewRequest::PanelPreview {
+                        path,
+                    } => Some(Event::RequestPcbPanelPreviewView {
+                        path,
+                    }),
                 };
                 // TODO remove the `if let`
                 if let Some(event) = event {
@@ -1590,6 +2013,15 @@ fn update<'context>(
 
                     None
                 }
+                PcbView::PanelPreview(panel_preview) => {
+                    let mut state = self.project_ui_state.lock().unwrap();
+
+                    for (_index, pcb_ui) in state.pcb_tab_uis.iter_mut() {
+                        pcb_ui.update_panel_preview(panel_preview.clone());
+                    }
+
+                    None
+                }
                 PcbView::PanelSizing(_panel_sizing) => {
                     // nothing requests this view
                     None
@@ -1602,28 +2034,55 @@ fn update<'context>(
             ProjectUiCommand::ToolbarCommand(toolbar_command) => {
                 let action = self
                     .toolbar
-                    .update(toolbar_command, &mut ());
+                    .update(toolbar_command, &mut ProjectToolbarContext {
+                        refreshing: self.refresh_token.is_some(),
+                    });
                 match action {
                     Some(ProjectToolbarAction::ShowProjectExplorer) => {
                         let task = self.show_explorer();
                         Some(ProjectAction::Task(key, task))
                     }
-                    Some(ProjectToolbarAction::GenerateArtifacts) => self
+                    Some(ProjectToolbarAction::GenerateArtifacts {
+                        artifacts,
+                    }) => self
                         .planner_core_service
-                        .update(Event::GenerateArtifacts)
+                        .update(Event::GenerateArtifacts {
+                            token: None,
+                            artifacts,
+                        })
                         .when_ok(key, |_| None),
                     Some(ProjectToolbarAction::Refresh) => {
-                        let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::RefreshPcbs)).chain(
-                            Task::done(ProjectAction::UiCommand(ProjectUiCommand::RefreshFromDesignVariants)),
-                        );
+                        let token = CancellationToken::new();
+                        self.refresh_token = Some(token.clone());
+
+                        let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::RefreshPcbs {
+                            token: Some(token.clone()),
+                        }))
+                        .chain(Task::done(ProjectAction::UiCommand(
+                            ProjectUiCommand::RefreshFromDesignVariants {
+                                token: Some(token),
+                            },
+                        )));
                         Some(ProjectAction::Task(key, task))
                     }
+                    Some(ProjectToolbarAction::CancelRefresh) => {
+                        let token = self.refresh_token.take()?;
+                        info!("Cancelling refresh.");
+
+                        // the refresh may be in progress, or waiting for the next step
+                        token.cancel();
+                        self.planner_core_service
+                            .update(Event::CancelOperation {
+                                token,
+                            })
+                            .when_ok(key, |_| None)
+                    }
                     Some(ProjectToolbarAction::RemoveUnusedPlacements) => self
                         .planner_core_service
                         .update(Event::RemoveUsedPlacements {
                             phase: None,
                         })
-                        .when_ok(key, |_| Some(ProjectUiCommand::ProjectRefreshed)),
+                        .when_ok(key, |_| None),
                     Some(ProjectToolbarAction::PickPcbFile) => {
                         self.pick_pcb_file();
                         None
@@ -1639,7 +2098,7 @@ fn update<'context>(
                     Some(ProjectToolbarAction::ResetOperations) => self
                         .planner_core_service
                         .update(Event::ResetOperations {})
-                        .when_ok(key, |_| Some(ProjectUiCommand::ProjectRefreshed)),
+                        .when_ok(key, |_| None),
                     None => None,
                 }
             }
@@ -1649,7 +2108,9 @@ fn update<'context>(
             //
             ProjectUiCommand::PcbRemoved => Some(ProjectAction::Task(
                 key,
-                Task::done(ProjectAction::UiCommand(ProjectUiCommand::RefreshFromDesignVariants)),
+                Task::done(ProjectAction::UiCommand(ProjectUiCommand::RefreshFromDesignVariants {
+                    token: None,
+                })),
             )),
             ProjectUiCommand::PcbFilePicked(pcb_path) => {
                 // FUTURE consider storing a relative file if the pcb_path is in a subdirectory of the project path.
@@ -1657,9 +2118,6 @@ fn update<'context>(
 
                 let mut tasks = vec![];
 
-                // Adding PCBs can clear issues.
-                request_issues_refresh = true;
-
                 match self
                     .planner_core_service
                     .update(Event::AddPcb {
@@ -1702,9 +2160,6 @@ fn update<'context>(
                                 .into_actions()
                             {
                                 Ok(actions) => {
-                                    // Adding phases can clear issues.
-                                    request_issues_refresh = true;
-
                                     let mut tasks = actions
                                         .into_iter()
                                         .map(Task::done)
@@ -1752,12 +2207,7 @@ fn update<'context>(
                                     packages_source: args.packages_source,
                                     package_mappings_source: args.package_mappings_source,
                                 })
-                                .when_ok(key, |_| {
-                                    // we raise this event, since changing package souces
-                                    // can have an effect of the sort order of placements if they are sorted by
-                                    // something that uses package mappings and packages.
-                                    Some(ProjectUiCommand::ProjectRefreshed)
-                                })
+                                .when_ok(key, |_| None)
                         }
                         Some(PackageSourcesModalAction::CloseDialog) => {
                             self.package_sources_modal.take();
@@ -1946,7 +2396,7 @@ fn update<'context>(
                     Some(IssuesTabUiAction::RefreshIssues) => Some(ProjectAction::Task(
                         key,
                         Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
-                            ProjectViewRequest::ProjectReport,
+                            ProjectViewRequest::Issues,
                         ))),
                     )),
                 }
@@ -1971,24 +2421,39 @@ fn update<'context>(
                         None
                     }
                     Some(OverviewTabUiAction::DeletePhase(reference)) => {
-                        // Deleting phases can create issues (no phases, unassigned placements)
-                        request_issues_refresh = true;
-
-                        self.planner_core_service
-                            .update(Event::DeletePhase {
-                                reference: reference.clone(),
-                            })
-                            .when_ok(key, |_| Some(ProjectUiCommand::PhaseDeleted(reference)))
+                        if self.confirmations.remove_phase {
+                            self.confirmation = Some(ProjectConfirmation::DeletePhase(reference));
+                            None
+                        } else {
+                            Some(ProjectAction::Task(
+                                key,
+                                Task::done(ProjectAction::UiCommand(ProjectUiCommand::DeletePhase(reference))),
+                            ))
+                        }
                     }
-                    Some(OverviewTabUiAction::RemovePcb(pcb_unit_index)) => {
-                        // Deleting pcbs can create issues (no pcbs, etc)
-                        request_issues_refresh = true;
+                    Some(OverviewTabUiAction::RemovePcb(pcb_unit_index)) => self
+                        .planner_core_service
+                        .update(Event::RemovePcb {
+                            index: pcb_unit_index,
+                            strategy: DeleteStrategy::Block,
+                        })
+                        .when_ok(key, |_| Some(ProjectUiCommand::PcbRemoved)),
+                    Some(OverviewTabUiAction::StartBuildRun {
+                        quantity,
+                    }) => {
+                        // the views that depend on the statuses are invalidated by the planner core
                         self.planner_core_service
-                            .update(Event::RemovePcb {
-                                index: pcb_unit_index,
+                            .update(Event::StartBuildRun {
+                                quantity,
                             })
-                            .when_ok(key, |_| Some(ProjectUiCommand::PcbRemoved))
+                            .when_ok(key, |_| None)
                     }
+                    Some(OverviewTabUiAction::ActivateBuildRunInstance(instance)) => self
+                        .planner_core_service
+                        .update(Event::ActivateBuildRunInstance {
+                            instance,
+                        })
+                        .when_ok(key, |_| None),
                 }
             }
             ProjectUiCommand::PartsTabUiCommand(command) => {
@@ -2124,14 +2589,13 @@ fn update<'context>(
                         new_placement,
                         old_placement,
                     }) => {
-                        let (mut tasks, actions) = Self::update_placement(
+                        let tasks = Self::update_placement(
                             &mut self.planner_core_service,
                             key,
                             object_path,
                             new_placement,
                             old_placement,
                         );
-                        Self::handle_update_placement_actions(&mut tasks, actions, &mut request_issues_refresh);
 
                         Some(ProjectAction::Task(key, Task::batch(tasks)))
                     }
@@ -2139,30 +2603,28 @@ fn update<'context>(
                         phase,
                         manufacturer_pattern,
                         mpn_pattern,
-                    }) => {
-                        // Adding parts to loadouts can clear issues
-                        request_issues_refresh = true;
-
-                        self.planner_core_service
-                            .update(Event::AddPartsToLoadout {
-                                phase,
-                                manufacturer: manufacturer_pattern,
-                                mpn: mpn_pattern,
-                            })
-                            .when_ok(key, |_| None)
-                    }
+                    }) => self
+                        .planner_core_service
+                        .update(Event::AddPartsToLoadout {
+                            phase,
+                            manufacturer: manufacturer_pattern,
+                            mpn: mpn_pattern,
+                        })
+                        .when_ok(key, |_| None),
                     Some(PhaseTabUiAction::SetPlacementOrderings(args)) => self
                         .planner_core_service
                         .update(Event::SetPlacementOrdering {
                             phase: phase.clone(),
                             placement_orderings: args.orderings,
                         })
-                        .when_ok(key, |_| Some(ProjectUiCommand::RefreshPhase(phase))),
+                        .when_ok(key, |_| None),
                     Some(PhaseTabUiAction::TaskAction {
                         phase,
                         operation,
                         task,
                         action,
+                        overridden_rules,
+                        parameter_values,
                     }) => self
                         .planner_core_service
                         .update(Event::RecordPhaseOperation {
@@ -2170,14 +2632,16 @@ fn update<'context>(
                             operation,
                             task,
                             action,
+                            overridden_rules,
+                            parameter_values,
                         })
-                        .when_ok(key, |_| Some(ProjectUiCommand::RefreshPhase(phase))),
+                        .when_ok(key, |_| None),
                     Some(PhaseTabUiAction::LocatePlacement {
                         object_path,
                         pcb_side,
                         design_position,
                         unit_position,
-                    }) => self.locate_component(object_path, pcb_side, design_position, unit_position),
+                    }) => self.locate_component(key, object_path, pcb_side, design_position, unit_position),
                     Some(PhaseTabUiAction::Refresh {
                         phase,
                     }) => {
@@ -2347,9 +2811,6 @@ fn update<'context>(
                         part,
                         feeder,
                     }) => {
-                        // Changing loadouts can create/clear issues.
-                        request_issues_refresh = true;
-
                         debug!(
                             "update feeder. phase: {:?}, part: {:?}, feeder: {:?}",
                             phase, part, feeder
@@ -2376,19 +2837,21 @@ fn update<'context>(
                 match placements_ui_action {
                     Some(PlacementsTabUiAction::None) => None,
                     Some(PlacementsTabUiAction::RequestRepaint) => Some(ProjectAction::RequestRepaint),
+                    Some(PlacementsTabUiAction::TableLayoutChanged(layout)) => Some(ProjectAction::SettingsChanged(
+                        SettingsChange::TableLayout(TableId::Placements, layout),
+                    )),
                     Some(PlacementsTabUiAction::UpdatePlacement {
                         object_path,
                         new_placement,
                         old_placement,
                     }) => {
-                        let (mut tasks, actions) = Self::update_placement(
+                        let tasks = Self::update_placement(
                             &mut self.planner_core_service,
                             key,
                             object_path,
                             new_placement,
                             old_placement,
                         );
-                        Self::handle_update_placement_actions(&mut tasks, actions, &mut request_issues_refresh);
 
                         Some(ProjectAction::Task(key, Task::batch(tasks)))
                     }
@@ -2397,44 +2860,41 @@ fn update<'context>(
                         pcb_side,
                         design_position,
                         unit_position,
-                    }) => self.locate_component(object_path, pcb_side, design_position, unit_position),
+                    }) => self.locate_component(key, object_path, pcb_side, design_position, unit_position),
                     None => None,
                     Some(PlacementsTabUiAction::ApplyPlacementsAction(selection, action)) => {
-                        let mut tasks = Vec::with_capacity(selection.len());
-                        let mut actions = Vec::with_capacity(selection.len());
-
-                        for item in selection {
-                            let new_phase = match &action {
-                                PlacementsTabUiApplyAction::RemovePhase(_phase) => None,
-                                PlacementsTabUiApplyAction::ApplyPhase(phase) => Some(phase.clone()),
-                            };
-
-                            if item.state.phase.eq(&new_phase) {
-                                continue;
-                            }
-
-                            let mut new_placement = item.state.clone();
-                            new_placement.phase = new_phase;
+                        let (phase, operation) = match action {
+                            PlacementsTabUiApplyAction::ApplyPhase(phase) => (phase, SetOrClearAction::Set),
+                            PlacementsTabUiApplyAction::RemovePhase(phase) => (phase, SetOrClearAction::Clear),
+                        };
+                        let new_phase = matches!(operation, SetOrClearAction::Set).then(|| phase.clone());
+
+                        // only the placements whose phase changes
+                        let items = selection
+                            .into_iter()
+                            .filter(|item| !item.state.phase.eq(&new_phase))
+                            .collect::<Vec<_>>();
 
-                            let old_placement = item.state.clone();
+                        if items.is_empty() {
+                            None
+                        } else {
+                            let object_paths = items
+                                .into_iter()
+                                .map(|item| item.path)
+                                .collect::<BTreeSet<_>>();
 
-                            let (item_tasks, item_actions) = Self::update_placement(
+                            let tasks = Self::update_selected_placements(
                                 &mut self.planner_core_service,
-                                key,
-                                item.path,
-                                new_placement,
-                                old_placement,
+                                ObjectPathPattern::Paths(object_paths),
+                                |placements| Event::AssignPlacementsToPhase {
+                                    phase,
+                                    operation,
+                                    placements,
+                                    force: false,
+                                },
                             );
-
-                            tasks.extend(item_tasks);
-                            actions.extend(item_actions);
+                            Some(ProjectAction::Task(key, Task::batch(tasks)))
                         }
-
-                        actions.dedup_by(|a, b| a == b);
-
-                        Self::handle_update_placement_actions(&mut tasks, actions, &mut request_issues_refresh);
-
-                        Some(ProjectAction::Task(key, Task::batch(tasks)))
                     }
                 }
             }
@@ -2468,6 +2928,45 @@ fn update<'context>(
                         ))),
                     )),
                     Some(PcbTabUiAction::ShowPcb(pcb_path)) => Some(ProjectAction::ShowPcb(pcb_path)),
+                    Some(PcbTabUiAction::RequestPanelPreview(path)) => Some(ProjectAction::Task(
+                        key,
+                        Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestPcbView(
+                            PcbViewRequest::PanelPreview {
+                                path,
+                            },
+                        ))),
+                    )),
+                    Some(PcbTabUiAction::RequestBoardPreview(pcb_index)) => Some(ProjectAction::Task(
+                        key,
+                        Task::batch(vec![
+                            Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
+                                ProjectViewRequest::BoardPreview {
+                                    pcb: pcb_index,
+                                },
+                            ))),
+                            Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
+                                ProjectViewRequest::PcbUnitOverlay {
+                                    pcb: pcb_index,
+                                },
+                            ))),
+                        ]),
+                    )),
+                    Some(PcbTabUiAction::HitTest {
+                        pcb,
+                        position,
+                        side,
+                    }) => Some(ProjectAction::Task(
+                        key,
+                        Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
+                            ProjectViewRequest::HitTest {
+                                query: HitTestQuery {
+                                    pcb,
+                                    position,
+                                    pcb_side: side,
+                                },
+                            },
+                        ))),
+                    )),
                 }
             }
             ProjectUiCommand::UnitAssignmentsTabUiCommand {
@@ -2489,9 +2988,6 @@ fn update<'context>(
                         pcb_index,
                         variant_map,
                     })) => {
-                        // Changing assignments can create/clear issues.
-                        request_issues_refresh = true;
-
                         let mut events = vec![];
 
                         for (pcb_unit_index, variant_name) in variant_map.iter().enumerate() {
@@ -2572,6 +3068,16 @@ fn update<'context>(
                 ];
                 Some(ProjectAction::Task(key, Task::batch(tasks)))
             }
+            ProjectUiCommand::DeletePhase(reference) => {
+                self.planner_core_service
+                    .update(Event::DeletePhase {
+                        reference: reference.clone(),
+                        // phases with assigned placements, or recorded operations, are refused, the
+                        // refusal is shown in the errors modal.
+                        strategy: DeleteStrategy::Block,
+                    })
+                    .when_ok(key, |_| Some(ProjectUiCommand::PhaseDeleted(reference)))
+            }
             ProjectUiCommand::PhaseDeleted(phase) => {
                 self.phases
                     .retain(|it| !it.phase_reference.eq(&phase));
@@ -2582,83 +3088,66 @@ fn update<'context>(
                 let mut ui_state = self.project_ui_state.lock().unwrap();
                 ui_state.phases_tab_uis.remove(&phase);
 
-                let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::ProjectRefreshed));
-                Some(ProjectAction::Task(key, task))
+                None
             }
 
             //
             // other
             //
-            ProjectUiCommand::RefreshFromDesignVariants => {
+            ProjectUiCommand::RefreshFromDesignVariants {
+                token,
+            } => {
                 info!("Refreshing from design variants.");
+                // the last step of a refresh, completed, failed or cancelled
+                if token.is_some() && self.refresh_token == token {
+                    self.refresh_token = None;
+                }
                 self.planner_core_service
-                    .update(Event::RefreshFromDesignVariants)
-                    .when_ok(key, |_| Some(ProjectUiCommand::ProjectRefreshed))
+                    .update(Event::RefreshFromDesignVariants {
+                        token,
+                    })
+                    .when_ok(key, |_| None)
             }
-            ProjectUiCommand::RefreshPcbs => {
+            ProjectUiCommand::RefreshPcbs {
+                token,
+            } => {
                 info!("Refreshing PCBs.");
                 self.planner_core_service
-                    .update(Event::RefreshPcbs)
-                    .when_ok(key, |_| Some(ProjectUiCommand::PcbsRefreshed))
-            }
-            ProjectUiCommand::PcbsRefreshed => {
-                info!("PCBs refreshed.");
-
-                let mut tasks = vec![];
-                for (index, _path) in self.pcbs.iter().enumerate() {
-                    let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
-                        ProjectViewRequest::PcbOverview {
-                            pcb: index as u16,
-                        },
-                    )));
-                    tasks.push(task);
-                }
-
-                // an updated PCB can effect designs and variants.
-                let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
-                    ProjectViewRequest::ProjectTree,
-                )));
-                tasks.push(task);
-
-                Some(ProjectAction::Task(key, Task::batch(tasks)))
+                    .update(Event::RefreshPcbs {
+                        token,
+                    })
+                    .when_ok(key, |_| None)
             }
             ProjectUiCommand::ProcessChanged {
                 process,
             } => {
                 info!("Process changed. process: {}", process);
 
-                let tasks = vec![
-                    Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
-                        ProjectViewRequest::ProjectTree,
-                    ))),
-                    Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
-                        ProjectViewRequest::Overview,
-                    ))),
-                    Task::done(ProjectAction::UiCommand(ProjectUiCommand::RequestProjectView(
-                        ProjectViewRequest::ProcessDefinition {
-                            process: process.clone(),
-                        },
-                    ))),
-                ];
-
+                // the core invalidates the tree, the overview and the process definition
                 let state = self.project_ui_state.lock().unwrap();
                 // if a phase tab that uses the process is open, it needs to be refreshed now too
                 for (_phase, tab_ui) in &state.phases_tab_uis {
                     tab_ui.on_process_changed(&process);
                 }
-                Some(ProjectAction::Task(key, Task::batch(tasks)))
+                None
             }
-        };
 
-        //
-        // Issues refresh
-        //
-        if request_issues_refresh {
-            self.component.send((
-                key,
-                ProjectUiCommand::RequestProjectView(ProjectViewRequest::ProjectReport),
-            ))
-        }
+            //
+            // commands
+            //
+            ProjectUiCommand::GenerateArtifacts => {
+                let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::ToolbarCommand(
+                    ProjectToolbarUiCommand::GenerateArtifactsClicked,
+                )));
+                Some(ProjectAction::Task(key, task))
+            }
+            ProjectUiCommand::LocateNextPlacement => {
+                let task = Task::done(ProjectAction::UiCommand(ProjectUiCommand::PlacementsTabUiCommand(
+                    PlacementsTabUiCommand::PlacementsTableUiCommand(PlacementsTableUiCommand::LocateNextPlacement),
+                )));
+                Some(ProjectAction::Task(key, task))
+            }
+        };
 
         action
     }
@@ -2680,6 +3169,9 @@ pub struct ProjectUiState {
     placements_ui: PlacementsTabUi,
     process_tab_uis: HashMap<ProcessReference, ProcessTabUi>,
     unit_assignment_tab_uis: HashMap<usize, UnitAssignmentsTabUi>,
+
+    /// initially the default settings until the settings view has been received and processed.
+    settings: ProjectSettings,
 }
 
 impl ProjectUiState {
@@ -2701,6 +3193,7 @@ pub fn new(
             placements_ui: PlacementsTabUi::new(),
             process_tab_uis: HashMap::default(),
             unit_assignment_tab_uis: HashMap::default(),
+            settings: ProjectSettings::default(),
         };
 
         instance
@@ -2762,6 +3255,13 @@ pub enum ProjectTabKind {
     UnitAssignments(UnitAssignmentsTab),
 }
 
+/// An action that is only performed once confirmed, see [`Confirmations`].
+#[derive(Debug, Clone)]
+enum ProjectConfirmation {
+    Close,
+    DeletePhase(PhaseReference),
+}
+
 #[derive(Debug, Clone)]
 pub enum ProjectUiCommand {
     None,
@@ -2769,6 +3269,9 @@ pub enum ProjectUiCommand {
     SetModifiedState {
         project_modified: bool,
         pcbs_modified: bool,
+        warnings: Warnings,
+        delete_report: Option<DeleteReport>,
+        recovery_available: bool,
     },
 
     //
@@ -2776,6 +3279,20 @@ pub enum ProjectUiCommand {
     //
     Error(PlannerError),
     ClearErrors,
+    ClearWarnings,
+    ClearDeleteReport,
+
+    /// The settings of the active profile, sent when opened and when the settings change.
+    SettingsChanged(Settings),
+    ChangeSettings(SettingsChange),
+
+    //
+    // confirmations
+    //
+    /// Asks for confirmation before closing, sent when a modified project is closed.
+    ConfirmClose,
+    Close,
+    ConfirmationResponse(ConfirmationResponse),
 
     //
     // projects
@@ -2786,15 +3303,21 @@ pub enum ProjectUiCommand {
     Loaded,
     Save,
     Saved,
+    Autosave,
+    RestoreRecovery,
+    DiscardRecovery,
     RequestProjectView(ProjectViewRequest),
     ProjectView(ProjectView),
-    ProjectRefreshed,
+    /// The view is out-of-date, it is requested again only if it is shown.
+    ViewInvalidated(ProjectViewRequest),
 
     //
     // phases
     //
     RefreshPhases,
     RefreshPhase(PhaseReference),
+    /// Deletes the phase without asking for confirmation.
+    DeletePhase(PhaseReference),
     PhaseDeleted(PhaseReference),
 
     //
@@ -2871,26 +3394,35 @@ pub enum ProjectUiCommand {
         command: ProcessTabUiCommand,
     },
 
-    RefreshFromDesignVariants,
-    RefreshPcbs,
-    PcbsRefreshed,
+    /// The token is the token of the refresh, see [`Project::refresh_token`], if any.
+    RefreshFromDesignVariants {
+        token: Option<CancellationToken>,
+    },
+    RefreshPcbs {
+        token: Option<CancellationToken>,
+    },
     ProcessChanged {
         process: ProcessReference,
     },
+
+    //
+    // commands, see `crate::ui_commands::CommandId`
+    //
+    GenerateArtifacts,
+    /// Locates the placement after the last located placement, in the placements table's order.
+    LocateNextPlacement,
 }
 
-fn project_path_from_view_path(view_path: &String) -> NavigationPath {
+fn project_path_from_view_path(view_path: &TreePath) -> NavigationPath {
     let project_path = NavigationPath::new(format!("/project{}", view_path).to_string());
     project_path
 }
 
-fn view_path_from_project_path(project_path: &NavigationPath) -> Option<String> {
+fn view_path_from_project_path(project_path: &NavigationPath) -> Option<TreePath> {
     let view_path = project_path
-        .to_string()
-        .split("/project")
-        .collect::<Vec<&str>>()
-        .get(1)?
-        .to_string();
+        .strip_prefix("/project")?
+        .parse::<TreePath>()
+        .ok()?;
     Some(view_path)
 }
 
@@ -2955,14 +3487,23 @@ fn into_project_action(action: PlannerAction) -> ProjectAction {
             PlannerAction::SetModifiedState {
                 project_modified,
                 pcbs_modified,
+                warnings,
+                delete_report,
+                recovery_available,
             } => ProjectAction::UiCommand(ProjectUiCommand::SetModifiedState {
                 project_modified,
                 pcbs_modified,
+                warnings,
+                delete_report,
+                recovery_available,
             }),
             PlannerAction::ProjectView(project_view) => {
                 ProjectAction::UiCommand(ProjectUiCommand::ProjectView(project_view))
             }
             PlannerAction::PcbView(pcb_view) => ProjectAction::UiCommand(ProjectUiCommand::PcbView(pcb_view)),
+            PlannerAction::ViewInvalidated(view_request) => {
+                ProjectAction::UiCommand(ProjectUiCommand::ViewInvalidated(view_request))
+            }
         }
     }
 
@@ -0,0 +1,206 @@
+//! Project sessions, the open tabs and view state of a project, saved to a sidecar file next to the project file, see
+//! [`build_session_file_path`], and restored the next time the project is opened.
+//!
+//! The session is saved when the project is saved, when the project is closed and when the application state is saved,
+//! e.g. when the application exits.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use planner_app::{ObjectPath, TreePath};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::filter::FilterState;
+use crate::project::tabs::ProjectTabs;
+
+/// Incremented when the session changes in a way that older sessions cannot be restored.
+///
+/// * `2` - the row ordering of tables uses object paths, instead of row indexes.
+pub const SESSION_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ProjectSession {
+    pub version: u32,
+    /// The serialized [`ProjectTabs`], including the tab layout and the active tabs.
+    ///
+    /// Kept as a value, so that a change to the tabs only causes the tabs to be reset, not the rest of the session.
+    pub tabs: Option<serde_json::Value>,
+    pub placements_table: TableSession,
+    pub explorer: ExplorerSession,
+}
+
+impl ProjectSession {
+    /// `None` if the tabs cannot be restored, e.g. they were saved by an older version.
+    pub fn tabs(&self) -> Option<ProjectTabs> {
+        let tabs = self.tabs.clone()?;
+
+        serde_json::from_value(tabs)
+            .inspect_err(|cause| warn!("Unable to restore the session tabs. cause: {}", cause))
+            .ok()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct TableSession {
+    pub filter: FilterState,
+    /// Object paths of the rows, in display order, `None` for the default order.
+    ///
+    /// Row indexes are not used, since they change when the project changes.
+    pub row_ordering: Option<Vec<ObjectPath>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ExplorerSession {
+    /// Paths are used, instead of node ids, since the nodes change when the project changes.
+    pub expanded: Vec<TreePath>,
+}
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("IO error. cause: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid session file. cause: {0}")]
+    InvalidSessionFile(#[from] serde_json::Error),
+}
+
+/// e.g. `project-job1.mpnp.json` -> `project-job1.mpnp.json.session`
+pub fn build_session_file_path(project_path: &Path) -> PathBuf {
+    let mut file_name = project_path
+        .file_name()
+        .map(|file_name| file_name.to_os_string())
+        .unwrap_or_default();
+    file_name.push(".session");
+
+    project_path.with_file_name(file_name)
+}
+
+pub fn save_session(session: &ProjectSession, project_path: &Path) -> Result<(), SessionError> {
+    let file = File::create(build_session_file_path(project_path))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, session)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// `None` if there is no session, or it was saved by an incompatible version.
+pub fn load_session(project_path: &Path) -> Result<Option<ProjectSession>, SessionError> {
+    let path = build_session_file_path(project_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let file = File::open(path)?;
+    // the version is checked first, older sessions are not expected to be deserializable.
+    let session: serde_json::Value = serde_json::from_reader(BufReader::new(file))?;
+
+    let version = session
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or_default();
+    if version != SESSION_VERSION as u64 {
+        warn!(
+            "Ignoring session, unsupported version. version: {}, supported: {}",
+            version, SESSION_VERSION
+        );
+        return Ok(None);
+    }
+
+    Ok(Some(serde_json::from_value(session)?))
+}
+
+#[cfg(test)]
+mod session_tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    pub fn session_file_path() {
+        // expect
+        assert_eq!(
+            build_session_file_path(Path::new("projects/project-job1.mpnp.json")),
+            PathBuf::from("projects/project-job1.mpnp.json.session")
+        );
+    }
+
+    #[test]
+    pub fn invalid_tabs_are_not_restored() {
+        // given
+        let session = ProjectSession {
+            version: SESSION_VERSION,
+            tabs: Some(serde_json::json!({ "unknown": true })),
+            ..ProjectSession::default()
+        };
+
+        // expect
+        assert!(session.tabs().is_none());
+    }
+
+    #[test]
+    pub fn saved_sessions_are_loaded() {
+        // given
+        let directory = tempfile::tempdir().unwrap();
+        let project_path = directory
+            .path()
+            .join("project-job1.mpnp.json");
+        let session = ProjectSession {
+            version: SESSION_VERSION,
+            tabs: None,
+            placements_table: TableSession {
+                filter: FilterState::default(),
+                row_ordering: Some(vec![
+                    ObjectPath::from_str("pcb=1::unit=1::ref_des=R2").unwrap(),
+                    ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap(),
+                ]),
+            },
+            explorer: ExplorerSession {
+                expanded: vec![TreePath::root().join("phases")],
+            },
+        };
+
+        // when
+        save_session(&session, &project_path).unwrap();
+        let loaded_session = load_session(&project_path).unwrap();
+
+        // then
+        assert_eq!(loaded_session, Some(session));
+    }
+
+    #[test]
+    pub fn sessions_of_other_versions_are_ignored() {
+        // given
+        let directory = tempfile::tempdir().unwrap();
+        let project_path = directory
+            .path()
+            .join("project-job1.mpnp.json");
+        std::fs::write(
+            build_session_file_path(&project_path),
+            r#"{ "version": 1, "placements_table": { "row_ordering": [1, 0] } }"#,
+        )
+        .unwrap();
+
+        // when
+        let loaded_session = load_session(&project_path).unwrap();
+
+        // then
+        assert_eq!(loaded_session, None);
+    }
+
+    #[test]
+    pub fn missing_sessions_are_not_an_error() {
+        // given
+        let directory = tempfile::tempdir().unwrap();
+        let project_path = directory
+            .path()
+            .join("project-job1.mpnp.json");
+
+        // expect
+        assert_eq!(load_session(&project_path).unwrap(), None);
+    }
+}
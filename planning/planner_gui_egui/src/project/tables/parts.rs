@@ -1,4 +1,4 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Display;
 
 use derivative::Derivative;
@@ -10,7 +10,7 @@
 use egui_i18n::tr;
 use egui_mobius::Value;
 use egui_mobius::types::Enqueue;
-use planner_app::{Part, PartStates, PartWithState, ProcessReference, RefDes};
+use planner_app::{Package, Part, PartPackages, PartStates, PartWithState, ProcessReference, RefDes};
 use tracing::{debug, info, trace};
 
 use crate::filter::{Filter, FilterUiAction, FilterUiCommand, FilterUiContext};
@@ -19,12 +19,13 @@
 mod columns {
     pub const MANUFACTURER_COL: usize = 0;
     pub const MPN_COL: usize = 1;
-    pub const QUANTITY_COL: usize = 2;
-    pub const PROCESSES_COL: usize = 3;
-    pub const REF_DES_SET_COL: usize = 4;
+    pub const PACKAGE_COL: usize = 2;
+    pub const QUANTITY_COL: usize = 3;
+    pub const PROCESSES_COL: usize = 4;
+    pub const REF_DES_SET_COL: usize = 5;
 
     /// count of columns
-    pub const COLUMN_COUNT: usize = 5;
+    pub const COLUMN_COUNT: usize = 6;
 }
 use columns::*;
 
@@ -33,6 +34,7 @@ pub struct PartDataSource {
     rows: Vec<PartWithState>,
 
     processes: Vec<ProcessReference>,
+    packages: BTreeMap<Part, Package>,
 }
 
 #[derive(Debug)]
@@ -81,12 +83,25 @@ pub fn new(mut processes: Vec<ProcessReference>) -> Self {
         Self {
             processes,
             rows: Default::default(),
+            packages: Default::default(),
         }
     }
 
     pub fn update_parts(&mut self, mut part_states: PartStates) {
         self.rows = part_states.parts.drain(..).collect();
     }
+
+    pub fn update_packages(&mut self, part_packages: PartPackages) {
+        self.packages = part_packages
+            .parts
+            .into_iter()
+            .filter_map(|part_package| {
+                part_package
+                    .package
+                    .map(|package| (part_package.part, package))
+            })
+            .collect();
+    }
 }
 
 impl PartRenderer {
@@ -205,6 +220,12 @@ fn render_cell(&self, ui: &mut Ui, cell_index: CellIndex, source: &PartDataSourc
         let _ = match cell_index.column {
             MANUFACTURER_COL => ui.label(&row.part.manufacturer),
             MPN_COL => ui.label(&row.part.mpn),
+            PACKAGE_COL => match source.packages.get(&row.part) {
+                Some(package) => ui
+                    .label(&package.name)
+                    .on_hover_text(package_details(package)),
+                None => ui.label(""),
+            },
             PROCESSES_COL => {
                 // Build in the same order as self.processes, specifically not just iterating over `row.processes`
                 let processes = source
@@ -294,6 +315,12 @@ pub fn update_parts(&mut self, part_states: PartStates) {
         source.update_parts(part_states);
     }
 
+    pub fn update_packages(&mut self, part_packages: PartPackages) {
+        let (source, _renderer, _editor, _editor_state) = &mut *self.source.lock().unwrap();
+
+        source.update_packages(part_packages);
+    }
+
     pub fn filter_ui(&self, ui: &mut Ui) {
         self.filter
             .ui(ui, &mut FilterUiContext::default());
@@ -344,6 +371,9 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
                 AxisParameters::default()
                     .name(tr!("table-parts-column-mpn"))
                     .default_dimension(200.0),
+                AxisParameters::default()
+                    .name(tr!("table-parts-column-package"))
+                    .default_dimension(150.0),
                 AxisParameters::default()
                     .name(tr!("table-parts-column-quantity"))
                     .default_dimension(100.0),
@@ -468,6 +498,30 @@ fn update<'context>(
     }
 }
 
+fn package_details(package: &Package) -> String {
+    let mut lines = vec![package.name.clone()];
+    if let Some(dimensions) = &package.dimensions_mm {
+        lines.push(format!(
+            "{}: {} x {} x {}",
+            tr!("table-parts-package-size"),
+            dimensions.size_x(),
+            dimensions.size_y(),
+            dimensions.size_z()
+        ));
+    }
+    if let Some(pitch) = &package.lead_pitch_mm {
+        lines.push(format!("{}: {}", tr!("table-parts-package-pitch"), pitch));
+    }
+    if let Some(pin1_orientation) = &package.pin1_orientation_degrees {
+        lines.push(format!(
+            "{}: {}°",
+            tr!("table-parts-package-pin1-orientation"),
+            pin1_orientation
+        ));
+    }
+    lines.join("\n")
+}
+
 fn refdes_set_to_string(ref_des_set: &BTreeSet<RefDes>) -> String {
     ref_des_set
         .iter()
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use derivative::Derivative;
 use eda_units::eda_units::angle::AngleUnit;
@@ -14,15 +14,17 @@
 use egui_mobius::types::Enqueue;
 use planner_app::{
     ObjectPath, PcbSide, PhaseOverview, PhaseReference, PlacementPositionUnit, PlacementState, PlacementStatus,
-    PlacementsItem, Reference,
+    PlacementsItem, ProjectSettings, Reference,
 };
 use tracing::{debug, info, trace};
 
-use crate::filter::{Filter, FilterUiAction, FilterUiCommand, FilterUiContext};
+use crate::config::TableLayout;
+use crate::filter::{Filter, FilterState, FilterUiAction, FilterUiCommand, FilterUiContext};
 use crate::i18n::conversions::{
     pcb_side_to_i18n_key, placement_operation_status_to_i18n_key, placement_place_to_i18n_key,
     placement_project_status_to_i18n_key,
 };
+use crate::project::session::TableSession;
 use crate::ui_component::{ComponentState, UiComponent};
 
 mod columns {
@@ -53,8 +55,14 @@ pub struct PlacementsDataSource {
 #[derive(Debug)]
 pub struct PlacementsRenderer {
     rows_to_filter: Vec<usize>,
+    /// e.g. the placements near a position clicked in a PCB view.
+    highlighted: BTreeSet<ObjectPath>,
+    /// The last clicked or located placement, see [`PlacementsTableUi::next_placement`].
+    current: Option<ObjectPath>,
     row_ordering: Option<Vec<usize>>,
     column_ordering: Option<Vec<usize>>,
+    /// The units and rotation direction of the positions, see [`PlacementsTableUi::apply_settings`].
+    settings: ProjectSettings,
 }
 
 #[derive(Debug)]
@@ -139,8 +147,11 @@ impl PlacementsRenderer {
     pub fn new() -> Self {
         Self {
             rows_to_filter: Default::default(),
+            highlighted: Default::default(),
+            current: None,
             row_ordering: None,
             column_ordering: None,
+            settings: ProjectSettings::default(),
         }
     }
 }
@@ -340,6 +351,11 @@ impl DeferredTableRenderer<PlacementsDataSource> for PlacementsRenderer {
     fn render_cell(&self, ui: &mut Ui, cell_index: CellIndex, source: &PlacementsDataSource) {
         let row = &source.rows[cell_index.row];
 
+        let previous_text_color = ui.visuals().override_text_color;
+        if self.highlighted.contains(&row.path) {
+            ui.visuals_mut().override_text_color = Some(ui.visuals().selection.stroke.color);
+        }
+
         let _ = match cell_index.column {
             OBJECT_PATH_COL => ui.label(&row.path.to_string()),
             REF_DES_COL => ui.label(row.state.placement.ref_des.to_string()),
@@ -349,9 +365,21 @@ fn render_cell(&self, ui: &mut Ui, cell_index: CellIndex, source: &PlacementsDat
             }
             MANUFACTURER_COL => ui.label(&row.state.placement.part.manufacturer),
             MPN_COL => ui.label(&row.state.placement.part.mpn),
-            ROTATION_COL => ui.label(format!("{}", &row.state.unit_position.rotation)),
-            X_COL => ui.label(format!("{}", &row.state.unit_position.x)),
-            Y_COL => ui.label(format!("{}", &row.state.unit_position.y)),
+            ROTATION_COL => ui.label(format!(
+                "{}",
+                self.settings
+                    .rotation(row.state.unit_position.rotation)
+            )),
+            X_COL => ui.label(format!(
+                "{}",
+                self.settings
+                    .length(row.state.unit_position.x)
+            )),
+            Y_COL => ui.label(format!(
+                "{}",
+                self.settings
+                    .length(row.state.unit_position.y)
+            )),
             PCB_SIDE_COL => {
                 let key = pcb_side_to_i18n_key(&row.state.placement.pcb_side);
                 ui.label(tr!(key))
@@ -377,6 +405,8 @@ fn render_cell(&self, ui: &mut Ui, cell_index: CellIndex, source: &PlacementsDat
 
             _ => unreachable!(),
         };
+
+        ui.visuals_mut().override_text_color = previous_text_color;
     }
 
     fn rows_to_filter(&self) -> Option<&[usize]> {
@@ -407,6 +437,8 @@ pub struct PlacementsTableUi {
     )>,
     #[derivative(Debug = "ignore")]
     pub(crate) filter: Filter,
+    /// A restored row ordering, applied when the placements are next updated, see [`Self::restore_session`].
+    pending_row_ordering: Option<Vec<ObjectPath>>,
 
     pub component: ComponentState<PlacementsTableUiCommand>,
 }
@@ -431,16 +463,29 @@ pub fn new() -> Self {
                 EditorState::default(),
             )),
             filter,
+            pending_row_ordering: None,
 
             component,
         }
     }
 
     pub fn update_placements(&mut self, placements: Vec<PlacementsItem>, phases: Vec<PhaseOverview>) {
-        let (source, _renderer, editor, _editor_state) = &mut *self.source.lock().unwrap();
+        {
+            let (source, renderer, editor, _editor_state) = &mut *self.source.lock().unwrap();
+
+            // the row indexes can change when the placements change, the ordering is kept using the object paths.
+            let row_ordering = match self.pending_row_ordering.take() {
+                Some(object_paths) => Some(object_paths),
+                None => row_ordering_object_paths(&source.rows, renderer.row_ordering.as_deref()),
+            };
+
+            source.update_placements(placements);
+            renderer.row_ordering =
+                row_ordering.and_then(|object_paths| row_ordering_from_object_paths(&source.rows, &object_paths));
+            editor.update_phases(phases);
+        }
 
-        source.update_placements(placements);
-        editor.update_phases(phases);
+        self.apply_filter();
     }
 
     pub fn update_phases(&mut self, phases: Vec<PhaseOverview>) {
@@ -449,6 +494,162 @@ pub fn update_phases(&mut self, phases: Vec<PhaseOverview>) {
         editor.update_phases(phases);
     }
 
+    /// `None` for the default layout, column orderings for a different amount of columns are ignored.
+    pub fn apply_layout(&mut self, layout: Option<&TableLayout>) {
+        let (_source, renderer, _editor, _editor_state) = &mut *self.source.lock().unwrap();
+
+        renderer.column_ordering = layout
+            .and_then(|layout| layout.column_ordering.clone())
+            .filter(|column_ordering| column_ordering.len() == COLUMN_COUNT);
+    }
+
+    /// The positions are shown in the units, and rotation direction, of the project settings.
+    ///
+    /// The origin of the settings is not applied, the panel size is only known when generating the artifacts.
+    pub fn apply_settings(&mut self, settings: ProjectSettings) {
+        let (_source, renderer, _editor, _editor_state) = &mut *self.source.lock().unwrap();
+
+        renderer.settings = settings;
+    }
+
+    /// Cross-probing, shows only the given placements, nearest first, by replacing the filter, so the rows are
+    /// visible without scrolling.  The nearest becomes the current placement and all are highlighted.  Clearing the
+    /// filter shows all the placements again.  If there are no placements the filter is kept and the highlighting is
+    /// cleared.
+    pub fn probe_placements(&mut self, object_paths: Vec<ObjectPath>) {
+        {
+            let (_source, renderer, _editor, _editor_state) = &mut *self.source.lock().unwrap();
+
+            renderer.current = object_paths.first().cloned();
+            renderer.highlighted = object_paths.iter().cloned().collect();
+        }
+
+        if object_paths.is_empty() {
+            return;
+        }
+
+        // matches the start of the haystack built by `apply_filter`
+        let expression = format!(
+            "^object_path: '({})'",
+            object_paths
+                .iter()
+                .map(|object_path| regex::escape(&object_path.to_string()))
+                .collect::<Vec<_>>()
+                .join("|")
+        );
+
+        self.filter.restore(&FilterState {
+            expression,
+            regex: true,
+            case_sensitive: true,
+        });
+        self.apply_filter();
+    }
+
+    /// Hides the rows that do not match the filter, must be called when the filter or the rows change.
+    fn apply_filter(&self) {
+        let (source, renderer, _editor, _editor_state) = &mut *self.source.lock().unwrap();
+
+        renderer.rows_to_filter = source.rows.iter().enumerate().filter_map(|(id, row)|{
+
+            let haystack = format!(
+                "object_path: '{}', refdes: '{}', manufacturer: '{}', mpn: '{}', place: {}, placed: {}, side: {}, phase: '{}', status: '{}'",
+                &row.path,
+                &row.state.placement.ref_des,
+                &row.state
+                    .placement
+                    .part
+                    .manufacturer,
+                &row.state.placement.part.mpn,
+                &tr!(placement_place_to_i18n_key(row.state.placement.place)),
+                &tr!(placement_operation_status_to_i18n_key(
+                        &row.state.operation_status
+                    )),
+                &tr!(pcb_side_to_i18n_key(&row.state.placement.pcb_side)),
+                &row.state
+                    .phase
+                    .as_ref()
+                    .map(|phase| phase.to_string())
+                    .unwrap_or_default(),
+                &tr!(placement_project_status_to_i18n_key(
+                        &row.state.project_status
+                    )),
+            );
+
+            // "Filter single row. If this returns false, the row will be hidden."
+            let result = self.filter.matches(haystack.as_str());
+
+            trace!("row: {:?}, haystack: {}, result: {}", row, haystack, result);
+
+            if !result {
+                Some(id)
+            } else {
+                None
+            }
+        }).collect::<Vec<usize>>();
+    }
+
+    /// The placement after the current placement, in display order, skipping filtered rows and wrapping around to the
+    /// first row.  The placement becomes the current placement and is highlighted.
+    fn next_placement(&self) -> Option<PlacementsItem> {
+        let (source, renderer, _editor, _editor_state) = &mut *self.source.lock().unwrap();
+
+        let current_row_index = renderer
+            .current
+            .as_ref()
+            .and_then(|current| {
+                source
+                    .rows
+                    .iter()
+                    .position(|row| row.path.eq(current))
+            });
+
+        let row_index = next_row_index(
+            source.rows.len(),
+            renderer.row_ordering.as_deref(),
+            &renderer.rows_to_filter,
+            current_row_index,
+        )?;
+
+        let row = &source.rows[row_index];
+        renderer.current = Some(row.path.clone());
+        renderer.highlighted = BTreeSet::from([row.path.clone()]);
+
+        Some(row.clone())
+    }
+
+    pub fn session(&self) -> TableSession {
+        let (source, renderer, _editor, _editor_state) = &*self.source.lock().unwrap();
+
+        let row_ordering = match &self.pending_row_ordering {
+            Some(object_paths) => Some(object_paths.clone()),
+            None => row_ordering_object_paths(&source.rows, renderer.row_ordering.as_deref()),
+        };
+
+        TableSession {
+            filter: self.filter.state(),
+            row_ordering,
+        }
+    }
+
+    /// Row orderings for different placements are ignored, e.g. a placement was added or removed since the session was
+    /// saved.  If there are no placements yet, the row ordering is applied when the placements are updated.
+    pub fn restore_session(&mut self, session: &TableSession) {
+        self.filter.restore(&session.filter);
+        {
+            let (source, renderer, _editor, _editor_state) = &mut *self.source.lock().unwrap();
+            if source.rows.is_empty() {
+                self.pending_row_ordering = session.row_ordering.clone();
+            } else {
+                renderer.row_ordering = session
+                    .row_ordering
+                    .as_ref()
+                    .and_then(|object_paths| row_ordering_from_object_paths(&source.rows, object_paths));
+            }
+        }
+        self.apply_filter();
+    }
+
     pub fn filter_ui(&self, ui: &mut Ui) {
         self.filter
             .ui(ui, &mut FilterUiContext::default());
@@ -474,6 +675,9 @@ pub enum PlacementsTableUiCommand {
         unit_position: PlacementPositionUnit,
     },
     NewSelection(Vec<PlacementsItem>),
+    ColumnOrderingChanged(Option<Vec<usize>>),
+    /// Locates the placement after the last clicked or located placement.
+    LocateNextPlacement,
 }
 
 #[derive(Debug, Clone)]
@@ -485,6 +689,8 @@ pub enum PlacementsTableUiAction {
         old_placement: PlacementState,
     },
     RequestRepaint,
+    /// e.g. the columns were re-ordered, so that the layout can be saved in the settings.
+    LayoutChanged(TableLayout),
     LocatePlacement {
         /// Full object path of the component
         object_path: ObjectPath,
@@ -498,6 +704,68 @@ pub enum PlacementsTableUiAction {
 #[derive(Debug, Clone, Default)]
 pub struct PlacementsTableUiContext {}
 
+/// The index of the row after the current row, in display order, skipping filtered rows and wrapping around to the
+/// first displayed row.  The first displayed row if there is no current row or the current row is filtered.
+fn next_row_index(
+    row_count: usize,
+    row_ordering: Option<&[usize]>,
+    rows_to_filter: &[usize],
+    current_row_index: Option<usize>,
+) -> Option<usize> {
+    let display_order = match row_ordering {
+        Some(row_ordering) => row_ordering.to_vec(),
+        None => (0..row_count).collect(),
+    }
+    .into_iter()
+    .filter(|row_index| !rows_to_filter.contains(row_index))
+    .collect::<Vec<_>>();
+
+    let current_position = current_row_index.and_then(|current_row_index| {
+        display_order
+            .iter()
+            .position(|row_index| *row_index == current_row_index)
+    });
+    let next_position = current_position.map_or(0, |position| (position + 1) % display_order.len());
+
+    display_order
+        .get(next_position)
+        .copied()
+}
+
+/// The object paths of the rows, in display order, `None` for the default order.
+fn row_ordering_object_paths(rows: &[PlacementsItem], row_ordering: Option<&[usize]>) -> Option<Vec<ObjectPath>> {
+    row_ordering?
+        .iter()
+        .map(|row_index| {
+            rows.get(*row_index)
+                .map(|row| row.path.clone())
+        })
+        .collect()
+}
+
+/// `None` unless the object paths are the object paths of the rows, in any order.
+fn row_ordering_from_object_paths(rows: &[PlacementsItem], object_paths: &[ObjectPath]) -> Option<Vec<usize>> {
+    if object_paths.len() != rows.len() {
+        return None;
+    }
+
+    let row_indexes = rows
+        .iter()
+        .enumerate()
+        .map(|(row_index, row)| (&row.path, row_index))
+        .collect::<HashMap<_, _>>();
+
+    let row_ordering = object_paths
+        .iter()
+        .map(|object_path| row_indexes.get(object_path).copied())
+        .collect::<Option<Vec<_>>>()?;
+
+    let unique_row_indexes = row_ordering
+        .iter()
+        .collect::<BTreeSet<_>>();
+    (unique_row_indexes.len() == rows.len()).then_some(row_ordering)
+}
+
 impl UiComponent for PlacementsTableUi {
     type UiContext<'context> = PlacementsTableUiContext;
     type UiCommand = PlacementsTableUiCommand;
@@ -544,29 +812,15 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
                     // FUTURE only do this if a *different* cell is clicked, requires tracking the current cell
 
                     let row = &source.rows[cell_index.row];
+                    renderer.current = Some(row.path.clone());
 
+                    let (design_position, unit_position) = placement_positions(row);
                     self.component
                         .send(PlacementsTableUiCommand::LocatePlacement {
                             object_path: row.path.clone(),
                             pcb_side: row.state.placement.pcb_side.clone(),
-                            // FIXME hard-coded use of UnitSystem::Millimeters
-                            design_position: PlacementPositionUnit::new(
-                                DimensionUnitPoint2::new_dim_decimal(
-                                    row.state.placement.x,
-                                    row.state.placement.y,
-                                    UnitSystem::Millimeters,
-                                ),
-                                AngleUnit::new_degrees_decimal(row.state.placement.rotation),
-                            ),
-                            // FIXME hard-coded use of UnitSystem::Millimeters
-                            unit_position: PlacementPositionUnit::new(
-                                DimensionUnitPoint2::new_dim_decimal(
-                                    row.state.unit_position.x,
-                                    row.state.unit_position.y,
-                                    UnitSystem::Millimeters,
-                                ),
-                                AngleUnit::new_degrees_decimal(row.state.unit_position.rotation),
-                            ),
+                            design_position,
+                            unit_position,
                         });
                 }
                 Action::ColumnReorder {
@@ -574,6 +828,10 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
                     to,
                 } => {
                     apply_reordering(&mut renderer.column_ordering, from, to);
+                    self.component
+                        .send(PlacementsTableUiCommand::ColumnOrderingChanged(
+                            renderer.column_ordering.clone(),
+                        ));
                 }
                 Action::RowReorder {
                     from,
@@ -613,45 +871,7 @@ fn update<'context>(
 
                 match action {
                     Some(FilterUiAction::ApplyFilter) => {
-                        let (source, renderer, _editor, _editor_state) = &mut *self.source.lock().unwrap();
-
-                        renderer.rows_to_filter = source.rows.iter().enumerate().filter_map(|(id, row)|{
-
-                            let haystack = format!(
-                                "object_path: '{}', refdes: '{}', manufacturer: '{}', mpn: '{}', place: {}, placed: {}, side: {}, phase: '{}', status: '{}'",
-                                &row.path,
-                                &row.state.placement.ref_des,
-                                &row.state
-                                    .placement
-                                    .part
-                                    .manufacturer,
-                                &row.state.placement.part.mpn,
-                                &tr!(placement_place_to_i18n_key(row.state.placement.place)),
-                                &tr!(placement_operation_status_to_i18n_key(
-                                        &row.state.operation_status
-                                    )),
-                                &tr!(pcb_side_to_i18n_key(&row.state.placement.pcb_side)),
-                                &row.state
-                                    .phase
-                                    .as_ref()
-                                    .map(|phase| phase.to_string())
-                                    .unwrap_or_default(),
-                                &tr!(placement_project_status_to_i18n_key(
-                                        &row.state.project_status
-                                    )),
-                            );
-
-                            // "Filter single row. If this returns false, the row will be hidden."
-                            let result = self.filter.matches(haystack.as_str());
-
-                            trace!("row: {:?}, haystack: {}, result: {}", row, haystack, result);
-
-                            if !result {
-                                Some(id)
-                            } else {
-                                None
-                            }
-                        }).collect::<Vec<usize>>();
+                        self.apply_filter();
 
                         Some(PlacementsTableUiAction::RequestRepaint)
                     }
@@ -702,6 +922,114 @@ fn update<'context>(
             PlacementsTableUiCommand::NewSelection(selection) => {
                 Some(PlacementsTableUiAction::ApplySelection(selection))
             }
+            PlacementsTableUiCommand::ColumnOrderingChanged(column_ordering) => {
+                Some(PlacementsTableUiAction::LayoutChanged(TableLayout {
+                    column_ordering,
+                }))
+            }
+            PlacementsTableUiCommand::LocateNextPlacement => {
+                let row = self.next_placement()?;
+
+                let (design_position, unit_position) = placement_positions(&row);
+                Some(PlacementsTableUiAction::LocatePlacement {
+                    object_path: row.path,
+                    pcb_side: row.state.placement.pcb_side,
+                    design_position,
+                    unit_position,
+                })
+            }
         }
     }
 }
+
+/// Returns the design position and the unit position of the placement.
+fn placement_positions(row: &PlacementsItem) -> (PlacementPositionUnit, PlacementPositionUnit) {
+    // FIXME hard-coded use of UnitSystem::Millimeters
+    let design_position = PlacementPositionUnit::new(
+        DimensionUnitPoint2::new_dim_decimal(row.state.placement.x, row.state.placement.y, UnitSystem::Millimeters),
+        AngleUnit::new_degrees_decimal(row.state.placement.rotation),
+    );
+    // FIXME hard-coded use of UnitSystem::Millimeters
+    let unit_position = PlacementPositionUnit::new(
+        DimensionUnitPoint2::new_dim_decimal(
+            row.state.unit_position.x,
+            row.state.unit_position.y,
+            UnitSystem::Millimeters,
+        ),
+        AngleUnit::new_degrees_decimal(row.state.unit_position.rotation),
+    );
+
+    (design_position, unit_position)
+}
+
+#[cfg(test)]
+mod placements_table_tests {
+    use super::*;
+
+    #[test]
+    fn next_row_is_the_first_row_when_there_is_no_current_row() {
+        // when
+        let row_index = next_row_index(3, None, &[], None);
+
+        // then
+        assert_eq!(row_index, Some(0));
+    }
+
+    #[test]
+    fn next_row_wraps_around_to_the_first_row() {
+        // when
+        let row_index = next_row_index(3, None, &[], Some(2));
+
+        // then
+        assert_eq!(row_index, Some(0));
+    }
+
+    #[test]
+    fn next_row_uses_the_row_ordering() {
+        // given
+        let row_ordering = [2, 0, 1];
+
+        // when
+        let row_indexes = [
+            next_row_index(3, Some(&row_ordering), &[], None),
+            next_row_index(3, Some(&row_ordering), &[], Some(2)),
+            next_row_index(3, Some(&row_ordering), &[], Some(1)),
+        ];
+
+        // then
+        assert_eq!(row_indexes, [Some(2), Some(0), Some(2)]);
+    }
+
+    #[test]
+    fn next_row_skips_filtered_rows() {
+        // given
+        let rows_to_filter = [1, 3];
+
+        // when
+        let row_indexes = [
+            next_row_index(4, None, &rows_to_filter, Some(0)),
+            next_row_index(4, None, &rows_to_filter, Some(2)),
+        ];
+
+        // then
+        assert_eq!(row_indexes, [Some(2), Some(0)]);
+    }
+
+    #[test]
+    fn next_row_is_the_first_displayed_row_when_the_current_row_is_filtered() {
+        // when
+        let row_index = next_row_index(3, None, &[0], Some(0));
+
+        // then
+        assert_eq!(row_index, Some(1));
+    }
+
+    #[test]
+    fn there_is_no_next_row_when_all_rows_are_filtered() {
+        // when
+        let row_index = next_row_index(2, None, &[0, 1], None);
+
+        // then
+        assert_eq!(row_index, None);
+    }
+}
@@ -14,12 +14,13 @@
 use i18n::fluent_argument_helpers::args::build_fluent_args;
 use petgraph::Graph;
 use petgraph::graph::NodeIndex;
-use planner_app::{Arg, PcbSide, PhaseReference, ProjectTreeItem, ProjectTreeView};
+use planner_app::{Arg, PcbSide, PhaseReference, PlacementStatus, ProjectTreeItem, ProjectTreeView};
 use tap::Tap;
 use tracing::{debug, trace};
 use util::path::clip_path;
 
-use crate::i18n::conversions::pcb_side_to_i18n_key;
+use crate::i18n::conversions::{pcb_side_to_i18n_key, placement_operation_status_to_i18n_key};
+use crate::project::session::ExplorerSession;
 use crate::project::tabs::ProjectTabContext;
 use crate::project::{project_path_from_view_path, view_path_from_project_path};
 use crate::tabs::{Tab, TabKey};
@@ -34,6 +35,8 @@ pub struct ExplorerTabUi {
 
     #[derivative(Debug = "ignore")]
     tree_view_state: Value<TreeViewState<usize>>,
+    /// Applied when the tree is next updated, see [`ExplorerTabUi::restore_session`].
+    pending_session: Option<ExplorerSession>,
 
     pub component: ComponentState<ExplorerTabUiCommand>,
 }
@@ -44,6 +47,7 @@ pub fn new(project_directory: PathBuf) -> Self {
             project_directory,
             project_tree_view: None,
             tree_view_state: Default::default(),
+            pending_session: None,
             component: Default::default(),
         }
     }
@@ -187,6 +191,38 @@ fn handle_phase<'p>(
             Ok((default_key, item))
         }
 
+        fn handle_placements_status<'p>(
+            default_key: String,
+            item: &'p ProjectTreeItem,
+            _project_directory: &'_ PathBuf,
+        ) -> Result<(String, Cow<'p, ProjectTreeItem>), ()> {
+            if !item.key.eq("placements-status") {
+                return Err(());
+            }
+
+            let Some(Arg::String(status)) = item.args.get("status") else {
+                return Err(());
+            };
+
+            // the core uses the `Display` form of the status
+            let status = match status.as_str() {
+                "Placed" => PlacementStatus::Placed,
+                "Skipped" => PlacementStatus::Skipped,
+                "Pending" => PlacementStatus::Pending,
+                _ => return Err(()),
+            };
+
+            let mut item = item.clone();
+            item.args.insert(
+                "status".to_string(),
+                Arg::String(tr!(placement_operation_status_to_i18n_key(&status))),
+            );
+
+            let item: Cow<'p, ProjectTreeItem> = Cow::Owned(item);
+
+            Ok((default_key, item))
+        }
+
         fn default_handler<'p>(
             default_key: String,
             item: &'p ProjectTreeItem,
@@ -200,6 +236,7 @@ fn default_handler<'p>(
             handle_phase,
             handle_phase_loadout,
             handle_unit_assignment,
+            handle_placements_status,
             default_handler,
         ];
 
@@ -274,6 +311,50 @@ pub fn select_path(&mut self, project_path: &NavigationPath) {
     pub fn update_tree(&mut self, project_tree_view: ProjectTreeView) {
         self.project_tree_view
             .replace(project_tree_view);
+
+        if let Some(session) = self.pending_session.take() {
+            self.restore_session(session);
+        }
+    }
+
+    pub fn session(&self) -> ExplorerSession {
+        let Some(project_tree_view) = &self.project_tree_view else {
+            return self
+                .pending_session
+                .clone()
+                .unwrap_or_default();
+        };
+
+        let tree_view_state = self.tree_view_state.lock().unwrap();
+        let graph = &project_tree_view.tree;
+
+        let expanded = graph
+            .node_indices()
+            .filter(|index| tree_view_state.is_open(&index.index()) == Some(true))
+            .map(|index| graph[index].path.clone())
+            .collect();
+
+        ExplorerSession {
+            expanded,
+        }
+    }
+
+    /// The session is applied when the tree is next updated, if there is no tree yet.
+    pub fn restore_session(&mut self, session: ExplorerSession) {
+        let Some(project_tree_view) = &self.project_tree_view else {
+            self.pending_session = Some(session);
+            return;
+        };
+
+        let mut tree_view_state = self.tree_view_state.lock().unwrap();
+        let graph = &project_tree_view.tree;
+
+        for index in graph.node_indices() {
+            let open = session
+                .expanded
+                .contains(&graph[index].path);
+            tree_view_state.set_openness(index.index(), open);
+        }
     }
 }
 
@@ -380,8 +461,8 @@ fn update<'context>(
                                 .captures(&path)
                         })
                         .map(|captures| {
-                            let phase_reference = captures.name("phase").unwrap().as_str();
-                            PhaseReference::from_raw_str(phase_reference)
+                            let phase_reference = crate::project::tree_item::segment(&captures, "phase");
+                            PhaseReference::from_raw(phase_reference)
                         })
                         .collect::<Vec<_>>();
                     trace!("moved_phases: {:?}", moved_phases);
@@ -410,8 +491,8 @@ fn update<'context>(
                                 .captures(&path)
                         })
                         .map(|captures| {
-                            let phase_reference = captures.name("phase").unwrap().as_str();
-                            PhaseReference::from_raw_str(phase_reference)
+                            let phase_reference = crate::project::tree_item::segment(&captures, "phase");
+                            PhaseReference::from_raw(phase_reference)
                         })
                         .collect::<Vec<_>>();
 
@@ -432,7 +513,8 @@ fn find_position(
                                 .phase
                                 .captures(&node_path);
                             if let Some(captures) = captures {
-                                let node_phase = PhaseReference::from_raw_str(captures.name("phase").unwrap().as_str());
+                                let node_phase =
+                                    PhaseReference::from_raw(crate::project::tree_item::segment(&captures, "phase"));
                                 return *phase == node_phase;
                             }
                             false
@@ -514,3 +596,73 @@ fn on_close<'a>(&mut self, _tab_key: &TabKey, _context: &mut Self::Context) -> O
         OnCloseResponse::Close
     }
 }
+
+#[cfg(test)]
+mod explorer_tab_tests {
+    use planner_app::TreePath;
+
+    use super::*;
+
+    fn build_project_tree_view() -> ProjectTreeView {
+        let mut tree = Graph::new();
+        let root = tree.add_node(ProjectTreeItem {
+            key: "root".to_string(),
+            path: TreePath::root(),
+            ..ProjectTreeItem::default()
+        });
+        let phases = tree.add_node(ProjectTreeItem {
+            key: "phases".to_string(),
+            path: TreePath::root().join("phases"),
+            ..ProjectTreeItem::default()
+        });
+        let pcbs = tree.add_node(ProjectTreeItem {
+            key: "pcbs".to_string(),
+            path: TreePath::root().join("pcbs"),
+            ..ProjectTreeItem::default()
+        });
+        tree.add_edge(root, phases, ());
+        tree.add_edge(root, pcbs, ());
+
+        ProjectTreeView {
+            tree,
+        }
+    }
+
+    #[test]
+    pub fn expanded_nodes_are_restored_when_the_tree_is_received() {
+        // given
+        let mut explorer_tab_ui = ExplorerTabUi::new(PathBuf::from("projects"));
+        let session = ExplorerSession {
+            expanded: vec![TreePath::root(), TreePath::root().join("phases")],
+        };
+
+        // when
+        explorer_tab_ui.restore_session(session.clone());
+
+        // then the session is kept until the tree is received
+        assert_eq!(explorer_tab_ui.session(), session);
+
+        // when
+        explorer_tab_ui.update_tree(build_project_tree_view());
+
+        // then
+        assert_eq!(explorer_tab_ui.session(), session);
+    }
+
+    #[test]
+    pub fn unknown_paths_are_ignored() {
+        // given
+        let mut explorer_tab_ui = ExplorerTabUi::new(PathBuf::from("projects"));
+        explorer_tab_ui.update_tree(build_project_tree_view());
+
+        // when
+        explorer_tab_ui.restore_session(ExplorerSession {
+            expanded: vec![TreePath::root().join("pcbs"), TreePath::root().join("unknown")],
+        });
+
+        // then
+        assert_eq!(explorer_tab_ui.session(), ExplorerSession {
+            expanded: vec![TreePath::root().join("pcbs")],
+        });
+    }
+}
@@ -3,7 +3,7 @@
 use egui_dock::tab_viewer::OnCloseResponse;
 use egui_extras::{Column, TableBuilder};
 use egui_i18n::tr;
-use planner_app::{IssueKind, ProjectReport};
+use planner_app::{IssueKind, ProjectIssue};
 
 use crate::project::tabs::ProjectTabContext;
 use crate::tabs::{Tab, TabKey};
@@ -11,7 +11,7 @@
 
 #[derive(Debug)]
 pub struct IssuesTabUi {
-    report: Option<ProjectReport>,
+    issues: Option<Vec<ProjectIssue>>,
     pub component: ComponentState<IssuesTabUiCommand>,
 }
 
@@ -20,17 +20,17 @@ impl IssuesTabUi {
 
     pub fn new() -> Self {
         Self {
-            report: None,
+            issues: None,
             component: Default::default(),
         }
     }
 
-    pub fn update_report(&mut self, report: ProjectReport) {
-        self.report = Some(report);
+    pub fn update_issues(&mut self, issues: Vec<ProjectIssue>) {
+        self.issues = Some(issues);
     }
 
     fn show_issues(&self, ui: &mut Ui, text_height: f32) {
-        let Some(report) = &self.report else { return };
+        let Some(issues) = &self.issues else { return };
 
         TableBuilder::new(ui)
             .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
@@ -38,6 +38,7 @@ fn show_issues(&self, ui: &mut Ui, text_height: f32) {
             .scroll_bar_visibility(ScrollBarVisibility::AlwaysVisible)
             .column(Column::auto())
             .column(Column::auto())
+            .column(Column::auto())
             .column(Column::remainder())
             .column(Column::remainder())
             .column(Column::auto().resizable(false))
@@ -51,6 +52,9 @@ fn show_issues(&self, ui: &mut Ui, text_height: f32) {
                 header.col(|ui| {
                     ui.strong(tr!("table-issues-column-severity"));
                 });
+                header.col(|ui| {
+                    ui.strong(tr!("table-issues-column-code"));
+                });
                 header.col(|ui| {
                     ui.strong(tr!("table-issues-column-message"));
                 });
@@ -62,9 +66,9 @@ fn show_issues(&self, ui: &mut Ui, text_height: f32) {
                 });
             })
             .body(|body| {
-                body.rows(text_height, report.issues.len(), |mut row| {
+                body.rows(text_height, issues.len(), |mut row| {
                     let index = row.index();
-                    if let Some(issue) = report.issues.get(index) {
+                    if let Some(issue) = issues.get(index) {
                         row.col(|ui| {
                             ui.label(index.to_string());
                         });
@@ -72,6 +76,9 @@ fn show_issues(&self, ui: &mut Ui, text_height: f32) {
                             // TODO translate
                             ui.label(format!("{:?}", issue.severity));
                         });
+                        row.col(|ui| {
+                            ui.monospace(&issue.code);
+                        });
                         row.col(|ui| {
                             // TODO translate - this highlights an issue with the message generation, it
                             //      needs an i18n key and args, not an actual message
@@ -102,6 +109,47 @@ fn show_issues(&self, ui: &mut Ui, text_height: f32) {
                             } => {
                                 ui.label(phase.to_string());
                             }
+                            IssueKind::PartWithNoProcesses {
+                                part,
+                            } => {
+                                ui.label(format!("{} {}", part.mpn, part.manufacturer));
+                            }
+                            IssueKind::PhaseWithEmptyLoadOut {
+                                phase,
+                            } => {
+                                ui.label(phase.to_string());
+                            }
+                            IssueKind::UnitAssignmentWithUnknownDesign {
+                                object_path,
+                                design,
+                            } => {
+                                ui.label(format!("{} - {}", object_path, design));
+                            }
+                            IssueKind::PcbWithNoProfileGerber {
+                                file,
+                            } => {
+                                ui.label(file.to_string());
+                            }
+                            IssueKind::PlacementOnOtherPcbSide {
+                                phase,
+                                object_path,
+                            } => {
+                                ui.label(format!("{} - {}", phase, object_path));
+                            }
+                            IssueKind::PartFloorLifeExceeded {
+                                phase,
+                                part,
+                            } => {
+                                ui.label(format!("{} - {} {}", phase, part.mpn, part.manufacturer));
+                            }
+                            IssueKind::PhaseExceedsFeederCapacity {
+                                phase,
+                                feeders,
+                                slots,
+                                passes,
+                            } => {
+                                ui.label(format!("{} - {}/{} ({})", phase, feeders, slots, passes));
+                            }
                         });
                         row.col(|ui| {
                             let _ = ui;
@@ -139,6 +187,52 @@ fn show_issues(&self, ui: &mut Ui, text_height: f32) {
                                     // TODO add button to show all placements so that assignments can be made
                                     let _ = phase;
                                 }
+                                IssueKind::PartWithNoProcesses {
+                                    part,
+                                } => {
+                                    // TODO add button to show the part in the parts tab
+                                    let _ = part;
+                                }
+                                IssueKind::PhaseWithEmptyLoadOut {
+                                    phase,
+                                } => {
+                                    // TODO add button to show the phase's load-out
+                                    let _ = phase;
+                                }
+                                IssueKind::UnitAssignmentWithUnknownDesign {
+                                    object_path,
+                                    design,
+                                } => {
+                                    // TODO add button to show the PCB's unit assignment
+                                    let (_, _) = (object_path, design);
+                                }
+                                IssueKind::PcbWithNoProfileGerber {
+                                    file,
+                                } => {
+                                    // TODO add button to show the PCB's gerbers
+                                    let _ = file;
+                                }
+                                IssueKind::PlacementOnOtherPcbSide {
+                                    phase,
+                                    object_path,
+                                } => {
+                                    // TODO add button to show the placement in the phase placements
+                                    let (_, _) = (phase, object_path);
+                                }
+                                IssueKind::PartFloorLifeExceeded {
+                                    phase,
+                                    part,
+                                } => {
+                                    // TODO add button to show the part in the parts tab
+                                    let (_, _) = (phase, part);
+                                }
+                                IssueKind::PhaseExceedsFeederCapacity {
+                                    phase,
+                                    ..
+                                } => {
+                                    // TODO add button to split the phase
+                                    let _ = phase;
+                                }
                             }
                         });
                     }
@@ -2,7 +2,8 @@
 use egui_dock::tab_viewer::OnCloseResponse;
 use egui_extras::{Column, TableBuilder};
 use egui_i18n::tr;
-use planner_app::{PcbInstanceIndex, PhaseOverview, PhaseReference, ProjectOverview};
+use egui_mobius::Value;
+use planner_app::{BuildRunInstanceNumber, PcbInstanceIndex, PhaseOverview, PhaseReference, ProjectOverview};
 
 use crate::i18n::conversions::phase_status_to_i18n_key;
 use crate::project::tabs::ProjectTabContext;
@@ -14,6 +15,9 @@ pub struct OverviewTabUi {
     overview: Option<ProjectOverview>,
     phases: Option<Vec<PhaseOverview>>,
 
+    build_run_quantity: Value<u32>,
+    build_run_instance: Value<BuildRunInstanceNumber>,
+
     pub component: ComponentState<OverviewTabUiCommand>,
 }
 
@@ -24,11 +28,16 @@ pub fn new() -> Self {
         Self {
             overview: None,
             phases: None,
+            build_run_quantity: Value::new(1),
+            build_run_instance: Value::new(1),
             component: Default::default(),
         }
     }
 
     pub fn update_overview(&mut self, project_overview: ProjectOverview) {
+        if let Some(build_run) = &project_overview.build_run {
+            *self.build_run_instance.lock().unwrap() = build_run.active_instance;
+        }
         self.overview.replace(project_overview);
     }
 
@@ -134,6 +143,60 @@ fn show_phases(&self, ui: &mut Ui, text_height: f32) {
             });
     }
 
+    fn show_build_run(&self, ui: &mut Ui) {
+        ui.heading(tr!("project-overview-build-run-header"));
+
+        let Some(overview) = &self.overview else {
+            return;
+        };
+
+        match &overview.build_run {
+            Some(build_run) => {
+                ui.label(tr!("project-overview-build-run-detail", {
+                    run: build_run.number,
+                    instance: build_run.active_instance,
+                    quantity: build_run.quantity,
+                    completed: build_run.completed_instances,
+                }));
+
+                ui.horizontal(|ui| {
+                    let mut instance = self.build_run_instance.lock().unwrap();
+                    ui.label(tr!("project-overview-build-run-instance"));
+                    ui.add(egui::DragValue::new(&mut *instance).range(1..=build_run.quantity));
+
+                    let can_activate = *instance != build_run.active_instance;
+                    if ui
+                        .add_enabled(
+                            can_activate,
+                            egui::Button::new(tr!("project-overview-build-run-button-activate")),
+                        )
+                        .clicked()
+                    {
+                        self.component
+                            .send(OverviewTabUiCommand::ActivateBuildRunInstanceClicked(*instance));
+                    }
+                });
+            }
+            None => {
+                ui.label(tr!("project-overview-build-run-none"));
+            }
+        }
+
+        ui.horizontal(|ui| {
+            let mut quantity = self.build_run_quantity.lock().unwrap();
+            ui.label(tr!("project-overview-build-run-quantity"));
+            ui.add(egui::DragValue::new(&mut *quantity).range(1..=u32::MAX));
+
+            if ui
+                .button(tr!("project-overview-build-run-button-start"))
+                .clicked()
+            {
+                self.component
+                    .send(OverviewTabUiCommand::StartBuildRunClicked(*quantity));
+            }
+        });
+    }
+
     fn show_pcbs(&self, ui: &mut Ui, text_height: f32) {
         egui::Sides::new().show(
             ui,
@@ -234,6 +297,8 @@ pub enum OverviewTabUiCommand {
     PhaseDeleteClicked(PhaseReference),
     AddPcbClicked,
     PcbRemoveClicked(PcbInstanceIndex),
+    StartBuildRunClicked(u32),
+    ActivateBuildRunInstanceClicked(BuildRunInstanceNumber),
 }
 
 #[derive(Debug, Clone)]
@@ -243,6 +308,8 @@ pub enum OverviewTabUiAction {
     DeletePhase(PhaseReference),
     AddPcb,
     RemovePcb(PcbInstanceIndex),
+    StartBuildRun { quantity: u32 },
+    ActivateBuildRunInstance(BuildRunInstanceNumber),
 }
 
 #[derive(Debug, Clone, Default)]
@@ -273,6 +340,10 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
         ui.push_id("pcbs", |ui| {
             self.show_pcbs(ui, text_height);
         });
+
+        ui.push_id("build_run", |ui| {
+            self.show_build_run(ui);
+        });
     }
 
     #[profiling::function]
@@ -289,6 +360,12 @@ fn update<'context>(
             OverviewTabUiCommand::PcbRemoveClicked(pcb_instance_index) => {
                 Some(OverviewTabUiAction::RemovePcb(pcb_instance_index))
             }
+            OverviewTabUiCommand::StartBuildRunClicked(quantity) => Some(OverviewTabUiAction::StartBuildRun {
+                quantity,
+            }),
+            OverviewTabUiCommand::ActivateBuildRunInstanceClicked(instance) => {
+                Some(OverviewTabUiAction::ActivateBuildRunInstance(instance))
+            }
         }
     }
 }
@@ -4,7 +4,7 @@
 use egui::{Ui, WidgetText};
 use egui_dock::tab_viewer::OnCloseResponse;
 use egui_i18n::tr;
-use planner_app::{Part, PartStates, ProcessReference};
+use planner_app::{Part, PartPackages, PartStates, ProcessReference};
 use tracing::trace;
 
 use crate::project::tables::parts::{PartTableUi, PartTableUiAction, PartTableUiCommand, PartTableUiContext};
@@ -57,6 +57,11 @@ pub fn update_part_states(&mut self, part_states: PartStates, processes: Vec<Pro
 
         self.processes = processes;
     }
+
+    pub fn update_part_packages(&mut self, part_packages: PartPackages) {
+        self.part_table_ui
+            .update_packages(part_packages);
+    }
 }
 
 #[derive(Debug, Clone)]
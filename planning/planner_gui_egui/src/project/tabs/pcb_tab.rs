@@ -5,12 +5,26 @@
 use egui_dock::tab_viewer::OnCloseResponse;
 use egui_extras::Column;
 use egui_i18n::tr;
-use planner_app::{PcbOverview, ProjectPcbOverview};
-use tracing::debug;
-
+use nalgebra::Vector2;
+use planner_app::{
+    BoardPreview, LayerPreset, PanelPreview, PcbOverview, PcbSide, PcbUnitOverlay, PlacementLocation,
+    ProjectPcbOverview,
+};
+use tracing::{debug, trace};
+
+use crate::i18n::conversions::pcb_side_to_i18n_key;
 use crate::project::tabs::ProjectTabContext;
 use crate::tabs::{Tab, TabKey};
 use crate::ui_component::{ComponentState, UiComponent};
+use crate::ui_components::board_preview_ui::BoardPreviewUi;
+use crate::ui_components::gerber_viewer_ui::{
+    GerberViewerMode, GerberViewerUi, GerberViewerUiAction, GerberViewerUiCommand, GerberViewerUiContext,
+    GerberViewerUiInstanceArgs,
+};
+use crate::ui_components::pcb_thumbnail_ui::PcbThumbnailUi;
+
+const BOARD_PREVIEW_HEIGHT: f32 = 300.0;
+const GERBER_VIEWER_HEIGHT: f32 = 400.0;
 
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -20,17 +34,42 @@ pub struct PcbTabUi {
     project_pcb_overview: Option<ProjectPcbOverview>,
     /// the actual pcb
     pcb_overview: Option<PcbOverview>,
+    board_preview: Option<BoardPreview>,
+    board_preview_ui: BoardPreviewUi,
+    /// Of the PCB's gerbers, for the side viewed in the board preview.
+    pcb_thumbnail_ui: PcbThumbnailUi,
+    /// Shows the panel, composed from the PCB's gerbers and the gerbers of the design assigned to each unit.
+    #[derivative(Debug = "ignore")]
+    gerber_viewer_ui: GerberViewerUi,
 
     pub component: ComponentState<PcbTabUiCommand>,
 }
 
 impl PcbTabUi {
     pub fn new(path: PathBuf) -> Self {
+        let component: ComponentState<PcbTabUiCommand> = Default::default();
+
+        let mut gerber_viewer_ui = GerberViewerUi::new(GerberViewerUiInstanceArgs {
+            mode: GerberViewerMode::Panel,
+            // all the layers are loaded, a layer preset is used to show the layers for the viewed side.
+            pcb_side: None,
+        });
+        gerber_viewer_ui
+            .component
+            .configure_mapper(component.sender.clone(), |gerber_viewer_command| {
+                trace!("gerber viewer mapper. command: {:?}", gerber_viewer_command);
+                PcbTabUiCommand::GerberViewerUiCommand(gerber_viewer_command)
+            });
+
         Self {
             project_path: path,
             pcb_overview: None,
             project_pcb_overview: None,
-            component: Default::default(),
+            board_preview: None,
+            board_preview_ui: BoardPreviewUi::default(),
+            pcb_thumbnail_ui: PcbThumbnailUi::default(),
+            gerber_viewer_ui,
+            component,
         }
     }
 
@@ -39,9 +78,23 @@ pub fn update_project_pcb_overview(&mut self, project_pcb_overview: ProjectPcbOv
             .send(PcbTabUiCommand::RequestPcbOverview(
                 project_pcb_overview.pcb_path.clone(),
             ));
+        self.component
+            .send(PcbTabUiCommand::RequestBoardPreview(project_pcb_overview.index));
         self.project_pcb_overview = Some(project_pcb_overview);
     }
 
+    pub fn update_board_preview(&mut self, board_preview: BoardPreview) {
+        let viewed_from = self.board_preview_ui.viewed_from();
+        self.board_preview_ui
+            .update(&board_preview, viewed_from);
+        self.board_preview = Some(board_preview);
+    }
+
+    pub fn update_unit_overlay(&mut self, unit_overlay: PcbUnitOverlay) {
+        self.board_preview_ui
+            .update_unit_overlay(unit_overlay);
+    }
+
     pub fn update_pcb_overview(&mut self, pcb_overview: &PcbOverview) {
         if !matches!(&self.project_pcb_overview, Some(project_pcb_overview) if project_pcb_overview.pcb_path.eq(&pcb_overview.path))
         {
@@ -49,18 +102,81 @@ pub fn update_pcb_overview(&mut self, pcb_overview: &PcbOverview) {
             return;
         }
 
-        let pcb_overview = pcb_overview.clone();
+        if is_panel_changed(self.pcb_overview.as_ref(), pcb_overview) {
+            self.component
+                .send(PcbTabUiCommand::RequestPanelPreview(pcb_overview.path.clone()));
+        }
+
+        self.pcb_thumbnail_ui
+            .update(&pcb_overview.pcb_gerbers, self.board_preview_ui.viewed_from());
+
+        self.pcb_overview = Some(pcb_overview.clone());
+    }
+
+    pub fn update_panel_preview(&mut self, panel_preview: PanelPreview) {
+        if !matches!(&self.project_pcb_overview, Some(overview) if overview.pcb_path.eq(&panel_preview.path)) {
+            // this panel preview is not for this pcb tab instance
+            return;
+        }
 
-        self.pcb_overview = Some(pcb_overview);
+        self.gerber_viewer_ui
+            .update_layers_from_panel_preview(panel_preview);
+        self.gerber_viewer_ui
+            .apply_layer_preset(Self::layer_preset(self.board_preview_ui.viewed_from()));
+    }
+
+    /// Shows the side of the placement, marks the placement and centers the gerber view on it.
+    pub fn locate_placement(&mut self, location: PlacementLocation) {
+        if self.board_preview_ui.viewed_from() != location.pcb_side {
+            if let Some(board_preview) = &self.board_preview {
+                self.board_preview_ui
+                    .update(board_preview, location.pcb_side);
+            }
+        }
+        if self.gerber_viewer_ui.viewed_from() != location.pcb_side {
+            self.gerber_viewer_ui
+                .apply_layer_preset(Self::layer_preset(location.pcb_side));
+        }
+
+        // the gerber viewer mirrors the bottom side itself, so the position as viewed from the top is used.
+        self.gerber_viewer_ui
+            .show_placement_marker(location.position);
+        self.gerber_viewer_ui
+            .locate_view(location.position.coords);
+    }
+
+    fn layer_preset(viewed_from: PcbSide) -> LayerPreset {
+        match viewed_from {
+            PcbSide::Top => LayerPreset::TopAssembly,
+            PcbSide::Bottom => LayerPreset::BottomAssembly,
+        }
     }
 }
 
+/// The panel preview is composed from the units, their designs and the gerbers, other changes, e.g. the name, do not
+/// change the panel.
+fn is_panel_changed(previous: Option<&PcbOverview>, pcb_overview: &PcbOverview) -> bool {
+    let Some(previous) = previous else {
+        return true;
+    };
+
+    previous.units != pcb_overview.units
+        || previous.unit_map != pcb_overview.unit_map
+        || previous.pcb_gerbers != pcb_overview.pcb_gerbers
+        || previous.gerber_offset != pcb_overview.gerber_offset
+        || previous.design_gerbers != pcb_overview.design_gerbers
+}
+
 #[derive(Debug, Clone)]
 pub enum PcbTabUiCommand {
     None,
     CreateUnitAssignmentClicked,
     RequestPcbOverview(PathBuf),
     ShowPcbClicked,
+    RequestBoardPreview(u16),
+    BoardPreviewSideChanged(PcbSide),
+    RequestPanelPreview(PathBuf),
+    GerberViewerUiCommand(GerberViewerUiCommand),
 }
 
 #[derive(Debug, Clone)]
@@ -69,6 +185,14 @@ pub enum PcbTabUiAction {
     ShowUnitAssignments(u16),
     RequestPcbOverview(PathBuf),
     ShowPcb(PathBuf),
+    RequestBoardPreview(u16),
+    RequestPanelPreview(PathBuf),
+    /// Find the placements near a clicked position, in millimeters, as viewed from the top.
+    HitTest {
+        pcb: u16,
+        position: Vector2<f64>,
+        side: PcbSide,
+    },
 }
 
 #[derive(Debug, Clone, Default)]
@@ -113,12 +237,18 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
         //
         // overview
         //
-        ui.label(
-            &project_pcb_overview
-                .pcb_file
-                .to_string(),
-        );
-        ui.label(&pcb_overview.name.to_string());
+        ui.horizontal(|ui| {
+            self.pcb_thumbnail_ui.ui(ui);
+
+            ui.vertical(|ui| {
+                ui.label(
+                    &project_pcb_overview
+                        .pcb_file
+                        .to_string(),
+                );
+                ui.label(&pcb_overview.name.to_string());
+            });
+        });
 
         let text_height = egui::TextStyle::Body
             .resolve(ui.style())
@@ -161,6 +291,46 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
             });
 
         ui.separator();
+
+        //
+        // gerbers
+        //
+        ui.heading(tr!("project-pcb-gerbers-header"));
+
+        ui.allocate_ui(egui::Vec2::new(ui.available_width(), GERBER_VIEWER_HEIGHT), |ui| {
+            self.gerber_viewer_ui
+                .ui(ui, &mut GerberViewerUiContext::default());
+        });
+
+        ui.separator();
+
+        //
+        // board preview
+        //
+        ui.heading(tr!("project-pcb-preview-header"));
+
+        if self.board_preview.is_none() {
+            ui.spinner();
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            for side in [PcbSide::Top, PcbSide::Bottom] {
+                if ui
+                    .selectable_label(
+                        self.board_preview_ui.viewed_from() == side,
+                        tr!(pcb_side_to_i18n_key(&side)),
+                    )
+                    .clicked()
+                {
+                    self.component
+                        .send(PcbTabUiCommand::BoardPreviewSideChanged(side));
+                }
+            }
+        });
+
+        self.board_preview_ui
+            .ui(ui, BOARD_PREVIEW_HEIGHT);
     }
 
     #[profiling::function]
@@ -183,6 +353,41 @@ fn update<'context>(
                 .project_pcb_overview
                 .as_ref()
                 .map(|project_pcb_overview| PcbTabUiAction::ShowPcb(project_pcb_overview.pcb_path.clone())),
+            PcbTabUiCommand::RequestBoardPreview(pcb_index) => Some(PcbTabUiAction::RequestBoardPreview(pcb_index)),
+            PcbTabUiCommand::BoardPreviewSideChanged(side) => {
+                if let Some(board_preview) = &self.board_preview {
+                    self.board_preview_ui
+                        .update(board_preview, side);
+                }
+                if let Some(pcb_overview) = &self.pcb_overview {
+                    self.pcb_thumbnail_ui
+                        .update(&pcb_overview.pcb_gerbers, side);
+                }
+                self.gerber_viewer_ui
+                    .apply_layer_preset(Self::layer_preset(side));
+                None
+            }
+            PcbTabUiCommand::RequestPanelPreview(path) => Some(PcbTabUiAction::RequestPanelPreview(path)),
+            PcbTabUiCommand::GerberViewerUiCommand(command) => {
+                let action = self
+                    .gerber_viewer_ui
+                    .update(command, &mut GerberViewerUiContext::default());
+                match action {
+                    None => None,
+                    Some(GerberViewerUiAction::None) => None,
+                    Some(GerberViewerUiAction::PointClicked {
+                        position,
+                        pcb_side,
+                    }) => self
+                        .project_pcb_overview
+                        .as_ref()
+                        .map(|project_pcb_overview| PcbTabUiAction::HitTest {
+                            pcb: project_pcb_overview.index,
+                            position,
+                            side: pcb_side,
+                        }),
+                }
+            }
         }
     }
 }
@@ -232,3 +437,63 @@ fn on_close<'a>(&mut self, _tab_key: &TabKey, context: &mut Self::Context) -> On
         OnCloseResponse::Close
     }
 }
+
+#[cfg(test)]
+mod pcb_tab_tests {
+    use std::collections::HashMap;
+
+    use planner_app::{PcbAssemblyOrientation, PcbGerberItem};
+
+    use super::*;
+
+    fn build_pcb_overview() -> PcbOverview {
+        PcbOverview {
+            path: PathBuf::from("panel_a.pcb.json"),
+            name: "PANEL_A".to_string(),
+            units: 2,
+            designs: vec![],
+            unit_map: HashMap::new(),
+            pcb_gerbers: vec![],
+            gerber_offset: Default::default(),
+            design_gerbers: vec![],
+            orientation: PcbAssemblyOrientation::default(),
+        }
+    }
+
+    #[test]
+    pub fn the_panel_preview_is_only_requested_when_the_panel_changes() {
+        // given
+        let previous = build_pcb_overview();
+
+        // then the first pcb overview changes the panel, the same pcb overview does not
+        assert!(is_panel_changed(None, &previous));
+        assert!(!is_panel_changed(Some(&previous), &previous.clone()));
+
+        // when the pcb is renamed
+        let renamed = PcbOverview {
+            name: "PANEL_B".to_string(),
+            ..previous.clone()
+        };
+        // then
+        assert!(!is_panel_changed(Some(&previous), &renamed));
+
+        // when a unit is assigned
+        let assigned = PcbOverview {
+            unit_map: HashMap::from([(0, 0)]),
+            ..previous.clone()
+        };
+        // then
+        assert!(is_panel_changed(Some(&previous), &assigned));
+
+        // when a gerber is added
+        let with_gerbers = PcbOverview {
+            pcb_gerbers: vec![PcbGerberItem {
+                path: PathBuf::from("panel_a/rails.gbr"),
+                function: None,
+            }],
+            ..previous.clone()
+        };
+        // then
+        assert!(is_panel_changed(Some(&previous), &with_gerbers));
+    }
+}
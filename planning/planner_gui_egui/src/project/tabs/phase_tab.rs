@@ -1,10 +1,14 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use derivative::Derivative;
 use egui::{Ui, WidgetText};
 use egui_dock::tab_viewer::OnCloseResponse;
 use egui_i18n::tr;
+use egui_mobius::Value;
 use planner_app::{
     ObjectPath, OperationReference, OperationStatus, PcbSide, PhaseOverview, PhasePlacements, PhaseReference,
-    PlacementPositionUnit, PlacementState, ProcessReference, Reference, TaskAction, TaskReference, TaskStatus,
+    PlacementPositionUnit, PlacementState, ProcessReference, ProcessRuleReference, ProjectSettings, Reference,
+    TaskAction, TaskParameterValue, TaskReference, TaskStatus,
 };
 use regex::Regex;
 use tracing::{debug, trace};
@@ -13,6 +17,9 @@
 use crate::project::dialogs::placement_orderings::{
     PlacementOrderingsArgs, PlacementOrderingsModal, PlacementOrderingsModalAction, PlacementOrderingsModalUiCommand,
 };
+use crate::project::dialogs::task_parameters::{
+    TaskParametersModal, TaskParametersModalAction, TaskParametersModalUiCommand,
+};
 use crate::project::process::build_task_actions;
 use crate::project::tables::placements::{
     PlacementsTableUi, PlacementsTableUiAction, PlacementsTableUiCommand, PlacementsTableUiContext,
@@ -30,6 +37,10 @@ pub struct PhaseTabUi {
     placements_table_ui: PlacementsTableUi,
 
     placement_orderings_modal: Option<PlacementOrderingsModal>,
+    task_parameters_modal: Option<TaskParametersModal>,
+
+    /// The process rules that are not enforced for the next task action, cleared when a task action is sent.
+    overridden_rules: Value<BTreeSet<ProcessRuleReference>>,
 
     pub component: ComponentState<PhaseTabUiCommand>,
 }
@@ -50,6 +61,8 @@ pub fn new() -> Self {
             overview: None,
             placements_table_ui,
             placement_orderings_modal: None,
+            task_parameters_modal: None,
+            overridden_rules: Default::default(),
             component,
         }
     }
@@ -58,6 +71,11 @@ pub fn update_overview(&mut self, phase_overview: PhaseOverview) {
         self.overview.replace(phase_overview);
     }
 
+    pub fn apply_settings(&mut self, settings: ProjectSettings) {
+        self.placements_table_ui
+            .apply_settings(settings);
+    }
+
     pub fn update_placements(&mut self, phase_placements: PhasePlacements, phases: Vec<PhaseOverview>) {
         self.placements_table_ui
             .update_placements(phase_placements.placements, phases);
@@ -73,6 +91,35 @@ pub fn request_refresh(&self) {
         self.component
             .send(PhaseTabUiCommand::Refresh);
     }
+
+    fn build_task_action(
+        &self,
+        operation: OperationReference,
+        task: TaskReference,
+        action: TaskAction,
+        parameter_values: BTreeMap<String, TaskParameterValue>,
+    ) -> PhaseTabUiAction {
+        let phase = self
+            .overview
+            .as_ref()
+            .unwrap()
+            .phase_reference
+            .clone();
+
+        // the overrides only apply to a single action
+        let overridden_rules = std::mem::take(&mut *self.overridden_rules.lock().unwrap())
+            .into_iter()
+            .collect();
+
+        PhaseTabUiAction::TaskAction {
+            phase,
+            operation,
+            task,
+            action,
+            overridden_rules,
+            parameter_values,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -86,6 +133,7 @@ pub enum PhaseTabUiCommand {
     },
     PhasePlacementsOrderingsClicked,
     PlacementOrderingsModalUiCommand(PlacementOrderingsModalUiCommand),
+    TaskParametersModalUiCommand(TaskParametersModalUiCommand),
     TaskAction {
         operation: OperationReference,
         task: TaskReference,
@@ -114,6 +162,8 @@ pub enum PhaseTabUiAction {
         operation: OperationReference,
         task: TaskReference,
         action: TaskAction,
+        overridden_rules: Vec<ProcessRuleReference>,
+        parameter_values: BTreeMap<String, TaskParameterValue>,
     },
     LocatePlacement {
         /// Full object path of the component
@@ -285,6 +335,34 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
                 self.component
                     .send(PhaseTabUiCommand::PhasePlacementsOrderingsClicked)
             }
+
+            if let Some(overview) = self
+                .overview
+                .as_ref()
+                .filter(|overview| !overview.process_rules.is_empty())
+            {
+                let mut overridden_rules = self.overridden_rules.lock().unwrap();
+                let title = match overridden_rules.len() {
+                    0 => tr!("phase-toolbar-override-rules"),
+                    count => tr!("phase-toolbar-override-rules-count", { count: count }),
+                };
+                ui.menu_button(title, |ui| {
+                    ui.weak(tr!("phase-toolbar-override-rules-help"));
+                    for rule in overview.process_rules.iter() {
+                        let mut overridden = overridden_rules.contains(rule);
+                        if ui
+                            .checkbox(&mut overridden, rule.to_string())
+                            .changed()
+                        {
+                            if overridden {
+                                overridden_rules.insert(rule.clone());
+                            } else {
+                                overridden_rules.remove(rule);
+                            }
+                        }
+                    }
+                });
+            }
         });
 
         ui.separator();
@@ -301,6 +379,9 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
         if let Some(dialog) = &self.placement_orderings_modal {
             dialog.ui(ui, &mut ());
         }
+        if let Some(dialog) = &self.task_parameters_modal {
+            dialog.ui(ui, &mut ());
+        }
     }
 
     #[profiling::function]
@@ -400,19 +481,55 @@ fn update<'context>(
                 task,
                 action,
             } => {
-                let phase = self
+                // the values of the parameters of a custom task are required when completing it
+                let parameters = self
                     .overview
                     .as_ref()
-                    .unwrap()
-                    .phase_reference
-                    .clone();
-
-                Some(PhaseTabUiAction::TaskAction {
-                    phase,
-                    operation,
-                    task,
-                    action,
-                })
+                    .and_then(|overview| overview.custom_tasks.get(&operation))
+                    .and_then(|custom_tasks| {
+                        custom_tasks
+                            .iter()
+                            .find(|custom_task| custom_task.reference.eq(&task))
+                    })
+                    .map(|custom_task| custom_task.parameters.clone())
+                    .filter(|parameters| matches!(action, TaskAction::Complete) && !parameters.is_empty());
+
+                match parameters {
+                    Some(parameters) => {
+                        let mut modal = TaskParametersModal::new(operation, task, parameters);
+                        modal
+                            .component
+                            .configure_mapper(self.component.sender.clone(), move |command| {
+                                trace!("task parameters modal mapper. command: {:?}", command);
+                                PhaseTabUiCommand::TaskParametersModalUiCommand(command)
+                            });
+
+                        self.task_parameters_modal = Some(modal);
+                        None
+                    }
+                    None => Some(self.build_task_action(operation, task, action, Default::default())),
+                }
+            }
+            PhaseTabUiCommand::TaskParametersModalUiCommand(command) => {
+                let action = self
+                    .task_parameters_modal
+                    .as_mut()?
+                    .update(command, &mut ());
+                match action {
+                    None => None,
+                    Some(TaskParametersModalAction::Submit {
+                        operation,
+                        task,
+                        parameter_values,
+                    }) => {
+                        self.task_parameters_modal.take();
+                        Some(self.build_task_action(operation, task, TaskAction::Complete, parameter_values))
+                    }
+                    Some(TaskParametersModalAction::CloseDialog) => {
+                        self.task_parameters_modal.take();
+                        None
+                    }
+                }
             }
             PhaseTabUiCommand::Refresh => {
                 let phase = self
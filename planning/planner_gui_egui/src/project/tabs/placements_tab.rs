@@ -4,10 +4,12 @@
 use egui_i18n::tr;
 use planner_app::{
     ObjectPath, PcbSide, PhaseOverview, PhaseReference, PlacementPositionUnit, PlacementState, PlacementsItem,
-    PlacementsList,
+    PlacementsList, ProjectSettings,
 };
 use tracing::trace;
 
+use crate::config::TableLayout;
+use crate::project::session::TableSession;
 use crate::project::tables::placements::{
     PlacementsTableUi, PlacementsTableUiAction, PlacementsTableUiCommand, PlacementsTableUiContext,
 };
@@ -58,6 +60,31 @@ pub fn update_placements(&mut self, placements: PlacementsList, phases: Vec<Phas
         self.update_phases(phases);
     }
 
+    /// `None` for the default layout.
+    pub fn apply_table_layout(&mut self, layout: Option<&TableLayout>) {
+        self.placements_table_ui
+            .apply_layout(layout);
+    }
+
+    pub fn apply_settings(&mut self, settings: ProjectSettings) {
+        self.placements_table_ui
+            .apply_settings(settings);
+    }
+
+    pub fn table_session(&self) -> TableSession {
+        self.placements_table_ui.session()
+    }
+
+    pub fn restore_table_session(&mut self, session: &TableSession) {
+        self.placements_table_ui
+            .restore_session(session);
+    }
+
+    pub fn probe_placements(&mut self, object_paths: Vec<ObjectPath>) {
+        self.placements_table_ui
+            .probe_placements(object_paths);
+    }
+
     pub fn update_phases(&mut self, phases: Vec<PhaseOverview>) {
         self.placements_table_ui
             .update_phases(phases.clone());
@@ -87,6 +114,7 @@ pub enum PlacementAction {
 pub enum PlacementsTabUiAction {
     None,
     RequestRepaint,
+    TableLayoutChanged(TableLayout),
     UpdatePlacement {
         object_path: ObjectPath,
         new_placement: PlacementState,
@@ -225,6 +253,9 @@ fn update<'context>(
                     None => None,
                     Some(PlacementsTableUiAction::None) => None,
                     Some(PlacementsTableUiAction::RequestRepaint) => Some(PlacementsTabUiAction::RequestRepaint),
+                    Some(PlacementsTableUiAction::LayoutChanged(layout)) => {
+                        Some(PlacementsTabUiAction::TableLayoutChanged(layout))
+                    }
                     Some(PlacementsTableUiAction::UpdatePlacement {
                         object_path,
                         new_placement,
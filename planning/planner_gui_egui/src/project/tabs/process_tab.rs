@@ -11,8 +11,8 @@
 use egui_taffy::tui;
 use indexmap::IndexMap;
 use planner_app::{
-    OperationDefinition, OperationReference, ProcessDefinition, ProcessReference, ProcessRuleReference, Reference,
-    TaskReference,
+    CustomTaskDefinition, FeederBank, NozzleConfiguration, OperationDefinition, OperationReference, ProcessDefinition,
+    ProcessReference, ProcessRuleReference, Reference, TaskReference,
 };
 use tracing::debug;
 use validator::Validate;
@@ -54,12 +54,20 @@ pub fn reset(&mut self) {
     }
 
     pub fn update_definition(&mut self, process_definition: ProcessDefinition) {
-        let available_tasks: Vec<TaskReference> = vec![
+        let mut available_tasks: Vec<TaskReference> = vec![
             TaskReference::from_raw_str("core::load_pcbs"),
+            TaskReference::from_raw_str("core::apply_solder_paste"),
             TaskReference::from_raw_str("core::place_components"),
             TaskReference::from_raw_str("core::automated_soldering"),
             TaskReference::from_raw_str("core::manual_soldering"),
         ];
+        available_tasks.extend(
+            process_definition
+                .operations
+                .iter()
+                .flat_map(|operation| operation.custom_tasks.iter())
+                .map(|custom_task| custom_task.reference.clone()),
+        );
 
         let initial_process_reference = process_definition.reference.clone();
 
@@ -304,10 +312,22 @@ pub struct ProcessFields {
     operations: IndexMap<OperationReference, Vec<TaskReference>>,
 
     rules: Vec<ProcessRuleReference>,
+
+    /// Not editable, kept so that applying the process does not discard them.
+    nozzles: NozzleConfiguration,
+    feeder_banks: Vec<FeederBank>,
+    /// Not editable, the definitions are added to the operation that uses the task.
+    custom_tasks: Vec<CustomTaskDefinition>,
 }
 
 impl ProcessFields {
     pub fn from_process_definition(process: ProcessDefinition) -> Self {
+        let custom_tasks = process
+            .operations
+            .iter()
+            .flat_map(|operation| operation.custom_tasks.clone())
+            .collect();
+
         Self {
             process_reference: process.reference.to_string(),
             operations: process
@@ -316,6 +336,9 @@ pub fn from_process_definition(process: ProcessDefinition) -> Self {
                 .map(|it| (it.reference, it.tasks))
                 .collect(),
             rules: process.rules.clone(),
+            nozzles: process.nozzles.clone(),
+            feeder_banks: process.feeder_banks.clone(),
+            custom_tasks,
         }
     }
 
@@ -327,6 +350,12 @@ pub fn build_args(&self, initial_process_reference: ProcessReference) -> Process
             .map(|(operation, tasks)| OperationDefinition {
                 reference: operation.clone(),
                 tasks: tasks.clone(),
+                custom_tasks: self
+                    .custom_tasks
+                    .iter()
+                    .filter(|custom_task| tasks.contains(&custom_task.reference))
+                    .cloned()
+                    .collect(),
             })
             .collect::<Vec<_>>();
 
@@ -338,6 +367,8 @@ pub fn build_args(&self, initial_process_reference: ProcessReference) -> Process
                 reference: ProcessReference::from_raw(self.process_reference.clone()),
                 operations,
                 rules,
+                nozzles: self.nozzles.clone(),
+                feeder_banks: self.feeder_banks.clone(),
             },
         }
     }
@@ -1,5 +1,8 @@
+use std::collections::BTreeSet;
+
 use egui::Ui;
 use egui_i18n::tr;
+use planner_app::Artifact;
 
 use crate::ui_component::{ComponentState, UiComponent};
 
@@ -10,7 +13,9 @@ pub enum ProjectToolbarUiCommand {
     AddPhaseClicked,
     PackageSourcesClicked,
     RefreshClicked,
+    CancelRefreshClicked,
     GenerateArtifactsClicked,
+    ArtifactSelectionChanged { artifact: Artifact, selected: bool },
     RemoveUnusedPlacementsClicked,
     ResetOperationsClicked,
 }
@@ -21,25 +26,52 @@ pub enum ProjectToolbarAction {
     ShowAddPhaseDialog,
     ShowPackageSourcesDialog,
     Refresh,
-    GenerateArtifacts,
+    CancelRefresh,
+    GenerateArtifacts { artifacts: BTreeSet<Artifact> },
     RemoveUnusedPlacements,
     ResetOperations,
 }
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct ProjectToolbar {
     pub component: ComponentState<ProjectToolbarUiCommand>,
+    /// The artifacts that are generated in addition to the phase placements and the report.
+    artifacts: BTreeSet<Artifact>,
+}
+
+impl Default for ProjectToolbar {
+    fn default() -> Self {
+        Self {
+            component: Default::default(),
+            artifacts: Artifact::all(),
+        }
+    }
+}
+
+impl ProjectToolbar {
+    fn artifact_key(artifact: &Artifact) -> &'static str {
+        match artifact {
+            Artifact::InspectionChecklist => "artifact-inspection-checklist",
+            Artifact::StencilSetupSheet => "artifact-stencil-setup-sheet",
+            Artifact::MachineJob => "artifact-machine-job",
+            Artifact::Labels => "artifact-labels",
+            Artifact::Analytics => "artifact-analytics",
+        }
+    }
 }
 
-impl ProjectToolbar {}
+pub struct ProjectToolbarContext {
+    /// a refresh is in progress, it can be cancelled instead
+    pub refreshing: bool,
+}
 
 impl UiComponent for ProjectToolbar {
-    type UiContext<'context> = ();
+    type UiContext<'context> = ProjectToolbarContext;
     type UiCommand = ProjectToolbarUiCommand;
     type UiAction = ProjectToolbarAction;
 
     #[profiling::function]
-    fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
+    fn ui<'context>(&self, ui: &mut Ui, context: &mut Self::UiContext<'context>) {
         ui.horizontal(|ui| {
             if ui
                 .button(tr!("project-toolbar-button-show-explorer"))
@@ -55,7 +87,30 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
                 self.component
                     .send(ProjectToolbarUiCommand::GenerateArtifactsClicked)
             }
-            if ui
+            ui.menu_button(tr!("project-toolbar-button-artifacts"), |ui| {
+                for artifact in Artifact::ALL {
+                    let mut selected = self.artifacts.contains(&artifact);
+                    if ui
+                        .checkbox(&mut selected, tr!(Self::artifact_key(&artifact)))
+                        .changed()
+                    {
+                        self.component
+                            .send(ProjectToolbarUiCommand::ArtifactSelectionChanged {
+                                artifact,
+                                selected,
+                            })
+                    }
+                }
+            });
+            if context.refreshing {
+                if ui
+                    .button(tr!("project-toolbar-button-cancel-refresh"))
+                    .clicked()
+                {
+                    self.component
+                        .send(ProjectToolbarUiCommand::CancelRefreshClicked)
+                }
+            } else if ui
                 .button(tr!("project-toolbar-button-refresh"))
                 .clicked()
             {
@@ -109,10 +164,24 @@ fn update<'context>(
         match command {
             ProjectToolbarUiCommand::ProjectExplorerClicked => Some(ProjectToolbarAction::ShowProjectExplorer),
             ProjectToolbarUiCommand::RefreshClicked => Some(ProjectToolbarAction::Refresh),
+            ProjectToolbarUiCommand::CancelRefreshClicked => Some(ProjectToolbarAction::CancelRefresh),
             ProjectToolbarUiCommand::AddPcbClicked => Some(ProjectToolbarAction::PickPcbFile),
             ProjectToolbarUiCommand::AddPhaseClicked => Some(ProjectToolbarAction::ShowAddPhaseDialog),
             ProjectToolbarUiCommand::PackageSourcesClicked => Some(ProjectToolbarAction::ShowPackageSourcesDialog),
-            ProjectToolbarUiCommand::GenerateArtifactsClicked => Some(ProjectToolbarAction::GenerateArtifacts),
+            ProjectToolbarUiCommand::GenerateArtifactsClicked => Some(ProjectToolbarAction::GenerateArtifacts {
+                artifacts: self.artifacts.clone(),
+            }),
+            ProjectToolbarUiCommand::ArtifactSelectionChanged {
+                artifact,
+                selected,
+            } => {
+                if selected {
+                    self.artifacts.insert(artifact);
+                } else {
+                    self.artifacts.remove(&artifact);
+                }
+                None
+            }
             ProjectToolbarUiCommand::RemoveUnusedPlacementsClicked => {
                 Some(ProjectToolbarAction::RemoveUnusedPlacements)
             }
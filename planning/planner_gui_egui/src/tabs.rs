@@ -103,6 +103,13 @@ fn on_close<'a>(&mut self, _tab_key: &TabKey, _context: &mut Self::Context) -> O
         OnCloseResponse::Close
     }
 
+    /// Called before [`Self::on_close`] when the user closes the tab, e.g. to ask for confirmation first.
+    ///
+    /// Not called for orphaned tabs, see `cleanup_tabs`, they are always closed.
+    fn on_close_requested<'a>(&mut self, _tab_key: &TabKey, _context: &mut Self::Context) -> OnCloseResponse {
+        OnCloseResponse::Close
+    }
+
     // FUTURE consider adding 'is_modified' method?
 }
 
@@ -142,6 +149,11 @@ fn on_close(&mut self, tab: &mut Self::Tab) -> OnCloseResponse {
         let mut tabs = self.tabs.lock().unwrap();
 
         let tab_instance = tabs.tabs.get_mut(tab).unwrap();
+        let close_response = tab_instance.on_close_requested(tab, self.context);
+        if !matches!(close_response, OnCloseResponse::Close) {
+            return close_response;
+        }
+
         let close_response = tab_instance.on_close(tab, self.context);
         if matches!(close_response, OnCloseResponse::Close) {
             let _removed = tabs.tabs.remove(tab);
@@ -31,6 +31,8 @@ pub enum ToolbarAction {
 pub struct ToolbarContext {
     pub active_tab: Option<TabKey>,
     pub can_save: bool,
+    /// the active tab is a project tab and the project has been loaded
+    pub can_generate_artifacts: bool,
 }
 
 pub struct Toolbar {
@@ -5,13 +5,16 @@
 use eframe::epaint::Color32;
 use egui::{CentralPanel, Frame, ThemePreference};
 use egui_i18n::tr;
+use egui_material_icons::icons::ICON_PUSH_PIN;
 use egui_mobius::slot::Slot;
 use egui_mobius::types::{Enqueue, Value, ValueGuard};
 use futures::StreamExt;
+use planner_app::{RecentProjects, recent};
 use slotmap::SlotMap;
-use tracing::{debug, error, info, trace};
+use tracing::{debug, error, info, trace, warn};
 
-use crate::config::Config;
+use crate::config::{Config, DirectoryKind, Settings, SettingsChange};
+use crate::dialogs::command_palette::CommandPaletteModal;
 use crate::file_picker::{PickError, Picker};
 use crate::pcb::tabs::PcbTabs;
 use crate::pcb::{Pcb, PcbKey, PcbUiCommand};
@@ -25,7 +28,7 @@
 use crate::ui_app::app_tabs::pcb::{PcbTab, PcbTabUiCommand};
 use crate::ui_app::app_tabs::project::{ProjectTab, ProjectTabUiCommand};
 use crate::ui_app::app_tabs::{AppTabs, TabKind, TabKindContext, TabKindUiCommand, TabUiCommand};
-use crate::ui_commands::{UiCommand, handle_command};
+use crate::ui_commands::{CommandRegistry, UiCommand, handle_command};
 use crate::ui_component::{ComponentState, UiComponent};
 use crate::{fonts, pcb, project, task};
 
@@ -52,6 +55,7 @@ pub struct UiApp {
 enum PickReason {
     PcbFile,
     ProjectFile,
+    MachineDefinitionFile,
 }
 
 impl PickReason {
@@ -59,6 +63,7 @@ pub fn file_filter(self) -> &'static str {
         match self {
             PickReason::ProjectFile => "*.project.json",
             PickReason::PcbFile => "*.pcb.json",
+            PickReason::MachineDefinitionFile => "*.json",
         }
     }
 }
@@ -78,6 +83,8 @@ pub struct AppState {
 
     pub toolbar: Toolbar,
     pub pcbs: Value<SlotMap<PcbKey, Pcb>>,
+
+    pub command_palette: Option<CommandPaletteModal>,
 }
 
 impl AppState {
@@ -98,11 +105,34 @@ pub fn init(sender: Enqueue<UiCommand>) -> Self {
             projects: Value::new(SlotMap::default()),
             pcbs: Value::new(SlotMap::default()),
             toolbar,
+            command_palette: None,
+        }
+    }
+
+    /// Shows the command palette, or closes it if it is already shown.
+    pub fn toggle_command_palette(&mut self, registry: &CommandRegistry) {
+        if self.command_palette.take().is_some() {
+            return;
         }
+
+        let mut command_palette = CommandPaletteModal::new(registry);
+        command_palette
+            .component
+            .configure_mapper(self.command_sender.clone(), |command| {
+                trace!("command palette mapper. command: {:?}", command);
+                UiCommand::CommandPaletteCommand(command)
+            });
+
+        self.command_palette = Some(command_palette);
     }
 
     // FUTURE consider returning a result to indicate if the picker was busy
-    fn pick_file(&mut self, reason: PickReason, command_fn: Box<dyn Fn(PathBuf) -> UiCommand + Send + Sync + 'static>) {
+    fn pick_file(
+        &mut self,
+        reason: PickReason,
+        directory: Option<PathBuf>,
+        command_fn: Box<dyn Fn(PathBuf) -> UiCommand + Send + Sync + 'static>,
+    ) {
         // TODO use the filter, picker API needs updating
         let _filter = reason.file_filter();
 
@@ -112,21 +142,63 @@ fn pick_file(&mut self, reason: PickReason, command_fn: Box<dyn Fn(PathBuf) -> U
             }
             None => {
                 let mut picker = Picker::default();
-                picker.pick_file();
+                picker.pick_file_in(directory);
                 self.file_picker = Some((reason, picker, command_fn));
             }
         }
     }
 
-    pub fn pick_project_file(&mut self) {
+    /// `directory` - the directory to start in, see [`crate::config::DefaultDirectories`].
+    pub fn pick_project_file(&mut self, directory: Option<PathBuf>) {
         let open_project_file_command_fn = |path: PathBuf| UiCommand::OpenProjectFile(path);
 
-        self.pick_file(PickReason::ProjectFile, Box::new(open_project_file_command_fn));
+        self.pick_file(
+            PickReason::ProjectFile,
+            directory,
+            Box::new(open_project_file_command_fn),
+        );
     }
 
-    pub fn pick_pcb_file(&mut self) {
+    /// `directory` - the directory to start in, see [`crate::config::DefaultDirectories`].
+    pub fn pick_pcb_file(&mut self, directory: Option<PathBuf>) {
         let open_pcb_file_command_fn = |path: PathBuf| UiCommand::OpenPcbFile(path);
-        self.pick_file(PickReason::PcbFile, Box::new(open_pcb_file_command_fn));
+        self.pick_file(PickReason::PcbFile, directory, Box::new(open_pcb_file_command_fn));
+    }
+
+    /// `directory` - the directory to start in, e.g. the directory of the current machine definition.
+    pub fn pick_machine_definition_file(&mut self, directory: Option<PathBuf>) {
+        let machine_definition_command_fn =
+            |path: PathBuf| UiCommand::SettingsChanged(SettingsChange::MachineDefinition(Some(path)));
+        self.pick_file(
+            PickReason::MachineDefinitionFile,
+            directory,
+            Box::new(machine_definition_command_fn),
+        );
+    }
+
+    /// Closing a project also saves its session, see [`Project::save_session`].
+    pub fn save_project_sessions(&self) {
+        let projects = self.projects.lock().unwrap();
+        for (_project_key, project) in projects.iter() {
+            project.save_session();
+        }
+    }
+
+    /// Sends the settings to all the open projects and PCBs, e.g. after the settings were changed or a project, or
+    /// PCB, was opened.
+    pub fn notify_settings_changed(&self, settings: &Settings) {
+        let projects = self.projects.lock().unwrap();
+        for (project_key, project) in projects.iter() {
+            project
+                .component
+                .send((project_key, ProjectUiCommand::SettingsChanged(settings.clone())));
+        }
+
+        let pcbs = self.pcbs.lock().unwrap();
+        for (pcb_key, pcb) in pcbs.iter() {
+            pcb.component
+                .send((pcb_key, PcbUiCommand::SettingsChanged(settings.clone())));
+        }
     }
 
     pub fn make_project_tab(
@@ -360,8 +432,13 @@ pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         };
 
         {
-            let config = instance.config.lock().unwrap();
-            egui_i18n::set_language(&config.language_identifier);
+            let mut config = instance.config.lock().unwrap();
+            config.migrate();
+            config.recent_projects_file = recent::default_recent_projects_file_path();
+
+            let settings = config.settings();
+            egui_i18n::set_language(&settings.language_identifier);
+            cc.egui_ctx.set_theme(settings.theme);
 
             // Safety: now safe to use i18n translation system (e.g. [`egui_i18n::tr!`])
         }
@@ -599,7 +676,10 @@ enum Kind {
 }
 
 impl eframe::App for UiApp {
+    /// Also called when the application exits, the open projects are not closed, so their sessions are saved here.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.app_state().save_project_sessions();
+
         eframe::set_value(storage, eframe::APP_KEY, self);
     }
 
@@ -610,6 +690,14 @@ fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
             // The top panel is often a good place for a menu bar:
 
             egui::MenuBar::new().ui(ui, |ui| {
+                let recent_projects = self
+                    .config
+                    .lock()
+                    .unwrap()
+                    .recent_projects
+                    .clone();
+                let recent_projects_sender = self.app_state().command_sender.clone();
+
                 egui::Sides::new().show(
                     ui,
                     |ui| {
@@ -617,6 +705,8 @@ fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
                         let is_web = cfg!(target_arch = "wasm32");
                         if !is_web {
                             ui.menu_button(tr!("menu-top-level-file"), |ui| {
+                                recent_projects_menu(ui, &recent_projects, &recent_projects_sender);
+                                ui.separator();
                                 if ui
                                     .button(tr!("menu-item-quit"))
                                     .clicked()
@@ -649,7 +739,7 @@ fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
                                     .clicked()
                                 {
                                     sender
-                                        .send(UiCommand::ThemeChanged(ThemePreference::Dark))
+                                        .send(UiCommand::SettingsChanged(SettingsChange::Theme(ThemePreference::Dark)))
                                         .expect("sent");
                                 }
                                 if ui
@@ -660,7 +750,9 @@ fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
                                     .clicked()
                                 {
                                     sender
-                                        .send(UiCommand::ThemeChanged(ThemePreference::Light))
+                                        .send(UiCommand::SettingsChanged(SettingsChange::Theme(
+                                            ThemePreference::Light,
+                                        )))
                                         .expect("sent");
                                 }
                                 if ui
@@ -671,7 +763,9 @@ fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
                                     .clicked()
                                 {
                                     sender
-                                        .send(UiCommand::ThemeChanged(ThemePreference::System))
+                                        .send(UiCommand::SettingsChanged(SettingsChange::Theme(
+                                            ThemePreference::System,
+                                        )))
                                         .expect("sent");
                                 }
                             });
@@ -694,7 +788,9 @@ fn format_language_key(language_identifier: &String) -> String {
                                         .clicked()
                                     {
                                         sender
-                                            .send(UiCommand::LangageChanged(other_language.clone()))
+                                            .send(UiCommand::SettingsChanged(SettingsChange::Language(
+                                                other_language.clone(),
+                                            )))
                                             .expect("sent");
                                     }
                                 }
@@ -710,6 +806,10 @@ fn format_language_key(language_identifier: &String) -> String {
                     .toolbar
                     .ui(ui, &mut context);
             }
+
+            if let Some(command_palette) = &self.app_state().command_palette {
+                command_palette.ui(ui, &mut ());
+            }
         });
 
         if !self.app_state().startup_done {
@@ -721,6 +821,7 @@ fn format_language_key(language_identifier: &String) -> String {
                     self.config
                         .lock()
                         .unwrap()
+                        .settings()
                         .show_home_tab_on_startup,
                 );
             }
@@ -732,6 +833,10 @@ fn format_language_key(language_identifier: &String) -> String {
                 self.remove_new_pcb_tabs_on_startup();
             }
             self.restore_documents_on_startup();
+
+            let settings = self.config.lock().unwrap().settings();
+            self.app_state()
+                .notify_settings_changed(&settings);
         }
 
         // in a block to limit the scope of the `app_tabs` borrow/guard
@@ -770,14 +875,51 @@ fn format_language_key(language_identifier: &String) -> String {
                 });
         }
 
+        // after the tabs, so that shortcuts used by the tabs, e.g. when recording a key binding, are not executed.
+        let registry = CommandRegistry::new(
+            &self
+                .config
+                .lock()
+                .unwrap()
+                .settings()
+                .key_bindings,
+        );
+
         let mut app_state = self.app_state();
 
-        if let Some((_reason, picker, command_fn)) = app_state.file_picker.as_mut() {
+        for command_id in registry.consume_shortcuts(ctx) {
+            app_state
+                .command_sender
+                .send(UiCommand::ExecuteCommand(command_id))
+                .ok();
+        }
+
+        if let Some((reason, picker, command_fn)) = app_state.file_picker.as_mut() {
             profiling::scope!("ui::file_picker");
             // FIXME this `update` method does not get called immediately after picking a file, instead update gets
             //       called when the user moves the mouse or interacts with the window again.
             match picker.picked() {
                 Ok(picked_file) => {
+                    // the next file of the same kind is picked from the same directory
+                    let directory_kind = match reason {
+                        PickReason::ProjectFile => Some(DirectoryKind::Projects),
+                        PickReason::PcbFile => Some(DirectoryKind::Pcbs),
+                        // the directory of the machine definition itself is used
+                        PickReason::MachineDefinitionFile => None,
+                    };
+                    if let Some(directory_kind) = directory_kind {
+                        let directory = picked_file
+                            .parent()
+                            .map(|parent| parent.to_path_buf());
+                        app_state
+                            .command_sender
+                            .send(UiCommand::SettingsChanged(SettingsChange::DefaultDirectory(
+                                directory_kind,
+                                directory,
+                            )))
+                            .ok();
+                    }
+
                     let command = command_fn(picked_file);
                     app_state
                         .command_sender
@@ -798,17 +940,33 @@ fn format_language_key(language_identifier: &String) -> String {
 //
 // project
 //
+/// The project's session is restored, the session's tabs are only used if there are no `persisted_tabs`.
 fn project_from_path(
     path: PathBuf,
     projects: &mut ValueGuard<SlotMap<ProjectKey, Project>>,
     persisted_tabs: Option<Value<ProjectTabs>>,
 ) -> (Vec<ProjectUiCommand>, ProjectKey, Value<ProjectTabs>) {
+    let session = project::session::load_session(&path)
+        .inspect_err(|cause| warn!("Unable to load the project session. path: {:?}, cause: {}", path, cause))
+        .ok()
+        .flatten();
+
+    let persisted_tabs = persisted_tabs.or_else(|| {
+        session
+            .as_ref()
+            .and_then(|session| session.tabs())
+            .map(Value::new)
+    });
+
     let mut project_commands = None;
     let mut project_tabs = None;
     let project_key = projects.insert_with_key(|key| {
         let new_project_tabs = persisted_tabs.unwrap_or_else(|| project::make_tabs(key));
 
-        let (project, commands) = Project::from_path(path.clone(), key, new_project_tabs.clone());
+        let (mut project, commands) = Project::from_path(path.clone(), key, new_project_tabs.clone());
+        if let Some(session) = &session {
+            project.restore_session(session);
+        }
 
         project_commands.replace(commands);
         project_tabs = Some(new_project_tabs);
@@ -838,6 +996,36 @@ fn project_from_args(
     (project_commands.unwrap(), project_key, project_tabs.unwrap(), path)
 }
 
+/// `File -> Open Recent`, pinned projects first.
+fn recent_projects_menu(ui: &mut egui::Ui, recent_projects: &RecentProjects, sender: &Enqueue<UiCommand>) {
+    ui.add_enabled_ui(!recent_projects.is_empty(), |ui| {
+        ui.menu_button(tr!("menu-item-open-recent"), |ui| {
+            for recent_project in recent_projects.sorted() {
+                let label = if recent_project.pinned {
+                    format!("{} {}", ICON_PUSH_PIN, recent_project.name)
+                } else {
+                    recent_project.name.clone()
+                };
+
+                if ui
+                    .button(label)
+                    .on_hover_text(
+                        recent_project
+                            .path
+                            .to_string_lossy()
+                            .to_string(),
+                    )
+                    .clicked()
+                {
+                    sender
+                        .send(UiCommand::OpenProjectFile(recent_project.path.clone()))
+                        .expect("sent");
+                }
+            }
+        });
+    });
+}
+
 fn configure_project_component(app_command_sender: Sender<UiCommand>, tab_key: TabKey, project: &mut Project) {
     project
         .component
@@ -932,9 +1120,17 @@ pub fn build_toolbar_context(app_tabs: &Value<AppTabs>) -> ToolbarContext {
         })
     });
 
+    let can_generate_artifacts = active_tab.map_or(false, |tab_key| {
+        app_tabs.with_tab_mut(&tab_key, |tab_kind| match tab_kind {
+            TabKind::Project(project_tab, _) => project_tab.loaded,
+            _ => false,
+        })
+    });
+
     let context = ToolbarContext {
         active_tab,
         can_save,
+        can_generate_artifacts,
     };
     context
 }
@@ -116,6 +116,35 @@ fn on_close(&mut self, tab_key: &TabKey, context: &mut Self::Context) -> OnClose
             }
         }
     }
+
+    /// Modified projects and PCBs are only closed once confirmed, if the confirmation is enabled, see
+    /// [`crate::config::Confirmations`].
+    fn on_close_requested(&mut self, tab_key: &TabKey, context: &mut Self::Context) -> OnCloseResponse {
+        let confirmations = context
+            .config
+            .lock()
+            .unwrap()
+            .settings()
+            .confirmations;
+
+        match self {
+            TabKind::Project(tab, _) if tab.modified && confirmations.close_modified_project => {
+                let mut project_tab_context = project::ProjectTabContext {
+                    tab_key: tab_key.clone(),
+                    projects: context.projects.clone(),
+                };
+                tab.on_close_requested(tab_key, &mut project_tab_context)
+            }
+            TabKind::Pcb(tab, _) if tab.modified && confirmations.close_modified_pcb => {
+                let mut pcb_tab_context = pcb::PcbTabContext {
+                    tab_key: tab_key.clone(),
+                    pcbs: context.pcbs.clone(),
+                };
+                tab.on_close_requested(tab_key, &mut pcb_tab_context)
+            }
+            _ => OnCloseResponse::Close,
+        }
+    }
 }
 
 impl UiComponent for TabKind {
@@ -382,6 +411,16 @@ pub fn with_tab_mut<F, O>(&self, tab_key: &TabKey, f: F) -> O
             f(&mut tab)
         }
 
+        /// Removes the tab from the dock tree without calling `on_close_requested`, e.g. after the close has been
+        /// confirmed, `on_close` is then called by [`Self::cleanup_tabs`].
+        #[allow(dead_code)]
+        pub fn close_tab(&mut self, tab_key: &TabKey) {
+            let mut tree = self.tree.lock().unwrap();
+            if let Some(find_result) = tree.find_tab(tab_key) {
+                tree.remove_tab(find_result);
+            }
+        }
+
         #[allow(dead_code)]
         pub fn show_tab<F>(&mut self, f: F) -> Result<TabKey, ()>
         where
@@ -585,6 +624,19 @@ pub fn show_home_tab(&mut self) {
             .ok();
     }
 
+    /// Shows the home tab, or closes it if it is the active tab.
+    pub fn toggle_home_tab(&mut self) {
+        match self.find_home_tab() {
+            Some(home_tab_key) if self.active_tab() == Some(home_tab_key) => {
+                let mut tree = self.tree.lock().unwrap();
+                let find_result = tree.find_tab(&home_tab_key).unwrap();
+
+                tree.remove_tab(find_result);
+            }
+            _ => self.show_home_tab(),
+        }
+    }
+
     pub fn show_pcb_tab(&mut self, path: &PathBuf) -> Result<TabKey, ()> {
         self.show_tab(|candidate_tab| matches!(candidate_tab, TabKind::Pcb(tab, _) if tab.path.eq(path)))
     }
@@ -1,21 +1,35 @@
-use egui::{Checkbox, FontFamily, RichText, Ui, WidgetText};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use egui::{Checkbox, FontFamily, KeyboardShortcut, RichText, Ui, WidgetText};
 use egui_i18n::tr;
-use egui_material_icons::icons::ICON_HOME;
+use egui_material_icons::icons::{ICON_DELETE, ICON_HOME, ICON_PUSH_PIN};
 use egui_mobius::types::Value;
 use egui_taffy::taffy::Style;
 use egui_taffy::taffy::prelude::{length, percent};
 use egui_taffy::{TuiBuilderLogic, taffy, tui};
+use planner_app::machine_definition::{self, MachineDefinition};
+use planner_app::{AxisKind, Kinematics};
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
-use crate::config::Config;
+use crate::config::{Config, Confirmations, SettingsChange};
 use crate::tabs::{Tab, TabKey};
+use crate::ui_commands::{CommandId, CommandRegistry, is_bindable_shortcut};
 use crate::ui_component::{ComponentState, UiComponent};
 
 #[derive(Default, Debug, Deserialize, Serialize)]
 pub struct HomeTab {
     show_on_startup: bool,
 
+    /// The command whose shortcut is being recorded, the next key press is used.
+    #[serde(skip)]
+    recording: Option<CommandId>,
+
+    /// The machine definition of the settings, loaded when it is first shown, see [`HomeTab::machine_ui`].
+    #[serde(skip)]
+    machine_definition: Mutex<Option<(PathBuf, Result<MachineDefinition, String>)>>,
+
     #[serde(skip)]
     pub component: ComponentState<HomeTabUiCommand>,
 }
@@ -24,11 +38,29 @@ pub struct HomeTab {
 pub enum HomeTabUiCommand {
     None,
     SetShowOnStartup(bool),
+    OpenProject(PathBuf),
+    PinRecentProject { path: PathBuf, pinned: bool },
+    RemoveRecentProject(PathBuf),
+    RecordKeyBinding(CommandId),
+    KeyBindingRecorded(CommandId, KeyboardShortcut),
+    CancelRecording,
+    ClearKeyBinding(CommandId),
+    ResetKeyBinding(CommandId),
+    SetActiveProfile(String),
+    SetConfirmations(Confirmations),
+    PickMachineDefinition,
+    ClearMachineDefinition,
+    ReloadMachineDefinition,
 }
 
 #[derive(Debug)]
 pub enum HomeTabAction {
     None,
+    SettingsChanged(SettingsChange),
+    OpenProject(PathBuf),
+    PinRecentProject { path: PathBuf, pinned: bool },
+    RemoveRecentProject(PathBuf),
+    PickMachineDefinition,
 }
 
 pub struct HomeTabContext {
@@ -52,6 +84,381 @@ fn ui(&mut self, ui: &mut Ui, tab_key: &TabKey, context: &mut Self::Context) {
     }
 }
 
+impl HomeTab {
+    /// Switching to a profile that does not exist creates it, with the default settings.
+    fn profile_ui(&self, ui: &mut Ui, context: &HomeTabContext) {
+        let (active_profile, profile_names) = {
+            let config = context.config.lock().unwrap();
+            let profile_names = config
+                .profile_names()
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>();
+            (config.active_profile.clone(), profile_names)
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("home-profile-header"));
+
+            egui::ComboBox::from_id_salt(ui.id().with("profile"))
+                .selected_text(&active_profile)
+                .show_ui(ui, |ui| {
+                    for profile_name in profile_names {
+                        let selected = profile_name == active_profile;
+                        if ui
+                            .add(egui::Button::selectable(selected, &profile_name))
+                            .clicked()
+                            && !selected
+                        {
+                            self.component
+                                .send(HomeTabUiCommand::SetActiveProfile(profile_name));
+                        }
+                    }
+                });
+
+            let new_profile_id = ui.id().with("new_profile");
+            let mut new_profile = ui
+                .data(|data| data.get_temp::<String>(new_profile_id))
+                .unwrap_or_default();
+
+            ui.add(egui::TextEdit::singleline(&mut new_profile).hint_text(tr!("home-profile-new-hint")));
+
+            let name = new_profile.trim().to_string();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new(tr!("home-profile-add")))
+                .on_hover_text(tr!("home-profile-add-hover-text"))
+                .clicked()
+            {
+                self.component
+                    .send(HomeTabUiCommand::SetActiveProfile(name));
+                new_profile.clear();
+            }
+
+            ui.data_mut(|data| data.insert_temp(new_profile_id, new_profile));
+        });
+    }
+
+    /// Pinned projects first, so that they can be quickly reopened on startup.
+    fn recent_projects_ui(&self, ui: &mut Ui, context: &HomeTabContext) {
+        let recent_projects = context
+            .config
+            .lock()
+            .unwrap()
+            .recent_projects
+            .clone();
+
+        ui.heading(tr!("home-recent-projects-header"));
+
+        if recent_projects.is_empty() {
+            ui.label(tr!("home-recent-projects-none"));
+            return;
+        }
+
+        egui::Grid::new(ui.id().with("recent_projects"))
+            .striped(true)
+            .show(ui, |ui| {
+                for recent_project in recent_projects.sorted() {
+                    if ui
+                        .link(&recent_project.name)
+                        .on_hover_text(tr!("home-recent-projects-open-hover-text"))
+                        .clicked()
+                    {
+                        self.component
+                            .send(HomeTabUiCommand::OpenProject(recent_project.path.clone()));
+                    }
+                    ui.label(
+                        recent_project
+                            .path
+                            .to_string_lossy()
+                            .to_string(),
+                    );
+                    ui.label(
+                        recent_project
+                            .last_opened
+                            .date()
+                            .to_string(),
+                    );
+
+                    let pin_hover_text = match recent_project.pinned {
+                        true => tr!("home-recent-projects-unpin-hover-text"),
+                        false => tr!("home-recent-projects-pin-hover-text"),
+                    };
+                    if ui
+                        .add(egui::Button::selectable(recent_project.pinned, ICON_PUSH_PIN))
+                        .on_hover_text(pin_hover_text)
+                        .clicked()
+                    {
+                        self.component
+                            .send(HomeTabUiCommand::PinRecentProject {
+                                path: recent_project.path.clone(),
+                                pinned: !recent_project.pinned,
+                            });
+                    }
+                    if ui
+                        .button(ICON_DELETE)
+                        .on_hover_text(tr!("home-recent-projects-remove-hover-text"))
+                        .clicked()
+                    {
+                        self.component
+                            .send(HomeTabUiCommand::RemoveRecentProject(recent_project.path.clone()));
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn key_bindings_ui(&self, ui: &mut Ui, context: &HomeTabContext) {
+        let registry = CommandRegistry::new(
+            &context
+                .config
+                .lock()
+                .unwrap()
+                .settings()
+                .key_bindings,
+        );
+
+        ui.collapsing(tr!("home-key-bindings-header"), |ui| {
+            egui::Grid::new(ui.id().with("key_bindings"))
+                .striped(true)
+                .show(ui, |ui| {
+                    for (command_id, shortcut) in registry.commands() {
+                        ui.label(tr!(command_id.i18n_key()));
+
+                        if self.recording == Some(command_id) {
+                            ui.label(tr!("home-key-bindings-recording"));
+                        } else {
+                            let shortcut_text = match shortcut {
+                                Some(shortcut) => ui.ctx().format_shortcut(&shortcut),
+                                None => tr!("home-key-bindings-unbound"),
+                            };
+                            if ui
+                                .button(shortcut_text)
+                                .on_hover_text(tr!("home-key-bindings-record-hover-text"))
+                                .clicked()
+                            {
+                                self.component
+                                    .send(HomeTabUiCommand::RecordKeyBinding(command_id));
+                            }
+                        }
+
+                        if ui
+                            .button(ICON_DELETE)
+                            .on_hover_text(tr!("home-key-bindings-clear-hover-text"))
+                            .clicked()
+                        {
+                            self.component
+                                .send(HomeTabUiCommand::ClearKeyBinding(command_id));
+                        }
+                        if ui
+                            .button(tr!("home-key-bindings-reset"))
+                            .on_hover_text(tr!("home-key-bindings-reset-hover-text"))
+                            .clicked()
+                        {
+                            self.component
+                                .send(HomeTabUiCommand::ResetKeyBinding(command_id));
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if let Some(command_id) = self.recording {
+            self.record_key_binding(ui, command_id);
+        }
+    }
+
+    fn confirmations_ui(&self, ui: &mut Ui, context: &HomeTabContext) {
+        let mut confirmations = context
+            .config
+            .lock()
+            .unwrap()
+            .settings()
+            .confirmations;
+
+        ui.collapsing(tr!("home-confirmations-header"), |ui| {
+            let mut changed = ui
+                .add(Checkbox::new(
+                    &mut confirmations.close_modified_project,
+                    tr!("home-confirmations-close-modified-project"),
+                ))
+                .changed();
+            changed |= ui
+                .add(Checkbox::new(
+                    &mut confirmations.close_modified_pcb,
+                    tr!("home-confirmations-close-modified-pcb"),
+                ))
+                .changed();
+            changed |= ui
+                .add(Checkbox::new(
+                    &mut confirmations.remove_phase,
+                    tr!("home-confirmations-remove-phase"),
+                ))
+                .changed();
+
+            if changed {
+                self.component
+                    .send(HomeTabUiCommand::SetConfirmations(confirmations));
+            }
+        });
+    }
+
+    /// Shows the capabilities of the machine, or the reason the machine definition could not be loaded.
+    fn machine_ui(&self, ui: &mut Ui, context: &HomeTabContext) {
+        let path = context
+            .config
+            .lock()
+            .unwrap()
+            .settings()
+            .machine_definition;
+
+        ui.collapsing(tr!("home-machine-header"), |ui| {
+            ui.horizontal(|ui| {
+                let path_text = match &path {
+                    Some(path) => path.to_string_lossy().to_string(),
+                    None => tr!("home-machine-none"),
+                };
+                ui.label(path_text);
+
+                if ui
+                    .button(tr!("home-machine-choose"))
+                    .clicked()
+                {
+                    self.component
+                        .send(HomeTabUiCommand::PickMachineDefinition);
+                }
+                if ui
+                    .add_enabled(path.is_some(), egui::Button::new(tr!("home-machine-reload")))
+                    .clicked()
+                {
+                    self.component
+                        .send(HomeTabUiCommand::ReloadMachineDefinition);
+                }
+                if ui
+                    .add_enabled(path.is_some(), egui::Button::new(ICON_DELETE))
+                    .on_hover_text(tr!("home-machine-clear-hover-text"))
+                    .clicked()
+                {
+                    self.component
+                        .send(HomeTabUiCommand::ClearMachineDefinition);
+                }
+            });
+
+            let Some(path) = path else {
+                return;
+            };
+
+            let mut loaded = self.machine_definition.lock().unwrap();
+            if !matches!(&*loaded, Some((loaded_path, _)) if loaded_path.eq(&path)) {
+                let result = machine_definition::load_machine_definition(&path).map_err(|error| error.to_string());
+                *loaded = Some((path, result));
+            }
+
+            match loaded.as_ref() {
+                Some((_path, Ok(definition))) => Self::machine_definition_ui(ui, definition),
+                Some((_path, Err(error))) => {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                None => {}
+            }
+        });
+    }
+
+    fn machine_definition_ui(ui: &mut Ui, definition: &MachineDefinition) {
+        let kinematics = match definition.kinematics {
+            Kinematics::Cartesian => tr!("home-machine-kinematics-cartesian"),
+            Kinematics::CoreXY => tr!("home-machine-kinematics-corexy"),
+        };
+        let homing_order = definition
+            .homing_order
+            .iter()
+            .map(|axes| {
+                axes.iter()
+                    .map(|axis| axis.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect::<Vec<_>>()
+            .join(" > ");
+
+        egui::Grid::new(ui.id().with("machine")).show(ui, |ui| {
+            ui.label(tr!("home-machine-name"));
+            ui.label(&definition.name);
+            ui.end_row();
+            ui.label(tr!("home-machine-kinematics"));
+            ui.label(kinematics);
+            ui.end_row();
+            ui.label(tr!("home-machine-homing-order"));
+            ui.label(homing_order);
+            ui.end_row();
+        });
+
+        egui::Grid::new(ui.id().with("machine_axes"))
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong(tr!("home-machine-axis"));
+                ui.strong(tr!("home-machine-axis-kind"));
+                ui.strong(tr!("home-machine-axis-steps-per-unit"));
+                ui.strong(tr!("home-machine-axis-soft-limits"));
+                ui.strong(tr!("home-machine-axis-max-velocity"));
+                ui.strong(tr!("home-machine-axis-max-acceleration"));
+                ui.end_row();
+
+                for axis in definition.axes.iter() {
+                    let kind = match axis.kind {
+                        AxisKind::Linear => tr!("home-machine-axis-kind-linear"),
+                        AxisKind::Rotary => tr!("home-machine-axis-kind-rotary"),
+                    };
+                    let soft_limits = match &axis.soft_limits {
+                        Some(soft_limits) => format!("{} - {}", soft_limits.min, soft_limits.max),
+                        None => tr!("home-machine-axis-unlimited"),
+                    };
+
+                    ui.label(axis.name.to_string());
+                    ui.label(kind);
+                    ui.label(axis.steps_per_unit.to_string());
+                    ui.label(soft_limits);
+                    ui.label(axis.max_velocity.to_string());
+                    ui.label(axis.max_acceleration.to_string());
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// Consumes the first key press, so that the command currently bound to it is not executed, escape cancels.
+    fn record_key_binding(&self, ui: &mut Ui, command_id: CommandId) {
+        let shortcut = ui.input_mut(|input| {
+            let shortcut = input
+                .events
+                .iter()
+                .find_map(|event| match event {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        modifiers,
+                        ..
+                    } => Some(KeyboardShortcut::new(*modifiers, *key)),
+                    _ => None,
+                })
+                // keys that would also be typed into text edits are ignored, recording continues
+                .filter(|shortcut| shortcut.logical_key == egui::Key::Escape || is_bindable_shortcut(shortcut))?;
+            input.consume_shortcut(&shortcut);
+            Some(shortcut)
+        });
+
+        match shortcut {
+            Some(shortcut) if shortcut.logical_key == egui::Key::Escape => {
+                self.component
+                    .send(HomeTabUiCommand::CancelRecording);
+            }
+            Some(shortcut) => {
+                self.component
+                    .send(HomeTabUiCommand::KeyBindingRecorded(command_id, shortcut));
+            }
+            None => {}
+        }
+    }
+}
+
 impl UiComponent for HomeTab {
     type UiContext<'context> = HomeTabContext;
     type UiCommand = HomeTabUiCommand;
@@ -101,11 +508,16 @@ fn ui<'context>(&self, ui: &mut Ui, context: &mut Self::UiContext<'context>) {
                     );
                 });
 
+                tui.ui(|ui| {
+                    self.profile_ui(ui, context);
+                });
+
                 tui.ui(|ui| {
                     let mut show_home_tab_on_startup = context
                         .config
                         .lock()
                         .unwrap()
+                        .settings()
                         .show_home_tab_on_startup;
                     if ui
                         .add(Checkbox::new(
@@ -118,6 +530,22 @@ fn ui<'context>(&self, ui: &mut Ui, context: &mut Self::UiContext<'context>) {
                             .send(HomeTabUiCommand::SetShowOnStartup(show_home_tab_on_startup));
                     }
                 });
+
+                tui.ui(|ui| {
+                    self.recent_projects_ui(ui, context);
+                });
+
+                tui.ui(|ui| {
+                    self.key_bindings_ui(ui, context);
+                });
+
+                tui.ui(|ui| {
+                    self.confirmations_ui(ui, context);
+                });
+
+                tui.ui(|ui| {
+                    self.machine_ui(ui, context);
+                });
             });
     }
 
@@ -125,17 +553,64 @@ fn ui<'context>(&self, ui: &mut Ui, context: &mut Self::UiContext<'context>) {
     fn update<'context>(
         &mut self,
         command: Self::UiCommand,
-        context: &mut Self::UiContext<'context>,
+        _context: &mut Self::UiContext<'context>,
     ) -> Option<Self::UiAction> {
         match command {
             HomeTabUiCommand::None => Some(HomeTabAction::None),
             HomeTabUiCommand::SetShowOnStartup(value) => {
                 debug!("SetShowOnStartup: {}", value);
-                context
-                    .config
-                    .lock()
+                Some(HomeTabAction::SettingsChanged(SettingsChange::ShowHomeTabOnStartup(
+                    value,
+                )))
+            }
+            HomeTabUiCommand::OpenProject(path) => Some(HomeTabAction::OpenProject(path)),
+            HomeTabUiCommand::PinRecentProject {
+                path,
+                pinned,
+            } => Some(HomeTabAction::PinRecentProject {
+                path,
+                pinned,
+            }),
+            HomeTabUiCommand::RemoveRecentProject(path) => Some(HomeTabAction::RemoveRecentProject(path)),
+            HomeTabUiCommand::RecordKeyBinding(command_id) => {
+                self.recording = Some(command_id);
+                None
+            }
+            HomeTabUiCommand::KeyBindingRecorded(command_id, shortcut) => {
+                // ignore key presses recorded after the first one, before the recording was stopped
+                if self.recording.take() != Some(command_id) {
+                    return None;
+                }
+                Some(HomeTabAction::SettingsChanged(SettingsChange::KeyBinding(
+                    command_id,
+                    Some(shortcut),
+                )))
+            }
+            HomeTabUiCommand::CancelRecording => {
+                self.recording = None;
+                None
+            }
+            HomeTabUiCommand::ClearKeyBinding(command_id) => Some(HomeTabAction::SettingsChanged(
+                SettingsChange::KeyBinding(command_id, None),
+            )),
+            HomeTabUiCommand::ResetKeyBinding(command_id) => Some(HomeTabAction::SettingsChanged(
+                SettingsChange::ResetKeyBinding(command_id),
+            )),
+            HomeTabUiCommand::SetActiveProfile(profile) => {
+                Some(HomeTabAction::SettingsChanged(SettingsChange::ActiveProfile(profile)))
+            }
+            HomeTabUiCommand::SetConfirmations(confirmations) => Some(HomeTabAction::SettingsChanged(
+                SettingsChange::Confirmations(confirmations),
+            )),
+            HomeTabUiCommand::PickMachineDefinition => Some(HomeTabAction::PickMachineDefinition),
+            HomeTabUiCommand::ClearMachineDefinition => {
+                Some(HomeTabAction::SettingsChanged(SettingsChange::MachineDefinition(None)))
+            }
+            HomeTabUiCommand::ReloadMachineDefinition => {
+                self.machine_definition
+                    .get_mut()
                     .unwrap()
-                    .show_home_tab_on_startup = value;
+                    .take();
                 None
             }
         }
@@ -7,6 +7,7 @@
 use slotmap::SlotMap;
 use tracing::debug;
 
+use crate::config::SettingsChange;
 use crate::pcb::tabs::PcbTabs;
 use crate::pcb::{Pcb, PcbAction, PcbContext, PcbKey, PcbUiCommand};
 use crate::tabs::{Tab, TabKey};
@@ -41,6 +42,9 @@ pub enum PcbTabAction {
     PcbTask(PcbKey, Task<PcbAction>),
     SetModifiedState(bool),
     RequestRepaint,
+    SettingsChanged(SettingsChange),
+    /// Closes the tab without asking for confirmation again.
+    Close,
 }
 
 pub struct PcbTabContext {
@@ -91,6 +95,19 @@ fn on_close(&mut self, _tab_key: &TabKey, _tab_context: &mut Self::Context) -> O
 
         OnCloseResponse::Close
     }
+
+    /// The PCB asks for confirmation, the tab is closed when confirmed, see [`PcbTabAction::Close`].
+    fn on_close_requested(&mut self, _tab_key: &TabKey, tab_context: &mut Self::Context) -> OnCloseResponse {
+        let pcbs = tab_context.pcbs.lock().unwrap();
+        let Some(pcb) = pcbs.get(self.pcb_key) else {
+            return OnCloseResponse::Close;
+        };
+
+        pcb.component
+            .send((self.pcb_key, PcbUiCommand::ConfirmClose));
+
+        OnCloseResponse::Focus
+    }
 }
 
 impl UiComponent for PcbTab {
@@ -139,6 +156,8 @@ fn update<'context>(
                         .update((key, command), &mut pcb_context)
                         .map(|action| PcbTabAction::PcbTask(key, Task::done(action))),
                     Some(PcbAction::RequestRepaint) => Some(PcbTabAction::RequestRepaint),
+                    Some(PcbAction::SettingsChanged(change)) => Some(PcbTabAction::SettingsChanged(change)),
+                    Some(PcbAction::Close) => Some(PcbTabAction::Close),
                 }
             }
         }
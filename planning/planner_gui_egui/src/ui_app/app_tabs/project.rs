@@ -8,6 +8,7 @@
 use slotmap::SlotMap;
 use tracing::debug;
 
+use crate::config::SettingsChange;
 use crate::project::tabs::ProjectTabs;
 use crate::project::{Project, ProjectAction, ProjectContext, ProjectKey, ProjectUiCommand};
 use crate::tabs::{Tab, TabKey};
@@ -26,6 +27,10 @@ pub struct ProjectTab {
     #[serde(skip)]
     pub modified: bool,
 
+    /// set once the project has been loaded or created, see [`ProjectTabAction::Opened`]
+    #[serde(skip)]
+    pub loaded: bool,
+
     #[serde(skip)]
     pub component: ComponentState<ProjectTabUiCommand>,
 
@@ -43,6 +48,14 @@ pub enum ProjectTabAction {
     SetModifiedState(bool),
     RequestRepaint,
     ShowPcb(PathBuf),
+    SettingsChanged(SettingsChange),
+    /// Closes the tab without asking for confirmation again.
+    Close,
+    /// The project was loaded or created, see [`ProjectAction::Opened`].
+    Opened {
+        path: PathBuf,
+        name: String,
+    },
     LocateComponent {
         pcb_file: PathBuf,
         object_path: ObjectPath,
@@ -70,6 +83,7 @@ pub fn new(label: String, path: PathBuf, project_key: ProjectKey, project_tabs:
             path,
             label,
             modified: false,
+            loaded: false,
             component: ComponentState::default(),
         }
     }
@@ -100,10 +114,26 @@ fn ui(&mut self, ui: &mut Ui, tab_key: &TabKey, tab_context: &mut Self::Context)
     fn on_close(&mut self, _tab_key: &TabKey, _tab_context: &mut Self::Context) -> OnCloseResponse {
         debug!("closing project. key: {:?}", self.project_key);
         let mut projects = _tab_context.projects.lock().unwrap();
-        projects.remove(self.project_key);
+        if let Some(project) = projects.remove(self.project_key) {
+            project.save_session();
+        }
 
         OnCloseResponse::Close
     }
+
+    /// The project asks for confirmation, the tab is closed when confirmed, see [`ProjectTabAction::Close`].
+    fn on_close_requested(&mut self, _tab_key: &TabKey, tab_context: &mut Self::Context) -> OnCloseResponse {
+        let projects = tab_context.projects.lock().unwrap();
+        let Some(project) = projects.get(self.project_key) else {
+            return OnCloseResponse::Close;
+        };
+
+        project
+            .component
+            .send((self.project_key, ProjectUiCommand::ConfirmClose));
+
+        OnCloseResponse::Focus
+    }
 }
 
 impl UiComponent for ProjectTab {
@@ -155,6 +185,18 @@ fn update<'context>(
                         .map(|action| ProjectTabAction::ProjectTask(key, Task::done(action))),
                     Some(ProjectAction::RequestRepaint) => Some(ProjectTabAction::RequestRepaint),
                     Some(ProjectAction::ShowPcb(path)) => Some(ProjectTabAction::ShowPcb(path)),
+                    Some(ProjectAction::SettingsChanged(change)) => Some(ProjectTabAction::SettingsChanged(change)),
+                    Some(ProjectAction::Close) => Some(ProjectTabAction::Close),
+                    Some(ProjectAction::Opened {
+                        path,
+                        name,
+                    }) => {
+                        self.loaded = true;
+                        Some(ProjectTabAction::Opened {
+                            path,
+                            name,
+                        })
+                    }
                     Some(ProjectAction::LocateComponent {
                         pcb_file,
                         object_path,
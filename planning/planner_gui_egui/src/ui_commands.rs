@@ -1,11 +1,14 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
-use egui::{Context, ThemePreference};
+use egui::{Context, Key, KeyboardShortcut, Modifiers};
 use egui_mobius::types::Value;
 use planner_app::{ObjectPath, PcbSide, PlacementPositionUnit};
 use tracing::{debug, trace};
 
-use crate::config::Config;
+use crate::config::{Config, SettingsChange};
+use crate::dialogs::command_palette::{CommandPaletteModalAction, CommandPaletteModalUiCommand};
 use crate::pcb::{PcbAction, PcbUiCommand};
 use crate::project::{ProjectAction, ProjectUiCommand};
 use crate::tabs::TabKey;
@@ -27,14 +30,23 @@ pub enum UiCommand {
     #[allow(dead_code)]
     None,
     ToolbarCommand(ToolbarUiCommand),
+    /// The project is added to the recent projects once it has been loaded, see [`ProjectTabAction::Opened`].
     OpenProjectFile(PathBuf),
     OpenPcbFile(PathBuf),
+    PinRecentProject {
+        path: PathBuf,
+        pinned: bool,
+    },
+    RemoveRecentProject(PathBuf),
+    /// Executes a named command, e.g. when its keyboard shortcut is pressed, see [`CommandRegistry`].
+    ExecuteCommand(CommandId),
+    CommandPaletteCommand(CommandPaletteModalUiCommand),
     TabCommand {
         tab_key: TabKey,
         command: TabUiCommand,
     },
-    LangageChanged(String),
-    ThemeChanged(ThemePreference),
+    /// Applied to the active profile, then the open projects are notified, see [`AppState::notify_settings_changed`].
+    SettingsChanged(SettingsChange),
     ShowPcb(PathBuf),
     LocateComponent {
         pcb_file: PathBuf,
@@ -57,16 +69,17 @@ pub fn handle_command(
 
     match command {
         UiCommand::None => Task::none(),
-        UiCommand::LangageChanged(language) => {
-            egui_i18n::set_language(&language);
-            config
+        UiCommand::SettingsChanged(change) => {
+            debug!("settings changed. change: {:?}", change);
+            let settings = config.lock().unwrap().apply(change);
+
+            egui_i18n::set_language(&settings.language_identifier);
+            ui_context.set_theme(settings.theme);
+
+            app_state
                 .lock()
                 .unwrap()
-                .language_identifier = language;
-            Task::none()
-        }
-        UiCommand::ThemeChanged(theme) => {
-            ui_context.set_theme(theme);
+                .notify_settings_changed(&settings);
             Task::none()
         }
         UiCommand::ToolbarCommand(command) => {
@@ -78,19 +91,62 @@ pub fn handle_command(
                 .toolbar
                 .update(command, &mut context);
 
-            let task = handle_toolbar_action(toolbar_action, &app_state, &app_tabs);
+            let task = handle_toolbar_action(toolbar_action, &app_state, &app_tabs, &config);
             task
         }
         UiCommand::OpenProjectFile(picked_file) => {
+            let config = config.lock().unwrap();
+
             let mut app_state = app_state.lock().unwrap();
             app_state.open_project_file(picked_file, app_tabs);
+            app_state.notify_settings_changed(&config.settings());
             Task::none()
         }
         UiCommand::OpenPcbFile(picked_file) => {
             let mut app_state = app_state.lock().unwrap();
             app_state.open_pcb_file(picked_file, app_tabs);
+            app_state.notify_settings_changed(&config.lock().unwrap().settings());
+            Task::none()
+        }
+        UiCommand::PinRecentProject {
+            path,
+            pinned,
+        } => {
+            config
+                .lock()
+                .unwrap()
+                .set_recent_project_pinned(&path, pinned);
             Task::none()
         }
+        UiCommand::RemoveRecentProject(path) => {
+            config
+                .lock()
+                .unwrap()
+                .remove_recent_project(&path);
+            Task::none()
+        }
+        UiCommand::ExecuteCommand(command_id) => {
+            debug!("executing command. command: {:?}", command_id);
+            execute_command(command_id, &app_state, &app_tabs, &config)
+        }
+        UiCommand::CommandPaletteCommand(command) => {
+            let mut app_state = app_state.lock().unwrap();
+            let Some(command_palette) = app_state.command_palette.as_mut() else {
+                return Task::none();
+            };
+
+            match command_palette.update(command, &mut ()) {
+                None => Task::none(),
+                Some(CommandPaletteModalAction::CloseDialog) => {
+                    app_state.command_palette = None;
+                    Task::none()
+                }
+                Some(CommandPaletteModalAction::Execute(command_id)) => {
+                    app_state.command_palette = None;
+                    Task::done(UiCommand::ExecuteCommand(command_id))
+                }
+            }
+        }
         UiCommand::ShowPcb(path) => {
             if let Ok(tab_key) = app_tabs
                 .lock()
@@ -166,6 +222,31 @@ pub fn handle_command(
                         action,
                     } => match action {
                         HomeTabAction::None => Task::none(),
+                        HomeTabAction::SettingsChanged(change) => Task::done(UiCommand::SettingsChanged(change)),
+                        HomeTabAction::OpenProject(path) => Task::done(UiCommand::OpenProjectFile(path)),
+                        HomeTabAction::PinRecentProject {
+                            path,
+                            pinned,
+                        } => Task::done(UiCommand::PinRecentProject {
+                            path,
+                            pinned,
+                        }),
+                        HomeTabAction::RemoveRecentProject(path) => Task::done(UiCommand::RemoveRecentProject(path)),
+                        HomeTabAction::PickMachineDefinition => {
+                            let directory = tab_context
+                                .config
+                                .lock()
+                                .unwrap()
+                                .settings()
+                                .machine_definition
+                                .and_then(|path| {
+                                    path.parent()
+                                        .map(|parent| parent.to_path_buf())
+                                });
+                            let mut app_state = app_state.lock().unwrap();
+                            app_state.pick_machine_definition_file(directory);
+                            Task::none()
+                        }
                     },
                     TabKindAction::NewProjectTabAction {
                         action,
@@ -173,6 +254,9 @@ pub fn handle_command(
                         NewProjectTabAction::Submit(args) => {
                             let mut app_state = app_state.lock().unwrap();
                             app_state.create_project(tab_key, args, app_tabs);
+
+                            let config = config.lock().unwrap();
+                            app_state.notify_settings_changed(&config.settings());
                             Task::none()
                         }
                     },
@@ -182,6 +266,7 @@ pub fn handle_command(
                         NewPcbTabAction::Submit(args) => {
                             let mut app_state = app_state.lock().unwrap();
                             app_state.create_pcb(tab_key, args, app_tabs);
+                            app_state.notify_settings_changed(&config.lock().unwrap().settings());
                             Task::none()
                         }
                     },
@@ -218,6 +303,14 @@ pub fn handle_command(
                             ui_context.request_repaint();
                             Task::none()
                         }
+                        PcbTabAction::SettingsChanged(change) => Task::done(UiCommand::SettingsChanged(change)),
+                        PcbTabAction::Close => {
+                            app_tabs
+                                .lock()
+                                .unwrap()
+                                .close_tab(&tab_key);
+                            Task::none()
+                        }
                     },
                     TabKindAction::ProjectTabAction {
                         action,
@@ -263,6 +356,24 @@ pub fn handle_command(
                             Task::none()
                         }
                         ProjectTabAction::ShowPcb(path) => Task::done(UiCommand::ShowPcb(path)),
+                        ProjectTabAction::SettingsChanged(change) => Task::done(UiCommand::SettingsChanged(change)),
+                        ProjectTabAction::Close => {
+                            app_tabs
+                                .lock()
+                                .unwrap()
+                                .close_tab(&tab_key);
+                            Task::none()
+                        }
+                        ProjectTabAction::Opened {
+                            path,
+                            name,
+                        } => {
+                            config
+                                .lock()
+                                .unwrap()
+                                .add_recent_project(&path, name);
+                            Task::none()
+                        }
                         ProjectTabAction::LocateComponent {
                             pcb_file,
                             object_path,
@@ -287,6 +398,7 @@ fn handle_toolbar_action(
     toolbar_action: Option<ToolbarAction>,
     app_state: &Value<AppState>,
     app_tabs: &Value<AppTabs>,
+    config: &Value<Config>,
 ) -> Task<UiCommand> {
     let Some(toolbar_action) = toolbar_action else {
         return Task::none();
@@ -314,13 +426,25 @@ fn handle_toolbar_action(
             Task::none()
         }
         ToolbarAction::PickProjectFile => {
+            let directory = config
+                .lock()
+                .unwrap()
+                .settings()
+                .default_directories
+                .projects;
             let mut app_state = app_state.lock().unwrap();
-            app_state.pick_project_file();
+            app_state.pick_project_file(directory);
             Task::none()
         }
         ToolbarAction::PickPcbFile => {
+            let directory = config
+                .lock()
+                .unwrap()
+                .settings()
+                .default_directories
+                .pcbs;
             let mut app_state = app_state.lock().unwrap();
-            app_state.pick_pcb_file();
+            app_state.pick_pcb_file(directory);
             Task::none()
         }
         ToolbarAction::SaveTab(tab_key) => {
@@ -355,3 +479,321 @@ fn handle_toolbar_action(
         }
     }
 }
+
+fn execute_command(
+    command_id: CommandId,
+    app_state: &Value<AppState>,
+    app_tabs: &Value<AppTabs>,
+    config: &Value<Config>,
+) -> Task<UiCommand> {
+    match command_id {
+        CommandId::Save => {
+            let context = build_toolbar_context(app_tabs);
+            match context.active_tab {
+                Some(tab_key) if context.can_save => {
+                    Task::done(UiCommand::ToolbarCommand(ToolbarUiCommand::SaveClicked(tab_key)))
+                }
+                _ => Task::none(),
+            }
+        }
+        CommandId::OpenProject => Task::done(UiCommand::ToolbarCommand(ToolbarUiCommand::OpenProjectClicked)),
+        CommandId::GenerateArtifacts => {
+            let context = build_toolbar_context(app_tabs);
+            match context.can_generate_artifacts {
+                true => active_project_command(app_tabs, ProjectUiCommand::GenerateArtifacts),
+                false => Task::none(),
+            }
+        }
+        CommandId::NextPlacement => active_project_command(app_tabs, ProjectUiCommand::LocateNextPlacement),
+        CommandId::ToggleHomeTab => {
+            let mut app_tabs = app_tabs.lock().unwrap();
+            app_tabs.toggle_home_tab();
+            Task::none()
+        }
+        CommandId::CommandPalette => {
+            let registry = CommandRegistry::new(
+                &config
+                    .lock()
+                    .unwrap()
+                    .settings()
+                    .key_bindings,
+            );
+            let mut app_state = app_state.lock().unwrap();
+            app_state.toggle_command_palette(&registry);
+            Task::none()
+        }
+    }
+}
+
+/// Sends the command to the project of the active tab, does nothing if the active tab is not a project tab.
+fn active_project_command(app_tabs: &Value<AppTabs>, command: ProjectUiCommand) -> Task<UiCommand> {
+    let app_tabs = app_tabs.lock().unwrap();
+    let Some(tab_key) = app_tabs.active_tab() else {
+        return Task::none();
+    };
+
+    app_tabs.with_tab_mut(&tab_key, |tab_kind| match tab_kind {
+        TabKind::Project(project_tab, _) => Task::done(UiCommand::TabCommand {
+            tab_key,
+            command: TabUiCommand::TabKindCommand(TabKindUiCommand::ProjectTabCommand {
+                command: ProjectTabUiCommand::ProjectCommand {
+                    key: project_tab.project_key,
+                    command: command.clone(),
+                },
+            }),
+        }),
+        _ => Task::none(),
+    })
+}
+
+//
+// command registry
+//
+
+/// Named commands, that can be bound to keyboard shortcuts and executed from the command palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub enum CommandId {
+    Save,
+    OpenProject,
+    GenerateArtifacts,
+    /// Locates the placement after the last located placement, in the placements table's order.
+    NextPlacement,
+    ToggleHomeTab,
+    CommandPalette,
+}
+
+impl CommandId {
+    /// In the order they are listed in the command palette.
+    pub const ALL: [CommandId; 6] = [
+        CommandId::Save,
+        CommandId::OpenProject,
+        CommandId::GenerateArtifacts,
+        CommandId::NextPlacement,
+        CommandId::ToggleHomeTab,
+        CommandId::CommandPalette,
+    ];
+
+    pub fn i18n_key(self) -> &'static str {
+        match self {
+            CommandId::Save => "command-save",
+            CommandId::OpenProject => "command-open-project",
+            CommandId::GenerateArtifacts => "command-generate-artifacts",
+            CommandId::NextPlacement => "command-next-placement",
+            CommandId::ToggleHomeTab => "command-toggle-home-tab",
+            CommandId::CommandPalette => "command-command-palette",
+        }
+    }
+
+    pub fn default_shortcut(self) -> Option<KeyboardShortcut> {
+        let shortcut = match self {
+            CommandId::Save => KeyboardShortcut::new(Modifiers::COMMAND, Key::S),
+            CommandId::OpenProject => KeyboardShortcut::new(Modifiers::COMMAND, Key::O),
+            CommandId::GenerateArtifacts => KeyboardShortcut::new(Modifiers::COMMAND, Key::G),
+            CommandId::NextPlacement => KeyboardShortcut::new(Modifiers::NONE, Key::F3),
+            CommandId::ToggleHomeTab => KeyboardShortcut::new(Modifiers::ALT, Key::H),
+            CommandId::CommandPalette => KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::P),
+        };
+
+        Some(shortcut)
+    }
+}
+
+/// The keyboard shortcuts of the commands, the default shortcuts with the user's key bindings applied, see
+/// [`crate::config::Settings::key_bindings`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandRegistry {
+    shortcuts: BTreeMap<CommandId, Option<KeyboardShortcut>>,
+}
+
+impl CommandRegistry {
+    /// `key_bindings` override the default shortcuts, a `None` binding removes the shortcut.
+    pub fn new(key_bindings: &BTreeMap<CommandId, Option<KeyboardShortcut>>) -> Self {
+        let shortcuts = CommandId::ALL
+            .iter()
+            .map(|command_id| {
+                let shortcut = key_bindings
+                    .get(command_id)
+                    .copied()
+                    .unwrap_or_else(|| command_id.default_shortcut());
+                (*command_id, shortcut)
+            })
+            .collect();
+
+        Self {
+            shortcuts,
+        }
+    }
+
+    pub fn shortcut(&self, command_id: CommandId) -> Option<KeyboardShortcut> {
+        self.shortcuts
+            .get(&command_id)
+            .copied()
+            .flatten()
+    }
+
+    /// The command that uses the shortcut, if any.
+    pub fn find_command(&self, shortcut: &KeyboardShortcut) -> Option<CommandId> {
+        self.shortcuts
+            .iter()
+            .find(|(_command_id, candidate)| candidate.as_ref() == Some(shortcut))
+            .map(|(command_id, _)| *command_id)
+    }
+
+    /// In the order they are listed in the command palette.
+    pub fn commands(&self) -> Vec<(CommandId, Option<KeyboardShortcut>)> {
+        CommandId::ALL
+            .iter()
+            .map(|command_id| (*command_id, self.shortcut(*command_id)))
+            .collect()
+    }
+
+    /// Consumes the pressed shortcuts, returns the commands to execute.
+    pub fn consume_shortcuts(&self, ctx: &Context) -> Vec<CommandId> {
+        let mut shortcuts = self
+            .shortcuts
+            .iter()
+            .filter_map(|(command_id, shortcut)| shortcut.map(|shortcut| (*command_id, shortcut)))
+            .collect::<Vec<_>>();
+
+        // shortcuts with more modifiers must be consumed first, otherwise `Ctrl+P` would also match `Ctrl+Shift+P`
+        shortcuts.sort_by_key(|(_command_id, shortcut)| Reverse(modifier_count(&shortcut.modifiers)));
+
+        ctx.input_mut(|input| {
+            shortcuts
+                .iter()
+                .filter(|(_command_id, shortcut)| input.consume_shortcut(shortcut))
+                .map(|(command_id, _shortcut)| *command_id)
+                .collect()
+        })
+    }
+}
+
+/// Shortcuts need `Ctrl`, `Alt` or `Command`, unless they use a function key, so that they are not triggered when
+/// typing into a text edit.
+pub fn is_bindable_shortcut(shortcut: &KeyboardShortcut) -> bool {
+    let modifiers = shortcut.modifiers;
+    modifiers.alt || modifiers.ctrl || modifiers.command || modifiers.mac_cmd || is_function_key(shortcut.logical_key)
+}
+
+fn is_function_key(key: Key) -> bool {
+    matches!(
+        key,
+        Key::F1
+            | Key::F2
+            | Key::F3
+            | Key::F4
+            | Key::F5
+            | Key::F6
+            | Key::F7
+            | Key::F8
+            | Key::F9
+            | Key::F10
+            | Key::F11
+            | Key::F12
+            | Key::F13
+            | Key::F14
+            | Key::F15
+            | Key::F16
+            | Key::F17
+            | Key::F18
+            | Key::F19
+            | Key::F20
+            | Key::F21
+            | Key::F22
+            | Key::F23
+            | Key::F24
+            | Key::F25
+            | Key::F26
+            | Key::F27
+            | Key::F28
+            | Key::F29
+            | Key::F30
+            | Key::F31
+            | Key::F32
+            | Key::F33
+            | Key::F34
+            | Key::F35
+    )
+}
+
+fn modifier_count(modifiers: &Modifiers) -> usize {
+    [modifiers.alt, modifiers.command || modifiers.ctrl, modifiers.shift]
+        .iter()
+        .filter(|modifier| **modifier)
+        .count()
+}
+
+#[cfg(test)]
+mod ui_commands_tests {
+    use super::*;
+
+    #[test]
+    pub fn default_shortcuts_are_used_without_key_bindings() {
+        // when
+        let registry = CommandRegistry::new(&BTreeMap::new());
+
+        // then
+        for command_id in CommandId::ALL {
+            assert_eq!(registry.shortcut(command_id), command_id.default_shortcut());
+        }
+    }
+
+    #[test]
+    pub fn key_bindings_override_the_default_shortcuts() {
+        // given
+        let shortcut = KeyboardShortcut::new(Modifiers::ALT, Key::S);
+        let key_bindings = BTreeMap::from([(CommandId::Save, Some(shortcut))]);
+
+        // when
+        let registry = CommandRegistry::new(&key_bindings);
+
+        // then
+        assert_eq!(registry.shortcut(CommandId::Save), Some(shortcut));
+        assert_eq!(registry.find_command(&shortcut), Some(CommandId::Save));
+        assert_eq!(
+            registry.shortcut(CommandId::OpenProject),
+            CommandId::OpenProject.default_shortcut()
+        );
+    }
+
+    #[test]
+    pub fn unbound_commands_have_no_shortcut() {
+        // given
+        let key_bindings = BTreeMap::from([(CommandId::Save, None)]);
+
+        // when
+        let registry = CommandRegistry::new(&key_bindings);
+
+        // then
+        assert_eq!(registry.shortcut(CommandId::Save), None);
+        let default_shortcut = CommandId::Save
+            .default_shortcut()
+            .unwrap();
+        assert_eq!(registry.find_command(&default_shortcut), None);
+        assert!(
+            registry
+                .commands()
+                .contains(&(CommandId::Save, None))
+        );
+    }
+
+    #[test]
+    pub fn shortcuts_need_a_modifier_or_a_function_key() {
+        // expect
+        assert!(is_bindable_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::S)));
+        assert!(is_bindable_shortcut(&KeyboardShortcut::new(Modifiers::ALT, Key::H)));
+        assert!(is_bindable_shortcut(&KeyboardShortcut::new(Modifiers::NONE, Key::F3)));
+        assert!(!is_bindable_shortcut(&KeyboardShortcut::new(Modifiers::NONE, Key::A)));
+        assert!(!is_bindable_shortcut(&KeyboardShortcut::new(Modifiers::SHIFT, Key::A)));
+    }
+
+    #[test]
+    pub fn default_shortcuts_are_bindable() {
+        // expect
+        for command_id in CommandId::ALL {
+            let shortcut = command_id.default_shortcut().unwrap();
+            assert!(is_bindable_shortcut(&shortcut), "{:?}", command_id);
+        }
+    }
+}
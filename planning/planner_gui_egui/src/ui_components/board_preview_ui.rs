@@ -0,0 +1,524 @@
+//! An isometric preview of an assembled board.
+//!
+//! The board profile is extruded to the thickness of the board and each component is drawn as a box, sized from the
+//! dimensions of its package, at its placement position.  The board is viewed from above, from the front-right, and
+//! when viewed from the bottom the board is flipped over the Y axis.  Faces are drawn furthest first.
+
+use derivative::Derivative;
+use eframe::emath::Align2;
+use eframe::epaint::{Color32, FontId, Mesh, Shape};
+use egui::{Pos2, Sense, Stroke, Ui, Vec2};
+use num_traits::ToPrimitive;
+use planner_app::{BoardPreview, BoardPreviewComponent, Outline, PcbSide, PcbUnitOverlay};
+
+pub const BOARD_THICKNESS_MM: f64 = 1.6;
+
+/// Used for components without package dimensions, (x, y, z) in millimeters.
+const DEFAULT_BODY_SIZE_MM: (f64, f64, f64) = (1.0, 0.5, 0.5);
+
+const PREVIEW_MARGIN: f32 = 10.0;
+
+const BOARD_COLOR: Color32 = Color32::from_rgb(0, 110, 40);
+const PLACED_COMPONENT_COLOR: Color32 = Color32::from_rgb(60, 60, 60);
+const PENDING_COMPONENT_COLOR: Color32 = Color32::from_rgb(150, 150, 160);
+const CUTOUT_COLOR: Color32 = Color32::from_rgb(230, 200, 0);
+const UNIT_LABEL_COLOR: Color32 = Color32::from_rgb(240, 240, 240);
+const UNIT_LABEL_FONT_SIZE: f32 = 14.0;
+
+/// cos(30°) and sin(30°)
+const ISOMETRIC_COS: f64 = 0.866_025_403_784_438_6;
+const ISOMETRIC_SIN: f64 = 0.5;
+
+#[derive(Derivative)]
+#[derivative(Debug, Default)]
+pub struct BoardPreviewUi {
+    #[derivative(Default(value = "PcbSide::Top"))]
+    viewed_from: PcbSide,
+    faces: Vec<Face>,
+    /// The faces of the board are first, followed by the faces of the components.
+    board_faces: usize,
+    /// Closed contours of the profile other than the board's outline, e.g. cutouts, drawn on the top face.
+    cutouts: Vec<Vec<[f64; 2]>>,
+    /// Of the projected faces, (min, max).
+    bounds: Option<([f64; 2], [f64; 2])>,
+
+    unit_overlay: Option<PcbUnitOverlay>,
+    /// Projected unit centers and labels, drawn on top of everything else.
+    unit_labels: Vec<([f64; 2], String)>,
+}
+
+/// A projected face, the y axis is up.
+#[derive(Debug, Clone, PartialEq)]
+struct Face {
+    points: Vec<[f64; 2]>,
+    color: Color32,
+    /// Triangle indices into `points`, for faces which may be concave, otherwise the face is convex.
+    triangles: Option<Vec<u32>>,
+}
+
+impl BoardPreviewUi {
+    pub fn viewed_from(&self) -> PcbSide {
+        self.viewed_from
+    }
+
+    /// Rebuilds the faces, call when the preview or the side it's viewed from changes.
+    pub fn update(&mut self, board_preview: &BoardPreview, viewed_from: PcbSide) {
+        self.viewed_from = viewed_from;
+        self.update_unit_labels();
+
+        self.faces.clear();
+        self.board_faces = 0;
+        self.cutouts.clear();
+
+        let mut contours = closed_contours(&board_preview.outline);
+        // the largest contour is the board, the others are cutouts or features within the board
+        contours.sort_by(|a, b| {
+            signed_area(b)
+                .abs()
+                .total_cmp(&signed_area(a).abs())
+        });
+
+        let board_top = match viewed_from {
+            PcbSide::Top => 0.0,
+            PcbSide::Bottom => BOARD_THICKNESS_MM,
+        };
+
+        if let Some(board) = contours.first() {
+            let board = board
+                .iter()
+                .map(|point| view_point(*point, viewed_from))
+                .collect::<Vec<_>>();
+            self.faces.extend(prism_faces(
+                &board,
+                board_top - BOARD_THICKNESS_MM,
+                board_top,
+                BOARD_COLOR,
+                true,
+            ));
+            self.board_faces = self.faces.len();
+
+            self.cutouts = contours
+                .iter()
+                .skip(1)
+                .map(|contour| {
+                    contour
+                        .iter()
+                        .map(|point| project(view_point3(*point, 0.0, viewed_from)))
+                        .collect()
+                })
+                .collect();
+        }
+
+        let mut components = board_preview
+            .components
+            .iter()
+            .filter(|component| component.pcb_side == viewed_from)
+            .map(|component| {
+                let (footprint, height) = component_footprint(component);
+                let footprint = footprint
+                    .iter()
+                    .map(|point| view_point(*point, viewed_from))
+                    .collect::<Vec<_>>();
+                let color = if component.placed {
+                    PLACED_COMPONENT_COLOR
+                } else {
+                    PENDING_COMPONENT_COLOR
+                };
+                let center = footprint
+                    .iter()
+                    .fold([0.0, 0.0], |center, point| {
+                        [
+                            center[0] + point[0] / footprint.len() as f64,
+                            center[1] + point[1] / footprint.len() as f64,
+                        ]
+                    });
+
+                (depth([center[0], center[1], board_top]), footprint, height, color)
+            })
+            .collect::<Vec<_>>();
+
+        components.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        for (_depth, footprint, height, color) in components {
+            self.faces
+                .extend(prism_faces(&footprint, board_top, board_top + height, color, false));
+        }
+
+        self.bounds = self
+            .faces
+            .iter()
+            .flat_map(|face| face.points.iter())
+            .fold(None, |bounds, point| {
+                Some(match bounds {
+                    None => (*point, *point),
+                    Some((min, max)) => ([min[0].min(point[0]), min[1].min(point[1])], [
+                        max[0].max(point[0]),
+                        max[1].max(point[1]),
+                    ]),
+                })
+            });
+    }
+
+    pub fn update_unit_overlay(&mut self, unit_overlay: PcbUnitOverlay) {
+        self.unit_overlay = Some(unit_overlay);
+        self.update_unit_labels();
+    }
+
+    fn update_unit_labels(&mut self) {
+        let viewed_from = self.viewed_from;
+        self.unit_labels = self
+            .unit_overlay
+            .iter()
+            .flat_map(|unit_overlay| unit_overlay.units.iter())
+            .map(|unit| {
+                let position = project(view_point3([unit.centroid.x, unit.centroid.y], 0.0, viewed_from));
+                let label = match &unit.variant_name {
+                    Some(variant_name) => format!("{} ({})", unit.unit_number, variant_name),
+                    None => unit.unit_number.to_string(),
+                };
+                (position, label)
+            })
+            .collect();
+    }
+
+    pub fn ui(&self, ui: &mut Ui, height: f32) {
+        let (response, painter) = ui.allocate_painter(Vec2::new(ui.available_width(), height), Sense::hover());
+        let rect = response.rect;
+
+        let Some((min, max)) = self.bounds else {
+            return;
+        };
+
+        let size = [(max[0] - min[0]).max(f64::EPSILON), (max[1] - min[1]).max(f64::EPSILON)];
+        let available = rect.shrink(PREVIEW_MARGIN);
+        let scale = (available.width() as f64 / size[0]).min(available.height() as f64 / size[1]);
+        let center = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+
+        let to_screen = |point: &[f64; 2]| -> Pos2 {
+            Pos2::new(
+                rect.center().x + ((point[0] - center[0]) * scale) as f32,
+                rect.center().y - ((point[1] - center[1]) * scale) as f32,
+            )
+        };
+
+        let (board_faces, component_faces) = self.faces.split_at(self.board_faces);
+
+        // cutouts are drawn on the top of the board, before the components
+        let shapes = board_faces
+            .iter()
+            .map(|face| face_shape(face, &to_screen))
+            .chain(self.cutouts.iter().map(|cutout| {
+                Shape::closed_line(cutout.iter().map(to_screen).collect(), Stroke::new(1.0, CUTOUT_COLOR))
+            }))
+            .chain(
+                component_faces
+                    .iter()
+                    .map(|face| face_shape(face, &to_screen)),
+            )
+            .collect::<Vec<_>>();
+
+        painter.extend(shapes);
+
+        for (position, label) in &self.unit_labels {
+            painter.text(
+                to_screen(position),
+                Align2::CENTER_CENTER,
+                label,
+                FontId::proportional(UNIT_LABEL_FONT_SIZE),
+                UNIT_LABEL_COLOR,
+            );
+        }
+    }
+}
+
+fn face_shape(face: &Face, to_screen: &impl Fn(&[f64; 2]) -> Pos2) -> Shape {
+    let points = face
+        .points
+        .iter()
+        .map(to_screen)
+        .collect::<Vec<_>>();
+    let stroke = Stroke::new(1.0, shade(face.color, 0.6));
+
+    match &face.triangles {
+        Some(triangles) => {
+            let mut mesh = Mesh::default();
+            for point in &points {
+                mesh.colored_vertex(*point, face.color);
+            }
+            mesh.indices = triangles.clone();
+
+            Shape::Vec(vec![Shape::mesh(mesh), Shape::closed_line(points, stroke)])
+        }
+        None => Shape::convex_polygon(points, face.color, stroke),
+    }
+}
+
+/// The corners of the body of the component, rotated about its position, and the height of the body.
+fn component_footprint(component: &BoardPreviewComponent) -> ([[f64; 2]; 4], f64) {
+    let (size_x, size_y, size_z) = component
+        .dimensions
+        .as_ref()
+        .and_then(|dimensions| {
+            Some((
+                dimensions.size_x().to_f64()?,
+                dimensions.size_y().to_f64()?,
+                dimensions.size_z().to_f64()?,
+            ))
+        })
+        .unwrap_or(DEFAULT_BODY_SIZE_MM);
+
+    let x = component.x.to_f64().unwrap_or_default();
+    let y = component.y.to_f64().unwrap_or_default();
+    let (sin, cos) = component
+        .rotation
+        .to_f64()
+        .unwrap_or_default()
+        .to_radians()
+        .sin_cos();
+
+    let corner = |dx: f64, dy: f64| [x + dx * cos - dy * sin, y + dx * sin + dy * cos];
+
+    (
+        [
+            corner(-size_x / 2.0, -size_y / 2.0),
+            corner(size_x / 2.0, -size_y / 2.0),
+            corner(size_x / 2.0, size_y / 2.0),
+            corner(-size_x / 2.0, size_y / 2.0),
+        ],
+        size_z,
+    )
+}
+
+fn closed_contours(outline: &Outline) -> Vec<Vec<[f64; 2]>> {
+    let contours = outline
+        .contours
+        .iter()
+        .filter(|contour| contour.closed && contour.vertices.len() >= 3)
+        .map(|contour| {
+            contour
+                .vertices
+                .iter()
+                .map(|(x, y)| [*x, *y])
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    if !contours.is_empty() {
+        return contours;
+    }
+
+    // the profile could not be joined into a closed contour, use its bounds instead
+    outline
+        .bounds()
+        .map(|bounds| {
+            vec![vec![
+                [bounds.min.0, bounds.min.1],
+                [bounds.max.0, bounds.min.1],
+                [bounds.max.0, bounds.max.1],
+                [bounds.min.0, bounds.max.1],
+            ]]
+        })
+        .unwrap_or_default()
+}
+
+/// When viewed from the bottom the board is flipped over the Y axis.
+fn view_point(point: [f64; 2], viewed_from: PcbSide) -> [f64; 2] {
+    match viewed_from {
+        PcbSide::Top => point,
+        PcbSide::Bottom => [-point[0], point[1]],
+    }
+}
+
+/// As [`view_point`], `z` is the height above the top of the board in the view.
+fn view_point3(point: [f64; 2], z: f64, viewed_from: PcbSide) -> [f64; 3] {
+    let [x, y] = view_point(point, viewed_from);
+    let board_top = match viewed_from {
+        PcbSide::Top => 0.0,
+        PcbSide::Bottom => BOARD_THICKNESS_MM,
+    };
+    [x, y, board_top + z]
+}
+
+/// The viewer is above the board, looking from the front-right, i.e. from +X, -Y, +Z.
+fn project(point: [f64; 3]) -> [f64; 2] {
+    let [x, y, z] = point;
+    [(x + y) * ISOMETRIC_COS, (y - x) * ISOMETRIC_SIN + z]
+}
+
+/// Larger values are nearer the viewer.
+fn depth(point: [f64; 3]) -> f64 {
+    let [x, y, z] = point;
+    x - y + z
+}
+
+/// Positive for anti-clockwise polygons.
+fn signed_area(points: &[[f64; 2]]) -> f64 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a[0] * b[1] - b[0] * a[1])
+        .sum::<f64>()
+        / 2.0
+}
+
+/// The faces of the polygon extruded from `bottom` to `top` which face the viewer, furthest first.
+///
+/// The top face is triangulated when `concave` is `true`, otherwise the polygon must be convex.
+fn prism_faces(polygon: &[[f64; 2]], bottom: f64, top: f64, color: Color32, concave: bool) -> Vec<Face> {
+    // the outward normal of an edge of an anti-clockwise polygon is on the right of the edge
+    let orientation = signed_area(polygon).signum();
+
+    let mut sides = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .filter_map(|(a, b)| {
+            let normal = [(b[1] - a[1]) * orientation, -(b[0] - a[0]) * orientation];
+            if !faces_viewer(normal) {
+                return None;
+            }
+
+            let face = Face {
+                points: vec![
+                    project([a[0], a[1], bottom]),
+                    project([b[0], b[1], bottom]),
+                    project([b[0], b[1], top]),
+                    project([a[0], a[1], top]),
+                ],
+                color: shade(color, side_shading(normal)),
+                triangles: None,
+            };
+            let middle = depth([(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0, bottom]);
+
+            Some((middle, face))
+        })
+        .collect::<Vec<_>>();
+
+    sides.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let top_face = Face {
+        points: polygon
+            .iter()
+            .map(|point| project([point[0], point[1], top]))
+            .collect(),
+        color,
+        triangles: concave.then(|| {
+            triangulate(polygon)
+                .into_iter()
+                .flatten()
+                .map(|index| index as u32)
+                .collect()
+        }),
+    };
+
+    sides
+        .into_iter()
+        .map(|(_depth, face)| face)
+        .chain(std::iter::once(top_face))
+        .collect()
+}
+
+/// The viewer is towards +X, -Y.
+fn faces_viewer(normal: [f64; 2]) -> bool {
+    normal[0] - normal[1] > 0.0
+}
+
+/// Faces towards +X are lighter than faces towards -Y.
+fn side_shading(normal: [f64; 2]) -> f32 {
+    let length = (normal[0] * normal[0] + normal[1] * normal[1]).sqrt();
+    if length == 0.0 {
+        return 0.7;
+    }
+    0.7 + 0.15 * (normal[0] / length) as f32
+}
+
+fn shade(color: Color32, factor: f32) -> Color32 {
+    let channel = |value: u8| (value as f32 * factor).clamp(0.0, 255.0) as u8;
+    Color32::from_rgb(channel(color.r()), channel(color.g()), channel(color.b()))
+}
+
+/// Ear-clipping triangulation of a simple polygon, of either winding.
+fn triangulate(polygon: &[[f64; 2]]) -> Vec<[usize; 3]> {
+    let orientation = signed_area(polygon).signum();
+    let mut remaining = (0..polygon.len()).collect::<Vec<_>>();
+    let mut triangles = Vec::with_capacity(polygon.len().saturating_sub(2));
+
+    let cross = |a: [f64; 2], b: [f64; 2], c: [f64; 2]| (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&index| {
+            let previous = polygon[remaining[(index + count - 1) % count]];
+            let current = polygon[remaining[index]];
+            let next = polygon[remaining[(index + 1) % count]];
+
+            // the ear must be convex, and no other vertex may be inside it
+            cross(previous, current, next) * orientation > 0.0
+                && !remaining.iter().any(|&other| {
+                    let point = polygon[other];
+                    point != previous
+                        && point != current
+                        && point != next
+                        && cross(previous, current, point) * orientation >= 0.0
+                        && cross(current, next, point) * orientation >= 0.0
+                        && cross(next, previous, point) * orientation >= 0.0
+                })
+        });
+
+        // degenerate polygons, e.g. with collinear or self-intersecting edges, have no ears, clip any vertex
+        let index = ear.unwrap_or(0);
+        triangles.push([
+            remaining[(index + count - 1) % count],
+            remaining[index],
+            remaining[(index + 1) % count],
+        ]);
+        remaining.remove(index);
+    }
+
+    if remaining.len() == 3 {
+        triangles.push([remaining[0], remaining[1], remaining[2]]);
+    }
+
+    triangles
+}
+
+#[cfg(test)]
+mod board_preview_ui_tests {
+    use super::*;
+
+    #[test]
+    pub fn triangulate_concave_polygon() {
+        // given
+        // an 'L' shape, clockwise
+        let polygon = [[0.0, 0.0], [0.0, 2.0], [1.0, 2.0], [1.0, 1.0], [2.0, 1.0], [2.0, 0.0]];
+
+        // when
+        let triangles = triangulate(&polygon);
+
+        // then
+        assert_eq!(triangles.len(), 4);
+        let area = triangles
+            .iter()
+            .map(|[a, b, c]| signed_area(&[polygon[*a], polygon[*b], polygon[*c]]).abs())
+            .sum::<f64>();
+        assert_eq!(area, 3.0);
+    }
+
+    #[test]
+    pub fn only_faces_towards_the_viewer_are_included() {
+        // given
+        let square = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+
+        // when
+        let faces = prism_faces(&square, 0.0, 1.0, BOARD_COLOR, false);
+
+        // then
+        // the front (-Y) and right (+X) sides, and the top
+        assert_eq!(faces.len(), 3);
+        assert_eq!(faces[2].color, BOARD_COLOR);
+        assert!(
+            faces
+                .iter()
+                .all(|face| face.points.len() == 4)
+        );
+    }
+}
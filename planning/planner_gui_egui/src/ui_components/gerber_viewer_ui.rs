@@ -22,9 +22,10 @@
 use indexmap::map::Entry;
 use nalgebra::{Point2, Vector2};
 use planner_app::{
-    DesignIndex, GerberFileFunction, PanelSizing, PcbAssemblyOrientation, PcbOverview, PcbSide, PcbUnitIndex,
-    PlacementPositionUnit,
+    DesignIndex, GerberFileFunction, LayerPreset, PanelPreview, PanelSizing, PcbAssemblyOrientation, PcbOverview,
+    PcbSide, PcbUnitIndex, PlacementPositionUnit, gerber_layers,
 };
+use rust_decimal::prelude::ToPrimitive;
 use thiserror::Error;
 use tracing::{debug, error, info, trace};
 
@@ -84,6 +85,8 @@ pub struct GerberViewerUi {
     assembly_orientation: Option<PcbAssemblyOrientation>,
     panel_sizing: Option<PanelSizing>,
     unit_map: Option<HashMap<PcbUnitIndex, DesignIndex>>,
+    /// The last applied preset, see [`Self::apply_layer_preset`].
+    layer_preset: Option<LayerPreset>,
 
     placement_marker: Option<PlacementPositionUnit>,
 
@@ -103,6 +106,7 @@ pub fn new(args: GerberViewerUiInstanceArgs) -> Self {
             assembly_orientation: None,
             panel_sizing: None,
             unit_map: None,
+            layer_preset: None,
 
             placement_marker: None,
 
@@ -143,11 +147,31 @@ pub fn set_unit_map(&mut self, new_unit_map: HashMap<PcbUnitIndex, DesignIndex>)
     pub fn add_layer(&mut self, function: Option<GerberFileFunction>, commands: Vec<Command>) {
         let mut gerber_state = self.gerber_state.lock().unwrap();
 
-        let (state, layer) = Self::build_gerber_layer_from_commands(0, commands);
+        let (state, layer) = Self::build_gerber_layer_from_commands(0, function.as_ref(), commands);
 
         gerber_state.add_layer(None, function, state, layer, None);
     }
 
+    /// Orders the layers for viewing from the side of the preset, shows the layers included in the preset and hides
+    /// the others.
+    ///
+    /// Layers for the other side, when a PCB side is specified, are always hidden, see [`Self::is_included`].
+    pub fn apply_layer_preset(&mut self, preset: LayerPreset) {
+        self.layer_preset = Some(preset);
+
+        let mut gerber_state = self.gerber_state.lock().unwrap();
+        {
+            let mut layers = gerber_state.layers.lock().unwrap();
+            sort_layers_for_viewing(&mut layers, preset.viewed_from());
+
+            for ((_path, function), (layer_view_state, _layer, _gerber_doc)) in layers.iter_mut() {
+                layer_view_state.visible = preset.includes(function.as_ref()) && self.is_included(function.as_ref());
+                layer_view_state.transform.mirroring.x = preset.viewed_from() == PcbSide::Bottom;
+            }
+        }
+        gerber_state.request_center_view();
+    }
+
     pub fn request_center_view(&mut self) {
         let mut gerber_state = self.gerber_state.lock().unwrap();
         gerber_state.request_center_view();
@@ -171,28 +195,12 @@ pub fn update_layers_from_pcb_overview(&mut self, pcb_overview: PcbOverview) {
 
         let gerber_items = gerber_items
             .iter()
-            .filter(|gerber| match self.args.pcb_side {
-                // if no PCB side filter is specified, include this item
-                None => true,
-                Some(pcb_side) => {
-                    if let Some(function) = gerber.function {
-                        let gerber_side = function.pcb_side();
-                        match gerber_side {
-                            // if the item has no side, include it.
-                            None => true,
-                            // if a PCB side is specified, include this item if it's side matches
-                            Some(gerber_side) => gerber_side == pcb_side,
-                        }
-                    } else {
-                        // if there is no function, exclude it
-                        false
-                    }
-                }
-            })
+            .filter(|gerber| self.is_included(gerber.function.as_ref()))
             .collect::<Vec<_>>();
 
         // The new list of gerber items may contain fewer, more or different entries and/or the same entries in a different
-        // order.  Only to reparse files that need reparsing, use the layer order defined by the gerber items collection.
+        // order.  Only to reparse files that need reparsing, then order the layers by function, see
+        // `sort_layers_for_viewing`.
 
         let mut gerber_state = self.gerber_state.lock().unwrap();
 
@@ -208,8 +216,8 @@ pub fn update_layers_from_pcb_overview(&mut self, pcb_overview: PcbOverview) {
             let errors = sync_indexmap(
                 &mut layers,
                 &gerber_items,
-                |index, (path, _name), _content| {
-                    Self::build_gerber_layer_from_file(index, path.as_ref().unwrap()).map(
+                |index, (path, function), _content| {
+                    Self::build_gerber_layer_from_file(index, function.as_ref(), path.as_ref().unwrap()).map(
                         |(mut layer_view_state, layer, gerber_doc)| {
                             if matches!(self.args.pcb_side, Some(PcbSide::Bottom)) {
                                 layer_view_state.transform.mirroring.x = true
@@ -230,6 +238,13 @@ pub fn update_layers_from_pcb_overview(&mut self, pcb_overview: PcbOverview) {
                 );
             }
 
+            sort_layers_for_viewing(
+                &mut layers,
+                self.args
+                    .pcb_side
+                    .unwrap_or(PcbSide::Top),
+            );
+
             gerber_state.update_layers(layers);
             gerber_state.request_center_view();
         }
@@ -280,18 +295,117 @@ pub fn sync_indexmap<K, T, S, E, FBuild, FKey, FReuse>(
         }
     }
 
+    /// Loads the PCB's gerbers and the gerbers of the design assigned to each unit of the panel.
+    ///
+    /// Each gerber file is only parsed once, a design's gerbers are drawn once for each unit the design is assigned to.
+    pub fn update_layers_from_panel_preview(&mut self, panel_preview: PanelPreview) {
+        let mut instances = panel_preview_instances(&panel_preview);
+
+        instances.retain(|(_path, function), _| self.is_included(function.as_ref()));
+
+        let mut gerber_state = self.gerber_state.lock().unwrap();
+
+        let mut existing_layers = gerber_state
+            .layers
+            .lock()
+            .unwrap()
+            .split_off(0);
+
+        let mut layers = LayersMap::with_capacity(instances.len());
+        for ((path, function), placements) in instances {
+            let key = (Some(path.clone()), function);
+
+            let entry = match existing_layers.shift_remove(&key) {
+                Some(entry) => Ok(entry),
+                None => Self::build_gerber_layer_from_file(layers.len(), function.as_ref(), &path).map(
+                    |(mut layer_view_state, layer, gerber_doc)| {
+                        if matches!(self.args.pcb_side, Some(PcbSide::Bottom)) {
+                            layer_view_state.transform.mirroring.x = true
+                        }
+
+                        (layer_view_state, layer, Some(gerber_doc))
+                    },
+                ),
+            };
+
+            match entry {
+                Ok((mut layer_view_state, layer, gerber_doc)) => {
+                    // the panel preview uses millimeters, the instances must use the units of the gerber file.
+                    let units = gerber_doc
+                        .as_ref()
+                        .and_then(|doc| doc.units);
+                    let doc_unit_system = UnitSystem::from_gerber_unit(&units);
+
+                    layer_view_state.instances = placements
+                        .iter()
+                        .map(|placement| placement.to_transform(doc_unit_system))
+                        .collect();
+
+                    layers.insert(key, (layer_view_state, layer, gerber_doc));
+                }
+                Err(error) => {
+                    error!("Error adding gerber layer. path: {:?}, error: {}", path, error);
+                }
+            }
+        }
+
+        sort_layers_for_viewing(
+            &mut layers,
+            self.args
+                .pcb_side
+                .unwrap_or(PcbSide::Top),
+        );
+
+        gerber_state.update_layers(layers);
+        gerber_state.request_center_view();
+    }
+
+    /// Gerbers without a function are only included when no PCB side is specified.
+    fn is_included(&self, function: Option<&GerberFileFunction>) -> bool {
+        match self.args.pcb_side {
+            // if no PCB side filter is specified, include this item
+            None => true,
+            Some(pcb_side) => {
+                if let Some(function) = function {
+                    let gerber_side = function.pcb_side();
+                    match gerber_side {
+                        // if the item has no side, include it.
+                        None => true,
+                        // if a PCB side is specified, include this item if it's side matches
+                        Some(gerber_side) => gerber_side == pcb_side,
+                    }
+                } else {
+                    // if there is no function, exclude it
+                    false
+                }
+            }
+        }
+    }
+
     fn build_gerber_layer_from_file(
         index: usize,
+        function: Option<&GerberFileFunction>,
         path: &PathBuf,
     ) -> Result<(LayerViewState, GerberLayer, GerberDoc), GerberViewerUiError> {
         let (gerber_doc, commands) = Self::parse_gerber(path)?;
-        let (state, layer) = Self::build_gerber_layer_from_commands(index, commands);
+        let (state, layer) = Self::build_gerber_layer_from_commands(index, function, commands);
 
         Ok((state, layer, gerber_doc))
     }
 
-    fn build_gerber_layer_from_commands(index: usize, commands: Vec<Command>) -> (LayerViewState, GerberLayer) {
-        let color = generate_pastel_color(index as u64);
+    /// Layers without a function use a generated color.
+    fn build_gerber_layer_from_commands(
+        index: usize,
+        function: Option<&GerberFileFunction>,
+        commands: Vec<Command>,
+    ) -> (LayerViewState, GerberLayer) {
+        let color = match function {
+            Some(function) => {
+                let color = gerber_layers::default_color(function);
+                Color32::from_rgba_unmultiplied(color.r, color.g, color.b, color.a)
+            }
+            None => generate_pastel_color(index as u64),
+        };
 
         let layer = GerberLayer::new(commands);
         let layer_view_state = LayerViewState::new(color);
@@ -333,9 +447,19 @@ fn parse_gerber(
         Ok((gerber_doc, commands))
     }
 
-    /// X and Y are in dimension units.
+    /// The side of the PCB the layers are viewed from, layers viewed from the bottom are mirrored.
+    pub fn viewed_from(&self) -> PcbSide {
+        self.layer_preset
+            .map(|preset| preset.viewed_from())
+            .or(self.args.pcb_side)
+            .unwrap_or(PcbSide::Top)
+    }
+
+    /// X and Y are in dimension units, as viewed from the top.
+    ///
+    /// If the view is about to be centered, e.g. after applying a layer preset, the point is located afterwards.
     pub fn locate_view(&mut self, mut point: Point2<DimensionUnit>) {
-        if matches!(self.args.pcb_side, Some(PcbSide::Bottom)) {
+        if self.viewed_from() == PcbSide::Bottom {
             point.x = -point.x;
         }
 
@@ -343,6 +467,10 @@ pub fn locate_view(&mut self, mut point: Point2<DimensionUnit>) {
         let center_screen_pos = ui_state.center_screen_pos;
 
         let mut gerber_state = self.gerber_state.lock().unwrap();
+        if gerber_state.needs_view_centering {
+            gerber_state.pending_locate = Some(point);
+            return;
+        }
         gerber_state.locate_view(point, center_screen_pos);
     }
 
@@ -360,6 +488,8 @@ pub fn clear_placement_marker(&mut self) {
 struct GerberViewState {
     view: ViewState,
     needs_view_centering: bool,
+    /// Located after the view has been centered, see [`GerberViewerUi::locate_view`].
+    pending_locate: Option<Point2<DimensionUnit>>,
     needs_bbox_update: bool,
     bounding_box: BoundingBox,
     render_configuration: RenderConfiguration,
@@ -373,6 +503,7 @@ fn default() -> Self {
         Self {
             view: Default::default(),
             needs_view_centering: true,
+            pending_locate: None,
             needs_bbox_update: true,
             bounding_box: BoundingBox::default(),
             render_configuration: RenderConfiguration::default(),
@@ -438,19 +569,17 @@ fn update_bbox_from_layers(&mut self) {
             .enumerate()
             .filter(|(_, (_path, (_, layer, _)))| !layer.is_empty())
         {
-            let layer_bbox = &layer.bounding_box();
-
-            let layer_transform = layer_view_state
-                .transform
-                .combine(&self.transform);
-
-            let layer_bbox = layer_bbox.apply_transform(&layer_transform);
-
-            debug!("layer bbox: {:?}", layer_bbox);
-            bbox.min.x = f64::min(bbox.min.x, layer_bbox.min.x);
-            bbox.min.y = f64::min(bbox.min.y, layer_bbox.min.y);
-            bbox.max.x = f64::max(bbox.max.x, layer_bbox.max.x);
-            bbox.max.y = f64::max(bbox.max.y, layer_bbox.max.y);
+            for layer_transform in layer_view_state.combined_transforms(&self.transform) {
+                let layer_bbox = layer
+                    .bounding_box()
+                    .apply_transform(&layer_transform);
+
+                debug!("layer bbox: {:?}", layer_bbox);
+                bbox.min.x = f64::min(bbox.min.x, layer_bbox.min.x);
+                bbox.min.y = f64::min(bbox.min.y, layer_bbox.min.y);
+                bbox.max.x = f64::max(bbox.max.x, layer_bbox.max.x);
+                bbox.max.y = f64::max(bbox.max.y, layer_bbox.max.y);
+            }
             debug!("view bbox after layer. layer: {}, bbox: {:?}", layer_index, bbox);
         }
 
@@ -482,6 +611,14 @@ fn locate_view(&mut self, point: Point2<DimensionUnit>, center_screen_pos: Pos2)
         );
         trace!("view translation (after): {:?}", self.view.translation);
     }
+
+    /// The inverse of the translation and scale used by [`Self::locate_view`], gerber coords are positive up.
+    fn screen_to_gerber_coords(&self, screen_position: Pos2) -> Point2<DimensionUnit> {
+        let x = (screen_position.x - self.view.translation.x) / self.view.scale;
+        let y = (self.view.translation.y - screen_position.y) / self.view.scale;
+
+        Point2::new(x as f64, y as f64).to_dimension_unit(self.target_unit_system)
+    }
 }
 
 #[derive(Error, Debug)]
@@ -497,6 +634,11 @@ enum GerberViewerUiError {
 pub struct LayerViewState {
     color: Color32,
     transform: GerberTransform,
+    visible: bool,
+    /// When not empty, the layer is drawn once for each transform, e.g. a design's gerbers for each unit of a panel.
+    ///
+    /// Each instance transform is applied before the layer's transform.
+    instances: Vec<GerberTransform>,
 }
 
 impl LayerViewState {
@@ -504,8 +646,99 @@ fn new(color: Color32) -> Self {
         Self {
             color,
             transform: GerberTransform::default(),
+            visible: true,
+            instances: vec![],
         }
     }
+
+    /// One transform for each instance, or just the layer's transform if there are no instances.
+    fn combined_transforms(&self, view_transform: &GerberTransform) -> Vec<GerberTransform> {
+        let layer_transform = self.transform.combine(view_transform);
+
+        if self.instances.is_empty() {
+            return vec![layer_transform];
+        }
+
+        self.instances
+            .iter()
+            .map(|instance| instance.combine(&layer_transform))
+            .collect()
+    }
+}
+
+/// The instances of each gerber file of the panel, in the order of the PCB's gerbers, then the gerbers of the units.
+fn panel_preview_instances(
+    panel_preview: &PanelPreview,
+) -> IndexMap<(PathBuf, Option<GerberFileFunction>), Vec<InstancePlacement>> {
+    let mut instances: IndexMap<(PathBuf, Option<GerberFileFunction>), Vec<InstancePlacement>> = IndexMap::new();
+
+    for gerber in panel_preview.pcb_gerbers.iter() {
+        instances
+            .entry((gerber.path.clone(), gerber.function))
+            .or_default()
+            .push(InstancePlacement {
+                rotation: 0.0,
+                pivot: Vector2::zeros(),
+                offset: panel_preview.gerber_offset,
+            });
+    }
+
+    for unit in panel_preview.units.iter() {
+        let rotation = unit
+            .rotation
+            .to_f32()
+            .unwrap_or_default()
+            .to_radians();
+
+        for gerber in unit.gerbers.iter() {
+            instances
+                .entry((gerber.path.clone(), gerber.function))
+                .or_default()
+                .push(InstancePlacement {
+                    rotation,
+                    pivot: unit.pivot,
+                    offset: unit.offset,
+                });
+        }
+    }
+
+    instances
+}
+
+/// The placement of one instance of a layer, in millimeters.
+#[derive(Debug, Clone, PartialEq)]
+struct InstancePlacement {
+    /// Anti-clockwise positive radians, about the `pivot`.
+    rotation: f32,
+    pivot: Vector2<f64>,
+    offset: Vector2<f64>,
+}
+
+impl InstancePlacement {
+    fn to_transform(&self, unit_system: UnitSystem) -> GerberTransform {
+        let convert = |vector: Vector2<f64>| {
+            vector
+                .to_position()
+                .to_dimension_unit(UnitSystem::Millimeters)
+                .to_point2(unit_system)
+                .coords
+        };
+
+        GerberTransform {
+            rotation: self.rotation,
+            origin: convert(self.pivot),
+            offset: convert(self.offset),
+            ..GerberTransform::default()
+        }
+    }
+}
+
+/// Layers furthest from the viewer are drawn first, see [`gerber_layers::viewing_order`].
+fn sort_layers_for_viewing(layers: &mut LayersMap, viewed_from: PcbSide) {
+    layers.sort_by(|(_, function_a), _, (_, function_b), _| {
+        gerber_layers::viewing_order(function_a.as_ref(), viewed_from)
+            .cmp(&gerber_layers::viewing_order(function_b.as_ref(), viewed_from))
+    });
 }
 
 #[derive(Debug, Clone)]
@@ -513,11 +746,18 @@ pub enum GerberViewerUiCommand {
     None,
     LocateView(Point2<DimensionUnit>),
     ShowPlacementMarker(PlacementPositionUnit),
+    /// In gerber coordinates, as viewed.
+    PointClicked(Point2<DimensionUnit>),
 }
 
 #[derive(Debug, Clone)]
 pub enum GerberViewerUiAction {
     None,
+    /// `position` is in millimeters, as viewed from the top, regardless of the side the layers are viewed from.
+    PointClicked {
+        position: Vector2<f64>,
+        pcb_side: PcbSide,
+    },
 }
 
 #[derive(Debug, Clone, Default)]
@@ -532,7 +772,7 @@ impl UiComponent for GerberViewerUi {
     fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
         let mut state = self.gerber_state.lock().unwrap();
 
-        let response = ui.allocate_rect(ui.available_rect_before_wrap(), egui::Sense::drag());
+        let response = ui.allocate_rect(ui.available_rect_before_wrap(), egui::Sense::click_and_drag());
         let viewport = response.rect;
 
         if state.needs_bbox_update {
@@ -546,16 +786,38 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
         let mut ui_state = self.gerber_ui_state.lock().unwrap();
         ui_state.update(ui, &viewport, &response, &mut state.view);
 
+        if let Some(point) = state.pending_locate.take() {
+            state.locate_view(point, ui_state.center_screen_pos);
+        }
+
+        if response.clicked() {
+            if let Some(screen_position) = response.interact_pointer_pos() {
+                self.component
+                    .send(GerberViewerUiCommand::PointClicked(
+                        state.screen_to_gerber_coords(screen_position),
+                    ));
+            }
+        }
+
         let painter = ui.painter().with_clip_rect(viewport);
 
         let mut request_draw_unit_unit_numbers = matches!(self.args.mode, GerberViewerMode::Panel);
         let mut request_draw_placement_marker = self.placement_marker.is_some();
 
         let layers = state.layers.lock().unwrap();
-        let layer_count = layers.len();
-        for (index, (_path, (layer_view_state, layer, doc))) in layers.iter().enumerate() {
+        let visible_layers = layers
+            .iter()
+            .filter(|(_key, (layer_view_state, _layer, _doc))| layer_view_state.visible)
+            .collect::<Vec<_>>();
+        let layer_count = visible_layers.len();
+        for (index, (_path, (layer_view_state, layer, doc))) in visible_layers.into_iter().enumerate() {
             let is_last_layer = index == layer_count - 1;
 
+            for instance_transform in layer_view_state.combined_transforms(&state.transform) {
+                let renderer = GerberRenderer::new(&state.render_configuration, state.view, &instance_transform, layer);
+                renderer.paint_layer(&painter, layer_view_state.color);
+            }
+
             let layer_transform = layer_view_state
                 .transform
                 .combine(&state.transform);
@@ -563,8 +825,8 @@ fn ui<'context>(&self, ui: &mut Ui, _context: &mut Self::UiContext<'context>) {
             let units = doc.as_ref().and_then(|doc| doc.units);
             let doc_unit_system = UnitSystem::from_gerber_unit(&units);
 
+            // the overlays are drawn relative to the layer, not its instances
             let renderer = GerberRenderer::new(&state.render_configuration, state.view, &layer_transform, layer);
-            renderer.paint_layer(&painter, layer_view_state.color);
 
             if is_last_layer {
                 // draw on top of the last layer
@@ -673,6 +935,145 @@ fn update<'context>(
 
                 None
             }
+            GerberViewerUiCommand::PointClicked(point) => {
+                let mut position = point.to_point2(UnitSystem::Millimeters);
+
+                let pcb_side = self.viewed_from();
+                if pcb_side == PcbSide::Bottom {
+                    position.x = -position.x;
+                }
+
+                Some(GerberViewerUiAction::PointClicked {
+                    position: position.coords,
+                    pcb_side,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod gerber_viewer_ui_tests {
+    use planner_app::{Dimensions, PanelPreviewUnit, PcbGerberItem};
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    fn build_gerber(path: &str, function: GerberFileFunction) -> PcbGerberItem {
+        PcbGerberItem {
+            path: PathBuf::from(path),
+            function: Some(function),
+        }
+    }
+
+    #[test]
+    pub fn design_gerbers_have_an_instance_for_each_unit() {
+        // given
+        let design_gerbers = vec![
+            build_gerber("design_a/top_copper.gbr", GerberFileFunction::Copper(PcbSide::Top)),
+            build_gerber("design_a/outline.gbr", GerberFileFunction::Profile),
+        ];
+        let build_unit = |unit: PcbUnitIndex, rotation: Decimal, offset: Vector2<f64>| PanelPreviewUnit {
+            unit,
+            design: 0,
+            gerbers: design_gerbers.clone(),
+            rotation,
+            pivot: Vector2::new(5.0, 5.0),
+            offset,
+        };
+        let panel_preview = PanelPreview {
+            path: PathBuf::from("panel_a.pcb.json"),
+            size: Vector2::new(100.0, 50.0),
+            edge_rails: Dimensions::default(),
+            fiducials: vec![],
+            pcb_gerbers: vec![build_gerber("panel_a/rails.gbr", GerberFileFunction::Profile)],
+            gerber_offset: Vector2::new(-10.0, -5.0),
+            units: vec![
+                build_unit(0, Decimal::ZERO, Vector2::new(10.0, 10.0)),
+                build_unit(1, Decimal::from(90), Vector2::new(60.0, 10.0)),
+            ],
+        };
+
+        // when
+        let instances = panel_preview_instances(&panel_preview);
+
+        // then
+        let keys = instances
+            .keys()
+            .map(|(path, _function)| path.to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![
+            "panel_a/rails.gbr",
+            "design_a/top_copper.gbr",
+            "design_a/outline.gbr"
+        ]);
+
+        // and the pcb's gerbers are only offset
+        assert_eq!(instances[0], vec![InstancePlacement {
+            rotation: 0.0,
+            pivot: Vector2::zeros(),
+            offset: Vector2::new(-10.0, -5.0),
+        }]);
+
+        // and the design's gerbers are placed on each unit
+        assert_eq!(instances[1], vec![
+            InstancePlacement {
+                rotation: 0.0,
+                pivot: Vector2::new(5.0, 5.0),
+                offset: Vector2::new(10.0, 10.0),
+            },
+            InstancePlacement {
+                rotation: 90.0_f32.to_radians(),
+                pivot: Vector2::new(5.0, 5.0),
+                offset: Vector2::new(60.0, 10.0),
+            },
+        ]);
+        assert_eq!(instances[2], instances[1]);
+    }
+
+    #[test]
+    pub fn layer_presets_only_show_the_layers_for_the_pcb_side() {
+        // given
+        let mut gerber_viewer_ui = GerberViewerUi::new(GerberViewerUiInstanceArgs {
+            mode: GerberViewerMode::Panel,
+            pcb_side: Some(PcbSide::Top),
+        });
+        for function in [
+            GerberFileFunction::Copper(PcbSide::Top),
+            GerberFileFunction::Copper(PcbSide::Bottom),
+            GerberFileFunction::Profile,
+        ] {
+            gerber_viewer_ui.add_layer(Some(function), vec![]);
         }
+
+        // when
+        gerber_viewer_ui.apply_layer_preset(LayerPreset::All);
+
+        // then
+        let layers = gerber_viewer_ui.layers();
+        let visible = layers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|((_path, function), (layer_view_state, _layer, _gerber_doc))| {
+                (function.clone().unwrap(), layer_view_state.visible)
+            })
+            .collect::<Vec<_>>();
+        assert!(visible.contains(&(GerberFileFunction::Copper(PcbSide::Top), true)));
+        assert!(visible.contains(&(GerberFileFunction::Copper(PcbSide::Bottom), false)));
+        assert!(visible.contains(&(GerberFileFunction::Profile, true)));
+
+        // when
+        gerber_viewer_ui.apply_layer_preset(LayerPreset::BottomCopper);
+
+        // then only the profile is shown
+        let visible_functions = layers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_key, (layer_view_state, _layer, _gerber_doc))| layer_view_state.visible)
+            .map(|((_path, function), _value)| function.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(visible_functions, vec![Some(GerberFileFunction::Profile)]);
     }
 }
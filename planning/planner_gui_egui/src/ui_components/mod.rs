@@ -1 +1,3 @@
+pub mod board_preview_ui;
 pub mod gerber_viewer_ui;
+pub mod pcb_thumbnail_ui;
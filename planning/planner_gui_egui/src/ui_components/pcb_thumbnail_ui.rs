@@ -0,0 +1,196 @@
+//! A thumbnail of a PCB, rendered from the PCB's gerbers without a GPU, see [`gerber::render`].
+//!
+//! The thumbnail is rendered when the gerbers or the viewed side change, the texture is created when it's first shown.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use derivative::Derivative;
+use egui::{ColorImage, TextureHandle, TextureOptions, Ui};
+use egui_i18n::tr;
+use gerber::render::{Color, RenderLayer, RenderOptions, RenderSize, RenderTransform, render_bitmap};
+use planner_app::{GerberFileFunction, PcbGerberItem, PcbSide};
+
+pub const THUMBNAIL_SIZE: u32 = 160;
+
+const BOARD_COLOR: Color = Color::rgb(0, 70, 25);
+const COPPER_COLOR: Color = Color::rgba(200, 150, 60, 220);
+const LEGEND_COLOR: Color = Color::rgb(240, 240, 240);
+const PROFILE_COLOR: Color = Color::rgb(230, 200, 0);
+
+#[derive(Derivative, Default)]
+#[derivative(Debug)]
+pub struct PcbThumbnailUi {
+    /// The gerbers and the side of the rendered thumbnail, so it's only rendered again when they change.
+    rendered: Option<(Vec<PcbGerberItem>, PcbSide)>,
+    /// `Err` contains the reason the thumbnail could not be rendered.
+    #[derivative(Debug = "ignore")]
+    image: Option<Result<ColorImage, String>>,
+    #[derivative(Debug = "ignore")]
+    texture: Mutex<Option<TextureHandle>>,
+}
+
+impl PcbThumbnailUi {
+    pub fn update(&mut self, gerbers: &[PcbGerberItem], viewed_from: PcbSide) {
+        let unchanged = self
+            .rendered
+            .as_ref()
+            .is_some_and(|(rendered_gerbers, rendered_side)| {
+                rendered_gerbers.eq(gerbers) && *rendered_side == viewed_from
+            });
+        if unchanged {
+            return;
+        }
+
+        self.image = match thumbnail_layers(gerbers, viewed_from).is_empty() {
+            true => None,
+            false => Some(render_thumbnail(gerbers, viewed_from)),
+        };
+        *self.texture.lock().unwrap() = None;
+        self.rendered = Some((gerbers.to_vec(), viewed_from));
+    }
+
+    pub fn ui(&self, ui: &mut Ui) {
+        match &self.image {
+            None => {
+                ui.label(tr!("pcb-thumbnail-no-gerbers"));
+            }
+            Some(Err(reason)) => {
+                ui.label(tr!("pcb-thumbnail-error"))
+                    .on_hover_text(reason);
+            }
+            Some(Ok(image)) => {
+                let mut texture = self.texture.lock().unwrap();
+                let texture = texture.get_or_insert_with(|| {
+                    ui.ctx()
+                        .load_texture("pcb-thumbnail", image.clone(), TextureOptions::LINEAR)
+                });
+                ui.image((texture.id(), texture.size_vec2()));
+            }
+        }
+    }
+}
+
+/// The gerbers of the viewed side and the profile, in drawing order, with their colors.
+fn thumbnail_layers(gerbers: &[PcbGerberItem], viewed_from: PcbSide) -> Vec<(PathBuf, Color)> {
+    let mut layers = gerbers
+        .iter()
+        .filter_map(|gerber| {
+            let (order, color) = match gerber.function? {
+                GerberFileFunction::Copper(side) if side == viewed_from => (0, COPPER_COLOR),
+                GerberFileFunction::Legend(side) if side == viewed_from => (1, LEGEND_COLOR),
+                GerberFileFunction::Profile => (2, PROFILE_COLOR),
+                _ => return None,
+            };
+            Some((order, gerber.path.clone(), color))
+        })
+        .collect::<Vec<_>>();
+    layers.sort_by_key(|(order, _path, _color)| *order);
+
+    layers
+        .into_iter()
+        .map(|(_order, path, color)| (path, color))
+        .collect()
+}
+
+/// The bottom side is mirrored, as if the PCB was flipped over.
+fn render_thumbnail(gerbers: &[PcbGerberItem], viewed_from: PcbSide) -> Result<ColorImage, String> {
+    let layers = thumbnail_layers(gerbers, viewed_from)
+        .into_iter()
+        .map(|(path, color)| RenderLayer::from_file(&path, color))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| error.to_string())?;
+
+    let options = RenderOptions {
+        size: RenderSize::FitWithin {
+            width: THUMBNAIL_SIZE,
+            height: THUMBNAIL_SIZE,
+        },
+        background: Some(BOARD_COLOR),
+        transform: RenderTransform {
+            mirrored: viewed_from == PcbSide::Bottom,
+            ..RenderTransform::default()
+        },
+        ..RenderOptions::default()
+    };
+
+    let bitmap = render_bitmap(&layers, &options).map_err(|error| error.to_string())?;
+
+    Ok(ColorImage::from_rgba_unmultiplied(
+        [bitmap.width as usize, bitmap.height as usize],
+        &bitmap.pixels,
+    ))
+}
+
+#[cfg(test)]
+mod pcb_thumbnail_ui_tests {
+    use super::*;
+
+    fn build_gerber(path: &str, function: Option<GerberFileFunction>) -> PcbGerberItem {
+        PcbGerberItem {
+            path: PathBuf::from(path),
+            function,
+        }
+    }
+
+    #[test]
+    pub fn the_layers_of_the_viewed_side_are_drawn_in_order() {
+        // given
+        let gerbers = vec![
+            build_gerber("outline.gbr", Some(GerberFileFunction::Profile)),
+            build_gerber("top_legend.gbr", Some(GerberFileFunction::Legend(PcbSide::Top))),
+            build_gerber("bottom_copper.gbr", Some(GerberFileFunction::Copper(PcbSide::Bottom))),
+            build_gerber("top_copper.gbr", Some(GerberFileFunction::Copper(PcbSide::Top))),
+            build_gerber("top_paste.gbr", Some(GerberFileFunction::Paste(PcbSide::Top))),
+            build_gerber("unknown.gbr", None),
+        ];
+
+        // when
+        let layers = thumbnail_layers(&gerbers, PcbSide::Top);
+
+        // then
+        assert_eq!(layers, vec![
+            (PathBuf::from("top_copper.gbr"), COPPER_COLOR),
+            (PathBuf::from("top_legend.gbr"), LEGEND_COLOR),
+            (PathBuf::from("outline.gbr"), PROFILE_COLOR),
+        ]);
+    }
+
+    #[test]
+    pub fn a_pcb_without_gerbers_for_the_side_has_no_thumbnail() {
+        // given
+        let gerbers = vec![build_gerber(
+            "bottom_copper.gbr",
+            Some(GerberFileFunction::Copper(PcbSide::Bottom)),
+        )];
+        let mut thumbnail_ui = PcbThumbnailUi::default();
+
+        // when
+        thumbnail_ui.update(&gerbers, PcbSide::Top);
+
+        // then
+        assert!(thumbnail_ui.image.is_none());
+        assert!(thumbnail_ui.rendered.is_some());
+    }
+
+    #[test]
+    pub fn a_gerber_that_cannot_be_loaded_is_reported() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let gerbers = vec![build_gerber(
+            temp_dir
+                .path()
+                .join("missing.gbr")
+                .to_str()
+                .unwrap(),
+            Some(GerberFileFunction::Copper(PcbSide::Top)),
+        )];
+        let mut thumbnail_ui = PcbThumbnailUi::default();
+
+        // when
+        thumbnail_ui.update(&gerbers, PcbSide::Top);
+
+        // then
+        assert!(matches!(thumbnail_ui.image, Some(Err(_))));
+    }
+}
@@ -0,0 +1,106 @@
+//! Generates the planner core's serialized interface types (`Event`, the view models, `ProjectView`, `PcbView` and the
+//! effect operations) for non-rust shells, e.g. a web dashboard (TypeScript) or a tablet operator UI (Swift, or
+//! Java/Kotlin).
+//!
+//! Usage: `cargo run -p planner_shared_types -- [<output directory>]`, the default output directory is `generated`.
+//!
+//! Shells exchange bincode-serialized events, effect requests and responses with the core, using the generated types.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crux_core::typegen::TypeGen;
+use planner_app::{MoistureSensitivityLevel, ObjectPath, ObjectPathQuery, Planner, SerialNumberScheme, TreePath};
+
+fn main() -> anyhow::Result<()> {
+    let output_directory = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("generated"));
+
+    generate(&output_directory)?;
+
+    println!("Generated shared types. output_directory: {:?}", output_directory);
+
+    Ok(())
+}
+
+/// Generates the types for each language into a sub-directory of `output_directory`, one per language.
+fn generate(output_directory: &Path) -> anyhow::Result<()> {
+    let mut generator = TypeGen::new();
+
+    // types that are validated when deserialized need samples, the placeholder values used when tracing are rejected.
+    generator.register_samples(vec![ObjectPath::from_str("pcb=1::unit=1::ref_des=R1")?])?;
+    generator.register_samples(vec![ObjectPathQuery::from_str("pcb=1, unit=*")?])?;
+    generator.register_samples(vec![MoistureSensitivityLevel::from_str("3")?])?;
+    generator.register_samples(vec![SerialNumberScheme::from_str("{project}-{seq:04}")?])?;
+    generator.register_samples(vec![TreePath::from_str("/phases/top_1")?])?;
+
+    generator.register_app::<Planner>()?;
+
+    generator.typescript("planner_shared_types", output_directory.join("typescript"))?;
+    generator.swift("PlannerSharedTypes", output_directory.join("swift"))?;
+    generator.java("org.makerpnp.planner.shared_types", output_directory.join("java"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The contents of all the generated files in the directory, and its sub-directories.
+    fn read_generated(directory: &Path) -> String {
+        std::fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .map(|path| match path.is_dir() {
+                true => read_generated(&path),
+                false => std::fs::read_to_string(&path).unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn generates_the_event_view_and_effect_types_for_each_language() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        // when
+        generate(temp_dir.path()).unwrap();
+
+        // then
+        for language in ["typescript", "swift", "java"] {
+            let generated = read_generated(&temp_dir.path().join(language));
+
+            for type_name in [
+                "Event",
+                "PlannerOperationViewModel",
+                "ProjectView",
+                "PcbView",
+                "ProjectViewRendererOperation",
+                "PcbViewRendererOperation",
+                "VisionOperation",
+                "FileIoOperation",
+                "ObjectPath",
+            ] {
+                assert!(
+                    generated.contains(type_name),
+                    "missing type. language: {}, type: {}",
+                    language,
+                    type_name
+                );
+            }
+        }
+
+        // and the event variants, e.g. those added for the placement session
+        let generated = read_generated(&temp_dir.path().join("typescript"));
+        for variant in ["CreateProject", "StartPlacementSession", "RequestPlacementSessionView"] {
+            assert!(
+                generated.contains(variant),
+                "missing event variant. variant: {}",
+                variant
+            );
+        }
+    }
+}
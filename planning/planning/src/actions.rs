@@ -75,3 +75,36 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         }
     }
 }
+
+/// How the items that refer to a deleted item are handled, e.g. the placements assigned to a phase.
+#[derive(
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DeleteStrategy {
+    /// Nothing is deleted if any item refers to the item.
+    #[default]
+    Block,
+    /// The items that refer to the item are deleted, or reset, too.
+    Cascade,
+    /// The items that refer to the item are kept, but no longer refer to it.
+    Orphan,
+}
+
+impl Display for DeleteStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeleteStrategy::Block => f.write_str("block"),
+            DeleteStrategy::Cascade => f.write_str("cascade"),
+            DeleteStrategy::Orphan => f.write_str("orphan"),
+        }
+    }
+}
@@ -0,0 +1,549 @@
+//! Project archives.
+//!
+//! An archive is a zip file containing a project and all the files it references, so that a project can be shared.
+//!
+//! Archive layout:
+//! * `archive.json` - see [`ArchiveManifest`].
+//! * `<project file>`
+//! * `<design>_<variant>_placements.csv`
+//! * `pcbs/<pcb number>/<pcb file>`
+//! * `pcbs/<pcb number>/gerbers/<gerber file>`
+//! * `pcbs/<pcb number>/gerbers/<design index>/<gerber file>`
+//! * `load_outs/<load-out file>`
+//!
+//! Files with the same name, from different directories, are numbered, e.g. `load_outs/1_<load-out file>`.
+//!
+//! When exporting, the references are rewritten to paths relative to the root of the archive.  When importing, the
+//! gerber and load-out references are rewritten to absolute paths, since they are not resolved relative to the project.
+//!
+//! Remote (URL) load-outs are not included.  For projects that use the sqlite store backend the database file is
+//! included instead of the placements and load-out files, a database outside of the project directory is added to the
+//! root of the archive.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Cursor, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{info, trace};
+use util::source::Source;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::design::DesignVariant;
+use crate::file::{self, FileReference};
+use crate::pcb::{self, Pcb};
+use crate::project::Project;
+use crate::store::StoreBackend;
+
+pub const MANIFEST_FILE_NAME: &str = "archive.json";
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct ArchiveManifest {
+    /// Relative to the root of the archive.
+    pub project_file: PathBuf,
+}
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("IO error. cause: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Zip error. cause: {0}")]
+    ZipError(#[from] zip::result::ZipError),
+    #[error("Invalid path. path: {0:?}")]
+    InvalidPath(PathBuf),
+    #[error("PCB not loaded. pcb_file: {0}")]
+    PcbNotLoaded(FileReference),
+}
+
+/// `pcbs` must contain the loaded PCBs, in the same order as the project's PCBs.
+pub fn export_project_archive(
+    project: &Project,
+    project_path: &Path,
+    pcbs: &[&Pcb],
+    archive_path: &Path,
+) -> Result<(), ArchiveError> {
+    info!(
+        "Exporting project archive. project: {:?}, archive: {:?}",
+        project_path, archive_path
+    );
+
+    let project_directory = project_path
+        .parent()
+        .ok_or_else(|| ArchiveError::InvalidPath(project_path.to_path_buf()))?
+        .to_path_buf();
+    let project_file_name = file_name(project_path)?;
+
+    let mut writer = ArchiveWriter::new(File::create(archive_path)?);
+
+    let mut project = project.clone();
+
+    for (pcb_index, project_pcb) in project.pcbs.iter_mut().enumerate() {
+        let pcb = pcbs
+            .get(pcb_index)
+            .ok_or_else(|| ArchiveError::PcbNotLoaded(project_pcb.pcb_file.clone()))?;
+        let pcb_number = pcb_index + 1;
+        let pcb_directory = format!("pcbs/{}", pcb_number);
+
+        let mut pcb = (*pcb).clone();
+        for gerber in pcb.pcb_gerbers.iter_mut() {
+            let name = format!("{}/gerbers/{}", pcb_directory, file_name(&gerber.file)?);
+            gerber.file = PathBuf::from(writer.add_file(&name, &gerber.file)?);
+        }
+        for (design_index, gerbers) in pcb.design_gerbers.iter_mut() {
+            for gerber in gerbers.iter_mut() {
+                let name = format!(
+                    "{}/gerbers/{}/{}",
+                    pcb_directory,
+                    design_index,
+                    file_name(&gerber.file)?
+                );
+                gerber.file = PathBuf::from(writer.add_file(&name, &gerber.file)?);
+            }
+        }
+
+        let pcb_path = project_pcb
+            .pcb_file
+            .build_path(&project_directory);
+        let name = format!("{}/{}", pcb_directory, file_name(&pcb_path)?);
+        let name = writer.add_json(&name, &pcb)?;
+        project_pcb.pcb_file = FileReference::Relative(PathBuf::from(name));
+    }
+
+    match project.store_backend.clone() {
+        StoreBackend::Csv => {
+            let mut design_variants = project
+                .unique_design_variants(pcbs)
+                .into_iter()
+                .collect::<Vec<_>>();
+            design_variants.sort();
+
+            for DesignVariant {
+                design_name,
+                variant_name,
+            } in design_variants
+            {
+                let name = format!("{}_{}_placements.csv", design_name, variant_name);
+                let path = project_directory.join(&name);
+                if path.exists() {
+                    writer.add_file(&name, &path)?;
+                }
+            }
+
+            // multiple phases can use the same load-out, it's only added once
+            for phase in project.phases.values_mut() {
+                let Ok(Source::File(path)) = phase.load_out_source.parse::<Source>() else {
+                    continue;
+                };
+                let path = match path.is_absolute() {
+                    true => path,
+                    false => project_directory.join(path),
+                };
+
+                let name = format!("load_outs/{}", file_name(&path)?);
+                phase.load_out_source = writer.add_file(&name, &path)?;
+            }
+        }
+        StoreBackend::Sqlite {
+            path,
+        } => {
+            // entry names must be relative to the root of the archive
+            let is_within_project_directory = path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+            let name = match is_within_project_directory {
+                true => path
+                    .to_string_lossy()
+                    .replace('\\', "/"),
+                false => file_name(&path)?,
+            };
+            let name = writer.add_file(&name, &project_directory.join(&path))?;
+            project.store_backend = StoreBackend::Sqlite {
+                path: PathBuf::from(name),
+            };
+        }
+    }
+
+    let project_file_name = writer.add_json(&project_file_name, &project)?;
+    writer.add_manifest(&ArchiveManifest {
+        project_file: PathBuf::from(&project_file_name),
+    })?;
+
+    writer.finish()?;
+
+    info!("Exported project archive. archive: {:?}", archive_path);
+
+    Ok(())
+}
+
+/// The files of an imported archive, to be written by the caller, see [`import_project_archive`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedArchive {
+    pub project_path: PathBuf,
+    /// `(path, content)` tuples, in archive order, the paths are in the import directory.
+    pub files: Vec<(PathBuf, Vec<u8>)>,
+}
+
+/// Extracts the archive content into memory, the paths of the returned files are in the import directory.
+///
+/// No files are read or written, so that the caller can perform the IO, e.g. using a shell.
+pub fn import_project_archive(content: &[u8], directory: &Path) -> Result<ImportedArchive, ArchiveError> {
+    info!("Importing project archive. directory: {:?}", directory);
+
+    let mut archive = ZipArchive::new(Cursor::new(content))?;
+
+    let mut entries: Vec<(PathBuf, Vec<u8>)> = vec![];
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry
+            .enclosed_name()
+            .ok_or_else(|| ArchiveError::InvalidPath(PathBuf::from(entry.name())))?;
+
+        let mut entry_content = vec![];
+        entry.read_to_end(&mut entry_content)?;
+        entries.push((name, entry_content));
+    }
+
+    let directory = directory.to_path_buf();
+    let manifest: ArchiveManifest =
+        file::from_bytes(&entries[entry_index(&entries, Path::new(MANIFEST_FILE_NAME))?].1)?;
+
+    let project_index = entry_index(&entries, &manifest.project_file)?;
+    let mut project: Project = file::from_bytes(&entries[project_index].1)?;
+
+    if project.store_backend.is_csv() {
+        for phase in project.phases.values_mut() {
+            if let Ok(Source::File(path)) = phase.load_out_source.parse::<Source>() {
+                if path.is_relative() {
+                    phase.load_out_source = directory
+                        .join(path)
+                        .display()
+                        .to_string();
+                }
+            }
+        }
+    }
+
+    for project_pcb in project.pcbs.iter() {
+        let FileReference::Relative(pcb_name) = &project_pcb.pcb_file else {
+            return Err(ArchiveError::InvalidPath(
+                project_pcb
+                    .pcb_file
+                    .build_path(&directory),
+            ));
+        };
+        let pcb_index = entry_index(&entries, pcb_name)?;
+        let mut pcb = pcb::parse_pcb(&entries[pcb_index].1)?;
+
+        for gerber in pcb.pcb_gerbers.iter_mut().chain(
+            pcb.design_gerbers
+                .values_mut()
+                .flatten(),
+        ) {
+            if gerber.file.is_relative() {
+                gerber.file = directory.join(&gerber.file);
+            }
+        }
+
+        trace!("Rewrote PCB gerber paths. pcb_file: {:?}", pcb_name);
+        entries[pcb_index].1 = file::to_bytes(&pcb)?;
+    }
+
+    entries[project_index].1 = file::to_bytes_with_format(&project, project.file_format)?;
+
+    let project_path = directory.join(&manifest.project_file);
+
+    info!("Imported project archive. project: {:?}", project_path);
+
+    Ok(ImportedArchive {
+        project_path,
+        files: entries
+            .into_iter()
+            .map(|(name, content)| (directory.join(name), content))
+            .collect(),
+    })
+}
+
+/// Uses the same formatting as [`file::save`].
+fn json_content<T: Serialize>(t: &T) -> Result<Vec<u8>, ArchiveError> {
+    let mut content = vec![];
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut ser = serde_json::Serializer::with_formatter(&mut content, formatter);
+    t.serialize(&mut ser)
+        .map_err(std::io::Error::from)?;
+    content.push(b'\n');
+
+    Ok(content)
+}
+
+fn entry_index(entries: &[(PathBuf, Vec<u8>)], name: &Path) -> Result<usize, ArchiveError> {
+    entries
+        .iter()
+        .position(|(entry_name, _)| entry_name.eq(name))
+        .ok_or_else(|| ArchiveError::InvalidPath(name.to_path_buf()))
+}
+
+fn file_name(path: &Path) -> Result<String, ArchiveError> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| ArchiveError::InvalidPath(path.to_path_buf()))
+}
+
+struct ArchiveWriter {
+    zip: ZipWriter<File>,
+    options: SimpleFileOptions,
+    /// The names of the entries, with the file they were added from, if any.
+    names: BTreeMap<String, Option<PathBuf>>,
+}
+
+impl ArchiveWriter {
+    fn new(file: File) -> Self {
+        Self {
+            zip: ZipWriter::new(file),
+            options: SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated),
+            // reserved, see `add_manifest`
+            names: BTreeMap::from([(MANIFEST_FILE_NAME.to_string(), None)]),
+        }
+    }
+
+    /// Returns the name of the entry, a file that was already added is not added again, see [`Self::unique_name`].
+    fn add_file(&mut self, name: &str, path: &Path) -> Result<String, ArchiveError> {
+        if let Some((existing_name, _path)) = self
+            .names
+            .iter()
+            .find(|(_name, added_path)| added_path.as_deref() == Some(path))
+        {
+            return Ok(existing_name.clone());
+        }
+
+        let name = self.unique_name(name);
+        trace!("Adding file to archive. name: {}, path: {:?}", name, path);
+        let content = std::fs::read(path)?;
+        self.add_bytes(&name, &content)?;
+        self.names
+            .insert(name.clone(), Some(path.to_path_buf()));
+
+        Ok(name)
+    }
+
+    /// Uses the same formatting as [`file::save`], returns the name of the entry, see [`Self::unique_name`].
+    fn add_json<T: Serialize>(&mut self, name: &str, t: &T) -> Result<String, ArchiveError> {
+        let name = self.unique_name(name);
+        self.add_bytes(&name, &json_content(t)?)?;
+        self.names.insert(name.clone(), None);
+
+        Ok(name)
+    }
+
+    fn add_manifest(&mut self, manifest: &ArchiveManifest) -> Result<(), ArchiveError> {
+        self.add_bytes(MANIFEST_FILE_NAME, &json_content(manifest)?)
+    }
+
+    /// Files with the same name, from different directories, are numbered, e.g. `load_outs/1_load_out.csv`.
+    fn unique_name(&self, name: &str) -> String {
+        if !self.names.contains_key(name) {
+            return name.to_string();
+        }
+
+        let (directory, file_name) = match name.rsplit_once('/') {
+            Some((directory, file_name)) => (format!("{}/", directory), file_name),
+            None => (String::new(), name),
+        };
+
+        (1..)
+            .map(|number| format!("{}{}_{}", directory, number, file_name))
+            .find(|candidate| !self.names.contains_key(candidate))
+            .unwrap()
+    }
+
+    fn add_bytes(&mut self, name: &str, content: &[u8]) -> Result<(), ArchiveError> {
+        self.zip
+            .start_file(name, self.options)?;
+        self.zip.write_all(content)?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), ArchiveError> {
+        self.zip.finish()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use gerber::GerberFile;
+    use indexmap::IndexSet;
+    use pnp::pcb::PcbSide;
+    use pnp::reference::Reference;
+
+    use super::*;
+    use crate::project::ProjectPcb;
+
+    #[test]
+    fn export_and_import() {
+        // given
+        let source_directory = tempfile::tempdir().unwrap();
+        let source_path = source_directory.path().to_path_buf();
+
+        let gerber_path = source_path.join("top_copper.gbr");
+        std::fs::write(&gerber_path, "G04 test*\n").unwrap();
+        let load_out_path = source_path.join("load_out_1.csv");
+        std::fs::write(&load_out_path, "").unwrap();
+
+        // and
+        let mut pcb = Pcb::new("panel_a".to_string(), 1, IndexSet::new(), BTreeMap::new());
+        pcb.pcb_gerbers.push(GerberFile {
+            file: gerber_path.clone(),
+            function: None,
+        });
+        file::save(&pcb, &source_path.join("panel_a.pcb.json")).unwrap();
+
+        // and
+        let mut project = Project::new("job1".to_string(), None, None);
+        project
+            .pcbs
+            .push(ProjectPcb::new(FileReference::Relative("panel_a.pcb.json".into())));
+        project
+            .update_phase(
+                Reference::from_raw_str("top_1"),
+                Reference::from_raw_str("pnp"),
+                load_out_path.display().to_string(),
+                PcbSide::Top,
+            )
+            .unwrap();
+        let project_path = source_path.join("project-job1.mpnp.json");
+
+        // and
+        let archive_path = source_path.join("job1.zip");
+        let target_directory = tempfile::tempdir().unwrap();
+        let target_path = target_directory.path().to_path_buf();
+
+        // when
+        export_project_archive(&project, &project_path, &[&pcb], &archive_path).unwrap();
+        let imported_archive = import_project_archive(&std::fs::read(&archive_path).unwrap(), &target_path).unwrap();
+        for (path, content) in imported_archive.files.iter() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let imported_project_path = imported_archive.project_path;
+
+        // then
+        assert_eq!(imported_project_path, target_path.join("project-job1.mpnp.json"));
+
+        let imported_project: Project = file::load(&imported_project_path).unwrap();
+        assert_eq!(
+            imported_project.pcbs[0].pcb_file,
+            FileReference::Relative("pcbs/1/panel_a.pcb.json".into())
+        );
+        assert_eq!(
+            imported_project.phases[&Reference::from_raw_str("top_1")].load_out_source,
+            target_path
+                .join("load_outs/load_out_1.csv")
+                .display()
+                .to_string()
+        );
+
+        // and
+        let imported_pcb = pcb::load_pcb(&target_path.join("pcbs/1/panel_a.pcb.json")).unwrap();
+        let imported_gerber_path = target_path.join("pcbs/1/gerbers/top_copper.gbr");
+        assert_eq!(imported_pcb.pcb_gerbers[0].file, imported_gerber_path);
+        assert_eq!(std::fs::read_to_string(imported_gerber_path).unwrap(), "G04 test*\n");
+    }
+
+    fn entry_names(archive_path: &Path) -> Vec<String> {
+        let archive = ZipArchive::new(File::open(archive_path).unwrap()).unwrap();
+        archive
+            .file_names()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn files_with_the_same_name_are_numbered() {
+        // given
+        let source_directory = tempfile::tempdir().unwrap();
+        let source_path = source_directory.path().to_path_buf();
+
+        let gerber_paths = [source_path.join("a/top.gbr"), source_path.join("b/top.gbr")];
+        for gerber_path in gerber_paths.iter() {
+            std::fs::create_dir_all(gerber_path.parent().unwrap()).unwrap();
+            std::fs::write(gerber_path, "G04 test*\n").unwrap();
+        }
+
+        // and
+        let mut pcb = Pcb::new("panel_a".to_string(), 1, IndexSet::new(), BTreeMap::new());
+        for gerber_path in gerber_paths.iter() {
+            pcb.pcb_gerbers.push(GerberFile {
+                file: gerber_path.clone(),
+                function: None,
+            });
+        }
+
+        // and
+        let mut project = Project::new("job1".to_string(), None, None);
+        project
+            .pcbs
+            .push(ProjectPcb::new(FileReference::Relative("panel_a.pcb.json".into())));
+        let project_path = source_path.join("project-job1.mpnp.json");
+        let archive_path = source_path.join("job1.zip");
+
+        // when
+        export_project_archive(&project, &project_path, &[&pcb], &archive_path).unwrap();
+
+        // then
+        let names = entry_names(&archive_path);
+        assert!(names.contains(&"pcbs/1/gerbers/top.gbr".to_string()));
+        assert!(names.contains(&"pcbs/1/gerbers/1_top.gbr".to_string()));
+    }
+
+    #[test]
+    fn a_sqlite_database_outside_of_the_project_directory_is_added_to_the_root() {
+        // given
+        let source_directory = tempfile::tempdir().unwrap();
+        let source_path = source_directory.path().to_path_buf();
+        let database_directory = tempfile::tempdir().unwrap();
+        let database_path = database_directory
+            .path()
+            .join("store.sqlite");
+        std::fs::write(&database_path, "database").unwrap();
+
+        // and
+        let mut project = Project::new("job1".to_string(), None, None);
+        project.store_backend = StoreBackend::Sqlite {
+            path: database_path,
+        };
+        let project_path = source_path.join("project-job1.mpnp.json");
+        let archive_path = source_path.join("job1.zip");
+
+        // when
+        export_project_archive(&project, &project_path, &[], &archive_path).unwrap();
+
+        // then
+        let mut names = entry_names(&archive_path);
+        names.sort();
+        assert_eq!(names, vec![
+            MANIFEST_FILE_NAME.to_string(),
+            "project-job1.mpnp.json".to_string(),
+            "store.sqlite".to_string(),
+        ]);
+
+        // and the project refers to the database in the archive
+        let imported_archive = import_project_archive(&std::fs::read(&archive_path).unwrap(), &source_path).unwrap();
+        let (_path, content) = imported_archive
+            .files
+            .iter()
+            .find(|(path, _content)| path.eq(&imported_archive.project_path))
+            .unwrap();
+        let imported_project: Project = file::from_bytes(content).unwrap();
+        assert_eq!(imported_project.store_backend, StoreBackend::Sqlite {
+            path: PathBuf::from("store.sqlite"),
+        });
+    }
+}
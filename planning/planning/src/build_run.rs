@@ -0,0 +1,421 @@
+//! Build runs, for building a quantity of the project's PCBs without resetting operations or cloning the project.
+//!
+//! Each run has a quantity of instances, and each instance has its own placement and operation statuses.  The statuses
+//! of the active instance are the project's statuses, see [`Project::placements`] and [`Project::phase_states`], the
+//! statuses of the other instances are kept by the run and are swapped in when an instance is activated.
+//!
+//! Starting a run keeps the previous runs, so that they can be reviewed later.
+
+use std::collections::BTreeMap;
+
+use pnp::object_path::ObjectPath;
+use serde_with::serde_as;
+use serde_with::DisplayFromStr;
+use thiserror::Error;
+use time::serde::rfc3339;
+use time::OffsetDateTime;
+use tracing::info;
+
+use crate::phase::{PhaseReference, PhaseState, PhaseStatus};
+use crate::placement::{PlacementInspection, PlacementStatus};
+use crate::project::{refresh_phase_operation_states, Project};
+
+/// 1-based
+pub type BuildRunNumber = u32;
+/// 1-based
+pub type BuildRunInstanceNumber = u32;
+
+/// Identifies an instance of a run, e.g. in the operation history.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize
+)]
+pub struct BuildRunInstanceReference {
+    pub run: BuildRunNumber,
+    pub instance: BuildRunInstanceNumber,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BuildRunError {
+    #[error("Invalid quantity, at least one instance is required. quantity: {0}")]
+    InvalidQuantity(u32),
+    #[error("No build run has been started")]
+    NoBuildRun,
+    #[error("Unknown instance. instance: {instance}, quantity: {quantity}")]
+    UnknownInstance {
+        instance: BuildRunInstanceNumber,
+        quantity: u32,
+    },
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BuildRun {
+    pub number: BuildRunNumber,
+    pub quantity: u32,
+    #[serde(with = "rfc3339")]
+    pub started: OffsetDateTime,
+    pub active_instance: BuildRunInstanceNumber,
+    /// The statuses of the inactive instances that have been activated before, instances that have never been
+    /// activated are not started.
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub instances: BTreeMap<BuildRunInstanceNumber, BuildRunInstanceState>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BuildRunInstanceState {
+    #[serde_as(as = "Vec<(DisplayFromStr, _)>")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub placements: BTreeMap<ObjectPath, PlacementInstanceState>,
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub phase_states: BTreeMap<PhaseReference, PhaseState>,
+}
+
+/// The per-instance fields of a [`crate::placement::PlacementState`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PlacementInstanceState {
+    pub operation_status: PlacementStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub inspection: Option<PlacementInspection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BuildRunSummary {
+    pub number: BuildRunNumber,
+    pub quantity: u32,
+    pub active_instance: BuildRunInstanceNumber,
+    /// Instances where every phase is complete.
+    pub completed_instances: u32,
+}
+
+impl BuildRunInstanceState {
+    fn capture(project: &Project) -> Self {
+        Self {
+            placements: project
+                .placements
+                .iter()
+                .map(|(object_path, state)| {
+                    (object_path.clone(), PlacementInstanceState {
+                        operation_status: state.operation_status.clone(),
+                        inspection: state.inspection.clone(),
+                    })
+                })
+                .collect(),
+            phase_states: project.phase_states.clone(),
+        }
+    }
+
+    /// Placements and phases that are not in the state, e.g. added since the state was captured, are not started.
+    fn restore(self, project: &mut Project) {
+        let Self {
+            mut placements,
+            mut phase_states,
+        } = self;
+
+        for (object_path, placement_state) in project.placements.iter_mut() {
+            let instance_state = placements.remove(object_path);
+            placement_state.operation_status = instance_state
+                .as_ref()
+                .map_or(PlacementStatus::Pending, |state| state.operation_status.clone());
+            placement_state.inspection = instance_state.and_then(|state| state.inspection);
+        }
+
+        for (reference, phase_state) in project.phase_states.iter_mut() {
+            match phase_states.remove(reference) {
+                Some(instance_phase_state) => *phase_state = instance_phase_state,
+                None => phase_state.reset(),
+            }
+        }
+
+        refresh_phase_operation_states(project);
+    }
+
+    fn is_complete(&self) -> bool {
+        !self.phase_states.is_empty()
+            && self
+                .phase_states
+                .values()
+                .all(|phase_state| phase_state.status() == PhaseStatus::Complete)
+    }
+}
+
+/// Starts a new run, with the first instance active and all other instances not started.
+///
+/// The statuses of the current run, if any, are kept and the first instance is not started.  When there is no current
+/// run the project's statuses become the statuses of the first instance, so work recorded before the first run is
+/// not lost.
+pub fn start_build_run(
+    project: &mut Project,
+    quantity: u32,
+    started: OffsetDateTime,
+) -> Result<BuildRunNumber, BuildRunError> {
+    if quantity == 0 {
+        return Err(BuildRunError::InvalidQuantity(quantity));
+    }
+
+    let captured = BuildRunInstanceState::capture(project);
+    if let Some(current_run) = project.build_runs.last_mut() {
+        current_run
+            .instances
+            .insert(current_run.active_instance, captured);
+
+        BuildRunInstanceState::default().restore(project);
+    }
+
+    let number = project.build_runs.len() as BuildRunNumber + 1;
+    project.build_runs.push(BuildRun {
+        number,
+        quantity,
+        started,
+        active_instance: 1,
+        instances: Default::default(),
+    });
+
+    info!("Started build run. run: {}, quantity: {}", number, quantity);
+
+    Ok(number)
+}
+
+/// Makes the statuses of the instance of the current run the project's statuses.
+///
+/// Returns `true` if the active instance was changed.
+pub fn activate_build_run_instance(
+    project: &mut Project,
+    instance: BuildRunInstanceNumber,
+) -> Result<bool, BuildRunError> {
+    let captured = BuildRunInstanceState::capture(project);
+
+    let current_run = project
+        .build_runs
+        .last_mut()
+        .ok_or(BuildRunError::NoBuildRun)?;
+
+    if instance == 0 || instance > current_run.quantity {
+        return Err(BuildRunError::UnknownInstance {
+            instance,
+            quantity: current_run.quantity,
+        });
+    }
+
+    if instance == current_run.active_instance {
+        return Ok(false);
+    }
+
+    current_run
+        .instances
+        .insert(current_run.active_instance, captured);
+    current_run.active_instance = instance;
+
+    let instance_state = current_run
+        .instances
+        .remove(&instance)
+        .unwrap_or_default();
+
+    let number = current_run.number;
+    instance_state.restore(project);
+
+    info!("Activated build run instance. run: {}, instance: {}", number, instance);
+
+    Ok(true)
+}
+
+pub fn active_build_run_instance(project: &Project) -> Option<BuildRunInstanceReference> {
+    project
+        .build_runs
+        .last()
+        .map(|current_run| BuildRunInstanceReference {
+            run: current_run.number,
+            instance: current_run.active_instance,
+        })
+}
+
+pub fn build_run_summary(project: &Project) -> Option<BuildRunSummary> {
+    let current_run = project.build_runs.last()?;
+
+    let active_instance_complete = BuildRunInstanceState {
+        placements: Default::default(),
+        phase_states: project.phase_states.clone(),
+    }
+    .is_complete();
+
+    let completed_instances = current_run
+        .instances
+        .values()
+        .filter(|instance_state| instance_state.is_complete())
+        .count() as u32
+        + active_instance_complete as u32;
+
+    Some(BuildRunSummary {
+        number: current_run.number,
+        quantity: current_run.quantity,
+        active_instance: current_run.active_instance,
+        completed_instances,
+    })
+}
+
+#[cfg(test)]
+mod build_run_tests {
+    use std::str::FromStr;
+
+    use time::format_description::well_known::Rfc3339;
+
+    use super::*;
+    use crate::placement::PlacementState;
+
+    fn project_with_placement(object_path: &ObjectPath) -> Project {
+        let mut project = Project::default();
+        project
+            .placements
+            .insert(object_path.clone(), PlacementState::default());
+        project
+    }
+
+    fn started() -> OffsetDateTime {
+        OffsetDateTime::parse("2025-03-01T08:00:00Z", &Rfc3339).unwrap()
+    }
+
+    #[test]
+    pub fn instances_have_independent_placement_statuses() {
+        // given
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap();
+        let mut project = project_with_placement(&object_path);
+        start_build_run(&mut project, 2, started()).unwrap();
+
+        // and
+        project
+            .placements
+            .get_mut(&object_path)
+            .unwrap()
+            .operation_status = PlacementStatus::Placed;
+
+        // when
+        activate_build_run_instance(&mut project, 2).unwrap();
+
+        // then
+        assert_eq!(
+            project.placements[&object_path].operation_status,
+            PlacementStatus::Pending
+        );
+
+        // when
+        activate_build_run_instance(&mut project, 1).unwrap();
+
+        // then
+        assert_eq!(
+            project.placements[&object_path].operation_status,
+            PlacementStatus::Placed
+        );
+    }
+
+    #[test]
+    pub fn starting_the_first_run_keeps_the_statuses() {
+        // given
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap();
+        let mut project = project_with_placement(&object_path);
+        project
+            .placements
+            .get_mut(&object_path)
+            .unwrap()
+            .operation_status = PlacementStatus::Placed;
+
+        // when
+        let number = start_build_run(&mut project, 2, started()).unwrap();
+
+        // then
+        assert_eq!(number, 1);
+        assert_eq!(
+            project.placements[&object_path].operation_status,
+            PlacementStatus::Placed
+        );
+
+        // when
+        activate_build_run_instance(&mut project, 2).unwrap();
+        activate_build_run_instance(&mut project, 1).unwrap();
+
+        // then
+        assert_eq!(
+            project.placements[&object_path].operation_status,
+            PlacementStatus::Placed
+        );
+    }
+
+    #[test]
+    pub fn starting_a_run_keeps_the_previous_run() {
+        // given
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap();
+        let mut project = project_with_placement(&object_path);
+        start_build_run(&mut project, 1, started()).unwrap();
+        project
+            .placements
+            .get_mut(&object_path)
+            .unwrap()
+            .operation_status = PlacementStatus::Skipped;
+
+        // when
+        let number = start_build_run(&mut project, 5, started()).unwrap();
+
+        // then
+        assert_eq!(number, 2);
+        assert_eq!(
+            project.placements[&object_path].operation_status,
+            PlacementStatus::Pending
+        );
+        assert_eq!(
+            project.build_runs[0].instances[&1].placements[&object_path].operation_status,
+            PlacementStatus::Skipped
+        );
+        assert_eq!(
+            build_run_summary(&project),
+            Some(BuildRunSummary {
+                number: 2,
+                quantity: 5,
+                active_instance: 1,
+                completed_instances: 0,
+            })
+        );
+    }
+
+    #[test]
+    pub fn invalid_instances() {
+        // given
+        let mut project = Project::default();
+
+        // expect
+        assert_eq!(
+            activate_build_run_instance(&mut project, 1),
+            Err(BuildRunError::NoBuildRun)
+        );
+        assert_eq!(
+            start_build_run(&mut project, 0, started()),
+            Err(BuildRunError::InvalidQuantity(0))
+        );
+
+        // given
+        start_build_run(&mut project, 3, started()).unwrap();
+
+        // expect
+        assert_eq!(
+            activate_build_run_instance(&mut project, 4),
+            Err(BuildRunError::UnknownInstance {
+                instance: 4,
+                quantity: 3
+            })
+        );
+        assert_eq!(activate_build_run_instance(&mut project, 1), Ok(false));
+    }
+}
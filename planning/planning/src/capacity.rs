@@ -0,0 +1,504 @@
+//! Feeder capacity planning for phases.
+//!
+//! Each part of a phase requires a feeder, when a phase requires more feeders than the machine has feeder slots, see
+//! [`ProcessDefinition::feeder_banks`], the phase has to be assembled in multiple passes, with the feeders being
+//! changed between passes.
+//!
+//! Parts are never split across passes, so the number of feeder changes is the number of parts that are not in the
+//! first pass.  To minimize the changes, parts that already have a feeder assigned in the load-out, and are therefore
+//! already loaded, are kept in the first pass, then parts are ordered by the number of placements, most first.
+
+use std::collections::BTreeMap;
+
+use indexmap::IndexSet;
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use thiserror::Error;
+use tracing::info;
+
+use crate::actions::DeleteStrategy;
+use crate::phase::{Phase, PhaseError, PhaseReference};
+use crate::process::ProcessDefinition;
+use crate::project::{build_phase_placement_states, Project};
+
+#[derive(Error, Debug)]
+pub enum CapacityError {
+    #[error("Unknown phase. phase: '{0}'")]
+    UnknownPhase(PhaseReference),
+    #[error("Unknown process. process: '{0}'")]
+    UnknownProcess(String),
+    #[error("The machine has no feeder slots, define the feeder banks of the process. process: '{0}'")]
+    NoFeederSlots(String),
+    #[error("Phase fits the feeder capacity, no need to split. phase: '{0}'")]
+    NoSplitRequired(PhaseReference),
+    #[error("Phase already exists. phase: '{0}'")]
+    DuplicatePhase(PhaseReference),
+    #[error("Phase error. cause: {0}")]
+    PhaseError(#[from] PhaseError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PhaseCapacity {
+    pub phase: PhaseReference,
+    pub slot_count: usize,
+    /// The number of feeders required, one for each part.
+    pub feeder_count: usize,
+    /// A single pass when the phase fits.
+    pub passes: Vec<CapacityPass>,
+}
+
+impl PhaseCapacity {
+    pub fn fits(&self) -> bool {
+        self.feeder_count <= self.slot_count
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CapacityPass {
+    pub parts: Vec<Part>,
+    pub placement_count: usize,
+}
+
+pub fn count_feeder_slots(process: &ProcessDefinition) -> usize {
+    process
+        .feeder_banks
+        .iter()
+        .map(|bank| bank.slots.len())
+        .sum()
+}
+
+/// Only placements that are to be placed require feeders.
+///
+/// Placements of a part that has an alias in the load-out use the alias' feeder, see [`Project::part_aliases`].
+pub fn analyze_phase_capacity(
+    project: &Project,
+    phase_reference: &PhaseReference,
+    load_out_items: &[LoadOutItem],
+) -> Result<PhaseCapacity, CapacityError> {
+    let phase = project
+        .phases
+        .get(phase_reference)
+        .ok_or(CapacityError::UnknownPhase(phase_reference.clone()))?;
+    let process = project
+        .find_process(&phase.process)
+        .map_err(|_| CapacityError::UnknownProcess(phase.process.to_string()))?;
+
+    let slot_count = count_feeder_slots(process);
+    if slot_count == 0 {
+        return Err(CapacityError::NoFeederSlots(process.reference.to_string()));
+    }
+
+    let feeder_parts = build_feeder_part_placement_counts(project, phase_reference, load_out_items);
+
+    let mut parts = feeder_parts
+        .iter()
+        .map(|(part, placement_count)| {
+            let loaded = pnp::load_out::find_load_out_item_by_part(load_out_items, part)
+                .is_some_and(|item| item.reference.is_some());
+            (part, *placement_count, loaded)
+        })
+        .collect::<Vec<_>>();
+    parts.sort_by(|(part_a, count_a, loaded_a), (part_b, count_b, loaded_b)| {
+        loaded_b
+            .cmp(loaded_a)
+            .then(count_b.cmp(count_a))
+            .then(part_a.cmp(part_b))
+    });
+
+    let passes = parts
+        .chunks(slot_count)
+        .map(|chunk| CapacityPass {
+            parts: chunk
+                .iter()
+                .map(|(part, _, _)| (*part).clone())
+                .collect(),
+            placement_count: chunk
+                .iter()
+                .map(|(_, placement_count, _)| placement_count)
+                .sum(),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(PhaseCapacity {
+        phase: phase_reference.clone(),
+        slot_count,
+        feeder_count: feeder_parts.len(),
+        passes,
+    })
+}
+
+fn build_feeder_part_placement_counts(
+    project: &Project,
+    phase_reference: &PhaseReference,
+    load_out_items: &[LoadOutItem],
+) -> BTreeMap<Part, usize> {
+    build_phase_placement_states(project, phase_reference)
+        .into_iter()
+        .filter(|(_object_path, placement_state)| placement_state.placement.place)
+        .fold(BTreeMap::new(), |mut feeder_parts, (_object_path, placement_state)| {
+            let feeder_part = resolve_feeder_part(project, &placement_state.placement.part, load_out_items);
+            *feeder_parts
+                .entry(feeder_part)
+                .or_default() += 1;
+            feeder_parts
+        })
+}
+
+/// A phase created by [`split_phase`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubPhase {
+    pub reference: PhaseReference,
+    pub load_out_source: String,
+    /// The items of the original load-out for the parts of the pass, feeder assignments are retained.
+    pub load_out_items: Vec<LoadOutItem>,
+}
+
+/// Replaces the phase with a phase for each pass, e.g. `top_smt` is replaced with `top_smt_1` and `top_smt_2`.
+///
+/// The sub-phases use the same process, PCB side, placement orderings and assembly window as the phase, the load-out
+/// sources are derived from the load-out source of the phase, e.g. `load_out_top.csv` -> `load_out_top_1.csv`.  The
+/// caller is responsible for storing the load-out items of the sub-phases.
+///
+/// The phase must not have been started.
+pub fn split_phase(
+    project: &mut Project,
+    capacity: &PhaseCapacity,
+    load_out_items: &[LoadOutItem],
+) -> Result<Vec<SubPhase>, CapacityError> {
+    if capacity.fits() {
+        return Err(CapacityError::NoSplitRequired(capacity.phase.clone()));
+    }
+
+    let phase = project
+        .phases
+        .get(&capacity.phase)
+        .ok_or(CapacityError::UnknownPhase(capacity.phase.clone()))?
+        .clone();
+
+    if !project
+        .phase_states
+        .get(&phase.reference)
+        .is_some_and(|phase_state| phase_state.is_pending())
+    {
+        return Err(PhaseError::PhaseInUse(phase.reference.clone()).into());
+    }
+
+    let sub_phases = capacity
+        .passes
+        .iter()
+        .enumerate()
+        .map(|(index, pass)| {
+            let reference = PhaseReference::from_raw(format!("{}_{}", phase.reference, index + 1));
+            if project.phases.contains_key(&reference) {
+                return Err(CapacityError::DuplicatePhase(reference));
+            }
+
+            let load_out_items = load_out_items
+                .iter()
+                .filter(|item| {
+                    pass.parts
+                        .iter()
+                        .any(|part| item.manufacturer.eq(&part.manufacturer) && item.mpn.eq(&part.mpn))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            Ok(SubPhase {
+                reference,
+                load_out_source: build_sub_phase_load_out_source(&phase.load_out_source, index + 1),
+                load_out_items,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let placement_phases = assign_placements_to_passes(project, &phase, capacity, load_out_items, &sub_phases);
+
+    for sub_phase in sub_phases.iter() {
+        project.update_phase(
+            sub_phase.reference.clone(),
+            phase.process.clone(),
+            sub_phase.load_out_source.clone(),
+            phase.pcb_side,
+        )?;
+        // Safety: the phase was just created
+        let created_phase = project
+            .phases
+            .get_mut(&sub_phase.reference)
+            .unwrap();
+        created_phase.placement_orderings = phase.placement_orderings.clone();
+        created_phase.assembly_window = phase.assembly_window.clone();
+    }
+
+    // the sub-phases replace the phase in the phase ordering
+    let phase_orderings = project
+        .phase_orderings
+        .iter()
+        .filter(|reference| {
+            !sub_phases
+                .iter()
+                .any(|sub_phase| sub_phase.reference.eq(*reference))
+        })
+        .flat_map(|reference| match reference.eq(&phase.reference) {
+            true => sub_phases
+                .iter()
+                .map(|sub_phase| sub_phase.reference.clone())
+                .collect::<Vec<_>>(),
+            false => vec![reference.clone()],
+        })
+        .collect::<IndexSet<_>>();
+
+    // the placements are re-assigned to the sub-phases below
+    project.delete_phase(phase.reference.clone(), DeleteStrategy::Orphan)?;
+    project.phase_orderings = phase_orderings;
+
+    for (object_path, sub_phase_reference) in placement_phases {
+        if let Some(placement_state) = project.placements.get_mut(&object_path) {
+            placement_state.phase = Some(sub_phase_reference);
+        }
+    }
+
+    info!(
+        "Split phase. phase: '{}', sub-phases: {:?}",
+        phase.reference,
+        sub_phases
+            .iter()
+            .map(|sub_phase| sub_phase.reference.to_string())
+            .collect::<Vec<_>>()
+    );
+
+    Ok(sub_phases)
+}
+
+/// Placements of parts that are not in any pass, i.e. placements that are not placed, are assigned to the first pass.
+fn assign_placements_to_passes(
+    project: &Project,
+    phase: &Phase,
+    capacity: &PhaseCapacity,
+    load_out_items: &[LoadOutItem],
+    sub_phases: &[SubPhase],
+) -> Vec<(ObjectPath, PhaseReference)> {
+    build_phase_placement_states(project, &phase.reference)
+        .into_iter()
+        .map(|(object_path, placement_state)| {
+            let feeder_part = resolve_feeder_part(project, &placement_state.placement.part, load_out_items);
+            let pass_index = capacity
+                .passes
+                .iter()
+                .position(|pass| pass.parts.contains(&feeder_part))
+                .unwrap_or(0);
+
+            (object_path.clone(), sub_phases[pass_index].reference.clone())
+        })
+        .collect()
+}
+
+/// The part of the load-out item that would be used for the part, i.e. the part or an alias.
+fn resolve_feeder_part(project: &Project, part: &Part, load_out_items: &[LoadOutItem]) -> Part {
+    pnp::load_out::find_load_out_item_by_part_or_alias(load_out_items, part, &project.part_aliases)
+        .map(|item| Part::new(item.manufacturer.clone(), item.mpn.clone()))
+        .unwrap_or_else(|| part.clone())
+}
+
+/// e.g. `load_out_top.csv` -> `load_out_top_1.csv`, `load_out_top` -> `load_out_top_1`
+fn build_sub_phase_load_out_source(load_out_source: &str, pass_number: usize) -> String {
+    match load_out_source.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() && !extension.contains(['/', '\\']) => {
+            format!("{}_{}.{}", stem, pass_number, extension)
+        }
+        _ => format!("{}_{}", load_out_source, pass_number),
+    }
+}
+
+#[cfg(test)]
+mod capacity_tests {
+    use std::str::FromStr;
+
+    use pnp::load_out::{FeederBank, FeederSlot};
+    use pnp::pcb::PcbSide;
+    use pnp::placement::Placement;
+    use pnp::reference::Reference;
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::placement::PlacementState;
+
+    fn build_project(slot_count: usize, parts: &[(&str, usize)]) -> Project {
+        let mut project = Project::default();
+
+        let process = project
+            .processes
+            .iter_mut()
+            .find(|process| {
+                process
+                    .reference
+                    .eq(&Reference::from_raw_str("pnp"))
+            })
+            .unwrap();
+        process.feeder_banks = vec![FeederBank {
+            reference: Reference::from_raw_str("FRONT"),
+            x: Decimal::ZERO,
+            y: Decimal::ZERO,
+            slots: (1..=slot_count)
+                .map(|index| FeederSlot {
+                    reference: Reference::from_raw(format!("F{}", index)),
+                    x: Decimal::ZERO,
+                    y: Decimal::ZERO,
+                    width: Decimal::from(8),
+                })
+                .collect(),
+        }];
+
+        project
+            .update_phase(
+                Reference::from_raw_str("top_smt"),
+                Reference::from_raw_str("pnp"),
+                "load_out_top.csv".to_string(),
+                PcbSide::Top,
+            )
+            .unwrap();
+
+        let mut ref_des_index = 0;
+        for (mpn, placement_count) in parts {
+            for _ in 0..*placement_count {
+                ref_des_index += 1;
+                let object_path = ObjectPath::from_str(&format!("pcb=1::unit=1::ref_des=R{}", ref_des_index)).unwrap();
+                project
+                    .placements
+                    .insert(object_path, PlacementState {
+                        unit_path: ObjectPath::from_str("pcb=1::unit=1").unwrap(),
+                        placement: Placement {
+                            part: Part::new("MFR".to_string(), mpn.to_string()),
+                            ..Placement::default()
+                        },
+                        phase: Some(Reference::from_raw_str("top_smt")),
+                        ..PlacementState::default()
+                    });
+            }
+        }
+
+        project
+    }
+
+    fn part(mpn: &str) -> Part {
+        Part::new("MFR".to_string(), mpn.to_string())
+    }
+
+    #[test]
+    pub fn fits() {
+        // given
+        let project = build_project(2, &[("PART1", 1), ("PART2", 3)]);
+
+        // when
+        let capacity = analyze_phase_capacity(&project, &Reference::from_raw_str("top_smt"), &[]).unwrap();
+
+        // then
+        assert!(capacity.fits());
+        assert_eq!(capacity.passes, vec![CapacityPass {
+            parts: vec![part("PART2"), part("PART1")],
+            placement_count: 4,
+        }]);
+    }
+
+    #[test]
+    pub fn loaded_parts_are_kept_in_the_first_pass() {
+        // given
+        let project = build_project(2, &[("PART1", 1), ("PART2", 3), ("PART3", 2)]);
+        let load_out_items = vec![LoadOutItem::new(
+            Some(Reference::from_raw_str("F1")),
+            "MFR".to_string(),
+            "PART1".to_string(),
+        )];
+
+        // when
+        let capacity = analyze_phase_capacity(&project, &Reference::from_raw_str("top_smt"), &load_out_items).unwrap();
+
+        // then
+        assert!(!capacity.fits());
+        assert_eq!(capacity.feeder_count, 3);
+        assert_eq!(capacity.passes, vec![
+            CapacityPass {
+                parts: vec![part("PART1"), part("PART2")],
+                placement_count: 4,
+            },
+            CapacityPass {
+                parts: vec![part("PART3")],
+                placement_count: 2,
+            },
+        ]);
+    }
+
+    #[test]
+    pub fn split() {
+        // given
+        let mut project = build_project(1, &[("PART1", 1), ("PART2", 2)]);
+        let load_out_items = vec![
+            LoadOutItem::new(
+                Some(Reference::from_raw_str("F1")),
+                "MFR".to_string(),
+                "PART1".to_string(),
+            ),
+            LoadOutItem::new(None, "MFR".to_string(), "PART2".to_string()),
+        ];
+        let capacity = analyze_phase_capacity(&project, &Reference::from_raw_str("top_smt"), &load_out_items).unwrap();
+
+        // when
+        let sub_phases = split_phase(&mut project, &capacity, &load_out_items).unwrap();
+
+        // then
+        assert_eq!(sub_phases, vec![
+            SubPhase {
+                reference: Reference::from_raw_str("top_smt_1"),
+                load_out_source: "load_out_top_1.csv".to_string(),
+                load_out_items: vec![load_out_items[0].clone()],
+            },
+            SubPhase {
+                reference: Reference::from_raw_str("top_smt_2"),
+                load_out_source: "load_out_top_2.csv".to_string(),
+                load_out_items: vec![load_out_items[1].clone()],
+            },
+        ]);
+
+        // and the sub-phases replace the phase
+        assert_eq!(
+            project
+                .phase_orderings
+                .iter()
+                .map(|reference| reference.to_string())
+                .collect::<Vec<_>>(),
+            vec!["top_smt_1", "top_smt_2"]
+        );
+        assert!(!project
+            .phases
+            .contains_key(&Reference::from_raw_str("top_smt")));
+
+        // and the placements are assigned to the sub-phase of the pass
+        let phase_of = |ref_des: &str| {
+            project
+                .placements
+                .get(&ObjectPath::from_str(&format!("pcb=1::unit=1::ref_des={}", ref_des)).unwrap())
+                .unwrap()
+                .phase
+                .clone()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(phase_of("R1"), "top_smt_1");
+        assert_eq!(phase_of("R2"), "top_smt_2");
+        assert_eq!(phase_of("R3"), "top_smt_2");
+    }
+
+    #[test]
+    pub fn sub_phase_load_out_sources() {
+        // expect
+        assert_eq!(
+            build_sub_phase_load_out_source("load_out_top.csv", 2),
+            "load_out_top_2.csv"
+        );
+        assert_eq!(build_sub_phase_load_out_source("load_out_top", 1), "load_out_top_1");
+        assert_eq!(
+            build_sub_phase_load_out_source("../top.d/load_out", 1),
+            "../top.d/load_out_1"
+        );
+    }
+}
@@ -0,0 +1,335 @@
+//! Machine jobs, the ordered placements, feeders, fiducials and panel origins of a phase, packaged so that a machine
+//! can place the parts of the phase without the project, see [`MachineJob`].
+//!
+//! Jobs always use millimeters, with the origin at the bottom-left corner of the panel, and positive anti-clockwise
+//! rotations, regardless of the [`crate::settings::ProjectSettings`].
+
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::pcb::{PcbInstanceNumber, PcbSide};
+use pnp::reference::Reference;
+use rust_decimal::Decimal;
+use thiserror::Error;
+use tracing::info;
+
+use crate::nozzle::{NozzleAssignments, NozzleReference};
+use crate::pcb::{Pcb, PcbSideAssemblyOrientation};
+use crate::phase::{Phase, PhaseReference};
+use crate::project::PhasePlacementRecord;
+
+/// Incremented when the format changes in a way that machines that only support older formats cannot load.
+pub const MACHINE_JOB_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct MachineJob {
+    /// See [`MACHINE_JOB_FORMAT_VERSION`].
+    pub version: u32,
+    pub phase: PhaseReference,
+    pub pcb_side: PcbSide,
+    pub pcbs: Vec<MachineJobPcb>,
+    /// The load-out items used by the placements, by feeder reference.
+    pub feeders: Vec<LoadOutItem>,
+    /// In placement order.
+    pub placements: Vec<MachineJobPlacement>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct MachineJobPcb {
+    pub pcb: PcbInstanceNumber,
+    pub name: String,
+    /// In millimeters, the origin of the panel is the bottom-left corner.
+    pub width: f64,
+    pub height: f64,
+    pub orientation: PcbSideAssemblyOrientation,
+    pub fiducials: Vec<MachineJobFiducial>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct MachineJobFiducial {
+    /// In millimeters, relative to the panel origin.
+    pub x: f64,
+    pub y: f64,
+    pub copper_diameter: f64,
+    pub mask_diameter: f64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct MachineJobPlacement {
+    pub object_path: ObjectPath,
+    /// `None` if the part is not in the load-out, or its load-out item has no feeder.
+    pub feeder_reference: Option<Reference>,
+    pub manufacturer: String,
+    pub mpn: String,
+    pub x: Decimal,
+    pub y: Decimal,
+    /// Includes the rotation offsets and the rotation offset of the feeder.
+    pub rotation: Decimal,
+    /// `None` if no nozzle of the process is compatible with the package of the part.
+    pub nozzle: Option<NozzleReference>,
+}
+
+/// The placement records must be in placement order, and use the default project settings, see
+/// [`crate::project::build_phase_placement_records`].
+pub fn build_machine_job(
+    phase: &Phase,
+    pcbs: &[&Pcb],
+    placement_records: Vec<PhasePlacementRecord>,
+    load_out_items: &[LoadOutItem],
+    nozzle_assignments: &NozzleAssignments,
+) -> MachineJob {
+    let pcbs = pcbs
+        .iter()
+        .enumerate()
+        .map(|(pcb_index, pcb)| {
+            let orientation = match phase.pcb_side {
+                PcbSide::Top => pcb.orientation.top.clone(),
+                PcbSide::Bottom => pcb.orientation.bottom.clone(),
+            };
+
+            let fiducials = pcb
+                .panel_sizing
+                .fiducials
+                .iter()
+                .map(|fiducial| MachineJobFiducial {
+                    x: fiducial.position.x,
+                    y: fiducial.position.y,
+                    copper_diameter: fiducial.copper_diameter,
+                    mask_diameter: fiducial.mask_diameter,
+                })
+                .collect();
+
+            MachineJobPcb {
+                pcb: pcb_index as PcbInstanceNumber + 1,
+                name: pcb.name.clone(),
+                width: pcb.panel_sizing.size.x,
+                height: pcb.panel_sizing.size.y,
+                orientation,
+                fiducials,
+            }
+        })
+        .collect();
+
+    let feeder_references = placement_records
+        .iter()
+        .filter_map(|record| record.feeder_reference.as_ref())
+        .collect::<BTreeSet<_>>();
+
+    let feeders = load_out_items
+        .iter()
+        .filter(|item| {
+            item.reference
+                .as_ref()
+                .is_some_and(|reference| feeder_references.contains(reference))
+        })
+        .cloned()
+        .collect();
+
+    let placements = placement_records
+        .into_iter()
+        .map(|record| MachineJobPlacement {
+            nozzle: nozzle_assignments
+                .get(&record.object_path)
+                .cloned(),
+            object_path: record.object_path,
+            feeder_reference: record.feeder_reference,
+            manufacturer: record.manufacturer,
+            mpn: record.mpn,
+            x: record.x,
+            y: record.y,
+            rotation: record.rotation,
+        })
+        .collect();
+
+    MachineJob {
+        version: MACHINE_JOB_FORMAT_VERSION,
+        phase: phase.reference.clone(),
+        pcb_side: phase.pcb_side,
+        pcbs,
+        feeders,
+        placements,
+    }
+}
+
+pub fn build_machine_job_file_path(phase: &Phase, directory: &Path) -> PathBuf {
+    let mut path = PathBuf::from(directory);
+    path.push(format!("{}_job.json", phase.reference));
+    path
+}
+
+pub fn machine_job_save_as_json(job: &MachineJob, path: &PathBuf) -> Result<(), anyhow::Error> {
+    let file = File::create(path)?;
+    let mut writer = std::io::BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, job)?;
+    writer.flush()?;
+
+    info!("Generated machine job. path: {:?}", path);
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum MachineJobLoadError {
+    #[error("Unable to load machine job. cause: {0:}")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid machine job. cause: {0:}")]
+    InvalidJob(#[from] serde_json::Error),
+    #[error("Unsupported machine job format version. version: {version}, supported: {supported}")]
+    UnsupportedVersion { version: u32, supported: u32 },
+}
+
+/// The counterpart of [`machine_job_save_as_json`], for machines, jobs with a newer format version are refused, see
+/// [`MACHINE_JOB_FORMAT_VERSION`].
+pub fn machine_job_load_from_json(path: &Path) -> Result<MachineJob, MachineJobLoadError> {
+    let content = std::fs::read(path)?;
+    let job: MachineJob = serde_json::from_slice(&content)?;
+
+    if job.version > MACHINE_JOB_FORMAT_VERSION {
+        return Err(MachineJobLoadError::UnsupportedVersion {
+            version: job.version,
+            supported: MACHINE_JOB_FORMAT_VERSION,
+        });
+    }
+
+    info!("Loaded machine job. path: {:?}", path);
+
+    Ok(job)
+}
+
+#[cfg(test)]
+mod export_tests {
+    use std::collections::BTreeMap;
+
+    use indexmap::IndexSet;
+    use nalgebra::{Point2, Vector2};
+    use pnp::panel::FiducialParameters;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::design::DesignName;
+
+    #[test]
+    pub fn build_job() {
+        // given
+        let mut pcb = Pcb::new(
+            "panel".to_string(),
+            1,
+            IndexSet::from([DesignName::from("design_a")]),
+            BTreeMap::from([(0, 0)]),
+        );
+        pcb.panel_sizing.size = Vector2::new(100.0, 50.0);
+        pcb.panel_sizing.fiducials = vec![FiducialParameters {
+            position: Point2::new(5.0, 5.0),
+            mask_diameter: 2.0,
+            copper_diameter: 1.0,
+        }];
+
+        let phase = Phase {
+            reference: Reference::from_raw_str("top_1"),
+            process: Reference::from_raw_str("pnp"),
+            load_out_source: "load_out_1".to_string(),
+            pcb_side: PcbSide::Top,
+            placement_orderings: vec![],
+            assembly_window: None,
+        };
+
+        let build_record = |ref_des: &str, feeder_reference: Option<&str>| PhasePlacementRecord {
+            object_path: ObjectPath::from_raw_str(&format!("pcb=1::unit=1::ref_des={}", ref_des)),
+            feeder_reference: feeder_reference.map(Reference::from_raw_str),
+            manufacturer: "MFR1".to_string(),
+            mpn: "PART1".to_string(),
+            x: dec!(10),
+            y: dec!(20),
+            rotation: dec!(90),
+        };
+        let placement_records = vec![build_record("R2", Some("F1")), build_record("R1", None)];
+
+        let load_out_items = vec![
+            LoadOutItem::new(
+                Some(Reference::from_raw_str("F1")),
+                "MFR1".to_string(),
+                "PART1".to_string(),
+            ),
+            LoadOutItem::new(
+                Some(Reference::from_raw_str("F2")),
+                "MFR1".to_string(),
+                "PART2".to_string(),
+            ),
+        ];
+
+        let nozzle_assignments = NozzleAssignments::from([(
+            ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R2"),
+            Reference::from_raw_str("CN065"),
+        )]);
+
+        // when
+        let job = build_machine_job(&phase, &[&pcb], placement_records, &load_out_items, &nozzle_assignments);
+
+        // then
+        assert_eq!(job.version, MACHINE_JOB_FORMAT_VERSION);
+        assert_eq!(job.pcbs.len(), 1);
+        assert_eq!(job.pcbs[0].height, 50.0);
+        assert_eq!(job.pcbs[0].fiducials, vec![MachineJobFiducial {
+            x: 5.0,
+            y: 5.0,
+            copper_diameter: 1.0,
+            mask_diameter: 2.0,
+        }]);
+
+        // and only the used feeders are included
+        assert_eq!(job.feeders, vec![load_out_items[0].clone()]);
+
+        // and the placement order is kept
+        let ref_des_list = job
+            .placements
+            .iter()
+            .map(|placement| placement.object_path.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ref_des_list, vec![
+            "pcb=1::unit=1::ref_des=R2",
+            "pcb=1::unit=1::ref_des=R1"
+        ]);
+        assert_eq!(job.placements[0].nozzle, Some(Reference::from_raw_str("CN065")));
+        assert_eq!(job.placements[1].nozzle, None);
+    }
+
+    #[test]
+    pub fn saved_jobs_can_be_loaded() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("top_1_job.json");
+        let job = MachineJob {
+            version: MACHINE_JOB_FORMAT_VERSION,
+            phase: Reference::from_raw_str("top_1"),
+            pcb_side: PcbSide::Top,
+            pcbs: vec![],
+            feeders: vec![],
+            placements: vec![],
+        };
+        machine_job_save_as_json(&job, &path).unwrap();
+
+        // when
+        let loaded_job = machine_job_load_from_json(&path).unwrap();
+
+        // then
+        assert_eq!(loaded_job, job);
+
+        // when the job uses a newer format
+        let newer_job = MachineJob {
+            version: MACHINE_JOB_FORMAT_VERSION + 1,
+            ..job
+        };
+        machine_job_save_as_json(&newer_job, &path).unwrap();
+        let result = machine_job_load_from_json(&path);
+
+        // then
+        assert!(matches!(
+            result,
+            Err(MachineJobLoadError::UnsupportedVersion { version, .. }) if version == MACHINE_JOB_FORMAT_VERSION + 1
+        ));
+    }
+}
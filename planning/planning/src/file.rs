@@ -3,7 +3,9 @@
 use std::io::Write;
 use std::path::PathBuf;
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use thiserror::Error;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, Ord, PartialOrd)]
@@ -58,17 +60,202 @@ pub fn load<'de, T: Deserialize<'de>>(file_path: &PathBuf) -> Result<T, std::io:
 }
 
 pub fn save<'se, T: Serialize>(t: &T, file_path: &PathBuf) -> Result<(), std::io::Error> {
-    let file = File::create(file_path)?;
-    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
-    let mut ser = serde_json::Serializer::with_formatter(file, formatter);
-    t.serialize(&mut ser)?;
+    save_with_format(t, file_path, FileFormat::Pretty)
+}
+
+pub fn save_with_format<T: Serialize>(t: &T, file_path: &PathBuf, format: FileFormat) -> Result<(), std::io::Error> {
+    let content = to_bytes_with_format(t, format)?;
 
-    let mut file = ser.into_inner();
-    let _written = file.write(b"\n")?;
+    let mut file = File::create(file_path)?;
+    file.write_all(&content)?;
 
     Ok(())
 }
 
+/// Deserializes file content, e.g. content read by a shell, see [`load`].
+pub fn from_bytes<T: DeserializeOwned>(content: &[u8]) -> Result<T, std::io::Error> {
+    let t = serde_json::from_slice(content)?;
+    Ok(t)
+}
+
+/// Serializes to the same format as [`save`], e.g. for content that will be written by a shell.
+pub fn to_bytes<T: Serialize>(t: &T) -> Result<Vec<u8>, std::io::Error> {
+    to_bytes_with_format(t, FileFormat::Pretty)
+}
+
+pub fn to_bytes_with_format<T: Serialize>(t: &T, format: FileFormat) -> Result<Vec<u8>, std::io::Error> {
+    let mut content = match format {
+        FileFormat::Pretty => {
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+            let mut ser = serde_json::Serializer::with_formatter(Vec::new(), formatter);
+            t.serialize(&mut ser)?;
+            ser.into_inner()
+        }
+        FileFormat::Canonical => {
+            let value = serde_json::to_value(t)?;
+            let mut content = String::new();
+            write_canonical(&value, 0, &mut content);
+            content.into_bytes()
+        }
+    };
+    content.push(b'\n');
+
+    Ok(content)
+}
+
+/// The formatting used when saving files.
+#[derive(Debug, Default, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileFormat {
+    /// Fields in declaration order, pretty-printed.
+    #[default]
+    Pretty,
+    /// Diff-friendly, the output only depends on the content, not on the order of insertion or declaration.
+    ///
+    /// Object keys are sorted, floats are written in fixed-point notation, e.g. `0.0000001` instead of `1e-7`, and the
+    /// items of `placements` arrays are written one item per line, so that a change to a placement is a one-line diff.
+    ///
+    /// The keys of `task_states` objects are not sorted, the order of the tasks is significant, see
+    /// [`crate::process::OperationState::status`].
+    Canonical,
+}
+
+impl FileFormat {
+    pub fn is_pretty(&self) -> bool {
+        matches!(self, FileFormat::Pretty)
+    }
+}
+
+const CANONICAL_INDENT: &str = "    ";
+
+/// Keys whose array items are written on a single line.
+const CANONICAL_SINGLE_LINE_ITEM_KEYS: [&str; 1] = ["placements"];
+
+/// Keys whose object entries are written in insertion order, instead of being sorted.
+const CANONICAL_ORDERED_KEYS: [&str; 1] = ["task_states"];
+
+fn write_canonical(value: &Value, depth: usize, out: &mut String) {
+    write_canonical_with_ordering(value, depth, true, out)
+}
+
+fn write_canonical_with_ordering(value: &Value, depth: usize, sort_keys: bool, out: &mut String) {
+    match value {
+        Value::Array(values) if !values.is_empty() => {
+            out.push_str("[\n");
+            for (index, value) in values.iter().enumerate() {
+                write_canonical_indent(depth + 1, out);
+                write_canonical(value, depth + 1, out);
+                write_canonical_separator(index, values.len(), out);
+            }
+            write_canonical_indent(depth, out);
+            out.push(']');
+        }
+        Value::Object(map) if !map.is_empty() => {
+            out.push_str("{\n");
+            let entries = match sort_keys {
+                true => sorted_entries(map),
+                false => map.iter().collect::<Vec<_>>(),
+            };
+            for (index, (key, value)) in entries.iter().enumerate() {
+                write_canonical_indent(depth + 1, out);
+                write_canonical_key(key, out);
+                match value {
+                    Value::Array(values)
+                        if CANONICAL_SINGLE_LINE_ITEM_KEYS.contains(&key.as_str()) && !values.is_empty() =>
+                    {
+                        out.push_str("[\n");
+                        for (index, value) in values.iter().enumerate() {
+                            write_canonical_indent(depth + 2, out);
+                            write_canonical_single_line(value, out);
+                            write_canonical_separator(index, values.len(), out);
+                        }
+                        write_canonical_indent(depth + 1, out);
+                        out.push(']');
+                    }
+                    Value::Object(_) if CANONICAL_ORDERED_KEYS.contains(&key.as_str()) => {
+                        write_canonical_with_ordering(value, depth + 1, false, out)
+                    }
+                    _ => write_canonical(value, depth + 1, out),
+                }
+                write_canonical_separator(index, entries.len(), out);
+            }
+            write_canonical_indent(depth, out);
+            out.push('}');
+        }
+        _ => write_canonical_single_line(value, out),
+    }
+}
+
+fn write_canonical_single_line(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(values) => {
+            out.push('[');
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                write_canonical_single_line(value, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            out.push('{');
+            for (index, (key, value)) in sorted_entries(map)
+                .into_iter()
+                .enumerate()
+            {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                write_canonical_key(key, out);
+                write_canonical_single_line(value, out);
+            }
+            out.push('}');
+        }
+        Value::Number(number) => match number.as_f64() {
+            Some(float) if number.is_f64() => out.push_str(&format_canonical_float(float)),
+            _ => out.push_str(&number.to_string()),
+        },
+        Value::Null | Value::Bool(_) | Value::String(_) => out.push_str(&value.to_string()),
+    }
+}
+
+fn sorted_entries(map: &Map<String, Value>) -> Vec<(&String, &Value)> {
+    let mut entries = map.iter().collect::<Vec<_>>();
+    entries.sort_by(|(key_a, _), (key_b, _)| key_a.cmp(key_b));
+    entries
+}
+
+fn write_canonical_key(key: &str, out: &mut String) {
+    out.push_str(&Value::String(key.to_string()).to_string());
+    out.push_str(": ");
+}
+
+fn write_canonical_indent(depth: usize, out: &mut String) {
+    for _ in 0..depth {
+        out.push_str(CANONICAL_INDENT);
+    }
+}
+
+fn write_canonical_separator(index: usize, len: usize, out: &mut String) {
+    if index + 1 < len {
+        out.push(',');
+    }
+    out.push('\n');
+}
+
+/// Fixed-point, the shortest representation that round-trips, always with a fractional part, e.g. `1.0`, `-0.5`.
+fn format_canonical_float(value: f64) -> String {
+    // normalize negative zero, so that `-0.0` and `0.0` do not cause a diff.
+    let value = if value == 0.0 { 0.0 } else { value };
+
+    let mut formatted = value.to_string();
+    if !formatted.contains('.') {
+        formatted.push_str(".0");
+    }
+    formatted
+}
+
 impl TryFrom<&str> for FileReference {
     type Error = FileReferenceError;
 
@@ -87,3 +274,98 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
         Err(FileReferenceError::Invalid(value.to_string()))
     }
 }
+
+#[cfg(test)]
+mod file_tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::process::{
+        LoadPcbsTaskState, ManualSolderingTaskState, OperationReference, OperationState, OperationStatus,
+        SerializableTaskState, TaskReference, TaskState,
+    };
+
+    #[derive(serde::Serialize)]
+    struct Example {
+        name: String,
+        offset: f64,
+        placements: Vec<(String, ExamplePlacement)>,
+        empty: Vec<u32>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct ExamplePlacement {
+        x: f64,
+        rotation: f64,
+        placed: bool,
+    }
+
+    #[test]
+    pub fn canonical_format() {
+        // given
+        let example = Example {
+            name: "job1".to_string(),
+            offset: 0.0000001,
+            placements: vec![
+                ("pcb=1::unit=1::ref_des=R1".to_string(), ExamplePlacement {
+                    x: 10.0,
+                    rotation: -0.0,
+                    placed: true,
+                }),
+                ("pcb=1::unit=1::ref_des=R2".to_string(), ExamplePlacement {
+                    x: 12.5,
+                    rotation: 90.0,
+                    placed: false,
+                }),
+            ],
+            empty: vec![],
+        };
+
+        // when
+        let content = to_bytes_with_format(&example, FileFormat::Canonical).unwrap();
+
+        // then
+        let expected_content = r#"{
+    "empty": [],
+    "name": "job1",
+    "offset": 0.0000001,
+    "placements": [
+        ["pcb=1::unit=1::ref_des=R1", {"placed": true, "rotation": 0.0, "x": 10.0}],
+        ["pcb=1::unit=1::ref_des=R2", {"placed": false, "rotation": 90.0, "x": 12.5}]
+    ]
+}
+"#;
+        assert_eq!(String::from_utf8(content).unwrap(), expected_content);
+    }
+
+    #[test]
+    pub fn canonical_format_round_trip_keeps_the_task_order() {
+        // given
+        let mut load_pcbs_task_state = LoadPcbsTaskState::default();
+        load_pcbs_task_state.set_completed();
+
+        // and tasks that are not in key order
+        let operation_state = OperationState {
+            reference: OperationReference::from_raw_str("operation_1"),
+            task_states: IndexMap::from([
+                (
+                    TaskReference::from_raw_str("z_load_pcbs"),
+                    Box::new(load_pcbs_task_state) as Box<dyn SerializableTaskState>,
+                ),
+                (
+                    TaskReference::from_raw_str("a_manual_soldering"),
+                    Box::new(ManualSolderingTaskState::default()) as Box<dyn SerializableTaskState>,
+                ),
+            ]),
+        };
+        assert_eq!(operation_state.status(), OperationStatus::Started);
+
+        // when
+        let content = to_bytes_with_format(&operation_state, FileFormat::Canonical).unwrap();
+        let loaded_operation_state: OperationState = from_bytes(&content).unwrap();
+
+        // then
+        assert_eq!(loaded_operation_state, operation_state);
+        assert_eq!(loaded_operation_state.status(), OperationStatus::Started);
+    }
+}
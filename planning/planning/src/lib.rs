@@ -1,7 +1,12 @@
 pub mod actions;
+pub mod archive;
+pub mod build_run;
+pub mod capacity;
 pub mod design;
+pub mod export;
 
 pub mod library;
+pub mod nozzle;
 pub mod operation;
 pub mod operation_history;
 pub mod part;
@@ -11,7 +16,15 @@
 pub mod process;
 pub mod project;
 
+pub mod recent;
+pub mod recovery;
+pub mod relink;
 pub mod report;
+pub mod rotation;
+pub mod serial_number;
+pub mod settings;
+pub mod store;
+pub mod template;
 pub mod variant;
 
 pub mod file;
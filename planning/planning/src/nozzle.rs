@@ -0,0 +1,291 @@
+//! Nozzle definitions and nozzle assignment.
+//!
+//! A pick-and-place machine has one or more heads, each head holds a single nozzle at a time.  Which nozzle can pick
+//! a package depends on the size of the package, explicit per-package rules can be used to override the size-based
+//! rules, e.g. for packages with holes or uneven tops.
+//!
+//! Placements are assigned the nozzle that is compatible with the most placements first, so that the number of
+//! distinct nozzles, and thus the number of nozzle changes, is kept to a minimum.
+
+use std::collections::BTreeMap;
+
+use pnp::object_path::ObjectPath;
+use pnp::package::Package;
+use pnp::part::Part;
+use pnp::reference::Reference;
+use rust_decimal::Decimal;
+use tracing::trace;
+
+use crate::placement::PlacementState;
+
+/// e.g. `CN040`
+pub type NozzleReference = Reference;
+
+/// The assigned nozzle for each placement, placements that could not be assigned a nozzle are absent.
+pub type NozzleAssignments = BTreeMap<ObjectPath, NozzleReference>;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct NozzleConfiguration {
+    /// The amount of heads, and thus the amount of nozzles that can be in use without a nozzle change.
+    pub heads: u8,
+
+    pub nozzles: Vec<NozzleDefinition>,
+
+    /// Explicit package-to-nozzle rules, these take precedence over the package size ranges of the nozzles.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub package_rules: Vec<NozzlePackageRule>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct NozzleDefinition {
+    pub reference: NozzleReference,
+
+    /// The outer diameter of the nozzle tip.
+    pub diameter_mm: Decimal,
+
+    /// The range of package sizes, inclusive, that the nozzle can pick.
+    ///
+    /// The size of a package is the smaller of its x and y dimensions.
+    pub min_package_size_mm: Decimal,
+    pub max_package_size_mm: Decimal,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct NozzlePackageRule {
+    /// The package name, see [`Package::name`].
+    pub package: String,
+
+    /// The compatible nozzles.
+    pub nozzles: Vec<NozzleReference>,
+}
+
+impl NozzleConfiguration {
+    pub fn is_empty(&self) -> bool {
+        self.heads == 0 && self.nozzles.is_empty() && self.package_rules.is_empty()
+    }
+
+    /// Returns the nozzles that can pick the package, in definition order.
+    ///
+    /// Packages without dimensions are only compatible with nozzles given by a package rule.
+    pub fn compatible_nozzles(&self, package: &Package) -> Vec<&NozzleReference> {
+        if let Some(rule) = self
+            .package_rules
+            .iter()
+            .find(|rule| rule.package.eq(&package.name))
+        {
+            return self
+                .nozzles
+                .iter()
+                .filter(|nozzle| rule.nozzles.contains(&nozzle.reference))
+                .map(|nozzle| &nozzle.reference)
+                .collect();
+        }
+
+        let Some(dimensions) = &package.dimensions_mm else {
+            return vec![];
+        };
+        let size = dimensions
+            .size_x()
+            .min(dimensions.size_y());
+
+        self.nozzles
+            .iter()
+            .filter(|nozzle| size >= nozzle.min_package_size_mm && size <= nozzle.max_package_size_mm)
+            .map(|nozzle| &nozzle.reference)
+            .collect()
+    }
+
+    /// Assign a nozzle to each placement, minimizing the amount of distinct nozzles used.
+    ///
+    /// Placements for parts without a package, or without a compatible nozzle, are not assigned a nozzle.
+    pub fn assign_nozzles(
+        &self,
+        placement_states: &[(&ObjectPath, &PlacementState)],
+        part_packages: &BTreeMap<&Part, &Package>,
+    ) -> NozzleAssignments {
+        let mut candidates: Vec<(&ObjectPath, Vec<&NozzleReference>)> = placement_states
+            .iter()
+            .filter_map(|(object_path, state)| {
+                let package = part_packages.get(&state.placement.part)?;
+                let nozzles = self.compatible_nozzles(package);
+
+                match nozzles.is_empty() {
+                    true => None,
+                    false => Some((*object_path, nozzles)),
+                }
+            })
+            .collect();
+
+        let mut assignments = NozzleAssignments::new();
+
+        // greedy; repeatedly choose the nozzle that can pick the most of the remaining placements,
+        // ties are resolved using the order of the nozzle definitions.
+        while !candidates.is_empty() {
+            let Some(nozzle) = self
+                .nozzles
+                .iter()
+                .map(|nozzle| {
+                    let count = candidates
+                        .iter()
+                        .filter(|(_, nozzles)| nozzles.contains(&&nozzle.reference))
+                        .count();
+                    (&nozzle.reference, count)
+                })
+                .rev()
+                .max_by_key(|(_, count)| *count)
+                .map(|(nozzle, _)| nozzle)
+            else {
+                break;
+            };
+
+            trace!("Assigning nozzle. nozzle: {}", nozzle);
+
+            candidates.retain(|(object_path, nozzles)| match nozzles.contains(&nozzle) {
+                true => {
+                    assignments.insert((*object_path).clone(), nozzle.clone());
+                    false
+                }
+                false => true,
+            });
+        }
+
+        assignments
+    }
+
+    /// The amount of nozzle changes required, assuming each head starts with a nozzle fitted.
+    pub fn nozzle_changes(&self, assignments: &NozzleAssignments) -> usize {
+        let mut nozzles: Vec<&NozzleReference> = assignments.values().collect();
+        nozzles.sort();
+        nozzles.dedup();
+
+        nozzles
+            .len()
+            .saturating_sub(self.heads as usize)
+    }
+}
+
+#[cfg(test)]
+mod nozzle_tests {
+    use pnp::package::PackageDimensions;
+    use pnp::placement::Placement;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn build_configuration() -> NozzleConfiguration {
+        NozzleConfiguration {
+            heads: 1,
+            nozzles: vec![
+                NozzleDefinition {
+                    reference: NozzleReference::from_raw_str("CN040"),
+                    diameter_mm: dec!(0.4),
+                    min_package_size_mm: dec!(0.5),
+                    max_package_size_mm: dec!(1.0),
+                },
+                NozzleDefinition {
+                    reference: NozzleReference::from_raw_str("CN065"),
+                    diameter_mm: dec!(0.65),
+                    min_package_size_mm: dec!(0.8),
+                    max_package_size_mm: dec!(3.0),
+                },
+                NozzleDefinition {
+                    reference: NozzleReference::from_raw_str("CN140"),
+                    diameter_mm: dec!(1.4),
+                    min_package_size_mm: dec!(2.0),
+                    max_package_size_mm: dec!(10.0),
+                },
+            ],
+            package_rules: vec![NozzlePackageRule {
+                package: "SW-6x6".to_string(),
+                nozzles: vec![NozzleReference::from_raw_str("CN040")],
+            }],
+        }
+    }
+
+    #[test]
+    pub fn compatible_nozzles() {
+        // given
+        let configuration = build_configuration();
+        let package_0603 =
+            Package::new("0603".to_string()).with_dimensions(PackageDimensions::new(dec!(1.6), dec!(0.8), dec!(0.45)));
+        let package_switch =
+            Package::new("SW-6x6".to_string()).with_dimensions(PackageDimensions::new(dec!(6.0), dec!(6.0), dec!(3.5)));
+        let package_unknown = Package::new("UNKNOWN".to_string());
+
+        // when
+        let nozzles_0603 = configuration.compatible_nozzles(&package_0603);
+        let nozzles_switch = configuration.compatible_nozzles(&package_switch);
+        let nozzles_unknown = configuration.compatible_nozzles(&package_unknown);
+
+        // then
+        assert_eq!(nozzles_0603, vec![
+            &NozzleReference::from_raw_str("CN040"),
+            &NozzleReference::from_raw_str("CN065"),
+        ]);
+        assert_eq!(nozzles_switch, vec![&NozzleReference::from_raw_str("CN040")]);
+        assert!(nozzles_unknown.is_empty());
+    }
+
+    #[test]
+    pub fn assign_nozzles_minimizes_distinct_nozzles() {
+        // given
+        let configuration = build_configuration();
+
+        // 0603 can use CN040 or CN065, 1206 and SOT-23 can only use CN065, so CN065 should be used for all of them.
+        let part_0603 = Part::new("MFR1".to_string(), "R_0603".to_string());
+        let part_1206 = Part::new("MFR1".to_string(), "R_1206".to_string());
+        let part_sot23 = Part::new("MFR2".to_string(), "SOT-23".to_string());
+        let part_unknown = Part::new("MFR3".to_string(), "UNKNOWN".to_string());
+
+        let package_0603 =
+            Package::new("0603".to_string()).with_dimensions(PackageDimensions::new(dec!(1.6), dec!(0.8), dec!(0.45)));
+        let package_1206 =
+            Package::new("1206".to_string()).with_dimensions(PackageDimensions::new(dec!(3.2), dec!(1.6), dec!(0.55)));
+        let package_sot23 =
+            Package::new("SOT-23".to_string()).with_dimensions(PackageDimensions::new(dec!(2.9), dec!(2.4), dec!(1.0)));
+
+        let part_packages: BTreeMap<&Part, &Package> = BTreeMap::from([
+            (&part_0603, &package_0603),
+            (&part_1206, &package_1206),
+            (&part_sot23, &package_sot23),
+        ]);
+
+        let placement_states = [
+            ("pcb=1::unit=1::ref_des=R1", &part_0603),
+            ("pcb=1::unit=1::ref_des=R2", &part_1206),
+            ("pcb=1::unit=1::ref_des=Q1", &part_sot23),
+            ("pcb=1::unit=1::ref_des=U1", &part_unknown),
+        ]
+        .into_iter()
+        .map(|(path, part)| {
+            (ObjectPath::from_raw_str(path), PlacementState {
+                placement: Placement {
+                    part: part.clone(),
+                    ..Placement::default()
+                },
+                ..PlacementState::default()
+            })
+        })
+        .collect::<Vec<_>>();
+        let placement_states = placement_states
+            .iter()
+            .map(|(object_path, state)| (object_path, state))
+            .collect::<Vec<_>>();
+
+        // when
+        let assignments = configuration.assign_nozzles(&placement_states, &part_packages);
+
+        // then
+        let cn065 = NozzleReference::from_raw_str("CN065");
+        assert_eq!(
+            assignments,
+            BTreeMap::from([
+                (ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=Q1"), cn065.clone()),
+                (ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R1"), cn065.clone()),
+                (ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R2"), cn065.clone()),
+            ])
+        );
+        assert_eq!(configuration.nozzle_changes(&assignments), 0);
+    }
+}
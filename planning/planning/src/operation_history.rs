@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 use std::fs::File;
 use std::path::PathBuf;
@@ -15,8 +15,10 @@
 use time::OffsetDateTime;
 use tracing::info;
 
+use crate::build_run::BuildRunInstanceReference;
 use crate::placement::PlacementOperation;
-use crate::process::{OperationReference, TaskReference, TaskStatus};
+use crate::process::{OperationReference, RuleViolation, TaskParameterValue, TaskReference, TaskStatus};
+use crate::serial_number::SerialNumber;
 
 #[typetag::serde(tag = "type")]
 pub trait OperationHistoryKind: AsAny + Debug {}
@@ -37,6 +39,14 @@ pub struct PlaceComponentsOperationTaskHistoryKind {
 #[typetag::serde(name = "place_components_operation")]
 impl OperationHistoryKind for PlaceComponentsOperationTaskHistoryKind {}
 
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct ApplySolderPasteOperationTaskHistoryKind {
+    pub(crate) status: TaskStatus,
+}
+
+#[typetag::serde(name = "apply_solder_paste_operation")]
+impl OperationHistoryKind for ApplySolderPasteOperationTaskHistoryKind {}
+
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct ManualSolderingOperationTaskHistoryKind {
     pub(crate) status: TaskStatus,
@@ -53,6 +63,18 @@ pub struct AutomatedSolderingOperationTaskHistoryKind {
 #[typetag::serde(name = "automated_soldering_operation")]
 impl OperationHistoryKind for AutomatedSolderingOperationTaskHistoryKind {}
 
+/// See [`crate::process::CustomTaskDefinition`].
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct CustomOperationTaskHistoryKind {
+    pub(crate) status: TaskStatus,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub(crate) parameter_values: BTreeMap<String, TaskParameterValue>,
+}
+
+#[typetag::serde(name = "custom_task_operation")]
+impl OperationHistoryKind for CustomOperationTaskHistoryKind {}
+
 #[serde_as]
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct PlacementOperationHistoryKind {
@@ -64,6 +86,40 @@ pub struct PlacementOperationHistoryKind {
 #[typetag::serde(name = "placement_operation")]
 impl OperationHistoryKind for PlacementOperationHistoryKind {}
 
+/// The result of a vision inspection of a placement, recorded for traceability.
+#[serde_as]
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct PlacementInspectionHistoryKind {
+    #[serde_as(as = "DisplayFromStr")]
+    pub object_path: ObjectPath,
+    /// From the expected position, millimeters.
+    pub offset_x: f64,
+    pub offset_y: f64,
+    /// From the expected rotation, degrees, positive is anti-clockwise.
+    pub rotation: f64,
+    /// 0.0 to 1.0
+    pub confidence: f64,
+}
+
+#[typetag::serde(name = "placement_inspection")]
+impl OperationHistoryKind for PlacementInspectionHistoryKind {}
+
+/// The result of locating a fiducial using vision, recorded for traceability.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct FiducialLocationHistoryKind {
+    /// The expected position, in machine coordinates, millimeters.
+    pub expected_x: f64,
+    pub expected_y: f64,
+    /// From the expected position, millimeters.
+    pub offset_x: f64,
+    pub offset_y: f64,
+    /// 0.0 to 1.0
+    pub confidence: f64,
+}
+
+#[typetag::serde(name = "fiducial_location")]
+impl OperationHistoryKind for FiducialLocationHistoryKind {}
+
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct OperationHistoryItem {
     #[serde(with = "rfc3339")]
@@ -74,6 +130,21 @@ pub struct OperationHistoryItem {
     pub task_reference: TaskReference,
     pub task_history: Box<dyn OperationHistoryKind>,
 
+    /// The serial number of the unit the operation was scoped to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub serial_number: Option<SerialNumber>,
+
+    /// The active build run instance when the operation was recorded, if any, see [`crate::build_run`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub build_run: Option<BuildRunInstanceReference>,
+
+    /// The process rules that were violated, but overridden, when the operation was recorded.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub overridden_rules: Vec<RuleViolation>,
+
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
@@ -1,5 +1,9 @@
 use std::collections::BTreeSet;
 
+use pnp::part::PartMoistureSensitivity;
+use time::serde::rfc3339;
+use time::{Duration, OffsetDateTime};
+
 use crate::process::ProcessReference;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default)]
@@ -8,4 +12,130 @@ pub struct PartState {
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
     #[serde(default)]
     pub applicable_processes: BTreeSet<ProcessReference>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub moisture_sensitivity: Option<PartMoistureSensitivity>,
+
+    /// `None` until the reel/dry-pack is opened.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub moisture_exposure: Option<MoistureExposure>,
+}
+
+impl PartState {
+    /// Returns the time at which the floor life of the part expires, exposure starts at `unopened_exposure_start` if
+    /// the reel has not been opened yet.
+    ///
+    /// `None` if the part has no moisture sensitivity, or an unlimited floor life.
+    pub fn floor_life_expiry(&self, unopened_exposure_start: OffsetDateTime) -> Option<OffsetDateTime> {
+        let floor_life_hours = self
+            .moisture_sensitivity
+            .as_ref()?
+            .floor_life_hours()?;
+
+        let exposed_since = self
+            .moisture_exposure
+            .as_ref()
+            .map_or(unopened_exposure_start, MoistureExposure::exposed_since);
+
+        Some(exposed_since + Duration::hours(floor_life_hours as i64))
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct MoistureExposure {
+    #[serde(with = "rfc3339")]
+    pub opened: OffsetDateTime,
+
+    /// Baking resets the floor life.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(with = "rfc3339::option")]
+    pub baked: Option<OffsetDateTime>,
+}
+
+impl MoistureExposure {
+    pub fn exposed_since(&self) -> OffsetDateTime {
+        match self.baked {
+            Some(baked) if baked > self.opened => baked,
+            _ => self.opened,
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub enum MoistureEvent {
+    ReelOpened,
+    Baked,
+}
+
+impl MoistureEvent {
+    /// Applies the event, occurring at `at`, to the exposure.
+    ///
+    /// Baking an unopened reel is ignored, the floor life starts when the reel is opened.
+    pub fn apply(&self, exposure: &mut Option<MoistureExposure>, at: OffsetDateTime) {
+        match self {
+            MoistureEvent::ReelOpened => {
+                *exposure = Some(MoistureExposure {
+                    opened: at,
+                    baked: None,
+                })
+            }
+            MoistureEvent::Baked => {
+                if let Some(exposure) = exposure {
+                    exposure.baked = Some(at)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod moisture_tests {
+    use pnp::part::MoistureSensitivityLevel;
+    use time::format_description::well_known::Rfc3339;
+
+    use super::*;
+
+    fn datetime(value: &str) -> OffsetDateTime {
+        OffsetDateTime::parse(value, &Rfc3339).unwrap()
+    }
+
+    #[test]
+    pub fn floor_life_expiry_is_reset_by_baking() {
+        // given
+        let mut part_state = PartState {
+            moisture_sensitivity: Some(PartMoistureSensitivity {
+                level: MoistureSensitivityLevel::Msl3,
+                floor_life_hours: None,
+            }),
+            ..PartState::default()
+        };
+        let window_start = datetime("2025-03-10T08:00:00Z");
+
+        // expect unopened parts to be exposed from the start of the window
+        assert_eq!(
+            part_state.floor_life_expiry(window_start),
+            Some(datetime("2025-03-17T08:00:00Z"))
+        );
+
+        // when
+        MoistureEvent::ReelOpened.apply(&mut part_state.moisture_exposure, datetime("2025-03-01T12:00:00Z"));
+
+        // then
+        assert_eq!(
+            part_state.floor_life_expiry(window_start),
+            Some(datetime("2025-03-08T12:00:00Z"))
+        );
+
+        // when
+        MoistureEvent::Baked.apply(&mut part_state.moisture_exposure, datetime("2025-03-09T12:00:00Z"));
+
+        // then
+        assert_eq!(
+            part_state.floor_life_expiry(window_start),
+            Some(datetime("2025-03-16T12:00:00Z"))
+        );
+    }
 }
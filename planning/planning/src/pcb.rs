@@ -5,17 +5,17 @@
 use gerber::{detect_purpose, GerberFile, GerberFileFunction};
 use indexmap::IndexSet;
 use itertools::Itertools;
-use math::angle::normalize_angle_deg_signed_decimal;
-use nalgebra::{Matrix3, Vector2, Vector3};
+use nalgebra::{Matrix3, Vector2};
 use pnp::panel::{DesignSizing, PanelSizing};
+pub use pnp::pcb::PcbAssemblyFlip;
 use pnp::pcb::{PcbUnitIndex, PcbUnitNumber};
 use pnp::placement::Placement;
-use rust_decimal::prelude::ToPrimitive;
+use pnp::transform::{MachinePlacementPosition, PlacementTransformer};
 use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
 use serde_with::serde_as;
 use thiserror::Error;
 use tracing::{info, trace};
+use util::cancellation::{CancellationToken, Cancelled};
 
 use crate::design::{DesignIndex, DesignName};
 use crate::file;
@@ -108,6 +108,9 @@ pub enum PcbError {
 
     #[error("Missing unit positioning information for unit {unit}")]
     MissingUnitPositioning { unit: PcbUnitIndex },
+
+    #[error(transparent)]
+    Cancelled(#[from] Cancelled),
 }
 
 impl PartialEq for Pcb {
@@ -208,6 +211,7 @@ pub fn update_gerbers(
         &mut self,
         design: Option<DesignName>,
         files: Vec<(PathBuf, Option<GerberFileFunction>)>,
+        cancellation: &CancellationToken,
     ) -> Result<bool, PcbError> {
         let gerbers = self.gerbers_for_pcb_or_design(design)?;
         let mut modified = false;
@@ -221,7 +225,9 @@ pub fn update_gerbers(
             });
         }
 
+        // the gerbers are only replaced when all of them have been processed
         for gerber in new_gerbers.iter_mut() {
+            cancellation.check()?;
             let new_purpose = detect_purpose(&gerber.file).ok();
             gerber.function = new_purpose;
         }
@@ -335,15 +341,22 @@ pub fn create_pcb(
 
 pub fn load_pcb(path: &PathBuf) -> Result<Pcb, std::io::Error> {
     info!("Loading PCB from {}", path.display());
-    file::load::<Pcb>(path).map(|mut pcb| {
-        // TODO can we somehow integrate this block into the deserialization so we don't have to do it explicitly?
-        pcb.panel_sizing
-            .ensure_design_sizings(pcb.design_names.len());
-        pcb.panel_sizing
-            .ensure_unit_positionings(pcb.units);
-
-        pcb
-    })
+    file::load::<Pcb>(path).map(ensure_panel_sizing)
+}
+
+/// Like [`load_pcb`], but for content that has already been read, e.g. by a shell.
+pub fn parse_pcb(content: &[u8]) -> Result<Pcb, std::io::Error> {
+    file::from_bytes::<Pcb>(content).map(ensure_panel_sizing)
+}
+
+// TODO can we somehow integrate this into the deserialization so we don't have to do it explicitly?
+fn ensure_panel_sizing(mut pcb: Pcb) -> Pcb {
+    pcb.panel_sizing
+        .ensure_design_sizings(pcb.design_names.len());
+    pcb.panel_sizing
+        .ensure_unit_positionings(pcb.units);
+
+    pcb
 }
 
 pub fn build_unit_to_design_index_mappping(
@@ -380,6 +393,9 @@ pub fn build_unit_to_design_index_mappping(
 }
 
 /// A transform matrix that can be applied to a placement to position it on the unit.
+///
+/// See [`PlacementTransformer`] for the stages.
+///
 /// transform order:
 /// DesignSizing::placement_offset,
 /// -DesignSizing::origin,
@@ -387,8 +403,8 @@ pub fn build_unit_to_design_index_mappping(
 /// unit_offset,
 /// +DesignSizing::origin,
 /// panel centering,
-/// orientation rotation,
 /// orientation flip,
+/// orientation rotation,
 /// panel un-centering,
 /// shift to positive coordinates
 #[derive(Debug)]
@@ -406,124 +422,19 @@ pub struct PcbUnitTransform {
 }
 
 impl PcbUnitTransform {
-    #[rustfmt::skip]
-    pub fn to_matrix(&self) -> Matrix3<f64> {
-        // Start with identity matrix
-        let mut matrix = Matrix3::identity();
-
-        // Translate placement offset (which for an EDA offset of 10,10 would be specifed at -10,-10, no need to invert the sign.
-        // Note: /PLACEMENT/ offset *not* /GERBER/ offset here.
-        let translate_offset = Matrix3::new(
-            1.0, 0.0, self.design_sizing.placement_offset.x,
-            0.0, 1.0, self.design_sizing.placement_offset.y,
-            0.0, 0.0, 1.0,
-        );
-        matrix = translate_offset * matrix;
-
-        // Translate to design origin (negative of design_sizing.origin)
-        let translation_to_origin = Matrix3::new(
-            1.0, 0.0, -self.design_sizing.origin.x,
-            0.0, 1.0, -self.design_sizing.origin.y,
-            0.0, 0.0, 1.0,
-        );
-        matrix = translation_to_origin * matrix;
-
-        // Apply unit rotation (anti-clockwise positive degrees)
-        let unit_rotation_radians = self.unit_rotation.to_f64().unwrap().to_radians();
-
-        let cos_theta = unit_rotation_radians.cos();
-        let sin_theta = unit_rotation_radians.sin();
-        let rotation = Matrix3::new(
-            cos_theta, -sin_theta, 0.0,
-            sin_theta, cos_theta, 0.0,
-            0.0, 0.0, 1.0
-        );
-        matrix = rotation * matrix;
-
-        // Apply unit offset
-        let unit_offset = Matrix3::new(
-            1.0,0.0,self.unit_offset.x,
-            0.0,1.0,self.unit_offset.y,
-            0.0,0.0,1.0,
-        );
-        matrix = unit_offset * matrix;
-
-        // Translate back from origin (positive of design_sizing.origin)
-        let translation_from_origin = Matrix3::new(
-            1.0, 0.0, self.design_sizing.origin.x,
-            0.0, 1.0, self.design_sizing.origin.y,
-            0.0, 0.0, 1.0,
-        );
-        matrix = translation_from_origin * matrix;
-
-        // Translate to panel center
-        let panel_center: Vector2<f64> = self.panel_size / 2.0;
-        let translation_to_panel_origin = Matrix3::new_translation(&-panel_center);
-        let translation_from_panel_origin = Matrix3::new_translation(&panel_center);
-        matrix = translation_to_panel_origin * matrix;
-
-        // Apply orientation flipping
-        if !matches!(self.orientation.flip, PcbAssemblyFlip::None) {
-            let flip_matrix: Matrix3<f64> = self.orientation.flip.into();
-            trace!("flip_matrix: {:?}", flip_matrix);
-            matrix = flip_matrix * matrix;
-        }
-
-        // Apply orientation rotation (anti-clockwise positive degrees)
-        let orientation_radians = self.orientation.rotation.to_f64().unwrap().to_radians();
-        let cos_theta = orientation_radians.cos();
-        let sin_theta = orientation_radians.sin();
-        let orientation_rotation = Matrix3::new(
-            cos_theta, -sin_theta, 0.0,
-            sin_theta, cos_theta, 0.0,
-            0.0, 0.0, 1.0
-        );
-        matrix = orientation_rotation * matrix;
-
-        // Translate from panel center
-        matrix = translation_from_panel_origin * matrix;
-
-        // Calculate bounding box for the rotated panel to ensure positive coordinates
-        let panel_corners = [
-            Vector2::new(0.0, 0.0),
-            Vector2::new(self.panel_size.x, 0.0),
-            Vector2::new(self.panel_size.x, self.panel_size.y),
-            Vector2::new(0.0, self.panel_size.y),
-        ];
-        trace!("panel_corners: {:?}", panel_corners);
-
-        // Rotate the corners using our current transformation matrix 
-        // (limiting to just the rotation part to avoid double-translation)
-        let rotated_corners: Vec<Vector3<f64>> = panel_corners
-            .iter()
-            .map(|&corner| {
-                // Apply only the rotation part of the matrix
-                let rotation_only = orientation_rotation *
-                    translation_to_panel_origin *
-                    Vector3::new(corner.x, corner.y, 1.0);
-
-                // Apply translation back from panel center
-                translation_from_panel_origin * rotation_only
-            })
-            .collect();
-
-        trace!("rotated_corners: {:?}", rotated_corners);
-
-        // Find minimum x and y to shift by
-
-        let shift = Vector2::new(
-            rotated_corners.iter().map(|p| p.x).fold(f64::INFINITY, f64::min),
-            rotated_corners.iter().map(|p| p.y).fold(f64::INFINITY, f64::min)
-        );
-        trace!("shift: {:?}", shift);
+    pub fn to_transformer(&self) -> PlacementTransformer {
+        PlacementTransformer::new(
+            &self.design_sizing,
+            self.unit_offset,
+            self.unit_rotation,
+            self.orientation.flip,
+            self.orientation.rotation,
+            self.panel_size,
+        )
+    }
 
-        // Add translation to shift all points to positive quadrant
-        let shift_to_positive = Matrix3::new(
-            1.0, 0.0, -shift.x,
-            0.0, 1.0, -shift.y,
-            0.0, 0.0, 1.0
-        );
-        matrix = shift_to_positive * matrix;
+    pub fn to_matrix(&self) -> Matrix3<f64> {
+        let matrix = self.to_transformer().to_matrix();
 
         trace!("PcbUnitTransform {:?}, matrix: {:?}", self, matrix);
 
@@ -531,50 +442,22 @@ pub fn to_matrix(&self) -> Matrix3<f64> {
     }
 
     pub fn apply_to_placement_matrix(&self, placement: &Placement) -> UnitPlacementPosition {
-        // Get the transformation matrix
-        let transform_matrix = self.to_matrix();
-
-        // Convert placement position to homogeneous coordinates (x, y, 1)
-        let position = nalgebra::Vector3::new(
-            placement.x.to_f64().unwrap_or(0.0),
-            placement.y.to_f64().unwrap_or(0.0),
-            1.0,
-        );
-
-        // Apply the transformation matrix to the position
-        let transformed_position = transform_matrix * position;
-
-        //
-        // Handle rotation
-        //
-
-        let flipped_rotation = match &self.orientation.flip {
-            PcbAssemblyFlip::None => placement.rotation,
-            PcbAssemblyFlip::Pitch => dec!(180.0) - placement.rotation,
-            PcbAssemblyFlip::Roll => dec!(360.0) - placement.rotation,
-        };
-
-        let new_rotation = flipped_rotation + self.orientation.rotation + self.unit_rotation;
-
-        // Normalize rotation to be within -180 to 180 degrees
-        let normalized_rotation = normalize_angle_deg_signed_decimal(new_rotation).normalize();
+        let MachinePlacementPosition {
+            x,
+            y,
+            rotation,
+        } = self
+            .to_transformer()
+            .apply_to_placement(placement);
 
         trace!(
-            "placement_rotation: {}, self.orientation.rotation: {}, unit_rotation: {}",
+            "placement_rotation: {}, self.orientation.rotation: {}, unit_rotation: {}, rotation: {}",
             placement.rotation,
             self.orientation.rotation,
-            self.unit_rotation
-        );
-        trace!(
-            "new_rotation: {}, normalized rotation: {}",
-            new_rotation,
-            normalized_rotation
+            self.unit_rotation,
+            rotation
         );
 
-        let x = Decimal::try_from(transformed_position.x).unwrap_or_default();
-        let y = Decimal::try_from(transformed_position.y).unwrap_or_default();
-        let rotation = Decimal::try_from(normalized_rotation).unwrap_or_default();
-
         UnitPlacementPosition {
             x,
             y,
@@ -646,89 +529,3 @@ pub struct PcbSideAssemblyOrientation {
     /// In degrees, counter-clockwise positive
     pub rotation: Decimal,
 }
-
-/// How to 'flip' a physical PCB.
-///
-/// We specifically do NOT use terms like 'mirror' or 'reflect' here because that is not what happens to the physical
-/// PCB in the real world.
-///
-/// Additionally, since there are inconsistencies between 'flip-along', 'flip-over', 'flip-about' and 'mirroring' and
-/// 'reflection' we use 'pitch' and 'roll' since they are unambiguous.
-///
-/// For clarity, this table describes the various different terminology used to describe flipping operations and how they relate to each other.
-///
-/// | Term           | Flip-over/about | Flip-along | Mirrored/Reflected axis | Matrix                             | Hold           | Result                       | Coordinate in | Coordinate out |
-/// | -------------- | --------------- | ---------- | ----------------------- | ---------------------------------- |--------------- | ---------------------------- | ------------- | -------------- |
-/// | Pitch flip     | x               | y          | y                       | [( 1, 0, 0), (0,-1, 0), (0, 0, 1)] | Left and right | Top edge becomes bottom edge | (1,1)         | ( 1,-1)        |
-/// | Roll flip      | y               | x          | x                       | [(-1, 0, 0), (0, 1, 0), (0, 0, 1)] | Top and bottom | Left edge becomes right edge | (1,1)         | (-1, 1)        |
-#[derive(Debug, serde::Serialize, serde::Deserialize, Copy, Clone, PartialEq, Eq)]
-pub enum PcbAssemblyFlip {
-    None,
-    Pitch, // Flip about the X axis (negates Y)
-    Roll,  // Flip about the Y axis (negates X)
-}
-
-impl From<PcbAssemblyFlip> for Matrix3<f64> {
-    fn from(flip: PcbAssemblyFlip) -> Self {
-        match flip {
-            PcbAssemblyFlip::None => Matrix3::identity(),
-            PcbAssemblyFlip::Pitch => Matrix3::new(1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 1.0),
-            PcbAssemblyFlip::Roll => Matrix3::new(-1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0),
-        }
-    }
-}
-
-#[cfg(test)]
-mod pcb_assembly_flip_tests {
-    use nalgebra::{Matrix3, Point2, Vector3};
-    use rstest::rstest;
-
-    use super::*;
-
-    #[rstest]
-    #[case(
-        PcbAssemblyFlip::None,
-        Matrix3::identity(),
-        Point2::new(1.0, 2.0),
-        Point2::new(1.0, 2.0)
-    )]
-    #[case(
-        PcbAssemblyFlip::Roll,
-        Matrix3::new(-1.0, 0.0, 0.0,
-                      0.0, 1.0, 0.0,
-                      0.0, 0.0, 1.0),
-        Point2::new(1.0, 2.0),
-        Point2::new(-1.0, 2.0)
-    )]
-    #[case(
-        PcbAssemblyFlip::Pitch,
-        Matrix3::new(1.0, 0.0, 0.0,
-                     0.0, -1.0, 0.0,
-                     0.0, 0.0, 1.0),
-        Point2::new(1.0, 2.0),
-        Point2::new(1.0, -2.0)
-    )]
-    fn test_pcb_assembly_flip_matrix(
-        #[case] flip: PcbAssemblyFlip,
-        #[case] expected_matrix: Matrix3<f64>,
-        #[case] input: Point2<f64>,
-        #[case] expected_output: Point2<f64>,
-    ) {
-        let flip_matrix: Matrix3<f64> = flip.into();
-        assert_eq!(flip_matrix, expected_matrix);
-
-        let input_vec = Vector3::new(input.x, input.y, 1.0);
-        let result = flip_matrix * input_vec;
-        let result_point = Point2::new(result.x, result.y);
-
-        assert!(
-            (result_point.coords - expected_output.coords)
-                .abs()
-                .max()
-                < 1e-9,
-            "expected {:?}, got {:?}",
-            expected_output,
-            result_point
-        );
-    }
-}
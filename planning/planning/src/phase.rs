@@ -4,14 +4,17 @@
 use pnp::pcb::PcbSide;
 use pnp::reference::Reference;
 use thiserror::Error;
+use time::serde::rfc3339;
+use time::OffsetDateTime;
 
 use crate::placement::PlacementSortingItem;
 #[cfg(test)]
 use crate::process::TestTaskState;
 use crate::process::{
-    can_modify_operation, can_modify_task, AutomatedSolderingTaskState, LoadPcbsTaskState, ManualSolderingTaskState,
-    OperationReference, OperationState, OperationStatus, PlacementTaskState, ProcessDefinition, ProcessReference,
-    SerializableTaskState, TaskReference,
+    can_modify_operation, can_modify_task, ApplySolderPasteTaskState, AutomatedSolderingTaskState,
+    CustomTaskDefinition, CustomTaskState, LoadPcbsTaskState, ManualSolderingTaskState, OperationReference,
+    OperationState, OperationStatus, PlacementTaskState, ProcessDefinition, ProcessReference, SerializableTaskState,
+    TaskReference,
 };
 
 pub type PhaseReference = Reference;
@@ -29,6 +32,19 @@ pub struct Phase {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
     pub placement_orderings: Vec<PlacementSortingItem>,
+
+    /// The scheduled assembly window, used to check the floor life of moisture sensitive parts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub assembly_window: Option<AssemblyWindow>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AssemblyWindow {
+    #[serde(with = "rfc3339")]
+    pub start: OffsetDateTime,
+    #[serde(with = "rfc3339")]
+    pub end: OffsetDateTime,
 }
 
 #[derive(Error, Debug)]
@@ -44,6 +60,8 @@ pub enum PhaseError {
     // PrecedingOperationIncomplete(Reference, OperationReference),
     #[error("Phase in use. phase: '{0:}'")]
     PhaseInUse(Reference),
+    #[error("Duplicate phase. phase: '{0:}'")]
+    DuplicatePhase(Reference),
     #[error("Unknown process. process: '{0:}'")]
     UnknownProcess(ProcessReference),
 }
@@ -81,7 +99,7 @@ pub fn from_process(process: &ProcessDefinition) -> Self {
                     .tasks
                     .iter()
                     .map(|task_reference| {
-                        let task_state = make_task_state(task_reference);
+                        let task_state = make_task_state(task_reference, &process_operation.custom_tasks);
 
                         (task_reference.clone(), task_state)
                     })
@@ -181,9 +199,19 @@ pub enum PhaseStatus {
     Abandoned,
 }
 
-pub(crate) fn make_task_state(task_reference: &TaskReference) -> Box<dyn SerializableTaskState> {
-    let task_state = if task_reference.eq(&TaskReference::from_raw_str("core::load_pcbs")) {
+pub(crate) fn make_task_state(
+    task_reference: &TaskReference,
+    custom_tasks: &[CustomTaskDefinition],
+) -> Box<dyn SerializableTaskState> {
+    let task_state = if custom_tasks
+        .iter()
+        .any(|custom_task| custom_task.reference.eq(task_reference))
+    {
+        Box::new(CustomTaskState::default()) as Box<dyn SerializableTaskState>
+    } else if task_reference.eq(&TaskReference::from_raw_str("core::load_pcbs")) {
         Box::new(LoadPcbsTaskState::default()) as Box<dyn SerializableTaskState>
+    } else if task_reference.eq(&TaskReference::from_raw_str("core::apply_solder_paste")) {
+        Box::new(ApplySolderPasteTaskState::default()) as Box<dyn SerializableTaskState>
     } else if task_reference.eq(&TaskReference::from_raw_str("core::place_components")) {
         Box::new(PlacementTaskState::default()) as Box<dyn SerializableTaskState>
     } else if task_reference.eq(&TaskReference::from_raw_str("core::automated_soldering")) {
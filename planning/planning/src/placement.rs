@@ -1,9 +1,14 @@
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 
 use pnp::object_path::ObjectPath;
+use pnp::panel::DesignSizing;
 use pnp::part::Part;
-use pnp::placement::Placement;
+use pnp::placement::{Placement, RefDes};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 use thiserror::Error;
@@ -30,9 +35,33 @@ pub struct PlacementState {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub phase: Option<PhaseReference>,
+
+    /// The result of the most recent inspection, cleared when the placement status changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub inspection: Option<PlacementInspection>,
+
+    /// Operator notes, e.g. rework instructions.  Unlike the inspection notes, these are kept when the placement
+    /// status changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub notes: Option<String>,
+
+    /// References to attached files, e.g. photos, relative to the project directory or absolute.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub attachments: Vec<PathBuf>,
 }
 
-#[cfg(test)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct PlacementInspection {
+    pub pass: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+#[cfg(any(test, feature = "testing"))]
 impl Default for PlacementState {
     fn default() -> Self {
         Self {
@@ -42,6 +71,9 @@ fn default() -> Self {
             operation_status: PlacementStatus::Pending,
             project_status: ProjectPlacementStatus::Used,
             phase: None,
+            inspection: None,
+            notes: None,
+            attachments: vec![],
         }
     }
 }
@@ -87,6 +119,8 @@ pub enum PlacementSortingMode {
     //Description,
     FeederReference,
     Height,
+    /// The assigned nozzle, groups placements by nozzle to minimize nozzle changes
+    Nozzle,
     Part,
     /// The pcb instance
     Pcb,
@@ -107,6 +141,7 @@ fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
             //Self::Description => write!(f, "Description"),
             Self::FeederReference => write!(f, "FeederReference"),
             Self::Height => write!(f, "Height"),
+            Self::Nozzle => write!(f, "Nozzle"),
             Self::Part => write!(f, "Part"),
             Self::Pcb => write!(f, "Pcb"),
             Self::PcbUnit => write!(f, "PcbUnit"),
@@ -162,6 +197,126 @@ pub fn build_unique_parts_from_design_variant_placement_map(
     unique_parts
 }
 
+/// Problems with placements, as exported by an EDA tool, that are imported anyway.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq, Eq)]
+pub enum PlacementWarning {
+    /// Placements are identified by their reference designator, so only one of the placements is used.
+    DuplicateRefDes {
+        ref_des: RefDes,
+        count: usize,
+    },
+    /// Usually indicates a placement that was not positioned in the EDA tool.
+    AtOrigin {
+        ref_des: RefDes,
+    },
+    /// The position, after applying the design's placement offset, is outside the design's bounds, which are centered on
+    /// the design's origin.
+    OutsideDesignBounds {
+        ref_des: RefDes,
+        x: Decimal,
+        y: Decimal,
+    },
+    RotationOutOfRange {
+        ref_des: RefDes,
+        rotation: Decimal,
+    },
+}
+
+impl Display for PlacementWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlacementWarning::DuplicateRefDes {
+                ref_des,
+                count,
+            } => write!(
+                f,
+                "Duplicate reference designator. ref_des: {}, count: {}",
+                ref_des, count
+            ),
+            PlacementWarning::AtOrigin {
+                ref_des,
+            } => write!(f, "Placement at origin (0,0). ref_des: {}", ref_des),
+            PlacementWarning::OutsideDesignBounds {
+                ref_des,
+                x,
+                y,
+            } => write!(
+                f,
+                "Placement outside design bounds. ref_des: {}, x: {}, y: {}",
+                ref_des, x, y
+            ),
+            PlacementWarning::RotationOutOfRange {
+                ref_des,
+                rotation,
+            } => write!(
+                f,
+                "Rotation out of range (+/-360). ref_des: {}, rotation: {}",
+                ref_des, rotation
+            ),
+        }
+    }
+}
+
+/// Checks the placements of a design variant.
+///
+/// The bounds are only checked if the design has a size, i.e. the design sizing has been configured, see
+/// [`DesignSizing::origin`].
+pub fn validate_placements(placements: &[Placement], design_sizing: Option<&DesignSizing>) -> Vec<PlacementWarning> {
+    let mut warnings = vec![];
+
+    let mut ref_des_counts: BTreeMap<&RefDes, usize> = BTreeMap::new();
+    for placement in placements {
+        *ref_des_counts
+            .entry(&placement.ref_des)
+            .or_default() += 1;
+    }
+    warnings.extend(
+        ref_des_counts
+            .into_iter()
+            .filter(|(_ref_des, count)| *count > 1)
+            .map(|(ref_des, count)| PlacementWarning::DuplicateRefDes {
+                ref_des: ref_des.clone(),
+                count,
+            }),
+    );
+
+    let bounds = design_sizing.filter(|design_sizing| design_sizing.size.x > 0.0 && design_sizing.size.y > 0.0);
+
+    for placement in placements {
+        let ref_des = &placement.ref_des;
+
+        if placement.x.is_zero() && placement.y.is_zero() {
+            warnings.push(PlacementWarning::AtOrigin {
+                ref_des: ref_des.clone(),
+            });
+        } else if let Some(design_sizing) = bounds {
+            let x = placement.x.to_f64().unwrap_or_default() + design_sizing.placement_offset.x;
+            let y = placement.y.to_f64().unwrap_or_default() + design_sizing.placement_offset.y;
+
+            let half_size = design_sizing.size / 2.0;
+            let min = design_sizing.origin - half_size;
+            let max = design_sizing.origin + half_size;
+
+            if !(min.x..=max.x).contains(&x) || !(min.y..=max.y).contains(&y) {
+                warnings.push(PlacementWarning::OutsideDesignBounds {
+                    ref_des: ref_des.clone(),
+                    x: placement.x,
+                    y: placement.y,
+                });
+            }
+        }
+
+        if placement.rotation.abs() > dec!(360) {
+            warnings.push(PlacementWarning::RotationOutOfRange {
+                ref_des: ref_des.clone(),
+                rotation: placement.rotation,
+            });
+        }
+    }
+
+    warnings
+}
+
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PlacementStatus {
     Pending,
@@ -190,6 +345,12 @@ pub enum PlacementOperation {
     Place,
     Skip,
     Reset,
+    /// Only applicable to placed placements, a failed inspection returns the placement to pending so it can be
+    /// re-worked.
+    Inspected {
+        pass: bool,
+        notes: Option<String>,
+    },
 }
 
 impl Display for PlacementOperation {
@@ -198,6 +359,77 @@ fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
             PlacementOperation::Place => f.write_str("Place"),
             PlacementOperation::Skip => f.write_str("Skip"),
             PlacementOperation::Reset => f.write_str("Reset"),
+            PlacementOperation::Inspected {
+                pass: true, ..
+            } => f.write_str("Inspected (pass)"),
+            PlacementOperation::Inspected {
+                pass: false, ..
+            } => f.write_str("Inspected (fail)"),
         }
     }
 }
+
+#[cfg(test)]
+mod placement_tests {
+    use nalgebra::Vector2;
+
+    use super::*;
+
+    fn placement(ref_des: &str, x: Decimal, y: Decimal, rotation: Decimal) -> Placement {
+        Placement {
+            ref_des: RefDes::from(ref_des),
+            x,
+            y,
+            rotation,
+            ..Placement::default()
+        }
+    }
+
+    #[test]
+    pub fn validate() {
+        // given a 10x10 design, with coordinates (0,0) - (10,10), exported with an offset of (10,5)
+        let design_sizing = DesignSizing {
+            size: Vector2::new(10.0, 10.0),
+            placement_offset: Vector2::new(-10.0, -5.0),
+            origin: Vector2::new(5.0, 5.0),
+            ..DesignSizing::default()
+        };
+
+        let placements = [
+            placement("R1", dec!(15), dec!(10), dec!(90)),
+            placement("R2", dec!(0), dec!(0), dec!(0)),
+            placement("R3", dec!(25), dec!(10), dec!(-720)),
+            placement("R1", dec!(12), dec!(6), dec!(0)),
+        ];
+
+        // when
+        let warnings = validate_placements(&placements, Some(&design_sizing));
+
+        // then
+        assert_eq!(warnings, vec![
+            PlacementWarning::DuplicateRefDes {
+                ref_des: RefDes::from("R1"),
+                count: 2,
+            },
+            PlacementWarning::AtOrigin {
+                ref_des: RefDes::from("R2"),
+            },
+            PlacementWarning::OutsideDesignBounds {
+                ref_des: RefDes::from("R3"),
+                x: dec!(25),
+                y: dec!(10),
+            },
+            PlacementWarning::RotationOutOfRange {
+                ref_des: RefDes::from("R3"),
+                rotation: dec!(-720),
+            },
+        ]);
+
+        // and the bounds are not checked when the design sizing has not been configured
+        let warnings = validate_placements(&placements[2..3], Some(&DesignSizing::default()));
+        assert_eq!(warnings, vec![PlacementWarning::RotationOutOfRange {
+            ref_des: RefDes::from("R3"),
+            rotation: dec!(-720),
+        }]);
+    }
+}
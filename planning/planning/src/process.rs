@@ -15,18 +15,26 @@
 
 // FIXME there's nothing currently preventing a process from being defined with more than one task where [`TaskState::requires_placements`] returns true
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::ops::ControlFlow;
+use std::str::FromStr;
 
 use dyn_clone::DynClone;
 use dyn_eq::DynEq;
 use indexmap::IndexMap;
+use itertools::Itertools;
+use pnp::load_out::{find_load_out_item_by_part_or_alias, FeederBank, LoadOutItem};
+use pnp::object_path::ObjectPath;
+use pnp::part::{Part, PartAliases};
 use pnp::reference::Reference;
+use rust_decimal::Decimal;
 use thiserror::Error;
 use util::dynamic::as_any::AsAny;
 
+use crate::nozzle::NozzleConfiguration;
 use crate::phase::PhaseState;
-use crate::placement::PlacementStatus;
+use crate::placement::{PlacementState, PlacementStatus};
 
 /// e.g. `manual` or `pnp`
 pub type ProcessReference = Reference;
@@ -44,6 +52,16 @@ pub struct ProcessDefinition {
 
     /// examples: `["core::..."]`
     pub rules: Vec<ProcessRuleReference>,
+
+    /// The nozzles of the machine used by the process, empty for processes that do not use a machine.
+    #[serde(skip_serializing_if = "NozzleConfiguration::is_empty")]
+    #[serde(default)]
+    pub nozzles: NozzleConfiguration,
+
+    /// The feeder banks of the machine used by the process, see [`pnp::load_out::LoadOutItem::reference`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub feeder_banks: Vec<FeederBank>,
 }
 
 /// A user defined (or pre-configured) process operation reference
@@ -59,6 +77,170 @@ pub struct OperationDefinition {
     /// e.g. `["core::place_components", "core::manual_solder"]`
     /// @see [`OperationState`]
     pub tasks: Vec<TaskReference>,
+
+    /// The definitions of the user-defined tasks in `tasks`, e.g. `"shop::stencil_tension_check"`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub custom_tasks: Vec<CustomTaskDefinition>,
+}
+
+impl OperationDefinition {
+    pub fn find_custom_task(&self, task_reference: &TaskReference) -> Option<&CustomTaskDefinition> {
+        self.custom_tasks
+            .iter()
+            .find(|custom_task| custom_task.reference.eq(task_reference))
+    }
+}
+
+/// The built-in tasks, see [`crate::phase::make_task_state`].
+pub const CORE_TASKS: [&str; 5] = [
+    "core::load_pcbs",
+    "core::apply_solder_paste",
+    "core::place_components",
+    "core::automated_soldering",
+    "core::manual_soldering",
+];
+
+/// A user-defined task, the values of the parameters are recorded when the task is completed.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct CustomTaskDefinition {
+    /// Must not be one of the [`CORE_TASKS`].
+    pub reference: TaskReference,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub parameters: Vec<TaskParameterDefinition>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct TaskParameterDefinition {
+    /// e.g. `oven_profile_id`
+    pub name: String,
+    pub kind: TaskParameterKind,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub enum TaskParameterKind {
+    Checkbox,
+    /// The range is inclusive, when given.
+    Numeric {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        min: Option<Decimal>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        max: Option<Decimal>,
+    },
+    Text,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub enum TaskParameterValue {
+    Checkbox(bool),
+    Numeric(Decimal),
+    Text(String),
+}
+
+impl Display for TaskParameterValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskParameterValue::Checkbox(value) => write!(f, "{}", value),
+            TaskParameterValue::Numeric(value) => write!(f, "{}", value),
+            TaskParameterValue::Text(value) => f.write_str(value),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum TaskParameterError {
+    #[error("Unknown parameter. task: {task}, parameter: '{name}'")]
+    UnknownParameter { task: TaskReference, name: String },
+    #[error("Missing parameter value. task: {task}, parameter: '{name}'")]
+    MissingValue { task: TaskReference, name: String },
+    #[error("Invalid parameter value. task: {task}, parameter: '{name}', value: '{value}', kind: {kind:?}")]
+    InvalidValue {
+        task: TaskReference,
+        name: String,
+        value: TaskParameterValue,
+        kind: TaskParameterKind,
+    },
+    #[error("Parameter values can only be recorded when completing a custom task. task: {task}")]
+    UnexpectedValues { task: TaskReference },
+}
+
+impl TaskParameterKind {
+    /// Text values are converted to the kind of the parameter, e.g. values given on the command line.
+    fn coerce(&self, value: &TaskParameterValue) -> Option<TaskParameterValue> {
+        match (self, value) {
+            (TaskParameterKind::Checkbox, TaskParameterValue::Checkbox(_)) => Some(value.clone()),
+            (TaskParameterKind::Checkbox, TaskParameterValue::Text(text)) => text
+                .parse::<bool>()
+                .ok()
+                .map(TaskParameterValue::Checkbox),
+            (
+                TaskParameterKind::Numeric {
+                    min,
+                    max,
+                },
+                TaskParameterValue::Numeric(_) | TaskParameterValue::Text(_),
+            ) => {
+                let number = match value {
+                    TaskParameterValue::Numeric(number) => *number,
+                    _ => Decimal::from_str(&value.to_string()).ok()?,
+                };
+
+                let in_range = min.is_none_or(|min| number >= min) && max.is_none_or(|max| number <= max);
+
+                in_range.then_some(TaskParameterValue::Numeric(number))
+            }
+            (TaskParameterKind::Text, _) => Some(TaskParameterValue::Text(value.to_string())),
+            _ => None,
+        }
+    }
+}
+
+impl CustomTaskDefinition {
+    /// Every parameter requires a value, the returned values are of the kind of each parameter.
+    pub fn validate_values(
+        &self,
+        values: &BTreeMap<String, TaskParameterValue>,
+    ) -> Result<BTreeMap<String, TaskParameterValue>, TaskParameterError> {
+        if let Some(name) = values.keys().find(|name| {
+            !self
+                .parameters
+                .iter()
+                .any(|parameter| parameter.name.eq(*name))
+        }) {
+            return Err(TaskParameterError::UnknownParameter {
+                task: self.reference.clone(),
+                name: name.clone(),
+            });
+        }
+
+        self.parameters
+            .iter()
+            .map(|parameter| {
+                let value = values
+                    .get(&parameter.name)
+                    .ok_or_else(|| TaskParameterError::MissingValue {
+                        task: self.reference.clone(),
+                        name: parameter.name.clone(),
+                    })?;
+
+                let value = parameter
+                    .kind
+                    .coerce(value)
+                    .ok_or_else(|| TaskParameterError::InvalidValue {
+                        task: self.reference.clone(),
+                        name: parameter.name.clone(),
+                        value: value.clone(),
+                        kind: parameter.kind.clone(),
+                    })?;
+
+                Ok((parameter.name.clone(), value))
+            })
+            .collect()
+    }
 }
 
 /// a namespaced operation task reference.  e.g. "core::place_components"
@@ -71,6 +253,47 @@ impl ProcessDefinition {
     pub fn has_rule(&self, rule: &ProcessRuleReference) -> bool {
         self.rules.contains(rule)
     }
+
+    /// Every task must be a core task or a custom task of the operation, and custom tasks cannot replace core tasks.
+    pub fn ensure_tasks_defined(&self) -> Result<(), ProcessError> {
+        for operation in self.operations.iter() {
+            if let Some(custom_task) = operation
+                .custom_tasks
+                .iter()
+                .find(|custom_task| is_core_task(&custom_task.reference))
+            {
+                return Err(ProcessError::CoreTaskRedefined {
+                    process_reference: self.reference.clone(),
+                    task_reference: custom_task.reference.clone(),
+                });
+            }
+
+            if let Some(task_reference) = operation
+                .tasks
+                .iter()
+                .find(|task_reference| {
+                    !is_core_task(task_reference)
+                        && operation
+                            .find_custom_task(task_reference)
+                            .is_none()
+                })
+            {
+                return Err(ProcessError::UndefinedTask {
+                    process_reference: self.reference.clone(),
+                    operation_reference: operation.reference.clone(),
+                    task_reference: task_reference.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn is_core_task(task_reference: &TaskReference) -> bool {
+    CORE_TASKS
+        .iter()
+        .any(|core_task| task_reference.eq(&TaskReference::from_raw_str(core_task)))
 }
 
 #[derive(Error, Debug)]
@@ -88,6 +311,233 @@ pub enum ProcessError {
     DuplicateProcessReference { process_reference: ProcessReference },
     #[error("Unknown pre-set process. available-presets: {:?}, pre-set: {}", presets, preset)]
     UnknownPreset { presets: Vec<String>, preset: String },
+    #[error(
+        "Undefined task. process: '{}', operation: '{}', task: '{}'",
+        process_reference,
+        operation_reference,
+        task_reference
+    )]
+    UndefinedTask {
+        process_reference: ProcessReference,
+        operation_reference: OperationReference,
+        task_reference: TaskReference,
+    },
+    #[error(
+        "Custom tasks cannot redefine core tasks. process: '{}', task: '{}'",
+        process_reference,
+        task_reference
+    )]
+    CoreTaskRedefined {
+        process_reference: ProcessReference,
+        task_reference: TaskReference,
+    },
+}
+
+const PLACE_COMPONENTS_TASK: &str = "core::place_components";
+const SOLDERING_TASKS: [&str; 2] = ["core::automated_soldering", "core::manual_soldering"];
+
+/// The rules that a process can enforce, see [`ProcessDefinition::rules`].
+///
+/// Rules are preconditions of task actions, they are checked before an action is applied, see [`check_rules`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessRule {
+    /// `core::unique_feeder_references`, a feeder is assigned to at most one load-out item.
+    ///
+    /// Checked when placing components starts.
+    UniqueFeederReferences,
+    /// `core::load_out_before_placement`, every part to be placed has a load-out item with a feeder assigned.
+    ///
+    /// Checked when placing components starts.
+    LoadOutBeforePlacement,
+    /// `core::placed_before_soldering`, every placement to be placed has been placed, skipped placements are
+    /// violations.
+    ///
+    /// Checked when soldering starts or completes.
+    PlacedBeforeSoldering,
+}
+
+impl ProcessRule {
+    pub const ALL: &'static [ProcessRule] = &[
+        ProcessRule::UniqueFeederReferences,
+        ProcessRule::LoadOutBeforePlacement,
+        ProcessRule::PlacedBeforeSoldering,
+    ];
+
+    pub fn reference(&self) -> ProcessRuleReference {
+        ProcessRuleReference::from_raw_str(match self {
+            ProcessRule::UniqueFeederReferences => "core::unique_feeder_references",
+            ProcessRule::LoadOutBeforePlacement => "core::load_out_before_placement",
+            ProcessRule::PlacedBeforeSoldering => "core::placed_before_soldering",
+        })
+    }
+
+    /// Returns `None` for unknown rules.
+    pub fn from_reference(reference: &ProcessRuleReference) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|rule| rule.reference().eq(reference))
+    }
+
+    fn applies_to(&self, task: &TaskReference, action: &TaskAction) -> bool {
+        match self {
+            ProcessRule::UniqueFeederReferences | ProcessRule::LoadOutBeforePlacement => {
+                task.eq(&TaskReference::from_raw_str(PLACE_COMPONENTS_TASK)) && matches!(action, TaskAction::Start)
+            }
+            ProcessRule::PlacedBeforeSoldering => {
+                SOLDERING_TASKS
+                    .iter()
+                    .any(|soldering_task| task.eq(&TaskReference::from_raw_str(soldering_task)))
+                    && matches!(action, TaskAction::Start | TaskAction::Complete)
+            }
+        }
+    }
+
+    /// Returns the reasons the rule is violated, empty if the rule is not violated.
+    fn check(&self, context: &RuleContext) -> Vec<String> {
+        match self {
+            ProcessRule::UniqueFeederReferences => context
+                .load_out_items
+                .iter()
+                .filter_map(|item| {
+                    item.reference
+                        .as_ref()
+                        .map(|feeder_reference| (feeder_reference, item))
+                })
+                .fold(
+                    BTreeMap::<_, Vec<_>>::new(),
+                    |mut items_by_feeder, (feeder_reference, item)| {
+                        items_by_feeder
+                            .entry(feeder_reference)
+                            .or_default()
+                            .push(item);
+                        items_by_feeder
+                    },
+                )
+                .into_iter()
+                .filter(|(_feeder_reference, items)| items.len() > 1)
+                .map(|(feeder_reference, items)| {
+                    format!(
+                        "Feeder assigned to multiple load-out items. feeder: {}, parts: [{}]",
+                        feeder_reference,
+                        items
+                            .iter()
+                            .map(|item| format!("{}:{}", item.manufacturer, item.mpn))
+                            .join(", ")
+                    )
+                })
+                .collect(),
+            ProcessRule::LoadOutBeforePlacement => context
+                .placements
+                .iter()
+                .filter(|(_object_path, state)| state.placement.place)
+                .map(|(_object_path, state)| &state.placement.part)
+                .collect::<BTreeSet<&Part>>()
+                .into_iter()
+                .filter_map(|part| {
+                    match find_load_out_item_by_part_or_alias(context.load_out_items, part, context.part_aliases) {
+                        None => Some(format!(
+                            "Part not in the load-out. manufacturer: {}, mpn: {}",
+                            part.manufacturer, part.mpn
+                        )),
+                        Some(item) if item.reference.is_none() => Some(format!(
+                            "No feeder assigned. manufacturer: {}, mpn: {}",
+                            part.manufacturer, part.mpn
+                        )),
+                        Some(_) => None,
+                    }
+                })
+                .collect(),
+            ProcessRule::PlacedBeforeSoldering => context
+                .placements
+                .iter()
+                .filter(|(_object_path, state)| {
+                    state.placement.place && state.operation_status != PlacementStatus::Placed
+                })
+                .map(|(object_path, state)| {
+                    format!(
+                        "Placement not placed. object_path: {}, status: {}",
+                        object_path, state.operation_status
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The state the rules are checked against.
+pub struct RuleContext<'a> {
+    pub task: &'a TaskReference,
+    pub action: &'a TaskAction,
+    /// The placements of the phase, only the placements of the unit when the action is for a single unit.
+    pub placements: &'a [(&'a ObjectPath, &'a PlacementState)],
+    /// The load-out of the phase.
+    pub load_out_items: &'a [LoadOutItem],
+    pub part_aliases: &'a PartAliases,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RuleViolation {
+    pub rule: ProcessRuleReference,
+    pub reasons: Vec<String>,
+}
+
+impl Display for RuleViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: [{}]", self.rule, self.reasons.join("; "))
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ProcessRuleError {
+    #[error(
+        "Process rules violated, the rules can be overridden. task: {}, action: {:?}, violations: [{}]",
+        task,
+        action,
+        violations.iter().join(", ")
+    )]
+    RulesViolated {
+        task: TaskReference,
+        action: TaskAction,
+        violations: Vec<RuleViolation>,
+    },
+}
+
+/// Checks the rules of the process that apply to the task action, unknown rules are ignored.
+///
+/// Violations of the overridden rules do not prevent the action, they are returned so that they can be reported.
+pub fn check_rules(
+    process: &ProcessDefinition,
+    context: &RuleContext,
+    overridden_rules: &[ProcessRuleReference],
+) -> Result<Vec<RuleViolation>, ProcessRuleError> {
+    let (overridden, violations): (Vec<_>, Vec<_>) = process
+        .rules
+        .iter()
+        .filter_map(ProcessRule::from_reference)
+        .filter(|rule| rule.applies_to(context.task, context.action))
+        .filter_map(|rule| {
+            let reasons = rule.check(context);
+            if reasons.is_empty() {
+                return None;
+            }
+
+            Some(RuleViolation {
+                rule: rule.reference(),
+                reasons,
+            })
+        })
+        .partition(|violation| overridden_rules.contains(&violation.rule));
+
+    if !violations.is_empty() {
+        return Err(ProcessRuleError::RulesViolated {
+            task: context.task.clone(),
+            action: context.action.clone(),
+            violations,
+        });
+    }
+
+    Ok(overridden)
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone)]
@@ -191,6 +641,282 @@ fn status(#[case] task_statuses: Vec<TaskStatus>, #[case] expected_result: Opera
     }
 }
 
+#[cfg(test)]
+mod process_rule_tests {
+    use std::str::FromStr;
+
+    use pnp::load_out::LoadOutItem;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::{Part, PartAliases};
+    use pnp::placement::Placement;
+    use pnp::reference::Reference;
+
+    use crate::placement::{PlacementState, PlacementStatus};
+    use crate::process::{
+        check_rules, ProcessDefinition, ProcessRule, ProcessRuleError, RuleContext, RuleViolation, TaskAction,
+        TaskReference,
+    };
+
+    fn process(rules: &[ProcessRule]) -> ProcessDefinition {
+        ProcessDefinition {
+            reference: Reference::from_raw_str("pnp"),
+            operations: vec![],
+            rules: rules
+                .iter()
+                .map(ProcessRule::reference)
+                .collect(),
+            nozzles: Default::default(),
+            feeder_banks: vec![],
+        }
+    }
+
+    fn placement_state(operation_status: PlacementStatus) -> PlacementState {
+        PlacementState {
+            unit_path: ObjectPath::from_str("pcb=1::unit=1").unwrap(),
+            placement: Placement {
+                part: Part::new("MFR1".to_string(), "PART1".to_string()),
+                ..Placement::default()
+            },
+            operation_status,
+            ..PlacementState::default()
+        }
+    }
+
+    #[test]
+    pub fn feeder_assigned_to_multiple_load_out_items() {
+        // given
+        let process = process(&[ProcessRule::UniqueFeederReferences]);
+        let load_out_items = vec![
+            LoadOutItem::new(
+                Some(Reference::from_raw_str("FEEDER_1")),
+                "MFR1".to_string(),
+                "PART1".to_string(),
+            ),
+            LoadOutItem::new(
+                Some(Reference::from_raw_str("FEEDER_1")),
+                "MFR2".to_string(),
+                "PART2".to_string(),
+            ),
+        ];
+
+        // and
+        let task = TaskReference::from_raw_str("core::place_components");
+        let context = RuleContext {
+            task: &task,
+            action: &TaskAction::Start,
+            placements: &[],
+            load_out_items: &load_out_items,
+            part_aliases: &PartAliases::default(),
+        };
+
+        // when
+        let result = check_rules(&process, &context, &[]);
+
+        // then
+        assert_eq!(
+            result,
+            Err(ProcessRuleError::RulesViolated {
+                task,
+                action: TaskAction::Start,
+                violations: vec![RuleViolation {
+                    rule: ProcessRule::UniqueFeederReferences.reference(),
+                    reasons: vec![
+                        "Feeder assigned to multiple load-out items. feeder: FEEDER_1, parts: [MFR1:PART1, MFR2:PART2]"
+                            .to_string()
+                    ],
+                }],
+            })
+        );
+    }
+
+    #[test]
+    pub fn overridden_violations_are_returned() {
+        // given
+        let process = process(&[ProcessRule::PlacedBeforeSoldering]);
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap();
+        let state = placement_state(PlacementStatus::Skipped);
+
+        // and
+        let task = TaskReference::from_raw_str("core::manual_soldering");
+        let context = RuleContext {
+            task: &task,
+            action: &TaskAction::Complete,
+            placements: &[(&object_path, &state)],
+            load_out_items: &[],
+            part_aliases: &PartAliases::default(),
+        };
+
+        // when
+        let result = check_rules(&process, &context, &[ProcessRule::PlacedBeforeSoldering.reference()]);
+
+        // then
+        assert_eq!(
+            result,
+            Ok(vec![RuleViolation {
+                rule: ProcessRule::PlacedBeforeSoldering.reference(),
+                reasons: vec![
+                    "Placement not placed. object_path: pcb=1::unit=1::ref_des=R1, status: Skipped".to_string()
+                ],
+            }])
+        );
+    }
+
+    #[test]
+    pub fn rules_are_only_checked_for_the_task_actions_they_apply_to() {
+        // given
+        let process = process(&[ProcessRule::LoadOutBeforePlacement, ProcessRule::PlacedBeforeSoldering]);
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap();
+        let state = placement_state(PlacementStatus::Pending);
+
+        // and
+        let task = TaskReference::from_raw_str("core::place_components");
+        let context = RuleContext {
+            task: &task,
+            action: &TaskAction::Complete,
+            placements: &[(&object_path, &state)],
+            load_out_items: &[],
+            part_aliases: &PartAliases::default(),
+        };
+
+        // when
+        let result = check_rules(&process, &context, &[]);
+
+        // then
+        assert_eq!(result, Ok(vec![]));
+    }
+}
+
+#[cfg(test)]
+mod custom_task_tests {
+    use std::collections::BTreeMap;
+
+    use pnp::reference::Reference;
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    use crate::process::{
+        CustomTaskDefinition, OperationDefinition, ProcessDefinition, ProcessError, TaskParameterDefinition,
+        TaskParameterError, TaskParameterKind, TaskParameterValue, TaskReference,
+    };
+
+    fn stencil_tension_check() -> CustomTaskDefinition {
+        CustomTaskDefinition {
+            reference: TaskReference::from_raw_str("shop::stencil_tension_check"),
+            parameters: vec![
+                TaskParameterDefinition {
+                    name: "passed".to_string(),
+                    kind: TaskParameterKind::Checkbox,
+                },
+                TaskParameterDefinition {
+                    name: "tension".to_string(),
+                    kind: TaskParameterKind::Numeric {
+                        min: Some(dec!(30)),
+                        max: Some(dec!(50)),
+                    },
+                },
+                TaskParameterDefinition {
+                    name: "note".to_string(),
+                    kind: TaskParameterKind::Text,
+                },
+            ],
+        }
+    }
+
+    fn text_values(values: &[(&str, &str)]) -> BTreeMap<String, TaskParameterValue> {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), TaskParameterValue::Text(value.to_string())))
+            .collect()
+    }
+
+    #[test]
+    pub fn values_are_converted_to_the_kind_of_each_parameter() {
+        // given
+        let values = text_values(&[("passed", "true"), ("tension", "42.5"), ("note", "40")]);
+
+        // when
+        let result = stencil_tension_check().validate_values(&values);
+
+        // then
+        assert_eq!(
+            result,
+            Ok(BTreeMap::from([
+                ("note".to_string(), TaskParameterValue::Text("40".to_string())),
+                ("passed".to_string(), TaskParameterValue::Checkbox(true)),
+                ("tension".to_string(), TaskParameterValue::Numeric(dec!(42.5))),
+            ]))
+        );
+    }
+
+    #[rstest]
+    #[case::out_of_range(&[("passed", "true"), ("tension", "51"), ("note", "")], "tension")]
+    #[case::not_a_boolean(&[("passed", "yes"), ("tension", "40"), ("note", "")], "passed")]
+    #[case::not_a_number(&[("passed", "true"), ("tension", "high"), ("note", "")], "tension")]
+    pub fn invalid_values(#[case] values: &[(&str, &str)], #[case] expected_invalid_parameter: &str) {
+        // when
+        let result = stencil_tension_check().validate_values(&text_values(values));
+
+        // then
+        assert!(matches!(
+            result,
+            Err(TaskParameterError::InvalidValue { name, .. }) if name == expected_invalid_parameter
+        ));
+    }
+
+    #[test]
+    pub fn missing_and_unknown_values() {
+        // given
+        let definition = stencil_tension_check();
+        let task = definition.reference.clone();
+
+        // expect
+        assert_eq!(
+            definition.validate_values(&text_values(&[("passed", "true"), ("tension", "40")])),
+            Err(TaskParameterError::MissingValue {
+                task: task.clone(),
+                name: "note".to_string()
+            })
+        );
+        assert_eq!(
+            definition.validate_values(&text_values(&[("operator", "bob")])),
+            Err(TaskParameterError::UnknownParameter {
+                task,
+                name: "operator".to_string()
+            })
+        );
+    }
+
+    #[test]
+    pub fn tasks_must_be_core_or_custom_tasks() {
+        // given
+        let process = ProcessDefinition {
+            reference: Reference::from_raw_str("pnp"),
+            operations: vec![OperationDefinition {
+                reference: Reference::from_raw_str("solder_paste"),
+                tasks: vec![
+                    TaskReference::from_raw_str("core::apply_solder_paste"),
+                    TaskReference::from_raw_str("shop::stencil_tension_check"),
+                    TaskReference::from_raw_str("shop::paste_inspection"),
+                ],
+                custom_tasks: vec![stencil_tension_check()],
+            }],
+            rules: vec![],
+            nozzles: Default::default(),
+            feeder_banks: vec![],
+        };
+
+        // when
+        let result = process.ensure_tasks_defined();
+
+        // then
+        assert!(matches!(
+            result,
+            Err(ProcessError::UndefinedTask { task_reference, .. })
+                if task_reference.eq(&TaskReference::from_raw_str("shop::paste_inspection"))
+        ));
+    }
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone)]
 pub enum OperationStatus {
     Pending,
@@ -238,6 +964,14 @@ fn placements_state_mut(&mut self) -> Option<&mut dyn PlacementsTaskState> {
     fn requires_placements(&self) -> bool {
         self.placements_state().is_some()
     }
+
+    /// Allows callers to record the parameter values of a custom task
+    fn custom_state(&self) -> Option<&CustomTaskState> {
+        None
+    }
+    fn custom_state_mut(&mut self) -> Option<&mut CustomTaskState> {
+        None
+    }
 }
 
 pub trait PlacementsTaskState: AsAny {
@@ -448,11 +1182,60 @@ fn set_abandoned(&mut self) {
 }
 
 generic_task_impl!(LoadPcbsTaskState, "core::load_pcbs_task_state");
+generic_task_impl!(ApplySolderPasteTaskState, "core::apply_solder_paste_task_state");
 generic_task_impl!(AutomatedSolderingTaskState, "core::automated_soldering_task_state");
 generic_task_impl!(ManualSolderingTaskState, "core::manual_soldering_task_state");
 #[cfg(test)]
 generic_task_impl!(TestTaskState, "core::test_task_state");
 
+/// The state of a user-defined task, see [`CustomTaskDefinition`].
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct CustomTaskState {
+    status: TaskStatus,
+
+    /// Recorded when the task is completed, see [`CustomTaskDefinition::validate_values`].
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub parameter_values: BTreeMap<String, TaskParameterValue>,
+}
+
+#[typetag::serde(name = "core::custom_task_state")]
+impl SerializableTaskState for CustomTaskState {}
+
+impl TaskState for CustomTaskState {
+    fn status(&self) -> TaskStatus {
+        self.status.clone()
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default()
+    }
+
+    fn can_complete(&self) -> bool {
+        true
+    }
+
+    fn set_started(&mut self) {
+        self.status = TaskStatus::Started;
+    }
+
+    fn set_completed(&mut self) {
+        self.status = TaskStatus::Complete;
+    }
+
+    fn set_abandoned(&mut self) {
+        self.status = TaskStatus::Abandoned;
+    }
+
+    fn custom_state(&self) -> Option<&CustomTaskState> {
+        Some(self)
+    }
+
+    fn custom_state_mut(&mut self) -> Option<&mut CustomTaskState> {
+        Some(self)
+    }
+}
+
 /// Checks if an operation can be modified:
 /// - All preceding operations must be complete
 /// - All following operations must be pending
@@ -9,52 +9,65 @@
 use eda_units::eda_units::unit_system::UnitSystem;
 use heck::ToShoutySnakeCase;
 use indexmap::IndexSet;
+use itertools::Itertools;
 use pnp;
 use pnp::load_out::LoadOutItem;
-use pnp::object_path::ObjectPath;
+use pnp::object_path::{ObjectPath, ObjectPathPattern};
 use pnp::package::Package;
-use pnp::part::Part;
+use pnp::panel::DesignSizing;
+use pnp::part::{Part, PartAliases, PartMoistureSensitivity};
 use pnp::pcb::{PcbInstanceIndex, PcbInstanceNumber, PcbSide, PcbUnitIndex, PcbUnitNumber};
 use pnp::placement::Placement;
 use pnp::reference::Reference;
 use regex::Regex;
+use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 use thiserror::Error;
-use time::OffsetDateTime;
+use time::{Date, OffsetDateTime};
 use tracing::{debug, error, info, trace, warn};
+use util::cancellation::{CancellationToken, Cancelled};
 use util::sorting::SortOrder;
 use util::source::Source;
 
-use crate::actions::{AddOrRemoveAction, SetOrClearAction};
+use crate::actions::{AddOrRemoveAction, DeleteStrategy, SetOrClearAction};
+use crate::build_run::BuildRun;
 use crate::design::{DesignIndex, DesignName, DesignVariant};
-use crate::file::FileReference;
+use crate::file::{FileFormat, FileReference};
 use crate::library::LibraryConfig;
+use crate::nozzle::{NozzleAssignments, NozzleConfiguration};
 use crate::operation_history::{
-    AutomatedSolderingOperationTaskHistoryKind, LoadPcbsOperationTaskHistoryKind,
+    ApplySolderPasteOperationTaskHistoryKind, AutomatedSolderingOperationTaskHistoryKind,
+    CustomOperationTaskHistoryKind, FiducialLocationHistoryKind, LoadPcbsOperationTaskHistoryKind,
     ManualSolderingOperationTaskHistoryKind, OperationHistoryItem, OperationHistoryKind,
-    PlaceComponentsOperationTaskHistoryKind, PlacementOperationHistoryKind,
+    PlaceComponentsOperationTaskHistoryKind, PlacementInspectionHistoryKind, PlacementOperationHistoryKind,
 };
-use crate::part::PartState;
+use crate::part::{MoistureEvent, PartState};
 use crate::pcb::{Pcb, PcbError, PcbUnitTransform, UnitPlacementPosition};
-use crate::phase::{Phase, PhaseError, PhaseOrderings, PhaseReference, PhaseState};
+use crate::phase::{AssemblyWindow, Phase, PhaseError, PhaseOrderings, PhaseReference, PhaseState};
 use crate::placement::{
-    PlacementOperation, PlacementSortingItem, PlacementSortingMode, PlacementState, PlacementStatus,
-    ProjectPlacementStatus,
+    PlacementInspection, PlacementOperation, PlacementSortingItem, PlacementSortingMode, PlacementState,
+    PlacementStatus, PlacementWarning, ProjectPlacementStatus,
 };
 use crate::process::{
-    can_modify_operation, can_modify_task, OperationDefinition, OperationReference, OperationStatus, ProcessDefinition,
-    ProcessError, ProcessReference, ProcessRuleReference, SerializableTaskState, TaskAction, TaskReference, TaskStatus,
+    can_modify_operation, can_modify_task, CustomTaskState, OperationDefinition, OperationReference, OperationStatus,
+    ProcessDefinition, ProcessError, ProcessReference, ProcessRuleReference, RuleContext, SerializableTaskState,
+    TaskAction, TaskParameterError, TaskParameterValue, TaskReference, TaskStatus,
 };
+use crate::report::handover::HandoverReportFormat;
 #[cfg(feature = "markdown")]
 use crate::report::project_report_json_to_markdown;
+use crate::rotation::RotationOffsets;
+use crate::serial_number::{SerialNumber, SerialNumberContext, SerialNumberError, SerialNumberScheme, UnitScope};
+use crate::settings::{OriginConvention, ProjectSettings};
+use crate::store::StoreBackend;
 use crate::variant::VariantName;
-use crate::{file, operation_history, pcb, placement, report};
+use crate::{build_run, export, file, operation_history, pcb, placement, process, report, rotation};
 
 #[serde_as]
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct Project {
     pub name: String,
@@ -93,6 +106,34 @@ pub struct Project {
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     #[serde(default)]
     pub placements: BTreeMap<ObjectPath, PlacementState>,
+
+    #[serde(skip_serializing_if = "RotationOffsets::is_empty")]
+    #[serde(default)]
+    pub rotation_offsets: RotationOffsets,
+
+    #[serde(skip_serializing_if = "StoreBackend::is_csv")]
+    #[serde(default)]
+    pub store_backend: StoreBackend,
+
+    /// The format used when saving the project file.
+    #[serde(skip_serializing_if = "FileFormat::is_pretty")]
+    #[serde(default)]
+    pub file_format: FileFormat,
+
+    /// Groups of equivalent parts, e.g. imported from the 'AliasGroup' column of a parts file.
+    #[serde(skip_serializing_if = "PartAliases::is_empty")]
+    #[serde(default)]
+    pub part_aliases: PartAliases,
+
+    /// The runs, the last one is the current run, see [`crate::build_run`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub build_runs: Vec<BuildRun>,
+
+    /// Units, coordinate and rotation conventions, see [`crate::settings`].
+    #[serde(skip_serializing_if = "ProjectSettings::is_default")]
+    #[serde(default)]
+    pub settings: ProjectSettings,
 }
 
 impl Project {
@@ -132,6 +173,110 @@ pub fn all_unit_assignments(&self, pcbs: &[&Pcb]) -> Vec<(ObjectPath, Option<Des
             .collect::<Vec<_>>()
     }
 
+    /// Returns the serial numbers of all the units, ordered by pcb and unit.
+    pub fn all_serial_numbers(&self) -> Vec<(ObjectPath, SerialNumber)> {
+        self.pcbs
+            .iter()
+            .enumerate()
+            .flat_map(|(pcb_index, project_pcb)| {
+                project_pcb
+                    .serial_numbers
+                    .iter()
+                    .map(move |(unit_index, serial_number)| {
+                        let mut object_path = ObjectPath::default();
+                        object_path.set_pcb_instance(pcb_index as u16 + 1);
+                        object_path.set_pcb_unit(unit_index + 1);
+
+                        (object_path, serial_number.clone())
+                    })
+            })
+            .collect::<Vec<_>>()
+    }
+
+    pub fn find_unit_by_serial_number(&self, serial_number: &SerialNumber) -> Result<UnitScope, SerialNumberError> {
+        self.all_serial_numbers()
+            .into_iter()
+            .find(|(_unit_path, candidate)| candidate.eq(serial_number))
+            .map(|(unit_path, serial_number)| UnitScope {
+                unit_path,
+                serial_number,
+            })
+            .ok_or(SerialNumberError::UnknownSerialNumber(serial_number.clone()))
+    }
+
+    /// Assigns serial numbers to the units of the pcb that do not already have one.
+    ///
+    /// Sequence numbers continue from the amount of serial numbers already assigned in the project, so they are
+    /// unique within the project.
+    ///
+    /// Returns the newly assigned serial numbers, or an error if a generated serial number is already in use.
+    pub fn assign_serial_numbers(
+        &mut self,
+        pcbs: &[&Pcb],
+        pcb_index: PcbInstanceIndex,
+        scheme: &SerialNumberScheme,
+        date: Date,
+    ) -> Result<Vec<(ObjectPath, SerialNumber)>, SerialNumberError> {
+        let pcb = pcbs
+            .get(pcb_index as usize)
+            .ok_or(SerialNumberError::UnknownPcb(pcb_index))?;
+
+        let mut existing_serial_numbers: HashSet<SerialNumber> = self
+            .all_serial_numbers()
+            .into_iter()
+            .map(|(_unit_path, serial_number)| serial_number)
+            .collect();
+        let mut sequence = existing_serial_numbers.len() as u32;
+
+        let project_pcb = self
+            .pcbs
+            .get_mut(pcb_index as usize)
+            .ok_or(SerialNumberError::UnknownPcb(pcb_index))?;
+
+        let mut assigned = vec![];
+        for unit_index in 0..pcb.units {
+            if project_pcb
+                .serial_numbers
+                .contains_key(&unit_index)
+            {
+                continue;
+            }
+
+            sequence += 1;
+            let serial_number = scheme.format(&SerialNumberContext {
+                project: &self.name,
+                date,
+                sequence,
+                pcb: pcb_index + 1,
+                unit: unit_index + 1,
+            });
+
+            if !existing_serial_numbers.insert(serial_number.clone()) {
+                return Err(SerialNumberError::DuplicateSerialNumber(serial_number));
+            }
+
+            let mut unit_path = ObjectPath::default();
+            unit_path.set_pcb_instance(pcb_index + 1);
+            unit_path.set_pcb_unit(unit_index + 1);
+
+            assigned.push((unit_path, serial_number));
+        }
+
+        // only modify the project once all the serial numbers have been generated
+        for (unit_path, serial_number) in assigned.iter() {
+            info!(
+                "Assigned serial number. unit: '{}', serial_number: '{}'",
+                unit_path, serial_number
+            );
+            let unit_index = unit_path.pcb_unit().unwrap() - 1;
+            project_pcb
+                .serial_numbers
+                .insert(unit_index, serial_number.clone());
+        }
+
+        Ok(assigned)
+    }
+
     pub fn ensure_process(&mut self, process: &ProcessDefinition) -> anyhow::Result<()> {
         if !self.processes.contains(process) {
             info!("Adding process to project.  process: '{}'", process.reference);
@@ -249,7 +394,11 @@ pub fn update_phase(
                     process: process_reference.clone(),
                     load_out_source: load_out_source.clone(),
                     pcb_side,
-                    placement_orderings: vec![],
+                    placement_orderings: self
+                        .settings
+                        .default_placement_orderings
+                        .clone(),
+                    assembly_window: None,
                 };
                 entry.insert(phase);
                 info!(
@@ -282,12 +431,18 @@ pub fn update_phase(
         Ok(())
     }
 
-    /// Deletes a phase and unassigns the phase from all placements using it.
+    /// Deletes a phase, the placements assigned to the phase are unassigned.
     ///
-    /// If the phase is not 'pending' it is considered in-use and cannot be deleted.
+    /// A phase that is not 'pending' has operation history, with [`DeleteStrategy::Block`] a phase that has operation
+    /// history, or assigned placements, is considered in-use and cannot be deleted.  With [`DeleteStrategy::Cascade`]
+    /// the status and inspection result of the placements are reset too.
     ///
     /// After deleting a phase, artifacts will need re-generating.
-    pub fn delete_phase(&mut self, reference: PhaseReference) -> Result<(), PhaseError> {
+    pub fn delete_phase(
+        &mut self,
+        reference: PhaseReference,
+        strategy: DeleteStrategy,
+    ) -> Result<DeleteReport, PhaseError> {
         if !self.phases.contains_key(&reference) {
             return Err(PhaseError::UnknownPhase(reference.clone()));
         }
@@ -296,7 +451,18 @@ pub fn delete_phase(&mut self, reference: PhaseReference) -> Result<(), PhaseErr
             .phase_states
             .get(&reference)
             .unwrap();
-        if !phase_state.is_pending() {
+        let operation_history = !phase_state.is_pending();
+
+        let object_paths = self
+            .placements
+            .iter()
+            .filter(|(_path, state)| {
+                matches!(&state.phase, Some(assigned_phase_reference) if assigned_phase_reference.eq(&reference))
+            })
+            .map(|(path, _state)| path.clone())
+            .collect::<Vec<_>>();
+
+        if strategy == DeleteStrategy::Block && (operation_history || !object_paths.is_empty()) {
             return Err(PhaseError::PhaseInUse(reference.clone()));
         }
 
@@ -306,15 +472,110 @@ pub fn delete_phase(&mut self, reference: PhaseReference) -> Result<(), PhaseErr
             .remove(&reference)
             .unwrap();
 
-        for (_path, state) in self.placements.iter_mut() {
-            if matches!(&state.phase, Some(assigned_phase_reference) if assigned_phase_reference.eq(&reference)) {
-                state.phase.take();
+        let mut report = DeleteReport {
+            operation_history,
+            ..DeleteReport::default()
+        };
+
+        for object_path in object_paths {
+            // Safety: the path was just found
+            let state = self
+                .placements
+                .get_mut(&object_path)
+                .unwrap();
+            state.phase.take();
+
+            if strategy == DeleteStrategy::Cascade
+                && (state.operation_status != PlacementStatus::Pending || state.inspection.is_some())
+            {
+                state.operation_status = PlacementStatus::Pending;
+                state.inspection = None;
+                report
+                    .reset_placements
+                    .push(object_path.clone());
+            }
+            report
+                .unassigned_placements
+                .push(object_path);
+        }
+
+        for build_run in self.build_runs.iter_mut() {
+            for instance in build_run.instances.values_mut() {
+                instance.phase_states.remove(&reference);
+                if strategy == DeleteStrategy::Cascade {
+                    instance
+                        .placements
+                        .retain(|object_path, _state| {
+                            !report
+                                .unassigned_placements
+                                .contains(object_path)
+                        });
+                }
             }
         }
 
         self.phase_orderings
             .shift_remove(&reference);
 
+        info!(
+            "Deleted phase. phase: '{}', strategy: {}, report: {:?}",
+            reference, strategy, report
+        );
+
+        Ok(report)
+    }
+
+    /// The load-out source of a copy of a phase, without changing the project, e.g. for copying the load-out before the
+    /// phase is cloned, see [`Self::clone_phase`].
+    ///
+    /// Returns an error if the phase does not exist, or if the new phase already exists.
+    pub fn cloned_phase_load_out_source(
+        &self,
+        reference: &PhaseReference,
+        new_reference: &PhaseReference,
+    ) -> Result<String, PhaseError> {
+        let phase = self
+            .phases
+            .get(reference)
+            .ok_or_else(|| PhaseError::UnknownPhase(reference.clone()))?;
+
+        if self.phases.contains_key(new_reference) {
+            return Err(PhaseError::DuplicatePhase(new_reference.clone()));
+        }
+
+        Ok(build_cloned_phase_load_out_source(
+            &phase.load_out_source,
+            reference,
+            new_reference,
+        ))
+    }
+
+    /// Copies the process, PCB side, placement orderings and assembly window of a phase, the load-out source of the
+    /// copy is named after the new phase, see [`build_cloned_phase_load_out_source`].
+    ///
+    /// No placements are assigned to the copy.
+    pub fn clone_phase(&mut self, reference: &PhaseReference, new_reference: PhaseReference) -> Result<(), PhaseError> {
+        let load_out_source = self.cloned_phase_load_out_source(reference, &new_reference)?;
+        // Safety: the phase exists, see above
+        let phase = self.phases[reference].clone();
+
+        self.update_phase(
+            new_reference.clone(),
+            phase.process.clone(),
+            load_out_source,
+            phase.pcb_side,
+        )?;
+
+        // Safety: the phase was just created
+        let cloned_phase = self
+            .phases
+            .get_mut(&new_reference)
+            .unwrap();
+        cloned_phase.placement_orderings = phase.placement_orderings;
+        cloned_phase.assembly_window = phase.assembly_window;
+
+        info!("Cloned phase. phase: '{}', new_phase: '{}'", reference, new_reference);
+
         Ok(())
     }
 
@@ -373,6 +634,44 @@ pub fn delete_process(&mut self, process_reference: &ProcessReference) -> Result
         Ok(())
     }
 
+    /// Copies a process definition, the copy is added after the process.
+    pub fn clone_process(
+        &mut self,
+        process_reference: &ProcessReference,
+        new_reference: ProcessReference,
+    ) -> Result<(), ProcessError> {
+        let mut process = self
+            .find_process(process_reference)?
+            .clone();
+
+        if self
+            .processes
+            .iter()
+            .any(|process| process.reference.eq(&new_reference))
+        {
+            return Err(ProcessError::DuplicateProcessReference {
+                process_reference: new_reference,
+            });
+        }
+
+        info!(
+            "Cloned process. process: '{}', new_process: '{}'",
+            process_reference, new_reference
+        );
+
+        process.reference = new_reference;
+        // Safety: the process was just found
+        let index = self
+            .processes
+            .iter()
+            .position(|process| process.reference.eq(process_reference))
+            .unwrap();
+        self.processes
+            .insert(index + 1, process);
+
+        Ok(())
+    }
+
     /// Check to see if the process is in-use by a phase
     /// Returns Err if it is, otherwise Ok
     ///
@@ -460,6 +759,93 @@ pub fn remove_unused_placements(&mut self, phase_reference: Option<Reference>) -
     }
 }
 
+#[cfg(test)]
+mod assign_serial_numbers_tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    use indexmap::IndexSet;
+    use time::{Date, Month};
+
+    use crate::design::DesignName;
+    use crate::file::FileReference;
+    use crate::pcb::Pcb;
+    use crate::project::{Project, ProjectPcb};
+    use crate::serial_number::{SerialNumberError, SerialNumberScheme};
+
+    fn build_project_and_pcb(units: u16) -> (Project, Pcb) {
+        let mut project = Project::new("job1".to_string(), None, None);
+        project
+            .pcbs
+            .push(ProjectPcb::new(FileReference::Relative("panel.pcb.json".into())));
+
+        let pcb = Pcb::new(
+            "panel".to_string(),
+            units,
+            IndexSet::from([DesignName::from("design_a")]),
+            (0..units)
+                .map(|unit_index| (unit_index, 0))
+                .collect::<BTreeMap<_, _>>(),
+        );
+
+        (project, pcb)
+    }
+
+    #[test]
+    pub fn assign_and_find() {
+        // given
+        let (mut project, pcb) = build_project_and_pcb(2);
+        let scheme = SerialNumberScheme::from_str("{project}-{date}-{seq:04}").unwrap();
+        let date = Date::from_calendar_date(2025, Month::March, 7).unwrap();
+
+        // when
+        let assigned = project
+            .assign_serial_numbers(&[&pcb], 0, &scheme, date)
+            .unwrap();
+
+        // then
+        let assigned = assigned
+            .iter()
+            .map(|(unit_path, serial_number)| (unit_path.to_string(), serial_number.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(assigned, vec![
+            ("pcb=1::unit=1".to_string(), "job1-20250307-0001"),
+            ("pcb=1::unit=2".to_string(), "job1-20250307-0002"),
+        ]);
+
+        // and
+        let scope = project
+            .find_unit_by_serial_number(&"job1-20250307-0002".to_string())
+            .unwrap();
+        assert_eq!(scope.unit_path.to_string(), "pcb=1::unit=2");
+
+        // and units that already have a serial number are not re-assigned
+        let assigned = project
+            .assign_serial_numbers(&[&pcb], 0, &scheme, date)
+            .unwrap();
+        assert!(assigned.is_empty());
+    }
+
+    #[test]
+    pub fn duplicate_serial_numbers_are_rejected() {
+        // given
+        let (mut project, pcb) = build_project_and_pcb(2);
+        let scheme = SerialNumberScheme::from_str("{project}-{date}").unwrap();
+        let date = Date::from_calendar_date(2025, Month::March, 7).unwrap();
+
+        // when
+        let result = project.assign_serial_numbers(&[&pcb], 0, &scheme, date);
+
+        // then
+        assert_eq!(
+            result,
+            Err(SerialNumberError::DuplicateSerialNumber("job1-20250307".to_string()))
+        );
+        // and no serial numbers are assigned
+        assert!(project.all_serial_numbers().is_empty());
+    }
+}
+
 #[serde_as]
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -471,6 +857,12 @@ pub struct ProjectPcb {
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     #[serde(default)]
     pub unit_assignments: BTreeMap<PcbUnitIndex, DesignVariant>,
+
+    /// Individual units can have a serial number assigned, for traceability.
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub serial_numbers: BTreeMap<PcbUnitIndex, SerialNumber>,
 }
 
 impl ProjectPcb {
@@ -478,6 +870,7 @@ pub fn new(pcb_file: FileReference) -> Self {
         Self {
             pcb_file,
             unit_assignments: BTreeMap::default(),
+            serial_numbers: BTreeMap::default(),
         }
     }
 
@@ -627,17 +1020,22 @@ pub fn by_preset_name(name: &str) -> Result<ProcessDefinition, ProcessPresetFact
                     OperationDefinition {
                         reference: Reference::from_raw_str("load_pcbs"),
                         tasks: vec![TaskReference::from_raw_str("core::load_pcbs")],
+                        custom_tasks: vec![],
                     },
                     OperationDefinition {
                         reference: Reference::from_raw_str("automated_pnp"),
                         tasks: vec![TaskReference::from_raw_str("core::place_components")],
+                        custom_tasks: vec![],
                     },
                     OperationDefinition {
                         reference: Reference::from_raw_str("reflow_oven_soldering"),
                         tasks: vec![TaskReference::from_raw_str("core::automated_soldering")],
+                        custom_tasks: vec![],
                     },
                 ],
                 rules: vec![ProcessRuleReference::from_raw_str("core::unique_feeder_references")],
+                nozzles: Default::default(),
+                feeder_banks: vec![],
             }),
             "manual" => Ok(ProcessDefinition {
                 reference: ProcessReference::from_raw_str("manual"),
@@ -645,6 +1043,7 @@ pub fn by_preset_name(name: &str) -> Result<ProcessDefinition, ProcessPresetFact
                     OperationDefinition {
                         reference: Reference::from_raw_str("load_pcbs"),
                         tasks: vec![TaskReference::from_raw_str("core::load_pcbs")],
+                        custom_tasks: vec![],
                     },
                     OperationDefinition {
                         reference: Reference::from_raw_str("manually_solder_components"),
@@ -652,9 +1051,12 @@ pub fn by_preset_name(name: &str) -> Result<ProcessDefinition, ProcessPresetFact
                             TaskReference::from_raw_str("core::place_components"),
                             TaskReference::from_raw_str("core::manual_soldering"),
                         ],
+                        custom_tasks: vec![],
                     },
                 ],
                 rules: vec![],
+                nozzles: Default::default(),
+                feeder_banks: vec![],
             }),
             preset @ _ => Err(ProcessPresetFactoryError::UnknownPreset {
                 preset: preset.to_string(),
@@ -678,6 +1080,12 @@ fn default() -> Self {
             phase_orderings: Default::default(),
             phase_states: Default::default(),
             library_config: Default::default(),
+            rotation_offsets: Default::default(),
+            store_backend: Default::default(),
+            file_format: Default::default(),
+            part_aliases: Default::default(),
+            build_runs: Default::default(),
+            settings: Default::default(),
         }
     }
 }
@@ -704,8 +1112,16 @@ pub enum PcbOperationError {
     MissingDesignSizing(String),
     #[error("Missing unit sizing. unit: {0}")]
     MissingPcbUnitPositioning(PcbUnitIndex),
+    #[error("Unit is outside the panel. unit: {unit}, x: {x}, y: {y}")]
+    PcbUnitOutsidePanel { unit: PcbUnitNumber, x: f64, y: f64 },
+    #[error("Unable to load unit positionings. cause: {0}")]
+    UnableToLoadUnitPositionings(anyhow::Error),
     #[error("PCB is in use.")]
     PcbInUse,
+    #[error("Unsupported delete strategy. strategy: {0}")]
+    UnsupportedDeleteStrategy(DeleteStrategy),
+    #[error("PCB file already exists. path: {0:?}")]
+    PcbFileExists(PathBuf),
 }
 
 pub fn add_pcb(project: &mut Project, pcb_file: &FileReference) -> Result<(), PcbOperationError> {
@@ -718,33 +1134,141 @@ pub fn add_pcb(project: &mut Project, pcb_file: &FileReference) -> Result<(), Pc
     Ok(())
 }
 
+/// What was affected by deleting an item, see [`DeleteStrategy`].
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DeleteReport {
+    /// Units that had a design variant assigned, e.g. `pcb=1::unit=1`.
+    pub unit_assignments: Vec<ObjectPath>,
+    pub deleted_placements: Vec<ObjectPath>,
+    /// Placements that were kept, but are no longer assigned to the deleted phase.
+    pub unassigned_placements: Vec<ObjectPath>,
+    /// Placements with a status or inspection result that was reset.
+    pub reset_placements: Vec<ObjectPath>,
+    /// `(old, new)`, e.g. the placements of the PCBs after a removed PCB.
+    pub renumbered_placements: Vec<(ObjectPath, ObjectPath)>,
+    /// `true` if operations had been recorded for the deleted phase.
+    pub operation_history: bool,
+}
+
+impl DeleteReport {
+    /// `true` if nothing other than the deleted item was affected.
+    pub fn is_empty(&self) -> bool {
+        self.unit_assignments.is_empty()
+            && self.deleted_placements.is_empty()
+            && self.unassigned_placements.is_empty()
+            && self.reset_placements.is_empty()
+            && self.renumbered_placements.is_empty()
+            && !self.operation_history
+    }
+}
+
+/// Removes a PCB, and its placements, from the project.
+///
+/// The PCB instance number is part of the object path of a placement, the placements of the PCBs after the removed
+/// PCB are renumbered, e.g. `pcb=2::unit=1::ref_des=R1` becomes `pcb=1::unit=1::ref_des=R1`.  For the same reason,
+/// placements cannot outlive their PCB and [`DeleteStrategy::Orphan`] is not supported.
+///
+/// With [`DeleteStrategy::Block`] a PCB with unit assignments, or placements, is considered in-use and cannot be
+/// removed.
 pub fn remove_pcb(
     project: &mut Project,
     pcb_instance_index: PcbInstanceIndex,
     project_directory: &PathBuf,
-) -> Result<PathBuf, PcbOperationError> {
-    if let Some(pcb) = project
+    strategy: DeleteStrategy,
+) -> Result<(PathBuf, DeleteReport), PcbOperationError> {
+    let Some(project_pcb) = project
         .pcbs
         .get(pcb_instance_index as usize)
-    {
-        if !pcb.unit_assignments.is_empty() {
+    else {
+        return Err(PcbOperationError::InvalidInstanceIndex);
+    };
+
+    let pcb_instance_number: PcbInstanceNumber = pcb_instance_index + 1;
+
+    let unit_assignments = project_pcb
+        .unit_assignments
+        .keys()
+        .map(|unit_index| {
+            let mut object_path = ObjectPath::default();
+            object_path.set_pcb_instance(pcb_instance_number);
+            object_path.set_pcb_unit(unit_index + 1);
+            object_path
+        })
+        .collect::<Vec<_>>();
+
+    let is_pcb_placement =
+        |object_path: &ObjectPath| matches!(object_path.pcb_instance(), Ok(number) if number == pcb_instance_number);
+
+    let deleted_placements = project
+        .placements
+        .keys()
+        .filter(|object_path| is_pcb_placement(object_path))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    match strategy {
+        DeleteStrategy::Block if !unit_assignments.is_empty() || !deleted_placements.is_empty() => {
             return Err(PcbOperationError::PcbInUse);
         }
-    } else {
-        return Err(PcbOperationError::InvalidInstanceIndex);
+        DeleteStrategy::Orphan => return Err(PcbOperationError::UnsupportedDeleteStrategy(strategy)),
+        _ => {}
     }
 
     let pcb = project
         .pcbs
         .remove(pcb_instance_index as usize);
 
-    info!("Removed PCB from project. pcb_file: {}", pcb.pcb_file);
+    let renumber = |object_path: &ObjectPath| match object_path.pcb_instance() {
+        Ok(number) if number > pcb_instance_number => {
+            let mut renumbered_object_path = object_path.clone();
+            renumbered_object_path.set_pcb_instance(number - 1);
+            Some(renumbered_object_path)
+        }
+        _ => None,
+    };
+
+    let mut renumbered_placements = vec![];
+    project.placements = std::mem::take(&mut project.placements)
+        .into_iter()
+        .filter(|(object_path, _state)| !is_pcb_placement(object_path))
+        .map(|(object_path, mut state)| match renumber(&object_path) {
+            Some(renumbered_object_path) => {
+                // Safety: the unit path is on the same PCB as the placement
+                state.unit_path = renumber(&state.unit_path).unwrap();
+                renumbered_placements.push((object_path, renumbered_object_path.clone()));
+                (renumbered_object_path, state)
+            }
+            None => (object_path, state),
+        })
+        .collect();
+
+    for build_run in project.build_runs.iter_mut() {
+        for instance in build_run.instances.values_mut() {
+            instance.placements = std::mem::take(&mut instance.placements)
+                .into_iter()
+                .filter(|(object_path, _state)| !is_pcb_placement(object_path))
+                .map(|(object_path, state)| (renumber(&object_path).unwrap_or(object_path), state))
+                .collect();
+        }
+    }
+
+    let report = DeleteReport {
+        unit_assignments,
+        deleted_placements,
+        renumbered_placements,
+        ..DeleteReport::default()
+    };
+
+    info!(
+        "Removed PCB from project. pcb_file: {}, strategy: {}, report: {:?}",
+        pcb.pcb_file, strategy, report
+    );
 
     let path = pcb
         .pcb_file
         .build_path(project_directory);
 
-    Ok(path)
+    Ok((path, report))
 }
 
 #[derive(Error, Debug)]
@@ -752,6 +1276,18 @@ pub enum ArtifactGenerationError {
     #[error("Unable to generate phase placements. cause: {0:}")]
     PhasePlacementsGenerationError(Error),
 
+    #[error("Unable to generate stencil setup sheet. cause: {0:}")]
+    StencilSetupGenerationError(Error),
+
+    #[error("Unable to generate inspection checklist. cause: {0:}")]
+    InspectionChecklistGenerationError(Error),
+
+    #[error("Unable to generate machine job. cause: {0:}")]
+    MachineJobGenerationError(Error),
+
+    #[error("Unable to generate labels. cause: {0:}")]
+    LabelGenerationError(Error),
+
     #[error("Unable to load items. source: {load_out_source}, error: {reason}")]
     UnableToLoadItems { load_out_source: String, reason: Error },
 
@@ -760,6 +1296,49 @@ pub enum ArtifactGenerationError {
 
     #[error("Unable to save report. cause: {reason:}")]
     UnableToSaveReport { reason: Error },
+
+    #[error(transparent)]
+    Cancelled(#[from] Cancelled),
+}
+
+/// The artifacts that can be selected, the phase placements and the project report are always generated, see
+/// [`generate_artifacts`].
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize
+)]
+pub enum Artifact {
+    /// One for each phase.
+    InspectionChecklist,
+    /// One for each phase whose process applies solder paste.
+    StencilSetupSheet,
+    /// One for each phase whose process uses a machine, see [`export::build_machine_job`].
+    MachineJob,
+    /// Feeder labels for each phase, and unit labels for the PCBs.
+    Labels,
+    /// The phases, parts and most skipped reference designators, see [`report::analytics`].
+    Analytics,
+}
+
+impl Artifact {
+    pub const ALL: [Artifact; 5] = [
+        Artifact::InspectionChecklist,
+        Artifact::StencilSetupSheet,
+        Artifact::MachineJob,
+        Artifact::Labels,
+        Artifact::Analytics,
+    ];
+
+    pub fn all() -> BTreeSet<Artifact> {
+        BTreeSet::from(Self::ALL)
+    }
 }
 
 pub fn generate_artifacts(
@@ -768,8 +1347,13 @@ pub fn generate_artifacts(
     directory: &Path,
     phase_load_out_items_map: BTreeMap<Reference, Vec<LoadOutItem>>,
     part_packages: &BTreeMap<&Part, &Package>,
+    artifacts: &BTreeSet<Artifact>,
+    cancellation: &CancellationToken,
 ) -> Result<(), ArtifactGenerationError> {
     for reference in project.phase_orderings.iter() {
+        // when cancelled, the artifacts of phases that were already generated are kept, but the report is not updated
+        cancellation.check()?;
+
         let phase = project.phases.get(reference).unwrap();
 
         let load_out_items = phase_load_out_items_map
@@ -778,16 +1362,39 @@ pub fn generate_artifacts(
 
         let phase_placement_states = build_phase_placement_states(project, reference);
 
+        let process = project
+            .find_process(&phase.process)
+            .map_err(|err| ArtifactGenerationError::PhasePlacementsGenerationError(err.into()))?;
+
         generate_phase_artifacts(
             pcbs,
             phase,
+            &process.nozzles,
             load_out_items.as_slice(),
+            &project.part_aliases,
             part_packages,
+            &project.rotation_offsets,
+            &project.settings,
             directory,
             &phase_placement_states,
+            artifacts,
         )?;
+
+        if artifacts.contains(&Artifact::StencilSetupSheet) && report::paste::process_applies_solder_paste(process) {
+            generate_stencil_setup_sheet(pcbs, phase, directory)?;
+        }
+
+        if artifacts.contains(&Artifact::Labels) {
+            generate_feeder_labels(project, phase, load_out_items, directory)?;
+        }
+    }
+
+    if artifacts.contains(&Artifact::Labels) && !pcbs.is_empty() {
+        generate_unit_labels(project, pcbs, directory)?;
     }
 
+    cancellation.check()?;
+
     let report = report::project_generate_report(project, pcbs, &phase_load_out_items_map);
 
     let report_file_path = report::build_report_file_path(&project.name, directory);
@@ -803,11 +1410,61 @@ pub fn generate_artifacts(
         reason: err.into(),
     })?;
 
-    info!("Generated artifacts.");
+    if artifacts.contains(&Artifact::Analytics) {
+        generate_analytics(project, directory)?;
+    }
+
+    info!("Generated artifacts. artifacts: {:?}", artifacts);
 
     Ok(())
 }
 
+fn generate_analytics(project: &Project, directory: &Path) -> Result<(), ArtifactGenerationError> {
+    let operation_histories = report::analytics::read_operation_histories(project, directory).map_err(|err| {
+        ArtifactGenerationError::ReportGenerationError {
+            reason: err.into(),
+        }
+    })?;
+
+    let analytics = report::analytics::build_analytics(project, &operation_histories);
+
+    report::analytics::store_analytics_as_csv(&analytics, &project.name, directory).map_err(|err| {
+        ArtifactGenerationError::UnableToSaveReport {
+            reason: err.into(),
+        }
+    })
+}
+
+/// Returns the path of the report.
+pub fn generate_handover_report(
+    project: &Project,
+    pcbs: &[&Pcb],
+    directory: &Path,
+    phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>,
+    format: HandoverReportFormat,
+) -> Result<PathBuf, ArtifactGenerationError> {
+    let report = report::handover::build_handover_report(
+        project,
+        pcbs,
+        directory,
+        phase_load_out_items_map,
+        OffsetDateTime::now_utc(),
+    )
+    .map_err(|err| ArtifactGenerationError::ReportGenerationError {
+        reason: err.into(),
+    })?;
+
+    let report_path = report::handover::build_handover_report_file_path(&project.name, directory, format);
+
+    report::handover::store_handover_report(&report, format, &report_path).map_err(|err| {
+        ArtifactGenerationError::UnableToSaveReport {
+            reason: err,
+        }
+    })?;
+
+    Ok(report_path)
+}
+
 pub fn build_phase_placement_states<'a>(
     project: &'a Project,
     phase_reference: &'_ PhaseReference,
@@ -827,66 +1484,158 @@ pub fn build_phase_placement_states<'a>(
 fn generate_phase_artifacts(
     pcbs: &[&Pcb],
     phase: &Phase,
+    nozzles: &NozzleConfiguration,
     load_out_items: &[LoadOutItem],
+    part_aliases: &PartAliases,
     part_packages: &BTreeMap<&Part, &Package>,
+    rotation_offsets: &RotationOffsets,
+    settings: &ProjectSettings,
     directory: &Path,
     phase_placement_states: &[(&ObjectPath, &PlacementState)],
+    artifacts: &BTreeSet<Artifact>,
 ) -> Result<(), ArtifactGenerationError> {
     let pcb_unit_positioning_map = build_pcbs_unit_positioning_map(pcbs);
 
     // make a Vec so we can sort it, we're not cloning the paths and states themselves
     let mut phase_placement_states = Vec::from(phase_placement_states);
 
+    let nozzle_assignments = nozzles.assign_nozzles(&phase_placement_states, part_packages);
+
     sort_placements(
         &mut phase_placement_states,
         &phase.placement_orderings,
         load_out_items,
+        part_aliases,
         part_packages,
         &pcb_unit_positioning_map,
+        &nozzle_assignments,
     );
 
     let mut phase_placements_path = PathBuf::from(directory);
     phase_placements_path.push(format!("{}_placements.csv", phase.reference));
 
-    store_phase_placements_as_csv(&phase_placements_path, &phase_placement_states, load_out_items)
-        .map_err(|e| ArtifactGenerationError::PhasePlacementsGenerationError(e))?;
+    store_phase_placements_as_csv(
+        &phase_placements_path,
+        &phase_placement_states,
+        load_out_items,
+        part_aliases,
+        part_packages,
+        rotation_offsets,
+        settings,
+        pcbs,
+    )
+    .map_err(|e| ArtifactGenerationError::PhasePlacementsGenerationError(e))?;
 
     info!(
         "Generated phase placements. phase: '{}', path: {:?}",
         phase.reference, phase_placements_path
     );
 
+    if artifacts.contains(&Artifact::InspectionChecklist) {
+        let inspection_checklist =
+            report::inspection::build_inspection_checklist(&phase_placement_states, part_packages, settings);
+        let inspection_checklist_path = report::inspection::build_inspection_checklist_file_path(phase, directory);
+
+        report::inspection::store_inspection_checklist_as_csv(&inspection_checklist_path, &inspection_checklist)
+            .map_err(ArtifactGenerationError::InspectionChecklistGenerationError)?;
+    }
+
+    // processes without nozzles do not use a machine
+    if artifacts.contains(&Artifact::MachineJob) && !nozzles.is_empty() {
+        // machine jobs use the default settings, see `export`
+        let placement_records = build_phase_placement_records(
+            &phase_placement_states,
+            load_out_items,
+            part_aliases,
+            part_packages,
+            rotation_offsets,
+            &ProjectSettings::default(),
+            pcbs,
+        )
+        .map_err(ArtifactGenerationError::MachineJobGenerationError)?;
+        let job = export::build_machine_job(phase, pcbs, placement_records, load_out_items, &nozzle_assignments);
+        let job_path = export::build_machine_job_file_path(phase, directory);
+
+        export::machine_job_save_as_json(&job, &job_path)
+            .map_err(ArtifactGenerationError::MachineJobGenerationError)?;
+    }
+
     Ok(())
 }
 
-/// returns a vector containing a vector of unit positions.
-/// where the index of the first vector matches in the index of the pcbs
-/// and where the index of the second vector matches the index of the unit for the pcb
-pub fn build_pcbs_unit_positioning_map(pcbs: &[&Pcb]) -> Vec<Vec<DimensionUnitVector2>> {
-    pcbs.iter()
-        .map(|pcb| {
-            let pcb_unit_positions = pcb
-                .panel_sizing
-                .pcb_unit_positionings
-                .iter()
-                .map(|positioning| {
-                    // FIXME hard-coded use of UnitSystem::Millimeters
-                    //       the pcb needs to have a unit system defined for it.
-                    DimensionUnitVector2::new_dim_vector2_f64(positioning.offset, UnitSystem::Millimeters)
-                })
-                .collect::<Vec<_>>();
+fn generate_stencil_setup_sheet(pcbs: &[&Pcb], phase: &Phase, directory: &Path) -> Result<(), ArtifactGenerationError> {
+    let sheet = report::paste::build_stencil_setup_sheet(phase, pcbs)
+        .map_err(|err| ArtifactGenerationError::StencilSetupGenerationError(err.into()))?;
 
-            pcb_unit_positions
-        })
-        .collect::<Vec<_>>()
+    let sheet_path = report::paste::build_stencil_setup_sheet_file_path(phase, directory);
+
+    report::paste::stencil_setup_sheet_save_as_json(&sheet, &sheet_path)
+        .map_err(ArtifactGenerationError::StencilSetupGenerationError)?;
+
+    Ok(())
+}
+
+fn generate_feeder_labels(
+    project: &Project,
+    phase: &Phase,
+    load_out_items: &[LoadOutItem],
+    directory: &Path,
+) -> Result<(), ArtifactGenerationError> {
+    let labels = report::labels::build_feeder_labels(project, phase, load_out_items);
+
+    let csv_path = report::labels::build_feeder_labels_file_path(phase, directory, "csv");
+    report::labels::store_labels_as_csv(&labels, &csv_path).map_err(ArtifactGenerationError::LabelGenerationError)?;
+
+    let zpl_path = report::labels::build_feeder_labels_file_path(phase, directory, "zpl");
+    report::labels::store_feeder_labels_as_zpl(&labels, &zpl_path)
+        .map_err(ArtifactGenerationError::LabelGenerationError)?;
+
+    Ok(())
+}
+
+fn generate_unit_labels(project: &Project, pcbs: &[&Pcb], directory: &Path) -> Result<(), ArtifactGenerationError> {
+    let labels = report::labels::build_unit_labels(project, pcbs);
+
+    let csv_path = report::labels::build_unit_labels_file_path(&project.name, directory, "csv");
+    report::labels::store_labels_as_csv(&labels, &csv_path).map_err(ArtifactGenerationError::LabelGenerationError)?;
+
+    let zpl_path = report::labels::build_unit_labels_file_path(&project.name, directory, "zpl");
+    report::labels::store_unit_labels_as_zpl(&labels, &zpl_path)
+        .map_err(ArtifactGenerationError::LabelGenerationError)?;
+
+    Ok(())
+}
+
+/// returns a vector containing a vector of unit positions.
+/// where the index of the first vector matches in the index of the pcbs
+/// and where the index of the second vector matches the index of the unit for the pcb
+pub fn build_pcbs_unit_positioning_map(pcbs: &[&Pcb]) -> Vec<Vec<DimensionUnitVector2>> {
+    pcbs.iter()
+        .map(|pcb| {
+            let pcb_unit_positions = pcb
+                .panel_sizing
+                .pcb_unit_positionings
+                .iter()
+                .map(|positioning| {
+                    // FIXME hard-coded use of UnitSystem::Millimeters
+                    //       the pcb needs to have a unit system defined for it.
+                    DimensionUnitVector2::new_dim_vector2_f64(positioning.offset, UnitSystem::Millimeters)
+                })
+                .collect::<Vec<_>>();
+
+            pcb_unit_positions
+        })
+        .collect::<Vec<_>>()
 }
 
 pub fn sort_placements(
     placement_states: &mut Vec<(&ObjectPath, &PlacementState)>,
     placement_orderings: &[PlacementSortingItem],
     load_out_items: &[LoadOutItem],
+    part_aliases: &PartAliases,
     part_packages: &BTreeMap<&Part, &Package>,
     pcb_unit_positioning_map: &Vec<Vec<DimensionUnitVector2>>,
+    nozzle_assignments: &NozzleAssignments,
 ) {
     placement_states.sort_by(
         |(object_path_a, placement_state_a), (object_path_b, placement_state_b)| {
@@ -898,16 +1647,18 @@ pub fn sort_placements(
                     }
                     acc = match sort_ordering.mode {
                         PlacementSortingMode::FeederReference => {
-                            let feeder_reference_a = match pnp::load_out::find_load_out_item_by_part(
+                            let feeder_reference_a = match pnp::load_out::find_load_out_item_by_part_or_alias(
                                 load_out_items,
                                 &placement_state_a.placement.part,
+                                part_aliases,
                             ) {
                                 Some(load_out_item) => load_out_item.reference.clone(),
                                 _ => None,
                             };
-                            let feeder_reference_b = match pnp::load_out::find_load_out_item_by_part(
+                            let feeder_reference_b = match pnp::load_out::find_load_out_item_by_part_or_alias(
                                 load_out_items,
                                 &placement_state_b.placement.part,
+                                part_aliases,
                             ) {
                                 Some(load_out_item) => load_out_item.reference.clone(),
                                 _ => None,
@@ -1044,6 +1795,17 @@ pub fn sort_placements(
 
                             height_a.cmp(&height_b)
                         }
+                        PlacementSortingMode::Nozzle => {
+                            let nozzle_a = nozzle_assignments.get(*object_path_a);
+                            let nozzle_b = nozzle_assignments.get(*object_path_b);
+
+                            trace!(
+                                "Comparing nozzles, nozzle_a: '{:?}', nozzle_b: '{:?}'",
+                                nozzle_a,
+                                nozzle_b
+                            );
+                            nozzle_a.cmp(&nozzle_b)
+                        }
                         PlacementSortingMode::Part => placement_state_a
                             .placement
                             .part
@@ -1079,6 +1841,11 @@ pub fn store_phase_placements_as_csv(
     output_path: &PathBuf,
     placement_states: &[(&ObjectPath, &PlacementState)],
     load_out_items: &[LoadOutItem],
+    part_aliases: &PartAliases,
+    part_packages: &BTreeMap<&Part, &Package>,
+    rotation_offsets: &RotationOffsets,
+    settings: &ProjectSettings,
+    pcbs: &[&Pcb],
 ) -> Result<(), Error> {
     trace!("Writing phase placements. output_path: {:?}", output_path);
 
@@ -1086,30 +1853,17 @@ pub fn store_phase_placements_as_csv(
         .quote_style(QuoteStyle::Always)
         .from_path(output_path)?;
 
-    for (object_path, placement_state) in placement_states.iter() {
-        let feeder_reference =
-            match pnp::load_out::find_load_out_item_by_part(&load_out_items, &placement_state.placement.part) {
-                Some(load_out_item) => load_out_item.reference.clone(),
-                _ => None,
-            };
-
-        writer.serialize(PhasePlacementRecord {
-            object_path: (*object_path).clone(),
-            feeder_reference,
-            manufacturer: placement_state
-                .placement
-                .part
-                .manufacturer
-                .to_string(),
-            mpn: placement_state
-                .placement
-                .part
-                .mpn
-                .to_string(),
-            x: placement_state.unit_position.x,
-            y: placement_state.unit_position.y,
-            rotation: placement_state.unit_position.rotation,
-        })?;
+    let records = build_phase_placement_records(
+        placement_states,
+        load_out_items,
+        part_aliases,
+        part_packages,
+        rotation_offsets,
+        settings,
+        pcbs,
+    )?;
+    for record in records {
+        writer.serialize(record)?;
     }
 
     writer.flush()?;
@@ -1117,18 +1871,115 @@ pub fn store_phase_placements_as_csv(
     Ok(())
 }
 
+/// The records are in the same order as the placement states.
+///
+/// The feeder is the load-out item for the part, or one of its aliases, the rotation includes the rotation offsets and
+/// the rotation offset of the feeder.
+///
+/// Returns an error for the top-left origin convention when the PCB of a placement has no panel size, since the
+/// positions cannot be converted.
+pub fn build_phase_placement_records(
+    placement_states: &[(&ObjectPath, &PlacementState)],
+    load_out_items: &[LoadOutItem],
+    part_aliases: &PartAliases,
+    part_packages: &BTreeMap<&Part, &Package>,
+    rotation_offsets: &RotationOffsets,
+    settings: &ProjectSettings,
+    pcbs: &[&Pcb],
+) -> Result<Vec<PhasePlacementRecord>, Error> {
+    placement_states
+        .iter()
+        .map(|(object_path, placement_state)| {
+            let load_out_item = pnp::load_out::find_load_out_item_by_part_or_alias(
+                &load_out_items,
+                &placement_state.placement.part,
+                part_aliases,
+            );
+            let feeder_reference = load_out_item.and_then(|load_out_item| load_out_item.reference.clone());
+
+            let part = &placement_state.placement.part;
+            let package = part_packages.get(part).copied();
+            let rotation = rotation_offsets.apply(placement_state.unit_position.rotation, part, package);
+            // the rotation of the part in the feeder is independent of the package and part offsets
+            let rotation = match load_out_item.and_then(|load_out_item| load_out_item.rotation_offset) {
+                Some(feeder_rotation_offset) => rotation::apply_rotation_offset(rotation, feeder_rotation_offset),
+                None => rotation,
+            };
+
+            // only required for the top-left origin convention
+            let panel_height = match settings.origin {
+                OriginConvention::BottomLeft => Decimal::ZERO,
+                OriginConvention::TopLeft => object_path
+                    .pcb_instance()
+                    .ok()
+                    .and_then(|pcb_instance| pcbs.get((pcb_instance as usize).checked_sub(1)?))
+                    .and_then(|pcb| Decimal::from_f64(pcb.panel_sizing.size.y))
+                    .filter(|height| height.is_sign_positive() && !height.is_zero())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "The top-left origin requires the panel size of the PCB. object_path: {}",
+                            object_path
+                        )
+                    })?,
+            };
+            let (x, y) = settings.position(
+                placement_state.unit_position.x,
+                placement_state.unit_position.y,
+                panel_height,
+            );
+
+            Ok(PhasePlacementRecord {
+                object_path: (*object_path).clone(),
+                feeder_reference,
+                manufacturer: placement_state
+                    .placement
+                    .part
+                    .manufacturer
+                    .to_string(),
+                mpn: placement_state
+                    .placement
+                    .part
+                    .mpn
+                    .to_string(),
+                x,
+                y,
+                rotation: settings.rotation(rotation),
+            })
+        })
+        .collect()
+}
+
 #[derive(Error, Debug)]
 pub enum AssignmentError {
     #[error("Project state error. All phases must be pending to perform assignments")]
     ProjectStateError,
+    #[error(
+        "Placements are not on the PCB side of the phase, use 'force' to assign them anyway. phase_side: {phase_side}, placements: [{}]",
+        .object_paths.iter().join(", ")
+    )]
+    PcbSideMismatch {
+        phase_side: PcbSide,
+        object_paths: Vec<ObjectPath>,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct PhaseAssignmentResult {
+    /// The parts of the matched placements, they are required in the load-out of the phase.
+    pub required_load_out_parts: BTreeSet<Part>,
+    /// Placements that matched the pattern but were not assigned, because they are not on the PCB side of the phase.
+    pub ignored_placements: Vec<ObjectPath>,
 }
 
+/// When some of the matched placements are on the other PCB side they are ignored and returned in the result, so that
+/// they can be reported; when all of them are, an error is returned.
 pub fn assign_placements_to_phase(
     project: &mut Project,
     phase: &Phase,
     action: SetOrClearAction,
-    placements_pattern: Regex,
-) -> Result<BTreeSet<Part>, AssignmentError> {
+    placements_pattern: ObjectPathPattern,
+    force: bool,
+) -> Result<PhaseAssignmentResult, AssignmentError> {
     if !project
         .phase_states
         .iter()
@@ -1142,24 +1993,50 @@ pub fn assign_placements_to_phase(
     }
 
     let mut required_load_out_parts = BTreeSet::new();
+    let mut ignored_placements = vec![];
 
     debug!(
-        "Assigning phase placements to {:?}, action: {:?}, pattern: {:?}",
-        phase, action, placements_pattern
+        "Assigning phase placements to {:?}, action: {:?}, pattern: {:?}, force: {}",
+        phase, action, placements_pattern, force
     );
-    let matched_placements: Vec<(&ObjectPath, &mut PlacementState)> = project
+
+    // placements on the other side are filtered out, unless forced (e.g. through-hole parts).
+    // clearing is never filtered, so that forced assignments can be removed.
+    let filter_by_side = matches!(action, SetOrClearAction::Set) && !force;
+
+    let (matched_placements, mismatched_placements): (Vec<(&ObjectPath, &mut PlacementState)>, Vec<_>) = project
         .placements
         .iter_mut()
-        .filter(|(path, state)| {
-            let path_str = format!("{}", path);
-
-            placements_pattern.is_match(&path_str)
-                && state
+        .filter(|(path, _state)| placements_pattern.is_match(path))
+        .partition(|(_path, state)| {
+            !filter_by_side
+                || state
                     .placement
                     .pcb_side
                     .eq(&phase.pcb_side)
-        })
-        .collect();
+        });
+
+    if !mismatched_placements.is_empty() {
+        let object_paths: Vec<ObjectPath> = mismatched_placements
+            .into_iter()
+            .map(|(path, _state)| path.clone())
+            .collect();
+
+        if matched_placements.is_empty() {
+            return Err(AssignmentError::PcbSideMismatch {
+                phase_side: phase.pcb_side,
+                object_paths,
+            });
+        }
+
+        warn!(
+            "Ignoring placements that are not on the PCB side of the phase. phase: {}, phase_side: {}, placements: [{}]",
+            phase.reference,
+            phase.pcb_side,
+            object_paths.iter().join(", ")
+        );
+        ignored_placements = object_paths;
+    }
 
     trace!("matched_placements: {:?}", matched_placements);
 
@@ -1207,21 +2084,78 @@ pub fn assign_placements_to_phase(
         let _inserted = required_load_out_parts.insert(state.placement.part.clone());
     }
 
-    Ok(required_load_out_parts)
+    Ok(PhaseAssignmentResult {
+        required_load_out_parts,
+        ignored_placements,
+    })
+}
+
+#[derive(Debug, Default)]
+pub struct RefreshResult {
+    /// True if the project was modified.
+    pub modified: bool,
+    /// Only design variants with warnings are included.
+    pub warnings: BTreeMap<DesignVariant, Vec<PlacementWarning>>,
+    /// The design variants whose placements were refreshed.
+    pub refreshed: BTreeSet<DesignVariant>,
 }
 
+/// The placements are validated, see [`placement::validate_placements`], but are imported regardless of any warnings.
+///
+/// When `changed` is specified, only the placements of the changed design variants are refreshed, this requires that
+/// the placements of the other design variants, the unit assignments, and the PCBs have not changed since the previous
+/// refresh.  The parts are always refreshed using the placements of all the design variants.
 pub fn refresh_from_design_variants<'a>(
     project: &'a mut Project,
     pcbs: &[&Pcb],
-    design_variant_placement_map: BTreeMap<DesignVariant, Vec<Placement>>,
-) -> Result<bool, ProjectError> {
+    mut design_variant_placement_map: BTreeMap<DesignVariant, Vec<Placement>>,
+    changed: Option<&BTreeSet<DesignVariant>>,
+) -> Result<RefreshResult, ProjectError> {
     let unique_parts = placement::build_unique_parts_from_design_variant_placement_map(&design_variant_placement_map);
 
     let mut modified = refresh_parts(project, unique_parts.as_slice());
 
-    modified |= refresh_placements(project, pcbs, &design_variant_placement_map)?;
+    if let Some(changed) = changed {
+        design_variant_placement_map.retain(|design_variant, _placements| changed.contains(design_variant));
+    }
+    let refresh_placements_required = changed.is_none() || !design_variant_placement_map.is_empty();
 
-    Ok(modified)
+    let warnings = design_variant_placement_map
+        .iter()
+        .filter_map(|(design_variant, placements)| {
+            let design_sizing = find_design_sizing(pcbs, &design_variant.design_name);
+            let warnings = placement::validate_placements(placements, design_sizing);
+            for warning in warnings.iter() {
+                warn!("{}. design_variant: {}", warning, design_variant);
+            }
+
+            (!warnings.is_empty()).then(|| (design_variant.clone(), warnings))
+        })
+        .collect();
+
+    if refresh_placements_required {
+        modified |= refresh_placements(project, pcbs, &design_variant_placement_map)?;
+    }
+
+    Ok(RefreshResult {
+        modified,
+        warnings,
+        refreshed: design_variant_placement_map
+            .into_keys()
+            .collect(),
+    })
+}
+
+/// Uses the first PCB with the design.
+fn find_design_sizing<'a>(pcbs: &[&'a Pcb], design_name: &DesignName) -> Option<&'a DesignSizing> {
+    pcbs.iter().find_map(|pcb| {
+        let design_index = pcb
+            .design_names
+            .get_index_of(design_name)?;
+        pcb.panel_sizing
+            .design_sizings
+            .get(design_index)
+    })
 }
 
 /// It is possible that the EDA files and/or PCBs have changed since the last time the project was refreshed.
@@ -1271,6 +2205,9 @@ fn refresh_placements(
                     operation_status: PlacementStatus::Pending,
                     project_status: ProjectPlacementStatus::Used,
                     phase: None,
+                    inspection: None,
+                    notes: None,
+                    attachments: vec![],
                 };
 
                 placement_state_entry.or_insert(placement_state);
@@ -1555,9 +2492,7 @@ pub fn find_parts_to_modify(
 
             match change {
                 Change::New | Change::Existing => {
-                    if manufacturer_pattern.is_match(part.manufacturer.as_str())
-                        && mpn_pattern.is_match(part.mpn.as_str())
-                    {
+                    if matches_part_or_alias(&project.part_aliases, part, &manufacturer_pattern, &mpn_pattern) {
                         Some((*part).clone())
                     } else {
                         None
@@ -1615,35 +2550,610 @@ pub fn add_process_to_part(part_state: &mut PartState, part: &Part, process: Pro
         );
     }
 
-    inserted
-}
+    inserted
+}
+
+#[must_use]
+pub fn remove_process_from_part(part_state: &mut PartState, part: &Part, process: ProcessReference) -> bool {
+    let removed = part_state
+        .applicable_processes
+        .remove(&process);
+
+    if removed {
+        info!(
+            "Removed process. part: {:?}, applicable_processes: {:?}",
+            part,
+            part_state
+                .applicable_processes
+                .iter()
+                .map(|it| it.to_string())
+                .collect::<Vec<String>>()
+        );
+    }
+
+    removed
+}
+
+/// Set, or clear when `moisture_sensitivity` is `None`, the moisture sensitivity of the parts.
+#[must_use]
+pub fn update_moisture_sensitivity(
+    project: &mut Project,
+    parts_to_modify: Vec<Part>,
+    moisture_sensitivity: Option<PartMoistureSensitivity>,
+) -> bool {
+    let mut modified = false;
+
+    for part in parts_to_modify {
+        project
+            .part_states
+            .entry(part.clone())
+            .and_modify(|part_state| {
+                if part_state
+                    .moisture_sensitivity
+                    .ne(&moisture_sensitivity)
+                {
+                    part_state
+                        .moisture_sensitivity
+                        .clone_from(&moisture_sensitivity);
+                    info!(
+                        "Moisture sensitivity set. part: {:?}, moisture_sensitivity: {:?}",
+                        part, moisture_sensitivity
+                    );
+                    modified = true;
+                }
+            });
+    }
+
+    modified
+}
+
+#[must_use]
+pub fn record_moisture_event(
+    project: &mut Project,
+    parts_to_modify: Vec<Part>,
+    event: MoistureEvent,
+    at: OffsetDateTime,
+) -> bool {
+    let mut modified = false;
+
+    for part in parts_to_modify {
+        project
+            .part_states
+            .entry(part.clone())
+            .and_modify(|part_state| {
+                let original = part_state.moisture_exposure.clone();
+                event.apply(&mut part_state.moisture_exposure, at);
+
+                if part_state
+                    .moisture_exposure
+                    .ne(&original)
+                {
+                    info!("Recorded moisture event. part: {:?}, event: {:?}", part, event);
+                    modified = true;
+                }
+            });
+    }
+
+    modified
+}
+
+/// Set, or clear when `assembly_window` is `None`, the scheduled assembly window of the phase.
+pub fn update_assembly_window(
+    project: &mut Project,
+    reference: &Reference,
+    assembly_window: Option<AssemblyWindow>,
+) -> anyhow::Result<bool> {
+    if let Some(window) = &assembly_window {
+        if window.end < window.start {
+            return Err(anyhow::anyhow!(
+                "Assembly window ends before it starts. start: {}, end: {}",
+                window.start,
+                window.end
+            ));
+        }
+    }
+
+    let phase = project
+        .phases
+        .get_mut(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    let modified = phase
+        .assembly_window
+        .ne(&assembly_window);
+
+    if modified {
+        phase.assembly_window = assembly_window;
+        info!(
+            "Phase assembly window set. phase: '{}', assembly_window: {:?}",
+            reference, phase.assembly_window
+        );
+    }
+
+    Ok(modified)
+}
+
+/// Blank notes are treated as `None`.
+pub fn update_placement_notes(
+    project: &mut Project,
+    object_path: &ObjectPath,
+    notes: Option<String>,
+) -> anyhow::Result<bool> {
+    let notes = notes.filter(|notes| !notes.trim().is_empty());
+
+    let placement_state = project
+        .placements
+        .get_mut(object_path)
+        .ok_or_else(|| anyhow::anyhow!("Unknown placement. object_path: {}", object_path))?;
+
+    let modified = placement_state.notes.ne(&notes);
+
+    if modified {
+        placement_state.notes = notes;
+        info!(
+            "Placement notes set. object_path: {}, notes: {:?}",
+            object_path, placement_state.notes
+        );
+    }
+
+    Ok(modified)
+}
+
+/// Duplicate attachments are removed, the order is otherwise kept.
+pub fn update_placement_attachments(
+    project: &mut Project,
+    object_path: &ObjectPath,
+    attachments: Vec<PathBuf>,
+) -> anyhow::Result<bool> {
+    let attachments = attachments
+        .into_iter()
+        .collect::<IndexSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let placement_state = project
+        .placements
+        .get_mut(object_path)
+        .ok_or_else(|| anyhow::anyhow!("Unknown placement. object_path: {}", object_path))?;
+
+    let modified = placement_state
+        .attachments
+        .ne(&attachments);
+
+    if modified {
+        placement_state.attachments = attachments;
+        info!(
+            "Placement attachments set. object_path: {}, attachments: {:?}",
+            object_path, placement_state.attachments
+        );
+    }
+
+    Ok(modified)
+}
+
+/// The reference of the phase is replaced in the file stem, if present, otherwise the new reference is appended to
+/// the file stem, the directory and the extension are unchanged.
+///
+/// e.g. `load_out_top_1.csv` -> `load_out_top_2.csv`, `feeders.csv` -> `feeders_top_2.csv`,
+/// `top_1/feeders.csv` -> `top_1/feeders_top_2.csv`
+fn build_cloned_phase_load_out_source(
+    load_out_source: &str,
+    reference: &PhaseReference,
+    new_reference: &PhaseReference,
+) -> String {
+    let (directory, file_name) = match load_out_source.rfind(['/', '\\']) {
+        Some(index) => load_out_source.split_at(index + 1),
+        None => ("", load_out_source),
+    };
+
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (file_name, None),
+    };
+
+    let reference = reference.to_string();
+    let stem = if stem.contains(&reference) {
+        stem.replace(&reference, &new_reference.to_string())
+    } else {
+        format!("{}_{}", stem, new_reference)
+    };
+
+    match extension {
+        Some(extension) => format!("{}{}.{}", directory, stem, extension),
+        None => format!("{}{}", directory, stem),
+    }
+}
+
+#[cfg(test)]
+mod clone_tests {
+    use super::*;
+
+    #[test]
+    pub fn clone_phase() {
+        // given
+        let mut project = Project::default();
+        project
+            .update_phase(
+                Reference::from_raw_str("top_1"),
+                Reference::from_raw_str("pnp"),
+                "load_out_top_1.csv".to_string(),
+                PcbSide::Top,
+            )
+            .unwrap();
+        let orderings = vec![PlacementSortingItem {
+            mode: PlacementSortingMode::FeederReference,
+            sort_order: SortOrder::Asc,
+        }];
+        project
+            .phases
+            .get_mut(&Reference::from_raw_str("top_1"))
+            .unwrap()
+            .placement_orderings = orderings.clone();
+
+        // when
+        project
+            .clone_phase(&Reference::from_raw_str("top_1"), Reference::from_raw_str("top_2"))
+            .unwrap();
+
+        // then
+        let phase = &project.phases[&Reference::from_raw_str("top_2")];
+        assert_eq!(phase.process, Reference::from_raw_str("pnp"));
+        assert_eq!(phase.load_out_source, "load_out_top_2.csv");
+        assert_eq!(phase.pcb_side, PcbSide::Top);
+        assert_eq!(phase.placement_orderings, orderings);
+
+        // and
+        assert!(matches!(
+            project.clone_phase(&Reference::from_raw_str("top_1"), Reference::from_raw_str("top_2")),
+            Err(PhaseError::DuplicatePhase(_))
+        ));
+    }
+
+    #[test]
+    pub fn cloned_load_out_sources() {
+        // expect
+        assert_eq!(
+            build_cloned_phase_load_out_source(
+                "feeders.csv",
+                &Reference::from_raw_str("top_1"),
+                &Reference::from_raw_str("top_2")
+            ),
+            "feeders_top_2.csv"
+        );
+        assert_eq!(
+            build_cloned_phase_load_out_source(
+                "feeders",
+                &Reference::from_raw_str("top_1"),
+                &Reference::from_raw_str("top_2")
+            ),
+            "feeders_top_2"
+        );
+        assert_eq!(
+            build_cloned_phase_load_out_source(
+                "top_1/load_out_top_1.csv",
+                &Reference::from_raw_str("top_1"),
+                &Reference::from_raw_str("top_2")
+            ),
+            "top_1/load_out_top_2.csv"
+        );
+        assert_eq!(
+            build_cloned_phase_load_out_source(
+                "top_1/feeders.top_1",
+                &Reference::from_raw_str("top_1"),
+                &Reference::from_raw_str("top_2")
+            ),
+            "top_1/feeders_top_2.top_1"
+        );
+    }
+
+    #[test]
+    pub fn clone_process() {
+        // given
+        let mut project = Project::default();
+
+        // when
+        project
+            .clone_process(&Reference::from_raw_str("pnp"), Reference::from_raw_str("pnp_2"))
+            .unwrap();
+
+        // then
+        let index = project
+            .processes
+            .iter()
+            .position(|process| {
+                process
+                    .reference
+                    .eq(&Reference::from_raw_str("pnp"))
+            })
+            .unwrap();
+        let process = &project.processes[index + 1];
+        assert_eq!(process.reference, Reference::from_raw_str("pnp_2"));
+        assert_eq!(process.operations, project.processes[index].operations);
+
+        // and
+        assert!(matches!(
+            project.clone_process(&Reference::from_raw_str("pnp"), Reference::from_raw_str("pnp_2")),
+            Err(ProcessError::DuplicateProcessReference { .. })
+        ));
+    }
+}
+
+#[cfg(test)]
+mod delete_tests {
+    use super::*;
+
+    fn placement_state(object_path: &str, phase: Option<&str>, operation_status: PlacementStatus) -> PlacementState {
+        let object_path = ObjectPath::from_raw_str(object_path);
+        PlacementState {
+            unit_path: object_path.pcb_unit_path().unwrap(),
+            phase: phase.map(Reference::from_raw_str),
+            operation_status,
+            ..PlacementState::default()
+        }
+    }
+
+    #[test]
+    pub fn delete_phase_with_assigned_placements() {
+        // given
+        let mut project = Project::default();
+        project
+            .update_phase(
+                Reference::from_raw_str("top_1"),
+                Reference::from_raw_str("pnp"),
+                "load_out_top_1.csv".to_string(),
+                PcbSide::Top,
+            )
+            .unwrap();
+        for (object_path, operation_status) in [
+            ("pcb=1::unit=1::ref_des=R1", PlacementStatus::Placed),
+            ("pcb=1::unit=1::ref_des=R2", PlacementStatus::Pending),
+        ] {
+            project.placements.insert(
+                ObjectPath::from_raw_str(object_path),
+                placement_state(object_path, Some("top_1"), operation_status),
+            );
+        }
+
+        // expect
+        assert!(matches!(
+            project.delete_phase(Reference::from_raw_str("top_1"), DeleteStrategy::Block),
+            Err(PhaseError::PhaseInUse(_))
+        ));
+
+        // when
+        let report = project
+            .delete_phase(Reference::from_raw_str("top_1"), DeleteStrategy::Cascade)
+            .unwrap();
+
+        // then
+        let r1 = ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R1");
+        assert_eq!(report.unassigned_placements.len(), 2);
+        assert_eq!(report.reset_placements, vec![r1.clone()]);
+        assert!(!report.operation_history);
+
+        // and
+        let state = &project.placements[&r1];
+        assert_eq!(state.phase, None);
+        assert_eq!(state.operation_status, PlacementStatus::Pending);
+        assert!(!project
+            .phases
+            .contains_key(&Reference::from_raw_str("top_1")));
+    }
+
+    #[test]
+    pub fn remove_pcb_renumbers_the_placements_of_later_pcbs() {
+        // given
+        let mut project = Project::default();
+        for pcb_file in ["panel_a.pcb.json", "panel_b.pcb.json"] {
+            project
+                .pcbs
+                .push(ProjectPcb::new(FileReference::Relative(PathBuf::from(pcb_file))));
+        }
+        for object_path in ["pcb=1::unit=1::ref_des=R1", "pcb=2::unit=1::ref_des=R1"] {
+            project.placements.insert(
+                ObjectPath::from_raw_str(object_path),
+                placement_state(object_path, None, PlacementStatus::Pending),
+            );
+        }
+
+        // expect
+        assert!(matches!(
+            remove_pcb(&mut project, 0, &PathBuf::from("."), DeleteStrategy::Block),
+            Err(PcbOperationError::PcbInUse)
+        ));
+
+        // when
+        let (_path, report) = remove_pcb(&mut project, 0, &PathBuf::from("."), DeleteStrategy::Cascade).unwrap();
+
+        // then
+        let renumbered_object_path = ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R1");
+        assert_eq!(report.deleted_placements, vec![renumbered_object_path.clone()]);
+        assert_eq!(report.renumbered_placements, vec![(
+            ObjectPath::from_raw_str("pcb=2::unit=1::ref_des=R1"),
+            renumbered_object_path.clone()
+        )]);
+
+        // and
+        assert_eq!(project.pcbs.len(), 1);
+        assert_eq!(project.placements.len(), 1);
+        assert_eq!(
+            project.placements[&renumbered_object_path].unit_path,
+            ObjectPath::from_raw_str("pcb=1::unit=1")
+        );
+    }
+}
+
+#[cfg(test)]
+mod assign_placements_to_phase_tests {
+    use super::*;
+
+    fn placement_state(ref_des: &str, pcb_side: PcbSide) -> (ObjectPath, PlacementState) {
+        let object_path = ObjectPath::from_raw_str(&format!("pcb=1::unit=1::ref_des={}", ref_des));
+        let placement_state = PlacementState {
+            unit_path: object_path.pcb_unit_path().unwrap(),
+            placement: Placement {
+                pcb_side,
+                ..Placement::default()
+            },
+            ..PlacementState::default()
+        };
+        (object_path, placement_state)
+    }
+
+    fn build_project() -> Project {
+        let mut project = Project::default();
+        project
+            .update_phase(
+                Reference::from_raw_str("top_1"),
+                Reference::from_raw_str("pnp"),
+                "load_out_top_1.csv".to_string(),
+                PcbSide::Top,
+            )
+            .unwrap();
+        project.placements.extend([
+            placement_state("R1", PcbSide::Top),
+            placement_state("R2", PcbSide::Bottom),
+        ]);
+        project
+    }
+
+    #[test]
+    pub fn placements_on_the_other_side_are_ignored_and_reported() {
+        // given
+        let mut project = build_project();
+        let phase = project.phases[&Reference::from_raw_str("top_1")].clone();
+
+        // when
+        let result = assign_placements_to_phase(
+            &mut project,
+            &phase,
+            SetOrClearAction::Set,
+            ObjectPathPattern::Regex(Regex::new(".*").unwrap()),
+            false,
+        )
+        .unwrap();
+
+        // then
+        let r2 = ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R2");
+        assert_eq!(result.ignored_placements, vec![r2.clone()]);
+
+        // and
+        assert_eq!(
+            project.placements[&ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R1")].phase,
+            Some(phase.reference.clone())
+        );
+        assert_eq!(project.placements[&r2].phase, None);
+    }
+
+    #[test]
+    pub fn forced_placements_on_the_other_side_are_assigned() {
+        // given
+        let mut project = build_project();
+        let phase = project.phases[&Reference::from_raw_str("top_1")].clone();
+
+        // when
+        let result = assign_placements_to_phase(
+            &mut project,
+            &phase,
+            SetOrClearAction::Set,
+            ObjectPathPattern::Regex(Regex::new(".*").unwrap()),
+            true,
+        )
+        .unwrap();
+
+        // then
+        assert!(result.ignored_placements.is_empty());
+        assert!(project
+            .placements
+            .values()
+            .all(|state| state.phase == Some(phase.reference.clone())));
+    }
+
+    #[test]
+    pub fn all_placements_on_the_other_side_is_an_error() {
+        // given
+        let mut project = build_project();
+        let phase = project.phases[&Reference::from_raw_str("top_1")].clone();
+
+        // expect
+        assert!(matches!(
+            assign_placements_to_phase(
+                &mut project,
+                &phase,
+                SetOrClearAction::Set,
+                ObjectPathPattern::Regex(Regex::new("R2$").unwrap()),
+                false,
+            ),
+            Err(AssignmentError::PcbSideMismatch { .. })
+        ));
+    }
+}
+
+#[cfg(test)]
+mod update_placement_annotations_tests {
+    use std::path::PathBuf;
+
+    use pnp::object_path::ObjectPath;
+
+    use crate::placement::PlacementState;
+    use crate::project::{update_placement_attachments, update_placement_notes, Project};
+
+    #[test]
+    pub fn blank_notes_clear_the_notes() {
+        // given
+        let mut project = Project::default();
+        let object_path = ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R1");
+        project
+            .placements
+            .insert(object_path.clone(), PlacementState::default());
+
+        // when
+        let set_modified = update_placement_notes(&mut project, &object_path, Some("lift pin 3".to_string())).unwrap();
+        let cleared_modified = update_placement_notes(&mut project, &object_path, Some("  ".to_string())).unwrap();
+
+        // then
+        assert!(set_modified);
+        assert!(cleared_modified);
+        assert_eq!(project.placements[&object_path].notes, None);
+    }
+
+    #[test]
+    pub fn duplicate_attachments_are_removed() {
+        // given
+        let mut project = Project::default();
+        let object_path = ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R1");
+        project
+            .placements
+            .insert(object_path.clone(), PlacementState::default());
+        let attachments = vec![
+            PathBuf::from("photos/r1_b.jpg"),
+            PathBuf::from("photos/r1_a.jpg"),
+            PathBuf::from("photos/r1_b.jpg"),
+        ];
 
-#[must_use]
-pub fn remove_process_from_part(part_state: &mut PartState, part: &Part, process: ProcessReference) -> bool {
-    let removed = part_state
-        .applicable_processes
-        .remove(&process);
+        // when
+        let modified = update_placement_attachments(&mut project, &object_path, attachments).unwrap();
 
-    if removed {
-        info!(
-            "Removed process. part: {:?}, applicable_processes: {:?}",
-            part,
-            part_state
-                .applicable_processes
-                .iter()
-                .map(|it| it.to_string())
-                .collect::<Vec<String>>()
-        );
-    }
+        // then
+        assert!(modified);
+        assert_eq!(project.placements[&object_path].attachments, vec![
+            PathBuf::from("photos/r1_b.jpg"),
+            PathBuf::from("photos/r1_a.jpg"),
+        ]);
 
-    removed
+        // and
+        let unknown_object_path = ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R2");
+        assert!(update_placement_attachments(&mut project, &unknown_object_path, vec![]).is_err());
+    }
 }
 
+/// When a `scope` is given, only the placements of the scoped unit are updated.
 pub fn update_placements_operation(
     project: &mut Project,
     directory: &Path,
-    object_path_patterns: Vec<Regex>,
+    object_path_patterns: Vec<ObjectPathPattern>,
     placement_operation: PlacementOperation,
+    scope: Option<&UnitScope>,
 ) -> anyhow::Result<bool> {
     let mut modified = false;
 
@@ -1655,7 +3165,14 @@ pub fn update_placements_operation(
         let placements: Vec<_> = project
             .placements
             .iter_mut()
-            .filter(|(object_path, _placement_state)| object_path_pattern.is_match(&object_path.to_string()))
+            .filter(|(object_path, _placement_state)| object_path_pattern.is_match(object_path))
+            .filter(|(_object_path, placement_state)| {
+                scope.map_or(true, |scope| {
+                    placement_state
+                        .unit_path
+                        .eq(&scope.unit_path)
+                })
+            })
             .collect();
 
         if placements.is_empty() {
@@ -1679,7 +3196,7 @@ pub fn update_placements_operation(
                 continue;
             }
 
-            let should_log = match placement_operation {
+            let should_log = match &placement_operation {
                 PlacementOperation::Place => match placement_state.operation_status {
                     PlacementStatus::Placed => {
                         warn!("Placement already marked as placed. object_path: {}", object_path);
@@ -1688,12 +3205,14 @@ pub fn update_placements_operation(
                     PlacementStatus::Skipped => {
                         warn!("Placement was previously skipped. object_path: {}", object_path);
                         placement_state.operation_status = PlacementStatus::Placed;
+                        placement_state.inspection = None;
                         modified = true;
                         true
                     }
                     PlacementStatus::Pending => {
                         info!("Placement marked as placed. object_path: {}", object_path);
                         placement_state.operation_status = PlacementStatus::Placed;
+                        placement_state.inspection = None;
                         modified = true;
                         true
                     }
@@ -1702,6 +3221,7 @@ pub fn update_placements_operation(
                     PlacementStatus::Placed | PlacementStatus::Skipped => {
                         info!("Resetting placed flag. object_path: {}", object_path);
                         placement_state.operation_status = PlacementStatus::Pending;
+                        placement_state.inspection = None;
                         modified = true;
                         true
                     }
@@ -1714,6 +3234,7 @@ pub fn update_placements_operation(
                     PlacementStatus::Placed => {
                         warn!("Placement was previously placed. object_path: {}", object_path);
                         placement_state.operation_status = PlacementStatus::Skipped;
+                        placement_state.inspection = None;
                         modified = true;
                         true
                     }
@@ -1724,9 +3245,34 @@ pub fn update_placements_operation(
                     PlacementStatus::Pending => {
                         info!("Placement marked as skipped. object_path: {}", object_path);
                         placement_state.operation_status = PlacementStatus::Skipped;
+                        placement_state.inspection = None;
+                        modified = true;
+                        true
+                    }
+                },
+                PlacementOperation::Inspected {
+                    pass,
+                    notes,
+                } => match placement_state.operation_status {
+                    PlacementStatus::Placed => {
+                        info!("Placement inspected. object_path: {}, pass: {}", object_path, pass);
+                        placement_state.inspection = Some(PlacementInspection {
+                            pass: *pass,
+                            notes: notes.clone(),
+                        });
+                        if !pass {
+                            placement_state.operation_status = PlacementStatus::Pending;
+                        }
                         modified = true;
                         true
                     }
+                    PlacementStatus::Skipped | PlacementStatus::Pending => {
+                        warn!(
+                            "Placement has not been placed, not inspecting. object_path: {}",
+                            object_path
+                        );
+                        false
+                    }
                 },
             };
 
@@ -1769,6 +3315,9 @@ pub fn update_placements_operation(
                     operation_reference: operation_reference.clone(),
                     task_reference: TaskReference::from_raw_str("core::place_components"),
                     task_history,
+                    serial_number: scope.map(|scope| scope.serial_number.clone()),
+                    build_run: build_run::active_build_run_instance(project),
+                    overridden_rules: vec![],
                 })
                 .collect::<Vec<_>>();
 
@@ -1786,6 +3335,274 @@ pub fn update_placements_operation(
     Ok(modified)
 }
 
+#[derive(Error, Debug)]
+pub enum InspectionError {
+    #[error("Unknown placement. object_path: {0}")]
+    UnknownPlacement(ObjectPath),
+    #[error("Placement not in phase. object_path: {object_path}, phase: {phase}")]
+    PlacementNotInPhase {
+        object_path: ObjectPath,
+        phase: PhaseReference,
+    },
+    #[error("No placement task in progress. phase: {0}")]
+    NoPlacementTask(PhaseReference),
+    #[error("Unable to record inspection. cause: {0}")]
+    HistoryError(Error),
+}
+
+/// Appends the inspection to the phase's operation history, the project is not modified.
+pub fn record_placement_inspection(
+    project: &Project,
+    directory: &Path,
+    phase_reference: &PhaseReference,
+    inspection: PlacementInspectionHistoryKind,
+) -> Result<(), InspectionError> {
+    let placement_state = project
+        .placements
+        .get(&inspection.object_path)
+        .ok_or_else(|| InspectionError::UnknownPlacement(inspection.object_path.clone()))?;
+
+    if !matches!(&placement_state.phase, Some(phase) if phase.eq(phase_reference)) {
+        return Err(InspectionError::PlacementNotInPhase {
+            object_path: inspection.object_path.clone(),
+            phase: phase_reference.clone(),
+        });
+    }
+
+    info!(
+        "Recording placement inspection. phase: '{}', object_path: '{}', confidence: {}",
+        phase_reference, inspection.object_path, inspection.confidence
+    );
+
+    append_vision_history(project, directory, phase_reference, Box::new(inspection))
+}
+
+/// Appends the fiducial location to the phase's operation history, the project is not modified.
+pub fn record_fiducial_location(
+    project: &Project,
+    directory: &Path,
+    phase_reference: &PhaseReference,
+    location: FiducialLocationHistoryKind,
+) -> Result<(), InspectionError> {
+    info!(
+        "Recording fiducial location. phase: '{}', expected: ({}, {}), confidence: {}",
+        phase_reference, location.expected_x, location.expected_y, location.confidence
+    );
+
+    append_vision_history(project, directory, phase_reference, Box::new(location))
+}
+
+/// Vision results are recorded against the phase's placement task, which must be in progress.
+fn append_vision_history(
+    project: &Project,
+    directory: &Path,
+    phase_reference: &PhaseReference,
+    task_history: Box<dyn OperationHistoryKind>,
+) -> Result<(), InspectionError> {
+    let phase_operation_task_map = build_phase_operation_task_map(&PlacementOperation::Place, &project.phase_states);
+    let (operation_reference, task_reference) = phase_operation_task_map
+        .get(phase_reference)
+        .ok_or_else(|| InspectionError::NoPlacementTask(phase_reference.clone()))?;
+
+    let history_item = OperationHistoryItem {
+        date_time: OffsetDateTime::now_utc(),
+        phase: phase_reference.clone(),
+        extra: Default::default(),
+        operation_reference: operation_reference.clone(),
+        task_reference: task_reference.clone(),
+        task_history,
+        serial_number: None,
+        build_run: build_run::active_build_run_instance(project),
+        overridden_rules: vec![],
+    };
+
+    let mut phase_log_path = PathBuf::from(directory);
+    phase_log_path.push(format!("{}_log.json", phase_reference));
+
+    let mut operation_history: Vec<OperationHistoryItem> =
+        operation_history::read_or_default(&phase_log_path).map_err(InspectionError::HistoryError)?;
+
+    operation_history.push(history_item);
+
+    operation_history::write(phase_log_path, &operation_history).map_err(InspectionError::HistoryError)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod record_vision_history_tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    use as_any::AsAny;
+    use indexmap::IndexMap;
+    use pnp::object_path::ObjectPath;
+
+    use super::*;
+    use crate::process::{OperationState, PlacementTaskState, SerializableTaskState, TaskState};
+
+    fn build_project(task_started: bool) -> (Project, PhaseReference, ObjectPath) {
+        let phase_reference = PhaseReference::from_raw_str("top_1");
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=U1").unwrap();
+
+        let mut project = Project::default();
+        project
+            .placements
+            .insert(object_path.clone(), PlacementState {
+                phase: Some(phase_reference.clone()),
+                ..PlacementState::default()
+            });
+
+        let mut placement_task_state = Box::new(PlacementTaskState::default()) as Box<dyn SerializableTaskState>;
+        if task_started {
+            placement_task_state.set_started();
+        }
+        project.phase_states = BTreeMap::from([(phase_reference.clone(), PhaseState {
+            operation_states: vec![OperationState {
+                reference: OperationReference::from_raw_str("operation_1"),
+                task_states: IndexMap::from([(TaskReference::from_raw_str("task_1"), placement_task_state)]),
+            }],
+        })]);
+
+        (project, phase_reference, object_path)
+    }
+
+    fn build_inspection(object_path: ObjectPath) -> PlacementInspectionHistoryKind {
+        PlacementInspectionHistoryKind {
+            object_path,
+            offset_x: 0.05,
+            offset_y: -0.02,
+            rotation: 0.5,
+            confidence: 0.9,
+        }
+    }
+
+    fn read_history(directory: &Path) -> Vec<OperationHistoryItem> {
+        operation_history::read_or_default(&directory.join("top_1_log.json")).unwrap()
+    }
+
+    #[test]
+    fn placement_inspections_are_recorded_against_the_placement_task() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (project, phase_reference, object_path) = build_project(true);
+
+        // when
+        record_placement_inspection(
+            &project,
+            temp_dir.path(),
+            &phase_reference,
+            build_inspection(object_path.clone()),
+        )
+        .unwrap();
+
+        // then
+        let history = read_history(temp_dir.path());
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            history[0].operation_reference,
+            OperationReference::from_raw_str("operation_1")
+        );
+        assert_eq!(history[0].task_reference, TaskReference::from_raw_str("task_1"));
+        let inspection = (*history[0].task_history)
+            .as_any()
+            .downcast_ref::<PlacementInspectionHistoryKind>()
+            .unwrap();
+        assert_eq!(inspection.object_path, object_path);
+        assert_eq!((inspection.offset_x, inspection.offset_y), (0.05, -0.02));
+        assert_eq!(inspection.rotation, 0.5);
+        assert_eq!(inspection.confidence, 0.9);
+    }
+
+    #[test]
+    fn placement_inspections_require_the_placement_to_be_in_the_phase() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (project, _phase_reference, object_path) = build_project(true);
+        let other_phase_reference = PhaseReference::from_raw_str("bottom_1");
+
+        // when
+        let result = record_placement_inspection(
+            &project,
+            temp_dir.path(),
+            &other_phase_reference,
+            build_inspection(object_path),
+        );
+
+        // then
+        assert!(
+            matches!(result, Err(InspectionError::PlacementNotInPhase { phase, .. }) if phase == other_phase_reference)
+        );
+        assert!(read_history(temp_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn placement_inspections_of_unknown_placements_are_rejected() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (project, phase_reference, _object_path) = build_project(true);
+        let unknown_object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=U2").unwrap();
+
+        // when
+        let result = record_placement_inspection(
+            &project,
+            temp_dir.path(),
+            &phase_reference,
+            build_inspection(unknown_object_path.clone()),
+        );
+
+        // then
+        assert!(
+            matches!(result, Err(InspectionError::UnknownPlacement(object_path)) if object_path == unknown_object_path)
+        );
+    }
+
+    #[test]
+    fn fiducial_locations_are_recorded_against_the_placement_task() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (project, phase_reference, _object_path) = build_project(true);
+        let location = FiducialLocationHistoryKind {
+            expected_x: 10.0,
+            expected_y: 20.0,
+            offset_x: 0.1,
+            offset_y: 0.2,
+            confidence: 0.8,
+        };
+
+        // when
+        record_fiducial_location(&project, temp_dir.path(), &phase_reference, location).unwrap();
+
+        // then
+        let history = read_history(temp_dir.path());
+        let location = (*history[0].task_history)
+            .as_any()
+            .downcast_ref::<FiducialLocationHistoryKind>()
+            .unwrap();
+        assert_eq!((location.expected_x, location.expected_y), (10.0, 20.0));
+        assert_eq!((location.offset_x, location.offset_y), (0.1, 0.2));
+        assert_eq!(location.confidence, 0.8);
+    }
+
+    #[test]
+    fn vision_results_require_a_placement_task_in_progress() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (project, phase_reference, object_path) = build_project(false);
+
+        // when
+        let result = record_placement_inspection(
+            &project,
+            temp_dir.path(),
+            &phase_reference,
+            build_inspection(object_path),
+        );
+
+        // then
+        assert!(matches!(result, Err(InspectionError::NoPlacementTask(phase)) if phase == phase_reference));
+    }
+}
+
 /// find the only tasks for each phase that allow placement changes.
 fn build_phase_operation_task_map(
     placement_operation: &PlacementOperation,
@@ -1816,7 +3633,13 @@ fn build_phase_operation_task_map(
                             }
 
                             let can_change = match (&placement_operation, task_state.status()) {
-                                (PlacementOperation::Reset, TaskStatus::Complete) => true,
+                                (
+                                    PlacementOperation::Reset
+                                    | PlacementOperation::Inspected {
+                                        ..
+                                    },
+                                    TaskStatus::Complete,
+                                ) => true,
                                 (_, TaskStatus::Started) => true,
                                 _ => false,
                             };
@@ -1881,10 +3704,11 @@ fn build_phase_states_1() -> BTreeMap<Reference, PhaseState> {
         })])
     }
 
-    #[test]
-    pub fn test_build_phase_operation_task_map_reset_when_completed() {
+    #[rstest]
+    #[case(PlacementOperation::Reset)]
+    #[case(PlacementOperation::Inspected { pass: false, notes: None })]
+    pub fn test_build_phase_operation_task_map_reset_when_completed(#[case] placement_operation: PlacementOperation) {
         // given
-        let placement_operation = PlacementOperation::Reset;
         let mut phase_states = build_phase_states_1();
 
         // and complete the first operation
@@ -2287,7 +4111,8 @@ pub fn can_apply_action(
                     let task_states = task_status_map
                         .iter()
                         .map(|(task_reference, task_status)| {
-                            let mut task_state = phase::make_task_state(&TaskReference::from_raw_str(task_reference));
+                            let mut task_state =
+                                phase::make_task_state(&TaskReference::from_raw_str(task_reference), &[]);
                             match task_status {
                                 // Default state is pending
                                 TaskStatus::Pending => {}
@@ -2330,6 +4155,103 @@ pub fn can_apply_action(
 
         assert_eq!(result, expected_result);
     }
+
+    #[test]
+    pub fn overridden_rule_violations_are_recorded_in_the_operation_history() {
+        use pnp::load_out::LoadOutItem;
+        use pnp::pcb::PcbSide;
+
+        use crate::operation_history;
+        use crate::process::ProcessRuleReference;
+        use crate::project::{apply_phase_operation_task_action, TaskActionOptions};
+
+        // given a pnp phase, the pnp process has the unique feeder references rule
+        let directory = tempfile::tempdir().unwrap();
+        let mut project = Project::default();
+        project
+            .update_phase(
+                Reference::from_raw_str("top_1"),
+                Reference::from_raw_str("pnp"),
+                "load_out_top_1.csv".to_string(),
+                PcbSide::Top,
+            )
+            .unwrap();
+        let phase_reference = Reference::from_raw_str("top_1");
+        for action in [TaskAction::Start, TaskAction::Complete] {
+            apply_phase_operation_task_action(
+                &mut project,
+                directory.path(),
+                &phase_reference,
+                Reference::from_raw_str("load_pcbs"),
+                TaskReference::from_raw_str("core::load_pcbs"),
+                action,
+                TaskActionOptions::default(),
+            )
+            .unwrap();
+        }
+
+        // and a load-out with a duplicate feeder reference
+        let load_out_items = ["R1", "R2"].map(|mpn| {
+            LoadOutItem::new(
+                Some(Reference::from_raw_str("FEEDER_1")),
+                "RES_MFR1".to_string(),
+                mpn.to_string(),
+            )
+        });
+        let rule = ProcessRuleReference::from_raw_str("core::unique_feeder_references");
+
+        // expect
+        assert!(apply_phase_operation_task_action(
+            &mut project,
+            directory.path(),
+            &phase_reference,
+            Reference::from_raw_str("automated_pnp"),
+            TaskReference::from_raw_str("core::place_components"),
+            TaskAction::Start,
+            TaskActionOptions {
+                load_out_items: &load_out_items,
+                ..TaskActionOptions::default()
+            },
+        )
+        .is_err());
+
+        // when
+        apply_phase_operation_task_action(
+            &mut project,
+            directory.path(),
+            &phase_reference,
+            Reference::from_raw_str("automated_pnp"),
+            TaskReference::from_raw_str("core::place_components"),
+            TaskAction::Start,
+            TaskActionOptions {
+                load_out_items: &load_out_items,
+                overridden_rules: &[rule.clone()],
+                ..TaskActionOptions::default()
+            },
+        )
+        .unwrap();
+
+        // then
+        let operation_history = operation_history::read_or_default(&directory.path().join("top_1_log.json")).unwrap();
+        let history_item = operation_history.last().unwrap();
+        assert_eq!(
+            history_item.task_reference,
+            TaskReference::from_raw_str("core::place_components")
+        );
+        assert_eq!(
+            history_item
+                .overridden_rules
+                .iter()
+                .map(|violation| violation.rule.clone())
+                .collect::<Vec<_>>(),
+            vec![rule]
+        );
+
+        // and the rule was not overridden for the earlier tasks
+        assert!(operation_history[0]
+            .overridden_rules
+            .is_empty());
+    }
 }
 
 #[derive(Error, Debug, PartialEq, Eq)]
@@ -2454,6 +4376,22 @@ fn can_apply_action<'p>(
     Ok(task_state)
 }
 
+#[derive(Debug, Default)]
+pub struct TaskActionOptions<'a> {
+    /// When given, the serial number of the scoped unit is recorded in the operation history, and only the
+    /// placements of the scoped unit are checked by the process rules.
+    pub scope: Option<&'a UnitScope>,
+    /// The load-out of the phase, see [`RuleContext::load_out_items`].
+    pub load_out_items: &'a [LoadOutItem],
+    /// Rules of the process that are not enforced for the action.
+    pub overridden_rules: &'a [ProcessRuleReference],
+    /// The values of the parameters of a custom task, only allowed when completing a custom task, see
+    /// [`process::CustomTaskDefinition::validate_values`].
+    pub parameter_values: BTreeMap<String, TaskParameterValue>,
+}
+
+/// The action is rejected if it violates any of the rules of the phase's process, unless the rule is overridden, see
+/// [`process::check_rules`].  The violations of overridden rules are recorded in the operation history.
 pub fn apply_phase_operation_task_action(
     project: &mut Project,
     directory: &Path,
@@ -2461,7 +4399,15 @@ pub fn apply_phase_operation_task_action(
     operation_reference: OperationReference,
     task_reference: TaskReference,
     action: TaskAction,
+    options: TaskActionOptions,
 ) -> anyhow::Result<bool> {
+    let TaskActionOptions {
+        scope,
+        load_out_items,
+        overridden_rules,
+        parameter_values,
+    } = options;
+
     let mut modified = false;
 
     let phase_state = project
@@ -2508,6 +4454,60 @@ pub fn apply_phase_operation_task_action(
         possible_task_references,
     ))?;
 
+    let (overridden_violations, custom_task) = {
+        let phase = project
+            .phases
+            .get(phase_reference)
+            .ok_or(PhaseError::UnknownPhase(phase_reference.clone()))?;
+        let process_definition = project.find_process(&phase.process)?;
+
+        let custom_task = process_definition
+            .operations
+            .iter()
+            .find(|operation| {
+                operation
+                    .reference
+                    .eq(&operation_reference)
+            })
+            .and_then(|operation| operation.find_custom_task(&task_reference))
+            .cloned();
+
+        let placements = build_phase_placement_states(project, phase_reference)
+            .into_iter()
+            .filter(|(_object_path, state)| scope.map_or(true, |scope| state.unit_path.eq(&scope.unit_path)))
+            .collect::<Vec<_>>();
+
+        let rule_context = RuleContext {
+            task: &task_reference,
+            action: &action,
+            placements: &placements,
+            load_out_items,
+            part_aliases: &project.part_aliases,
+        };
+
+        let overridden_violations = process::check_rules(process_definition, &rule_context, overridden_rules)?;
+
+        (overridden_violations, custom_task)
+    };
+
+    let parameter_values = match (&custom_task, &action) {
+        (Some(custom_task), TaskAction::Complete) => custom_task.validate_values(&parameter_values)?,
+        _ if parameter_values.is_empty() => BTreeMap::new(),
+        _ => {
+            return Err(TaskParameterError::UnexpectedValues {
+                task: task_reference,
+            }
+            .into())
+        }
+    };
+
+    for violation in overridden_violations.iter() {
+        warn!(
+            "Process rule overridden. phase: {}, operation: {}, task: {}, violation: {}",
+            phase_reference, operation_reference, task_reference, violation
+        );
+    }
+
     // make sure the operation's CAN be changed.
     // reasons why it might not be possible include:
     // 1) trying to change a task where preceding tasks or operations are not in the correct state
@@ -2530,7 +4530,11 @@ pub fn apply_phase_operation_task_action(
                 "Marking task as completed. phase: {}, operation: {}, task: {}",
                 phase_reference, operation_reference, task_reference
             );
-            task_state.set_completed()
+            task_state.set_completed();
+
+            if let Some(custom_state) = task_state.custom_state_mut() {
+                custom_state.parameter_values = parameter_values;
+            }
         }
         TaskAction::Abandon => {
             info!(
@@ -2543,21 +4547,39 @@ pub fn apply_phase_operation_task_action(
 
     let mut task_history_items: Vec<(&TaskReference, Box<dyn OperationHistoryKind>)> = Vec::new();
 
-    if let Some(task_history_item) = build_operation_task_history_item(&task_reference, task_state.status()) {
+    if let Some(task_history_item) =
+        build_operation_task_history_item(&task_reference, task_state.status(), task_state.custom_state())
+    {
         task_history_items.push(task_history_item);
     }
 
-    fn build_operation_task_history_item(
-        reference: &TaskReference,
+    fn build_operation_task_history_item<'r>(
+        reference: &'r TaskReference,
         new_status: TaskStatus,
-    ) -> Option<(&TaskReference, Box<dyn OperationHistoryKind>)> {
-        if reference.eq(&TaskReference::from_raw_str("core::load_pcbs")) {
+        custom_state: Option<&CustomTaskState>,
+    ) -> Option<(&'r TaskReference, Box<dyn OperationHistoryKind>)> {
+        if let Some(custom_state) = custom_state {
+            Some((
+                reference,
+                Box::new(CustomOperationTaskHistoryKind {
+                    status: new_status,
+                    parameter_values: custom_state.parameter_values.clone(),
+                }) as Box<dyn OperationHistoryKind>,
+            ))
+        } else if reference.eq(&TaskReference::from_raw_str("core::load_pcbs")) {
             Some((
                 reference,
                 Box::new(LoadPcbsOperationTaskHistoryKind {
                     status: new_status,
                 }) as Box<dyn OperationHistoryKind>,
             ))
+        } else if reference.eq(&TaskReference::from_raw_str("core::apply_solder_paste")) {
+            Some((
+                reference,
+                Box::new(ApplySolderPasteOperationTaskHistoryKind {
+                    status: new_status,
+                }) as Box<dyn OperationHistoryKind>,
+            ))
         } else if reference.eq(&TaskReference::from_raw_str("core::place_components")) {
             Some((
                 reference,
@@ -2597,6 +4619,9 @@ fn build_operation_task_history_item(
                 operation_reference: operation_reference.clone(),
                 task_reference: task_reference.clone(),
                 task_history,
+                serial_number: scope.map(|scope| scope.serial_number.clone()),
+                build_run: build_run::active_build_run_instance(project),
+                overridden_rules: overridden_violations.clone(),
                 extra: Default::default(),
             };
 
@@ -2683,6 +4708,18 @@ fn reset_phase_operations(project: &mut Project) {
     }
 }
 
+/// `true` if the part, or any of its aliases, matches the patterns.
+pub fn matches_part_or_alias(
+    part_aliases: &PartAliases,
+    part: &Part,
+    manufacturer_pattern: &Regex,
+    mpn_pattern: &Regex,
+) -> bool {
+    part_aliases
+        .aliases(part)
+        .any(|alias| manufacturer_pattern.is_match(&alias.manufacturer) && mpn_pattern.is_match(&alias.mpn))
+}
+
 pub fn find_phase_parts(
     project: &Project,
     phase_reference: &Reference,
@@ -2694,13 +4731,12 @@ pub fn find_phase_parts(
         .iter()
         .filter_map(|(_object_path, placement_state)| match &placement_state.phase {
             Some(candidate_phase) if candidate_phase.eq(phase_reference) => {
-                if manufacturer_pattern.is_match(
-                    &placement_state
-                        .placement
-                        .part
-                        .manufacturer,
-                ) && mpn_pattern.is_match(&placement_state.placement.part.mpn)
-                {
+                if matches_part_or_alias(
+                    &project.part_aliases,
+                    &placement_state.placement.part,
+                    &manufacturer_pattern,
+                    &mpn_pattern,
+                ) {
                     Some(placement_state.placement.part.clone())
                 } else {
                     None
@@ -2711,6 +4747,131 @@ pub fn find_phase_parts(
         .collect()
 }
 
+#[cfg(test)]
+mod matches_part_or_alias_tests {
+    use super::*;
+
+    #[test]
+    pub fn matches_alias() {
+        // given
+        let part1 = Part::new("MFR1".to_string(), "RES1".to_string());
+        let part2 = Part::new("MFR2".to_string(), "RES2".to_string());
+        let part3 = Part::new("MFR3".to_string(), "RES3".to_string());
+
+        let mut part_aliases = PartAliases::default();
+        part_aliases.add("R_10K_0402".to_string(), part1.clone());
+        part_aliases.add("R_10K_0402".to_string(), part2.clone());
+
+        // and
+        let manufacturer_pattern = Regex::new("^MFR2$").unwrap();
+        let mpn_pattern = Regex::new(".*").unwrap();
+
+        // expect
+        assert!(matches_part_or_alias(
+            &part_aliases,
+            &part1,
+            &manufacturer_pattern,
+            &mpn_pattern
+        ));
+        assert!(matches_part_or_alias(
+            &part_aliases,
+            &part2,
+            &manufacturer_pattern,
+            &mpn_pattern
+        ));
+        assert!(!matches_part_or_alias(
+            &part_aliases,
+            &part3,
+            &manufacturer_pattern,
+            &mpn_pattern
+        ));
+    }
+}
+
+#[cfg(test)]
+mod build_phase_placement_records_tests {
+    use indexmap::IndexSet;
+    use nalgebra::Vector2;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::design::DesignName;
+
+    fn placement_state() -> (ObjectPath, PlacementState) {
+        let object_path = ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R1");
+        let placement_state = PlacementState {
+            unit_path: object_path.pcb_unit_path().unwrap(),
+            unit_position: UnitPlacementPosition {
+                x: dec!(10),
+                y: dec!(20),
+                rotation: dec!(0),
+            },
+            ..PlacementState::default()
+        };
+        (object_path, placement_state)
+    }
+
+    fn build_pcb() -> Pcb {
+        Pcb::new(
+            "panel".to_string(),
+            1,
+            IndexSet::from([DesignName::from("design_a")]),
+            BTreeMap::from([(0, 0)]),
+        )
+    }
+
+    fn top_left_settings() -> ProjectSettings {
+        ProjectSettings {
+            origin: OriginConvention::TopLeft,
+            ..ProjectSettings::default()
+        }
+    }
+
+    #[test]
+    pub fn top_left_origin_uses_the_panel_height() {
+        // given
+        let (object_path, placement_state) = placement_state();
+        let mut pcb = build_pcb();
+        pcb.panel_sizing.size = Vector2::new(100.0, 50.0);
+
+        // when
+        let records = build_phase_placement_records(
+            &[(&object_path, &placement_state)],
+            &[],
+            &PartAliases::default(),
+            &BTreeMap::new(),
+            &RotationOffsets::default(),
+            &top_left_settings(),
+            &[&pcb],
+        )
+        .unwrap();
+
+        // then
+        assert_eq!((records[0].x, records[0].y), (dec!(10), dec!(30)));
+    }
+
+    #[test]
+    pub fn top_left_origin_without_a_panel_size_is_an_error() {
+        // given
+        let (object_path, placement_state) = placement_state();
+        let pcb = build_pcb();
+
+        // when
+        let result = build_phase_placement_records(
+            &[(&object_path, &placement_state)],
+            &[],
+            &PartAliases::default(),
+            &BTreeMap::new(),
+            &RotationOffsets::default(),
+            &top_left_settings(),
+            &[&pcb],
+        );
+
+        // then
+        assert!(result.is_err());
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ProjectError {
     #[error("Unable to load placements, cause: {0}")]
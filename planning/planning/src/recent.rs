@@ -0,0 +1,268 @@
+//! Recently opened projects, shared by the GUI and the CLI, see [`RecentProjects`].
+//!
+//! The GUI persists the recent projects with its configuration, and exports them to a file, see
+//! [`default_recent_projects_file_path`], so that the CLI can list them.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+use time::serde::rfc3339;
+use time::OffsetDateTime;
+
+/// The maximum amount of unpinned recent projects, the least recently opened are removed first.
+pub const MAX_RECENT_PROJECTS: usize = 10;
+
+/// Overrides the path of the recent projects file.
+pub const RECENT_PROJECTS_FILE_ENV: &str = "MAKERPNP_RECENT_PROJECTS";
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecentProject {
+    pub path: PathBuf,
+    pub name: String,
+    #[serde(with = "rfc3339")]
+    pub last_opened: OffsetDateTime,
+    /// Pinned projects are never removed to make room for more recently opened projects.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Most recently opened first.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct RecentProjects(Vec<RecentProject>);
+
+impl RecentProjects {
+    /// Recent projects without names, e.g. persisted by older versions, most recently opened first.
+    ///
+    /// The names are derived from the paths, see [`project_name_from_path`].
+    pub fn from_paths(paths: Vec<PathBuf>, opened: OffsetDateTime) -> Self {
+        let mut recent_projects = Self::default();
+        for path in paths.iter().rev() {
+            recent_projects.add(path, project_name_from_path(path), opened);
+        }
+        recent_projects
+    }
+
+    /// Moves the project to the start, keeping its pinned state, then removes the least recently opened unpinned
+    /// projects, if there are too many.
+    ///
+    /// `name` is the name of the project, not the name of the file, they can differ.
+    pub fn add(&mut self, path: &Path, name: String, opened: OffsetDateTime) {
+        let pinned = self
+            .0
+            .iter()
+            .find(|candidate| candidate.path.eq(path))
+            .is_some_and(|candidate| candidate.pinned);
+
+        self.remove(path);
+        self.0.insert(0, RecentProject {
+            path: path.to_path_buf(),
+            name,
+            last_opened: opened,
+            pinned,
+        });
+
+        while self
+            .0
+            .iter()
+            .filter(|candidate| !candidate.pinned)
+            .count()
+            > MAX_RECENT_PROJECTS
+        {
+            let Some(index) = self
+                .0
+                .iter()
+                .rposition(|candidate| !candidate.pinned)
+            else {
+                break;
+            };
+            self.0.remove(index);
+        }
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        self.0
+            .retain(|candidate| !candidate.path.eq(path));
+    }
+
+    /// Does nothing if the project is not a recent project.
+    pub fn set_pinned(&mut self, path: &Path, pinned: bool) {
+        if let Some(project) = self
+            .0
+            .iter_mut()
+            .find(|candidate| candidate.path.eq(path))
+        {
+            project.pinned = pinned;
+        }
+    }
+
+    /// Most recently opened first.
+    pub fn iter(&self) -> impl Iterator<Item = &RecentProject> {
+        self.0.iter()
+    }
+
+    /// Pinned projects first, then the most recently opened first, as they should be displayed.
+    pub fn sorted(&self) -> Vec<&RecentProject> {
+        let mut projects = self.0.iter().collect::<Vec<_>>();
+        // reminder: the sort is stable, the most recently opened order is kept.
+        projects.sort_by_key(|project| !project.pinned);
+        projects
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// e.g. `projects/project-job1.mpnp.json` -> `job1`
+pub fn project_name_from_path(path: &Path) -> String {
+    let file_name = path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let name = file_name
+        .strip_suffix(".mpnp.json")
+        .unwrap_or(&file_name);
+    let name = name
+        .strip_prefix("project-")
+        .unwrap_or(name);
+
+    name.to_string()
+}
+
+/// `None` if there is no home directory, e.g. on the web.
+pub fn default_recent_projects_file_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(RECENT_PROJECTS_FILE_ENV) {
+        return Some(PathBuf::from(path));
+    }
+
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| {
+            PathBuf::from(home)
+                .join(".makerpnp")
+                .join("recent_projects.json")
+        })
+}
+
+#[derive(Error, Debug)]
+pub enum RecentProjectsError {
+    #[error("IO error. cause: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid recent projects file. cause: {0}")]
+    InvalidRecentProjectsFile(#[from] serde_json::Error),
+}
+
+/// An empty list is returned if the file does not exist.
+pub fn load_recent_projects(path: &Path) -> Result<RecentProjects, RecentProjectsError> {
+    if !path.exists() {
+        return Ok(RecentProjects::default());
+    }
+
+    let file = File::open(path)?;
+    let recent_projects = serde_json::from_reader(BufReader::new(file))?;
+
+    Ok(recent_projects)
+}
+
+/// The parent directory is created if required.
+pub fn save_recent_projects(recent_projects: &RecentProjects, path: &Path) -> Result<(), RecentProjectsError> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, recent_projects)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod recent_tests {
+    use time::Duration;
+
+    use super::*;
+
+    #[test]
+    pub fn pinned_projects_are_kept() {
+        // given
+        let now = OffsetDateTime::UNIX_EPOCH;
+        let mut recent_projects = RecentProjects::default();
+        recent_projects.add(Path::new("project-pinned.mpnp.json"), "pinned".to_string(), now);
+        recent_projects.set_pinned(Path::new("project-pinned.mpnp.json"), true);
+
+        // when
+        for index in 0..=MAX_RECENT_PROJECTS {
+            recent_projects.add(
+                &PathBuf::from(format!("project-{}.mpnp.json", index)),
+                format!("{}", index),
+                now + Duration::minutes(index as i64 + 1),
+            );
+        }
+
+        // then
+        assert_eq!(recent_projects.len(), MAX_RECENT_PROJECTS + 1);
+        let names = recent_projects
+            .sorted()
+            .iter()
+            .map(|project| project.name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names[0], "pinned");
+        assert_eq!(names[1], format!("{}", MAX_RECENT_PROJECTS));
+        // and the least recently opened unpinned project was removed
+        assert!(!names.contains(&"0".to_string()));
+    }
+
+    #[test]
+    pub fn reopening_a_pinned_project_keeps_it_pinned() {
+        // given
+        let now = OffsetDateTime::UNIX_EPOCH;
+        let mut recent_projects = RecentProjects::default();
+        recent_projects.add(Path::new("project-job1.mpnp.json"), "job1".to_string(), now);
+        recent_projects.set_pinned(Path::new("project-job1.mpnp.json"), true);
+        recent_projects.add(Path::new("project-job2.mpnp.json"), "job2".to_string(), now);
+
+        // when
+        recent_projects.add(
+            Path::new("project-job1.mpnp.json"),
+            "renamed".to_string(),
+            now + Duration::hours(1),
+        );
+
+        // then
+        let project = recent_projects.iter().next().unwrap();
+        assert_eq!(project.name, "renamed");
+        assert!(project.pinned);
+        assert_eq!(project.last_opened, now + Duration::hours(1));
+        assert_eq!(recent_projects.len(), 2);
+    }
+
+    #[test]
+    pub fn names_are_derived_from_paths() {
+        // given
+        let now = OffsetDateTime::UNIX_EPOCH;
+        let paths = vec![
+            PathBuf::from("projects/project-job2.mpnp.json"),
+            PathBuf::from("projects/project-job1.mpnp.json"),
+        ];
+
+        // when
+        let recent_projects = RecentProjects::from_paths(paths, now);
+
+        // then
+        let names = recent_projects
+            .iter()
+            .map(|project| project.name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["job2", "job1"]);
+    }
+}
@@ -0,0 +1,60 @@
+//! Crash-recovery snapshots.
+//!
+//! A snapshot of the project and its modified PCBs is periodically written to a sidecar file, next to the project
+//! file, see [`build_recovery_file_path`].  The sidecar is removed when the project is saved, so a sidecar found when
+//! loading a project contains changes that were not saved, e.g. due to a crash, and can be restored.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use time::serde::rfc3339;
+use time::OffsetDateTime;
+
+use crate::pcb::Pcb;
+use crate::project::Project;
+
+/// How often shells take a snapshot, while there are unsaved changes.
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RecoverySnapshot {
+    #[serde(with = "rfc3339")]
+    pub taken: OffsetDateTime,
+    pub project: Project,
+    /// Only the PCBs that were modified.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub pcbs: Vec<RecoveryPcb>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RecoveryPcb {
+    /// The path of the PCB file, the snapshot is restored to this path.
+    pub path: PathBuf,
+    pub pcb: Pcb,
+}
+
+/// e.g. `project-job1.mpnp.json` -> `project-job1.mpnp.json.recovery`
+pub fn build_recovery_file_path(project_path: &Path) -> PathBuf {
+    let mut file_name = project_path
+        .file_name()
+        .map(|file_name| file_name.to_os_string())
+        .unwrap_or_default();
+    file_name.push(".recovery");
+
+    project_path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod recovery_tests {
+    use super::*;
+
+    #[test]
+    pub fn recovery_file_path() {
+        // expect
+        assert_eq!(
+            build_recovery_file_path(Path::new("projects/project-job1.mpnp.json")),
+            PathBuf::from("projects/project-job1.mpnp.json.recovery")
+        );
+    }
+}
@@ -0,0 +1,382 @@
+//! Detects dangling file references, e.g. after moving or renaming files, and searches for the missing files.
+//!
+//! Candidates are found by file name, candidates with identical content are only included once, so a file that has
+//! been copied to several places is still an unambiguous match.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use tracing::{info, trace};
+use util::source::Source;
+
+use crate::design::DesignIndex;
+use crate::file::FileReference;
+use crate::pcb::Pcb;
+use crate::phase::PhaseReference;
+use crate::project::Project;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DanglingFileKind {
+    Pcb {
+        pcb_index: usize,
+    },
+    Gerber {
+        pcb_index: usize,
+        /// `None` for PCB gerbers
+        design_index: Option<DesignIndex>,
+    },
+    LoadOut {
+        phase: PhaseReference,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DanglingFile {
+    pub kind: DanglingFileKind,
+    /// The path that does not exist.
+    pub path: PathBuf,
+    /// Existing files with the same file name, in search order.
+    pub candidates: Vec<PathBuf>,
+}
+
+impl DanglingFile {
+    fn new(kind: DanglingFileKind, path: PathBuf) -> Self {
+        Self {
+            kind,
+            path,
+            candidates: vec![],
+        }
+    }
+
+    /// The file to relink to, only if there is exactly one candidate.
+    pub fn resolution(&self) -> Option<&PathBuf> {
+        match self.candidates.as_slice() {
+            [candidate] => Some(candidate),
+            _ => None,
+        }
+    }
+}
+
+/// Finds the project's PCB files and CSV load-outs that do not exist.
+pub fn find_dangling_project_files(project: &Project, project_directory: &Path) -> Vec<DanglingFile> {
+    let project_directory = project_directory.to_path_buf();
+
+    let dangling_pcbs = project
+        .pcbs
+        .iter()
+        .enumerate()
+        .map(|(pcb_index, project_pcb)| {
+            (
+                DanglingFileKind::Pcb {
+                    pcb_index,
+                },
+                project_pcb
+                    .pcb_file
+                    .build_path(&project_directory),
+            )
+        });
+
+    // load-outs in a database, and remote load-outs, are not files
+    let dangling_load_outs = project
+        .phases
+        .values()
+        .filter(|_phase| project.store_backend.is_csv())
+        .filter_map(|phase| match phase.load_out_source.parse::<Source>() {
+            Ok(Source::File(path)) => Some((
+                DanglingFileKind::LoadOut {
+                    phase: phase.reference.clone(),
+                },
+                resolve(&project_directory, path),
+            )),
+            _ => None,
+        });
+
+    dangling_pcbs
+        .chain(dangling_load_outs)
+        .filter(|(_kind, path)| !path.exists())
+        .map(|(kind, path)| DanglingFile::new(kind, path))
+        .collect()
+}
+
+/// Finds the PCB's gerber files that do not exist.
+pub fn find_dangling_gerbers(pcb_index: usize, pcb: &Pcb) -> Vec<DanglingFile> {
+    let pcb_gerbers = pcb
+        .pcb_gerbers
+        .iter()
+        .map(|gerber| (None, gerber));
+    let design_gerbers = pcb
+        .design_gerbers
+        .iter()
+        .flat_map(|(design_index, gerbers)| {
+            gerbers
+                .iter()
+                .map(|gerber| (Some(*design_index), gerber))
+        });
+
+    pcb_gerbers
+        .chain(design_gerbers)
+        .filter(|(_design_index, gerber)| !gerber.file.exists())
+        .map(|(design_index, gerber)| {
+            DanglingFile::new(
+                DanglingFileKind::Gerber {
+                    pcb_index,
+                    design_index,
+                },
+                gerber.file.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Searches the roots, recursively, for candidates for each dangling file.
+pub fn search(dangling_files: &mut [DanglingFile], search_roots: &[PathBuf]) -> Result<(), std::io::Error> {
+    let mut files_by_name: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for search_root in search_roots {
+        info!("Searching for files. root: {:?}", search_root);
+        collect_files(search_root, &mut files_by_name)?;
+    }
+
+    for dangling_file in dangling_files.iter_mut() {
+        let Some(files) = dangling_file
+            .path
+            .file_name()
+            .and_then(|name| files_by_name.get(name.to_string_lossy().as_ref()))
+        else {
+            continue;
+        };
+
+        let mut content_hashes = vec![];
+        for file in files {
+            let content_hash = hash_file(file)?;
+            if !content_hashes.contains(&content_hash) {
+                content_hashes.push(content_hash);
+                dangling_file
+                    .candidates
+                    .push(file.clone());
+            }
+        }
+        trace!(
+            "Candidates found. path: {:?}, candidates: {:?}",
+            dangling_file.path,
+            dangling_file.candidates
+        );
+    }
+
+    Ok(())
+}
+
+/// Relinks a PCB file or load-out to the file, returns true if the project was modified.
+///
+/// PCB files within the project directory use relative file references.
+pub fn relink_project_file(
+    project: &mut Project,
+    project_directory: &Path,
+    kind: &DanglingFileKind,
+    file: &Path,
+) -> bool {
+    match kind {
+        DanglingFileKind::Pcb {
+            pcb_index,
+        } => {
+            let Some(project_pcb) = project.pcbs.get_mut(*pcb_index) else {
+                return false;
+            };
+
+            project_pcb.pcb_file = match file.strip_prefix(project_directory) {
+                Ok(relative_path) => FileReference::Relative(relative_path.to_path_buf()),
+                Err(_) => FileReference::Absolute(file.to_path_buf()),
+            };
+            info!("Relinked PCB file. pcb_file: {}", project_pcb.pcb_file);
+            true
+        }
+        DanglingFileKind::LoadOut {
+            phase,
+        } => {
+            let Some(phase) = project.phases.get_mut(phase) else {
+                return false;
+            };
+
+            phase.load_out_source = file.display().to_string();
+            info!(
+                "Relinked load-out. phase: {}, load_out_source: {}",
+                phase.reference, phase.load_out_source
+            );
+            true
+        }
+        DanglingFileKind::Gerber {
+            ..
+        } => false,
+    }
+}
+
+/// Relinks a gerber file to the file, returns true if the PCB was modified.
+pub fn relink_gerber(pcb: &mut Pcb, dangling_file: &DanglingFile, file: &Path) -> bool {
+    let DanglingFileKind::Gerber {
+        design_index, ..
+    } = &dangling_file.kind
+    else {
+        return false;
+    };
+
+    let gerbers = match design_index {
+        None => Some(&mut pcb.pcb_gerbers),
+        Some(design_index) => pcb.design_gerbers.get_mut(design_index),
+    };
+
+    let Some(gerber) = gerbers.and_then(|gerbers| {
+        gerbers
+            .iter_mut()
+            .find(|gerber| gerber.file.eq(&dangling_file.path))
+    }) else {
+        return false;
+    };
+
+    gerber.file = file.to_path_buf();
+    info!("Relinked gerber. file: {:?}", gerber.file);
+    true
+}
+
+fn resolve(project_directory: &Path, path: PathBuf) -> PathBuf {
+    match path.is_absolute() {
+        true => path,
+        false => project_directory.join(path),
+    }
+}
+
+fn collect_files(directory: &Path, files_by_name: &mut BTreeMap<String, Vec<PathBuf>>) -> Result<(), std::io::Error> {
+    let mut entries = std::fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    // for a stable candidate order
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            collect_files(&path, files_by_name)?;
+        } else if let Some(name) = path.file_name() {
+            files_by_name
+                .entry(name.to_string_lossy().to_string())
+                .or_default()
+                .push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn hash_file(path: &Path) -> Result<u64, std::io::Error> {
+    let content = std::fs::read(path)?;
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use pnp::pcb::PcbSide;
+    use pnp::reference::Reference;
+
+    use super::*;
+    use crate::project::ProjectPcb;
+
+    #[test]
+    fn find_and_relink() {
+        // given
+        let directory = tempfile::tempdir().unwrap();
+        let project_directory = directory.path().join("project");
+        let moved_directory = directory.path().join("moved");
+        let copy_directory = directory.path().join("copy");
+        for path in [&project_directory, &moved_directory, &copy_directory] {
+            std::fs::create_dir_all(path).unwrap();
+        }
+
+        // and
+        std::fs::write(moved_directory.join("panel_a.pcb.json"), "{}").unwrap();
+        std::fs::write(moved_directory.join("load_out_1.csv"), "1").unwrap();
+        std::fs::write(copy_directory.join("load_out_1.csv"), "2").unwrap();
+
+        // and
+        let mut project = Project::new("job1".to_string(), None, None);
+        project
+            .pcbs
+            .push(ProjectPcb::new(FileReference::Relative("panel_a.pcb.json".into())));
+        project
+            .update_phase(
+                Reference::from_raw_str("top_1"),
+                Reference::from_raw_str("pnp"),
+                "load_out_1.csv".to_string(),
+                PcbSide::Top,
+            )
+            .unwrap();
+
+        // when
+        let mut dangling_files = find_dangling_project_files(&project, &project_directory);
+        search(&mut dangling_files, &[moved_directory.clone(), copy_directory.clone()]).unwrap();
+
+        // then
+        assert_eq!(dangling_files, vec![
+            DanglingFile {
+                kind: DanglingFileKind::Pcb {
+                    pcb_index: 0
+                },
+                path: project_directory.join("panel_a.pcb.json"),
+                candidates: vec![moved_directory.join("panel_a.pcb.json")],
+            },
+            DanglingFile {
+                kind: DanglingFileKind::LoadOut {
+                    phase: Reference::from_raw_str("top_1")
+                },
+                path: project_directory.join("load_out_1.csv"),
+                candidates: vec![
+                    moved_directory.join("load_out_1.csv"),
+                    copy_directory.join("load_out_1.csv"),
+                ],
+            },
+        ]);
+
+        // when
+        let results = dangling_files
+            .iter()
+            .map(|dangling_file| {
+                dangling_file
+                    .resolution()
+                    .map(|file| relink_project_file(&mut project, &project_directory, &dangling_file.kind, file))
+            })
+            .collect::<Vec<_>>();
+
+        // then
+        assert_eq!(results, vec![Some(true), None]);
+        assert_eq!(
+            project.pcbs[0].pcb_file,
+            FileReference::Absolute(moved_directory.join("panel_a.pcb.json"))
+        );
+    }
+
+    #[test]
+    fn identical_candidates_are_included_once() {
+        // given
+        let directory = tempfile::tempdir().unwrap();
+        let copy_directory = directory.path().join("copy");
+        std::fs::create_dir_all(&copy_directory).unwrap();
+        std::fs::write(directory.path().join("top_copper.gbr"), "G04*").unwrap();
+        std::fs::write(copy_directory.join("top_copper.gbr"), "G04*").unwrap();
+
+        // and
+        let mut dangling_files = vec![DanglingFile::new(
+            DanglingFileKind::Gerber {
+                pcb_index: 0,
+                design_index: None,
+            },
+            PathBuf::from("/missing/top_copper.gbr"),
+        )];
+
+        // when
+        search(&mut dangling_files, &[directory.path().to_path_buf()]).unwrap();
+
+        // then
+        assert_eq!(dangling_files[0].candidates.len(), 1);
+        assert!(dangling_files[0].resolution().is_some());
+    }
+}
@@ -23,11 +23,20 @@
 use crate::file::FileReference;
 use crate::pcb::Pcb;
 use crate::phase::{PhaseReference, PhaseStatus};
-use crate::placement::{PlacementState, ProjectPlacementStatus};
+use crate::placement::PlacementState;
 use crate::process::{OperationReference, OperationStatus, TaskReference};
-use crate::project::{build_phase_placement_states, Project};
+use crate::project::Project;
+use crate::report::issues::IssueCheck;
+use crate::serial_number::SerialNumber;
 use crate::variant::VariantName;
 
+pub mod analytics;
+pub mod handover;
+pub mod inspection;
+pub mod issues;
+pub mod labels;
+pub mod paste;
+
 // FUTURE add a test to ensure that duplicate issues are not added to the report.
 //        currently a BTreeSet is used to prevent duplicate issues.
 
@@ -38,73 +47,7 @@ pub fn project_generate_report(
 ) -> ProjectReport {
     let mut report = ProjectReport::default();
 
-    let mut issue_set: BTreeSet<ProjectReportIssue> = BTreeSet::new();
-
     report.name.clone_from(&project.name);
-    if project.pcbs.is_empty() {
-        issue_set.insert(ProjectReportIssue {
-            message: "No PCBs have been assigned to the project.".to_string(),
-            severity: IssueSeverity::Severe,
-            kind: IssueKind::NoPcbsAssigned,
-        });
-    } else {
-        for pcb in project.pcbs.iter() {
-            if pcb.unit_assignments.is_empty() {
-                issue_set.insert(ProjectReportIssue {
-                    message: "A PCB has no unit assignments.".to_string(),
-                    severity: IssueSeverity::Severe,
-                    kind: IssueKind::PcbWithNoUnitAssignments {
-                        file: pcb.pcb_file.clone(),
-                    },
-                });
-            }
-        }
-    }
-
-    if project.placements.is_empty() {
-        issue_set.insert(ProjectReportIssue {
-            message: "No placements.".to_string(),
-            severity: IssueSeverity::Severe,
-            kind: IssueKind::NoPlacements,
-        });
-    }
-
-    for phase_reference in project.phase_orderings.iter() {
-        let phase_placement_states = build_phase_placement_states(project, phase_reference);
-        if phase_placement_states.is_empty() {
-            issue_set.insert(ProjectReportIssue {
-                message: "Phase with no placements.".to_string(),
-                severity: IssueSeverity::Warning,
-                kind: IssueKind::PhaseWithNoPlacements {
-                    phase: phase_reference.clone(),
-                },
-            });
-        }
-
-        for (_object_path, placement_state) in phase_placement_states.iter() {
-            let load_out_items = phase_load_out_items_map
-                .get(phase_reference)
-                .unwrap();
-
-            let feeder_reference =
-                match pnp::load_out::find_load_out_item_by_part(load_out_items, &placement_state.placement.part) {
-                    Some(load_out_item) => load_out_item.reference.clone(),
-                    _ => None,
-                };
-
-            if feeder_reference.is_none() {
-                let issue = ProjectReportIssue {
-                    message: "A part has not been assigned to a feeder".to_string(),
-                    severity: IssueSeverity::Warning,
-                    kind: IssueKind::UnassignedPartFeeder {
-                        phase: phase_reference.clone(),
-                        part: placement_state.placement.part.clone(),
-                    },
-                };
-                issue_set.insert(issue);
-            };
-        }
-    }
 
     let mut all_phases_complete = true;
 
@@ -144,6 +87,10 @@ pub fn project_generate_report(
                                                 })
                                                     as Box<dyn TaskOverview>
                                             })
+                                    } else if task_reference
+                                        .eq(&TaskReference::from_raw_str("core::apply_solder_paste"))
+                                    {
+                                        Some(Box::new(ApplySolderPasteTaskOverview {}) as Box<dyn TaskOverview>)
                                     } else if task_reference
                                         .eq(&TaskReference::from_raw_str("core::automated_soldering"))
                                     {
@@ -184,12 +131,6 @@ pub fn project_generate_report(
                     }
                 }),
         );
-    } else {
-        issue_set.insert(ProjectReportIssue {
-            message: "No phases have been created.".to_string(),
-            severity: IssueSeverity::Severe,
-            kind: IssueKind::NoPhasesCreated,
-        });
     }
 
     report.status = match all_phases_complete {
@@ -207,19 +148,16 @@ pub fn project_generate_report(
         .phase_specifications
         .extend(phase_specifications);
 
-    project_report_add_placement_issues(project, &mut issue_set);
-    let mut issues: Vec<ProjectReportIssue> = issue_set.iter().cloned().collect();
-
-    project_report_sort_issues(&mut issues);
+    report.issues = issues::analyze(project, pcbs, phase_load_out_items_map, IssueCheck::REPORT);
 
-    for issue in issues.iter() {
-        info!(
-            "Issue detected. severity: {:?}, message: '{}', kind: {:?}",
-            issue.severity, issue.message, issue.kind
-        );
-    }
-
-    report.issues = issues;
+    report.serial_numbers = project
+        .all_serial_numbers()
+        .into_iter()
+        .map(|(unit_path, serial_number)| UnitSerialNumberItem {
+            unit_path,
+            serial_number,
+        })
+        .collect();
 
     report
 }
@@ -245,8 +183,9 @@ fn build_phase_specification(
             .filter(|(_object_path, placement_state)| {
                 matches!(&placement_state.phase, Some(other_phase_reference) if phase.reference.eq(other_phase_reference))
                     && placement_state.placement.place
-                    && load_out_item.manufacturer.eq(&placement_state.placement.part.manufacturer)
-                    && load_out_item.mpn.eq(&placement_state.placement.part.mpn)
+                    // placements of an alias are counted for the item that would be used, not for every alias
+                    && pnp::load_out::find_load_out_item_by_part_or_alias(load_out_items, &placement_state.placement.part, &project.part_aliases)
+                        .is_some_and(|candidate| std::ptr::eq(candidate, load_out_item))
             })
             .fold(0_u32, |quantity, _placement_state| {
                 quantity + 1
@@ -275,6 +214,8 @@ fn build_phase_specification(
                         }) as Box<dyn TaskSpecification>)
                     } else if task_reference.eq(&TaskReference::from_raw_str("core::place_components")) {
                         Some(Box::new(PlaceComponentsTaskSpecification {}) as Box<dyn TaskSpecification>)
+                    } else if task_reference.eq(&TaskReference::from_raw_str("core::apply_solder_paste")) {
+                        Some(Box::new(ApplySolderPasteTaskSpecification {}) as Box<dyn TaskSpecification>)
                     } else if task_reference.eq(&TaskReference::from_raw_str("core::automated_soldering")) {
                         Some(Box::new(AutomatedSolderingTaskSpecification {}) as Box<dyn TaskSpecification>)
                     } else if task_reference.eq(&TaskReference::from_raw_str("core::manual_soldering")) {
@@ -348,25 +289,7 @@ fn build_unit_paths_with_placements(placement_states: &BTreeMap<ObjectPath, Plac
     )
 }
 
-fn project_report_add_placement_issues(project: &Project, issues: &mut BTreeSet<ProjectReportIssue>) {
-    for (object_path, _placement_state) in project
-        .placements
-        .iter()
-        .filter(|(_object_path, placement_state)| {
-            placement_state.phase.is_none() && placement_state.project_status == ProjectPlacementStatus::Used
-        })
-    {
-        issues.insert(ProjectReportIssue {
-            message: "A placement has not been assigned to a phase".to_string(),
-            severity: IssueSeverity::Warning,
-            kind: IssueKind::UnassignedPlacement {
-                object_path: object_path.clone(),
-            },
-        });
-    }
-}
-
-fn project_report_sort_issues(issues: &mut [ProjectReportIssue]) {
+pub(crate) fn project_report_sort_issues(issues: &mut [ProjectReportIssue]) {
     issues.sort_by(|a, b| {
         let sort_orderings = &[
             ("severity", SortOrder::Desc),
@@ -398,6 +321,21 @@ fn kind_ordinal(kind: &IssueKind) -> usize {
                         IssueKind::UnassignedPartFeeder {
                             ..
                         } => 6,
+                        IssueKind::UnitAssignmentWithUnknownDesign {
+                            ..
+                        } => 7,
+                        IssueKind::PcbWithNoProfileGerber {
+                            ..
+                        } => 8,
+                        IssueKind::PhaseWithEmptyLoadOut {
+                            ..
+                        } => 9,
+                        IssueKind::PartWithNoProcesses {
+                            ..
+                        } => 10,
+                        IssueKind::PlacementOnOtherPcbSide {
+                            ..
+                        } => 11,
                     }
                 }
                 fn severity_ordinal(severity: &IssueSeverity) -> usize {
@@ -713,6 +651,16 @@ pub struct ProjectReport {
     /// A list of unique issues.
     /// Note: Using a Vec doesn't prevent duplicates, duplicates must be filtered before adding them.
     pub issues: Vec<ProjectReportIssue>,
+    /// The serial numbers assigned to the units, ordered by pcb and unit.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub serial_numbers: Vec<UnitSerialNumberItem>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct UnitSerialNumberItem {
+    pub unit_path: ObjectPath,
+    pub serial_number: SerialNumber,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
@@ -835,6 +783,7 @@ pub struct $name {}
     };
 }
 
+generic_task_specification!(ApplySolderPasteTaskSpecification, "apply_solder_paste_specification");
 generic_task_specification!(ManualSolderingTaskSpecification, "manual_soldering_specification");
 generic_task_specification!(AutomatedSolderingTaskSpecification, "automated_soldering_specification");
 generic_task_specification!(PlaceComponentsTaskSpecification, "place_components_specification");
@@ -862,6 +811,7 @@ pub struct $name {}
 }
 
 generic_task_overview!(LoadPcbsTaskOverview, "load_pcbs_overview");
+generic_task_overview!(ApplySolderPasteTaskOverview, "apply_solder_paste_overview");
 generic_task_overview!(ManualSolderingTaskOverview, "manual_soldering_overview");
 generic_task_overview!(AutomatedSolderingTaskOverview, "automated_soldering_overview");
 
@@ -932,6 +882,79 @@ pub enum IssueKind {
     PhaseWithNoPlacements {
         phase: PhaseReference,
     },
+    PartWithNoProcesses {
+        part: Part,
+    },
+    PhaseWithEmptyLoadOut {
+        phase: PhaseReference,
+    },
+    UnitAssignmentWithUnknownDesign {
+        #[serde_as(as = "DisplayFromStr")]
+        object_path: ObjectPath,
+        design: DesignName,
+    },
+    PcbWithNoProfileGerber {
+        file: FileReference,
+    },
+    PlacementOnOtherPcbSide {
+        phase: PhaseReference,
+        #[serde_as(as = "DisplayFromStr")]
+        object_path: ObjectPath,
+    },
+    PartFloorLifeExceeded {
+        phase: PhaseReference,
+        part: Part,
+    },
+    PhaseExceedsFeederCapacity {
+        phase: PhaseReference,
+        feeders: usize,
+        slots: usize,
+        passes: usize,
+    },
+}
+
+impl IssueKind {
+    /// A stable, machine-readable code for the kind of issue, e.g. for filtering or documentation lookups.
+    pub fn code(&self) -> &'static str {
+        match self {
+            IssueKind::NoPcbsAssigned => "no-pcbs-assigned",
+            IssueKind::NoPhasesCreated => "no-phases-created",
+            IssueKind::UnassignedPlacement {
+                ..
+            } => "unassigned-placement",
+            IssueKind::UnassignedPartFeeder {
+                ..
+            } => "unassigned-part-feeder",
+            IssueKind::PcbWithNoUnitAssignments {
+                ..
+            } => "pcb-with-no-unit-assignments",
+            IssueKind::NoPlacements => "no-placements",
+            IssueKind::PhaseWithNoPlacements {
+                ..
+            } => "phase-with-no-placements",
+            IssueKind::PartWithNoProcesses {
+                ..
+            } => "part-with-no-processes",
+            IssueKind::PhaseWithEmptyLoadOut {
+                ..
+            } => "phase-with-empty-load-out",
+            IssueKind::UnitAssignmentWithUnknownDesign {
+                ..
+            } => "unit-assignment-with-unknown-design",
+            IssueKind::PcbWithNoProfileGerber {
+                ..
+            } => "pcb-with-no-profile-gerber",
+            IssueKind::PlacementOnOtherPcbSide {
+                ..
+            } => "placement-on-other-pcb-side",
+            IssueKind::PartFloorLifeExceeded {
+                ..
+            } => "part-floor-life-exceeded",
+            IssueKind::PhaseExceedsFeederCapacity {
+                ..
+            } => "phase-exceeds-feeder-capacity",
+        }
+    }
 }
 
 pub(crate) fn build_report_file_path(name: &str, directory: &Path) -> PathBuf {
@@ -0,0 +1,399 @@
+//! Build analytics, aggregated from the operation history of each phase.
+//!
+//! The history is kept across build runs and resets, so the analytics cover every build of the project.  The time
+//! spent on a phase is measured per build run instance, from the first to the last operation recorded for it,
+//! operations recorded without a build run are treated as a single build.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use as_any::AsAny;
+use csv::QuoteStyle;
+use pnp::part::Part;
+use pnp::placement::RefDes;
+use rust_decimal::Decimal;
+use thiserror::Error;
+use time::OffsetDateTime;
+use tracing::{info, trace};
+
+use crate::build_run::BuildRunInstanceReference;
+use crate::operation_history::{self, OperationHistoryItem, PlacementOperationHistoryKind};
+use crate::phase::PhaseReference;
+use crate::placement::PlacementOperation;
+use crate::project::Project;
+
+/// The amount of reference designators included in [`ProjectAnalytics::most_skipped`].
+pub const MOST_SKIPPED_LIMIT: usize = 10;
+
+#[derive(Error, Debug)]
+pub enum AnalyticsError {
+    #[error("Unable to read operation history. path: {path:?}, cause: {cause}")]
+    OperationHistoryError { path: PathBuf, cause: anyhow::Error },
+    #[error("Unable to store analytics. path: {path:?}, cause: {cause}")]
+    StoreError { path: PathBuf, cause: anyhow::Error },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ProjectAnalytics {
+    /// In phase order.
+    pub phases: Vec<PhaseAnalytics>,
+    /// Sorted by part.
+    pub parts: Vec<PartAnalytics>,
+    /// Most skipped first, see [`MOST_SKIPPED_LIMIT`].
+    pub most_skipped: Vec<SkippedRefDesAnalytics>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PhaseAnalytics {
+    pub phase: PhaseReference,
+    /// The amount of builds the phase has operations for.
+    pub builds: usize,
+    /// `None` when there are no builds.
+    pub average_seconds: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PartAnalytics {
+    pub manufacturer: String,
+    pub mpn: String,
+    pub placed: usize,
+    pub skipped: usize,
+    pub inspected: usize,
+    pub failed_inspections: usize,
+    /// Skipped / (placed + skipped), `None` when there are no placed or skipped placements.
+    pub skip_rate: Option<Decimal>,
+    /// Failed inspections / inspected, `None` when there are no inspections.
+    pub defect_rate: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SkippedRefDesAnalytics {
+    pub ref_des: RefDes,
+    pub skipped: usize,
+}
+
+#[derive(Default)]
+struct PartCounts {
+    placed: usize,
+    skipped: usize,
+    inspected: usize,
+    failed_inspections: usize,
+}
+
+pub fn read_operation_histories(
+    project: &Project,
+    directory: &Path,
+) -> Result<BTreeMap<PhaseReference, Vec<OperationHistoryItem>>, AnalyticsError> {
+    project
+        .phase_orderings
+        .iter()
+        .map(|reference| {
+            let mut phase_log_path = PathBuf::from(directory);
+            phase_log_path.push(format!("{}_log.json", reference));
+
+            operation_history::read_or_default(&phase_log_path)
+                .map(|operation_history| (reference.clone(), operation_history))
+                .map_err(|cause| AnalyticsError::OperationHistoryError {
+                    path: phase_log_path.clone(),
+                    cause,
+                })
+        })
+        .collect()
+}
+
+/// Placement operations for placements that are no-longer in the project are ignored, since their part is unknown.
+pub fn build_analytics(
+    project: &Project,
+    operation_histories: &BTreeMap<PhaseReference, Vec<OperationHistoryItem>>,
+) -> ProjectAnalytics {
+    let mut part_counts: BTreeMap<&Part, PartCounts> = BTreeMap::new();
+    let mut skipped_counts: BTreeMap<&RefDes, usize> = BTreeMap::new();
+
+    let phases = project
+        .phase_orderings
+        .iter()
+        .map(|reference| {
+            let operation_history = operation_histories
+                .get(reference)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+
+            let mut build_date_times: BTreeMap<Option<BuildRunInstanceReference>, (OffsetDateTime, OffsetDateTime)> =
+                BTreeMap::new();
+
+            for item in operation_history.iter() {
+                build_date_times
+                    .entry(item.build_run)
+                    .and_modify(|(first, last)| {
+                        *first = (*first).min(item.date_time);
+                        *last = (*last).max(item.date_time);
+                    })
+                    .or_insert((item.date_time, item.date_time));
+
+                let Some(placement_history) = (*item.task_history)
+                    .as_any()
+                    .downcast_ref::<PlacementOperationHistoryKind>()
+                else {
+                    continue;
+                };
+
+                let Some(placement_state) = project
+                    .placements
+                    .get(&placement_history.object_path)
+                else {
+                    trace!(
+                        "Ignoring unknown placement. object_path: {}",
+                        placement_history.object_path
+                    );
+                    continue;
+                };
+
+                let counts = part_counts
+                    .entry(&placement_state.placement.part)
+                    .or_default();
+
+                match &placement_history.operation {
+                    PlacementOperation::Place => counts.placed += 1,
+                    PlacementOperation::Skip => {
+                        counts.skipped += 1;
+                        *skipped_counts
+                            .entry(&placement_state.placement.ref_des)
+                            .or_default() += 1;
+                    }
+                    PlacementOperation::Inspected {
+                        pass, ..
+                    } => {
+                        counts.inspected += 1;
+                        if !pass {
+                            counts.failed_inspections += 1;
+                        }
+                    }
+                    PlacementOperation::Reset => {}
+                }
+            }
+
+            let builds = build_date_times.len();
+            let total_seconds: i64 = build_date_times
+                .values()
+                .map(|(first, last)| (*last - *first).whole_seconds())
+                .sum();
+
+            PhaseAnalytics {
+                phase: reference.clone(),
+                builds,
+                average_seconds: (builds > 0).then(|| total_seconds / builds as i64),
+            }
+        })
+        .collect();
+
+    let parts = part_counts
+        .into_iter()
+        .map(|(part, counts)| PartAnalytics {
+            manufacturer: part.manufacturer.clone(),
+            mpn: part.mpn.clone(),
+            placed: counts.placed,
+            skipped: counts.skipped,
+            inspected: counts.inspected,
+            failed_inspections: counts.failed_inspections,
+            skip_rate: rate(counts.skipped, counts.placed + counts.skipped),
+            defect_rate: rate(counts.failed_inspections, counts.inspected),
+        })
+        .collect();
+
+    let mut most_skipped: Vec<SkippedRefDesAnalytics> = skipped_counts
+        .into_iter()
+        .map(|(ref_des, skipped)| SkippedRefDesAnalytics {
+            ref_des: ref_des.clone(),
+            skipped,
+        })
+        .collect();
+    // stable, so ties remain in ref-des order
+    most_skipped.sort_by(|a, b| b.skipped.cmp(&a.skipped));
+    most_skipped.truncate(MOST_SKIPPED_LIMIT);
+
+    ProjectAnalytics {
+        phases,
+        parts,
+        most_skipped,
+    }
+}
+
+fn rate(count: usize, total: usize) -> Option<Decimal> {
+    (total > 0).then(|| (Decimal::from(count) / Decimal::from(total)).round_dp(4))
+}
+
+pub fn build_analytics_file_path(project_name: &str, directory: &Path, kind: &str) -> PathBuf {
+    let mut path = PathBuf::from(directory);
+    path.push(format!("{}_analytics_{}.csv", project_name, kind));
+    path
+}
+
+/// Stores the phases, parts and most skipped reference designators as separate CSV files.
+pub fn store_analytics_as_csv(
+    analytics: &ProjectAnalytics,
+    project_name: &str,
+    directory: &Path,
+) -> Result<(), AnalyticsError> {
+    store_csv(
+        &analytics.phases,
+        &build_analytics_file_path(project_name, directory, "phases"),
+    )?;
+    store_csv(
+        &analytics.parts,
+        &build_analytics_file_path(project_name, directory, "parts"),
+    )?;
+    store_csv(
+        &analytics.most_skipped,
+        &build_analytics_file_path(project_name, directory, "most_skipped"),
+    )?;
+
+    Ok(())
+}
+
+fn store_csv<T: serde::Serialize>(records: &[T], path: &PathBuf) -> Result<(), AnalyticsError> {
+    let store = || -> Result<(), anyhow::Error> {
+        let mut writer = csv::WriterBuilder::new()
+            .quote_style(QuoteStyle::Always)
+            .from_path(path)?;
+
+        for record in records {
+            writer.serialize(record)?;
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    };
+
+    store().map_err(|cause| AnalyticsError::StoreError {
+        path: path.clone(),
+        cause,
+    })?;
+
+    info!("Generated analytics. path: {:?}", path);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod analytics_tests {
+    use std::str::FromStr;
+
+    use pnp::object_path::ObjectPath;
+    use pnp::placement::Placement;
+    use pnp::reference::Reference;
+    use rust_decimal_macros::dec;
+    use time::format_description::well_known::Rfc3339;
+
+    use super::*;
+    use crate::placement::PlacementState;
+    use crate::process::{OperationReference, TaskReference};
+
+    fn placement_state(ref_des: &str, mpn: &str) -> PlacementState {
+        PlacementState {
+            placement: Placement {
+                ref_des: ref_des.into(),
+                part: Part::new("MFR1".to_string(), mpn.to_string()),
+                ..Placement::default()
+            },
+            ..PlacementState::default()
+        }
+    }
+
+    fn history_item(
+        date_time: &str,
+        build_run: Option<BuildRunInstanceReference>,
+        object_path: &ObjectPath,
+        operation: PlacementOperation,
+    ) -> OperationHistoryItem {
+        OperationHistoryItem {
+            date_time: OffsetDateTime::parse(date_time, &Rfc3339).unwrap(),
+            phase: Reference::from_raw_str("top_1"),
+            operation_reference: OperationReference::from_raw_str("pnp"),
+            task_reference: TaskReference::from_raw_str("core::place_components"),
+            task_history: Box::new(PlacementOperationHistoryKind {
+                object_path: object_path.clone(),
+                operation,
+            }),
+            serial_number: None,
+            build_run,
+            overridden_rules: vec![],
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    pub fn aggregate_across_build_runs() {
+        // given
+        let r1_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap();
+        let r2_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R2").unwrap();
+
+        let mut project = Project::default();
+        project
+            .phase_orderings
+            .insert(Reference::from_raw_str("top_1"));
+        project
+            .placements
+            .insert(r1_path.clone(), placement_state("R1", "PART1"));
+        project
+            .placements
+            .insert(r2_path.clone(), placement_state("R2", "PART2"));
+
+        // and
+        let run_1 = Some(BuildRunInstanceReference {
+            run: 1,
+            instance: 1,
+        });
+        let run_2 = Some(BuildRunInstanceReference {
+            run: 1,
+            instance: 2,
+        });
+        let operation_histories = BTreeMap::from([(Reference::from_raw_str("top_1"), vec![
+            history_item("2025-03-01T08:00:00Z", run_1, &r1_path, PlacementOperation::Place),
+            history_item("2025-03-01T08:10:00Z", run_1, &r2_path, PlacementOperation::Skip),
+            history_item("2025-03-01T09:00:00Z", run_2, &r1_path, PlacementOperation::Place),
+            history_item("2025-03-01T09:01:00Z", run_2, &r1_path, PlacementOperation::Inspected {
+                pass: false,
+                notes: None,
+            }),
+            history_item("2025-03-01T09:30:00Z", run_2, &r2_path, PlacementOperation::Skip),
+        ])]);
+
+        // when
+        let analytics = build_analytics(&project, &operation_histories);
+
+        // then
+        assert_eq!(analytics.phases, vec![PhaseAnalytics {
+            phase: Reference::from_raw_str("top_1"),
+            builds: 2,
+            // (10 minutes + 30 minutes) / 2
+            average_seconds: Some(1200),
+        }]);
+        assert_eq!(analytics.parts, vec![
+            PartAnalytics {
+                manufacturer: "MFR1".to_string(),
+                mpn: "PART1".to_string(),
+                placed: 2,
+                skipped: 0,
+                inspected: 1,
+                failed_inspections: 1,
+                skip_rate: Some(dec!(0)),
+                defect_rate: Some(dec!(1)),
+            },
+            PartAnalytics {
+                manufacturer: "MFR1".to_string(),
+                mpn: "PART2".to_string(),
+                placed: 0,
+                skipped: 2,
+                inspected: 0,
+                failed_inspections: 0,
+                skip_rate: Some(dec!(1)),
+                defect_rate: None,
+            },
+        ]);
+        assert_eq!(analytics.most_skipped, vec![SkippedRefDesAnalytics {
+            ref_des: "R2".into(),
+            skipped: 2,
+        }]);
+    }
+}
@@ -0,0 +1,611 @@
+//! Shift-handover reports.
+//!
+//! A snapshot of the work in progress, so that the next shift can resume without a verbal handover; the progress of
+//! each phase, the placements remaining for each feeder, the open issues and the most recent operations.  The report is
+//! stored as Markdown or HTML.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use pnp::load_out::{find_load_out_item_by_part_or_alias, LoadOutItem};
+use pnp::object_path::ObjectPath;
+use pnp::part::{Part, PartAliases};
+use pnp::pcb::PcbSide;
+use pnp::reference::Reference;
+use serde_json::Value;
+use thiserror::Error;
+use time::format_description::well_known::Rfc3339;
+use time::serde::rfc3339;
+use time::OffsetDateTime;
+use tracing::info;
+
+use crate::operation_history;
+use crate::pcb::Pcb;
+use crate::phase::{PhaseReference, PhaseState, PhaseStatus};
+use crate::placement::{PlacementState, PlacementStatus};
+use crate::process::{OperationReference, TaskReference, TaskStatus};
+use crate::project::{build_phase_placement_states, Project};
+use crate::report::issues::{self, IssueCheck};
+use crate::report::ProjectReportIssue;
+use crate::serial_number::SerialNumber;
+
+/// The amount of operations, across all phases, included in the report.
+pub const RECENT_OPERATIONS_LIMIT: usize = 20;
+
+#[derive(Error, Debug)]
+pub enum HandoverReportError {
+    #[error("Unable to read operation history. path: {path:?}, cause: {cause}")]
+    OperationHistoryError { path: PathBuf, cause: anyhow::Error },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum HandoverReportFormat {
+    Markdown,
+    Html,
+}
+
+impl HandoverReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            HandoverReportFormat::Markdown => "md",
+            HandoverReportFormat::Html => "html",
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, PartialEq)]
+pub struct HandoverReport {
+    pub name: String,
+    #[serde(with = "rfc3339")]
+    pub generated_at: OffsetDateTime,
+    /// In phase order.
+    pub phases: Vec<PhaseProgress>,
+    pub issues: Vec<ProjectReportIssue>,
+    /// Most recent first, see [`RECENT_OPERATIONS_LIMIT`].
+    pub recent_operations: Vec<RecentOperationItem>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, PartialEq)]
+pub struct PhaseProgress {
+    pub phase: PhaseReference,
+    pub pcb_side: PcbSide,
+    pub status: PhaseStatus,
+    /// 0 to 100, see [`completion_percentage`].
+    pub completion_percentage: u8,
+    pub placed: usize,
+    pub skipped: usize,
+    /// The amount of placements to be placed.
+    pub total: usize,
+    pub remaining_by_feeder: Vec<FeederRemainingItem>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, PartialEq)]
+pub struct FeederRemainingItem {
+    /// `None` when the part is not in the load-out, or no feeder is assigned.
+    pub feeder: Option<Reference>,
+    pub manufacturer: String,
+    pub mpn: String,
+    pub remaining: usize,
+}
+
+#[derive(Debug, Clone, serde::Serialize, PartialEq)]
+pub struct RecentOperationItem {
+    #[serde(with = "rfc3339")]
+    pub date_time: OffsetDateTime,
+    pub phase: PhaseReference,
+    pub operation: OperationReference,
+    pub task: TaskReference,
+    pub serial_number: Option<SerialNumber>,
+    /// The history of the operation, e.g. `{"type": "load_pcbs_operation", "status": "Complete"}`
+    pub details: Value,
+}
+
+pub fn build_handover_report(
+    project: &Project,
+    pcbs: &[&Pcb],
+    directory: &Path,
+    phase_load_out_items_map: &BTreeMap<PhaseReference, Vec<LoadOutItem>>,
+    generated_at: OffsetDateTime,
+) -> Result<HandoverReport, HandoverReportError> {
+    let phases = project
+        .phase_orderings
+        .iter()
+        .filter_map(|reference| {
+            let phase = project.phases.get(reference)?;
+            let phase_state = project.phase_states.get(reference)?;
+
+            let placement_states = build_phase_placement_states(project, reference);
+            let load_out_items = phase_load_out_items_map
+                .get(reference)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+
+            let count = |status: PlacementStatus| {
+                placement_states
+                    .iter()
+                    .filter(|(_object_path, state)| state.placement.place && state.operation_status == status)
+                    .count()
+            };
+
+            Some(PhaseProgress {
+                phase: reference.clone(),
+                pcb_side: phase.pcb_side,
+                status: phase_state.status(),
+                completion_percentage: completion_percentage(phase_state),
+                placed: count(PlacementStatus::Placed),
+                skipped: count(PlacementStatus::Skipped),
+                total: placement_states
+                    .iter()
+                    .filter(|(_object_path, state)| state.placement.place)
+                    .count(),
+                remaining_by_feeder: build_remaining_by_feeder(
+                    &placement_states,
+                    load_out_items,
+                    &project.part_aliases,
+                ),
+            })
+        })
+        .collect();
+
+    let mut recent_operations = vec![];
+    for reference in project.phase_orderings.iter() {
+        let mut phase_log_path = PathBuf::from(directory);
+        phase_log_path.push(format!("{}_log.json", reference));
+
+        let operation_history = operation_history::read_or_default(&phase_log_path).map_err(|cause| {
+            HandoverReportError::OperationHistoryError {
+                path: phase_log_path.clone(),
+                cause,
+            }
+        })?;
+
+        recent_operations.extend(
+            operation_history
+                .into_iter()
+                .map(|item| RecentOperationItem {
+                    date_time: item.date_time,
+                    phase: item.phase,
+                    operation: item.operation_reference,
+                    task: item.task_reference,
+                    serial_number: item.serial_number,
+                    details: serde_json::to_value(&item.task_history).unwrap_or_default(),
+                }),
+        );
+    }
+    recent_operations.sort_by(|a, b| b.date_time.cmp(&a.date_time));
+    recent_operations.truncate(RECENT_OPERATIONS_LIMIT);
+
+    Ok(HandoverReport {
+        name: project.name.clone(),
+        generated_at,
+        phases,
+        issues: issues::analyze(project, pcbs, phase_load_out_items_map, IssueCheck::REPORT),
+        recent_operations,
+    })
+}
+
+/// Each task contributes equally, complete tasks are 100%, the placements task contributes the proportion of placed
+/// and skipped placements, other tasks are 0%.
+pub fn completion_percentage(phase_state: &PhaseState) -> u8 {
+    let (completion, count) = phase_state
+        .operation_states
+        .iter()
+        .flat_map(|operation_state| operation_state.task_states.values())
+        .fold((0.0, 0_usize), |(completion, count), task_state| {
+            let task_completion = match (task_state.status(), task_state.placements_state()) {
+                (TaskStatus::Complete, _) => 1.0,
+                (_, Some(placements_state)) => {
+                    let summary = placements_state.summary();
+                    match summary.total {
+                        0 => 0.0,
+                        total => (summary.placed + summary.skipped) as f64 / total as f64,
+                    }
+                }
+                _ => 0.0,
+            };
+
+            (completion + task_completion, count + 1)
+        });
+
+    if count == 0 {
+        return 0;
+    }
+
+    (completion / count as f64 * 100.0).floor() as u8
+}
+
+/// The pending placements, grouped by part, ordered by feeder, parts without a feeder are last.
+pub fn build_remaining_by_feeder(
+    placement_states: &[(&ObjectPath, &PlacementState)],
+    load_out_items: &[LoadOutItem],
+    part_aliases: &PartAliases,
+) -> Vec<FeederRemainingItem> {
+    let remaining_by_part = placement_states
+        .iter()
+        .filter(|(_object_path, state)| state.placement.place && state.operation_status == PlacementStatus::Pending)
+        .fold(
+            BTreeMap::<&Part, usize>::new(),
+            |mut remaining_by_part, (_object_path, state)| {
+                *remaining_by_part
+                    .entry(&state.placement.part)
+                    .or_default() += 1;
+                remaining_by_part
+            },
+        );
+
+    let mut items = remaining_by_part
+        .into_iter()
+        .map(|(part, remaining)| FeederRemainingItem {
+            feeder: find_load_out_item_by_part_or_alias(load_out_items, part, part_aliases)
+                .and_then(|item| item.reference.clone()),
+            manufacturer: part.manufacturer.clone(),
+            mpn: part.mpn.clone(),
+            remaining,
+        })
+        .collect::<Vec<_>>();
+
+    items.sort_by(|a, b| {
+        (a.feeder.is_none(), &a.feeder, &a.manufacturer, &a.mpn).cmp(&(
+            b.feeder.is_none(),
+            &b.feeder,
+            &b.manufacturer,
+            &b.mpn,
+        ))
+    });
+
+    items
+}
+
+pub fn build_handover_report_file_path(name: &str, directory: &Path, format: HandoverReportFormat) -> PathBuf {
+    let mut path = PathBuf::from(directory);
+    path.push(format!("{}_handover.{}", name, format.extension()));
+    path
+}
+
+pub fn store_handover_report(
+    report: &HandoverReport,
+    format: HandoverReportFormat,
+    output_path: &PathBuf,
+) -> Result<(), anyhow::Error> {
+    let content = match format {
+        HandoverReportFormat::Markdown => render_markdown(report),
+        HandoverReportFormat::Html => render_html(report),
+    };
+
+    std::fs::write(output_path, content)?;
+
+    info!("Generated handover report. path: {:?}", output_path);
+
+    Ok(())
+}
+
+/// A section of the report, rendered as a heading followed by a table, or a note when there are no rows.
+struct Section {
+    heading: String,
+    level: usize,
+    headers: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+    empty_note: &'static str,
+}
+
+fn build_sections(report: &HandoverReport) -> Vec<Section> {
+    let mut sections = vec![Section {
+        heading: "Phases".to_string(),
+        level: 2,
+        headers: &[
+            "Phase",
+            "PCB side",
+            "Status",
+            "Completion",
+            "Placed",
+            "Skipped",
+            "Total",
+        ],
+        rows: report
+            .phases
+            .iter()
+            .map(|phase| {
+                vec![
+                    phase.phase.to_string(),
+                    format!("{:?}", phase.pcb_side),
+                    format!("{:?}", phase.status),
+                    format!("{}%", phase.completion_percentage),
+                    phase.placed.to_string(),
+                    phase.skipped.to_string(),
+                    phase.total.to_string(),
+                ]
+            })
+            .collect(),
+        empty_note: "No phases.",
+    }];
+
+    sections.extend(report.phases.iter().map(|phase| {
+        Section {
+            heading: format!("Remaining placements - {}", phase.phase),
+            level: 3,
+            headers: &["Feeder", "Manufacturer", "MPN", "Remaining"],
+            rows: phase
+                .remaining_by_feeder
+                .iter()
+                .map(|item| {
+                    vec![
+                        item.feeder
+                            .as_ref()
+                            .map(Reference::to_string)
+                            .unwrap_or_default(),
+                        item.manufacturer.clone(),
+                        item.mpn.clone(),
+                        item.remaining.to_string(),
+                    ]
+                })
+                .collect(),
+            empty_note: "No remaining placements.",
+        }
+    }));
+
+    sections.push(Section {
+        heading: "Open issues".to_string(),
+        level: 2,
+        headers: &["Severity", "Issue"],
+        rows: report
+            .issues
+            .iter()
+            .map(|issue| vec![format!("{:?}", issue.severity), issue.message.clone()])
+            .collect(),
+        empty_note: "No open issues.",
+    });
+
+    sections.push(Section {
+        heading: "Recent operations".to_string(),
+        level: 2,
+        headers: &["Date/time", "Phase", "Operation", "Task", "Serial number", "Details"],
+        rows: report
+            .recent_operations
+            .iter()
+            .map(|item| {
+                vec![
+                    format_date_time(&item.date_time),
+                    item.phase.to_string(),
+                    item.operation.to_string(),
+                    item.task.to_string(),
+                    item.serial_number
+                        .clone()
+                        .unwrap_or_default(),
+                    item.details.to_string(),
+                ]
+            })
+            .collect(),
+        empty_note: "No operations recorded.",
+    });
+
+    sections
+}
+
+fn format_date_time(date_time: &OffsetDateTime) -> String {
+    date_time
+        .format(&Rfc3339)
+        .unwrap_or_else(|_| date_time.to_string())
+}
+
+pub fn render_markdown(report: &HandoverReport) -> String {
+    fn escape(value: &str) -> String {
+        value
+            .replace('|', "\\|")
+            .replace('\n', " ")
+    }
+
+    let mut markdown = format!(
+        "# Handover report - {}\n\nGenerated: {}\n",
+        escape(&report.name),
+        format_date_time(&report.generated_at)
+    );
+
+    for section in build_sections(report) {
+        markdown.push_str(&format!(
+            "\n{} {}\n\n",
+            "#".repeat(section.level),
+            escape(&section.heading)
+        ));
+
+        if section.rows.is_empty() {
+            markdown.push_str(&format!("{}\n", section.empty_note));
+            continue;
+        }
+
+        markdown.push_str(&format!("| {} |\n", section.headers.join(" | ")));
+        markdown.push_str(&format!("|{}\n", "---|".repeat(section.headers.len())));
+        for row in section.rows.iter() {
+            markdown.push_str(&format!(
+                "| {} |\n",
+                row.iter()
+                    .map(|cell| escape(cell))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            ));
+        }
+    }
+
+    markdown
+}
+
+pub fn render_html(report: &HandoverReport) -> String {
+    fn escape(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    let title = format!("Handover report - {}", escape(&report.name));
+
+    let mut html = format!(
+        concat!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n",
+            "<body>\n<h1>{title}</h1>\n<p>Generated: {generated_at}</p>\n"
+        ),
+        title = title,
+        generated_at = format_date_time(&report.generated_at)
+    );
+
+    for section in build_sections(report) {
+        html.push_str(&format!(
+            "<h{level}>{}</h{level}>\n",
+            escape(&section.heading),
+            level = section.level
+        ));
+
+        if section.rows.is_empty() {
+            html.push_str(&format!("<p>{}</p>\n", section.empty_note));
+            continue;
+        }
+
+        html.push_str("<table>\n<tr>");
+        for header in section.headers.iter() {
+            html.push_str(&format!("<th>{}</th>", header));
+        }
+        html.push_str("</tr>\n");
+        for row in section.rows.iter() {
+            html.push_str("<tr>");
+            for cell in row.iter() {
+                html.push_str(&format!("<td>{}</td>", escape(cell)));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+
+    html
+}
+
+#[cfg(test)]
+mod handover_tests {
+    use std::str::FromStr;
+
+    use pnp::placement::Placement;
+
+    use super::*;
+
+    fn placement_state(mpn: &str, operation_status: PlacementStatus) -> PlacementState {
+        PlacementState {
+            placement: Placement {
+                part: Part::new("MFR1".to_string(), mpn.to_string()),
+                ..Placement::default()
+            },
+            operation_status,
+            ..PlacementState::default()
+        }
+    }
+
+    #[test]
+    pub fn remaining_placements_are_grouped_by_feeder() {
+        // given
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap();
+        let states = [
+            placement_state("RES1", PlacementStatus::Pending),
+            placement_state("RES1", PlacementStatus::Placed),
+            placement_state("RES1", PlacementStatus::Pending),
+            placement_state("CAP1", PlacementStatus::Pending),
+            placement_state("LED1", PlacementStatus::Pending),
+        ];
+        let placement_states = states
+            .iter()
+            .map(|state| (&object_path, state))
+            .collect::<Vec<_>>();
+
+        // and
+        let load_out_items = vec![
+            LoadOutItem::new(
+                Some(Reference::from_raw_str("FEEDER_2")),
+                "MFR1".to_string(),
+                "RES1".to_string(),
+            ),
+            LoadOutItem::new(
+                Some(Reference::from_raw_str("FEEDER_1")),
+                "MFR1".to_string(),
+                "CAP1".to_string(),
+            ),
+        ];
+
+        // when
+        let items = build_remaining_by_feeder(&placement_states, &load_out_items, &PartAliases::default());
+
+        // then
+        assert_eq!(
+            items
+                .iter()
+                .map(|item| (
+                    item.feeder
+                        .as_ref()
+                        .map(Reference::to_string),
+                    item.mpn.as_str(),
+                    item.remaining
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some("FEEDER_1".to_string()), "CAP1", 1),
+                (Some("FEEDER_2".to_string()), "RES1", 2),
+                (None, "LED1", 1),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn render_markdown_report() {
+        // given
+        let report = HandoverReport {
+            name: "job|1".to_string(),
+            generated_at: OffsetDateTime::parse("2025-03-01T14:30:00Z", &Rfc3339).unwrap(),
+            phases: vec![PhaseProgress {
+                phase: Reference::from_raw_str("top_1"),
+                pcb_side: PcbSide::Top,
+                status: PhaseStatus::Incomplete,
+                completion_percentage: 66,
+                placed: 1,
+                skipped: 0,
+                total: 2,
+                remaining_by_feeder: vec![FeederRemainingItem {
+                    feeder: Some(Reference::from_raw_str("FEEDER_1")),
+                    manufacturer: "MFR1".to_string(),
+                    mpn: "RES1".to_string(),
+                    remaining: 1,
+                }],
+            }],
+            issues: vec![],
+            recent_operations: vec![],
+        };
+
+        // when
+        let markdown = render_markdown(&report);
+
+        // then
+        assert_eq!(
+            markdown,
+            "# Handover report - job\\|1\n\
+            \n\
+            Generated: 2025-03-01T14:30:00Z\n\
+            \n\
+            ## Phases\n\
+            \n\
+            | Phase | PCB side | Status | Completion | Placed | Skipped | Total |\n\
+            |---|---|---|---|---|---|---|\n\
+            | top_1 | Top | Incomplete | 66% | 1 | 0 | 2 |\n\
+            \n\
+            ### Remaining placements - top_1\n\
+            \n\
+            | Feeder | Manufacturer | MPN | Remaining |\n\
+            |---|---|---|---|\n\
+            | FEEDER_1 | MFR1 | RES1 | 1 |\n\
+            \n\
+            ## Open issues\n\
+            \n\
+            No open issues.\n\
+            \n\
+            ## Recent operations\n\
+            \n\
+            No operations recorded.\n"
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Inspection checklists.
+//!
+//! A checklist is generated for each phase, the placements are grouped by PCB unit so that an operator can work
+//! through one unit at a time.  Inspection results are recorded using [`crate::placement::PlacementOperation::Inspected`].
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Error;
+use csv::QuoteStyle;
+use pnp::object_path::ObjectPath;
+use pnp::package::Package;
+use pnp::part::Part;
+use pnp::placement::RefDes;
+use rust_decimal::Decimal;
+use serde_with::serde_as;
+use serde_with::DisplayFromStr;
+use tracing::{info, trace};
+
+use crate::phase::Phase;
+use crate::placement::PlacementState;
+use crate::settings::ProjectSettings;
+
+#[serde_as]
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct InspectionChecklistRecord {
+    #[serde_as(as = "DisplayFromStr")]
+    pub unit_path: ObjectPath,
+    #[serde_as(as = "DisplayFromStr")]
+    pub object_path: ObjectPath,
+    #[serde_as(as = "DisplayFromStr")]
+    pub ref_des: RefDes,
+    pub manufacturer: String,
+    pub mpn: String,
+    /// The rotation of the placement on the unit, without any rotation offsets applied.
+    pub expected_rotation: Decimal,
+    /// `None` when the part has no package, or the package has no lead count or pin 1 orientation.
+    pub polarity_sensitive: Option<bool>,
+    /// The operator notes of the placement, see [`PlacementState::notes`].
+    pub notes: Option<String>,
+    /// `;` separated, see [`PlacementState::attachments`].
+    pub attachments: String,
+}
+
+/// Builds the checklist records, grouped by unit and sorted by reference designator within each unit.
+pub fn build_inspection_checklist(
+    placement_states: &[(&ObjectPath, &PlacementState)],
+    part_packages: &BTreeMap<&Part, &Package>,
+    settings: &ProjectSettings,
+) -> Vec<InspectionChecklistRecord> {
+    let mut placement_states = Vec::from(placement_states);
+    placement_states.sort_by(|(_, state_a), (_, state_b)| {
+        state_a
+            .unit_path
+            .cmp(&state_b.unit_path)
+            .then_with(|| {
+                state_a
+                    .placement
+                    .ref_des
+                    .cmp(&state_b.placement.ref_des)
+            })
+    });
+
+    placement_states
+        .into_iter()
+        .map(|(object_path, placement_state)| {
+            let part = &placement_state.placement.part;
+            let polarity_sensitive = part_packages
+                .get(part)
+                .and_then(|package| package.is_polarity_sensitive());
+
+            InspectionChecklistRecord {
+                unit_path: placement_state.unit_path.clone(),
+                object_path: object_path.clone(),
+                ref_des: placement_state
+                    .placement
+                    .ref_des
+                    .clone(),
+                manufacturer: part.manufacturer.to_string(),
+                mpn: part.mpn.to_string(),
+                expected_rotation: settings.rotation(placement_state.unit_position.rotation),
+                polarity_sensitive,
+                notes: placement_state.notes.clone(),
+                attachments: placement_state
+                    .attachments
+                    .iter()
+                    .map(|attachment| attachment.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(";"),
+            }
+        })
+        .collect()
+}
+
+pub fn build_inspection_checklist_file_path(phase: &Phase, directory: &Path) -> PathBuf {
+    let mut path = PathBuf::from(directory);
+    path.push(format!("{}_inspection_checklist.csv", phase.reference));
+    path
+}
+
+pub fn store_inspection_checklist_as_csv(
+    output_path: &PathBuf,
+    records: &[InspectionChecklistRecord],
+) -> Result<(), Error> {
+    trace!("Writing inspection checklist. output_path: {:?}", output_path);
+
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_path(output_path)?;
+
+    for record in records {
+        writer.serialize(record)?;
+    }
+
+    writer.flush()?;
+
+    info!("Generated inspection checklist. path: {:?}", output_path);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod inspection_tests {
+    use pnp::placement::Placement;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::pcb::UnitPlacementPosition;
+    use crate::placement::PlacementState;
+
+    #[test]
+    pub fn checklist_is_grouped_by_unit() {
+        // given
+        let part_resistor = Part::new("MFR1".to_string(), "R_0603".to_string());
+        let part_regulator = Part::new("MFR2".to_string(), "LDO_SOT-223".to_string());
+        let part_unknown = Part::new("MFR3".to_string(), "UNKNOWN".to_string());
+
+        let package_0603 = Package::new("0603".to_string()).with_lead_count(2);
+        let package_sot223 = Package::new("SOT-223".to_string()).with_lead_count(4);
+
+        let part_packages: BTreeMap<&Part, &Package> =
+            BTreeMap::from([(&part_resistor, &package_0603), (&part_regulator, &package_sot223)]);
+
+        let placement_states = [
+            ("pcb=1::unit=2", "R1", &part_resistor, dec!(90)),
+            ("pcb=1::unit=1", "U1", &part_regulator, dec!(180)),
+            ("pcb=1::unit=1", "R1", &part_resistor, dec!(0)),
+            ("pcb=1::unit=1", "J1", &part_unknown, dec!(-90)),
+        ]
+        .into_iter()
+        .map(|(unit_path, ref_des, part, rotation)| {
+            let unit_path = ObjectPath::from_raw_str(unit_path);
+            let mut object_path = unit_path.clone();
+            object_path.set_ref_des(RefDes::from(ref_des));
+
+            (object_path, PlacementState {
+                unit_path,
+                placement: Placement {
+                    ref_des: RefDes::from(ref_des),
+                    part: part.clone(),
+                    ..Placement::default()
+                },
+                unit_position: UnitPlacementPosition {
+                    rotation,
+                    ..UnitPlacementPosition::default()
+                },
+                ..PlacementState::default()
+            })
+        })
+        .collect::<Vec<_>>();
+        let placement_states = placement_states
+            .iter()
+            .map(|(object_path, state)| (object_path, state))
+            .collect::<Vec<_>>();
+
+        // when
+        let records = build_inspection_checklist(&placement_states, &part_packages, &ProjectSettings::default());
+
+        // then
+        let summary = records
+            .iter()
+            .map(|record| {
+                (
+                    record.object_path.to_string(),
+                    record.expected_rotation,
+                    record.polarity_sensitive,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(summary, vec![
+            ("pcb=1::unit=1::ref_des=J1".to_string(), dec!(-90), None),
+            ("pcb=1::unit=1::ref_des=R1".to_string(), dec!(0), Some(false)),
+            ("pcb=1::unit=1::ref_des=U1".to_string(), dec!(180), Some(true)),
+            ("pcb=1::unit=2::ref_des=R1".to_string(), dec!(90), Some(false)),
+        ]);
+    }
+}
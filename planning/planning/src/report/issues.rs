@@ -0,0 +1,846 @@
+//! Scans a project for problems.
+//!
+//! Each check is independent, and produces zero or more issues, see [`IssueCheck`].
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use gerber::GerberFileFunction;
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use tracing::info;
+
+use crate::capacity;
+use crate::pcb::Pcb;
+use crate::phase::PhaseReference;
+use crate::placement::ProjectPlacementStatus;
+use crate::project::{build_phase_placement_states, Project};
+use crate::report::{project_report_sort_issues, IssueKind, IssueSeverity, ProjectReportIssue};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueCheck {
+    Pcbs,
+    Placements,
+    Phases,
+    PhasePlacements,
+    PartFeeders,
+    UnassignedPlacements,
+    PartProcesses,
+    PhaseLoadOuts,
+    UnitAssignmentDesigns,
+    PcbProfileGerbers,
+    PlacementPcbSides,
+    PartFloorLife,
+    PhaseFeederCapacity,
+}
+
+impl IssueCheck {
+    pub const ALL: &'static [IssueCheck] = &[
+        IssueCheck::Pcbs,
+        IssueCheck::Placements,
+        IssueCheck::Phases,
+        IssueCheck::PhasePlacements,
+        IssueCheck::PartFeeders,
+        IssueCheck::UnassignedPlacements,
+        IssueCheck::PartProcesses,
+        IssueCheck::PhaseLoadOuts,
+        IssueCheck::UnitAssignmentDesigns,
+        IssueCheck::PcbProfileGerbers,
+        IssueCheck::PlacementPcbSides,
+        IssueCheck::PartFloorLife,
+        IssueCheck::PhaseFeederCapacity,
+    ];
+
+    /// The checks that are included in the generated project report.
+    ///
+    /// FUTURE include all the checks, requires the expected reports used by the tests to be updated.
+    pub const REPORT: &'static [IssueCheck] = &[
+        IssueCheck::Pcbs,
+        IssueCheck::Placements,
+        IssueCheck::Phases,
+        IssueCheck::PhasePlacements,
+        IssueCheck::PartFeeders,
+        IssueCheck::UnassignedPlacements,
+    ];
+}
+
+/// An issue, with a machine-readable code, for presentation to the user.
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct ProjectIssue {
+    pub code: String,
+    pub severity: IssueSeverity,
+    pub message: String,
+    pub kind: IssueKind,
+}
+
+impl From<ProjectReportIssue> for ProjectIssue {
+    fn from(issue: ProjectReportIssue) -> Self {
+        Self {
+            code: issue.kind.code().to_string(),
+            severity: issue.severity,
+            message: issue.message,
+            kind: issue.kind,
+        }
+    }
+}
+
+/// Returns a sorted list of unique issues.
+///
+/// `phase_load_out_items_map` must contain an entry for every phase.
+pub fn analyze(
+    project: &Project,
+    pcbs: &[&Pcb],
+    phase_load_out_items_map: &BTreeMap<PhaseReference, Vec<LoadOutItem>>,
+    checks: &[IssueCheck],
+) -> Vec<ProjectReportIssue> {
+    let mut issue_set: BTreeSet<ProjectReportIssue> = BTreeSet::new();
+
+    for check in checks {
+        match check {
+            IssueCheck::Pcbs => check_pcbs(project, &mut issue_set),
+            IssueCheck::Placements => check_placements(project, &mut issue_set),
+            IssueCheck::Phases => check_phases(project, &mut issue_set),
+            IssueCheck::PhasePlacements => check_phase_placements(project, &mut issue_set),
+            IssueCheck::PartFeeders => check_part_feeders(project, phase_load_out_items_map, &mut issue_set),
+            IssueCheck::UnassignedPlacements => check_unassigned_placements(project, &mut issue_set),
+            IssueCheck::PartProcesses => check_part_processes(project, &mut issue_set),
+            IssueCheck::PhaseLoadOuts => check_phase_load_outs(project, phase_load_out_items_map, &mut issue_set),
+            IssueCheck::UnitAssignmentDesigns => check_unit_assignment_designs(project, pcbs, &mut issue_set),
+            IssueCheck::PcbProfileGerbers => check_pcb_profile_gerbers(project, pcbs, &mut issue_set),
+            IssueCheck::PlacementPcbSides => check_placement_pcb_sides(project, &mut issue_set),
+            IssueCheck::PartFloorLife => check_part_floor_life(project, &mut issue_set),
+            IssueCheck::PhaseFeederCapacity => {
+                check_phase_feeder_capacity(project, phase_load_out_items_map, &mut issue_set)
+            }
+        }
+    }
+
+    let mut issues: Vec<ProjectReportIssue> = issue_set.into_iter().collect();
+
+    project_report_sort_issues(&mut issues);
+
+    for issue in issues.iter() {
+        info!(
+            "Issue detected. severity: {:?}, code: {}, message: '{}', kind: {:?}",
+            issue.severity,
+            issue.kind.code(),
+            issue.message,
+            issue.kind
+        );
+    }
+
+    issues
+}
+
+fn check_pcbs(project: &Project, issue_set: &mut BTreeSet<ProjectReportIssue>) {
+    if project.pcbs.is_empty() {
+        issue_set.insert(ProjectReportIssue {
+            message: "No PCBs have been assigned to the project.".to_string(),
+            severity: IssueSeverity::Severe,
+            kind: IssueKind::NoPcbsAssigned,
+        });
+    } else {
+        for pcb in project.pcbs.iter() {
+            if pcb.unit_assignments.is_empty() {
+                issue_set.insert(ProjectReportIssue {
+                    message: "A PCB has no unit assignments.".to_string(),
+                    severity: IssueSeverity::Severe,
+                    kind: IssueKind::PcbWithNoUnitAssignments {
+                        file: pcb.pcb_file.clone(),
+                    },
+                });
+            }
+        }
+    }
+}
+
+fn check_placements(project: &Project, issue_set: &mut BTreeSet<ProjectReportIssue>) {
+    if project.placements.is_empty() {
+        issue_set.insert(ProjectReportIssue {
+            message: "No placements.".to_string(),
+            severity: IssueSeverity::Severe,
+            kind: IssueKind::NoPlacements,
+        });
+    }
+}
+
+fn check_phases(project: &Project, issue_set: &mut BTreeSet<ProjectReportIssue>) {
+    if project.phases.is_empty() {
+        issue_set.insert(ProjectReportIssue {
+            message: "No phases have been created.".to_string(),
+            severity: IssueSeverity::Severe,
+            kind: IssueKind::NoPhasesCreated,
+        });
+    }
+}
+
+fn check_phase_placements(project: &Project, issue_set: &mut BTreeSet<ProjectReportIssue>) {
+    for phase_reference in project.phase_orderings.iter() {
+        let phase_placement_states = build_phase_placement_states(project, phase_reference);
+        if phase_placement_states.is_empty() {
+            issue_set.insert(ProjectReportIssue {
+                message: "Phase with no placements.".to_string(),
+                severity: IssueSeverity::Warning,
+                kind: IssueKind::PhaseWithNoPlacements {
+                    phase: phase_reference.clone(),
+                },
+            });
+        }
+    }
+}
+
+fn check_part_feeders(
+    project: &Project,
+    phase_load_out_items_map: &BTreeMap<PhaseReference, Vec<LoadOutItem>>,
+    issue_set: &mut BTreeSet<ProjectReportIssue>,
+) {
+    for phase_reference in project.phase_orderings.iter() {
+        let phase_placement_states = build_phase_placement_states(project, phase_reference);
+
+        for (_object_path, placement_state) in phase_placement_states.iter() {
+            let load_out_items = phase_load_out_items_map
+                .get(phase_reference)
+                .unwrap();
+
+            let feeder_reference = match pnp::load_out::find_load_out_item_by_part_or_alias(
+                load_out_items,
+                &placement_state.placement.part,
+                &project.part_aliases,
+            ) {
+                Some(load_out_item) => load_out_item.reference.clone(),
+                _ => None,
+            };
+
+            if feeder_reference.is_none() {
+                let issue = ProjectReportIssue {
+                    message: "A part has not been assigned to a feeder".to_string(),
+                    severity: IssueSeverity::Warning,
+                    kind: IssueKind::UnassignedPartFeeder {
+                        phase: phase_reference.clone(),
+                        part: placement_state.placement.part.clone(),
+                    },
+                };
+                issue_set.insert(issue);
+            };
+        }
+    }
+}
+
+fn check_unassigned_placements(project: &Project, issue_set: &mut BTreeSet<ProjectReportIssue>) {
+    for (object_path, _placement_state) in project
+        .placements
+        .iter()
+        .filter(|(_object_path, placement_state)| {
+            placement_state.phase.is_none() && placement_state.project_status == ProjectPlacementStatus::Used
+        })
+    {
+        issue_set.insert(ProjectReportIssue {
+            message: "A placement has not been assigned to a phase".to_string(),
+            severity: IssueSeverity::Warning,
+            kind: IssueKind::UnassignedPlacement {
+                object_path: object_path.clone(),
+            },
+        });
+    }
+}
+
+/// Only parts that are used by placements are checked.
+fn check_part_processes(project: &Project, issue_set: &mut BTreeSet<ProjectReportIssue>) {
+    let used_parts = project
+        .placements
+        .values()
+        .filter(|placement_state| placement_state.project_status == ProjectPlacementStatus::Used)
+        .map(|placement_state| &placement_state.placement.part)
+        .collect::<BTreeSet<_>>();
+
+    for (part, part_state) in project.part_states.iter() {
+        if part_state.applicable_processes.is_empty() && used_parts.contains(part) {
+            issue_set.insert(ProjectReportIssue {
+                message: "A part has not been assigned to any processes".to_string(),
+                severity: IssueSeverity::Warning,
+                kind: IssueKind::PartWithNoProcesses {
+                    part: part.clone(),
+                },
+            });
+        }
+    }
+}
+
+fn check_phase_load_outs(
+    project: &Project,
+    phase_load_out_items_map: &BTreeMap<PhaseReference, Vec<LoadOutItem>>,
+    issue_set: &mut BTreeSet<ProjectReportIssue>,
+) {
+    for phase_reference in project.phase_orderings.iter() {
+        let is_empty = phase_load_out_items_map
+            .get(phase_reference)
+            .map(Vec::is_empty)
+            .unwrap_or(true);
+
+        if is_empty {
+            issue_set.insert(ProjectReportIssue {
+                message: "Phase with an empty load-out.".to_string(),
+                severity: IssueSeverity::Warning,
+                kind: IssueKind::PhaseWithEmptyLoadOut {
+                    phase: phase_reference.clone(),
+                },
+            });
+        }
+    }
+}
+
+/// Phases using a process without feeder banks are not checked.
+fn check_phase_feeder_capacity(
+    project: &Project,
+    phase_load_out_items_map: &BTreeMap<PhaseReference, Vec<LoadOutItem>>,
+    issue_set: &mut BTreeSet<ProjectReportIssue>,
+) {
+    for phase_reference in project.phase_orderings.iter() {
+        let load_out_items = phase_load_out_items_map
+            .get(phase_reference)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let Ok(capacity) = capacity::analyze_phase_capacity(project, phase_reference, load_out_items) else {
+            continue;
+        };
+
+        if !capacity.fits() {
+            issue_set.insert(ProjectReportIssue {
+                message: "Phase requires more feeders than the machine has feeder slots.".to_string(),
+                severity: IssueSeverity::Warning,
+                kind: IssueKind::PhaseExceedsFeederCapacity {
+                    phase: phase_reference.clone(),
+                    feeders: capacity.feeder_count,
+                    slots: capacity.slot_count,
+                    passes: capacity.passes.len(),
+                },
+            });
+        }
+    }
+}
+
+/// Safety: PCBs that have not been loaded are not checked.
+fn check_unit_assignment_designs(project: &Project, pcbs: &[&Pcb], issue_set: &mut BTreeSet<ProjectReportIssue>) {
+    for (pcb_index, (project_pcb, pcb)) in project
+        .pcbs
+        .iter()
+        .zip(pcbs)
+        .enumerate()
+    {
+        for (pcb_unit_index, design_variant) in project_pcb.unit_assignments.iter() {
+            if pcb
+                .design_names
+                .contains(&design_variant.design_name)
+            {
+                continue;
+            }
+
+            let mut object_path = ObjectPath::default();
+            object_path.set_pcb_instance(pcb_index as u16 + 1);
+            object_path.set_pcb_unit(pcb_unit_index + 1);
+
+            issue_set.insert(ProjectReportIssue {
+                message: "A unit assignment references a design that is not on the PCB".to_string(),
+                severity: IssueSeverity::Severe,
+                kind: IssueKind::UnitAssignmentWithUnknownDesign {
+                    object_path,
+                    design: design_variant.design_name.clone(),
+                },
+            });
+        }
+    }
+}
+
+/// PCBs without any gerbers are not checked.
+fn check_pcb_profile_gerbers(project: &Project, pcbs: &[&Pcb], issue_set: &mut BTreeSet<ProjectReportIssue>) {
+    for (project_pcb, pcb) in project.pcbs.iter().zip(pcbs) {
+        let mut gerbers = pcb
+            .pcb_gerbers
+            .iter()
+            .chain(pcb.design_gerbers.values().flatten())
+            .peekable();
+
+        if gerbers.peek().is_none() {
+            continue;
+        }
+
+        let has_profile = gerbers.any(|gerber| matches!(gerber.function, Some(GerberFileFunction::Profile)));
+        if !has_profile {
+            issue_set.insert(ProjectReportIssue {
+                message: "A PCB has gerbers, but none of them are a profile (outline) layer".to_string(),
+                severity: IssueSeverity::Warning,
+                kind: IssueKind::PcbWithNoProfileGerber {
+                    file: project_pcb.pcb_file.clone(),
+                },
+            });
+        }
+    }
+}
+
+/// Placements can be assigned to a phase for the other PCB side when forced, e.g. for through-hole parts.
+fn check_placement_pcb_sides(project: &Project, issue_set: &mut BTreeSet<ProjectReportIssue>) {
+    for (object_path, placement_state) in project.placements.iter() {
+        let Some(phase) = placement_state
+            .phase
+            .as_ref()
+            .and_then(|phase_reference| project.phases.get(phase_reference))
+        else {
+            continue;
+        };
+
+        if placement_state.placement.pcb_side != phase.pcb_side {
+            issue_set.insert(ProjectReportIssue {
+                message: "A placement is assigned to a phase for the other PCB side".to_string(),
+                severity: IssueSeverity::Warning,
+                kind: IssueKind::PlacementOnOtherPcbSide {
+                    phase: phase.reference.clone(),
+                    object_path: object_path.clone(),
+                },
+            });
+        }
+    }
+}
+
+/// Only phases with an assembly window are checked, parts from unopened reels are assumed to be opened at the start
+/// of the window.
+fn check_part_floor_life(project: &Project, issue_set: &mut BTreeSet<ProjectReportIssue>) {
+    for phase_reference in project.phase_orderings.iter() {
+        let Some(window) = project
+            .phases
+            .get(phase_reference)
+            .and_then(|phase| phase.assembly_window.as_ref())
+        else {
+            continue;
+        };
+
+        let phase_parts = build_phase_placement_states(project, phase_reference)
+            .into_iter()
+            .map(|(_object_path, placement_state)| &placement_state.placement.part)
+            .collect::<BTreeSet<_>>();
+
+        for part in phase_parts {
+            let Some(expiry) = project
+                .part_states
+                .get(part)
+                .and_then(|part_state| part_state.floor_life_expiry(window.start))
+            else {
+                continue;
+            };
+
+            if expiry < window.end {
+                issue_set.insert(ProjectReportIssue {
+                    message: "The floor life of a moisture sensitive part would be exceeded during the assembly window"
+                        .to_string(),
+                    severity: IssueSeverity::Warning,
+                    kind: IssueKind::PartFloorLifeExceeded {
+                        phase: phase_reference.clone(),
+                        part: part.clone(),
+                    },
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use gerber::GerberFile;
+    use indexmap::IndexSet;
+    use pnp::part::{MoistureSensitivityLevel, Part, PartMoistureSensitivity};
+    use pnp::pcb::PcbSide;
+    use pnp::placement::Placement;
+    use pnp::reference::Reference;
+    use time::format_description::well_known::Rfc3339;
+    use time::OffsetDateTime;
+
+    use super::*;
+    use crate::design::{DesignName, DesignVariant};
+    use crate::file::FileReference;
+    use crate::part::{MoistureExposure, PartState};
+    use crate::phase::{AssemblyWindow, Phase};
+    use crate::placement::PlacementState;
+    use crate::project::ProjectPcb;
+    use crate::variant::VariantName;
+
+    #[test]
+    fn empty_project() {
+        // given
+        let project = Project::default();
+
+        // when
+        let issues = analyze(&project, &[], &BTreeMap::new(), IssueCheck::ALL);
+
+        // then
+        let codes = issues
+            .iter()
+            .map(|issue| issue.kind.code())
+            .collect::<Vec<_>>();
+        assert_eq!(codes, vec!["no-pcbs-assigned", "no-phases-created", "no-placements"]);
+    }
+
+    #[test]
+    fn unused_parts_without_processes_are_ignored() {
+        // given
+        let mut project = Project::default();
+        project
+            .part_states
+            .insert(Part::new("MFR1".to_string(), "MPN1".to_string()), PartState::default());
+
+        // when
+        let issues = analyze(&project, &[], &BTreeMap::new(), &[IssueCheck::PartProcesses]);
+
+        // then
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn placement_assigned_to_a_phase_for_the_other_pcb_side() {
+        // given
+        let mut project = Project::default();
+        let phase_reference = Reference::from_raw_str("top_1");
+        project.phases.insert(phase_reference.clone(), Phase {
+            reference: phase_reference.clone(),
+            process: Reference::from_raw_str("pnp"),
+            load_out_source: "load_out_1".to_string(),
+            pcb_side: PcbSide::Top,
+            placement_orderings: vec![],
+            assembly_window: None,
+        });
+
+        // and
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=J1").unwrap();
+        project
+            .placements
+            .insert(object_path.clone(), PlacementState {
+                unit_path: ObjectPath::from_str("pcb=1::unit=1").unwrap(),
+                placement: Placement {
+                    pcb_side: PcbSide::Bottom,
+                    ..Placement::default()
+                },
+                phase: Some(phase_reference.clone()),
+                ..PlacementState::default()
+            });
+
+        // when
+        let issues = analyze(&project, &[], &BTreeMap::new(), &[IssueCheck::PlacementPcbSides]);
+
+        // then
+        assert_eq!(issues, vec![ProjectReportIssue {
+            message: "A placement is assigned to a phase for the other PCB side".to_string(),
+            severity: IssueSeverity::Warning,
+            kind: IssueKind::PlacementOnOtherPcbSide {
+                phase: phase_reference,
+                object_path,
+            },
+        }]);
+    }
+
+    #[test]
+    fn part_floor_life_exceeded_during_assembly_window() {
+        // given
+        let datetime = |value: &str| OffsetDateTime::parse(value, &Rfc3339).unwrap();
+
+        let mut project = Project::default();
+        let phase_reference = Reference::from_raw_str("top_1");
+        project
+            .phases
+            .insert(phase_reference.clone(), Phase {
+                reference: phase_reference.clone(),
+                process: Reference::from_raw_str("pnp"),
+                load_out_source: "load_out_1".to_string(),
+                pcb_side: PcbSide::Top,
+                placement_orderings: vec![],
+                assembly_window: Some(AssemblyWindow {
+                    start: datetime("2025-03-10T08:00:00Z"),
+                    end: datetime("2025-03-12T17:00:00Z"),
+                }),
+            });
+        project
+            .phase_orderings
+            .insert(phase_reference.clone());
+
+        // and an opened MSL 4 part (72 hours), an unopened MSL 4 part, and an opened MSL 1 part
+        let part_opened = Part::new("MFR1".to_string(), "OPENED".to_string());
+        let part_unopened = Part::new("MFR1".to_string(), "UNOPENED".to_string());
+        let part_msl1 = Part::new("MFR1".to_string(), "MSL1".to_string());
+
+        let part_state = |level: MoistureSensitivityLevel, opened: Option<&str>| PartState {
+            moisture_sensitivity: Some(PartMoistureSensitivity {
+                level,
+                floor_life_hours: None,
+            }),
+            moisture_exposure: opened.map(|opened| MoistureExposure {
+                opened: datetime(opened),
+                baked: None,
+            }),
+            ..PartState::default()
+        };
+        project.part_states.insert(
+            part_opened.clone(),
+            part_state(MoistureSensitivityLevel::Msl4, Some("2025-03-08T08:00:00Z")),
+        );
+        project
+            .part_states
+            .insert(part_unopened.clone(), part_state(MoistureSensitivityLevel::Msl4, None));
+        project.part_states.insert(
+            part_msl1.clone(),
+            part_state(MoistureSensitivityLevel::Msl1, Some("2025-01-01T08:00:00Z")),
+        );
+
+        // and
+        for (ref_des, part) in [("U1", &part_opened), ("U2", &part_unopened), ("U3", &part_msl1)] {
+            let object_path = ObjectPath::from_str(&format!("pcb=1::unit=1::ref_des={}", ref_des)).unwrap();
+            project
+                .placements
+                .insert(object_path, PlacementState {
+                    unit_path: ObjectPath::from_str("pcb=1::unit=1").unwrap(),
+                    placement: Placement {
+                        part: part.clone(),
+                        ..Placement::default()
+                    },
+                    phase: Some(phase_reference.clone()),
+                    ..PlacementState::default()
+                });
+        }
+
+        // when
+        let issues = analyze(&project, &[], &BTreeMap::new(), &[IssueCheck::PartFloorLife]);
+
+        // then
+        assert_eq!(issues, vec![ProjectReportIssue {
+            message: "The floor life of a moisture sensitive part would be exceeded during the assembly window"
+                .to_string(),
+            severity: IssueSeverity::Warning,
+            kind: IssueKind::PartFloorLifeExceeded {
+                phase: phase_reference,
+                part: part_opened,
+            },
+        }]);
+    }
+
+    #[test]
+    fn used_parts_without_processes() {
+        // given
+        let mut project = Project::default();
+        let part_without_processes = Part::new("MFR1".to_string(), "MPN1".to_string());
+        let part_with_processes = Part::new("MFR1".to_string(), "MPN2".to_string());
+        project
+            .part_states
+            .insert(part_without_processes.clone(), PartState::default());
+        project
+            .part_states
+            .insert(part_with_processes.clone(), PartState {
+                applicable_processes: BTreeSet::from([Reference::from_raw_str("pnp")]),
+                ..PartState::default()
+            });
+
+        // and
+        for (ref_des, part) in [("R1", &part_without_processes), ("R2", &part_with_processes)] {
+            project.placements.insert(
+                ObjectPath::from_str(&format!("pcb=1::unit=1::ref_des={}", ref_des)).unwrap(),
+                PlacementState {
+                    placement: Placement {
+                        part: part.clone(),
+                        ..Placement::default()
+                    },
+                    ..PlacementState::default()
+                },
+            );
+        }
+
+        // when
+        let issues = analyze(&project, &[], &BTreeMap::new(), &[IssueCheck::PartProcesses]);
+
+        // then
+        assert_eq!(issues, vec![ProjectReportIssue {
+            message: "A part has not been assigned to any processes".to_string(),
+            severity: IssueSeverity::Warning,
+            kind: IssueKind::PartWithNoProcesses {
+                part: part_without_processes,
+            },
+        }]);
+        assert_eq!(issues[0].kind.code(), "part-with-no-processes");
+    }
+
+    #[test]
+    fn used_placements_not_in_a_phase() {
+        // given
+        let mut project = Project::default();
+        let unassigned_object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap();
+        let unused_object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R2").unwrap();
+        let assigned_object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R3").unwrap();
+        project
+            .placements
+            .insert(unassigned_object_path.clone(), PlacementState::default());
+        project
+            .placements
+            .insert(unused_object_path, PlacementState {
+                project_status: ProjectPlacementStatus::Unused,
+                ..PlacementState::default()
+            });
+        project
+            .placements
+            .insert(assigned_object_path, PlacementState {
+                phase: Some(Reference::from_raw_str("top_1")),
+                ..PlacementState::default()
+            });
+
+        // when
+        let issues = analyze(&project, &[], &BTreeMap::new(), &[IssueCheck::UnassignedPlacements]);
+
+        // then
+        assert_eq!(issues, vec![ProjectReportIssue {
+            message: "A placement has not been assigned to a phase".to_string(),
+            severity: IssueSeverity::Warning,
+            kind: IssueKind::UnassignedPlacement {
+                object_path: unassigned_object_path,
+            },
+        }]);
+        assert_eq!(issues[0].kind.code(), "unassigned-placement");
+    }
+
+    #[test]
+    fn phases_with_empty_load_outs() {
+        // given
+        let mut project = Project::default();
+        let empty_phase_reference = Reference::from_raw_str("top_1");
+        let missing_phase_reference = Reference::from_raw_str("top_2");
+        let loaded_phase_reference = Reference::from_raw_str("top_3");
+        for phase_reference in [
+            &empty_phase_reference,
+            &missing_phase_reference,
+            &loaded_phase_reference,
+        ] {
+            project
+                .phase_orderings
+                .insert(phase_reference.clone());
+        }
+
+        // and the load-out for the second phase could not be loaded
+        let phase_load_out_items_map = BTreeMap::from([
+            (empty_phase_reference.clone(), vec![]),
+            (loaded_phase_reference, vec![LoadOutItem::new(
+                Some(Reference::from_raw_str("FEEDER_1")),
+                "MFR1".to_string(),
+                "MPN1".to_string(),
+            )]),
+        ]);
+
+        // when
+        let issues = analyze(&project, &[], &phase_load_out_items_map, &[IssueCheck::PhaseLoadOuts]);
+
+        // then
+        let phases = issues
+            .iter()
+            .map(|issue| match &issue.kind {
+                IssueKind::PhaseWithEmptyLoadOut {
+                    phase,
+                } => (phase.clone(), issue.kind.code()),
+                kind => panic!("unexpected issue kind: {:?}", kind),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(phases, vec![
+            (empty_phase_reference, "phase-with-empty-load-out"),
+            (missing_phase_reference, "phase-with-empty-load-out"),
+        ]);
+        assert!(issues
+            .iter()
+            .all(|issue| issue.severity == IssueSeverity::Warning));
+    }
+
+    #[test]
+    fn unit_assignments_referencing_missing_designs() {
+        // given
+        let pcb = Pcb::new(
+            "panel".to_string(),
+            2,
+            IndexSet::from([DesignName::from("design_a")]),
+            BTreeMap::from([(0, 0), (1, 0)]),
+        );
+
+        // and
+        let mut project_pcb = ProjectPcb::new(FileReference::Relative("panel.pcb.json".into()));
+        project_pcb
+            .unit_assignments
+            .insert(0, DesignVariant {
+                design_name: DesignName::from("design_a"),
+                variant_name: VariantName::from("variant_1"),
+            });
+        project_pcb
+            .unit_assignments
+            .insert(1, DesignVariant {
+                design_name: DesignName::from("design_b"),
+                variant_name: VariantName::from("variant_1"),
+            });
+        let mut project = Project::default();
+        project.pcbs.push(project_pcb);
+
+        // when
+        let issues = analyze(&project, &[&pcb], &BTreeMap::new(), &[
+            IssueCheck::UnitAssignmentDesigns,
+        ]);
+
+        // then
+        assert_eq!(issues, vec![ProjectReportIssue {
+            message: "A unit assignment references a design that is not on the PCB".to_string(),
+            severity: IssueSeverity::Severe,
+            kind: IssueKind::UnitAssignmentWithUnknownDesign {
+                object_path: ObjectPath::from_str("pcb=1::unit=2").unwrap(),
+                design: DesignName::from("design_b"),
+            },
+        }]);
+        assert_eq!(issues[0].kind.code(), "unit-assignment-with-unknown-design");
+    }
+
+    #[test]
+    fn pcbs_with_gerbers_but_no_profile_gerber() {
+        // given
+        let gerber = |file: &str, function: GerberFileFunction| GerberFile {
+            file: file.into(),
+            function: Some(function),
+        };
+        let build_pcb = |gerbers: Vec<GerberFile>| {
+            let mut pcb = Pcb::new("panel".to_string(), 1, IndexSet::new(), BTreeMap::new());
+            pcb.pcb_gerbers = gerbers;
+            pcb
+        };
+        let pcb_without_profile = build_pcb(vec![gerber("top.gbr", GerberFileFunction::Copper(PcbSide::Top))]);
+        let pcb_with_profile = build_pcb(vec![
+            gerber("top.gbr", GerberFileFunction::Copper(PcbSide::Top)),
+            gerber("outline.gbr", GerberFileFunction::Profile),
+        ]);
+        let pcb_without_gerbers = build_pcb(vec![]);
+
+        // and
+        let mut project = Project::default();
+        for file in [
+            "without_profile.pcb.json",
+            "with_profile.pcb.json",
+            "without_gerbers.pcb.json",
+        ] {
+            project
+                .pcbs
+                .push(ProjectPcb::new(FileReference::Relative(file.into())));
+        }
+
+        // when
+        let issues = analyze(
+            &project,
+            &[&pcb_without_profile, &pcb_with_profile, &pcb_without_gerbers],
+            &BTreeMap::new(),
+            &[IssueCheck::PcbProfileGerbers],
+        );
+
+        // then
+        assert_eq!(issues, vec![ProjectReportIssue {
+            message: "A PCB has gerbers, but none of them are a profile (outline) layer".to_string(),
+            severity: IssueSeverity::Warning,
+            kind: IssueKind::PcbWithNoProfileGerber {
+                file: FileReference::Relative("without_profile.pcb.json".into()),
+            },
+        }]);
+        assert_eq!(issues[0].kind.code(), "pcb-with-no-profile-gerber");
+    }
+}
@@ -0,0 +1,360 @@
+//! Label data for feeders and PCB units.
+//!
+//! Labels are generated as CSV, for use with label design software, and as ZPL, for printing directly on Zebra
+//! compatible label printers.  Each label includes a QR code payload, see [`QrPayload`].
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Error;
+use csv::QuoteStyle;
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use pnp::reference::Reference;
+use serde_with::serde_as;
+use serde_with::DisplayFromStr;
+use tracing::info;
+
+use crate::design::DesignName;
+use crate::pcb::Pcb;
+use crate::phase::{Phase, PhaseReference};
+use crate::project::Project;
+use crate::serial_number::SerialNumber;
+use crate::variant::VariantName;
+
+/// The version of the QR payload schema, increment when making incompatible changes to [`QrPayload`].
+pub const QR_PAYLOAD_VERSION: u32 = 1;
+
+/// The content of a label's QR code, serialized as compact JSON, e.g.
+///
+/// `{"type":"load_out_item","v":1,"project":"job1","phase":"top_1","feeder":"FEEDER_1","manufacturer":"RES_MFR1","mpn":"RES1"}`
+///
+/// The operator UI scans the payload to look up the corresponding load-out item or unit.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QrPayload {
+    LoadOutItem {
+        v: u32,
+        project: String,
+        phase: PhaseReference,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        feeder: Option<Reference>,
+        manufacturer: String,
+        mpn: String,
+    },
+    Unit {
+        v: u32,
+        project: String,
+        unit: ObjectPath,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        serial: Option<SerialNumber>,
+    },
+}
+
+impl QrPayload {
+    pub fn to_json(&self) -> String {
+        // Safety: the payload only contains strings and numbers, serialization cannot fail.
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, PartialEq)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct FeederLabel {
+    pub phase: PhaseReference,
+    pub feeder_reference: Option<Reference>,
+    pub manufacturer: String,
+    pub mpn: String,
+    pub package: Option<String>,
+    /// Moisture sensitivity level, e.g. '3'
+    pub msl: Option<String>,
+    // FUTURE populate the lot once reels/lots are tracked.
+    pub lot: Option<String>,
+    pub qr_payload: String,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, serde::Serialize, PartialEq)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct UnitLabel {
+    #[serde_as(as = "DisplayFromStr")]
+    pub unit_path: ObjectPath,
+    pub serial_number: Option<SerialNumber>,
+    pub project: String,
+    pub design: Option<DesignName>,
+    pub variant: Option<VariantName>,
+    pub qr_payload: String,
+}
+
+pub fn build_feeder_labels(project: &Project, phase: &Phase, load_out_items: &[LoadOutItem]) -> Vec<FeederLabel> {
+    load_out_items
+        .iter()
+        .map(|item| {
+            let qr_payload = QrPayload::LoadOutItem {
+                v: QR_PAYLOAD_VERSION,
+                project: project.name.clone(),
+                phase: phase.reference.clone(),
+                feeder: item.reference.clone(),
+                manufacturer: item.manufacturer.clone(),
+                mpn: item.mpn.clone(),
+            };
+
+            let msl = project
+                .part_states
+                .get(&Part::new(item.manufacturer.clone(), item.mpn.clone()))
+                .and_then(|part_state| part_state.moisture_sensitivity.as_ref())
+                .map(|moisture_sensitivity| moisture_sensitivity.level.to_string());
+
+            FeederLabel {
+                phase: phase.reference.clone(),
+                feeder_reference: item.reference.clone(),
+                manufacturer: item.manufacturer.clone(),
+                mpn: item.mpn.clone(),
+                package: item.package.clone(),
+                msl,
+                lot: None,
+                qr_payload: qr_payload.to_json(),
+            }
+        })
+        .collect()
+}
+
+/// Builds a label for every unit of every PCB, ordered by pcb and unit.
+pub fn build_unit_labels(project: &Project, pcbs: &[&Pcb]) -> Vec<UnitLabel> {
+    project
+        .pcbs
+        .iter()
+        .zip(pcbs)
+        .enumerate()
+        .flat_map(|(pcb_index, (project_pcb, pcb))| {
+            (0..pcb.units).map(move |unit_index| {
+                let mut unit_path = ObjectPath::default();
+                unit_path.set_pcb_instance(pcb_index as u16 + 1);
+                unit_path.set_pcb_unit(unit_index + 1);
+
+                let design = pcb
+                    .unit_map
+                    .get(&unit_index)
+                    .and_then(|design_index| {
+                        pcb.design_names
+                            .get_index(*design_index)
+                    })
+                    .cloned();
+                let variant = project_pcb
+                    .unit_assignments
+                    .get(&unit_index)
+                    .map(|design_variant| design_variant.variant_name.clone());
+                let serial_number = project_pcb
+                    .serial_numbers
+                    .get(&unit_index)
+                    .cloned();
+
+                let qr_payload = QrPayload::Unit {
+                    v: QR_PAYLOAD_VERSION,
+                    project: project.name.clone(),
+                    unit: unit_path.clone(),
+                    serial: serial_number.clone(),
+                };
+
+                UnitLabel {
+                    unit_path,
+                    serial_number,
+                    project: project.name.clone(),
+                    design,
+                    variant,
+                    qr_payload: qr_payload.to_json(),
+                }
+            })
+        })
+        .collect()
+}
+
+pub fn build_feeder_labels_file_path(phase: &Phase, directory: &Path, extension: &str) -> PathBuf {
+    let mut path = PathBuf::from(directory);
+    path.push(format!("{}_feeder_labels.{}", phase.reference, extension));
+    path
+}
+
+pub fn build_unit_labels_file_path(project_name: &str, directory: &Path, extension: &str) -> PathBuf {
+    let mut path = PathBuf::from(directory);
+    path.push(format!("{}_unit_labels.{}", project_name, extension));
+    path
+}
+
+pub fn store_labels_as_csv<T: serde::Serialize>(labels: &[T], path: &PathBuf) -> Result<(), Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_path(path)?;
+
+    for label in labels {
+        writer.serialize(label)?;
+    }
+
+    writer.flush()?;
+
+    info!("Generated labels. path: {:?}", path);
+
+    Ok(())
+}
+
+pub fn store_feeder_labels_as_zpl(labels: &[FeederLabel], path: &PathBuf) -> Result<(), Error> {
+    store_zpl(
+        labels.iter().map(|label| {
+            let title = label
+                .feeder_reference
+                .as_ref()
+                .map_or_else(|| label.mpn.clone(), |reference| reference.to_string());
+            let details = [
+                Some(format!("{} {}", label.manufacturer, label.mpn)),
+                label
+                    .package
+                    .as_ref()
+                    .map(|package| format!("Package: {}", package)),
+                label
+                    .msl
+                    .as_ref()
+                    .map(|msl| format!("MSL: {}", msl)),
+                label
+                    .lot
+                    .as_ref()
+                    .map(|lot| format!("Lot: {}", lot)),
+            ];
+            build_zpl_label(&title, details.into_iter().flatten(), &label.qr_payload)
+        }),
+        path,
+    )
+}
+
+pub fn store_unit_labels_as_zpl(labels: &[UnitLabel], path: &PathBuf) -> Result<(), Error> {
+    store_zpl(
+        labels.iter().map(|label| {
+            let title = label
+                .serial_number
+                .clone()
+                .unwrap_or_else(|| label.unit_path.to_string());
+            let details = [
+                Some(label.project.clone()),
+                Some(label.unit_path.to_string()),
+                match (&label.design, &label.variant) {
+                    (Some(design), Some(variant)) => Some(format!("{} / {}", design, variant)),
+                    (Some(design), None) => Some(design.to_string()),
+                    _ => None,
+                },
+            ];
+            build_zpl_label(&title, details.into_iter().flatten(), &label.qr_payload)
+        }),
+        path,
+    )
+}
+
+fn store_zpl(labels: impl Iterator<Item = String>, path: &PathBuf) -> Result<(), Error> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    for label in labels {
+        writer.write_all(label.as_bytes())?;
+    }
+    writer.flush()?;
+
+    info!("Generated labels. path: {:?}", path);
+
+    Ok(())
+}
+
+/// A 2" x 1" label at 203 dpi, with the title and details on the left and the QR code on the right.
+fn build_zpl_label(title: &str, details: impl Iterator<Item = String>, qr_payload: &str) -> String {
+    let mut zpl = String::from("^XA\n^CI28\n");
+    zpl.push_str(&format!("^FO10,10^A0N,32,32^FH^FD{}^FS\n", zpl_escape(title)));
+    for (index, detail) in details.enumerate() {
+        zpl.push_str(&format!(
+            "^FO10,{}^A0N,20,20^FH^FD{}^FS\n",
+            50 + index * 24,
+            zpl_escape(&detail)
+        ));
+    }
+    zpl.push_str(&format!("^FO270,10^BQN,2,3^FH^FDQA,{}^FS\n", zpl_escape(qr_payload)));
+    zpl.push_str("^XZ\n");
+    zpl
+}
+
+/// Escapes the characters that have a special meaning in ZPL field data, for use with `^FH`.
+fn zpl_escape(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '_' => "_5F".to_string(),
+            '^' => "_5E".to_string(),
+            '~' => "_7E".to_string(),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod labels_tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    pub fn qr_payload() {
+        // given
+        let payload = QrPayload::LoadOutItem {
+            v: QR_PAYLOAD_VERSION,
+            project: "job1".to_string(),
+            phase: PhaseReference::from_raw_str("top_1"),
+            feeder: Some(Reference::from_raw_str("FEEDER_1")),
+            manufacturer: "RES_MFR1".to_string(),
+            mpn: "RES1".to_string(),
+        };
+
+        // when
+        let json = payload.to_json();
+
+        // then
+        assert_eq!(
+            json,
+            r#"{"type":"load_out_item","v":1,"project":"job1","phase":"top_1","feeder":"FEEDER_1","manufacturer":"RES_MFR1","mpn":"RES1"}"#
+        );
+
+        // and
+        let deserialized: QrPayload = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, payload);
+    }
+
+    #[test]
+    pub fn unit_qr_payload() {
+        // given
+        let payload = QrPayload::Unit {
+            v: QR_PAYLOAD_VERSION,
+            project: "job1".to_string(),
+            unit: ObjectPath::from_str("pcb=1::unit=2").unwrap(),
+            serial: None,
+        };
+
+        // when
+        let json = payload.to_json();
+
+        // then
+        assert_eq!(json, r#"{"type":"unit","v":1,"project":"job1","unit":"pcb=1::unit=2"}"#);
+    }
+
+    #[test]
+    pub fn zpl_label() {
+        // when
+        let zpl = build_zpl_label("FEEDER_1", ["MFR MPN^1".to_string()].into_iter(), r#"{"type":"unit"}"#);
+
+        // then
+        assert_eq!(
+            zpl,
+            "^XA\n^CI28\n\
+            ^FO10,10^A0N,32,32^FH^FDFEEDER_5F1^FS\n\
+            ^FO10,50^A0N,20,20^FH^FDMFR MPN_5E1^FS\n\
+            ^FO270,10^BQN,2,3^FH^FDQA,{\"type\":\"unit\"}^FS\n\
+            ^XZ\n"
+        );
+    }
+}
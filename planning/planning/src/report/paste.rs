@@ -0,0 +1,346 @@
+//! Solder paste analysis and stencil setup sheets.
+//!
+//! The paste apertures of each unit are taken from the 'Paste' gerbers of the unit's design, units without a design,
+//! or whose design has no paste gerber for the side, are reported as missing paste data.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use gerber::geometry::{summarize_apertures, ApertureSummary, GeometryError};
+use gerber::{GerberFileFunction, LoadError};
+use pnp::pcb::{PcbInstanceNumber, PcbSide, PcbUnitNumber};
+use rust_decimal::prelude::ToPrimitive;
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::design::{DesignIndex, DesignName};
+use crate::pcb::{Pcb, PcbSideAssemblyOrientation};
+use crate::phase::{Phase, PhaseReference};
+use crate::process::{ProcessDefinition, TaskReference};
+
+#[derive(Error, Debug)]
+pub enum PasteAnalysisError {
+    #[error("Unable to load paste gerber. file: {file:?}, cause: {cause}")]
+    LoadError { file: PathBuf, cause: LoadError },
+
+    #[error("Unable to interpret paste gerber. file: {file:?}, cause: {cause}")]
+    GeometryError { file: PathBuf, cause: GeometryError },
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct UnitPasteSummary {
+    pub unit: PcbUnitNumber,
+    pub design: DesignName,
+    pub aperture_count: usize,
+    /// In square millimeters.
+    pub total_area: f64,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct PcbPasteAnalysis {
+    pub units: Vec<UnitPasteSummary>,
+    /// Units without a design, or whose design has no paste gerber for the side.
+    pub missing_paste: Vec<PcbUnitNumber>,
+}
+
+/// Summarizes the paste apertures of each unit of the PCB, for the side.
+///
+/// Each design's paste gerbers are only interpreted once, regardless of the amount of units using the design.
+pub fn analyze_pcb_paste(pcb: &Pcb, pcb_side: PcbSide) -> Result<PcbPasteAnalysis, PasteAnalysisError> {
+    let mut design_summaries: BTreeMap<DesignIndex, Option<ApertureSummary>> = BTreeMap::new();
+    let mut analysis = PcbPasteAnalysis::default();
+
+    for unit_index in 0..pcb.units {
+        let unit = unit_index + 1;
+
+        let Some(design_index) = pcb.unit_map.get(&unit_index) else {
+            analysis.missing_paste.push(unit);
+            continue;
+        };
+
+        let summary = match design_summaries.get(design_index) {
+            Some(summary) => *summary,
+            None => {
+                let summary = summarize_design_paste(pcb, *design_index, pcb_side)?;
+                design_summaries.insert(*design_index, summary);
+                summary
+            }
+        };
+
+        let design = pcb
+            .design_names
+            .get_index(*design_index)
+            .cloned();
+
+        match (summary, design) {
+            (Some(summary), Some(design)) => analysis.units.push(UnitPasteSummary {
+                unit,
+                design,
+                aperture_count: summary.count,
+                total_area: summary.area,
+            }),
+            _ => analysis.missing_paste.push(unit),
+        }
+    }
+
+    if !analysis.missing_paste.is_empty() {
+        warn!(
+            "Units missing paste data. pcb: '{}', side: {:?}, units: {:?}",
+            pcb.name, pcb_side, analysis.missing_paste
+        );
+    }
+
+    Ok(analysis)
+}
+
+/// Returns `None` if the design has no paste gerbers for the side.
+fn summarize_design_paste(
+    pcb: &Pcb,
+    design_index: DesignIndex,
+    pcb_side: PcbSide,
+) -> Result<Option<ApertureSummary>, PasteAnalysisError> {
+    let paste_gerbers = pcb
+        .design_gerbers
+        .get(&design_index)
+        .into_iter()
+        .flatten()
+        .filter(|gerber| gerber.function == Some(GerberFileFunction::Paste(pcb_side)))
+        .collect::<Vec<_>>();
+
+    if paste_gerbers.is_empty() {
+        return Ok(None);
+    }
+
+    let mut total = ApertureSummary::default();
+    for gerber in paste_gerbers {
+        let commands = gerber::load_commands(&gerber.file).map_err(|cause| PasteAnalysisError::LoadError {
+            file: gerber.file.clone(),
+            cause,
+        })?;
+        let summary = summarize_apertures(&commands).map_err(|cause| PasteAnalysisError::GeometryError {
+            file: gerber.file.clone(),
+            cause,
+        })?;
+
+        total.count += summary.count;
+        total.area += summary.area;
+    }
+
+    Ok(Some(total))
+}
+
+/// The direction the squeegee travels, as seen from the front of the printer.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum SqueegeeDirection {
+    FrontToBack,
+    LeftToRight,
+}
+
+impl SqueegeeDirection {
+    /// The squeegee travels along the shorter side of the panel, so that the blade spans the longer side.
+    ///
+    /// `size` is the panel size before the orientation's rotation is applied.
+    pub fn for_panel(size: (f64, f64), orientation: &PcbSideAssemblyOrientation) -> Self {
+        let quarter_turns = orientation
+            .rotation
+            .to_i64()
+            .map_or(0, |rotation| (rotation / 90).rem_euclid(2));
+
+        let (width, height) = match quarter_turns {
+            1 => (size.1, size.0),
+            _ => size,
+        };
+
+        match width >= height {
+            true => Self::FrontToBack,
+            false => Self::LeftToRight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct StencilSetupFiducial {
+    /// In millimeters, relative to the panel origin.
+    pub x: f64,
+    pub y: f64,
+    pub copper_diameter: f64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct StencilSetupPcb {
+    pub pcb: PcbInstanceNumber,
+    pub name: String,
+    pub orientation: PcbSideAssemblyOrientation,
+    pub squeegee_direction: SqueegeeDirection,
+    pub fiducials: Vec<StencilSetupFiducial>,
+    pub paste: PcbPasteAnalysis,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct StencilSetupSheet {
+    pub phase: PhaseReference,
+    pub pcb_side: PcbSide,
+    pub pcbs: Vec<StencilSetupPcb>,
+}
+
+/// Returns true if any of the process's operations applies solder paste.
+pub fn process_applies_solder_paste(process: &ProcessDefinition) -> bool {
+    let task = TaskReference::from_raw_str("core::apply_solder_paste");
+
+    process
+        .operations
+        .iter()
+        .any(|operation| operation.tasks.contains(&task))
+}
+
+pub fn build_stencil_setup_sheet(phase: &Phase, pcbs: &[&Pcb]) -> Result<StencilSetupSheet, PasteAnalysisError> {
+    let pcbs = pcbs
+        .iter()
+        .enumerate()
+        .map(|(pcb_index, pcb)| {
+            let orientation = match phase.pcb_side {
+                PcbSide::Top => pcb.orientation.top.clone(),
+                PcbSide::Bottom => pcb.orientation.bottom.clone(),
+            };
+
+            let panel_sizing = &pcb.panel_sizing;
+            let squeegee_direction =
+                SqueegeeDirection::for_panel((panel_sizing.size.x, panel_sizing.size.y), &orientation);
+
+            let fiducials = panel_sizing
+                .fiducials
+                .iter()
+                .map(|fiducial| StencilSetupFiducial {
+                    x: fiducial.position.x,
+                    y: fiducial.position.y,
+                    copper_diameter: fiducial.copper_diameter,
+                })
+                .collect();
+
+            let paste = analyze_pcb_paste(pcb, phase.pcb_side)?;
+
+            Ok(StencilSetupPcb {
+                pcb: pcb_index as PcbInstanceNumber + 1,
+                name: pcb.name.clone(),
+                orientation,
+                squeegee_direction,
+                fiducials,
+                paste,
+            })
+        })
+        .collect::<Result<Vec<_>, PasteAnalysisError>>()?;
+
+    Ok(StencilSetupSheet {
+        phase: phase.reference.clone(),
+        pcb_side: phase.pcb_side,
+        pcbs,
+    })
+}
+
+pub fn build_stencil_setup_sheet_file_path(phase: &Phase, directory: &Path) -> PathBuf {
+    let mut path = PathBuf::from(directory);
+    path.push(format!("{}_stencil_setup.json", phase.reference));
+    path
+}
+
+pub fn stencil_setup_sheet_save_as_json(sheet: &StencilSetupSheet, path: &PathBuf) -> Result<(), anyhow::Error> {
+    let file = File::create(path)?;
+    let mut writer = std::io::BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, sheet)?;
+    writer.flush()?;
+
+    info!("Generated stencil setup sheet. path: {:?}", path);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod paste_tests {
+    use std::collections::BTreeMap;
+
+    use gerber::GerberFile;
+    use indexmap::IndexSet;
+    use rust_decimal_macros::dec;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::pcb::PcbAssemblyFlip;
+
+    const PASTE_GERBER: &str = "%FSLAX46Y46*%
+%MOMM*%
+%TF.FileFunction,Paste,Top*%
+%ADD10R,1.000000X2.000000*%
+D10*
+X0Y0D03*
+X5000000Y0D03*
+M02*
+";
+
+    #[test]
+    fn analyze_paste_per_unit() {
+        // given
+        let temp_dir = tempdir().unwrap();
+        let paste_path = temp_dir
+            .path()
+            .join("design_a_paste_top.gbr");
+        std::fs::write(&paste_path, PASTE_GERBER).unwrap();
+
+        // and 3 units, unit 1 and 2 use design a, which has paste, unit 3 uses design b, which does not.
+        let mut pcb = Pcb::new(
+            "panel".to_string(),
+            3,
+            IndexSet::from([DesignName::from("design_a"), DesignName::from("design_b")]),
+            BTreeMap::from([(0, 0), (1, 0), (2, 1)]),
+        );
+        pcb.design_gerbers
+            .insert(0, vec![GerberFile {
+                file: paste_path,
+                function: Some(GerberFileFunction::Paste(PcbSide::Top)),
+            }]);
+
+        // when
+        let analysis = analyze_pcb_paste(&pcb, PcbSide::Top).unwrap();
+
+        // then
+        let expected_unit = |unit| UnitPasteSummary {
+            unit,
+            design: DesignName::from("design_a"),
+            aperture_count: 2,
+            total_area: 4.0,
+        };
+        assert_eq!(analysis, PcbPasteAnalysis {
+            units: vec![expected_unit(1), expected_unit(2)],
+            missing_paste: vec![3],
+        });
+
+        // and the bottom has no paste at all
+        let analysis = analyze_pcb_paste(&pcb, PcbSide::Bottom).unwrap();
+        assert!(analysis.units.is_empty());
+        assert_eq!(analysis.missing_paste, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn squeegee_direction() {
+        // given
+        let orientation = PcbSideAssemblyOrientation {
+            flip: PcbAssemblyFlip::None,
+            rotation: dec!(0),
+        };
+        let rotated_orientation = PcbSideAssemblyOrientation {
+            flip: PcbAssemblyFlip::None,
+            rotation: dec!(90),
+        };
+
+        // expect
+        assert_eq!(
+            SqueegeeDirection::for_panel((100.0, 50.0), &orientation),
+            SqueegeeDirection::FrontToBack
+        );
+        assert_eq!(
+            SqueegeeDirection::for_panel((100.0, 50.0), &rotated_orientation),
+            SqueegeeDirection::LeftToRight
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+
+use math::angle::normalize_angle_deg_signed_decimal;
+use pnp::package::Package;
+use pnp::part::Part;
+use rust_decimal::Decimal;
+use serde_with::serde_as;
+use tracing::info;
+
+/// Rotation offsets, in degrees, positive is anti-clockwise.
+///
+/// EDA tools and machines rarely agree on the zero rotation of a package, the offsets are added to the placement
+/// rotations when generating artifacts.
+///
+/// A part offset takes precedence over a package offset.
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RotationOffsets {
+    /// keyed by package name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub packages: BTreeMap<String, Decimal>,
+
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub parts: BTreeMap<Part, Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum RotationOffsetTarget {
+    Package(String),
+    Part(Part),
+}
+
+/// Where an effective rotation offset came from.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum RotationOffsetSource {
+    None,
+    Package(String),
+    Part,
+}
+
+impl RotationOffsets {
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty() && self.parts.is_empty()
+    }
+
+    /// Set, or clear when `offset` is `None`, the offset for the target.
+    ///
+    /// Returns `true` if the offsets were modified.
+    pub fn update(&mut self, target: RotationOffsetTarget, offset: Option<Decimal>) -> bool {
+        info!("Updating rotation offset. target: {:?}, offset: {:?}", target, offset);
+
+        fn update_entry<K: Ord>(map: &mut BTreeMap<K, Decimal>, key: K, offset: Option<Decimal>) -> bool {
+            match offset {
+                Some(offset) => map.insert(key, offset) != Some(offset),
+                None => map.remove(&key).is_some(),
+            }
+        }
+
+        match target {
+            RotationOffsetTarget::Package(name) => update_entry(&mut self.packages, name, offset),
+            RotationOffsetTarget::Part(part) => update_entry(&mut self.parts, part, offset),
+        }
+    }
+
+    /// Returns the offset to use for a part, `Decimal::ZERO` if there is no offset for the part or its package.
+    pub fn offset_for(&self, part: &Part, package: Option<&Package>) -> (Decimal, RotationOffsetSource) {
+        if let Some(offset) = self.parts.get(part) {
+            return (*offset, RotationOffsetSource::Part);
+        }
+
+        package
+            .and_then(|package| {
+                self.packages
+                    .get(&package.name)
+                    .map(|offset| (*offset, RotationOffsetSource::Package(package.name.clone())))
+            })
+            .unwrap_or((Decimal::ZERO, RotationOffsetSource::None))
+    }
+
+    /// Returns the rotation with the offset for the part applied, normalized to be within -180 to 180 degrees.
+    pub fn apply(&self, rotation: Decimal, part: &Part, package: Option<&Package>) -> Decimal {
+        let (offset, _source) = self.offset_for(part, package);
+
+        apply_rotation_offset(rotation, offset)
+    }
+}
+
+pub fn apply_rotation_offset(rotation: Decimal, offset: Decimal) -> Decimal {
+    if offset.is_zero() {
+        return rotation;
+    }
+
+    normalize_angle_deg_signed_decimal(rotation + offset).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[rstest]
+    #[case(dec!(0), dec!(90), dec!(90))]
+    #[case(dec!(90), dec!(-90), dec!(0))]
+    #[case(dec!(170), dec!(20), dec!(-170))]
+    #[case(dec!(-170), dec!(-20), dec!(170))]
+    #[case(dec!(45), dec!(0), dec!(45))]
+    pub fn apply_offset(#[case] rotation: Decimal, #[case] offset: Decimal, #[case] expected_rotation: Decimal) {
+        // expect
+        assert_eq!(apply_rotation_offset(rotation, offset), expected_rotation);
+    }
+
+    #[test]
+    pub fn part_offset_takes_precedence_over_package_offset() {
+        // given
+        let part = Part::new("MFR1".to_string(), "PART1".to_string());
+        let package = Package::new("SOT-23".to_string());
+
+        let mut rotation_offsets = RotationOffsets::default();
+        rotation_offsets.update(RotationOffsetTarget::Package("SOT-23".to_string()), Some(dec!(90)));
+
+        // when
+        let package_offset = rotation_offsets.offset_for(&part, Some(&package));
+
+        // and
+        rotation_offsets.update(RotationOffsetTarget::Part(part.clone()), Some(dec!(-90)));
+        let part_offset = rotation_offsets.offset_for(&part, Some(&package));
+
+        // then
+        assert_eq!(
+            package_offset,
+            (dec!(90), RotationOffsetSource::Package("SOT-23".to_string()))
+        );
+        assert_eq!(part_offset, (dec!(-90), RotationOffsetSource::Part));
+    }
+}
@@ -0,0 +1,198 @@
+//! Serial numbers for PCB units, for per-board traceability.
+//!
+//! Serial numbers are generated from a scheme, e.g. `{project}-{date}-{seq:04}`, the supported placeholders are:
+//!
+//! * `{project}` - the name of the project.
+//! * `{date}` - the date of assignment, as `YYYYMMDD`.
+//! * `{seq}` or `{seq:<width>}` - a sequence number, unique within the project, optionally zero-padded to `width`.
+//! * `{pcb}` - the PCB instance number, 1-based.
+//! * `{unit}` - the PCB unit number, 1-based.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use pnp::object_path::ObjectPath;
+use pnp::pcb::{PcbInstanceIndex, PcbInstanceNumber, PcbUnitNumber};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+use time::Date;
+
+pub type SerialNumber = String;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SerialNumberSchemeError {
+    #[error("Unterminated placeholder. scheme: '{0}'")]
+    UnterminatedPlaceholder(String),
+    #[error("Unknown placeholder. placeholder: '{0}'")]
+    UnknownPlaceholder(String),
+    #[error("Invalid sequence width. placeholder: '{0}'")]
+    InvalidSequenceWidth(String),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SerialNumberError {
+    #[error("Unknown PCB. index: {0}")]
+    UnknownPcb(PcbInstanceIndex),
+    #[error("Duplicate serial number, the scheme should include '{{seq}}'. serial_number: '{0}'")]
+    DuplicateSerialNumber(SerialNumber),
+    #[error("Unknown serial number. serial_number: '{0}'")]
+    UnknownSerialNumber(SerialNumber),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SchemeSegment {
+    Literal(String),
+    Project,
+    Date,
+    Sequence { width: usize },
+    Pcb,
+    Unit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, SerializeDisplay, DeserializeFromStr)]
+pub struct SerialNumberScheme {
+    pattern: String,
+    segments: Vec<SchemeSegment>,
+}
+
+/// The values used to generate a single serial number.
+#[derive(Debug, Clone)]
+pub struct SerialNumberContext<'a> {
+    pub project: &'a str,
+    pub date: Date,
+    pub sequence: u32,
+    pub pcb: PcbInstanceNumber,
+    pub unit: PcbUnitNumber,
+}
+
+impl SerialNumberScheme {
+    pub fn format(&self, context: &SerialNumberContext) -> SerialNumber {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                SchemeSegment::Literal(literal) => literal.clone(),
+                SchemeSegment::Project => context.project.to_string(),
+                SchemeSegment::Date => format!(
+                    "{:04}{:02}{:02}",
+                    context.date.year(),
+                    context.date.month() as u8,
+                    context.date.day()
+                ),
+                SchemeSegment::Sequence {
+                    width,
+                } => format!("{:0width$}", context.sequence, width = width),
+                SchemeSegment::Pcb => context.pcb.to_string(),
+                SchemeSegment::Unit => context.unit.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl FromStr for SerialNumberScheme {
+    type Err = SerialNumberSchemeError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let mut segments = vec![];
+        let mut remainder = pattern;
+
+        while let Some(start) = remainder.find('{') {
+            if start > 0 {
+                segments.push(SchemeSegment::Literal(remainder[..start].to_string()));
+            }
+
+            let end = remainder[start..]
+                .find('}')
+                .ok_or(SerialNumberSchemeError::UnterminatedPlaceholder(pattern.to_string()))?
+                + start;
+
+            let placeholder = &remainder[start + 1..end];
+            let segment = match placeholder.split_once(':') {
+                None => match placeholder {
+                    "project" => SchemeSegment::Project,
+                    "date" => SchemeSegment::Date,
+                    "seq" => SchemeSegment::Sequence {
+                        width: 0,
+                    },
+                    "pcb" => SchemeSegment::Pcb,
+                    "unit" => SchemeSegment::Unit,
+                    _ => return Err(SerialNumberSchemeError::UnknownPlaceholder(placeholder.to_string())),
+                },
+                Some(("seq", width)) => {
+                    let width = width
+                        .parse::<usize>()
+                        .map_err(|_| SerialNumberSchemeError::InvalidSequenceWidth(placeholder.to_string()))?;
+                    SchemeSegment::Sequence {
+                        width,
+                    }
+                }
+                Some(_) => return Err(SerialNumberSchemeError::UnknownPlaceholder(placeholder.to_string())),
+            };
+            segments.push(segment);
+
+            remainder = &remainder[end + 1..];
+        }
+
+        if !remainder.is_empty() {
+            segments.push(SchemeSegment::Literal(remainder.to_string()));
+        }
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+            segments,
+        })
+    }
+}
+
+impl Display for SerialNumberScheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.pattern)
+    }
+}
+
+/// Restricts an operation to a single serialized unit, the serial number is recorded in the operation history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitScope {
+    pub unit_path: ObjectPath,
+    pub serial_number: SerialNumber,
+}
+
+#[cfg(test)]
+mod serial_number_tests {
+    use rstest::rstest;
+    use time::Month;
+
+    use super::*;
+
+    #[test]
+    pub fn format() {
+        // given
+        let scheme = SerialNumberScheme::from_str("{project}-{date}-{seq:04}/P{pcb}U{unit}").unwrap();
+        let context = SerialNumberContext {
+            project: "job1",
+            date: Date::from_calendar_date(2025, Month::March, 7).unwrap(),
+            sequence: 12,
+            pcb: 1,
+            unit: 3,
+        };
+
+        // when
+        let serial_number = scheme.format(&context);
+
+        // then
+        assert_eq!(serial_number, "job1-20250307-0012/P1U3");
+        assert_eq!(scheme.to_string(), "{project}-{date}-{seq:04}/P{pcb}U{unit}");
+    }
+
+    #[rstest]
+    #[case("{project", SerialNumberSchemeError::UnterminatedPlaceholder("{project".to_string()))]
+    #[case("{name}", SerialNumberSchemeError::UnknownPlaceholder("name".to_string()))]
+    #[case("{date:4}", SerialNumberSchemeError::UnknownPlaceholder("date:4".to_string()))]
+    #[case("{seq:x}", SerialNumberSchemeError::InvalidSequenceWidth("seq:x".to_string()))]
+    pub fn invalid_schemes(#[case] pattern: &str, #[case] expected_error: SerialNumberSchemeError) {
+        // when
+        let result = SerialNumberScheme::from_str(pattern);
+
+        // then
+        assert_eq!(result, Err(expected_error));
+    }
+}
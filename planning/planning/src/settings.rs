@@ -0,0 +1,145 @@
+//! Project-wide conventions for lengths, positions and rotations.
+//!
+//! Positions are always stored in millimeters, with the origin at the bottom-left, and positive anti-clockwise
+//! rotations, as they are exported by EDA tools; the settings are applied when they are exported, e.g. the phase
+//! placements, or reported.
+
+use eda_units::eda_units::unit_system::UnitSystem;
+use math::angle::normalize_angle_deg_signed_decimal;
+use rust_decimal::Decimal;
+
+use crate::placement::PlacementSortingItem;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct ProjectSettings {
+    #[serde(default = "default_unit_system")]
+    pub unit_system: UnitSystem,
+
+    #[serde(default)]
+    pub origin: OriginConvention,
+
+    #[serde(default)]
+    pub rotation_direction: RotationDirection,
+
+    /// The placement orderings of new phases.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub default_placement_orderings: Vec<PlacementSortingItem>,
+}
+
+fn default_unit_system() -> UnitSystem {
+    UnitSystem::Millimeters
+}
+
+impl Default for ProjectSettings {
+    fn default() -> Self {
+        Self {
+            unit_system: default_unit_system(),
+            origin: Default::default(),
+            rotation_direction: Default::default(),
+            default_placement_orderings: vec![],
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OriginConvention {
+    /// Positive Y = Up
+    #[default]
+    BottomLeft,
+    /// Positive Y = Down, e.g. like images and some placement machines.
+    TopLeft,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RotationDirection {
+    #[default]
+    AntiClockwise,
+    Clockwise,
+}
+
+impl ProjectSettings {
+    pub fn is_default(&self) -> bool {
+        self.eq(&Self::default())
+    }
+
+    /// Converts a length, in millimeters, to the unit system of the settings.
+    pub fn length(&self, millimeters: Decimal) -> Decimal {
+        (millimeters * UnitSystem::Millimeters.scale_decimal_for(self.unit_system)).normalize()
+    }
+
+    /// Converts a position, in millimeters with the origin at the bottom-left, to the conventions of the settings.
+    ///
+    /// `height` is the height of the panel, in millimeters, it is only used for [`OriginConvention::TopLeft`].
+    pub fn position(&self, x: Decimal, y: Decimal, height: Decimal) -> (Decimal, Decimal) {
+        let y = match self.origin {
+            OriginConvention::BottomLeft => y,
+            OriginConvention::TopLeft => height - y,
+        };
+
+        (self.length(x), self.length(y))
+    }
+
+    /// Converts a positive anti-clockwise rotation, in degrees, to the rotation direction of the settings.
+    pub fn rotation(&self, degrees: Decimal) -> Decimal {
+        match self.rotation_direction {
+            RotationDirection::AntiClockwise => degrees,
+            RotationDirection::Clockwise => normalize_angle_deg_signed_decimal(-degrees).normalize(),
+        }
+    }
+
+    /// Converts a rotation, in degrees, in the rotation direction of the settings, to a positive anti-clockwise
+    /// rotation, e.g. a rotation entered by a user.
+    pub fn rotation_from(&self, degrees: Decimal) -> Decimal {
+        match self.rotation_direction {
+            RotationDirection::AntiClockwise => degrees,
+            RotationDirection::Clockwise => normalize_angle_deg_signed_decimal(-degrees).normalize(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod settings_tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    pub fn default_settings_do_not_convert() {
+        // given
+        let settings = ProjectSettings::default();
+
+        // expect
+        assert_eq!(settings.position(dec!(10), dec!(20), dec!(100)), (dec!(10), dec!(20)));
+        assert_eq!(settings.rotation(dec!(90)), dec!(90));
+    }
+
+    #[test]
+    pub fn convert_to_inches_top_left_and_clockwise() {
+        // given
+        let settings = ProjectSettings {
+            unit_system: UnitSystem::Inches,
+            origin: OriginConvention::TopLeft,
+            rotation_direction: RotationDirection::Clockwise,
+            ..ProjectSettings::default()
+        };
+
+        // expect
+        assert_eq!(settings.position(dec!(25.4), dec!(74.6), dec!(100)), (dec!(1), dec!(1)));
+        assert_eq!(settings.rotation(dec!(90)), dec!(-90));
+    }
+
+    #[test]
+    pub fn convert_from_clockwise() {
+        // given
+        let settings = ProjectSettings {
+            rotation_direction: RotationDirection::Clockwise,
+            ..ProjectSettings::default()
+        };
+
+        // expect
+        assert_eq!(settings.rotation_from(dec!(90)), dec!(-90));
+        assert_eq!(settings.rotation_from(settings.rotation(dec!(45))), dec!(45));
+    }
+}
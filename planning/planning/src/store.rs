@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+/// Selects where a project's parts, load-outs and placements are stored.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Default, PartialEq, Clone, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StoreBackend {
+    /// One CSV file per parts list, load-out and design variant's placements.
+    #[default]
+    Csv,
+    /// A single database file, the path is relative to the project directory.
+    Sqlite { path: PathBuf },
+}
+
+impl StoreBackend {
+    pub fn is_csv(&self) -> bool {
+        matches!(self, StoreBackend::Csv)
+    }
+}
@@ -0,0 +1,205 @@
+//! Project templates.
+//!
+//! A template is a user-managed directory containing a `template.json` file and, optionally, load-out files that
+//! are copied into each new project.
+
+use std::path::{Path, PathBuf};
+
+use pnp::pcb::PcbSide;
+use thiserror::Error;
+use tracing::info;
+use util::source::Source;
+
+use crate::file;
+use crate::library::LibraryConfig;
+use crate::phase::{PhaseError, PhaseReference};
+use crate::process::{ProcessDefinition, ProcessReference};
+use crate::project::Project;
+use crate::rotation::RotationOffsets;
+
+pub const TEMPLATE_FILE_NAME: &str = "template.json";
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Default, Clone, PartialEq)]
+pub struct ProjectTemplate {
+    #[serde(default)]
+    pub library_config: LibraryConfig,
+
+    /// If empty, the default processes are used.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub processes: Vec<ProcessDefinition>,
+
+    /// In order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub phases: Vec<PhaseTemplate>,
+
+    #[serde(skip_serializing_if = "RotationOffsets::is_empty")]
+    #[serde(default)]
+    pub rotation_offsets: RotationOffsets,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct PhaseTemplate {
+    pub reference: PhaseReference,
+    pub process: ProcessReference,
+
+    /// A URL or a path, relative paths are relative to the template directory for the template and relative to the
+    /// project directory for the project.
+    pub load_out_source: String,
+
+    pub pcb_side: PcbSide,
+}
+
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    #[error("Unable to load template. path: {path}, cause: {cause}")]
+    UnableToLoad { path: PathBuf, cause: std::io::Error },
+    #[error("Phase error. cause: {0}")]
+    PhaseError(#[from] PhaseError),
+}
+
+pub fn load_template(template_directory: &Path) -> Result<ProjectTemplate, TemplateError> {
+    let path = template_directory.join(TEMPLATE_FILE_NAME);
+    info!("Loading project template. path: {:?}", path);
+
+    file::load::<ProjectTemplate>(&path).map_err(|cause| TemplateError::UnableToLoad {
+        path,
+        cause,
+    })
+}
+
+impl ProjectTemplate {
+    /// Relative load-out paths are resolved against the project directory.
+    pub fn build_project(&self, name: String, project_directory: &Path) -> Result<Project, TemplateError> {
+        let mut project = Project {
+            name,
+            library_config: self.library_config.clone(),
+            rotation_offsets: self.rotation_offsets.clone(),
+            ..Project::default()
+        };
+
+        if !self.processes.is_empty() {
+            project.processes = self.processes.clone();
+        }
+
+        for phase in self.phases.iter() {
+            let load_out_source = match relative_load_out_path(&phase.load_out_source) {
+                Some(path) => project_directory
+                    .join(path)
+                    .display()
+                    .to_string(),
+                None => phase.load_out_source.clone(),
+            };
+
+            project.update_phase(
+                phase.reference.clone(),
+                phase.process.clone(),
+                load_out_source,
+                phase.pcb_side,
+            )?;
+        }
+
+        Ok(project)
+    }
+
+    /// The load-out files in the template directory that should be copied to the project directory.
+    ///
+    /// Returns `(template file, project file)` tuples, load-outs that are not in the template directory are skipped.
+    pub fn load_out_files(&self, template_directory: &Path, project_directory: &Path) -> Vec<(PathBuf, PathBuf)> {
+        self.phases
+            .iter()
+            .filter_map(|phase| relative_load_out_path(&phase.load_out_source))
+            .map(|path| (template_directory.join(&path), project_directory.join(&path)))
+            .filter(|(template_file, _project_file)| template_file.is_file())
+            .collect()
+    }
+}
+
+fn relative_load_out_path(load_out_source: &str) -> Option<PathBuf> {
+    match load_out_source.parse::<Source>() {
+        Ok(Source::File(path)) if path.is_relative() => Some(path),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pnp::reference::Reference;
+
+    use super::*;
+
+    #[test]
+    fn build_project() {
+        // given
+        let template = ProjectTemplate {
+            phases: vec![
+                PhaseTemplate {
+                    reference: Reference::from_raw_str("top_1"),
+                    process: Reference::from_raw_str("pnp"),
+                    load_out_source: "load_outs/smt.csv".to_string(),
+                    pcb_side: PcbSide::Top,
+                },
+                PhaseTemplate {
+                    reference: Reference::from_raw_str("bottom_1"),
+                    process: Reference::from_raw_str("manual"),
+                    load_out_source: "https://example.com/load_outs/th".to_string(),
+                    pcb_side: PcbSide::Bottom,
+                },
+            ],
+            ..ProjectTemplate::default()
+        };
+        let project_directory = PathBuf::from("project");
+
+        // when
+        let project = template
+            .build_project("job1".to_string(), &project_directory)
+            .unwrap();
+
+        // then
+        assert_eq!(project.name, "job1");
+        assert_eq!(
+            project
+                .phase_orderings
+                .iter()
+                .map(|reference| reference.to_string())
+                .collect::<Vec<_>>(),
+            vec!["top_1", "bottom_1"]
+        );
+        assert_eq!(
+            project.phases[&Reference::from_raw_str("top_1")].load_out_source,
+            project_directory
+                .join("load_outs/smt.csv")
+                .display()
+                .to_string()
+        );
+        assert_eq!(
+            project.phases[&Reference::from_raw_str("bottom_1")].load_out_source,
+            "https://example.com/load_outs/th"
+        );
+        assert_eq!(project.phase_states.len(), 2);
+    }
+
+    #[test]
+    fn build_project_with_unknown_process() {
+        // given
+        let template = ProjectTemplate {
+            phases: vec![PhaseTemplate {
+                reference: Reference::from_raw_str("top_1"),
+                process: Reference::from_raw_str("reflow"),
+                load_out_source: "load_out.csv".to_string(),
+                pcb_side: PcbSide::Top,
+            }],
+            ..ProjectTemplate::default()
+        };
+
+        // when
+        let result = template.build_project("job1".to_string(), Path::new("project"));
+
+        // then
+        assert!(matches!(
+            result,
+            Err(TemplateError::PhaseError(PhaseError::UnknownProcess(_)))
+        ));
+    }
+}
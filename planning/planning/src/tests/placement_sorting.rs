@@ -4,10 +4,11 @@
 use eda_units::eda_units::unit_system::UnitSystem;
 use pnp::object_path::ObjectPath;
 use pnp::package::Package;
-use pnp::part::Part;
+use pnp::part::{Part, PartAliases};
 use pnp::placement::Placement;
 use util::sorting::SortOrder;
 
+use crate::nozzle::{NozzleAssignments, NozzleReference};
 use crate::placement::{PlacementSortingMode, PlacementState};
 use crate::project::sort_placements;
 
@@ -36,8 +37,10 @@ fn test_placement_sorting_pcb_unit_xy() {
         &mut sortable_placement_states,
         &placement_orderings,
         &load_out_items,
+        &PartAliases::default(),
         &part_packages,
         &pcb_unit_positioning_map,
+        &NozzleAssignments::new(),
     );
 
     // then
@@ -80,8 +83,10 @@ fn test_placement_sorting_pcb_unit_yx() {
         &mut sortable_placement_states,
         &placement_orderings,
         &load_out_items,
+        &PartAliases::default(),
         &part_packages,
         &pcb_unit_positioning_map,
+        &NozzleAssignments::new(),
     );
 
     // then
@@ -99,6 +104,59 @@ fn test_placement_sorting_pcb_unit_yx() {
     ])
 }
 
+#[test]
+fn test_placement_sorting_nozzle() {
+    // given
+    let placement_states = build_placements_for_unit_position_testing();
+    let mut sortable_placement_states = placement_states
+        .iter()
+        .map(|(object_path, placement_state)| (object_path, placement_state))
+        .collect::<Vec<_>>();
+    let placement_orderings = vec![
+        (PlacementSortingMode::Nozzle, SortOrder::Asc).into(),
+        (PlacementSortingMode::PcbUnit, SortOrder::Asc).into(),
+    ];
+    let load_out_items = vec![];
+    let part_packages: BTreeMap<&Part, &Package> = BTreeMap::new();
+    let pcb_unit_positioning_map = vec![];
+
+    // and
+    let nozzle_assignments = NozzleAssignments::from([
+        (
+            ObjectPath::from_raw_str("pcb=1::unit=1::ref_des=R1"),
+            NozzleReference::from_raw_str("CN065"),
+        ),
+        (
+            ObjectPath::from_raw_str("pcb=1::unit=2::ref_des=R1"),
+            NozzleReference::from_raw_str("CN040"),
+        ),
+        (
+            ObjectPath::from_raw_str("pcb=1::unit=3::ref_des=R1"),
+            NozzleReference::from_raw_str("CN065"),
+        ),
+    ]);
+
+    // when
+    sort_placements(
+        &mut sortable_placement_states,
+        &placement_orderings,
+        &load_out_items,
+        &PartAliases::default(),
+        &part_packages,
+        &pcb_unit_positioning_map,
+        &nozzle_assignments,
+    );
+
+    // then
+    let placement_units = sortable_placement_states
+        .iter()
+        .map(|(object_path, _)| object_path.pcb_unit().unwrap())
+        .collect::<Vec<_>>();
+
+    // unassigned placements first, then grouped by nozzle
+    assert_eq!(placement_units, vec![4, 2, 1, 3])
+}
+
 fn build_2x2_panel() -> Vec<(i32, DimensionUnitVector2, &'static str)> {
     // two of the units have their coordinates inches, to ensure that the sorting correctly
     // handles conversions.
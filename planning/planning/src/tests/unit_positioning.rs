@@ -215,6 +215,9 @@ fn test_build_placement_unit_positions(
         operation_status: PlacementStatus::Pending,
         project_status: ProjectPlacementStatus::Used,
         phase: Some(PhaseReference::from_raw_str("Top_SMT")),
+        inspection: None,
+        notes: None,
+        attachments: vec![],
     };
 
     let placement_state2 = PlacementState {
@@ -224,6 +227,9 @@ fn test_build_placement_unit_positions(
         operation_status: PlacementStatus::Pending,
         project_status: ProjectPlacementStatus::Used,
         phase: Some(PhaseReference::from_raw_str("Bottom_SMT")),
+        inspection: None,
+        notes: None,
+        attachments: vec![],
     };
     project.placements.insert(
         ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").unwrap(),
@@ -4,6 +4,7 @@
 use tracing::{info, trace};
 use util::source::Source;
 
+use crate::csv::dialect;
 use crate::csv::AssemblyRuleRecord;
 
 pub type AssemblyRuleSource = Source;
@@ -16,8 +17,7 @@ pub fn load(source: &AssemblyRuleSource) -> Result<Vec<AssemblyRule>, Error> {
         .path()
         .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
 
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .from_path(path.clone())
+    let mut csv_reader = dialect::open_reader(&path)
         .with_context(|| format!("Error reading assembly rules. file: {}", path.display()))?;
 
     let mut assembly_rules: Vec<AssemblyRule> = vec![];
@@ -0,0 +1,241 @@
+//! Store backends, a backend stores parts, load-outs and placements.
+//!
+//! The CSV backend uses a file per source, other backends use the source to identify the data within the backend.
+//!
+//! See [`planning::store::StoreBackend`] for selecting a backend for a project.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Error};
+use planning::store::StoreBackend;
+use pnp::load_out::LoadOutItem;
+use pnp::part::Part;
+use pnp::placement::Placement;
+
+use crate::load_out::{LoadOutFingerprint, LoadOutSource};
+use crate::parts::PartsSource;
+use crate::placements::PlacementsSource;
+use crate::{load_out, parts, placements};
+
+pub trait PartsStore {
+    fn load_parts(&self, source: &PartsSource) -> Result<Vec<Part>, Error>;
+}
+
+pub trait LoadOutStore {
+    fn load_items(&self, source: &LoadOutSource) -> Result<Vec<LoadOutItem>, Error>;
+
+    /// Replaces all the items of the load-out.
+    fn store_items(&self, source: &LoadOutSource, items: &[LoadOutItem]) -> Result<(), Error>;
+
+    /// Creates an empty load-out, if it does not already exist.
+    fn ensure_load_out(&self, source: &LoadOutSource) -> Result<(), Error>;
+
+    /// `false` if the load-out has not been created yet, see [`Self::ensure_load_out`].
+    fn load_out_exists(&self, source: &LoadOutSource) -> Result<bool, Error>;
+}
+
+pub trait PlacementsStore {
+    fn load_placements(&self, source: &PlacementsSource) -> Result<Vec<Placement>, Error>;
+}
+
+pub trait Store: PartsStore + LoadOutStore + PlacementsStore {}
+
+impl<T: PartsStore + LoadOutStore + PlacementsStore> Store for T {}
+
+/// Opens the store for the backend, relative paths are relative to the `directory`.
+///
+/// With the `remote` feature, URL sources for parts and load-outs are handled by the remote store regardless of the
+/// backend, the remote items are cached in the `directory`.
+pub fn open_store(backend: &StoreBackend, directory: &Path) -> Result<Box<dyn Store>, Error> {
+    let store = open_local_store(backend, directory)?;
+
+    #[cfg(feature = "remote")]
+    let store = Box::new(RoutingStore {
+        local: store,
+        remote: crate::remote::RemoteStore::new(directory.join(REMOTE_CACHE_DIRECTORY))?,
+    });
+
+    Ok(store)
+}
+
+fn open_local_store(backend: &StoreBackend, directory: &Path) -> Result<Box<dyn Store>, Error> {
+    match backend {
+        StoreBackend::Csv => Ok(Box::new(CsvStore::default())),
+        #[cfg(feature = "sqlite")]
+        StoreBackend::Sqlite {
+            path,
+        } => Ok(Box::new(crate::sqlite::SqliteStore::open(&directory.join(path))?)),
+        #[cfg(not(feature = "sqlite"))]
+        StoreBackend::Sqlite {
+            path,
+        } => Err(sqlite_unavailable(&directory.join(path))),
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn sqlite_unavailable(path: &Path) -> Error {
+    anyhow!(
+        "SQLite store backend is not available, build with the 'sqlite' feature. path: {}",
+        path.display()
+    )
+}
+
+/// The CSV files to import into a backend, see [`import_csv`].
+#[derive(Debug, Default)]
+pub struct CsvImport {
+    pub parts: Vec<PartsSource>,
+    /// The CSV file, and the source of the load-out in the backend.
+    pub load_outs: Vec<(LoadOutSource, LoadOutSource)>,
+    pub placements: Vec<PlacementsSource>,
+}
+
+/// Imports the CSV files into the backend, replacing the parts, load-outs and placements of the same sources.
+///
+/// The CSV backend uses the files directly, so there is nothing to import.
+pub fn import_csv(backend: &StoreBackend, directory: &Path, import: &CsvImport) -> Result<(), Error> {
+    match backend {
+        StoreBackend::Csv => Err(anyhow!(
+            "The CSV store backend uses the CSV files directly, there is nothing to import"
+        )),
+        #[cfg(feature = "sqlite")]
+        StoreBackend::Sqlite {
+            path,
+        } => {
+            let store = crate::sqlite::SqliteStore::open(&directory.join(path))?;
+            let csv_store = CsvStore::default();
+
+            for source in import.parts.iter() {
+                let parts = csv_store.load_parts(source)?;
+                store.store_parts(source, &parts)?;
+            }
+            for (file_source, source) in import.load_outs.iter() {
+                let items = csv_store.load_items(file_source)?;
+                store.store_items(source, &items)?;
+            }
+            for source in import.placements.iter() {
+                let placements = csv_store.load_placements(source)?;
+                store.store_placements(source, &placements)?;
+            }
+
+            Ok(())
+        }
+        #[cfg(not(feature = "sqlite"))]
+        StoreBackend::Sqlite {
+            path,
+        } => Err(sqlite_unavailable(&directory.join(path))),
+    }
+}
+
+/// Each source is a CSV file.
+///
+/// Load-outs that were modified by another process, since they were loaded by this store, are not overwritten, see
+/// [`load_out::update_items`].
+#[derive(Debug, Default)]
+pub struct CsvStore {
+    load_out_fingerprints: Mutex<HashMap<LoadOutSource, LoadOutFingerprint>>,
+}
+
+impl PartsStore for CsvStore {
+    fn load_parts(&self, source: &PartsSource) -> Result<Vec<Part>, Error> {
+        parts::load_parts(source)
+    }
+}
+
+impl LoadOutStore for CsvStore {
+    fn load_items(&self, source: &LoadOutSource) -> Result<Vec<LoadOutItem>, Error> {
+        let (items, _version, fingerprint) = load_out::load_fingerprinted_items(source)?;
+
+        self.load_out_fingerprints
+            .lock()
+            .unwrap()
+            .insert(source.clone(), fingerprint);
+
+        Ok(items)
+    }
+
+    fn store_items(&self, source: &LoadOutSource, items: &[LoadOutItem]) -> Result<(), Error> {
+        let mut fingerprints = self
+            .load_out_fingerprints
+            .lock()
+            .unwrap();
+
+        let fingerprint = load_out::update_items(source, items, fingerprints.get(source))?;
+        fingerprints.insert(source.clone(), fingerprint);
+
+        Ok(())
+    }
+
+    fn ensure_load_out(&self, source: &LoadOutSource) -> Result<(), Error> {
+        load_out::ensure_load_out(source)
+    }
+
+    fn load_out_exists(&self, source: &LoadOutSource) -> Result<bool, Error> {
+        Ok(load_out::load_out_exists(source))
+    }
+}
+
+impl PlacementsStore for CsvStore {
+    fn load_placements(&self, source: &PlacementsSource) -> Result<Vec<Placement>, Error> {
+        placements::load_placements(source)
+    }
+}
+
+#[cfg(feature = "remote")]
+const REMOTE_CACHE_DIRECTORY: &str = ".remote_cache";
+
+/// Routes URL sources to the remote store, everything else to the local store.
+#[cfg(feature = "remote")]
+struct RoutingStore {
+    local: Box<dyn Store>,
+    remote: crate::remote::RemoteStore,
+}
+
+#[cfg(feature = "remote")]
+impl PartsStore for RoutingStore {
+    fn load_parts(&self, source: &PartsSource) -> Result<Vec<Part>, Error> {
+        match source {
+            util::source::Source::Url(_) => self.remote.load_parts(source),
+            _ => self.local.load_parts(source),
+        }
+    }
+}
+
+#[cfg(feature = "remote")]
+impl LoadOutStore for RoutingStore {
+    fn load_items(&self, source: &LoadOutSource) -> Result<Vec<LoadOutItem>, Error> {
+        match source {
+            util::source::Source::Url(_) => self.remote.load_items(source),
+            _ => self.local.load_items(source),
+        }
+    }
+
+    fn store_items(&self, source: &LoadOutSource, items: &[LoadOutItem]) -> Result<(), Error> {
+        match source {
+            util::source::Source::Url(_) => self.remote.store_items(source, items),
+            _ => self.local.store_items(source, items),
+        }
+    }
+
+    fn ensure_load_out(&self, source: &LoadOutSource) -> Result<(), Error> {
+        match source {
+            util::source::Source::Url(_) => self.remote.ensure_load_out(source),
+            _ => self.local.ensure_load_out(source),
+        }
+    }
+
+    fn load_out_exists(&self, source: &LoadOutSource) -> Result<bool, Error> {
+        match source {
+            util::source::Source::Url(_) => self.remote.load_out_exists(source),
+            _ => self.local.load_out_exists(source),
+        }
+    }
+}
+
+#[cfg(feature = "remote")]
+impl PlacementsStore for RoutingStore {
+    fn load_placements(&self, source: &PlacementsSource) -> Result<Vec<Placement>, Error> {
+        self.local.load_placements(source)
+    }
+}
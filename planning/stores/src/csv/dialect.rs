@@ -0,0 +1,412 @@
+//! CSV dialects, for importing files exported by EDA/ERP tools without editing them first.
+//!
+//! Exported files often use semicolons or tabs as delimiters, decimal commas, non-UTF-8 encodings and different header
+//! names.  A dialect is configured per source using a sidecar file next to the CSV file, see
+//! [`build_dialect_file_path`], e.g. `parts.csv.dialect.json`:
+//!
+//! ```json
+//! {
+//!   "delimiter": ";",
+//!   "decimal_separator": ",",
+//!   "header_aliases": { "Hersteller": "Manufacturer", "Artikelnummer": "Mpn" },
+//!   "encoding": "windows1252"
+//! }
+//! ```
+//!
+//! All the fields are optional.  The content is normalized to UTF-8, comma delimited, CSV with '.' decimal separators
+//! and the header names expected by the store before it is parsed.
+//!
+//! Dialects only apply to the import stores, files written by the planner (e.g. load-outs) are always standard CSV.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+use tracing::debug;
+
+#[derive(Error, Debug)]
+pub enum CsvDialectError {
+    #[error("IO error. cause: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid dialect file. file: {path}, cause: {cause}")]
+    InvalidDialectFile { path: PathBuf, cause: serde_json::Error },
+    #[error("Invalid delimiter, only ASCII delimiters are supported. delimiter: '{0}'")]
+    InvalidDelimiter(char),
+    #[error("Invalid decimal separator, expected '.' or ','. decimal_separator: '{0}'")]
+    InvalidDecimalSeparator(char),
+    #[error("Invalid UTF-8 content, a different encoding may be required. cause: {0}")]
+    InvalidUtf8(#[from] std::string::FromUtf8Error),
+    #[error("Invalid UTF-16 content. cause: {0}")]
+    InvalidUtf16(#[from] std::string::FromUtf16Error),
+    #[error("CSV error. cause: {0}")]
+    CsvError(#[from] csv::Error),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvEncoding {
+    #[default]
+    Utf8,
+    /// ISO-8859-1
+    Latin1,
+    Windows1252,
+    /// e.g. for EasyEDA Pro pick-and-place exports.
+    Utf16le,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CsvDialect {
+    pub delimiter: char,
+    /// Only applies to fields that contain a single decimal number, e.g. `-1,5`.
+    pub decimal_separator: char,
+    /// Maps header names used in the file to the header names expected by the store.
+    pub header_aliases: BTreeMap<String, String>,
+    pub encoding: CsvEncoding,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            decimal_separator: '.',
+            header_aliases: Default::default(),
+            encoding: Default::default(),
+        }
+    }
+}
+
+impl CsvDialect {
+    pub fn with_delimiter(delimiter: char) -> Self {
+        Self {
+            delimiter,
+            ..Self::default()
+        }
+    }
+
+    /// Converts content in this dialect into UTF-8, comma delimited, CSV with '.' decimal separators and aliased
+    /// headers replaced.
+    pub fn normalize(&self, content: Vec<u8>) -> Result<String, CsvDialectError> {
+        if !self.delimiter.is_ascii() {
+            return Err(CsvDialectError::InvalidDelimiter(self.delimiter));
+        }
+        if !matches!(self.decimal_separator, '.' | ',') {
+            return Err(CsvDialectError::InvalidDecimalSeparator(self.decimal_separator));
+        }
+
+        let content = decode(content, &self.encoding)?;
+
+        if self.delimiter == ',' && self.decimal_separator == '.' && self.header_aliases.is_empty() {
+            return Ok(content);
+        }
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter as u8)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(content.as_bytes());
+        let mut writer = csv::WriterBuilder::new()
+            .flexible(true)
+            .from_writer(vec![]);
+
+        for (index, result) in reader.records().enumerate() {
+            let record = result?;
+            let fields: Vec<Cow<str>> = if index == 0 {
+                record
+                    .iter()
+                    .map(|header| match self.header_aliases.get(header.trim()) {
+                        Some(alias) => Cow::Borrowed(alias.as_str()),
+                        None => Cow::Borrowed(header),
+                    })
+                    .collect()
+            } else {
+                record
+                    .iter()
+                    .map(|field| normalize_decimal(field, self.decimal_separator))
+                    .collect()
+            };
+            writer.write_record(
+                fields
+                    .iter()
+                    .map(|field| field.as_ref()),
+            )?;
+        }
+
+        let content = writer
+            .into_inner()
+            .map_err(|error| error.into_error())?;
+
+        // Safety: only UTF-8 strings were written
+        Ok(String::from_utf8(content).unwrap())
+    }
+
+    /// Returns a reader for content in this dialect.
+    pub fn reader(&self, content: Vec<u8>) -> Result<csv::Reader<Cursor<Vec<u8>>>, CsvDialectError> {
+        let content = self.normalize(content)?;
+
+        Ok(csv::ReaderBuilder::new().from_reader(Cursor::new(content.into_bytes())))
+    }
+}
+
+/// e.g. `parts.csv` -> `parts.csv.dialect.json`
+pub fn build_dialect_file_path(path: &Path) -> PathBuf {
+    let mut file_name = path
+        .file_name()
+        .map(|file_name| file_name.to_os_string())
+        .unwrap_or_default();
+    file_name.push(".dialect.json");
+
+    path.with_file_name(file_name)
+}
+
+/// Loads the dialect for the CSV file, from its sidecar file, if there is one.
+pub fn load_dialect(path: &Path) -> Result<Option<CsvDialect>, CsvDialectError> {
+    let dialect_path = build_dialect_file_path(path);
+    if !dialect_path.exists() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(&dialect_path)?;
+    let dialect = serde_json::from_str(&content).map_err(|cause| CsvDialectError::InvalidDialectFile {
+        path: dialect_path.clone(),
+        cause,
+    })?;
+    debug!(
+        "Loaded CSV dialect. file: {}, dialect: {:?}",
+        dialect_path.display(),
+        dialect
+    );
+
+    Ok(Some(dialect))
+}
+
+/// Opens a reader for the CSV file, using the dialect of the file, if configured, or the default dialect.
+pub fn open_reader(path: &Path) -> Result<csv::Reader<Cursor<Vec<u8>>>, CsvDialectError> {
+    open_reader_with_default_dialect(path, &CsvDialect::default())
+}
+
+/// As [`open_reader`], but using `default_dialect` when the file does not have a dialect, e.g. for EDA tools that
+/// export tab delimited files.
+pub fn open_reader_with_default_dialect(
+    path: &Path,
+    default_dialect: &CsvDialect,
+) -> Result<csv::Reader<Cursor<Vec<u8>>>, CsvDialectError> {
+    let dialect = load_dialect(path)?;
+    let content = std::fs::read(path)?;
+
+    dialect
+        .as_ref()
+        .unwrap_or(default_dialect)
+        .reader(content)
+}
+
+fn decode(content: Vec<u8>, encoding: &CsvEncoding) -> Result<String, CsvDialectError> {
+    let content = match encoding {
+        CsvEncoding::Utf8 => String::from_utf8(content)?,
+        CsvEncoding::Latin1 => content
+            .into_iter()
+            .map(char::from)
+            .collect(),
+        CsvEncoding::Windows1252 => content
+            .into_iter()
+            .map(decode_windows1252)
+            .collect(),
+        CsvEncoding::Utf16le => {
+            let units = content
+                .chunks(2)
+                .map(|chunk| {
+                    u16::from_le_bytes([
+                        chunk[0],
+                        chunk
+                            .get(1)
+                            .copied()
+                            .unwrap_or_default(),
+                    ])
+                })
+                .collect::<Vec<u16>>();
+            String::from_utf16(&units)?
+        }
+    };
+
+    match content.starts_with('\u{feff}') {
+        true => Ok(content['\u{feff}'.len_utf8()..].to_string()),
+        false => Ok(content),
+    }
+}
+
+/// Windows-1252 is ISO-8859-1 except for 0x80-0x9F, undefined bytes are mapped to the C1 control characters.
+fn decode_windows1252(byte: u8) -> char {
+    const TABLE: [char; 32] = [
+        '\u{20ac}', '\u{0081}', '\u{201a}', '\u{0192}', '\u{201e}', '\u{2026}', '\u{2020}', '\u{2021}', '\u{02c6}',
+        '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008d}', '\u{017d}', '\u{008f}', '\u{0090}', '\u{2018}',
+        '\u{2019}', '\u{201c}', '\u{201d}', '\u{2022}', '\u{2013}', '\u{2014}', '\u{02dc}', '\u{2122}', '\u{0161}',
+        '\u{203a}', '\u{0153}', '\u{009d}', '\u{017e}', '\u{0178}',
+    ];
+
+    match byte {
+        0x80..=0x9f => TABLE[(byte - 0x80) as usize],
+        _ => char::from(byte),
+    }
+}
+
+/// e.g. `-1,5` -> `-1.5`, fields that are not a single decimal number are unchanged.
+fn normalize_decimal(field: &str, decimal_separator: char) -> Cow<'_, str> {
+    if decimal_separator == '.' {
+        return Cow::Borrowed(field);
+    }
+
+    let trimmed = field.trim();
+    let unsigned = trimmed
+        .strip_prefix(['-', '+'])
+        .unwrap_or(trimmed);
+
+    let is_decimal = unsigned
+        .split_once(decimal_separator)
+        .is_some_and(|(integer, fraction)| {
+            !integer.is_empty()
+                && !fraction.is_empty()
+                && integer
+                    .chars()
+                    .all(|c| c.is_ascii_digit())
+                && fraction
+                    .chars()
+                    .all(|c| c.is_ascii_digit())
+        });
+
+    match is_decimal {
+        true => Cow::Owned(trimmed.replacen(decimal_separator, ".", 1)),
+        false => Cow::Borrowed(field),
+    }
+}
+
+#[cfg(test)]
+mod dialect_tests {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    pub fn normalize() {
+        // given
+        let dialect = CsvDialect {
+            delimiter: ';',
+            decimal_separator: ',',
+            header_aliases: BTreeMap::from([
+                ("Hersteller".to_string(), "Manufacturer".to_string()),
+                ("Artikelnummer".to_string(), "Mpn".to_string()),
+            ]),
+            encoding: CsvEncoding::Windows1252,
+        };
+        // 0xB5 = 'µ', 0x80 = '€'
+        let content =
+            b"Hersteller;Artikelnummer;Wert;Preis\nMFR1;\"1,5\";1\xb5F;-0,25\x80\nMFR2;PART2;-0,25;10\n".to_vec();
+
+        // when
+        let result = dialect.normalize(content).unwrap();
+
+        // then
+        assert_eq!(
+            result,
+            "Manufacturer,Mpn,Wert,Preis\nMFR1,1.5,1µF,\"-0,25€\"\nMFR2,PART2,-0.25,10\n"
+        );
+    }
+
+    #[test]
+    pub fn utf16le() {
+        // given
+        let dialect = CsvDialect {
+            delimiter: '\t',
+            encoding: CsvEncoding::Utf16le,
+            ..CsvDialect::default()
+        };
+        let content = "\u{feff}Designator\tMid X\nR1\t10µm\n"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect::<Vec<u8>>();
+
+        // when
+        let result = dialect.normalize(content).unwrap();
+
+        // then
+        assert_eq!(result, "Designator,Mid X\nR1,10µm\n");
+    }
+
+    #[test]
+    pub fn default_dialect_is_unchanged() {
+        // given
+        let content = "\u{feff}Manufacturer,Mpn\nMFR1,\"PART;1\"\n";
+
+        // when
+        let result = CsvDialect::default()
+            .normalize(content.as_bytes().to_vec())
+            .unwrap();
+
+        // then
+        assert_eq!(result, "Manufacturer,Mpn\nMFR1,\"PART;1\"\n");
+    }
+
+    #[rstest]
+    #[case("1,5", "1.5")]
+    #[case(" -10,25 ", "-10.25")]
+    #[case("+0,5", "+0.5")]
+    #[case("1,", "1,")]
+    #[case(",5", ",5")]
+    #[case("1,2,3", "1,2,3")]
+    #[case("R1,R2", "R1,R2")]
+    pub fn decimal_fields(#[case] field: &str, #[case] expected_field: &str) {
+        // expect
+        assert_eq!(normalize_decimal(field, ','), expected_field);
+    }
+
+    #[test]
+    pub fn open_reader_with_dialect_file() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let csv_file = temp_dir.child("parts.csv");
+        csv_file.write_str("Hersteller\tMpn\nMFR1\tPART1\n")?;
+        temp_dir
+            .child("parts.csv.dialect.json")
+            .write_str(r#"{"delimiter": "\t", "header_aliases": {"Hersteller": "Manufacturer"}}"#)?;
+
+        // when
+        let mut reader = open_reader(csv_file.path())?;
+
+        // then
+        assert_eq!(
+            reader
+                .headers()?
+                .iter()
+                .collect::<Vec<_>>(),
+            vec!["Manufacturer", "Mpn"]
+        );
+        let records = reader
+            .records()
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(records, vec![csv::StringRecord::from(vec!["MFR1", "PART1"])]);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn invalid_dialect_file() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let csv_file = temp_dir.child("parts.csv");
+        csv_file.write_str("Manufacturer,Mpn\n")?;
+        temp_dir
+            .child("parts.csv.dialect.json")
+            .write_str(r#"{"separator": ";"}"#)?;
+
+        // when
+        let result = open_reader(csv_file.path());
+
+        // then
+        assert!(matches!(result, Err(CsvDialectError::InvalidDialectFile { .. })));
+
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 
 use assembly::rules::AssemblyRule;
 use criteria::{ExactMatchCriterion, FieldCriterion, GenericCriteria, RegexMatchCriterion};
@@ -9,13 +10,15 @@
 use package_mapper::package_mapping::PackageMapping;
 use part_mapper::criteria::PlacementMappingCriteria;
 use part_mapper::part_mapping::PartMapping;
-use pnp::load_out::LoadOutItem;
+use pnp::load_out::{FeederType, LoadOutItem};
 use pnp::package::Package;
-use pnp::part::Part;
+use pnp::part::{MoistureSensitivityLevel, Part, PartAliasGroupName, PartMoistureSensitivity};
 use pnp::reference::Reference;
 use regex::{Error, Regex};
+use rust_decimal::Decimal;
 use thiserror::Error;
 
+pub mod dialect;
 pub mod packages;
 
 // FUTURE Investigate whether the `build` methods should be taking `self` instead of `&self` to avoid additional allocations
@@ -231,6 +234,15 @@ pub fn build_value_kind(value: &str) -> Result<ValueKind, Error> {
 pub struct PartRecord {
     manufacturer: String,
     mpn: String,
+    /// Optional, e.g. '3' or 'MSL3'
+    #[serde(default)]
+    msl: Option<String>,
+    /// Optional, overrides the default floor life of the MSL
+    #[serde(default)]
+    floor_life_hours: Option<u32>,
+    /// Optional, parts with the same alias group are equivalent, e.g. 'R_10K_0402'
+    #[serde(default)]
+    alias_group: Option<String>,
 }
 
 impl PartRecord {
@@ -240,6 +252,29 @@ pub fn build_part(&self) -> Result<Part, anyhow::Error> {
             mpn: self.mpn.clone(),
         })
     }
+
+    pub fn build_alias_group(&self) -> Option<PartAliasGroupName> {
+        self.alias_group
+            .as_ref()
+            .map(|alias_group| alias_group.trim())
+            .filter(|alias_group| !alias_group.is_empty())
+            .map(|alias_group| alias_group.to_string())
+    }
+
+    pub fn build_moisture_sensitivity(&self) -> Result<Option<PartMoistureSensitivity>, anyhow::Error> {
+        let Some(msl) = self
+            .msl
+            .as_ref()
+            .filter(|msl| !msl.trim().is_empty())
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(PartMoistureSensitivity {
+            level: MoistureSensitivityLevel::from_str(msl)?,
+            floor_life_hours: self.floor_life_hours,
+        }))
+    }
 }
 
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
@@ -251,15 +286,73 @@ pub struct LoadOutItemRecord {
 }
 
 impl LoadOutItemRecord {
+    pub fn build_load_out_item(&self) -> Result<LoadOutItem, anyhow::Error> {
+        Ok(LoadOutItem::new(
+            self.reference.clone(),
+            self.manufacturer.clone(),
+            self.mpn.clone(),
+        ))
+    }
+}
+
+impl From<&LoadOutItem> for LoadOutItemRecord {
+    fn from(item: &LoadOutItem) -> Self {
+        Self {
+            reference: item.reference.clone(),
+            manufacturer: item.manufacturer.clone(),
+            mpn: item.mpn.clone(),
+        }
+    }
+}
+
+/// Adds package, feeder type, tape and rotation offset columns to [`LoadOutItemRecord`].
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LoadOutItemRecordV2 {
+    pub reference: Option<Reference>,
+    pub manufacturer: String,
+    pub mpn: String,
+    pub package: Option<String>,
+    pub feeder_type: Option<FeederType>,
+    pub tape_width: Option<Decimal>,
+    pub tape_pitch: Option<Decimal>,
+    pub rotation_offset: Option<Decimal>,
+}
+
+impl LoadOutItemRecordV2 {
+    /// The headers that are not present in [`LoadOutItemRecord`]
+    pub const ADDITIONAL_HEADERS: [&'static str; 5] =
+        ["Package", "FeederType", "TapeWidth", "TapePitch", "RotationOffset"];
+
     pub fn build_load_out_item(&self) -> Result<LoadOutItem, anyhow::Error> {
         Ok(LoadOutItem {
             reference: self.reference.clone(),
             manufacturer: self.manufacturer.clone(),
             mpn: self.mpn.clone(),
+            package: self.package.clone(),
+            feeder_type: self.feeder_type,
+            tape_width: self.tape_width,
+            tape_pitch: self.tape_pitch,
+            rotation_offset: self.rotation_offset,
         })
     }
 }
 
+impl From<&LoadOutItem> for LoadOutItemRecordV2 {
+    fn from(item: &LoadOutItem) -> Self {
+        Self {
+            reference: item.reference.clone(),
+            manufacturer: item.manufacturer.clone(),
+            mpn: item.mpn.clone(),
+            package: item.package.clone(),
+            feeder_type: item.feeder_type,
+            tape_width: item.tape_width,
+            tape_pitch: item.tape_pitch,
+            rotation_offset: item.rotation_offset,
+        }
+    }
+}
+
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all(deserialize = "PascalCase"))]
 pub struct SubstitutionRecord(HashMap<String, String>);
@@ -344,7 +437,7 @@ pub fn build_eda_substitution(&self) -> anyhow::Result<EdaSubstitutionRule, Subs
     }
 }
 
-fn eda_fields_names(eda: &EdaTool) -> &'static [&'static str] {
+pub(crate) fn eda_fields_names(eda: &EdaTool) -> &'static [&'static str] {
     match eda {
         EdaTool::DipTrace => &["name", "value"],
         EdaTool::KiCad => &["package", "val"],
@@ -352,7 +445,7 @@ fn eda_fields_names(eda: &EdaTool) -> &'static [&'static str] {
     }
 }
 
-fn csv_eda_tool_value_to_eda_tool(eda: &String) -> Option<EdaTool> {
+pub(crate) fn csv_eda_tool_value_to_eda_tool(eda: &String) -> Option<EdaTool> {
     if eda.to_upper_camel_case().eq("DipTrace") {
         Some(EdaTool::DipTrace)
     } else if eda.to_upper_camel_case().eq("KiCad") {
@@ -11,6 +11,7 @@
 pub const FIELD_SIZE_X: &str = "SizeX";
 pub const FIELD_SIZE_Y: &str = "SizeY";
 pub const FIELD_SIZE_Z: &str = "SizeZ";
+pub const FIELD_PIN1_ORIENTATION_DEGREES: &str = "Pin1OrientationDegrees";
 pub const FIELD_GENERIC_SHORTHAND: &str = "GenericShorthand";
 pub const FIELD_EIA_IMPERIAL_CODE: &str = "EiaImperialCode";
 pub const FIELD_EIA_METRIC_CODE: &str = "EiaMetricCode";
@@ -28,6 +29,7 @@ pub fn get_base_package_headers() -> Vec<String> {
         FIELD_SIZE_X.into(),
         FIELD_SIZE_Y.into(),
         FIELD_SIZE_Z.into(),
+        FIELD_PIN1_ORIENTATION_DEGREES.into(),
         FIELD_GENERIC_SHORTHAND.into(),
         FIELD_EIA_IMPERIAL_CODE.into(),
         FIELD_EIA_METRIC_CODE.into(),
@@ -62,6 +64,13 @@ pub fn build_package_from_field_map(fields: &HashMap<String, String>) -> Result<
         package = package.with_pitch(lead_pitch);
     }
 
+    if let Some(pin1_orientation) = fields
+        .get(FIELD_PIN1_ORIENTATION_DEGREES)
+        .and_then(|v| v.parse::<Decimal>().ok())
+    {
+        package = package.with_pin1_orientation(pin1_orientation);
+    }
+
     // Handle other basic fields
     if let Some(shorthand) = fields
         .get(FIELD_GENERIC_SHORTHAND)
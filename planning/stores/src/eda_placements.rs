@@ -1,43 +1,119 @@
+use std::path::Path;
+
 use anyhow::{anyhow, Context, Error};
 use eda::diptrace::csv::DiptracePlacementRecord;
 use eda::easyeda::csv::EasyEdaPlacementRecord;
 use eda::kicad::csv::KiCadPlacementRecord;
 use eda::placement::EdaPlacement;
+use eda::units::{detect_unit_system_from_headers, UnitSystem};
 use eda::EdaTool;
+use eda::{easyeda, kicad};
 use tracing::Level;
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
 use util::source::Source;
 
+use crate::csv::dialect::{self, CsvDialect};
+
 pub type EdaPlacementsSource = Source;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdaPlacementsSourceType {
+    /// A placements CSV file, exported from the EDA tool.
+    Placements,
+    /// The EDA tool's board file, e.g. a `.kicad_pcb` file, or an EasyEDA `.json` file.
+    Board,
+}
+
+impl EdaPlacementsSourceType {
+    /// Board files are detected using the file extension, only KiCad and EasyEDA board files are supported.
+    pub fn detect(eda_tool: EdaTool, path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        match (eda_tool, extension.as_deref()) {
+            (EdaTool::KiCad, Some("kicad_pcb")) => Self::Board,
+            (EdaTool::EasyEda, Some("json")) => Self::Board,
+            _ => Self::Placements,
+        }
+    }
+}
+
+/// Placements, as loaded from a placements source.
+#[derive(Debug)]
+pub struct EdaPlacements {
+    /// The unit system used by the source, placement positions are always converted to millimeters.
+    pub unit_system: UnitSystem,
+    pub placements: Vec<EdaPlacement>,
+}
+
+/// Load placements, the source type and unit system are detected from the source, see [`load_eda_placements_from`].
 #[tracing::instrument(level = Level::DEBUG)]
 pub fn load_eda_placements(eda_tool: EdaTool, source: &EdaPlacementsSource) -> Result<Vec<EdaPlacement>, Error> {
-    info!("Loading eda placements. source: {}", source);
+    load_eda_placements_from(eda_tool, None, None, source).map(|eda_placements| eda_placements.placements)
+}
 
+/// Load placements, converting the positions to millimeters.
+///
+/// When `source_type` is `None` it is detected from the source, see [`EdaPlacementsSourceType::detect`].
+///
+/// When `unit_system` is `None` it is detected from the source, as follows:
+/// * DipTrace - from the column headers, e.g. 'Center X (mils)'.
+/// * EasyEDA - from the values, e.g. '12.5mil'.
+/// * KiCad - KiCad does not include the unit in CSV exports, so millimeters is assumed.
+///
+/// Board files always use millimeters.
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_eda_placements_from(
+    eda_tool: EdaTool,
+    source_type: Option<EdaPlacementsSourceType>,
+    unit_system: Option<UnitSystem>,
+    source: &EdaPlacementsSource,
+) -> Result<EdaPlacements, Error> {
     let path = source
         .path()
         .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
 
-    let mut csv_reader_builder = csv::ReaderBuilder::new();
+    let source_type = source_type.unwrap_or_else(|| EdaPlacementsSourceType::detect(eda_tool, &path));
 
-    // TODO consider moving the creation of the CSV reader builder into the EdaTool specific modules.
-    let csv_reader_builder = match eda_tool {
-        EdaTool::EasyEda => {
-            csv_reader_builder
-                //.flexible(true)
-                .delimiter(b'\t')
+    info!(
+        "Loading eda placements. source: {}, source_type: {:?}, unit_system: {:?}",
+        source, source_type, unit_system
+    );
+
+    if source_type == EdaPlacementsSourceType::Board {
+        if unit_system.is_some_and(|unit_system| unit_system != UnitSystem::Millimeters) {
+            warn!("Ignoring unit system for board file, board files always use millimeters.");
         }
-        _ => &mut csv_reader_builder,
+
+        return Ok(EdaPlacements {
+            unit_system: UnitSystem::Millimeters,
+            placements: load_board_placements(eda_tool, &path)?,
+        });
+    }
+
+    // TODO consider moving the default dialect into the EdaTool specific modules.
+    let default_dialect = match eda_tool {
+        EdaTool::EasyEda => CsvDialect::with_delimiter('\t'),
+        _ => CsvDialect::default(),
     };
 
-    let mut csv_reader = csv_reader_builder
-        .from_path(path.clone())
+    let mut csv_reader = dialect::open_reader_with_default_dialect(&path, &default_dialect)
         .with_context(|| format!("Error reading placements. file: {}", path.display()))?;
 
+    let headers = csv_reader
+        .headers()
+        .with_context(|| format!("Error reading placement headers. file: {}", path.display()))?;
+    let header_unit_system = detect_unit_system_from_headers(headers.iter());
+
     let mut placements: Vec<EdaPlacement> = vec![];
+    let mut detected_unit_system = unit_system.or(header_unit_system);
 
     match eda_tool {
         EdaTool::DipTrace => {
+            let unit_system = detected_unit_system.unwrap_or(UnitSystem::Millimeters);
+
             for result in csv_reader.deserialize() {
                 let record: DiptracePlacementRecord =
                     result.with_context(|| "Deserializing placement record".to_string())?;
@@ -45,13 +121,15 @@ pub fn load_eda_placements(eda_tool: EdaTool, source: &EdaPlacementsSource) -> R
                 trace!("{:?}", record);
 
                 let placement = record
-                    .build_eda_placement()
+                    .build_eda_placement(unit_system)
                     .with_context(|| format!("Building placement from record. record: {:?}", record))?;
 
                 placements.push(placement);
             }
         }
         EdaTool::KiCad => {
+            let unit_system = detected_unit_system.unwrap_or(UnitSystem::Millimeters);
+
             for result in csv_reader.deserialize() {
                 let record: KiCadPlacementRecord =
                     result.with_context(|| "Deserializing placement record".to_string())?;
@@ -59,7 +137,7 @@ pub fn load_eda_placements(eda_tool: EdaTool, source: &EdaPlacementsSource) -> R
                 trace!("{:?}", record);
 
                 let placement = record
-                    .build_eda_placement()
+                    .build_eda_placement(unit_system)
                     .with_context(|| format!("Building placement from record. record: {:?}", record))?;
 
                 placements.push(placement);
@@ -72,13 +150,77 @@ pub fn load_eda_placements(eda_tool: EdaTool, source: &EdaPlacementsSource) -> R
 
                 trace!("{:?}", record);
 
+                // each value has a unit, an explicit unit system takes precedence
+                let record_unit_system = unit_system
+                    .or(record.unit_system())
+                    .unwrap_or(UnitSystem::Millimeters);
+                detected_unit_system.get_or_insert(record_unit_system);
+
                 let placement = record
-                    .build_eda_placement()
+                    .build_eda_placement(record_unit_system)
                     .with_context(|| format!("Building placement from record. record: {:?}", record))?;
 
                 placements.push(placement);
             }
         }
     }
+
+    let unit_system = detected_unit_system.unwrap_or(UnitSystem::Millimeters);
+    info!(
+        "Loaded eda placements. count: {}, unit_system: {:?}",
+        placements.len(),
+        unit_system
+    );
+
+    Ok(EdaPlacements {
+        unit_system,
+        placements,
+    })
+}
+
+fn load_board_placements(eda_tool: EdaTool, path: &Path) -> Result<Vec<EdaPlacement>, Error> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Error reading board file. file: {}", path.display()))?;
+
+    let placements = match eda_tool {
+        EdaTool::KiCad => kicad::board::load_board_placements(&content)
+            .with_context(|| format!("Loading placements from board. file: {}", path.display()))?,
+        EdaTool::EasyEda => easyeda::board::load_board_placements(&content)
+            .with_context(|| format!("Loading placements from board. file: {}", path.display()))?,
+        EdaTool::DipTrace => return Err(anyhow!("Loading placements from DipTrace board files is not supported")),
+    };
+
     Ok(placements)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use eda::EdaTool;
+
+    use crate::eda_placements::EdaPlacementsSourceType;
+
+    #[test]
+    pub fn detect_source_type() {
+        // expect
+        for (eda_tool, file, expected_source_type) in [
+            (EdaTool::KiCad, "board.kicad_pcb", EdaPlacementsSourceType::Board),
+            (EdaTool::KiCad, "board-top-pos.csv", EdaPlacementsSourceType::Placements),
+            (EdaTool::EasyEda, "board.json", EdaPlacementsSourceType::Board),
+            (
+                EdaTool::EasyEda,
+                "pick-and-place.csv",
+                EdaPlacementsSourceType::Placements,
+            ),
+            (EdaTool::DipTrace, "board.json", EdaPlacementsSourceType::Placements),
+        ] {
+            assert_eq!(
+                EdaPlacementsSourceType::detect(eda_tool, &PathBuf::from(file)),
+                expected_source_type,
+                "file: {}",
+                file
+            );
+        }
+    }
+}
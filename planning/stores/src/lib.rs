@@ -1,6 +1,6 @@
 //! Stores are for loading/storing different kinds of data.
 //!
-//! Currently, all stores are just simple files, mostly CSV.
+//! By default, stores are just simple files, mostly CSV, see [`backend`] for other backends.
 //!
 //! Example store backends:
 //! * Files (e.g. CSV).
@@ -8,6 +8,7 @@
 //! * Databases.
 //! * Etc.
 pub mod assembly_rules;
+pub mod backend;
 pub mod csv;
 pub mod eda_placements;
 pub mod load_out;
@@ -16,6 +17,11 @@
 pub mod part_mappings;
 pub mod parts;
 pub mod placements;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 pub mod substitutions;
+pub mod unit_positionings;
 
 pub mod test;
@@ -1,12 +1,15 @@
 use std::collections::BTreeSet;
-use std::fs::File;
+use std::fs::{File, OpenOptions};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 use anyhow::{anyhow, Context, Error};
 use csv::QuoteStyle;
 use planning::process::{ProcessDefinition, ProcessReference, ProcessRuleReference};
 use pnp::load_out::LoadOutItem;
-use pnp::part::Part;
+use pnp::part::{Part, PartAliases};
 use pnp::reference::Reference;
 use regex::Regex;
 use thiserror::Error;
@@ -14,58 +17,292 @@
 use tracing::{info, Level};
 use util::source::Source;
 
-use crate::csv::LoadOutItemRecord;
+use crate::backend::LoadOutStore;
+use crate::csv::{LoadOutItemRecord, LoadOutItemRecordV2};
 
 pub type LoadOutSource = Source;
 
+/// Load-out CSV file versions, the version of a file is detected from its headers.
+///
+/// * V1 - `Reference`, `Manufacturer`, `Mpn`.
+/// * V2 - as V1, plus `Package`, `FeederType`, `TapeWidth`, `TapePitch`, `RotationOffset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LoadOutCsvVersion {
+    V1,
+    V2,
+}
+
+impl LoadOutCsvVersion {
+    pub const LATEST: LoadOutCsvVersion = LoadOutCsvVersion::V2;
+
+    fn detect(headers: &csv::StringRecord) -> Self {
+        let is_v2 = headers
+            .iter()
+            .any(|header| LoadOutItemRecordV2::ADDITIONAL_HEADERS.contains(&header));
+
+        match is_v2 {
+            true => Self::V2,
+            false => Self::V1,
+        }
+    }
+
+    /// The minimum version required to store the items without losing any information.
+    pub fn required_for(items: &[LoadOutItem]) -> Self {
+        let requires_v2 = items.iter().any(|item| {
+            item.package.is_some()
+                || item.feeder_type.is_some()
+                || item.tape_width.is_some()
+                || item.tape_pitch.is_some()
+                || item.rotation_offset.is_some()
+        });
+
+        match requires_v2 {
+            true => Self::V2,
+            false => Self::V1,
+        }
+    }
+}
+
 #[tracing::instrument(level = Level::DEBUG)]
 pub fn load_items(source: &LoadOutSource) -> Result<Vec<LoadOutItem>, Error> {
+    load_versioned_items(source).map(|(items, _version)| items)
+}
+
+/// Load items, also returning the version of the file.
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_versioned_items(source: &LoadOutSource) -> Result<(Vec<LoadOutItem>, LoadOutCsvVersion), Error> {
+    load_fingerprinted_items(source).map(|(items, version, _fingerprint)| (items, version))
+}
+
+/// Load items, also returning the version and the fingerprint of the file.
+///
+/// The fingerprint can be used to detect modifications by other processes when storing, see [`update_items`].
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_fingerprinted_items(
+    source: &LoadOutSource,
+) -> Result<(Vec<LoadOutItem>, LoadOutCsvVersion, LoadOutFingerprint), Error> {
     info!("Loading load-out. source: '{}'", source);
 
     let path = source
         .path()
         .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
 
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .from_path(path.clone())
+    let mut file = File::open(&path).with_context(|| format!("Error reading load-out. file: {}", path.display()))?;
+    file.lock_shared()
+        .with_context(|| format!("Error locking load-out. file: {}", path.display()))?;
+
+    let mut content = vec![];
+    file.read_to_end(&mut content)
         .with_context(|| format!("Error reading load-out. file: {}", path.display()))?;
+    let fingerprint = LoadOutFingerprint::new(&file, &content);
+
+    // closing the file releases the lock
+    drop(file);
+
+    let mut csv_reader = csv::ReaderBuilder::new().from_reader(content.as_slice());
+
+    let version = LoadOutCsvVersion::detect(
+        csv_reader
+            .headers()
+            .with_context(|| format!("Error reading load-out headers. file: {}", path.display()))?,
+    );
+    trace!("Detected load-out version. version: {:?}", version);
 
     let mut items: Vec<LoadOutItem> = vec![];
 
-    for result in csv_reader.deserialize() {
-        let record: LoadOutItemRecord = result.with_context(|| "Deserializing load-out record".to_string())?;
+    match version {
+        LoadOutCsvVersion::V1 => {
+            for result in csv_reader.deserialize() {
+                let record: LoadOutItemRecord = result.with_context(|| "Deserializing load-out record".to_string())?;
+
+                trace!("{:?}", record);
 
-        trace!("{:?}", record);
+                let load_out_item = record
+                    .build_load_out_item()
+                    .with_context(|| format!("Building load-out from record. record: {:?}", record))?;
 
-        let load_out_item = record
-            .build_load_out_item()
-            .with_context(|| format!("Building load-out from record. record: {:?}", record))?;
+                items.push(load_out_item);
+            }
+        }
+        LoadOutCsvVersion::V2 => {
+            for result in csv_reader.deserialize() {
+                let record: LoadOutItemRecordV2 =
+                    result.with_context(|| "Deserializing load-out record".to_string())?;
+
+                trace!("{:?}", record);
 
-        items.push(load_out_item);
+                let load_out_item = record
+                    .build_load_out_item()
+                    .with_context(|| format!("Building load-out from record. record: {:?}", record))?;
+
+                items.push(load_out_item);
+            }
+        }
     }
-    Ok(items)
+    Ok((items, version, fingerprint))
 }
 
+/// Store items, using the oldest version that can store the items without losing any information.
 pub fn store_items(load_out_source: &LoadOutSource, items: &[LoadOutItem]) -> Result<(), Error> {
-    info!("Storing load-out. source: '{}'", load_out_source);
+    store_versioned_items(load_out_source, items, LoadOutCsvVersion::required_for(items))
+}
+
+/// Store items, never downgrading the version of an existing file.
+///
+/// If an `expected` fingerprint is given and the file has been modified since, e.g. by another instance of the planner,
+/// a [`ConcurrentModificationError`] is returned and the file is not modified.
+///
+/// Returns the fingerprint of the stored file.
+pub fn update_items(
+    load_out_source: &LoadOutSource,
+    items: &[LoadOutItem],
+    expected: Option<&LoadOutFingerprint>,
+) -> Result<LoadOutFingerprint, Error> {
+    let required_version = LoadOutCsvVersion::required_for(items);
+
+    write_items(load_out_source, items, expected, |existing_version| {
+        existing_version.max(required_version)
+    })
+}
+
+pub fn store_versioned_items(
+    load_out_source: &LoadOutSource,
+    items: &[LoadOutItem],
+    version: LoadOutCsvVersion,
+) -> Result<(), Error> {
+    write_items(load_out_source, items, None, |_existing_version| version).map(|_fingerprint| ())
+}
+
+/// Writes the items whilst holding an exclusive lock on the file.
+///
+/// The version is selected using the version of the existing file, an empty or missing file is treated as V1.
+fn write_items<F>(
+    load_out_source: &LoadOutSource,
+    items: &[LoadOutItem],
+    expected: Option<&LoadOutFingerprint>,
+    select_version: F,
+) -> Result<LoadOutFingerprint, Error>
+where
+    F: FnOnce(LoadOutCsvVersion) -> LoadOutCsvVersion,
+{
+    let path = load_out_source
+        .path()
+        .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
+
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .with_context(|| format!("Error opening load-out. file: {}", path.display()))?;
+    file.lock()
+        .with_context(|| format!("Error locking load-out. file: {}", path.display()))?;
+
+    let mut existing_content = vec![];
+    file.read_to_end(&mut existing_content)
+        .with_context(|| format!("Error reading load-out. file: {}", path.display()))?;
+
+    if let Some(expected) = expected {
+        if LoadOutFingerprint::new(&file, &existing_content) != *expected {
+            return Err(ConcurrentModificationError(path).into());
+        }
+    }
 
-    let output_path = PathBuf::from(load_out_source.to_string());
+    let existing_version = LoadOutCsvVersion::detect(
+        csv::ReaderBuilder::new()
+            .from_reader(existing_content.as_slice())
+            .headers()
+            .with_context(|| format!("Error reading load-out headers. file: {}", path.display()))?,
+    );
+    let version = select_version(existing_version);
+
+    info!(
+        "Storing load-out. source: '{}', version: {:?}",
+        load_out_source, version
+    );
+
+    if version < LoadOutCsvVersion::required_for(items) {
+        return Err(anyhow!(
+            "Load-out items cannot be stored without losing information. version: {:?}",
+            version
+        ));
+    }
 
     let mut writer = csv::WriterBuilder::new()
         .quote_style(QuoteStyle::Always)
-        .from_path(output_path)?;
+        .from_writer(vec![]);
 
     for item in items {
-        writer.serialize(LoadOutItemRecord {
-            reference: item.reference.clone(),
-            manufacturer: item.manufacturer.to_string(),
-            mpn: item.mpn.to_string(),
-        })?;
+        match version {
+            LoadOutCsvVersion::V1 => writer.serialize(LoadOutItemRecord::from(item))?,
+            LoadOutCsvVersion::V2 => writer.serialize(LoadOutItemRecordV2::from(item))?,
+        }
     }
 
-    writer.flush()?;
+    let content = writer
+        .into_inner()
+        .map_err(|error| anyhow!("Error serializing load-out. cause: {}", error))?;
 
-    Ok(())
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(&content)?;
+    file.flush()?;
+
+    Ok(LoadOutFingerprint::new(&file, &content))
+}
+
+/// Identifies the content of a load-out file at the time it was read or written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadOutFingerprint {
+    modified: Option<SystemTime>,
+    hash: u64,
+}
+
+impl LoadOutFingerprint {
+    fn new(file: &File, content: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+
+        Self {
+            modified: file
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok(),
+            hash: hasher.finish(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Load-out modified by another process since it was loaded. file: {}", .0.display())]
+pub struct ConcurrentModificationError(pub PathBuf);
+
+/// Upgrade a load-out file, in place, to the latest version.
+///
+/// Returns `true` if the file was upgraded, `false` if it was already using the latest version.
+pub fn migrate_load_out(load_out_source: &LoadOutSource) -> Result<bool, Error> {
+    let (items, version, fingerprint) = load_fingerprinted_items(load_out_source)?;
+
+    if version == LoadOutCsvVersion::LATEST {
+        return Ok(false);
+    }
+
+    write_items(load_out_source, &items, Some(&fingerprint), |_existing_version| {
+        LoadOutCsvVersion::LATEST
+    })?;
+    info!(
+        "Migrated load-out. source: '{}', from: {:?}, to: {:?}",
+        load_out_source,
+        version,
+        LoadOutCsvVersion::LATEST
+    );
+
+    Ok(true)
+}
+
+pub fn load_out_exists(load_out_source: &LoadOutSource) -> bool {
+    PathBuf::from(load_out_source.to_string()).exists()
 }
 
 pub fn ensure_load_out(load_out_source: &LoadOutSource) -> anyhow::Result<()> {
@@ -98,50 +335,77 @@ pub enum LoadOutOperationError {
         load_out_source: LoadOutSource,
         reason: anyhow::Error,
     },
+
+    #[error("Load-out modified by someone else, reload and try again. source: {load_out_source}")]
+    ConcurrentModification { load_out_source: LoadOutSource },
 }
 
-pub fn perform_load_out_operation<F, R, E>(source: &LoadOutSource, mut f: F) -> Result<R, LoadOutOperationError>
+pub fn perform_load_out_operation<F, R, E>(
+    store: &dyn LoadOutStore,
+    source: &LoadOutSource,
+    mut f: F,
+) -> Result<R, LoadOutOperationError>
 where
     F: FnMut(&mut Vec<LoadOutItem>) -> Result<R, E>,
     E: std::error::Error + Send + Sync + 'static,
 {
-    let mut load_out_items = load_items(source).map_err(|err| LoadOutOperationError::UnableToLoadItems {
-        load_out_source: source.clone(),
-        reason: err,
-    })?;
+    let mut load_out_items = store
+        .load_items(source)
+        .map_err(|err| LoadOutOperationError::UnableToLoadItems {
+            load_out_source: source.clone(),
+            reason: err,
+        })?;
 
     let result = f(&mut load_out_items).map_err(|err| LoadOutOperationError::OperationError {
         load_out_source: source.clone(),
         reason: err.into(),
     })?;
 
-    store_items(source, &load_out_items).map_err(|err| LoadOutOperationError::UnableToStoreItems {
-        load_out_source: source.clone(),
-        reason: err,
-    })?;
+    store
+        .store_items(source, &load_out_items)
+        .map_err(|err| match is_concurrent_modification(&err) {
+            true => LoadOutOperationError::ConcurrentModification {
+                load_out_source: source.clone(),
+            },
+            false => LoadOutOperationError::UnableToStoreItems {
+                load_out_source: source.clone(),
+                reason: err,
+            },
+        })?;
 
     Ok(result)
 }
 
+fn is_concurrent_modification(error: &Error) -> bool {
+    #[cfg(feature = "remote")]
+    if matches!(
+        error.downcast_ref::<crate::remote::RemoteStoreError>(),
+        Some(crate::remote::RemoteStoreError::Conflict(_))
+    ) {
+        return true;
+    }
+
+    error.is::<ConcurrentModificationError>()
+}
+
+/// Parts that are already in the load-out, or that have an alias in the load-out, are not added.
 pub fn add_parts_to_load_out(
+    store: &dyn LoadOutStore,
     load_out_source: &LoadOutSource,
     parts: BTreeSet<Part>,
+    part_aliases: &PartAliases,
 ) -> Result<(), LoadOutOperationError> {
-    perform_load_out_operation(load_out_source, |load_out_items| {
+    perform_load_out_operation(store, load_out_source, |load_out_items| {
         for part in parts.iter() {
             trace!("Checking for part in load_out. part: {:?}", part);
 
-            let matched = pnp::load_out::find_load_out_item_by_part(load_out_items, part);
+            let matched = pnp::load_out::find_load_out_item_by_part_or_alias(load_out_items, part, part_aliases);
 
             if matched.is_some() {
                 continue;
             }
 
-            let load_out_item = LoadOutItem {
-                reference: None,
-                manufacturer: part.manufacturer.clone(),
-                mpn: part.mpn.clone(),
-            };
+            let load_out_item = LoadOutItem::new(None, part.manufacturer.clone(), part.mpn.clone());
 
             info!("Adding part to load_out. part: {:?}", part);
             load_out_items.push(load_out_item)
@@ -165,18 +429,25 @@ pub enum FeederAssignmentError {
 }
 
 pub fn assign_feeder_to_load_out_item(
+    store: &dyn LoadOutStore,
     load_out_source: &LoadOutSource,
     process: &ProcessDefinition,
     feeder_reference: Option<Reference>,
     manufacturer: Regex,
     mpn: Regex,
+    part_aliases: &PartAliases,
 ) -> anyhow::Result<Vec<Part>> {
     let mut parts: Vec<Part> = vec![];
 
-    perform_load_out_operation(load_out_source, |load_out_items| {
+    perform_load_out_operation(store, load_out_source, |load_out_items| {
         let mut items: Vec<_> = load_out_items
             .iter_mut()
-            .filter(|item| manufacturer.is_match(&item.manufacturer) && mpn.is_match(&item.mpn))
+            .filter(|item| {
+                let part = Part::new(item.manufacturer.clone(), item.mpn.clone());
+                part_aliases
+                    .aliases(&part)
+                    .any(|alias| manufacturer.is_match(&alias.manufacturer) && mpn.is_match(&alias.mpn))
+            })
             .collect();
 
         if items.is_empty() {
@@ -224,3 +495,114 @@ pub fn assign_feeder_to_load_out_item(
 
     Ok(parts)
 }
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use assert_fs::NamedTempFile;
+    use indoc::indoc;
+    use pnp::load_out::FeederType;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::backend::CsvStore;
+
+    #[test]
+    pub fn migrate_v1_to_v2() {
+        // given
+        let file = NamedTempFile::new("load_out.csv").unwrap();
+        file.write_str(indoc! {r#"
+            "Reference","Manufacturer","Mpn"
+            "FEEDER_1","MFR1","PART1"
+            "","MFR2","PART2"
+        "#})
+            .unwrap();
+        let source = LoadOutSource::File(file.path().to_path_buf());
+
+        let (original_items, original_version) = load_versioned_items(&source).unwrap();
+
+        // when
+        let migrated = migrate_load_out(&source).unwrap();
+
+        // then
+        assert_eq!(original_version, LoadOutCsvVersion::V1);
+        assert!(migrated);
+
+        let (items, version) = load_versioned_items(&source).unwrap();
+        assert_eq!(version, LoadOutCsvVersion::V2);
+        assert_eq!(items, original_items);
+
+        // and
+        assert!(!migrate_load_out(&source).unwrap());
+    }
+
+    #[test]
+    pub fn load_v2() {
+        // given
+        let file = NamedTempFile::new("load_out.csv").unwrap();
+        file.write_str(indoc! {r#"
+            "Reference","Manufacturer","Mpn","Package","FeederType","TapeWidth","TapePitch","RotationOffset"
+            "FEEDER_1","MFR1","PART1","0402","Tape","8","2","-90"
+            "","MFR2","PART2","","","","",""
+        "#})
+            .unwrap();
+        let source = LoadOutSource::File(file.path().to_path_buf());
+
+        let mut expected_item_1 = LoadOutItem::new(
+            Some(Reference::from_raw_str("FEEDER_1")),
+            "MFR1".to_string(),
+            "PART1".to_string(),
+        );
+        expected_item_1.package = Some("0402".to_string());
+        expected_item_1.feeder_type = Some(FeederType::Tape);
+        expected_item_1.tape_width = Some(dec!(8));
+        expected_item_1.tape_pitch = Some(dec!(2));
+        expected_item_1.rotation_offset = Some(dec!(-90));
+
+        let expected_item_2 = LoadOutItem::new(None, "MFR2".to_string(), "PART2".to_string());
+
+        // when
+        let (items, version) = load_versioned_items(&source).unwrap();
+
+        // then
+        assert_eq!(version, LoadOutCsvVersion::V2);
+        assert_eq!(items, vec![expected_item_1, expected_item_2]);
+    }
+
+    #[test]
+    pub fn concurrent_modification() {
+        // given
+        let file = NamedTempFile::new("load_out.csv").unwrap();
+        file.write_str(indoc! {r#"
+            "Reference","Manufacturer","Mpn"
+            "","MFR1","PART1"
+        "#})
+            .unwrap();
+        let source = LoadOutSource::File(file.path().to_path_buf());
+        let store = CsvStore::default();
+
+        let other_content = indoc! {r#"
+            "Reference","Manufacturer","Mpn"
+            "FEEDER_1","MFR1","PART1"
+        "#};
+
+        // when
+        let result = perform_load_out_operation(&store, &source, |items| {
+            // simulate another process storing the load-out during the operation
+            std::fs::write(file.path(), other_content)?;
+
+            items.push(LoadOutItem::new(None, "MFR2".to_string(), "PART2".to_string()));
+
+            Ok::<(), std::io::Error>(())
+        });
+
+        // then
+        assert!(matches!(
+            result,
+            Err(LoadOutOperationError::ConcurrentModification { .. })
+        ));
+
+        // and the other process's changes are not overwritten
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), other_content);
+    }
+}
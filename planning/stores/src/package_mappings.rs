@@ -4,6 +4,7 @@
 use tracing::{info, trace, Level};
 use util::source::Source;
 
+use crate::csv::dialect;
 use crate::csv::PackageMappingRecord;
 
 pub type PackageMappingsSource = Source;
@@ -19,8 +20,7 @@ pub fn load_package_mappings<'packages>(
         .path()
         .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
 
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .from_path(path.clone())
+    let mut csv_reader = dialect::open_reader(&path)
         .with_context(|| format!("Error reading package mappings. file: {}", path.display()))?;
 
     let mut package_mappings: Vec<PackageMapping> = vec![];
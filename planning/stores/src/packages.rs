@@ -7,6 +7,7 @@
 use tracing::{info, Level};
 use util::source::Source;
 
+use crate::csv::dialect;
 use crate::csv::packages::build_package_from_field_map;
 
 pub type PackagesSource = Source;
@@ -20,9 +21,8 @@ pub fn load_packages(source: &PackagesSource) -> Result<Vec<Package>, Error> {
         .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
 
     // Use the CSV reader directly without serialization
-    let mut reader = csv::ReaderBuilder::new()
-        .from_path(path.clone())
-        .with_context(|| format!("Error reading packages. file: {}", path.display()))?;
+    let mut reader =
+        dialect::open_reader(&path).with_context(|| format!("Error reading packages. file: {}", path.display()))?;
 
     let mut packages = Vec::new();
 
@@ -113,6 +113,13 @@ pub fn save_packages(packages: &[Package], output_path: &Path) -> Result<(), Err
             record.push(String::new()); // SizeZ
         }
 
+        record.push(
+            package
+                .pin1_orientation_degrees
+                .as_ref()
+                .map_or(String::new(), |v| v.to_string()),
+        );
+
         record.push(
             package
                 .generic_shorthand
@@ -185,6 +192,8 @@ pub fn save_packages(packages: &[Package], output_path: &Path) -> Result<(), Err
 
 #[cfg(test)]
 mod tests {
+    use pnp::package::PackageDimensions;
+    use rust_decimal_macros::dec;
     use tempfile::tempdir;
     use util::test::{build_temp_csv_file, dump_file};
 
@@ -213,4 +222,33 @@ fn test_load_packages() -> Result<(), anyhow::Error> {
 
         Ok(())
     }
+
+    #[test]
+    fn test_load_packages_with_body_size_pitch_and_pin1_orientation() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+
+        // and packages
+        let (test_packages_path, _test_packages_file_name) = build_temp_csv_file(&temp_dir, "packages");
+        let packages_source = PackagesSource::from_absolute_path(test_packages_path.clone())?;
+
+        let package = Package::new("SOIC-8".into())
+            .with_lead_count(8)
+            .with_pitch(dec!(1.27))
+            .with_dimensions(PackageDimensions::new(dec!(4.9), dec!(6.0), dec!(1.75)))
+            .with_pin1_orientation(dec!(135));
+        save_packages(&[package.clone(), Package::new("0402".into())], &test_packages_path)?;
+
+        dump_file("packages", test_packages_path)?;
+
+        // when
+        let packages = load_packages(&packages_source)?;
+
+        // then
+        assert_eq!(packages, vec![package, Package::new("0402".into())]);
+        assert_eq!(packages[0].pin1_orientation_degrees, Some(dec!(135)));
+        assert_eq!(packages[1].pin1_orientation_degrees, None);
+
+        Ok(())
+    }
 }
@@ -1,4 +1,9 @@
-use anyhow::{anyhow, Context, Error};
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Context, Error};
+use eda::placement::EdaPlacement;
+use eda::EdaTool;
+use heck::ToUpperCamelCase;
 use part_mapper::part_mapping::PartMapping;
 use pnp::part::Part;
 use tracing::Level;
@@ -6,6 +11,7 @@
 use util::source::Source;
 
 use crate::csv::PartMappingRecord;
+use crate::csv::{csv_eda_tool_value_to_eda_tool, dialect, eda_fields_names};
 
 pub type PartMappingsSource = Source;
 
@@ -20,8 +26,7 @@ pub fn load_part_mappings<'part>(
         .path()
         .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
 
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .from_path(path.clone())
+    let mut csv_reader = dialect::open_reader(&path)
         .with_context(|| format!("Error reading part mappings. file: {}", path.display()))?;
 
     let mut part_mappings: Vec<PartMapping> = vec![];
@@ -40,6 +45,241 @@ pub fn load_part_mappings<'part>(
     Ok(part_mappings)
 }
 
+const EDA_HEADER: &str = "Eda";
+const MANUFACTURER_HEADER: &str = "Manufacturer";
+const MPN_HEADER: &str = "Mpn";
+
+/// A part mapping rule as it is stored, for editing the rules, see [`load_part_mapping_rules`].
+///
+/// Unlike a [`PartMapping`] the part does not have to be a known part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartMappingRule {
+    pub eda: EdaTool,
+    /// The criteria, keyed by the name of the EDA placement field, e.g. `name` and `value` for DipTrace.
+    ///
+    /// Values enclosed in '/' are regular expressions, e.g. `/^10K.*/`, other values must match exactly.
+    pub criteria: BTreeMap<String, String>,
+    pub part: Part,
+    /// Fields of the record that are not used by the rule, keyed by header, e.g. notes or the fields of other EDA
+    /// tools, they are saved unchanged.
+    pub other_fields: BTreeMap<String, String>,
+}
+
+impl PartMappingRule {
+    /// Creates a rule that matches the placement exactly, using the fields of the placement that are used for part
+    /// mapping by the EDA tool.
+    pub fn from_placement(eda: EdaTool, placement: &EdaPlacement, part: Part) -> Self {
+        let criteria = placement
+            .fields
+            .iter()
+            .filter(|field| eda_fields_names(&eda).contains(&field.name.as_str()))
+            .map(|field| (field.name.clone(), escape_exact_match_value(&field.value)))
+            .collect();
+
+        Self {
+            eda,
+            criteria,
+            part,
+            other_fields: Default::default(),
+        }
+    }
+}
+
+/// Values that would be read as a regular expression are converted to a regular expression that matches the value
+/// exactly.
+fn escape_exact_match_value(value: &str) -> String {
+    match value.len() > 1 && value.starts_with('/') && value.ends_with('/') {
+        true => format!("/^{}$/", regex::escape(value)),
+        false => value.to_string(),
+    }
+}
+
+/// The part mapping rules of a source, in the order they are stored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartMappingRules {
+    /// The headers, in the order they are stored, additional headers required by the rules are added when saving.
+    pub headers: Vec<String>,
+    pub rules: Vec<PartMappingRule>,
+}
+
+impl PartMappingRules {
+    pub fn add(&mut self, rule: PartMappingRule) {
+        self.rules.push(rule);
+    }
+
+    /// Returns the replaced rule, or `None` if there is no rule at the index.
+    pub fn replace(&mut self, index: usize, rule: PartMappingRule) -> Option<PartMappingRule> {
+        self.rules
+            .get_mut(index)
+            .map(|existing| std::mem::replace(existing, rule))
+    }
+
+    /// Returns the removed rule, or `None` if there is no rule at the index.
+    pub fn remove(&mut self, index: usize) -> Option<PartMappingRule> {
+        match index < self.rules.len() {
+            true => Some(self.rules.remove(index)),
+            false => None,
+        }
+    }
+
+    /// The stored headers followed by any additional headers required by the rules, new criteria headers are added
+    /// before the part headers.
+    fn build_headers(&self) -> Vec<String> {
+        let mut headers = self.headers.clone();
+        let add_header = |headers: &mut Vec<String>, header: String, before: Option<&str>| {
+            if headers.contains(&header) {
+                return;
+            }
+            let index = before
+                .and_then(|before| {
+                    headers
+                        .iter()
+                        .position(|candidate| candidate.eq(before))
+                })
+                .unwrap_or(headers.len());
+            headers.insert(index, header);
+        };
+
+        add_header(&mut headers, EDA_HEADER.to_string(), None);
+        for rule in self.rules.iter() {
+            for name in rule.criteria.keys() {
+                add_header(&mut headers, name.to_upper_camel_case(), Some(MANUFACTURER_HEADER));
+            }
+        }
+        add_header(&mut headers, MANUFACTURER_HEADER.to_string(), None);
+        add_header(&mut headers, MPN_HEADER.to_string(), None);
+        for rule in self.rules.iter() {
+            for header in rule.other_fields.keys() {
+                add_header(&mut headers, header.clone(), None);
+            }
+        }
+
+        headers
+    }
+}
+
+/// Loads the rules for editing, a missing file has no rules.
+///
+/// See also [`load_part_mappings`] which builds the mappings used to map placements to parts.
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_part_mapping_rules(source: &PartMappingsSource) -> Result<PartMappingRules, Error> {
+    info!("Loading part mapping rules. source: {}", source);
+
+    let path = source
+        .path()
+        .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
+
+    if !path.exists() {
+        return Ok(PartMappingRules::default());
+    }
+
+    let mut csv_reader = dialect::open_reader(&path)
+        .with_context(|| format!("Error reading part mappings. file: {}", path.display()))?;
+
+    let headers: Vec<String> = csv_reader
+        .headers()?
+        .iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut rules = vec![];
+    for result in csv_reader.records() {
+        let record = result.with_context(|| "Reading part mapping record".to_string())?;
+        let mut fields: BTreeMap<String, String> = headers
+            .iter()
+            .cloned()
+            .zip(record.iter().map(str::to_string))
+            .collect();
+
+        let mut take_field = |header: &str| {
+            fields
+                .remove(header)
+                .ok_or_else(|| anyhow!("Missing field. field: {}, record: {:?}", header, record))
+        };
+
+        let eda = take_field(EDA_HEADER)?;
+        let eda = csv_eda_tool_value_to_eda_tool(&eda).ok_or_else(|| anyhow!("Unknown EDA. value: {:?}", eda))?;
+        let part = Part::new(take_field(MANUFACTURER_HEADER)?, take_field(MPN_HEADER)?);
+
+        let (criteria, other_fields) = fields
+            .into_iter()
+            .partition::<BTreeMap<String, String>, _>(|(header, _value)| {
+                eda_fields_names(&eda).contains(&header.to_lowercase().as_str())
+            });
+        let criteria = criteria
+            .into_iter()
+            .map(|(header, value)| (header.to_lowercase(), value))
+            .collect();
+
+        rules.push(PartMappingRule {
+            eda,
+            criteria,
+            part,
+            other_fields,
+        });
+    }
+
+    Ok(PartMappingRules {
+        headers,
+        rules,
+    })
+}
+
+/// Saves the rules, preserving the order of the rules and the stored headers.
+///
+/// Files with a CSV dialect are not supported, since the planner always writes standard CSV, see [`dialect`].
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn save_part_mapping_rules(
+    source: &PartMappingsSource,
+    part_mapping_rules: &PartMappingRules,
+) -> Result<(), Error> {
+    info!("Saving part mapping rules. source: {}", source);
+
+    let path = source
+        .path()
+        .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
+
+    let dialect_path = dialect::build_dialect_file_path(&path);
+    if dialect_path.exists() {
+        bail!(
+            "Unable to save part mappings with a CSV dialect. file: {}, dialect: {}",
+            path.display(),
+            dialect_path.display()
+        );
+    }
+
+    let headers = part_mapping_rules.build_headers();
+
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(csv::QuoteStyle::Always)
+        .from_path(&path)
+        .with_context(|| format!("Error writing part mappings. file: {}", path.display()))?;
+
+    writer.write_record(&headers)?;
+
+    for rule in part_mapping_rules.rules.iter() {
+        let record = headers
+            .iter()
+            .map(|header| match header.as_str() {
+                EDA_HEADER => format!("{:?}", rule.eda),
+                MANUFACTURER_HEADER => rule.part.manufacturer.clone(),
+                MPN_HEADER => rule.part.mpn.clone(),
+                _ => rule
+                    .criteria
+                    .get(&header.to_lowercase())
+                    .filter(|_| eda_fields_names(&rule.eda).contains(&header.to_lowercase().as_str()))
+                    .or_else(|| rule.other_fields.get(header))
+                    .cloned()
+                    .unwrap_or_default(),
+            });
+        writer.write_record(record)?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 pub mod csv_loading_tests {
     use assert_fs::TempDir;
@@ -181,6 +421,94 @@ pub fn use_exact_match_and_regex_match_criterion() -> anyhow::Result<()> {
     }
 }
 
+#[cfg(test)]
+mod part_mapping_rules_tests {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use eda::placement::{EdaPlacement, EdaPlacementField};
+
+    use super::*;
+
+    #[test]
+    pub fn round_trip() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let csv_file = temp_dir.child("part-mappings.csv");
+        csv_file.write_str(concat!(
+            "\"Eda\",\"Name\",\"Value\",\"Manufacturer\",\"Mpn\",\"Notes\"\n",
+            "\"DipTrace\",\"RES_0402\",\"/10K.*/\",\"MFR1\",\"PART1\",\"note 1\"\n",
+            "\"DipTrace\",\"CAP_0402\",\"100nF\",\"MFR1\",\"PART2\",\"\"\n",
+        ))?;
+        let source = PartMappingsSource::from_absolute_path(csv_file.path().to_path_buf())?;
+
+        // and
+        let placement = EdaPlacement {
+            ref_des: "R2".to_string(),
+            fields: vec![
+                EdaPlacementField::new("name".to_string(), "RES_0603".to_string()),
+                EdaPlacementField::new("value".to_string(), "/1K/".to_string()),
+                EdaPlacementField::new("x".to_string(), "10".to_string()),
+            ],
+            ..EdaPlacement::default()
+        };
+
+        // when
+        let mut part_mapping_rules = load_part_mapping_rules(&source)?;
+        part_mapping_rules.add(PartMappingRule::from_placement(
+            EdaTool::DipTrace,
+            &placement,
+            Part::new("MFR2".to_string(), "PART3".to_string()),
+        ));
+        save_part_mapping_rules(&source, &part_mapping_rules)?;
+
+        // then
+        let content = std::fs::read_to_string(csv_file.path())?;
+        assert_eq!(
+            content,
+            concat!(
+                "\"Eda\",\"Name\",\"Value\",\"Manufacturer\",\"Mpn\",\"Notes\"\n",
+                "\"DipTrace\",\"RES_0402\",\"/10K.*/\",\"MFR1\",\"PART1\",\"note 1\"\n",
+                "\"DipTrace\",\"CAP_0402\",\"100nF\",\"MFR1\",\"PART2\",\"\"\n",
+                "\"DipTrace\",\"RES_0603\",\"/^/1K/$/\",\"MFR2\",\"PART3\",\"\"\n",
+            )
+        );
+
+        // and
+        let reloaded_part_mapping_rules = load_part_mapping_rules(&source)?;
+        assert_eq!(
+            reloaded_part_mapping_rules.rules[2].criteria,
+            part_mapping_rules.rules[2].criteria
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn criteria_headers_are_added_before_the_part_headers() {
+        // given
+        let mut part_mapping_rules = PartMappingRules::default();
+        part_mapping_rules.add(PartMappingRule {
+            eda: EdaTool::KiCad,
+            criteria: BTreeMap::from([
+                ("package".to_string(), "R_0402".to_string()),
+                ("val".to_string(), "10K".to_string()),
+            ]),
+            part: Part::new("MFR1".to_string(), "PART1".to_string()),
+            other_fields: BTreeMap::from([("Notes".to_string(), "note 1".to_string())]),
+        });
+
+        // expect
+        assert_eq!(part_mapping_rules.build_headers(), vec![
+            "Eda",
+            "Package",
+            "Val",
+            "Manufacturer",
+            "Mpn",
+            "Notes"
+        ]);
+    }
+}
+
 // FUTURE Ideally we want to include this module ONLY for integration tests or for unit tests
 //        but when compiling for integration tests, `test` is NOT defined so we cannot use
 //        just `#[cfg(test)]`
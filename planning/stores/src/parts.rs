@@ -1,9 +1,10 @@
 use anyhow::{anyhow, Context, Error};
-use pnp::part::Part;
+use pnp::part::{Part, PartAliases, PartMoistureSensitivity};
 use tracing::Level;
 use tracing::{info, trace};
 use util::source::Source;
 
+use crate::csv::dialect;
 use crate::csv::PartRecord;
 
 pub type PartsSource = Source;
@@ -16,9 +17,8 @@ pub fn load_parts(source: &PartsSource) -> Result<Vec<Part>, Error> {
         .path()
         .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
 
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .from_path(path.clone())
-        .with_context(|| format!("Error reading parts. file: {}", path.display()))?;
+    let mut csv_reader =
+        dialect::open_reader(&path).with_context(|| format!("Error reading parts. file: {}", path.display()))?;
 
     let mut parts: Vec<Part> = vec![];
 
@@ -35,3 +35,144 @@ pub fn load_parts(source: &PartsSource) -> Result<Vec<Part>, Error> {
     }
     Ok(parts)
 }
+
+/// Loads the moisture sensitivity of the parts that have an 'Msl' column value, other parts are omitted.
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_part_moisture_sensitivities(source: &PartsSource) -> Result<Vec<(Part, PartMoistureSensitivity)>, Error> {
+    info!("Loading part moisture sensitivities. source: {}", source);
+
+    let path = source
+        .path()
+        .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
+
+    let mut csv_reader =
+        dialect::open_reader(&path).with_context(|| format!("Error reading parts. file: {}", path.display()))?;
+
+    let mut sensitivities: Vec<(Part, PartMoistureSensitivity)> = vec![];
+
+    for result in csv_reader.deserialize() {
+        let record: PartRecord = result.with_context(|| "Deserializing part record".to_string())?;
+
+        let sensitivity = record
+            .build_moisture_sensitivity()
+            .with_context(|| format!("Building moisture sensitivity from record. record: {:?}", record))?;
+
+        if let Some(sensitivity) = sensitivity {
+            let part = record.build_part()?;
+            sensitivities.push((part, sensitivity));
+        }
+    }
+    Ok(sensitivities)
+}
+
+/// Loads the alias groups of the parts that have an 'AliasGroup' column value.
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_part_aliases(source: &PartsSource) -> Result<PartAliases, Error> {
+    info!("Loading part aliases. source: {}", source);
+
+    let path = source
+        .path()
+        .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
+
+    let mut csv_reader =
+        dialect::open_reader(&path).with_context(|| format!("Error reading parts. file: {}", path.display()))?;
+
+    let mut part_aliases = PartAliases::default();
+
+    for result in csv_reader.deserialize() {
+        let record: PartRecord = result.with_context(|| "Deserializing part record".to_string())?;
+
+        if let Some(alias_group) = record.build_alias_group() {
+            let part = record.build_part()?;
+            part_aliases.add(alias_group, part);
+        }
+    }
+    Ok(part_aliases)
+}
+
+#[cfg(test)]
+mod moisture_sensitivity_loading_tests {
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use pnp::part::MoistureSensitivityLevel;
+
+    use super::*;
+
+    #[test]
+    pub fn load_moisture_sensitivities() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let mut parts_path = temp_dir.path().to_path_buf();
+        parts_path.push("parts.csv");
+        std::fs::write(&parts_path, indoc! {r#"
+            "Manufacturer","Mpn","Msl","FloorLifeHours"
+            "RES_MFR1","RES1","",""
+            "IC_MFR1","IC1","3",""
+            "IC_MFR2","IC2","MSL6","4"
+        "#})?;
+        let source = PartsSource::from_absolute_path(parts_path)?;
+
+        // when
+        let sensitivities = load_part_moisture_sensitivities(&source)?;
+
+        // then
+        assert_eq!(sensitivities, vec![
+            (
+                Part::new("IC_MFR1".to_string(), "IC1".to_string()),
+                PartMoistureSensitivity {
+                    level: MoistureSensitivityLevel::Msl3,
+                    floor_life_hours: None,
+                }
+            ),
+            (
+                Part::new("IC_MFR2".to_string(), "IC2".to_string()),
+                PartMoistureSensitivity {
+                    level: MoistureSensitivityLevel::Msl6,
+                    floor_life_hours: Some(4),
+                }
+            ),
+        ]);
+
+        // and parts without the optional columns can still be loaded
+        assert_eq!(load_parts(&source)?.len(), 3);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod part_aliases_loading_tests {
+    use assert_fs::TempDir;
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    pub fn load_aliases() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let mut parts_path = temp_dir.path().to_path_buf();
+        parts_path.push("parts.csv");
+        std::fs::write(&parts_path, indoc! {r#"
+            "Manufacturer","Mpn","AliasGroup"
+            "RES_MFR1","RES1","R_10K_0402"
+            "RES_MFR2","RES2","R_10K_0402"
+            "IC_MFR1","IC1",""
+        "#})?;
+        let source = PartsSource::from_absolute_path(parts_path)?;
+
+        // when
+        let part_aliases = load_part_aliases(&source)?;
+
+        // then
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let res2 = Part::new("RES_MFR2".to_string(), "RES2".to_string());
+        let ic1 = Part::new("IC_MFR1".to_string(), "IC1".to_string());
+
+        assert!(part_aliases.are_aliases(&res1, &res2));
+        assert!(!part_aliases.are_aliases(&res1, &ic1));
+        assert_eq!(part_aliases.find_group(&ic1), None);
+
+        Ok(())
+    }
+}
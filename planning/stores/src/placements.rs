@@ -1,4 +1,6 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context};
@@ -7,9 +9,12 @@
 use pnp::pcb::PcbSide;
 use pnp::placement::Placement;
 use rust_decimal::Decimal;
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
+use util::cancellation::CancellationToken;
 use util::source::Source;
 
+use crate::backend::PlacementsStore;
+
 /// See `EdaPlacement` for details of co-ordinate system
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -84,11 +89,15 @@ pub fn load_placements(source: &PlacementsSource) -> Result<Vec<Placement>, anyh
         .inspect(|record| {
             trace!("{:?}", record);
         })
-        .filter_map(|record: Result<PlacementRecord, csv::Error>| {
-            // TODO report errors
-            match record {
-                Ok(record) => Some(record.as_placement()),
-                _ => None,
+        .filter_map(|record: Result<PlacementRecord, csv::Error>| match record {
+            Ok(record) => Some(record.as_placement()),
+            Err(error) => {
+                // e.g. 'NaN' coordinates
+                let line = error
+                    .position()
+                    .map(|position| position.line());
+                warn!("Skipping invalid placement record. line: {:?}, cause: {}", line, error);
+                None
             }
         })
         .collect();
@@ -96,24 +105,181 @@ pub fn load_placements(source: &PlacementsSource) -> Result<Vec<Placement>, anyh
     Ok(records)
 }
 
+/// Placements of design variants, with a hash of the content of the placements file they were loaded from, so that
+/// design variants with unchanged placements files are not loaded again, see [`load_all_placements`].
+#[derive(Debug, Default)]
+pub struct PlacementsCache {
+    entries: BTreeMap<DesignVariant, CachedPlacements>,
+}
+
+#[derive(Debug)]
+struct CachedPlacements {
+    /// `None` when the content is unavailable, e.g. when the store does not use the file.
+    content_hash: Option<u64>,
+    placements: Vec<Placement>,
+}
+
+#[derive(Debug, Default)]
+pub struct AllPlacements {
+    pub placements: BTreeMap<DesignVariant, Vec<Placement>>,
+    /// The design variants whose placements were not cached, or have changed since they were cached.
+    pub changed: BTreeSet<DesignVariant>,
+}
+
+pub fn build_placements_path(directory: &Path, design_variant: &DesignVariant) -> PathBuf {
+    let DesignVariant {
+        design_name: design,
+        variant_name: variant,
+    } = design_variant;
+
+    let mut placements_path = PathBuf::from(directory);
+    placements_path.push(format!("{}_{}_placements.csv", design, variant));
+    placements_path
+}
+
+fn content_hash(path: &Path) -> Option<u64> {
+    let content = fs::read(path).ok()?;
+
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Loads the placements for each design variant, design variants whose placements file has the same content as when
+/// they were cached are not loaded from the store.
+///
+/// When the content of the placements file is unavailable the placements are always loaded, and are compared with the
+/// cached placements to determine if they have changed.
 pub fn load_all_placements(
+    store: &dyn PlacementsStore,
     unique_design_variants: HashSet<DesignVariant>,
     directory: &Path,
-) -> anyhow::Result<BTreeMap<DesignVariant, Vec<Placement>>> {
-    let mut all_placements: BTreeMap<DesignVariant, Vec<Placement>> = Default::default();
+    cache: &mut PlacementsCache,
+    cancellation: &CancellationToken,
+) -> anyhow::Result<AllPlacements> {
+    let mut all_placements = AllPlacements::default();
 
     for design_variant in unique_design_variants {
-        let DesignVariant {
-            design_name: design,
-            variant_name: variant,
-        } = &design_variant;
+        cancellation.check()?;
+
+        let placements_path = build_placements_path(directory, &design_variant);
+        let content_hash = content_hash(&placements_path);
 
-        let mut placements_path = PathBuf::from(directory);
-        placements_path.push(format!("{}_{}_placements.csv", design, variant));
-        let source = PlacementsSource::File(placements_path);
+        let cached = cache
+            .entries
+            .get(&design_variant)
+            .filter(|cached| content_hash.is_some() && cached.content_hash == content_hash);
 
-        let placements = load_placements(&source)?;
-        let _ = all_placements.insert(design_variant, placements);
+        let placements = match cached {
+            Some(cached) => {
+                trace!("Placements unchanged. design_variant: {}", design_variant);
+                cached.placements.clone()
+            }
+            None => {
+                let source = PlacementsSource::File(placements_path);
+                let placements = store.load_placements(&source)?;
+
+                let changed = cache
+                    .entries
+                    .get(&design_variant)
+                    .is_none_or(|cached| cached.placements != placements);
+                if changed {
+                    all_placements
+                        .changed
+                        .insert(design_variant.clone());
+                }
+
+                cache
+                    .entries
+                    .insert(design_variant.clone(), CachedPlacements {
+                        content_hash,
+                        placements: placements.clone(),
+                    });
+                placements
+            }
+        };
+
+        let _ = all_placements
+            .placements
+            .insert(design_variant, placements);
     }
     Ok(all_placements)
 }
+
+#[cfg(test)]
+mod placements_tests {
+    use std::cell::Cell;
+
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use indoc::indoc;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct CountingStore {
+        loads: Cell<usize>,
+    }
+
+    impl PlacementsStore for CountingStore {
+        fn load_placements(&self, source: &PlacementsSource) -> Result<Vec<Placement>, anyhow::Error> {
+            self.loads.set(self.loads.get() + 1);
+            load_placements(source)
+        }
+    }
+
+    #[test]
+    pub fn only_changed_placements_are_loaded() {
+        // given
+        let directory = TempDir::new().unwrap();
+        let design_variant = DesignVariant {
+            design_name: "design_a".into(),
+            variant_name: "variant_a".into(),
+        };
+        let placements_file = directory.child("design_a_variant_a_placements.csv");
+        placements_file
+            .write_str(indoc! {r#"
+                "RefDes","Manufacturer","Mpn","Place","PcbSide","X","Y","Rotation"
+                "R1","RES_MFR1","RES1","true","Top","10","110","0"
+            "#})
+            .unwrap();
+
+        let store = CountingStore::default();
+        let mut cache = PlacementsCache::default();
+        let mut load = || {
+            load_all_placements(
+                &store,
+                HashSet::from([design_variant.clone()]),
+                directory.path(),
+                &mut cache,
+                &CancellationToken::default(),
+            )
+            .unwrap()
+        };
+        let all_placements = load();
+
+        // when
+        let cached_placements = load();
+
+        // then
+        assert_eq!(all_placements.changed, BTreeSet::from([design_variant.clone()]));
+        assert!(cached_placements.changed.is_empty());
+        assert_eq!(cached_placements.placements, all_placements.placements);
+        assert_eq!(store.loads.get(), 1);
+
+        // when
+        placements_file
+            .write_str(indoc! {r#"
+                "RefDes","Manufacturer","Mpn","Place","PcbSide","X","Y","Rotation"
+                "R1","RES_MFR1","RES1","true","Top","10","110","0"
+                "R2","RES_MFR1","RES1","true","Top","20","110","0"
+            "#})
+            .unwrap();
+        let changed_placements = load();
+
+        // then
+        assert_eq!(changed_placements.changed, BTreeSet::from([design_variant.clone()]));
+        assert_eq!(changed_placements.placements[&design_variant].len(), 2);
+        assert_eq!(store.loads.get(), 2);
+    }
+}
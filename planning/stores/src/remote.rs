@@ -0,0 +1,382 @@
+//! Remote (REST) store backend, allows multiple workstations to share parts and load-outs via a central service.
+//!
+//! Sources are [`Source::Url`]s, the service is expected to implement the following for each URL:
+//!
+//! * `GET` - returns a JSON array of items, with an `ETag` header.  `If-None-Match` is honoured with a `304`.
+//! * `PUT` - replaces the items with a JSON array.  `If-Match` is honoured with a `412` when the items were updated by
+//!   someone else, `If-None-Match: *` is honoured with a `412` when the items already exist.  Returns the new `ETag`.
+//!
+//! The last fetched items are cached locally, along with their `ETag`, so that the data is available when the service
+//! is not and so that concurrent modifications can be detected when updating.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::Error;
+use pnp::load_out::LoadOutItem;
+use pnp::part::Part;
+use reqwest::header::{ETAG, IF_MATCH, IF_NONE_MATCH};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::runtime::Runtime;
+use tracing::{info, warn};
+use util::source::Source;
+
+use crate::backend::{LoadOutStore, PartsStore};
+use crate::load_out::LoadOutSource;
+use crate::parts::PartsSource;
+
+#[derive(Error, Debug)]
+pub enum RemoteStoreError {
+    #[error("Unsupported source, a URL is required. source: {0:?}")]
+    UnsupportedSource(Source),
+    #[error("Request error. url: {url}, cause: {cause}")]
+    RequestError { url: String, cause: reqwest::Error },
+    #[error("Unexpected response. url: {url}, status: {status}")]
+    UnexpectedResponse { url: String, status: StatusCode },
+    #[error("Not found. url: {0}")]
+    NotFound(String),
+    #[error("Modified by someone else since it was fetched, fetch it again and retry. url: {0}")]
+    Conflict(String),
+    #[error("Cache error. path: {path}, cause: {cause}")]
+    CacheError { path: PathBuf, cause: anyhow::Error },
+}
+
+/// The cached copy of the items at a URL.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct CacheEntry<T> {
+    etag: Option<String>,
+    items: Vec<T>,
+}
+
+pub struct RemoteClient {
+    client: reqwest::Client,
+    cache_directory: PathBuf,
+}
+
+impl RemoteClient {
+    pub fn new(cache_directory: PathBuf) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            cache_directory,
+        }
+    }
+
+    pub async fn fetch_load_out(&self, source: &LoadOutSource) -> Result<Vec<LoadOutItem>, RemoteStoreError> {
+        self.fetch(source).await
+    }
+
+    pub async fn update_load_out(&self, source: &LoadOutSource, items: &[LoadOutItem]) -> Result<(), RemoteStoreError> {
+        self.update(source, items).await
+    }
+
+    /// Creates an empty load-out, if it does not already exist.
+    pub async fn create_load_out(&self, source: &LoadOutSource) -> Result<(), RemoteStoreError> {
+        let url = source_url(source)?;
+
+        let response = self
+            .client
+            .put(url)
+            .header(IF_NONE_MATCH, "*")
+            .json(&Vec::<LoadOutItem>::new())
+            .send()
+            .await
+            .map_err(|cause| request_error(url, cause))?;
+
+        match response.status() {
+            StatusCode::PRECONDITION_FAILED => Ok(()),
+            status if status.is_success() => {
+                info!("Created load-out. source: '{}'", source);
+                Ok(())
+            }
+            status => Err(RemoteStoreError::UnexpectedResponse {
+                url: url.to_string(),
+                status,
+            }),
+        }
+    }
+
+    pub async fn fetch_parts(&self, source: &PartsSource) -> Result<Vec<Part>, RemoteStoreError> {
+        self.fetch(source).await
+    }
+
+    pub async fn update_parts(&self, source: &PartsSource, parts: &[Part]) -> Result<(), RemoteStoreError> {
+        self.update(source, parts).await
+    }
+
+    async fn fetch<T: Serialize + DeserializeOwned>(&self, source: &Source) -> Result<Vec<T>, RemoteStoreError> {
+        let url = source_url(source)?;
+        let cache_path = self.cache_path(url);
+        let cached: Option<CacheEntry<T>> = read_cache(&cache_path)?;
+
+        let mut request = self.client.get(url);
+        if let Some(etag) = cached
+            .as_ref()
+            .and_then(|entry| entry.etag.as_ref())
+        {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(cause) => {
+                return match cached {
+                    Some(entry) => {
+                        warn!(
+                            "Service unavailable, using cached items. url: {}, cause: {}",
+                            url, cause
+                        );
+                        Ok(entry.items)
+                    }
+                    None => Err(request_error(url, cause)),
+                };
+            }
+        };
+
+        match (response.status(), cached) {
+            (StatusCode::NOT_MODIFIED, Some(entry)) => Ok(entry.items),
+            (StatusCode::NOT_FOUND, _) => Err(RemoteStoreError::NotFound(url.to_string())),
+            (status, _) if status.is_success() => {
+                let etag = etag(&response);
+                let items: Vec<T> = response
+                    .json()
+                    .await
+                    .map_err(|cause| request_error(url, cause))?;
+
+                let entry = CacheEntry {
+                    etag,
+                    items,
+                };
+                write_cache(&cache_path, &entry)?;
+
+                Ok(entry.items)
+            }
+            (status, _) => Err(RemoteStoreError::UnexpectedResponse {
+                url: url.to_string(),
+                status,
+            }),
+        }
+    }
+
+    /// Updates are only accepted if the items have not been modified since they were last fetched.
+    async fn update<T: Serialize + DeserializeOwned + Clone>(
+        &self,
+        source: &Source,
+        items: &[T],
+    ) -> Result<(), RemoteStoreError> {
+        let url = source_url(source)?;
+        let cache_path = self.cache_path(url);
+        let cached: Option<CacheEntry<T>> = read_cache(&cache_path)?;
+
+        let mut request = self.client.put(url).json(items);
+        if let Some(etag) = cached.and_then(|entry| entry.etag) {
+            request = request.header(IF_MATCH, etag);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|cause| request_error(url, cause))?;
+
+        match response.status() {
+            StatusCode::PRECONDITION_FAILED => Err(RemoteStoreError::Conflict(url.to_string())),
+            StatusCode::NOT_FOUND => Err(RemoteStoreError::NotFound(url.to_string())),
+            status if status.is_success() => {
+                let entry = CacheEntry {
+                    etag: etag(&response),
+                    items: items.to_vec(),
+                };
+                write_cache(&cache_path, &entry)
+            }
+            status => Err(RemoteStoreError::UnexpectedResponse {
+                url: url.to_string(),
+                status,
+            }),
+        }
+    }
+
+    fn cache_path(&self, url: &str) -> PathBuf {
+        let file_name: String = url
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() {
+                true => c,
+                false => '_',
+            })
+            .collect();
+
+        self.cache_directory
+            .join(format!("{}.json", file_name))
+    }
+}
+
+fn source_url(source: &Source) -> Result<&str, RemoteStoreError> {
+    match source {
+        Source::Url(url) => Ok(url),
+        _ => Err(RemoteStoreError::UnsupportedSource(source.clone())),
+    }
+}
+
+fn etag(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+fn request_error(url: &str, cause: reqwest::Error) -> RemoteStoreError {
+    RemoteStoreError::RequestError {
+        url: url.to_string(),
+        cause,
+    }
+}
+
+fn read_cache<T: DeserializeOwned>(path: &Path) -> Result<Option<CacheEntry<T>>, RemoteStoreError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let cache_error = |cause: anyhow::Error| RemoteStoreError::CacheError {
+        path: path.to_path_buf(),
+        cause,
+    };
+
+    let content = fs::read_to_string(path).map_err(|cause| cache_error(cause.into()))?;
+    let entry = serde_json::from_str(&content).map_err(|cause| cache_error(cause.into()))?;
+
+    Ok(Some(entry))
+}
+
+fn write_cache<T: Serialize>(path: &Path, entry: &CacheEntry<T>) -> Result<(), RemoteStoreError> {
+    let cache_error = |cause: anyhow::Error| RemoteStoreError::CacheError {
+        path: path.to_path_buf(),
+        cause,
+    };
+
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(|cause| cache_error(cause.into()))?;
+    }
+
+    let content = serde_json::to_string_pretty(entry).map_err(|cause| cache_error(cause.into()))?;
+    fs::write(path, content).map_err(|cause| cache_error(cause.into()))
+}
+
+/// A blocking store, for use by the apps, which are synchronous.
+pub struct RemoteStore {
+    client: RemoteClient,
+    runtime: &'static Runtime,
+}
+
+impl RemoteStore {
+    pub fn new(cache_directory: PathBuf) -> Result<Self, Error> {
+        Ok(Self {
+            client: RemoteClient::new(cache_directory),
+            runtime: runtime()?,
+        })
+    }
+}
+
+/// The runtime is built once and shared by all the stores, since a store is opened for each operation.
+///
+/// `block_on` can be called concurrently from multiple threads, see [`Runtime::block_on`].
+fn runtime() -> Result<&'static Runtime, Error> {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+impl PartsStore for RemoteStore {
+    fn load_parts(&self, source: &PartsSource) -> Result<Vec<Part>, Error> {
+        info!("Loading parts. source: {}", source);
+        Ok(self
+            .runtime
+            .block_on(self.client.fetch_parts(source))?)
+    }
+}
+
+impl LoadOutStore for RemoteStore {
+    fn load_items(&self, source: &LoadOutSource) -> Result<Vec<LoadOutItem>, Error> {
+        info!("Loading load-out. source: '{}'", source);
+        Ok(self
+            .runtime
+            .block_on(self.client.fetch_load_out(source))?)
+    }
+
+    fn store_items(&self, source: &LoadOutSource, items: &[LoadOutItem]) -> Result<(), Error> {
+        info!("Storing load-out. source: '{}'", source);
+        Ok(self.runtime.block_on(
+            self.client
+                .update_load_out(source, items),
+        )?)
+    }
+
+    fn ensure_load_out(&self, source: &LoadOutSource) -> Result<(), Error> {
+        Ok(self
+            .runtime
+            .block_on(self.client.create_load_out(source))?)
+    }
+
+    fn load_out_exists(&self, source: &LoadOutSource) -> Result<bool, Error> {
+        match self
+            .runtime
+            .block_on(self.client.fetch_load_out(source))
+        {
+            Ok(_items) => Ok(true),
+            Err(RemoteStoreError::NotFound(_)) => Ok(false),
+            Err(cause) => Err(cause.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn cache_path_is_a_file_name() {
+        // given
+        let client = RemoteClient::new(PathBuf::from("cache"));
+
+        // when
+        let path = client.cache_path("https://example.com:8080/load_outs/1?phase=top");
+
+        // then
+        assert_eq!(
+            path,
+            PathBuf::from("cache").join("https___example_com_8080_load_outs_1_phase_top.json")
+        );
+    }
+
+    #[test]
+    pub fn stores_share_the_runtime() {
+        // when
+        let store_1 = RemoteStore::new(PathBuf::from("cache_1")).unwrap();
+        let store_2 = RemoteStore::new(PathBuf::from("cache_2")).unwrap();
+
+        // then
+        assert!(std::ptr::eq(store_1.runtime, store_2.runtime));
+    }
+
+    #[test]
+    pub fn file_sources_are_unsupported() {
+        // given
+        let source = Source::File(PathBuf::from("load_out.csv"));
+
+        // expect
+        assert!(matches!(
+            source_url(&source),
+            Err(RemoteStoreError::UnsupportedSource(_))
+        ));
+    }
+}
@@ -0,0 +1,602 @@
+//! SQLite store backend, all the parts, load-outs and placements of a project are stored in a single database file.
+//!
+//! Sources are used as keys, paths are stored relative to the directory containing the database so that the project
+//! directory can be moved.  Decimals are stored as text to avoid any loss of precision.
+//!
+//! Existing CSV files are imported using [`crate::backend::import_csv`].
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Error};
+use pnp::load_out::{FeederType, LoadOutItem};
+use pnp::part::Part;
+use pnp::pcb::PcbSide;
+use pnp::placement::Placement;
+use pnp::reference::Reference;
+use rusqlite::{params, Connection, OptionalExtension};
+use rust_decimal::Decimal;
+use tracing::info;
+use util::source::Source;
+
+use crate::backend::{LoadOutStore, PartsStore, PlacementsStore};
+use crate::load_out::LoadOutSource;
+use crate::parts::PartsSource;
+use crate::placements::PlacementsSource;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS parts_sources (
+    source TEXT NOT NULL PRIMARY KEY
+);
+
+CREATE TABLE IF NOT EXISTS parts (
+    source TEXT NOT NULL REFERENCES parts_sources (source),
+    position INTEGER NOT NULL,
+    manufacturer TEXT NOT NULL,
+    mpn TEXT NOT NULL,
+    PRIMARY KEY (source, position)
+);
+
+CREATE TABLE IF NOT EXISTS load_outs (
+    source TEXT NOT NULL PRIMARY KEY
+);
+
+CREATE TABLE IF NOT EXISTS load_out_items (
+    source TEXT NOT NULL REFERENCES load_outs (source),
+    position INTEGER NOT NULL,
+    reference TEXT,
+    manufacturer TEXT NOT NULL,
+    mpn TEXT NOT NULL,
+    package TEXT,
+    feeder_type TEXT,
+    tape_width TEXT,
+    tape_pitch TEXT,
+    rotation_offset TEXT,
+    PRIMARY KEY (source, position)
+);
+
+CREATE TABLE IF NOT EXISTS placements_sources (
+    source TEXT NOT NULL PRIMARY KEY
+);
+
+CREATE TABLE IF NOT EXISTS placements (
+    source TEXT NOT NULL REFERENCES placements_sources (source),
+    position INTEGER NOT NULL,
+    ref_des TEXT NOT NULL,
+    manufacturer TEXT NOT NULL,
+    mpn TEXT NOT NULL,
+    place INTEGER NOT NULL,
+    pcb_side TEXT NOT NULL,
+    x TEXT NOT NULL,
+    y TEXT NOT NULL,
+    rotation TEXT NOT NULL,
+    PRIMARY KEY (source, position)
+);
+
+-- databases created before the sources tables were added
+INSERT OR IGNORE INTO parts_sources (source) SELECT DISTINCT source FROM parts;
+INSERT OR IGNORE INTO placements_sources (source) SELECT DISTINCT source FROM placements;
+"#;
+
+pub struct SqliteStore {
+    connection: Connection,
+    directory: PathBuf,
+}
+
+impl SqliteStore {
+    /// Opens the database, creating it if required.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        info!("Opening database. path: {}", path.display());
+
+        let connection =
+            Connection::open(path).with_context(|| format!("Error opening database. path: {}", path.display()))?;
+
+        let directory = path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        Self::new(connection, directory)
+    }
+
+    fn new(connection: Connection, directory: PathBuf) -> Result<Self, Error> {
+        connection
+            .execute_batch(SCHEMA)
+            .context("Error creating database schema")?;
+
+        Ok(Self {
+            connection,
+            directory,
+        })
+    }
+
+    /// Replaces all the parts of the source, e.g. when importing parts.
+    pub fn store_parts(&self, source: &PartsSource, parts: &[Part]) -> Result<(), Error> {
+        let key = self.key(source)?;
+
+        let transaction = self
+            .connection
+            .unchecked_transaction()?;
+        transaction.execute("INSERT OR IGNORE INTO parts_sources (source) VALUES (?1)", params![key])?;
+        transaction.execute("DELETE FROM parts WHERE source = ?1", params![key])?;
+        for (position, part) in parts.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO parts (source, position, manufacturer, mpn) VALUES (?1, ?2, ?3, ?4)",
+                params![key, position, part.manufacturer, part.mpn],
+            )?;
+        }
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    /// Replaces all the placements of the source, e.g. when importing placements.
+    pub fn store_placements(&self, source: &PlacementsSource, placements: &[Placement]) -> Result<(), Error> {
+        let key = self.key(source)?;
+
+        let transaction = self
+            .connection
+            .unchecked_transaction()?;
+        transaction.execute(
+            "INSERT OR IGNORE INTO placements_sources (source) VALUES (?1)",
+            params![key],
+        )?;
+        transaction.execute("DELETE FROM placements WHERE source = ?1", params![key])?;
+        for (position, placement) in placements.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO placements (source, position, ref_des, manufacturer, mpn, place, pcb_side, x, y, rotation) \
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    key,
+                    position,
+                    placement.ref_des.to_string(),
+                    placement.part.manufacturer,
+                    placement.part.mpn,
+                    placement.place,
+                    pcb_side_to_text(&placement.pcb_side),
+                    placement.x.to_string(),
+                    placement.y.to_string(),
+                    placement.rotation.to_string(),
+                ],
+            )?;
+        }
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    fn key(&self, source: &Source) -> Result<String, Error> {
+        let path = source
+            .path()
+            .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
+
+        let relative_path = path
+            .strip_prefix(&self.directory)
+            .unwrap_or(&path);
+
+        // use the same key regardless of the platform
+        Ok(relative_path
+            .to_string_lossy()
+            .replace('\\', "/"))
+    }
+
+    /// `false` if nothing has been stored for the source in the table, e.g. `parts_sources`.
+    fn source_exists(&self, sources_table: &str, key: &str) -> Result<bool, Error> {
+        Ok(self
+            .connection
+            .query_row(
+                &format!("SELECT 1 FROM {} WHERE source = ?1", sources_table),
+                params![key],
+                |_row| Ok(()),
+            )
+            .optional()?
+            .is_some())
+    }
+}
+
+impl PartsStore for SqliteStore {
+    fn load_parts(&self, source: &PartsSource) -> Result<Vec<Part>, Error> {
+        info!("Loading parts. source: {}", source);
+        let key = self.key(source)?;
+
+        if !self.source_exists("parts_sources", &key)? {
+            return Err(anyhow!("Parts not found. source: '{}'", source));
+        }
+
+        let mut statement = self
+            .connection
+            .prepare("SELECT manufacturer, mpn FROM parts WHERE source = ?1 ORDER BY position")?;
+
+        let parts = statement
+            .query_map(params![key], |row| Ok(Part::new(row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(parts)
+    }
+}
+
+impl LoadOutStore for SqliteStore {
+    fn load_items(&self, source: &LoadOutSource) -> Result<Vec<LoadOutItem>, Error> {
+        info!("Loading load-out. source: '{}'", source);
+        let key = self.key(source)?;
+
+        if !self.source_exists("load_outs", &key)? {
+            return Err(anyhow!("Load-out not found. source: '{}'", source));
+        }
+
+        let mut statement = self.connection.prepare(
+            "SELECT reference, manufacturer, mpn, package, feeder_type, tape_width, tape_pitch, rotation_offset \
+            FROM load_out_items WHERE source = ?1 ORDER BY position",
+        )?;
+
+        let rows = statement
+            .query_map(params![key], |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, Option<String>>(6)?,
+                    row.get::<_, Option<String>>(7)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(
+                |(reference, manufacturer, mpn, package, feeder_type, tape_width, tape_pitch, rotation_offset)| {
+                    Ok(LoadOutItem {
+                        reference: reference
+                            .map(|reference| Reference::from_str(&reference))
+                            .transpose()?,
+                        manufacturer,
+                        mpn,
+                        package,
+                        feeder_type: feeder_type
+                            .map(|feeder_type| feeder_type_from_text(&feeder_type))
+                            .transpose()?,
+                        tape_width: tape_width
+                            .map(parse_decimal)
+                            .transpose()?,
+                        tape_pitch: tape_pitch
+                            .map(parse_decimal)
+                            .transpose()?,
+                        rotation_offset: rotation_offset
+                            .map(parse_decimal)
+                            .transpose()?,
+                    })
+                },
+            )
+            .collect()
+    }
+
+    fn store_items(&self, source: &LoadOutSource, items: &[LoadOutItem]) -> Result<(), Error> {
+        info!("Storing load-out. source: '{}'", source);
+        let key = self.key(source)?;
+
+        let transaction = self
+            .connection
+            .unchecked_transaction()?;
+        transaction.execute("INSERT OR IGNORE INTO load_outs (source) VALUES (?1)", params![key])?;
+        transaction.execute("DELETE FROM load_out_items WHERE source = ?1", params![key])?;
+        for (position, item) in items.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO load_out_items \
+                (source, position, reference, manufacturer, mpn, package, feeder_type, tape_width, tape_pitch, rotation_offset) \
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    key,
+                    position,
+                    item.reference
+                        .as_ref()
+                        .map(|reference| reference.to_string()),
+                    item.manufacturer,
+                    item.mpn,
+                    item.package,
+                    item.feeder_type
+                        .as_ref()
+                        .map(feeder_type_to_text),
+                    item.tape_width
+                        .map(|value| value.to_string()),
+                    item.tape_pitch
+                        .map(|value| value.to_string()),
+                    item.rotation_offset
+                        .map(|value| value.to_string()),
+                ],
+            )?;
+        }
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    fn ensure_load_out(&self, source: &LoadOutSource) -> Result<(), Error> {
+        let key = self.key(source)?;
+
+        let inserted = self
+            .connection
+            .execute("INSERT OR IGNORE INTO load_outs (source) VALUES (?1)", params![key])?;
+        if inserted > 0 {
+            info!("Created load-out. source: '{}'", source);
+        }
+
+        Ok(())
+    }
+
+    fn load_out_exists(&self, source: &LoadOutSource) -> Result<bool, Error> {
+        let key = self.key(source)?;
+
+        self.source_exists("load_outs", &key)
+    }
+}
+
+impl PlacementsStore for SqliteStore {
+    fn load_placements(&self, source: &PlacementsSource) -> Result<Vec<Placement>, Error> {
+        info!("Loading placements. source: {}", source);
+        let key = self.key(source)?;
+
+        if !self.source_exists("placements_sources", &key)? {
+            return Err(anyhow!("Placements not found. source: '{}'", source));
+        }
+
+        let mut statement = self.connection.prepare(
+            "SELECT ref_des, manufacturer, mpn, place, pcb_side, x, y, rotation \
+            FROM placements WHERE source = ?1 ORDER BY position",
+        )?;
+
+        let rows = statement
+            .query_map(params![key], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, bool>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, String>(6)?,
+                    row.get::<_, String>(7)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(|(ref_des, manufacturer, mpn, place, pcb_side, x, y, rotation)| {
+                Ok(Placement {
+                    ref_des: ref_des.into(),
+                    part: Part::new(manufacturer, mpn),
+                    place,
+                    pcb_side: pcb_side_from_text(&pcb_side)?,
+                    x: parse_decimal(x)?,
+                    y: parse_decimal(y)?,
+                    rotation: parse_decimal(rotation)?,
+                })
+            })
+            .collect()
+    }
+}
+
+fn parse_decimal(value: String) -> Result<Decimal, Error> {
+    Decimal::from_str(&value).with_context(|| format!("Invalid decimal. value: {}", value))
+}
+
+fn pcb_side_to_text(pcb_side: &PcbSide) -> &'static str {
+    match pcb_side {
+        PcbSide::Top => "Top",
+        PcbSide::Bottom => "Bottom",
+    }
+}
+
+fn pcb_side_from_text(value: &str) -> Result<PcbSide, Error> {
+    match value {
+        "Top" => Ok(PcbSide::Top),
+        "Bottom" => Ok(PcbSide::Bottom),
+        _ => Err(anyhow!("Invalid PCB side. value: {}", value)),
+    }
+}
+
+fn feeder_type_to_text(feeder_type: &FeederType) -> &'static str {
+    match feeder_type {
+        FeederType::Tape => "Tape",
+        FeederType::Tray => "Tray",
+        FeederType::Tube => "Tube",
+    }
+}
+
+fn feeder_type_from_text(value: &str) -> Result<FeederType, Error> {
+    match value {
+        "Tape" => Ok(FeederType::Tape),
+        "Tray" => Ok(FeederType::Tray),
+        "Tube" => Ok(FeederType::Tube),
+        _ => Err(anyhow!("Invalid feeder type. value: {}", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use assert_fs::prelude::*;
+    use indoc::indoc;
+    use planning::store::StoreBackend;
+    use pnp::part::PartAliases;
+    use pnp::placement::Placement;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::backend::{import_csv, CsvImport};
+    use crate::load_out::add_parts_to_load_out;
+
+    fn build_store() -> SqliteStore {
+        SqliteStore::new(Connection::open_in_memory().unwrap(), PathBuf::from("/project")).unwrap()
+    }
+
+    #[test]
+    pub fn load_out_round_trip() {
+        // given
+        let store = build_store();
+        let source = LoadOutSource::File(PathBuf::from("/project/load_out_1.csv"));
+
+        let mut item_1 = LoadOutItem::new(
+            Some(Reference::from_raw_str("FEEDER_1")),
+            "MFR1".to_string(),
+            "PART1".to_string(),
+        );
+        item_1.feeder_type = Some(FeederType::Tape);
+        item_1.rotation_offset = Some(dec!(-90));
+        let item_2 = LoadOutItem::new(None, "MFR2".to_string(), "PART2".to_string());
+
+        // when
+        store
+            .store_items(&source, &[item_1.clone(), item_2.clone()])
+            .unwrap();
+
+        // then
+        assert_eq!(store.load_items(&source).unwrap(), vec![item_1, item_2]);
+    }
+
+    #[test]
+    pub fn add_parts_to_new_load_out() {
+        // given
+        let store = build_store();
+        let source = LoadOutSource::File(PathBuf::from("/project/load_out_1.csv"));
+        let parts = BTreeSet::from([Part::new("MFR1".to_string(), "PART1".to_string())]);
+
+        // and
+        assert!(store.load_items(&source).is_err());
+        assert!(!store.load_out_exists(&source).unwrap());
+
+        // when
+        store.ensure_load_out(&source).unwrap();
+        add_parts_to_load_out(&store, &source, parts, &PartAliases::default()).unwrap();
+
+        // then
+        assert!(store.load_out_exists(&source).unwrap());
+        assert_eq!(store.load_items(&source).unwrap(), vec![LoadOutItem::new(
+            None,
+            "MFR1".to_string(),
+            "PART1".to_string()
+        )]);
+    }
+
+    #[test]
+    pub fn placements_round_trip() {
+        // given
+        let store = build_store();
+        let source = PlacementsSource::File(PathBuf::from("/project/design_a_variant_a_placements.csv"));
+        let other_source = PlacementsSource::File(PathBuf::from("/project/design_a_variant_b_placements.csv"));
+
+        let placements = vec![Placement {
+            ref_des: "R1".into(),
+            part: Part::new("MFR1".to_string(), "PART1".to_string()),
+            place: true,
+            pcb_side: PcbSide::Bottom,
+            x: dec!(10.5),
+            y: dec!(-0.25),
+            rotation: dec!(90),
+        }];
+
+        // when
+        store
+            .store_placements(&source, &placements)
+            .unwrap();
+
+        // then
+        assert_eq!(store.load_placements(&source).unwrap(), placements);
+
+        // and placements that were never stored are an error, like a missing placements file
+        assert!(store
+            .load_placements(&other_source)
+            .is_err());
+    }
+
+    #[test]
+    pub fn empty_placements_are_not_an_error() {
+        // given
+        let store = build_store();
+        let source = PlacementsSource::File(PathBuf::from("/project/design_a_variant_a_placements.csv"));
+
+        // when
+        store
+            .store_placements(&source, &[])
+            .unwrap();
+
+        // then
+        assert_eq!(store.load_placements(&source).unwrap(), vec![]);
+    }
+
+    #[test]
+    pub fn parts_round_trip() {
+        // given
+        let store = build_store();
+        let source = PartsSource::File(PathBuf::from("/project/parts.csv"));
+        let parts = vec![
+            Part::new("MFR1".to_string(), "PART1".to_string()),
+            Part::new("MFR2".to_string(), "PART2".to_string()),
+        ];
+
+        // and
+        assert!(store.load_parts(&source).is_err());
+
+        // when
+        store
+            .store_parts(&source, &parts)
+            .unwrap();
+
+        // then
+        assert_eq!(store.load_parts(&source).unwrap(), parts);
+    }
+
+    #[test]
+    pub fn import_csv_files() {
+        // given
+        let directory = assert_fs::TempDir::new().unwrap();
+        let parts_file = directory.child("parts.csv");
+        parts_file
+            .write_str(indoc! {r#"
+                "Manufacturer","Mpn"
+                "RES_MFR1","RES1"
+            "#})
+            .unwrap();
+        let placements_file = directory.child("design_a_variant_a_placements.csv");
+        placements_file
+            .write_str(indoc! {r#"
+                "RefDes","Manufacturer","Mpn","Place","PcbSide","X","Y","Rotation"
+                "R1","RES_MFR1","RES1","true","Top","10","110","0"
+            "#})
+            .unwrap();
+
+        // and
+        let backend = StoreBackend::Sqlite {
+            path: PathBuf::from("project.db"),
+        };
+        let parts_source = PartsSource::File(parts_file.to_path_buf());
+        let placements_source = PlacementsSource::File(placements_file.to_path_buf());
+
+        // when
+        import_csv(&backend, directory.path(), &CsvImport {
+            parts: vec![parts_source.clone()],
+            load_outs: vec![],
+            placements: vec![placements_source.clone()],
+        })
+        .unwrap();
+
+        // then the data is loaded from the database, not the files
+        std::fs::remove_file(parts_file.path()).unwrap();
+        std::fs::remove_file(placements_file.path()).unwrap();
+
+        let store = SqliteStore::open(&directory.path().join("project.db")).unwrap();
+        assert_eq!(store.load_parts(&parts_source).unwrap(), vec![Part::new(
+            "RES_MFR1".to_string(),
+            "RES1".to_string()
+        )]);
+        assert_eq!(
+            store
+                .load_placements(&placements_source)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -4,6 +4,7 @@
 use tracing::{info, trace};
 use util::source::Source;
 
+use crate::csv::dialect;
 use crate::csv::SubstitutionRecord;
 
 pub type EdaSubstitutionsSource = Source;
@@ -16,8 +17,7 @@ pub fn load_eda_substitutions(source: &EdaSubstitutionsSource) -> Result<Vec<Eda
         .path()
         .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
 
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .from_path(path.clone())
+    let mut csv_reader = dialect::open_reader(&path)
         .with_context(|| format!("Error reading substitutions. file: {}", path.display()))?;
 
     let mut eda_substitutions: Vec<EdaSubstitutionRule> = vec![];
@@ -0,0 +1,120 @@
+//! PCB unit positionings, e.g. the unit origin table of a panel drawing, provided by a panel house.
+//!
+//! Columns:
+//! * `Unit` - the 1-based unit number.
+//! * `X`, `Y` - the offset of the unit on the panel, in millimeters.
+//! * `Rotation` - in degrees, positive anti-clockwise.
+//!
+//! Other columns are ignored, e.g. a description of the unit.  The header names can be mapped using a dialect, see
+//! [`crate::csv::dialect`].
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Context, Error};
+use pnp::pcb::PcbUnitNumber;
+use rust_decimal::Decimal;
+use tracing::Level;
+use tracing::{info, trace};
+use util::source::Source;
+
+use crate::csv::dialect;
+
+pub type UnitPositioningsSource = Source;
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all(deserialize = "PascalCase"))]
+pub struct UnitPositioningRecord {
+    pub unit: PcbUnitNumber,
+    pub x: Decimal,
+    pub y: Decimal,
+    pub rotation: Decimal,
+}
+
+/// Returns an error if a unit number is zero, or if a unit has more than one row.
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load(source: &UnitPositioningsSource) -> Result<BTreeMap<PcbUnitNumber, UnitPositioningRecord>, Error> {
+    info!("Loading unit positionings. source: {}", source);
+
+    let path = source
+        .path()
+        .map_err(|error| anyhow!("Unsupported source type. cause: {:?}", error))?;
+
+    let mut csv_reader = dialect::open_reader(&path)
+        .with_context(|| format!("Error reading unit positionings. file: {}", path.display()))?;
+
+    let mut unit_positionings = BTreeMap::new();
+
+    for result in csv_reader.deserialize() {
+        let record: UnitPositioningRecord =
+            result.with_context(|| "Deserializing unit positioning record".to_string())?;
+
+        trace!("{:?}", record);
+
+        if record.unit == 0 {
+            bail!("Invalid unit number, units are 1-based. record: {:?}", record);
+        }
+
+        if let Some(existing) = unit_positionings.insert(record.unit, record) {
+            bail!("Duplicate unit. unit: {}", existing.unit);
+        }
+    }
+
+    Ok(unit_positionings)
+}
+
+#[cfg(test)]
+mod unit_positionings_tests {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    pub fn load_unit_positionings() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let csv_file = temp_dir.child("unit-positionings.csv");
+        csv_file.write_str(concat!(
+            "\"Unit\",\"X\",\"Y\",\"Rotation\",\"Description\"\n",
+            "\"2\",\"60.5\",\"5\",\"180\",\"right\"\n",
+            "\"1\",\"5\",\"5\",\"0\",\"left\"\n",
+        ))?;
+        let source = UnitPositioningsSource::from_absolute_path(csv_file.path().to_path_buf())?;
+
+        // when
+        let unit_positionings = load(&source)?;
+
+        // then
+        assert_eq!(unit_positionings.len(), 2);
+        assert_eq!(unit_positionings[&2], UnitPositioningRecord {
+            unit: 2,
+            x: dec!(60.5),
+            y: dec!(5),
+            rotation: dec!(180),
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn duplicate_units_are_rejected() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let csv_file = temp_dir.child("unit-positionings.csv");
+        csv_file.write_str(concat!(
+            "\"Unit\",\"X\",\"Y\",\"Rotation\"\n",
+            "\"1\",\"5\",\"5\",\"0\"\n",
+            "\"1\",\"60\",\"5\",\"0\"\n",
+        ))?;
+        let source = UnitPositioningsSource::from_absolute_path(csv_file.path().to_path_buf())?;
+
+        // when
+        let result = load(&source);
+
+        // then
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
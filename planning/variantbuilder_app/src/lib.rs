@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 use anyhow::Error;
@@ -10,12 +11,16 @@
 use csv::QuoteStyle;
 use eda::placement::{EdaPlacement, EdaPlacementField};
 use eda::substitution::{EdaSubstitutionResult, EdaSubstitutionRule, EdaSubstitutor};
+pub use eda::units::UnitSystem;
 pub use eda::EdaTool;
 use part_mapper::{PartMapper, PartMapperError, PartMappingError, PartMappingResult, PlacementPartMappingResult};
+pub use pnp::part::Part;
 use serde_with::serde_as;
 pub use stores::assembly_rules::AssemblyRuleSource;
+use stores::eda_placements::EdaPlacements;
 pub use stores::eda_placements::EdaPlacementsSource;
 pub use stores::load_out::LoadOutSource;
+use stores::part_mappings::PartMappingRule;
 pub use stores::part_mappings::PartMappingsSource;
 pub use stores::parts::PartsSource;
 use stores::placements::PlacementRecord;
@@ -25,7 +30,13 @@
 use termtree::Tree;
 use thiserror::Error;
 use tracing::Level;
-use tracing::{error, info, trace};
+use tracing::{error, info, trace, warn};
+
+use crate::report::BuildReport;
+pub use crate::report::ReportFormat;
+
+pub mod report;
+pub mod validation;
 
 extern crate serde_regex;
 
@@ -55,6 +66,8 @@ pub enum Event {
     Build {
         eda_tool: EdaTool,
         placements: EdaPlacementsSource,
+        /// `None` to detect the unit system from the placements
+        placements_unit: Option<UnitSystem>,
         assembly_variant: AssemblyVariant,
         parts: PartsSource,
         part_mappings: PartMappingsSource,
@@ -63,6 +76,51 @@ pub enum Event {
         assembly_rules: Option<AssemblyRuleSource>,
         output: String,
         ref_des_disable_list: Vec<String>,
+        /// Also write a build report, in the given format, next to each output file.
+        report: Option<ReportFormat>,
+    },
+    /// Build multiple assembly variants, the placements, parts, mappings, etc. are only loaded once.
+    BuildVariants {
+        eda_tool: EdaTool,
+        placements: EdaPlacementsSource,
+        /// `None` to detect the unit system from the placements
+        placements_unit: Option<UnitSystem>,
+        assembly_variants: Vec<AssemblyVariant>,
+        parts: PartsSource,
+        part_mappings: PartMappingsSource,
+        substitutions: Vec<EdaSubstitutionsSource>,
+        load_out: Option<LoadOutSource>,
+        assembly_rules: Option<AssemblyRuleSource>,
+        /// The name of each output file, `{variant}` is replaced with the name of the assembly variant.
+        output_pattern: String,
+        ref_des_disable_list: Vec<String>,
+        /// Also write a build report, in the given format, next to each output file.
+        report: Option<ReportFormat>,
+    },
+    /// Check the part mappings, substitutions and assembly rules against the placements, without building.
+    ValidateRules {
+        eda_tool: EdaTool,
+        placements: EdaPlacementsSource,
+        /// `None` to detect the unit system from the placements
+        placements_unit: Option<UnitSystem>,
+        parts: PartsSource,
+        part_mappings: PartMappingsSource,
+        substitutions: Vec<EdaSubstitutionsSource>,
+        assembly_rules: Option<AssemblyRuleSource>,
+    },
+    /// Add a part mapping rule that maps a placement to a part, e.g. for a placement with no mappings.
+    ///
+    /// The rule matches the fields of the placement exactly, after the substitutions are applied.
+    AddPartMapping {
+        eda_tool: EdaTool,
+        placements: EdaPlacementsSource,
+        /// `None` to detect the unit system from the placements
+        placements_unit: Option<UnitSystem>,
+        parts: PartsSource,
+        part_mappings: PartMappingsSource,
+        substitutions: Vec<EdaSubstitutionsSource>,
+        ref_des: String,
+        part: Part,
     },
     //
     // Views
@@ -87,6 +145,7 @@ fn update(
             Event::Build {
                 eda_tool,
                 placements,
+                placements_unit,
                 assembly_variant,
                 parts,
                 part_mappings,
@@ -95,25 +154,139 @@ fn update(
                 assembly_rules,
                 output,
                 ref_des_disable_list,
+                report,
+            } => {
+                let try_fn = |_model: &mut Model| -> Result<Command<Self::Effect, Self::Event>, AppError> {
+                    build_assembly_variants(
+                        eda_tool,
+                        &placements,
+                        placements_unit,
+                        vec![(assembly_variant, output)],
+                        &parts,
+                        &part_mappings,
+                        &substitutions,
+                        &load_out,
+                        &assembly_rules,
+                        &ref_des_disable_list,
+                        report,
+                    )
+                    .map_err(|cause| AppError::OperationError(cause.into()))?;
+
+                    Ok(render::render())
+                };
+
+                match try_fn(model) {
+                    Ok(command) => command,
+                    Err(e) => {
+                        model.error.replace(format!("{:?}", e));
+                        render::render()
+                    }
+                }
+            }
+            Event::BuildVariants {
+                eda_tool,
+                placements,
+                placements_unit,
+                assembly_variants,
+                parts,
+                part_mappings,
+                substitutions,
+                load_out,
+                assembly_rules,
+                output_pattern,
+                ref_des_disable_list,
+                report,
             } => {
                 let try_fn = |_model: &mut Model| -> Result<Command<Self::Effect, Self::Event>, AppError> {
-                    build_assembly_variant(
+                    let variants = build_variant_outputs(assembly_variants, &output_pattern)?;
+
+                    build_assembly_variants(
                         eda_tool,
                         &placements,
-                        assembly_variant,
+                        placements_unit,
+                        variants,
                         &parts,
                         &part_mappings,
                         &substitutions,
                         &load_out,
                         &assembly_rules,
-                        &output,
                         &ref_des_disable_list,
+                        report,
                     )
                     .map_err(|cause| AppError::OperationError(cause.into()))?;
 
                     Ok(render::render())
                 };
 
+                match try_fn(model) {
+                    Ok(command) => command,
+                    Err(e) => {
+                        model.error.replace(format!("{:?}", e));
+                        render::render()
+                    }
+                }
+            }
+            Event::ValidateRules {
+                eda_tool,
+                placements,
+                placements_unit,
+                parts,
+                part_mappings,
+                substitutions,
+                assembly_rules,
+            } => {
+                let try_fn = |_model: &mut Model| -> Result<Command<Self::Effect, Self::Event>, AppError> {
+                    let issue_count = validate_assembly_rules(
+                        eda_tool,
+                        &placements,
+                        placements_unit,
+                        &parts,
+                        &part_mappings,
+                        &substitutions,
+                        &assembly_rules,
+                    )
+                    .map_err(|cause| AppError::OperationError(cause.into()))?;
+
+                    if issue_count > 0 {
+                        return Err(AppError::RuleValidationFailed(issue_count));
+                    }
+
+                    Ok(render::render())
+                };
+
+                match try_fn(model) {
+                    Ok(command) => command,
+                    Err(e) => {
+                        model.error.replace(format!("{:?}", e));
+                        render::render()
+                    }
+                }
+            }
+            Event::AddPartMapping {
+                eda_tool,
+                placements,
+                placements_unit,
+                parts,
+                part_mappings,
+                substitutions,
+                ref_des,
+                part,
+            } => {
+                let try_fn = |_model: &mut Model| -> Result<Command<Self::Effect, Self::Event>, AppError> {
+                    add_part_mapping(
+                        eda_tool,
+                        &placements,
+                        placements_unit,
+                        &parts,
+                        &part_mappings,
+                        &substitutions,
+                        &ref_des,
+                        part,
+                    )?;
+
+                    Ok(render::render())
+                };
+
                 match try_fn(model) {
                     Ok(command) => command,
                     Err(e) => {
@@ -136,23 +309,67 @@ fn view(&self, model: &Self::Model) -> Self::ViewModel {
 enum AppError {
     #[error("Operation error, cause: {0}")]
     OperationError(anyhow::Error),
+    #[error("The output pattern must contain '{{variant}}'. pattern: {0}")]
+    InvalidOutputPattern(String),
+    #[error("Duplicate assembly variant name. name: {0}")]
+    DuplicateAssemblyVariant(String),
+    #[error("Rule validation failed. issues: {0}")]
+    RuleValidationFailed(usize),
+    #[error("Unknown placement. ref_des: {0}")]
+    UnknownPlacement(String),
+    #[error("Unknown part, add the part to the parts first. part: {0:?}")]
+    UnknownPart(Part),
 }
 
+const VARIANT_PLACEHOLDER: &str = "{variant}";
+
+/// Pairs each assembly variant with its output file name.
+fn build_variant_outputs(
+    assembly_variants: Vec<AssemblyVariant>,
+    output_pattern: &str,
+) -> Result<Vec<(AssemblyVariant, String)>, AppError> {
+    if !output_pattern.contains(VARIANT_PLACEHOLDER) {
+        return Err(AppError::InvalidOutputPattern(output_pattern.to_string()));
+    }
+
+    let mut names = BTreeSet::new();
+
+    assembly_variants
+        .into_iter()
+        .map(|assembly_variant| {
+            if !names.insert(assembly_variant.name.clone()) {
+                return Err(AppError::DuplicateAssemblyVariant(assembly_variant.name));
+            }
+
+            let output = output_pattern.replace(VARIANT_PLACEHOLDER, &assembly_variant.name);
+            Ok((assembly_variant, output))
+        })
+        .collect()
+}
+
+/// Builds each assembly variant, writing the output for each one to the corresponding file.
+///
+/// When building more than one variant a combined report, showing the differences between the variants, is produced.
 #[tracing::instrument(level = Level::DEBUG)]
-fn build_assembly_variant(
+fn build_assembly_variants(
     eda_tool: EdaTool,
     placements_source: &EdaPlacementsSource,
-    assembly_variant: AssemblyVariant,
+    placements_unit: Option<UnitSystem>,
+    variants: Vec<(AssemblyVariant, String)>,
     parts_source: &PartsSource,
     part_mappings_source: &PartMappingsSource,
     eda_substitutions_sources: &[EdaSubstitutionsSource],
     load_out_source: &Option<LoadOutSource>,
     assembly_rules_source: &Option<AssemblyRuleSource>,
-    output: &String,
     ref_des_disable_list: &Vec<String>,
+    report_format: Option<ReportFormat>,
 ) -> Result<(), Error> {
-    let mut original_eda_placements = eda_placements::load_eda_placements(eda_tool, placements_source)?;
+    let EdaPlacements {
+        unit_system: placements_unit,
+        placements: mut original_eda_placements,
+    } = eda_placements::load_eda_placements_from(eda_tool, None, placements_unit, placements_source)?;
     info!("Loaded {} placements", original_eda_placements.len());
+    info!("Placements unit: {}", placements_unit.display_name());
 
     let eda_substitution_rules = eda_substitutions_sources
         .iter()
@@ -164,11 +381,29 @@ fn build_assembly_variant(
             Ok::<Vec<EdaSubstitutionRule>, anyhow::Error>(rules)
         })?;
 
-    let eda_substitution_results = EdaSubstitutor::substitute(
+    let (eda_substitution_results, eda_substitution_diagnostics) = EdaSubstitutor::substitute_with_diagnostics(
         original_eda_placements.as_mut_slice(),
         eda_substitution_rules.as_slice(),
     );
     trace!("eda_substitution_results: {:?}", eda_substitution_results);
+    for rule in eda_substitution_diagnostics
+        .unmatched_rules
+        .iter()
+    {
+        warn!(
+            "Substitution rule did not match any placement. criteria: ({})",
+            rule.format_criteria()
+        );
+    }
+    for (ref_des, chain_error) in eda_substitution_diagnostics
+        .chain_errors
+        .iter()
+    {
+        error!(
+            "Substitution chain stopped. ref_des: {}, cause: {}",
+            ref_des, chain_error
+        );
+    }
 
     info!("disabling placements: {:?}", ref_des_disable_list);
     let mut eda_placements: Vec<EdaPlacement> = eda_substitution_results
@@ -185,6 +420,9 @@ fn build_assembly_variant(
     let parts = parts::load_parts(parts_source)?;
     info!("Loaded {} parts", parts.len());
 
+    let part_aliases = parts::load_part_aliases(parts_source)?;
+    info!("Loaded {} part alias groups", part_aliases.groups().count());
+
     let part_mappings = part_mappings::load_part_mappings(&parts, part_mappings_source)?;
     info!("Loaded {} part mappings", part_mappings.len());
     trace!("{:?}", part_mappings);
@@ -201,41 +439,277 @@ fn build_assembly_variant(
     }?;
     info!("Loaded {} assembly rules", assembly_rules.len());
 
-    info!("Assembly variant: {}", assembly_variant.name);
-    info!("Ref_des list: {}", assembly_variant.ref_des_list.join(", "));
+    let mut variant_outcomes: Vec<(String, BTreeMap<String, String>)> = vec![];
 
-    let result = AssemblyVariantProcessor::process(&eda_placements, assembly_variant)?;
-    let variant_placements = result.placements;
-    let variant_placements_count = variant_placements.len();
+    let is_combined = variants.len() > 1;
 
-    info!("Matched {} placements for assembly variant", variant_placements_count);
+    for (assembly_variant, output) in variants {
+        let assembly_variant_name = assembly_variant.name.clone();
 
-    trace!("{:?}", part_mappings);
+        info!("Assembly variant: {}", assembly_variant.name);
+        info!("Ref_des list: {}", assembly_variant.ref_des_list.join(", "));
 
-    let processing_result = PartMapper::process(&variant_placements, &part_mappings, &load_out_items, &assembly_rules);
+        let result = AssemblyVariantProcessor::process(&eda_placements, assembly_variant)?;
+        let variant_placements = result.placements;
+        let variant_placements_count = variant_placements.len();
 
-    trace!("{:?}", processing_result);
+        info!("Matched {} placements for assembly variant", variant_placements_count);
 
-    let matched_mappings = match &processing_result {
-        Ok(mappings) => mappings,
-        Err(PartMapperError::MappingErrors(mappings)) => mappings,
-    };
+        trace!("{:?}", part_mappings);
 
-    let tree = build_mapping_tree(matched_mappings, eda_substitution_results);
-    info!("{}", tree);
+        let processing_result = PartMapper::process(
+            &variant_placements,
+            &part_mappings,
+            &load_out_items,
+            &assembly_rules,
+            &part_aliases,
+        );
+
+        trace!("{:?}", processing_result);
+
+        let matched_mappings = match &processing_result {
+            Ok(mappings) => mappings,
+            Err(PartMapperError::MappingErrors(mappings)) => mappings,
+        };
 
-    match &processing_result {
-        Ok(_) => (),
-        Err(PartMapperError::MappingErrors(_)) => {
-            error!("Mapping failures")
+        let tree = build_mapping_tree(matched_mappings, &eda_substitution_results);
+        info!("{}", tree);
+
+        match &processing_result {
+            Ok(_) => (),
+            Err(PartMapperError::MappingErrors(_)) => {
+                error!("Mapping failures")
+            }
         }
+
+        write_output_csv(&output, matched_mappings)?;
+
+        if let Some(report_format) = report_format {
+            let report = BuildReport::build(
+                &assembly_variant_name,
+                &output,
+                matched_mappings,
+                &eda_substitution_results,
+                &eda_substitution_diagnostics,
+                ref_des_disable_list,
+            );
+            let report_path = report.write(report_format)?;
+            info!(
+                "Written build report. errors: {}, path: {}",
+                report.summary.errors,
+                report_path.display()
+            );
+        }
+
+        if is_combined {
+            info!(
+                "Written assembly variant. name: {}, output: {}",
+                assembly_variant_name, output
+            );
+            variant_outcomes.push((assembly_variant_name, build_mapping_outcomes(matched_mappings)));
+        }
+    }
+
+    if is_combined {
+        let tree = build_variant_differences_tree(&variant_outcomes);
+        info!("{}", tree);
+    }
+
+    Ok(())
+}
+
+/// Loads the rules and the placements and reports any issues with the rules, no output files are written.
+///
+/// Returns the number of issues found.
+#[tracing::instrument(level = Level::DEBUG)]
+fn validate_assembly_rules(
+    eda_tool: EdaTool,
+    placements_source: &EdaPlacementsSource,
+    placements_unit: Option<UnitSystem>,
+    parts_source: &PartsSource,
+    part_mappings_source: &PartMappingsSource,
+    eda_substitutions_sources: &[EdaSubstitutionsSource],
+    assembly_rules_source: &Option<AssemblyRuleSource>,
+) -> Result<usize, Error> {
+    let EdaPlacements {
+        placements: eda_placements,
+        ..
+    } = eda_placements::load_eda_placements_from(eda_tool, None, placements_unit, placements_source)?;
+    info!("Loaded {} placements", eda_placements.len());
+
+    let eda_substitution_rules = eda_substitutions_sources
+        .iter()
+        .try_fold(vec![], |mut rules, source| {
+            let source_rules = substitutions::load_eda_substitutions(source)?;
+            info!("Loaded {} substitution rules from {}", source_rules.len(), source);
+            rules.extend(source_rules);
+
+            Ok::<Vec<EdaSubstitutionRule>, anyhow::Error>(rules)
+        })?;
+
+    let parts = parts::load_parts(parts_source)?;
+    info!("Loaded {} parts", parts.len());
+
+    let part_mappings = part_mappings::load_part_mappings(&parts, part_mappings_source)?;
+    info!("Loaded {} part mappings", part_mappings.len());
+
+    let assembly_rules = match assembly_rules_source {
+        Some(source) => assembly_rules::load(source),
+        None => Ok(vec![]),
+    }?;
+    info!("Loaded {} assembly rules", assembly_rules.len());
+
+    let issues = validation::validate_rules(
+        &eda_placements,
+        &eda_substitution_rules,
+        &part_mappings,
+        &assembly_rules,
+    );
+
+    match issues.is_empty() {
+        true => info!("No rule issues found"),
+        false => error!("{}", validation::build_rule_issues_tree(&issues)),
     }
 
-    write_output_csv(output, matched_mappings)?;
+    Ok(issues.len())
+}
+
+/// Adds a part mapping rule for the placement to the part mappings, the existing rules are preserved.
+#[tracing::instrument(level = Level::DEBUG)]
+fn add_part_mapping(
+    eda_tool: EdaTool,
+    placements_source: &EdaPlacementsSource,
+    placements_unit: Option<UnitSystem>,
+    parts_source: &PartsSource,
+    part_mappings_source: &PartMappingsSource,
+    eda_substitutions_sources: &[EdaSubstitutionsSource],
+    ref_des: &str,
+    part: Part,
+) -> Result<(), AppError> {
+    let try_load = || -> Result<(Vec<EdaPlacement>, Vec<Part>), Error> {
+        let EdaPlacements {
+            placements: original_eda_placements,
+            ..
+        } = eda_placements::load_eda_placements_from(eda_tool, None, placements_unit, placements_source)?;
+        info!("Loaded {} placements", original_eda_placements.len());
+
+        let eda_substitution_rules = eda_substitutions_sources
+            .iter()
+            .try_fold(vec![], |mut rules, source| {
+                let source_rules = substitutions::load_eda_substitutions(source)?;
+                info!("Loaded {} substitution rules from {}", source_rules.len(), source);
+                rules.extend(source_rules);
+
+                Ok::<Vec<EdaSubstitutionRule>, anyhow::Error>(rules)
+            })?;
+
+        let eda_placements =
+            EdaSubstitutor::substitute(original_eda_placements.as_slice(), eda_substitution_rules.as_slice())
+                .into_iter()
+                .map(|eda_substitution_result| eda_substitution_result.resulting_placement)
+                .collect();
+
+        let parts = parts::load_parts(parts_source)?;
+        info!("Loaded {} parts", parts.len());
+
+        Ok((eda_placements, parts))
+    };
+
+    let (eda_placements, parts) = try_load().map_err(AppError::OperationError)?;
+
+    // the part mappings only load when the part is known
+    if !parts.contains(&part) {
+        return Err(AppError::UnknownPart(part));
+    }
+
+    let eda_placement = eda_placements
+        .iter()
+        .find(|eda_placement| eda_placement.ref_des.eq(ref_des))
+        .ok_or_else(|| AppError::UnknownPlacement(ref_des.to_string()))?;
+
+    let rule = PartMappingRule::from_placement(eda_tool, eda_placement, part);
+    info!("Adding part mapping rule. rule: {:?}", rule);
+
+    let mut part_mapping_rules =
+        part_mappings::load_part_mapping_rules(part_mappings_source).map_err(AppError::OperationError)?;
+    part_mapping_rules.add(rule);
+    part_mappings::save_part_mapping_rules(part_mappings_source, &part_mapping_rules)
+        .map_err(AppError::OperationError)?;
 
     Ok(())
 }
 
+/// A short description of the outcome of the mapping for each placement, keyed by ref_des.
+fn build_mapping_outcomes(matched_mappings: &[PlacementPartMappingResult]) -> BTreeMap<String, String> {
+    matched_mappings
+        .iter()
+        .map(
+            |PlacementPartMappingResult {
+                 eda_placement,
+                 mapping_result,
+                 part,
+             }| {
+                let outcome = match (part, mapping_result) {
+                    (Some(part), _) => format!("manufacturer: '{}', mpn: '{}'", part.manufacturer, part.mpn),
+                    (None, Err(PartMappingError::ConflictingRules(_))) => {
+                        "ERROR: Unresolved mapping - Conflicting rules.".to_string()
+                    }
+                    (None, Err(PartMappingError::NoRulesApplied(_))) => {
+                        "ERROR: Unresolved mapping - No rules applied.".to_string()
+                    }
+                    (None, Err(PartMappingError::NoMappings)) => {
+                        "ERROR: Unresolved mapping - No mappings found.".to_string()
+                    }
+                    (None, Ok(_)) => "No part".to_string(),
+                };
+
+                let outcome = match eda_placement.place {
+                    true => outcome,
+                    false => format!("{} (not placed)", outcome),
+                };
+
+                (eda_placement.ref_des.clone(), outcome)
+            },
+        )
+        .collect()
+}
+
+/// Only placements where the outcome differs between variants are included.
+fn build_variant_differences_tree(variant_outcomes: &[(String, BTreeMap<String, String>)]) -> Tree<String> {
+    let mut tree = Tree::new("Variant Differences".to_string());
+
+    let ref_des_list: BTreeSet<&String> = variant_outcomes
+        .iter()
+        .flat_map(|(_name, outcomes)| outcomes.keys())
+        .collect();
+
+    for ref_des in ref_des_list {
+        let outcomes: Vec<Option<&String>> = variant_outcomes
+            .iter()
+            .map(|(_name, outcomes)| outcomes.get(ref_des))
+            .collect();
+
+        let all_equal = outcomes
+            .windows(2)
+            .all(|pair| pair[0] == pair[1]);
+        if all_equal {
+            continue;
+        }
+
+        let mut placement_node = Tree::new(ref_des.clone());
+        for ((name, _), outcome) in variant_outcomes.iter().zip(outcomes) {
+            let outcome = outcome.map_or("Not in variant", String::as_str);
+            placement_node
+                .leaves
+                .push(Tree::new(format!("{}: {}", name, outcome)));
+        }
+
+        tree.leaves.push(placement_node);
+    }
+
+    tree
+}
+
 fn write_output_csv(
     output_file_name: &String,
     matched_mappings: &Vec<PlacementPartMappingResult>,
@@ -277,7 +751,7 @@ fn write_output_csv(
 
 fn build_mapping_tree(
     matched_mappings: &Vec<PlacementPartMappingResult>,
-    eda_substitution_results: Vec<EdaSubstitutionResult>,
+    eda_substitution_results: &[EdaSubstitutionResult],
 ) -> Tree<String> {
     let mut tree = Tree::new("Mapping Result".to_string());
 
@@ -411,4 +885,35 @@ fn minimal() {
         let expected_view = OperationViewModel::default();
         assert_eq!(actual_view, &expected_view);
     }
+
+    #[test]
+    fn variant_outputs() {
+        // given
+        let assembly_variants = vec![
+            AssemblyVariant::new("Variant_1".to_string(), vec!["R1".to_string()]),
+            AssemblyVariant::new("Variant_2".to_string(), vec!["R2".to_string()]),
+        ];
+
+        // when
+        let variants = build_variant_outputs(assembly_variants, "output_{variant}.csv").unwrap();
+
+        // then
+        let outputs: Vec<&str> = variants
+            .iter()
+            .map(|(_assembly_variant, output)| output.as_str())
+            .collect();
+        assert_eq!(outputs, vec!["output_Variant_1.csv", "output_Variant_2.csv"]);
+    }
+
+    #[test]
+    fn variant_outputs_require_placeholder() {
+        // given
+        let assembly_variants = vec![AssemblyVariant::default()];
+
+        // when
+        let result = build_variant_outputs(assembly_variants, "output.csv");
+
+        // then
+        assert!(matches!(result, Err(AppError::InvalidOutputPattern(_))));
+    }
 }
@@ -0,0 +1,494 @@
+//! Machine-readable build report, so that CI can fail builds on mapping errors and dashboards can track them.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eda::substitution::{EdaSubstitutionDiagnostics, EdaSubstitutionResult};
+use part_mapper::{PartMappingError, PartMappingResult, PlacementPartMappingResult};
+use pnp::part::Part;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    Json,
+    Html,
+}
+
+impl ReportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Json => "report.json",
+            ReportFormat::Html => "report.html",
+        }
+    }
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+pub struct BuildReport {
+    pub assembly_variant: String,
+    pub output: String,
+    pub summary: BuildSummary,
+    pub disabled_ref_des: Vec<String>,
+    pub placements: Vec<PlacementReport>,
+    pub part_counts: Vec<PartCount>,
+    pub substitution_diagnostics: SubstitutionDiagnosticsReport,
+}
+
+#[derive(serde::Serialize, Debug, Default, PartialEq)]
+pub struct BuildSummary {
+    pub placements: usize,
+    pub placed: usize,
+    pub disabled: usize,
+    pub resolved: usize,
+    pub errors: usize,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+pub struct PlacementReport {
+    pub ref_des: String,
+    pub place: bool,
+    pub substitutions: Vec<SubstitutionReport>,
+    pub candidates: Vec<CandidateReport>,
+    pub part: Option<Part>,
+    pub status: MappingStatus,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+pub struct SubstitutionReport {
+    pub criteria: String,
+    pub transform: String,
+}
+
+/// Problems with the substitution rules, they apply to all the placements, not just the placements of the variant.
+#[derive(serde::Serialize, Debug, Default, PartialEq)]
+pub struct SubstitutionDiagnosticsReport {
+    pub unmatched_rules: Vec<SubstitutionReport>,
+    pub chain_errors: Vec<SubstitutionChainErrorReport>,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+pub struct SubstitutionChainErrorReport {
+    pub ref_des: String,
+    pub error: String,
+}
+
+impl From<&EdaSubstitutionDiagnostics<'_>> for SubstitutionDiagnosticsReport {
+    fn from(diagnostics: &EdaSubstitutionDiagnostics<'_>) -> Self {
+        Self {
+            unmatched_rules: diagnostics
+                .unmatched_rules
+                .iter()
+                .map(|rule| SubstitutionReport {
+                    criteria: rule.format_criteria(),
+                    transform: rule.format_transform(),
+                })
+                .collect(),
+            chain_errors: diagnostics
+                .chain_errors
+                .iter()
+                .map(|(ref_des, chain_error)| SubstitutionChainErrorReport {
+                    ref_des: ref_des.clone(),
+                    error: chain_error.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+pub struct CandidateReport {
+    pub part: Part,
+    pub applied_rule: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MappingStatus {
+    Resolved,
+    ConflictingRules,
+    NoRulesApplied,
+    NoMappings,
+}
+
+impl MappingStatus {
+    pub fn is_error(&self) -> bool {
+        !matches!(self, MappingStatus::Resolved)
+    }
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+pub struct PartCount {
+    pub manufacturer: String,
+    pub mpn: String,
+    pub count: usize,
+}
+
+impl BuildReport {
+    pub fn build(
+        assembly_variant: &str,
+        output: &str,
+        matched_mappings: &[PlacementPartMappingResult],
+        eda_substitution_results: &[EdaSubstitutionResult],
+        eda_substitution_diagnostics: &EdaSubstitutionDiagnostics,
+        ref_des_disable_list: &[String],
+    ) -> Self {
+        let placements: Vec<PlacementReport> = matched_mappings
+            .iter()
+            .map(|matched_mapping| build_placement_report(matched_mapping, eda_substitution_results))
+            .collect();
+
+        let summary = BuildSummary {
+            placements: placements.len(),
+            placed: placements
+                .iter()
+                .filter(|placement| placement.place)
+                .count(),
+            disabled: placements
+                .iter()
+                .filter(|placement| !placement.place)
+                .count(),
+            resolved: placements
+                .iter()
+                .filter(|placement| !placement.status.is_error())
+                .count(),
+            errors: placements
+                .iter()
+                .filter(|placement| placement.status.is_error())
+                .count(),
+        };
+
+        let disabled_ref_des = placements
+            .iter()
+            .filter(|placement| ref_des_disable_list.contains(&placement.ref_des))
+            .map(|placement| placement.ref_des.clone())
+            .collect();
+
+        // only placed parts are counted
+        let mut part_counts: BTreeMap<&Part, usize> = BTreeMap::new();
+        for placement in placements.iter() {
+            if let (true, Some(part)) = (placement.place, &placement.part) {
+                *part_counts.entry(part).or_default() += 1;
+            }
+        }
+        let part_counts = part_counts
+            .into_iter()
+            .map(|(part, count)| PartCount {
+                manufacturer: part.manufacturer.clone(),
+                mpn: part.mpn.clone(),
+                count,
+            })
+            .collect();
+
+        Self {
+            assembly_variant: assembly_variant.to_string(),
+            output: output.to_string(),
+            summary,
+            disabled_ref_des,
+            placements,
+            part_counts,
+            substitution_diagnostics: SubstitutionDiagnosticsReport::from(eda_substitution_diagnostics),
+        }
+    }
+
+    /// Writes the report next to the output file, e.g. `output.csv` -> `output.report.json`.
+    ///
+    /// Returns the path of the report.
+    pub fn write(&self, format: ReportFormat) -> anyhow::Result<PathBuf> {
+        let report_path = Path::new(&self.output).with_extension(format.extension());
+
+        let content = match format {
+            ReportFormat::Json => serde_json::to_string_pretty(self)?,
+            ReportFormat::Html => self.to_html(),
+        };
+
+        fs::write(&report_path, content)?;
+
+        Ok(report_path)
+    }
+
+    fn to_html(&self) -> String {
+        let mut html = String::new();
+
+        // writing to a `String` cannot fail
+        let _ = writeln!(html, "<!DOCTYPE html>");
+        let _ = writeln!(html, "<html>");
+        let _ = writeln!(
+            html,
+            "<head><meta charset=\"utf-8\"><title>Build report - {}</title></head>",
+            escape_html(&self.assembly_variant)
+        );
+        let _ = writeln!(html, "<body>");
+        let _ = writeln!(html, "<h1>Build report - {}</h1>", escape_html(&self.assembly_variant));
+        let _ = writeln!(html, "<p>Output: {}</p>", escape_html(&self.output));
+
+        let _ = writeln!(html, "<h2>Summary</h2>");
+        let _ = writeln!(html, "<table>");
+        for (label, value) in [
+            ("Placements", self.summary.placements),
+            ("Placed", self.summary.placed),
+            ("Disabled", self.summary.disabled),
+            ("Resolved", self.summary.resolved),
+            ("Errors", self.summary.errors),
+        ] {
+            let _ = writeln!(html, "<tr><th>{}</th><td>{}</td></tr>", label, value);
+        }
+        let _ = writeln!(html, "</table>");
+
+        let _ = writeln!(html, "<h2>Placements</h2>");
+        let _ = writeln!(html, "<table>");
+        let _ = writeln!(
+            html,
+            "<tr><th>Ref-des</th><th>Place</th><th>Substitutions</th><th>Candidates</th><th>Part</th><th>Status</th></tr>"
+        );
+        for placement in self.placements.iter() {
+            let substitutions: Vec<String> = placement
+                .substitutions
+                .iter()
+                .map(|substitution| {
+                    escape_html(&format!("({}), by ({})", substitution.transform, substitution.criteria))
+                })
+                .collect();
+            let candidates: Vec<String> = placement
+                .candidates
+                .iter()
+                .map(|candidate| {
+                    let label = match &candidate.applied_rule {
+                        Some(rule) => format!("{} ({})", format_part(&candidate.part), rule),
+                        None => format_part(&candidate.part),
+                    };
+                    escape_html(&label)
+                })
+                .collect();
+            let part = placement
+                .part
+                .as_ref()
+                .map_or_else(String::new, |part| escape_html(&format_part(part)));
+
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:?}</td></tr>",
+                escape_html(&placement.ref_des),
+                placement.place,
+                substitutions.join("<br>"),
+                candidates.join("<br>"),
+                part,
+                placement.status,
+            );
+        }
+        let _ = writeln!(html, "</table>");
+
+        let _ = writeln!(html, "<h2>Part counts</h2>");
+        let _ = writeln!(html, "<table>");
+        let _ = writeln!(html, "<tr><th>Manufacturer</th><th>MPN</th><th>Count</th></tr>");
+        for part_count in self.part_counts.iter() {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&part_count.manufacturer),
+                escape_html(&part_count.mpn),
+                part_count.count
+            );
+        }
+        let _ = writeln!(html, "</table>");
+
+        let diagnostics = &self.substitution_diagnostics;
+        if !diagnostics.unmatched_rules.is_empty() || !diagnostics.chain_errors.is_empty() {
+            let _ = writeln!(html, "<h2>Substitution diagnostics</h2>");
+            let _ = writeln!(html, "<table>");
+            let _ = writeln!(html, "<tr><th>Problem</th><th>Details</th></tr>");
+            for rule in diagnostics.unmatched_rules.iter() {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>Unmatched rule</td><td>{}</td></tr>",
+                    escape_html(&format!("({}), by ({})", rule.transform, rule.criteria))
+                );
+            }
+            for chain_error in diagnostics.chain_errors.iter() {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>Chain error</td><td>{}</td></tr>",
+                    escape_html(&format!("{}, ref_des: {}", chain_error.error, chain_error.ref_des))
+                );
+            }
+            let _ = writeln!(html, "</table>");
+        }
+
+        let _ = writeln!(html, "</body>");
+        let _ = writeln!(html, "</html>");
+
+        html
+    }
+}
+
+fn build_placement_report(
+    matched_mapping: &PlacementPartMappingResult,
+    eda_substitution_results: &[EdaSubstitutionResult],
+) -> PlacementReport {
+    let PlacementPartMappingResult {
+        eda_placement,
+        mapping_result,
+        part,
+    } = matched_mapping;
+
+    let substitutions = eda_substitution_results
+        .iter()
+        .find(|candidate| {
+            candidate
+                .original_placement
+                .ref_des
+                .eq(&eda_placement.ref_des)
+        })
+        .map(|substitution_result| {
+            substitution_result
+                .chain
+                .iter()
+                .map(|chain_entry| SubstitutionReport {
+                    criteria: chain_entry.rule.format_criteria(),
+                    transform: chain_entry.rule.format_transform(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let (candidates, status) = match mapping_result {
+        Ok(part_mapping_results) => (build_candidates(part_mapping_results), MappingStatus::Resolved),
+        Err(PartMappingError::ConflictingRules(part_mapping_results)) => {
+            (build_candidates(part_mapping_results), MappingStatus::ConflictingRules)
+        }
+        Err(PartMappingError::NoRulesApplied(part_mapping_results)) => {
+            (build_candidates(part_mapping_results), MappingStatus::NoRulesApplied)
+        }
+        Err(PartMappingError::NoMappings) => (vec![], MappingStatus::NoMappings),
+    };
+
+    PlacementReport {
+        ref_des: eda_placement.ref_des.clone(),
+        place: eda_placement.place,
+        substitutions,
+        candidates,
+        part: part.cloned(),
+        status,
+    }
+}
+
+fn build_candidates(part_mapping_results: &[PartMappingResult]) -> Vec<CandidateReport> {
+    part_mapping_results
+        .iter()
+        .map(
+            |PartMappingResult {
+                 part_mapping,
+                 applied_rule,
+             }| CandidateReport {
+                part: part_mapping.part.clone(),
+                applied_rule: applied_rule
+                    .as_ref()
+                    .map(ToString::to_string),
+            },
+        )
+        .collect()
+}
+
+fn format_part(part: &Part) -> String {
+    format!("manufacturer: '{}', mpn: '{}'", part.manufacturer, part.mpn)
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use eda::placement::EdaPlacement;
+    use part_mapper::part_mapping::PartMapping;
+
+    use super::*;
+
+    #[test]
+    fn build_report() {
+        // given
+        let part = Part::new("MFR1".to_string(), "PART1".to_string());
+        let part_mapping = PartMapping::new(&part, vec![]);
+
+        let resolved_placement = EdaPlacement {
+            ref_des: "R1".to_string(),
+            place: true,
+            ..EdaPlacement::default()
+        };
+        let disabled_placement = EdaPlacement {
+            ref_des: "R2".to_string(),
+            place: false,
+            ..EdaPlacement::default()
+        };
+        let unresolved_placement = EdaPlacement {
+            ref_des: "C1".to_string(),
+            place: true,
+            ..EdaPlacement::default()
+        };
+
+        let matched_mappings = vec![
+            PlacementPartMappingResult {
+                eda_placement: &resolved_placement,
+                mapping_result: Ok(vec![PartMappingResult {
+                    part_mapping: &part_mapping,
+                    applied_rule: Some(part_mapper::AppliedMappingRule::AutoSelected),
+                }]),
+                part: Some(&part),
+            },
+            PlacementPartMappingResult {
+                eda_placement: &disabled_placement,
+                mapping_result: Ok(vec![PartMappingResult {
+                    part_mapping: &part_mapping,
+                    applied_rule: Some(part_mapper::AppliedMappingRule::AutoSelected),
+                }]),
+                part: Some(&part),
+            },
+            PlacementPartMappingResult {
+                eda_placement: &unresolved_placement,
+                mapping_result: Err(PartMappingError::NoMappings),
+                part: None,
+            },
+        ];
+
+        // when
+        let report = BuildReport::build(
+            "Variant_1",
+            "output.csv",
+            &matched_mappings,
+            &[],
+            &EdaSubstitutionDiagnostics::default(),
+            &["R2".to_string()],
+        );
+
+        // then
+        assert_eq!(report.summary, BuildSummary {
+            placements: 3,
+            placed: 2,
+            disabled: 1,
+            resolved: 2,
+            errors: 1,
+        });
+        assert_eq!(report.disabled_ref_des, vec!["R2".to_string()]);
+        assert_eq!(report.placements[0].candidates, vec![CandidateReport {
+            part: part.clone(),
+            applied_rule: Some("Auto-selected".to_string()),
+        }]);
+        assert_eq!(report.placements[2].status, MappingStatus::NoMappings);
+        assert_eq!(report.part_counts, vec![PartCount {
+            manufacturer: "MFR1".to_string(),
+            mpn: "PART1".to_string(),
+            count: 1,
+        }]);
+    }
+
+    #[test]
+    fn escape_html_special_characters() {
+        // expect
+        assert_eq!(escape_html("<R1 & \"R2\">"), "&lt;R1 &amp; &quot;R2&quot;&gt;");
+    }
+}
@@ -0,0 +1,411 @@
+//! Validation of part mappings, substitution rules and assembly rules against a set of placements, so that broken
+//! rules can be found without building.
+
+use std::fmt::{Display, Formatter};
+
+use assembly::rules::AssemblyRule;
+use criteria::{ExactMatchCriterion, FieldCriterion, GenericCriteria, RegexMatchCriterion};
+use eda::placement::EdaPlacement;
+use eda::substitution::{EdaSubstitutionRule, EdaSubstitutor};
+use part_mapper::criteria::PlacementMappingCriteria;
+use part_mapper::part_mapping::PartMapping;
+use termtree::Tree;
+use util::dynamic::as_any::AsAny;
+
+#[derive(Debug, PartialEq)]
+pub enum RuleIssue {
+    /// The substitution rule was not applied to any placement.
+    UnreachableSubstitutionRule { rule: String },
+    /// The criteria of the substitution rule can never all match.
+    ContradictorySubstitutionCriteria { rule: String, reason: String },
+    /// The substitution rules were stopped for the placement, e.g. because they change it back and forth.
+    SubstitutionChainError { ref_des: String, reason: String },
+    /// The criteria of the part mapping can never all match.
+    ContradictoryPartMappingCriteria { part: String, reason: String },
+    /// The part mapping does not match any placement, after substitutions.
+    UnmatchedPartMapping { part: String },
+    /// More than one assembly rule for the same placement.
+    ConflictingAssemblyRules { ref_des: String },
+    /// There is no placement with the ref-des of the assembly rule.
+    UnknownAssemblyRuleRefDes { ref_des: String },
+    /// The part of the assembly rule is not mapped to the placement, so the rule can never be applied.
+    UnreachableAssemblyRule { ref_des: String, part: String },
+}
+
+impl Display for RuleIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleIssue::UnreachableSubstitutionRule {
+                rule,
+            } => write!(f, "Substitution rule never applied. rule: ({})", rule),
+            RuleIssue::ContradictorySubstitutionCriteria {
+                rule,
+                reason,
+            } => write!(
+                f,
+                "Substitution rule has contradictory criteria. rule: ({}), reason: {}",
+                rule, reason
+            ),
+            RuleIssue::SubstitutionChainError {
+                ref_des,
+                reason,
+            } => write!(
+                f,
+                "Substitution chain stopped. ref_des: {}, reason: {}",
+                ref_des, reason
+            ),
+            RuleIssue::ContradictoryPartMappingCriteria {
+                part,
+                reason,
+            } => write!(
+                f,
+                "Part mapping has contradictory criteria. part: ({}), reason: {}",
+                part, reason
+            ),
+            RuleIssue::UnmatchedPartMapping {
+                part,
+            } => write!(f, "Part mapping never matches a placement. part: ({})", part),
+            RuleIssue::ConflictingAssemblyRules {
+                ref_des,
+            } => write!(
+                f,
+                "Multiple assembly rules for the same placement. ref_des: {}",
+                ref_des
+            ),
+            RuleIssue::UnknownAssemblyRuleRefDes {
+                ref_des,
+            } => write!(f, "Assembly rule for an unknown placement. ref_des: {}", ref_des),
+            RuleIssue::UnreachableAssemblyRule {
+                ref_des,
+                part,
+            } => write!(
+                f,
+                "Assembly rule part is not mapped to the placement. ref_des: {}, part: ({})",
+                ref_des, part
+            ),
+        }
+    }
+}
+
+/// Checks the rules against the placements, the placements should be the original placements, before substitution.
+pub fn validate_rules(
+    eda_placements: &[EdaPlacement],
+    eda_substitution_rules: &[EdaSubstitutionRule],
+    part_mappings: &[PartMapping],
+    assembly_rules: &[AssemblyRule],
+) -> Vec<RuleIssue> {
+    let mut issues = vec![];
+
+    let (eda_substitution_results, eda_substitution_diagnostics) =
+        EdaSubstitutor::substitute_with_diagnostics(eda_placements, eda_substitution_rules);
+
+    for (ref_des, chain_error) in eda_substitution_diagnostics.chain_errors {
+        issues.push(RuleIssue::SubstitutionChainError {
+            ref_des,
+            reason: chain_error.to_string(),
+        });
+    }
+
+    for rule in eda_substitution_rules.iter() {
+        if let Some(reason) = find_contradiction(&rule.criteria) {
+            issues.push(RuleIssue::ContradictorySubstitutionCriteria {
+                rule: rule.format_criteria(),
+                reason,
+            });
+        }
+
+        let applied = eda_substitution_results
+            .iter()
+            .flat_map(|result| result.chain.iter())
+            .any(|chain_entry| std::ptr::eq(chain_entry.rule, rule));
+        if !applied {
+            issues.push(RuleIssue::UnreachableSubstitutionRule {
+                rule: rule.format_criteria(),
+            });
+        }
+    }
+
+    let substituted_placements: Vec<&EdaPlacement> = eda_substitution_results
+        .iter()
+        .map(|result| &result.resulting_placement)
+        .collect();
+
+    for part_mapping in part_mappings.iter() {
+        for criteria in part_mapping.criteria.iter() {
+            let reason = (**criteria)
+                .as_any()
+                .downcast_ref::<GenericCriteria>()
+                .and_then(|generic_criteria| find_contradiction(&generic_criteria.criteria));
+
+            if let Some(reason) = reason {
+                issues.push(RuleIssue::ContradictoryPartMappingCriteria {
+                    part: format_part_mapping(part_mapping),
+                    reason,
+                });
+            }
+        }
+
+        let matched = substituted_placements
+            .iter()
+            .any(|placement| {
+                part_mapping
+                    .criteria
+                    .iter()
+                    .any(|criteria| criteria.matches(placement))
+            });
+        if !matched {
+            issues.push(RuleIssue::UnmatchedPartMapping {
+                part: format_part_mapping(part_mapping),
+            });
+        }
+    }
+
+    for (index, assembly_rule) in assembly_rules.iter().enumerate() {
+        let is_first_for_ref_des = !assembly_rules[..index]
+            .iter()
+            .any(|other| other.ref_des == assembly_rule.ref_des);
+        let is_conflicting = assembly_rules[index + 1..]
+            .iter()
+            .any(|other| other.ref_des == assembly_rule.ref_des);
+        if is_first_for_ref_des && is_conflicting {
+            issues.push(RuleIssue::ConflictingAssemblyRules {
+                ref_des: assembly_rule.ref_des.clone(),
+            });
+        }
+
+        let Some(placement) = substituted_placements
+            .iter()
+            .find(|placement| placement.ref_des == assembly_rule.ref_des)
+        else {
+            issues.push(RuleIssue::UnknownAssemblyRuleRefDes {
+                ref_des: assembly_rule.ref_des.clone(),
+            });
+            continue;
+        };
+
+        let mapped = part_mappings
+            .iter()
+            .any(|part_mapping| {
+                part_mapping.part.manufacturer == assembly_rule.manufacturer
+                    && part_mapping.part.mpn == assembly_rule.mpn
+                    && part_mapping
+                        .criteria
+                        .iter()
+                        .any(|criteria| criteria.matches(placement))
+            });
+        if !mapped {
+            issues.push(RuleIssue::UnreachableAssemblyRule {
+                ref_des: assembly_rule.ref_des.clone(),
+                part: format!(
+                    "manufacturer: '{}', mpn: '{}'",
+                    assembly_rule.manufacturer, assembly_rule.mpn
+                ),
+            });
+        }
+    }
+
+    issues
+}
+
+pub fn build_rule_issues_tree(issues: &[RuleIssue]) -> Tree<String> {
+    let mut tree = Tree::new("Rule Validation".to_string());
+
+    for issue in issues.iter() {
+        tree.leaves
+            .push(Tree::new(issue.to_string()));
+    }
+
+    tree
+}
+
+fn format_part_mapping(part_mapping: &PartMapping) -> String {
+    format!(
+        "manufacturer: '{}', mpn: '{}'",
+        part_mapping.part.manufacturer, part_mapping.part.mpn
+    )
+}
+
+/// Criteria can never all match when two exact-match criteria for the same field have different values, or when the
+/// value of an exact-match criterion does not match a regex-match criterion for the same field.
+fn find_contradiction(criteria: &[Box<dyn FieldCriterion>]) -> Option<String> {
+    let exact_criteria: Vec<&ExactMatchCriterion> = criteria
+        .iter()
+        .filter_map(|criterion| {
+            (**criterion)
+                .as_any()
+                .downcast_ref::<ExactMatchCriterion>()
+        })
+        .collect();
+    let regex_criteria: Vec<&RegexMatchCriterion> = criteria
+        .iter()
+        .filter_map(|criterion| {
+            (**criterion)
+                .as_any()
+                .downcast_ref::<RegexMatchCriterion>()
+        })
+        .collect();
+
+    for (index, exact_criterion) in exact_criteria.iter().enumerate() {
+        if let Some(other) = exact_criteria[index + 1..]
+            .iter()
+            .find(|other| {
+                other.field_name == exact_criterion.field_name && other.field_pattern != exact_criterion.field_pattern
+            })
+        {
+            return Some(format!("({}) and ({})", exact_criterion, other));
+        }
+
+        if let Some(regex_criterion) = regex_criteria
+            .iter()
+            .find(|regex_criterion| {
+                regex_criterion.field_name == exact_criterion.field_name
+                    && !regex_criterion
+                        .field_pattern
+                        .is_match(&exact_criterion.field_pattern)
+            })
+        {
+            return Some(format!("({}) and ({})", exact_criterion, regex_criterion));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use eda::placement::EdaPlacementField;
+    use eda::substitution::EdaSubstitutionRuleTransformItem;
+    use pnp::part::Part;
+    use regex::Regex;
+
+    use super::*;
+
+    fn placement(ref_des: &str, name: &str) -> EdaPlacement {
+        EdaPlacement {
+            ref_des: ref_des.to_string(),
+            place: true,
+            fields: vec![EdaPlacementField::new("name".to_string(), name.to_string())],
+            ..EdaPlacement::default()
+        }
+    }
+
+    fn name_criteria(name: &str) -> Box<dyn PlacementMappingCriteria> {
+        Box::new(GenericCriteria {
+            criteria: vec![Box::new(ExactMatchCriterion::new("name".to_string(), name.to_string()))],
+        })
+    }
+
+    #[test]
+    fn valid_rules() {
+        // given
+        let eda_placements = vec![placement("R1", "RES_1K")];
+        let part = Part::new("MFR1".to_string(), "PART1".to_string());
+        let part_mappings = vec![PartMapping::new(&part, vec![name_criteria("RES_1K")])];
+        let assembly_rules = vec![AssemblyRule {
+            ref_des: "R1".to_string(),
+            manufacturer: "MFR1".to_string(),
+            mpn: "PART1".to_string(),
+        }];
+
+        // when
+        let issues = validate_rules(&eda_placements, &[], &part_mappings, &assembly_rules);
+
+        // then
+        assert_eq!(issues, vec![]);
+    }
+
+    #[test]
+    fn substituted_placements_are_matched() {
+        // given
+        let eda_placements = vec![placement("R1", "RES_1K_0402")];
+        let eda_substitution_rules = vec![EdaSubstitutionRule {
+            criteria: vec![Box::new(ExactMatchCriterion::new(
+                "name".to_string(),
+                "RES_1K_0402".to_string(),
+            ))],
+            transforms: vec![EdaSubstitutionRuleTransformItem {
+                field_name: "name".to_string(),
+                field_value: "RES_1K".to_string(),
+            }],
+        }];
+        let part = Part::new("MFR1".to_string(), "PART1".to_string());
+        let part_mappings = vec![PartMapping::new(&part, vec![name_criteria("RES_1K")])];
+
+        // when
+        let issues = validate_rules(&eda_placements, &eda_substitution_rules, &part_mappings, &[]);
+
+        // then
+        assert_eq!(issues, vec![]);
+    }
+
+    #[test]
+    fn invalid_rules() {
+        // given
+        let eda_placements = vec![placement("R1", "RES_1K")];
+        let eda_substitution_rules = vec![EdaSubstitutionRule {
+            criteria: vec![
+                Box::new(ExactMatchCriterion::new("name".to_string(), "RES_1K".to_string())),
+                Box::new(RegexMatchCriterion::new(
+                    "name".to_string(),
+                    Regex::new("^CAP_.*").unwrap(),
+                )),
+            ],
+            transforms: vec![],
+        }];
+        let part1 = Part::new("MFR1".to_string(), "PART1".to_string());
+        let part2 = Part::new("MFR2".to_string(), "PART2".to_string());
+        let part_mappings = vec![
+            PartMapping::new(&part1, vec![name_criteria("RES_1K")]),
+            PartMapping::new(&part2, vec![name_criteria("RES_2K")]),
+        ];
+        let assembly_rules = vec![
+            AssemblyRule {
+                ref_des: "R1".to_string(),
+                manufacturer: "MFR2".to_string(),
+                mpn: "PART2".to_string(),
+            },
+            AssemblyRule {
+                ref_des: "R1".to_string(),
+                manufacturer: "MFR1".to_string(),
+                mpn: "PART1".to_string(),
+            },
+            AssemblyRule {
+                ref_des: "R2".to_string(),
+                manufacturer: "MFR1".to_string(),
+                mpn: "PART1".to_string(),
+            },
+        ];
+
+        // when
+        let issues = validate_rules(
+            &eda_placements,
+            &eda_substitution_rules,
+            &part_mappings,
+            &assembly_rules,
+        );
+
+        // then
+        assert_eq!(issues, vec![
+            RuleIssue::ContradictorySubstitutionCriteria {
+                rule: "name_pattern: 'RES_1K', name_pattern: '^CAP_.*'".to_string(),
+                reason: "(name_pattern: 'RES_1K') and (name_pattern: '^CAP_.*')".to_string(),
+            },
+            RuleIssue::UnreachableSubstitutionRule {
+                rule: "name_pattern: 'RES_1K', name_pattern: '^CAP_.*'".to_string(),
+            },
+            RuleIssue::UnmatchedPartMapping {
+                part: "manufacturer: 'MFR2', mpn: 'PART2'".to_string(),
+            },
+            RuleIssue::ConflictingAssemblyRules {
+                ref_des: "R1".to_string(),
+            },
+            RuleIssue::UnreachableAssemblyRule {
+                ref_des: "R1".to_string(),
+                part: "manufacturer: 'MFR2', mpn: 'PART2'".to_string(),
+            },
+            RuleIssue::UnknownAssemblyRuleRefDes {
+                ref_des: "R2".to_string(),
+            },
+        ]);
+    }
+}
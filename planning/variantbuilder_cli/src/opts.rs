@@ -1,11 +1,12 @@
 use std::path::PathBuf;
 
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use clap_verbosity_flag::{InfoLevel, Verbosity};
-use cli::args::EdaToolArg;
+use cli::args::{EdaToolArg, UnitSystemArg};
 use thiserror::Error;
 use variantbuilder_app::{
-    AssemblyRuleSource, AssemblyVariant, EdaSubstitutionsSource, Event, LoadOutSource, PartsSource, PlacementsSource,
+    AssemblyRuleSource, AssemblyVariant, EdaSubstitutionsSource, Event, LoadOutSource, Part, PartsSource,
+    PlacementsSource, ReportFormat,
 };
 
 #[derive(Parser)]
@@ -48,6 +49,42 @@ pub fn build_assembly_variant(&self) -> Result<AssemblyVariant, AssemblyVariantE
     }
 }
 
+/// Parses an assembly variant in the form `NAME:REF_DES,REF_DES,...`, e.g. `Variant_1:R1,R2,C1`.
+fn parse_assembly_variant(value: &str) -> Result<AssemblyVariant, String> {
+    let (name, ref_des_list) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected 'NAME:REF_DES,REF_DES,...', found '{}'", value))?;
+
+    if name.is_empty() {
+        return Err("the name of the assembly variant is required".to_string());
+    }
+
+    let ref_des_list = ref_des_list
+        .split(',')
+        .map(str::trim)
+        .filter(|ref_des| !ref_des.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    Ok(AssemblyVariant::new(name.to_string(), ref_des_list))
+}
+
+#[derive(Debug, Clone)]
+#[derive(ValueEnum)]
+pub enum ReportFormatArg {
+    Json,
+    Html,
+}
+
+impl From<ReportFormatArg> for ReportFormat {
+    fn from(value: ReportFormatArg) -> Self {
+        match value {
+            ReportFormatArg::Json => ReportFormat::Json,
+            ReportFormatArg::Html => ReportFormat::Html,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 #[command(arg_required_else_help(true))]
 pub enum Command {
@@ -61,10 +98,14 @@ pub enum Command {
         #[arg(long, value_name = "SOURCE")]
         load_out: Option<LoadOutSource>,
 
-        /// Placements source
+        /// Placements source, a placements CSV file or a board file (KiCad `.kicad_pcb`, EasyEDA `.json`)
         #[arg(long, value_name = "SOURCE")]
         placements: PlacementsSource,
 
+        /// Placements unit, detected from the placements if not specified
+        #[arg(long, value_name = "UNIT")]
+        placements_unit: Option<UnitSystemArg>,
+
         /// Parts source
         #[arg(long, value_name = "SOURCE")]
         parts: PartsSource,
@@ -89,9 +130,131 @@ pub enum Command {
         #[arg(long, value_name = "FILE")]
         output: String,
 
+        /// Build report format, the report is written next to each output file
+        #[arg(long, value_name = "FORMAT")]
+        report: Option<ReportFormatArg>,
+
         #[command(flatten)]
         assembly_variant_args: Option<AssemblyVariantArgs>,
     },
+    /// Build multiple variants, with a combined report
+    BuildVariants {
+        /// EDA tool
+        #[arg(long)]
+        eda: EdaToolArg,
+
+        /// Load-out source
+        #[arg(long, value_name = "SOURCE")]
+        load_out: Option<LoadOutSource>,
+
+        /// Placements source, a placements CSV file or a board file (KiCad `.kicad_pcb`, EasyEDA `.json`)
+        #[arg(long, value_name = "SOURCE")]
+        placements: PlacementsSource,
+
+        /// Placements unit, detected from the placements if not specified
+        #[arg(long, value_name = "UNIT")]
+        placements_unit: Option<UnitSystemArg>,
+
+        /// Parts source
+        #[arg(long, value_name = "SOURCE")]
+        parts: PartsSource,
+
+        /// Part-mappings source
+        #[arg(long, value_name = "SOURCE")]
+        part_mappings: PartsSource,
+
+        /// Substitution sources
+        #[arg(long, value_delimiter = ',', num_args = 0.., value_name = "SOURCE")]
+        substitutions: Vec<EdaSubstitutionsSource>,
+
+        /// List of reference designators to disable (use for do-not-fit, no-place, test-points, fiducials, etc)
+        #[arg(long, num_args = 0.., value_delimiter = ',')]
+        ref_des_disable_list: Vec<String>,
+
+        /// Assembly rules source
+        #[arg(long, value_name = "SOURCE")]
+        assembly_rules: Option<AssemblyRuleSource>,
+
+        /// Assembly variant, as 'NAME:REF_DES,REF_DES,...', repeat for each variant
+        #[arg(long = "variant", value_name = "VARIANT", required = true, value_parser = parse_assembly_variant)]
+        variants: Vec<AssemblyVariant>,
+
+        /// Output CSV file pattern, '{variant}' is replaced with the name of each variant
+        #[arg(long, value_name = "PATTERN")]
+        output: String,
+
+        /// Build report format, the report is written next to each output file
+        #[arg(long, value_name = "FORMAT")]
+        report: Option<ReportFormatArg>,
+    },
+    /// Validate mappings, substitutions and assembly rules, without building
+    ValidateRules {
+        /// EDA tool
+        #[arg(long)]
+        eda: EdaToolArg,
+
+        /// Placements source, a placements CSV file or a board file (KiCad `.kicad_pcb`, EasyEDA `.json`)
+        #[arg(long, value_name = "SOURCE")]
+        placements: PlacementsSource,
+
+        /// Placements unit, detected from the placements if not specified
+        #[arg(long, value_name = "UNIT")]
+        placements_unit: Option<UnitSystemArg>,
+
+        /// Parts source
+        #[arg(long, value_name = "SOURCE")]
+        parts: PartsSource,
+
+        /// Part-mappings source
+        #[arg(long, value_name = "SOURCE")]
+        part_mappings: PartsSource,
+
+        /// Substitution sources
+        #[arg(long, value_delimiter = ',', num_args = 0.., value_name = "SOURCE")]
+        substitutions: Vec<EdaSubstitutionsSource>,
+
+        /// Assembly rules source
+        #[arg(long, value_name = "SOURCE")]
+        assembly_rules: Option<AssemblyRuleSource>,
+    },
+    /// Add a part mapping for a placement, e.g. for a placement with no mappings
+    AddPartMapping {
+        /// EDA tool
+        #[arg(long)]
+        eda: EdaToolArg,
+
+        /// Placements source, a placements CSV file or a board file (KiCad `.kicad_pcb`, EasyEDA `.json`)
+        #[arg(long, value_name = "SOURCE")]
+        placements: PlacementsSource,
+
+        /// Placements unit, detected from the placements if not specified
+        #[arg(long, value_name = "UNIT")]
+        placements_unit: Option<UnitSystemArg>,
+
+        /// Parts source
+        #[arg(long, value_name = "SOURCE")]
+        parts: PartsSource,
+
+        /// Part-mappings source, the mapping is added to the existing mappings
+        #[arg(long, value_name = "SOURCE")]
+        part_mappings: PartsSource,
+
+        /// Substitution sources
+        #[arg(long, value_delimiter = ',', num_args = 0.., value_name = "SOURCE")]
+        substitutions: Vec<EdaSubstitutionsSource>,
+
+        /// Reference designator of the placement
+        #[arg(long)]
+        ref_des: String,
+
+        /// Manufacturer of the part
+        #[arg(long)]
+        manufacturer: String,
+
+        /// Manufacturer part number of the part
+        #[arg(long)]
+        mpn: String,
+    },
 }
 
 #[derive(Error, Debug)]
@@ -110,6 +273,7 @@ fn try_from(ops: Opts) -> Result<Self, Self::Error> {
             Command::Build {
                 eda,
                 placements,
+                placements_unit,
                 assembly_variant_args,
                 parts,
                 part_mappings,
@@ -118,6 +282,7 @@ fn try_from(ops: Opts) -> Result<Self, Self::Error> {
                 assembly_rules,
                 output,
                 ref_des_disable_list,
+                report,
             } => {
                 let eda_tool = eda.build();
                 let assembly_variant = assembly_variant_args
@@ -128,6 +293,7 @@ fn try_from(ops: Opts) -> Result<Self, Self::Error> {
                 let event = Event::Build {
                     eda_tool,
                     placements,
+                    placements_unit: placements_unit.map(Into::into),
                     assembly_variant,
                     parts,
                     part_mappings,
@@ -136,6 +302,83 @@ fn try_from(ops: Opts) -> Result<Self, Self::Error> {
                     assembly_rules,
                     output,
                     ref_des_disable_list,
+                    report: report.map(Into::into),
+                };
+
+                Ok(event)
+            }
+            Command::BuildVariants {
+                eda,
+                placements,
+                placements_unit,
+                parts,
+                part_mappings,
+                substitutions,
+                load_out,
+                assembly_rules,
+                ref_des_disable_list,
+                variants,
+                output,
+                report,
+            } => {
+                let event = Event::BuildVariants {
+                    eda_tool: eda.build(),
+                    placements,
+                    placements_unit: placements_unit.map(Into::into),
+                    assembly_variants: variants,
+                    parts,
+                    part_mappings,
+                    substitutions,
+                    load_out,
+                    assembly_rules,
+                    output_pattern: output,
+                    ref_des_disable_list,
+                    report: report.map(Into::into),
+                };
+
+                Ok(event)
+            }
+            Command::ValidateRules {
+                eda,
+                placements,
+                placements_unit,
+                parts,
+                part_mappings,
+                substitutions,
+                assembly_rules,
+            } => {
+                let event = Event::ValidateRules {
+                    eda_tool: eda.build(),
+                    placements,
+                    placements_unit: placements_unit.map(Into::into),
+                    parts,
+                    part_mappings,
+                    substitutions,
+                    assembly_rules,
+                };
+
+                Ok(event)
+            }
+            Command::AddPartMapping {
+                eda,
+                placements,
+                placements_unit,
+                parts,
+                part_mappings,
+                substitutions,
+                ref_des,
+                manufacturer,
+                mpn,
+            } => {
+                let event = Event::AddPartMapping {
+                    eda_tool: eda.build(),
+                    placements,
+                    placements_unit: placements_unit.map(Into::into),
+                    parts,
+                    part_mappings,
+                    substitutions,
+                    ref_des,
+                    part: Part::new(manufacturer, mpn),
                 };
 
                 Ok(event)
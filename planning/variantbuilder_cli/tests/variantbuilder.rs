@@ -1091,8 +1091,11 @@ fn no_args() {
             Usage: variantbuilder_cli [OPTIONS] <COMMAND>
 
             Commands:
-              build  Build variant
-              help   Print this message or the help of the given subcommand(s)
+              build             Build variant
+              build-variants    Build multiple variants, with a combined report
+              validate-rules    Validate mappings, substitutions and assembly rules, without building
+              add-part-mapping  Add a part mapping for a placement, e.g. for a placement with no mappings
+              help              Print this message or the help of the given subcommand(s)
 
             Options:
                   --trace [<TRACE>]  Trace log file
@@ -1135,13 +1138,15 @@ fn help_for_build_subcommand() {
                   --load-out <SOURCE>
                       Load-out source
                   --placements <SOURCE>
-                      Placements source
+                      Placements source, a placements CSV file or a board file (KiCad `.kicad_pcb`, EasyEDA `.json`)
               -v, --verbose...
                       Increase logging verbosity
-                  --parts <SOURCE>
-                      Parts source
+                  --placements-unit <UNIT>
+                      Placements unit, detected from the placements if not specified [possible values: mm, mil, inch]
               -q, --quiet...
                       Decrease logging verbosity
+                  --parts <SOURCE>
+                      Parts source
                   --part-mappings <SOURCE>
                       Part-mappings source
                   --substitutions [<SOURCE>...]
@@ -1152,6 +1157,8 @@ fn help_for_build_subcommand() {
                       Assembly rules source
                   --output <FILE>
                       Output CSV file
+                  --report <FORMAT>
+                      Build report format, the report is written next to each output file [possible values: json, html]
                   --name <NAME>
                       Name of assembly variant [default: Default]
                   --ref-des-list [<REF_DES_LIST>...]
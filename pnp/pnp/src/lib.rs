@@ -10,3 +10,4 @@
 pub mod reference;
 
 pub mod panel;
+pub mod transform;
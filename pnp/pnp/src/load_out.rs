@@ -1,4 +1,7 @@
-use crate::part::Part;
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::part::{Part, PartAliases};
 use crate::reference::Reference;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -11,6 +14,28 @@ pub struct LoadOutItem {
     // FUTURE consider using 'Part' here instead of these two fields.
     pub manufacturer: String,
     pub mpn: String,
+
+    /// The name of the package, see [`crate::package::Package`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub package: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub feeder_type: Option<FeederType>,
+    /// Millimeters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub tape_width: Option<Decimal>,
+    /// Millimeters, the distance between parts in the tape
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub tape_pitch: Option<Decimal>,
+    /// Degrees, positive is anti-clockwise.
+    ///
+    /// The rotation of the parts in the feeder, added to the placement rotation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub rotation_offset: Option<Decimal>,
 }
 
 impl LoadOutItem {
@@ -19,10 +44,23 @@ pub fn new(reference: Option<Reference>, manufacturer: String, mpn: String) -> S
             reference,
             manufacturer,
             mpn,
+            package: None,
+            feeder_type: None,
+            tape_width: None,
+            tape_pitch: None,
+            rotation_offset: None,
         }
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum FeederType {
+    Tape,
+    Tray,
+    Tube,
+}
+
 pub fn find_load_out_item_by_part<'load_out>(
     load_out_items: &'load_out [LoadOutItem],
     part: &Part,
@@ -37,3 +75,184 @@ pub fn find_load_out_item_by_part<'load_out>(
         });
     matched_item
 }
+
+/// As [`find_load_out_item_by_part`], but if the part is not in the load-out an item for one of its aliases is returned.
+pub fn find_load_out_item_by_part_or_alias<'load_out>(
+    load_out_items: &'load_out [LoadOutItem],
+    part: &Part,
+    part_aliases: &PartAliases,
+) -> Option<&'load_out LoadOutItem> {
+    part_aliases
+        .aliases(part)
+        .find_map(|alias| find_load_out_item_by_part(load_out_items, alias))
+}
+
+/// A bank of feeder slots on a machine.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct FeederBank {
+    pub reference: Reference,
+
+    /// Millimeters, the position of the bank in machine coordinates.
+    pub x: Decimal,
+    /// Millimeters, the position of the bank in machine coordinates.
+    pub y: Decimal,
+
+    pub slots: Vec<FeederSlot>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct FeederSlot {
+    /// The feeder reference, as used by [`LoadOutItem::reference`].
+    pub reference: Reference,
+
+    /// Millimeters, the pick position, relative to the bank.
+    pub x: Decimal,
+    /// Millimeters, the pick position, relative to the bank.
+    pub y: Decimal,
+
+    /// Millimeters, the widest tape that fits the slot.
+    pub width: Decimal,
+}
+
+impl FeederBank {
+    /// Returns the pick position of the slot in machine coordinates.
+    pub fn slot_position(&self, slot: &FeederSlot) -> (Decimal, Decimal) {
+        (self.x + slot.x, self.y + slot.y)
+    }
+}
+
+pub fn find_feeder_slot<'bank>(
+    feeder_banks: &'bank [FeederBank],
+    feeder_reference: &Reference,
+) -> Option<(&'bank FeederBank, &'bank FeederSlot)> {
+    feeder_banks.iter().find_map(|bank| {
+        bank.slots
+            .iter()
+            .find(|slot| slot.reference.eq(feeder_reference))
+            .map(|slot| (bank, slot))
+    })
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum FeederLayoutIssue {
+    #[error("No feeder slot for feeder reference. reference: '{reference}'")]
+    UnknownFeederSlot { reference: Reference },
+
+    #[error(
+        "Tape too wide for feeder slot. reference: '{reference}', tape_width: {tape_width}, slot_width: {slot_width}"
+    )]
+    TapeTooWide {
+        reference: Reference,
+        tape_width: Decimal,
+        slot_width: Decimal,
+    },
+}
+
+/// Checks that each load-out item with a feeder reference maps to a feeder slot, and that its tape fits the slot.
+///
+/// Items without a feeder reference are ignored.
+pub fn validate_feeder_layout(feeder_banks: &[FeederBank], load_out_items: &[LoadOutItem]) -> Vec<FeederLayoutIssue> {
+    load_out_items
+        .iter()
+        .filter_map(|item| {
+            let reference = item.reference.as_ref()?;
+
+            let Some((_bank, slot)) = find_feeder_slot(feeder_banks, reference) else {
+                return Some(FeederLayoutIssue::UnknownFeederSlot {
+                    reference: reference.clone(),
+                });
+            };
+
+            match item.tape_width {
+                Some(tape_width) if tape_width > slot.width => Some(FeederLayoutIssue::TapeTooWide {
+                    reference: reference.clone(),
+                    tape_width,
+                    slot_width: slot.width,
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod feeder_layout_tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn build_feeder_banks() -> Vec<FeederBank> {
+        vec![FeederBank {
+            reference: Reference::from_raw_str("FRONT"),
+            x: dec!(100),
+            y: dec!(10),
+            slots: vec![
+                FeederSlot {
+                    reference: Reference::from_raw_str("F1"),
+                    x: dec!(0),
+                    y: dec!(0),
+                    width: dec!(8),
+                },
+                FeederSlot {
+                    reference: Reference::from_raw_str("F2"),
+                    x: dec!(10),
+                    y: dec!(0),
+                    width: dec!(12),
+                },
+            ],
+        }]
+    }
+
+    #[test]
+    pub fn slot_position() {
+        // given
+        let feeder_banks = build_feeder_banks();
+
+        // when
+        let (bank, slot) = find_feeder_slot(&feeder_banks, &Reference::from_raw_str("F2")).unwrap();
+
+        // then
+        assert_eq!(bank.slot_position(slot), (dec!(110), dec!(10)));
+    }
+
+    #[test]
+    pub fn validate() {
+        // given
+        let feeder_banks = build_feeder_banks();
+
+        let build_item = |reference: Option<&str>, tape_width: Option<Decimal>| LoadOutItem {
+            tape_width,
+            ..LoadOutItem::new(
+                reference.map(Reference::from_raw_str),
+                "MFR1".to_string(),
+                "PART1".to_string(),
+            )
+        };
+
+        let load_out_items = vec![
+            build_item(Some("F1"), Some(dec!(8))),
+            build_item(Some("F1"), Some(dec!(12))),
+            build_item(Some("F2"), None),
+            build_item(Some("F3"), Some(dec!(8))),
+            build_item(None, Some(dec!(16))),
+        ];
+
+        // when
+        let issues = validate_feeder_layout(&feeder_banks, &load_out_items);
+
+        // then
+        assert_eq!(issues, vec![
+            FeederLayoutIssue::TapeTooWide {
+                reference: Reference::from_raw_str("F1"),
+                tape_width: dec!(12),
+                slot_width: dec!(8),
+            },
+            FeederLayoutIssue::UnknownFeederSlot {
+                reference: Reference::from_raw_str("F3"),
+            },
+        ]);
+    }
+}
@@ -7,8 +7,11 @@
 use thiserror::Error;
 
 use crate::object_path::leading_keys::leading_keys;
+pub use crate::object_path::query::{ObjectPathPattern, ObjectPathQuery};
 use crate::placement::RefDes;
 
+pub mod query;
+
 // TODO consider if unit paths should use zero-based index
 //      * there is a lot of +1 -1 in the codebase
 //      * greater potential for error
@@ -102,7 +105,7 @@ fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 ///
 /// Currently, there are example where wildcards are used to search for objects, e.g. `pcb=.*::unit=.*::ref_des=R1`
 /// however this object is not for STORING such patterns, but the string representation of a path
-/// can be compared to such a pattern.
+/// can be compared to such a pattern.  See [`ObjectPathPattern`] and [`ObjectPathQuery`].
 #[derive(Debug, Clone, DeserializeFromStr, SerializeDisplay, Eq, PartialEq, Default, Hash)]
 pub struct ObjectPath {
     // FUTURE consider if it's better/simpler to use a HashMap here.
@@ -0,0 +1,383 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use regex::Regex;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+
+use crate::object_path::ObjectPath;
+
+/// Selects object paths, either with a regular expression that is matched against the string representation of the
+/// object path, with an [`ObjectPathQuery`], or from a set of object paths.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum ObjectPathPattern {
+    Regex(#[serde(with = "serde_regex")] Regex),
+    Query(ObjectPathQuery),
+    /// An explicit set of object paths, e.g. a previously resolved selection, matching is a lookup.
+    Paths(BTreeSet<ObjectPath>),
+}
+
+impl ObjectPathPattern {
+    /// A pattern that only matches the given object path.
+    pub fn exact(object_path: &ObjectPath) -> Self {
+        Self::Regex(Regex::new(&format!("^{}$", regex::escape(&object_path.to_string()))).unwrap())
+    }
+
+    pub fn is_match(&self, object_path: &ObjectPath) -> bool {
+        match self {
+            ObjectPathPattern::Regex(regex) => regex.is_match(&object_path.to_string()),
+            ObjectPathPattern::Query(query) => query.is_match(object_path),
+            ObjectPathPattern::Paths(object_paths) => object_paths.contains(object_path),
+        }
+    }
+}
+
+impl Display for ObjectPathPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectPathPattern::Regex(regex) => write!(f, "{}", regex),
+            ObjectPathPattern::Query(query) => write!(f, "{}", query),
+            // the set can be large, e.g. thousands of placements
+            ObjectPathPattern::Paths(object_paths) => write!(f, "paths({})", object_paths.len()),
+        }
+    }
+}
+
+impl PartialEq for ObjectPathPattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ObjectPathPattern::Regex(a), ObjectPathPattern::Regex(b)) => a.as_str() == b.as_str(),
+            (ObjectPathPattern::Query(a), ObjectPathPattern::Query(b)) => a == b,
+            (ObjectPathPattern::Paths(a), ObjectPathPattern::Paths(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl From<Regex> for ObjectPathPattern {
+    fn from(value: Regex) -> Self {
+        Self::Regex(value)
+    }
+}
+
+impl From<ObjectPathQuery> for ObjectPathPattern {
+    fn from(value: ObjectPathQuery) -> Self {
+        Self::Query(value)
+    }
+}
+
+/// A structured object path matcher, with a constraint for each field.
+///
+/// `pcb=<index>, unit=<index>, ref_des=<glob>`
+///
+/// <index> = a number, an inclusive range (`3..8`, `3..`, `..8`) or `*`.
+/// <glob> = a ref_des, `*` matches any sequence of characters, `?` matches a single character.
+///
+/// Fields may be omitted, omitted fields match anything, `refdes` is accepted as an alias for `ref_des`.
+///
+/// examples:
+///
+/// `pcb=1` (all objects of the first pcb instance)
+/// `pcb=1, unit=3..8, ref_des=R*` (resistors on units 3 to 8 of the first pcb instance)
+/// `ref_des=C?` (capacitors with a single digit ref_des on all units of all pcb instances)
+#[derive(Debug, Clone, Default, Eq, PartialEq, DeserializeFromStr, SerializeDisplay)]
+pub struct ObjectPathQuery {
+    pub pcb: Option<IndexConstraint>,
+    pub unit: Option<IndexConstraint>,
+    pub ref_des: Option<GlobPattern>,
+}
+
+impl ObjectPathQuery {
+    pub fn is_match(&self, object_path: &ObjectPath) -> bool {
+        if let Some(constraint) = &self.pcb {
+            match object_path.pcb_instance() {
+                Ok(pcb_instance) if constraint.is_match(pcb_instance) => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(constraint) = &self.unit {
+            match object_path.pcb_unit() {
+                Ok(pcb_unit) if constraint.is_match(pcb_unit) => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(pattern) = &self.ref_des {
+            match object_path.find_chunk_by_key("ref_des") {
+                Some(chunk) if pattern.is_match(&chunk.value) => {}
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+impl FromStr for ObjectPathQuery {
+    type Err = ObjectPathQueryError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut query = ObjectPathQuery::default();
+
+        for constraint in value.split(',') {
+            let constraint = constraint.trim();
+            let (key, value) = constraint
+                .split_once('=')
+                .ok_or(ObjectPathQueryError::InvalidConstraint(constraint.to_string()))?;
+            let (key, value) = (key.trim(), value.trim());
+
+            let duplicate = match key {
+                "pcb" => query
+                    .pcb
+                    .replace(IndexConstraint::from_str(value)?)
+                    .is_some(),
+                "unit" => query
+                    .unit
+                    .replace(IndexConstraint::from_str(value)?)
+                    .is_some(),
+                "ref_des" | "refdes" => query
+                    .ref_des
+                    .replace(GlobPattern::from_str(value)?)
+                    .is_some(),
+                _ => return Err(ObjectPathQueryError::UnknownKey(key.to_string())),
+            };
+
+            if duplicate {
+                return Err(ObjectPathQueryError::DuplicateKey(key.to_string()));
+            }
+        }
+
+        Ok(query)
+    }
+}
+
+impl Display for ObjectPathQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut chunks: Vec<String> = vec![];
+        if let Some(constraint) = &self.pcb {
+            chunks.push(format!("pcb={}", constraint));
+        }
+        if let Some(constraint) = &self.unit {
+            chunks.push(format!("unit={}", constraint));
+        }
+        if let Some(pattern) = &self.ref_des {
+            chunks.push(format!("ref_des={}", pattern));
+        }
+
+        write!(f, "{}", chunks.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum IndexConstraint {
+    Any,
+    Exact(u16),
+    /// Inclusive, `None` for an open end.
+    Range(Option<u16>, Option<u16>),
+}
+
+impl IndexConstraint {
+    pub fn is_match(&self, index: u16) -> bool {
+        match self {
+            IndexConstraint::Any => true,
+            IndexConstraint::Exact(value) => index == *value,
+            IndexConstraint::Range(start, end) => {
+                start.is_none_or(|start| index >= start) && end.is_none_or(|end| index <= end)
+            }
+        }
+    }
+}
+
+impl FromStr for IndexConstraint {
+    type Err = ObjectPathQueryError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let parse_index = |index: &str| -> Result<u16, ObjectPathQueryError> {
+            match index.parse::<u16>() {
+                Ok(index) if index > 0 => Ok(index),
+                _ => Err(ObjectPathQueryError::InvalidIndex(value.to_string())),
+            }
+        };
+
+        if value == "*" {
+            return Ok(IndexConstraint::Any);
+        }
+
+        match value.split_once("..") {
+            Some((start, end)) => {
+                let start = (!start.is_empty())
+                    .then(|| parse_index(start))
+                    .transpose()?;
+                let end = (!end.is_empty())
+                    .then(|| parse_index(end))
+                    .transpose()?;
+
+                if let (Some(start), Some(end)) = (start, end) {
+                    if start > end {
+                        return Err(ObjectPathQueryError::InvalidIndex(value.to_string()));
+                    }
+                }
+
+                Ok(IndexConstraint::Range(start, end))
+            }
+            None => Ok(IndexConstraint::Exact(parse_index(value)?)),
+        }
+    }
+}
+
+impl Display for IndexConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndexConstraint::Any => write!(f, "*"),
+            IndexConstraint::Exact(value) => write!(f, "{}", value),
+            IndexConstraint::Range(start, end) => {
+                if let Some(start) = start {
+                    write!(f, "{}", start)?;
+                }
+                write!(f, "..")?;
+                if let Some(end) = end {
+                    write!(f, "{}", end)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A glob pattern, `*` matches any sequence of characters, `?` matches a single character.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GlobPattern(String);
+
+impl GlobPattern {
+    pub fn is_match(&self, value: &str) -> bool {
+        let pattern: Vec<char> = self.0.chars().collect();
+        let value: Vec<char> = value.chars().collect();
+
+        let (mut pattern_index, mut value_index) = (0, 0);
+        // the position of the last `*` in the pattern, and the position in the value when it was encountered
+        let mut backtrack: Option<(usize, usize)> = None;
+
+        while value_index < value.len() {
+            match pattern.get(pattern_index) {
+                Some('*') => {
+                    backtrack = Some((pattern_index, value_index));
+                    pattern_index += 1;
+                }
+                Some(&c) if c == '?' || c == value[value_index] => {
+                    pattern_index += 1;
+                    value_index += 1;
+                }
+                _ => match backtrack {
+                    // let the `*` consume one more character
+                    Some((star_index, star_value_index)) => {
+                        pattern_index = star_index + 1;
+                        value_index = star_value_index + 1;
+                        backtrack = Some((star_index, value_index));
+                    }
+                    None => return false,
+                },
+            }
+        }
+
+        pattern[pattern_index..]
+            .iter()
+            .all(|&c| c == '*')
+    }
+}
+
+impl FromStr for GlobPattern {
+    type Err = ObjectPathQueryError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.is_empty() {
+            return Err(ObjectPathQueryError::EmptyPattern);
+        }
+        Ok(Self(value.to_string()))
+    }
+}
+
+impl Display for GlobPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ObjectPathQueryError {
+    #[error("Invalid constraint, expected 'key=value'. constraint: '{0}'")]
+    InvalidConstraint(String),
+    #[error("Unknown key, expected 'pcb', 'unit' or 'ref_des'. key: '{0}'")]
+    UnknownKey(String),
+    #[error("Duplicate key. key: '{0}'")]
+    DuplicateKey(String),
+    #[error("Invalid index, expected a number greater than zero, a range or '*'. value: '{0}'")]
+    InvalidIndex(String),
+    #[error("Empty pattern")]
+    EmptyPattern,
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("pcb=1", true)]
+    #[case("pcb=2", false)]
+    #[case("pcb=*, unit=*", true)]
+    #[case("pcb=1, unit=3..8, refdes=R*", true)]
+    #[case("pcb=1, unit=3..", true)]
+    #[case("pcb=1, unit=..2", false)]
+    #[case("unit=4..8", false)]
+    #[case("ref_des=R?", true)]
+    #[case("ref_des=R??", false)]
+    #[case("ref_des=*1", true)]
+    #[case("ref_des=C*", false)]
+    pub fn is_match(#[case] query: &str, #[case] expected_result: bool) {
+        // given
+        let object_path = ObjectPath::from_str("pcb=1::unit=3::ref_des=R1").expect("always ok");
+        let query = ObjectPathQuery::from_str(query).expect("ok");
+
+        // expect
+        assert_eq!(query.is_match(&object_path), expected_result);
+    }
+
+    #[rstest]
+    #[case("pcb", ObjectPathQueryError::InvalidConstraint("pcb".to_string()))]
+    #[case("panel=1", ObjectPathQueryError::UnknownKey("panel".to_string()))]
+    #[case("pcb=1, pcb=2", ObjectPathQueryError::DuplicateKey("pcb".to_string()))]
+    #[case("pcb=0", ObjectPathQueryError::InvalidIndex("0".to_string()))]
+    #[case("unit=8..3", ObjectPathQueryError::InvalidIndex("8..3".to_string()))]
+    #[case("unit=a..b", ObjectPathQueryError::InvalidIndex("a..b".to_string()))]
+    #[case("ref_des=", ObjectPathQueryError::EmptyPattern)]
+    pub fn from_str_errors(#[case] query: &str, #[case] expected_error: ObjectPathQueryError) {
+        // expect
+        assert_eq!(ObjectPathQuery::from_str(query), Err(expected_error));
+    }
+
+    #[test]
+    pub fn display() {
+        // given
+        let query = ObjectPathQuery::from_str("refdes=R*,unit=3..8,pcb=1").expect("ok");
+
+        // expect
+        assert_eq!(query.to_string(), "pcb=1, unit=3..8, ref_des=R*");
+    }
+
+    #[test]
+    pub fn exact_pattern() {
+        // given
+        let object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R1").expect("always ok");
+        let other_object_path = ObjectPath::from_str("pcb=1::unit=1::ref_des=R10").expect("always ok");
+
+        // when
+        let pattern = ObjectPathPattern::exact(&object_path);
+
+        // then
+        assert!(pattern.is_match(&object_path));
+        assert!(!pattern.is_match(&other_object_path));
+    }
+}
@@ -28,6 +28,12 @@ pub struct Package {
     /// Includes terminals (i.e. not just the body size)
     pub dimensions_mm: Option<PackageDimensions>,
 
+    /// The direction of pin 1, from the center of the package, when the package is at 0 degrees rotation.
+    ///
+    /// In degrees, counter-clockwise from the positive X axis, e.g. 135 when pin 1 is at the top-left.
+    /// Used to correct the rotation of placements where EDA and machine conventions differ.
+    pub pin1_orientation_degrees: Option<Decimal>,
+
     //
     // Standardized identifiers
     //
@@ -68,6 +74,7 @@ pub fn new(name: String) -> Self {
             lead_count: None,
             lead_pitch_mm: None,
             dimensions_mm: None,
+            pin1_orientation_degrees: None,
             generic_shorthand: None,
             eia_imperial_code: None,
             eia_metric_code: None,
@@ -79,6 +86,19 @@ pub fn new(name: String) -> Self {
         }
     }
 
+    /// Returns `Some(true)` if the package can be placed the wrong way around, i.e. it has a pin 1 orientation or
+    /// more than 2 leads, `Some(false)` for 2-lead packages without a pin 1 orientation and `None` when unknown.
+    ///
+    /// Note: 2-lead polarized packages (diodes, electrolytic capacitors, LEDs) need a pin 1 orientation to be flagged.
+    pub fn is_polarity_sensitive(&self) -> Option<bool> {
+        if self.pin1_orientation_degrees.is_some() {
+            return Some(true);
+        }
+
+        self.lead_count
+            .map(|lead_count| lead_count > 2)
+    }
+
     pub fn with_lead_count(mut self, lead_count: u32) -> Self {
         self.lead_count = Some(lead_count);
         self
@@ -94,6 +114,11 @@ pub fn with_pitch(mut self, pitch_mm: Decimal) -> Self {
         self
     }
 
+    pub fn with_pin1_orientation(mut self, pin1_orientation_degrees: Decimal) -> Self {
+        self.pin1_orientation_degrees = Some(pin1_orientation_degrees);
+        self
+    }
+
     pub fn with_generic_shorthand(mut self, generic_shorthand: String) -> Self {
         self.generic_shorthand = Some(generic_shorthand);
         self
@@ -173,6 +198,7 @@ fn default() -> Self {
             lead_count: None,
             dimensions_mm: None,
             lead_pitch_mm: None,
+            pin1_orientation_degrees: None,
             generic_shorthand: None,
             eia_imperial_code: None,
             eia_metric_code: None,
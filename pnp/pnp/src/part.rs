@@ -1,3 +1,10 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+
 #[derive(Debug, Clone)]
 #[derive(Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -24,3 +31,260 @@ fn default() -> Self {
         }
     }
 }
+
+pub type PartAliasGroupName = String;
+
+/// Groups of equivalent parts, e.g. the same electrical part from multiple approved manufacturers.
+///
+/// A part belongs to at most one group, adding a part to a group removes it from any other group.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PartAliases(BTreeMap<PartAliasGroupName, BTreeSet<Part>>);
+
+impl PartAliases {
+    pub fn add(&mut self, group: PartAliasGroupName, part: Part) {
+        for parts in self.0.values_mut() {
+            parts.remove(&part);
+        }
+        self.0
+            .retain(|_group, parts| !parts.is_empty());
+
+        self.0
+            .entry(group)
+            .or_default()
+            .insert(part);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = (&PartAliasGroupName, &BTreeSet<Part>)> {
+        self.0.iter()
+    }
+
+    pub fn find_group(&self, part: &Part) -> Option<&PartAliasGroupName> {
+        self.0
+            .iter()
+            .find_map(|(group, parts)| parts.contains(part).then_some(group))
+    }
+
+    /// Returns the part and its aliases, the part is always first.
+    pub fn aliases<'a>(&'a self, part: &'a Part) -> impl Iterator<Item = &'a Part> {
+        let others = self
+            .0
+            .values()
+            .find(|parts| parts.contains(part))
+            .into_iter()
+            .flatten()
+            .filter(move |other| *other != part);
+
+        std::iter::once(part).chain(others)
+    }
+
+    /// `true` if the parts are the same, or in the same group.
+    pub fn are_aliases(&self, part: &Part, other: &Part) -> bool {
+        part == other
+            || self
+                .0
+                .values()
+                .any(|parts| parts.contains(part) && parts.contains(other))
+    }
+}
+
+/// Moisture sensitivity level, per IPC/JEDEC J-STD-020.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    SerializeDisplay,
+    DeserializeFromStr
+)]
+pub enum MoistureSensitivityLevel {
+    Msl1,
+    Msl2,
+    Msl2a,
+    Msl3,
+    Msl4,
+    Msl5,
+    Msl5a,
+    Msl6,
+}
+
+impl MoistureSensitivityLevel {
+    /// The floor life, in hours, at or below 30°C/60% RH, per IPC/JEDEC J-STD-033.
+    ///
+    /// `None` for MSL 1, which has an unlimited floor life.  MSL 6 parts must be baked before use and used within the
+    /// time on the label, so the floor life must be specified, see [`PartMoistureSensitivity::floor_life_hours`].
+    pub fn default_floor_life_hours(&self) -> Option<u32> {
+        match self {
+            MoistureSensitivityLevel::Msl1 => None,
+            MoistureSensitivityLevel::Msl2 => Some(365 * 24),
+            MoistureSensitivityLevel::Msl2a => Some(4 * 7 * 24),
+            MoistureSensitivityLevel::Msl3 => Some(168),
+            MoistureSensitivityLevel::Msl4 => Some(72),
+            MoistureSensitivityLevel::Msl5 => Some(48),
+            MoistureSensitivityLevel::Msl5a => Some(24),
+            MoistureSensitivityLevel::Msl6 => Some(0),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid moisture sensitivity level, expected one of 1, 2, 2a, 3, 4, 5, 5a or 6. value: '{0}'")]
+pub struct MoistureSensitivityLevelError(String);
+
+impl FromStr for MoistureSensitivityLevel {
+    type Err = MoistureSensitivityLevelError;
+
+    /// Accepts the level with or without an 'MSL' prefix, e.g. `3`, `2a`, `MSL3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim().to_lowercase();
+        let level = value
+            .strip_prefix("msl")
+            .unwrap_or(&value)
+            .trim_start_matches(['-', ' ']);
+
+        match level {
+            "1" => Ok(MoistureSensitivityLevel::Msl1),
+            "2" => Ok(MoistureSensitivityLevel::Msl2),
+            "2a" => Ok(MoistureSensitivityLevel::Msl2a),
+            "3" => Ok(MoistureSensitivityLevel::Msl3),
+            "4" => Ok(MoistureSensitivityLevel::Msl4),
+            "5" => Ok(MoistureSensitivityLevel::Msl5),
+            "5a" => Ok(MoistureSensitivityLevel::Msl5a),
+            "6" => Ok(MoistureSensitivityLevel::Msl6),
+            _ => Err(MoistureSensitivityLevelError(s.to_string())),
+        }
+    }
+}
+
+impl Display for MoistureSensitivityLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoistureSensitivityLevel::Msl1 => f.write_str("1"),
+            MoistureSensitivityLevel::Msl2 => f.write_str("2"),
+            MoistureSensitivityLevel::Msl2a => f.write_str("2a"),
+            MoistureSensitivityLevel::Msl3 => f.write_str("3"),
+            MoistureSensitivityLevel::Msl4 => f.write_str("4"),
+            MoistureSensitivityLevel::Msl5 => f.write_str("5"),
+            MoistureSensitivityLevel::Msl5a => f.write_str("5a"),
+            MoistureSensitivityLevel::Msl6 => f.write_str("6"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PartMoistureSensitivity {
+    pub level: MoistureSensitivityLevel,
+
+    /// Overrides the default floor life for the level, e.g. as specified by the label on an MSL 6 dry pack.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub floor_life_hours: Option<u32>,
+}
+
+impl PartMoistureSensitivity {
+    /// `None` when the floor life is unlimited.
+    pub fn floor_life_hours(&self) -> Option<u32> {
+        self.floor_life_hours
+            .or(self.level.default_floor_life_hours())
+    }
+}
+
+#[cfg(test)]
+mod moisture_sensitivity_tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("1", MoistureSensitivityLevel::Msl1)]
+    #[case("2a", MoistureSensitivityLevel::Msl2a)]
+    #[case("MSL3", MoistureSensitivityLevel::Msl3)]
+    #[case("msl-5A", MoistureSensitivityLevel::Msl5a)]
+    pub fn parse_level(#[case] value: &str, #[case] expected_level: MoistureSensitivityLevel) {
+        // expect
+        assert_eq!(MoistureSensitivityLevel::from_str(value).unwrap(), expected_level);
+    }
+
+    #[test]
+    pub fn floor_life() {
+        // given
+        let msl3 = PartMoistureSensitivity {
+            level: MoistureSensitivityLevel::Msl3,
+            floor_life_hours: None,
+        };
+        let msl6 = PartMoistureSensitivity {
+            level: MoistureSensitivityLevel::Msl6,
+            floor_life_hours: Some(6),
+        };
+        let msl1 = PartMoistureSensitivity {
+            level: MoistureSensitivityLevel::Msl1,
+            floor_life_hours: None,
+        };
+
+        // expect
+        assert_eq!(msl3.floor_life_hours(), Some(168));
+        assert_eq!(msl6.floor_life_hours(), Some(6));
+        assert_eq!(msl1.floor_life_hours(), None);
+    }
+}
+
+#[cfg(test)]
+mod part_aliases_tests {
+    use super::*;
+
+    fn part(manufacturer: &str, mpn: &str) -> Part {
+        Part::new(manufacturer.to_string(), mpn.to_string())
+    }
+
+    #[test]
+    pub fn aliases() {
+        // given
+        let mut part_aliases = PartAliases::default();
+        part_aliases.add("R_10K_0402".to_string(), part("MFR1", "RES1"));
+        part_aliases.add("R_10K_0402".to_string(), part("MFR2", "RES2"));
+        part_aliases.add("C_100N_0402".to_string(), part("MFR1", "CAP1"));
+
+        // expect
+        assert!(part_aliases.are_aliases(&part("MFR1", "RES1"), &part("MFR2", "RES2")));
+        assert!(!part_aliases.are_aliases(&part("MFR1", "RES1"), &part("MFR1", "CAP1")));
+        assert!(part_aliases.are_aliases(&part("MFR3", "RES3"), &part("MFR3", "RES3")));
+
+        // and the part is first
+        assert_eq!(
+            part_aliases
+                .aliases(&part("MFR2", "RES2"))
+                .collect::<Vec<_>>(),
+            vec![&part("MFR2", "RES2"), &part("MFR1", "RES1")]
+        );
+        assert_eq!(
+            part_aliases
+                .aliases(&part("MFR3", "RES3"))
+                .collect::<Vec<_>>(),
+            vec![&part("MFR3", "RES3")]
+        );
+    }
+
+    #[test]
+    pub fn add_moves_part_to_group() {
+        // given
+        let mut part_aliases = PartAliases::default();
+        part_aliases.add("GROUP1".to_string(), part("MFR1", "RES1"));
+
+        // when
+        part_aliases.add("GROUP2".to_string(), part("MFR1", "RES1"));
+
+        // then
+        assert_eq!(
+            part_aliases.find_group(&part("MFR1", "RES1")),
+            Some(&"GROUP2".to_string())
+        );
+        assert_eq!(part_aliases.groups().count(), 1);
+    }
+}
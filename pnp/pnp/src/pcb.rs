@@ -7,6 +7,8 @@
 use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
+use nalgebra::Matrix3;
+
 /// 0-based
 pub type PcbInstanceIndex = u16;
 /// 1-based
@@ -55,3 +57,89 @@ fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         }
     }
 }
+
+/// How to 'flip' a physical PCB.
+///
+/// We specifically do NOT use terms like 'mirror' or 'reflect' here because that is not what happens to the physical
+/// PCB in the real world.
+///
+/// Additionally, since there are inconsistencies between 'flip-along', 'flip-over', 'flip-about' and 'mirroring' and
+/// 'reflection' we use 'pitch' and 'roll' since they are unambiguous.
+///
+/// For clarity, this table describes the various different terminology used to describe flipping operations and how they relate to each other.
+///
+/// | Term           | Flip-over/about | Flip-along | Mirrored/Reflected axis | Matrix                             | Hold           | Result                       | Coordinate in | Coordinate out |
+/// | -------------- | --------------- | ---------- | ----------------------- | ---------------------------------- |--------------- | ---------------------------- | ------------- | -------------- |
+/// | Pitch flip     | x               | y          | y                       | [( 1, 0, 0), (0,-1, 0), (0, 0, 1)] | Left and right | Top edge becomes bottom edge | (1,1)         | ( 1,-1)        |
+/// | Roll flip      | y               | x          | x                       | [(-1, 0, 0), (0, 1, 0), (0, 0, 1)] | Top and bottom | Left edge becomes right edge | (1,1)         | (-1, 1)        |
+#[derive(Debug, serde::Serialize, serde::Deserialize, Copy, Clone, PartialEq, Eq)]
+pub enum PcbAssemblyFlip {
+    None,
+    Pitch, // Flip about the X axis (negates Y)
+    Roll,  // Flip about the Y axis (negates X)
+}
+
+impl From<PcbAssemblyFlip> for Matrix3<f64> {
+    fn from(flip: PcbAssemblyFlip) -> Self {
+        match flip {
+            PcbAssemblyFlip::None => Matrix3::identity(),
+            PcbAssemblyFlip::Pitch => Matrix3::new(1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 1.0),
+            PcbAssemblyFlip::Roll => Matrix3::new(-1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod pcb_assembly_flip_tests {
+    use nalgebra::{Matrix3, Point2, Vector3};
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        PcbAssemblyFlip::None,
+        Matrix3::identity(),
+        Point2::new(1.0, 2.0),
+        Point2::new(1.0, 2.0)
+    )]
+    #[case(
+        PcbAssemblyFlip::Roll,
+        Matrix3::new(-1.0, 0.0, 0.0,
+                      0.0, 1.0, 0.0,
+                      0.0, 0.0, 1.0),
+        Point2::new(1.0, 2.0),
+        Point2::new(-1.0, 2.0)
+    )]
+    #[case(
+        PcbAssemblyFlip::Pitch,
+        Matrix3::new(1.0, 0.0, 0.0,
+                     0.0, -1.0, 0.0,
+                     0.0, 0.0, 1.0),
+        Point2::new(1.0, 2.0),
+        Point2::new(1.0, -2.0)
+    )]
+    fn test_pcb_assembly_flip_matrix(
+        #[case] flip: PcbAssemblyFlip,
+        #[case] expected_matrix: Matrix3<f64>,
+        #[case] input: Point2<f64>,
+        #[case] expected_output: Point2<f64>,
+    ) {
+        let flip_matrix: Matrix3<f64> = flip.into();
+        assert_eq!(flip_matrix, expected_matrix);
+
+        let input_vec = Vector3::new(input.x, input.y, 1.0);
+        let result = flip_matrix * input_vec;
+        let result_point = Point2::new(result.x, result.y);
+
+        assert!(
+            (result_point.coords - expected_output.coords)
+                .abs()
+                .max()
+                < 1e-9,
+            "expected {:?}, got {:?}",
+            expected_output,
+            result_point
+        );
+    }
+}
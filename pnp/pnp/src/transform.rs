@@ -0,0 +1,560 @@
+//! Placement position transforms.
+//!
+//! Placement coordinates are converted through the following coordinate spaces:
+//!
+//! design -> unit -> panel -> machine
+//!
+//! * design - coordinates as exported by the EDA tool, including any export offsets.
+//! * unit - coordinates relative to the design's (0,0), export offsets removed.
+//! * panel - coordinates after the unit has been rotated and positioned in the panel.
+//! * machine - coordinates after the panel has been oriented (flipped/rotated) for assembly, always positive.
+//!
+//! Each conversion is a [`TransformStage`], the [`PlacementTransformer`] composes all the stages.
+
+use std::marker::PhantomData;
+
+use math::angle::normalize_angle_deg_signed_decimal;
+use nalgebra::{Matrix3, Vector2, Vector3};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::panel::DesignSizing;
+use crate::pcb::PcbAssemblyFlip;
+use crate::placement::Placement;
+
+pub trait CoordinateSpace {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DesignSpace;
+impl CoordinateSpace for DesignSpace {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitSpace;
+impl CoordinateSpace for UnitSpace {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanelSpace;
+impl CoordinateSpace for PanelSpace {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MachineSpace;
+impl CoordinateSpace for MachineSpace {}
+
+/// An (x,y) position in a specific coordinate space.
+///
+/// The space is part of the type so that positions in different spaces cannot be mixed up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position<S: CoordinateSpace> {
+    pub x: f64,
+    pub y: f64,
+    space: PhantomData<S>,
+}
+
+impl<S: CoordinateSpace> Position<S> {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self {
+            x,
+            y,
+            space: PhantomData,
+        }
+    }
+
+    fn transform<T: CoordinateSpace>(self, matrix: &Matrix3<f64>) -> Position<T> {
+        let transformed = matrix * Vector3::new(self.x, self.y, 1.0);
+        Position::new(transformed.x, transformed.y)
+    }
+}
+
+pub trait TransformStage {
+    type From: CoordinateSpace;
+    type To: CoordinateSpace;
+
+    /// The matrices for this stage, in the order they are applied.
+    fn steps(&self) -> Vec<Matrix3<f64>>;
+
+    fn to_matrix(&self) -> Matrix3<f64> {
+        compose(&self.steps())
+    }
+
+    fn apply(&self, position: Position<Self::From>) -> Position<Self::To> {
+        position.transform(&self.to_matrix())
+    }
+}
+
+/// Removes the EDA export offset.
+///
+/// Placements and gerbers can be exported with different offsets, see [`DesignSizing`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignToUnit {
+    pub offset: Vector2<f64>,
+}
+
+impl DesignToUnit {
+    pub fn for_placements(design_sizing: &DesignSizing) -> Self {
+        Self {
+            offset: design_sizing.placement_offset,
+        }
+    }
+
+    pub fn for_gerbers(design_sizing: &DesignSizing) -> Self {
+        Self {
+            offset: design_sizing.gerber_offset,
+        }
+    }
+}
+
+impl TransformStage for DesignToUnit {
+    type From = DesignSpace;
+    type To = UnitSpace;
+
+    fn steps(&self) -> Vec<Matrix3<f64>> {
+        // offsets are specified with the sign already inverted, e.g. an EDA offset of 10,10 is specified as -10,-10
+        vec![translation(self.offset)]
+    }
+}
+
+/// Rotates the unit about the design origin and moves it to its position in the panel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitToPanel {
+    /// See [`DesignSizing::origin`]
+    pub origin: Vector2<f64>,
+    /// In degrees, positive is anti-clockwise
+    pub unit_rotation: Decimal,
+    pub unit_offset: Vector2<f64>,
+}
+
+impl UnitToPanel {
+    /// The center of the unit, in panel space.
+    ///
+    /// In unit space the design is from (0,0) to the size of the design.
+    pub fn unit_center(&self, design_size: Vector2<f64>) -> Position<PanelSpace> {
+        self.apply(Position::new(design_size.x / 2.0, design_size.y / 2.0))
+    }
+}
+
+impl TransformStage for UnitToPanel {
+    type From = UnitSpace;
+    type To = PanelSpace;
+
+    fn steps(&self) -> Vec<Matrix3<f64>> {
+        vec![
+            translation(-self.origin),
+            rotation(self.unit_rotation),
+            translation(self.unit_offset),
+            translation(self.origin),
+        ]
+    }
+}
+
+/// Flips and rotates the panel about its center, then shifts it so that all coordinates are positive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PanelToMachine {
+    pub panel_size: Vector2<f64>,
+    pub flip: PcbAssemblyFlip,
+    /// In degrees, positive is anti-clockwise
+    pub rotation: Decimal,
+}
+
+impl PanelToMachine {
+    /// The translation required to move the bounding box of the rotated panel into the positive quadrant.
+    fn shift_to_positive(&self) -> Matrix3<f64> {
+        let panel_center = self.panel_size / 2.0;
+        let orientation_rotation = rotation(self.rotation);
+
+        let panel_corners = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(self.panel_size.x, 0.0),
+            Vector2::new(self.panel_size.x, self.panel_size.y),
+            Vector2::new(0.0, self.panel_size.y),
+        ];
+
+        // only the rotation is considered, flipping about the center does not change the bounding box
+        let rotated_corners: Vec<Vector3<f64>> = panel_corners
+            .iter()
+            .map(|corner| {
+                let rotation_only =
+                    orientation_rotation * translation(-panel_center) * Vector3::new(corner.x, corner.y, 1.0);
+
+                translation(panel_center) * rotation_only
+            })
+            .collect();
+
+        let shift = Vector2::new(
+            rotated_corners
+                .iter()
+                .map(|p| p.x)
+                .fold(f64::INFINITY, f64::min),
+            rotated_corners
+                .iter()
+                .map(|p| p.y)
+                .fold(f64::INFINITY, f64::min),
+        );
+
+        translation(-shift)
+    }
+
+    /// Apply the flip and the orientation rotation to a rotation, in degrees.
+    pub fn apply_to_rotation(&self, rotation: Decimal) -> Decimal {
+        let flipped_rotation = match self.flip {
+            PcbAssemblyFlip::None => rotation,
+            PcbAssemblyFlip::Pitch => Decimal::from(180) - rotation,
+            PcbAssemblyFlip::Roll => Decimal::from(360) - rotation,
+        };
+
+        flipped_rotation + self.rotation
+    }
+}
+
+impl TransformStage for PanelToMachine {
+    type From = PanelSpace;
+    type To = MachineSpace;
+
+    fn steps(&self) -> Vec<Matrix3<f64>> {
+        let panel_center = self.panel_size / 2.0;
+
+        let mut steps = vec![translation(-panel_center)];
+        if !matches!(self.flip, PcbAssemblyFlip::None) {
+            steps.push(self.flip.into());
+        }
+        steps.push(rotation(self.rotation));
+        steps.push(translation(panel_center));
+        steps.push(self.shift_to_positive());
+
+        steps
+    }
+}
+
+/// The position and rotation of a placement in machine space.
+///
+/// Uses the same coordinate scheme as [`Placement`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MachinePlacementPosition {
+    pub x: Decimal,
+    pub y: Decimal,
+    /// Positive values indicate anti-clockwise rotation
+    /// Range is >-180 to +180 degrees
+    pub rotation: Decimal,
+}
+
+/// Transforms placements from design space to machine space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacementTransformer {
+    pub design_to_unit: DesignToUnit,
+    pub unit_to_panel: UnitToPanel,
+    pub panel_to_machine: PanelToMachine,
+}
+
+impl PlacementTransformer {
+    pub fn new(
+        design_sizing: &DesignSizing,
+        unit_offset: Vector2<f64>,
+        unit_rotation: Decimal,
+        flip: PcbAssemblyFlip,
+        orientation_rotation: Decimal,
+        panel_size: Vector2<f64>,
+    ) -> Self {
+        Self {
+            design_to_unit: DesignToUnit::for_placements(design_sizing),
+            unit_to_panel: UnitToPanel {
+                origin: design_sizing.origin,
+                unit_rotation,
+                unit_offset,
+            },
+            panel_to_machine: PanelToMachine {
+                panel_size,
+                flip,
+                rotation: orientation_rotation,
+            },
+        }
+    }
+
+    /// A single matrix for all stages.
+    pub fn to_matrix(&self) -> Matrix3<f64> {
+        let steps: Vec<Matrix3<f64>> = self
+            .design_to_unit
+            .steps()
+            .into_iter()
+            .chain(self.unit_to_panel.steps())
+            .chain(self.panel_to_machine.steps())
+            .collect();
+
+        compose(&steps)
+    }
+
+    pub fn apply_to_position(&self, position: Position<DesignSpace>) -> Position<MachineSpace> {
+        position.transform(&self.to_matrix())
+    }
+
+    /// Apply all rotations to a placement rotation, the result is normalized to the range >-180 to +180 degrees.
+    pub fn apply_to_rotation(&self, rotation: Decimal) -> Decimal {
+        let new_rotation = self
+            .panel_to_machine
+            .apply_to_rotation(rotation)
+            + self.unit_to_panel.unit_rotation;
+
+        normalize_angle_deg_signed_decimal(new_rotation).normalize()
+    }
+
+    pub fn apply_to_placement(&self, placement: &Placement) -> MachinePlacementPosition {
+        let position = self.apply_to_position(Position::new(
+            placement.x.to_f64().unwrap_or(0.0),
+            placement.y.to_f64().unwrap_or(0.0),
+        ));
+
+        MachinePlacementPosition {
+            x: Decimal::try_from(position.x).unwrap_or_default(),
+            y: Decimal::try_from(position.y).unwrap_or_default(),
+            rotation: self.apply_to_rotation(placement.rotation),
+        }
+    }
+}
+
+/// Transforms a design's gerbers from design space to panel space, to preview the panel as designed.
+///
+/// Unlike placements, the panel is not oriented for assembly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GerberTransformer {
+    pub design_to_unit: DesignToUnit,
+    pub unit_to_panel: UnitToPanel,
+}
+
+impl GerberTransformer {
+    pub fn new(design_sizing: &DesignSizing, unit_offset: Vector2<f64>, unit_rotation: Decimal) -> Self {
+        Self {
+            design_to_unit: DesignToUnit::for_gerbers(design_sizing),
+            unit_to_panel: UnitToPanel {
+                origin: design_sizing.origin,
+                unit_rotation,
+                unit_offset,
+            },
+        }
+    }
+
+    /// A single matrix for all stages.
+    pub fn to_matrix(&self) -> Matrix3<f64> {
+        let steps: Vec<Matrix3<f64>> = self
+            .design_to_unit
+            .steps()
+            .into_iter()
+            .chain(self.unit_to_panel.steps())
+            .collect();
+
+        compose(&steps)
+    }
+
+    pub fn apply_to_position(&self, position: Position<DesignSpace>) -> Position<PanelSpace> {
+        position.transform(&self.to_matrix())
+    }
+
+    /// The point, in design space, that the gerbers are rotated about.
+    ///
+    /// Renderers that apply a rotation about a point followed by a translation can use this with [`Self::offset`].
+    pub fn pivot(&self) -> Vector2<f64> {
+        self.unit_to_panel.origin - self.design_to_unit.offset
+    }
+
+    /// The translation applied after rotating about the [`Self::pivot`].
+    pub fn offset(&self) -> Vector2<f64> {
+        self.unit_to_panel.unit_offset + self.design_to_unit.offset
+    }
+}
+
+/// Combine matrices, in the order they are applied.
+fn compose(steps: &[Matrix3<f64>]) -> Matrix3<f64> {
+    steps
+        .iter()
+        .fold(Matrix3::identity(), |matrix, step| step * matrix)
+}
+
+fn translation(offset: Vector2<f64>) -> Matrix3<f64> {
+    Matrix3::new_translation(&offset)
+}
+
+/// Anti-clockwise positive degrees
+#[rustfmt::skip]
+fn rotation(degrees: Decimal) -> Matrix3<f64> {
+    let radians = degrees.to_f64().unwrap().to_radians();
+    let cos_theta = radians.cos();
+    let sin_theta = radians.sin();
+
+    Matrix3::new(
+        cos_theta, -sin_theta, 0.0,
+        sin_theta, cos_theta, 0.0,
+        0.0, 0.0, 1.0,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn design_sizing() -> DesignSizing {
+        DesignSizing {
+            size: Vector2::new(10.0, 20.0),
+            gerber_offset: Vector2::new(-5.0, -5.0),
+            placement_offset: Vector2::new(-10.0, -10.0),
+            origin: Vector2::new(5.0, 10.0),
+        }
+    }
+
+    fn assert_position<S: CoordinateSpace>(position: Position<S>, expected: (f64, f64)) {
+        assert!(
+            (position.x - expected.0).abs() < 1e-9 && (position.y - expected.1).abs() < 1e-9,
+            "expected {:?}, got ({}, {})",
+            expected,
+            position.x,
+            position.y
+        );
+    }
+
+    #[test]
+    fn design_to_unit_uses_the_offset_for_the_source() {
+        // given
+        let design_sizing = design_sizing();
+        let position = Position::<DesignSpace>::new(12.0, 13.0);
+
+        // when
+        let placement_result = DesignToUnit::for_placements(&design_sizing).apply(position);
+        let gerber_result = DesignToUnit::for_gerbers(&design_sizing).apply(position);
+
+        // then
+        assert_position(placement_result, (2.0, 3.0));
+        assert_position(gerber_result, (7.0, 8.0));
+    }
+
+    #[test]
+    fn unit_to_panel_rotates_about_the_origin() {
+        // given
+        let stage = UnitToPanel {
+            origin: Vector2::new(5.0, 10.0),
+            unit_rotation: dec!(90),
+            unit_offset: Vector2::new(100.0, 0.0),
+        };
+
+        // when
+        let result = stage.apply(Position::new(6.0, 10.0));
+
+        // then
+        assert_position(result, (105.0, 11.0));
+    }
+
+    #[test]
+    fn unit_center_is_rotated_and_offset() {
+        // given
+        let stage = UnitToPanel {
+            origin: Vector2::new(0.0, 0.0),
+            unit_rotation: dec!(90),
+            unit_offset: Vector2::new(100.0, 0.0),
+        };
+
+        // when
+        let result = stage.unit_center(Vector2::new(10.0, 20.0));
+
+        // then
+        // (5,10) rotated by 90 degrees about (0,0) is (-10,5)
+        assert_position(result, (90.0, 5.0));
+    }
+
+    #[rstest]
+    #[case(PcbAssemblyFlip::None, dec!(0), (1.0, 2.0))]
+    #[case(PcbAssemblyFlip::Pitch, dec!(0), (1.0, 18.0))]
+    #[case(PcbAssemblyFlip::Roll, dec!(0), (9.0, 2.0))]
+    #[case(PcbAssemblyFlip::None, dec!(90), (18.0, 1.0))]
+    fn panel_to_machine(#[case] flip: PcbAssemblyFlip, #[case] rotation: Decimal, #[case] expected: (f64, f64)) {
+        // given
+        let stage = PanelToMachine {
+            panel_size: Vector2::new(10.0, 20.0),
+            flip,
+            rotation,
+        };
+
+        // when
+        let result = stage.apply(Position::new(1.0, 2.0));
+
+        // then
+        assert_position(result, expected);
+    }
+
+    #[test]
+    fn transformer_composes_all_stages() {
+        // given
+        let transformer = PlacementTransformer::new(
+            &design_sizing(),
+            Vector2::new(5.0, 5.0),
+            dec!(0),
+            PcbAssemblyFlip::Roll,
+            dec!(0),
+            Vector2::new(20.0, 30.0),
+        );
+        let position = Position::new(12.0, 13.0);
+
+        // when
+        let result = transformer.apply_to_position(position);
+
+        // then
+        let expected = transformer.panel_to_machine.apply(
+            transformer.unit_to_panel.apply(
+                transformer
+                    .design_to_unit
+                    .apply(position),
+            ),
+        );
+        assert_position(result, (expected.x, expected.y));
+        assert_position(result, (13.0, 3.0));
+    }
+
+    #[test]
+    fn gerber_transformer_pivot_and_offset() {
+        // given
+        let transformer = GerberTransformer::new(&design_sizing(), Vector2::new(100.0, 50.0), dec!(90));
+        let position = Position::new(12.0, 13.0);
+
+        // when
+        let result = transformer.apply_to_position(position);
+
+        // then
+        let expected = transformer.unit_to_panel.apply(
+            transformer
+                .design_to_unit
+                .apply(position),
+        );
+        assert_position(result, (expected.x, expected.y));
+
+        // rotating (12,13) by 90 degrees about the pivot (10,15) gives (12,17), then offset by (95,45)
+        assert_eq!(transformer.pivot(), Vector2::new(10.0, 15.0));
+        assert_eq!(transformer.offset(), Vector2::new(95.0, 45.0));
+        assert_position(result, (107.0, 62.0));
+    }
+
+    #[rstest]
+    #[case(PcbAssemblyFlip::None, dec!(0), dec!(0), dec!(45), dec!(45))]
+    #[case(PcbAssemblyFlip::None, dec!(90), dec!(90), dec!(45), dec!(-135))]
+    #[case(PcbAssemblyFlip::Pitch, dec!(0), dec!(0), dec!(45), dec!(135))]
+    #[case(PcbAssemblyFlip::Roll, dec!(0), dec!(90), dec!(45), dec!(45))]
+    fn transformer_rotation(
+        #[case] flip: PcbAssemblyFlip,
+        #[case] orientation_rotation: Decimal,
+        #[case] unit_rotation: Decimal,
+        #[case] rotation: Decimal,
+        #[case] expected: Decimal,
+    ) {
+        // given
+        let transformer = PlacementTransformer::new(
+            &design_sizing(),
+            Vector2::new(0.0, 0.0),
+            unit_rotation,
+            flip,
+            orientation_rotation,
+            Vector2::new(10.0, 20.0),
+        );
+
+        // when
+        let result = transformer.apply_to_rotation(rotation);
+
+        // then
+        assert_eq!(result, expected);
+    }
+}